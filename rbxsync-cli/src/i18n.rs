@@ -0,0 +1,186 @@
+//! Localized CLI output
+//!
+//! User-facing messages live in per-language catalogs under `locales/`, keyed
+//! by message ID, with `{0}`, `{1}`, ... placeholders for arguments. The
+//! language is picked from `--lang`, then `RBXSYNC_LANG`, and falls back to
+//! English for unknown languages or messages missing from a catalog.
+//!
+//! Only human-readable output goes through here. JSON written to disk or
+//! printed for other tools is never translated.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// Built-in catalogs: (language code, catalog JSON)
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.json")),
+    ("es", include_str!("../locales/es.json")),
+];
+
+/// Language used when none is configured
+pub const DEFAULT_LANG: &str = "en";
+
+/// Environment variable selecting the output language
+pub const LANG_ENV_VAR: &str = "RBXSYNC_LANG";
+
+struct Messages {
+    active: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+static MESSAGES: OnceLock<Messages> = OnceLock::new();
+
+fn parse_catalog(lang: &str) -> Option<HashMap<String, String>> {
+    CATALOGS
+        .iter()
+        .find(|(code, _)| *code == lang)
+        .map(|(_, json)| serde_json::from_str(json).expect("built-in message catalog is valid JSON"))
+}
+
+/// Reduce a locale like "es_MX.UTF-8" or "es-MX" to its language code ("es")
+fn normalize_lang(lang: &str) -> String {
+    lang.split(['_', '-', '.'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Languages with a built-in catalog
+pub fn available_languages() -> Vec<&'static str> {
+    CATALOGS.iter().map(|(code, _)| *code).collect()
+}
+
+/// Select the output language. Call once at startup, before printing anything.
+///
+/// Returns `false` if the requested language has no catalog (English is used).
+pub fn init(lang: Option<&str>) -> bool {
+    let requested = lang
+        .map(str::to_string)
+        .or_else(|| std::env::var(LANG_ENV_VAR).ok())
+        .map(|l| normalize_lang(&l))
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| DEFAULT_LANG.to_string());
+
+    let fallback = parse_catalog(DEFAULT_LANG).unwrap_or_default();
+    let (active, found) = match parse_catalog(&requested) {
+        Some(catalog) => (catalog, true),
+        None => (fallback.clone(), false),
+    };
+
+    let _ = MESSAGES.set(Messages { active, fallback });
+    found
+}
+
+/// Look up a message by ID
+pub fn t(key: &str) -> &str {
+    let messages = MESSAGES.get_or_init(|| {
+        let fallback = parse_catalog(DEFAULT_LANG).unwrap_or_default();
+        Messages {
+            active: fallback.clone(),
+            fallback,
+        }
+    });
+
+    messages
+        .active
+        .get(key)
+        .or_else(|| messages.fallback.get(key))
+        .map(String::as_str)
+        .unwrap_or(key)
+}
+
+/// Substitute `{0}`, `{1}`, ... in a message template. The template is read
+/// once, so placeholder-like text inside an argument (a path or instance
+/// name containing `{1}`) comes out as written.
+pub fn format_message(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let arg = after
+            .find('}')
+            .and_then(|close| Some((after[..close].parse::<usize>().ok()?, close)))
+            .and_then(|(index, close)| Some((args.get(index)?, close)));
+        match arg {
+            Some((arg, close)) => {
+                out.push_str(&arg.to_string());
+                rest = &after[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Look up a message by ID and fill in its placeholders
+///
+/// ```ignore
+/// println!("{}", tr!("sync.synced", count));
+/// ```
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::i18n::t($key).to_string()
+    };
+    ($key:expr, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::format_message($crate::i18n::t($key), &[$(&$arg as &dyn std::fmt::Display),+])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(message: &str) -> Vec<usize> {
+        let mut found: Vec<usize> = (0..10)
+            .filter(|i| message.contains(&format!("{{{}}}", i)))
+            .collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn test_catalogs_match_english() {
+        let english = parse_catalog(DEFAULT_LANG).unwrap();
+        for lang in available_languages() {
+            let catalog = parse_catalog(lang).unwrap();
+            for (key, message) in &catalog {
+                let Some(source) = english.get(key) else {
+                    panic!("{}: unknown message ID '{}'", lang, key);
+                };
+                assert_eq!(
+                    placeholders(message),
+                    placeholders(source),
+                    "{}: placeholders differ for '{}'",
+                    lang,
+                    key
+                );
+            }
+            if lang != DEFAULT_LANG {
+                let missing: Vec<&String> = english.keys().filter(|k| !catalog.contains_key(*k)).collect();
+                assert!(missing.is_empty(), "{}: missing messages {:?}", lang, missing);
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_message() {
+        assert_eq!(normalize_lang("es_MX.UTF-8"), "es");
+        assert_eq!(
+            format_message("{1} de {0}", &[&"a", &2]),
+            "2 de a"
+        );
+        // Arguments are never substituted into again
+        assert_eq!(
+            format_message("Wrote {0} to {1}", &[&"src/{1}.luau", &"out"]),
+            "Wrote src/{1}.luau to out"
+        );
+        assert_eq!(format_message("{x} {2} {0", &[&"a"]), "{x} {2} {0");
+    }
+}
@@ -294,7 +294,8 @@ When users ask to modify the game, write Luau code to do it. Use print() to retu
     async fn run_code(&self, code: &str) -> Result<String> {
         let response = self
             .http_client
-            .post("http://127.0.0.1:44755/run")
+            .post(format!("{}/run", crate::server_url(None)))
+            .headers(crate::server_headers())
             .json(&serde_json::json!({ "code": code }))
             .send()
             .await
@@ -313,7 +314,8 @@ When users ask to modify the game, write Luau code to do it. Use print() to retu
     async fn insert_model(&self, query: &str) -> Result<String> {
         let response = self
             .http_client
-            .post("http://127.0.0.1:44755/insert-model")
+            .post(format!("{}/insert-model", crate::server_url(None)))
+            .headers(crate::server_headers())
             .json(&serde_json::json!({ "query": query }))
             .send()
             .await
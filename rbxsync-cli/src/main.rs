@@ -3,28 +3,30 @@
 //! Command-line interface for Roblox game extraction and synchronization.
 
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::BufWriter;
 use std::path::PathBuf;
-use std::sync::mpsc::channel;
-use std::time::Duration;
 
 use anyhow::{bail, Context, Result};
 use clap::{Parser, Subcommand};
-use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
-use rbx_dom_weak::types::Variant;
-use rbx_dom_weak::{InstanceBuilder, WeakDom};
+use rbxsync_core::builder::{child_instance_path, find_sibling_script, init_script_class, parse_script_name};
 use rbxsync_core::{
     build_plugin, find_existing_rbxsync_plugin, find_rojo_project, get_studio_plugins_folder,
-    install_plugin, parse_rojo_project, rojo_to_tree_mapping, PluginBuildConfig, ProjectConfig,
+    install_plugin, parse_rojo_project, replace_property, rojo_to_tree_mapping, BuildFormat,
+    BuildOptions, PluginBuildConfig, ProjectConfig, PropertyReplace,
 };
 use rbxsync_server::{run_server, ServerConfig};
 
+#[macro_use]
+mod i18n;
+
 #[derive(Parser)]
 #[command(name = "rbxsync")]
 #[command(about = "Roblox game extraction and synchronization tool")]
 #[command(version)]
 struct Cli {
+    /// Language for output messages (en, es). Defaults to $RBXSYNC_LANG
+    #[arg(long, global = true)]
+    lang: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -44,10 +46,17 @@ enum Commands {
         /// Skip generating sourcemap.json
         #[arg(long)]
         no_sourcemap: bool,
+
+        /// Start from a template in ~/.rbxsync/templates
+        #[arg(short, long)]
+        template: Option<String>,
     },
 
-    /// Launch Roblox Studio
+    /// Launch Roblox Studio, or save, publish and playtest the connected one
     Studio {
+        #[command(subcommand)]
+        action: Option<StudioAction>,
+
         /// Place file to open (.rbxl or .rbxlx)
         place: Option<PathBuf>,
 
@@ -62,6 +71,52 @@ enum Commands {
         action: DebugAction,
     },
 
+    /// Run the place's specs in a playtest and report pass/fail per spec
+    Test {
+        /// Playtest mode: play (default) or run
+        #[arg(short, long, default_value = "play")]
+        mode: String,
+
+        /// Seconds before the playtest is stopped and the run fails
+        #[arg(short, long, default_value_t = 120)]
+        timeout: u64,
+
+        /// Instance to search for *.spec modules, e.g. ReplicatedStorage/Shared (repeatable)
+        #[arg(long = "root")]
+        roots: Vec<String>,
+
+        /// Luau script, relative to the project, to run instead of the built-in bootstrap
+        #[arg(long)]
+        bootstrap: Option<String>,
+
+        /// Where the specs run: studio (the connected Studio) or cloud (Open Cloud
+        /// against the published place). Default: test.backend in rbxsync.json, else studio
+        #[arg(long)]
+        backend: Option<String>,
+
+        /// Print the JUnit-style JSON report instead of a summary
+        #[arg(long)]
+        json: bool,
+
+        /// Also report the results for CI: junit (XML) or github (Actions annotations)
+        #[arg(long)]
+        report: Option<String>,
+
+        /// Write the --report output to this file instead of stdout
+        #[arg(short, long, requires = "report")]
+        output: Option<PathBuf>,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+
+    /// Drive the playtest bot
+    Bot {
+        #[command(subcommand)]
+        action: BotAction,
+    },
+
     /// Extract game from connected Roblox Studio
     Extract {
         /// Specific services to extract (default: all)
@@ -79,6 +134,14 @@ enum Commands {
         /// Output directory (default: project src directory)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Fail if any property can't be serialized instead of writing a placeholder
+        #[arg(long)]
+        strict: bool,
+
+        /// Only extract instances that changed since the last extraction
+        #[arg(long)]
+        incremental: bool,
     },
 
     /// Start the sync server (connects to Studio plugin)
@@ -90,6 +153,51 @@ enum Commands {
         /// Run server in background (detached)
         #[arg(short, long)]
         background: bool,
+
+        /// If a --port you chose is taken, start on a free failover port without asking
+        /// (the default port always fails over)
+        #[arg(long)]
+        failover: bool,
+
+        /// Restart the background server when it crashes, writing crash reports to ~/.rbxsync/crashes
+        #[arg(long, requires = "background")]
+        supervised: bool,
+
+        /// Run as the supervisor of a server process (used by --supervised)
+        #[arg(long, hide = true)]
+        supervisor: bool,
+
+        /// Require the token from ~/.rbxsync/token on mutating requests (created if missing)
+        #[arg(long)]
+        auth: bool,
+
+        /// Address to listen on instead of 127.0.0.1 (e.g. 0.0.0.0:44755); turns on --auth
+        #[arg(long, conflicts_with = "port")]
+        listen: Option<std::net::SocketAddr>,
+
+        /// PEM certificate chain, to serve HTTPS
+        #[arg(long, requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+
+        /// PEM private key for --tls-cert
+        #[arg(long, requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+
+        /// Also accept the keychain tokens of the team users added with `rbxsync users add`; turns on --auth
+        #[arg(long)]
+        users: bool,
+
+        /// Also accept ID tokens from this OpenID Connect issuer (see `rbxsync login`); turns on --auth
+        #[arg(long, value_name = "URL", requires_all = ["oidc_client_id", "oidc_allow"])]
+        oidc_issuer: Option<String>,
+
+        /// OAuth client ID that tokens must be issued to (the one users pass to `rbxsync login`)
+        #[arg(long, value_name = "ID", requires = "oidc_issuer")]
+        oidc_client_id: Option<String>,
+
+        /// Users the OIDC issuer may sign in: an email, @domain for a whole domain, or a subject id
+        #[arg(long, value_name = "USER", value_delimiter = ',', requires = "oidc_issuer")]
+        oidc_allow: Vec<String>,
     },
 
     /// Stop the running sync server
@@ -100,2351 +208,6316 @@ enum Commands {
     },
 
     /// Show sync status
-    Status,
+    Status {
+        /// List every rbxsync server on this machine and the projects it owns
+        #[arg(long)]
+        all: bool,
 
-    /// Show diff between local files and Studio
-    Diff,
+        /// Cancel a stuck plugin request by id (from the job list)
+        #[arg(long, value_name = "ID")]
+        cancel: Option<String>,
+    },
 
-    /// Sync local changes to connected Studio instance
-    Sync {
+    /// Check which local dev tools hold the rbxsync, Rojo and Argon ports
+    Doctor {
+        /// Port rbxsync is configured to use
+        #[arg(short, long, default_value = "44755")]
+        port: u16,
+    },
+
+    /// Finish or roll back an operation interrupted by a crash
+    Recover {
         /// Project directory (default: current directory)
         #[arg(short, long)]
         path: Option<PathBuf>,
 
-        /// Keep orphaned instances in Studio (by default, they are deleted)
+        /// Revert the journaled changes and restore the previous files
+        #[arg(long, conflicts_with = "finish")]
+        rollback: bool,
+
+        /// Replay the journaled changes to complete the operation
         #[arg(long)]
-        no_delete: bool,
+        finish: bool,
     },
 
-    /// Build the Studio plugin as .rbxm file
-    BuildPlugin {
-        /// Source directory containing Luau files (default: plugin/src)
+    /// Show the API token required by `rbxsync serve --auth`
+    Token {
+        /// Replace the token with a new one (restart the server to use it)
+        #[arg(long)]
+        regenerate: bool,
+    },
+
+    /// Sign in to a team server, keeping the token in the OS keychain
+    Login {
+        /// Token a team server's owner gave you (from `rbxsync users add`)
+        #[arg(long, required_unless_present = "issuer", conflicts_with = "issuer")]
+        token: Option<String>,
+
+        /// OpenID Connect issuer to sign in with (the server's --oidc-issuer)
+        #[arg(long, value_name = "URL", requires = "client_id")]
+        issuer: Option<String>,
+
+        /// OAuth client ID registered with the issuer for rbxsync (the server's --oidc-client-id)
+        #[arg(long)]
+        client_id: Option<String>,
+    },
+
+    /// Forget the token saved by `rbxsync login`
+    Logout,
+
+    /// Manage the team users a `serve --users` server accepts
+    Users {
+        #[command(subcommand)]
+        action: UsersAction,
+    },
+
+    /// Show the server's log for a project (.rbxsync/logs/)
+    Logs {
+        #[command(subcommand)]
+        studio: Option<LogsSource>,
+
+        /// Keep printing new entries as the server logs them
         #[arg(short, long)]
-        source: Option<PathBuf>,
+        follow: bool,
 
-        /// Output path for the .rbxm file (default: build/RbxSync.rbxm)
+        /// Minimum level: error, warn, info, debug or trace
         #[arg(short, long)]
-        output: Option<PathBuf>,
+        level: Option<String>,
 
-        /// Plugin name (default: RbxSync)
+        /// Only entries from this subsystem (e.g. assets, file_watcher)
         #[arg(short, long)]
-        name: Option<String>,
+        subsystem: Option<String>,
 
-        /// Install plugin to Studio's plugins folder after building
-        #[arg(long)]
-        install: bool,
+        /// Number of recent entries to show
+        #[arg(short = 'n', long, default_value = "50")]
+        limit: usize,
 
-        /// Skip obfuscation (obfuscation is enabled by default)
+        /// Project directory (default: current directory)
         #[arg(long)]
-        no_obfuscate: bool,
+        path: Option<PathBuf>,
 
-        /// Path to obfuscation config file (default: obfuscate.toml)
+        /// Server port (default: the project's server)
         #[arg(long)]
-        obfuscate_config: Option<PathBuf>,
+        port: Option<u16>,
     },
 
-    /// Manage the RbxSync Studio plugin
-    Plugin {
-        #[command(subcommand)]
-        action: PluginAction,
-    },
+    /// Undo an extraction by restoring src/ from a backup made before one
+    Undo {
+        /// List the backups that can be restored, newest first
+        #[arg(long, conflicts_with = "to")]
+        list: bool,
+
+        /// Backup to restore, by id or name (default: the newest)
+        #[arg(long)]
+        to: Option<String>,
 
-    /// Generate sourcemap.json for Luau LSP
-    Sourcemap {
         /// Project directory (default: current directory)
         #[arg(short, long)]
         path: Option<PathBuf>,
+    },
 
-        /// Output file (default: sourcemap.json)
-        #[arg(short, long)]
-        output: Option<PathBuf>,
+    /// List or restore the compressed src backups made before each extraction
+    Backup {
+        #[command(subcommand)]
+        action: BackupAction,
+    },
 
-        /// Include non-script instances
-        #[arg(long, default_value = "false")]
-        include_non_scripts: bool,
+    /// Save the extracted tree as a named snapshot, or push one back to Studio
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
     },
 
-    /// Build a .rbxl or .rbxm file from project files
-    Build {
+    /// List or toggle the feature flags in flags.json
+    Flags {
+        #[command(subcommand)]
+        action: FlagsAction,
+    },
+
+    /// Install Wally packages from wally.lock, or check them against it
+    Wally {
+        #[command(subcommand)]
+        action: WallyAction,
+    },
+
+    /// Write a compact project overview (service tree, module exports, remotes, constants)
+    Summarize {
         /// Project directory (default: current directory)
         #[arg(short, long)]
         path: Option<PathBuf>,
 
-        /// Output file (default: build/game.rbxl)
-        #[arg(short, long)]
-        output: Option<PathBuf>,
-
-        /// Output format: rbxl, rbxm, rbxlx (XML place), or rbxmx (XML model)
-        #[arg(short, long, default_value = "rbxl")]
-        format: String,
+        /// Print the summary as JSON instead of Markdown
+        #[arg(long)]
+        json: bool,
+    },
 
-        /// Watch for file changes and rebuild automatically
+    /// Show sync history and trends from .rbxsync/stats.jsonl
+    Stats {
+        /// Project directory (default: current directory)
         #[arg(short, long)]
-        watch: bool,
+        path: Option<PathBuf>,
 
-        /// Output to Studio plugins folder with this filename (e.g., MyPlugin.rbxm)
+        /// Weeks of history to show
+        #[arg(short, long, default_value = "8")]
+        weeks: usize,
+
+        /// Print the summary as JSON
         #[arg(long)]
-        plugin: Option<String>,
+        json: bool,
     },
 
-    /// Format project JSON files with consistent style
-    FmtProject {
+    /// Show who changed an instance and when (git history plus Studio edits)
+    Blame {
+        /// Instance path (e.g. Workspace/Lobby/SpawnGate)
+        instance_path: String,
+
         /// Project directory (default: current directory)
         #[arg(short, long)]
         path: Option<PathBuf>,
 
-        /// Check formatting without writing (exit 1 if unformatted)
+        /// Maximum history entries to show
+        #[arg(short = 'n', long, default_value = "20")]
+        limit: usize,
+
+        /// Print the history as JSON
         #[arg(long)]
-        check: bool,
+        json: bool,
     },
 
-    /// Open RbxSync documentation in browser
-    Doc,
+    /// Benchmark the sync pipeline on a synthetic game
+    Bench {
+        /// Instances in the synthetic game
+        #[arg(short = 'n', long, default_value = "5000")]
+        instances: usize,
 
-    /// Update RbxSync from GitHub releases
-    Update {
-        /// Build from source instead of downloading (requires Rust)
+        /// Runs per stage (the median is reported)
+        #[arg(long, default_value = "3")]
+        iterations: usize,
+
+        /// Write the results as JSON, for use as a baseline
         #[arg(long)]
-        from_source: bool,
+        save: Option<PathBuf>,
 
-        /// Also update VS Code extension
+        /// Fail if any stage is slower than this baseline allows
         #[arg(long)]
-        vscode: bool,
+        baseline: Option<PathBuf>,
 
-        /// Skip confirmation prompt
-        #[arg(short, long)]
-        yes: bool,
+        /// Allowed slowdown against the baseline, in percent
+        #[arg(long, default_value = "25")]
+        max_regression: f64,
     },
 
-    /// Uninstall RbxSync (remove CLI, plugin, and optionally VS Code extension)
-    Uninstall {
-        /// Also remove VS Code extension
-        #[arg(long)]
-        vscode: bool,
-
-        /// Keep the cloned repo at ~/.rbxsync/repo
+    /// Export the project as a static HTML site for review in a browser
+    Export {
+        /// Output directory for the site
         #[arg(long)]
-        keep_repo: bool,
+        site: PathBuf,
 
-        /// Skip confirmation prompt
+        /// Project directory (default: current directory)
         #[arg(short, long)]
-        yes: bool,
+        path: Option<PathBuf>,
     },
 
-    /// Show current version and check for updates
-    Version,
+    /// Show diff between local files and Studio, or between two connected places
+    Diff {
+        /// Compare two connected Studio places instead (session ID, place ID or name)
+        #[arg(long, num_args = 2, value_names = ["A", "B"])]
+        places: Option<Vec<String>>,
 
-    /// Migrate from Rojo project to RbxSync
-    Migrate {
-        /// Source format (currently only "rojo" is supported)
-        #[arg(long, default_value = "rojo")]
-        from: String,
+        /// With --places, also compare property values
+        #[arg(long, requires = "places")]
+        properties: bool,
 
-        /// Path to project directory (default: current directory)
+        /// With --places, only compare instances under this DataModel path
+        #[arg(long, requires = "places")]
+        root: Option<String>,
+
+        /// Only compare which instances exist, not their properties (faster on large games)
+        #[arg(long, conflicts_with = "places")]
+        paths_only: bool,
+    },
+
+    /// List or diff instances grouped by CollectionService tag
+    Components {
+        #[command(subcommand)]
+        action: ComponentsAction,
+    },
+
+    /// Upload local assets through Open Cloud
+    Asset {
+        #[command(subcommand)]
+        action: AssetAction,
+    },
+
+    /// Sync local changes to connected Studio instance
+    Sync {
+        #[command(subcommand)]
+        action: Option<SyncAction>,
+
+        /// Only sync instances under these DataModel paths (e.g. ServerScriptService/Combat)
+        #[arg(conflicts_with = "watch")]
+        instances: Vec<String>,
+
+        /// Project directory (default: current directory)
         #[arg(short, long)]
         path: Option<PathBuf>,
 
-        /// Overwrite existing rbxsync.json
+        /// Keep orphaned instances in Studio (by default, they are deleted)
         #[arg(long)]
-        force: bool,
+        no_delete: bool,
+
+        /// Override variant from rbxsync.json (default: RBXSYNC_VARIANT, then "variant")
+        #[arg(long, conflicts_with = "watch")]
+        variant: Option<String>,
+
+        /// Keep running and push file changes to Studio as they happen
+        #[arg(short, long)]
+        watch: bool,
     },
 
-    /// Start the Flux agent (control Studio via iMessage)
-    Flux {
-        /// Run in local mode (terminal testing, no iMessage)
+    /// Run git in the project through the server: branches, checkout, pull, push, stash
+    Git {
+        #[command(subcommand)]
+        action: GitAction,
+    },
+
+    /// List sync conflicts, or resolve one with the files, Studio or a merged version
+    Resolve {
+        /// Instance path of the conflict to resolve (lists conflicts when omitted)
+        path: Option<String>,
+
+        /// Keep the local files; the next sync sends them to Studio
+        #[arg(long, requires = "path", conflicts_with_all = ["studio", "merged"])]
+        file: bool,
+
+        /// Overwrite the local files with the Studio change
+        #[arg(long, requires = "path", conflicts_with = "merged")]
+        studio: bool,
+
+        /// Merge both versions of a script in $EDITOR
+        #[arg(long, requires = "path")]
+        merged: bool,
+    },
+
+    /// Replace a property value on every matching instance (files and Studio)
+    Replace {
+        /// Class to match (e.g. Part)
         #[arg(long)]
-        local: bool,
+        class: String,
 
-        /// Set the Anthropic API key
+        /// Property to rewrite (e.g. Material)
         #[arg(long)]
-        set_api_key: Option<String>,
-    },
+        prop: String,
 
-    /// Manage AI development harness (multi-session tracking)
-    Harness {
-        #[command(subcommand)]
-        action: HarnessAction,
+        /// Current value to match (e.g. Plastic)
+        #[arg(long)]
+        from: String,
+
+        /// New value (e.g. SmoothPlastic)
+        #[arg(long)]
+        to: String,
+
+        /// Only instances under this DataModel path (e.g. Workspace/Map)
+        #[arg(long)]
+        path: Option<String>,
+
+        /// Preview matches without changing files or Studio
+        #[arg(long)]
+        dry_run: bool,
     },
-}
 
-#[derive(Subcommand)]
-enum PluginAction {
-    /// Install the plugin to Roblox Studio's plugins folder (downloads from GitHub if needed)
-    Install {
-        /// Path to .rbxm plugin file (downloads from GitHub if not specified)
+    /// Build the Studio plugin as .rbxm file
+    BuildPlugin {
+        /// Source directory containing Luau files (default: plugin/src)
         #[arg(short, long)]
-        path: Option<PathBuf>,
+        source: Option<PathBuf>,
+
+        /// Output path for the .rbxm file (default: build/RbxSync.rbxm)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
 
         /// Plugin name (default: RbxSync)
         #[arg(short, long)]
         name: Option<String>,
 
-        /// Force download from GitHub even if local file exists
+        /// Install plugin to Studio's plugins folder after building
         #[arg(long)]
-        download: bool,
+        install: bool,
 
-        /// Force install even if marketplace plugin is detected
+        /// Skip obfuscation (obfuscation is enabled by default)
         #[arg(long)]
-        force: bool,
-    },
-    /// Uninstall the plugin from Roblox Studio's plugins folder
-    Uninstall {
-        /// Plugin name to uninstall (default: RbxSync)
+        no_obfuscate: bool,
+
+        /// Path to obfuscation config file (default: obfuscate.toml)
+        #[arg(long)]
+        obfuscate_config: Option<PathBuf>,
+
+        /// Rebuild everything, ignoring the incremental build cache
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Rebuild whenever a source file changes
         #[arg(short, long)]
-        name: Option<String>,
+        watch: bool,
+
+        /// Send each build to the hot-reload bootstrap plugin in Studio through the server
+        #[arg(long)]
+        hot: bool,
     },
-    /// List installed Roblox Studio plugins
-    List,
-}
 
-#[derive(Subcommand)]
-enum DebugAction {
-    /// Start a playtest (Run mode)
-    Start {
-        /// Playtest mode: run (default), play, server
-        #[arg(short, long, default_value = "run")]
-        mode: String,
+    /// Obfuscate the Luau files in a directory and report what changed
+    Obfuscate {
+        /// Directory with .luau/.lua files
+        dir: PathBuf,
+
+        /// Write obfuscated copies here instead of only reporting
+        #[arg(short, long, conflicts_with = "in_place")]
+        output: Option<PathBuf>,
+
+        /// Overwrite the files in place
+        #[arg(long)]
+        in_place: bool,
+
+        /// Path to obfuscation config file (default: obfuscate.toml)
+        #[arg(long)]
+        config: Option<PathBuf>,
     },
-    /// Stop the current playtest
-    Stop,
-    /// Show playtest status
-    Status,
-}
 
-#[derive(Subcommand)]
-enum HarnessAction {
-    /// Initialize harness for a project
-    Init {
-        /// Game name
+    /// Manage the RbxSync Studio plugin
+    Plugin {
+        #[command(subcommand)]
+        action: PluginAction,
+    },
+
+    /// Generate sourcemap.json for Luau LSP
+    Sourcemap {
+        /// Project directory (default: current directory)
         #[arg(short, long)]
-        name: String,
+        path: Option<PathBuf>,
 
-        /// Game genre (e.g., RPG, Simulator, Obby)
+        /// Output file (default: sourcemap.json)
         #[arg(short, long)]
-        genre: Option<String>,
+        output: Option<PathBuf>,
 
-        /// Game description
+        /// Include non-script instances
+        #[arg(long, default_value = "false")]
+        include_non_scripts: bool,
+
+        /// Annotate nodes with child counts, script line counts and file sizes
+        #[arg(long)]
+        stats: bool,
+
+        /// Keep running and regenerate when instances are created, renamed or deleted
+        #[arg(long)]
+        watch: bool,
+    },
+
+    /// Generate a Luau module that builds a src/ subtree with Instance.new
+    Codegen {
+        /// Directory or .rbxjson file under src/ to convert
+        path: PathBuf,
+
+        /// Output file (default: print to stdout)
         #[arg(short, long)]
-        description: Option<String>,
+        output: Option<PathBuf>,
+
+        /// Overwrite the output file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Production console: import errors, generate the in-game reporter, view grouped errors
+    Console {
+        #[command(subcommand)]
+        action: ConsoleAction,
+    },
+
+    /// Save a project as a reusable template
+    Template {
+        #[command(subcommand)]
+        action: TemplateAction,
+    },
+
+    /// Work with the .rbxjson JSON Schema
+    Schema {
+        #[command(subcommand)]
+        action: SchemaAction,
+    },
+
+    /// Expand a .rbxl or .rbxlx place into the project's src without Studio
+    Open {
+        /// Place file to import
+        file: PathBuf,
 
         /// Project directory (default: current directory)
         #[arg(short, long)]
         path: Option<PathBuf>,
+
+        /// Replace an existing, non-empty src directory
+        #[arg(long)]
+        force: bool,
     },
-    /// Show harness status for the project
-    Status {
+
+    /// Publish a place to Roblox through Open Cloud
+    Publish {
+        /// Place file to publish (default: build the project first)
+        file: Option<PathBuf>,
+
         /// Project directory (default: current directory)
         #[arg(short, long)]
         path: Option<PathBuf>,
+
+        /// Target universe ID (default: publish.universeId in rbxsync.json)
+        #[arg(long)]
+        universe: Option<u64>,
+
+        /// Target place ID (default: publish.placeId in rbxsync.json)
+        #[arg(long)]
+        place: Option<u64>,
+
+        /// Description logged with the new version in builds/publishes.jsonl
+        #[arg(long)]
+        version_description: Option<String>,
+
+        /// Target place ID, to publish without the prompt
+        #[arg(long)]
+        confirm: Option<u64>,
     },
-    /// List features
-    Features {
-        /// Filter by status (planned, in_progress, completed, blocked, cancelled)
-        #[arg(short, long)]
-        status: Option<String>,
 
+    /// Build a .rbxl or .rbxm file from project files
+    Build {
         /// Project directory (default: current directory)
         #[arg(short, long)]
         path: Option<PathBuf>,
-    },
-    /// Create or update a feature
-    Feature {
-        /// Feature name (creates new feature if no --id is provided)
-        name: String,
 
-        /// Feature ID (for updating existing feature)
-        #[arg(long)]
-        id: Option<String>,
+        /// Output file (default: build/game.rbxl)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
 
-        /// Feature status (planned, in_progress, completed, blocked, cancelled)
+        /// Output format: rbxl, rbxm, rbxlx (XML place), or rbxmx (XML model) (default: rbxl)
         #[arg(short, long)]
-        status: Option<String>,
+        format: Option<String>,
 
-        /// Feature description
+        /// Watch for file changes and rebuild automatically
         #[arg(short, long)]
-        description: Option<String>,
+        watch: bool,
 
-        /// Feature priority (low, medium, high, critical)
+        /// Output to Studio plugins folder with this filename (e.g., MyPlugin.rbxm)
         #[arg(long)]
-        priority: Option<String>,
+        plugin: Option<String>,
 
-        /// Add a note to the feature
+        /// Fail if any property value can't be built instead of dropping it
         #[arg(long)]
-        note: Option<String>,
+        strict: bool,
+
+        /// Override variant from rbxsync.json (default: RBXSYNC_VARIANT, then "variant")
+        #[arg(long)]
+        variant: Option<String>,
+
+        /// Build only this subtree as a model, e.g. ReplicatedStorage/UIKit (repeatable)
+        #[arg(long = "model", value_name = "PATH", conflicts_with = "plugin")]
+        models: Vec<String>,
+
+        /// Build every model in the "models" section of rbxsync.json
+        #[arg(long = "all-models", conflicts_with_all = ["plugin", "models"])]
+        all_models: bool,
+
+        /// Obfuscate scripts (limited by "obfuscation" in rbxsync.json)
+        #[arg(long)]
+        obfuscate: bool,
+
+        /// Replace TOKEN with VALUE in scripts, over "defines" in rbxsync.json (repeatable)
+        #[arg(long = "define", value_name = "TOKEN=VALUE")]
+        defines: Vec<String>,
+
+        /// Build profile from "profiles" in rbxsync.json (e.g. release)
+        #[arg(long)]
+        profile: Option<String>,
+    },
 
+    /// Build src in memory, read it back and report anything lost (exit 1 if lossy)
+    Verify {
         /// Project directory (default: current directory)
         #[arg(short, long)]
         path: Option<PathBuf>,
-    },
-    /// Manage development sessions
-    Session {
-        #[command(subcommand)]
-        action: SessionAction,
-    },
-}
 
-#[derive(Subcommand)]
-enum SessionAction {
-    /// Start a new development session
-    Start {
-        /// Initial goals for the session
-        #[arg(short, long)]
-        goals: Option<String>,
+        /// Format to round-trip through: rbxl, rbxm, rbxlx, or rbxmx
+        #[arg(short, long, default_value = "rbxl")]
+        format: String,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
 
+    /// Watch the project, rebuilding and live-syncing to Studio on every change
+    Dev {
         /// Project directory (default: current directory)
         #[arg(short, long)]
         path: Option<PathBuf>,
-    },
-    /// End the current session
-    End {
-        /// Session ID to end
-        #[arg(short, long)]
-        id: String,
 
-        /// Summary of what was accomplished
+        /// Output file (default: build/game.rbxl)
         #[arg(short, long)]
-        summary: Option<String>,
+        output: Option<PathBuf>,
 
-        /// Handoff notes for future sessions
-        #[arg(long)]
-        handoff: Option<Vec<String>>,
+        /// Output format: rbxl, rbxm, rbxlx (XML place), or rbxmx (XML model)
+        #[arg(short, long, default_value = "rbxl")]
+        format: String,
+    },
 
+    /// Format project JSON files with consistent style
+    FmtProject {
         /// Project directory (default: current directory)
         #[arg(short, long)]
         path: Option<PathBuf>,
+
+        /// Check formatting without writing (exit 1 if unformatted)
+        #[arg(long)]
+        check: bool,
     },
-}
 
-/// Check for duplicate rbxsync installations that might cause version confusion
-fn check_duplicate_installations() {
-    // Skip if we're being called recursively (to check version)
-    if std::env::var("RBXSYNC_VERSION_CHECK").is_ok() {
-        return;
-    }
+    /// Open RbxSync documentation in browser
+    Doc,
 
-    let current_exe = match std::env::current_exe() {
-        Ok(path) => path,
-        Err(_) => return,
-    };
+    /// Update RbxSync from GitHub releases
+    Update {
+        /// Build from source instead of downloading (requires Rust)
+        #[arg(long)]
+        from_source: bool,
 
-    let current_version = env!("CARGO_PKG_VERSION");
+        /// Also update VS Code extension
+        #[arg(long)]
+        vscode: bool,
 
-    // Common installation paths to check
-    let home = std::env::var("HOME").unwrap_or_default();
-    let paths_to_check = [
-        "/usr/local/bin/rbxsync".to_string(),
-        "/usr/bin/rbxsync".to_string(),
-        format!("{}/.cargo/bin/rbxsync", home),
-        format!("{}/.local/bin/rbxsync", home),
-    ];
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
 
-    for path_str in &paths_to_check {
-        let path = std::path::Path::new(path_str);
+    /// Uninstall RbxSync (remove CLI, plugin, and optionally VS Code extension)
+    Uninstall {
+        /// Also remove VS Code extension
+        #[arg(long)]
+        vscode: bool,
 
-        // Skip if it's the same as current exe or doesn't exist
-        if !path.exists() {
-            continue;
-        }
+        /// Keep the cloned repo at ~/.rbxsync/repo
+        #[arg(long)]
+        keep_repo: bool,
 
-        if let Ok(canonical_current) = current_exe.canonicalize() {
-            if let Ok(canonical_other) = path.canonicalize() {
-                if canonical_current == canonical_other {
-                    continue;
-                }
-            }
-        }
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
 
-        // Found a different installation - check its version
-        // Set env var to prevent recursive check
-        if let Ok(output) = std::process::Command::new(path)
-            .arg("--version")
-            .env("RBXSYNC_VERSION_CHECK", "1")
-            .output()
-        {
-            let version_output = String::from_utf8_lossy(&output.stdout);
-            let other_version = version_output
-                .split_whitespace()
-                .last()
-                .unwrap_or("unknown");
+    /// Show current version and check for updates
+    Version,
 
-            if other_version != current_version {
-                eprintln!("⚠️  Warning: Multiple rbxsync installations detected with different versions!");
-                eprintln!("   Running:  {} (v{})", current_exe.display(), current_version);
-                eprintln!("   Found:    {} (v{})", path_str, other_version);
-                eprintln!();
-                eprintln!("   This can cause confusion. To fix, remove the older version:");
-                eprintln!("   sudo rm {}", path_str);
-                eprintln!();
-            }
-        }
-    }
-}
+    /// Migrate from Rojo project to RbxSync, or legacy .lua files to .luau
+    Migrate {
+        /// Source format: "rojo" (project file) or "lua" (.lua scripts)
+        #[arg(long, default_value = "rojo")]
+        from: String,
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive("rbxsync=info".parse().unwrap()),
-        )
+        /// Export this project to another format instead: "rojo"
+        #[arg(long, conflicts_with = "from")]
+        to: Option<String>,
+
+        /// Path to project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+
+        /// Overwrite existing rbxsync.json (or default.project.json with --to rojo)
+        #[arg(long)]
+        force: bool,
+
+        /// Show what would change without modifying files
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Start the Flux agent (control Studio via iMessage)
+    Flux {
+        /// Run in local mode (terminal testing, no iMessage)
+        #[arg(long)]
+        local: bool,
+
+        /// Set the Anthropic API key
+        #[arg(long)]
+        set_api_key: Option<String>,
+    },
+
+    /// Manage AI development harness (multi-session tracking)
+    Harness {
+        #[command(subcommand)]
+        action: HarnessAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum PluginAction {
+    /// Install the plugin to Roblox Studio's plugins folder (downloads from GitHub if needed)
+    Install {
+        /// Path to .rbxm plugin file (downloads from GitHub if not specified)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+
+        /// Plugin name (default: RbxSync)
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Force download from GitHub even if local file exists
+        #[arg(long)]
+        download: bool,
+
+        /// Force install even if marketplace plugin is detected
+        #[arg(long)]
+        force: bool,
+    },
+    /// Uninstall the plugin from Roblox Studio's plugins folder
+    Uninstall {
+        /// Plugin name to uninstall (default: RbxSync)
+        #[arg(short, long)]
+        name: Option<String>,
+    },
+    /// List installed Roblox Studio plugins
+    List,
+}
+
+#[derive(Subcommand)]
+enum BotAction {
+    /// Run a scenario file (YAML or JSON) against the running playtest
+    Run {
+        /// Scenario file
+        file: PathBuf,
+
+        /// Print the JSON report instead of a summary
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConsoleAction {
+    /// Send errors from an analytics export (CSV, JSON or JSON lines) to the console
+    Import {
+        /// Export file
+        file: PathBuf,
+
+        /// Place the export belongs to
+        #[arg(long)]
+        place_id: Option<u64>,
+    },
+    /// Write a server Script that reports live-game errors to RbxSync
+    Reporter {
+        /// Publicly reachable URL of this server's /console/ingest endpoint
+        #[arg(long)]
+        url: String,
+
+        /// Key the server expects (see RBXSYNC_INGEST_KEY)
+        #[arg(long)]
+        key: Option<String>,
+
+        /// Output file (default: src/ServerScriptService/RbxSyncErrorReporter.server.luau)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Show errors grouped by message, most frequent first
+    Errors {
+        /// Only messages with this tag ("production", or "studio" for local output)
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Include warnings
+        #[arg(long)]
+        warnings: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum LogsSource {
+    /// Search Studio's console output kept by the server, or export it
+    Studio {
+        /// Only messages containing this text (case-insensitive)
+        #[arg(short, long)]
+        grep: Option<String>,
+
+        /// Treat --grep as a regular expression
+        #[arg(short = 'E', long, requires = "grep")]
+        regex: bool,
+
+        /// Minimum level: info, warn or error
+        #[arg(short, long)]
+        level: Option<String>,
+
+        /// Only messages from this source (studio, plugin, production, ...)
+        #[arg(short, long)]
+        source: Option<String>,
+
+        /// Only messages received since then: Unix seconds or an age (30s, 10m, 2h, 1d)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only messages received until then, in the same forms
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Number of recent messages to show
+        #[arg(short = 'n', long, default_value = "50")]
+        limit: usize,
+
+        /// Write every matching message to this file instead of printing them
+        #[arg(long)]
+        export: Option<PathBuf>,
+
+        /// Export format: text or jsonl (default: from the file extension)
+        #[arg(long, requires = "export")]
+        format: Option<String>,
+
+        /// Server port (default: the project's server)
+        #[arg(long)]
+        port: Option<u16>,
+    },
+}
+
+#[derive(Subcommand)]
+enum BackupAction {
+    /// List backups, newest first
+    List {
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+
+    /// Restore src/ from a backup, or only some paths in it
+    Restore {
+        /// Paths relative to src/ (default: the whole tree)
+        paths: Vec<String>,
+
+        /// Backup to restore (default: the newest)
+        #[arg(long)]
+        backup: Option<String>,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SnapshotAction {
+    /// Copy src (and optionally terrain) to .rbxsync/snapshots/<name>
+    Save {
+        /// Snapshot name
+        name: String,
+
+        /// Save terrain/ too
+        #[arg(long)]
+        terrain: bool,
+
+        /// Replace an existing snapshot of the same name
+        #[arg(short, long)]
+        force: bool,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+
+    /// List snapshots, newest first
+    List {
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+
+    /// Make Studio match a snapshot: update its instances and delete the rest
+    Restore {
+        /// Snapshot name
+        name: String,
+
+        /// Replace src with the snapshot's files too (the current src is backed up)
+        #[arg(long)]
+        files: bool,
+
+        /// Leave terrain alone even if the snapshot has it
+        #[arg(long)]
+        no_terrain: bool,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum WallyAction {
+    /// Compare wally.lock with the installed Packages folders
+    Check {
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+
+        /// Reinstall from wally.lock to bring Packages back in line with it
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Download the packages in wally.lock and regenerate their link scripts
+    Install {
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+
+        /// Download every package again, even ones already installed
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum FlagsAction {
+    /// List flags and their state
+    List {
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+
+    /// Turn a flag on or off, creating it if needed
+    Set {
+        /// Flag name (letters, digits and underscores)
+        name: String,
+
+        /// on or off
+        value: String,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+
+    /// Remove a flag
+    Unset {
+        /// Flag name
+        name: String,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TemplateAction {
+    /// Create a template from a project, without its place IDs, secrets and local state
+    Create {
+        /// Project directory to copy (default: current directory)
+        #[arg(long)]
+        from: Option<PathBuf>,
+
+        /// Template name
+        #[arg(short, long)]
+        name: String,
+
+        /// Replace an existing template with the same name
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// List saved templates
+    List,
+}
+
+#[derive(Subcommand)]
+enum SchemaAction {
+    /// Write the schema into the project for editor validation
+    Emit {
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+
+        /// Don't add the schema to .vscode/settings.json
+        #[arg(long)]
+        no_vscode: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ComponentsAction {
+    /// List tagged instances in local files, grouped by tag
+    List {
+        /// Only show this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Show tag/attribute differences between local files and Studio
+    Diff {
+        /// Only show this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum UsersAction {
+    /// Add a user, or give an existing one a new token, and print the token
+    Add {
+        /// User name, recorded in the request audit log
+        name: String,
+    },
+    /// Remove a user and delete their token
+    Remove { name: String },
+    /// List the users
+    List,
+}
+
+#[derive(Subcommand)]
+enum SyncAction {
+    /// Hold live sync changes, e.g. during a large git operation
+    Pause {
+        /// Why it's paused, shown by `rbxsync status`
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Send the changes made while paused and resume live sync
+    Resume,
+    /// Diff the whole project with Studio and push it, e.g. after switching branches
+    Resync {
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum GitAction {
+    /// List branches, or create one
+    Branch {
+        /// Branch to create (lists branches when omitted)
+        name: Option<String>,
+
+        /// Commit or branch to start from (default: HEAD)
+        #[arg(long, requires = "name")]
+        from: Option<String>,
+
+        /// Check the new branch out
+        #[arg(short, long, requires = "name")]
+        checkout: bool,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Check out a branch or commit; Studio follows per `sync.branchSync`
+    Checkout {
+        /// Branch or commit
+        target: String,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Pull from the remote
+    Pull {
+        /// Remote (default: the upstream's)
+        remote: Option<String>,
+
+        /// Remote branch (default: the upstream)
+        branch: Option<String>,
+
+        /// Rebase instead of merging
+        #[arg(long)]
+        rebase: bool,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Push to the remote
+    Push {
+        /// Remote (default: the upstream's)
+        remote: Option<String>,
+
+        /// Branch to push (default: the current branch)
+        branch: Option<String>,
+
+        /// Track the pushed branch
+        #[arg(short = 'u', long)]
+        set_upstream: bool,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Stash changes, or pop, apply, drop or list stashes
+    Stash {
+        #[arg(value_parser = ["push", "pop", "apply", "drop", "list"], default_value = "push")]
+        action: String,
+
+        /// Message for push
+        #[arg(short, long)]
+        message: Option<String>,
+
+        /// Stash for pop, apply and drop (default: the newest)
+        #[arg(long)]
+        index: Option<usize>,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AssetAction {
+    /// Upload images, audio or models and point .rbxjson references at them
+    Upload {
+        /// Files to upload
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum StudioAction {
+    /// Save the open place to Roblox
+    Save,
+    /// Build the project and publish it to the place in rbxsync.json
+    Publish {
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+
+        /// Target place ID, to publish without the prompt
+        #[arg(long)]
+        confirm: Option<u64>,
+    },
+    /// Start a playtest and report its output
+    Play {
+        /// Playtest mode: play (default), run, server-and-clients
+        #[arg(short, long, default_value = "play")]
+        mode: String,
+
+        /// Clients for server-and-clients
+        players: Option<u32>,
+
+        /// Seconds before the playtest stops itself
+        #[arg(short, long)]
+        duration: Option<u64>,
+
+        /// Return once the playtest has started instead of waiting for it
+        #[arg(long)]
+        detach: bool,
+    },
+    /// Stop the playtest and report its output
+    Stop,
+}
+
+#[derive(Subcommand)]
+enum DebugAction {
+    /// Start a playtest (Run mode)
+    Start {
+        /// Playtest mode: run (default), play, server
+        #[arg(short, long, default_value = "run")]
+        mode: String,
+    },
+    /// Stop the current playtest
+    Stop,
+    /// Show playtest status
+    Status,
+}
+
+#[derive(Subcommand)]
+enum HarnessAction {
+    /// Initialize harness for a project
+    Init {
+        /// Game name
+        #[arg(short, long)]
+        name: String,
+
+        /// Game genre (e.g., RPG, Simulator, Obby)
+        #[arg(short, long)]
+        genre: Option<String>,
+
+        /// Game description
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Show harness status for the project
+    Status {
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// List features
+    Features {
+        /// Filter by status (planned, in_progress, completed, blocked, cancelled)
+        #[arg(short, long)]
+        status: Option<String>,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Create or update a feature
+    Feature {
+        /// Feature name (creates new feature if no --id is provided)
+        name: String,
+
+        /// Feature ID (for updating existing feature)
+        #[arg(long)]
+        id: Option<String>,
+
+        /// Feature status (planned, in_progress, completed, blocked, cancelled)
+        #[arg(short, long)]
+        status: Option<String>,
+
+        /// Feature description
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Feature priority (low, medium, high, critical)
+        #[arg(long)]
+        priority: Option<String>,
+
+        /// Add a note to the feature
+        #[arg(long)]
+        note: Option<String>,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Manage development sessions
+    Session {
+        #[command(subcommand)]
+        action: SessionAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum SessionAction {
+    /// Start a new development session
+    Start {
+        /// Initial goals for the session
+        #[arg(short, long)]
+        goals: Option<String>,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// End the current session
+    End {
+        /// Session ID to end
+        #[arg(short, long)]
+        id: String,
+
+        /// Summary of what was accomplished
+        #[arg(short, long)]
+        summary: Option<String>,
+
+        /// Handoff notes for future sessions
+        #[arg(long)]
+        handoff: Option<Vec<String>>,
+
+        /// Project directory (default: current directory)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+}
+
+/// Check for duplicate rbxsync installations that might cause version confusion
+fn check_duplicate_installations() {
+    // Skip if we're being called recursively (to check version)
+    if std::env::var("RBXSYNC_VERSION_CHECK").is_ok() {
+        return;
+    }
+
+    let current_exe = match std::env::current_exe() {
+        Ok(path) => path,
+        Err(_) => return,
+    };
+
+    let current_version = env!("CARGO_PKG_VERSION");
+
+    // Common installation paths to check
+    let home = std::env::var("HOME").unwrap_or_default();
+    let paths_to_check = [
+        "/usr/local/bin/rbxsync".to_string(),
+        "/usr/bin/rbxsync".to_string(),
+        format!("{}/.cargo/bin/rbxsync", home),
+        format!("{}/.local/bin/rbxsync", home),
+    ];
+
+    for path_str in &paths_to_check {
+        let path = std::path::Path::new(path_str);
+
+        // Skip if it's the same as current exe or doesn't exist
+        if !path.exists() {
+            continue;
+        }
+
+        if let Ok(canonical_current) = current_exe.canonicalize() {
+            if let Ok(canonical_other) = path.canonicalize() {
+                if canonical_current == canonical_other {
+                    continue;
+                }
+            }
+        }
+
+        // Found a different installation - check its version
+        // Set env var to prevent recursive check
+        if let Ok(output) = std::process::Command::new(path)
+            .arg("--version")
+            .env("RBXSYNC_VERSION_CHECK", "1")
+            .output()
+        {
+            let version_output = String::from_utf8_lossy(&output.stdout);
+            let other_version = version_output
+                .split_whitespace()
+                .last()
+                .unwrap_or("unknown");
+
+            if other_version != current_version {
+                eprintln!("{}", tr!("install.duplicate_warning"));
+                eprintln!("{}", tr!("install.running", current_exe.display(), current_version));
+                eprintln!("{}", tr!("install.found", path_str, other_version));
+                eprintln!();
+                eprintln!("{}", tr!("install.remove_older"));
+                eprintln!("   sudo rm {}", path_str);
+                eprintln!();
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize logging: the console, plus per-project log files when serving
+    use tracing_subscriber::prelude::*;
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer())
+        .with(rbxsync_server::logs::ProjectLogLayer)
+        .with(
+            tracing_subscriber::EnvFilter::from_default_env()
+                .add_directive("rbxsync=info".parse().unwrap()),
+        )
         .init();
 
-    // Check for duplicate installations that might cause confusion
-    check_duplicate_installations();
+    let cli = Cli::parse();
+
+    if !i18n::init(cli.lang.as_deref()) {
+        let requested = cli.lang.clone().or_else(|| std::env::var(i18n::LANG_ENV_VAR).ok()).unwrap_or_default();
+        eprintln!("{}", tr!("lang.unknown", requested, i18n::available_languages().join(", ")));
+    }
+
+    // Check for duplicate installations that might cause confusion (after
+    // the language is picked, since the warning is translated)
+    check_duplicate_installations();
+
+    match cli.command {
+        Commands::Init { name, path, no_sourcemap, template } => {
+            cmd_init(name, path, no_sourcemap, template).await?;
+        }
+        Commands::Studio { action: Some(action), .. } => {
+            cmd_studio_control(action).await?;
+        }
+        Commands::Studio { action: None, place, serve } => {
+            cmd_studio(place, serve).await?;
+        }
+        Commands::Debug { action } => {
+            cmd_debug(action).await?;
+        }
+        Commands::Test { mode, timeout, roots, bootstrap, backend, json, report, output, path } => {
+            let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+            let request = rbxsync_core::api::TestRunRequest {
+                mode: Some(mode),
+                timeout: Some(timeout),
+                roots,
+                bootstrap,
+                backend,
+                project_dir: Some(project_dir.to_string_lossy().to_string()),
+            };
+            cmd_test(request, json, report, output).await?;
+        }
+        Commands::Bot { action } => {
+            cmd_bot(action).await?;
+        }
+        Commands::Extract {
+            service,
+            terrain,
+            assets,
+            output,
+            strict,
+            incremental,
+        } => {
+            cmd_extract(service, terrain, assets, output, strict, incremental).await?;
+        }
+        Commands::Serve {
+            port,
+            background,
+            failover,
+            supervised,
+            supervisor,
+            auth,
+            listen,
+            tls_cert,
+            tls_key,
+            users,
+            oidc_issuer,
+            oidc_client_id,
+            oidc_allow,
+        } => {
+            let ip = listen.map(|addr| addr.ip()).unwrap_or(std::net::Ipv4Addr::LOCALHOST.into());
+            let network = ServeNetwork {
+                ip,
+                // Off this machine, the token is all that stands between the network and /run
+                auth: auth || users || oidc_issuer.is_some() || !ip.is_loopback(),
+                tls: tls_cert.zip(tls_key).map(|(cert, key)| rbxsync_server::TlsConfig { cert, key }),
+                users,
+                oidc: oidc_issuer.zip(oidc_client_id).map(|(issuer, client_id)| rbxsync_server::oidc::OidcConfig {
+                    issuer,
+                    client_id,
+                    allow: oidc_allow,
+                }),
+            };
+            let port = listen.map(|addr| addr.port()).unwrap_or(port);
+            cmd_serve(port, background, failover, supervised, supervisor, network).await?;
+        }
+        Commands::Doctor { port } => {
+            cmd_doctor(port).await?;
+        }
+        Commands::Stop { port } => {
+            cmd_stop(&port).await?;
+        }
+        Commands::Status { all, cancel } => {
+            if all {
+                cmd_status_all().await?;
+            } else if let Some(id) = cancel {
+                cmd_cancel_job(&id).await?;
+            } else {
+                cmd_status().await?;
+            }
+        }
+        Commands::Recover { path, rollback, finish } => {
+            cmd_recover(path, rollback, finish).await?;
+        }
+        Commands::Token { regenerate } => {
+            cmd_token(regenerate)?;
+        }
+        Commands::Login { token, issuer, client_id } => {
+            cmd_login(token, issuer.zip(client_id)).await?;
+        }
+        Commands::Logout => {
+            rbxsync_core::auth::delete_keychain_token(rbxsync_core::auth::LOGIN_ACCOUNT)
+                .map_err(|e| anyhow::anyhow!(tr!("auth.keychain_failed", e)))?;
+            println!("{}", tr!("login.signed_out"));
+        }
+        Commands::Users { action } => {
+            cmd_users(action)?;
+        }
+        Commands::Logs { studio: Some(source), .. } => {
+            cmd_logs_studio(source).await?;
+        }
+        Commands::Logs { studio: None, follow, level, subsystem, limit, path, port } => {
+            cmd_logs(follow, level, subsystem, limit, path, port).await?;
+        }
+        Commands::Undo { list, to, path } => {
+            let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+            if list {
+                print_backups(&project_dir);
+            } else {
+                restore_backup(&project_dir, to.as_deref(), &[])?;
+            }
+        }
+        Commands::Backup { action } => {
+            cmd_backup(action)?;
+        }
+        Commands::Snapshot { action } => {
+            cmd_snapshot(action).await?;
+        }
+        Commands::Flags { action } => {
+            cmd_flags(action)?;
+        }
+        Commands::Wally { action } => {
+            cmd_wally(action).await?;
+        }
+        Commands::Summarize { path, json } => {
+            cmd_summarize(path, json)?;
+        }
+        Commands::Stats { path, weeks, json } => {
+            cmd_stats(path, weeks, json)?;
+        }
+        Commands::Blame { instance_path, path, limit, json } => {
+            cmd_blame(&instance_path, path, limit, json)?;
+        }
+        Commands::Export { site, path } => {
+            cmd_export_site(&site, path)?;
+        }
+        Commands::Bench { instances, iterations, save, baseline, max_regression } => {
+            cmd_bench(instances, iterations, save, baseline, max_regression).await?;
+        }
+        Commands::Diff { places, properties, root, paths_only } => match places {
+            Some(places) => cmd_diff_places(&places[0], &places[1], properties, root).await?,
+            None => cmd_diff(!paths_only).await?,
+        },
+        Commands::Components { action } => {
+            cmd_components(action).await?;
+        }
+        Commands::Asset { action } => {
+            cmd_asset(action).await?;
+        }
+        Commands::Sync { action, instances, path, no_delete, variant, watch } => {
+            if let Some(action) = action {
+                cmd_sync_pause(action).await?;
+            } else if watch {
+                cmd_sync_watch(path, !no_delete).await?;
+            } else {
+                cmd_sync(path, !no_delete, variant, &instances).await?;
+            }
+        }
+        Commands::Git { action } => {
+            cmd_git(action).await?;
+        }
+        Commands::Resolve { path, file, studio, merged } => {
+            let resolution = [(file, "file"), (studio, "studio"), (merged, "merged")]
+                .into_iter()
+                .find_map(|(set, name)| set.then_some(name));
+            cmd_resolve(path, resolution).await?;
+        }
+        Commands::Replace { class, prop, from, to, path, dry_run } => {
+            let spec = PropertyReplace { class_name: class, property: prop, from, to, path };
+            cmd_replace(spec, dry_run).await?;
+        }
+        Commands::BuildPlugin {
+            source,
+            output,
+            name,
+            install,
+            no_obfuscate,
+            obfuscate_config,
+            no_cache,
+            watch,
+            hot,
+        } => {
+            let config = PluginBuildConfig {
+                source_dir: source.unwrap_or_else(|| PathBuf::from("plugin/src")),
+                output_path: output.unwrap_or_else(|| PathBuf::from("build/RbxSync.rbxm")),
+                plugin_name: name.unwrap_or_else(|| "RbxSync".to_string()),
+                obfuscate: !no_obfuscate,
+                obfuscate_config,
+                cache_dir: (!no_cache).then(|| PathBuf::from(rbxsync_core::DEFAULT_BUILD_CACHE_DIR)),
+            };
+            cmd_build_plugin(config, install, watch, hot).await?;
+        }
+        Commands::Obfuscate {
+            dir,
+            output,
+            in_place,
+            config,
+        } => {
+            cmd_obfuscate(dir, output, in_place, config)?;
+        }
+        Commands::Plugin { action } => {
+            cmd_plugin(action).await?;
+        }
+        Commands::Sourcemap {
+            path,
+            output,
+            include_non_scripts,
+            stats,
+            watch,
+        } => {
+            cmd_sourcemap(path, output, include_non_scripts, stats, watch).await?;
+        }
+        Commands::Codegen { path, output, force } => {
+            cmd_codegen(path, output, force)?;
+        }
+        Commands::Console { action } => {
+            cmd_console(action).await?;
+        }
+        Commands::Template { action } => {
+            cmd_template(action)?;
+        }
+        Commands::Schema { action } => {
+            cmd_schema(action)?;
+        }
+        Commands::Open { file, path, force } => {
+            cmd_open(&file, path, force)?;
+        }
+        Commands::Build {
+            path,
+            output,
+            format,
+            watch,
+            plugin,
+            strict,
+            variant,
+            models,
+            all_models,
+            obfuscate,
+            defines,
+            profile,
+        } => {
+            let defines = defines
+                .iter()
+                .map(|d| rbxsync_core::parse_define(d).with_context(|| tr!("build.invalid_define", d)))
+                .collect::<Result<_>>()?;
+            let mut options = BuildOptions { variant, strict, obfuscate, defines, ..Default::default() };
+            let (output, format) = match profile {
+                Some(name) => apply_build_profile(path.as_deref(), &name, output, format, &mut options)?,
+                None => (output, format),
+            };
+            let format = format.unwrap_or_else(|| "rbxl".to_string());
+            if !models.is_empty() || all_models {
+                cmd_build_models(path, output, format, watch, options, models).await?;
+            } else {
+                cmd_build(path, output, format, watch, plugin, options).await?;
+            }
+        }
+        Commands::Publish {
+            file,
+            path,
+            universe,
+            place,
+            version_description,
+            confirm,
+        } => {
+            cmd_publish(file, path, universe, place, version_description, confirm).await?;
+        }
+        Commands::Verify { path, format, json } => {
+            cmd_verify(path, &format, json)?;
+        }
+        Commands::Dev { path, output, format } => {
+            cmd_dev(path, output, format).await?;
+        }
+        Commands::FmtProject { path, check } => {
+            cmd_fmt_project(path, check)?;
+        }
+        Commands::Doc => {
+            cmd_doc()?;
+        }
+        Commands::Update { from_source, vscode, yes } => {
+            cmd_update(from_source, vscode, yes).await?;
+        }
+        Commands::Version => {
+            cmd_version().await?;
+        }
+        Commands::Flux { local, set_api_key } => {
+            // Flux agent is not yet implemented in the CLI
+            println!("{}", tr!("flux.coming_soon"));
+            if local {
+                println!("{}", tr!("flux.local_noted"));
+            }
+            if let Some(key) = set_api_key {
+                println!("{}", tr!("flux.api_key", &key[..8.min(key.len())]));
+            }
+        }
+        Commands::Uninstall { vscode, keep_repo, yes } => {
+            cmd_uninstall(vscode, keep_repo, yes)?;
+        }
+        Commands::Migrate { from, to, path, force, dry_run } => {
+            match to {
+                Some(to) => cmd_export(&to, path, force, dry_run)?,
+                None => cmd_migrate(from, path, force, dry_run)?,
+            }
+        }
+        Commands::Harness { action } => {
+            cmd_harness(action).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Initialize a new project
+async fn cmd_init(
+    name: Option<String>,
+    path: Option<PathBuf>,
+    no_sourcemap: bool,
+    template: Option<String>,
+) -> Result<()> {
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let project_name = name.unwrap_or_else(|| {
+        project_dir
+            .file_name()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "MyGame".to_string())
+    });
+
+    tracing::info!("Initializing RbxSync project: {}", project_name);
+
+    // Create directory structure
+    let src_dir = project_dir.join("src");
+    let assets_dir = project_dir.join("assets");
+    let terrain_dir = project_dir.join("terrain");
+
+    std::fs::create_dir_all(&src_dir).with_context(|| tr!("init.create_dir_failed", "src"))?;
+    std::fs::create_dir_all(&assets_dir).with_context(|| tr!("init.create_dir_failed", "assets"))?;
+    std::fs::create_dir_all(&terrain_dir).with_context(|| tr!("init.create_dir_failed", "terrain"))?;
+
+    // Create default service directories
+    for service in &[
+        "Workspace",
+        "ReplicatedStorage",
+        "ServerScriptService",
+        "ServerStorage",
+        "StarterGui",
+        "StarterPack",
+        "StarterPlayer",
+    ] {
+        std::fs::create_dir_all(src_dir.join(service))
+            .with_context(|| tr!("init.create_dir_failed", service))?;
+    }
+
+    // Copy the template first so its rbxsync.json wins over the default
+    if let Some(template_name) = &template {
+        let template_dir = rbxsync_core::templates_dir()
+            .with_context(|| tr!("common.no_home"))?
+            .join(template_name);
+        if !template_dir.is_dir() {
+            anyhow::bail!("{}", tr!("init.template_not_found", template_name, template_dir.display()));
+        }
+        let written = rbxsync_core::instantiate_template(&template_dir, &project_dir, &project_name)
+            .with_context(|| tr!("init.copy_template_failed"))?;
+        println!("{}", tr!("init.copied_template", written, template_name));
+    }
+
+    // Create project config
+    let config_path = project_dir.join("rbxsync.json");
+    if template.is_none() || !config_path.exists() {
+        let config = ProjectConfig {
+            name: project_name.clone(),
+            ..Default::default()
+        };
+        let config_json = serde_json::to_string_pretty(&config)?;
+        std::fs::write(&config_path, config_json).with_context(|| tr!("common.write_failed", "rbxsync.json"))?;
+    }
+
+    // Create or update .gitignore (append entries instead of overwriting)
+    let gitignore_path = project_dir.join(".gitignore");
+    let rbxsync_entries = [".rbxsync/", "*.rbxl", "*.rbxlx", ".DS_Store", "Thumbs.db"];
+
+    let existing_content = if gitignore_path.exists() {
+        std::fs::read_to_string(&gitignore_path).unwrap_or_default()
+    } else {
+        String::new()
+    };
+
+    let existing_lines: HashSet<&str> = existing_content.lines().map(|l| l.trim()).collect();
+    let mut additions: Vec<&str> = Vec::new();
+
+    for entry in &rbxsync_entries {
+        if !existing_lines.contains(entry) {
+            additions.push(entry);
+        }
+    }
+
+    if !additions.is_empty() {
+        let mut new_content = existing_content.clone();
+        if !new_content.is_empty() && !new_content.ends_with('\n') {
+            new_content.push('\n');
+        }
+        new_content.push_str("\n# RbxSync\n");
+        for entry in additions {
+            new_content.push_str(entry);
+            new_content.push('\n');
+        }
+        std::fs::write(&gitignore_path, new_content).with_context(|| tr!("common.write_failed", ".gitignore"))?;
+    } else if !gitignore_path.exists() {
+        // Create new .gitignore if it doesn't exist
+        let gitignore_content = "# RbxSync\n.rbxsync/\n*.rbxl\n*.rbxlx\n\n# OS files\n.DS_Store\nThumbs.db\n";
+        std::fs::write(&gitignore_path, gitignore_content).with_context(|| tr!("common.write_failed", ".gitignore"))?;
+    }
+
+    // Generate sourcemap for Luau LSP (unless --no-sourcemap)
+    if !no_sourcemap {
+        let sourcemap_path = project_dir.join("sourcemap.json");
+        rbxsync_core::sourcemap::write_sourcemap(&project_dir, &sourcemap_path, false)
+            .with_context(|| tr!("common.write_failed", "sourcemap.json"))?;
+    }
+
+    println!("{}", tr!("init.initialized", project_name, format!("{:?}", project_dir)));
+    println!("\n{}", tr!("init.structure"));
+    println!("{}", tr!("init.structure_config"));
+    println!("{}", tr!("init.structure_src"));
+    println!("{}", tr!("init.structure_assets"));
+    println!("{}", tr!("init.structure_terrain"));
+    if !no_sourcemap {
+        println!("{}", tr!("init.structure_sourcemap"));
+    }
+    println!("\n{}", tr!("init.next_steps"));
+    println!("{}", tr!("init.step_open"));
+    println!("{}", tr!("init.step_plugin"));
+    println!("{}", tr!("init.step_extract"));
+
+    Ok(())
+}
+
+/// Launch Roblox Studio
+async fn cmd_studio(place: Option<PathBuf>, serve: bool) -> Result<()> {
+    // Find Roblox Studio installation
+    let studio_path = find_studio_path()?;
+
+    println!("{}", tr!("studio.found", studio_path.display()));
+
+    // Optionally start the sync server
+    if serve {
+        let client = server_client().build()?;
+        let base = server_url(None);
+        if client.get(format!("{}/health", base)).send().await.is_err() {
+            println!("{}", tr!("studio.starting_server"));
+            let config = ServerConfig::default();
+            tokio::spawn(async move {
+                if let Err(e) = run_server(config).await {
+                    tracing::error!("Server error: {}", e);
+                }
+            });
+            // Give server time to start
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        } else {
+            println!("{}", tr!("studio.server_running"));
+        }
+    }
+
+    // Build command to launch Studio
+    let mut command = std::process::Command::new("open");
+
+    #[cfg(target_os = "macos")]
+    {
+        command.arg("-a").arg(&studio_path);
+        if let Some(ref place_file) = place {
+            // Validate the file exists and has correct extension
+            if !place_file.exists() {
+                anyhow::bail!("{}", tr!("studio.place_not_found", place_file.display()));
+            }
+            let ext = place_file
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("");
+            if ext != "rbxl" && ext != "rbxlx" {
+                anyhow::bail!("{}", tr!("studio.invalid_place"));
+            }
+            command.arg(place_file);
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        command = std::process::Command::new(&studio_path);
+        if let Some(ref place_file) = place {
+            if !place_file.exists() {
+                anyhow::bail!("{}", tr!("studio.place_not_found", place_file.display()));
+            }
+            command.arg(place_file);
+        }
+    }
+
+    println!("{}", tr!("studio.launching"));
+    command
+        .spawn()
+        .with_context(|| tr!("studio.launch_failed"))?;
+
+    if let Some(place_file) = place {
+        println!("{}", tr!("studio.opening", place_file.display()));
+    }
+
+    if serve {
+        println!("\n{}", tr!("studio.serving"));
+        // Keep running to serve
+        tokio::signal::ctrl_c().await?;
+    }
+
+    Ok(())
+}
+
+/// Control playtest in Studio
+/// Save, publish or playtest the connected Studio
+async fn cmd_studio_control(action: StudioAction) -> Result<()> {
+    let client = server_client().build()?;
+    let base = server_url(None);
+
+    // Check server is running
+    if client.get(format!("{}/health", base)).send().await.is_err() {
+        println!("{}", tr!("server.not_running"));
+        return Ok(());
+    }
+
+    let (endpoint, body) = match action {
+        StudioAction::Save => {
+            println!("{}", tr!("studio.saving"));
+            ("save", serde_json::json!({}))
+        }
+        StudioAction::Publish { path, confirm } => {
+            let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+            let project = project_dir.to_string_lossy().to_string();
+            let Some(target) = rbxsync_server::studio::publish_target(&project) else {
+                println!("{}", tr!("studio.no_publish_target"));
+                println!("  \"publish\": {{ \"universeId\": 123, \"placeId\": 456 }}");
+                std::process::exit(1);
+            };
+            let confirm = confirm_publish(&project_dir.display().to_string(), &target, confirm)?;
+            println!("{}", tr!("studio.publishing"));
+            ("publish", serde_json::json!({ "projectDir": project, "confirm": confirm }))
+        }
+        StudioAction::Play { mode, players, duration, detach } => {
+            println!("{}", tr!("studio.starting_play", mode));
+            let project_dir = std::env::current_dir()?.to_string_lossy().to_string();
+            (
+                "play",
+                serde_json::json!({
+                    "mode": mode,
+                    "players": players,
+                    "duration": duration,
+                    "wait": !detach,
+                    "projectDir": project_dir
+                }),
+            )
+        }
+        StudioAction::Stop => {
+            println!("{}", tr!("studio.stopping_play"));
+            ("stop", serde_json::json!({}))
+        }
+    };
+
+    let response = client
+        .post(format!("{}/studio/{}", base, endpoint))
+        .json(&body)
+        .send()
+        .await
+        .with_context(|| tr!("studio.send_failed"))?;
+    let result: serde_json::Value = response.json().await?;
+
+    if result.get("success").and_then(|v| v.as_bool()) != Some(true) && result.get("output").is_none() {
+        let error = result.get("error").and_then(|v| v.as_str()).unwrap_or("Unknown error");
+        println!("\x1b[31m{}\x1b[0m {}", tr!("studio.failed"), error);
+        std::process::exit(1);
+    }
+
+    match endpoint {
+        "save" => {
+            let name = result.get("placeName").and_then(|v| v.as_str()).unwrap_or("place");
+            println!("\x1b[32m✓\x1b[0m {}", tr!("studio.saved", name));
+        }
+        "publish" => {
+            let version = result.get("versionNumber").and_then(|v| v.as_u64()).unwrap_or(0);
+            let place = result.get("placeId").and_then(|v| v.as_u64()).unwrap_or(0);
+            println!("\x1b[32m✓\x1b[0m {}", tr!("studio.published", place, version));
+        }
+        _ if result.get("started").is_some() => println!("\x1b[32m✓\x1b[0m {}", tr!("studio.play_started")),
+        _ => {
+            for message in result.get("output").and_then(|v| v.as_array()).into_iter().flatten() {
+                let text = message.get("message").and_then(|v| v.as_str()).unwrap_or("");
+                match message.get("type").and_then(|v| v.as_str()) {
+                    Some("MessageError") => println!("\x1b[31m{}\x1b[0m", text),
+                    Some("MessageWarning") => println!("\x1b[33m{}\x1b[0m", text),
+                    _ => println!("{}", text),
+                }
+            }
+            let errors = result.get("errors").and_then(|v| v.as_u64()).unwrap_or(0);
+            let warnings = result.get("warnings").and_then(|v| v.as_u64()).unwrap_or(0);
+            if let Some(error) = result.get("error").and_then(|v| v.as_str()) {
+                println!("\x1b[31m{}\x1b[0m {}", tr!("studio.play_failed"), error);
+                std::process::exit(1);
+            }
+            println!("{}", tr!("studio.play_finished", errors, warnings));
+            if errors > 0 {
+                std::process::exit(1);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Ask for the target place ID before a publish (or take it from --confirm);
+/// exits when it doesn't match
+fn confirm_publish(what: &str, target: &rbxsync_server::nightly::PublishTarget, confirm: Option<u64>) -> Result<u64> {
+    let confirm = match confirm {
+        Some(id) => id,
+        None => {
+            use std::io::{IsTerminal, Write};
+            if !std::io::stdin().is_terminal() {
+                println!("{}", tr!("studio.confirm_needed", target.place_id));
+                std::process::exit(1);
+            }
+            print!("{}", tr!("studio.confirm_prompt", what, target.place_id, target.universe_id));
+            std::io::stdout().flush()?;
+            let mut input = String::new();
+            std::io::stdin().read_line(&mut input)?;
+            input.trim().parse().unwrap_or(0)
+        }
+    };
+    if confirm != target.place_id {
+        println!("{}", tr!("studio.confirm_mismatch"));
+        std::process::exit(1);
+    }
+    Ok(confirm)
+}
+
+/// Publish a place file or a fresh build through Open Cloud (no Studio or server needed)
+async fn cmd_publish(
+    file: Option<PathBuf>,
+    path: Option<PathBuf>,
+    universe: Option<u64>,
+    place: Option<u64>,
+    version_description: Option<String>,
+    confirm: Option<u64>,
+) -> Result<()> {
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let project = project_dir.to_string_lossy().to_string();
+    let Some(target) = rbxsync_server::studio::resolve_target(&project, universe, place) else {
+        println!("{}", tr!("studio.no_publish_target_flags"));
+        println!("  \"publish\": {{ \"universeId\": 123, \"placeId\": 456 }}");
+        std::process::exit(1);
+    };
+    let what = file.as_ref().unwrap_or(&project_dir).display().to_string();
+    confirm_publish(&what, &target, confirm)?;
+
+    println!("{}", if file.is_some() { tr!("studio.publishing_file") } else { tr!("studio.publishing") });
+    let published = rbxsync_server::studio::publish_project(&project, &target, file.as_deref(), version_description.as_deref()).await;
+    match published {
+        Ok(version) => {
+            println!("{}", tr!("studio.published_version", version, target.place_id, target.universe_id));
+            Ok(())
+        }
+        Err((_, e)) => bail!("{}", tr!("studio.publish_failed", e)),
+    }
+}
+
+async fn cmd_test(
+    request: rbxsync_core::api::TestRunRequest,
+    json: bool,
+    report_format: Option<String>,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    use rbxsync_core::api::{TestCaseStatus, TestReport};
+    use rbxsync_server::test_run::{self, report as ci_report, Backend};
+
+    if let Some(format) = &report_format {
+        if !ci_report::FORMATS.contains(&format.as_str()) {
+            bail!("{}", tr!("test.unknown_format", format, ci_report::FORMATS.join(", ")));
+        }
+    }
+    let project = request.project_dir.clone().unwrap_or_default();
+    let backend = Backend::resolve(request.backend.as_deref(), Some(&project)).map_err(|e| anyhow::anyhow!(e))?;
+
+    // JUnit XML on stdout replaces the summary, so it can be redirected to a file
+    let quiet = json || (report_format.as_deref() == Some("junit") && output.is_none());
+    if !quiet {
+        let place = if backend == Backend::Cloud { "Open Cloud" } else { "Studio" };
+        println!("{}", tr!("test.running", place, request.timeout.unwrap_or_default()));
+    }
+
+    let report: TestReport = if backend == Backend::Cloud {
+        // Open Cloud needs no Studio or server, so CI machines run it directly
+        test_run::run_cloud(&request, &project).await.map_err(|(_, e)| anyhow::anyhow!(e))?
+    } else {
+        let base = project_server_url(std::path::Path::new(&project), None);
+        let response = server_client()
+            .build()?
+            .post(format!("{}/test/run", base))
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| tr!("server.not_running"))?;
+        let body: serde_json::Value = response.json().await?;
+        if body.get("suites").is_none() {
+            bail!("{}", body["error"].as_str().map(str::to_string).unwrap_or_else(|| tr!("test.request_failed")));
+        }
+        serde_json::from_value(body)?
+    };
+
+    // Printed ahead of the summary so CI picks it up even when the summary is long
+    if let Some(format) = &report_format {
+        let rendered = match format.as_str() {
+            "junit" => ci_report::junit_xml(&report),
+            _ => {
+                let base = std::env::current_dir()?;
+                ci_report::github_annotations(&report, &std::fs::canonicalize(&base).unwrap_or(base))
+            }
+        };
+        match &output {
+            Some(file) => {
+                std::fs::write(file, rendered).with_context(|| tr!("common.write_failed", file.display()))?;
+                if !quiet {
+                    println!("{}", tr!("test.wrote_report", format, file.display()));
+                }
+            }
+            None => print!("{}", rendered),
+        }
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if !quiet {
+        for suite in &report.suites {
+            println!("{}", suite.name);
+            for case in &suite.cases {
+                let name = if case.classname == suite.name {
+                    case.name.clone()
+                } else {
+                    format!("{} > {}", case.classname, case.name)
+                };
+                match case.status {
+                    TestCaseStatus::Passed => println!("  \x1b[32m✓\x1b[0m {} \x1b[2m({:.2}s)\x1b[0m", name, case.time),
+                    TestCaseStatus::Skipped => println!("  \x1b[33m-\x1b[0m {} \x1b[2m{}\x1b[0m", name, tr!("test.skipped")),
+                    TestCaseStatus::Failed => {
+                        println!("  \x1b[31m✗\x1b[0m {}", name);
+                        for line in case.failure.iter().flat_map(|f| f.message.lines()) {
+                            println!("      \x1b[31m{}\x1b[0m", line);
+                        }
+                        if let Some(location) = case.failure.as_ref().and_then(|f| f.location.as_ref()) {
+                            println!("      \x1b[2m{}\x1b[0m", tr!("test.failure_at", location.link));
+                        }
+                    }
+                }
+            }
+        }
+        if let Some(error) = &report.error {
+            println!("\x1b[31m{}\x1b[0m {}", tr!("test.run_failed"), error);
+        }
+        let time = format!("{:.1}", report.time);
+        println!(
+            "{}",
+            tr!("test.summary", report.tests, report.framework, report.passed, report.failures, report.skipped, time)
+        );
+    }
+
+    if !report.success {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+async fn cmd_debug(action: DebugAction) -> Result<()> {
+    let client = server_client().build()?;
+    let base = server_url(None);
+
+    // Check server is running
+    if client.get(format!("{}/health", base)).send().await.is_err() {
+        println!("{}", tr!("server.not_running"));
+        return Ok(());
+    }
+
+    match action {
+        DebugAction::Start { mode } => {
+            println!("{}", tr!("debug.starting", mode));
+
+            let response = client
+                .post(format!("{}/sync/command", base))
+                .json(&serde_json::json!({
+                    "command": "debug:start",
+                    "payload": {
+                        "mode": mode
+                    }
+                }))
+                .send()
+                .await
+                .with_context(|| tr!("debug.start_request_failed"))?;
+
+            let result: serde_json::Value = response.json().await?;
+            if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
+                println!("{}", tr!("debug.started"));
+            } else {
+                let error = result
+                    .get("error")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| tr!("debug.unknown_error"));
+                println!("{}", tr!("debug.start_failed", error));
+            }
+        }
+        DebugAction::Stop => {
+            println!("{}", tr!("debug.stopping"));
+
+            let response = client
+                .post(format!("{}/sync/command", base))
+                .json(&serde_json::json!({
+                    "command": "debug:stop",
+                    "payload": {}
+                }))
+                .send()
+                .await
+                .with_context(|| tr!("debug.stop_request_failed"))?;
+
+            let result: serde_json::Value = response.json().await?;
+            if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
+                println!("{}", tr!("debug.stopped"));
+            } else {
+                let error = result
+                    .get("error")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| tr!("debug.unknown_error"));
+                println!("{}", tr!("debug.stop_failed", error));
+            }
+        }
+        DebugAction::Status => {
+            let response = client
+                .post(format!("{}/sync/command", base))
+                .json(&serde_json::json!({
+                    "command": "debug:status",
+                    "payload": {}
+                }))
+                .send()
+                .await
+                .with_context(|| tr!("debug.status_request_failed"))?;
+
+            let result: serde_json::Value = response.json().await?;
+            if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
+                let data = result.get("data").cloned().unwrap_or_default();
+                let running = data.get("running").and_then(|v| v.as_bool()).unwrap_or(false);
+                let mode = data.get("mode").and_then(|v| v.as_str()).unwrap_or("unknown");
+
+                if running {
+                    println!("{}", tr!("debug.running", mode));
+                } else {
+                    println!("{}", tr!("debug.not_running"));
+                }
+            } else {
+                let error = result
+                    .get("error")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| tr!("debug.unknown_error"));
+                println!("{}", tr!("debug.status_failed", error));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Find Roblox Studio installation path
+fn find_studio_path() -> Result<PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        let default_path = PathBuf::from("/Applications/RobloxStudio.app");
+        if default_path.exists() {
+            return Ok(default_path);
+        }
+
+        // Try user Applications folder
+        if let Ok(home) = std::env::var("HOME") {
+            let user_path = PathBuf::from(home).join("Applications/RobloxStudio.app");
+            if user_path.exists() {
+                return Ok(user_path);
+            }
+        }
+
+        anyhow::bail!("{}", tr!("studio.not_found_macos"));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // Check common Windows install locations
+        let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_default();
+        let program_files = std::env::var("PROGRAMFILES(X86)")
+            .or_else(|_| std::env::var("PROGRAMFILES"))
+            .unwrap_or_default();
+
+        let possible_paths = [
+            PathBuf::from(&local_app_data).join("Roblox/Versions"),
+            PathBuf::from(&program_files).join("Roblox/Versions"),
+        ];
+
+        for versions_dir in possible_paths {
+            if versions_dir.exists() {
+                // Find the latest version with RobloxStudioBeta.exe
+                if let Ok(entries) = std::fs::read_dir(&versions_dir) {
+                    for entry in entries.flatten() {
+                        let studio_exe = entry.path().join("RobloxStudioBeta.exe");
+                        if studio_exe.exists() {
+                            return Ok(studio_exe);
+                        }
+                    }
+                }
+            }
+        }
+
+        anyhow::bail!("{}", tr!("studio.not_found_windows"));
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        anyhow::bail!("{}", tr!("studio.unsupported_platform"));
+    }
+}
+
+/// Extract game from Studio
+async fn cmd_extract(
+    services: Option<Vec<String>>,
+    terrain: bool,
+    assets: bool,
+    _output: Option<PathBuf>,
+    strict: bool,
+    incremental: bool,
+) -> Result<()> {
+    tracing::info!("Starting extraction...");
+
+    // Incremental extraction compares against this project's last extraction
+    let project_dir = if incremental {
+        Some(std::env::current_dir()?.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    // Check if server is running
+    let client = server_client().build()?;
+    let base = server_url(None);
+    let health_check = client.get(format!("{}/health", base)).send().await;
+
+    if health_check.is_err() {
+        println!("{}", tr!("server.not_running_short"));
+        println!("{}", tr!("server.starting"));
+
+        // Start server in background
+        tokio::spawn(async {
+            if let Err(e) = run_server(ServerConfig::default()).await {
+                tracing::error!("Server error: {}", e);
+            }
+        });
+
+        // Wait for server to start
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    }
+
+    // Send extraction request
+    let response = client
+        .post(format!("{}/extract/start", base))
+        .json(&serde_json::json!({
+            "services": services,
+            "include_terrain": terrain,
+            "include_assets": assets,
+            "strict": strict,
+            "project_dir": project_dir,
+            "incremental": incremental,
+        }))
+        .send()
+        .await
+        .with_context(|| tr!("extract.start_failed"))?;
+
+    let result: serde_json::Value = response.json().await?;
+    println!("{}", tr!("extract.started", serde_json::to_string_pretty(&result)?));
+    if incremental && result.get("incremental").and_then(|v| v.as_bool()) != Some(true) {
+        println!("{}", tr!("extract.no_previous"));
+    }
+
+    println!("\n{}", tr!("extract.waiting"));
+    println!("{}", tr!("extract.plugin_enabled"));
+
+    // Poll for completion
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
+        let status = client
+            .get(format!("{}/extract/status", base))
+            .send()
+            .await?
+            .json::<serde_json::Value>()
+            .await?;
+
+        if let Some(complete) = status.get("complete").and_then(|v| v.as_bool()) {
+            if complete {
+                let chunks = status.get("chunksReceived").and_then(|v| v.as_u64()).unwrap_or(0);
+                let unsupported: Vec<rbxsync_core::UnsupportedProperty> = status
+                    .get("unsupportedProperties")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default();
+                if !unsupported.is_empty() {
+                    let summary = rbxsync_core::summarize_unsupported(&unsupported, 20);
+                    if strict {
+                        bail!("{}", tr!("extract.strict_unsupported", unsupported.len(), summary));
+                    }
+                    println!("\n\x1b[33m!\x1b[0m {}", tr!("extract.placeholders", unsupported.len(), summary));
+                }
+                println!("\n{}", tr!("extract.complete", chunks));
+                break;
+            }
+        }
+
+        if let Some(received) = status.get("chunksReceived").and_then(|v| v.as_u64()) {
+            if let Some(total) = status.get("totalChunks").and_then(|v| v.as_u64()) {
+                print!("\r{}", tr!("extract.progress_total", received, total));
+            } else {
+                print!("\r{}", tr!("extract.progress", received));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Detect project structure for zero-config mode
+fn detect_project_structure() -> Option<String> {
+    let cwd = std::env::current_dir().ok()?;
+
+    // Check for common project structures
+    if cwd.join("src").is_dir() {
+        return Some("src".to_string());
+    }
+
+    // Check for Rojo-style project
+    if cwd.join("default.project.json").exists() {
+        return Some("rojo".to_string());
+    }
+
+    // Check for any .luau or .lua files indicating a Roblox project
+    if let Ok(entries) = std::fs::read_dir(&cwd) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if let Some(ext) = path.extension() {
+                if ext == "luau" || ext == "lua" {
+                    return Some("flat".to_string());
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Where `serve` listens and what it requires of clients
+struct ServeNetwork {
+    ip: std::net::IpAddr,
+    auth: bool,
+    tls: Option<rbxsync_server::TlsConfig>,
+    /// Accept team users' keychain tokens
+    users: bool,
+    oidc: Option<rbxsync_server::oidc::OidcConfig>,
+}
+
+impl ServeNetwork {
+    /// `serve` flags that start a child server with the same settings
+    fn args(&self, port: u16) -> Vec<String> {
+        let mut args = if self.ip.is_loopback() {
+            vec!["--port".to_string(), port.to_string()]
+        } else {
+            vec!["--listen".to_string(), std::net::SocketAddr::new(self.ip, port).to_string()]
+        };
+        if self.auth {
+            args.push("--auth".to_string());
+        }
+        if let Some(tls) = &self.tls {
+            args.push("--tls-cert".to_string());
+            args.push(tls.cert.to_string_lossy().to_string());
+            args.push("--tls-key".to_string());
+            args.push(tls.key.to_string_lossy().to_string());
+        }
+        if self.users {
+            args.push("--users".to_string());
+        }
+        if let Some(oidc) = &self.oidc {
+            args.push("--oidc-issuer".to_string());
+            args.push(oidc.issuer.clone());
+            args.push("--oidc-client-id".to_string());
+            args.push(oidc.client_id.clone());
+            for user in &oidc.allow {
+                args.push("--oidc-allow".to_string());
+                args.push(user.clone());
+            }
+        }
+        args
+    }
+}
+
+/// Start the sync server
+async fn cmd_serve(
+    port: u16,
+    background: bool,
+    failover: bool,
+    supervised: bool,
+    supervisor: bool,
+    network: ServeNetwork,
+) -> Result<()> {
+    if supervisor {
+        let exe = std::env::current_exe()?;
+        let dir = rbxsync_server::crashes::crash_dir().with_context(|| tr!("common.no_home"))?;
+        let args = network.args(port);
+        let server = move || {
+            let mut cmd = std::process::Command::new(&exe);
+            cmd.arg("serve").args(&args);
+            cmd
+        };
+        tokio::task::spawn_blocking(move || rbxsync_server::crashes::supervise(server, &dir))
+            .await?
+            .with_context(|| tr!("serve.supervise_failed"))?;
+        return Ok(());
+    }
+
+    let config_path = std::env::current_dir()?.join("rbxsync.json");
+    let zero_config_mode = !config_path.exists();
+
+    if zero_config_mode {
+        // Zero-config mode: work without rbxsync.json
+        let project_structure = detect_project_structure();
+
+        println!("{}", tr!("serve.zero_config"));
+        println!();
+
+        match project_structure {
+            Some(ref structure) if structure == "src" => {
+                println!("{}", tr!("serve.detected_src"));
+            }
+            Some(ref structure) if structure == "rojo" => {
+                println!("{}", tr!("serve.detected_rojo"));
+                println!("{}", tr!("serve.migrate_tip"));
+            }
+            Some(_) => {
+                println!("{}", tr!("serve.detected_luau"));
+            }
+            None => {
+                println!("{}", tr!("serve.no_project"));
+            }
+        }
+
+        println!();
+        println!("{}", tr!("serve.using_defaults"));
+        println!("{}", tr!("serve.source_folder"));
+        println!("{}", tr!("serve.assets_folder"));
+        println!();
+        println!("{}", tr!("serve.more_control"));
+        println!();
+    } else {
+        // Validate JSON is parseable if config exists
+        let config_content = std::fs::read_to_string(&config_path)
+            .with_context(|| tr!("serve.read_config_failed"))?;
+
+        if let Err(e) = serde_json::from_str::<serde_json::Value>(&config_content) {
+            eprintln!("{}", tr!("serve.invalid_json"));
+            eprintln!();
+            eprintln!("{}", tr!("serve.parse_error", e));
+            eprintln!();
+            eprintln!("{}", tr!("serve.fix_json"));
+            std::process::exit(1);
+        }
+    }
+
+    // Check if port is available before attempting to start
+    let port = if is_port_available(port) {
+        port
+    } else {
+        // The default port moves aside on its own; a port asked for by name only with --failover
+        resolve_port_collision(port, background, failover || port == rbxsync_core::DEFAULT_PORT).await?
+    };
+
+    if background {
+        // Spawn server as a detached background process
+        let exe = std::env::current_exe()?;
+        let mut cmd = std::process::Command::new(&exe);
+        cmd.arg("serve").args(network.args(port));
+        if supervised {
+            cmd.arg("--supervisor");
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0); // Create new process group
+        }
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            const DETACHED_PROCESS: u32 = 0x00000008;
+            const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+            cmd.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+        }
+
+        let child = cmd
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .with_context(|| tr!("serve.spawn_failed"))?;
+
+        println!("{}", tr!("serve.started_background", child.id()));
+        println!("{}", tr!("serve.port", port));
+        if supervised {
+            println!("{}", tr!("serve.supervised"));
+        }
+        println!("{}", tr!("serve.stop_with"));
+        return Ok(());
+    }
+
+    // Setting RBXSYNC_TOKEN also turns auth on
+    let token = if network.auth || std::env::var(rbxsync_core::TOKEN_ENV).is_ok_and(|t| !t.trim().is_empty()) {
+        let path = rbxsync_core::token_path().with_context(|| tr!("common.no_home"))?;
+        Some(rbxsync_core::server_token(&path).with_context(|| tr!("serve.token_failed"))?)
+    } else {
+        None
+    };
+
+    // Foreground mode
+    if network.ip.is_loopback() && network.tls.is_none() {
+        println!("{}", tr!("serve.running_port", port));
+    } else {
+        let scheme = if network.tls.is_some() { "https" } else { "http" };
+        let url = format!("{}://{}", scheme, std::net::SocketAddr::new(network.ip, port));
+        println!("{}", tr!("serve.running_on", url));
+    }
+    if token.is_some() {
+        println!("{}", tr!("serve.token_auth"));
+    }
+    println!("{}", tr!("serve.stop_hint"));
+    println!("{}", tr!("serve.background_hint"));
+    run_server(ServerConfig {
+        port,
+        host: network.ip.to_string(),
+        token,
+        users: network.users,
+        oidc: network.oidc,
+        tls: network.tls,
+    })
+    .await
+}
+
+/// Headers for requests to the local server: the API token, when there is one
+pub(crate) fn server_headers() -> reqwest::header::HeaderMap {
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Some(value) = rbxsync_core::client_token().and_then(|t| reqwest::header::HeaderValue::from_str(&t).ok()) {
+        headers.insert(rbxsync_core::TOKEN_HEADER, value);
+    }
+    headers
+}
+
+/// Client for the local server. Not for other hosts: it sends the API token.
+fn server_client() -> reqwest::ClientBuilder {
+    reqwest::Client::builder().default_headers(server_headers())
+}
+
+/// Port of the server for the project in `project_dir`: the one recorded in
+/// its `.rbxsync/server.json`, else the registered server of this version,
+/// which may have failed over off the default port, else the default port
+fn server_port(project_dir: &std::path::Path) -> u16 {
+    rbxsync_core::read_workspace_server(project_dir)
+        .map(|entry| entry.port)
+        .or_else(|| {
+            rbxsync_core::registry_path().and_then(|path| {
+                rbxsync_core::port_for_version(&rbxsync_core::load_registry(&path), env!("CARGO_PKG_VERSION"))
+            })
+        })
+        .unwrap_or(rbxsync_core::DEFAULT_PORT)
+}
+
+/// Base URL of the server for `project_dir`, e.g. `http://localhost:44755`.
+/// `port` is a command's own `--port`, which wins over discovery.
+fn project_server_url(project_dir: &std::path::Path, port: Option<u16>) -> String {
+    format!("http://localhost:{}", port.unwrap_or_else(|| server_port(project_dir)))
+}
+
+/// Base URL of the server for the project in the current directory
+pub(crate) fn server_url(port: Option<u16>) -> String {
+    project_server_url(&std::env::current_dir().unwrap_or_default(), port)
+}
+
+/// Print (or replace) the API token
+fn cmd_token(regenerate: bool) -> Result<()> {
+    let path = rbxsync_core::token_path().with_context(|| tr!("common.no_home"))?;
+    let token = if regenerate {
+        let token = rbxsync_core::auth::generate_token();
+        rbxsync_core::auth::write_token(&path, &token)?;
+        token
+    } else {
+        rbxsync_core::server_token(&path)?
+    };
+    if std::env::var(rbxsync_core::TOKEN_ENV).is_ok() && !regenerate {
+        eprintln!("{}", tr!("token.from_env", rbxsync_core::TOKEN_ENV));
+    }
+    println!("{}", token);
+    Ok(())
+}
+
+/// Save a team server token in the keychain: one given by the server's
+/// owner, or an ID token from signing in with an OIDC issuer
+async fn cmd_login(token: Option<String>, oidc: Option<(String, String)>) -> Result<()> {
+    let token = match (token, oidc) {
+        (Some(token), _) => token.trim().to_string(),
+        (None, Some((issuer, client_id))) => {
+            use rbxsync_server::oidc;
+            let client = reqwest::Client::new();
+            let discovery = oidc::discover(&client, &issuer).await?;
+            let code = oidc::start_device_login(&client, &discovery, &client_id).await?;
+            match &code.verification_uri_complete {
+                Some(uri) => println!("{}", tr!("login.open_complete", uri, code.user_code)),
+                None => println!("{}", tr!("login.open", code.verification_uri, code.user_code)),
+            }
+            println!("{}", tr!("login.waiting"));
+            oidc::finish_device_login(&client, &discovery, &client_id, &code).await?
+        }
+        (None, None) => bail!("{}", tr!("login.need_token")),
+    };
+    rbxsync_core::auth::store_keychain_token(rbxsync_core::auth::LOGIN_ACCOUNT, &token)
+        .map_err(|e| anyhow::anyhow!(tr!("auth.keychain_failed", e)))?;
+    println!("\x1b[32m{}\x1b[0m", tr!("login.signed_in"));
+    Ok(())
+}
+
+/// Add, remove or list team users: names in ~/.rbxsync/users.json, tokens in the keychain
+fn cmd_users(action: UsersAction) -> Result<()> {
+    use rbxsync_core::auth;
+
+    let path = auth::users_path().with_context(|| tr!("common.no_home"))?;
+    let mut users = auth::load_users(&path);
+    let keychain_err = |e: auth::KeychainError| anyhow::anyhow!(tr!("auth.keychain_failed", e));
+    match action {
+        UsersAction::Add { name } => {
+            let token = auth::generate_token();
+            auth::store_keychain_token(&auth::user_account(&name), &token).map_err(keychain_err)?;
+            users.push(name.clone());
+            auth::save_users(&path, &users)?;
+            println!("\x1b[32m{}\x1b[0m", tr!("users.added", name));
+            println!("{}", token);
+            println!("{}", tr!("users.restart"));
+        }
+        UsersAction::Remove { name } => {
+            if !users.contains(&name) {
+                bail!("{}", tr!("users.not_found", name));
+            }
+            auth::delete_keychain_token(&auth::user_account(&name)).map_err(keychain_err)?;
+            users.retain(|user| *user != name);
+            auth::save_users(&path, &users)?;
+            println!("{}", tr!("users.removed", name));
+            println!("{}", tr!("users.restart"));
+        }
+        UsersAction::List if users.is_empty() => println!("{}", tr!("users.none")),
+        UsersAction::List => {
+            for user in &users {
+                println!("{}", user);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The port is taken: say who holds it, and move to a failover port when
+/// it's another rbxsync version (asking first unless --failover)
+async fn resolve_port_collision(port: u16, background: bool, failover: bool) -> Result<u16> {
+    use rbxsync_server::ports;
+    use std::io::IsTerminal;
+
+    let status = ports::probe_port(port, "rbxsync").await;
+    let ours = env!("CARGO_PKG_VERSION");
+    let same_version = status.is_rbxsync() && status.version.as_deref() == Some(ours);
+
+    eprintln!("{}", tr!("ports.in_use", port, status.owner_label()));
+    if same_version {
+        eprintln!();
+        eprintln!("{}", tr!("ports.same_version"));
+        eprintln!("{}", tr!("ports.stop_try", port));
+        std::process::exit(1);
+    }
+
+    let offer = failover || status.is_rbxsync();
+    let free = ports::find_free_port();
+    if let (true, Some(free)) = (offer, free) {
+        let accepted = failover
+            || (!background && std::io::stdin().is_terminal() && {
+                eprint!("{} ", tr!("ports.offer_failover", ours, free));
+                use std::io::Write;
+                std::io::stderr().flush()?;
+                let mut input = String::new();
+                std::io::stdin().read_line(&mut input)?;
+                let input = input.trim().to_lowercase();
+                input.is_empty() || input == "y" || input == "yes"
+            });
+        if accepted {
+            println!("{}", tr!("ports.failing_over", free));
+            return Ok(free);
+        }
+    }
+
+    eprintln!();
+    if status.is_rbxsync() {
+        eprintln!("{}", tr!("ports.stop_hint", port));
+    } else {
+        eprintln!("{}", tr!("ports.stop_other_hint"));
+    }
+    eprintln!("{}", tr!("ports.other_port_hint"));
+    eprintln!("{}", tr!("ports.doctor_hint"));
+    std::process::exit(1);
+}
+
+/// Show who holds the well-known local dev ports
+async fn cmd_doctor(port: u16) -> Result<()> {
+    use rbxsync_server::ports;
+
+    println!("{}", tr!("doctor.ports"));
+    for status in ports::check_ports(port).await {
+        let state = if !status.in_use {
+            format!("\x1b[2m{}\x1b[0m", tr!("doctor.free"))
+        } else if status.default_for.starts_with("rbxsync") && !status.is_rbxsync() {
+            format!("\x1b[31m{}\x1b[0m", status.owner_label())
+        } else {
+            format!("\x1b[32m{}\x1b[0m", status.owner_label())
+        };
+        // The failover range is only interesting when something is there
+        if status.default_for.contains("failover") && !status.in_use {
+            continue;
+        }
+        println!("  {:>5}  {:<20} {}", status.port, status.default_for, state);
+    }
+
+    let servers = rbxsync_core::registry_path()
+        .map(|path| rbxsync_core::load_registry(&path))
+        .unwrap_or_default();
+    if !servers.is_empty() {
+        println!("\n{}", tr!("doctor.registered"));
+        for server in &servers {
+            println!(
+                "  {:>5}  rbxsync {}  \x1b[2m{}\x1b[0m",
+                server.port,
+                server.version,
+                tr!("doctor.server_pid", server.pid, server.cwd)
+            );
+        }
+    }
+
+    if let Ok(cwd) = std::env::current_dir() {
+        if let Some(drift) = rbxsync_server::check_wally_drift(&cwd.to_string_lossy()) {
+            println!("\n{}", tr!("doctor.wally_drift"));
+            print_wally_drift(&drift);
+        }
+    }
+    Ok(())
+}
+
+/// Stop the running sync server
+async fn cmd_stop(port: &str) -> Result<()> {
+    // Handle "all" to stop all rbxsync servers
+    if port == "all" {
+        return stop_all_servers().await;
+    }
+
+    let port_num: u16 = port.parse().with_context(|| tr!("stop.invalid_port"))?;
+    stop_server_on_port(port_num).await
+}
+
+/// Stop all rbxsync servers
+#[cfg(unix)]
+async fn stop_all_servers() -> Result<()> {
+    let output = std::process::Command::new("pgrep")
+        .args(["-f", "rbxsync.*serve"])
+        .output();
+
+    if let Ok(output) = output {
+        let pids = String::from_utf8_lossy(&output.stdout);
+        let pids: Vec<&str> = pids.lines().filter(|p| !p.is_empty()).collect();
+
+        if pids.is_empty() {
+            println!("{}", tr!("stop.none_running"));
+            return Ok(());
+        }
+
+        println!("{}", tr!("stop.stopping_all", pids.len()));
+        for pid in pids {
+            if let Ok(pid) = pid.trim().parse::<i32>() {
+                unsafe {
+                    libc::kill(pid, libc::SIGTERM);
+                }
+            }
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        println!("{}", tr!("stop.all_stopped"));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn stop_all_servers() -> Result<()> {
+    println!("{}", tr!("stop.all_unsupported"));
+    println!("{}", tr!("stop.specify_port"));
+    Ok(())
+}
+
+/// Wait for a port to be released, polling up to `timeout_ms` milliseconds
+async fn wait_for_port_release(port: u16, timeout_ms: u64) -> bool {
+    let start = std::time::Instant::now();
+    let timeout = std::time::Duration::from_millis(timeout_ms);
+
+    while start.elapsed() < timeout {
+        // Try to bind to the port - if successful, the port is free
+        match std::net::TcpListener::bind(format!("127.0.0.1:{}", port)) {
+            Ok(_) => return true,
+            Err(_) => {
+                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+            }
+        }
+    }
+    false
+}
+
+/// Check if a port is available
+fn is_port_available(port: u16) -> bool {
+    std::net::TcpListener::bind(format!("127.0.0.1:{}", port)).is_ok()
+}
+
+/// Stop server on a specific port
+async fn stop_server_on_port(port: u16) -> Result<()> {
+    // First, try to find any rbxsync server processes by port
+    #[cfg(unix)]
+    {
+        let port_arg = format!(":{}", port);
+        let output = std::process::Command::new("lsof")
+            .args(["-ti", &port_arg])
+            .output();
+
+        if let Ok(output) = output {
+            let pids = String::from_utf8_lossy(&output.stdout);
+            let pids: Vec<&str> = pids.lines().filter(|p| !p.is_empty()).collect();
+
+            if pids.is_empty() {
+                println!("{}", tr!("stop.not_running_on_port", port));
+                return Ok(());
+            }
+
+            // Try graceful shutdown first via HTTP
+            let client = server_client()
+                .timeout(std::time::Duration::from_secs(2))
+                .build()?;
+
+            let url = format!("{}/shutdown", server_url(Some(port)));
+            let _ = client.post(&url).send().await;  // Ignore result, check if port is released
+
+            // Wait briefly for graceful shutdown
+            if wait_for_port_release(port, 2000).await {
+                println!("{}", tr!("stop.stopped"));
+                return Ok(());
+            }
+
+            // Graceful shutdown didn't work, try SIGTERM first (allows cleanup)
+            println!("{}", tr!("stop.sigterm"));
+            for pid in &pids {
+                if let Ok(pid) = pid.trim().parse::<i32>() {
+                    unsafe {
+                        libc::kill(pid, libc::SIGTERM);
+                    }
+                }
+            }
+
+            // Wait for SIGTERM to take effect
+            if wait_for_port_release(port, 2000).await {
+                println!("{}", tr!("stop.stopped"));
+                return Ok(());
+            }
+
+            // SIGTERM didn't work, force kill with SIGKILL
+            println!("{}", tr!("stop.sigkill"));
+            for pid in &pids {
+                if let Ok(pid) = pid.trim().parse::<i32>() {
+                    unsafe {
+                        libc::kill(pid, libc::SIGKILL);
+                    }
+                }
+            }
+
+            // Final check
+            if wait_for_port_release(port, 2000).await {
+                println!("{}", tr!("stop.stopped"));
+            } else {
+                // Last resort: print the PIDs so user can manually kill
+                println!("{}", tr!("stop.manual"));
+                for pid in &pids {
+                    println!("  kill -9 {}", pid.trim());
+                }
+            }
+            return Ok(());
+        }
+    }
+
+    // Fallback for non-unix or if lsof failed
+    let client = server_client()
+        .timeout(std::time::Duration::from_secs(2))
+        .build()?;
+
+    let url = format!("{}/shutdown", server_url(Some(port)));
+    match client.post(&url).send().await {
+        Ok(_) => {
+            println!("{}", tr!("stop.stopped"));
+            Ok(())
+        }
+        Err(_) => {
+            println!("{}", tr!("stop.not_running_on_port", port));
+            Ok(())
+        }
+    }
+}
+
+/// Bot commands
+async fn cmd_bot(action: BotAction) -> Result<()> {
+    use rbxsync_core::api::{ScenarioReport, ScenarioStepStatus};
+
+    match action {
+        BotAction::Run { file, json } => {
+            let source = std::fs::read_to_string(&file).with_context(|| tr!("common.read_failed", file.display()))?;
+            // Check the file here, so a typo doesn't need a server round trip
+            rbxsync_server::scenario::parse(&source).map_err(|e| anyhow::anyhow!(e))?;
+
+            let base = server_url(None);
+            let response = server_client()
+                .build()?
+                .post(format!("{}/bot/scenario/run", base))
+                .json(&rbxsync_core::api::ScenarioRunRequest { source: Some(source), scenario: None })
+                .send()
+                .await
+                .with_context(|| tr!("server.not_running"))?;
+            let body: serde_json::Value = response.json().await?;
+            if body.get("steps").is_none() {
+                bail!("{}", body["error"].as_str().unwrap_or("Request failed"));
+            }
+            let report: ScenarioReport = serde_json::from_value(body)?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("{}", report.name);
+                for step in &report.steps {
+                    match step.status {
+                        ScenarioStepStatus::Passed => println!(
+                            "  \x1b[32m✓\x1b[0m {}. {} \x1b[2m({:.2}s)\x1b[0m",
+                            step.index, step.description, step.time
+                        ),
+                        ScenarioStepStatus::Skipped => {
+                            println!("  \x1b[33m-\x1b[0m {}. {} \x1b[2m{}\x1b[0m", step.index, step.description, tr!("bot.skipped"))
+                        }
+                        ScenarioStepStatus::Failed => {
+                            println!("  \x1b[31m✗\x1b[0m {}. {}", step.index, step.description);
+                            if let Some(message) = &step.message {
+                                println!("      \x1b[31m{}\x1b[0m", message);
+                            }
+                        }
+                    }
+                }
+                for faults in &report.faults {
+                    println!(
+                        "  \x1b[2m{}\x1b[0m",
+                        tr!(
+                            "bot.faults",
+                            faults["seed"],
+                            faults["delayed"].as_u64().unwrap_or(0),
+                            faults["dropped"].as_u64().unwrap_or(0)
+                        )
+                    );
+                }
+                println!(
+                    "{}",
+                    tr!(
+                        "bot.summary",
+                        report.steps.len(),
+                        report.passed,
+                        report.failed,
+                        report.skipped,
+                        format!("{:.1}", report.time)
+                    )
+                );
+            }
+            if !report.success {
+                std::process::exit(1);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Show status
+async fn cmd_status() -> Result<()> {
+    let client = server_client().build()?;
+    // The project's own server, if one has claimed it
+    let base = server_url(None);
+
+    match client.get(format!("{}/health", base)).send().await {
+        Ok(response) => {
+            let health: serde_json::Value = response.json().await?;
+            println!("{}", tr!("status.server", serde_json::to_string_pretty(&health)?));
+            if let Some(crash) = health.get("lastCrash").filter(|c| !c.is_null()) {
+                println!(
+                    "{}",
+                    tr!(
+                        "status.last_crash",
+                        rbxsync_server::stats::date_label(crash["timestamp"].as_u64().unwrap_or(0)),
+                        crash["panic"].as_str().or(crash["exitStatus"].as_str()).unwrap_or("unknown"),
+                        crash["report"].as_str().unwrap_or("")
+                    )
+                );
+            }
+
+            // Check extraction status
+            let status = client
+                .get(format!("{}/extract/status", base))
+                .send()
+                .await?
+                .json::<serde_json::Value>()
+                .await?;
+
+            println!("{}", tr!("status.extraction", serde_json::to_string_pretty(&status)?));
+
+            let live_sync = &health["liveSync"];
+            match live_sync["pausedBy"].as_str() {
+                Some("user") => println!(
+                    "{}",
+                    tr!(
+                        "status.sync_paused",
+                        live_sync["reason"].as_str().unwrap_or("-"),
+                        live_sync["heldChanges"].as_u64().unwrap_or(0)
+                    )
+                ),
+                Some("extraction") => println!("{}", tr!("status.sync_paused_extraction")),
+                _ => {}
+            }
 
-    let cli = Cli::parse();
+            // Branch switches waiting for a full resync
+            if let Ok(response) = client.get(format!("{}/git/branch-sync", base)).send().await {
+                if let Ok(branches) = response.json::<serde_json::Value>().await {
+                    for (project_dir, project) in branches["projects"].as_object().into_iter().flatten() {
+                        if project["pending"] == true {
+                            println!("{}", tr!("status.branch_pending", project_dir, head_label(&project["head"])));
+                        }
+                    }
+                }
+            }
 
-    match cli.command {
-        Commands::Init { name, path, no_sourcemap } => {
-            cmd_init(name, path, no_sourcemap).await?;
+            // Deduplicated errors from Studio's console
+            if let Ok(response) = client.get(format!("{}/console/errors/summary", base)).send().await {
+                if let Ok(summary) = response.json::<serde_json::Value>().await {
+                    if summary["unique"].as_u64().unwrap_or(0) > 0 {
+                        println!("{}", tr!("status.console_errors", summary["summary"].as_str().unwrap_or_default()));
+                    }
+                }
+            }
+
+            // Queued and in-flight plugin requests, to see what a hung sync waits on
+            if let Ok(response) = client.get(format!("{}/jobs", base)).send().await {
+                if let Ok(jobs) = response.json::<serde_json::Value>().await {
+                    print_jobs(&jobs);
+                }
+            }
         }
-        Commands::Studio { place, serve } => {
-            cmd_studio(place, serve).await?;
+        Err(_) => {
+            println!("{}", tr!("status.not_running"));
+            println!("{}", tr!("status.start_hint"));
         }
-        Commands::Debug { action } => {
-            cmd_debug(action).await?;
+    }
+
+    Ok(())
+}
+
+/// Print the job list from GET /jobs
+fn print_jobs(response: &serde_json::Value) {
+    let jobs = response["jobs"].as_array().cloned().unwrap_or_default();
+    if jobs.is_empty() {
+        println!("{}", tr!("status.no_jobs"));
+        return;
+    }
+    let in_flight = jobs.iter().filter(|job| job["state"] == "inFlight").count();
+    println!("{}", tr!("status.jobs", jobs.len() - in_flight, in_flight));
+    for job in &jobs {
+        let state = match job["state"].as_str() {
+            Some("inFlight") => format!(
+                "in flight {}",
+                format_duration_ms(job["inFlightMs"].as_u64().unwrap_or(0))
+            ),
+            _ => format!("queued ({})", job["queue"].as_str().unwrap_or("?")),
+        };
+        println!(
+            "  {}  {:<24} {:<22} age {:>7}  {:>8} B  \x1b[2m{}\x1b[0m",
+            job["id"].as_str().unwrap_or("?"),
+            job["command"].as_str().unwrap_or("?"),
+            state,
+            format_duration_ms(job["ageMs"].as_u64().unwrap_or(0)),
+            job["payloadBytes"].as_u64().unwrap_or(0),
+            job["projectDir"].as_str().or(job["sessionId"].as_str()).unwrap_or("")
+        );
+    }
+    println!("{}", tr!("status.cancel_hint"));
+}
+
+/// Cancel a queued or in-flight plugin request
+async fn cmd_cancel_job(id: &str) -> Result<()> {
+    let client = server_client().build()?;
+    let base = server_url(None);
+    let response = client
+        .post(format!("{}/jobs/{}/cancel", base, id))
+        .send()
+        .await
+        .with_context(|| tr!("server.not_running"))?;
+    let body: serde_json::Value = response.json().await?;
+    if body["success"] != true {
+        bail!("{}", body["error"].as_str().unwrap_or("Cancel failed"));
+    }
+    println!("{}", tr!("status.cancelled", id, body["was"].as_str().unwrap_or("")));
+    Ok(())
+}
+
+/// "main (1a2b3c4)" for a HEAD from /git/branch-sync
+fn head_label(head: &serde_json::Value) -> String {
+    let commit = head["commit"].as_str().unwrap_or("unknown commit");
+    match head["branch"].as_str() {
+        Some(branch) => format!("{} ({})", branch, commit),
+        None => format!("detached at {}", commit),
+    }
+}
+
+/// Pause, resume or resync live sync on the project's server
+async fn cmd_sync_pause(action: SyncAction) -> Result<()> {
+    let client = server_client().build()?;
+    let base = server_url(None);
+    let request = match &action {
+        SyncAction::Pause { reason } => client
+            .post(format!("{}/sync/pause", base))
+            .json(&serde_json::json!({ "reason": reason })),
+        SyncAction::Resume => client.post(format!("{}/sync/resume", base)),
+        SyncAction::Resync { path } => {
+            let project_dir = path.clone().unwrap_or_else(|| std::env::current_dir().unwrap());
+            println!("{}", tr!("sync.resyncing"));
+            client
+                .post(format!("{}/git/branch-sync", base))
+                .json(&serde_json::json!({ "projectDir": project_dir.to_string_lossy() }))
         }
-        Commands::Extract {
-            service,
-            terrain,
-            assets,
-            output,
-        } => {
-            cmd_extract(service, terrain, assets, output).await?;
+    };
+    let response = request.send().await.with_context(|| tr!("server.not_running"))?;
+    let body: serde_json::Value = response.json().await?;
+    if body["success"] != true {
+        bail!("{}", body["error"].as_str().unwrap_or("Request failed"));
+    }
+    match action {
+        SyncAction::Pause { .. } => println!("{}", tr!("sync.paused")),
+        SyncAction::Resume => println!("{}", tr!("sync.resumed", body["changes"].as_u64().unwrap_or(0))),
+        SyncAction::Resync { .. } => println!(
+            "\x1b[32m{}\x1b[0m",
+            tr!("sync.resynced", body["operations"].as_u64().unwrap_or(0), head_label(&body["head"]))
+        ),
+    }
+    Ok(())
+}
+
+/// POST to a /git endpoint on the project's server and return its `data`
+async fn git_request<T: serde::de::DeserializeOwned>(endpoint: &str, body: &impl serde::Serialize) -> Result<T> {
+    let base = server_url(None);
+    let response = server_client()
+        .build()?
+        .post(format!("{}/git/{}", base, endpoint))
+        .json(body)
+        .send()
+        .await
+        .with_context(|| tr!("server.not_running"))?;
+    let body: serde_json::Value = response.json().await?;
+    if body["success"] != true {
+        bail!("{}", body["error"].as_str().unwrap_or("Request failed"));
+    }
+    Ok(serde_json::from_value(body["data"].clone())?)
+}
+
+/// Print a checkout, pull or push result
+fn print_git_result(result: &rbxsync_core::api::GitRemoteResult) {
+    if !result.output.is_empty() {
+        println!("\x1b[2m{}\x1b[0m", result.output);
+    }
+    if result.up_to_date {
+        println!("{}", tr!("git.up_to_date", result.head.label()));
+        return;
+    }
+    println!("\x1b[32m{}\x1b[0m", tr!("git.now_on", result.head.label()));
+    if !result.changed_files.is_empty() {
+        println!("{}", tr!("git.changed_files", result.changed_files.len()));
+        for file in result.changed_files.iter().take(20) {
+            println!("  {}", file);
         }
-        Commands::Serve { port, background } => {
-            cmd_serve(port, background).await?;
+        if result.changed_files.len() > 20 {
+            println!("{}", tr!("diff.more", result.changed_files.len() - 20));
         }
-        Commands::Stop { port } => {
-            cmd_stop(&port).await?;
+    }
+}
+
+/// Branch, checkout, pull, push or stash through the server
+async fn cmd_git(action: GitAction) -> Result<()> {
+    use rbxsync_core::api::*;
+
+    let project_dir = |path: Option<PathBuf>| {
+        path.unwrap_or_else(|| std::env::current_dir().unwrap()).to_string_lossy().to_string()
+    };
+    match action {
+        GitAction::Branch { name: None, path, .. } => {
+            let branches: Vec<GitBranch> =
+                git_request("branches", &GitProjectRequest { project_dir: project_dir(path) }).await?;
+            for branch in branches {
+                let marker = if branch.current { "\x1b[32m*" } else { " " };
+                let mut tracking = branch.upstream.clone().unwrap_or_default();
+                if branch.ahead > 0 || branch.behind > 0 {
+                    tracking = format!("{} +{}/-{}", tracking, branch.ahead, branch.behind);
+                }
+                println!(
+                    "{} {:<24}\x1b[0m \x1b[33m{}\x1b[0m {} \x1b[2m{}\x1b[0m",
+                    marker, branch.name, branch.commit, branch.subject, tracking
+                );
+            }
         }
-        Commands::Status => {
-            cmd_status().await?;
+        GitAction::Branch { name: Some(name), from, checkout, path } => {
+            let request = GitBranchCreateRequest { project_dir: project_dir(path), name, start_point: from, checkout };
+            let branch: GitBranch = git_request("branch/create", &request).await?;
+            println!("\x1b[32m{}\x1b[0m", tr!("git.branch_created", branch.name, branch.commit));
         }
-        Commands::Diff => {
-            cmd_diff().await?;
+        GitAction::Checkout { target, path } => {
+            let result = git_request("checkout", &GitCheckoutRequest { project_dir: project_dir(path), target }).await?;
+            print_git_result(&result);
         }
-        Commands::Sync { path, no_delete } => {
-            cmd_sync(path, !no_delete).await?;
+        GitAction::Pull { remote, branch, rebase, path } => {
+            let request = GitRemoteRequest { project_dir: project_dir(path), remote, branch, rebase, ..Default::default() };
+            print_git_result(&git_request("pull", &request).await?);
         }
-        Commands::BuildPlugin {
-            source,
-            output,
-            name,
-            install,
-            no_obfuscate,
-            obfuscate_config,
-        } => {
-            cmd_build_plugin(source, output, name, install, !no_obfuscate, obfuscate_config)?;
+        GitAction::Push { remote, branch, set_upstream, path } => {
+            let request =
+                GitRemoteRequest { project_dir: project_dir(path), remote, branch, set_upstream, ..Default::default() };
+            print_git_result(&git_request("push", &request).await?);
         }
-        Commands::Plugin { action } => {
-            cmd_plugin(action).await?;
+        GitAction::Stash { action, message, index, path } => {
+            let request = GitStashRequest { project_dir: project_dir(path), action: Some(action), message, index };
+            let result: GitStashResult = git_request("stash", &request).await?;
+            if !result.output.is_empty() {
+                println!("{}", result.output);
+            }
+            if result.stashes.is_empty() {
+                println!("{}", tr!("git.no_stashes"));
+            }
+            for entry in result.stashes {
+                println!("\x1b[33mstash@{{{}}}\x1b[0m {}", entry.index, entry.message);
+            }
         }
-        Commands::Sourcemap {
-            path,
-            output,
-            include_non_scripts,
-        } => {
-            cmd_sourcemap(path, output, include_non_scripts)?;
+    }
+    Ok(())
+}
+
+/// List every running rbxsync server and the projects connected to it
+async fn cmd_status_all() -> Result<()> {
+    let servers = rbxsync_server::ports::discover_servers().await;
+    if servers.is_empty() {
+        println!("{}", tr!("stop.none_running"));
+        println!("{}", tr!("status.start_one_hint"));
+        return Ok(());
+    }
+
+    let here = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| rbxsync_core::read_workspace_server(&cwd))
+        .map(|entry| entry.port);
+    for server in &servers {
+        let marker = if here == Some(server.port) { format!(" \x1b[32m{}\x1b[0m", tr!("status.this_project")) } else { String::new() };
+        let mut details = Vec::new();
+        if let Some(pid) = server.pid {
+            details.push(format!("pid {}", pid));
         }
-        Commands::Build {
-            path,
-            output,
-            format,
-            watch,
-            plugin,
-        } => {
-            cmd_build(path, output, format, watch, plugin).await?;
+        if server.auth {
+            details.push("auth".to_string());
+        }
+        println!(
+            "{:>5}  rbxsync {}{}  \x1b[2m{}\x1b[0m",
+            server.port,
+            server.version.as_deref().unwrap_or("?"),
+            marker,
+            details.join(", ")
+        );
+        if server.projects.is_empty() {
+            println!("         \x1b[2m{}\x1b[0m", tr!("status.no_projects"));
+        }
+        for project in &server.projects {
+            println!("         {}", project);
+        }
+    }
+    Ok(())
+}
+
+/// Print a project's log entries, then optionally tail the running server
+async fn cmd_logs(
+    follow: bool,
+    level: Option<String>,
+    subsystem: Option<String>,
+    limit: usize,
+    path: Option<PathBuf>,
+    port: Option<u16>,
+) -> Result<()> {
+    use rbxsync_server::logs::{self, LogEntry, LogQuery};
+
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let url = format!("{}/logs/query", project_server_url(&project_dir, port));
+    let client = server_client().build()?;
+
+    let print = |entry: &LogEntry| {
+        let color = match entry.level.as_str() {
+            "ERROR" => "31",
+            "WARN" => "33",
+            "INFO" => "32",
+            _ => "2",
+        };
+        let time = entry.timestamp.split('T').nth(1).unwrap_or(&entry.timestamp);
+        println!(
+            "\x1b[2m{}\x1b[0m \x1b[{}m{:<5}\x1b[0m \x1b[36m{:<14}\x1b[0m {}",
+            time.trim_end_matches('Z'),
+            color,
+            entry.level,
+            entry.subsystem,
+            entry.message
+        );
+    };
+
+    let mut params = vec![
+        ("project_dir", project_dir.to_string_lossy().to_string()),
+        ("limit", limit.to_string()),
+    ];
+    if let Some(level) = &level {
+        params.push(("level", level.clone()));
+    }
+    if let Some(subsystem) = &subsystem {
+        params.push(("subsystem", subsystem.clone()));
+    }
+
+    let response = match client.get(&url).query(&params).send().await {
+        Ok(response) => response.json::<serde_json::Value>().await?,
+        Err(_) if !follow => {
+            // The files outlive the server, so history is still readable
+            let query = LogQuery { level, subsystem, limit: Some(limit), ..Default::default() };
+            let entries = logs::read_project_log(&project_dir, &query);
+            if entries.is_empty() {
+                println!("{}", tr!("logs.no_entries", logs::log_path(&project_dir).display()));
+            }
+            entries.iter().for_each(print);
+            return Ok(());
+        }
+        Err(_) => bail!("{}", tr!("server.not_running")),
+    };
+    if !response["success"].as_bool().unwrap_or(false) {
+        bail!("{}", response["error"].as_str().unwrap_or("Failed to query logs"));
+    }
+
+    let entries: Vec<LogEntry> = serde_json::from_value(response["entries"].clone())?;
+    entries.iter().for_each(print);
+    if !follow {
+        return Ok(());
+    }
+
+    let mut after = response["latestSeq"].as_u64().unwrap_or(0);
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        let mut tail = params.clone();
+        tail.push(("after", after.to_string()));
+        // Keep waiting while a supervised server restarts
+        let Ok(response) = client.get(&url).query(&tail).send().await else {
+            continue;
+        };
+        let Ok(response) = response.json::<serde_json::Value>().await else {
+            continue;
+        };
+        let entries: Vec<LogEntry> = serde_json::from_value(response["entries"].clone()).unwrap_or_default();
+        entries.iter().for_each(print);
+        // A restarted server numbers its entries from 1 again
+        let latest = response["latestSeq"].as_u64().unwrap_or(after);
+        after = if latest < after { 0 } else { latest };
+    }
+}
+
+/// Search or export the Studio console buffer
+async fn cmd_logs_studio(source: LogsSource) -> Result<()> {
+    use rbxsync_core::api::{ConsoleExportRequest, ConsoleHistoryQuery, ConsoleHistoryResponse};
+
+    let LogsSource::Studio { grep, regex, level, source, since, until, limit, export, format, port } = source;
+    let mut filter = ConsoleHistoryQuery { limit: Some(limit), level, source, grep, since, until, ..Default::default() };
+    if regex {
+        filter.regex = Some(true);
+    }
+    let client = server_client().build()?;
+    let base = server_url(port);
+
+    if let Some(export) = export {
+        filter.limit = None;
+        let path = std::env::current_dir()?.join(export);
+        let request = ConsoleExportRequest { path: path.to_string_lossy().to_string(), format, filter };
+        let response: serde_json::Value = client
+            .post(format!("{}/console/export", base))
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| tr!("server.not_running"))?
+            .json()
+            .await?;
+        if response["success"] != true {
+            bail!("{}", response["error"].as_str().map(str::to_string).unwrap_or_else(|| tr!("logs.export_failed")));
+        }
+        println!(
+            "\x1b[32m✓\x1b[0m {}",
+            tr!(
+                "logs.exported",
+                response["exported"].as_u64().unwrap_or(0),
+                response["path"].as_str().unwrap_or_default()
+            )
+        );
+        return Ok(());
+    }
+
+    let response: serde_json::Value = client
+        .get(format!("{}/console/history", base))
+        .query(&filter)
+        .send()
+        .await
+        .with_context(|| tr!("server.not_running"))?
+        .json()
+        .await?;
+    if response["success"] == false {
+        bail!("{}", response["error"].as_str().map(str::to_string).unwrap_or_else(|| tr!("logs.query_failed")));
+    }
+    let history: ConsoleHistoryResponse = serde_json::from_value(response)?;
+    if history.messages.is_empty() {
+        println!("{}", tr!("logs.no_matches", history.total));
+    }
+    for message in &history.messages {
+        let color = match message.message_type.as_str() {
+            "error" => "31",
+            "warn" => "33",
+            _ => "0",
+        };
+        let time = message
+            .received_at
+            .map(|at| rbxsync_core::defines::utc_timestamp(at).replace('T', " ").trim_end_matches('Z').to_string())
+            .unwrap_or_else(|| message.timestamp.clone());
+        println!(
+            "\x1b[2m{}\x1b[0m \x1b[36m{:<8}\x1b[0m \x1b[{}m{}\x1b[0m",
+            time,
+            message.source.as_deref().unwrap_or(""),
+            color,
+            message.message
+        );
+        for location in &message.locations {
+            println!("    \x1b[2m{}\x1b[0m", location.link);
+        }
+    }
+    if history.matched > history.messages.len() {
+        println!("\x1b[2m{}\x1b[0m", tr!("logs.more", history.messages.len(), history.matched));
+    }
+    Ok(())
+}
+
+/// Resolve an operation interrupted by a crash (safe mode)
+async fn cmd_recover(path: Option<PathBuf>, rollback: bool, finish: bool) -> Result<()> {
+    use rbxsync_server::recovery::{self, RecoveryAction};
+
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+
+    let Some(journal) = recovery::read_journal(&project_dir) else {
+        println!("{}", tr!("recover.nothing"));
+        return Ok(());
+    };
+
+    println!("{}", tr!("recover.operation", journal.operation));
+    println!("{}", tr!("recover.started_at", journal.started_at));
+    println!("{}", tr!("recover.changes", journal.ops.len()));
+    if journal.committed {
+        println!("{}", tr!("recover.committed"));
+    }
+
+    let action = match (rollback, finish) {
+        (true, _) => RecoveryAction::Rollback,
+        (_, true) => RecoveryAction::Finish,
+        _ => {
+            println!();
+            println!("{}", tr!("recover.choose"));
+            println!("{}", tr!("recover.finish_hint"));
+            println!("{}", tr!("recover.rollback_hint"));
+            return Ok(());
+        }
+    };
+
+    // Go through the server if it's running so it also leaves safe mode
+    let client = server_client().build()?;
+    let base = server_url(None);
+    if client.get(format!("{}/health", base)).send().await.is_ok() {
+        let response = client
+            .post(format!("{}/recovery/recover", base))
+            .json(&serde_json::json!({
+                "project_dir": project_dir.to_string_lossy(),
+                "action": if rollback { "rollback" } else { "finish" }
+            }))
+            .send()
+            .await
+            .with_context(|| tr!("recover.contact_failed"))?;
+        let result: serde_json::Value = response.json().await?;
+
+        if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
+            let message = result.get("message").and_then(|v| v.as_str()).unwrap_or("Recovered");
+            println!("\n\x1b[32m✓ {}\x1b[0m", message);
+        } else {
+            let error = result.get("error").and_then(|v| v.as_str()).unwrap_or("Unknown error");
+            bail!("{}", tr!("recover.failed", error));
+        }
+        return Ok(());
+    }
+
+    match recovery::recover(&project_dir, action) {
+        Ok(message) => println!("\n\x1b[32m✓ {}\x1b[0m", message),
+        Err(e) => bail!("{}", tr!("recover.failed", e)),
+    }
+
+    Ok(())
+}
+
+/// Show diff between local files and Studio
+async fn cmd_diff(properties: bool) -> Result<()> {
+    let project_dir = std::env::current_dir().unwrap();
+    let project_dir_str = project_dir.to_string_lossy().to_string();
+
+    let client = server_client().build()?;
+    let base = server_url(None);
+
+    // Check server is running
+    if client.get(format!("{}/health", base)).send().await.is_err() {
+        println!("{}", tr!("server.not_running"));
+        return Ok(());
+    }
+
+    println!("{}", tr!("diff.comparing"));
+
+    // Call diff endpoint
+    let response = client
+        .post(format!("{}/diff", base))
+        .json(&serde_json::json!({
+            "project_dir": project_dir_str,
+            "include_properties": properties
+        }))
+        .send()
+        .await
+        .with_context(|| tr!("sync.diff_failed"))?;
+
+    let diff: serde_json::Value = response.json().await?;
+
+    if diff.get("success").and_then(|v| v.as_bool()) != Some(true) {
+        let error = diff.get("error").and_then(|v| v.as_str()).unwrap_or("Unknown error");
+        println!("{}", tr!("diff.error", error));
+        return Ok(());
+    }
+
+    let added = diff.get("added").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    let removed = diff.get("removed").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    let moved = diff.get("moved").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    let modified: Vec<rbxsync_core::ModifiedEntry> = diff
+        .get("modified")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
+    let kept = diff.get("kept").and_then(|v| v.as_u64()).unwrap_or(0);
+    let common = diff.get("common").and_then(|v| v.as_u64()).unwrap_or(0);
+    let file_count = diff.get("file_count").and_then(|v| v.as_u64()).unwrap_or(0);
+    let studio_count = diff.get("studio_count").and_then(|v| v.as_u64()).unwrap_or(0);
+
+    // Print added (in files, not in Studio)
+    if !added.is_empty() {
+        println!("\n\x1b[32m{}\x1b[0m", tr!("diff.added", added.len()));
+        for entry in added.iter().take(20) {
+            let path = entry.get("path").and_then(|v| v.as_str()).unwrap_or("");
+            let class = entry.get("className").and_then(|v| v.as_str()).unwrap_or("");
+            println!("  + {} ({})", path, class);
+        }
+        if added.len() > 20 {
+            println!("{}", tr!("diff.more", added.len() - 20));
+        }
+    }
+
+    // Print removed (in Studio, not in files)
+    if !removed.is_empty() {
+        println!("\n\x1b[31m{}\x1b[0m", tr!("diff.removed", removed.len()));
+        for entry in removed.iter().take(20) {
+            let path = entry.get("path").and_then(|v| v.as_str()).unwrap_or("");
+            let class = entry.get("className").and_then(|v| v.as_str()).unwrap_or("");
+            println!("  - {} ({})", path, class);
+        }
+        if removed.len() > 20 {
+            println!("{}", tr!("diff.more", removed.len() - 20));
+        }
+    }
+
+    // Print moved (same instance GUID, different path)
+    if !moved.is_empty() {
+        println!("\n\x1b[33m{}\x1b[0m", tr!("diff.moved", moved.len()));
+        for entry in moved.iter().take(20) {
+            let from = entry.get("from").and_then(|v| v.as_str()).unwrap_or("");
+            let to = entry.get("to").and_then(|v| v.as_str()).unwrap_or("");
+            let class = entry.get("className").and_then(|v| v.as_str()).unwrap_or("");
+            println!("  ~ {} → {} ({})", from, to, class);
+        }
+        if moved.len() > 20 {
+            println!("{}", tr!("diff.more", moved.len() - 20));
+        }
+    }
+
+    // Print modified (in both, properties differ): Studio value → file value
+    if !modified.is_empty() {
+        println!("\n\x1b[36m{}\x1b[0m", tr!("diff.modified", modified.len()));
+        for entry in modified.iter().take(20) {
+            println!("  * {} ({})", entry.path, entry.class_name);
+            for prop in &entry.properties {
+                let side = |value: &Option<String>| value.clone().unwrap_or_else(|| "(unset)".to_string());
+                println!("      {}: {} → {}", prop.name, side(&prop.before), side(&prop.after));
+            }
+        }
+        if modified.len() > 20 {
+            println!("{}", tr!("diff.more", modified.len() - 20));
+        }
+    }
+
+    // Summary
+    println!("\n\x1b[1m{}\x1b[0m", tr!("diff.summary"));
+    println!("{}", tr!("diff.summary_files", file_count));
+    println!("{}", tr!("diff.summary_studio", studio_count));
+    println!("{}", tr!("diff.summary_common", common));
+    println!("{}", tr!("diff.summary_added", added.len()));
+    println!("{}", tr!("diff.summary_removed", removed.len()));
+    if kept > 0 {
+        println!("{}", tr!("diff.summary_kept", kept));
+    }
+    let protected = diff.get("protected").and_then(|v| v.as_array()).map(|a| a.len()).unwrap_or(0);
+    if protected > 0 {
+        println!("{}", tr!("diff.summary_protected", protected));
+    }
+    if !moved.is_empty() {
+        println!("{}", tr!("diff.summary_moved", moved.len()));
+    }
+    if !modified.is_empty() {
+        println!("{}", tr!("diff.summary_modified", modified.len()));
+    }
+
+    if added.is_empty() && removed.is_empty() && moved.is_empty() && modified.is_empty() {
+        println!("\n\x1b[32m{}\x1b[0m", tr!("diff.in_sync"));
+    }
+
+    Ok(())
+}
+
+/// Diff two connected Studio places against each other
+async fn cmd_diff_places(place_a: &str, place_b: &str, properties: bool, root: Option<String>) -> Result<()> {
+    let client = server_client().build()?;
+    let base = server_url(None);
+
+    // Check server is running
+    if client.get(format!("{}/health", base)).send().await.is_err() {
+        println!("{}", tr!("server.not_running"));
+        return Ok(());
+    }
+
+    println!("{}", tr!("diff.comparing_places", place_a, place_b));
+
+    let response = client
+        .post(format!("{}/diff/places", base))
+        .json(&serde_json::json!({
+            "place_a": place_a,
+            "place_b": place_b,
+            "include_properties": properties,
+            "path": root
+        }))
+        .send()
+        .await
+        .with_context(|| tr!("diff.places_failed"))?;
+
+    let result: serde_json::Value = response.json().await?;
+
+    if result.get("success").and_then(|v| v.as_bool()) != Some(true) {
+        let error = result.get("error").and_then(|v| v.as_str()).map(str::to_string);
+        println!("{}", tr!("diff.error", error.unwrap_or_else(|| tr!("diff.unknown_error"))));
+
+        // Help pick a selector
+        if let Ok(resp) = client.get(format!("{}/rbxsync/places", base)).send().await {
+            let places: serde_json::Value = resp.json().await.unwrap_or_default();
+            let places = places.get("places").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+            println!("\n{}", tr!("diff.connected_places"));
+            for place in &places {
+                println!(
+                    "{}",
+                    tr!(
+                        "diff.place_entry",
+                        place.get("place_name").and_then(|v| v.as_str()).unwrap_or("?"),
+                        place.get("place_id").and_then(|v| v.as_u64()).unwrap_or(0),
+                        place.get("session_id").and_then(|v| v.as_str()).unwrap_or("-")
+                    )
+                );
+            }
+            if places.is_empty() {
+                println!("{}", tr!("diff.none"));
+            }
         }
-        Commands::FmtProject { path, check } => {
-            cmd_fmt_project(path, check)?;
+        return Ok(());
+    }
+
+    let name = |key: &str| {
+        result
+            .get(key)
+            .and_then(|p| p.get("place_name"))
+            .and_then(|v| v.as_str())
+            .unwrap_or(key)
+            .to_string()
+    };
+    let (name_a, name_b) = (name("placeA"), name("placeB"));
+    let diff = result.get("diff").cloned().unwrap_or_default();
+    let list = |key: &str| diff.get(key).and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    let (only_a, only_b) = (list("onlyInA"), list("onlyInB"));
+    let (class_changed, property_changed) = (list("classChanged"), list("propertyChanged"));
+    let moved = list("moved");
+
+    let print_entries = |title: String, color: &str, sign: char, entries: &[serde_json::Value]| {
+        if entries.is_empty() {
+            return;
         }
-        Commands::Doc => {
-            cmd_doc()?;
+        println!("\n{}{}: {}\x1b[0m", color, title, entries.len());
+        for entry in entries.iter().take(20) {
+            let path = entry.get("path").and_then(|v| v.as_str()).unwrap_or("");
+            let class = entry.get("className").and_then(|v| v.as_str()).unwrap_or("");
+            println!("  {} {} ({})", sign, path, class);
         }
-        Commands::Update { from_source, vscode, yes } => {
-            cmd_update(from_source, vscode, yes).await?;
+        if entries.len() > 20 {
+            println!("{}", tr!("diff.more", entries.len() - 20));
         }
-        Commands::Version => {
-            cmd_version().await?;
+    };
+    print_entries(tr!("diff.only_in", &name_a), "\x1b[31m", '-', &only_a);
+    print_entries(tr!("diff.only_in", &name_b), "\x1b[32m", '+', &only_b);
+
+    if !moved.is_empty() {
+        println!("\n\x1b[33m{}\x1b[0m", tr!("diff.places_moved", moved.len()));
+        for entry in moved.iter().take(20) {
+            println!(
+                "  ~ {} → {} ({})",
+                entry.get("from").and_then(|v| v.as_str()).unwrap_or(""),
+                entry.get("to").and_then(|v| v.as_str()).unwrap_or(""),
+                entry.get("className").and_then(|v| v.as_str()).unwrap_or("")
+            );
         }
-        Commands::Flux { local, set_api_key } => {
-            // Flux agent is not yet implemented in the CLI
-            println!("Flux agent coming soon. Use the flux-agent npm package directly for now.");
-            if local {
-                println!("  --local flag noted");
-            }
-            if let Some(key) = set_api_key {
-                println!("  API key would be set to: {}...", &key[..8.min(key.len())]);
-            }
+        if moved.len() > 20 {
+            println!("{}", tr!("diff.more", moved.len() - 20));
         }
-        Commands::Uninstall { vscode, keep_repo, yes } => {
-            cmd_uninstall(vscode, keep_repo, yes)?;
+    }
+
+    if !class_changed.is_empty() {
+        println!("\n\x1b[33m{}\x1b[0m", tr!("diff.class_differs", class_changed.len()));
+        for entry in class_changed.iter().take(20) {
+            println!(
+                "  ~ {} ({} → {})",
+                entry.get("path").and_then(|v| v.as_str()).unwrap_or(""),
+                entry.get("classA").and_then(|v| v.as_str()).unwrap_or(""),
+                entry.get("classB").and_then(|v| v.as_str()).unwrap_or("")
+            );
         }
-        Commands::Migrate { from, path, force } => {
-            cmd_migrate(from, path, force)?;
+    }
+
+    if !property_changed.is_empty() {
+        println!("\n\x1b[33m{}\x1b[0m", tr!("diff.properties_differ", property_changed.len()));
+        for entry in property_changed.iter().take(20) {
+            let props: Vec<&str> = entry
+                .get("properties")
+                .and_then(|v| v.as_array())
+                .map(|a| a.iter().filter_map(|p| p.as_str()).collect())
+                .unwrap_or_default();
+            println!(
+                "  ~ {}: {}",
+                entry.get("path").and_then(|v| v.as_str()).unwrap_or(""),
+                props.join(", ")
+            );
         }
-        Commands::Harness { action } => {
-            cmd_harness(action).await?;
+        if property_changed.len() > 20 {
+            println!("{}", tr!("diff.more", property_changed.len() - 20));
         }
     }
 
+    // Summary
+    println!("\n\x1b[1m{}\x1b[0m", tr!("diff.summary"));
+    println!("{}", tr!("diff.place_instances", &name_a, result.get("countA").and_then(|v| v.as_u64()).unwrap_or(0)));
+    println!("{}", tr!("diff.place_instances", &name_b, result.get("countB").and_then(|v| v.as_u64()).unwrap_or(0)));
+    println!("{}", tr!("diff.identical_count", diff.get("unchanged").and_then(|v| v.as_u64()).unwrap_or(0)));
+
+    if only_a.is_empty() && only_b.is_empty() && moved.is_empty() && class_changed.is_empty() && property_changed.is_empty() {
+        let message = if properties { tr!("diff.places_identical") } else { tr!("diff.places_identical_structure") };
+        println!("\n\x1b[32m{}\x1b[0m", message);
+    }
+
     Ok(())
 }
 
-/// Initialize a new project
-async fn cmd_init(name: Option<String>, path: Option<PathBuf>, no_sourcemap: bool) -> Result<()> {
+/// List or diff components (tagged instances)
+async fn cmd_components(action: ComponentsAction) -> Result<()> {
+    let client = server_client().build()?;
+    let base = server_url(None);
+
+    // Check server is running
+    if client.get(format!("{}/health", base)).send().await.is_err() {
+        println!("{}", tr!("server.not_running"));
+        return Ok(());
+    }
+
+    let (endpoint, tag, path) = match action {
+        ComponentsAction::List { tag, path } => ("list", tag, path),
+        ComponentsAction::Diff { tag, path } => ("diff", tag, path),
+    };
     let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
-    let project_name = name.unwrap_or_else(|| {
-        project_dir
-            .file_name()
-            .map(|s| s.to_string_lossy().to_string())
-            .unwrap_or_else(|| "MyGame".to_string())
-    });
 
-    tracing::info!("Initializing RbxSync project: {}", project_name);
+    if endpoint == "diff" {
+        println!("{}", tr!("components.comparing"));
+    }
 
-    // Create directory structure
-    let src_dir = project_dir.join("src");
-    let assets_dir = project_dir.join("assets");
-    let terrain_dir = project_dir.join("terrain");
+    let response = client
+        .post(format!("{}/components/{}", base, endpoint))
+        .json(&serde_json::json!({
+            "project_dir": project_dir.to_string_lossy(),
+            "tag": tag
+        }))
+        .send()
+        .await
+        .with_context(|| tr!("components.query_failed"))?;
 
-    std::fs::create_dir_all(&src_dir).context("Failed to create src directory")?;
-    std::fs::create_dir_all(&assets_dir).context("Failed to create assets directory")?;
-    std::fs::create_dir_all(&terrain_dir).context("Failed to create terrain directory")?;
+    let result: serde_json::Value = response.json().await?;
 
-    // Create default service directories
-    for service in &[
-        "Workspace",
-        "ReplicatedStorage",
-        "ServerScriptService",
-        "ServerStorage",
-        "StarterGui",
-        "StarterPack",
-        "StarterPlayer",
-    ] {
-        std::fs::create_dir_all(src_dir.join(service))
-            .context(format!("Failed to create {} directory", service))?;
+    if result.get("success").and_then(|v| v.as_bool()) != Some(true) {
+        let error = result.get("error").and_then(|v| v.as_str()).map(str::to_string);
+        println!("{}", tr!("components.error", error.unwrap_or_else(|| tr!("components.unknown_error"))));
+        return Ok(());
     }
 
-    // Create project config
-    let config = ProjectConfig {
-        name: project_name.clone(),
-        ..Default::default()
+    let components = result.get("components").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    if components.is_empty() {
+        println!("{}", tr!("components.none"));
+        return Ok(());
+    }
+
+    let str_list = |v: Option<&serde_json::Value>| -> Vec<String> {
+        v.and_then(|a| a.as_array())
+            .map(|a| a.iter().filter_map(|s| s.as_str().map(String::from)).collect())
+            .unwrap_or_default()
     };
 
-    let config_path = project_dir.join("rbxsync.json");
-    let config_json = serde_json::to_string_pretty(&config)?;
-    std::fs::write(&config_path, config_json).context("Failed to write rbxsync.json")?;
+    if endpoint == "list" {
+        for group in &components {
+            let tag = group.get("tag").and_then(|v| v.as_str()).unwrap_or("");
+            let instances = group.get("instances").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+            println!("\n\x1b[1m{}\x1b[0m {}", tag, tr!("components.instance_count", instances.len()));
+
+            if let Some(attrs) = group.get("attributes").and_then(|v| v.as_object()) {
+                if !attrs.is_empty() {
+                    let summary: Vec<String> = attrs
+                        .iter()
+                        .map(|(name, count)| format!("{} ({})", name, count))
+                        .collect();
+                    println!("{}", tr!("components.attributes", summary.join(", ")));
+                }
+            }
+            for inst in &instances {
+                let path = inst.get("path").and_then(|v| v.as_str()).unwrap_or("");
+                let class = inst.get("className").and_then(|v| v.as_str()).unwrap_or("");
+                println!("  {} ({})", path, class);
+            }
+        }
+        return Ok(());
+    }
 
-    // Create or update .gitignore (append entries instead of overwriting)
-    let gitignore_path = project_dir.join(".gitignore");
-    let rbxsync_entries = [".rbxsync/", "*.rbxl", "*.rbxlx", ".DS_Store", "Thumbs.db"];
+    let mut changed_tags = 0;
+    for group in &components {
+        let tag = group.get("tag").and_then(|v| v.as_str()).unwrap_or("");
+        let added = str_list(group.get("added"));
+        let removed = str_list(group.get("removed"));
+        let changed = str_list(group.get("changed"));
+        if added.is_empty() && removed.is_empty() && changed.is_empty() {
+            continue;
+        }
+        changed_tags += 1;
 
-    let existing_content = if gitignore_path.exists() {
-        std::fs::read_to_string(&gitignore_path).unwrap_or_default()
+        println!("\n\x1b[1m{}\x1b[0m", tag);
+        for path in &added {
+            println!("  \x1b[32m+ {}\x1b[0m", path);
+        }
+        for path in &removed {
+            println!("  \x1b[31m- {}\x1b[0m", path);
+        }
+        for path in &changed {
+            println!("  \x1b[33m~ {}\x1b[0m {}", path, tr!("components.attributes_changed"));
+        }
+    }
+
+    if changed_tags == 0 {
+        println!("\n\x1b[32m{}\x1b[0m", tr!("components.in_sync"));
     } else {
-        String::new()
-    };
+        println!("\n{}", tr!("components.tags_differ", changed_tags, components.len()));
+    }
 
-    let existing_lines: HashSet<&str> = existing_content.lines().map(|l| l.trim()).collect();
-    let mut additions: Vec<&str> = Vec::new();
+    Ok(())
+}
 
-    for entry in &rbxsync_entries {
-        if !existing_lines.contains(entry) {
-            additions.push(entry);
+/// Upload assets through Open Cloud (no Studio or server needed)
+async fn cmd_asset(action: AssetAction) -> Result<()> {
+    let AssetAction::Upload { files, path } = action;
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let project_dir = project_dir.canonicalize().unwrap_or(project_dir);
+    // Files are given relative to the working directory
+    let files: Vec<PathBuf> = files.into_iter().map(|file| file.canonicalize().unwrap_or(file)).collect();
+
+    println!("{}", tr!("asset.uploading", files.len()));
+    let report = rbxsync_server::assets::upload_assets(&project_dir, &files)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    for asset in &report.uploaded {
+        println!("  {} -> {}", asset.file, asset.url);
+        for property in &asset.updated {
+            println!("{}", tr!("asset.updated", property));
         }
     }
+    for failure in &report.failed {
+        println!("{}", tr!("asset.failed", failure.file, failure.error));
+    }
+    println!("{}", tr!("asset.summary", report.uploaded.len(), report.failed.len()));
+    if !report.failed.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
 
-    if !additions.is_empty() {
-        let mut new_content = existing_content.clone();
-        if !new_content.is_empty() && !new_content.ends_with('\n') {
-            new_content.push('\n');
+/// Sync local changes to Studio
+/// List sync conflicts or resolve one
+async fn cmd_resolve(path: Option<String>, resolution: Option<&str>) -> Result<()> {
+    let project_dir = std::env::current_dir().unwrap();
+    let project_dir_str = project_dir.to_string_lossy().to_string();
+
+    let client = server_client().build()?;
+    let base = server_url(None);
+
+    // Check server is running
+    if client.get(format!("{}/health", base)).send().await.is_err() {
+        println!("{}", tr!("server.not_running"));
+        return Ok(());
+    }
+
+    let response: serde_json::Value = client
+        .post(format!("{}/sync/conflicts", base))
+        .json(&serde_json::json!({ "project_dir": project_dir_str }))
+        .send()
+        .await
+        .with_context(|| tr!("resolve.list_failed"))?
+        .json()
+        .await?;
+    let conflicts: Vec<rbxsync_core::api::SyncConflict> = response
+        .get("conflicts")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
+
+    let Some(path) = path else {
+        if conflicts.is_empty() {
+            println!("\x1b[32m{}\x1b[0m", tr!("resolve.none"));
+            return Ok(());
         }
-        new_content.push_str("\n# RbxSync\n");
-        for entry in additions {
-            new_content.push_str(entry);
-            new_content.push('\n');
+        println!("\x1b[33m{}\x1b[0m", tr!("resolve.list", conflicts.len()));
+        for conflict in &conflicts {
+            let class = conflict.class_name.as_deref().unwrap_or("Instance");
+            println!("{}", tr!("resolve.conflict", conflict.path, class, conflict.studio.change_type));
         }
-        std::fs::write(&gitignore_path, new_content).context("Failed to write .gitignore")?;
-    } else if !gitignore_path.exists() {
-        // Create new .gitignore if it doesn't exist
-        let gitignore_content = "# RbxSync\n.rbxsync/\n*.rbxl\n*.rbxlx\n\n# OS files\n.DS_Store\nThumbs.db\n";
-        std::fs::write(&gitignore_path, gitignore_content).context("Failed to write .gitignore")?;
+        println!("\n{}", tr!("resolve.hint"));
+        return Ok(());
+    };
+
+    let Some(conflict) = conflicts.iter().find(|c| c.path == path) else {
+        bail!("{}", tr!("resolve.not_found", path));
+    };
+    let Some(resolution) = resolution else {
+        bail!("{}", tr!("resolve.choose"));
+    };
+
+    let mut body = serde_json::json!({
+        "project_dir": project_dir_str,
+        "path": conflict.path,
+        "resolution": resolution
+    });
+    if resolution == "merged" {
+        body["source"] = serde_json::json!(merge_script_in_editor(&project_dir, conflict)?);
     }
 
-    // Generate sourcemap for Luau LSP (unless --no-sourcemap)
-    if !no_sourcemap {
-        let sourcemap_path = project_dir.join("sourcemap.json");
-        let root = build_sourcemap_node("game", "DataModel", &src_dir, false)?;
-        let json = serde_json::to_string_pretty(&root)?;
-        std::fs::write(&sourcemap_path, json).context("Failed to write sourcemap.json")?;
+    let result: serde_json::Value = client
+        .post(format!("{}/sync/conflicts/resolve", base))
+        .json(&body)
+        .send()
+        .await
+        .with_context(|| tr!("resolve.failed"))?
+        .json()
+        .await?;
+    if let Some(error) = result.get("error").and_then(|v| v.as_str()) {
+        bail!("{}", error);
     }
+    for error in result.get("errors").and_then(|v| v.as_array()).into_iter().flatten() {
+        println!("\x1b[31m  {}\x1b[0m", error.as_str().unwrap_or_default());
+    }
+    println!("\x1b[32m{}\x1b[0m", tr!("resolve.done", conflict.path, resolution));
+    Ok(())
+}
 
-    println!("Initialized RbxSync project '{}' at {:?}", project_name, project_dir);
-    println!("\nProject structure:");
-    println!("  rbxsync.json      - Project configuration");
-    println!("  src/              - Instance tree");
-    println!("  assets/           - Binary assets (meshes, images, sounds)");
-    println!("  terrain/          - Terrain voxel data");
-    if !no_sourcemap {
-        println!("  sourcemap.json    - For Luau LSP");
+/// Open both versions of a conflicted script in $EDITOR with conflict
+/// markers and return the result once the markers are gone
+fn merge_script_in_editor(project_dir: &std::path::Path, conflict: &rbxsync_core::api::SyncConflict) -> Result<String> {
+    let studio_source = conflict
+        .studio
+        .data
+        .as_ref()
+        .and_then(|d| d.get("source"))
+        .and_then(|s| s.as_str());
+    let local_file = [".server.luau", ".client.luau", ".luau"]
+        .iter()
+        .map(|suffix| rbxsync_core::pathbuf_with_suffix(&project_dir.join("src").join(&conflict.key), suffix))
+        .find(|p| p.is_file());
+    let (Some(studio_source), Some(local_file)) = (studio_source, local_file) else {
+        bail!("{}", tr!("resolve.merge_scripts_only"));
+    };
+    let local_source = std::fs::read_to_string(&local_file)?;
+
+    let merge_file = std::env::temp_dir().join(format!(
+        "rbxsync-merge-{}.luau",
+        conflict.key.replace(['/', '\\'], "-")
+    ));
+    std::fs::write(
+        &merge_file,
+        format!(
+            "<<<<<<< files\n{}\n=======\n{}\n>>>>>>> studio\n",
+            local_source.trim_end_matches('\n'),
+            studio_source.trim_end_matches('\n')
+        ),
+    )?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad".to_string() } else { "vi".to_string() });
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = std::process::Command::new(program)
+        .args(parts)
+        .arg(&merge_file)
+        .status()
+        .with_context(|| tr!("resolve.editor_start_failed", editor))?;
+    let merged = std::fs::read_to_string(&merge_file)?;
+    let _ = std::fs::remove_file(&merge_file);
+    if !status.success() {
+        bail!("{}", tr!("resolve.editor_failed"));
     }
-    println!("\nNext steps:");
-    println!("  1. Open your game in Roblox Studio");
-    println!("  2. Install the RbxSync plugin");
-    println!("  3. Run: rbxsync extract");
 
-    Ok(())
+    let has_markers = merged
+        .lines()
+        .any(|line| line.starts_with("<<<<<<<") || line.starts_with(">>>>>>>") || line == "=======");
+    if has_markers {
+        bail!("{}", tr!("resolve.markers_left"));
+    }
+    Ok(merged)
 }
 
-/// Launch Roblox Studio
-async fn cmd_studio(place: Option<PathBuf>, serve: bool) -> Result<()> {
-    // Find Roblox Studio installation
-    let studio_path = find_studio_path()?;
+async fn cmd_sync(path: Option<PathBuf>, delete: bool, variant: Option<String>, instances: &[String]) -> Result<()> {
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let project_dir_str = project_dir.to_string_lossy().to_string();
+    let roots: Vec<String> = instances.iter().map(|p| rbxsync_core::normalize_instance_root(p)).collect();
+    if !roots.is_empty() {
+        println!("{}", tr!("sync.only", roots.join(", ")));
+    }
 
-    println!("Found Roblox Studio at: {}", studio_path.display());
+    tracing::info!("Syncing from {:?}...", project_dir);
 
-    // Optionally start the sync server
-    if serve {
-        let client = reqwest::Client::new();
-        if client.get("http://localhost:44755/health").send().await.is_err() {
-            println!("Starting sync server in background...");
-            let config = ServerConfig::default();
-            tokio::spawn(async move {
-                if let Err(e) = run_server(config).await {
-                    tracing::error!("Server error: {}", e);
-                }
-            });
-            // Give server time to start
-            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        } else {
-            println!("Sync server already running.");
+    let client = server_client().build()?;
+    let base = server_url(None);
+
+    // Check server is running
+    if client.get(format!("{}/health", base)).send().await.is_err() {
+        println!("{}", tr!("server.not_running"));
+        return Ok(());
+    }
+
+    // Read the local tree
+    println!("{}", tr!("sync.reading"));
+    let tree_response = client
+        .post(format!("{}/sync/read-tree", base))
+        .json(&serde_json::json!({
+            "project_dir": project_dir_str,
+            "variant": variant,
+            "paths": roots
+        }))
+        .send()
+        .await
+        .with_context(|| tr!("sync.read_tree_failed"))?;
+
+    let tree: serde_json::Value = tree_response.json().await?;
+    if tree.get("success").and_then(|v| v.as_bool()) == Some(false) {
+        bail!("{}", tree.get("error").and_then(|v| v.as_str()).unwrap_or("Failed to read local tree"));
+    }
+    let instances = tree.get("instances").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+
+    // Legacy .lua files are either migrated by the server or skipped
+    if let Some(legacy) = tree.get("legacyLua").filter(|v| !v.is_null()) {
+        if let Some(files) = legacy.get("files").and_then(|v| v.as_array()) {
+            println!("\x1b[33m{}\x1b[0m", tr!("sync.legacy_skipped", files.len()));
+            for file in files.iter().filter_map(|f| f.as_str()).take(5) {
+                println!("    {}", file);
+            }
+        } else if let Some(count) = legacy.pointer("/migrated/migrated").and_then(|v| v.as_array()).map(|a| a.len()) {
+            println!("{}", tr!("sync.legacy_migrated", count));
         }
     }
 
-    // Build command to launch Studio
-    let mut command = std::process::Command::new("open");
+    if let Some(report) = tree.get("overrides").filter(|v| !v.is_null()) {
+        if let Ok(report) = serde_json::from_value::<rbxsync_core::OverrideReport>(report.clone()) {
+            print_override_report(&report);
+        }
+    }
 
-    #[cfg(target_os = "macos")]
-    {
-        command.arg("-a").arg(&studio_path);
-        if let Some(ref place_file) = place {
-            // Validate the file exists and has correct extension
-            if !place_file.exists() {
-                anyhow::bail!("Place file not found: {}", place_file.display());
+    // Build sync operations for updates
+    let mut operations: Vec<serde_json::Value> = instances
+        .into_iter()
+        .map(|inst| {
+            serde_json::json!({
+                "type": "update",
+                "path": inst.get("path"),
+                "data": inst
+            })
+        })
+        .collect();
+
+    // If --delete flag is set, get diff and add delete operations
+    if delete {
+        println!("{}", tr!("sync.checking_orphans"));
+        let diff_response = client
+            .post(format!("{}/diff", base))
+            .json(&serde_json::json!({
+                "project_dir": project_dir_str
+            }))
+            .send()
+            .await
+            .with_context(|| tr!("sync.diff_failed"))?;
+
+        let diff: serde_json::Value = diff_response.json().await?;
+        let mut removed = diff.get("removed").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        removed.retain(|entry| rbxsync_core::is_under_any(entry["path"].as_str().unwrap_or(""), &roots));
+
+        // The server leaves protected instances out of the diff; check again
+        // in case it's an older server
+        let config = std::fs::read_to_string(project_dir.join("rbxsync.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .unwrap_or_default();
+        let protected_paths = rbxsync_core::ProtectedPaths::from_config(&config);
+        let mut protected: Vec<String> = diff
+            .get("protected")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry["path"].as_str().map(str::to_string))
+            .filter(|path| rbxsync_core::is_under_any(path, &roots))
+            .collect();
+        removed.retain(|entry| {
+            let path = entry["path"].as_str().unwrap_or("");
+            let keep = !protected_paths.protects(path);
+            if !keep {
+                protected.push(path.to_string());
             }
-            let ext = place_file
-                .extension()
-                .and_then(|e| e.to_str())
-                .unwrap_or("");
-            if ext != "rbxl" && ext != "rbxlx" {
-                anyhow::bail!("Invalid place file format. Expected .rbxl or .rbxlx");
+            keep
+        });
+        if !protected.is_empty() {
+            println!("\x1b[33m{}\x1b[0m", tr!("sync.protected_skipped", protected.len()));
+            for path in protected.iter().take(10) {
+                println!("    {}", path);
             }
-            command.arg(place_file);
         }
-    }
 
-    #[cfg(target_os = "windows")]
-    {
-        command = std::process::Command::new(&studio_path);
-        if let Some(ref place_file) = place {
-            if !place_file.exists() {
-                anyhow::bail!("Place file not found: {}", place_file.display());
+        if !removed.is_empty() {
+            println!("{}", tr!("sync.found_orphans", removed.len()));
+            for entry in removed {
+                let path = entry.get("path").and_then(|v| v.as_str()).unwrap_or("");
+                let class_name = entry.get("class_name").and_then(|v| v.as_str()).unwrap_or("Instance");
+                println!("  \x1b[31m- {}\x1b[0m ({})", path, class_name);
+                operations.push(serde_json::json!({
+                    "type": "delete",
+                    "path": path
+                }));
             }
-            command.arg(place_file);
         }
     }
 
-    println!("Launching Roblox Studio...");
-    command
-        .spawn()
-        .context("Failed to launch Roblox Studio")?;
-
-    if let Some(place_file) = place {
-        println!("Opening: {}", place_file.display());
-    }
-
-    if serve {
-        println!("\nSync server is running. Press Ctrl+C to stop.");
-        // Keep running to serve
-        tokio::signal::ctrl_c().await?;
+    if operations.is_empty() {
+        println!("{}", tr!("sync.no_changes"));
+        return Ok(());
     }
 
-    Ok(())
-}
-
-/// Control playtest in Studio
-async fn cmd_debug(action: DebugAction) -> Result<()> {
-    let client = reqwest::Client::new();
+    let update_count = operations.iter().filter(|op| op.get("type").and_then(|v| v.as_str()) == Some("update")).count();
+    let delete_count = operations.iter().filter(|op| op.get("type").and_then(|v| v.as_str()) == Some("delete")).count();
 
-    // Check server is running
-    if client.get("http://localhost:44755/health").send().await.is_err() {
-        println!("RbxSync server is not running. Start it with: rbxsync serve");
-        return Ok(());
+    if delete_count > 0 {
+        println!("{}", tr!("sync.syncing_with_deletes", update_count, delete_count));
+    } else {
+        println!("{}", tr!("sync.syncing", update_count));
     }
 
-    match action {
-        DebugAction::Start { mode } => {
-            println!("Starting playtest (mode: {})...", mode);
+    // Send batch sync
+    let sync_response = client
+        .post(format!("{}/sync/batch", base))
+        .json(&serde_json::json!({
+            "operations": operations,
+            "projectDir": project_dir_str,
+            "paths": roots
+        }))
+        .send()
+        .await
+        .with_context(|| tr!("sync.failed"))?;
 
-            let response = client
-                .post("http://localhost:44755/sync/command")
-                .json(&serde_json::json!({
-                    "command": "debug:start",
-                    "payload": {
-                        "mode": mode
-                    }
-                }))
-                .send()
-                .await
-                .context("Failed to send debug start command")?;
+    let result: serde_json::Value = sync_response.json().await?;
 
-            let result: serde_json::Value = response.json().await?;
-            if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
-                println!("Playtest started.");
-            } else {
-                let error = result
-                    .get("error")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown error");
-                println!("Failed to start playtest: {}", error);
-            }
+    if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
+        // Use our own counts since server response may not include all operations
+        if delete_count > 0 {
+            println!("\x1b[32m{}\x1b[0m", tr!("sync.synced_with_deletes", update_count, delete_count));
+        } else {
+            println!("\x1b[32m{}\x1b[0m", tr!("sync.synced", update_count));
         }
-        DebugAction::Stop => {
-            println!("Stopping playtest...");
-
-            let response = client
-                .post("http://localhost:44755/sync/command")
-                .json(&serde_json::json!({
-                    "command": "debug:stop",
-                    "payload": {}
-                }))
-                .send()
-                .await
-                .context("Failed to send debug stop command")?;
-
-            let result: serde_json::Value = response.json().await?;
-            if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
-                println!("Playtest stopped.");
-            } else {
-                let error = result
-                    .get("error")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown error");
-                println!("Failed to stop playtest: {}", error);
-            }
+    } else {
+        let errors = result.get("errors").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        println!("{}", tr!("sync.completed_with_errors"));
+        for err in errors {
+            println!("  - {}", err);
         }
-        DebugAction::Status => {
-            let response = client
-                .post("http://localhost:44755/sync/command")
-                .json(&serde_json::json!({
-                    "command": "debug:status",
-                    "payload": {}
-                }))
-                .send()
-                .await
-                .context("Failed to get debug status")?;
+    }
 
-            let result: serde_json::Value = response.json().await?;
-            if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
-                let data = result.get("data").cloned().unwrap_or_default();
-                let running = data.get("running").and_then(|v| v.as_bool()).unwrap_or(false);
-                let mode = data.get("mode").and_then(|v| v.as_str()).unwrap_or("unknown");
+    // Check for terrain data (terrain/, or a legacy terrain.rbxjson) and sync if present
+    if !rbxsync_core::is_under_any("Workspace/Terrain", &roots) {
+        return Ok(());
+    }
+    let terrain_data = rbxsync_core::terrain::read_terrain(&project_dir).with_context(|| tr!("sync.read_terrain_failed"))?;
+    if let Some(terrain_data) = terrain_data {
+        println!("{}", tr!("sync.terrain"));
 
-                if running {
-                    println!("Playtest is running (mode: {})", mode);
-                } else {
-                    println!("No playtest running");
+        // Send terrain sync command
+        let terrain_response = client
+            .post(format!("{}/sync/command", base))
+            .json(&serde_json::json!({
+                "command": "terrain:sync",
+                "payload": {
+                    "terrain": terrain_data,
+                    "clear": true
                 }
-            } else {
-                let error = result
-                    .get("error")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown error");
-                println!("Failed to get status: {}", error);
-            }
+            }))
+            .send()
+            .await
+            .with_context(|| tr!("sync.terrain_request_failed"))?;
+
+        let terrain_result: serde_json::Value = terrain_response.json().await?;
+
+        if terrain_result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
+            let chunks = terrain_result.get("data")
+                .and_then(|d| d.get("chunksApplied"))
+                .and_then(|c| c.as_u64())
+                .unwrap_or(0);
+            println!("\x1b[32m{}\x1b[0m", tr!("sync.terrain_synced", chunks));
+        } else {
+            let error = terrain_result.get("error")
+                .or_else(|| terrain_result.get("data").and_then(|d| d.get("error")))
+                .and_then(|e| e.as_str())
+                .unwrap_or("Unknown error");
+            println!("\x1b[33m{}\x1b[0m", tr!("sync.terrain_failed", error));
         }
     }
 
     Ok(())
 }
 
-/// Find Roblox Studio installation path
-fn find_studio_path() -> Result<PathBuf> {
-    #[cfg(target_os = "macos")]
-    {
-        let default_path = PathBuf::from("/Applications/RobloxStudio.app");
-        if default_path.exists() {
-            return Ok(default_path);
-        }
+/// Sync once, then keep pushing file changes to Studio through the server's
+/// file watcher, without a VS Code workspace
+async fn cmd_sync_watch(path: Option<PathBuf>, delete: bool) -> Result<()> {
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    if !project_dir.join("src").exists() {
+        bail!("{}", tr!("common.src_not_found", project_dir.join("src").display()));
+    }
 
-        // Try user Applications folder
-        if let Ok(home) = std::env::var("HOME") {
-            let user_path = PathBuf::from(home).join("Applications/RobloxStudio.app");
-            if user_path.exists() {
-                return Ok(user_path);
-            }
+    // Subscribing starts the watcher with live sync on (and the server, if needed)
+    let mut events = FileEventStream::subscribe(&project_dir, true).await?;
+
+    let client = server_client().build()?;
+    let base = server_url(None);
+    let places: serde_json::Value = client
+        .get(format!("{}/rbxsync/places", base))
+        .send()
+        .await?
+        .json()
+        .await
+        .unwrap_or_default();
+    let connected = places["places"].as_array().is_some_and(|p| !p.is_empty());
+    if connected {
+        // Start from a Studio tree that matches the files
+        if let Err(e) = cmd_sync(Some(project_dir.clone()), delete, None, &[]).await {
+            println!("\x1b[33m!\x1b[0m {}", tr!("sync.initial_failed", e));
         }
+    } else {
+        println!("\x1b[33m!\x1b[0m {}", tr!("sync.no_session"));
+    }
 
-        anyhow::bail!(
-            "Roblox Studio not found. Expected at:\n  - /Applications/RobloxStudio.app\n  - ~/Applications/RobloxStudio.app"
+    println!(
+        "\n\x1b[32m{}\x1b[0m {}",
+        tr!("sync.watching", project_dir.join("src").display()),
+        tr!("build.ctrl_c")
+    );
+    let mut totals = SyncWatchTotals::default();
+    while let Some(batch) = events.next().await? {
+        let changes = batch["changes"].as_array().cloned().unwrap_or_default();
+        let counts = totals.add(&changes);
+        println!(
+            "\x1b[36m↑\x1b[0m {}  \x1b[2m{} {}\x1b[0m",
+            describe_changes(&batch),
+            counts,
+            tr!("sync.pushed_session", totals.total())
         );
     }
 
-    #[cfg(target_os = "windows")]
-    {
-        // Check common Windows install locations
-        let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_default();
-        let program_files = std::env::var("PROGRAMFILES(X86)")
-            .or_else(|_| std::env::var("PROGRAMFILES"))
-            .unwrap_or_default();
+    println!("{}", tr!("common.stream_closed"));
+    Ok(())
+}
 
-        let possible_paths = [
-            PathBuf::from(&local_app_data).join("Roblox/Versions"),
-            PathBuf::from(&program_files).join("Roblox/Versions"),
-        ];
+/// Running counts of the changes `sync --watch` pushed, by kind
+#[derive(Default)]
+struct SyncWatchTotals {
+    counts: std::collections::BTreeMap<String, usize>,
+}
 
-        for versions_dir in possible_paths {
-            if versions_dir.exists() {
-                // Find the latest version with RobloxStudioBeta.exe
-                if let Ok(entries) = std::fs::read_dir(&versions_dir) {
-                    for entry in entries.flatten() {
-                        let studio_exe = entry.path().join("RobloxStudioBeta.exe");
-                        if studio_exe.exists() {
-                            return Ok(studio_exe);
-                        }
-                    }
-                }
-            }
+impl SyncWatchTotals {
+    /// Count a batch's file changes; returns the batch's own summary
+    /// (e.g. "2 modify, 1 rename")
+    fn add(&mut self, changes: &[serde_json::Value]) -> String {
+        let mut batch: std::collections::BTreeMap<&str, usize> = std::collections::BTreeMap::new();
+        // A new folder is counted through the files in it
+        for change in changes.iter().filter(|c| !(c["isDir"] == true && c["kind"] == "create")) {
+            let kind = change["kind"].as_str().unwrap_or("modify");
+            *batch.entry(kind).or_default() += 1;
+            *self.counts.entry(kind.to_string()).or_default() += 1;
         }
+        batch.iter().map(|(kind, n)| format!("{} {}", n, kind)).collect::<Vec<_>>().join(", ")
+    }
 
-        anyhow::bail!("Roblox Studio not found. Please install it from roblox.com");
+    fn total(&self) -> usize {
+        self.counts.values().sum()
     }
+}
 
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-    {
-        anyhow::bail!("Roblox Studio is not available on this platform");
+/// Human-readable form of a stored property value
+fn display_property_value(value: &serde_json::Value) -> String {
+    match value.get("value").filter(|_| value.get("enumType").is_some()).unwrap_or(value) {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
     }
 }
 
-/// Extract game from Studio
-async fn cmd_extract(
-    services: Option<Vec<String>>,
-    terrain: bool,
-    assets: bool,
-    _output: Option<PathBuf>,
-) -> Result<()> {
-    tracing::info!("Starting extraction...");
+/// Replace a property value in .rbxjson files and push the changes to Studio
+async fn cmd_replace(spec: PropertyReplace, dry_run: bool) -> Result<()> {
+    let project_dir = std::env::current_dir()?;
+    let src_dir = project_dir.join("src");
+    if !src_dir.exists() {
+        bail!("{}", tr!("replace.no_src", src_dir.display()));
+    }
 
-    // Check if server is running
-    let client = reqwest::Client::new();
-    let health_check = client.get("http://localhost:44755/health").send().await;
+    let replacements = replace_property(&src_dir, &spec, dry_run).with_context(|| tr!("replace.failed"))?;
+    if replacements.is_empty() {
+        println!("{}", tr!("replace.none", spec.class_name, spec.property, spec.from));
+        return Ok(());
+    }
 
-    if health_check.is_err() {
-        println!("RbxSync server is not running.");
-        println!("Starting server in background...");
+    let header = if dry_run { "replace.would_update" } else { "replace.updated" };
+    println!("{}", tr!(header, replacements.len()));
+    for r in replacements.iter().take(20) {
+        println!(
+            "  \x1b[33m~\x1b[0m {}: {} → {}",
+            r.instance_path,
+            display_property_value(&r.old_value),
+            display_property_value(&r.new_value)
+        );
+    }
+    if replacements.len() > 20 {
+        println!("{}", tr!("diff.more", replacements.len() - 20));
+    }
 
-        // Start server in background
-        tokio::spawn(async {
-            if let Err(e) = run_server(ServerConfig::default()).await {
-                tracing::error!("Server error: {}", e);
-            }
-        });
+    if dry_run {
+        println!("\n{}", tr!("replace.dry_run"));
+        return Ok(());
+    }
 
-        // Wait for server to start
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    let client = server_client().build()?;
+    let base = server_url(None);
+    if client.get(format!("{}/health", base)).send().await.is_err() {
+        println!("\n{}", tr!("replace.offline"));
+        return Ok(());
     }
 
-    // Send extraction request
-    let response = client
-        .post("http://localhost:44755/extract/start")
-        .json(&serde_json::json!({
-            "services": services,
-            "include_terrain": terrain,
-            "include_assets": assets,
-        }))
+    // Same instance data read-tree would produce for these files
+    let mut operations = Vec::new();
+    for r in &replacements {
+        let content = std::fs::read_to_string(&r.file)?;
+        let mut data: serde_json::Value = serde_json::from_str(&content)?;
+        let path = rbxsync_server::normalize_path_for_comparison(&r.instance_path);
+        if let Some(obj) = data.as_object_mut() {
+            obj.insert("path".to_string(), serde_json::json!(path));
+            if !obj.contains_key("name") {
+                let name = path.rsplit('/').next().unwrap_or_default();
+                obj.insert("name".to_string(), serde_json::json!(name));
+            }
+        }
+        operations.push(serde_json::json!({
+            "type": "update",
+            "path": path,
+            "data": data
+        }));
+    }
+
+    let result: serde_json::Value = client
+        .post(format!("{}/sync/batch", base))
+        .json(&serde_json::json!({ "operations": operations }))
         .send()
         .await
-        .context("Failed to start extraction")?;
+        .with_context(|| tr!("sync.failed"))?
+        .json()
+        .await?;
 
-    let result: serde_json::Value = response.json().await?;
-    println!("Extraction started: {}", serde_json::to_string_pretty(&result)?);
-
-    println!("\nWaiting for Studio plugin to send data...");
-    println!("Make sure the RbxSync plugin is enabled in Roblox Studio.");
-
-    // Poll for completion
-    loop {
-        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+    if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
+        println!("\n\x1b[32m{}\x1b[0m", tr!("replace.synced", operations.len()));
+    } else {
+        let errors = result.get("errors").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        println!("\n{}", tr!("sync.completed_with_errors"));
+        for err in errors {
+            println!("  - {}", err);
+        }
+    }
 
-        let status = client
-            .get("http://localhost:44755/extract/status")
-            .send()
-            .await?
-            .json::<serde_json::Value>()
-            .await?;
+    Ok(())
+}
 
-        if let Some(complete) = status.get("complete").and_then(|v| v.as_bool()) {
-            if complete {
-                let chunks = status.get("chunksReceived").and_then(|v| v.as_u64()).unwrap_or(0);
-                println!("\nExtraction complete! Received {} chunks.", chunks);
-                break;
-            }
-        }
+/// Build the Studio plugin as .rbxm
+async fn cmd_build_plugin(config: PluginBuildConfig, install: bool, watch: bool, hot: bool) -> Result<()> {
+    println!("{}", tr!("plugin.building_from", format!("{:?}", config.source_dir)));
+    if config.obfuscate {
+        println!("{}", tr!("plugin.obfuscation_on"));
+    } else {
+        println!("{}", tr!("plugin.obfuscation_off"));
+    }
 
-        if let Some(received) = status.get("chunksReceived").and_then(|v| v.as_u64()) {
-            if let Some(total) = status.get("totalChunks").and_then(|v| v.as_u64()) {
-                print!("\rReceived {}/{} chunks...", received, total);
-            } else {
-                print!("\rReceived {} chunks...", received);
+    let output_path = do_build_plugin(&config)?;
+    if hot {
+        publish_hot_plugin(&output_path, &config.plugin_name).await?;
+    }
+
+    if watch {
+        // Poll the source directory; it's usually outside any synced project
+        let mut fingerprint = plugin_source_fingerprint(&config.source_dir);
+        println!("\n{}", tr!("plugin.watching", config.source_dir.display()));
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            let current = plugin_source_fingerprint(&config.source_dir);
+            if current == fingerprint {
+                continue;
+            }
+            fingerprint = current;
+            println!("\n{}", tr!("plugin.rebuilding"));
+            match do_build_plugin(&config) {
+                Ok(output_path) if hot => {
+                    if let Err(e) = publish_hot_plugin(&output_path, &config.plugin_name).await {
+                        println!("{}", tr!("plugin.hot_failed", format!("{:#}", e)));
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => println!("{}", tr!("plugin.build_error", format!("{:#}", e))),
             }
         }
     }
 
+    if install {
+        println!("\n{}", tr!("plugin.installing"));
+        let installed_path =
+            install_plugin(&output_path, &config.plugin_name).with_context(|| tr!("plugin.install_failed"))?;
+        println!("\x1b[32m{}\x1b[0m: {}", tr!("plugin.installed"), installed_path.display());
+        println!("\n{}", tr!("plugin.restart_to_load"));
+    } else {
+        println!("\n{}", tr!("plugin.install_hint"));
+        println!("{}", tr!("plugin.copy_hint", output_path.display()));
+    }
+
     Ok(())
 }
 
-/// Detect project structure for zero-config mode
-fn detect_project_structure() -> Option<String> {
-    let cwd = std::env::current_dir().ok()?;
+/// Build the plugin once and report it
+fn do_build_plugin(config: &PluginBuildConfig) -> Result<PathBuf> {
+    let (output_path, stats) = rbxsync_core::build_plugin_with_stats(config).with_context(|| tr!("plugin.build_failed"))?;
 
-    // Check for common project structures
-    if cwd.join("src").is_dir() {
-        return Some("src".to_string());
+    println!("\n\x1b[32m{}\x1b[0m", tr!("plugin.built"));
+    println!("{}", tr!("plugin.output", output_path.display()));
+    println!("{}", tr!("plugin.files_processed", stats.files_processed));
+    if config.obfuscate {
+        println!("{}", tr!("plugin.patterns_obfuscated", stats.obfuscation_transforms));
+    }
+    if config.cache_dir.is_some() {
+        if stats.output_reused {
+            println!("{}", tr!("plugin.cache_output_reused", stats.cache_hits, stats.cache_misses));
+        } else {
+            println!("{}", tr!("plugin.cache", stats.cache_hits, stats.cache_misses));
+        }
     }
+    Ok(output_path)
+}
 
-    // Check for Rojo-style project
-    if cwd.join("default.project.json").exists() {
-        return Some("rojo".to_string());
+/// Names, sizes and modification times of the files in a plugin source directory
+fn plugin_source_fingerprint(dir: &std::path::Path) -> Vec<(PathBuf, u64, Option<std::time::SystemTime>)> {
+    let mut files: Vec<_> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let metadata = entry.metadata().ok()?;
+            Some((entry.path(), metadata.len(), metadata.modified().ok()))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Hand a build to the server for the hot-reload bootstrap plugin
+async fn publish_hot_plugin(output_path: &std::path::Path, name: &str) -> Result<()> {
+    let client = server_client().build()?;
+    let base = server_url(None);
+    ensure_local_server(&client).await;
+    let rbxm = std::fs::read(output_path).with_context(|| tr!("plugin.read_failed", output_path.display()))?;
+    let response: serde_json::Value = client
+        .post(format!("{}/plugin/hot", base))
+        .query(&[("name", name)])
+        .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+        .header(rbxsync_core::auth::CLIENT_HEADER, "cli")
+        .body(rbxm)
+        .send()
+        .await
+        .with_context(|| tr!("server.not_running"))?
+        .json()
+        .await?;
+    if response["success"].as_bool() != Some(true) {
+        bail!("{}", response["error"].as_str().map(String::from).unwrap_or_else(|| tr!("plugin.hot_rejected")));
     }
+    println!("\x1b[36m↻\x1b[0m {}", tr!("plugin.hot_sent", response["version"].as_u64().unwrap_or_default()));
+    Ok(())
+}
 
-    // Check for any .luau or .lua files indicating a Roblox project
-    if let Ok(entries) = std::fs::read_dir(&cwd) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if let Some(ext) = path.extension() {
-                if ext == "luau" || ext == "lua" {
-                    return Some("flat".to_string());
-                }
-            }
+/// Obfuscate every Luau file under `dir`, reporting per-file and total
+/// transforms. Files are only written with `--output` or `--in-place`.
+fn cmd_obfuscate(dir: PathBuf, output: Option<PathBuf>, in_place: bool, config: Option<PathBuf>) -> Result<()> {
+    use rbxsync_core::{ObfuscationStats, Obfuscator};
+
+    if !dir.is_dir() {
+        bail!("{}", tr!("obfuscate.dir_not_found", dir.display()));
+    }
+    let config = config.or_else(|| Some(PathBuf::from("obfuscate.toml")).filter(|p| p.exists()));
+    let mut obfuscator = match &config {
+        Some(path) => Obfuscator::from_config_file(path)?,
+        None => Obfuscator::with_defaults(),
+    };
+
+    let mut files = Vec::new();
+    collect_luau_files(&dir, &mut files)?;
+    files.sort();
+
+    let mut stats = ObfuscationStats::default();
+    for file in &files {
+        obfuscator.regenerate_prefix();
+        let result = obfuscator.obfuscate_file(file)?;
+        stats.add(&result);
+        let relative = file.strip_prefix(&dir).unwrap_or(file);
+        if result.total_transforms() > 0 {
+            println!(
+                "{}",
+                tr!(
+                    "obfuscate.file_stats",
+                    relative.display(),
+                    result.strings_encoded,
+                    result.debug_stripped,
+                    result.comments_removed
+                )
+            );
+        }
+
+        let target = match &output {
+            Some(output) => output.join(relative),
+            None if in_place => file.clone(),
+            None => continue,
+        };
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
         }
+        std::fs::write(&target, &result.source).with_context(|| tr!("common.write_failed", target.display()))?;
     }
 
-    None
+    println!(
+        "\n\x1b[32m✓\x1b[0m {}",
+        tr!(
+            "obfuscate.total",
+            stats.scripts,
+            stats.total_transforms(),
+            stats.strings_encoded,
+            stats.debug_stripped,
+            stats.comments_removed
+        )
+    );
+    match (&output, in_place) {
+        (Some(output), _) => println!("{}", tr!("obfuscate.written_to", output.display())),
+        (None, true) => println!("{}", tr!("obfuscate.in_place")),
+        (None, false) => println!("{}", tr!("obfuscate.nothing_written")),
+    }
+    Ok(())
 }
 
-/// Start the sync server
-async fn cmd_serve(port: u16, background: bool) -> Result<()> {
-    let config_path = std::env::current_dir()?.join("rbxsync.json");
-    let zero_config_mode = !config_path.exists();
+/// `.luau` and `.lua` files under `dir`, recursively
+fn collect_luau_files(dir: &std::path::Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir).with_context(|| tr!("common.read_failed", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_luau_files(&path, files)?;
+        } else if path.extension().is_some_and(|e| e == "luau" || e == "lua") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
 
-    if zero_config_mode {
-        // Zero-config mode: work without rbxsync.json
-        let project_structure = detect_project_structure();
+/// Manage the Studio plugin
+async fn cmd_plugin(action: PluginAction) -> Result<()> {
+    let plugins_folder = get_studio_plugins_folder()
+        .with_context(|| tr!("plugin.no_plugins_folder"))?;
 
-        println!("Running in zero-config mode (no rbxsync.json found)");
-        println!();
+    match action {
+        PluginAction::Install { path, name, download, force } => {
+            let plugin_name = name.unwrap_or_else(|| "RbxSync".to_string());
 
-        match project_structure {
-            Some(ref structure) if structure == "src" => {
-                println!("Detected: src/ directory (standard structure)");
-            }
-            Some(ref structure) if structure == "rojo" => {
-                println!("Detected: Rojo project (default.project.json)");
-                println!("Tip: Run `rbxsync migrate` to convert to RbxSync format");
-            }
-            Some(_) => {
-                println!("Detected: Luau files in current directory");
-            }
-            None => {
-                println!("No existing project detected - will create src/ on first extract");
+            // Check for existing marketplace plugin
+            if !force {
+                if let Some(existing) = find_existing_rbxsync_plugin() {
+                    println!("\x1b[33m{}\x1b[0m {}", tr!("plugin.existing_detected"), existing.display());
+                    println!();
+                    println!("{}", tr!("plugin.marketplace_detected"));
+                    println!("{}", tr!("plugin.or_force"));
+                    println!();
+                    println!("{}", tr!("plugin.uninstall_marketplace"));
+                    println!("{}", tr!("plugin.step_open_studio"));
+                    println!("{}", tr!("plugin.step_manage"));
+                    println!("{}", tr!("plugin.step_uninstall"));
+                    println!();
+                    println!("{}", tr!("plugin.then_install"));
+                    return Ok(());
+                }
             }
+
+            // Determine the plugin path
+            let plugin_path = if let Some(p) = path {
+                // User specified a path
+                p
+            } else if download {
+                // Force download from GitHub
+                download_plugin_from_github().await?
+            } else if PathBuf::from("build/RbxSync.rbxm").exists() {
+                // Use local build
+                PathBuf::from("build/RbxSync.rbxm")
+            } else if PathBuf::from("plugin/src").exists() {
+                // Build from source
+                println!("{}", tr!("plugin.building_from_source"));
+                let output_path = PathBuf::from("build/RbxSync.rbxm");
+                let config = PluginBuildConfig {
+                    source_dir: PathBuf::from("plugin/src"),
+                    output_path: output_path.clone(),
+                    plugin_name: plugin_name.clone(),
+                    obfuscate: true,
+                    obfuscate_config: None,
+                    cache_dir: None,
+                };
+                build_plugin(&config).with_context(|| tr!("plugin.build_failed"))?;
+                output_path
+            } else {
+                // Download from GitHub
+                println!("{}", tr!("plugin.downloading"));
+                download_plugin_from_github().await?
+            };
+
+            println!("{}", tr!("plugin.installing"));
+            let installed_path =
+                install_plugin(&plugin_path, &plugin_name).with_context(|| tr!("plugin.install_failed"))?;
+            println!("{}", tr!("plugin.installed_to", installed_path.display()));
+            println!("\n{}", tr!("plugin.restart_to_load"));
         }
+        PluginAction::Uninstall { name } => {
+            let plugin_name = name.unwrap_or_else(|| "RbxSync".to_string());
+            let plugin_path = plugins_folder.join(format!("{}.rbxm", plugin_name));
 
-        println!();
-        println!("Using defaults:");
-        println!("  Source folder: ./src");
-        println!("  Assets folder: ./assets");
-        println!();
-        println!("For more control, create rbxsync.json with: rbxsync init");
-        println!();
-    } else {
-        // Validate JSON is parseable if config exists
-        let config_content = std::fs::read_to_string(&config_path)
-            .context("Failed to read rbxsync.json")?;
+            if !plugin_path.exists() {
+                println!("{}", tr!("plugin.not_installed", plugin_name));
+                return Ok(());
+            }
 
-        if let Err(e) = serde_json::from_str::<serde_json::Value>(&config_content) {
-            eprintln!("Error: Invalid JSON in rbxsync.json");
-            eprintln!();
-            eprintln!("Parse error: {}", e);
-            eprintln!();
-            eprintln!("Please fix the JSON syntax and try again.");
-            std::process::exit(1);
+            std::fs::remove_file(&plugin_path).with_context(|| tr!("plugin.remove_failed"))?;
+            println!("{}", tr!("plugin.uninstalled_from", plugin_name, plugin_path.display()));
+            println!("\n{}", tr!("plugin.restart_to_apply"));
         }
-    }
+        PluginAction::List => {
+            println!("{}", tr!("plugin.plugins_folder", plugins_folder.display()));
+            println!();
 
-    // Check if port is available before attempting to start
-    if !is_port_available(port) {
-        eprintln!("Error: Port {} is already in use.", port);
-        eprintln!();
-        eprintln!("This could mean:");
-        eprintln!("  - Another rbxsync server is already running");
-        eprintln!("  - Another application is using this port");
-        eprintln!();
-        eprintln!("Try: rbxsync stop --port {}", port);
-        eprintln!("Or use a different port: rbxsync serve --port <PORT>");
-        std::process::exit(1);
-    }
+            if !plugins_folder.exists() {
+                println!("{}", tr!("plugin.folder_missing"));
+                return Ok(());
+            }
 
-    if background {
-        // Spawn server as a detached background process
-        let exe = std::env::current_exe()?;
-        let mut cmd = std::process::Command::new(&exe);
-        cmd.args(["serve", "--port", &port.to_string()]);
+            let entries: Vec<_> = std::fs::read_dir(&plugins_folder)
+                .with_context(|| tr!("plugin.read_folder_failed"))?
+                .filter_map(|e| e.ok())
+                .filter(|e| {
+                    e.path()
+                        .extension()
+                        .map(|ext| ext == "rbxm" || ext == "rbxmx")
+                        .unwrap_or(false)
+                })
+                .collect();
 
-        #[cfg(unix)]
-        {
-            use std::os::unix::process::CommandExt;
-            cmd.process_group(0); // Create new process group
-        }
+            if entries.is_empty() {
+                println!("{}", tr!("plugin.none_installed"));
+            } else {
+                println!("{}", tr!("plugin.installed_list"));
+                for entry in entries {
+                    let name = entry.file_name();
+                    let metadata = entry.metadata().ok();
+                    let size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
 
-        #[cfg(windows)]
-        {
-            use std::os::windows::process::CommandExt;
-            const DETACHED_PROCESS: u32 = 0x00000008;
-            const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
-            cmd.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+                    println!(
+                        "  {} ({:.1} KB)",
+                        name.to_string_lossy(),
+                        size as f64 / 1024.0
+                    );
+                }
+            }
         }
+    }
 
-        let child = cmd
-            .stdin(std::process::Stdio::null())
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
-            .spawn()
-            .context("Failed to spawn background server")?;
+    Ok(())
+}
 
-        println!("RbxSync server started in background (PID: {})", child.id());
-        println!("  Port: {}", port);
-        println!("  Stop with: rbxsync stop");
-        return Ok(());
+/// Generate sourcemap for Luau LSP
+/// Generate the sourcemap, annotate it if asked and write it
+fn write_project_sourcemap(
+    project_dir: &std::path::Path,
+    output_path: &std::path::Path,
+    include_non_scripts: bool,
+    stats: bool,
+) -> Result<()> {
+    let mut root = rbxsync_core::sourcemap::generate_sourcemap(project_dir, include_non_scripts)?;
+
+    if stats {
+        let totals = annotate_sourcemap_stats(&mut root);
+        if let Some(obj) = root.as_object_mut() {
+            obj.insert("summary".to_string(), totals.summary());
+        }
+        println!(
+            "{}",
+            tr!("sourcemap.annotated", totals.instances, totals.scripts, totals.lines, totals.bytes)
+        );
     }
 
-    // Foreground mode
-    println!("RbxSync server running on port {}", port);
-    println!("Stop with: Ctrl+C or `rbxsync stop` from another terminal");
-    println!("Run in background with: rbxsync serve --background");
-    run_server(ServerConfig {
-        port,
-        ..Default::default()
-    })
-    .await
+    let json = serde_json::to_string_pretty(&root)?;
+    std::fs::write(output_path, json).with_context(|| tr!("sourcemap.write_failed"))?;
+    Ok(())
 }
 
-/// Stop the running sync server
-async fn cmd_stop(port: &str) -> Result<()> {
-    // Handle "all" to stop all rbxsync servers
-    if port == "all" {
-        return stop_all_servers().await;
+async fn cmd_sourcemap(
+    path: Option<PathBuf>,
+    output: Option<PathBuf>,
+    include_non_scripts: bool,
+    stats: bool,
+    watch: bool,
+) -> Result<()> {
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let output_path = output.unwrap_or_else(|| project_dir.join(rbxsync_core::sourcemap::SOURCEMAP_FILE));
+    let src_dir = project_dir.join("src");
+
+    if !src_dir.exists() {
+        anyhow::bail!("{}", tr!("common.src_not_found", src_dir.display()));
     }
 
-    let port_num: u16 = port.parse().context("Invalid port number")?;
-    stop_server_on_port(port_num).await
-}
+    println!("{}", tr!("sourcemap.generating", format!("{:?}", src_dir)));
+    write_project_sourcemap(&project_dir, &output_path, include_non_scripts, stats)?;
 
-/// Stop all rbxsync servers
-#[cfg(unix)]
-async fn stop_all_servers() -> Result<()> {
-    let output = std::process::Command::new("pgrep")
-        .args(["-f", "rbxsync.*serve"])
-        .output();
+    println!("{}", tr!("sourcemap.written", output_path.display()));
+    println!("\n{}", tr!("sourcemap.luaurc_hint"));
+    println!("{{");
+    println!("  \"languageMode\": \"strict\",");
+    println!("  \"aliases\": {{}}");
+    println!("}}");
 
-    if let Ok(output) = output {
-        let pids = String::from_utf8_lossy(&output.stdout);
-        let pids: Vec<&str> = pids.lines().filter(|p| !p.is_empty()).collect();
+    if !watch {
+        return Ok(());
+    }
 
-        if pids.is_empty() {
-            println!("No rbxsync servers running.");
-            return Ok(());
+    // File changes and files written for Studio changes both come through the stream
+    let mut events = FileEventStream::subscribe(&project_dir, false).await?;
+    println!("\n{}", tr!("sourcemap.watching"));
+
+    while let Some(batch) = events.next().await? {
+        let affected = batch
+            .get("changes")
+            .and_then(|c| c.as_array())
+            .into_iter()
+            .flatten()
+            .any(|c| {
+                let kind = c.get("kind").and_then(|k| k.as_str()).unwrap_or_default();
+                let path = c.get("path").and_then(|p| p.as_str()).unwrap_or_default();
+                std::path::Path::new(path) != output_path && rbxsync_core::sourcemap::affects_sourcemap(kind, path)
+            });
+        if !affected {
+            continue;
         }
-
-        println!("Stopping {} rbxsync server(s)...", pids.len());
-        for pid in pids {
-            if let Ok(pid) = pid.trim().parse::<i32>() {
-                unsafe {
-                    libc::kill(pid, libc::SIGTERM);
-                }
-            }
+        match write_project_sourcemap(&project_dir, &output_path, include_non_scripts, stats) {
+            Ok(()) => println!("{}", tr!("sourcemap.updated", format!("\x1b[36m↻ {}\x1b[0m", describe_changes(&batch)))),
+            Err(e) => println!("\x1b[31m{}\x1b[0m", tr!("sourcemap.error", e)),
         }
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        println!("All servers stopped.");
     }
-    Ok(())
-}
 
-#[cfg(not(unix))]
-async fn stop_all_servers() -> Result<()> {
-    println!("Stopping all servers is only supported on Unix systems.");
-    println!("Please specify a port: rbxsync stop --port PORT");
+    println!("{}", tr!("common.stream_closed"));
     Ok(())
 }
 
-/// Wait for a port to be released, polling up to `timeout_ms` milliseconds
-async fn wait_for_port_release(port: u16, timeout_ms: u64) -> bool {
-    let start = std::time::Instant::now();
-    let timeout = std::time::Duration::from_millis(timeout_ms);
+fn cmd_codegen(path: PathBuf, output: Option<PathBuf>, force: bool) -> Result<()> {
+    if !path.exists() {
+        bail!("{}", tr!("codegen.path_not_found", path.display()));
+    }
+    if let Some(out) = &output {
+        if out.exists() && !force {
+            bail!("{}", tr!("codegen.output_exists", out.display()));
+        }
+    }
 
-    while start.elapsed() < timeout {
-        // Try to bind to the port - if successful, the port is free
-        match std::net::TcpListener::bind(format!("127.0.0.1:{}", port)) {
-            Ok(_) => return true,
-            Err(_) => {
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    // Instance path of the parent, so service containers get their class
+    let path = path.canonicalize().unwrap_or(path);
+    let parent_inst_path = path
+        .ancestors()
+        .find(|dir| dir.file_name().is_some_and(|n| n == "src"))
+        .and_then(|src| path.parent()?.strip_prefix(src).ok())
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_default();
+
+    let root = codegen_node(&path, &parent_inst_path)?
+        .with_context(|| tr!("codegen.not_instance", path.display()))?;
+    let label = match path.file_name() {
+        Some(name) if !parent_inst_path.is_empty() => format!("src/{}/{}", parent_inst_path, name.to_string_lossy()),
+        Some(name) => name.to_string_lossy().to_string(),
+        None => path.display().to_string(),
+    };
+    let generated = rbxsync_core::generate_module(&root, &label);
+
+    match &output {
+        Some(out) => {
+            if let Some(parent) = out.parent() {
+                std::fs::create_dir_all(parent).ok();
             }
+            std::fs::write(out, &generated.source).with_context(|| tr!("codegen.write_failed"))?;
+            eprintln!(
+                "\x1b[32m✓\x1b[0m {}",
+                tr!("codegen.generated", out.display(), generated.instances, generated.source.len())
+            );
         }
+        None => print!("{}", generated.source),
     }
-    false
-}
 
-/// Check if a port is available
-fn is_port_available(port: u16) -> bool {
-    std::net::TcpListener::bind(format!("127.0.0.1:{}", port)).is_ok()
+    // Warnings go to stderr so stdout stays a valid module
+    if !generated.warnings.is_empty() {
+        eprintln!("\x1b[33m{}\x1b[0m", tr!("codegen.warnings", generated.warnings.len()));
+        for warning in &generated.warnings {
+            eprintln!("  \x1b[33m!\x1b[0m {}", warning);
+        }
+    }
+    Ok(())
 }
 
-/// Stop server on a specific port
-async fn stop_server_on_port(port: u16) -> Result<()> {
-    // First, try to find any rbxsync server processes by port
-    #[cfg(unix)]
-    {
-        let port_arg = format!(":{}", port);
-        let output = std::process::Command::new("lsof")
-            .args(["-ti", &port_arg])
-            .output();
+/// Read an instance and its descendants for codegen, following the same file
+/// layout rules as `build_dom_children`. Returns `None` for files that aren't
+/// instances.
+fn codegen_node(path: &std::path::Path, parent_inst_path: &str) -> Result<Option<rbxsync_core::CodegenNode>> {
+    use rbxsync_core::CodegenNode;
+
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    if path.is_file() {
+        if file_name.ends_with(".rbxjson") {
+            let name = file_name.trim_end_matches(".rbxjson");
+            let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)
+                .with_context(|| tr!("common.parse_failed", path.display()))?;
+            let class_name = json
+                .get("className")
+                .and_then(|c| c.as_str())
+                .or_else(|| rbxsync_core::container_class_name(parent_inst_path, name))
+                .unwrap_or("Folder");
+            let mut node = CodegenNode::new(class_name, name);
+            node.apply_rbxjson(&json);
+            return Ok(Some(node));
+        }
+        if file_name.ends_with(".luau") || file_name.ends_with(".lua") {
+            let (name, class_name) = parse_script_name(&file_name);
+            return Ok(Some(CodegenNode::new(class_name, name)));
+        }
+        return Ok(None);
+    }
 
-        if let Ok(output) = output {
-            let pids = String::from_utf8_lossy(&output.stdout);
-            let pids: Vec<&str> = pids.lines().filter(|p| !p.is_empty()).collect();
+    let meta: Option<serde_json::Value> = std::fs::read_to_string(path.join("_meta.rbxjson"))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok());
+
+    // Class priority: init file, sibling script, _meta.rbxjson, special container
+    let class_name = init_script_class(path)
+        .or_else(|| find_sibling_script(path).map(|(_, class)| class))
+        .or_else(|| meta.as_ref()?.get("className")?.as_str())
+        .or_else(|| rbxsync_core::container_class_name(parent_inst_path, &file_name))
+        .unwrap_or("Folder");
+    let mut node = CodegenNode::new(class_name, &file_name);
+    if let Some(meta) = &meta {
+        node.apply_rbxjson(meta);
+    }
 
-            if pids.is_empty() {
-                println!("No server running on port {}.", port);
-                return Ok(());
-            }
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(path)
+        .with_context(|| tr!("fmt.read_dir_failed"))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .collect();
+    entries.sort();
 
-            // Try graceful shutdown first via HTTP
-            let client = reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(2))
-                .build()?;
+    let sibling_scripts: HashSet<PathBuf> = entries
+        .iter()
+        .filter(|p| p.is_dir())
+        .filter_map(|p| find_sibling_script(p).map(|(script, _)| script))
+        .collect();
+    let inst_path = child_instance_path(parent_inst_path, &file_name);
+    for entry in entries {
+        let name = entry.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        if ["init.luau", "init.server.luau", "init.client.luau", "_meta.rbxjson"].contains(&name.as_str())
+            || sibling_scripts.contains(&entry)
+        {
+            continue;
+        }
+        if let Some(child) = codegen_node(&entry, &inst_path)? {
+            node.children.push(child);
+        }
+    }
+    Ok(Some(node))
+}
 
-            let url = format!("http://localhost:{}/shutdown", port);
-            let _ = client.post(&url).send().await;  // Ignore result, check if port is released
+async fn cmd_console(action: ConsoleAction) -> Result<()> {
+    let client = server_client().build()?;
+    let base = server_url(None);
 
-            // Wait briefly for graceful shutdown
-            if wait_for_port_release(port, 2000).await {
-                println!("Server stopped.");
-                return Ok(());
+    match action {
+        ConsoleAction::Reporter { url, key, output } => {
+            let output = output.unwrap_or_else(|| {
+                PathBuf::from("src/ServerScriptService/RbxSyncErrorReporter.server.luau")
+            });
+            if let Some(parent) = output.parent() {
+                std::fs::create_dir_all(parent)?;
             }
-
-            // Graceful shutdown didn't work, try SIGTERM first (allows cleanup)
-            println!("Sending SIGTERM to server...");
-            for pid in &pids {
-                if let Ok(pid) = pid.trim().parse::<i32>() {
-                    unsafe {
-                        libc::kill(pid, libc::SIGTERM);
-                    }
-                }
+            let source = rbxsync_server::production::reporter_source(&url, key.as_deref());
+            std::fs::write(&output, source).with_context(|| tr!("console.reporter_write_failed"))?;
+            println!("\x1b[32m{}\x1b[0m", tr!("console.reporter_written", output.display()));
+            println!("{}", tr!("console.reporter_hint"));
+        }
+        ConsoleAction::Import { file, place_id } => {
+            let content = std::fs::read_to_string(&file)
+                .with_context(|| tr!("common.read_failed", file.display()))?;
+            let events = rbxsync_server::production::parse_analytics_export(&content)
+                .map_err(|e| anyhow::anyhow!(e))?;
+            if events.is_empty() {
+                println!("{}", tr!("console.import_empty"));
+                return Ok(());
             }
 
-            // Wait for SIGTERM to take effect
-            if wait_for_port_release(port, 2000).await {
-                println!("Server stopped.");
+            if client.get(format!("{}/health", base)).send().await.is_err() {
+                println!("{}", tr!("server.not_running"));
                 return Ok(());
             }
 
-            // SIGTERM didn't work, force kill with SIGKILL
-            println!("Force killing server (SIGKILL)...");
-            for pid in &pids {
-                if let Ok(pid) = pid.trim().parse::<i32>() {
-                    unsafe {
-                        libc::kill(pid, libc::SIGKILL);
-                    }
-                }
+            let mut request = client
+                .post(format!("{}/console/ingest", base))
+                .json(&serde_json::json!({ "placeId": place_id, "events": events }));
+            if let Ok(key) = std::env::var(rbxsync_server::production::INGEST_KEY_ENV) {
+                request = request.header(rbxsync_server::production::INGEST_KEY_HEADER, key);
             }
-
-            // Final check
-            if wait_for_port_release(port, 2000).await {
-                println!("Server stopped.");
+            let result: serde_json::Value = request.send().await?.json().await?;
+            if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
+                let received = result.get("received").and_then(|v| v.as_u64()).unwrap_or(0);
+                println!("\x1b[32m{}\x1b[0m", tr!("console.imported", received));
             } else {
-                // Last resort: print the PIDs so user can manually kill
-                println!("Warning: Could not stop server. Try manually:");
-                for pid in &pids {
-                    println!("  kill -9 {}", pid.trim());
-                }
+                let error = result.get("error").and_then(|v| v.as_str()).unwrap_or("unknown error");
+                anyhow::bail!("{}", error);
             }
-            return Ok(());
         }
-    }
-
-    // Fallback for non-unix or if lsof failed
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(2))
-        .build()?;
+        ConsoleAction::Errors { tag, warnings } => {
+            let mut url = format!("{}/console/errors?warnings={}", base, warnings);
+            if let Some(tag) = &tag {
+                url.push_str(&format!("&tag={}", tag));
+            }
+            let Ok(response) = client.get(&url).send().await else {
+                println!("{}", tr!("server.not_running"));
+                return Ok(());
+            };
+            let result: serde_json::Value = response.json().await?;
+            let groups = result.get("groups").and_then(|g| g.as_array()).cloned().unwrap_or_default();
+            if groups.is_empty() {
+                println!("{}", tr!("console.no_errors"));
+                return Ok(());
+            }
 
-    let url = format!("http://localhost:{}/shutdown", port);
-    match client.post(&url).send().await {
-        Ok(_) => {
-            println!("Server stopped.");
-            Ok(())
-        }
-        Err(_) => {
-            println!("No server running on port {}.", port);
-            Ok(())
+            for group in groups {
+                let count = group.get("count").and_then(|v| v.as_u64()).unwrap_or(0);
+                let message = group.get("message").and_then(|v| v.as_str()).unwrap_or_default();
+                let is_production = group
+                    .get("tags")
+                    .and_then(|t| t.as_array())
+                    .is_some_and(|t| t.iter().any(|v| v == "production"));
+                let label = if is_production { " \x1b[35m[production]\x1b[0m" } else { "" };
+                println!("\x1b[31m{:>6}×\x1b[0m {}{}", count, message, label);
+                if let Some(last) = group.get("lastSeen").and_then(|v| v.as_str()) {
+                    println!("        \x1b[2m{}\x1b[0m", tr!("console.last_seen", last));
+                }
+                let locations = group.get("locations").and_then(|l| l.as_array()).cloned().unwrap_or_default();
+                for link in locations.iter().filter_map(|l| l.get("link").and_then(|v| v.as_str())) {
+                    println!("        \x1b[36m→ {}\x1b[0m", link);
+                }
+            }
         }
     }
+    Ok(())
 }
 
-/// Show status
-async fn cmd_status() -> Result<()> {
-    let client = reqwest::Client::new();
-
-    match client.get("http://localhost:44755/health").send().await {
-        Ok(response) => {
-            let health: serde_json::Value = response.json().await?;
-            println!("Server status: {}", serde_json::to_string_pretty(&health)?);
-
-            // Check extraction status
-            let status = client
-                .get("http://localhost:44755/extract/status")
-                .send()
-                .await?
-                .json::<serde_json::Value>()
-                .await?;
-
-            println!("Extraction status: {}", serde_json::to_string_pretty(&status)?);
+fn cmd_backup(action: BackupAction) -> Result<()> {
+    match action {
+        BackupAction::List { path } => {
+            let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+            print_backups(&project_dir);
         }
-        Err(_) => {
-            println!("Server is not running.");
-            println!("Start it with: rbxsync serve");
+        BackupAction::Restore { paths, backup: name, path } => {
+            let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+            restore_backup(&project_dir, name.as_deref(), &paths)?;
         }
     }
-
     Ok(())
 }
 
-/// Show diff between local files and Studio
-async fn cmd_diff() -> Result<()> {
-    let project_dir = std::env::current_dir().unwrap();
-    let project_dir_str = project_dir.to_string_lossy().to_string();
-
-    let client = reqwest::Client::new();
+/// List a project's backups, newest first, with their snapshot ids
+fn print_backups(project_dir: &std::path::Path) {
+    use rbxsync_server::backup;
 
-    // Check server is running
-    if client.get("http://localhost:44755/health").send().await.is_err() {
-        println!("RbxSync server is not running. Start it with: rbxsync serve");
-        return Ok(());
+    let backups = backup::list_backups(project_dir);
+    if backups.is_empty() {
+        println!("{}", tr!("backup.none", backup::backup_dir(project_dir).display()));
+        return;
     }
+    let config = backup::load_config(project_dir);
+    let total: u64 = backups.iter().map(|b| b.size).sum();
+    for b in &backups {
+        println!(
+            "  {} \x1b[2m{}\x1b[0m",
+            b.id,
+            tr!("backup.entry", format!("{:.1}", b.size as f64 / 1_048_576.0), b.created_at)
+        );
+    }
+    println!(
+        "\n{}",
+        tr!(
+            "backup.summary",
+            backups.len(),
+            format!("{:.1}", total as f64 / 1_048_576.0),
+            if config.keep == 0 { tr!("backup.keep_all") } else { config.keep.to_string() },
+            config.max_size_mb
+        )
+    );
+}
 
-    println!("Comparing files with Studio...");
-
-    // Call diff endpoint
-    let response = client
-        .post("http://localhost:44755/diff")
-        .json(&serde_json::json!({
-            "project_dir": project_dir_str
-        }))
-        .send()
-        .await
-        .context("Failed to get diff")?;
-
-    let diff: serde_json::Value = response.json().await?;
+/// Restore src/ (or some paths in it) from a backup, by id or name, or the newest
+fn restore_backup(project_dir: &std::path::Path, name: Option<&str>, paths: &[String]) -> Result<()> {
+    use rbxsync_server::backup;
 
-    if diff.get("success").and_then(|v| v.as_bool()) != Some(true) {
-        let error = diff.get("error").and_then(|v| v.as_str()).unwrap_or("Unknown error");
-        println!("Error: {}", error);
-        return Ok(());
+    let archive = backup::find_backup(project_dir, name).with_context(|| match name {
+        Some(name) => tr!("backup.not_found", name),
+        None => tr!("backup.none_found"),
+    })?;
+    let src_dir = project_dir.join("src");
+    let files = if paths.is_empty() {
+        backup::restore_all(&archive, &src_dir)
+    } else {
+        backup::restore_paths(&archive, &src_dir, paths)
     }
+    .with_context(|| tr!("backup.restore_failed"))?;
+    println!(
+        "\x1b[32m✓\x1b[0m {}",
+        tr!("backup.restored", files, archive.file_name().unwrap_or_default().to_string_lossy())
+    );
+    Ok(())
+}
 
-    let added = diff.get("added").and_then(|v| v.as_array()).cloned().unwrap_or_default();
-    let removed = diff.get("removed").and_then(|v| v.as_array()).cloned().unwrap_or_default();
-    let common = diff.get("common").and_then(|v| v.as_u64()).unwrap_or(0);
-    let file_count = diff.get("file_count").and_then(|v| v.as_u64()).unwrap_or(0);
-    let studio_count = diff.get("studio_count").and_then(|v| v.as_u64()).unwrap_or(0);
+/// Save, list or restore named snapshots
+async fn cmd_snapshot(action: SnapshotAction) -> Result<()> {
+    use rbxsync_server::snapshot;
 
-    // Print added (in files, not in Studio)
-    if !added.is_empty() {
-        println!("\n\x1b[32mFiles → Studio (would be created): {}\x1b[0m", added.len());
-        for entry in added.iter().take(20) {
-            let path = entry.get("path").and_then(|v| v.as_str()).unwrap_or("");
-            let class = entry.get("className").and_then(|v| v.as_str()).unwrap_or("");
-            println!("  + {} ({})", path, class);
-        }
-        if added.len() > 20 {
-            println!("  ... and {} more", added.len() - 20);
+    let project_dir = |path: Option<PathBuf>| path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    match action {
+        SnapshotAction::Save { name, terrain, force, path } => {
+            let project_dir = project_dir(path);
+            let info = snapshot::create(&project_dir, &name, terrain, force).map_err(|e| anyhow::anyhow!(e))?;
+            println!(
+                "\x1b[32m{}\x1b[0m",
+                tr!("snapshot.saved", info.name, info.files, snapshot::snapshot_dir(&project_dir, &info.name).display())
+            );
         }
-    }
-
-    // Print removed (in Studio, not in files)
-    if !removed.is_empty() {
-        println!("\n\x1b[31mStudio only (would be deleted with --delete): {}\x1b[0m", removed.len());
-        for entry in removed.iter().take(20) {
-            let path = entry.get("path").and_then(|v| v.as_str()).unwrap_or("");
-            let class = entry.get("className").and_then(|v| v.as_str()).unwrap_or("");
-            println!("  - {} ({})", path, class);
+        SnapshotAction::List { path } => {
+            let snapshots = snapshot::list(&project_dir(path));
+            if snapshots.is_empty() {
+                println!("{}", tr!("snapshot.none"));
+            }
+            for info in snapshots {
+                let mut details = vec![info.created_at.clone(), tr!("snapshot.files", info.files)];
+                if info.terrain {
+                    details.push("terrain".to_string());
+                }
+                if let Some(head) = &info.head {
+                    details.push(head.label());
+                }
+                println!("  {:<24} \x1b[2m{}\x1b[0m", info.name, details.join(", "));
+            }
         }
-        if removed.len() > 20 {
-            println!("  ... and {} more", removed.len() - 20);
+        SnapshotAction::Restore { name, files, no_terrain, path } => {
+            let project_dir = project_dir(path).to_string_lossy().to_string();
+            let base = server_url(None);
+            let response = server_client()
+                .build()?
+                .post(format!("{}/snapshot/restore", base))
+                .json(&serde_json::json!({
+                    "projectDir": project_dir,
+                    "name": name,
+                    "files": files,
+                    "terrain": !no_terrain
+                }))
+                .send()
+                .await
+                .with_context(|| tr!("server.not_running"))?;
+            let body: serde_json::Value = response.json().await?;
+            if body["success"] != true {
+                bail!("{}", body["error"].as_str().unwrap_or("Request failed"));
+            }
+            if let Some(count) = body["filesRestored"].as_u64() {
+                println!("{}", tr!("snapshot.files_restored", count));
+            }
+            if let Some(chunks) = body["terrainChunks"].as_u64() {
+                println!("{}", tr!("snapshot.terrain_restored", chunks));
+            }
+            println!("\x1b[32m{}\x1b[0m", tr!("snapshot.restored", name, body["operations"].as_u64().unwrap_or(0)));
         }
     }
-
-    // Summary
-    println!("\n\x1b[1mSummary:\x1b[0m");
-    println!("  Files: {} instances", file_count);
-    println!("  Studio: {} instances", studio_count);
-    println!("  Common: {} (in sync)", common);
-    println!("  Added: {} (files → studio)", added.len());
-    println!("  Removed: {} (studio only)", removed.len());
-
-    if added.is_empty() && removed.is_empty() {
-        println!("\n\x1b[32m✓ Files and Studio are in sync!\x1b[0m");
-    }
-
     Ok(())
 }
 
-/// Sync local changes to Studio
-async fn cmd_sync(path: Option<PathBuf>, delete: bool) -> Result<()> {
-    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
-    let project_dir_str = project_dir.to_string_lossy().to_string();
+fn cmd_flags(action: FlagsAction) -> Result<()> {
+    use rbxsync_server::flags;
 
-    tracing::info!("Syncing from {:?}...", project_dir);
+    let (path, change) = match action {
+        FlagsAction::List { path } => (path, None),
+        FlagsAction::Set { name, value, path } => {
+            if !flags::valid_flag_name(&name) {
+                anyhow::bail!("{}", tr!("flags.invalid_name", name));
+            }
+            let enabled = flags::parse_flag_value(&value)
+                .with_context(|| tr!("flags.invalid_value", value))?;
+            (path, Some((name, Some(enabled))))
+        }
+        FlagsAction::Unset { name, path } => (path, Some((name, None))),
+    };
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let mut all = flags::load_flags(&project_dir).map_err(|e| anyhow::anyhow!(e))?;
 
-    let client = reqwest::Client::new();
+    match change {
+        None => {
+            if all.is_empty() {
+                println!("{}", tr!("flags.none", project_dir.join(flags::FLAGS_FILE).display()));
+                return Ok(());
+            }
+            for (name, enabled) in &all {
+                let state = if *enabled { "\x1b[32mon\x1b[0m" } else { "\x1b[2moff\x1b[0m" };
+                println!("  {} {}", name, state);
+            }
+        }
+        Some((name, value)) => {
+            match value {
+                Some(enabled) => {
+                    all.insert(name.clone(), enabled);
+                }
+                None if all.remove(&name).is_none() => anyhow::bail!("{}", tr!("flags.not_found", name)),
+                None => {}
+            }
+            let server = project_server_url(&project_dir, None);
+            let module = flags::save_flags(&project_dir, &all, &server).map_err(|e| anyhow::anyhow!(e))?;
+            let state = match value {
+                Some(true) => "on".to_string(),
+                Some(false) => "off".to_string(),
+                None => tr!("flags.removed"),
+            };
+            println!("\x1b[32m✓\x1b[0m {} {}", name, state);
+            println!("  \x1b[2m{}\x1b[0m", tr!("flags.updated_module", module.display()));
+        }
+    }
+    Ok(())
+}
 
-    // Check server is running
-    if client.get("http://localhost:44755/health").send().await.is_err() {
-        println!("RbxSync server is not running. Start it with: rbxsync serve");
-        return Ok(());
+fn print_wally_drift(drift: &rbxsync_core::WallyDrift) {
+    for line in drift.lines() {
+        println!("  \x1b[33m!\x1b[0m {}", line);
     }
+}
 
-    // Read the local tree
-    println!("Reading local files...");
-    let tree_response = client
-        .post("http://localhost:44755/sync/read-tree")
-        .json(&serde_json::json!({
-            "project_dir": project_dir_str
-        }))
+/// Index behind `wally.run`, whose registry API is known without reading its config
+const WALLY_DEFAULT_INDEX: &str = "https://github.com/UpliftGames/wally-index";
+const WALLY_DEFAULT_API: &str = "https://api.wally.run";
+/// Wally release whose registry protocol we speak
+const WALLY_CLIENT_VERSION: &str = "0.3.2";
+
+/// Registry API for an index repository, from the `api` field of its config.json
+async fn wally_api_url(client: &reqwest::Client, registry: &str) -> Result<String> {
+    let repo = registry.trim_end_matches('/').trim_end_matches(".git");
+    if repo.eq_ignore_ascii_case(WALLY_DEFAULT_INDEX) {
+        return Ok(WALLY_DEFAULT_API.to_string());
+    }
+    let Some(github) = repo.strip_prefix("https://github.com/") else {
+        anyhow::bail!("{}", tr!("wally.github_only", registry));
+    };
+    let url = format!("https://raw.githubusercontent.com/{}/HEAD/config.json", github);
+    let config: serde_json::Value = client
+        .get(&url)
         .send()
         .await
-        .context("Failed to read local tree")?;
-
-    let tree: serde_json::Value = tree_response.json().await?;
-    let instances = tree.get("instances").and_then(|v| v.as_array()).cloned().unwrap_or_default();
-
-    // Build sync operations for updates
-    let mut operations: Vec<serde_json::Value> = instances
-        .into_iter()
-        .map(|inst| {
-            serde_json::json!({
-                "type": "update",
-                "path": inst.get("path"),
-                "data": inst
-            })
-        })
-        .collect();
+        .and_then(|r| r.error_for_status())
+        .with_context(|| tr!("common.fetch_failed", url))?
+        .json()
+        .await
+        .with_context(|| tr!("common.parse_failed", url))?;
+    config
+        .get("api")
+        .and_then(|api| api.as_str())
+        .map(|api| api.trim_end_matches('/').to_string())
+        .ok_or_else(|| anyhow::anyhow!("{}", tr!("wally.no_api", url)))
+}
 
-    // If --delete flag is set, get diff and add delete operations
-    if delete {
-        println!("Checking for orphaned instances in Studio...");
-        let diff_response = client
-            .post("http://localhost:44755/diff")
-            .json(&serde_json::json!({
-                "project_dir": project_dir_str
-            }))
-            .send()
-            .await
-            .context("Failed to get diff")?;
+/// Lay out the packages in wally.lock under the project's Packages folders,
+/// downloading the ones that aren't installed yet
+async fn wally_install(project_dir: &std::path::Path, force: bool) -> Result<()> {
+    let project = project_dir.to_string_lossy().to_string();
+    let plan = rbxsync_core::plan_wally_install(project_dir, &rbxsync_server::packages_folder(&project))?;
+    let pending: Vec<&rbxsync_core::PlannedPackage> =
+        plan.packages.iter().filter(|p| force || !p.is_installed(project_dir)).collect();
+
+    if !pending.is_empty() {
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("rbxsync/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        let api = wally_api_url(&client, &plan.registry).await?;
+        for package in &pending {
+            let (scope, name) = package.name.split_once('/').unwrap_or(("", &package.name));
+            let url = format!("{}/v1/package-contents/{}/{}/{}", api, scope, name, package.version);
+            println!("{}", tr!("wally.downloading", package.name, package.version));
+            let archive = client
+                .get(&url)
+                .header("Wally-Version", WALLY_CLIENT_VERSION)
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .with_context(|| tr!("wally.download_failed", package.name, package.version))?
+                .bytes()
+                .await?;
+            rbxsync_core::unpack_wally_package(project_dir, package, &archive)
+                .with_context(|| tr!("wally.unpack_failed", package.name, package.version))?;
+        }
+    }
 
-        let diff: serde_json::Value = diff_response.json().await?;
-        let removed = diff.get("removed").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    for folder in rbxsync_core::finish_wally_install(project_dir, &plan)? {
+        println!("{}", tr!("wally.removed", folder));
+    }
+    let folders =
+        plan.folders.iter().filter(|f| project_dir.join(f).is_dir()).cloned().collect::<Vec<_>>().join(", ");
+    println!("\x1b[32m✓\x1b[0m {}", tr!("wally.installed", plan.packages.len(), pending.len(), folders));
+    Ok(())
+}
 
-        if !removed.is_empty() {
-            println!("Found {} orphaned instances to delete", removed.len());
-            for entry in removed {
-                let path = entry.get("path").and_then(|v| v.as_str()).unwrap_or("");
-                let class_name = entry.get("class_name").and_then(|v| v.as_str()).unwrap_or("Instance");
-                println!("  \x1b[31m- {}\x1b[0m ({})", path, class_name);
-                operations.push(serde_json::json!({
-                    "type": "delete",
-                    "path": path
-                }));
-            }
+async fn cmd_wally(action: WallyAction) -> Result<()> {
+    let (path, fix) = match action {
+        WallyAction::Install { path, force } => {
+            let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+            return wally_install(&project_dir, force).await;
         }
+        WallyAction::Check { path, fix } => (path, fix),
+    };
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let project = project_dir.to_string_lossy().to_string();
+    if rbxsync_core::find_wally_lock(&project_dir).is_none() {
+        println!("{}", tr!("wally.no_lock", project_dir.display()));
+        return Ok(());
     }
 
-    if operations.is_empty() {
-        println!("No changes to sync.");
+    let Some(drift) = rbxsync_server::check_wally_drift(&project) else {
+        println!("\x1b[32m✓\x1b[0m {}", tr!("wally.match"));
         return Ok(());
+    };
+    println!("{}", tr!("wally.mismatch"));
+    print_wally_drift(&drift);
+
+    if !fix {
+        println!("\n{}", tr!("wally.fix_hint", "\x1b[1mrbxsync wally check --fix\x1b[0m"));
+        std::process::exit(1);
     }
 
-    let update_count = operations.iter().filter(|op| op.get("type").and_then(|v| v.as_str()) == Some("update")).count();
-    let delete_count = operations.iter().filter(|op| op.get("type").and_then(|v| v.as_str()) == Some("delete")).count();
+    println!("\n{}", tr!("wally.installing_from_lock"));
+    wally_install(&project_dir, false).await?;
 
-    if delete_count > 0 {
-        println!("Syncing {} updates and {} deletes to Studio...", update_count, delete_count);
+    match rbxsync_server::check_wally_drift(&project) {
+        None => println!("\x1b[32m✓\x1b[0m {}", tr!("wally.match")),
+        Some(drift) => {
+            println!("{}", tr!("wally.still_mismatch"));
+            print_wally_drift(&drift);
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
+
+fn cmd_summarize(path: Option<PathBuf>, json: bool) -> Result<()> {
+    use rbxsync_server::summary;
+
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let result = summary::generate(&project_dir).map_err(|e| anyhow::anyhow!(e))?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+        print!("{}", rbxsync_core::render_markdown(&result));
+        eprintln!("\x1b[2m{}\x1b[0m", tr!("summarize.written_to", project_dir.join(summary::SUMMARY_FILE).display()));
+    }
+    Ok(())
+}
+
+fn format_duration_ms(ms: u64) -> String {
+    if ms >= 1000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
     } else {
-        println!("Syncing {} instances to Studio...", update_count);
+        format!("{}ms", ms)
     }
+}
 
-    // Send batch sync
-    let sync_response = client
-        .post("http://localhost:44755/sync/batch")
-        .json(&serde_json::json!({
-            "operations": operations
-        }))
-        .send()
-        .await
-        .context("Failed to sync")?;
+fn cmd_stats(path: Option<PathBuf>, weeks: usize, json: bool) -> Result<()> {
+    use rbxsync_server::stats;
 
-    let result: serde_json::Value = sync_response.json().await?;
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let runs = stats::load_runs(&project_dir);
+    let summary = stats::summarize(&runs, weeks);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+    if runs.is_empty() {
+        println!("{}", tr!("stats.no_history", stats::stats_file(&project_dir).display()));
+        return Ok(());
+    }
 
-    if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
-        // Use our own counts since server response may not include all operations
-        if delete_count > 0 {
-            println!("\x1b[32m✓ Synced {} instances, deleted {} orphans.\x1b[0m", update_count, delete_count);
+    println!("\x1b[1m{}\x1b[0m {}\n", tr!("stats.history"), tr!("stats.runs", summary.total_runs));
+    for d in &summary.directions {
+        let errors = if d.errors > 0 {
+            format!("  \x1b[31m{}\x1b[0m", tr!("stats.errors", d.errors))
         } else {
-            println!("\x1b[32m✓ Synced {} instances to Studio.\x1b[0m", update_count);
-        }
-    } else {
-        let errors = result.get("errors").and_then(|v| v.as_array()).cloned().unwrap_or_default();
-        println!("Sync completed with errors:");
-        for err in errors {
-            println!("  - {}", err);
-        }
+            String::new()
+        };
+        println!(
+            "  {:<12} {}{}",
+            d.direction,
+            tr!(
+                "stats.direction_row",
+                format!("{:>5}", d.runs),
+                format!("{:>7}", format_duration_ms(d.avg_duration_ms)),
+                format!("{:>7}", format_duration_ms(d.last_duration_ms)),
+                d.last_instances
+            ),
+            errors
+        );
     }
 
-    // Check for terrain data and sync if present
-    let terrain_file = project_dir.join("src").join("Workspace").join("Terrain").join("terrain.rbxjson");
-    if terrain_file.exists() {
-        println!("Syncing terrain...");
+    println!("\n\x1b[1m{}\x1b[0m\n", tr!("stats.by_week"));
+    for week in &summary.weeks {
+        println!(
+            "  {}  {}",
+            stats::date_label(week.week_start),
+            tr!(
+                "stats.week_row",
+                format!("{:>5}", week.runs),
+                format!("{:>7}", format_duration_ms(week.avg_duration_ms)),
+                format!("{:>7}", week.max_instances)
+            )
+        );
+    }
 
-        // Read terrain data
-        let terrain_json = std::fs::read_to_string(&terrain_file)
-            .context("Failed to read terrain file")?;
-        let terrain_data: serde_json::Value = serde_json::from_str(&terrain_json)
-            .context("Failed to parse terrain file")?;
+    if let (Some(duration), Some(growth)) = (summary.duration_change_pct, summary.instance_growth_pct) {
+        let trend = tr!(
+            "stats.trend",
+            format!("{:+.1}", duration),
+            format!("{:+.1}", growth),
+            summary.weeks.len()
+        );
+        println!("\n  {}", trend);
+    }
+    for note in &summary.notes {
+        println!("  \x1b[33m!\x1b[0m {}", note);
+    }
+    Ok(())
+}
 
-        // Send terrain sync command
-        let terrain_response = client
-            .post("http://localhost:44755/sync/command")
-            .json(&serde_json::json!({
-                "command": "terrain:sync",
-                "payload": {
-                    "terrain": terrain_data,
-                    "clear": true
-                }
-            }))
-            .send()
-            .await
-            .context("Failed to sync terrain")?;
+/// Unified change history for one instance
+fn cmd_blame(instance_path: &str, path: Option<PathBuf>, limit: usize, json: bool) -> Result<()> {
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let history = rbxsync_server::blame::blame(&project_dir, instance_path, limit).map_err(|e| anyhow::anyhow!(e))?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&history)?);
+        return Ok(());
+    }
 
-        let terrain_result: serde_json::Value = terrain_response.json().await?;
+    println!("\x1b[1m{}\x1b[0m", history.instance_path);
+    for file in &history.files {
+        println!("  {}", file);
+    }
+    for note in &history.notes {
+        println!("  \x1b[33m!\x1b[0m {}", note);
+    }
 
-        if terrain_result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
-            let chunks = terrain_result.get("data")
-                .and_then(|d| d.get("chunksApplied"))
-                .and_then(|c| c.as_u64())
-                .unwrap_or(0);
-            println!("\x1b[32m✓ Synced {} terrain chunks.\x1b[0m", chunks);
-        } else {
-            let error = terrain_result.get("error")
-                .or_else(|| terrain_result.get("data").and_then(|d| d.get("error")))
-                .and_then(|e| e.as_str())
-                .unwrap_or("Unknown error");
-            println!("\x1b[33m⚠ Terrain sync failed: {}\x1b[0m", error);
+    if !history.properties.is_empty() {
+        println!("\n\x1b[1m{}\x1b[0m\n", tr!("blame.properties"));
+        for p in &history.properties {
+            println!(
+                "  {:<24} {:<8} {}  {}",
+                p.property,
+                p.commit.as_deref().unwrap_or("-"),
+                rbxsync_server::stats::date_label(p.timestamp),
+                p.author
+            );
         }
     }
 
+    println!("\n\x1b[1m{}\x1b[0m\n", tr!("blame.history"));
+    if history.history.is_empty() {
+        println!("{}", tr!("blame.no_changes"));
+    }
+    for entry in &history.history {
+        let source = match entry.commit.as_deref() {
+            Some(commit) => format!("\x1b[33m{}\x1b[0m", commit),
+            None => "\x1b[36mstudio \x1b[0m".to_string(),
+        };
+        println!(
+            "  {} {}  {}  {}",
+            source,
+            rbxsync_server::stats::date_label(entry.timestamp),
+            entry.author,
+            entry.summary
+        );
+    }
     Ok(())
 }
 
-/// Build the Studio plugin as .rbxm
-fn cmd_build_plugin(
-    source: Option<PathBuf>,
-    output: Option<PathBuf>,
-    name: Option<String>,
-    install: bool,
-    obfuscate: bool,
-    obfuscate_config: Option<PathBuf>,
+async fn cmd_bench(
+    instances: usize,
+    iterations: usize,
+    save: Option<PathBuf>,
+    baseline: Option<PathBuf>,
+    max_regression: f64,
 ) -> Result<()> {
-    use rbxsync_core::build_plugin_with_stats;
-
-    let config = PluginBuildConfig {
-        source_dir: source.unwrap_or_else(|| PathBuf::from("plugin/src")),
-        output_path: output.unwrap_or_else(|| PathBuf::from("build/RbxSync.rbxm")),
-        plugin_name: name.unwrap_or_else(|| "RbxSync".to_string()),
-        obfuscate,
-        obfuscate_config,
+    use rbxsync_server::bench::{self, StageResult};
+
+    // Read the baseline first so a bad path fails before the run
+    let baseline: Option<Vec<StageResult>> = match &baseline {
+        Some(path) => Some(
+            serde_json::from_str(
+                &std::fs::read_to_string(path).with_context(|| tr!("common.read_failed", path.display()))?,
+            )
+            .with_context(|| tr!("bench.invalid_baseline"))?,
+        ),
+        None => None,
     };
 
-    println!("Building plugin from {:?}...", config.source_dir);
-    if config.obfuscate {
-        println!("Obfuscation: enabled");
-    } else {
-        println!("Obfuscation: disabled");
+    let project_dir = std::env::temp_dir().join(format!("rbxsync-bench-{}", std::process::id()));
+    println!("{}\n", tr!("bench.running", instances, iterations));
+    let results = bench::run(&project_dir, instances, iterations).await;
+    let _ = std::fs::remove_dir_all(&project_dir);
+    let results = results.map_err(|e| anyhow::anyhow!(e))?;
+
+    for result in &results {
+        let base = baseline
+            .as_ref()
+            .and_then(|b| b.iter().find(|b| b.stage == result.stage && b.instances == result.instances));
+        match base {
+            Some(base) => println!(
+                "  {:<12} {:>9.1} ms   {}",
+                result.stage,
+                result.millis,
+                tr!(
+                    "bench.vs_baseline",
+                    format!("{:.1}", base.millis),
+                    format!("{:+.0}", (result.millis / base.millis.max(f64::EPSILON) - 1.0) * 100.0)
+                )
+            ),
+            None => println!("  {:<12} {:>9.1} ms", result.stage, result.millis),
+        }
     }
 
-    let (output_path, stats) = build_plugin_with_stats(&config).context("Failed to build plugin")?;
-
-    println!("\n\x1b[32m✓ Plugin built successfully\x1b[0m");
-    println!("  Output: {}", output_path.display());
-    println!("  Files processed: {}", stats.files_processed);
-    if config.obfuscate {
-        println!("  Patterns obfuscated: {}", stats.obfuscation_transforms);
+    if let Some(path) = save {
+        std::fs::write(&path, serde_json::to_string_pretty(&results)?)
+            .with_context(|| tr!("common.write_failed", path.display()))?;
+        println!("\n{}", tr!("bench.saved", path.display()));
     }
 
-    if install {
-        println!("\nInstalling plugin to Studio...");
-        let installed_path =
-            install_plugin(&output_path, &config.plugin_name).context("Failed to install plugin")?;
-        println!("\x1b[32m✓ Plugin installed\x1b[0m: {}", installed_path.display());
-        println!("\nRestart Roblox Studio to load the plugin.");
-    } else {
-        println!("\nTo install, run: rbxsync build-plugin --install");
-        println!("Or manually copy {} to your Studio plugins folder.", output_path.display());
+    if let Some(baseline) = baseline {
+        let regressions = bench::compare(&baseline, &results, max_regression);
+        if !regressions.is_empty() {
+            println!();
+            for r in &regressions {
+                println!(
+                    "\x1b[31m✗\x1b[0m {}",
+                    tr!(
+                        "bench.slower",
+                        &r.stage,
+                        format!("{:.0}", r.percent),
+                        format!("{:.1}", r.millis),
+                        format!("{:.1}", r.baseline_millis)
+                    )
+                );
+            }
+            anyhow::bail!("{}", tr!("bench.regressed", regressions.len(), max_regression));
+        }
+        println!("\n\x1b[32m✓\x1b[0m {}", tr!("bench.no_regression", max_regression));
     }
-
     Ok(())
 }
 
-/// Manage the Studio plugin
-async fn cmd_plugin(action: PluginAction) -> Result<()> {
-    let plugins_folder = get_studio_plugins_folder()
-        .context("Could not determine Studio plugins folder")?;
+fn cmd_export_site(out_dir: &std::path::Path, path: Option<PathBuf>) -> Result<()> {
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let files = rbxsync_server::site::export_site(&project_dir, out_dir).map_err(|e| anyhow::anyhow!(e))?;
+    println!("\x1b[32m✓\x1b[0m {}", tr!("site.wrote", files, out_dir.display()));
+    println!("{}", tr!("site.open_hint", out_dir.join("index.html").display()));
+    Ok(())
+}
 
+fn cmd_template(action: TemplateAction) -> Result<()> {
+    let templates_dir = rbxsync_core::templates_dir().with_context(|| tr!("common.no_home"))?;
     match action {
-        PluginAction::Install { path, name, download, force } => {
-            let plugin_name = name.unwrap_or_else(|| "RbxSync".to_string());
+        TemplateAction::Create { from, name, force } => {
+            let from = from.unwrap_or_else(|| std::env::current_dir().unwrap());
+            let from = from.canonicalize().with_context(|| tr!("template.project_not_found", from.display()))?;
+            if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+                anyhow::bail!("{}", tr!("template.invalid_name", name));
+            }
 
-            // Check for existing marketplace plugin
-            if !force {
-                if let Some(existing) = find_existing_rbxsync_plugin() {
-                    println!("\x1b[33m⚠ Existing RbxSync plugin detected:\x1b[0m {}", existing.display());
-                    println!();
-                    println!("Marketplace plugin detected. Please uninstall from Roblox first,");
-                    println!("or use --force to install anyway.");
-                    println!();
-                    println!("To uninstall marketplace plugin:");
-                    println!("  1. Open Roblox Studio");
-                    println!("  2. Go to Plugins > Manage Plugins");
-                    println!("  3. Uninstall RbxSync");
-                    println!();
-                    println!("Then run: rbxsync plugin install");
-                    return Ok(());
+            let dest = templates_dir.join(&name);
+            if dest.exists() {
+                if !force {
+                    anyhow::bail!("{}", tr!("template.exists", name));
                 }
+                std::fs::remove_dir_all(&dest).with_context(|| tr!("template.remove_failed"))?;
             }
 
-            // Determine the plugin path
-            let plugin_path = if let Some(p) = path {
-                // User specified a path
-                p
-            } else if download {
-                // Force download from GitHub
-                download_plugin_from_github().await?
-            } else if PathBuf::from("build/RbxSync.rbxm").exists() {
-                // Use local build
-                PathBuf::from("build/RbxSync.rbxm")
-            } else if PathBuf::from("plugin/src").exists() {
-                // Build from source
-                println!("Building plugin from source...");
-                let output_path = PathBuf::from("build/RbxSync.rbxm");
-                let config = PluginBuildConfig {
-                    source_dir: PathBuf::from("plugin/src"),
-                    output_path: output_path.clone(),
-                    plugin_name: plugin_name.clone(),
-                    obfuscate: true,
-                    obfuscate_config: None,
-                };
-                build_plugin(&config).context("Failed to build plugin")?;
-                output_path
-            } else {
-                // Download from GitHub
-                println!("Downloading plugin from GitHub releases...");
-                download_plugin_from_github().await?
-            };
-
-            println!("Installing plugin to Studio...");
-            let installed_path =
-                install_plugin(&plugin_path, &plugin_name).context("Failed to install plugin")?;
-            println!("Plugin installed to: {}", installed_path.display());
-            println!("\nRestart Roblox Studio to load the plugin.");
-        }
-        PluginAction::Uninstall { name } => {
-            let plugin_name = name.unwrap_or_else(|| "RbxSync".to_string());
-            let plugin_path = plugins_folder.join(format!("{}.rbxm", plugin_name));
+            let project_name = std::fs::read_to_string(from.join("rbxsync.json"))
+                .ok()
+                .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+                .and_then(|c| c.get("name").and_then(|n| n.as_str()).map(str::to_string))
+                .or_else(|| from.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or_default();
+            let report = rbxsync_core::create_template(&from, &dest, &name, &project_name)
+                .with_context(|| tr!("template.create_failed"))?;
 
-            if !plugin_path.exists() {
-                println!("Plugin '{}' is not installed.", plugin_name);
-                return Ok(());
+            println!("\x1b[32m✓\x1b[0m {}", tr!("template.saved", &name, report.files, dest.display()));
+            if !report.skipped.is_empty() {
+                let skipped = report.skipped.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ");
+                println!("{}", tr!("template.left_out", skipped));
             }
-
-            std::fs::remove_file(&plugin_path).context("Failed to remove plugin file")?;
-            println!("Plugin '{}' uninstalled from: {}", plugin_name, plugin_path.display());
-            println!("\nRestart Roblox Studio to apply changes.");
-        }
-        PluginAction::List => {
-            println!("Studio plugins folder: {}", plugins_folder.display());
-            println!();
-
-            if !plugins_folder.exists() {
-                println!("  (folder does not exist)");
-                return Ok(());
+            for key in &report.scrubbed {
+                println!("{}", tr!("template.scrubbed", key));
             }
-
-            let entries: Vec<_> = std::fs::read_dir(&plugins_folder)
-                .context("Failed to read plugins folder")?
-                .filter_map(|e| e.ok())
-                .filter(|e| {
-                    e.path()
-                        .extension()
-                        .map(|ext| ext == "rbxm" || ext == "rbxmx")
-                        .unwrap_or(false)
+            if !project_name.is_empty() {
+                println!("{}", tr!("template.name_replaced", project_name));
+            }
+            println!("\n{}", tr!("template.use_it", name));
+        }
+        TemplateAction::List => {
+            let mut names: Vec<_> = std::fs::read_dir(&templates_dir)
+                .map(|entries| {
+                    entries
+                        .filter_map(|e| e.ok())
+                        .filter(|e| e.path().is_dir())
+                        .map(|e| e.file_name().to_string_lossy().to_string())
+                        .collect()
                 })
-                .collect();
-
-            if entries.is_empty() {
-                println!("  No plugins installed.");
-            } else {
-                println!("Installed plugins:");
-                for entry in entries {
-                    let name = entry.file_name();
-                    let metadata = entry.metadata().ok();
-                    let size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+                .unwrap_or_default();
+            names.sort();
 
-                    println!(
-                        "  {} ({:.1} KB)",
-                        name.to_string_lossy(),
-                        size as f64 / 1024.0
-                    );
+            if names.is_empty() {
+                println!("{}", tr!("template.none", templates_dir.display()));
+                println!("{}", tr!("template.create_hint"));
+            }
+            for name in names {
+                let manifest: Option<rbxsync_core::TemplateManifest> =
+                    std::fs::read_to_string(templates_dir.join(&name).join("template.json"))
+                        .ok()
+                        .and_then(|c| serde_json::from_str(&c).ok());
+                match manifest {
+                    Some(m) => println!("  {} \x1b[2m{}\x1b[0m", name, tr!("template.entry", m.files, m.source_name)),
+                    None => println!("  {}", name),
                 }
             }
         }
     }
-
     Ok(())
 }
 
-/// Generate sourcemap for Luau LSP
-fn cmd_sourcemap(
-    path: Option<PathBuf>,
-    output: Option<PathBuf>,
-    include_non_scripts: bool,
-) -> Result<()> {
-    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
-    let output_path = output.unwrap_or_else(|| project_dir.join("sourcemap.json"));
-    let src_dir = project_dir.join("src");
+fn cmd_schema(action: SchemaAction) -> Result<()> {
+    match action {
+        SchemaAction::Emit { path, no_vscode } => {
+            let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+            let schema_path = rbxsync_core::emit_schema(&project_dir).with_context(|| tr!("schema.write_failed"))?;
+            println!(
+                "\x1b[32m✓\x1b[0m {}",
+                tr!("schema.wrote", rbxsync_core::SCHEMA_VERSION, schema_path.display())
+            );
 
-    if !src_dir.exists() {
-        anyhow::bail!("Source directory not found: {}", src_dir.display());
+            if !no_vscode {
+                add_vscode_schema_mapping(&project_dir)?;
+            }
+        }
     }
+    Ok(())
+}
 
-    println!("Generating sourcemap from {:?}...", src_dir);
+/// Map *.rbxjson to the emitted schema in .vscode/settings.json, keeping other settings
+fn add_vscode_schema_mapping(project_dir: &std::path::Path) -> Result<()> {
+    let settings_path = project_dir.join(".vscode").join("settings.json");
+    let mut settings: serde_json::Value = match std::fs::read_to_string(&settings_path) {
+        Ok(content) => match serde_json::from_str(&content) {
+            Ok(settings) => settings,
+            Err(_) => {
+                // Likely has comments; don't risk rewriting it
+                println!("\x1b[33m⚠\x1b[0m {}", tr!("schema.settings_unparsed", settings_path.display()));
+                println!("  {{ \"fileMatch\": [\"*.rbxjson\"], \"url\": \"./{}\" }}", rbxsync_core::SCHEMA_FILE);
+                return Ok(());
+            }
+        },
+        Err(_) => serde_json::json!({}),
+    };
 
-    // Build the sourcemap tree
-    let root = build_sourcemap_node("game", "DataModel", &src_dir, include_non_scripts)?;
+    let url = format!("./{}", rbxsync_core::SCHEMA_FILE);
+    let Some(obj) = settings.as_object_mut() else {
+        anyhow::bail!("{}", tr!("schema.not_object", settings_path.display()));
+    };
+    let schemas = obj
+        .entry("json.schemas")
+        .or_insert_with(|| serde_json::json!([]));
+    let Some(schemas) = schemas.as_array_mut() else {
+        anyhow::bail!("{}", tr!("schema.not_array", settings_path.display()));
+    };
+    if schemas.iter().any(|s| s.get("url").and_then(|u| u.as_str()) == Some(url.as_str())) {
+        return Ok(());
+    }
+    schemas.push(serde_json::json!({ "fileMatch": ["*.rbxjson"], "url": url }));
 
-    // Write to file
-    let json = serde_json::to_string_pretty(&root)?;
-    std::fs::write(&output_path, json).context("Failed to write sourcemap")?;
+    std::fs::create_dir_all(settings_path.parent().unwrap())?;
+    std::fs::write(&settings_path, serde_json::to_string_pretty(&settings)?)?;
+    println!("\x1b[32m✓\x1b[0m {}", tr!("schema.mapping_added", settings_path.display()));
+    Ok(())
+}
 
-    println!("Sourcemap written to: {}", output_path.display());
-    println!("\nTo use with Luau LSP, add to .luaurc:");
-    println!("{{");
-    println!("  \"languageMode\": \"strict\",");
-    println!("  \"aliases\": {{}}");
-    println!("}}");
+/// Totals for a sourcemap subtree
+#[derive(Default)]
+struct SourcemapStats {
+    instances: usize,
+    scripts: usize,
+    lines: usize,
+    bytes: u64,
+    classes: std::collections::BTreeMap<String, usize>,
+}
 
-    Ok(())
+impl SourcemapStats {
+    fn add(&mut self, other: &SourcemapStats) {
+        self.instances += other.instances;
+        self.scripts += other.scripts;
+        self.lines += other.lines;
+        self.bytes += other.bytes;
+        for (class, count) in &other.classes {
+            *self.classes.entry(class.clone()).or_default() += count;
+        }
+    }
+
+    /// Project-wide summary written at the end of the sourcemap root
+    fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "instances": self.instances,
+            "scripts": self.scripts,
+            "lines": self.lines,
+            "bytes": self.bytes,
+            "classes": self.classes
+        })
+    }
 }
 
-/// Build a sourcemap node recursively
-fn build_sourcemap_node(
-    name: &str,
-    class_name: &str,
-    dir_path: &std::path::Path,
-    include_non_scripts: bool,
-) -> Result<serde_json::Value> {
-    let mut children = Vec::new();
-    let mut file_paths = Vec::new();
+/// Add a `stats` object to every sourcemap node and return the tree's totals.
+///
+/// Counts cover the node and everything below it: `children` is the direct
+/// child count, `descendants` the full subtree, `lines` the lines of script
+/// source and `bytes` the size of the node's files (directories excluded).
+fn annotate_sourcemap_stats(node: &mut serde_json::Value) -> SourcemapStats {
+    let mut totals = SourcemapStats {
+        instances: 1,
+        ..Default::default()
+    };
+    let class_name = node.get("className").and_then(|c| c.as_str()).unwrap_or("Instance");
+    totals.classes.insert(class_name.to_string(), 1);
+
+    let files: Vec<PathBuf> = node
+        .get("filePaths")
+        .and_then(|p| p.as_array())
+        .map(|paths| paths.iter().filter_map(|p| p.as_str()).map(PathBuf::from).collect())
+        .unwrap_or_default();
+    let mut is_script = false;
+    for file in files.iter().filter(|f| f.is_file()) {
+        totals.bytes += std::fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+        if file.extension().is_some_and(|e| e == "luau" || e == "lua") {
+            is_script = true;
+            totals.lines += std::fs::read_to_string(file).map(|s| s.lines().count()).unwrap_or(0);
+        }
+    }
+    if is_script {
+        totals.scripts += 1;
+    }
 
-    // Add the directory itself as a file path
-    file_paths.push(dir_path.to_string_lossy().to_string());
+    let mut child_count = 0;
+    if let Some(children) = node.get_mut("children").and_then(|c| c.as_array_mut()) {
+        child_count = children.len();
+        for child in children {
+            totals.add(&annotate_sourcemap_stats(child));
+        }
+    }
 
-    if dir_path.exists() && dir_path.is_dir() {
-        let mut entries: Vec<_> = std::fs::read_dir(dir_path)
-            .context("Failed to read directory")?
-            .filter_map(|e| e.ok())
-            .collect();
+    if let Some(obj) = node.as_object_mut() {
+        obj.insert(
+            "stats".to_string(),
+            serde_json::json!({
+                "children": child_count,
+                "descendants": totals.instances - 1,
+                "lines": totals.lines,
+                "bytes": totals.bytes
+            }),
+        );
+    }
+    totals
+}
 
-        // Sort for consistent output
-        entries.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
-
-        for entry in entries {
-            let entry_path = entry.path();
-            let entry_name = entry.file_name().to_string_lossy().to_string();
-
-            if entry_path.is_dir() {
-                // Determine class name from directory name
-                let child_class = match entry_name.as_str() {
-                    "Workspace" => "Workspace",
-                    "ReplicatedStorage" => "ReplicatedStorage",
-                    "ReplicatedFirst" => "ReplicatedFirst",
-                    "ServerScriptService" => "ServerScriptService",
-                    "ServerStorage" => "ServerStorage",
-                    "StarterGui" => "StarterGui",
-                    "StarterPack" => "StarterPack",
-                    "StarterPlayer" => "StarterPlayer",
-                    "StarterPlayerScripts" => "StarterPlayerScripts",
-                    "StarterCharacterScripts" => "StarterCharacterScripts",
-                    "Lighting" => "Lighting",
-                    "SoundService" => "SoundService",
-                    "Chat" => "Chat",
-                    "Teams" => "Teams",
-                    _ => "Folder",
-                };
+/// Resolve the build output path and format
+fn resolve_build_target(
+    project_dir: &std::path::Path,
+    output: Option<PathBuf>,
+    format: &str,
+    plugin: Option<&str>,
+) -> Result<(PathBuf, BuildFormat)> {
+    let format = BuildFormat::parse(format)
+        .with_context(|| tr!("build.unknown_format", format))?;
 
-                // Check if directory has an init file
-                let has_init = entry_path.join("init.luau").exists()
-                    || entry_path.join("init.server.luau").exists()
-                    || entry_path.join("init.client.luau").exists();
+    // Determine output path
+    let output_path = if let Some(plugin_name) = plugin {
+        // Output to Studio plugins folder
+        let plugins_folder = get_studio_plugins_folder()
+            .with_context(|| tr!("plugin.no_plugins_folder"))?;
+        std::fs::create_dir_all(&plugins_folder).ok();
+        plugins_folder.join(plugin_name)
+    } else if let Some(out) = output {
+        out
+    } else {
+        project_dir.join(format!("build/game.{}", format.extension()))
+    };
 
-                let actual_class = if has_init {
-                    if entry_path.join("init.server.luau").exists() {
-                        "Script"
-                    } else if entry_path.join("init.client.luau").exists() {
-                        "LocalScript"
-                    } else {
-                        "ModuleScript"
-                    }
-                } else {
-                    child_class
-                };
+    Ok((output_path, format))
+}
 
-                if include_non_scripts || has_init || actual_class != "Folder" {
-                    let child_node =
-                        build_sourcemap_node(&entry_name, actual_class, &entry_path, include_non_scripts)?;
-                    children.push(child_node);
-                }
-            } else if let Some(ext) = entry_path.extension() {
-                if ext == "luau" || ext == "lua" {
-                    // Script file
-                    let (script_name, script_class) = parse_script_name(&entry_name);
+/// Fill in build options from a profile in rbxsync.json. Flags given on the
+/// command line win; the profile's output is relative to the project.
+fn apply_build_profile(
+    path: Option<&std::path::Path>,
+    name: &str,
+    output: Option<PathBuf>,
+    format: Option<String>,
+    options: &mut BuildOptions,
+) -> Result<(Option<PathBuf>, Option<String>)> {
+    let project_dir = path.map(PathBuf::from).unwrap_or_else(|| std::env::current_dir().unwrap());
+    let config = std::fs::read_to_string(project_dir.join("rbxsync.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .unwrap_or_default();
+    let profile = rbxsync_core::BuildProfile::from_config(&config, name).map_err(anyhow::Error::msg)?;
+    profile.apply_to(options);
+    println!("{}", tr!("build.using_profile", name));
+
+    let output = output.or_else(|| profile.output.as_ref().map(|o| project_dir.join(o)));
+    let format = format.or_else(|| profile.format().map(|f| f.extension().to_string()));
+    Ok((output, format))
+}
 
-                    // Skip init files (handled by directory)
-                    if script_name == "init" {
-                        continue;
-                    }
+/// Build a .rbxl or .rbxm file from project files
+/// Import a place file into src, like an extraction without Studio
+fn cmd_open(file: &std::path::Path, path: Option<PathBuf>, force: bool) -> Result<()> {
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let src_dir = project_dir.join("src");
 
-                    children.push(serde_json::json!({
-                        "name": script_name,
-                        "className": script_class,
-                        "filePaths": [entry_path.to_string_lossy()]
-                    }));
-                } else if ext == "rbxjson" && include_non_scripts {
-                    // Instance JSON file
-                    let instance_name = entry_path
-                        .file_stem()
-                        .map(|s| s.to_string_lossy().to_string())
-                        .unwrap_or_default();
-
-                    // Try to read class name from JSON
-                    let class_name = if let Ok(content) = std::fs::read_to_string(&entry_path) {
-                        serde_json::from_str::<serde_json::Value>(&content)
-                            .ok()
-                            .and_then(|v| v.get("className").and_then(|c| c.as_str()).map(String::from))
-                            .unwrap_or_else(|| "Instance".to_string())
-                    } else {
-                        "Instance".to_string()
-                    };
-
-                    children.push(serde_json::json!({
-                        "name": instance_name,
-                        "className": class_name,
-                        "filePaths": [entry_path.to_string_lossy()]
-                    }));
-                }
-            }
+    let has_files = std::fs::read_dir(&src_dir).is_ok_and(|mut entries| entries.next().is_some());
+    if has_files {
+        if !force {
+            bail!("{}", tr!("open.src_not_empty", src_dir.display()));
         }
+        std::fs::remove_dir_all(&src_dir).with_context(|| tr!("open.clear_src_failed"))?;
     }
 
-    Ok(serde_json::json!({
-        "name": name,
-        "className": class_name,
-        "filePaths": file_paths,
-        "children": children
-    }))
-}
-
-/// Parse script name and class from filename
-fn parse_script_name(filename: &str) -> (String, &'static str) {
-    let name = filename
-        .trim_end_matches(".luau")
-        .trim_end_matches(".lua");
+    let config_path = project_dir.join("rbxsync.json");
+    if !config_path.exists() {
+        std::fs::create_dir_all(&project_dir).with_context(|| tr!("open.create_dir_failed"))?;
+        let config = ProjectConfig {
+            name: file
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "MyGame".to_string()),
+            ..Default::default()
+        };
+        std::fs::write(&config_path, serde_json::to_string_pretty(&config)?).with_context(|| tr!("common.write_failed", "rbxsync.json"))?;
+    }
 
-    if name.ends_with(".server") {
-        (name.trim_end_matches(".server").to_string(), "Script")
-    } else if name.ends_with(".client") {
-        (name.trim_end_matches(".client").to_string(), "LocalScript")
-    } else {
-        (name.to_string(), "ModuleScript")
+    println!("{}", tr!("open.reading", file.display()));
+    let report = rbxsync_core::import_place(file, &project_dir)?;
+    if report.duplicates > 0 {
+        println!("{}", tr!("open.duplicates", report.duplicates));
+    }
+    if !report.unsupported.is_empty() {
+        println!(
+            "{}\n{}",
+            tr!("open.unsupported", report.unsupported.len()),
+            rbxsync_core::summarize_unsupported(&report.unsupported, 20)
+        );
     }
+    println!("{}", tr!("open.done", report.instances, report.scripts, report.files, src_dir.display()));
+    Ok(())
 }
 
-/// Build a .rbxl or .rbxm file from project files
 async fn cmd_build(
     path: Option<PathBuf>,
     output: Option<PathBuf>,
     format: String,
     watch: bool,
     plugin: Option<String>,
+    options: BuildOptions,
 ) -> Result<()> {
     let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
     let src_dir = project_dir.join("src");
 
     if !src_dir.exists() {
-        bail!("Source directory not found: {}", src_dir.display());
+        bail!("{}", tr!("common.src_not_found", src_dir.display()));
     }
 
-    // Parse format and determine if XML
-    let format = format.to_lowercase();
-    let (extension, is_xml) = match format.as_str() {
-        "rbxl" | "place" => ("rbxl", false),
-        "rbxm" | "model" => ("rbxm", false),
-        "rbxlx" | "place-xml" => ("rbxlx", true),
-        "rbxmx" | "model-xml" => ("rbxmx", true),
-        _ => bail!("Unknown format: {}. Use rbxl, rbxm, rbxlx, or rbxmx", format),
-    };
-
-    // Determine output path
-    let output_path = if let Some(plugin_name) = &plugin {
-        // Output to Studio plugins folder
-        let plugins_folder = get_studio_plugins_folder()
-            .context("Could not determine Studio plugins folder")?;
-        std::fs::create_dir_all(&plugins_folder).ok();
-        plugins_folder.join(plugin_name)
-    } else if let Some(out) = output {
-        out
-    } else {
-        std::fs::create_dir_all(project_dir.join("build")).ok();
-        project_dir.join(format!("build/game.{}", extension))
-    };
+    let (output_path, format) = resolve_build_target(&project_dir, output, &format, plugin.as_deref())?;
+    let options = BuildOptions { format, ..options };
 
     // Initial build
-    do_build(&src_dir, &output_path, extension, is_xml)?;
+    do_build(&project_dir, &output_path, &options)?;
 
     // If not watch mode, we're done
     if !watch {
         return Ok(());
     }
 
-    // Watch mode
-    println!("\nWatching for changes... (Ctrl+C to stop)");
-
-    let (tx, rx) = channel();
-
-    let mut watcher = RecommendedWatcher::new(
-        move |res| {
-            if let Ok(event) = res {
-                let _ = tx.send(event);
-            }
-        },
-        Config::default().with_poll_interval(Duration::from_millis(500)),
-    )
-    .context("Failed to create file watcher")?;
-
-    watcher
-        .watch(&src_dir, RecursiveMode::Recursive)
-        .context("Failed to watch source directory")?;
-
-    // Debounce tracking
-    let mut last_build = std::time::Instant::now();
-    let debounce = Duration::from_millis(500);
+    // Watch mode: changes come from the sync server's file watcher
+    let mut events = FileEventStream::subscribe(&project_dir, false).await?;
+    println!("\n{}", tr!("build.watching"));
 
-    loop {
-        match rx.recv_timeout(Duration::from_secs(1)) {
-            Ok(_event) => {
-                // Debounce: only rebuild if enough time has passed
-                if last_build.elapsed() >= debounce {
-                    println!("\nChange detected, rebuilding...");
-                    match do_build(&src_dir, &output_path, extension, is_xml) {
-                        Ok(()) => last_build = std::time::Instant::now(),
-                        Err(e) => println!("Build error: {}", e),
-                    }
-                }
-            }
-            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
-                // Continue watching
-            }
-            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
-                println!("Watcher disconnected");
-                break;
-            }
+    while let Some(batch) = events.next().await? {
+        println!("\n{}", tr!("build.rebuilding", describe_changes(&batch)));
+        if let Err(e) = do_build(&project_dir, &output_path, &options) {
+            println!("{}", tr!("build.error", e));
         }
     }
 
+    println!("{}", tr!("common.stream_closed"));
     Ok(())
 }
 
-/// Perform the actual build operation
-fn do_build(src_dir: &PathBuf, output_path: &PathBuf, extension: &str, is_xml: bool) -> Result<()> {
-    let is_place = extension == "rbxl" || extension == "rbxlx";
-
-    println!("Building {} from {:?}...", extension, src_dir);
-
-    // Build the DOM
-    let dom = build_dom_from_src(src_dir, is_place)?;
+/// Build subtrees of src as models (`build --model` / `--all-models`).
+/// With no paths, every model in rbxsync.json is built.
+async fn cmd_build_models(
+    path: Option<PathBuf>,
+    output: Option<PathBuf>,
+    format: String,
+    watch: bool,
+    options: BuildOptions,
+    paths: Vec<String>,
+) -> Result<()> {
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let src_dir = project_dir.join("src");
 
-    // Ensure output directory exists
-    if let Some(parent) = output_path.parent() {
-        std::fs::create_dir_all(parent).context("Failed to create output directory")?;
+    if !src_dir.exists() {
+        bail!("{}", tr!("common.src_not_found", src_dir.display()));
     }
 
-    // Write to file
-    let output_file = BufWriter::new(
-        File::create(output_path).context("Failed to create output file")?,
-    );
-
-    // Export the children (services/instances) directly, not the root wrapper
-    // For places: services should have null parent referent (top-level in file)
-    // For models: instances should be top-level items
-    // The rbx_binary crate handles service detection based on class names
-    let refs_to_export: Vec<_> = dom.root().children().to_vec();
-
-    if is_xml {
-        rbx_xml::to_writer_default(output_file, &dom, &refs_to_export)
-            .context("Failed to write XML output file")?;
+    let config = std::fs::read_to_string(project_dir.join("rbxsync.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .unwrap_or_default();
+    let configured = rbxsync_core::model_targets(&config);
+    let format = BuildFormat::parse(&format)
+        .with_context(|| tr!("build.unknown_format", format))?;
+
+    // Models named on the command line use their configured output, else
+    // -o (for a single model) or build/<Name>.rbxm
+    let targets: Vec<rbxsync_core::ModelTarget> = if paths.is_empty() {
+        if configured.is_empty() {
+            bail!("{}", tr!("build.no_models"));
+        }
+        configured
     } else {
-        rbx_binary::to_writer(output_file, &dom, &refs_to_export)
-            .context("Failed to write binary output file")?;
+        if output.is_some() && paths.len() > 1 {
+            bail!("{}", tr!("build.output_single_model"));
+        }
+        paths
+            .iter()
+            .map(|path| {
+                let path = rbxsync_core::normalize_instance_root(path);
+                configured.iter().find(|t| t.path == path).cloned().unwrap_or_else(|| {
+                    let name = path.rsplit('/').next().unwrap_or(&path);
+                    let extension = if format.is_xml() { "rbxmx" } else { "rbxm" };
+                    rbxsync_core::ModelTarget {
+                        output: output.clone().unwrap_or_else(|| PathBuf::from(format!("build/{}.{}", name, extension))),
+                        path,
+                    }
+                })
+            })
+            .collect()
+    };
+
+    for target in &targets {
+        do_build_model(&project_dir, target, &options)?;
     }
 
-    println!("Built successfully: {}", output_path.display());
+    if !watch {
+        return Ok(());
+    }
 
-    // Show file size
-    if let Ok(metadata) = std::fs::metadata(output_path) {
-        println!("Size: {:.1} KB", metadata.len() as f64 / 1024.0);
+    // Watch mode: only models whose files changed are rebuilt
+    let mut events = FileEventStream::subscribe(&project_dir, false).await?;
+    println!("\n{}", tr!("build.watching_models", targets.len()));
+
+    while let Some(batch) = events.next().await? {
+        let changed: Vec<&str> = batch
+            .get("changes")
+            .and_then(|c| c.as_array())
+            .into_iter()
+            .flatten()
+            .flat_map(|c| [c.get("path"), c.get("oldPath")])
+            .filter_map(|p| p.and_then(|p| p.as_str()))
+            .collect();
+        for target in targets.iter().filter(|t| changed.iter().any(|p| model_touched(&src_dir, &t.path, p))) {
+            println!("\n{}", tr!("build.rebuilding_model", describe_changes(&batch), &target.path));
+            if let Err(e) = do_build_model(&project_dir, target, &options) {
+                println!("{}", tr!("build.error", e));
+            }
+        }
     }
 
+    println!("{}", tr!("common.stream_closed"));
     Ok(())
 }
 
-/// Build a DOM from the src directory
-fn build_dom_from_src(src_dir: &std::path::Path, is_place: bool) -> Result<WeakDom> {
-    let root_class = if is_place { "DataModel" } else { "Folder" };
-    let root_name = if is_place { "game" } else { "Model" };
+/// Whether a changed file belongs to the model at `model_path`: it's inside
+/// the model's directory, or the model's own `Name.*` script next to it
+fn model_touched(src_dir: &std::path::Path, model_path: &str, changed: &str) -> bool {
+    let model_dir = rbxsync_core::path_to_string(&src_dir.join(model_path));
+    let changed = rbxsync_core::normalize_path(changed);
+    if changed == model_dir || changed.starts_with(&format!("{}/", model_dir)) {
+        return true;
+    }
+    let (parent, name) = model_dir.rsplit_once('/').unwrap_or(("", &model_dir));
+    changed
+        .rsplit_once('/')
+        .is_some_and(|(dir, file)| dir == parent && file.starts_with(&format!("{}.", name)))
+}
 
-    let mut dom = WeakDom::new(InstanceBuilder::new(root_class).with_name(root_name));
-    let root_ref = dom.root_ref();
+/// Build one model and report it
+fn do_build_model(
+    project_dir: &std::path::Path,
+    target: &rbxsync_core::ModelTarget,
+    options: &BuildOptions,
+) -> Result<()> {
+    let output = project_dir.join(&target.output);
+    let options = BuildOptions { format: target.format(), ..options.clone() };
+    let source = project_dir.join("src").join(&target.path);
+    println!("{}", tr!("build.building_model", &target.path, format!("{:?}", source)));
 
-    // Process each service directory
-    let mut entries: Vec<_> = std::fs::read_dir(src_dir)
-        .context("Failed to read src directory")?
-        .filter_map(|e| e.ok())
-        .collect();
+    let report = rbxsync_core::build_model(project_dir, &target.path, &output, &options)?;
+    print_build_warnings(&report);
 
-    entries.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    println!("{}", tr!("build.built_model", output.display(), report.instances));
+    println!("{}", tr!("build.size", format!("{:.1}", report.bytes as f64 / 1024.0)));
+    Ok(())
+}
 
-    for entry in entries {
-        let entry_path = entry.path();
-        let entry_name = entry.file_name().to_string_lossy().to_string();
-
-        if entry_path.is_dir() {
-            // Directory becomes a service or folder
-            let class_name = service_class_name(&entry_name);
-            let service_ref = dom.insert(
-                root_ref,
-                InstanceBuilder::new(class_name).with_name(&entry_name),
-            );
+/// Rebuild and live-sync on every change
+async fn cmd_dev(path: Option<PathBuf>, output: Option<PathBuf>, format: String) -> Result<()> {
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let src_dir = project_dir.join("src");
 
-            // Recursively add children
-            build_dom_children(&mut dom, service_ref, &entry_path)?;
-        } else if entry_path.extension().map(|e| e == "rbxjson").unwrap_or(false) {
-            // .rbxjson file becomes an instance
-            let instance_name = entry_path
-                .file_stem()
-                .map(|s| s.to_string_lossy().to_string())
-                .unwrap_or_default();
+    if !src_dir.exists() {
+        bail!("{}", tr!("common.src_not_found", src_dir.display()));
+    }
 
-            if let Ok(content) = std::fs::read_to_string(&entry_path) {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                    let class_name = json
-                        .get("className")
-                        .and_then(|c| c.as_str())
-                        .unwrap_or("Folder");
+    let (output_path, format) = resolve_build_target(&project_dir, output, &format, None)?;
+    let options = BuildOptions { format, ..Default::default() };
 
-                    let mut builder = InstanceBuilder::new(class_name).with_name(&instance_name);
+    do_build(&project_dir, &output_path, &options)?;
 
-                    // Add properties from JSON
-                    if let Some(props) = json.get("properties").and_then(|p| p.as_object()) {
-                        for (prop_name, prop_value) in props {
-                            if let Some(value) = json_to_variant(prop_value) {
-                                builder = builder.with_property(prop_name, value);
-                            }
-                        }
-                    }
+    // The server debounces changes once and uses the same batch for live sync
+    // and for this stream, so each rebuild lines up with one sync push.
+    let mut events = FileEventStream::subscribe(&project_dir, true).await?;
+    println!("\n\x1b[32m{}\x1b[0m {}", tr!("build.dev_running"), tr!("build.ctrl_c"));
+    println!("{}", tr!("build.dev_output", output_path.display()));
+    println!("{}", tr!("build.dev_live_sync"));
 
-                    dom.insert(root_ref, builder);
-                }
-            }
-        } else if entry_path.extension().map(|e| e == "luau" || e == "lua").unwrap_or(false) {
-            // Script file
-            let (script_name, class_name) = parse_script_name(&entry_name);
-            if let Ok(source) = std::fs::read_to_string(&entry_path) {
-                dom.insert(
-                    root_ref,
-                    InstanceBuilder::new(class_name)
-                        .with_name(&script_name)
-                        .with_property("Source", Variant::String(source)),
-                );
-            }
+    while let Some(batch) = events.next().await? {
+        println!("\n\x1b[36m↻ {}\x1b[0m", describe_changes(&batch));
+        match do_build(&project_dir, &output_path, &options) {
+            Ok(()) => println!("\x1b[32m{}\x1b[0m", tr!("build.dev_rebuilt")),
+            Err(e) => println!("\x1b[31m{}\x1b[0m {}", tr!("build.dev_error", e), tr!("build.dev_still_synced")),
         }
     }
 
-    Ok(dom)
+    println!("{}", tr!("common.stream_closed"));
+    Ok(())
 }
 
-/// Recursively build DOM children from a directory
-fn build_dom_children(
-    dom: &mut WeakDom,
-    parent_ref: rbx_dom_weak::types::Ref,
-    dir_path: &std::path::Path,
-) -> Result<()> {
-    let mut entries: Vec<_> = std::fs::read_dir(dir_path)
-        .context("Failed to read directory")?
-        .filter_map(|e| e.ok())
+/// Summarize a file event batch for console output
+fn describe_changes(batch: &serde_json::Value) -> String {
+    let changes = batch
+        .get("changes")
+        .and_then(|c| c.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let names: Vec<&str> = changes
+        .iter()
+        .filter_map(|c| c.get("path").and_then(|p| p.as_str()))
+        .map(|p| p.rsplit('/').next().unwrap_or(p))
         .collect();
 
-    entries.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
-
-    // Check for init file first
-    let init_files = ["init.luau", "init.server.luau", "init.client.luau"];
-    for init_name in init_files {
-        let init_path = dir_path.join(init_name);
-        if init_path.exists() {
-            if let Ok(source) = std::fs::read_to_string(&init_path) {
-                // Set Source property on parent
-                if let Some(instance) = dom.get_by_ref_mut(parent_ref) {
-                    instance
-                        .properties
-                        .insert("Source".to_string(), Variant::String(source));
-                }
+    match names.len() {
+        0 => tr!("build.change_detected"),
+        1 => tr!("build.changed", names[0]),
+        n if n <= 3 => tr!("build.changed", names.join(", ")),
+        n => tr!("build.changed_more", names[..3].join(", "), n - 3),
+    }
+}
+
+/// Start the sync server in-process unless one is already running
+async fn ensure_local_server(client: &reqwest::Client) {
+    if client.get(format!("{}/health", server_url(None))).send().await.is_err() {
+        println!("{}", tr!("server.starting"));
+        tokio::spawn(async {
+            if let Err(e) = run_server(ServerConfig::default()).await {
+                tracing::error!("Server error: {}", e);
             }
-            break;
-        }
+        });
+        // Give server time to start
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
     }
+}
 
-    for entry in entries {
-        let entry_path = entry.path();
-        let entry_name = entry.file_name().to_string_lossy().to_string();
+/// Debounced file change batches from the server's `/events` stream
+struct FileEventStream {
+    response: reqwest::Response,
+    buffer: String,
+}
 
-        // Skip init files and _meta.rbxjson
-        if init_files.iter().any(|&n| entry_name == n) || entry_name == "_meta.rbxjson" {
-            continue;
-        }
+impl FileEventStream {
+    /// Subscribe to changes for a project, starting the server in-process if needed
+    async fn subscribe(project_dir: &std::path::Path, live_sync: bool) -> Result<Self> {
+        let client = server_client().build()?;
+        let base = server_url(None);
+        ensure_local_server(&client).await;
 
-        if entry_path.is_dir() {
-            // Check if directory has _meta.rbxjson (contains class and properties)
-            let meta_path = entry_path.join("_meta.rbxjson");
-            let meta_data: Option<serde_json::Value> = if meta_path.exists() {
-                std::fs::read_to_string(&meta_path)
-                    .ok()
-                    .and_then(|s| serde_json::from_str(&s).ok())
-            } else {
-                None
-            };
+        let project_dir = if project_dir.is_absolute() {
+            project_dir.to_path_buf()
+        } else {
+            std::env::current_dir()?.join(project_dir)
+        };
+        let response = client
+            .get(format!("{}/events", base))
+            .query(&[
+                ("project_dir", project_dir.to_string_lossy().to_string()),
+                ("live_sync", live_sync.to_string()),
+            ])
+            .send()
+            .await
+            .with_context(|| tr!("events.subscribe_failed"))?
+            .error_for_status()
+            .with_context(|| tr!("events.subscribe_rejected"))?;
 
-            // Check if directory has init file (makes it a script)
-            let has_init = init_files.iter().any(|&n| entry_path.join(n).exists());
+        Ok(Self {
+            response,
+            buffer: String::new(),
+        })
+    }
 
-            let class_name = if has_init {
-                if entry_path.join("init.server.luau").exists() {
-                    "Script"
-                } else if entry_path.join("init.client.luau").exists() {
-                    "LocalScript"
-                } else {
-                    "ModuleScript"
+    /// Wait for the next change batch. Returns `None` when the stream ends.
+    async fn next(&mut self) -> Result<Option<serde_json::Value>> {
+        loop {
+            // SSE events are separated by a blank line
+            while let Some(end) = self.buffer.find("\n\n") {
+                let event: String = self.buffer.drain(..end + 2).collect();
+                let data: String = event
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("data:"))
+                    .map(|d| d.trim_start())
+                    .collect();
+                if data.is_empty() {
+                    continue; // keepalive comment
                 }
-            } else if let Some(ref meta) = meta_data {
-                // Use class from _meta.rbxjson if available
-                meta.get("className")
-                    .and_then(|c| c.as_str())
-                    .unwrap_or("Folder")
-            } else {
-                "Folder"
-            };
-
-            let mut builder = InstanceBuilder::new(class_name).with_name(&entry_name);
-
-            // Apply properties from _meta.rbxjson if available
-            if let Some(ref meta) = meta_data {
-                if let Some(props) = meta.get("properties").and_then(|p| p.as_object()) {
-                    for (prop_name, prop_value) in props {
-                        if let Some(value) = json_to_variant(prop_value) {
-                            builder = builder.with_property(prop_name, value);
-                        }
-                    }
+                if let Ok(batch) = serde_json::from_str(&data) {
+                    return Ok(Some(batch));
                 }
             }
 
-            let child_ref = dom.insert(parent_ref, builder);
+            match self.response.chunk().await.with_context(|| tr!("events.stream_failed"))? {
+                Some(chunk) => self.buffer.push_str(&String::from_utf8_lossy(&chunk).replace("\r\n", "\n")),
+                None => return Ok(None),
+            }
+        }
+    }
+}
 
-            build_dom_children(dom, child_ref, &entry_path)?;
-        } else if entry_path.extension().map(|e| e == "rbxjson").unwrap_or(false) {
-            // .rbxjson file
-            let instance_name = entry_path
-                .file_stem()
-                .map(|s| s.to_string_lossy().to_string())
-                .unwrap_or_default();
+/// Perform the actual build operation
+fn do_build(project_dir: &std::path::Path, output_path: &std::path::Path, options: &BuildOptions) -> Result<()> {
+    println!("{}", tr!("build.building", options.format.extension(), format!("{:?}", project_dir.join("src"))));
 
-            if let Ok(content) = std::fs::read_to_string(&entry_path) {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                    let class_name = json
-                        .get("className")
-                        .and_then(|c| c.as_str())
-                        .unwrap_or("Folder");
+    let report = rbxsync_core::build_project(project_dir, output_path, options)?;
+    print_build_warnings(&report);
 
-                    let mut builder = InstanceBuilder::new(class_name).with_name(&instance_name);
+    println!("{}", tr!("build.built", output_path.display()));
+    println!("{}", tr!("build.size", format!("{:.1}", report.bytes as f64 / 1024.0)));
 
-                    if let Some(props) = json.get("properties").and_then(|p| p.as_object()) {
-                        for (prop_name, prop_value) in props {
-                            if let Some(value) = json_to_variant(prop_value) {
-                                builder = builder.with_property(prop_name, value);
-                            }
-                        }
-                    }
+    Ok(())
+}
 
-                    dom.insert(parent_ref, builder);
-                }
+/// Warnings from a build: dropped values, unknown properties, unresolved
+/// assets and applied overrides, then what the build transformed
+fn print_build_warnings(report: &rbxsync_core::BuildReport) {
+    if !report.unsupported.is_empty() {
+        println!("\x1b[33m!\x1b[0m {}", tr!("build.dropped", report.dropped()));
+    }
+    if !report.unknown.is_empty() {
+        println!(
+            "\x1b[33m!\x1b[0m {}",
+            tr!(
+                "build.unknown_properties",
+                report.unknown.len(),
+                rbxsync_core::summarize_unsupported(&report.unknown, 20)
+            )
+        );
+    }
+    if !report.unresolved_assets.is_empty() {
+        println!(
+            "\x1b[33m!\x1b[0m {}",
+            tr!("build.unresolved_assets", report.unresolved_assets.len(), report.unresolved_assets.join("\n  "))
+        );
+    }
+    if let Some(overrides) = &report.overrides {
+        print_override_report(overrides);
+    }
+    if report.excluded > 0 {
+        println!("{}", tr!("build.excluded", report.excluded));
+    }
+    if report.defines > 0 {
+        println!("{}", tr!("build.defines", report.defines));
+    }
+    if let Some(stats) = &report.obfuscation {
+        println!(
+            "{}",
+            tr!(
+                "build.obfuscated",
+                stats.scripts,
+                stats.strings_encoded,
+                stats.debug_stripped,
+                stats.comments_removed
+            )
+        );
+    }
+}
+
+/// Round-trip src through a build and report what was lost
+fn cmd_verify(path: Option<PathBuf>, format: &str, json: bool) -> Result<()> {
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let Some(format) = BuildFormat::parse(format) else {
+        bail!("{}", tr!("build.unknown_format", format));
+    };
+
+    let report = rbxsync_core::verify_project(&project_dir, format)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}", tr!("verify.checked", report.instances, format.extension()));
+        if !report.unbuildable.is_empty() {
+            println!("{}", tr!("verify.unbuildable", report.unbuildable.iter().map(|u| u.count).sum::<usize>()));
+            println!("{}", rbxsync_core::summarize_unsupported(&report.unbuildable, 20));
+        }
+        if !report.missing.is_empty() {
+            println!("{}", tr!("verify.missing", report.missing.len()));
+            for path in &report.missing {
+                println!("  \x1b[31m-\x1b[0m {}", path);
             }
-        } else if entry_path.extension().map(|e| e == "luau" || e == "lua").unwrap_or(false) {
-            // Script file
-            let (script_name, class_name) = parse_script_name(&entry_name);
-            if let Ok(source) = std::fs::read_to_string(&entry_path) {
-                dom.insert(
-                    parent_ref,
-                    InstanceBuilder::new(class_name)
-                        .with_name(&script_name)
-                        .with_property("Source", Variant::String(source)),
-                );
+        }
+        if !report.lossy.is_empty() {
+            println!("{}", tr!("verify.lossy", report.lossy.len()));
+            for lost in &report.lossy {
+                let read_back = lost.read_back.as_deref().unwrap_or("(missing)");
+                println!("  \x1b[33m~\x1b[0m {}.{}: {} → {}", lost.path, lost.property, lost.built, read_back);
             }
         }
+        if report.is_lossless() {
+            println!("{}", tr!("verify.lossless"));
+        } else {
+            println!("{}", tr!("verify.failed", report.problems()));
+        }
     }
 
+    if !report.is_lossless() {
+        std::process::exit(1);
+    }
     Ok(())
 }
 
-/// Get the appropriate class name for a service directory
-fn service_class_name(name: &str) -> &'static str {
-    match name {
-        "Workspace" => "Workspace",
-        "ReplicatedStorage" => "ReplicatedStorage",
-        "ReplicatedFirst" => "ReplicatedFirst",
-        "ServerScriptService" => "ServerScriptService",
-        "ServerStorage" => "ServerStorage",
-        "StarterGui" => "StarterGui",
-        "StarterPack" => "StarterPack",
-        "StarterPlayer" => "StarterPlayer",
-        "Lighting" => "Lighting",
-        "SoundService" => "SoundService",
-        "Chat" => "Chat",
-        "Teams" => "Teams",
-        "TestService" => "TestService",
-        "Players" => "Players",
-        _ => "Folder",
+/// Print the overrides a sync or build applied
+fn print_override_report(report: &rbxsync_core::OverrideReport) {
+    if report.applied.is_empty() && report.missing.is_empty() {
+        return;
     }
-}
-
-/// Convert JSON property value to rbx_dom Variant
-fn json_to_variant(value: &serde_json::Value) -> Option<Variant> {
-    use rbx_dom_weak::types::*;
-
-    // Check if it has a type field (our format)
-    if let Some(obj) = value.as_object() {
-        if let Some(type_str) = obj.get("type").and_then(|t| t.as_str()) {
-            let val = obj.get("value");
-            return match type_str {
-                // Basic types
-                "string" => val?.as_str().map(|s| Variant::String(s.to_string())),
-                "int" | "int32" => val?.as_i64().map(|n| Variant::Int32(n as i32)),
-                "int64" => val?.as_i64().map(Variant::Int64),
-                "float" | "float32" => val?.as_f64().map(|n| Variant::Float32(n as f32)),
-                "float64" | "double" => val?.as_f64().map(Variant::Float64),
-                "bool" => val?.as_bool().map(Variant::Bool),
-
-                // nil means "use default" - skip the property entirely
-                "nil" => None,
-
-                // Vector types
-                "Vector2" => {
-                    let v = val?.as_object()?;
-                    Some(Variant::Vector2(Vector2::new(
-                        v.get("x")?.as_f64()? as f32,
-                        v.get("y")?.as_f64()? as f32,
-                    )))
-                }
-                "Vector3" => {
-                    let v = val?.as_object()?;
-                    Some(Variant::Vector3(Vector3::new(
-                        v.get("x")?.as_f64()? as f32,
-                        v.get("y")?.as_f64()? as f32,
-                        v.get("z")?.as_f64()? as f32,
-                    )))
-                }
-
-                // Color types
-                "Color3" => {
-                    let v = val?.as_object()?;
-                    Some(Variant::Color3(Color3::new(
-                        v.get("r")?.as_f64()? as f32,
-                        v.get("g")?.as_f64()? as f32,
-                        v.get("b")?.as_f64()? as f32,
-                    )))
-                }
-                "Color3uint8" => {
-                    let v = val?.as_object()?;
-                    Some(Variant::Color3uint8(Color3uint8::new(
-                        v.get("r")?.as_u64()? as u8,
-                        v.get("g")?.as_u64()? as u8,
-                        v.get("b")?.as_u64()? as u8,
-                    )))
-                }
-                "BrickColor" => {
-                    val?.as_u64().map(|n| Variant::BrickColor(BrickColor::from_number(n as u16).unwrap_or(BrickColor::MediumStoneGrey)))
-                }
-
-                // UDim types
-                "UDim" => {
-                    let v = val?.as_object()?;
-                    Some(Variant::UDim(UDim::new(
-                        v.get("scale")?.as_f64()? as f32,
-                        v.get("offset")?.as_i64()? as i32,
-                    )))
-                }
-                "UDim2" => {
-                    let v = val?.as_object()?;
-                    let x = v.get("x")?.as_object()?;
-                    let y = v.get("y")?.as_object()?;
-                    Some(Variant::UDim2(UDim2::new(
-                        UDim::new(
-                            x.get("scale")?.as_f64()? as f32,
-                            x.get("offset")?.as_i64()? as i32,
-                        ),
-                        UDim::new(
-                            y.get("scale")?.as_f64()? as f32,
-                            y.get("offset")?.as_i64()? as i32,
-                        ),
-                    )))
-                }
-
-                // CFrame
-                "CFrame" => {
-                    let v = val?.as_object()?;
-                    let pos = v.get("position")?.as_array()?;
-                    let rot = v.get("rotation")?.as_array()?;
-                    if pos.len() >= 3 && rot.len() >= 9 {
-                        Some(Variant::CFrame(CFrame::new(
-                            Vector3::new(
-                                pos[0].as_f64()? as f32,
-                                pos[1].as_f64()? as f32,
-                                pos[2].as_f64()? as f32,
-                            ),
-                            Matrix3::new(
-                                Vector3::new(rot[0].as_f64()? as f32, rot[1].as_f64()? as f32, rot[2].as_f64()? as f32),
-                                Vector3::new(rot[3].as_f64()? as f32, rot[4].as_f64()? as f32, rot[5].as_f64()? as f32),
-                                Vector3::new(rot[6].as_f64()? as f32, rot[7].as_f64()? as f32, rot[8].as_f64()? as f32),
-                            ),
-                        )))
-                    } else {
-                        None
-                    }
-                }
-
-                // Enum (store as u32)
-                "Enum" => {
-                    let v = val?.as_object()?;
-                    let enum_value = v.get("value")?;
-                    // Try to get numeric value, or parse from string
-                    if let Some(n) = enum_value.as_u64() {
-                        Some(Variant::Enum(rbx_dom_weak::types::Enum::from_u32(n as u32)))
-                    } else {
-                        // For string enum values, we'd need the reflection database
-                        // For now, default to 0
-                        Some(Variant::Enum(rbx_dom_weak::types::Enum::from_u32(0)))
-                    }
-                }
-
-                // Rect
-                "Rect" => {
-                    let v = val?.as_object()?;
-                    let min = v.get("min")?.as_object()?;
-                    let max = v.get("max")?.as_object()?;
-                    Some(Variant::Rect(Rect::new(
-                        Vector2::new(min.get("x")?.as_f64()? as f32, min.get("y")?.as_f64()? as f32),
-                        Vector2::new(max.get("x")?.as_f64()? as f32, max.get("y")?.as_f64()? as f32),
-                    )))
-                }
-
-                // NumberRange
-                "NumberRange" => {
-                    let v = val?.as_object()?;
-                    Some(Variant::NumberRange(NumberRange::new(
-                        v.get("min")?.as_f64()? as f32,
-                        v.get("max")?.as_f64()? as f32,
-                    )))
-                }
-
-                // Font
-                "Font" => {
-                    let v = val?.as_object()?;
-                    let family = v.get("family")?.as_str()?.to_string();
-                    let weight = v.get("weight").and_then(|w| w.as_u64()).unwrap_or(400) as u16;
-                    let style = v.get("style").and_then(|s| s.as_str()).unwrap_or("Normal");
-                    Some(Variant::Font(Font {
-                        family,
-                        weight: FontWeight::from_u16(weight).unwrap_or(FontWeight::Regular),
-                        style: if style == "Italic" { FontStyle::Italic } else { FontStyle::Normal },
-                        cached_face_id: None,
-                    }))
-                }
-
-                // Content (asset URLs)
-                "Content" => {
-                    val?.as_str().map(|s| Variant::Content(Content::from(s.to_string())))
-                }
-
-                // Refs - we skip these as they need special handling
-                "Ref" => None,
-
-                // Skip unknown/unsupported types
-                _ => {
-                    tracing::debug!("Unsupported property type: {}", type_str);
-                    None
-                }
-            };
-        }
+    println!("{}", tr!("build.overrides", report.variant));
+    for applied in &report.applied {
+        println!("  \x1b[36m~\x1b[0m {}.{} = {}", applied.path, applied.property, applied.value);
     }
-
-    // Direct value
-    match value {
-        serde_json::Value::String(s) => Some(Variant::String(s.clone())),
-        serde_json::Value::Bool(b) => Some(Variant::Bool(*b)),
-        serde_json::Value::Number(n) => {
-            if let Some(i) = n.as_i64() {
-                Some(Variant::Int32(i as i32))
-            } else {
-                n.as_f64().map(|f| Variant::Float64(f))
-            }
-        }
-        _ => None,
+    for path in &report.missing {
+        println!("  \x1b[33m!\x1b[0m {}", tr!("build.override_missing", path));
     }
 }
 
@@ -2454,7 +6527,7 @@ fn cmd_fmt_project(path: Option<PathBuf>, check: bool) -> Result<()> {
     let src_dir = project_dir.join("src");
 
     if !src_dir.exists() {
-        bail!("Source directory not found: {}", src_dir.display());
+        bail!("{}", tr!("common.src_not_found", src_dir.display()));
     }
 
     let mut unformatted = Vec::new();
@@ -2471,7 +6544,7 @@ fn cmd_fmt_project(path: Option<PathBuf>, check: bool) -> Result<()> {
             return Ok(());
         }
 
-        let entries = std::fs::read_dir(dir).context("Failed to read directory")?;
+        let entries = std::fs::read_dir(dir).with_context(|| tr!("fmt.read_dir_failed"))?;
 
         for entry in entries.flatten() {
             let path = entry.path();
@@ -2480,11 +6553,11 @@ fn cmd_fmt_project(path: Option<PathBuf>, check: bool) -> Result<()> {
                 visit_dir(&path, check, unformatted, formatted_count)?;
             } else if path.extension().map_or(false, |ext| ext == "rbxjson") {
                 let content = std::fs::read_to_string(&path)
-                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                    .with_context(|| tr!("common.read_failed", path.display()))?;
 
                 // Parse and re-serialize with consistent formatting
                 let value: serde_json::Value = serde_json::from_str(&content)
-                    .with_context(|| format!("Failed to parse {}", path.display()))?;
+                    .with_context(|| tr!("common.parse_failed", path.display()))?;
 
                 let formatted = serde_json::to_string_pretty(&value)? + "\n";
 
@@ -2493,8 +6566,8 @@ fn cmd_fmt_project(path: Option<PathBuf>, check: bool) -> Result<()> {
                         unformatted.push(path);
                     } else {
                         std::fs::write(&path, &formatted)
-                            .with_context(|| format!("Failed to write {}", path.display()))?;
-                        println!("Formatted: {}", path.display());
+                            .with_context(|| tr!("common.write_failed", path.display()))?;
+                        println!("{}", tr!("fmt.formatted_file", path.display()));
                         *formatted_count += 1;
                     }
                 }
@@ -2517,7 +6590,7 @@ fn cmd_fmt_project(path: Option<PathBuf>, check: bool) -> Result<()> {
                         unformatted.push(config_path);
                     } else {
                         std::fs::write(&config_path, &formatted)?;
-                        println!("Formatted: {}", config_path.display());
+                        println!("{}", tr!("fmt.formatted_file", config_path.display()));
                         formatted_count += 1;
                     }
                 }
@@ -2527,18 +6600,18 @@ fn cmd_fmt_project(path: Option<PathBuf>, check: bool) -> Result<()> {
 
     if check {
         if unformatted.is_empty() {
-            println!("All files are properly formatted.");
+            println!("{}", tr!("fmt.all_formatted"));
         } else {
-            println!("The following files need formatting:");
+            println!("{}", tr!("fmt.need_formatting"));
             for path in &unformatted {
                 println!("  {}", path.display());
             }
             std::process::exit(1);
         }
     } else if formatted_count == 0 {
-        println!("All files are already properly formatted.");
+        println!("{}", tr!("fmt.already_formatted"));
     } else {
-        println!("\nFormatted {} file(s).", formatted_count);
+        println!("\n{}", tr!("fmt.formatted_count", formatted_count));
     }
 
     Ok(())
@@ -2553,7 +6626,7 @@ fn cmd_doc() -> Result<()> {
         std::process::Command::new("open")
             .arg(doc_url)
             .spawn()
-            .context("Failed to open browser")?;
+            .with_context(|| tr!("doc.browser_failed"))?;
     }
 
     #[cfg(target_os = "windows")]
@@ -2561,7 +6634,7 @@ fn cmd_doc() -> Result<()> {
         std::process::Command::new("cmd")
             .args(["/C", "start", doc_url])
             .spawn()
-            .context("Failed to open browser")?;
+            .with_context(|| tr!("doc.browser_failed"))?;
     }
 
     #[cfg(target_os = "linux")]
@@ -2569,10 +6642,10 @@ fn cmd_doc() -> Result<()> {
         std::process::Command::new("xdg-open")
             .arg(doc_url)
             .spawn()
-            .context("Failed to open browser")?;
+            .with_context(|| tr!("doc.browser_failed"))?;
     }
 
-    println!("Opening documentation: {}", doc_url);
+    println!("{}", tr!("doc.opening", doc_url));
     Ok(())
 }
 
@@ -2602,14 +6675,14 @@ async fn download_file(client: &reqwest::Client, url: &str, path: &PathBuf) -> R
         .header("User-Agent", "rbxsync-cli")
         .send()
         .await
-        .context("Failed to start download")?;
+        .with_context(|| tr!("update.download_start_failed"))?;
 
     if !response.status().is_success() {
-        bail!("Download failed with status: {}", response.status());
+        bail!("{}", tr!("update.download_status", response.status()));
     }
 
-    let bytes = response.bytes().await.context("Failed to read download")?;
-    std::fs::write(path, &bytes).context("Failed to write file")?;
+    let bytes = response.bytes().await.with_context(|| tr!("update.download_read_failed"))?;
+    std::fs::write(path, &bytes).with_context(|| tr!("update.write_file_failed"))?;
 
     Ok(())
 }
@@ -2621,20 +6694,20 @@ async fn download_plugin_from_github() -> Result<PathBuf> {
         .build()?;
 
     // Fetch latest release info
-    print!("Fetching latest release... ");
+    print!("{}", tr!("update.fetching_release"));
     let response = client
         .get("https://api.github.com/repos/Smokestack-Games/rbxsync/releases/latest")
         .header("User-Agent", "rbxsync-cli")
         .send()
         .await
-        .context("Failed to fetch release info")?;
+        .with_context(|| tr!("update.fetch_failed"))?;
 
     if !response.status().is_success() {
-        bail!("GitHub API returned status: {}", response.status());
+        bail!("{}", tr!("update.github_status", response.status()));
     }
 
     let release: serde_json::Value = response.json().await
-        .context("Failed to parse release info")?;
+        .with_context(|| tr!("update.parse_failed"))?;
 
     let version = release.get("tag_name")
         .and_then(|t| t.as_str())
@@ -2644,24 +6717,24 @@ async fn download_plugin_from_github() -> Result<PathBuf> {
     // Find plugin download URL
     let assets = release.get("assets")
         .and_then(|a| a.as_array())
-        .context("Could not find assets in release")?;
+        .with_context(|| tr!("update.no_assets"))?;
 
     let plugin_url = assets.iter()
         .find(|a| a.get("name").and_then(|n| n.as_str()) == Some("RbxSync.rbxm"))
         .and_then(|a| a.get("browser_download_url"))
         .and_then(|u| u.as_str())
-        .context("Could not find RbxSync.rbxm in release assets")?;
+        .with_context(|| tr!("update.asset_missing", "RbxSync.rbxm"))?;
 
     // Download to ~/.rbxsync/downloads
-    let home_dir = dirs::home_dir().context("Failed to get home directory")?;
+    let home_dir = dirs::home_dir().with_context(|| tr!("update.no_home"))?;
     let download_dir = home_dir.join(".rbxsync").join("downloads");
-    std::fs::create_dir_all(&download_dir).context("Failed to create download directory")?;
+    std::fs::create_dir_all(&download_dir).with_context(|| tr!("update.no_download_dir"))?;
 
     let plugin_path = download_dir.join("RbxSync.rbxm");
 
-    print!("Downloading plugin... ");
+    print!("{}", tr!("update.downloading_plugin_inline"));
     download_file(&client, plugin_url, &plugin_path).await?;
-    println!("done!");
+    println!("{}", tr!("update.done_inline"));
 
     Ok(plugin_path)
 }
@@ -2670,9 +6743,10 @@ async fn download_plugin_from_github() -> Result<PathBuf> {
 async fn cmd_update(from_source: bool, vscode: bool, yes: bool) -> Result<()> {
     let current_version = env!("CARGO_PKG_VERSION");
 
-    println!("RbxSync Update");
-    println!("==============");
-    println!("Current version: v{}", current_version);
+    let title = tr!("update.title");
+    println!("{}", title);
+    println!("{}", "=".repeat(title.chars().count()));
+    println!("{}", tr!("update.current_version", current_version));
     println!();
 
     // If --from-source, use the old build-from-source method
@@ -2683,14 +6757,14 @@ async fn cmd_update(from_source: bool, vscode: bool, yes: bool) -> Result<()> {
     // Check for platform support
     let platform_asset = get_platform_asset_name();
     if platform_asset.is_none() {
-        println!("Pre-built binaries are not available for your platform.");
-        println!("Use --from-source to build from source instead.");
+        println!("{}", tr!("update.no_binaries"));
+        println!("{}", tr!("update.use_from_source"));
         return Ok(());
     }
     let platform_asset = platform_asset.unwrap();
 
     // Fetch latest release info from GitHub
-    print!("Checking for updates... ");
+    print!("{}", tr!("update.checking"));
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(30))
         .build()?;
@@ -2700,55 +6774,55 @@ async fn cmd_update(from_source: bool, vscode: bool, yes: bool) -> Result<()> {
         .header("User-Agent", "rbxsync-cli")
         .send()
         .await
-        .context("Failed to fetch release info from GitHub")?;
+        .with_context(|| tr!("update.fetch_failed"))?;
 
     if !response.status().is_success() {
-        bail!("GitHub API returned status: {}", response.status());
+        bail!("{}", tr!("update.github_status", response.status()));
     }
 
     let release: serde_json::Value = response.json().await
-        .context("Failed to parse GitHub release response")?;
+        .with_context(|| tr!("update.parse_failed"))?;
 
     let latest_version = release.get("tag_name")
         .and_then(|t| t.as_str())
         .map(|s| s.trim_start_matches('v'))
-        .context("Could not find version tag in release")?;
+        .with_context(|| tr!("update.no_version_tag"))?;
 
     if !is_newer_version(latest_version, current_version) {
-        println!("\x1b[32mAlready up to date!\x1b[0m");
+        println!("\x1b[32m{}\x1b[0m", tr!("update.up_to_date"));
         return Ok(());
     }
 
-    println!("\x1b[33mUpdate available: v{}\x1b[0m", latest_version);
+    println!("\x1b[33m{}\x1b[0m", tr!("update.available", latest_version));
     println!();
 
     // Find download URLs for CLI and plugin
     let assets = release.get("assets")
         .and_then(|a| a.as_array())
-        .context("Could not find assets in release")?;
+        .with_context(|| tr!("update.no_assets"))?;
 
     let cli_url = assets.iter()
         .find(|a| a.get("name").and_then(|n| n.as_str()) == Some(platform_asset))
         .and_then(|a| a.get("browser_download_url"))
         .and_then(|u| u.as_str())
-        .context(format!("Could not find {} in release assets", platform_asset))?;
+        .with_context(|| tr!("update.asset_missing", platform_asset))?;
 
     let plugin_url = assets.iter()
         .find(|a| a.get("name").and_then(|n| n.as_str()) == Some("RbxSync.rbxm"))
         .and_then(|a| a.get("browser_download_url"))
         .and_then(|u| u.as_str())
-        .context("Could not find RbxSync.rbxm in release assets")?;
+        .with_context(|| tr!("update.asset_missing", "RbxSync.rbxm"))?;
 
     // Confirm update
     if !yes {
-        println!("This will update:");
-        println!("  - CLI binary ({})", platform_asset);
-        println!("  - Studio plugin (RbxSync.rbxm)");
+        println!("{}", tr!("update.will_update"));
+        println!("{}", tr!("update.item_cli", platform_asset));
+        println!("{}", tr!("update.item_plugin"));
         if vscode {
-            println!("  - VS Code extension");
+            println!("{}", tr!("update.item_vscode"));
         }
         println!();
-        print!("Continue? [Y/n] ");
+        print!("{}", tr!("update.continue"));
         use std::io::Write;
         std::io::stdout().flush()?;
 
@@ -2756,34 +6830,34 @@ async fn cmd_update(from_source: bool, vscode: bool, yes: bool) -> Result<()> {
         std::io::stdin().read_line(&mut input)?;
         let input = input.trim().to_lowercase();
         if !input.is_empty() && input != "y" && input != "yes" {
-            println!("Update cancelled.");
+            println!("{}", tr!("update.cancelled"));
             return Ok(());
         }
     }
 
     // Create temp directory for downloads
-    let home_dir = dirs::home_dir().context("Failed to get home directory")?;
+    let home_dir = dirs::home_dir().with_context(|| tr!("update.no_home"))?;
     let download_dir = home_dir.join(".rbxsync").join("downloads");
-    std::fs::create_dir_all(&download_dir).context("Failed to create download directory")?;
+    std::fs::create_dir_all(&download_dir).with_context(|| tr!("update.no_download_dir"))?;
 
     // Step 1: Download and install CLI
     println!();
-    println!("1. Downloading CLI...");
+    println!("{}", tr!("update.downloading_cli"));
     let cli_path = download_dir.join(platform_asset);
     download_file(&client, cli_url, &cli_path).await
-        .context("Failed to download CLI")?;
-    println!("   Downloaded!");
+        .with_context(|| tr!("update.download_cli_failed"))?;
+    println!("{}", tr!("update.downloaded"));
 
     // Install CLI
-    let current_exe = std::env::current_exe().context("Failed to get current executable path")?;
-    println!("   Installing to {}...", current_exe.display());
+    let current_exe = std::env::current_exe().with_context(|| tr!("update.no_exe_path"))?;
+    println!("{}", tr!("update.installing_to", current_exe.display()));
 
     #[cfg(unix)]
     {
         // Make executable
         use std::os::unix::fs::PermissionsExt;
         std::fs::set_permissions(&cli_path, std::fs::Permissions::from_mode(0o755))
-            .context("Failed to set executable permission")?;
+            .with_context(|| tr!("update.chmod_failed"))?;
 
         // Try to copy directly, fall back to sudo
         if std::fs::copy(&cli_path, &current_exe).is_err() {
@@ -2792,14 +6866,14 @@ async fn cmd_update(from_source: bool, vscode: bool, yes: bool) -> Result<()> {
                 .status();
 
             match status {
-                Ok(s) if s.success() => println!("   Installed!"),
+                Ok(s) if s.success() => println!("{}", tr!("update.installed")),
                 _ => {
-                    println!("   Could not auto-install. Run manually:");
+                    println!("{}", tr!("update.install_manually"));
                     println!("   sudo cp {} {}", cli_path.display(), current_exe.display());
                 }
             }
         } else {
-            println!("   Installed!");
+            println!("{}", tr!("update.installed"));
         }
     }
 
@@ -2808,7 +6882,7 @@ async fn cmd_update(from_source: bool, vscode: bool, yes: bool) -> Result<()> {
         // On Windows, we can't replace a running executable
         // Download to a temp location and use a batch file to replace after exit
         let temp_exe = download_dir.join("rbxsync-new.exe");
-        std::fs::copy(&cli_path, &temp_exe).context("Failed to copy new binary")?;
+        std::fs::copy(&cli_path, &temp_exe).with_context(|| tr!("update.copy_failed"))?;
 
         let batch_path = download_dir.join("update.bat");
         let batch_content = format!(
@@ -2824,30 +6898,30 @@ del "%~f0"
         );
         std::fs::write(&batch_path, batch_content)?;
 
-        println!("   Will install on exit (Windows limitation)");
+        println!("{}", tr!("update.install_on_exit"));
 
         // Schedule the batch file to run
         std::process::Command::new("cmd")
             .args(["/C", "start", "/min", batch_path.to_str().unwrap()])
             .spawn()
-            .context("Failed to schedule update")?;
+            .with_context(|| tr!("update.schedule_failed"))?;
     }
     println!();
 
     // Step 2: Download and install plugin
-    println!("2. Downloading Studio plugin...");
+    println!("{}", tr!("update.downloading_plugin"));
     let plugin_path = download_dir.join("RbxSync.rbxm");
     download_file(&client, plugin_url, &plugin_path).await
-        .context("Failed to download plugin")?;
-    println!("   Downloaded!");
+        .with_context(|| tr!("update.download_plugin_failed"))?;
+    println!("{}", tr!("update.downloaded"));
 
-    install_plugin(&plugin_path, "RbxSync").context("Failed to install plugin")?;
-    println!("   Installed!");
+    install_plugin(&plugin_path, "RbxSync").with_context(|| tr!("update.install_plugin_failed"))?;
+    println!("{}", tr!("update.installed"));
     println!();
 
     // Step 3: VS Code extension (optional)
     if vscode {
-        println!("3. Downloading VS Code extension...");
+        println!("{}", tr!("update.downloading_vscode"));
 
         // Find .vsix file in assets
         let vsix_asset = assets.iter()
@@ -2864,8 +6938,8 @@ del "%~f0"
             let vsix_path = download_dir.join(vsix_name);
 
             download_file(&client, vsix_url, &vsix_path).await
-                .context("Failed to download VS Code extension")?;
-            println!("   Downloaded!");
+                .with_context(|| tr!("update.download_vscode_failed"))?;
+            println!("{}", tr!("update.downloaded"));
 
             // Install using code CLI
             let status = std::process::Command::new("code")
@@ -2873,24 +6947,24 @@ del "%~f0"
                 .status();
 
             match status {
-                Ok(s) if s.success() => println!("   Installed!"),
+                Ok(s) if s.success() => println!("{}", tr!("update.installed")),
                 _ => {
-                    println!("   Could not auto-install. Run manually:");
+                    println!("{}", tr!("update.install_manually"));
                     println!("   code --install-extension {}", vsix_path.display());
                 }
             }
         } else {
-            println!("   Warning: VS Code extension not found in release");
+            println!("{}", tr!("update.vscode_missing"));
         }
         println!();
     }
 
-    println!("\x1b[32mUpdate complete!\x1b[0m");
+    println!("\x1b[32m{}\x1b[0m", tr!("update.complete"));
     println!();
-    println!("Next steps:");
-    println!("  1. Restart Roblox Studio to load the updated plugin");
+    println!("{}", tr!("update.next_steps"));
+    println!("{}", tr!("update.restart_studio"));
     if vscode {
-        println!("  2. Restart VS Code to load the updated extension");
+        println!("{}", tr!("update.restart_vscode"));
     }
 
     Ok(())
@@ -2898,11 +6972,11 @@ del "%~f0"
 
 /// Update RbxSync by building from source (legacy method)
 fn cmd_update_from_source(vscode: bool) -> Result<()> {
-    println!("Building from source...");
+    println!("{}", tr!("update.building_from_source"));
     println!();
 
-    let cwd = std::env::current_dir().context("Failed to get current directory")?;
-    let home_dir = dirs::home_dir().context("Failed to get home directory")?;
+    let cwd = std::env::current_dir().with_context(|| tr!("update.no_cwd"))?;
+    let home_dir = dirs::home_dir().with_context(|| tr!("update.no_home"))?;
     let managed_repo = home_dir.join(".rbxsync").join("repo");
 
     let repo_dir = if cwd.join("Cargo.toml").exists() && cwd.join("plugin").exists() {
@@ -2910,7 +6984,7 @@ fn cmd_update_from_source(vscode: bool) -> Result<()> {
     } else if managed_repo.join("Cargo.toml").exists() && managed_repo.join("plugin").exists() {
         managed_repo.clone()
     } else {
-        let exe_path = std::env::current_exe().context("Failed to get executable path")?;
+        let exe_path = std::env::current_exe().with_context(|| tr!("update.no_exe_path"))?;
         let mut found_dir = exe_path.parent().map(|p| p.to_path_buf());
 
         for _ in 0..5 {
@@ -2925,57 +6999,57 @@ fn cmd_update_from_source(vscode: bool) -> Result<()> {
         match found_dir {
             Some(dir) if dir.join("Cargo.toml").exists() && dir.join("plugin").exists() => dir,
             _ => {
-                println!("Cloning repository to ~/.rbxsync/repo...");
+                println!("{}", tr!("update.cloning"));
                 std::fs::create_dir_all(&home_dir.join(".rbxsync"))
-                    .context("Failed to create ~/.rbxsync directory")?;
+                    .with_context(|| tr!("update.no_rbxsync_dir"))?;
 
                 let status = std::process::Command::new("git")
                     .args(["clone", "https://github.com/Smokestack-Games/rbxsync.git", managed_repo.to_str().unwrap()])
                     .status()
-                    .context("Failed to clone repository")?;
+                    .with_context(|| tr!("update.clone_failed"))?;
 
                 if !status.success() {
-                    bail!("Failed to clone repository");
+                    bail!("{}", tr!("update.clone_failed"));
                 }
                 managed_repo.clone()
             }
         }
     };
 
-    println!("Repository: {}", repo_dir.display());
+    println!("{}", tr!("update.repository", repo_dir.display()));
     println!();
 
-    println!("1. Pulling latest changes...");
+    println!("{}", tr!("update.pulling"));
     let status = std::process::Command::new("git")
         .args(["pull", "--ff-only"])
         .current_dir(&repo_dir)
         .status()
-        .context("Failed to run git pull")?;
+        .with_context(|| tr!("update.pull_run_failed"))?;
 
     if !status.success() {
-        println!("   Warning: git pull failed (local changes?)");
+        println!("{}", tr!("update.pull_failed"));
     } else {
-        println!("   Done!");
+        println!("{}", tr!("update.done"));
     }
     println!();
 
-    println!("2. Building CLI...");
+    println!("{}", tr!("update.building_cli"));
     let status = std::process::Command::new("cargo")
         .args(["build", "--release", "-p", "rbxsync"])
         .current_dir(&repo_dir)
         .status()
-        .context("Failed to build CLI")?;
+        .with_context(|| tr!("update.build_cli_failed"))?;
 
     if !status.success() {
-        bail!("Failed to build CLI");
+        bail!("{}", tr!("update.build_cli_failed"));
     }
-    println!("   Done!");
+    println!("{}", tr!("update.done"));
 
     let new_binary = repo_dir.join("target/release/rbxsync");
-    let current_exe = std::env::current_exe().context("Failed to get current executable path")?;
+    let current_exe = std::env::current_exe().with_context(|| tr!("update.no_exe_path"))?;
 
     if !current_exe.to_string_lossy().contains("target") {
-        println!("   Installing to {}...", current_exe.display());
+        println!("{}", tr!("update.installing_to", current_exe.display()));
 
         #[cfg(unix)]
         {
@@ -2985,42 +7059,43 @@ fn cmd_update_from_source(vscode: bool) -> Result<()> {
                     .status();
 
                 match status {
-                    Ok(s) if s.success() => println!("   Installed!"),
-                    _ => println!("   Run: sudo cp {} {}", new_binary.display(), current_exe.display()),
+                    Ok(s) if s.success() => println!("{}", tr!("update.installed")),
+                    _ => println!("{}", tr!("update.run_manually", format!("sudo cp {} {}", new_binary.display(), current_exe.display()))),
                 }
             } else {
-                println!("   Installed!");
+                println!("{}", tr!("update.installed"));
             }
         }
 
         #[cfg(windows)]
         {
             if std::fs::copy(&new_binary, &current_exe).is_err() {
-                println!("   Run as Admin: copy {} {}", new_binary.display(), current_exe.display());
+                println!("{}", tr!("update.run_as_admin", format!("copy {} {}", new_binary.display(), current_exe.display())));
             } else {
-                println!("   Installed!");
+                println!("{}", tr!("update.installed"));
             }
         }
     }
     println!();
 
-    println!("3. Building and installing plugin...");
+    println!("{}", tr!("update.building_plugin"));
     let plugin_config = PluginBuildConfig {
         source_dir: repo_dir.join("plugin/src"),
         output_path: repo_dir.join("build/RbxSync.rbxm"),
         plugin_name: "RbxSync".to_string(),
         obfuscate: true,
         obfuscate_config: None,
+        cache_dir: Some(repo_dir.join(rbxsync_core::DEFAULT_BUILD_CACHE_DIR)),
     };
 
-    build_plugin(&plugin_config).context("Failed to build plugin")?;
+    build_plugin(&plugin_config).with_context(|| tr!("update.build_plugin_failed"))?;
     install_plugin(&repo_dir.join("build/RbxSync.rbxm"), "RbxSync")
-        .context("Failed to install plugin")?;
-    println!("   Done!");
+        .with_context(|| tr!("update.install_plugin_failed"))?;
+    println!("{}", tr!("update.done"));
     println!();
 
     if vscode {
-        println!("4. Building VS Code extension...");
+        println!("{}", tr!("update.building_vscode"));
         let vscode_dir = repo_dir.join("rbxsync-vscode");
 
         if vscode_dir.exists() {
@@ -3040,15 +7115,15 @@ fn cmd_update_from_source(vscode: bool) -> Result<()> {
                 .status();
 
             if status.map(|s| s.success()).unwrap_or(false) {
-                println!("   Built! Install with: code --install-extension rbxsync-vscode/rbxsync-*.vsix");
+                println!("{}", tr!("update.vscode_built", "code --install-extension rbxsync-vscode/rbxsync-*.vsix"));
             } else {
-                println!("   Build failed");
+                println!("{}", tr!("update.build_failed"));
             }
         }
         println!();
     }
 
-    println!("Update complete! Restart Studio to load the new plugin.");
+    println!("{}", tr!("update.complete_from_source"));
     Ok(())
 }
 
@@ -3083,13 +7158,13 @@ async fn cmd_version() -> Result<()> {
     {
         if output.status.success() {
             let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            println!("Git commit: {}", commit);
+            println!("{}", tr!("version.git_commit", commit));
         }
     }
 
     // Check for updates from GitHub releases
     println!();
-    print!("Checking for updates... ");
+    print!("{}", tr!("update.checking"));
 
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(5))
@@ -3106,34 +7181,35 @@ async fn cmd_version() -> Result<()> {
                 if let Some(tag) = release.get("tag_name").and_then(|t| t.as_str()) {
                     let latest = tag.trim_start_matches('v');
                     if is_newer_version(latest, version) {
-                        println!("\x1b[33mUpdate available: v{}\x1b[0m", latest);
-                        println!("  Run: rbxsync update");
-                        println!("  Or download: https://github.com/Smokestack-Games/rbxsync/releases/latest");
+                        println!("\x1b[33m{}\x1b[0m", tr!("version.update_available", latest));
+                        println!("{}", tr!("version.run_update"));
+                        println!("{}", tr!("version.or_download", "https://github.com/Smokestack-Games/rbxsync/releases/latest"));
                     } else {
-                        println!("\x1b[32mUp to date!\x1b[0m");
+                        println!("\x1b[32m{}\x1b[0m", tr!("version.up_to_date"));
                     }
                 } else {
-                    println!("Could not parse version");
+                    println!("{}", tr!("version.no_version"));
                 }
             } else {
-                println!("Could not parse response");
+                println!("{}", tr!("version.no_response"));
             }
         }
         Err(_) => {
-            println!("Could not check (offline?)");
+            println!("{}", tr!("version.offline"));
         }
     }
 
     println!();
-    println!("Documentation: https://rbxsync.dev");
+    println!("{}", tr!("version.documentation", "https://rbxsync.dev"));
 
     Ok(())
 }
 
 /// Uninstall RbxSync completely
 fn cmd_uninstall(vscode: bool, keep_repo: bool, yes: bool) -> Result<()> {
-    println!("RbxSync Uninstaller");
-    println!("===================");
+    let title = tr!("uninstall.title");
+    println!("{}", title);
+    println!("{}", "=".repeat(title.chars().count()));
     println!();
 
     // Gather what will be removed
@@ -3144,7 +7220,7 @@ fn cmd_uninstall(vscode: bool, keep_repo: bool, yes: bool) -> Result<()> {
     if let Some(ref exe) = current_exe {
         // Only list if it's in a system location (not in target/)
         if !exe.to_string_lossy().contains("target") {
-            items_to_remove.push(("CLI binary".to_string(), exe.clone()));
+            items_to_remove.push((tr!("uninstall.item_cli"), exe.clone()));
         }
     }
 
@@ -3152,7 +7228,7 @@ fn cmd_uninstall(vscode: bool, keep_repo: bool, yes: bool) -> Result<()> {
     if let Some(plugins_folder) = get_studio_plugins_folder() {
         let plugin_path = plugins_folder.join("RbxSync.rbxm");
         if plugin_path.exists() {
-            items_to_remove.push(("Studio plugin".to_string(), plugin_path));
+            items_to_remove.push((tr!("uninstall.item_plugin"), plugin_path));
         }
     }
 
@@ -3162,7 +7238,7 @@ fn cmd_uninstall(vscode: bool, keep_repo: bool, yes: bool) -> Result<()> {
     if !keep_repo {
         if let Some(ref dir) = rbxsync_dir {
             if dir.exists() {
-                items_to_remove.push(("Data directory (~/.rbxsync)".to_string(), dir.clone()));
+                items_to_remove.push((tr!("uninstall.item_data"), dir.clone()));
             }
         }
     }
@@ -3171,31 +7247,31 @@ fn cmd_uninstall(vscode: bool, keep_repo: bool, yes: bool) -> Result<()> {
     let vscode_extension_id = "rbxsync.rbxsync";
 
     if items_to_remove.is_empty() && !vscode {
-        println!("Nothing to uninstall. RbxSync does not appear to be installed.");
+        println!("{}", tr!("uninstall.nothing"));
         return Ok(());
     }
 
     // Show what will be removed
-    println!("The following will be removed:");
+    println!("{}", tr!("uninstall.will_remove"));
     println!();
     for (name, path) in &items_to_remove {
         println!("  - {} ({})", name, path.display());
     }
     if vscode {
-        println!("  - VS Code extension ({})", vscode_extension_id);
+        println!("{}", tr!("uninstall.item_vscode", vscode_extension_id));
     }
     println!();
 
     // Confirm unless --yes
     if !yes {
-        print!("Are you sure you want to uninstall? [y/N] ");
+        print!("{}", tr!("uninstall.confirm"));
         std::io::Write::flush(&mut std::io::stdout())?;
 
         let mut input = String::new();
         std::io::stdin().read_line(&mut input)?;
 
         if !input.trim().eq_ignore_ascii_case("y") && !input.trim().eq_ignore_ascii_case("yes") {
-            println!("Uninstall cancelled.");
+            println!("{}", tr!("uninstall.cancelled"));
             return Ok(());
         }
         println!();
@@ -3209,8 +7285,8 @@ fn cmd_uninstall(vscode: bool, keep_repo: bool, yes: bool) -> Result<()> {
         let plugin_path = plugins_folder.join("RbxSync.rbxm");
         if plugin_path.exists() {
             match std::fs::remove_file(&plugin_path) {
-                Ok(()) => println!("Removed Studio plugin: {}", plugin_path.display()),
-                Err(e) => errors.push(format!("Failed to remove plugin: {}", e)),
+                Ok(()) => println!("{}", tr!("uninstall.removed_plugin", plugin_path.display())),
+                Err(e) => errors.push(tr!("uninstall.remove_plugin_failed", e)),
             }
         }
     }
@@ -3220,8 +7296,8 @@ fn cmd_uninstall(vscode: bool, keep_repo: bool, yes: bool) -> Result<()> {
         if let Some(ref dir) = rbxsync_dir {
             if dir.exists() {
                 match std::fs::remove_dir_all(dir) {
-                    Ok(()) => println!("Removed data directory: {}", dir.display()),
-                    Err(e) => errors.push(format!("Failed to remove ~/.rbxsync: {}", e)),
+                    Ok(()) => println!("{}", tr!("uninstall.removed_data", dir.display())),
+                    Err(e) => errors.push(tr!("uninstall.remove_data_failed", e)),
                 }
             }
         }
@@ -3229,7 +7305,7 @@ fn cmd_uninstall(vscode: bool, keep_repo: bool, yes: bool) -> Result<()> {
 
     // Remove VS Code extension
     if vscode {
-        println!("Uninstalling VS Code extension...");
+        println!("{}", tr!("uninstall.uninstalling_vscode"));
 
         #[cfg(target_os = "macos")]
         let code_cmd = "code";
@@ -3243,13 +7319,13 @@ fn cmd_uninstall(vscode: bool, keep_repo: bool, yes: bool) -> Result<()> {
             .status()
         {
             Ok(status) if status.success() => {
-                println!("Removed VS Code extension: {}", vscode_extension_id);
+                println!("{}", tr!("uninstall.removed_vscode", vscode_extension_id));
             }
             Ok(_) => {
-                errors.push("VS Code extension uninstall failed (may not be installed)".to_string());
+                errors.push(tr!("uninstall.vscode_failed"));
             }
             Err(e) => {
-                errors.push(format!("Could not run 'code' command: {}. Uninstall manually from VS Code.", e));
+                errors.push(tr!("uninstall.no_code_command", e));
             }
         }
     }
@@ -3258,7 +7334,7 @@ fn cmd_uninstall(vscode: bool, keep_repo: bool, yes: bool) -> Result<()> {
     if let Some(ref exe) = current_exe {
         if !exe.to_string_lossy().contains("target") {
             println!();
-            println!("Removing CLI binary...");
+            println!("{}", tr!("uninstall.removing_cli"));
 
             #[cfg(unix)]
             {
@@ -3270,17 +7346,15 @@ fn cmd_uninstall(vscode: bool, keep_repo: bool, yes: bool) -> Result<()> {
                         .status()
                     {
                         Ok(status) if status.success() => {
-                            println!("Removed CLI: {}", exe.display());
+                            println!("{}", tr!("uninstall.removed_cli", exe.display()));
                         }
                         _ => {
-                            errors.push(format!(
-                                "Could not remove CLI binary. Run manually:\n  sudo rm {}",
-                                exe.display()
-                            ));
+                            let command = format!("sudo rm {}", exe.display());
+                            errors.push(tr!("uninstall.remove_cli_manually", command));
                         }
                     }
                 } else {
-                    println!("Removed CLI: {}", exe.display());
+                    println!("{}", tr!("uninstall.removed_cli", exe.display()));
                 }
             }
 
@@ -3301,12 +7375,10 @@ fn cmd_uninstall(vscode: bool, keep_repo: bool, yes: bool) -> Result<()> {
                     let _ = std::process::Command::new("cmd")
                         .args(["/C", "start", "/min", batch_path.to_str().unwrap()])
                         .spawn();
-                    println!("CLI will be removed after exit.");
+                    println!("{}", tr!("uninstall.cli_removed_on_exit"));
                 } else {
-                    errors.push(format!(
-                        "Could not remove CLI binary. Delete manually:\n  del \"{}\"",
-                        exe.display()
-                    ));
+                    let command = format!("del \"{}\"", exe.display());
+                    errors.push(tr!("uninstall.delete_cli_manually", command));
                 }
             }
         }
@@ -3315,12 +7387,12 @@ fn cmd_uninstall(vscode: bool, keep_repo: bool, yes: bool) -> Result<()> {
     println!();
 
     if errors.is_empty() {
-        println!("RbxSync has been uninstalled successfully!");
+        println!("{}", tr!("uninstall.success"));
         println!();
-        println!("Thanks for using RbxSync! If you have feedback, please share at:");
+        println!("{}", tr!("uninstall.thanks"));
         println!("  https://github.com/Smokestack-Games/rbxsync/issues");
     } else {
-        println!("Uninstall completed with some issues:");
+        println!("{}", tr!("uninstall.with_issues"));
         for err in &errors {
             println!("  - {}", err);
         }
@@ -3329,12 +7401,13 @@ fn cmd_uninstall(vscode: bool, keep_repo: bool, yes: bool) -> Result<()> {
     Ok(())
 }
 
-/// Migrate from Rojo project to RbxSync
-fn cmd_migrate(from: String, path: Option<PathBuf>, force: bool) -> Result<()> {
+/// Migrate from Rojo project to RbxSync, or legacy .lua files to .luau
+fn cmd_migrate(from: String, path: Option<PathBuf>, force: bool, dry_run: bool) -> Result<()> {
     let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
 
-    println!("RbxSync Migration Tool");
-    println!("======================");
+    let title = tr!("migrate.title");
+    println!("{}", title);
+    println!("{}", "=".repeat(title.chars().count()));
     println!();
 
     match from.to_lowercase().as_str() {
@@ -3343,33 +7416,27 @@ fn cmd_migrate(from: String, path: Option<PathBuf>, force: bool) -> Result<()> {
             let rojo_path = match find_rojo_project(&project_dir) {
                 Ok(path) => path,
                 Err(e) => {
-                    bail!(
-                        "No Rojo project file found in {}.\n\
-                        Expected: default.project.json or *.project.json\n\
-                        Error: {}",
-                        project_dir.display(),
-                        e
-                    );
+                    bail!("{}", tr!("migrate.no_rojo_project", project_dir.display(), e));
                 }
             };
 
-            println!("Found Rojo project: {}", rojo_path.display());
+            println!("{}", tr!("migrate.found_rojo", rojo_path.display()));
             println!();
 
             // Parse Rojo config
-            let rojo = parse_rojo_project(&rojo_path).context("Failed to parse Rojo project")?;
+            let rojo = parse_rojo_project(&rojo_path).with_context(|| tr!("migrate.parse_failed"))?;
 
-            println!("Project name: {}", rojo.name);
+            println!("{}", tr!("migrate.project_name", rojo.name));
             println!();
 
             // Convert to RbxSync tree_mapping
             let tree_mapping = rojo_to_tree_mapping(&rojo);
 
             if tree_mapping.is_empty() {
-                println!("Warning: No path mappings found in Rojo project.");
-                println!("The Rojo project may use inline definitions without $path.");
+                println!("{}", tr!("migrate.no_mappings"));
+                println!("{}", tr!("migrate.inline_definitions"));
             } else {
-                println!("Detected directory mappings:");
+                println!("{}", tr!("migrate.detected_mappings"));
                 let mut sorted_mappings: Vec<_> = tree_mapping.iter().collect();
                 sorted_mappings.sort_by(|a, b| a.0.cmp(b.0));
                 for (datamodel_path, fs_path) in &sorted_mappings {
@@ -3381,11 +7448,7 @@ fn cmd_migrate(from: String, path: Option<PathBuf>, force: bool) -> Result<()> {
             // Check for existing rbxsync.json
             let rbxsync_path = project_dir.join("rbxsync.json");
             if rbxsync_path.exists() && !force {
-                bail!(
-                    "rbxsync.json already exists at {}.\n\
-                    Use --force to overwrite.",
-                    rbxsync_path.display()
-                );
+                bail!("{}", tr!("migrate.config_exists", rbxsync_path.display()));
             }
 
             // Determine source directory from Rojo config
@@ -3402,46 +7465,139 @@ fn cmd_migrate(from: String, path: Option<PathBuf>, force: bool) -> Result<()> {
 
             // Write rbxsync.json
             let json = serde_json::to_string_pretty(&rbxsync_config)?;
-            std::fs::write(&rbxsync_path, &json).context("Failed to write rbxsync.json")?;
+            std::fs::write(&rbxsync_path, &json).with_context(|| tr!("migrate.write_failed"))?;
 
-            println!("Created: {}", rbxsync_path.display());
+            println!("{}", tr!("migrate.created", rbxsync_path.display()));
             println!();
 
             // Show the generated config
-            println!("Generated rbxsync.json:");
+            println!("{}", tr!("migrate.generated"));
             println!("{}", json);
             println!();
 
-            println!("Migration complete!");
+            println!("{}", tr!("migrate.complete"));
+            println!();
+            println!("{}", tr!("migrate.next_steps"));
+            println!("{}", tr!("migrate.step_review"));
+            println!("{}", tr!("migrate.step_serve"));
+            println!("{}", tr!("migrate.step_connect"));
+            println!();
+            println!("{}", tr!("migrate.rojo_untouched"));
+            println!("{}", tr!("migrate.side_by_side"));
+        }
+        "lua" => {
+            let report = rbxsync_core::migrate_lua_files(&project_dir, dry_run)
+                .with_context(|| tr!("migrate.lua_failed"))?;
+
+            if report.migrated.is_empty() && report.skipped.is_empty() {
+                println!("{}", tr!("migrate.no_lua_files", project_dir.join("src").display()));
+                return Ok(());
+            }
+
+            for migration in &report.migrated {
+                println!("  {} -> {}", migration.from, migration.to);
+                if migration.requires_updated > 0 {
+                    println!("{}", tr!("migrate.requires_updated", migration.requires_updated));
+                }
+                for warning in &migration.warnings {
+                    println!("\x1b[33m{}\x1b[0m", tr!("migrate.line_warning", warning.line, &warning.message));
+                }
+            }
+            for (file, reason) in &report.skipped {
+                println!("\x1b[33m{}\x1b[0m", tr!("migrate.skipped", file, reason));
+            }
             println!();
-            println!("Next steps:");
-            println!("  1. Review rbxsync.json and adjust settings if needed");
-            println!("  2. Start the sync server: rbxsync serve");
-            println!("  3. Connect from Roblox Studio with the RbxSync plugin");
+
+            let warning_count: usize = report.migrated.iter().map(|m| m.warnings.len()).sum();
+            if dry_run {
+                println!("{}", tr!("migrate.dry_run", report.migrated.len()));
+            } else {
+                println!("\x1b[32m{}\x1b[0m", tr!("migrate.migrated", report.migrated.len()));
+            }
+            if report.sourcemap_entries > 0 {
+                println!("{}", tr!("migrate.sourcemap_updated", report.sourcemap_entries));
+            }
+            if warning_count > 0 {
+                println!("\x1b[33m{}\x1b[0m", tr!("migrate.rejected_lines", warning_count));
+            }
             println!();
-            println!("Note: Your existing Rojo project file was not modified.");
-            println!("You can keep using both tools side-by-side if desired.");
+            println!("{}", tr!("migrate.legacy_lua_hint"));
         }
         other => {
-            bail!(
-                "Unknown source format: '{}'\n\
-                Supported formats:\n\
-                  - rojo: Migrate from Rojo project (default.project.json)",
-                other
-            );
+            bail!("{}", tr!("migrate.unknown_source", other));
+        }
+    }
+
+    Ok(())
+}
+
+/// Export an rbxsync project to another tool's layout (`migrate --to`)
+fn cmd_export(to: &str, path: Option<PathBuf>, force: bool, dry_run: bool) -> Result<()> {
+    let project_dir = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+
+    if to.to_lowercase() != "rojo" {
+        bail!("{}", tr!("export.unknown_format", to));
+    }
+    if !project_dir.join("src").is_dir() {
+        bail!("{}", tr!("export.no_src", project_dir.display()));
+    }
+    let project_file = project_dir.join(rbxsync_core::ROJO_PROJECT_FILE);
+    if project_file.exists() && !force && !dry_run {
+        bail!("{}", tr!("export.exists", rbxsync_core::ROJO_PROJECT_FILE, project_file.display()));
+    }
+
+    let project_name = std::fs::read_to_string(project_dir.join("rbxsync.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        .and_then(|c| c.get("name").and_then(|n| n.as_str()).map(String::from))
+        .or_else(|| project_dir.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "MyGame".to_string());
+
+    let report = rbxsync_core::export_to_rojo(&project_dir, &project_name, dry_run)
+        .with_context(|| tr!("export.failed"))?;
+
+    for file in &report.converted {
+        match (file.from.as_str(), &file.to) {
+            ("", Some(to)) => println!("  + {}", to),
+            (from, Some(to)) => println!("  {} -> {}", from, to),
+            (from, None) => println!("  - {}", from),
+        }
+    }
+    if !report.unrepresentable.is_empty() {
+        println!();
+        println!("\x1b[33m{}\x1b[0m", tr!("export.unrepresentable", report.unrepresentable.len()));
+        for (path, reason) in &report.unrepresentable {
+            println!("  \x1b[33m⚠ {}: {}\x1b[0m", path, reason);
         }
     }
+    println!();
+
+    if dry_run {
+        println!("{}", tr!("export.dry_run", report.converted.len()));
+        println!("{}", tr!("export.would_be", rbxsync_core::ROJO_PROJECT_FILE));
+        println!("{}", serde_json::to_string_pretty(&report.project)?);
+    } else {
+        println!("\x1b[32m{}\x1b[0m", tr!("export.converted", report.converted.len()));
+        println!("{}", tr!("export.created", project_file.display()));
+        println!();
+        println!("{}", tr!("export.next_steps"));
+        println!("{}", tr!("export.step_review", rbxsync_core::ROJO_PROJECT_FILE));
+        println!("{}", tr!("export.step_serve"));
+        println!();
+        println!("{}", tr!("export.note"));
+    }
 
     Ok(())
 }
 
 /// Manage AI development harness
 async fn cmd_harness(action: HarnessAction) -> Result<()> {
-    let client = reqwest::Client::new();
+    let client = server_client().build()?;
+    let base = server_url(None);
 
     // Check server is running
-    if client.get("http://localhost:44755/health").send().await.is_err() {
-        println!("RbxSync server is not running. Start it with: rbxsync serve");
+    if client.get(format!("{}/health", base)).send().await.is_err() {
+        println!("{}", tr!("server.not_running"));
         return Ok(());
     }
 
@@ -3457,7 +7613,7 @@ async fn cmd_harness(action: HarnessAction) -> Result<()> {
                 .to_string_lossy()
                 .to_string();
 
-            println!("Initializing harness for project: {}", project_dir);
+            println!("{}", tr!("harness.initializing", project_dir));
 
             let mut body = serde_json::json!({
                 "projectDir": project_dir,
@@ -3472,11 +7628,11 @@ async fn cmd_harness(action: HarnessAction) -> Result<()> {
             }
 
             let response = client
-                .post("http://localhost:44755/harness/init")
+                .post(format!("{}/harness/init", base))
                 .json(&body)
                 .send()
                 .await
-                .context("Failed to initialize harness")?;
+                .with_context(|| tr!("harness.init_request_failed"))?;
 
             let result: serde_json::Value = response.json().await?;
             if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
@@ -3489,15 +7645,16 @@ async fn cmd_harness(action: HarnessAction) -> Result<()> {
                     .and_then(|v| v.as_str())
                     .unwrap_or("unknown");
 
-                println!("Harness initialized successfully!");
-                println!("  Directory: {}", harness_dir);
-                println!("  Game ID: {}", game_id);
+                println!("{}", tr!("harness.initialized"));
+                println!("{}", tr!("harness.directory", harness_dir));
+                println!("{}", tr!("harness.game_id", game_id));
             } else {
                 let error = result
                     .get("message")
                     .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown error");
-                println!("Failed to initialize harness: {}", error);
+                    .map(str::to_string)
+                    .unwrap_or_else(|| tr!("harness.unknown_error"));
+                println!("{}", tr!("harness.init_failed", error));
             }
         }
 
@@ -3508,31 +7665,32 @@ async fn cmd_harness(action: HarnessAction) -> Result<()> {
                 .to_string();
 
             let response = client
-                .post("http://localhost:44755/harness/status")
+                .post(format!("{}/harness/status", base))
                 .json(&serde_json::json!({
                     "projectDir": project_dir,
                 }))
                 .send()
                 .await
-                .context("Failed to get harness status")?;
+                .with_context(|| tr!("harness.status_failed"))?;
 
             let result: serde_json::Value = response.json().await?;
 
             if !result.get("initialized").and_then(|v| v.as_bool()).unwrap_or(false) {
-                println!("Harness not initialized for this project.");
-                println!("Run: rbxsync harness init --name 'Your Game'");
+                println!("{}", tr!("harness.not_initialized"));
+                println!("{}", tr!("harness.run_init"));
                 return Ok(());
             }
 
             // Print game info
             if let Some(game) = result.get("game") {
-                println!("Game: {}", game.get("name").and_then(|v| v.as_str()).unwrap_or("Unknown"));
+                let name = game.get("name").and_then(|v| v.as_str()).map(str::to_string);
+                println!("{}", tr!("harness.game", name.unwrap_or_else(|| tr!("harness.unknown"))));
                 if let Some(genre) = game.get("genre").and_then(|v| v.as_str()) {
-                    println!("Genre: {}", genre);
+                    println!("{}", tr!("harness.genre", genre));
                 }
                 if let Some(desc) = game.get("description").and_then(|v| v.as_str()) {
                     if !desc.is_empty() {
-                        println!("Description: {}", desc);
+                        println!("{}", tr!("harness.description", desc));
                     }
                 }
                 println!();
@@ -3540,25 +7698,25 @@ async fn cmd_harness(action: HarnessAction) -> Result<()> {
 
             // Print feature summary
             if let Some(summary) = result.get("featureSummary") {
-                println!("Features:");
+                println!("{}", tr!("harness.features"));
                 let total = summary.get("total").and_then(|v| v.as_u64()).unwrap_or(0);
                 let planned = summary.get("planned").and_then(|v| v.as_u64()).unwrap_or(0);
                 let in_progress = summary.get("inProgress").and_then(|v| v.as_u64()).unwrap_or(0);
                 let completed = summary.get("completed").and_then(|v| v.as_u64()).unwrap_or(0);
                 let blocked = summary.get("blocked").and_then(|v| v.as_u64()).unwrap_or(0);
 
-                println!("  Total: {}", total);
+                println!("{}", tr!("harness.total", total));
                 if planned > 0 {
-                    println!("  Planned: {}", planned);
+                    println!("{}", tr!("harness.planned", planned));
                 }
                 if in_progress > 0 {
-                    println!("  In Progress: {}", in_progress);
+                    println!("{}", tr!("harness.in_progress", in_progress));
                 }
                 if completed > 0 {
-                    println!("  Completed: {}", completed);
+                    println!("{}", tr!("harness.completed", completed));
                 }
                 if blocked > 0 {
-                    println!("  Blocked: {}", blocked);
+                    println!("{}", tr!("harness.blocked", blocked));
                 }
                 println!();
             }
@@ -3566,12 +7724,12 @@ async fn cmd_harness(action: HarnessAction) -> Result<()> {
             // Print recent sessions
             if let Some(sessions) = result.get("recentSessions").and_then(|v| v.as_array()) {
                 if !sessions.is_empty() {
-                    println!("Recent Sessions:");
+                    println!("{}", tr!("harness.recent_sessions"));
                     for session in sessions.iter().take(3) {
                         let id = session.get("id").and_then(|v| v.as_str()).unwrap_or("unknown");
                         let started = session.get("startedAt").and_then(|v| v.as_str()).unwrap_or("unknown");
                         let ended = session.get("endedAt").and_then(|v| v.as_str());
-                        let status = if ended.is_some() { "completed" } else { "active" };
+                        let status = if ended.is_some() { tr!("harness.session_completed") } else { tr!("harness.session_active") };
                         println!("  {} ({}) - {}", &id[..8.min(id.len())], status, started);
                     }
                 }
@@ -3585,34 +7743,40 @@ async fn cmd_harness(action: HarnessAction) -> Result<()> {
                 .to_string();
 
             let response = client
-                .post("http://localhost:44755/harness/status")
+                .post(format!("{}/harness/status", base))
                 .json(&serde_json::json!({
                     "projectDir": project_dir,
                 }))
                 .send()
                 .await
-                .context("Failed to get features")?;
+                .with_context(|| tr!("harness.features_failed"))?;
 
             let result: serde_json::Value = response.json().await?;
 
             if !result.get("initialized").and_then(|v| v.as_bool()).unwrap_or(false) {
-                println!("Harness not initialized for this project.");
+                println!("{}", tr!("harness.not_initialized"));
                 return Ok(());
             }
 
             let features = result.get("features").and_then(|v| v.as_array());
             if let Some(features) = features {
                 if features.is_empty() {
-                    println!("No features found.");
-                    println!("Add one with: rbxsync harness feature 'Feature Name'");
+                    println!("{}", tr!("harness.no_features"));
+                    println!("{}", tr!("harness.add_feature"));
                     return Ok(());
                 }
 
                 // Filter by status if provided
                 let status_filter = status.as_ref().map(|s| s.to_lowercase());
 
-                println!("Features:");
-                println!("{:<36} {:<12} {:<8} {}", "ID", "Status", "Priority", "Name");
+                println!("{}", tr!("harness.features"));
+                println!(
+                    "{:<36} {:<12} {:<8} {}",
+                    "ID",
+                    tr!("harness.col_status"),
+                    tr!("harness.col_priority"),
+                    tr!("harness.col_name")
+                );
                 println!("{}", "-".repeat(80));
 
                 for feature in features {
@@ -3642,7 +7806,7 @@ async fn cmd_harness(action: HarnessAction) -> Result<()> {
                     println!("{:<36} {:<12} {:<8} {}", id, feature_status, priority, name);
                 }
             } else {
-                println!("No features found.");
+                println!("{}", tr!("harness.no_features"));
             }
         }
 
@@ -3700,11 +7864,11 @@ async fn cmd_harness(action: HarnessAction) -> Result<()> {
             }
 
             let response = client
-                .post("http://localhost:44755/harness/feature/update")
+                .post(format!("{}/harness/feature/update", base))
                 .json(&body)
                 .send()
                 .await
-                .context("Failed to update feature")?;
+                .with_context(|| tr!("harness.update_request_failed"))?;
 
             let result: serde_json::Value = response.json().await?;
             if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
@@ -3715,16 +7879,18 @@ async fn cmd_harness(action: HarnessAction) -> Result<()> {
                 let message = result
                     .get("message")
                     .and_then(|v| v.as_str())
-                    .unwrap_or("Feature updated");
+                    .map(str::to_string)
+                    .unwrap_or_else(|| tr!("harness.feature_updated"));
 
                 println!("{}", message);
-                println!("Feature ID: {}", feature_id);
+                println!("{}", tr!("harness.feature_id", feature_id));
             } else {
                 let error = result
                     .get("message")
                     .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown error");
-                println!("Failed to update feature: {}", error);
+                    .map(str::to_string)
+                    .unwrap_or_else(|| tr!("harness.unknown_error"));
+                println!("{}", tr!("harness.update_failed", error));
             }
         }
 
@@ -3744,11 +7910,11 @@ async fn cmd_harness(action: HarnessAction) -> Result<()> {
                 }
 
                 let response = client
-                    .post("http://localhost:44755/harness/session/start")
+                    .post(format!("{}/harness/session/start", base))
                     .json(&body)
                     .send()
                     .await
-                    .context("Failed to start session")?;
+                    .with_context(|| tr!("harness.start_request_failed"))?;
 
                 let result: serde_json::Value = response.json().await?;
                 if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
@@ -3757,17 +7923,18 @@ async fn cmd_harness(action: HarnessAction) -> Result<()> {
                         .and_then(|v| v.as_str())
                         .unwrap_or("unknown");
 
-                    println!("Session started successfully!");
-                    println!("Session ID: {}", session_id);
+                    println!("{}", tr!("harness.session_started"));
+                    println!("{}", tr!("harness.session_id", session_id));
                     println!();
-                    println!("When finished, end with:");
+                    println!("{}", tr!("harness.end_with"));
                     println!("  rbxsync harness session end --id {}", session_id);
                 } else {
                     let error = result
                         .get("message")
                         .and_then(|v| v.as_str())
-                        .unwrap_or("Unknown error");
-                    println!("Failed to start session: {}", error);
+                        .map(str::to_string)
+                        .unwrap_or_else(|| tr!("harness.unknown_error"));
+                    println!("{}", tr!("harness.start_failed", error));
                 }
             }
 
@@ -3798,21 +7965,22 @@ async fn cmd_harness(action: HarnessAction) -> Result<()> {
                 }
 
                 let response = client
-                    .post("http://localhost:44755/harness/session/end")
+                    .post(format!("{}/harness/session/end", base))
                     .json(&body)
                     .send()
                     .await
-                    .context("Failed to end session")?;
+                    .with_context(|| tr!("harness.end_request_failed"))?;
 
                 let result: serde_json::Value = response.json().await?;
                 if result.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
-                    println!("Session ended successfully!");
+                    println!("{}", tr!("harness.session_ended"));
                 } else {
                     let error = result
                         .get("message")
                         .and_then(|v| v.as_str())
-                        .unwrap_or("Unknown error");
-                    println!("Failed to end session: {}", error);
+                        .map(str::to_string)
+                        .unwrap_or_else(|| tr!("harness.unknown_error"));
+                    println!("{}", tr!("harness.end_failed", error));
                 }
             }
         },
@@ -0,0 +1,429 @@
+//! RbxSync Client
+//!
+//! Typed async client for the rbxsync-server HTTP API. Request and response
+//! bodies are the `rbxsync_core::api` types the server itself deserializes
+//! and returns, re-exported here as [`api`].
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), rbxsync_client::ClientError> {
+//! use rbxsync_client::{api::DiffRequest, RbxSyncClient};
+//!
+//! let client = RbxSyncClient::localhost(rbxsync_core::DEFAULT_PORT);
+//! let diff = client.diff(&DiffRequest { project_dir: "/games/obby".into(), include_properties: None }).await?;
+//! println!("{} to create, {} to delete, {} modified", diff.added.len(), diff.removed.len(), diff.modified.len());
+//! # Ok(())
+//! # }
+//! ```
+
+pub use rbxsync_core::api;
+
+use api::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("Failed to reach rbxsync server: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Server returned {status}: {message}")]
+    Api { status: u16, message: String },
+
+    #[error("Unexpected response body: {0}")]
+    Decode(#[from] serde_json::Error),
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+/// Responses that wrap their payload as `{ "success": true, "data": ... }`
+#[derive(Deserialize)]
+struct DataResponse<T> {
+    data: T,
+}
+
+/// HTTP client for a running rbxsync server
+#[derive(Debug, Clone)]
+pub struct RbxSyncClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl RbxSyncClient {
+    /// Client for the server at `base_url`, e.g. `http://127.0.0.1:44755`.
+    /// Sends the API token from `RBXSYNC_TOKEN`, the `rbxsync login` keychain
+    /// entry or `~/.rbxsync/token`, whichever comes first.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_token(base_url, rbxsync_core::client_token())
+    }
+
+    /// Client sending `token` in the `X-RbxSync-Token` header (none if `None`)
+    pub fn with_token(base_url: impl Into<String>, token: Option<String>) -> Self {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(value) = token.and_then(|t| reqwest::header::HeaderValue::from_str(&t).ok()) {
+            headers.insert(rbxsync_core::TOKEN_HEADER, value);
+        }
+        Self {
+            http: reqwest::Client::builder()
+                .default_headers(headers)
+                .build()
+                .unwrap_or_default(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Client for a server on this machine
+    pub fn localhost(port: u16) -> Self {
+        Self::new(format!("http://127.0.0.1:{}", port))
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.send(self.http.get(format!("{}{}", self.base_url, path))).await
+    }
+
+    async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        self.send(self.http.post(format!("{}{}", self.base_url, path)).json(body)).await
+    }
+
+    /// Send a request, turning error statuses and `"success": false` bodies
+    /// into [`ClientError::Api`]
+    async fn send<T: DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T> {
+        let response = request.send().await?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() || body.get("success") == Some(&Value::Bool(false)) {
+            let message = ["error", "message"]
+                .iter()
+                .find_map(|key| body.get(*key).and_then(|v| v.as_str()))
+                .map(str::to_string)
+                .unwrap_or_else(|| status.to_string());
+            return Err(ClientError::Api { status: status.as_u16(), message });
+        }
+        Ok(serde_json::from_value(body)?)
+    }
+
+    pub async fn health(&self) -> Result<HealthResponse> {
+        self.get("/health").await
+    }
+
+    // ------------------------------------------------------------------------
+    // Extraction
+    // ------------------------------------------------------------------------
+
+    /// Ask the plugin to start extracting; clears (and backs up) `src` first
+    pub async fn extract_start(&self, req: &ExtractStartRequest) -> Result<ExtractStartResponse> {
+        self.post("/extract/start", req).await
+    }
+
+    /// Progress of the active extraction session
+    pub async fn extract_status(&self) -> Result<Value> {
+        self.get("/extract/status").await
+    }
+
+    /// Upload one chunk of an extraction (what the plugin sends)
+    pub async fn extract_chunk(&self, req: &ExtractChunkRequest) -> Result<Value> {
+        self.post("/extract/chunk", req).await
+    }
+
+    /// Chunk indices the server still needs after a failed upload
+    pub async fn extract_resume(&self, req: &ExtractResumeRequest) -> Result<ExtractResumeResponse> {
+        self.post("/extract/resume", req).await
+    }
+
+    /// Write the received chunks to the project's `src`
+    pub async fn extract_finalize(&self, req: &FinalizeRequest) -> Result<Value> {
+        self.post("/extract/finalize", req).await
+    }
+
+    // ------------------------------------------------------------------------
+    // Sync
+    // ------------------------------------------------------------------------
+
+    /// Send a command to the plugin and wait for its response
+    pub async fn sync_command(&self, req: &SyncCommandRequest) -> Result<Value> {
+        self.post("/sync/command", req).await
+    }
+
+    /// Apply a batch of operations in Studio
+    pub async fn sync_batch(&self, req: &SyncBatchRequest) -> Result<Value> {
+        self.post("/sync/batch", req).await
+    }
+
+    /// Every instance in the project's `src`
+    pub async fn read_tree(&self, req: &ReadTreeRequest) -> Result<Value> {
+        self.post("/sync/read-tree", req).await
+    }
+
+    /// Instances whose files changed since the last sync
+    pub async fn sync_incremental(&self, req: &IncrementalSyncRequest) -> Result<Value> {
+        self.post("/sync/incremental", req).await
+    }
+
+    /// Write changes made in Studio to files
+    pub async fn sync_from_studio(&self, req: &SyncFromStudioRequest) -> Result<Value> {
+        self.post("/sync/from-studio", req).await
+    }
+
+    /// Studio changes held back because the files were edited too
+    pub async fn sync_conflicts(&self, req: &ConflictsRequest) -> Result<ConflictsResponse> {
+        self.post("/sync/conflicts", req).await
+    }
+
+    /// Settle a conflict with the files, the Studio change or a merge
+    pub async fn resolve_conflict(&self, req: &ResolveConflictRequest) -> Result<Value> {
+        self.post("/sync/conflicts/resolve", req).await
+    }
+
+    /// Number of file changes waiting to be synced
+    pub async fn pending_changes(&self, req: &PendingChangesRequest) -> Result<usize> {
+        let body: Value = self.post("/sync/pending-changes", req).await?;
+        Ok(body.get("count").and_then(|c| c.as_u64()).unwrap_or(0) as usize)
+    }
+
+    // ------------------------------------------------------------------------
+    // Diff
+    // ------------------------------------------------------------------------
+
+    /// Compare the project's files with the connected Studio
+    pub async fn diff(&self, req: &DiffRequest) -> Result<DiffResult> {
+        self.post("/diff", req).await
+    }
+
+    // ------------------------------------------------------------------------
+    // Git
+    // ------------------------------------------------------------------------
+
+    pub async fn git_status(&self, req: &GitStatusRequest) -> Result<GitStatus> {
+        self.post::<_, DataResponse<_>>("/git/status", req).await.map(|r| r.data)
+    }
+
+    pub async fn git_log(&self, req: &GitLogRequest) -> Result<Vec<GitCommit>> {
+        self.post::<_, DataResponse<_>>("/git/log", req).await.map(|r| r.data)
+    }
+
+    /// Commit and return git's output
+    pub async fn git_commit(&self, req: &GitCommitRequest) -> Result<String> {
+        self.post::<_, DataResponse<_>>("/git/commit", req).await.map(|r| r.data)
+    }
+
+    /// Initialize a repository and return git's output
+    pub async fn git_init(&self, req: &GitProjectRequest) -> Result<String> {
+        self.post::<_, DataResponse<_>>("/git/init", req).await.map(|r| r.data)
+    }
+
+    /// Local branches, most recently committed first
+    pub async fn git_branches(&self, req: &GitProjectRequest) -> Result<Vec<GitBranch>> {
+        self.post::<_, DataResponse<_>>("/git/branches", req).await.map(|r| r.data)
+    }
+
+    pub async fn git_branch_create(&self, req: &GitBranchCreateRequest) -> Result<GitBranch> {
+        self.post::<_, DataResponse<_>>("/git/branch/create", req).await.map(|r| r.data)
+    }
+
+    pub async fn git_checkout(&self, req: &GitCheckoutRequest) -> Result<GitRemoteResult> {
+        self.post::<_, DataResponse<_>>("/git/checkout", req).await.map(|r| r.data)
+    }
+
+    pub async fn git_pull(&self, req: &GitRemoteRequest) -> Result<GitRemoteResult> {
+        self.post::<_, DataResponse<_>>("/git/pull", req).await.map(|r| r.data)
+    }
+
+    pub async fn git_push(&self, req: &GitRemoteRequest) -> Result<GitRemoteResult> {
+        self.post::<_, DataResponse<_>>("/git/push", req).await.map(|r| r.data)
+    }
+
+    /// Run a stash action and return the stashes left
+    pub async fn git_stash(&self, req: &GitStashRequest) -> Result<GitStashResult> {
+        self.post::<_, DataResponse<_>>("/git/stash", req).await.map(|r| r.data)
+    }
+
+    // ------------------------------------------------------------------------
+    // Tests
+    // ------------------------------------------------------------------------
+
+    /// Run the place's specs in a playtest and wait for the report
+    pub async fn run_tests(&self, req: &TestRunRequest) -> Result<TestReport> {
+        self.post("/test/run", req).await
+    }
+
+    // ------------------------------------------------------------------------
+    // Bot controller
+    // ------------------------------------------------------------------------
+
+    pub async fn bot_command(&self, req: &BotCommandRequest) -> Result<Value> {
+        self.post("/bot/command", req).await
+    }
+
+    /// Current character state from the running playtest
+    pub async fn bot_state(&self) -> Result<Value> {
+        self.get("/bot/state").await
+    }
+
+    pub async fn bot_move(&self, req: &BotMoveRequest) -> Result<Value> {
+        self.post("/bot/move", req).await
+    }
+
+    pub async fn bot_action(&self, req: &BotActionRequest) -> Result<Value> {
+        self.post("/bot/action", req).await
+    }
+
+    pub async fn bot_observe(&self, req: &BotObserveRequest) -> Result<Value> {
+        self.post("/bot/observe", req).await
+    }
+
+    /// Run Luau on the playtest server
+    pub async fn bot_query_server(&self, req: &BotQueryServerRequest) -> Result<Value> {
+        self.post("/bot/query-server", req).await
+    }
+
+    /// Run a scenario's steps in order and report pass/fail per step
+    pub async fn run_bot_scenario(&self, req: &ScenarioRunRequest) -> Result<ScenarioReport> {
+        self.post("/bot/scenario/run", req).await
+    }
+
+    // ------------------------------------------------------------------------
+    // Console
+    // ------------------------------------------------------------------------
+
+    /// Push messages into the console buffer; returns how many were received
+    pub async fn console_push(&self, req: &ConsolePushRequest) -> Result<usize> {
+        let body: Value = self.post("/console/push", req).await?;
+        Ok(body.get("received").and_then(|c| c.as_u64()).unwrap_or(0) as usize)
+    }
+
+    /// The most recent console messages matching the query's filters (the
+    /// server defaults to 100)
+    pub async fn console_history(&self, query: &ConsoleHistoryQuery) -> Result<ConsoleHistoryResponse> {
+        self.send(self.http.get(format!("{}/console/history", self.base_url)).query(query)).await
+    }
+
+    /// Errors and warnings in the console buffer, deduplicated, with the
+    /// five most frequent errors
+    pub async fn console_error_summary(&self) -> Result<ErrorSummary> {
+        self.get("/console/errors/summary").await
+    }
+
+    /// Write the matching console messages to a file on the server's
+    /// machine; returns the response with the absolute `path` and the count `exported`
+    pub async fn console_export(&self, req: &ConsoleExportRequest) -> Result<Value> {
+        self.post("/console/export", req).await
+    }
+
+    // ------------------------------------------------------------------------
+    // Harness
+    // ------------------------------------------------------------------------
+
+    pub async fn harness_init(&self, req: &HarnessInitRequest) -> Result<HarnessInitResponse> {
+        self.post("/harness/init", req).await
+    }
+
+    pub async fn session_start(&self, req: &SessionStartRequest) -> Result<SessionStartResponse> {
+        self.post("/harness/session/start", req).await
+    }
+
+    pub async fn session_end(&self, req: &SessionEndRequest) -> Result<SessionEndResponse> {
+        self.post("/harness/session/end", req).await
+    }
+
+    pub async fn feature_update(&self, req: &FeatureUpdateRequest) -> Result<FeatureUpdateResponse> {
+        self.post("/harness/feature/update", req).await
+    }
+
+    pub async fn harness_status(&self, req: &HarnessStatusRequest) -> Result<HarnessStatusResponse> {
+        self.post("/harness/status", req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A server on a free local port, and a client for it
+    async fn serve() -> RbxSyncClient {
+        serve_with_token(None).await
+    }
+
+    async fn serve_with_token(token: Option<&str>) -> RbxSyncClient {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let state = rbxsync_server::AppState::new();
+        if let Some(token) = token {
+            state.auth_token.set(token.to_string()).unwrap();
+        }
+        let router = rbxsync_server::create_router(state);
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, router).await;
+        });
+        RbxSyncClient::with_token(format!("http://127.0.0.1:{}", port), token.map(str::to_string))
+    }
+
+    #[tokio::test]
+    async fn test_client_round_trips_shared_types() {
+        let client = serve().await;
+        assert_eq!(client.health().await.unwrap().status, "ok");
+
+        // Harness responses decode into the server's own structs
+        let project = tempfile::tempdir().unwrap();
+        let project_dir = project.path().to_string_lossy().to_string();
+        let init = client
+            .harness_init(&HarnessInitRequest {
+                project_dir: project_dir.clone(),
+                game_name: "Obby".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(init.success);
+        let status = client
+            .harness_status(&HarnessStatusRequest { project_dir: project_dir.clone() })
+            .await
+            .unwrap();
+        assert!(status.initialized);
+        assert_eq!(status.game.unwrap().name, "Obby");
+        assert_eq!(status.feature_summary.total, status.features.len());
+
+        // "success": false bodies become errors carrying the server's message
+        match client.git_status(&GitStatusRequest { project_dir }).await {
+            Err(ClientError::Api { message, .. }) => assert_eq!(message, "Not a git repository"),
+            other => panic!("expected an API error, got {:?}", other),
+        }
+
+        let message = ConsoleMessage {
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            message_type: "warn".to_string(),
+            message: "Infinite yield possible".to_string(),
+            source: Some("plugin".to_string()),
+            tags: Vec::new(),
+            count: None,
+            locations: Vec::new(),
+            received_at: None,
+        };
+        assert_eq!(client.console_push(&ConsolePushRequest { messages: vec![message] }).await.unwrap(), 1);
+        let history = client.console_history(&ConsoleHistoryQuery { limit: Some(10), ..Default::default() }).await.unwrap();
+        assert_eq!(history.total, 1);
+        assert_eq!(history.messages[0].message, "Infinite yield possible");
+        let query = ConsoleHistoryQuery { level: Some("error".to_string()), ..Default::default() };
+        let errors = client.console_history(&query).await.unwrap();
+        assert_eq!((errors.total, errors.matched), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_token_required_for_mutating_requests() {
+        let client = serve_with_token(Some("secret")).await;
+        let anonymous = RbxSyncClient::with_token(client.base_url(), None);
+        let push = ConsolePushRequest { messages: Vec::new() };
+
+        // Reads stay open
+        assert_eq!(anonymous.health().await.unwrap().status, "ok");
+
+        match anonymous.console_push(&push).await {
+            Err(ClientError::Api { status, .. }) => assert_eq!(status, 401),
+            other => panic!("expected 401, got {:?}", other),
+        }
+        assert_eq!(client.console_push(&push).await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,1078 @@
+//! HTTP API request and response types
+//!
+//! Bodies for the rbxsync-server routes. The server deserializes requests
+//! into these and `rbxsync-client` serializes them, so the two sides share
+//! one definition per endpoint. Field names follow the wire format of each
+//! route, which is not uniform (snake_case for older endpoints, camelCase
+//! for harness and some sync fields).
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Feature, FeaturePriority, FeatureStatus, GameDefinition};
+
+// ============================================================================
+// Health
+// ============================================================================
+
+/// Response from `GET /health`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HealthResponse {
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// No clients linked; watchers and polling are suspended
+    #[serde(default)]
+    pub idle: bool,
+    /// Running under `rbxsync serve --background`'s watchdog
+    #[serde(default)]
+    pub supervised: bool,
+}
+
+// ============================================================================
+// Extraction
+// ============================================================================
+
+/// Start extraction request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractStartRequest {
+    /// Project directory to extract to
+    pub project_dir: Option<String>,
+    /// Services to extract
+    pub services: Option<Vec<String>>,
+    /// Include terrain
+    pub include_terrain: Option<bool>,
+    /// Include binary assets
+    pub include_assets: Option<bool>,
+    /// Fail the extraction if any property can't be serialized
+    pub strict: Option<bool>,
+    /// Only extract instances that changed since the last extraction
+    #[serde(default)]
+    pub incremental: Option<bool>,
+}
+
+/// Response from `POST /extract/start`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractStartResponse {
+    pub session_id: String,
+    pub status: String,
+    pub limits: ExtractLimits,
+    /// Whether the extraction runs incrementally; false when it was asked
+    /// for but the project has no previous extraction to compare against
+    #[serde(default)]
+    pub incremental: bool,
+}
+
+/// Size limits advertised to the plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractLimits {
+    pub max_body_bytes: usize,
+    pub max_part_bytes: usize,
+    pub max_instance_bytes: usize,
+}
+
+/// Describes one part of an oversized instance; the request's `data` is the
+/// part's slice of the instance JSON, as a string
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkPart {
+    /// Identifies the instance across its parts (unique within the session)
+    pub instance_id: String,
+    /// Zero-based part index
+    pub index: usize,
+    pub total: usize,
+    /// Byte length of the full instance JSON
+    pub total_bytes: usize,
+    /// Adler-32 of the full instance JSON
+    pub checksum: u32,
+}
+
+/// Extraction chunk from the plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractChunkRequest {
+    pub session_id: String,
+    pub chunk_index: usize,
+    pub total_chunks: usize,
+    pub data: serde_json::Value,
+    pub project_dir: Option<String>,
+    /// Set when `data` is one part of an oversized instance's JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part: Option<ChunkPart>,
+    /// Instances in the whole chunk, when it is sent in pieces; the chunk
+    /// counts as received once that many have arrived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_count: Option<usize>,
+}
+
+/// Ask which chunks of an extraction still have to be sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractResumeRequest {
+    pub session_id: String,
+    pub project_dir: Option<String>,
+    /// Chunks in the extraction, if the server may not have seen any yet
+    #[serde(default)]
+    pub total_chunks: Option<usize>,
+}
+
+/// Response from `POST /extract/resume`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractResumeResponse {
+    pub session_id: String,
+    /// False when the server has nothing for this session; send everything
+    pub resumable: bool,
+    pub total_chunks: Option<usize>,
+    pub chunks_received: usize,
+    /// Chunk indices to (re-)send, in order
+    pub missing: Vec<usize>,
+}
+
+/// Finalize extraction - build proper file tree from chunks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinalizeRequest {
+    pub project_dir: String,
+    /// The chunks only hold instances that changed since the last extraction
+    #[serde(default)]
+    pub incremental: bool,
+    /// Paths of an incremental extraction that no longer exist in Studio
+    #[serde(default)]
+    pub removed: Vec<String>,
+}
+
+// ============================================================================
+// Sync
+// ============================================================================
+
+/// Sync command request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncCommandRequest {
+    pub command: String,
+    pub payload: serde_json::Value,
+}
+
+/// Sync batch request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncBatchRequest {
+    pub operations: Vec<serde_json::Value>,
+    /// Optional project directory for operation tracking (RBXSYNC-77)
+    #[serde(rename = "projectDir")]
+    pub project_dir: Option<String>,
+    /// Only apply operations on instances under these DataModel paths
+    /// (e.g. "ServerScriptService/Combat"); empty applies all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+}
+
+/// Sync changes from Studio back to files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncFromStudioRequest {
+    pub operations: Vec<StudioChangeOperation>,
+    #[serde(rename = "projectDir")]
+    pub project_dir: String,
+    /// Studio user who made the changes (for the audit log)
+    #[serde(rename = "userId", default)]
+    pub user_id: Option<u64>,
+    #[serde(rename = "placeName", default)]
+    pub place_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StudioChangeOperation {
+    #[serde(rename = "type")]
+    pub change_type: String,  // "create", "modify", "delete", "rename"
+    pub path: String,
+    #[serde(rename = "className")]
+    pub class_name: Option<String>,
+    pub data: Option<serde_json::Value>,
+}
+
+/// Read file tree for sync - returns all instances from project directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadTreeRequest {
+    pub project_dir: String,
+    /// Override variant (defaults to RBXSYNC_VARIANT, then the config's `variant`)
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// Only read instances under these DataModel paths; empty reads everything
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+}
+
+/// Request to check pending changes count
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingChangesRequest {
+    pub project_dir: String,
+}
+
+/// Request for incremental sync - returns only files changed since last sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncrementalSyncRequest {
+    pub project_dir: String,
+    /// If true, mark current time as last sync (call after successful sync)
+    #[serde(default)]
+    pub mark_synced: bool,
+}
+
+/// A Studio change held back because the instance's files were also edited
+/// since the last sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+    /// Instance path in the DataModel
+    pub path: String,
+    /// Path of the instance's files under `src/`, without extension
+    pub key: String,
+    pub class_name: Option<String>,
+    /// Unix seconds when the conflicting Studio change arrived
+    pub detected_at: u64,
+    /// The Studio change, applied as-is when resolving with Studio
+    pub studio: StudioChangeOperation,
+}
+
+/// List a project's sync conflicts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictsRequest {
+    pub project_dir: String,
+}
+
+/// Response from `POST /sync/conflicts`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictsResponse {
+    pub conflicts: Vec<SyncConflict>,
+}
+
+/// Resolve one sync conflict
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolveConflictRequest {
+    pub project_dir: String,
+    /// Instance path of the conflict
+    pub path: String,
+    /// "file" keeps the files, "studio" writes the Studio change, "merged"
+    /// writes the Studio change with `source`/`properties` replaced
+    pub resolution: String,
+    /// Merged script source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Merged properties, laid over the Studio change's properties
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<serde_json::Value>,
+}
+
+// ============================================================================
+// Diff
+// ============================================================================
+
+/// Diff request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffRequest {
+    pub project_dir: String,
+    /// Also compare property values and script sources (default: true)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_properties: Option<bool>,
+}
+
+/// Single diff entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffEntry {
+    pub path: String,
+    #[serde(rename = "className")]
+    pub class_name: String,
+}
+
+/// Diff result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffResult {
+    pub added: Vec<DiffEntry>,      // In files, not in Studio (would be created)
+    pub removed: Vec<DiffEntry>,    // In Studio, not in files (would be deleted)
+    /// In Studio only, but under a tree that ignores unknown instances (left alone)
+    #[serde(default)]
+    pub kept: usize,
+    /// In Studio only, but matched by `protectedPaths` (never deleted)
+    #[serde(default)]
+    pub protected: Vec<DiffEntry>,
+    pub moved: Vec<crate::MovedEntry>, // Same GUID, different path (from = Studio, to = files)
+    pub common: usize,              // In both
+    /// In both, with different properties (only when properties are compared)
+    #[serde(default)]
+    pub modified: Vec<crate::ModifiedEntry>,
+    pub file_count: usize,
+    pub studio_count: usize,
+}
+
+// ============================================================================
+// Git
+// ============================================================================
+
+/// Git project directory request (shared by all git endpoints)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitProjectRequest {
+    pub project_dir: String,
+}
+
+/// Git status request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitStatusRequest {
+    pub project_dir: String,
+}
+
+/// Git log request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLogRequest {
+    pub project_dir: String,
+    pub limit: Option<usize>,
+}
+
+/// Git commit request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitCommitRequest {
+    pub project_dir: String,
+    pub message: String,
+    pub add_all: Option<bool>,
+}
+
+/// Git repository status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitStatus {
+    pub branch: String,
+    pub is_dirty: bool,
+    pub staged_count: usize,
+    pub unstaged_count: usize,
+    pub untracked_count: usize,
+    pub ahead: usize,
+    pub behind: usize,
+    /// List of changed files (relative paths)
+    pub changed_files: Vec<ChangedFile>,
+}
+
+/// A changed file with its status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedFile {
+    pub path: String,
+    pub status: String, // "modified", "added", "deleted", "renamed", "untracked"
+}
+
+/// Git commit information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitCommit {
+    pub hash: String,
+    pub message: String,
+    pub author: String,
+    pub date: String,
+}
+
+/// What HEAD points at
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitHead {
+    /// Checked-out branch, None when HEAD is detached
+    pub branch: Option<String>,
+    /// Short commit hash, when it could be resolved
+    pub commit: Option<String>,
+}
+
+impl GitHead {
+    /// e.g. "main (1a2b3c4)" or "detached at 1a2b3c4"
+    pub fn label(&self) -> String {
+        let commit = self.commit.as_deref().unwrap_or("unknown commit");
+        match &self.branch {
+            Some(branch) => format!("{} ({})", branch, commit),
+            None => format!("detached at {}", commit),
+        }
+    }
+}
+
+/// A local branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitBranch {
+    pub name: String,
+    /// Whether it's checked out
+    pub current: bool,
+    /// Tracked remote branch, e.g. "origin/main"
+    pub upstream: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    /// Short hash and subject of its newest commit
+    pub commit: String,
+    pub subject: String,
+}
+
+/// Create a branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitBranchCreateRequest {
+    pub project_dir: String,
+    pub name: String,
+    /// Commit or branch to start from (default: HEAD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_point: Option<String>,
+    /// Check the new branch out
+    #[serde(default)]
+    pub checkout: bool,
+}
+
+/// Check out a branch or commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitCheckoutRequest {
+    pub project_dir: String,
+    pub target: String,
+}
+
+/// Pull or push
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitRemoteRequest {
+    pub project_dir: String,
+    /// Remote name (default: the upstream's, or `origin` when `branch` is set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    /// Remote branch (default: the current branch's upstream)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Pull with `--rebase` instead of merging
+    #[serde(default)]
+    pub rebase: bool,
+    /// Push with `--set-upstream`
+    #[serde(default)]
+    pub set_upstream: bool,
+}
+
+/// Outcome of a checkout, pull or push
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitRemoteResult {
+    /// HEAD afterwards
+    pub head: GitHead,
+    /// Nothing changed (already on the target, already up to date)
+    pub up_to_date: bool,
+    /// Files that differ between HEAD before and after (empty for push)
+    pub changed_files: Vec<String>,
+    /// git's own output
+    pub output: String,
+}
+
+/// Stash, or work with existing stashes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitStashRequest {
+    pub project_dir: String,
+    /// `push` (default), `pop`, `apply`, `drop` or `list`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    /// Message for `push`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Stash for `pop`, `apply` and `drop` (default: the newest, 0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+}
+
+/// A stash entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitStashEntry {
+    pub index: usize,
+    pub message: String,
+}
+
+/// Stashes left after a stash action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitStashResult {
+    pub stashes: Vec<GitStashEntry>,
+    /// git's own output (empty for `list`)
+    pub output: String,
+}
+
+// ============================================================================
+// Test runner
+// ============================================================================
+
+/// Response from test operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestConsoleMessage {
+    pub message: String,
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    pub timestamp: f64,
+}
+
+/// Test status response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestStatusResponse {
+    pub capturing: bool,
+    pub output: Vec<TestConsoleMessage>,
+    pub total_messages: usize,
+}
+
+/// Request for `POST /test/run`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestRunRequest {
+    /// `play` (default) or `run`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// Seconds before the playtest is stopped and the run fails (default 120)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Instances searched for `*.spec` modules, e.g. `ReplicatedStorage/Shared`
+    /// (default: ReplicatedStorage, ServerScriptService, ServerStorage)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roots: Vec<String>,
+    /// Luau script, relative to the project, to run instead of the built-in bootstrap
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bootstrap: Option<String>,
+    /// `studio` or `cloud` (default: `test.backend` in rbxsync.json, else studio)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_dir: Option<String>,
+}
+
+/// Outcome of one spec
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestCaseStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// Why a spec failed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestFailure {
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<String>,
+    /// Where it failed, resolved from the message or trace to the project's src tree
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+}
+
+/// One spec, like a JUnit `<testcase>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestCase {
+    pub name: String,
+    /// The `describe` blocks around the spec, joined with ` > `
+    #[serde(default)]
+    pub classname: String,
+    /// Seconds
+    #[serde(default)]
+    pub time: f64,
+    pub status: TestCaseStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<TestFailure>,
+}
+
+/// The specs of one module, like a JUnit `<testsuite>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestSuite {
+    pub name: String,
+    pub tests: usize,
+    pub failures: usize,
+    pub skipped: usize,
+    pub time: f64,
+    pub cases: Vec<TestCase>,
+}
+
+/// Result of `POST /test/run`, like a JUnit `<testsuites>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestReport {
+    /// Every spec passed and the run finished in time
+    pub success: bool,
+    /// `testez`, `spec` or `custom`
+    #[serde(default)]
+    pub framework: String,
+    pub tests: usize,
+    pub passed: usize,
+    pub failures: usize,
+    pub skipped: usize,
+    /// Seconds, including starting and stopping the playtest
+    pub time: f64,
+    #[serde(default)]
+    pub timed_out: bool,
+    /// Why the run produced no results (bootstrap error, timeout, Studio stopped)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub suites: Vec<TestSuite>,
+    /// Console output of the playtest (JUnit's `system-out`)
+    #[serde(default)]
+    pub output: Vec<String>,
+}
+
+// ============================================================================
+// Bot controller
+// ============================================================================
+
+/// Generic bot command request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BotCommandRequest {
+    #[serde(rename = "type")]
+    pub command_type: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: serde_json::Value,
+}
+
+/// Bot movement request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BotMoveRequest {
+    #[serde(default)]
+    pub position: Option<serde_json::Value>,
+    #[serde(default)]
+    pub object: Option<String>,
+    #[serde(rename = "objectName", default)]
+    pub object_name: Option<String>,
+}
+
+/// Bot action request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BotActionRequest {
+    pub action: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(rename = "toolName", default)]
+    pub tool_name: Option<String>,
+    #[serde(rename = "objectName", default)]
+    pub object_name: Option<String>,
+}
+
+/// Bot observe request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BotObserveRequest {
+    #[serde(rename = "type", default = "default_observe_type")]
+    pub observe_type: String,
+    #[serde(default)]
+    pub radius: Option<f64>,
+    #[serde(default)]
+    pub query: Option<String>,
+}
+
+impl Default for BotObserveRequest {
+    fn default() -> Self {
+        Self {
+            observe_type: default_observe_type(),
+            radius: None,
+            query: None,
+        }
+    }
+}
+
+fn default_observe_type() -> String {
+    "state".to_string()
+}
+
+/// Bot query server request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BotQueryServerRequest {
+    pub code: String,
+}
+
+/// `POST /bot/scenario/run`: a scenario as YAML or JSON text, or as a
+/// parsed object
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioRunRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scenario: Option<serde_json::Value>,
+}
+
+/// How a scenario step went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScenarioStepStatus {
+    Passed,
+    Failed,
+    /// Not run because an earlier step failed
+    Skipped,
+}
+
+/// One step of a scenario run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioStepResult {
+    /// Position in the scenario, from 1
+    pub index: usize,
+    /// "move", "action", "wait", "observe", "social", "query", "assert" or "faults"
+    pub kind: String,
+    /// Short description of the step, e.g. `health > 50`
+    pub description: String,
+    pub status: ScenarioStepStatus,
+    /// Seconds the step took
+    pub time: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// What the bot returned, for observe and query steps
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+}
+
+/// Result of `POST /bot/scenario/run`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioReport {
+    pub name: String,
+    pub success: bool,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub time: f64,
+    pub steps: Vec<ScenarioStepResult>,
+    /// Report of each fault run the scenario started (see `POST /bot/faults`):
+    /// seed, delivered, delayed and dropped counts, and the injected faults
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub faults: Vec<serde_json::Value>,
+}
+
+// ============================================================================
+// Console
+// ============================================================================
+
+/// A script location from a console message, resolved to a local file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceLocation {
+    /// DataModel path as it appeared in the message (dotted)
+    pub instance_path: String,
+    pub line: u32,
+    /// Absolute path of the script file
+    pub file: String,
+    /// `file:line`, the form editors and terminals open directly
+    pub link: String,
+}
+
+/// Console message from Studio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsoleMessage {
+    pub timestamp: String,
+    pub message_type: String,  // "info", "warn", "error"
+    pub message: String,
+    pub source: Option<String>,  // e.g., "sync", "extract", "plugin"
+    /// e.g. "production" for errors ingested from live servers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Occurrences this message stands for, when pre-aggregated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    /// Local files and lines referenced by the message, filled in on push
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<SourceLocation>,
+    /// Unix seconds when the server received the message, set on push
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub received_at: Option<u64>,
+}
+
+/// Request to push console message(s) from plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolePushRequest {
+    pub messages: Vec<ConsoleMessage>,
+}
+
+/// Query for `GET /console/history`; every filter is optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConsoleHistoryQuery {
+    /// Newest matching messages to return
+    pub limit: Option<usize>,
+    /// Minimum level: `info`, `warn` or `error`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    /// Only messages from this source (`studio`, `plugin`, ...); `production`
+    /// also matches `production:<placeId>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Only messages containing this text (case-insensitive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grep: Option<String>,
+    /// Treat `grep` as a regular expression
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<bool>,
+    /// Only messages received at or after this time: Unix seconds, or an age
+    /// such as `30s`, `10m`, `2h` or `1d`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// Only messages received at or before this time, in the same forms
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+}
+
+/// Response from `GET /console/history`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsoleHistoryResponse {
+    /// Most recent matching messages, oldest first
+    pub messages: Vec<ConsoleMessage>,
+    /// Messages in the buffer
+    pub total: usize,
+    /// Messages in the buffer that match the filters, before `limit`
+    #[serde(default)]
+    pub matched: usize,
+}
+
+/// Request for `POST /console/export`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConsoleExportRequest {
+    /// File to write; relative paths are resolved against the server's
+    /// working directory
+    pub path: String,
+    /// `text` or `jsonl` (default: `jsonl` for `.json`/`.jsonl` paths, else `text`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Same filters as the history; without a `limit` every match is written
+    #[serde(flatten)]
+    pub filter: ConsoleHistoryQuery,
+}
+
+/// One of the most frequent errors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopError {
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    pub count: u64,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub sources: Vec<String>,
+    /// `file:line` of the first resolved location
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+/// Response from `GET /console/errors/summary`: deduplicated errors in the
+/// console buffer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorSummary {
+    /// Distinct errors
+    pub unique: usize,
+    /// Error occurrences
+    pub total: u64,
+    /// Distinct warnings, and their occurrences
+    pub unique_warnings: usize,
+    pub warnings: u64,
+    /// Most frequent errors first
+    pub top: Vec<TopError>,
+    /// One line, e.g. `3 unique errors (52 total), top: attempt to index nil in Combat.server.luau:88 (x40)`
+    pub summary: String,
+}
+
+impl ErrorSummary {
+    /// The summary line, then one line per listed error, for agents
+    pub fn describe(&self) -> String {
+        let mut lines = vec![self.summary.clone()];
+        if self.warnings > 0 {
+            lines.push(format!("{} unique warnings ({} total)", self.unique_warnings, self.warnings));
+        }
+        for error in &self.top {
+            let location = error.link.as_ref().or(error.location.as_ref());
+            lines.push(format!(
+                "- x{} {}{} (first {}, last {})",
+                error.count,
+                error.message,
+                location.map(|l| format!(" at {}", l)).unwrap_or_default(),
+                error.first_seen,
+                error.last_seen
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+// ============================================================================
+// Harness
+// ============================================================================
+
+/// Request to initialize a harness for a project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarnessInitRequest {
+    /// Project directory path
+    pub project_dir: String,
+
+    /// Game name
+    pub game_name: String,
+
+    /// Optional game description
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Optional game genre
+    #[serde(default)]
+    pub genre: Option<String>,
+
+    /// Optional template to initialize with (tycoon, obby, simulator, rpg, horror)
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+/// Response from harness init
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarnessInitResponse {
+    pub success: bool,
+    pub message: String,
+    pub harness_dir: String,
+    pub game_id: Option<String>,
+    /// Template that was applied (if any)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_applied: Option<String>,
+    /// Number of features added from template
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub features_added: Option<usize>,
+}
+
+/// Request to start a new session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStartRequest {
+    /// Project directory path
+    pub project_dir: String,
+
+    /// Optional initial summary/goals for the session
+    #[serde(default)]
+    pub initial_goals: Option<String>,
+}
+
+/// Response from session start
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStartResponse {
+    pub success: bool,
+    pub message: String,
+    pub session_id: Option<String>,
+    pub session_path: Option<String>,
+}
+
+/// Request to end a session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEndRequest {
+    /// Project directory path
+    pub project_dir: String,
+
+    /// Session ID to end
+    pub session_id: String,
+
+    /// Summary of what was accomplished
+    #[serde(default)]
+    pub summary: Option<String>,
+
+    /// Handoff notes for future sessions
+    #[serde(default)]
+    pub handoff_notes: Option<Vec<String>>,
+}
+
+/// Response from session end
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEndResponse {
+    pub success: bool,
+    pub message: String,
+}
+
+/// Request to update a feature
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureUpdateRequest {
+    /// Project directory path
+    pub project_dir: String,
+
+    /// Feature ID (if updating existing feature)
+    #[serde(default)]
+    pub feature_id: Option<String>,
+
+    /// Feature name (required for new features)
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Feature description
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// New status
+    #[serde(default)]
+    pub status: Option<FeatureStatus>,
+
+    /// Priority
+    #[serde(default)]
+    pub priority: Option<FeaturePriority>,
+
+    /// Tags to add
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+
+    /// Acceptance criteria
+    #[serde(default)]
+    pub acceptance_criteria: Option<Vec<String>>,
+
+    /// Note to add
+    #[serde(default)]
+    pub add_note: Option<String>,
+
+    /// Files affected
+    #[serde(default)]
+    pub affected_files: Option<Vec<String>>,
+
+    /// Session ID working on this feature
+    #[serde(default)]
+    pub session_id: Option<String>,
+
+    /// Blocked reason (if setting status to blocked)
+    #[serde(default)]
+    pub blocked_reason: Option<String>,
+
+    /// Dependencies (feature IDs)
+    #[serde(default)]
+    pub dependencies: Option<Vec<String>>,
+
+    /// Complexity (1-5)
+    #[serde(default)]
+    pub complexity: Option<u8>,
+}
+
+/// Response from feature update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureUpdateResponse {
+    pub success: bool,
+    pub message: String,
+    pub feature_id: Option<String>,
+}
+
+/// Request for harness status
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarnessStatusRequest {
+    /// Project directory path
+    pub project_dir: String,
+}
+
+/// Response with harness status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarnessStatusResponse {
+    pub success: bool,
+    pub initialized: bool,
+    pub game: Option<GameDefinition>,
+    pub features: Vec<Feature>,
+    pub feature_summary: FeatureSummary,
+    pub recent_sessions: Vec<SessionSummary>,
+}
+
+/// Summary of feature statuses
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureSummary {
+    pub total: usize,
+    pub planned: usize,
+    pub in_progress: usize,
+    pub completed: usize,
+    pub blocked: usize,
+    pub cancelled: usize,
+}
+
+/// Brief session summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub id: String,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub summary: String,
+    pub features_count: usize,
+}
@@ -0,0 +1,485 @@
+//! Asset references and the `assets/` manifest
+//!
+//! Extraction records every asset a place uses (meshes, images, sounds and
+//! animations referenced by `Content` properties such as `MeshPart.MeshId`,
+//! `Decal.Texture` or `Sound.SoundId`) in `assets/manifest.json`, along with
+//! the properties that use each one. The server can also download their
+//! content into `assets/<kind>/`, and records the file in the manifest.
+//!
+//! A `.rbxjson` can point at a downloaded file instead of the URL, e.g.
+//! `{ "type": "Content", "value": "assets/meshes/1234.mesh" }`. Sync and build
+//! resolve such values back to the URL the manifest has for that file
+//! ([`resolve_assets`], [`resolve_dom_assets`]); Studio only loads assets by URL.
+//!
+//! Built-in `rbxasset://` content ships with Studio, so it's recorded but
+//! never downloaded.
+//!
+//! Local files can also be uploaded through Open Cloud; [`upload_type`] says
+//! what a file uploads as, and [`rewrite_file_references`] points the
+//! `.rbxjson` values naming the file at the new asset.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use rbx_dom_weak::types::{Content, Variant};
+use rbx_dom_weak::WeakDom;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Manifest file name inside the assets directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Kind of asset, named as in `config.binaryAssetTypes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum AssetKind {
+    Mesh,
+    Image,
+    Sound,
+    Animation,
+}
+
+impl AssetKind {
+    /// Kind of asset a Content property holds: `MeshId`, `SoundId` and
+    /// `AnimationId` by name, and images (textures, decals, skyboxes) otherwise
+    pub fn of_property(property: &str) -> Self {
+        if property.ends_with("MeshId") {
+            AssetKind::Mesh
+        } else if property.ends_with("SoundId") {
+            AssetKind::Sound
+        } else if property.ends_with("AnimationId") {
+            AssetKind::Animation
+        } else {
+            AssetKind::Image
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AssetKind::Mesh => "Mesh",
+            AssetKind::Image => "Image",
+            AssetKind::Sound => "Sound",
+            AssetKind::Animation => "Animation",
+        }
+    }
+
+    /// Directory under `assets/` downloads of this kind go in
+    pub fn dir(self) -> &'static str {
+        match self {
+            AssetKind::Mesh => "meshes",
+            AssetKind::Image => "images",
+            AssetKind::Sound => "sounds",
+            AssetKind::Animation => "animations",
+        }
+    }
+
+    /// File extension for downloaded content, from its first bytes
+    pub fn extension(self, content: &[u8]) -> &'static str {
+        if content.starts_with(b"\x89PNG") {
+            "png"
+        } else if content.starts_with(b"\xFF\xD8") {
+            "jpg"
+        } else if content.starts_with(b"OggS") {
+            "ogg"
+        } else if content.starts_with(b"ID3") || content.starts_with(b"\xFF\xFB") {
+            "mp3"
+        } else if content.starts_with(b"<roblox!") {
+            "rbxm"
+        } else if content.starts_with(b"<roblox") {
+            "rbxmx"
+        } else {
+            match self {
+                AssetKind::Mesh => "mesh",
+                AssetKind::Image => "png",
+                AssetKind::Sound => "ogg",
+                AssetKind::Animation => "rbxm",
+            }
+        }
+    }
+}
+
+/// `assets/manifest.json`: each asset URL and what uses it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetManifest {
+    pub assets: BTreeMap<String, AssetEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetEntry {
+    pub kind: AssetKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    /// Downloaded content, relative to the project (e.g. `assets/meshes/1234.mesh`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Properties using the asset, as `Instance/Path.Property`
+    #[serde(default)]
+    pub used_by: BTreeSet<String>,
+}
+
+/// Where downloaded content comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetSource {
+    /// Asset delivery, by asset id
+    Asset(u64),
+    /// Thumbnail API: the id and `WxH` size of an `rbxthumb://` URL
+    Thumbnail { id: u64, size: String },
+}
+
+impl AssetManifest {
+    /// Read a project's manifest; empty when there's none yet
+    pub fn load(project_dir: &Path) -> Self {
+        std::fs::read_to_string(assets_dir(project_dir).join(MANIFEST_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, project_dir: &Path) -> std::io::Result<()> {
+        let dir = assets_dir(project_dir);
+        std::fs::create_dir_all(&dir)?;
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(dir.join(MANIFEST_FILE), json)
+    }
+
+    /// Replace what uses each asset with the Content properties of a whole
+    /// extraction. Assets no longer used are dropped; downloaded files are kept.
+    /// Only kinds in `kinds` (names as in `config.binaryAssetTypes`) are recorded.
+    pub fn record_instances(&mut self, instances: &[Value], kinds: &HashSet<String>) -> usize {
+        self.clear_usage();
+        self.add_usage(instances, kinds);
+        self.drop_unused()
+    }
+
+    /// Forget what uses each asset, before recording an extraction in parts
+    pub fn clear_usage(&mut self) {
+        for entry in self.assets.values_mut() {
+            entry.used_by.clear();
+        }
+    }
+
+    /// Record the Content properties of some of an extraction's instances
+    pub fn add_usage(&mut self, instances: &[Value], kinds: &HashSet<String>) {
+        for instance in instances {
+            let path = instance.get("path").and_then(|p| p.as_str()).unwrap_or_default();
+            let Some(properties) = instance.get("properties").and_then(|p| p.as_object()) else {
+                continue;
+            };
+            for (property, value) in properties {
+                let Some(url) = content_url(value).filter(|url| is_asset_url(url)) else {
+                    continue;
+                };
+                let kind = AssetKind::of_property(property);
+                if !kinds.contains(kind.as_str()) {
+                    continue;
+                }
+                self.assets
+                    .entry(url.to_string())
+                    .or_insert_with(|| AssetEntry { kind, id: asset_id(url), file: None, used_by: BTreeSet::new() })
+                    .used_by
+                    .insert(format!("{}.{}", path, property));
+            }
+        }
+    }
+
+    /// Drop assets nothing uses once an extraction is recorded; returns how
+    /// many are left
+    pub fn drop_unused(&mut self) -> usize {
+        self.assets.retain(|_, entry| !entry.used_by.is_empty());
+        self.assets.len()
+    }
+
+    /// Record a file uploaded as `url`, replacing any entry for the same file.
+    /// What uses it is filled in by the next extraction.
+    pub fn record_upload(&mut self, url: &str, kind: AssetKind, file: &str) {
+        let file = file.trim_start_matches("./");
+        self.assets.retain(|_, entry| entry.file.as_deref() != Some(file));
+        self.assets.insert(
+            url.to_string(),
+            AssetEntry { kind, id: asset_id(url), file: Some(file.to_string()), used_by: BTreeSet::new() },
+        );
+    }
+
+    /// URL recorded for a downloaded file
+    pub fn url_for_file(&self, file: &str) -> Option<&str> {
+        let file = file.trim_start_matches("./");
+        self.assets
+            .iter()
+            .find(|(_, entry)| entry.file.as_deref() == Some(file))
+            .map(|(url, _)| url.as_str())
+    }
+}
+
+/// A project's assets directory: `assets` in rbxsync.json, `./assets` by default
+pub fn assets_dir(project_dir: &Path) -> PathBuf {
+    let configured = std::fs::read_to_string(project_dir.join("rbxsync.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|config| config.get("assets")?.as_str().map(str::to_string));
+    project_dir.join(configured.as_deref().unwrap_or("assets"))
+}
+
+/// Whether a Content value refers to an asset (rather than being empty or a local file)
+pub fn is_asset_url(url: &str) -> bool {
+    url.starts_with("rbxassetid://")
+        || url.starts_with("rbxasset://")
+        || url.starts_with("rbxthumb://")
+        || (url.starts_with("http") && url.contains("roblox.com/") && asset_id(url).is_some())
+}
+
+/// Asset id of `rbxassetid://`, `rbxthumb://` and roblox.com asset URLs
+pub fn asset_id(url: &str) -> Option<u64> {
+    if let Some(id) = url.strip_prefix("rbxassetid://") {
+        return id.trim().parse().ok();
+    }
+    query_param(url, "id")?.parse().ok()
+}
+
+/// Where to download an asset from; `None` for built-in `rbxasset://` content
+pub fn download_source(url: &str) -> Option<AssetSource> {
+    if url.starts_with("rbxasset://") {
+        return None;
+    }
+    let id = asset_id(url)?;
+    if url.starts_with("rbxthumb://") {
+        let width = query_param(url, "w").unwrap_or("420");
+        let height = query_param(url, "h").unwrap_or(width);
+        return Some(AssetSource::Thumbnail { id, size: format!("{}x{}", width, height) });
+    }
+    Some(AssetSource::Asset(id))
+}
+
+/// File to download an asset to, relative to the assets directory and without
+/// its extension
+pub fn download_stem(url: &str, kind: AssetKind) -> Option<String> {
+    let name = match download_source(url)? {
+        AssetSource::Asset(id) => id.to_string(),
+        AssetSource::Thumbnail { id, size } => format!("{}-thumb-{}", id, size),
+    };
+    Some(format!("{}/{}", kind.dir(), name))
+}
+
+/// What a local file uploads as through Open Cloud: the kind of asset it
+/// becomes, the Open Cloud `assetType` and the file's content type. `None`
+/// for file types Open Cloud doesn't take.
+pub fn upload_type(file: &Path) -> Option<(AssetKind, &'static str, &'static str)> {
+    let extension = file.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match extension.as_str() {
+        "png" => (AssetKind::Image, "Decal", "image/png"),
+        "jpg" | "jpeg" => (AssetKind::Image, "Decal", "image/jpeg"),
+        "bmp" => (AssetKind::Image, "Decal", "image/bmp"),
+        "tga" => (AssetKind::Image, "Decal", "image/tga"),
+        "mp3" => (AssetKind::Sound, "Audio", "audio/mpeg"),
+        "ogg" => (AssetKind::Sound, "Audio", "audio/ogg"),
+        "wav" => (AssetKind::Sound, "Audio", "audio/wav"),
+        "flac" => (AssetKind::Sound, "Audio", "audio/flac"),
+        "fbx" => (AssetKind::Mesh, "Model", "model/fbx"),
+        "gltf" => (AssetKind::Mesh, "Model", "model/gltf+json"),
+        "glb" => (AssetKind::Mesh, "Model", "model/gltf-binary"),
+        "rbxm" => (AssetKind::Animation, "Animation", "model/x-rbxm"),
+        _ => return None,
+    })
+}
+
+/// Point every Content value in `src_dir`'s `.rbxjson` files that names
+/// `file` (relative to the project) at `url`. Returns the properties changed,
+/// as `file.rbxjson:Property`.
+pub fn rewrite_file_references(src_dir: &Path, file: &str, url: &str) -> std::io::Result<Vec<String>> {
+    let file = file.trim_start_matches("./");
+    let mut changed = Vec::new();
+    let mut dirs = vec![src_dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+            if path.extension().and_then(|e| e.to_str()) != Some("rbxjson") {
+                continue;
+            }
+            let Some(mut instance) = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            else {
+                continue;
+            };
+            let Some(properties) = instance.get_mut("properties").and_then(|p| p.as_object_mut()) else {
+                continue;
+            };
+            let mut rewritten = Vec::new();
+            for (property, value) in properties.iter_mut() {
+                if content_url(value).map(|v| v.trim_start_matches("./")) == Some(file) {
+                    value["value"] = Value::String(url.to_string());
+                    rewritten.push(property.clone());
+                }
+            }
+            if rewritten.is_empty() {
+                continue;
+            }
+            let json = serde_json::to_string_pretty(&instance).map_err(std::io::Error::other)?;
+            std::fs::write(&path, json)?;
+            let relative = crate::path_to_string(path.strip_prefix(src_dir).unwrap_or(&path));
+            changed.extend(rewritten.into_iter().map(|property| format!("{}:{}", relative, property)));
+        }
+    }
+    changed.sort();
+    Ok(changed)
+}
+
+/// Replace Content values that name a downloaded file with the asset's URL.
+/// Returns the file references the manifest doesn't know.
+pub fn resolve_assets(instance: &mut Value, manifest: &AssetManifest) -> Vec<String> {
+    let mut unresolved = Vec::new();
+    let Some(properties) = instance.get_mut("properties").and_then(|p| p.as_object_mut()) else {
+        return unresolved;
+    };
+    for value in properties.values_mut() {
+        let Some(file) = content_url(value).filter(|v| is_asset_file(v)).map(str::to_string) else {
+            continue;
+        };
+        match manifest.url_for_file(&file) {
+            Some(url) => value["value"] = Value::String(url.to_string()),
+            None => unresolved.push(file),
+        }
+    }
+    unresolved
+}
+
+/// [`resolve_assets`] for a built DOM
+pub fn resolve_dom_assets(dom: &mut WeakDom, manifest: &AssetManifest) -> Vec<String> {
+    let mut unresolved = Vec::new();
+    let referents: Vec<_> = dom.descendants().map(|i| i.referent()).collect();
+    for referent in referents {
+        let Some(instance) = dom.get_by_ref_mut(referent) else {
+            continue;
+        };
+        for value in instance.properties.values_mut() {
+            let file = match value {
+                Variant::Content(content) if is_asset_file(content.as_ref()) => AsRef::<str>::as_ref(content).to_string(),
+                _ => continue,
+            };
+            match manifest.url_for_file(&file) {
+                Some(url) => *value = Variant::Content(Content::from(url.to_string())),
+                None => unresolved.push(file),
+            }
+        }
+    }
+    unresolved
+}
+
+/// The URL of a `{ "type": "Content", "value": ... }` property value
+fn content_url(value: &Value) -> Option<&str> {
+    if value.get("type").and_then(|t| t.as_str()) != Some("Content") {
+        return None;
+    }
+    value.get("value")?.as_str().filter(|url| !url.is_empty())
+}
+
+/// A project path rather than a URL
+fn is_asset_file(value: &str) -> bool {
+    !value.is_empty() && !value.contains("://")
+}
+
+/// Value of `key` in a URL's query (or an `rbxthumb://` parameter list)
+fn query_param<'a>(url: &'a str, key: &str) -> Option<&'a str> {
+    url.split(['?', '&'])
+        .find_map(|pair| pair.strip_prefix(key)?.strip_prefix('='))
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_asset_urls() {
+        assert_eq!(asset_id("rbxassetid://1234"), Some(1234));
+        assert_eq!(asset_id("http://www.roblox.com/asset/?id=55"), Some(55));
+        assert_eq!(asset_id("rbxthumb://type=Asset&id=7&w=150&h=150"), Some(7));
+        assert_eq!(asset_id("rbxasset://textures/face.png"), None);
+        assert!(is_asset_url("rbxasset://textures/face.png"));
+        assert!(!is_asset_url("assets/meshes/1234.mesh"));
+
+        assert_eq!(download_source("rbxassetid://1234"), Some(AssetSource::Asset(1234)));
+        assert_eq!(
+            download_source("rbxthumb://type=Asset&id=7&w=150&h=150"),
+            Some(AssetSource::Thumbnail { id: 7, size: "150x150".to_string() })
+        );
+        assert_eq!(download_source("rbxasset://textures/face.png"), None);
+        assert_eq!(download_stem("rbxassetid://1234", AssetKind::Mesh).as_deref(), Some("meshes/1234"));
+        assert_eq!(AssetKind::Image.extension(b"\x89PNG\r\n"), "png");
+        assert_eq!(AssetKind::Mesh.extension(b"version 4.00"), "mesh");
+    }
+
+    #[test]
+    fn test_record_and_resolve() {
+        let instances = vec![
+            json!({ "path": "Workspace/Sword", "className": "MeshPart", "properties": {
+                "MeshId": { "type": "Content", "value": "rbxassetid://1234" },
+                "TextureID": { "type": "Content", "value": "rbxassetid://99" }
+            }}),
+            json!({ "path": "Workspace/Music", "className": "Sound", "properties": {
+                "SoundId": { "type": "Content", "value": "rbxassetid://42" },
+                "Volume": { "type": "float", "value": 0.5 }
+            }}),
+        ];
+        let kinds: HashSet<String> = ["Mesh", "Image"].iter().map(|s| s.to_string()).collect();
+        let mut manifest = AssetManifest::default();
+        assert_eq!(manifest.record_instances(&instances, &kinds), 2);
+        let mesh = &manifest.assets["rbxassetid://1234"];
+        assert_eq!(mesh.kind, AssetKind::Mesh);
+        assert_eq!(mesh.id, Some(1234));
+        assert!(mesh.used_by.contains("Workspace/Sword.MeshId"));
+        assert!(!manifest.assets.contains_key("rbxassetid://42"), "sounds weren't asked for");
+
+        manifest.assets.get_mut("rbxassetid://1234").unwrap().file = Some("assets/meshes/1234.mesh".to_string());
+        let mut part = json!({ "className": "MeshPart", "properties": {
+            "MeshId": { "type": "Content", "value": "assets/meshes/1234.mesh" },
+            "TextureID": { "type": "Content", "value": "./assets/images/missing.png" }
+        }});
+        let unresolved = resolve_assets(&mut part, &manifest);
+        assert_eq!(part["properties"]["MeshId"]["value"], "rbxassetid://1234");
+        assert_eq!(unresolved, vec!["./assets/images/missing.png".to_string()]);
+
+        // Re-recording keeps the downloaded file
+        manifest.record_instances(&instances, &kinds);
+        assert_eq!(manifest.assets["rbxassetid://1234"].file.as_deref(), Some("assets/meshes/1234.mesh"));
+    }
+
+    #[test]
+    fn test_upload_and_rewrite() {
+        assert_eq!(upload_type(Path::new("assets/images/Logo.PNG")), Some((AssetKind::Image, "Decal", "image/png")));
+        assert_eq!(upload_type(Path::new("assets/sounds/hit.ogg")).map(|t| t.1), Some("Audio"));
+        assert_eq!(upload_type(Path::new("notes.txt")), None);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir_all(src.join("Workspace/Board")).unwrap();
+        std::fs::write(
+            src.join("Workspace/Board/Decal.rbxjson"),
+            r#"{ "className": "Decal", "properties": { "Texture": { "type": "Content", "value": "./assets/images/logo.png" } } }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            src.join("Workspace/Other.rbxjson"),
+            r#"{ "className": "Decal", "properties": { "Texture": { "type": "Content", "value": "rbxassetid://1" } } }"#,
+        )
+        .unwrap();
+
+        let changed = rewrite_file_references(&src, "assets/images/logo.png", "rbxassetid://777").unwrap();
+        assert_eq!(changed, vec!["Workspace/Board/Decal.rbxjson:Texture".to_string()]);
+        let decal: Value =
+            serde_json::from_str(&std::fs::read_to_string(src.join("Workspace/Board/Decal.rbxjson")).unwrap()).unwrap();
+        assert_eq!(decal["properties"]["Texture"]["value"], "rbxassetid://777");
+
+        let mut manifest = AssetManifest::default();
+        manifest.record_upload("rbxassetid://777", AssetKind::Image, "./assets/images/logo.png");
+        assert_eq!(manifest.url_for_file("assets/images/logo.png"), Some("rbxassetid://777"));
+        assert_eq!(manifest.assets["rbxassetid://777"].id, Some(777));
+    }
+}
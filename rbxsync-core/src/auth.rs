@@ -0,0 +1,177 @@
+//! API token shared by the server and its clients
+//!
+//! A server started with `rbxsync serve --auth` requires this token on every
+//! mutating request. It comes from `RBXSYNC_TOKEN` if set, else from
+//! `~/.rbxsync/token`, which is created on first use. The CLI, MCP server
+//! and VS Code extension read the same file and send the token on their own.
+//!
+//! A team server can also accept per-user tokens. Their secrets live in the
+//! OS keychain under [`KEYCHAIN_SERVICE`], with only the user names in
+//! `~/.rbxsync/users.json`. A client signed in with `rbxsync login` keeps its
+//! token in the keychain too, and sends it in place of the token file.
+
+use std::path::{Path, PathBuf};
+
+use rand::Rng;
+
+pub use keyring::Error as KeychainError;
+
+/// Overrides the token file on both sides
+pub const TOKEN_ENV: &str = "RBXSYNC_TOKEN";
+
+/// Request header carrying the token
+pub const TOKEN_HEADER: &str = "x-rbxsync-token";
+
+/// Request header naming the rbxsync tool that sent a raw upload. A web page
+/// can't add it without a CORS preflight, which the server never approves.
+pub const CLIENT_HEADER: &str = "x-rbxsync-client";
+
+/// Keychain service holding login and per-user tokens
+pub const KEYCHAIN_SERVICE: &str = "rbxsync";
+
+/// Keychain account for the token saved by `rbxsync login`
+pub const LOGIN_ACCOUNT: &str = "login";
+
+/// `~/.rbxsync/token`
+pub fn token_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".rbxsync").join("token"))
+}
+
+/// Token clients should send: `RBXSYNC_TOKEN`, else the login token in the
+/// keychain, else the token file
+pub fn client_token() -> Option<String> {
+    std::env::var(TOKEN_ENV)
+        .ok()
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+        .or_else(login_token)
+        .or_else(|| read_token(&token_path()?))
+}
+
+/// The `rbxsync login` token, looked up once per process: a keychain query
+/// can be slow or even prompt the user, and clients ask on every request
+fn login_token() -> Option<String> {
+    static LOGIN_TOKEN: std::sync::OnceLock<Option<String>> = std::sync::OnceLock::new();
+    LOGIN_TOKEN.get_or_init(|| keychain_token(LOGIN_ACCOUNT)).clone()
+}
+
+/// Token stored in the OS keychain for `account`, if any
+pub fn keychain_token(account: &str) -> Option<String> {
+    let token = keyring::Entry::new(KEYCHAIN_SERVICE, account).ok()?.get_password().ok()?;
+    let token = token.trim().to_string();
+    (!token.is_empty()).then_some(token)
+}
+
+/// Store a token in the OS keychain, replacing any previous one
+pub fn store_keychain_token(account: &str, token: &str) -> Result<(), KeychainError> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, account)?.set_password(token)
+}
+
+/// Remove a token from the OS keychain. Succeeds if there was none.
+pub fn delete_keychain_token(account: &str) -> Result<(), KeychainError> {
+    match keyring::Entry::new(KEYCHAIN_SERVICE, account)?.delete_credential() {
+        Err(KeychainError::NoEntry) => Ok(()),
+        result => result,
+    }
+}
+
+/// `~/.rbxsync/users.json`: names of the users a team server accepts
+pub fn users_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".rbxsync").join("users.json"))
+}
+
+/// Keychain account holding a team user's token
+pub fn user_account(name: &str) -> String {
+    format!("user:{}", name)
+}
+
+/// Team user names, sorted. Missing or unreadable files have none.
+pub fn load_users(path: &Path) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Write the team user names
+pub fn save_users(path: &Path, users: &[String]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut users = users.to_vec();
+    users.sort();
+    users.dedup();
+    std::fs::write(path, serde_json::to_string_pretty(&users)?)
+}
+
+/// Token in a token file, if it has one
+pub fn read_token(path: &Path) -> Option<String> {
+    let token = std::fs::read_to_string(path).ok()?.trim().to_string();
+    (!token.is_empty()).then_some(token)
+}
+
+/// 64 hex characters from the thread RNG
+pub fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Write a new token, readable only by the current user
+pub fn write_token(path: &Path, token: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, format!("{}\n", token))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+/// Token for a server to require: `RBXSYNC_TOKEN`, else the token file,
+/// generating one if there isn't one yet
+pub fn server_token(path: &Path) -> std::io::Result<String> {
+    if let Some(token) = std::env::var(TOKEN_ENV).ok().filter(|t| !t.trim().is_empty()) {
+        return Ok(token.trim().to_string());
+    }
+    if let Some(token) = read_token(path) {
+        return Ok(token);
+    }
+    let token = generate_token();
+    write_token(path, &token)?;
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_token_is_created_once() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("nested").join("token");
+
+        let token = server_token(&path).unwrap();
+        assert_eq!(token.len(), 64);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(server_token(&path).unwrap(), token);
+        assert_eq!(read_token(&path), Some(token.clone()));
+
+        std::fs::write(&path, "  \n").unwrap();
+        assert_eq!(read_token(&path), None);
+        assert_ne!(server_token(&path).unwrap(), token);
+    }
+
+    #[test]
+    fn test_users_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("users.json");
+        assert!(load_users(&path).is_empty());
+
+        save_users(&path, &["bob".to_string(), "alice".to_string(), "bob".to_string()]).unwrap();
+        assert_eq!(load_users(&path), vec!["alice", "bob"]);
+        assert_eq!(user_account("alice"), "user:alice");
+    }
+}
@@ -0,0 +1,1173 @@
+//! Headless place and model building
+//!
+//! Turns a project's `src` directory into a `WeakDom` and writes it as
+//! `.rbxl`, `.rbxm`, `.rbxlx` or `.rbxmx`. This is the pipeline behind
+//! `rbxsync build`, the server's `/build` endpoint and nightly builds, and it
+//! can be used directly:
+//!
+//! ```no_run
+//! use rbxsync_core::builder::{build_project, BuildFormat, BuildOptions};
+//!
+//! let options = BuildOptions { format: BuildFormat::Rbxl, ..Default::default() };
+//! let report = build_project("my-game".as_ref(), "my-game/build/game.rbxl".as_ref(), &options)?;
+//! println!("{} instances, {} scripts", report.instances, report.scripts);
+//! # Ok::<(), rbxsync_core::builder::BuildError>(())
+//! ```
+//!
+//! File layout follows extraction: directories are services and folders (or
+//! the class in `_meta.rbxjson`), `.rbxjson` files are instances, `.luau`
+//! files are scripts, `init*.luau` makes a directory a script, and a script
+//! with children is a `Name.server.luau` next to a `Name/` directory. Property
+//! values that can't be converted are dropped and listed in the report.
+//! Content values naming a downloaded asset file are resolved to the asset's
+//! URL through the [asset manifest](crate::assets), and the selected
+//! [override variant](crate::overrides) is applied last. Script sources then
+//! get their [define tokens](crate::defines) replaced and, with `obfuscate`
+//! set, go through the [obfuscator](crate::obfuscator).
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+use rbx_dom_weak::types::{Enum, Ref, Variant};
+use rbx_dom_weak::{InstanceBuilder, WeakDom};
+use serde::{Deserialize, Serialize};
+
+use crate::assets::{resolve_dom_assets, AssetManifest};
+use crate::defines::{define_text, Defines};
+use crate::obfuscator::{ObfuscationSettings, ObfuscationStats, Obfuscator};
+use crate::overrides::{AppliedOverride, OverrideReport, Overrides, RUN_CONTEXTS};
+use crate::types::PropertyValue;
+use crate::unsupported::{unsupported_type, UnsupportedProperty, UnsupportedReport};
+use crate::serde_props::is_known_property;
+use crate::sync_filter::SyncFilter;
+pub use crate::serde_props::json_to_variant;
+
+/// Output file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildFormat {
+    /// Binary place
+    #[default]
+    Rbxl,
+    /// Binary model
+    Rbxm,
+    /// XML place
+    Rbxlx,
+    /// XML model
+    Rbxmx,
+}
+
+impl BuildFormat {
+    /// Parse a format name: `rbxl`/`place`, `rbxm`/`model`, `rbxlx`/`place-xml`, `rbxmx`/`model-xml`
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "rbxl" | "place" => Some(Self::Rbxl),
+            "rbxm" | "model" => Some(Self::Rbxm),
+            "rbxlx" | "place-xml" => Some(Self::Rbxlx),
+            "rbxmx" | "model-xml" => Some(Self::Rbxmx),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Rbxl => "rbxl",
+            Self::Rbxm => "rbxm",
+            Self::Rbxlx => "rbxlx",
+            Self::Rbxmx => "rbxmx",
+        }
+    }
+
+    /// Places have a DataModel root; models a Folder
+    pub fn is_place(self) -> bool {
+        matches!(self, Self::Rbxl | Self::Rbxlx)
+    }
+
+    pub fn is_xml(self) -> bool {
+        matches!(self, Self::Rbxlx | Self::Rbxmx)
+    }
+}
+
+/// How to build a project
+#[derive(Debug, Clone, Default)]
+pub struct BuildOptions {
+    pub format: BuildFormat,
+    /// Override variant; `None` falls back to `RBXSYNC_VARIANT`, then the config's `variant`
+    pub variant: Option<String>,
+    /// Fail instead of dropping property values that can't be built
+    pub strict: bool,
+    /// Obfuscate script sources, limited by the config's `obfuscation` section
+    pub obfuscate: bool,
+    /// Defines on top of the config's `defines` (e.g. from `--define`)
+    pub defines: BTreeMap<String, String>,
+    /// Instance path globs left out of the build (e.g. `**/Tests/**`)
+    pub exclude: Vec<String>,
+}
+
+/// What a build produced
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildReport {
+    /// Instances in the output, not counting the root
+    pub instances: usize,
+    pub scripts: usize,
+    /// Property values dropped because they can't be built, grouped by class and type
+    pub unsupported: Vec<UnsupportedProperty>,
+    /// Properties the reflection database doesn't know on their class; they're still written
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unknown: Vec<UnsupportedProperty>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<OverrideReport>,
+    /// Content values naming a file under `assets/` the asset manifest has no URL for
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unresolved_assets: Vec<String>,
+    /// Instances left out through `exclude`, counting each removed subtree once
+    #[serde(skip_serializing_if = "is_zero")]
+    pub excluded: usize,
+    /// Define tokens replaced in script sources
+    pub defines: usize,
+    /// What the obfuscator changed, when the build was obfuscated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub obfuscation: Option<ObfuscationStats>,
+    /// Size of the written file (0 until written)
+    pub bytes: u64,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+impl BuildReport {
+    /// Property values dropped across all instances
+    pub fn dropped(&self) -> usize {
+        self.unsupported.iter().map(|u| u.count).sum()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BuildError {
+    #[error("Source directory not found: {0}")]
+    MissingSource(PathBuf),
+
+    #[error("Model not found in src: {0}")]
+    MissingModel(String),
+
+    #[error("Failed to read project files: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Strict mode: {count} property values can't be built:\n{summary}")]
+    Unbuildable { count: usize, summary: String },
+
+    #[error("{0}")]
+    Overrides(String),
+
+    #[error("Obfuscation: {0}")]
+    Obfuscation(String),
+
+    #[error("Failed to write output file: {0}")]
+    Write(String),
+
+    #[error("Failed to read the built file back: {0}")]
+    ReadBack(String),
+}
+
+/// Build a project and write it to `output`
+pub fn build_project(project_dir: &Path, output: &Path, options: &BuildOptions) -> Result<BuildReport, BuildError> {
+    let (dom, mut report) = build_dom(project_dir, options)?;
+    report.bytes = write_dom(&dom, output, options.format)?;
+    Ok(report)
+}
+
+/// Build a project's `src` directory into a DOM, applying the selected override variant
+pub fn build_dom(project_dir: &Path, options: &BuildOptions) -> Result<(WeakDom, BuildReport), BuildError> {
+    build_dom_at(project_dir, None, options)
+}
+
+/// A subtree of `src` built as its own model (`"models"` in rbxsync.json)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelTarget {
+    /// Instance path of the subtree, e.g. `ReplicatedStorage/UIKit`
+    pub path: String,
+    /// Output file, relative to the project directory
+    pub output: PathBuf,
+}
+
+impl ModelTarget {
+    /// Format from the output's extension; binary unless it's `.rbxmx`
+    pub fn format(&self) -> BuildFormat {
+        match self.output.extension().and_then(|e| e.to_str()) {
+            Some("rbxmx") => BuildFormat::Rbxmx,
+            _ => BuildFormat::Rbxm,
+        }
+    }
+}
+
+/// The `"models"` section of rbxsync.json: instance path -> output file
+pub fn model_targets(config: &serde_json::Value) -> Vec<ModelTarget> {
+    let mut targets: Vec<ModelTarget> = config
+        .get("models")
+        .and_then(|m| m.as_object())
+        .into_iter()
+        .flatten()
+        .filter_map(|(path, output)| {
+            Some(ModelTarget { path: crate::normalize_instance_root(path), output: PathBuf::from(output.as_str()?) })
+        })
+        .collect();
+    targets.sort_by(|a, b| a.path.cmp(&b.path));
+    targets
+}
+
+/// A named set of build settings (`"profiles"` in rbxsync.json), e.g. a
+/// `release` profile that obfuscates and leaves out tests
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildProfile {
+    /// Output file, relative to the project directory
+    #[serde(default)]
+    pub output: Option<PathBuf>,
+    /// Output format; defaults to the output's extension
+    #[serde(default)]
+    pub format: Option<BuildFormat>,
+    /// Override variant
+    #[serde(default)]
+    pub variant: Option<String>,
+    #[serde(default)]
+    pub strict: bool,
+    #[serde(default)]
+    pub obfuscate: bool,
+    /// Defines on top of the config's `defines`
+    #[serde(default)]
+    pub defines: BTreeMap<String, serde_json::Value>,
+    /// Instance path globs left out of the build
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl BuildProfile {
+    /// The profile called `name` in an rbxsync.json value
+    pub fn from_config(config: &serde_json::Value, name: &str) -> Result<Self, String> {
+        let profiles = config.get("profiles").and_then(|p| p.as_object());
+        let Some(profile) = profiles.and_then(|p| p.get(name)) else {
+            let known: Vec<&str> = profiles.map(|p| p.keys().map(String::as_str).collect()).unwrap_or_default();
+            return Err(if known.is_empty() {
+                format!("Unknown profile '{}': rbxsync.json has no profiles", name)
+            } else {
+                format!("Unknown profile '{}' (defined: {})", name, known.join(", "))
+            });
+        };
+        serde_json::from_value(profile.clone()).map_err(|e| format!("profiles.{}: {}", name, e))
+    }
+
+    /// The explicit format, else the one matching the output's extension
+    pub fn format(&self) -> Option<BuildFormat> {
+        self.format.or_else(|| {
+            let extension = self.output.as_ref()?.extension()?.to_str()?;
+            BuildFormat::parse(extension)
+        })
+    }
+
+    /// Fill in `options` from the profile. What's already set wins: an
+    /// explicit variant, and defines with the same token.
+    pub fn apply_to(&self, options: &mut BuildOptions) {
+        if options.variant.is_none() {
+            options.variant = self.variant.clone();
+        }
+        options.strict |= self.strict;
+        options.obfuscate |= self.obfuscate;
+        let mut defines: BTreeMap<String, String> =
+            self.defines.iter().map(|(token, value)| (token.clone(), define_text(value))).collect();
+        defines.append(&mut options.defines);
+        options.defines = defines;
+        options.exclude.extend(self.exclude.iter().cloned());
+    }
+}
+
+/// Build one subtree of `src` (a directory such as `ReplicatedStorage/UIKit`)
+/// and write it as a model with that instance at its top
+pub fn build_model(
+    project_dir: &Path,
+    model_path: &str,
+    output: &Path,
+    options: &BuildOptions,
+) -> Result<BuildReport, BuildError> {
+    let options = BuildOptions {
+        format: if options.format.is_xml() { BuildFormat::Rbxmx } else { BuildFormat::Rbxm },
+        ..options.clone()
+    };
+    let (dom, mut report) = build_dom_at(project_dir, Some(model_path), &options)?;
+    report.bytes = write_dom(&dom, output, options.format)?;
+    Ok(report)
+}
+
+/// Build all of `src`, or only the subtree at instance path `model`. The
+/// whole tree is built either way so overrides and assets resolve the same;
+/// only the subtree is kept and checked for unbuildable values.
+fn build_dom_at(project_dir: &Path, model: Option<&str>, options: &BuildOptions) -> Result<(WeakDom, BuildReport), BuildError> {
+    let src_dir = project_dir.join("src");
+    if !src_dir.is_dir() {
+        return Err(BuildError::MissingSource(src_dir));
+    }
+    let model = model.map(crate::normalize_instance_root);
+    let scan_dir = match &model {
+        Some(path) => src_dir.join(path),
+        None => src_dir.clone(),
+    };
+    if !scan_dir.is_dir() {
+        return Err(BuildError::MissingModel(model.unwrap_or_default()));
+    }
+
+    let mut unknown = UnsupportedReport::default();
+    let mut unsupported = UnsupportedReport::default();
+    collect_unbuildable(&src_dir, &scan_dir, &mut unsupported, &mut unknown);
+    if options.strict && !unsupported.is_empty() {
+        return Err(BuildError::Unbuildable {
+            count: unsupported.total(),
+            summary: unsupported.summary(20),
+        });
+    }
+
+    let mut dom = build_dom_from_src(&src_dir, options.format.is_place())?;
+    let excluded = exclude_dom_paths(&mut dom, &options.exclude);
+
+    let config = std::fs::read_to_string(project_dir.join("rbxsync.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .unwrap_or_default();
+    let overrides = Overrides::from_config(&config, options.variant.as_deref())
+        .map_err(BuildError::Overrides)?
+        .map(|overrides| apply_overrides(&mut dom, &overrides));
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let defines = apply_defines(&mut dom, &Defines::from_config(&config, &options.defines, now), model.as_deref());
+
+    let obfuscation = if options.obfuscate {
+        let settings = ObfuscationSettings::from_config(&config);
+        let mut obfuscator = settings
+            .obfuscator(project_dir)
+            .map_err(|e| BuildError::Obfuscation(format!("{:#}", e)))?;
+        Some(obfuscate_dom(&mut dom, &mut obfuscator, &settings.filter(), model.as_deref()))
+    } else {
+        None
+    };
+
+    if let Some(path) = &model {
+        let referent = find_dom_path(&dom, path).ok_or_else(|| BuildError::MissingModel(path.clone()))?;
+        let mut model_dom = WeakDom::new(InstanceBuilder::new("Folder").with_name("Model"));
+        let model_root = model_dom.root_ref();
+        dom.transfer(referent, &mut model_dom, model_root);
+        dom = model_dom;
+    }
+
+    let mut unresolved_assets = resolve_dom_assets(&mut dom, &AssetManifest::load(project_dir));
+    unresolved_assets.sort();
+    unresolved_assets.dedup();
+
+    let descendants: Vec<Ref> = dom.descendants().map(|i| i.referent()).collect();
+    let scripts = descendants
+        .iter()
+        .filter_map(|r| dom.get_by_ref(*r))
+        .filter(|i| matches!(i.class.as_str(), "Script" | "LocalScript" | "ModuleScript"))
+        .count();
+    let report = BuildReport {
+        instances: descendants.len().saturating_sub(1),
+        scripts,
+        unsupported: unsupported.entries(),
+        unknown: unknown.entries(),
+        overrides,
+        unresolved_assets,
+        excluded,
+        defines,
+        obfuscation,
+        bytes: 0,
+    };
+    Ok((dom, report))
+}
+
+/// Write a built DOM's top-level instances; returns the file size
+pub fn write_dom(dom: &WeakDom, output: &Path, format: BuildFormat) -> Result<u64, BuildError> {
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| BuildError::Write(e.to_string()))?;
+    }
+    let file = BufWriter::new(File::create(output).map_err(|e| BuildError::Write(e.to_string()))?);
+
+    // Export the children (services/instances) directly, not the root wrapper
+    // For places: services should have null parent referent (top-level in file)
+    // For models: instances should be top-level items
+    // The rbx_binary crate handles service detection based on class names
+    let refs: Vec<Ref> = dom.root().children().to_vec();
+    if format.is_xml() {
+        rbx_xml::to_writer_default(file, dom, &refs).map_err(|e| BuildError::Write(e.to_string()))?;
+    } else {
+        rbx_binary::to_writer(file, dom, &refs).map_err(|e| BuildError::Write(e.to_string()))?;
+    }
+    Ok(std::fs::metadata(output).map(|m| m.len()).unwrap_or(0))
+}
+
+/// Property values in a project's .rbxjson files that the build would drop
+pub fn unbuildable_properties(src_dir: &Path) -> UnsupportedReport {
+    let mut report = UnsupportedReport::default();
+    collect_unbuildable(src_dir, src_dir, &mut report, &mut UnsupportedReport::default());
+    report
+}
+
+/// Walk src for values the build drops, and properties unknown to the reflection database
+fn collect_unbuildable(src_dir: &Path, dir: &Path, report: &mut UnsupportedReport, unknown: &mut UnsupportedReport) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if path.is_dir() {
+            collect_unbuildable(src_dir, &path, report, unknown);
+            continue;
+        }
+        if path.extension().is_none_or(|e| e != "rbxjson") {
+            continue;
+        }
+        let Some(json) = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        else {
+            continue;
+        };
+        let Some(props) = json.get("properties").and_then(|p| p.as_object()) else {
+            continue;
+        };
+        let class_name = json.get("className").and_then(|c| c.as_str()).unwrap_or("Folder");
+        let relative = path.strip_prefix(src_dir).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+        for (name, value) in props {
+            let type_name = value.get("type").and_then(|t| t.as_str()).unwrap_or("untyped");
+            // nil means "use the default"; placeholders build but lost the real value.
+            // Refs are linked after the tree is built.
+            let lost = unsupported_type(value).or_else(|| {
+                (!matches!(type_name, "nil" | "Ref") && json_to_variant(value).is_none()).then_some(type_name)
+            });
+            if let Some(lost) = lost {
+                report.add(class_name, name, lost, &relative);
+            }
+            if !is_known_property(class_name, name) {
+                unknown.add(class_name, name, type_name, &relative);
+            }
+        }
+    }
+}
+
+/// Find an instance by DataModel path (e.g. "ServerScriptService/Analytics")
+pub fn find_dom_path(dom: &WeakDom, path: &str) -> Option<Ref> {
+    let mut current = dom.root_ref();
+    for name in path.split('/') {
+        current = dom
+            .get_by_ref(current)?
+            .children()
+            .iter()
+            .copied()
+            .find(|r| dom.get_by_ref(*r).is_some_and(|i| i.name == name))?;
+    }
+    Some(current)
+}
+
+/// Every instance in a DOM below the root with its instance path
+fn instance_paths(dom: &WeakDom) -> Vec<(Ref, String)> {
+    let mut paths = Vec::new();
+    let mut pending: Vec<(Ref, String)> = vec![(dom.root_ref(), String::new())];
+    while let Some((referent, path)) = pending.pop() {
+        let Some(instance) = dom.get_by_ref(referent) else {
+            continue;
+        };
+        for child in instance.children() {
+            if let Some(child_instance) = dom.get_by_ref(*child) {
+                let child_path = if path.is_empty() {
+                    child_instance.name.clone()
+                } else {
+                    format!("{}/{}", path, child_instance.name)
+                };
+                pending.push((*child, child_path));
+            }
+        }
+        if !path.is_empty() {
+            paths.push((referent, path));
+        }
+    }
+    paths
+}
+
+/// Scripts in a DOM with their instance paths, only those under `within`
+/// when it's set
+fn script_paths(dom: &WeakDom, within: Option<&str>) -> Vec<(Ref, String)> {
+    instance_paths(dom)
+        .into_iter()
+        .filter(|(referent, path)| {
+            within.is_none_or(|w| path == w || path.starts_with(&format!("{}/", w)))
+                && dom
+                    .get_by_ref(*referent)
+                    .is_some_and(|i| matches!(i.class.as_str(), "Script" | "LocalScript" | "ModuleScript"))
+        })
+        .collect()
+}
+
+/// Remove the instances whose paths match any of `patterns`, with everything
+/// under them; returns how many subtrees were removed
+pub fn exclude_dom_paths(dom: &mut WeakDom, patterns: &[String]) -> usize {
+    if patterns.is_empty() {
+        return 0;
+    }
+    let mut removed = 0;
+    for (referent, path) in instance_paths(dom) {
+        // Skip what went with an excluded ancestor
+        if dom.get_by_ref(referent).is_some() && patterns.iter().any(|p| crate::glob_match(p, &path)) {
+            dom.destroy(referent);
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// Rewrite a script's `Source` with `transform`
+fn transform_source(dom: &mut WeakDom, referent: Ref, transform: impl FnOnce(&str) -> String) {
+    let Some(instance) = dom.get_by_ref_mut(referent) else {
+        return;
+    };
+    let Some(Variant::String(source)) = instance.properties.get("Source") else {
+        return;
+    };
+    let source = transform(source);
+    instance.properties.insert("Source".to_string(), Variant::String(source));
+}
+
+/// Replace define tokens in the scripts under `within` (all scripts when
+/// it's `None`); returns how many were replaced
+pub fn apply_defines(dom: &mut WeakDom, defines: &Defines, within: Option<&str>) -> usize {
+    let mut replaced = 0;
+    for (referent, _) in script_paths(dom, within) {
+        transform_source(dom, referent, |source| {
+            let (source, count) = defines.apply(source);
+            replaced += count;
+            source
+        });
+    }
+    replaced
+}
+
+/// Obfuscate the source of every script whose instance path the filter
+/// matches, only looking under `within` when it's set. Each script gets its
+/// own variable prefix.
+pub fn obfuscate_dom(
+    dom: &mut WeakDom,
+    obfuscator: &mut Obfuscator,
+    filter: &SyncFilter,
+    within: Option<&str>,
+) -> ObfuscationStats {
+    let mut stats = ObfuscationStats::default();
+    for (referent, path) in script_paths(dom, within) {
+        if !filter.matches(&path) {
+            continue;
+        }
+        transform_source(dom, referent, |source| {
+            obfuscator.regenerate_prefix();
+            let result = obfuscator.obfuscate(source);
+            stats.add(&result);
+            result.source
+        });
+    }
+    stats
+}
+
+/// Apply the selected variant's overrides to a built DOM
+pub fn apply_overrides(dom: &mut WeakDom, overrides: &Overrides) -> OverrideReport {
+    let mut applied = Vec::new();
+    for path in overrides.paths() {
+        let Some(instance) = find_dom_path(dom, path).and_then(|r| dom.get_by_ref_mut(r)) else {
+            continue;
+        };
+        for entry in overrides.for_path(path) {
+            let value = match &entry.value {
+                PropertyValue::Bool(b) => Variant::Bool(*b),
+                PropertyValue::Enum(e) => match RUN_CONTEXTS.iter().position(|c| *c == e.value) {
+                    Some(index) => Variant::Enum(Enum::from_u32(index as u32)),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            instance.properties.insert(entry.property.clone(), value);
+            applied.push(AppliedOverride {
+                path: entry.path.clone(),
+                property: entry.property.clone(),
+                value: entry.display_value(),
+            });
+        }
+    }
+    overrides.report(applied)
+}
+
+/// Build a DOM from the src directory
+pub fn build_dom_from_src(src_dir: &Path, is_place: bool) -> io::Result<WeakDom> {
+    let root_class = if is_place { "DataModel" } else { "Folder" };
+    let root_name = if is_place { "game" } else { "Model" };
+
+    let mut dom = WeakDom::new(InstanceBuilder::new(root_class).with_name(root_name));
+    let root_ref = dom.root_ref();
+    let mut links = RefLinks::default();
+
+    // Process each service directory
+    let mut entries: Vec<_> = std::fs::read_dir(src_dir)?
+        .filter_map(|e| e.ok())
+        .collect();
+
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let entry_path = entry.path();
+        let entry_name = entry.file_name().to_string_lossy().to_string();
+
+        if entry_path.is_dir() {
+            // Directory becomes a service or folder
+            let class_name = crate::service_class_name(&entry_name).unwrap_or("Folder");
+            let service_ref = dom.insert(
+                root_ref,
+                InstanceBuilder::new(class_name).with_name(&entry_name),
+            );
+
+            // Recursively add children
+            build_dom_children(&mut dom, service_ref, &entry_name, &entry_path, &mut links)?;
+        } else if entry_path.extension().map(|e| e == "rbxjson").unwrap_or(false) {
+            // .rbxjson file becomes an instance
+            let instance_name = entry_path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            if let Ok(content) = std::fs::read_to_string(&entry_path) {
+                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                    let class_name = json
+                        .get("className")
+                        .and_then(|c| c.as_str())
+                        .unwrap_or("Folder");
+
+                    let builder = with_json_properties(InstanceBuilder::new(class_name).with_name(&instance_name), &json);
+                    let referent = dom.insert(root_ref, builder);
+                    links.record(referent, &json);
+                }
+            }
+        } else if entry_path.extension().map(|e| e == "luau" || e == "lua").unwrap_or(false) {
+            // Script file
+            let (script_name, class_name) = parse_script_name(&entry_name);
+            if let Ok(source) = std::fs::read_to_string(&entry_path) {
+                dom.insert(
+                    root_ref,
+                    InstanceBuilder::new(class_name)
+                        .with_name(&script_name)
+                        .with_property("Source", Variant::String(source)),
+                );
+            }
+        }
+    }
+
+    links.link(&mut dom);
+    if is_place {
+        link_current_camera(&mut dom);
+    }
+
+    Ok(dom)
+}
+
+/// Point Workspace.CurrentCamera at the extracted Workspace.Camera, if any
+fn link_current_camera(dom: &mut WeakDom) {
+    let workspace_ref = dom.root().children().iter().copied().find(|r| {
+        dom.get_by_ref(*r).map(|i| i.class == "Workspace").unwrap_or(false)
+    });
+    let Some(workspace_ref) = workspace_ref else {
+        return;
+    };
+    let camera_ref = dom.get_by_ref(workspace_ref).and_then(|ws| {
+        ws.children().iter().copied().find(|r| {
+            dom.get_by_ref(*r)
+                .map(|i| i.class == "Camera" && i.name == "Camera")
+                .unwrap_or(false)
+        })
+    });
+    if let (Some(camera_ref), Some(workspace)) = (camera_ref, dom.get_by_ref_mut(workspace_ref)) {
+        workspace
+            .properties
+            .insert("CurrentCamera".to_string(), Variant::Ref(camera_ref));
+    }
+}
+
+/// Recursively build DOM children from a directory
+///
+/// `inst_path` is the DataModel path of `parent_ref`, used to detect special
+/// containers like StarterPlayerScripts that have no _meta.rbxjson.
+fn build_dom_children(
+    dom: &mut WeakDom,
+    parent_ref: Ref,
+    inst_path: &str,
+    dir_path: &Path,
+    links: &mut RefLinks,
+) -> io::Result<()> {
+    let mut entries: Vec<_> = std::fs::read_dir(dir_path)?
+        .filter_map(|e| e.ok())
+        .collect();
+
+    entries.sort_by_key(|e| e.file_name());
+
+    // Check for init file first
+    let init_files = ["init.luau", "init.server.luau", "init.client.luau"];
+    for init_name in init_files {
+        let init_path = dir_path.join(init_name);
+        if init_path.exists() {
+            if let Ok(source) = std::fs::read_to_string(&init_path) {
+                // Set Source property on parent
+                if let Some(instance) = dom.get_by_ref_mut(parent_ref) {
+                    instance
+                        .properties
+                        .insert("Source".to_string(), Variant::String(source));
+                }
+            }
+            break;
+        }
+    }
+
+    // Scripts with children are written as `Name.server.luau` next to a `Name/`
+    // directory. Collect those sibling files so they merge into the directory
+    // instance instead of producing a duplicate script.
+    let sibling_scripts: HashSet<PathBuf> = entries
+        .iter()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .filter_map(|p| find_sibling_script(&p).map(|(script, _)| script))
+        .collect();
+
+    for entry in entries {
+        let entry_path = entry.path();
+        let entry_name = entry.file_name().to_string_lossy().to_string();
+
+        // Skip init files, _meta.rbxjson and scripts merged into a sibling directory
+        if init_files.iter().any(|&n| entry_name == n)
+            || entry_name == "_meta.rbxjson"
+            || sibling_scripts.contains(&entry_path)
+        {
+            continue;
+        }
+
+        if entry_path.is_dir() {
+            // Check if directory has _meta.rbxjson (contains class and properties)
+            let meta_path = entry_path.join("_meta.rbxjson");
+            let meta_data: Option<serde_json::Value> = if meta_path.exists() {
+                std::fs::read_to_string(&meta_path)
+                    .ok()
+                    .and_then(|s| serde_json::from_str(&s).ok())
+            } else {
+                None
+            };
+
+            let sibling_script = find_sibling_script(&entry_path);
+
+            // Class priority: init file, sibling script, _meta.rbxjson, special container
+            let class_name = if let Some(class) = init_script_class(&entry_path) {
+                class
+            } else if let Some((_, class)) = &sibling_script {
+                class
+            } else if let Some(class) = meta_data
+                .as_ref()
+                .and_then(|m| m.get("className"))
+                .and_then(|c| c.as_str())
+            {
+                class
+            } else {
+                crate::container_class_name(inst_path, &entry_name).unwrap_or("Folder")
+            };
+
+            let mut builder = InstanceBuilder::new(class_name).with_name(&entry_name);
+
+            if let Some((script_path, _)) = &sibling_script {
+                if let Ok(source) = std::fs::read_to_string(script_path) {
+                    builder = builder.with_property("Source", Variant::String(source));
+                }
+            }
+
+            // Apply properties from _meta.rbxjson if available
+            if let Some(meta) = &meta_data {
+                builder = with_json_properties(builder, meta);
+            }
+
+            let child_ref = dom.insert(parent_ref, builder);
+            if let Some(meta) = &meta_data {
+                links.record(child_ref, meta);
+            }
+
+            build_dom_children(dom, child_ref, &child_instance_path(inst_path, &entry_name), &entry_path, links)?;
+        } else if entry_path.extension().map(|e| e == "rbxjson").unwrap_or(false) {
+            // .rbxjson file
+            let instance_name = entry_path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            if let Ok(content) = std::fs::read_to_string(&entry_path) {
+                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                    let class_name = json
+                        .get("className")
+                        .and_then(|c| c.as_str())
+                        .or_else(|| crate::container_class_name(inst_path, &instance_name))
+                        .unwrap_or("Folder");
+
+                    let builder = with_json_properties(InstanceBuilder::new(class_name).with_name(&instance_name), &json);
+                    let referent = dom.insert(parent_ref, builder);
+                    links.record(referent, &json);
+                }
+            }
+        } else if entry_path.extension().map(|e| e == "luau" || e == "lua").unwrap_or(false) {
+            // Script file
+            let (script_name, class_name) = parse_script_name(&entry_name);
+            if let Ok(source) = std::fs::read_to_string(&entry_path) {
+                dom.insert(
+                    parent_ref,
+                    InstanceBuilder::new(class_name)
+                        .with_name(&script_name)
+                        .with_property("Source", Variant::String(source)),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply an instance's `.rbxjson` properties, attributes and tags
+fn with_json_properties(mut builder: InstanceBuilder, json: &serde_json::Value) -> InstanceBuilder {
+    if let Some(props) = json.get("properties").and_then(|p| p.as_object()) {
+        for (prop_name, prop_value) in props {
+            if let Some(value) = json_to_variant(prop_value) {
+                builder = builder.with_property(prop_name, value);
+            }
+        }
+    }
+    if let Some(attributes) = json.get("attributes").and_then(crate::serde_props::json_to_attributes) {
+        builder = builder.with_property("Attributes", attributes);
+    }
+    if let Some(tags) = json.get("tags").and_then(crate::serde_props::json_to_tags) {
+        builder = builder.with_property("Tags", tags);
+    }
+    builder
+}
+
+/// `referenceId`s of built instances and the `Ref` properties that point at
+/// them, linked once the whole tree exists
+#[derive(Default)]
+struct RefLinks {
+    ids: HashMap<String, Ref>,
+    pending: Vec<(Ref, String, String)>,
+}
+
+impl RefLinks {
+    fn record(&mut self, referent: Ref, json: &serde_json::Value) {
+        if let Some(id) = json.get("referenceId").and_then(|id| id.as_str()) {
+            self.ids.insert(id.to_string(), referent);
+        }
+        let Some(props) = json.get("properties").and_then(|p| p.as_object()) else {
+            return;
+        };
+        for (name, value) in props {
+            if value.get("type").and_then(|t| t.as_str()) != Some("Ref") {
+                continue;
+            }
+            if let Some(target) = value.get("value").and_then(|v| v.as_str()) {
+                self.pending.push((referent, name.clone(), target.to_string()));
+            }
+        }
+    }
+
+    /// Set every Ref whose target was built; the rest stay unset
+    fn link(self, dom: &mut WeakDom) {
+        for (referent, property, target) in self.pending {
+            if let (Some(target), Some(instance)) = (self.ids.get(&target), dom.get_by_ref_mut(referent)) {
+                instance.properties.insert(property, Variant::Ref(*target));
+            }
+        }
+    }
+}
+
+/// Join a parent instance path and a child name
+pub fn child_instance_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", parent, name)
+    }
+}
+
+/// Get the script class of a directory from its init file, if it has one
+pub fn init_script_class(dir_path: &Path) -> Option<&'static str> {
+    if dir_path.join("init.server.luau").exists() {
+        Some("Script")
+    } else if dir_path.join("init.client.luau").exists() {
+        Some("LocalScript")
+    } else if dir_path.join("init.luau").exists() {
+        Some("ModuleScript")
+    } else {
+        None
+    }
+}
+
+/// Find the source file written next to a script directory.
+///
+/// Extraction writes scripts that have children as `Name.server.luau` plus a
+/// `Name/` directory holding the children, so both describe the same instance.
+pub fn find_sibling_script(dir_path: &Path) -> Option<(PathBuf, &'static str)> {
+    let candidates = [
+        (".server.luau", "Script"),
+        (".client.luau", "LocalScript"),
+        (".luau", "ModuleScript"),
+        (".server.lua", "Script"),
+        (".client.lua", "LocalScript"),
+        (".lua", "ModuleScript"),
+    ];
+    candidates.iter().find_map(|(suffix, class)| {
+        let path = crate::pathbuf_with_suffix(dir_path, suffix);
+        path.is_file().then_some((path, *class))
+    })
+}
+
+/// Read the className from a directory's _meta.rbxjson
+pub fn meta_class_name(dir_path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir_path.join("_meta.rbxjson")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    json.get("className").and_then(|c| c.as_str()).map(String::from)
+}
+
+/// Parse script name and class from filename
+pub fn parse_script_name(filename: &str) -> (String, &'static str) {
+    let name = filename
+        .trim_end_matches(".luau")
+        .trim_end_matches(".lua");
+
+    if name.ends_with(".server") {
+        (name.trim_end_matches(".server").to_string(), "Script")
+    } else if name.ends_with(".client") {
+        (name.trim_end_matches(".client").to_string(), "LocalScript")
+    } else {
+        (name.to_string(), "ModuleScript")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rbx_dom_weak::types::Attributes;
+
+    #[test]
+    fn test_build_project() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        assert!(matches!(
+            build_dom(root, &BuildOptions::default()),
+            Err(BuildError::MissingSource(_))
+        ));
+
+        std::fs::write(
+            root.join("rbxsync.json"),
+            r#"{ "overrides": { "dev": { "ServerScriptService/Main": { "Enabled": false } } } }"#,
+        )
+        .unwrap();
+        let sss = root.join("src/ServerScriptService");
+        std::fs::create_dir_all(&sss).unwrap();
+        std::fs::write(sss.join("Main.server.luau"), "print('hi')").unwrap();
+        std::fs::write(sss.join("Util.luau"), "return {}").unwrap();
+        std::fs::write(
+            sss.join("Config.rbxjson"),
+            r#"{ "className": "Configuration", "properties": {
+                "Tint": { "type": "Color3", "value": { "r": 1, "g": 0, "b": 0 } },
+                "Curve": { "type": "ColorSequence", "value": [] }
+            } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(BuildFormat::parse("model-xml"), Some(BuildFormat::Rbxmx));
+        assert!(matches!(
+            build_dom(root, &BuildOptions { strict: true, ..Default::default() }),
+            Err(BuildError::Unbuildable { count: 1, .. })
+        ));
+        assert!(matches!(
+            build_dom(root, &BuildOptions { variant: Some("prod".into()), ..Default::default() }),
+            Err(BuildError::Overrides(_))
+        ));
+
+        let options = BuildOptions { variant: Some("dev".into()), ..Default::default() };
+        let (dom, report) = build_dom(root, &options).unwrap();
+        assert_eq!(report.instances, 4);
+        assert_eq!(report.scripts, 2);
+        assert_eq!(report.dropped(), 1);
+        // Configuration has neither property
+        let unknown: Vec<&str> = report.unknown.iter().map(|u| u.property.as_str()).collect();
+        assert_eq!(unknown, vec!["Curve", "Tint"]);
+        assert_eq!(report.overrides.as_ref().unwrap().applied.len(), 1);
+        let main = find_dom_path(&dom, "ServerScriptService/Main").unwrap();
+        assert_eq!(dom.get_by_ref(main).unwrap().properties.get("Enabled"), Some(&Variant::Bool(false)));
+
+        for format in [BuildFormat::Rbxl, BuildFormat::Rbxmx] {
+            let output = root.join("build").join(format!("game.{}", format.extension()));
+            let report = build_project(root, &output, &BuildOptions { format, ..Default::default() }).unwrap();
+            assert!(report.bytes > 0);
+            assert_eq!(report.bytes, std::fs::metadata(&output).unwrap().len());
+        }
+    }
+
+    #[test]
+    fn test_build_model() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        std::fs::write(
+            root.join("rbxsync.json"),
+            r#"{ "models": { "ReplicatedStorage/UIKit/": "build/UIKit.rbxmx", "Bad": 3 } }"#,
+        )
+        .unwrap();
+        let uikit = root.join("src/ReplicatedStorage/UIKit");
+        std::fs::create_dir_all(uikit.join("Components")).unwrap();
+        std::fs::write(uikit.join("init.luau"), "return {}").unwrap();
+        std::fs::write(uikit.join("Components/Button.luau"), "return {}").unwrap();
+        std::fs::write(root.join("src/ReplicatedStorage/Other.luau"), "return {}").unwrap();
+
+        let config: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(root.join("rbxsync.json")).unwrap()).unwrap();
+        let targets = model_targets(&config);
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].path, "ReplicatedStorage/UIKit");
+        assert_eq!(targets[0].format(), BuildFormat::Rbxmx);
+
+        let (dom, report) = build_dom_at(root, Some("ReplicatedStorage/UIKit"), &BuildOptions::default()).unwrap();
+        assert_eq!(report.instances, 3);
+        assert_eq!(report.scripts, 2);
+        let top = dom.root().children();
+        assert_eq!(top.len(), 1);
+        let uikit = dom.get_by_ref(top[0]).unwrap();
+        assert_eq!((uikit.name.as_str(), uikit.class.as_str()), ("UIKit", "ModuleScript"));
+
+        assert!(matches!(
+            build_model(root, "ReplicatedStorage/Missing", &root.join("build/x.rbxm"), &BuildOptions::default()),
+            Err(BuildError::MissingModel(_))
+        ));
+        let output = root.join(&targets[0].output);
+        let report = build_model(root, &targets[0].path, &output, &BuildOptions { format: targets[0].format(), ..Default::default() }).unwrap();
+        assert_eq!(report.bytes, std::fs::metadata(&output).unwrap().len());
+    }
+
+    #[test]
+    fn test_build_links_refs_attributes_and_tags() {
+        let project = tempfile::tempdir().unwrap();
+        let workspace = project.path().join("src/Workspace");
+        std::fs::create_dir_all(&workspace).unwrap();
+        std::fs::write(
+            workspace.join("Door.rbxjson"),
+            r#"{ "className": "Part", "referenceId": "door-id", "properties": {},
+                "attributes": { "Locked": { "type": "bool", "value": true }, "Cost": { "type": "int", "value": 25 } },
+                "tags": ["Interactable"] }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            workspace.join("Hinge.rbxjson"),
+            r#"{ "className": "ObjectValue", "referenceId": "hinge-id", "properties": {
+                "Value": { "type": "Ref", "value": "door-id" },
+                "Missing": { "type": "Ref", "value": "not-built" }
+            } }"#,
+        )
+        .unwrap();
+
+        let (dom, report) = build_dom(project.path(), &BuildOptions::default()).unwrap();
+        assert!(report.unsupported.is_empty(), "refs aren't reported as unbuildable");
+        let door_ref = find_dom_path(&dom, "Workspace/Door").unwrap();
+        let hinge = dom.get_by_ref(find_dom_path(&dom, "Workspace/Hinge").unwrap()).unwrap();
+        assert_eq!(hinge.properties.get("Value"), Some(&Variant::Ref(door_ref)));
+        assert_eq!(hinge.properties.get("Missing"), None);
+
+        let door = dom.get_by_ref(door_ref).unwrap();
+        let Some(Variant::Attributes(attributes)) = door.properties.get("Attributes") else {
+            panic!("expected attributes");
+        };
+        let expected = Attributes::new()
+            .with("Cost", Variant::Float64(25.0))
+            .with("Locked", Variant::Bool(true));
+        assert_eq!(attributes, &expected);
+        let Some(Variant::Tags(tags)) = door.properties.get("Tags") else {
+            panic!("expected tags");
+        };
+        assert_eq!(tags.iter().collect::<Vec<_>>(), vec!["Interactable"]);
+    }
+
+    #[test]
+    fn test_build_defines_and_obfuscation() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        std::fs::write(
+            root.join("rbxsync.json"),
+            r#"{ "version": "2.0.1", "obfuscation": { "exclude": ["**/Tests/**"] } }"#,
+        )
+        .unwrap();
+        let sss = root.join("src/ServerScriptService");
+        std::fs::create_dir_all(sss.join("Tests")).unwrap();
+        let source = "local env = \"getfenv\"\nprint(\"[DEBUG] hi\")\nreturn \"__VERSION__\", __STAGE__\n";
+        std::fs::write(sss.join("Main.server.luau"), source).unwrap();
+        std::fs::write(sss.join("Tests/Helper.luau"), source).unwrap();
+
+        let options = BuildOptions {
+            defines: BTreeMap::from([("__STAGE__".to_string(), "\"beta\"".to_string())]),
+            ..Default::default()
+        };
+        let (dom, report) = build_dom(root, &options).unwrap();
+        assert!(report.obfuscation.is_none());
+        assert_eq!(report.defines, 4);
+        let main = dom.get_by_ref(find_dom_path(&dom, "ServerScriptService/Main").unwrap()).unwrap();
+        let Some(Variant::String(built)) = main.properties.get("Source") else {
+            panic!("expected a source");
+        };
+        assert!(built.contains("return \"2.0.1\", \"beta\""));
+
+        let (dom, report) = build_dom(root, &BuildOptions { obfuscate: true, ..Default::default() }).unwrap();
+        let stats = report.obfuscation.unwrap();
+        assert_eq!((stats.scripts, stats.strings_encoded, stats.debug_stripped), (1, 1, 1));
+        let source_of = |path: &str| match dom.get_by_ref(find_dom_path(&dom, path).unwrap()).unwrap().properties.get("Source") {
+            Some(Variant::String(source)) => source.clone(),
+            _ => panic!("expected a source"),
+        };
+        assert!(!source_of("ServerScriptService/Main").contains("getfenv"));
+        assert!(source_of("ServerScriptService/Tests/Helper").contains("getfenv"));
+    }
+
+    #[test]
+    fn test_build_profile() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        let config = serde_json::json!({
+            "profiles": {
+                "release": {
+                    "output": "build/release.rbxlx",
+                    "obfuscate": true,
+                    "defines": { "__DEBUG__": false, "__CHANNEL__": "live" },
+                    "exclude": ["**/Tests"]
+                }
+            }
+        });
+        assert!(BuildProfile::from_config(&config, "dev").unwrap_err().contains("release"));
+        let profile = BuildProfile::from_config(&config, "release").unwrap();
+        assert_eq!(profile.format(), Some(BuildFormat::Rbxlx));
+
+        let mut options = BuildOptions {
+            defines: BTreeMap::from([("__CHANNEL__".to_string(), "beta".to_string())]),
+            ..Default::default()
+        };
+        profile.apply_to(&mut options);
+        assert!(options.obfuscate);
+        assert_eq!(options.defines["__DEBUG__"], "false");
+        assert_eq!(options.defines["__CHANNEL__"], "beta");
+
+        let rs = root.join("src/ReplicatedStorage");
+        std::fs::create_dir_all(rs.join("Tests")).unwrap();
+        std::fs::write(rs.join("Tests/Spec.luau"), "return {}").unwrap();
+        std::fs::write(rs.join("Shared.luau"), "return __DEBUG__").unwrap();
+        let (dom, report) = build_dom(root, &options).unwrap();
+        assert_eq!(report.excluded, 1);
+        assert!(find_dom_path(&dom, "ReplicatedStorage/Tests").is_none());
+        assert_eq!(report.defines, 1);
+    }
+}
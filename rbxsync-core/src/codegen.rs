@@ -0,0 +1,451 @@
+//! Luau builder code generation
+//!
+//! `rbxsync codegen` turns a subtree of `src/` into a ModuleScript that rebuilds
+//! it at runtime with `Instance.new` and property assignments, for structures
+//! that games spawn procedurally instead of cloning from storage.
+//!
+//! Each child is built inside its own `do ... end` block so only its ancestors'
+//! locals stay in scope; deep or wide trees would otherwise hit Luau's
+//! 200-local limit.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use serde_json::{Map, Value};
+
+/// Instance count above which the output gets a size warning
+pub const WARN_INSTANCES: usize = 1000;
+
+/// Generated source size (bytes) above which the output gets a size warning
+pub const WARN_SOURCE_BYTES: usize = 200 * 1024;
+
+/// Properties that are never assigned (Name is set first, Parent last)
+const SKIPPED_PROPERTIES: &[&str] = &["Name", "Parent", "Source"];
+
+/// Script classes, whose Source can't be set from a running game
+const SCRIPT_CLASSES: &[&str] = &["Script", "LocalScript", "ModuleScript"];
+
+const LUAU_KEYWORDS: &[&str] = &[
+    "and", "break", "continue", "do", "else", "elseif", "end", "export", "false", "for", "function", "if", "in",
+    "local", "nil", "not", "or", "repeat", "return", "then", "true", "type", "typeof", "until", "while",
+];
+
+/// An instance to generate code for, read from the project's files
+#[derive(Debug, Clone, Default)]
+pub struct CodegenNode {
+    pub class_name: String,
+    pub name: String,
+    /// Properties in the .rbxjson typed format
+    pub properties: Map<String, Value>,
+    /// Attributes in the .rbxjson typed format
+    pub attributes: Map<String, Value>,
+    pub tags: Vec<String>,
+    pub children: Vec<CodegenNode>,
+}
+
+impl CodegenNode {
+    pub fn new(class_name: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            class_name: class_name.into(),
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Fill in properties, attributes and tags from a parsed .rbxjson or
+    /// _meta.rbxjson file
+    pub fn apply_rbxjson(&mut self, json: &Value) {
+        if let Some(props) = json.get("properties").and_then(|p| p.as_object()) {
+            self.properties.extend(props.clone());
+        }
+        if let Some(attrs) = json.get("attributes").and_then(|a| a.as_object()) {
+            self.attributes.extend(attrs.clone());
+        }
+        if let Some(tags) = json.get("tags").and_then(|t| t.as_array()) {
+            self.tags
+                .extend(tags.iter().filter_map(|t| t.as_str()).map(String::from));
+        }
+    }
+}
+
+/// Generated module and what couldn't be carried over
+#[derive(Debug, Clone)]
+pub struct CodegenOutput {
+    pub source: String,
+    pub instances: usize,
+    pub warnings: Vec<String>,
+}
+
+/// Luau string literal
+fn luau_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 || c as u32 == 0x7f => {
+                let _ = write!(out, "\\{}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Luau number literal
+fn luau_number(n: f64) -> String {
+    if n.is_nan() {
+        "0 / 0".to_string()
+    } else if n.is_infinite() {
+        if n > 0.0 { "math.huge" } else { "-math.huge" }.to_string()
+    } else if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        format!("{}", n)
+    }
+}
+
+fn num(value: &Value, key: &str) -> Option<String> {
+    value.get(key)?.as_f64().map(luau_number)
+}
+
+fn numbers(values: &[Value]) -> Option<Vec<String>> {
+    values.iter().map(|v| v.as_f64().map(luau_number)).collect()
+}
+
+fn vector2(v: &Value) -> Option<String> {
+    Some(format!("Vector2.new({}, {})", num(v, "x")?, num(v, "y")?))
+}
+
+fn color3(v: &Value) -> Option<String> {
+    Some(format!("Color3.new({}, {}, {})", num(v, "r")?, num(v, "g")?, num(v, "b")?))
+}
+
+fn udim(v: &Value) -> Option<String> {
+    Some(format!("UDim.new({}, {})", num(v, "scale")?, num(v, "offset")?))
+}
+
+/// Luau identifier part of an enum item or type name
+fn enum_ident(s: &str) -> Option<&str> {
+    let valid = !s.is_empty()
+        && !s.starts_with(|c: char| c.is_ascii_digit())
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then_some(s)
+}
+
+/// FontWeight item for a numeric weight
+fn font_weight_name(weight: u64) -> Option<&'static str> {
+    Some(match weight {
+        100 => "Thin",
+        200 => "ExtraLight",
+        300 => "Light",
+        400 => "Regular",
+        500 => "Medium",
+        600 => "SemiBold",
+        700 => "Bold",
+        800 => "ExtraBold",
+        900 => "Heavy",
+        _ => return None,
+    })
+}
+
+/// Convert a typed .rbxjson value to a Luau expression.
+///
+/// Returns `None` for values that can't be expressed in a script (Refs, binary
+/// data) or are malformed. Untyped JSON strings, numbers and bools are taken
+/// as-is.
+pub fn luau_value(prop: &Value) -> Option<String> {
+    let Some(type_str) = prop.get("type").and_then(|t| t.as_str()) else {
+        return match prop {
+            Value::String(s) => Some(luau_string(s)),
+            Value::Bool(b) => Some(b.to_string()),
+            Value::Number(n) => n.as_f64().map(luau_number),
+            _ => None,
+        };
+    };
+    let val = prop.get("value")?;
+    match type_str {
+        "string" | "Content" | "ContentId" => val.as_str().map(luau_string),
+        "bool" => val.as_bool().map(|b| b.to_string()),
+        "int" | "int32" | "int64" | "float" | "float32" | "float64" | "double" | "number" => {
+            val.as_f64().map(luau_number)
+        }
+        "Vector2" => vector2(val),
+        "Vector3" => Some(format!("Vector3.new({}, {}, {})", num(val, "x")?, num(val, "y")?, num(val, "z")?)),
+        "CFrame" => {
+            let mut parts = numbers(val.get("position")?.as_array()?)?;
+            let rotation = numbers(val.get("rotation")?.as_array()?)?;
+            if parts.len() != 3 || rotation.len() != 9 {
+                return None;
+            }
+            parts.extend(rotation);
+            Some(format!("CFrame.new({})", parts.join(", ")))
+        }
+        "Color3" => color3(val),
+        "Color3uint8" => Some(format!(
+            "Color3.fromRGB({}, {}, {})",
+            num(val, "r")?,
+            num(val, "g")?,
+            num(val, "b")?
+        )),
+        "BrickColor" => val.as_u64().map(|n| format!("BrickColor.new({})", n)),
+        "UDim" => udim(val),
+        "UDim2" => Some(format!("UDim2.new({}, {})", udim(val.get("x")?)?, udim(val.get("y")?)?)),
+        "Rect" => Some(format!("Rect.new({}, {})", vector2(val.get("min")?)?, vector2(val.get("max")?)?)),
+        "NumberRange" => Some(format!("NumberRange.new({}, {})", num(val, "min")?, num(val, "max")?)),
+        "NumberSequence" => {
+            let keypoints: Option<Vec<String>> = val
+                .get("keypoints")
+                .unwrap_or(val)
+                .as_array()?
+                .iter()
+                .map(|k| {
+                    let envelope = num(k, "envelope").map(|e| format!(", {}", e)).unwrap_or_default();
+                    Some(format!("NumberSequenceKeypoint.new({}, {}{})", num(k, "time")?, num(k, "value")?, envelope))
+                })
+                .collect();
+            Some(format!("NumberSequence.new({{ {} }})", keypoints?.join(", ")))
+        }
+        "ColorSequence" => {
+            let keypoints: Option<Vec<String>> = val
+                .get("keypoints")
+                .unwrap_or(val)
+                .as_array()?
+                .iter()
+                .map(|k| Some(format!("ColorSequenceKeypoint.new({}, {})", num(k, "time")?, color3(k.get("color")?)?)))
+                .collect();
+            Some(format!("ColorSequence.new({{ {} }})", keypoints?.join(", ")))
+        }
+        "Enum" => {
+            let enum_type = enum_ident(val.get("enumType")?.as_str()?)?;
+            match val.get("value")? {
+                Value::String(item) => Some(format!("Enum.{}.{}", enum_type, enum_ident(item)?)),
+                Value::Number(n) => Some(format!("Enum.{}:FromValue({})", enum_type, n.as_u64()?)),
+                _ => None,
+            }
+        }
+        "Font" => {
+            let family = luau_string(val.get("family")?.as_str()?);
+            let weight = match val.get("weight") {
+                Some(Value::Number(n)) => font_weight_name(n.as_u64()?)?,
+                Some(Value::String(s)) => enum_ident(s)?,
+                _ => "Regular",
+            };
+            let style = val.get("style").and_then(|s| s.as_str()).and_then(enum_ident).unwrap_or("Normal");
+            Some(format!("Font.new({}, Enum.FontWeight.{}, Enum.FontStyle.{})", family, weight, style))
+        }
+        _ => None,
+    }
+}
+
+/// A local name for an instance that doesn't shadow an ancestor's
+fn local_name(name: &str, taken: &HashSet<String>) -> String {
+    let mut base: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect();
+    if let Some(first) = base.get(..1) {
+        base.replace_range(..1, &first.to_ascii_lowercase());
+    }
+    if base.is_empty() || base.starts_with(|c: char| c.is_ascii_digit()) {
+        base.insert_str(0, "instance");
+    }
+    if LUAU_KEYWORDS.contains(&base.as_str()) {
+        base.push('_');
+    }
+    let mut candidate = base.clone();
+    let mut n = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{}{}", base, n);
+        n += 1;
+    }
+    candidate
+}
+
+struct Generator {
+    out: String,
+    instances: usize,
+    warnings: Vec<String>,
+}
+
+impl Generator {
+    fn line(&mut self, depth: usize, text: &str) {
+        for _ in 0..depth {
+            self.out.push('\t');
+        }
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    /// Emit statements creating `node` as local `var`, then its children
+    fn emit(&mut self, node: &CodegenNode, var: &str, path: &str, depth: usize, taken: &mut HashSet<String>) {
+        self.instances += 1;
+        self.line(depth, &format!("local {} = Instance.new({})", var, luau_string(&node.class_name)));
+        self.line(depth, &format!("{}.Name = {}", var, luau_string(&node.name)));
+
+        if SCRIPT_CLASSES.contains(&node.class_name.as_str()) {
+            self.warnings.push(format!(
+                "{}: {} Source can't be set at runtime; the script will be empty",
+                path, node.class_name
+            ));
+        }
+        if node.class_name.ends_with("Operation") {
+            self.warnings.push(format!(
+                "{}: {} geometry can't be recreated with Instance.new",
+                path, node.class_name
+            ));
+        }
+
+        for (prop, value) in &node.properties {
+            if SKIPPED_PROPERTIES.contains(&prop.as_str()) {
+                continue;
+            }
+            // MeshId can only be set by creating the MeshPart through AssetService
+            if node.class_name == "MeshPart" && prop == "MeshId" {
+                self.warnings.push(format!("{}.MeshId can't be set at runtime", path));
+                continue;
+            }
+            match (enum_ident(prop), luau_value(value)) {
+                (Some(prop), Some(expr)) => self.line(depth, &format!("{}.{} = {}", var, prop, expr)),
+                _ => {
+                    let type_str = value.get("type").and_then(|t| t.as_str()).unwrap_or("untyped");
+                    if type_str != "nil" {
+                        self.warnings
+                            .push(format!("{}.{}: skipped {} value", path, prop, type_str));
+                    }
+                }
+            }
+        }
+
+        for (attr, value) in &node.attributes {
+            match luau_value(value) {
+                Some(expr) => self.line(depth, &format!("{}:SetAttribute({}, {})", var, luau_string(attr), expr)),
+                None => self.warnings.push(format!("{}: skipped attribute {}", path, attr)),
+            }
+        }
+
+        for tag in &node.tags {
+            self.line(depth, &format!("{}:AddTag({})", var, luau_string(tag)));
+        }
+
+        taken.insert(var.to_string());
+        for child in &node.children {
+            let child_var = local_name(&child.name, taken);
+            let child_path = format!("{}/{}", path, child.name);
+            self.line(depth, "do");
+            self.emit(child, &child_var, &child_path, depth + 1, taken);
+            self.line(depth + 1, &format!("{}.Parent = {}", child_var, var));
+            self.line(depth, "end");
+        }
+        taken.remove(var);
+    }
+}
+
+/// Generate a ModuleScript returning a function that builds `root`.
+///
+/// `source_label` names where the tree came from in the header comment.
+pub fn generate_module(root: &CodegenNode, source_label: &str) -> CodegenOutput {
+    let mut generator = Generator {
+        out: String::new(),
+        instances: 0,
+        warnings: Vec::new(),
+    };
+
+    let mut taken: HashSet<String> = ["parent".to_string()].into_iter().collect();
+    let root_var = local_name(&root.name, &taken);
+    generator.emit(root, &root_var, &root.name, 1, &mut taken);
+    generator.line(1, "");
+    generator.line(1, &format!("{}.Parent = parent", root_var));
+    generator.line(1, &format!("return {}", root_var));
+    generator.line(0, "end");
+
+    let mut source = format!(
+        "-- Generated by `rbxsync codegen` from {}.\n\
+         -- Regenerate instead of editing by hand.\n\
+         --\n\
+         -- Returns a function that builds a new {} ({}) and parents it to `parent`.\n\n",
+        source_label, root.name, root.class_name
+    );
+    let _ = write!(
+        source,
+        "return function(parent: Instance?): {}\n{}",
+        enum_ident(&root.class_name).unwrap_or("Instance"),
+        generator.out.replace("\t\n", "\n")
+    );
+
+    let mut warnings = generator.warnings;
+    if generator.instances > WARN_INSTANCES {
+        warnings.push(format!(
+            "{} instances generated; cloning a prebuilt model is usually faster than building this many at runtime",
+            generator.instances
+        ));
+    }
+    if source.len() > WARN_SOURCE_BYTES {
+        warnings.push(format!(
+            "Generated module is {} KB; large modules slow down script loading",
+            source.len() / 1024
+        ));
+    }
+
+    CodegenOutput {
+        source,
+        instances: generator.instances,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_generate_module() {
+        let mut root = CodegenNode::new("Model", "Tower");
+        let mut base = CodegenNode::new("Part", "Base");
+        base.apply_rbxjson(&json!({
+            "properties": {
+                "Anchored": { "type": "bool", "value": true },
+                "Size": { "type": "Vector3", "value": { "x": 4, "y": 1, "z": 4.5 } },
+                "Material": { "type": "Enum", "value": { "enumType": "Material", "value": "Slate" } },
+                "Parent": { "type": "Ref", "value": null },
+                "Target": { "type": "Ref", "value": "abc" }
+            },
+            "attributes": { "Health": { "type": "float64", "value": 100 } },
+            "tags": ["Destructible"]
+        }));
+        // Same name as the root: must not shadow it
+        base.children.push(CodegenNode::new("Part", "Tower"));
+        root.children.push(base);
+        root.children.push(CodegenNode::new("Script", "Spin"));
+
+        let output = generate_module(&root, "src/Workspace/Tower");
+        assert_eq!(output.instances, 4);
+        let source = &output.source;
+        assert!(source.contains("return function(parent: Instance?): Model"));
+        assert!(source.contains("\tlocal tower = Instance.new(\"Model\")"));
+        assert!(source.contains("\t\tbase.Size = Vector3.new(4, 1, 4.5)"));
+        assert!(source.contains("\t\tbase.Material = Enum.Material.Slate"));
+        assert!(source.contains("\t\tbase:SetAttribute(\"Health\", 100)"));
+        assert!(source.contains("\t\tbase:AddTag(\"Destructible\")"));
+        assert!(source.contains("\t\t\ttower2.Parent = base"));
+        assert!(source.contains("\ttower.Parent = parent\n\treturn tower\nend\n"));
+        assert!(!source.contains("Target"));
+        assert_eq!(output.warnings.len(), 2, "{:?}", output.warnings);
+
+        assert_eq!(luau_value(&json!({ "type": "string", "value": "a\"b\n" })).unwrap(), "\"a\\\"b\\n\"");
+        assert_eq!(luau_value(&json!({ "type": "float", "value": 0.25 })).unwrap(), "0.25");
+        assert_eq!(
+            luau_value(&json!({ "type": "NumberSequence", "value": { "keypoints": [
+                { "time": 0, "value": 1, "envelope": 0 }, { "time": 1, "value": 0, "envelope": 0 }
+            ] } }))
+            .unwrap(),
+            "NumberSequence.new({ NumberSequenceKeypoint.new(0, 1, 0), NumberSequenceKeypoint.new(1, 0, 0) })"
+        );
+    }
+}
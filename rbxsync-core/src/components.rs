@@ -0,0 +1,276 @@
+//! Tag and attribute component index
+//!
+//! Component frameworks built on CollectionService tag instances and configure
+//! them through attributes. This module indexes an instance tree by tag and
+//! attribute so tools can list and diff a project by component instead of path.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A single tagged or attributed instance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentEntry {
+    /// DataModel path (e.g. "Workspace/Doors/FrontDoor")
+    pub path: String,
+    /// Roblox class name
+    pub class_name: String,
+    /// CollectionService tags on the instance
+    pub tags: Vec<String>,
+    /// Attribute names on the instance (sorted)
+    pub attributes: Vec<String>,
+}
+
+impl ComponentEntry {
+    /// Build an entry from serialized instance JSON (.rbxjson or plugin format).
+    ///
+    /// Returns `None` if the instance has neither tags nor attributes.
+    pub fn from_instance_json(path: &str, inst: &serde_json::Value) -> Option<Self> {
+        let mut tags: Vec<String> = inst
+            .get("tags")
+            .and_then(|t| t.as_array())
+            .map(|arr| arr.iter().filter_map(|t| t.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+        tags.sort();
+        tags.dedup();
+
+        // Attributes are a map in .rbxjson; Studio path listings send a name array
+        let mut attributes: Vec<String> = match inst.get("attributes") {
+            Some(serde_json::Value::Object(map)) => map.keys().cloned().collect(),
+            Some(serde_json::Value::Array(arr)) => {
+                arr.iter().filter_map(|a| a.as_str().map(String::from)).collect()
+            }
+            _ => Vec::new(),
+        };
+        attributes.sort();
+
+        if tags.is_empty() && attributes.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            path: path.to_string(),
+            class_name: inst
+                .get("className")
+                .and_then(|c| c.as_str())
+                .unwrap_or("Instance")
+                .to_string(),
+            tags,
+            attributes,
+        })
+    }
+}
+
+/// Instances grouped under one tag
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentGroup {
+    /// The tag name
+    pub tag: String,
+    /// Tagged instances, sorted by path
+    pub instances: Vec<ComponentEntry>,
+    /// Attribute names used by instances with this tag, with usage counts
+    pub attributes: BTreeMap<String, usize>,
+}
+
+/// Per-tag differences between two component indexes
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentDiff {
+    /// The tag name
+    pub tag: String,
+    /// Paths tagged in the new index but not the old one
+    pub added: Vec<String>,
+    /// Paths tagged in the old index but not the new one
+    pub removed: Vec<String>,
+    /// Paths tagged in both whose attribute set changed
+    pub changed: Vec<String>,
+    /// Paths tagged in both with identical attributes
+    pub unchanged: usize,
+}
+
+impl ComponentDiff {
+    /// Whether this tag has any differences
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Index of instances by tag and attribute
+#[derive(Debug, Clone, Default)]
+pub struct ComponentIndex {
+    entries: BTreeMap<String, ComponentEntry>,
+}
+
+impl ComponentIndex {
+    /// Create an empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an entry, replacing any previous entry at the same path
+    pub fn insert(&mut self, entry: ComponentEntry) {
+        self.entries.insert(entry.path.clone(), entry);
+    }
+
+    /// Number of indexed instances
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the index is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// All tags in the index, sorted
+    pub fn tags(&self) -> BTreeSet<String> {
+        self.entries.values().flat_map(|e| e.tags.iter().cloned()).collect()
+    }
+
+    /// Instances carrying the given tag, sorted by path
+    pub fn with_tag(&self, tag: &str) -> Vec<&ComponentEntry> {
+        self.entries.values().filter(|e| e.tags.iter().any(|t| t == tag)).collect()
+    }
+
+    /// Instances carrying the given attribute, sorted by path
+    pub fn with_attribute(&self, attribute: &str) -> Vec<&ComponentEntry> {
+        self.entries
+            .values()
+            .filter(|e| e.attributes.iter().any(|a| a == attribute))
+            .collect()
+    }
+
+    /// Group instances by tag. Untagged instances with attributes are omitted.
+    pub fn groups(&self) -> Vec<ComponentGroup> {
+        self.tags()
+            .into_iter()
+            .map(|tag| {
+                let instances: Vec<ComponentEntry> =
+                    self.with_tag(&tag).into_iter().cloned().collect();
+                let mut attributes = BTreeMap::new();
+                for inst in &instances {
+                    for attr in &inst.attributes {
+                        *attributes.entry(attr.clone()).or_insert(0) += 1;
+                    }
+                }
+                ComponentGroup { tag, instances, attributes }
+            })
+            .collect()
+    }
+
+    /// Compare this index (old) against another (new), grouped by tag.
+    ///
+    /// Tags with no differences are included with only `unchanged` set so callers
+    /// can report totals; use [`ComponentDiff::is_empty`] to filter them.
+    pub fn diff(&self, new: &ComponentIndex) -> Vec<ComponentDiff> {
+        let mut all_tags = self.tags();
+        all_tags.extend(new.tags());
+
+        all_tags
+            .into_iter()
+            .map(|tag| {
+                let old_entries: BTreeMap<&str, &ComponentEntry> =
+                    self.with_tag(&tag).into_iter().map(|e| (e.path.as_str(), e)).collect();
+                let new_entries: BTreeMap<&str, &ComponentEntry> =
+                    new.with_tag(&tag).into_iter().map(|e| (e.path.as_str(), e)).collect();
+
+                let mut diff = ComponentDiff { tag, ..Default::default() };
+                for (path, new_entry) in &new_entries {
+                    match old_entries.get(path) {
+                        None => diff.added.push(path.to_string()),
+                        Some(old_entry) if old_entry.attributes != new_entry.attributes => {
+                            diff.changed.push(path.to_string())
+                        }
+                        Some(_) => diff.unchanged += 1,
+                    }
+                }
+                for path in old_entries.keys() {
+                    if !new_entries.contains_key(path) {
+                        diff.removed.push(path.to_string());
+                    }
+                }
+                diff
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(path: &str, tags: &[&str], attrs: &[&str]) -> ComponentEntry {
+        ComponentEntry {
+            path: path.to_string(),
+            class_name: "Part".to_string(),
+            tags: tags.iter().map(|s| s.to_string()).collect(),
+            attributes: attrs.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_entry_from_instance_json() {
+        let inst = json!({
+            "className": "Model",
+            "tags": ["Door", "Interactable", "Door"],
+            "attributes": {
+                "Locked": {"type": "bool", "value": true},
+                "KeyId": {"type": "string", "value": "red"}
+            }
+        });
+        let e = ComponentEntry::from_instance_json("Workspace/FrontDoor", &inst).unwrap();
+        assert_eq!(e.class_name, "Model");
+        assert_eq!(e.tags, vec!["Door", "Interactable"]);
+        assert_eq!(e.attributes, vec!["KeyId", "Locked"]);
+
+        // Attribute name arrays (Studio listing format) are also accepted
+        let inst = json!({"className": "Part", "attributes": ["Speed"]});
+        let e = ComponentEntry::from_instance_json("Workspace/Part", &inst).unwrap();
+        assert_eq!(e.attributes, vec!["Speed"]);
+
+        let plain = json!({"className": "Part"});
+        assert!(ComponentEntry::from_instance_json("Workspace/Part", &plain).is_none());
+    }
+
+    #[test]
+    fn test_groups() {
+        let mut index = ComponentIndex::new();
+        index.insert(entry("Workspace/A", &["Door"], &["Locked"]));
+        index.insert(entry("Workspace/B", &["Door", "Lamp"], &["Locked", "Color"]));
+        index.insert(entry("Workspace/C", &[], &["Speed"]));
+
+        let groups = index.groups();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].tag, "Door");
+        assert_eq!(groups[0].instances.len(), 2);
+        assert_eq!(groups[0].attributes.get("Locked"), Some(&2));
+        assert_eq!(groups[1].tag, "Lamp");
+        assert_eq!(index.with_attribute("Speed").len(), 1);
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut old = ComponentIndex::new();
+        old.insert(entry("Workspace/A", &["Door"], &["Locked"]));
+        old.insert(entry("Workspace/B", &["Door"], &["Locked"]));
+        old.insert(entry("Workspace/C", &["Lamp"], &[]));
+
+        let mut new = ComponentIndex::new();
+        new.insert(entry("Workspace/A", &["Door"], &["Locked"]));
+        new.insert(entry("Workspace/B", &["Door"], &["Locked", "KeyId"]));
+        new.insert(entry("Workspace/D", &["Door"], &[]));
+
+        let diff = old.diff(&new);
+        let door = diff.iter().find(|d| d.tag == "Door").unwrap();
+        assert_eq!(door.added, vec!["Workspace/D"]);
+        assert_eq!(door.changed, vec!["Workspace/B"]);
+        assert_eq!(door.unchanged, 1);
+        assert!(door.removed.is_empty());
+
+        let lamp = diff.iter().find(|d| d.tag == "Lamp").unwrap();
+        assert_eq!(lamp.removed, vec!["Workspace/C"]);
+        assert!(!lamp.is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+//! Special container detection
+//!
+//! Some instances in the DataModel can't be told apart from a plain `Folder` by
+//! their directory alone: services, the `StarterPlayer` script containers and the
+//! `Workspace` camera. Extraction, sourcemap generation and build all share this
+//! mapping so a round trip preserves their class exactly.
+
+/// Top-level services that map 1:1 from directory name to class name
+const SERVICES: &[&str] = &[
+    "Workspace",
+    "Players",
+    "Lighting",
+    "ReplicatedFirst",
+    "ReplicatedStorage",
+    "ServerScriptService",
+    "ServerStorage",
+    "StarterGui",
+    "StarterPack",
+    "StarterPlayer",
+    "SoundService",
+    "Chat",
+    "Teams",
+    "TestService",
+    "LocalizationService",
+    "TextChatService",
+    "VoiceChatService",
+];
+
+/// Containers that only exist directly under `StarterPlayer`
+const STARTER_PLAYER_CONTAINERS: &[&str] = &["StarterPlayerScripts", "StarterCharacterScripts"];
+
+/// Get the class name of a top-level service directory
+pub fn service_class_name(name: &str) -> Option<&'static str> {
+    SERVICES.iter().find(|s| **s == name).copied()
+}
+
+/// Get the class name for a special container given its parent instance path.
+///
+/// `parent_path` is the `/`-separated DataModel path of the parent (empty for
+/// direct children of the DataModel). Returns `None` for ordinary instances.
+pub fn container_class_name(parent_path: &str, name: &str) -> Option<&'static str> {
+    match parent_path {
+        "" => service_class_name(name),
+        "StarterPlayer" => STARTER_PLAYER_CONTAINERS.iter().find(|s| **s == name).copied(),
+        "Workspace" if name == "Camera" => Some("Camera"),
+        _ => None,
+    }
+}
+
+/// Check whether an instance path must always be written as a directory.
+///
+/// Services and the StarterPlayer script containers are kept as folders even
+/// when empty, so build and sourcemap generation can see them.
+pub fn is_special_container(path: &str) -> bool {
+    match path.rsplit_once('/') {
+        None => service_class_name(path).is_some(),
+        Some((parent, name)) => {
+            parent == "StarterPlayer" && STARTER_PLAYER_CONTAINERS.contains(&name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_class_name() {
+        assert_eq!(service_class_name("Workspace"), Some("Workspace"));
+        assert_eq!(service_class_name("StarterPlayer"), Some("StarterPlayer"));
+        assert_eq!(service_class_name("StarterPlayerScripts"), None);
+        assert_eq!(service_class_name("MyFolder"), None);
+    }
+
+    #[test]
+    fn test_container_class_name() {
+        assert_eq!(container_class_name("", "ReplicatedStorage"), Some("ReplicatedStorage"));
+        assert_eq!(
+            container_class_name("StarterPlayer", "StarterPlayerScripts"),
+            Some("StarterPlayerScripts")
+        );
+        assert_eq!(
+            container_class_name("StarterPlayer", "StarterCharacterScripts"),
+            Some("StarterCharacterScripts")
+        );
+        assert_eq!(container_class_name("Workspace", "Camera"), Some("Camera"));
+        // Only special in their expected parent
+        assert_eq!(container_class_name("ReplicatedStorage", "StarterPlayerScripts"), None);
+        assert_eq!(container_class_name("Workspace/Map", "Camera"), None);
+        assert_eq!(container_class_name("", "Camera"), None);
+    }
+
+    #[test]
+    fn test_is_special_container() {
+        assert!(is_special_container("Workspace"));
+        assert!(is_special_container("StarterPlayer/StarterPlayerScripts"));
+        assert!(is_special_container("StarterPlayer/StarterCharacterScripts"));
+        assert!(!is_special_container("StarterPlayer/StarterPlayerScripts/Controller"));
+        assert!(!is_special_container("Workspace/Camera"));
+        assert!(!is_special_container("MyFolder"));
+    }
+}
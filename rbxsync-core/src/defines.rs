@@ -0,0 +1,167 @@
+//! Build-time constants
+//!
+//! Builds replace define tokens in script sources, so a release can embed its
+//! version or switch features off without editing scripts:
+//!
+//! ```json
+//! {
+//!   "version": "1.4.0",
+//!   "defines": { "__API_URL__": "https://api.example.com", "__DEBUG__": false }
+//! }
+//! ```
+//!
+//! `__VERSION__` (the config's `version`, else `dev`) and `__BUILD_TIME__`
+//! (UTC, e.g. `2026-10-16T02:00:00Z`) are always defined. Values go in as
+//! written: strings without quotes, so `"__API_URL__"` in a script becomes a
+//! string literal and `if __DEBUG__ then` becomes `if false then`. Tokens only
+//! match as whole identifiers, and anything not defined is left alone.
+
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde_json::Value;
+
+pub const VERSION_TOKEN: &str = "__VERSION__";
+pub const BUILD_TIME_TOKEN: &str = "__BUILD_TIME__";
+
+/// Version used when the config has none
+pub const DEFAULT_VERSION: &str = "dev";
+
+/// Tokens and the text each is replaced with
+#[derive(Debug, Clone)]
+pub struct Defines {
+    values: BTreeMap<String, String>,
+    pattern: Option<Regex>,
+}
+
+impl Defines {
+    pub fn new(values: BTreeMap<String, String>) -> Self {
+        let mut tokens: Vec<&String> = values.keys().filter(|t| !t.is_empty()).collect();
+        // Longest first, so a token never shadows a longer one it prefixes
+        tokens.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+        let pattern = (!tokens.is_empty())
+            .then(|| {
+                let alternation: Vec<String> = tokens.iter().map(|t| regex::escape(t)).collect();
+                Regex::new(&format!(r"\b(?:{})\b", alternation.join("|"))).ok()
+            })
+            .flatten();
+        Self { values, pattern }
+    }
+
+    /// The built-in tokens, then the config's `defines`, then `extra` (from
+    /// the command line), later ones winning. `now` is the build time in Unix seconds.
+    pub fn from_config(config: &Value, extra: &BTreeMap<String, String>, now: u64) -> Self {
+        let mut values = BTreeMap::new();
+        let version = config.get("version").and_then(|v| v.as_str()).unwrap_or(DEFAULT_VERSION);
+        values.insert(VERSION_TOKEN.to_string(), version.to_string());
+        values.insert(BUILD_TIME_TOKEN.to_string(), utc_timestamp(now));
+        if let Some(defines) = config.get("defines").and_then(|d| d.as_object()) {
+            for (token, value) in defines {
+                values.insert(token.clone(), define_text(value));
+            }
+        }
+        values.extend(extra.iter().map(|(k, v)| (k.clone(), v.clone())));
+        Self::new(values)
+    }
+
+    pub fn get(&self, token: &str) -> Option<&str> {
+        self.values.get(token).map(String::as_str)
+    }
+
+    /// Replace every token in `source`; returns the new source and how many were replaced
+    pub fn apply(&self, source: &str) -> (String, usize) {
+        let Some(pattern) = &self.pattern else {
+            return (source.to_string(), 0);
+        };
+        let mut count = 0;
+        let replaced = pattern.replace_all(source, |caps: &regex::Captures| {
+            count += 1;
+            self.values[&caps[0]].clone()
+        });
+        (replaced.into_owned(), count)
+    }
+}
+
+/// The text a JSON define value is replaced with: strings as they are,
+/// `null` as `nil`, anything else as JSON
+pub fn define_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "nil".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Parse `KEY=VALUE` from the command line
+pub fn parse_define(define: &str) -> Option<(String, String)> {
+    let (key, value) = define.split_once('=')?;
+    let key = key.trim();
+    (!key.is_empty()).then(|| (key.to_string(), value.to_string()))
+}
+
+/// (year, month, day) for days since 1970-01-01
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// UTC date stamp (YYYYMMDD) and time stamp (HHMMSS) for a Unix time
+pub fn utc_stamps(secs: u64) -> (String, String) {
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let time = secs % 86_400;
+    (
+        format!("{:04}{:02}{:02}", year, month, day),
+        format!("{:02}{:02}{:02}", time / 3600, time / 60 % 60, time % 60),
+    )
+}
+
+/// ISO 8601 UTC time for a Unix time, e.g. `2026-10-16T02:00:00Z`
+pub fn utc_timestamp(secs: u64) -> String {
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let time = secs % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_defines() {
+        let config = serde_json::json!({
+            "version": "1.4.0",
+            "defines": { "__API__": "https://api.example.com", "__DEBUG__": false, "__API_V2__": 2 }
+        });
+        let extra = BTreeMap::from([("__DEBUG__".to_string(), "true".to_string())]);
+        let defines = Defines::from_config(&config, &extra, 1_791_511_200);
+        assert_eq!(defines.get(BUILD_TIME_TOKEN), Some("2026-10-09T02:00:00Z"));
+
+        let source = "local v = \"__VERSION__\"\nif __DEBUG__ then print(\"__API__\", __API_V2__, x__API__, __UNSET__) end";
+        let (replaced, count) = defines.apply(source);
+        assert_eq!(
+            replaced,
+            "local v = \"1.4.0\"\nif true then print(\"https://api.example.com\", 2, x__API__, __UNSET__) end"
+        );
+        assert_eq!(count, 4);
+
+        assert_eq!(Defines::from_config(&Value::Null, &BTreeMap::new(), 0).get(VERSION_TOKEN), Some(DEFAULT_VERSION));
+        assert_eq!(parse_define("__FLAG__=on=1"), Some(("__FLAG__".to_string(), "on=1".to_string())));
+        assert_eq!(parse_define("=1"), None);
+    }
+}
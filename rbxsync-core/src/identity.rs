@@ -0,0 +1,89 @@
+//! Persistent instance identity
+//!
+//! `referenceId` comes from `GetDebugId` and changes every Studio session, so it
+//! can't tell a renamed instance from a new one. The plugin gives each instance
+//! a GUID in the `RbxSyncId` attribute when extracting; attributes travel with
+//! the instance through renames and moves in Studio and are written to
+//! .rbxjson, so diffs can pair an instance's old and new paths.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Attribute holding an instance's GUID
+pub const GUID_ATTRIBUTE: &str = "RbxSyncId";
+
+/// An instance found under different paths on each side of a diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MovedEntry {
+    pub from: String,
+    pub to: String,
+    pub class_name: String,
+    pub guid: String,
+}
+
+/// GUID of a serialized instance or `studio:paths` entry.
+///
+/// Reads `guid` (listings) or the `RbxSyncId` attribute (.rbxjson).
+pub fn guid_of(entry: &serde_json::Value) -> Option<&str> {
+    entry
+        .get("guid")
+        .and_then(|g| g.as_str())
+        .or_else(|| {
+            let attr = entry.get("attributes")?.get(GUID_ATTRIBUTE)?;
+            // Attributes are stored as { "type": "string", "value": ... }
+            attr.get("value").and_then(|v| v.as_str()).or_else(|| attr.as_str())
+        })
+        .filter(|g| !g.is_empty())
+}
+
+/// Pair up entries present on only one side that share a GUID.
+///
+/// `only_a` and `only_b` are `(path, guid)` pairs. GUIDs seen more than once on
+/// a side (e.g. a duplicated instance that kept its attribute) are ambiguous
+/// and left unpaired. Returns `(path in a, path in b, guid)`, sorted by path in a.
+pub fn find_moves<'a>(
+    only_a: impl IntoIterator<Item = (&'a str, &'a str)>,
+    only_b: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<(&'a str, &'a str, &'a str)> {
+    fn index<'a>(entries: impl IntoIterator<Item = (&'a str, &'a str)>) -> HashMap<&'a str, Option<&'a str>> {
+        let mut by_guid = HashMap::new();
+        for (path, guid) in entries {
+            by_guid
+                .entry(guid)
+                .and_modify(|p: &mut Option<&str>| *p = None)
+                .or_insert(Some(path));
+        }
+        by_guid
+    }
+
+    let by_guid_b = index(only_b);
+    let mut moves: Vec<_> = index(only_a)
+        .into_iter()
+        .filter_map(|(guid, path_a)| {
+            let path_b = (*by_guid_b.get(guid)?)?;
+            Some((path_a?, path_b, guid))
+        })
+        .collect();
+    moves.sort();
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_find_moves() {
+        let file = json!({ "attributes": { "RbxSyncId": { "type": "string", "value": "g1" } } });
+        assert_eq!(guid_of(&file), Some("g1"));
+        assert_eq!(guid_of(&json!({ "guid": "g2" })), Some("g2"));
+        assert_eq!(guid_of(&json!({ "guid": "" })), None);
+
+        let a = [("Workspace/Old", "g1"), ("Workspace/Gone", "g2"), ("Workspace/CopyA", "g3")];
+        let b = [("Workspace/New", "g1"), ("Workspace/CopyB", "g3"), ("Workspace/CopyC", "g3")];
+        assert_eq!(find_moves(a, b), vec![("Workspace/Old", "Workspace/New", "g1")]);
+    }
+}
@@ -2,32 +2,126 @@
 //!
 //! This crate provides the core functionality for RbxSync:
 //! - Roblox property type definitions and serialization
+//! - Conversion between .rbxjson values and rbx-dom Variants (serde_props)
 //! - Instance representation
 //! - Project configuration
 //! - Plugin building (.rbxm generation)
-//! - Rojo project file parsing and migration
+//! - Rojo project file parsing and migration, and export back to Rojo's layout
 //! - Luau obfuscation for build-time transforms
+//! - Tag/attribute component indexing
+//! - Legacy .lua to .luau migration
+//! - Place-to-place diffing
+//! - Property-level diffing of files against Studio
+//! - Persistent instance identity (GUIDs) for matching renamed instances
+//! - Property search-and-replace across .rbxjson files
+//! - JSON Schema for .rbxjson files
+//! - Project templates
+//! - Luau builder code generation from src/ subtrees
+//! - Reporting of properties that can't be extracted or built
+//! - Registry of running servers and their ports
+//! - API token for servers that require one
+//! - Compact project summaries (service tree, module exports, remotes)
+//! - Per-variant property overrides (script Enabled and RunContext)
+//! - Headless place/model building from src (builder)
+//! - Build-time constants substituted into scripts (defines)
+//! - Offline import of .rbxl/.rbxlx places into src
+//! - Round-trip verification of builds (verify)
+//! - Static HTML site export for design review
+//! - sourcemap.json generation for Luau LSP
+//! - Include/exclude globs for selective sync
+//! - Chunked terrain storage in terrain/
+//! - Request/response types for the HTTP API, shared by server and client
 
+pub mod api;
+pub mod assets;
+pub mod auth;
+pub mod builder;
+pub mod codegen;
+pub mod components;
+pub mod containers;
+pub mod defines;
+pub mod identity;
+pub mod migrate;
 pub mod obfuscator;
+pub mod overrides;
 pub mod path_utils;
+pub mod place_diff;
+pub mod place_import;
 pub mod plugin_builder;
+pub mod property_diff;
+pub mod replace;
 pub mod rojo;
+pub mod rojo_export;
+pub mod schema;
+pub mod serde_props;
+pub mod server_registry;
+pub mod site;
+pub mod sourcemap;
+pub mod summary;
+pub mod sync_filter;
+pub mod template;
+pub mod terrain;
 pub mod types;
+pub mod unsupported;
+pub mod verify;
 
 // Re-export commonly used types
-pub use obfuscator::{Obfuscator, ObfuscatorConfig, ObfuscationResult};
-pub use plugin_builder::{build_plugin, build_plugin_with_stats, find_existing_rbxsync_plugin, get_studio_plugins_folder, install_plugin, PluginBuildConfig, PluginBuildStats};
+pub use assets::{resolve_assets, resolve_dom_assets, AssetKind, AssetManifest};
+pub use auth::{client_token, server_token, token_path, TOKEN_ENV, TOKEN_HEADER};
+pub use builder::{
+    build_model, build_project, model_targets, BuildError, BuildFormat, BuildOptions, BuildProfile, BuildReport, ModelTarget,
+};
+pub use codegen::{generate_module, luau_value, CodegenNode, CodegenOutput};
+pub use components::{ComponentDiff, ComponentEntry, ComponentGroup, ComponentIndex};
+pub use containers::{container_class_name, is_special_container, service_class_name};
+pub use defines::{parse_define, Defines};
+pub use identity::{find_moves, guid_of, MovedEntry, GUID_ATTRIBUTE};
+pub use migrate::{
+    check_luau_compat, find_legacy_lua_files, migrate_lua_files, LuaCompatWarning, LuaMigration,
+    LuaMigrationReport,
+};
+pub use obfuscator::{ObfuscationResult, ObfuscationSettings, ObfuscationStats, Obfuscator, ObfuscatorConfig};
+pub use overrides::{AppliedOverride, OverrideReport, Overrides, PropertyOverride, RUN_CONTEXTS, VARIANT_ENV};
+pub use place_diff::{diff_places, ClassChange, PlaceDiff, PlaceOnlyEntry, PropertyChange};
+pub use place_import::{import_place, read_place, ImportError, ImportReport};
+pub use plugin_builder::{build_plugin, build_plugin_with_stats, find_existing_rbxsync_plugin, get_studio_plugins_folder, install_plugin, read_plugin_modules, PluginBuildConfig, PluginBuildStats, PluginModule, DEFAULT_BUILD_CACHE_DIR};
+pub use property_diff::{diff_instance, summarize_value, ModifiedEntry, PropertyDelta};
+pub use replace::{replace_property, PropertyReplace, PropertyReplacement};
+pub use schema::{emit_schema, rbxjson_schema, schema_ref, with_schema_ref, SCHEMA_FILE, SCHEMA_URL, SCHEMA_VERSION};
+pub use serde_props::{json_to_variant, normalize_properties, omit_defaults, strip_defaults, validate_value, variant_to_json};
+pub use server_registry::{
+    discovery_ports, load_registry, port_for_version, read_workspace_server, register_server, registry_path,
+    unregister_server, write_workspace_server, ServerEntry, WORKSPACE_SERVER_FILE,
+    DEFAULT_PORT, FAILOVER_PORTS,
+};
+pub use site::{find_requires, highlight_luau, render_site, SiteInstance, SitePage};
+pub use summary::{
+    build_summary, render_markdown, scan_module, ModuleApi, ProjectSummary, SummaryEntry,
+};
+pub use sync_filter::{glob_match, ProtectedPaths, SyncFilter};
+pub use template::{create_template, instantiate_template, templates_dir, TemplateManifest, TemplateReport, NAME_PLACEHOLDER};
+pub use unsupported::{summarize_unsupported, unsupported_type, UnsupportedProperty, UnsupportedReport};
+pub use verify::{verify_project, LossyProperty, VerifyReport};
 pub use rojo::{
-    find_rojo_project, parse_rojo_project, rojo_to_tree_mapping, RojoError, RojoProject, RojoTree,
+    find_rojo_project, keeps_unknown_instance, parse_rojo_project, rojo_to_tree_mapping, unknown_instance_settings,
+    RojoError, RojoProject, RojoTree,
 };
+pub use rojo_export::{export_to_rojo, RojoExportFile, RojoExportReport, ROJO_PROJECT_FILE};
 pub use types::{
     AttributeValue, CFrame, Color3, EnumValue, Instance, InstanceMeta, ProjectConfig,
     PropertyValue, Vector2, Vector3,
     // Wally package support
     PackageConfig, PackageDirectories, WallyError, WallyLock, WallyLockedPackage,
     WallyManifest, WallyPackageInfo, find_wally_manifest, find_wally_lock, is_package_path,
+    InstalledPackage, VersionDrift, WallyDrift, check_wally_drift, compare_wally_lock, installed_packages,
+    parse_index_folder, WALLY_PACKAGE_FOLDERS,
+    PackageLink, PlannedPackage, WallyInstallPlan, WallyLockDependency, finish_wally_install,
+    index_folder_name, plan_wally_install, split_package_spec, unpack_wally_package,
     // Harness system for multi-session AI development
     Feature, FeaturePriority, FeatureStatus, FeaturesFile, GameDefinition,
     HarnessState, SessionLog, SessionLogEntry,
 };
-pub use path_utils::{normalize_path, path_to_string, path_with_suffix, pathbuf_with_suffix, sanitize_filename};
+pub use path_utils::{
+    is_under_any, may_contain_any, normalize_instance_root, normalize_path, path_to_string, path_with_suffix,
+    pathbuf_with_suffix, sanitize_filename,
+};
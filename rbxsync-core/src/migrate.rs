@@ -0,0 +1,461 @@
+//! Legacy .lua migration
+//!
+//! Older projects store scripts as `.lua`, which Luau tooling (luau-lsp, selene's
+//! Roblox std, StyLua) treats differently from `.luau`. This module finds those
+//! files, renames them to `.luau`, and reports Lua 5.2+ syntax that Luau rejects.
+//!
+//! Instance-path requires (`require(script.Parent.Foo)`) don't see the file
+//! extension and keep working as-is; string requires that spell out `.lua` and
+//! `sourcemap.json` entries are rewritten to the new paths.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::path_utils::path_to_string;
+
+/// Directories never scanned for legacy files
+const SKIP_DIRS: &[&str] = &[".git", ".rbxsync", "node_modules", "Packages"];
+
+/// A construct Luau will reject, found in a legacy file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LuaCompatWarning {
+    /// 1-based line number
+    pub line: usize,
+    pub message: String,
+}
+
+/// One migrated (or planned) file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LuaMigration {
+    /// Original path, relative to the project directory
+    pub from: String,
+    /// New path, relative to the project directory
+    pub to: String,
+    /// String requires rewritten in this file
+    pub requires_updated: usize,
+    pub warnings: Vec<LuaCompatWarning>,
+}
+
+/// Result of migrating a project
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LuaMigrationReport {
+    pub migrated: Vec<LuaMigration>,
+    /// Files left alone (path, reason)
+    pub skipped: Vec<(String, String)>,
+    /// Number of sourcemap.json entries updated
+    pub sourcemap_entries: usize,
+    /// Whether files were actually changed (false for dry runs)
+    pub applied: bool,
+}
+
+/// Find `.lua` files under `dir`, sorted by path
+pub fn find_legacy_lua_files(dir: &Path) -> Vec<PathBuf> {
+    fn walk(dir: &Path, out: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                let skip = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| SKIP_DIRS.contains(&n))
+                    .unwrap_or(false);
+                if !skip {
+                    walk(&path, out);
+                }
+            } else if path.extension().map(|e| e == "lua").unwrap_or(false) {
+                out.push(path);
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    walk(dir, &mut files);
+    files.sort();
+    files
+}
+
+/// Replace code inside strings and comments with spaces, keeping line breaks,
+/// so syntax checks only see real code.
+pub(crate) fn strip_strings_and_comments(source: &str) -> String {
+    blank_out(source, true)
+}
+
+/// Replace comments with spaces, keeping strings and line breaks
+pub(crate) fn strip_comments(source: &str) -> String {
+    blank_out(source, false)
+}
+
+fn blank_out(source: &str, strings: bool) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::with_capacity(source.len());
+    let mut i = 0;
+
+    // Length of a long bracket opener (`[[`, `[==[`) at `i`, or None
+    let long_bracket = |i: usize| -> Option<usize> {
+        if chars.get(i) != Some(&'[') {
+            return None;
+        }
+        let mut j = i + 1;
+        while chars.get(j) == Some(&'=') {
+            j += 1;
+        }
+        (chars.get(j) == Some(&'[')).then_some(j - i - 1)
+    };
+
+    let blank = |c: char| if c == '\n' { '\n' } else { ' ' };
+    let blank_string = |c: char| if strings { blank(c) } else { c };
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '-' && chars.get(i + 1) == Some(&'-') {
+            // Comment: long or to end of line
+            if let Some(level) = long_bracket(i + 2) {
+                let close: String = format!("]{}]", "=".repeat(level));
+                let rest: String = chars[i..].iter().collect();
+                let end = rest[4 + level..]
+                    .find(&close)
+                    .map(|p| p + 4 + level + close.len())
+                    .unwrap_or(rest.len());
+                let skipped = rest[..end].chars().count();
+                out.extend(chars[i..i + skipped].iter().map(|c| blank(*c)));
+                i += skipped;
+            } else {
+                while i < chars.len() && chars[i] != '\n' {
+                    out.push(' ');
+                    i += 1;
+                }
+            }
+        } else if let Some(level) = long_bracket(i) {
+            let close: String = format!("]{}]", "=".repeat(level));
+            let rest: String = chars[i..].iter().collect();
+            let end = rest[2 + level..]
+                .find(&close)
+                .map(|p| p + 2 + level + close.len())
+                .unwrap_or(rest.len());
+            let skipped = rest[..end].chars().count();
+            out.extend(chars[i..i + skipped].iter().map(|c| blank_string(*c)));
+            i += skipped;
+        } else if c == '"' || c == '\'' || c == '`' {
+            out.push(blank_string(c));
+            i += 1;
+            while i < chars.len() && chars[i] != c && chars[i] != '\n' {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    out.push(blank_string(chars[i]));
+                    i += 1;
+                }
+                out.push(blank_string(chars[i]));
+                i += 1;
+            }
+            if i < chars.len() {
+                out.push(blank_string(chars[i]));
+                i += 1;
+            }
+        } else {
+            out.push(c);
+            i += 1;
+        }
+    }
+    out
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Check whether the text starting at `rest` (after skipping spaces) begins an operand
+fn starts_operand(rest: &str) -> bool {
+    rest.trim_start()
+        .chars()
+        .next()
+        .map(|c| is_ident_char(c) || c == '(')
+        .unwrap_or(false)
+}
+
+/// Report Lua 5.2+ syntax that the Luau parser rejects.
+///
+/// This is a lexical check, not a parser: it looks for `goto` and labels,
+/// bitwise operators and `<const>`/`<close>` attributes outside strings and
+/// comments. Lines that look like Luau type annotations are not checked for
+/// `&`/`|`, which double as intersection and union types there.
+pub fn check_luau_compat(source: &str) -> Vec<LuaCompatWarning> {
+    let code = strip_strings_and_comments(source);
+    let mut warnings = Vec::new();
+
+    for (index, line) in code.lines().enumerate() {
+        let mut warn = |message: &str| {
+            warnings.push(LuaCompatWarning {
+                line: index + 1,
+                message: message.to_string(),
+            })
+        };
+        let trimmed = line.trim_start();
+
+        // goto statements (`goto` is only a keyword when followed by a label name)
+        let has_goto = line.match_indices("goto").any(|(pos, _)| {
+            let before_ok = !line[..pos].chars().next_back().map(is_ident_char).unwrap_or(false);
+            let rest = &line[pos + 4..];
+            before_ok && rest.starts_with(char::is_whitespace) && starts_operand(rest)
+        });
+        if has_goto {
+            warn("`goto` is not supported in Luau");
+        }
+
+        // ::label:: (but not `x :: Type` casts)
+        if let Some(start) = line.find("::") {
+            let after = &line[start + 2..];
+            let name_len = after.chars().take_while(|c| is_ident_char(*c)).count();
+            if name_len > 0 && after[name_len..].starts_with("::") {
+                warn("labels (`::name::`) are not supported in Luau");
+            }
+        }
+
+        // Lua 5.4 variable attributes
+        if line.contains("<const>") || line.contains("<close>") {
+            warn("`<const>`/`<close>` attributes are not supported in Luau");
+        }
+
+        // Bitwise operators (Luau uses the bit32 library)
+        let bytes: Vec<char> = line.chars().collect();
+        let mut bitwise = false;
+        for (i, c) in bytes.iter().enumerate() {
+            let next = bytes.get(i + 1).copied();
+            let prev = if i > 0 { bytes.get(i - 1).copied() } else { None };
+            match c {
+                '<' if next == Some('<') => bitwise = true,
+                '>' if next == Some('>') && prev != Some('>') => {
+                    // `Array<Map<K, V>>` closes generics; only flag when an operand follows
+                    let rest: String = bytes[i + 2..].iter().collect();
+                    bitwise |= starts_operand(&rest);
+                }
+                '~' if next != Some('=') => bitwise = true,
+                '&' | '|' => {
+                    let type_context = trimmed.starts_with("type ")
+                        || trimmed.starts_with("export type ")
+                        || line.contains(':');
+                    bitwise |= !type_context;
+                }
+                _ => {}
+            }
+        }
+        if bitwise {
+            warn("bitwise operators are not supported in Luau; use the bit32 library");
+        }
+    }
+
+    warnings
+}
+
+/// Rewrite string requires that name a `.lua` file (`require("./util.lua")`).
+///
+/// Returns the new source and the number of requires rewritten.
+pub fn rewrite_string_requires(source: &str) -> (String, usize) {
+    let mut out = String::with_capacity(source.len());
+    let mut count = 0;
+    let mut rest = source;
+
+    while let Some(pos) = rest.find("require") {
+        let (before, after) = rest.split_at(pos + "require".len());
+        out.push_str(before);
+        rest = after;
+
+        // require("x.lua") or require "x.lua"
+        let trimmed = rest.trim_start();
+        let trimmed = trimmed.strip_prefix('(').map(str::trim_start).unwrap_or(trimmed);
+        let Some(quote) = trimmed.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        let literal_start = rest.len() - trimmed.len() + 1;
+        let Some(len) = rest[literal_start..].find(quote) else {
+            continue;
+        };
+        let literal = &rest[literal_start..literal_start + len];
+        if let Some(stem) = literal.strip_suffix(".lua") {
+            out.push_str(&rest[..literal_start]);
+            out.push_str(stem);
+            out.push_str(".luau");
+            rest = &rest[literal_start + len..];
+            count += 1;
+        }
+    }
+    out.push_str(rest);
+    (out, count)
+}
+
+/// Point `filePaths` entries in a sourcemap at renamed files.
+///
+/// `renames` maps old to new paths relative to the project directory, with `/`
+/// separators. Returns the number of entries updated.
+pub fn update_sourcemap_paths(node: &mut serde_json::Value, renames: &HashMap<String, String>) -> usize {
+    let mut updated = 0;
+    if let Some(paths) = node.get_mut("filePaths").and_then(|p| p.as_array_mut()) {
+        for path in paths.iter_mut() {
+            let Some(current) = path.as_str().map(|s| s.replace('\\', "/")) else {
+                continue;
+            };
+            let current = current.trim_start_matches("./").to_string();
+            let renamed = renames.iter().find_map(|(from, to)| {
+                current
+                    .strip_suffix(from.as_str())
+                    .filter(|prefix| prefix.is_empty() || prefix.ends_with('/'))
+                    .map(|prefix| format!("{}{}", prefix, to))
+            });
+            if let Some(renamed) = renamed {
+                *path = serde_json::Value::String(renamed);
+                updated += 1;
+            }
+        }
+    }
+    if let Some(children) = node.get_mut("children").and_then(|c| c.as_array_mut()) {
+        for child in children {
+            updated += update_sourcemap_paths(child, renames);
+        }
+    }
+    updated
+}
+
+/// Migrate every `.lua` file under `<project_dir>/src` to `.luau`.
+///
+/// Files whose `.luau` counterpart already exists are skipped rather than
+/// overwritten. With `dry_run`, the report is built without touching disk.
+pub fn migrate_lua_files(project_dir: &Path, dry_run: bool) -> io::Result<LuaMigrationReport> {
+    let src_dir = project_dir.join("src");
+    let mut report = LuaMigrationReport {
+        applied: !dry_run,
+        ..Default::default()
+    };
+    let mut renames = HashMap::new();
+
+    for path in find_legacy_lua_files(&src_dir) {
+        let rel = |p: &Path| path_to_string(p.strip_prefix(project_dir).unwrap_or(p));
+        let target = path.with_extension("luau");
+        if target.exists() {
+            report
+                .skipped
+                .push((rel(&path), format!("{} already exists", rel(&target))));
+            continue;
+        }
+
+        let source = fs::read_to_string(&path)?;
+        let warnings = check_luau_compat(&source);
+        let (rewritten, requires_updated) = rewrite_string_requires(&source);
+
+        if !dry_run {
+            fs::write(&target, rewritten)?;
+            fs::remove_file(&path)?;
+        }
+
+        renames.insert(rel(&path), rel(&target));
+        report.migrated.push(LuaMigration {
+            from: rel(&path),
+            to: rel(&target),
+            requires_updated,
+            warnings,
+        });
+    }
+
+    let sourcemap_path = project_dir.join("sourcemap.json");
+    if !renames.is_empty() && sourcemap_path.exists() {
+        if let Some(mut sourcemap) = fs::read_to_string(&sourcemap_path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        {
+            report.sourcemap_entries = update_sourcemap_paths(&mut sourcemap, &renames);
+            if report.sourcemap_entries > 0 && !dry_run {
+                let json = serde_json::to_string_pretty(&sourcemap).map_err(io::Error::other)?;
+                fs::write(&sourcemap_path, json)?;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_luau_compat() {
+        let source = r#"
+local a = 1 << 4
+local b = x & 0xFF
+goto continue
+::continue::
+local c <const> = 5
+local ok = a ~= b
+local s = "a & b | c << d"
+-- goto nowhere ~ 1
+local t: Map<string, Array<number>> = {}
+local u: string | number = 1
+local v = y :: number
+"#;
+        let warnings = check_luau_compat(source);
+        let lines: Vec<usize> = warnings.iter().map(|w| w.line).collect();
+        assert_eq!(lines, vec![2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_rewrite_string_requires() {
+        let source = "local a = require(\"./util.lua\")\nlocal b = require 'lib.lua'\nlocal c = require(script.Parent.Foo)\n";
+        let (out, count) = rewrite_string_requires(source);
+        assert_eq!(count, 2);
+        assert!(out.contains("require(\"./util.luau\")"));
+        assert!(out.contains("require 'lib.luau'"));
+        assert!(out.contains("require(script.Parent.Foo)"));
+    }
+
+    #[test]
+    fn test_migrate_lua_files() {
+        let temp = TempDir::new().unwrap();
+        let scripts = temp.path().join("src/ServerScriptService");
+        fs::create_dir_all(&scripts).unwrap();
+        fs::write(scripts.join("Main.server.lua"), "print(1 << 2)").unwrap();
+        fs::write(scripts.join("Util.lua"), "return {}").unwrap();
+        fs::write(scripts.join("Dup.lua"), "return 1").unwrap();
+        fs::write(scripts.join("Dup.luau"), "return 2").unwrap();
+        fs::write(
+            temp.path().join("sourcemap.json"),
+            json!({
+                "name": "Game",
+                "className": "DataModel",
+                "children": [{
+                    "name": "Main",
+                    "className": "Script",
+                    "filePaths": ["src/ServerScriptService/Main.server.lua"]
+                }]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        // Dry run changes nothing
+        let report = migrate_lua_files(temp.path(), true).unwrap();
+        assert_eq!(report.migrated.len(), 2);
+        assert!(scripts.join("Util.lua").exists());
+
+        let report = migrate_lua_files(temp.path(), false).unwrap();
+        assert_eq!(report.migrated.len(), 2);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.sourcemap_entries, 1);
+        assert!(scripts.join("Main.server.luau").exists());
+        assert!(!scripts.join("Util.lua").exists());
+        assert_eq!(fs::read_to_string(scripts.join("Dup.luau")).unwrap(), "return 2");
+
+        let main = report.migrated.iter().find(|m| m.from.ends_with("Main.server.lua")).unwrap();
+        assert_eq!(main.warnings.len(), 1);
+
+        let sourcemap = fs::read_to_string(temp.path().join("sourcemap.json")).unwrap();
+        assert!(sourcemap.contains("src/ServerScriptService/Main.server.luau"));
+    }
+}
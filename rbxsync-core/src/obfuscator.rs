@@ -9,13 +9,22 @@
 //! - Debug statement stripping
 //! - Comment removal
 //! - Variable prefix randomization
+//!
+//! `rbxsync build --obfuscate` runs it over the scripts of a build, limited by
+//! the `obfuscation` section of rbxsync.json:
+//!
+//! ```json
+//! { "obfuscation": { "include": ["ServerScriptService/**"], "exclude": ["**/Tests/**"], "config": "obfuscate.toml" } }
+//! ```
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use rand::Rng;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::sync_filter::SyncFilter;
 
 /// Configuration for the obfuscator loaded from obfuscate.toml
 #[derive(Debug, Clone, Deserialize)]
@@ -128,6 +137,72 @@ impl ObfuscationResult {
     }
 }
 
+/// The `obfuscation` section of rbxsync.json
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ObfuscationSettings {
+    /// Instance paths of the scripts to obfuscate, as in `sync.include`; empty means all
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Obfuscator config, relative to the project (default: `obfuscate.toml` if it exists)
+    #[serde(default)]
+    pub config: Option<PathBuf>,
+}
+
+impl ObfuscationSettings {
+    /// The settings in an rbxsync.json value
+    pub fn from_config(config: &serde_json::Value) -> Self {
+        config
+            .get("obfuscation")
+            .and_then(|section| serde_json::from_value(section.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Which scripts are obfuscated
+    pub fn filter(&self) -> SyncFilter {
+        SyncFilter { include: self.include.clone(), exclude: self.exclude.clone() }
+    }
+
+    /// An obfuscator with the configured (or default) settings
+    pub fn obfuscator(&self, project_dir: &Path) -> Result<Obfuscator> {
+        match &self.config {
+            Some(path) => Obfuscator::from_config_file(&project_dir.join(path)),
+            None => {
+                let default = project_dir.join("obfuscate.toml");
+                if default.is_file() {
+                    Obfuscator::from_config_file(&default)
+                } else {
+                    Ok(Obfuscator::with_defaults())
+                }
+            }
+        }
+    }
+}
+
+/// Totals over several obfuscated scripts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObfuscationStats {
+    pub scripts: usize,
+    pub strings_encoded: usize,
+    pub debug_stripped: usize,
+    pub comments_removed: usize,
+}
+
+impl ObfuscationStats {
+    pub fn add(&mut self, result: &ObfuscationResult) {
+        self.scripts += 1;
+        self.strings_encoded += result.strings_encoded;
+        self.debug_stripped += result.debug_stripped;
+        self.comments_removed += result.comments_removed;
+    }
+
+    pub fn total_transforms(&self) -> usize {
+        self.strings_encoded + self.debug_stripped + self.comments_removed
+    }
+}
+
 /// Obfuscator instance with configuration
 pub struct Obfuscator {
     config: ObfuscatorConfig,
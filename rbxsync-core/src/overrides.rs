@@ -0,0 +1,270 @@
+//! Config-driven property overrides
+//!
+//! Projects can switch sets of scripts on or off per variant from rbxsync.json
+//! instead of editing them by hand:
+//!
+//! ```json
+//! {
+//!   "variant": "dev",
+//!   "overrides": {
+//!     "dev": {
+//!       "ServerScriptService/Analytics": { "Enabled": false },
+//!       "ServerScriptService/Combat": { "RunContext": "Server" }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! The variant is chosen explicitly (`--variant`), then by `RBXSYNC_VARIANT`,
+//! then by the config's `variant`. Overrides are applied last, on top of what
+//! the files say, so the files themselves never change.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::{EnumValue, PropertyValue};
+
+/// Environment variable selecting the variant
+pub const VARIANT_ENV: &str = "RBXSYNC_VARIANT";
+
+/// Properties an override may set
+pub const OVERRIDE_PROPERTIES: &[&str] = &["Enabled", "Disabled", "RunContext"];
+
+/// `Enum.RunContext` items, in value order
+pub const RUN_CONTEXTS: &[&str] = &["Legacy", "Server", "Client", "Plugin"];
+
+/// One property set on one instance
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyOverride {
+    /// DataModel path (e.g. "ServerScriptService/Analytics")
+    pub path: String,
+    pub property: String,
+    pub value: PropertyValue,
+}
+
+impl PropertyOverride {
+    /// The value as written in rbxsync.json
+    pub fn display_value(&self) -> String {
+        match &self.value {
+            PropertyValue::Bool(b) => b.to_string(),
+            PropertyValue::Enum(e) => e.value.clone(),
+            other => format!("{:?}", other),
+        }
+    }
+}
+
+/// An override that took effect
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedOverride {
+    pub path: String,
+    pub property: String,
+    pub value: String,
+}
+
+/// What a sync or build did with the selected variant's overrides
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideReport {
+    pub variant: String,
+    pub applied: Vec<AppliedOverride>,
+    /// Override paths with no matching instance
+    pub missing: Vec<String>,
+}
+
+/// The selected variant's overrides
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Overrides {
+    pub variant: String,
+    pub entries: Vec<PropertyOverride>,
+}
+
+/// Variant in effect: `explicit`, then `RBXSYNC_VARIANT`, then the config's `variant`
+pub fn selected_variant(config: &Value, explicit: Option<&str>) -> Option<String> {
+    explicit
+        .map(str::to_string)
+        .or_else(|| std::env::var(VARIANT_ENV).ok())
+        .or_else(|| config.get("variant").and_then(|v| v.as_str()).map(str::to_string))
+        .filter(|v| !v.is_empty())
+}
+
+fn parse_value(path: &str, property: &str, value: &Value) -> Result<PropertyValue, String> {
+    match (property, value) {
+        ("Enabled" | "Disabled", Value::Bool(b)) => Ok(PropertyValue::Bool(*b)),
+        ("RunContext", Value::String(s)) => {
+            let item = s.strip_prefix("Enum.RunContext.").unwrap_or(s);
+            RUN_CONTEXTS
+                .iter()
+                .find(|c| c.eq_ignore_ascii_case(item))
+                .map(|c| {
+                    PropertyValue::Enum(EnumValue {
+                        enum_type: "RunContext".to_string(),
+                        value: c.to_string(),
+                    })
+                })
+                .ok_or_else(|| format!("{}: RunContext must be one of {}", path, RUN_CONTEXTS.join(", ")))
+        }
+        ("Enabled" | "Disabled", _) => Err(format!("{}: {} must be true or false", path, property)),
+        ("RunContext", _) => Err(format!("{}: RunContext must be a string", path)),
+        _ => Err(format!(
+            "{}: {} can't be overridden (supported: {})",
+            path,
+            property,
+            OVERRIDE_PROPERTIES.join(", ")
+        )),
+    }
+}
+
+impl Overrides {
+    /// Overrides for the selected variant, or `None` when no variant is selected.
+    ///
+    /// An explicitly requested variant must exist; one picked up from the
+    /// environment or the config is ignored when the project doesn't define it.
+    pub fn from_config(config: &Value, explicit: Option<&str>) -> Result<Option<Self>, String> {
+        let Some(variant) = selected_variant(config, explicit) else {
+            return Ok(None);
+        };
+        let variants = config.get("overrides").and_then(|o| o.as_object());
+        let Some(section) = variants.and_then(|v| v.get(&variant)) else {
+            if explicit.is_some() {
+                let known: Vec<&str> = variants.map(|v| v.keys().map(String::as_str).collect()).unwrap_or_default();
+                return Err(if known.is_empty() {
+                    format!("Unknown variant '{}': rbxsync.json has no overrides", variant)
+                } else {
+                    format!("Unknown variant '{}' (defined: {})", variant, known.join(", "))
+                });
+            }
+            return Ok(None);
+        };
+        let section = section
+            .as_object()
+            .ok_or_else(|| format!("overrides.{} must map instance paths to properties", variant))?;
+
+        let mut entries = Vec::new();
+        for (path, properties) in section {
+            let path = path.trim_matches('/').to_string();
+            let properties = properties
+                .as_object()
+                .ok_or_else(|| format!("{}: expected an object of properties", path))?;
+            for (property, value) in properties {
+                entries.push(PropertyOverride {
+                    value: parse_value(&path, property, value)?,
+                    path: path.clone(),
+                    property: property.clone(),
+                });
+            }
+        }
+        Ok(Some(Self { variant, entries }))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Distinct override paths, in config order
+    pub fn paths(&self) -> Vec<&str> {
+        let mut paths: Vec<&str> = Vec::new();
+        for entry in &self.entries {
+            if !paths.contains(&entry.path.as_str()) {
+                paths.push(&entry.path);
+            }
+        }
+        paths
+    }
+
+    /// Overrides for one instance path
+    pub fn for_path<'a>(&'a self, path: &'a str) -> impl Iterator<Item = &'a PropertyOverride> + 'a {
+        self.entries.iter().filter(move |e| e.path == path)
+    }
+
+    /// Set the overrides for `path` in an instance's JSON (`properties` map)
+    pub fn apply(&self, path: &str, data: &mut Value) -> Vec<AppliedOverride> {
+        let mut applied = Vec::new();
+        for entry in self.for_path(path) {
+            let Some(obj) = data.as_object_mut() else {
+                break;
+            };
+            let properties = obj.entry("properties").or_insert_with(|| Value::Object(Default::default()));
+            if let (Some(properties), Ok(value)) = (properties.as_object_mut(), serde_json::to_value(&entry.value)) {
+                properties.insert(entry.property.clone(), value);
+                applied.push(AppliedOverride {
+                    path: entry.path.clone(),
+                    property: entry.property.clone(),
+                    value: entry.display_value(),
+                });
+            }
+        }
+        applied
+    }
+
+    /// Apply to every instance whose `path` (mapped by `instance_path`) has overrides
+    pub fn apply_all(&self, instances: &mut [Value], instance_path: impl Fn(&str) -> String) -> OverrideReport {
+        let mut applied = Vec::new();
+        for inst in instances.iter_mut() {
+            let Some(path) = inst.get("path").and_then(|p| p.as_str()).map(&instance_path) else {
+                continue;
+            };
+            applied.extend(self.apply(&path, inst));
+        }
+        self.report(applied)
+    }
+
+    /// Report listing the override paths that nothing matched
+    pub fn report(&self, applied: Vec<AppliedOverride>) -> OverrideReport {
+        let missing = self
+            .paths()
+            .into_iter()
+            .filter(|p| !applied.iter().any(|a| a.path == *p))
+            .map(str::to_string)
+            .collect();
+        OverrideReport {
+            variant: self.variant.clone(),
+            applied,
+            missing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_from_config_and_apply() {
+        let config = serde_json::json!({
+            "variant": "dev",
+            "overrides": {
+                "dev": {
+                    "/ServerScriptService/Analytics/": { "Enabled": false },
+                    "ServerScriptService/Combat": { "RunContext": "Enum.RunContext.server", "Disabled": false },
+                    "Workspace/Gone": { "Enabled": true }
+                },
+                "release": {}
+            }
+        });
+        assert_eq!(selected_variant(&config, Some("release")).as_deref(), Some("release"));
+        let overrides = Overrides::from_config(&config, None).unwrap().unwrap();
+        assert_eq!(overrides.variant, "dev");
+        assert_eq!(overrides.entries.len(), 4);
+        assert_eq!(overrides.paths()[0], "ServerScriptService/Analytics");
+        assert!(Overrides::from_config(&config, Some("release")).unwrap().unwrap().is_empty());
+        assert!(Overrides::from_config(&config, Some("staging")).unwrap_err().contains("dev, release"));
+        assert!(Overrides::from_config(&serde_json::json!({}), None).unwrap().is_none());
+
+        let bad = serde_json::json!({ "overrides": { "dev": { "A/B": { "Name": "x" } } } });
+        assert!(Overrides::from_config(&bad, Some("dev")).unwrap_err().contains("can't be overridden"));
+        let bad = serde_json::json!({ "overrides": { "dev": { "A/B": { "RunContext": "Everywhere" } } } });
+        assert!(Overrides::from_config(&bad, Some("dev")).is_err());
+
+        let mut instances = vec![
+            serde_json::json!({ "path": "ServerScriptService/Analytics_a1b2c3d4", "className": "Script" }),
+            serde_json::json!({ "path": "ServerScriptService/Combat", "className": "Script", "properties": {} }),
+        ];
+        let report = overrides.apply_all(&mut instances, |p| p.trim_end_matches("_a1b2c3d4").to_string());
+        assert_eq!(report.applied.len(), 3);
+        assert_eq!(report.missing, vec!["Workspace/Gone".to_string()]);
+        assert_eq!(instances[0]["properties"]["Enabled"], serde_json::json!({ "type": "bool", "value": false }));
+        assert_eq!(instances[1]["properties"]["RunContext"]["value"]["value"], "Server");
+        assert_eq!(report.applied[2].value, "Server");
+    }
+}
@@ -29,6 +29,29 @@ pub fn pathbuf_with_suffix(path: &Path, suffix: &str) -> PathBuf {
     PathBuf::from(path_with_suffix(path, suffix))
 }
 
+/// Clean up an instance path given by a user: forward slashes, no leading or
+/// trailing slash (e.g. `\ServerScriptService\Combat\` -> `ServerScriptService/Combat`)
+pub fn normalize_instance_root(path: &str) -> String {
+    normalize_path(path.trim()).trim_matches('/').to_string()
+}
+
+/// Whether instance path `path` is `root` or under it
+pub(crate) fn is_under(path: &str, root: &str) -> bool {
+    root.is_empty() || path == root || (path.starts_with(root) && path.as_bytes().get(root.len()) == Some(&b'/'))
+}
+
+/// Whether an instance path is one of `roots` or under one. Empty `roots`
+/// match everything.
+pub fn is_under_any(path: &str, roots: &[String]) -> bool {
+    roots.is_empty() || roots.iter().any(|root| is_under(path, root))
+}
+
+/// Whether a folder at instance path `dir` can hold anything under `roots`:
+/// it is under a root, or a root is under it
+pub fn may_contain_any(dir: &str, roots: &[String]) -> bool {
+    roots.is_empty() || roots.iter().any(|root| is_under(dir, root) || is_under(root, dir))
+}
+
 /// Sanitize filename for Windows compatibility
 pub fn sanitize_filename(name: &str) -> String {
     name.chars()
@@ -76,6 +99,23 @@ mod tests {
         assert!(result_str.ends_with(".rbxjson"));
     }
 
+    #[test]
+    fn test_instance_roots() {
+        let roots = vec![normalize_instance_root("/ServerScriptService\\Combat/")];
+        assert_eq!(roots[0], "ServerScriptService/Combat");
+
+        assert!(is_under_any("ServerScriptService/Combat", &roots));
+        assert!(is_under_any("ServerScriptService/Combat/Hitbox", &roots));
+        assert!(!is_under_any("ServerScriptService/CombatUtils", &roots));
+        assert!(!is_under_any("ServerScriptService", &roots));
+        assert!(is_under_any("Workspace", &[]));
+
+        assert!(may_contain_any("ServerScriptService", &roots));
+        assert!(may_contain_any("ServerScriptService/Combat/Weapons", &roots));
+        assert!(may_contain_any("", &roots));
+        assert!(!may_contain_any("Workspace", &roots));
+    }
+
     #[test]
     fn test_sanitize_filename() {
         assert_eq!(sanitize_filename("normal_name"), "normal_name");
@@ -0,0 +1,241 @@
+//! Place-to-place diff
+//!
+//! Compares the instance listings of two Studio places (e.g. a test copy and
+//! production) to find drift between environments. Works on the entries
+//! returned by the plugin's `studio:paths` command. Instances are matched by
+//! path; the rest are paired by GUID, so a renamed or moved instance shows up
+//! as moved instead of removed and added.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::identity::{find_moves, guid_of, MovedEntry};
+
+/// An instance present in only one place
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaceOnlyEntry {
+    pub path: String,
+    pub class_name: String,
+}
+
+/// An instance whose class differs between places
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassChange {
+    pub path: String,
+    pub class_a: String,
+    pub class_b: String,
+}
+
+/// An instance whose properties differ between places
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyChange {
+    pub path: String,
+    /// Names of properties that differ (or exist on only one side), sorted
+    pub properties: Vec<String>,
+}
+
+/// Differences between place A and place B
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaceDiff {
+    pub only_in_a: Vec<PlaceOnlyEntry>,
+    pub only_in_b: Vec<PlaceOnlyEntry>,
+    /// Same instance (by GUID) under a different path; `from` is the path in A
+    pub moved: Vec<MovedEntry>,
+    pub class_changed: Vec<ClassChange>,
+    /// Only populated when both listings include properties
+    pub property_changed: Vec<PropertyChange>,
+    /// Instances present in both places with no differences
+    pub unchanged: usize,
+}
+
+impl PlaceDiff {
+    /// Whether the two places are identical
+    pub fn is_empty(&self) -> bool {
+        self.only_in_a.is_empty()
+            && self.only_in_b.is_empty()
+            && self.moved.is_empty()
+            && self.class_changed.is_empty()
+            && self.property_changed.is_empty()
+    }
+}
+
+/// Index `studio:paths` entries by path. Duplicate paths (same-named siblings)
+/// keep the first entry.
+fn index_entries<'a>(
+    entries: &'a [serde_json::Value],
+    prefix: Option<&str>,
+) -> BTreeMap<&'a str, &'a serde_json::Value> {
+    let mut index = BTreeMap::new();
+    for entry in entries {
+        let Some(path) = entry.get("path").and_then(|p| p.as_str()) else {
+            continue;
+        };
+        if let Some(prefix) = prefix {
+            if path != prefix && !path.starts_with(&format!("{}/", prefix)) {
+                continue;
+            }
+        }
+        index.entry(path).or_insert(entry);
+    }
+    index
+}
+
+fn class_of(entry: &serde_json::Value) -> String {
+    entry
+        .get("className")
+        .and_then(|c| c.as_str())
+        .unwrap_or("Instance")
+        .to_string()
+}
+
+/// Compare two property values. Refs hold per-session instance IDs, so only
+/// whether they are set is comparable across places.
+pub(crate) fn property_eq(a: &serde_json::Value, b: &serde_json::Value) -> bool {
+    let is_ref = |v: &serde_json::Value| v.get("type").and_then(|t| t.as_str()) == Some("Ref");
+    if is_ref(a) && is_ref(b) {
+        return a.get("value").map(|v| v.is_null()) == b.get("value").map(|v| v.is_null());
+    }
+    a == b
+}
+
+/// Names of properties whose values differ between two entries
+fn changed_properties(a: &serde_json::Value, b: &serde_json::Value) -> Vec<String> {
+    let empty = serde_json::Map::new();
+    let props_a = a.get("properties").and_then(|p| p.as_object()).unwrap_or(&empty);
+    let props_b = b.get("properties").and_then(|p| p.as_object()).unwrap_or(&empty);
+
+    let mut changed: Vec<String> = props_a
+        .iter()
+        .filter(|(name, value)| !props_b.get(*name).map(|other| property_eq(value, other)).unwrap_or(false))
+        .map(|(name, _)| name.clone())
+        .chain(props_b.keys().filter(|name| !props_a.contains_key(*name)).cloned())
+        .collect();
+    changed.sort();
+    changed
+}
+
+/// Compare two `studio:paths` listings, optionally limited to a subtree.
+pub fn diff_places<'a>(
+    a: &'a [serde_json::Value],
+    b: &'a [serde_json::Value],
+    prefix: Option<&str>,
+) -> PlaceDiff {
+    let index_a = index_entries(a, prefix);
+    let index_b = index_entries(b, prefix);
+    let mut diff = PlaceDiff::default();
+
+    for (path, entry_a) in &index_a {
+        let Some(entry_b) = index_b.get(path) else {
+            diff.only_in_a.push(PlaceOnlyEntry {
+                path: path.to_string(),
+                class_name: class_of(entry_a),
+            });
+            continue;
+        };
+
+        let (class_a, class_b) = (class_of(entry_a), class_of(entry_b));
+        if class_a != class_b {
+            diff.class_changed.push(ClassChange {
+                path: path.to_string(),
+                class_a,
+                class_b,
+            });
+            continue;
+        }
+
+        let properties = changed_properties(entry_a, entry_b);
+        if properties.is_empty() {
+            diff.unchanged += 1;
+        } else {
+            diff.property_changed.push(PropertyChange {
+                path: path.to_string(),
+                properties,
+            });
+        }
+    }
+
+    for (path, entry_b) in &index_b {
+        if !index_a.contains_key(path) {
+            diff.only_in_b.push(PlaceOnlyEntry {
+                path: path.to_string(),
+                class_name: class_of(entry_b),
+            });
+        }
+    }
+
+    fn with_guid<'a>(
+        entries: &[PlaceOnlyEntry],
+        index: &BTreeMap<&'a str, &'a serde_json::Value>,
+    ) -> Vec<(&'a str, &'a str)> {
+        entries
+            .iter()
+            .filter_map(|e| {
+                let (path, entry) = index.get_key_value(e.path.as_str())?;
+                Some((*path, guid_of(entry)?))
+            })
+            .collect()
+    }
+    let moves = find_moves(with_guid(&diff.only_in_a, &index_a), with_guid(&diff.only_in_b, &index_b));
+    for (from, to, guid) in moves {
+        diff.only_in_a.retain(|e| e.path != from);
+        diff.only_in_b.retain(|e| e.path != to);
+        diff.moved.push(MovedEntry {
+            from: from.to_string(),
+            to: to.to_string(),
+            class_name: class_of(index_b[to]),
+            guid: guid.to_string(),
+        });
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_places() {
+        let a = vec![
+            json!({"path": "Workspace", "className": "Workspace"}),
+            json!({"path": "Workspace/Spawn", "className": "SpawnLocation",
+                   "properties": {"Anchored": {"type": "bool", "value": true},
+                                  "Target": {"type": "Ref", "value": "a-uuid"}}}),
+            json!({"path": "Workspace/Old", "className": "Part"}),
+            json!({"path": "ReplicatedStorage/Config", "className": "ModuleScript"}),
+            json!({"path": "Workspace/Door", "className": "Model", "guid": "door-guid"}),
+        ];
+        let b = vec![
+            json!({"path": "Workspace", "className": "Workspace"}),
+            json!({"path": "Workspace/Spawn", "className": "SpawnLocation",
+                   "properties": {"Anchored": {"type": "bool", "value": false},
+                                  "Locked": {"type": "bool", "value": true},
+                                  "Target": {"type": "Ref", "value": "b-uuid"}}}),
+            json!({"path": "Workspace/New", "className": "Part"}),
+            json!({"path": "ReplicatedStorage/Config", "className": "Folder"}),
+            json!({"path": "Workspace/Props/FrontDoor", "className": "Model", "guid": "door-guid"}),
+        ];
+
+        let diff = diff_places(&a, &b, None);
+        assert_eq!(diff.only_in_a[0].path, "Workspace/Old");
+        assert_eq!(diff.only_in_b[0].path, "Workspace/New");
+        assert_eq!(diff.only_in_a.len(), 1);
+        assert_eq!(diff.moved[0].from, "Workspace/Door");
+        assert_eq!(diff.moved[0].to, "Workspace/Props/FrontDoor");
+        assert_eq!(diff.class_changed[0].class_b, "Folder");
+        assert_eq!(diff.property_changed[0].properties, vec!["Anchored", "Locked"]);
+        assert_eq!(diff.unchanged, 1);
+        assert!(!diff.is_empty());
+
+        // Prefix limits the comparison to a subtree
+        let diff = diff_places(&a, &b, Some("ReplicatedStorage"));
+        assert!(diff.only_in_a.is_empty());
+        assert_eq!(diff.class_changed.len(), 1);
+    }
+}
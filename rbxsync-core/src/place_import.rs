@@ -0,0 +1,338 @@
+//! Offline place import
+//!
+//! `rbxsync open` turns a `.rbxl` or `.rbxlx` file into a project's `src`
+//! without Studio. The place is read with rbx_binary/rbx_xml and every
+//! instance is serialized to the JSON the plugin sends during extraction
+//! (`className`, `name`, `referenceId`, `parentId`, `path`, `properties`,
+//! `attributes`, `tags`), then written in the extraction layout: services and
+//! instances with children become directories holding `_meta.rbxjson`, other
+//! instances `Name.rbxjson`, and script sources go to `.luau` files. Repeated
+//! sibling names get the `_xxxxxxxx` suffix extraction uses.
+//!
+//! Values are encoded with [`crate::serde_props::variant_to_json`], which names
+//! enum items through the reflection database. Values with no `.rbxjson`
+//! encoding (shared strings, such as terrain voxels) are left out and
+//! reported. Reference ids are derived from instance paths, so importing the
+//! same place twice writes the same files.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+
+use rbx_dom_weak::types::{Ref, Variant};
+use rbx_dom_weak::WeakDom;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::builder::{child_instance_path, BuildFormat};
+use crate::types::ExtractionConfig;
+use crate::unsupported::{UnsupportedProperty, UnsupportedReport};
+use crate::serde_props::{attributes_to_json, variant_to_json};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    #[error("Unsupported file type: {0} (expected .rbxl or .rbxlx)")]
+    UnknownFormat(PathBuf),
+
+    #[error("Failed to parse {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+
+    #[error("Failed to read or write project files: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// What an import wrote
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub instances: usize,
+    pub scripts: usize,
+    pub files: usize,
+    /// Instances whose name repeats a sibling's, written with a suffix
+    pub duplicates: usize,
+    /// Property values left out because they have no `.rbxjson` encoding
+    pub unsupported: Vec<UnsupportedProperty>,
+}
+
+/// Read a `.rbxl` or `.rbxlx` place
+pub fn read_place(path: &Path) -> Result<WeakDom, ImportError> {
+    let format = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(BuildFormat::parse)
+        .filter(|f| f.is_place())
+        .ok_or_else(|| ImportError::UnknownFormat(path.to_path_buf()))?;
+    let reader = BufReader::new(File::open(path)?);
+    let parsed = if format.is_xml() {
+        rbx_xml::from_reader_default(reader).map_err(|e| e.to_string())
+    } else {
+        rbx_binary::from_reader(reader).map_err(|e| e.to_string())
+    };
+    parsed.map_err(|message| ImportError::Parse { path: path.to_path_buf(), message })
+}
+
+/// Import a place into `project_dir/src`, honoring the project's
+/// `config.excludeServices`, `config.excludeClasses` and `$schema` setting.
+/// Existing files are overwritten but not removed.
+pub fn import_place(place: &Path, project_dir: &Path) -> Result<ImportReport, ImportError> {
+    let dom = read_place(place)?;
+    let project_config = std::fs::read_to_string(project_dir.join("rbxsync.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+    let config: ExtractionConfig = project_config
+        .as_ref()
+        .and_then(|c| c.get("config"))
+        .and_then(|c| serde_json::from_value(c.clone()).ok())
+        .unwrap_or_default();
+
+    let mut unsupported = UnsupportedReport::default();
+    let (mut instances, duplicates) = serialize_dom(&dom, &config, &mut unsupported);
+    if config.omit_default_properties {
+        instances.iter_mut().for_each(|instance| {
+            crate::strip_defaults(instance);
+        });
+    }
+    let src_dir = project_dir.join("src");
+    std::fs::create_dir_all(&src_dir)?;
+    let schema_ref = crate::schema_ref(project_config.as_ref());
+    let files = write_src_layout(&src_dir, &instances, schema_ref.as_deref())?;
+
+    let scripts = instances
+        .iter()
+        .filter(|i| matches!(i["className"].as_str(), Some("Script" | "LocalScript" | "ModuleScript")))
+        .count();
+    Ok(ImportReport {
+        instances: instances.len(),
+        scripts,
+        files,
+        duplicates,
+        unsupported: unsupported.entries(),
+    })
+}
+
+/// Serialize every instance below the DOM root as extraction does, parents
+/// before children. Returns the instances and how many names were repeated.
+pub fn serialize_dom(dom: &WeakDom, config: &ExtractionConfig, unsupported: &mut UnsupportedReport) -> (Vec<Value>, usize) {
+    let mut placed = Vec::new();
+    let mut duplicates = 0;
+    place_children(dom, dom.root_ref(), "", config, &mut placed, &mut duplicates);
+
+    let ids: HashMap<Ref, &str> = placed.iter().map(|p| (p.referent, p.id.as_str())).collect();
+    let instances = placed
+        .iter()
+        .filter_map(|p| {
+            let instance = dom.get_by_ref(p.referent)?;
+            let mut properties = Map::new();
+            let mut attributes = Map::new();
+            let mut tags: Vec<String> = Vec::new();
+            for (name, value) in &instance.properties {
+                match value {
+                    Variant::Tags(t) => tags.extend(t.iter().map(str::to_string)),
+                    Variant::Attributes(attrs) => attributes.extend(attributes_to_json(attrs)),
+                    // Session-specific, never synced
+                    Variant::UniqueId(_) => {}
+                    _ => match variant_to_json(&instance.class, name, value, &ids) {
+                        Some(encoded) => {
+                            properties.insert(name.clone(), encoded);
+                        }
+                        None => unsupported.add(&instance.class, name, &format!("{:?}", value.ty()), &p.path),
+                    },
+                }
+            }
+
+            let parent_id = ids.get(&instance.parent()).map(|id| id.to_string());
+            let mut serialized = json!({
+                "className": instance.class,
+                "name": instance.name,
+                "referenceId": p.id,
+                "parentId": parent_id,
+                "path": p.path,
+                "properties": properties,
+            });
+            if !attributes.is_empty() {
+                serialized["attributes"] = Value::Object(attributes);
+            }
+            if !tags.is_empty() {
+                serialized["tags"] = json!(tags);
+            }
+            Some(serialized)
+        })
+        .collect();
+    (instances, duplicates)
+}
+
+/// An instance with its extraction path and reference id
+struct Placed {
+    referent: Ref,
+    path: String,
+    id: String,
+}
+
+fn place_children(
+    dom: &WeakDom,
+    parent: Ref,
+    parent_path: &str,
+    config: &ExtractionConfig,
+    placed: &mut Vec<Placed>,
+    duplicates: &mut usize,
+) {
+    let Some(parent_instance) = dom.get_by_ref(parent) else {
+        return;
+    };
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for &referent in parent_instance.children() {
+        let Some(child) = dom.get_by_ref(referent) else {
+            continue;
+        };
+        if config.exclude_classes.contains(&child.class)
+            || (parent_path.is_empty() && config.exclude_services.contains(&child.name))
+        {
+            continue;
+        }
+
+        // Same escaping and disambiguation as the plugin and finalize
+        let base = child_instance_path(parent_path, &child.name.replace('/', "[SLASH]"));
+        let occurrence = seen.entry(child.name.as_str()).or_insert(0);
+        *occurrence += 1;
+        let id = stable_id(&format!("{}#{}", base, occurrence));
+        let path = if *occurrence > 1 {
+            *duplicates += 1;
+            format!("{}_{}", base, &id[..8])
+        } else {
+            base
+        };
+
+        placed.push(Placed { referent, path: path.clone(), id });
+        place_children(dom, referent, &path, config, placed, duplicates);
+    }
+}
+
+/// 32 hex characters derived from `key` (two FNV-1a hashes)
+fn stable_id(key: &str) -> String {
+    let fnv = |seed: u64| {
+        key.bytes().fold(seed, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+    };
+    format!("{:016x}{:016x}", fnv(0xcbf29ce484222325), fnv(0x84222325cbf29ce4))
+}
+
+/// Write serialized instances under `src_dir` in the extraction layout.
+/// Returns the number of files written.
+pub fn write_src_layout(src_dir: &Path, instances: &[Value], schema_ref: Option<&str>) -> io::Result<usize> {
+    let paths: Vec<&str> = instances.iter().filter_map(|i| i["path"].as_str()).collect();
+    let parent_paths: HashSet<&str> = paths
+        .iter()
+        .flat_map(|p| p.match_indices('/').map(move |(i, _)| &p[..i]))
+        .collect();
+
+    let mut files = 0;
+    for instance in instances {
+        let Some(path) = instance["path"].as_str().filter(|p| !p.is_empty()) else {
+            continue;
+        };
+        let class_name = instance["className"].as_str().unwrap_or("Folder");
+        let full_path = src_dir.join(path);
+        let is_container = parent_paths.contains(path) || crate::is_special_container(path);
+
+        let mut clean = instance.clone();
+        if matches!(class_name, "Script" | "LocalScript" | "ModuleScript") {
+            let source = clean
+                .get_mut("properties")
+                .and_then(|p| p.as_object_mut())
+                .and_then(|p| p.remove("Source"));
+            if let Some(source) = source.as_ref().and_then(|s| s["value"].as_str()) {
+                let extension = match class_name {
+                    "Script" => ".server.luau",
+                    "LocalScript" => ".client.luau",
+                    _ => ".luau",
+                };
+                if let Some(parent) = full_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(crate::pathbuf_with_suffix(&full_path, extension), source)?;
+                files += 1;
+            }
+        }
+
+        let json_path = if is_container {
+            std::fs::create_dir_all(&full_path)?;
+            full_path.join("_meta.rbxjson")
+        } else {
+            if let Some(parent) = full_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            crate::pathbuf_with_suffix(&full_path, ".rbxjson")
+        };
+        crate::with_schema_ref(&mut clean, schema_ref);
+        std::fs::write(json_path, serde_json::to_string_pretty(&clean).unwrap_or_default())?;
+        files += 1;
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rbx_dom_weak::types::{Color3uint8, Vector3};
+    use rbx_dom_weak::InstanceBuilder;
+
+    #[test]
+    fn test_import_place_writes_extraction_layout() {
+        let mut dom = WeakDom::new(InstanceBuilder::new("DataModel"));
+        let root = dom.root_ref();
+        let sss = dom.insert(root, InstanceBuilder::new("ServerScriptService").with_name("ServerScriptService"));
+        dom.insert(
+            sss,
+            InstanceBuilder::new("Script")
+                .with_name("Main")
+                .with_property("Source", "print('hi')"),
+        );
+        let workspace = dom.insert(root, InstanceBuilder::new("Workspace").with_name("Workspace"));
+        for _ in 0..2 {
+            dom.insert(
+                workspace,
+                InstanceBuilder::new("Part")
+                    .with_name("Floor")
+                    .with_property("Size", Vector3::new(4.0, 1.0, 2.0))
+                    .with_property("Color", Color3uint8::new(255, 0, 0)),
+            );
+        }
+        dom.insert(root, InstanceBuilder::new("CoreGui").with_name("CoreGui"));
+
+        let project = tempfile::tempdir().unwrap();
+        let place = project.path().join("game.rbxl");
+        let top_level: Vec<Ref> = dom.root().children().to_vec();
+        rbx_binary::to_writer(File::create(&place).unwrap(), &dom, &top_level).unwrap();
+
+        let report = import_place(&place, project.path()).unwrap();
+        assert_eq!((report.instances, report.scripts, report.duplicates), (5, 1, 1));
+
+        let src = project.path().join("src");
+        assert_eq!(std::fs::read_to_string(src.join("ServerScriptService/Main.server.luau")).unwrap(), "print('hi')");
+        let script: Value =
+            serde_json::from_str(&std::fs::read_to_string(src.join("ServerScriptService/Main.rbxjson")).unwrap()).unwrap();
+        assert!(script["properties"].get("Source").is_none());
+        assert!(src.join("Workspace/_meta.rbxjson").is_file());
+        assert!(!src.join("CoreGui").exists(), "excluded services are skipped");
+
+        let floor: Value = serde_json::from_str(&std::fs::read_to_string(src.join("Workspace/Floor.rbxjson")).unwrap()).unwrap();
+        assert_eq!(floor["properties"]["Size"], json!({ "type": "Vector3", "value": { "x": 4.0, "y": 1.0, "z": 2.0 } }));
+        let duplicates: Vec<_> = std::fs::read_dir(src.join("Workspace"))
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with("Floor_"))
+            .collect();
+        assert_eq!(duplicates.len(), 1);
+
+        // Reference ids are stable, so a second import writes the same files
+        let again = import_place(&place, project.path()).unwrap();
+        assert_eq!(again.files, report.files);
+        assert_eq!(std::fs::read_dir(src.join("Workspace")).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn test_encode_enum_by_name() {
+        let value = variant_to_json("Part", "Material", &Variant::Enum(rbx_dom_weak::types::Enum::from_u32(256)), &HashMap::new());
+        assert_eq!(value, Some(json!({ "type": "Enum", "value": { "enumType": "Material", "value": "Plastic" } })));
+    }
+}
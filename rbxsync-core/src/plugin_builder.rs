@@ -2,7 +2,15 @@
 //!
 //! Bundles Luau source files into a Roblox plugin .rbxm file using rbx-dom.
 //! Supports optional obfuscation of Luau source code at build time.
+//!
+//! Builds are incremental when a cache directory is configured: each module's
+//! processed source is cached by input hash, so only changed modules are
+//! re-obfuscated, and the .rbxm is only re-serialized when some input changed.
+//!
+//! For hot reload, [`read_plugin_modules`] turns a built .rbxm back into its
+//! script tree, which the server hands to the bootstrap plugin in Studio.
 
+use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::BufWriter;
 use std::path::{Path, PathBuf};
@@ -10,6 +18,7 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
 use rbx_dom_weak::types::Variant;
 use rbx_dom_weak::{InstanceBuilder, WeakDom};
+use serde::{Deserialize, Serialize};
 
 use crate::obfuscator::Obfuscator;
 
@@ -26,8 +35,13 @@ pub struct PluginBuildConfig {
     pub obfuscate: bool,
     /// Path to obfuscation config file (default: obfuscate.toml)
     pub obfuscate_config: Option<PathBuf>,
+    /// Directory for the incremental build cache (None disables caching)
+    pub cache_dir: Option<PathBuf>,
 }
 
+/// Default location of the incremental build cache
+pub const DEFAULT_BUILD_CACHE_DIR: &str = ".rbxsync/build-cache";
+
 impl Default for PluginBuildConfig {
     fn default() -> Self {
         Self {
@@ -36,6 +50,7 @@ impl Default for PluginBuildConfig {
             plugin_name: "RbxSync".to_string(),
             obfuscate: true,
             obfuscate_config: None,
+            cache_dir: Some(PathBuf::from(DEFAULT_BUILD_CACHE_DIR)),
         }
     }
 }
@@ -47,11 +62,83 @@ pub struct PluginBuildStats {
     pub files_processed: usize,
     /// Total obfuscation transforms applied
     pub obfuscation_transforms: usize,
+    /// Modules reused from the build cache
+    pub cache_hits: usize,
+    /// Modules rebuilt because they changed (or weren't cached)
+    pub cache_misses: usize,
+    /// Whether the existing output file was up to date and left untouched
+    pub output_reused: bool,
+}
+
+/// Bump when the cache layout or processing pipeline changes
+const BUILD_CACHE_VERSION: u32 = 1;
+
+/// Cached result of processing one module
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedModule {
+    input_hash: u64,
+    source: String,
+    transforms: usize,
+}
+
+/// On-disk build cache for one plugin
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildCache {
+    version: u32,
+    /// Hash of the build settings (obfuscation on/off, config, plugin name)
+    settings_hash: u64,
+    /// Processed modules keyed by file name
+    modules: HashMap<String, CachedModule>,
+    /// Hash of all module inputs that produced `output_path`
+    output_hash: u64,
+    output_path: String,
+}
+
+impl BuildCache {
+    fn path(cache_dir: &Path, plugin_name: &str) -> PathBuf {
+        cache_dir.join(format!("plugin-{}.json", plugin_name))
+    }
+
+    /// Load the cache, discarding it if it was built with other settings
+    fn load(cache_dir: &Path, plugin_name: &str, settings_hash: u64) -> Self {
+        let cache = fs::read_to_string(Self::path(cache_dir, plugin_name))
+            .ok()
+            .and_then(|s| serde_json::from_str::<BuildCache>(&s).ok())
+            .filter(|c| c.version == BUILD_CACHE_VERSION && c.settings_hash == settings_hash);
+        cache.unwrap_or(BuildCache {
+            version: BUILD_CACHE_VERSION,
+            settings_hash,
+            ..Default::default()
+        })
+    }
+
+    fn save(&self, cache_dir: &Path, plugin_name: &str) -> Result<()> {
+        fs::create_dir_all(cache_dir).context("Failed to create build cache directory")?;
+        let json = serde_json::to_string(self)?;
+        fs::write(Self::path(cache_dir, plugin_name), json).context("Failed to write build cache")?;
+        Ok(())
+    }
 }
 
+/// Stable 64-bit FNV-1a hash (std's hasher isn't stable across releases)
+fn fnv1a(data: &[u8], seed: u64) -> u64 {
+    let mut hash = seed;
+    for byte in data {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+
 /// Represents a Luau script file
 #[derive(Debug)]
 struct ScriptFile {
+    /// File name on disk (cache key)
+    file_name: String,
     /// File name without extension
     name: String,
     /// Script class (Script, LocalScript, ModuleScript)
@@ -87,33 +174,96 @@ pub fn build_plugin_with_stats(config: &PluginBuildConfig) -> Result<(PathBuf, P
 
     let mut stats = PluginBuildStats {
         files_processed: scripts.len(),
-        obfuscation_transforms: 0,
+        ..Default::default()
     };
 
-    // Apply obfuscation if enabled
+    // Resolve the obfuscation config up front - its contents are part of the cache key
+    let obfuscate_config = config
+        .obfuscate_config
+        .clone()
+        .or_else(|| Some(PathBuf::from("obfuscate.toml")))
+        .filter(|p| p.exists());
+
+    let mut settings = format!("obfuscate={};name={};", config.obfuscate, config.plugin_name);
     if config.obfuscate {
-        let mut obfuscator = if let Some(config_path) = &config.obfuscate_config {
-            if config_path.exists() {
-                Obfuscator::from_config_file(config_path)?
-            } else {
-                Obfuscator::with_defaults()
-            }
-        } else {
-            // Try default config path
-            let default_config = PathBuf::from("obfuscate.toml");
-            if default_config.exists() {
-                Obfuscator::from_config_file(&default_config)?
-            } else {
-                Obfuscator::with_defaults()
-            }
-        };
+        if let Some(path) = &obfuscate_config {
+            settings.push_str(&fs::read_to_string(path).unwrap_or_default());
+        }
+    }
+    let settings_hash = fnv1a(settings.as_bytes(), FNV_OFFSET);
+
+    let mut cache = config
+        .cache_dir
+        .as_ref()
+        .map(|dir| BuildCache::load(dir, &config.plugin_name, settings_hash));
+
+    // Process each module, reusing cached output for unchanged inputs
+    let mut obfuscator: Option<Obfuscator> = None;
+    let mut output_hash = settings_hash;
+    scripts.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+
+    for script in &mut scripts {
+        let input_hash = fnv1a(script.source.as_bytes(), FNV_OFFSET);
+        output_hash = fnv1a(script.file_name.as_bytes(), output_hash);
+        output_hash = fnv1a(&input_hash.to_le_bytes(), output_hash);
+
+        if let Some(cached) = cache
+            .as_ref()
+            .and_then(|c| c.modules.get(&script.file_name))
+            .filter(|m| m.input_hash == input_hash)
+        {
+            stats.cache_hits += 1;
+            stats.obfuscation_transforms += cached.transforms;
+            script.source = cached.source.clone();
+            continue;
+        }
 
-        for script in &mut scripts {
+        stats.cache_misses += 1;
+        let mut transforms = 0;
+
+        // Apply obfuscation if enabled
+        if config.obfuscate {
+            if obfuscator.is_none() {
+                obfuscator = Some(match &obfuscate_config {
+                    Some(path) => Obfuscator::from_config_file(path)?,
+                    None => Obfuscator::with_defaults(),
+                });
+            }
+            let obfuscator = obfuscator.as_mut().unwrap();
             obfuscator.regenerate_prefix(); // New prefix per file
             let result = obfuscator.obfuscate(&script.source);
-            stats.obfuscation_transforms += result.total_transforms();
+            transforms = result.total_transforms();
             script.source = result.source;
         }
+
+        stats.obfuscation_transforms += transforms;
+        if let Some(cache) = cache.as_mut() {
+            cache.modules.insert(
+                script.file_name.clone(),
+                CachedModule {
+                    input_hash,
+                    source: script.source.clone(),
+                    transforms,
+                },
+            );
+        }
+    }
+
+    // Skip serialization entirely if nothing changed since the last build
+    let output_path_str = crate::path_to_string(&config.output_path);
+    if let Some(cache) = cache.as_mut() {
+        // Forget modules that were deleted from the source directory
+        cache
+            .modules
+            .retain(|name, _| scripts.iter().any(|s| &s.file_name == name));
+
+        if cache.output_hash == output_hash
+            && cache.output_path == output_path_str
+            && config.output_path.exists()
+        {
+            stats.output_reused = true;
+            return Ok((config.output_path.clone(), stats));
+        }
     }
 
     // Find the entry point (init.server.luau)
@@ -137,6 +287,12 @@ pub fn build_plugin_with_stats(config: &PluginBuildConfig) -> Result<(PathBuf, P
     let root_refs = vec![dom.root_ref()];
     rbx_binary::to_writer(output_file, &dom, &root_refs).context("Failed to write .rbxm file")?;
 
+    if let (Some(mut cache), Some(cache_dir)) = (cache, config.cache_dir.as_ref()) {
+        cache.output_hash = output_hash;
+        cache.output_path = output_path_str;
+        cache.save(cache_dir, &config.plugin_name)?;
+    }
+
     Ok((config.output_path.clone(), stats))
 }
 
@@ -198,6 +354,7 @@ fn parse_script_file(path: &Path) -> Result<ScriptFile> {
     };
 
     Ok(ScriptFile {
+        file_name: file_name.to_string(),
         name,
         class_name,
         source,
@@ -228,6 +385,46 @@ fn build_dom(plugin_name: &str, entry: &ScriptFile, all_scripts: &[ScriptFile])
     Ok(WeakDom::new(root_builder))
 }
 
+/// One script of a built plugin, with the scripts under it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginModule {
+    pub name: String,
+    pub class_name: String,
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<PluginModule>,
+}
+
+impl PluginModule {
+    /// The `PLUGIN_VERSION` the entry script declares, e.g. `1.2.1`
+    pub fn version(&self) -> Option<String> {
+        let pattern = regex::Regex::new(r#"\bPLUGIN_VERSION\s*=\s*"([^"]+)""#).ok()?;
+        pattern.captures(&self.source).map(|caps| caps[1].to_string())
+    }
+}
+
+/// Read the script tree out of a built plugin .rbxm; the root is the entry script
+pub fn read_plugin_modules(rbxm: &[u8]) -> Result<PluginModule> {
+    let dom = rbx_binary::from_reader(rbxm).context("Not a valid .rbxm file")?;
+    let root = *dom.root().children().first().context("The .rbxm file is empty")?;
+
+    fn module(dom: &WeakDom, referent: rbx_dom_weak::types::Ref) -> Option<PluginModule> {
+        let instance = dom.get_by_ref(referent)?;
+        let source = match instance.properties.get("Source") {
+            Some(Variant::String(source)) => source.clone(),
+            _ => String::new(),
+        };
+        Some(PluginModule {
+            name: instance.name.clone(),
+            class_name: instance.class.clone(),
+            source,
+            children: instance.children().iter().filter_map(|child| module(dom, *child)).collect(),
+        })
+    }
+    module(&dom, root).context("The .rbxm file is empty")
+}
+
 /// Get the default Studio plugins folder path for the current platform
 pub fn get_studio_plugins_folder() -> Option<PathBuf> {
     #[cfg(target_os = "macos")]
@@ -357,11 +554,23 @@ mod tests {
             plugin_name: "TestPlugin".to_string(),
             obfuscate: false,
             obfuscate_config: None,
+            cache_dir: None,
         };
 
         let result = build_plugin(&config).unwrap();
         assert!(result.exists());
         assert!(result.metadata().unwrap().len() > 0);
+
+        let root = read_plugin_modules(&fs::read(&result).unwrap()).unwrap();
+        assert_eq!((root.name.as_str(), root.class_name.as_str()), ("TestPlugin", "Script"));
+        assert_eq!(root.source, "-- entry point");
+        assert_eq!(root.version(), None);
+        assert_eq!(root.children.len(), 1);
+        assert_eq!(root.children[0].name, "Helper");
+        assert!(read_plugin_modules(b"not a model").is_err());
+
+        let versioned = PluginModule { source: "local PLUGIN_VERSION=\"1.3.0\" -- current".to_string(), ..root };
+        assert_eq!(versioned.version().as_deref(), Some("1.3.0"));
     }
 
     #[test]
@@ -385,6 +594,7 @@ mod tests {
             plugin_name: "TestPlugin".to_string(),
             obfuscate: true,
             obfuscate_config: None,
+            cache_dir: None,
         };
 
         let (result, stats) = build_plugin_with_stats(&config).unwrap();
@@ -392,4 +602,40 @@ mod tests {
         assert!(stats.files_processed == 1);
         assert!(stats.obfuscation_transforms > 0); // InsertService should be encoded
     }
+
+    #[test]
+    fn test_build_plugin_incremental_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::write(src_dir.join("init.server.luau"), "-- entry point").unwrap();
+        fs::write(src_dir.join("Helper.luau"), "return {}").unwrap();
+
+        let config = PluginBuildConfig {
+            source_dir: src_dir.clone(),
+            output_path: temp_dir.path().join("output.rbxm"),
+            plugin_name: "TestPlugin".to_string(),
+            obfuscate: false,
+            obfuscate_config: None,
+            cache_dir: Some(temp_dir.path().join("cache")),
+        };
+
+        // Cold build processes everything
+        let (_, stats) = build_plugin_with_stats(&config).unwrap();
+        assert_eq!(stats.cache_misses, 2);
+        assert_eq!(stats.cache_hits, 0);
+        assert!(!stats.output_reused);
+
+        // Nothing changed: output is reused
+        let (_, stats) = build_plugin_with_stats(&config).unwrap();
+        assert_eq!(stats.cache_hits, 2);
+        assert!(stats.output_reused);
+
+        // One module changed: only it is rebuilt
+        fs::write(src_dir.join("Helper.luau"), "return { changed = true }").unwrap();
+        let (_, stats) = build_plugin_with_stats(&config).unwrap();
+        assert_eq!(stats.cache_hits, 1);
+        assert_eq!(stats.cache_misses, 1);
+        assert!(!stats.output_reused);
+    }
 }
@@ -0,0 +1,154 @@
+//! Property-level diff
+//!
+//! Compares one instance as written in `src/` with the same instance in
+//! Studio, property by property. Both sides come from the plugin's serializer
+//! (extraction and `studio:paths` with properties), so values are compared as
+//! their `.rbxjson` JSON. Script sources are compared as `Source`, with the
+//! file side read from the `.luau` file.
+//!
+//! Values are reported as short summaries rather than raw JSON so a diff of a
+//! large script or mesh doesn't flood the output.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::place_diff::property_eq;
+
+/// Longest value summary before it is cut short
+const MAX_SUMMARY_LEN: usize = 60;
+
+/// Order numeric components are listed in
+const COMPONENT_ORDER: [&str; 8] = ["x", "y", "z", "w", "r", "g", "b", "a"];
+
+/// One property that differs; `before` is Studio, `after` is the files (what
+/// a sync would set). A side is `None` when the property is missing there.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PropertyDelta {
+    pub name: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// An instance present in both files and Studio whose properties differ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModifiedEntry {
+    pub path: String,
+    pub class_name: String,
+    /// Differing properties, sorted by name
+    pub properties: Vec<PropertyDelta>,
+}
+
+/// Short human-readable form of a `.rbxjson` property value
+pub fn summarize_value(name: &str, value: &Value) -> String {
+    let inner = value.get("value").unwrap_or(value);
+    if name == "Source" {
+        if let Some(source) = inner.as_str() {
+            return format!("{} lines", source.lines().count());
+        }
+    }
+    let text = match inner {
+        Value::String(s) => format!("{:?}", s),
+        Value::Object(fields) if fields.values().all(|v| v.is_number()) => {
+            // Components in their usual order (x, y, z / r, g, b), others after
+            let mut keys: Vec<&String> = fields.keys().collect();
+            keys.sort_by_key(|k| COMPONENT_ORDER.iter().position(|c| *c == k.as_str()).unwrap_or(COMPONENT_ORDER.len()));
+            let parts: Vec<String> = keys.iter().map(|k| fields[k.as_str()].to_string()).collect();
+            let kind = value.get("type").and_then(|t| t.as_str()).unwrap_or("");
+            format!("{}({})", kind, parts.join(", "))
+        }
+        other => other.to_string(),
+    };
+    if text.chars().count() > MAX_SUMMARY_LEN {
+        let cut: String = text.chars().take(MAX_SUMMARY_LEN - 1).collect();
+        format!("{}…", cut)
+    } else {
+        text
+    }
+}
+
+/// Properties that differ between the files' and Studio's copy of an
+/// instance, or `None` if they match
+pub fn diff_instance(path: &str, files: &Value, studio: &Value) -> Option<ModifiedEntry> {
+    let empty = serde_json::Map::new();
+    let file_props = files.get("properties").and_then(|p| p.as_object()).unwrap_or(&empty);
+    let studio_props = studio.get("properties").and_then(|p| p.as_object()).unwrap_or(&empty);
+
+    let mut properties: Vec<PropertyDelta> = file_props
+        .iter()
+        .filter(|(name, value)| !studio_props.get(*name).is_some_and(|other| property_eq(value, other)))
+        .map(|(name, value)| PropertyDelta {
+            name: name.clone(),
+            before: studio_props.get(name).map(|v| summarize_value(name, v)),
+            after: Some(summarize_value(name, value)),
+        })
+        .chain(
+            studio_props
+                .iter()
+                .filter(|(name, _)| !file_props.contains_key(*name))
+                .map(|(name, value)| PropertyDelta {
+                    name: name.clone(),
+                    before: Some(summarize_value(name, value)),
+                    after: None,
+                }),
+        )
+        .collect();
+    if properties.is_empty() {
+        return None;
+    }
+    properties.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let class_name = files
+        .get("className")
+        .or_else(|| studio.get("className"))
+        .and_then(|c| c.as_str())
+        .unwrap_or("Instance")
+        .to_string();
+    Some(ModifiedEntry { path: path.to_string(), class_name, properties })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_instance_reports_changed_properties() {
+        let files = json!({"className": "Part", "properties": {
+            "Size": {"type": "Vector3", "value": {"x": 4.0, "y": 1.0, "z": 2.0}},
+            "Anchored": {"type": "bool", "value": true},
+            "Target": {"type": "Ref", "value": "file-id"}
+        }});
+        let studio = json!({"className": "Part", "properties": {
+            "Size": {"type": "Vector3", "value": {"x": 2.0, "y": 1.0, "z": 2.0}},
+            "Anchored": {"type": "bool", "value": true},
+            "Target": {"type": "Ref", "value": "studio-id"},
+            "Locked": {"type": "bool", "value": true}
+        }});
+
+        let entry = diff_instance("Workspace/Floor", &files, &studio).unwrap();
+        assert_eq!(entry.class_name, "Part");
+        assert_eq!(
+            entry.properties,
+            vec![
+                PropertyDelta { name: "Locked".into(), before: Some("true".into()), after: None },
+                PropertyDelta {
+                    name: "Size".into(),
+                    before: Some("Vector3(2.0, 1.0, 2.0)".into()),
+                    after: Some("Vector3(4.0, 1.0, 2.0)".into()),
+                },
+            ]
+        );
+        assert!(diff_instance("Workspace/Floor", &files, &files).is_none());
+    }
+
+    #[test]
+    fn test_summarize_value() {
+        let source = json!({"type": "string", "value": "local x = 1\nreturn x\n"});
+        assert_eq!(summarize_value("Source", &source), "2 lines");
+        let color = json!({"type": "Color3", "value": {"r": 1.0, "g": 0.5, "b": 0.0}});
+        assert_eq!(summarize_value("Color", &color), "Color3(1.0, 0.5, 0.0)");
+        let long = json!({"type": "string", "value": "a".repeat(100)});
+        assert_eq!(summarize_value("Name", &long).chars().count(), MAX_SUMMARY_LEN);
+    }
+}
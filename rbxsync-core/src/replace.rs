@@ -0,0 +1,250 @@
+//! Property search-and-replace
+//!
+//! Rewrites one property value across every instance of a class in a project's
+//! `.rbxjson` files, e.g. switching all `Plastic` parts under a map to
+//! `SmoothPlastic`. Values are matched and written in the shape already stored
+//! in the file, so enums stay enums and numbers stay numbers.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::path_utils::path_to_string;
+
+/// What to replace
+#[derive(Debug, Clone)]
+pub struct PropertyReplace {
+    /// Exact class name to match (e.g. "Part")
+    pub class_name: String,
+    /// Property to rewrite (e.g. "Material")
+    pub property: String,
+    /// Current value to match (e.g. "Plastic")
+    pub from: String,
+    /// Value to write (e.g. "SmoothPlastic")
+    pub to: String,
+    /// Only touch instances under this DataModel path (e.g. "Workspace/Map")
+    pub path: Option<String>,
+}
+
+/// One matched (and possibly rewritten) instance
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyReplacement {
+    /// The `.rbxjson` file holding the instance
+    pub file: PathBuf,
+    /// DataModel path derived from the file location (e.g. "Workspace/Map/Wall")
+    pub instance_path: String,
+    pub old_value: serde_json::Value,
+    pub new_value: serde_json::Value,
+}
+
+/// DataModel path for a `.rbxjson` file relative to `src/`.
+/// `_meta.rbxjson` describes its parent folder.
+fn instance_path(rel: &Path) -> String {
+    let path = path_to_string(rel);
+    match path.strip_suffix("/_meta.rbxjson") {
+        Some(folder) => folder.to_string(),
+        None => path.trim_end_matches(".rbxjson").to_string(),
+    }
+}
+
+/// The comparable part of a stored value: enums store `{enumType, value}`
+fn scalar(value: &serde_json::Value) -> &serde_json::Value {
+    match value.get("value") {
+        Some(inner) if value.get("enumType").is_some() => inner,
+        _ => value,
+    }
+}
+
+/// Whether a stored property value equals the user-supplied `text`
+fn value_matches(value: &serde_json::Value, text: &str) -> bool {
+    let text = text.strip_prefix("Enum.").and_then(|t| t.rsplit('.').next()).unwrap_or(text);
+    match scalar(value) {
+        serde_json::Value::String(s) => s == text,
+        serde_json::Value::Bool(b) => text.parse::<bool>().ok() == Some(*b),
+        serde_json::Value::Number(n) => text.parse::<f64>().ok() == n.as_f64(),
+        other => serde_json::from_str::<serde_json::Value>(text).ok().as_ref() == Some(other),
+    }
+}
+
+/// Build a value with the same shape as `current` from the user-supplied `text`
+fn replacement_value(current: &serde_json::Value, text: &str) -> io::Result<serde_json::Value> {
+    let invalid = |what: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("'{}' is not a valid {} value", text, what),
+        )
+    };
+
+    if current.get("enumType").is_some() {
+        let name = text.rsplit('.').next().unwrap_or(text);
+        let mut value = current.clone();
+        value["value"] = serde_json::Value::String(name.to_string());
+        return Ok(value);
+    }
+
+    match current {
+        serde_json::Value::String(_) => Ok(serde_json::Value::String(text.to_string())),
+        serde_json::Value::Bool(_) => text.parse::<bool>().map(Into::into).map_err(|_| invalid("bool")),
+        serde_json::Value::Number(n) => {
+            let float = || text.parse::<f64>().ok().and_then(serde_json::Number::from_f64);
+            let number = if n.is_f64() {
+                float()
+            } else {
+                text.parse::<i64>().ok().map(Into::into).or_else(float)
+            };
+            number.map(serde_json::Value::Number).ok_or_else(|| invalid("number"))
+        }
+        _ => serde_json::from_str(text).map_err(|_| invalid("JSON")),
+    }
+}
+
+fn collect_rbxjson(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_rbxjson(&path, out);
+        } else if path.extension().map(|e| e == "rbxjson").unwrap_or(false)
+            // Terrain chunk data, not an instance
+            && path.file_name().map(|n| n != "terrain.rbxjson").unwrap_or(false)
+        {
+            out.push(path);
+        }
+    }
+}
+
+/// Replace a property value on every matching instance under `src_dir`.
+///
+/// Returns the matches sorted by path. With `dry_run`, nothing is written.
+pub fn replace_property(
+    src_dir: &Path,
+    spec: &PropertyReplace,
+    dry_run: bool,
+) -> io::Result<Vec<PropertyReplacement>> {
+    let mut files = Vec::new();
+    collect_rbxjson(src_dir, &mut files);
+    files.sort();
+
+    let prefix = spec.path.as_deref().map(|p| p.trim_matches('/'));
+    let mut replacements = Vec::new();
+
+    for file in files {
+        let inst_path = instance_path(file.strip_prefix(src_dir).unwrap_or(&file));
+        if let Some(prefix) = prefix {
+            if inst_path != prefix && !inst_path.starts_with(&format!("{}/", prefix)) {
+                continue;
+            }
+        }
+
+        let content = fs::read_to_string(&file)?;
+        let Ok(mut instance) = serde_json::from_str::<serde_json::Value>(&content) else {
+            continue;
+        };
+        if instance.get("className").and_then(|c| c.as_str()) != Some(spec.class_name.as_str()) {
+            continue;
+        }
+        let Some(property) = instance
+            .get_mut("properties")
+            .and_then(|p| p.get_mut(&spec.property))
+        else {
+            continue;
+        };
+        let Some(old_value) = property.get("value").cloned() else {
+            continue;
+        };
+        if !value_matches(&old_value, &spec.from) {
+            continue;
+        }
+
+        let new_value = replacement_value(&old_value, &spec.to)?;
+        property["value"] = new_value.clone();
+
+        if !dry_run {
+            // Keep the file's existing layout (extraction writes pretty JSON)
+            let json = if content.trim_end().contains('\n') {
+                serde_json::to_string_pretty(&instance)
+            } else {
+                serde_json::to_string(&instance)
+            }
+            .map_err(io::Error::other)?;
+            fs::write(&file, json)?;
+        }
+
+        replacements.push(PropertyReplacement {
+            file,
+            instance_path: inst_path,
+            old_value,
+            new_value,
+        });
+    }
+
+    Ok(replacements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_replace_property() {
+        let temp = TempDir::new().unwrap();
+        let src = temp.path().join("src");
+        let map = src.join("Workspace/Map");
+        fs::create_dir_all(&map).unwrap();
+
+        let part = |material: &str, transparency: f64| {
+            json!({
+                "className": "Part",
+                "properties": {
+                    "Material": {"type": "Enum", "value": {"enumType": "Material", "value": material}},
+                    "Transparency": {"type": "float", "value": transparency}
+                }
+            })
+            .to_string()
+        };
+        fs::write(map.join("Wall.rbxjson"), part("Plastic", 0.0)).unwrap();
+        fs::write(map.join("Floor.rbxjson"), part("Wood", 0.0)).unwrap();
+        fs::write(src.join("Workspace/Outside.rbxjson"), part("Plastic", 0.5)).unwrap();
+
+        let spec = PropertyReplace {
+            class_name: "Part".to_string(),
+            property: "Material".to_string(),
+            from: "Enum.Material.Plastic".to_string(),
+            to: "SmoothPlastic".to_string(),
+            path: Some("Workspace/Map".to_string()),
+        };
+
+        // Dry run only reports
+        let found = replace_property(&src, &spec, true).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].instance_path, "Workspace/Map/Wall");
+        assert!(fs::read_to_string(map.join("Wall.rbxjson")).unwrap().contains("\"Plastic\""));
+
+        let applied = replace_property(&src, &spec, false).unwrap();
+        assert_eq!(applied[0].new_value["value"], "SmoothPlastic");
+        let wall: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(map.join("Wall.rbxjson")).unwrap()).unwrap();
+        assert_eq!(wall["properties"]["Material"]["value"]["enumType"], "Material");
+        assert_eq!(wall["properties"]["Material"]["value"]["value"], "SmoothPlastic");
+
+        // Numbers keep their type; no path filter reaches everything
+        let spec = PropertyReplace {
+            class_name: "Part".to_string(),
+            property: "Transparency".to_string(),
+            from: "0.5".to_string(),
+            to: "0.25".to_string(),
+            path: None,
+        };
+        let applied = replace_property(&src, &spec, false).unwrap();
+        assert_eq!(applied.len(), 1);
+        assert_eq!(applied[0].instance_path, "Workspace/Outside");
+        assert_eq!(applied[0].new_value, json!(0.25));
+    }
+}
@@ -178,6 +178,47 @@ fn walk_tree(tree: &RojoTree, datamodel_path: &str, mapping: &mut HashMap<String
     }
 }
 
+/// Explicit `$ignoreUnknownInstances` settings in a Rojo project, by
+/// DataModel path
+///
+/// Only nodes that set the flag are listed. Rojo also ignores unknown
+/// instances by default under nodes without a `$path`, but rbxsync-generated
+/// project files leave the services it manages that way, so the implicit
+/// default isn't applied.
+pub fn unknown_instance_settings(project: &RojoProject) -> HashMap<String, bool> {
+    let mut settings = HashMap::new();
+    walk_unknown_settings(&project.tree, "", &mut settings);
+    settings
+}
+
+fn walk_unknown_settings(tree: &RojoTree, datamodel_path: &str, settings: &mut HashMap<String, bool>) {
+    if let Some(ignore) = tree.ignore_unknown_instances {
+        settings.insert(datamodel_path.to_string(), ignore);
+    }
+    for (name, child) in &tree.children {
+        if name.starts_with('$') {
+            continue;
+        }
+        let child_path = if datamodel_path.is_empty() {
+            name.clone()
+        } else {
+            format!("{}/{}", datamodel_path, name)
+        };
+        walk_unknown_settings(child, &child_path, settings);
+    }
+}
+
+/// Whether a Studio-only instance at `path` should be left alone rather than
+/// deleted. The deepest setting at or above the instance decides, so a
+/// subtree can turn the flag back off.
+pub fn keeps_unknown_instance(path: &str, settings: &HashMap<String, bool>) -> bool {
+    settings
+        .iter()
+        .filter(|(root, _)| crate::path_utils::is_under(path, root))
+        .max_by_key(|(root, _)| root.len())
+        .is_some_and(|(_, ignore)| *ignore)
+}
+
 /// Get the source directory from a Rojo project
 ///
 /// Returns the most commonly used source path, typically "src"
@@ -282,6 +323,37 @@ mod tests {
         assert_eq!(mapping.get("ReplicatedStorage/Packages"), Some(&"Packages".to_string()));
     }
 
+    #[test]
+    fn test_unknown_instance_settings() {
+        let json = r#"{
+            "name": "TestProject",
+            "tree": {
+                "$className": "DataModel",
+                "Workspace": {
+                    "$className": "Workspace",
+                    "$ignoreUnknownInstances": true,
+                    "Generated": {
+                        "$path": "src/generated",
+                        "$ignoreUnknownInstances": false
+                    }
+                },
+                "ServerScriptService": {
+                    "$path": "src/server"
+                }
+            }
+        }"#;
+
+        let project: RojoProject = serde_json::from_str(json).unwrap();
+        let settings = unknown_instance_settings(&project);
+        assert_eq!(settings.len(), 2);
+
+        assert!(keeps_unknown_instance("Workspace/Baseplate", &settings));
+        assert!(keeps_unknown_instance("Workspace/Map/Tree", &settings));
+        assert!(!keeps_unknown_instance("Workspace/Generated/Part", &settings));
+        assert!(!keeps_unknown_instance("ServerScriptService/Old", &settings));
+        assert!(!keeps_unknown_instance("WorkspaceExtras/Part", &settings));
+    }
+
     #[test]
     fn test_get_source_dir() {
         let json = r#"{
@@ -0,0 +1,397 @@
+//! Export to Rojo's file layout
+//!
+//! The reverse of `rbxsync migrate --from rojo`, for handing a project back to
+//! a Rojo-based pipeline. The `src` tree is rewritten in place:
+//!
+//! - `_meta.rbxjson` becomes `init.meta.json`, and a leaf `Name.rbxjson`
+//!   becomes `Name.model.json` (or `Name.meta.json` next to a script)
+//! - a script with children, written as `Name.server.luau` next to `Name/`,
+//!   moves into the directory as `Name/init.server.luau`
+//! - containers known only by their place in the tree (services,
+//!   StarterPlayerScripts) get their class spelled out
+//!
+//! and `default.project.json` maps each service directory. Property values are
+//! written in Rojo's explicit `{ "Vector3": [1, 2, 3] }` form through the same
+//! `Variant` conversion the builder uses. Values Rojo files can't hold, such as
+//! `Ref`s, are left out and listed in the report.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use rbx_dom_weak::types::Variant;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::builder::{child_instance_path, find_sibling_script, init_script_class};
+use crate::path_utils::path_to_string;
+
+/// Project file written at the project root
+pub const ROJO_PROJECT_FILE: &str = "default.project.json";
+
+/// One converted (or planned) file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RojoExportFile {
+    /// Original path, relative to the project directory
+    pub from: String,
+    /// New path, relative to the project directory; `None` when the file
+    /// carried nothing Rojo needs and is only removed
+    pub to: Option<String>,
+}
+
+/// Result of exporting a project
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RojoExportReport {
+    pub converted: Vec<RojoExportFile>,
+    /// What couldn't be carried over (instance or file path, reason)
+    pub unrepresentable: Vec<(String, String)>,
+    /// The generated project file
+    pub project: Value,
+    /// Whether files were actually changed (false for dry runs)
+    pub applied: bool,
+}
+
+struct Exporter<'a> {
+    project_dir: &'a Path,
+    dry_run: bool,
+    report: RojoExportReport,
+}
+
+impl Exporter<'_> {
+    fn rel(&self, path: &Path) -> String {
+        path_to_string(path.strip_prefix(self.project_dir).unwrap_or(path))
+    }
+
+    /// Replace `from` with `content` at `to`, or just remove it
+    fn convert(&mut self, from: &Path, to: Option<(&Path, &Value)>) -> io::Result<()> {
+        if !self.dry_run {
+            if let Some((to, content)) = to {
+                let json = serde_json::to_string_pretty(content).map_err(io::Error::other)?;
+                fs::write(to, json + "\n")?;
+            }
+            if to.map(|(to, _)| to) != Some(from) {
+                fs::remove_file(from)?;
+            }
+        }
+        self.report.converted.push(RojoExportFile {
+            from: self.rel(from),
+            to: to.map(|(to, _)| self.rel(to)),
+        });
+        Ok(())
+    }
+
+    fn rename(&mut self, from: &Path, to: &Path) -> io::Result<()> {
+        if !self.dry_run {
+            fs::rename(from, to)?;
+        }
+        self.report.converted.push(RojoExportFile { from: self.rel(from), to: Some(self.rel(to)) });
+        Ok(())
+    }
+
+    /// Properties, attributes and tags of an `.rbxjson` instance in Rojo's
+    /// explicit value form
+    fn properties(&mut self, inst_path: &str, json: &Value) -> (Map<String, Value>, Map<String, Value>) {
+        let mut properties = Map::new();
+        for (name, value) in json.get("properties").and_then(|p| p.as_object()).into_iter().flatten() {
+            let type_name = value.get("type").and_then(|t| t.as_str()).unwrap_or("");
+            if name == "Source" || type_name == "nil" {
+                continue;
+            }
+            if type_name == "Ref" {
+                if value.get("value").is_some_and(|v| !v.is_null()) {
+                    self.report
+                        .unrepresentable
+                        .push((format!("{}.{}", inst_path, name), "Ref properties can't be written in Rojo files".to_string()));
+                }
+                continue;
+            }
+            match crate::json_to_variant(value).and_then(|v| serde_json::to_value(v).ok()) {
+                Some(rojo_value) => {
+                    properties.insert(name.clone(), rojo_value);
+                }
+                None => self
+                    .report
+                    .unrepresentable
+                    .push((format!("{}.{}", inst_path, name), format!("{} value can't be converted", type_name))),
+            }
+        }
+        if let Some(tags) = json.get("tags").and_then(crate::serde_props::json_to_tags) {
+            if let Ok(tags) = serde_json::to_value(Variant::Tags(tags)) {
+                properties.insert("Tags".to_string(), tags);
+            }
+        }
+
+        let mut attributes = Map::new();
+        if let Some(values) = json.get("attributes").and_then(crate::serde_props::json_to_attributes) {
+            for (name, value) in values.iter() {
+                if let Ok(value) = serde_json::to_value(value) {
+                    attributes.insert(name.clone(), value);
+                }
+            }
+        }
+        (properties, attributes)
+    }
+
+    /// Rojo metadata for an instance: `className` (when given), `properties`
+    /// and `attributes`, or `None` if there is nothing to say
+    fn metadata(&mut self, inst_path: &str, class_name: Option<&str>, json: Option<&Value>) -> Option<Value> {
+        let (properties, attributes) = match json {
+            Some(json) => self.properties(inst_path, json),
+            None => Default::default(),
+        };
+        let mut meta = Map::new();
+        if let Some(class_name) = class_name {
+            meta.insert("className".to_string(), json!(class_name));
+        }
+        if !properties.is_empty() {
+            meta.insert("properties".to_string(), Value::Object(properties));
+        }
+        if !attributes.is_empty() {
+            meta.insert("attributes".to_string(), Value::Object(attributes));
+        }
+        (!meta.is_empty()).then_some(Value::Object(meta))
+    }
+
+    /// Write a directory's own metadata as `init.meta.json`: its
+    /// `_meta.rbxjson`, or a class only its place in the tree implies.
+    /// A script directory keeps the class its init script gives it.
+    fn export_dir_meta(&mut self, dir: &Path, inst_path: &str, is_script: bool) -> io::Result<()> {
+        let (parent_path, name) = inst_path.rsplit_once('/').unwrap_or(("", inst_path));
+        let meta_path = dir.join("_meta.rbxjson");
+        let meta = read_json(&meta_path);
+        let class_name = if is_script {
+            None
+        } else {
+            meta.as_ref()
+                .and_then(|m| m.get("className"))
+                .and_then(|c| c.as_str())
+                .or_else(|| crate::container_class_name(parent_path, name))
+                .filter(|class| *class != "Folder")
+                .map(str::to_string)
+        };
+        let content = self.metadata(inst_path, class_name.as_deref(), meta.as_ref());
+        let init_meta = dir.join("init.meta.json");
+        match (meta.is_some(), content) {
+            (true, content) => self.convert(&meta_path, content.as_ref().map(|c| (init_meta.as_path(), c))),
+            (false, Some(content)) => {
+                if !self.dry_run {
+                    fs::write(&init_meta, serde_json::to_string_pretty(&content).map_err(io::Error::other)? + "\n")?;
+                }
+                self.report.converted.push(RojoExportFile { from: String::new(), to: Some(self.rel(&init_meta)) });
+                Ok(())
+            }
+            (false, None) => Ok(()),
+        }
+    }
+
+    /// Convert the instances in a directory at instance path `inst_path`
+    fn export_dir(&mut self, dir: &Path, inst_path: &str) -> io::Result<()> {
+        let mut entries: Vec<_> = fs::read_dir(dir)?.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        entries.sort();
+
+        for path in entries {
+            let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                continue;
+            };
+            if path.is_dir() {
+                let child_path = child_instance_path(inst_path, &file_name);
+                let is_script = match (init_script_class(&path), find_sibling_script(&path)) {
+                    (Some(_), _) => true,
+                    (None, Some((script, _))) => {
+                        let script_name = script.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                        let init_name = format!("init{}", &script_name[file_name.len()..]);
+                        self.rename(&script, &path.join(init_name))?;
+                        true
+                    }
+                    (None, None) => false,
+                };
+                self.export_dir_meta(&path, &child_path, is_script)?;
+                self.export_dir(&path, &child_path)?;
+            } else if file_name == "_meta.rbxjson" {
+                continue;
+            } else if let Some(stem) = file_name.strip_suffix(".rbxjson") {
+                let child_path = child_instance_path(inst_path, stem);
+                let Some(json) = read_json(&path) else {
+                    self.report.unrepresentable.push((self.rel(&path), "not valid JSON".to_string()));
+                    continue;
+                };
+                if find_sibling_script(&dir.join(stem)).is_some() {
+                    let content = self.metadata(&child_path, None, Some(&json));
+                    let target = dir.join(format!("{}.meta.json", stem));
+                    self.convert(&path, content.as_ref().map(|c| (target.as_path(), c)))?;
+                } else {
+                    let class_name = json
+                        .get("className")
+                        .and_then(|c| c.as_str())
+                        .or_else(|| crate::container_class_name(inst_path, stem))
+                        .unwrap_or("Folder")
+                        .to_string();
+                    let content = self.metadata(&child_path, Some(&class_name), Some(&json)).unwrap_or_default();
+                    let target = dir.join(format!("{}.model.json", stem));
+                    self.convert(&path, Some((&target, &content)))?;
+                }
+            } else if !(file_name.ends_with(".luau") || file_name.ends_with(".lua") || file_name.ends_with(".meta.json")) {
+                self.report
+                    .unrepresentable
+                    .push((self.rel(&path), "not an rbxsync instance file; Rojo may read it differently".to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Rewrite `<project_dir>/src` in Rojo's layout and write
+/// `default.project.json`.
+///
+/// Each top-level directory of `src` becomes a node of the project tree with
+/// its `$path`, and its `_meta.rbxjson` properties move into the node. With
+/// `dry_run`, the report is built without touching disk.
+pub fn export_to_rojo(project_dir: &Path, project_name: &str, dry_run: bool) -> io::Result<RojoExportReport> {
+    let src_dir = project_dir.join("src");
+    let mut exporter = Exporter {
+        project_dir,
+        dry_run,
+        report: RojoExportReport { applied: !dry_run, ..Default::default() },
+    };
+
+    let mut tree = Map::new();
+    tree.insert("$className".to_string(), json!("DataModel"));
+
+    let mut entries: Vec<_> = fs::read_dir(&src_dir)?.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    entries.sort();
+    for path in entries {
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let mut node = Map::new();
+        if path.is_dir() {
+            if let Some(class) = crate::service_class_name(&name) {
+                node.insert("$className".to_string(), json!(class));
+            }
+            // The project node holds the service's own properties
+            let meta_path = path.join("_meta.rbxjson");
+            if let Some(meta) = read_json(&meta_path) {
+                let (properties, attributes) = exporter.properties(&name, &meta);
+                if !properties.is_empty() {
+                    node.insert("$properties".to_string(), Value::Object(properties));
+                }
+                if !attributes.is_empty() {
+                    node.insert("$attributes".to_string(), Value::Object(attributes));
+                }
+                exporter.convert(&meta_path, None)?;
+            }
+            exporter.export_dir(&path, &name)?;
+            node.insert("$path".to_string(), json!(exporter.rel(&path)));
+        } else if let Some(stem) = name.strip_suffix(".rbxjson") {
+            let Some(json) = read_json(&path) else {
+                continue;
+            };
+            let class_name = json.get("className").and_then(|c| c.as_str()).unwrap_or("Folder").to_string();
+            let content = exporter.metadata(stem, Some(&class_name), Some(&json)).unwrap_or_default();
+            let target = src_dir.join(format!("{}.model.json", stem));
+            exporter.convert(&path, Some((&target, &content)))?;
+            node.insert("$path".to_string(), json!(exporter.rel(&target)));
+            tree.insert(stem.to_string(), Value::Object(node));
+            continue;
+        } else if name.ends_with(".luau") || name.ends_with(".lua") {
+            let (script_name, _) = crate::builder::parse_script_name(&name);
+            node.insert("$path".to_string(), json!(exporter.rel(&path)));
+            tree.insert(script_name, Value::Object(node));
+            continue;
+        } else {
+            continue;
+        }
+        tree.insert(name, Value::Object(node));
+    }
+
+    let project = json!({
+        "name": project_name,
+        "tree": tree,
+        "globIgnorePaths": ["**/node_modules"]
+    });
+    if !dry_run {
+        let content = serde_json::to_string_pretty(&project).map_err(io::Error::other)?;
+        fs::write(project_dir.join(ROJO_PROJECT_FILE), content + "\n")?;
+    }
+    exporter.report.project = project;
+    Ok(exporter.report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_export_to_rojo() {
+        let temp = TempDir::new().unwrap();
+        let src = temp.path().join("src");
+        let sss = src.join("ServerScriptService");
+        fs::create_dir_all(sss.join("Handler")).unwrap();
+        fs::write(sss.join("Main.server.luau"), "print('hi')").unwrap();
+        fs::write(
+            sss.join("Main.rbxjson"),
+            r#"{ "className": "Script", "properties": { "Disabled": { "type": "bool", "value": true } } }"#,
+        )
+        .unwrap();
+        fs::write(sss.join("Handler.server.luau"), "require(script.Util)").unwrap();
+        fs::write(sss.join("Handler/_meta.rbxjson"), r#"{ "className": "Script", "properties": {} }"#).unwrap();
+        fs::write(sss.join("Handler/Util.luau"), "return {}").unwrap();
+
+        let workspace = src.join("Workspace");
+        fs::create_dir_all(&workspace).unwrap();
+        fs::write(
+            workspace.join("_meta.rbxjson"),
+            r#"{ "className": "Workspace", "properties": { "Gravity": { "type": "float32", "value": 100 } } }"#,
+        )
+        .unwrap();
+        fs::write(
+            workspace.join("Door.rbxjson"),
+            r#"{ "className": "Part", "referenceId": "door", "properties": {
+                "Size": { "type": "Vector3", "value": { "x": 4, "y": 8, "z": 1 } },
+                "Hinge": { "type": "Ref", "value": "hinge" }
+            }, "tags": ["Door"] }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(src.join("StarterPlayer/StarterPlayerScripts")).unwrap();
+
+        // Dry run changes nothing
+        let report = export_to_rojo(temp.path(), "Game", true).unwrap();
+        assert!(!report.applied);
+        assert!(sss.join("Main.rbxjson").exists());
+        assert!(!temp.path().join(ROJO_PROJECT_FILE).exists());
+
+        let report = export_to_rojo(temp.path(), "Game", false).unwrap();
+        assert_eq!(report.unrepresentable.len(), 1);
+        assert_eq!(report.unrepresentable[0].0, "Workspace/Door.Hinge");
+
+        let main = read_json(&sss.join("Main.meta.json")).unwrap();
+        assert_eq!(main["properties"]["Disabled"], json!({ "Bool": true }));
+        assert!(main.get("className").is_none());
+        assert!(sss.join("Handler/init.server.luau").exists());
+        assert!(!sss.join("Handler.server.luau").exists());
+        assert!(!sss.join("Handler/_meta.rbxjson").exists());
+        assert!(!sss.join("Handler/init.meta.json").exists());
+
+        let door = read_json(&workspace.join("Door.model.json")).unwrap();
+        assert_eq!(door["className"], "Part");
+        assert_eq!(door["properties"]["Size"], json!({ "Vector3": [4.0, 8.0, 1.0] }));
+        assert!(door["properties"].get("Hinge").is_none());
+        assert!(!workspace.join("Door.rbxjson").exists());
+
+        let scripts = read_json(&src.join("StarterPlayer/StarterPlayerScripts/init.meta.json")).unwrap();
+        assert_eq!(scripts["className"], "StarterPlayerScripts");
+
+        let project = read_json(&temp.path().join(ROJO_PROJECT_FILE)).unwrap();
+        assert_eq!(project["tree"]["Workspace"]["$className"], "Workspace");
+        assert_eq!(project["tree"]["Workspace"]["$path"], "src/Workspace");
+        assert_eq!(project["tree"]["Workspace"]["$properties"]["Gravity"], json!({ "Float32": 100.0 }));
+        assert!(!workspace.join("init.meta.json").exists());
+    }
+}
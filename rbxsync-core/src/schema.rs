@@ -0,0 +1,103 @@
+//! JSON Schema for .rbxjson files
+//!
+//! The schema is generated from the typed [`Instance`] model, then relaxed to
+//! match what is actually written to disk: only `className` is required and
+//! the extraction-only fields (`path`, `parentId`) are documented. Files point
+//! at it through a `$schema` key so editors can validate and complete them.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::types::Instance;
+
+/// Version of the .rbxjson format described by the schema
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Published location of the current schema
+pub const SCHEMA_URL: &str = "https://rbxsync.dev/schema/rbxjson-v1.json";
+
+/// Where `rbxsync schema emit` writes the schema, relative to the project
+pub const SCHEMA_FILE: &str = ".rbxsync/rbxjson.schema.json";
+
+/// Generate the JSON Schema for .rbxjson files
+pub fn rbxjson_schema() -> Value {
+    let mut schema = serde_json::to_value(schemars::schema_for!(Instance)).unwrap_or_default();
+
+    if let Some(obj) = schema.as_object_mut() {
+        obj.insert("$id".to_string(), json!(SCHEMA_URL));
+        obj.insert("title".to_string(), json!(format!("RbxSync instance (.rbxjson v{})", SCHEMA_VERSION)));
+        obj.insert("required".to_string(), json!(["className"]));
+
+        if let Some(props) = obj.get_mut("properties").and_then(|p| p.as_object_mut()) {
+            props.insert(
+                "$schema".to_string(),
+                json!({ "type": "string", "description": "Schema this file was written against" }),
+            );
+            props.insert(
+                "path".to_string(),
+                json!({ "type": "string", "description": "DataModel path at extraction time" }),
+            );
+            props.insert(
+                "parentId".to_string(),
+                json!({ "type": ["string", "null"], "description": "referenceId of the parent instance" }),
+            );
+        }
+    }
+    schema
+}
+
+/// The `$schema` reference to write into .rbxjson files for a project.
+///
+/// Reads `config.rbxjsonSchema` from a parsed rbxsync.json: missing means the
+/// published URL, `null` or `false` disables the reference, a string overrides it.
+pub fn schema_ref(project_config: Option<&Value>) -> Option<String> {
+    let setting = project_config
+        .and_then(|c| c.get("config"))
+        .and_then(|c| c.get("rbxjsonSchema"));
+    match setting {
+        None => Some(SCHEMA_URL.to_string()),
+        Some(Value::String(url)) if !url.is_empty() => Some(url.clone()),
+        Some(_) => None,
+    }
+}
+
+/// Add (or replace) the `$schema` key of a serialized instance
+pub fn with_schema_ref(instance: &mut Value, schema_ref: Option<&str>) {
+    if let (Some(obj), Some(url)) = (instance.as_object_mut(), schema_ref) {
+        obj.insert("$schema".to_string(), json!(url));
+    }
+}
+
+/// Write the schema into a project and return its path
+pub fn emit_schema(project_dir: &Path) -> std::io::Result<PathBuf> {
+    let path = project_dir.join(SCHEMA_FILE);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(&rbxjson_schema()).map_err(std::io::Error::other)?;
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rbxjson_schema() {
+        let schema = rbxjson_schema();
+        assert_eq!(schema["$id"], SCHEMA_URL);
+        assert_eq!(schema["required"], json!(["className"]));
+        assert!(schema["properties"]["properties"].is_object());
+        assert!(schema["properties"]["$schema"].is_object());
+
+        let config = json!({ "config": { "rbxjsonSchema": null } });
+        assert_eq!(schema_ref(Some(&config)), None);
+        assert_eq!(schema_ref(None).as_deref(), Some(SCHEMA_URL));
+
+        let mut inst = json!({ "className": "Part" });
+        with_schema_ref(&mut inst, Some("./schema.json"));
+        assert_eq!(inst["$schema"], "./schema.json");
+    }
+}
@@ -0,0 +1,1019 @@
+//! Conversion between `.rbxjson` property values and rbx_dom_weak `Variant`s
+//!
+//! [`json_to_variant`] reads the `{ "type": ..., "value": ... }` values the
+//! plugin writes during extraction, and [`variant_to_json`] writes them back
+//! in the same shapes. The builder, `rbxsync open` and `rbxsync verify` all go
+//! through these two functions, so a value that builds also round-trips. The
+//! server's sync paths pass values to the plugin through
+//! [`normalize_properties`], which fills in the enum item and font weight
+//! names the plugin needs from the same reflection lookups.
+//!
+//! [`validate_value`] checks values that come from API edits rather than
+//! files, with messages saying what shape was expected.
+//!
+//! The reflection database also backs [`is_known_property`], used by the
+//! build to flag properties that don't exist on their class, and
+//! [`strip_defaults`], which leaves properties at their class default out of
+//! extracted files when `config.omitDefaultProperties` is set.
+//!
+//! Two kinds of values need more than the value itself and are handled by the
+//! builder: `Ref` properties point at another instance's `referenceId` and are
+//! linked once the whole tree is built (a `null` Ref converts here), and the
+//! instance-level `attributes` and `tags` fields become the `Attributes` and
+//! `Tags` properties through [`json_to_attributes`] and [`json_to_tags`].
+//!
+//! `SharedString` has no inline encoding (`.rbxjson` stores only its hash) and
+//! `MaterialColors` none at all; both convert to `None`.
+
+use std::collections::HashMap;
+
+use base64::Engine;
+use rbx_dom_weak::types::*;
+use rbx_reflection::DataType;
+use serde_json::{json, Map, Value};
+
+/// Convert a JSON property value to a Variant, or `None` if it can't be built
+pub fn json_to_variant(value: &Value) -> Option<Variant> {
+    // Check if it has a type field (our format)
+    if let Some(obj) = value.as_object() {
+        if let Some(type_str) = obj.get("type").and_then(|t| t.as_str()) {
+            let val = obj.get("value");
+            return match type_str {
+                // Basic types
+                // Script sources come through as ProtectedString, which is stored as a string
+                "string" | "ProtectedString" => val?.as_str().map(|s| Variant::String(s.to_string())),
+                "int" | "int32" => val?.as_i64().map(|n| Variant::Int32(n as i32)),
+                "int64" => val?.as_i64().map(Variant::Int64),
+                "float" | "float32" => number(val?).map(|n| Variant::Float32(n as f32)),
+                "float64" | "double" | "number" => number(val?).map(Variant::Float64),
+                "bool" => val?.as_bool().map(Variant::Bool),
+
+                // nil means "use default" - skip the property entirely
+                "nil" => None,
+
+                // Vector types
+                "Vector2" => vector2(val?).map(Variant::Vector2),
+                "Vector2int16" => {
+                    let v = val?;
+                    Some(Variant::Vector2int16(Vector2int16::new(int16(v, "x")?, int16(v, "y")?)))
+                }
+                "Vector3" => vector3(val?).map(Variant::Vector3),
+                "Vector3int16" => vector3int16(val?).map(Variant::Vector3int16),
+
+                // Color types
+                "Color3" => color3(val?).map(Variant::Color3),
+                "Color3uint8" => {
+                    let v = val?.as_object()?;
+                    Some(Variant::Color3uint8(Color3uint8::new(
+                        v.get("r")?.as_u64()? as u8,
+                        v.get("g")?.as_u64()? as u8,
+                        v.get("b")?.as_u64()? as u8,
+                    )))
+                }
+                "BrickColor" => {
+                    val?.as_u64().map(|n| Variant::BrickColor(BrickColor::from_number(n as u16).unwrap_or(BrickColor::MediumStoneGrey)))
+                }
+
+                // UDim types
+                "UDim" => udim(val?).map(Variant::UDim),
+                "UDim2" => {
+                    let v = val?;
+                    Some(Variant::UDim2(UDim2::new(udim(v.get("x")?)?, udim(v.get("y")?)?)))
+                }
+
+                // CFrame; an OptionalCFrame may also be null
+                "CFrame" => cframe(val?).map(Variant::CFrame),
+                "OptionalCFrame" => match val.filter(|v| !v.is_null()) {
+                    None => Some(Variant::OptionalCFrame(None)),
+                    Some(v) => cframe(v).map(|cf| Variant::OptionalCFrame(Some(cf))),
+                },
+
+                // Enum, by number or by item name ({ enumType, value }), or
+                // by full name ("Enum.Material.Neon")
+                "Enum" => {
+                    let val = val?;
+                    let (enum_type, item) = match val {
+                        Value::String(text) => qualified_enum(text)?,
+                        _ => {
+                            let enum_value = val.get("value")?;
+                            if let Some(n) = enum_value.as_u64() {
+                                return Some(Variant::Enum(Enum::from_u32(n as u32)));
+                            }
+                            let text = enum_value.as_str()?;
+                            match qualified_enum(text) {
+                                Some(parts) => parts,
+                                None => (val.get("enumType")?.as_str()?, text),
+                            }
+                        }
+                    };
+                    enum_number(enum_type, item).map(|n| Variant::Enum(Enum::from_u32(n)))
+                }
+
+                // Rect
+                "Rect" => {
+                    let v = val?;
+                    Some(Variant::Rect(Rect::new(vector2(v.get("min")?)?, vector2(v.get("max")?)?)))
+                }
+
+                // NumberRange
+                "NumberRange" => {
+                    let v = val?.as_object()?;
+                    Some(Variant::NumberRange(NumberRange::new(
+                        v.get("min")?.as_f64()? as f32,
+                        v.get("max")?.as_f64()? as f32,
+                    )))
+                }
+
+                // Sequences ({ keypoints = [...] }, or a bare keypoint array)
+                "NumberSequence" => {
+                    let v = val?;
+                    let keypoints = v.get("keypoints").unwrap_or(v).as_array()?;
+                    let keypoints = keypoints
+                        .iter()
+                        .map(|k| {
+                            Some(NumberSequenceKeypoint::new(
+                                k.get("time")?.as_f64()? as f32,
+                                k.get("value")?.as_f64()? as f32,
+                                k.get("envelope").and_then(|e| e.as_f64()).unwrap_or(0.0) as f32,
+                            ))
+                        })
+                        .collect::<Option<Vec<_>>>()?;
+                    (keypoints.len() >= 2).then_some(Variant::NumberSequence(NumberSequence { keypoints }))
+                }
+                "ColorSequence" => {
+                    let v = val?;
+                    let keypoints = v.get("keypoints").unwrap_or(v).as_array()?;
+                    let keypoints = keypoints
+                        .iter()
+                        .map(|k| Some(ColorSequenceKeypoint::new(k.get("time")?.as_f64()? as f32, color3(k.get("color")?)?)))
+                        .collect::<Option<Vec<_>>>()?;
+                    (keypoints.len() >= 2).then_some(Variant::ColorSequence(ColorSequence { keypoints }))
+                }
+
+                // Physics; a missing or null value is the material's default
+                "PhysicalProperties" => match val.filter(|v| !v.is_null()) {
+                    None => Some(Variant::PhysicalProperties(PhysicalProperties::Default)),
+                    Some(v) => {
+                        let field = |camel: &str, snake: &str| {
+                            v.get(camel).or_else(|| v.get(snake)).and_then(|n| n.as_f64()).map(|n| n as f32)
+                        };
+                        Some(Variant::PhysicalProperties(PhysicalProperties::Custom(CustomPhysicalProperties {
+                            density: field("density", "density")?,
+                            friction: field("friction", "friction")?,
+                            elasticity: field("elasticity", "elasticity")?,
+                            friction_weight: field("frictionWeight", "friction_weight").unwrap_or(1.0),
+                            elasticity_weight: field("elasticityWeight", "elasticity_weight").unwrap_or(1.0),
+                        })))
+                    }
+                },
+                "Ray" => {
+                    let v = val?;
+                    Some(Variant::Ray(Ray::new(vector3(v.get("origin")?)?, vector3(v.get("direction")?)?)))
+                }
+                "Region3" => {
+                    let v = val?;
+                    Some(Variant::Region3(Region3::new(vector3(v.get("min")?)?, vector3(v.get("max")?)?)))
+                }
+                "Region3int16" => {
+                    let v = val?;
+                    Some(Variant::Region3int16(Region3int16::new(
+                        vector3int16(v.get("min")?)?,
+                        vector3int16(v.get("max")?)?,
+                    )))
+                }
+
+                // Faces/Axes (one bool per face or axis)
+                "Faces" => {
+                    let v = val?;
+                    let bits = FACES
+                        .into_iter()
+                        .filter(|(face, _)| v.get(*face).and_then(|b| b.as_bool()).unwrap_or(false))
+                        .fold(0, |bits, (_, bit)| bits | bit);
+                    Faces::from_bits(bits).map(Variant::Faces)
+                }
+                "Axes" => {
+                    let v = val?;
+                    let bits = AXES
+                        .into_iter()
+                        .filter(|(axis, _)| v.get(*axis).and_then(|b| b.as_bool()).unwrap_or(false))
+                        .fold(0, |bits, (_, bit)| bits | bit);
+                    Axes::from_bits(bits).map(Variant::Axes)
+                }
+
+                // Font; the plugin writes weight and style as enum item names
+                "Font" => {
+                    let v = val?.as_object()?;
+                    let family = v.get("family")?.as_str()?.to_string();
+                    let weight = match v.get("weight") {
+                        Some(Value::String(name)) => font_weight(name)?,
+                        Some(n) => FontWeight::from_u16(n.as_u64()? as u16)?,
+                        None => FontWeight::Regular,
+                    };
+                    let style = v.get("style").and_then(|s| s.as_str()).unwrap_or("Normal");
+                    Some(Variant::Font(Font {
+                        family,
+                        weight,
+                        style: if style == "Italic" { FontStyle::Italic } else { FontStyle::Normal },
+                        cached_face_id: None,
+                    }))
+                }
+
+                // Content (asset URLs)
+                "Content" => {
+                    val?.as_str().map(|s| Variant::Content(Content::from(s.to_string())))
+                }
+
+                // Base64 data; an empty string is the plugin's placeholder for
+                // data it can't read, not an empty value
+                "BinaryString" => {
+                    let bytes = base64::engine::general_purpose::STANDARD
+                        .decode(val?.as_str().filter(|s| !s.is_empty())?)
+                        .ok()?;
+                    Some(Variant::BinaryString(BinaryString::from(bytes)))
+                }
+                "SecurityCapabilities" => {
+                    val?.as_u64().map(|bits| Variant::SecurityCapabilities(SecurityCapabilities::from_bits(bits)))
+                }
+                "UniqueId" => parse_unique_id(val?.as_str()?).map(Variant::UniqueId),
+
+                // Tags and attributes written as properties
+                "Tags" => json_to_tags(val?).map(Variant::Tags),
+                "Attributes" => json_to_attributes(val?).map(Variant::Attributes),
+
+                // Nil refs convert here; refs to instances are linked by the builder
+                "Ref" => val.filter(|v| !v.is_null()).is_none().then_some(Variant::Ref(Ref::none())),
+
+                // Skip unknown/unsupported types
+                _ => None,
+            };
+        }
+    }
+
+    // Direct value
+    match value {
+        Value::String(s) => Some(Variant::String(s.clone())),
+        Value::Bool(b) => Some(Variant::Bool(*b)),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Some(Variant::Int32(i as i32))
+            } else {
+                n.as_f64().map(Variant::Float64)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Attributes from an instance's `attributes` object. Attribute numbers are
+/// always doubles, whichever number type they were written with.
+pub fn json_to_attributes(value: &Value) -> Option<Attributes> {
+    let mut attributes = Attributes::new();
+    for (name, attribute) in value.as_object()? {
+        let type_name = attribute.get("type").and_then(|t| t.as_str());
+        let converted = match type_name {
+            Some("int" | "int32" | "int64" | "float" | "float32" | "double" | "float64" | "number") => {
+                attribute.get("value").and_then(number).map(Variant::Float64)
+            }
+            _ => json_to_variant(attribute),
+        };
+        if let Some(converted) = converted {
+            attributes.insert(name.clone(), converted);
+        }
+    }
+    Some(attributes)
+}
+
+/// Tags from an instance's `tags` array
+pub fn json_to_tags(value: &Value) -> Option<Tags> {
+    let tags: Vec<String> = value.as_array()?.iter().filter_map(|t| t.as_str()).map(str::to_string).collect();
+    Some(Tags::from(tags))
+}
+
+/// Encode a Variant as the plugin's serializer does, or `None` if it has no
+/// `.rbxjson` encoding. `class_name` and `property` name enum values through
+/// the reflection database; `ids` maps Ref targets to their `referenceId`s.
+pub fn variant_to_json(class_name: &str, property: &str, value: &Variant, ids: &HashMap<Ref, &str>) -> Option<Value> {
+    let encoded = match value {
+        Variant::Bool(b) => json!({ "type": "bool", "value": b }),
+        Variant::Int32(n) => json!({ "type": "int", "value": n }),
+        Variant::Int64(n) => json!({ "type": "int64", "value": n }),
+        Variant::Float32(n) => json!({ "type": "float", "value": float_value(*n as f64) }),
+        Variant::Float64(n) => json!({ "type": "double", "value": float_value(*n) }),
+        Variant::String(s) => json!({ "type": "string", "value": s }),
+        Variant::Content(c) => json!({ "type": "Content", "value": AsRef::<str>::as_ref(c) }),
+        Variant::Vector2(v) => json!({ "type": "Vector2", "value": { "x": v.x, "y": v.y } }),
+        Variant::Vector2int16(v) => json!({ "type": "Vector2int16", "value": { "x": v.x, "y": v.y } }),
+        Variant::Vector3(v) => json!({ "type": "Vector3", "value": vector3_json(v) }),
+        Variant::Vector3int16(v) => json!({ "type": "Vector3int16", "value": { "x": v.x, "y": v.y, "z": v.z } }),
+        Variant::CFrame(cf) => json!({ "type": "CFrame", "value": cframe_json(cf) }),
+        Variant::OptionalCFrame(cf) => json!({ "type": "OptionalCFrame", "value": cf.as_ref().map(cframe_json) }),
+        Variant::Color3(c) => json!({ "type": "Color3", "value": color3_json(c) }),
+        Variant::Color3uint8(c) => json!({ "type": "Color3uint8", "value": { "r": c.r, "g": c.g, "b": c.b } }),
+        Variant::BrickColor(bc) => json!({ "type": "BrickColor", "value": *bc as u16 }),
+        Variant::UDim(u) => json!({ "type": "UDim", "value": udim_json(u) }),
+        Variant::UDim2(u) => json!({ "type": "UDim2", "value": { "x": udim_json(&u.x), "y": udim_json(&u.y) } }),
+        Variant::Rect(r) => json!({
+            "type": "Rect",
+            "value": { "min": { "x": r.min.x, "y": r.min.y }, "max": { "x": r.max.x, "y": r.max.y } }
+        }),
+        Variant::NumberRange(r) => json!({ "type": "NumberRange", "value": { "min": r.min, "max": r.max } }),
+        Variant::NumberSequence(s) => {
+            let keypoints: Vec<Value> = s
+                .keypoints
+                .iter()
+                .map(|k| json!({ "time": k.time, "value": k.value, "envelope": k.envelope }))
+                .collect();
+            json!({ "type": "NumberSequence", "value": { "keypoints": keypoints } })
+        }
+        Variant::ColorSequence(s) => {
+            let keypoints: Vec<Value> = s
+                .keypoints
+                .iter()
+                .map(|k| json!({ "time": k.time, "color": color3_json(&k.color) }))
+                .collect();
+            json!({ "type": "ColorSequence", "value": { "keypoints": keypoints } })
+        }
+        Variant::Enum(e) => {
+            let value = match enum_item(class_name, property, e.to_u32()) {
+                Some((enum_type, item)) => json!({ "enumType": enum_type, "value": item }),
+                None => json!({ "value": e.to_u32() }),
+            };
+            json!({ "type": "Enum", "value": value })
+        }
+        Variant::Font(font) => json!({
+            "type": "Font",
+            "value": {
+                "family": font.family,
+                "weight": format!("{:?}", font.weight),
+                "style": format!("{:?}", font.style)
+            }
+        }),
+        Variant::PhysicalProperties(PhysicalProperties::Custom(p)) => json!({
+            "type": "PhysicalProperties",
+            "value": {
+                "density": p.density,
+                "friction": p.friction,
+                "elasticity": p.elasticity,
+                "frictionWeight": p.friction_weight,
+                "elasticityWeight": p.elasticity_weight
+            }
+        }),
+        Variant::PhysicalProperties(_) => json!({ "type": "PhysicalProperties", "value": null }),
+        Variant::Ray(r) => json!({
+            "type": "Ray",
+            "value": { "origin": vector3_json(&r.origin), "direction": vector3_json(&r.direction) }
+        }),
+        Variant::Region3(r) => json!({
+            "type": "Region3",
+            "value": { "min": vector3_json(&r.min), "max": vector3_json(&r.max) }
+        }),
+        Variant::Region3int16(r) => json!({
+            "type": "Region3int16",
+            "value": {
+                "min": { "x": r.min.x, "y": r.min.y, "z": r.min.z },
+                "max": { "x": r.max.x, "y": r.max.y, "z": r.max.z }
+            }
+        }),
+        Variant::Faces(f) => {
+            let faces: Map<String, Value> =
+                FACES.into_iter().map(|(face, bit)| (face.to_string(), json!(f.bits() & bit != 0))).collect();
+            json!({ "type": "Faces", "value": faces })
+        }
+        Variant::Axes(a) => {
+            let axes: Map<String, Value> =
+                AXES.into_iter().map(|(axis, bit)| (axis.to_string(), json!(a.bits() & bit != 0))).collect();
+            json!({ "type": "Axes", "value": axes })
+        }
+        Variant::BinaryString(b) => {
+            let bytes: &[u8] = b.as_ref();
+            json!({ "type": "BinaryString", "value": base64::engine::general_purpose::STANDARD.encode(bytes) })
+        }
+        Variant::SecurityCapabilities(s) => json!({ "type": "SecurityCapabilities", "value": s.bits() }),
+        Variant::UniqueId(id) => json!({ "type": "UniqueId", "value": id.to_string() }),
+        Variant::Tags(tags) => json!({ "type": "Tags", "value": tags.iter().collect::<Vec<_>>() }),
+        Variant::Attributes(attributes) => json!({ "type": "Attributes", "value": attributes_to_json(attributes) }),
+        Variant::Ref(r) => json!({ "type": "Ref", "value": ids.get(r).copied() }),
+        _ => return None,
+    };
+    Some(encoded)
+}
+
+/// Rewrite an instance's property values into the form the plugin applies.
+/// The plugin only sets enum items as `{ enumType, value }` with the item
+/// name, and font weights by name, so enums written as numbers or full names
+/// and numeric weights are rewritten using the reflection database. Values
+/// without a known name and everything else are left as written.
+pub fn normalize_properties(instance: &mut Value) {
+    let class_name = instance.get("className").and_then(|c| c.as_str()).unwrap_or("Folder").to_string();
+    let Some(properties) = instance.get_mut("properties").and_then(|p| p.as_object_mut()) else {
+        return;
+    };
+    for (name, property) in properties.iter_mut() {
+        match property.get("type").and_then(|t| t.as_str()) {
+            Some("Enum") => {
+                let Some(Variant::Enum(number)) = json_to_variant(property) else {
+                    continue;
+                };
+                let value = &property["value"];
+                let enum_type = value.get("enumType").and_then(|t| t.as_str()).or_else(|| {
+                    let text = value.as_str().or_else(|| value.get("value")?.as_str())?;
+                    qualified_enum(text).map(|(enum_type, _)| enum_type)
+                });
+                let named = match enum_type {
+                    Some(enum_type) => enum_item_name(enum_type, number.to_u32()).map(|item| (enum_type.to_string(), item)),
+                    None => enum_item(&class_name, name, number.to_u32()),
+                };
+                if let Some((enum_type, item)) = named {
+                    property["value"] = json!({ "enumType": enum_type, "value": item });
+                }
+            }
+            Some("Font") => {
+                let Some(value) = property.get_mut("value").and_then(|v| v.as_object_mut()) else {
+                    continue;
+                };
+                let weight = value.get("weight").and_then(|w| w.as_u64()).and_then(|w| FontWeight::from_u16(w as u16));
+                if let Some(weight) = weight {
+                    value.insert("weight".to_string(), json!(format!("{:?}", weight)));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Check a property value from outside a project (an API edit) before it's
+/// sent to Studio, saying what's wrong with it. Enums must name their enum,
+/// since there's no class to look the property up on.
+pub fn validate_value(value: &Value) -> Result<(), String> {
+    let Some(type_name) = value.get("type").and_then(|t| t.as_str()) else {
+        return Err("expected a typed value like { \"type\": \"Vector3\", \"value\": { \"x\": 0, \"y\": 5, \"z\": 0 } }".to_string());
+    };
+    let expected = match type_name {
+        "nil" | "Ref" | "SharedString" | "MaterialColors" => return Err(format!("{} values can't be set by an edit", type_name)),
+        "Vector3" => "{ x, y, z }",
+        "Color3" => "{ r, g, b } from 0 to 1",
+        "CFrame" => "{ position: [x, y, z], rotation: [9 numbers] }",
+        "Enum" => "\"Enum.Material.Neon\" or { enumType, value } naming a known item",
+        _ => "",
+    };
+    let invalid = || match expected {
+        "" => format!("not a valid {} value", type_name),
+        expected => format!("not a valid {} value: expected {}", type_name, expected),
+    };
+    let variant = json_to_variant(value).ok_or_else(invalid)?;
+
+    if let Variant::Enum(number) = variant {
+        let val = &value["value"];
+        let enum_type = val.get("enumType").and_then(|t| t.as_str()).or_else(|| {
+            let text = val.as_str().or_else(|| val.get("value")?.as_str())?;
+            qualified_enum(text).map(|(enum_type, _)| enum_type)
+        });
+        let named = enum_type.is_some_and(|enum_type| enum_item_name(enum_type, number.to_u32()).is_some());
+        if !named {
+            return Err(invalid());
+        }
+    }
+    Ok(())
+}
+
+/// Whether the reflection database knows `property` on `class_name` or one of
+/// its superclasses. Properties of classes it doesn't know aren't flagged.
+pub fn is_known_property(class_name: &str, property: &str) -> bool {
+    let database = rbx_reflection_database::get();
+    let Some(mut class) = database.classes.get(class_name) else {
+        return true;
+    };
+    loop {
+        if class.properties.contains_key(property) {
+            return true;
+        }
+        match class.superclass.as_deref().and_then(|name| database.classes.get(name)) {
+            Some(superclass) => class = superclass,
+            None => return false,
+        }
+    }
+}
+
+/// Default value of a property, from the reflection database
+pub fn default_value(class_name: &str, property: &str) -> Option<&'static Variant> {
+    let database = rbx_reflection_database::get();
+    let mut class = database.classes.get(class_name)?;
+    loop {
+        if let Some(value) = class.default_properties.get(property) {
+            return Some(value);
+        }
+        class = database.classes.get(class.superclass.as_deref()?)?;
+    }
+}
+
+/// Remove the properties of a serialized instance whose value is the class
+/// default. Returns how many were removed.
+pub fn strip_defaults(instance: &mut Value) -> usize {
+    let class_name = instance.get("className").and_then(|c| c.as_str()).unwrap_or("Folder").to_string();
+    let Some(properties) = instance.get_mut("properties").and_then(|p| p.as_object_mut()) else {
+        return 0;
+    };
+    let before = properties.len();
+    properties.retain(|name, value| {
+        let is_default = default_value(&class_name, name)
+            .zip(json_to_variant(value))
+            .is_some_and(|(default, value)| same_value(&value, default));
+        !is_default
+    });
+    before - properties.len()
+}
+
+/// Whether `config.omitDefaultProperties` is set in a parsed rbxsync.json
+pub fn omit_defaults(project_config: Option<&Value>) -> bool {
+    project_config
+        .and_then(|c| c.get("config"))
+        .and_then(|c| c.get("omitDefaultProperties"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// Whether two values are the same once stored, allowing for the types the
+/// database keeps some defaults as
+fn same_value(value: &Variant, default: &Variant) -> bool {
+    let to_u8 = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    match (value, default) {
+        (Variant::Color3(c), Variant::Color3uint8(u)) => (to_u8(c.r), to_u8(c.g), to_u8(c.b)) == (u.r, u.g, u.b),
+        (Variant::Float32(x), Variant::Float64(y)) => *x as f64 == *y,
+        (Variant::Float64(x), Variant::Float32(y)) => *x == *y as f64,
+        _ => value == default,
+    }
+}
+
+/// Attributes as an instance's `attributes` object: numbers are written as
+/// `int` or `float` like the plugin does
+pub fn attributes_to_json(attributes: &Attributes) -> Map<String, Value> {
+    attributes
+        .iter()
+        .filter_map(|(name, value)| {
+            let encoded = match value {
+                Variant::Float64(n) => number_json(*n),
+                Variant::Float32(n) => number_json(*n as f64),
+                other => variant_to_json("", "", other, &HashMap::new())?,
+            };
+            Some((name.clone(), encoded))
+        })
+        .collect()
+}
+
+const FACES: [(&str, u8); 6] = [("right", 1), ("top", 2), ("back", 4), ("left", 8), ("bottom", 16), ("front", 32)];
+const AXES: [(&str, u8); 3] = [("x", 1), ("y", 2), ("z", 4)];
+
+/// A number, or NaN/Infinity as the strings the plugin writes for them
+fn number(value: &Value) -> Option<f64> {
+    match value.as_str() {
+        Some("NaN" | "nan") => Some(f64::NAN),
+        Some("Infinity" | "inf") => Some(f64::INFINITY),
+        Some("-Infinity" | "-inf") => Some(f64::NEG_INFINITY),
+        Some(_) => None,
+        None => value.as_f64(),
+    }
+}
+
+fn int16(value: &Value, key: &str) -> Option<i16> {
+    i16::try_from(value.get(key)?.as_i64()?).ok()
+}
+
+fn vector2(value: &Value) -> Option<Vector2> {
+    Some(Vector2::new(value.get("x")?.as_f64()? as f32, value.get("y")?.as_f64()? as f32))
+}
+
+fn vector3(value: &Value) -> Option<Vector3> {
+    Some(Vector3::new(
+        value.get("x")?.as_f64()? as f32,
+        value.get("y")?.as_f64()? as f32,
+        value.get("z")?.as_f64()? as f32,
+    ))
+}
+
+fn vector3int16(value: &Value) -> Option<Vector3int16> {
+    Some(Vector3int16::new(int16(value, "x")?, int16(value, "y")?, int16(value, "z")?))
+}
+
+fn color3(value: &Value) -> Option<Color3> {
+    Some(Color3::new(
+        value.get("r")?.as_f64()? as f32,
+        value.get("g")?.as_f64()? as f32,
+        value.get("b")?.as_f64()? as f32,
+    ))
+}
+
+fn udim(value: &Value) -> Option<UDim> {
+    Some(UDim::new(value.get("scale")?.as_f64()? as f32, value.get("offset")?.as_i64()? as i32))
+}
+
+fn cframe(value: &Value) -> Option<CFrame> {
+    let pos = value.get("position")?.as_array()?;
+    let rot = value.get("rotation")?.as_array()?;
+    if pos.len() < 3 || rot.len() < 9 {
+        return None;
+    }
+    let n = |v: &Value| v.as_f64().map(|n| n as f32);
+    Some(CFrame::new(
+        Vector3::new(n(&pos[0])?, n(&pos[1])?, n(&pos[2])?),
+        Matrix3::new(
+            Vector3::new(n(&rot[0])?, n(&rot[1])?, n(&rot[2])?),
+            Vector3::new(n(&rot[3])?, n(&rot[4])?, n(&rot[5])?),
+            Vector3::new(n(&rot[6])?, n(&rot[7])?, n(&rot[8])?),
+        ),
+    ))
+}
+
+/// Weight from its Enum.FontWeight item name
+fn font_weight(name: &str) -> Option<FontWeight> {
+    let weight = match name {
+        "Thin" => 100,
+        "ExtraLight" => 200,
+        "Light" => 300,
+        "Regular" => 400,
+        "Medium" => 500,
+        "SemiBold" => 600,
+        "Bold" => 700,
+        "ExtraBold" => 800,
+        "Heavy" => 900,
+        _ => return None,
+    };
+    FontWeight::from_u16(weight)
+}
+
+/// UniqueIds print as 32 hex digits: random (64 bits), time, index (32 each)
+fn parse_unique_id(text: &str) -> Option<UniqueId> {
+    if text.len() != 32 || !text.is_ascii() {
+        return None;
+    }
+    let random = u64::from_str_radix(&text[..16], 16).ok()? as i64;
+    let time = u32::from_str_radix(&text[16..24], 16).ok()?;
+    let index = u32::from_str_radix(&text[24..], 16).ok()?;
+    Some(UniqueId::new(index, time, random))
+}
+
+/// Enum type and item of a full name like `Enum.Material.Neon`
+fn qualified_enum(text: &str) -> Option<(&str, &str)> {
+    text.strip_prefix("Enum.")?.split_once('.')
+}
+
+/// Number of an enum item, from the reflection database
+fn enum_number(enum_type: &str, item: &str) -> Option<u32> {
+    rbx_reflection_database::get().enums.get(enum_type)?.items.get(item).copied()
+}
+
+/// Name of an enum item by number, from the reflection database
+fn enum_item_name(enum_type: &str, value: u32) -> Option<String> {
+    let (item, _) = rbx_reflection_database::get().enums.get(enum_type)?.items.iter().find(|(_, v)| **v == value)?;
+    Some(item.to_string())
+}
+
+/// Enum type and item name of a property value, from the reflection database
+fn enum_item(class_name: &str, property: &str, value: u32) -> Option<(String, String)> {
+    let database = rbx_reflection_database::get();
+    let mut class = database.classes.get(class_name)?;
+    loop {
+        if let Some(descriptor) = class.properties.get(property) {
+            let DataType::Enum(enum_name) = &descriptor.data_type else {
+                return None;
+            };
+            return enum_item_name(enum_name, value).map(|item| (enum_name.to_string(), item));
+        }
+        class = database.classes.get(class.superclass.as_deref()?)?;
+    }
+}
+
+/// A number as the plugin writes it: `int` when whole, otherwise `float`
+fn number_json(n: f64) -> Value {
+    if n.is_finite() && n.fract() == 0.0 && n.abs() < 9.0e15 {
+        json!({ "type": "int", "value": n as i64 })
+    } else {
+        json!({ "type": "float", "value": float_value(n) })
+    }
+}
+
+/// Finite floats as numbers; NaN and infinities as the strings the plugin uses
+fn float_value(n: f64) -> Value {
+    if n.is_nan() {
+        json!("NaN")
+    } else if n.is_infinite() {
+        json!(if n > 0.0 { "Infinity" } else { "-Infinity" })
+    } else {
+        json!(n)
+    }
+}
+
+fn vector3_json(v: &Vector3) -> Value {
+    json!({ "x": v.x, "y": v.y, "z": v.z })
+}
+
+fn color3_json(c: &Color3) -> Value {
+    json!({ "r": c.r, "g": c.g, "b": c.b })
+}
+
+fn udim_json(u: &UDim) -> Value {
+    json!({ "scale": u.scale, "offset": u.offset })
+}
+
+fn cframe_json(cf: &CFrame) -> Value {
+    let o = &cf.orientation;
+    json!({
+        "position": [cf.position.x, cf.position.y, cf.position.z],
+        "rotation": [o.x.x, o.x.y, o.x.z, o.y.x, o.y.y, o.y.z, o.z.x, o.z.y, o.z.z]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(type_name: &str, value: Value) -> Option<Variant> {
+        json_to_variant(&json!({ "type": type_name, "value": value }))
+    }
+
+    #[test]
+    fn test_json_to_variant_conversions() {
+        assert_eq!(convert("string", json!("hi")), Some(Variant::String("hi".into())));
+        assert_eq!(convert("int", json!(5)), Some(Variant::Int32(5)));
+        assert_eq!(convert("int32", json!(-5)), Some(Variant::Int32(-5)));
+        assert_eq!(convert("int64", json!(1i64 << 40)), Some(Variant::Int64(1 << 40)));
+        assert_eq!(convert("float", json!(0.5)), Some(Variant::Float32(0.5)));
+        assert_eq!(convert("float32", json!(1.5)), Some(Variant::Float32(1.5)));
+        assert_eq!(convert("double", json!(0.25)), Some(Variant::Float64(0.25)));
+        assert_eq!(convert("float64", json!(2.25)), Some(Variant::Float64(2.25)));
+        assert_eq!(convert("bool", json!(true)), Some(Variant::Bool(true)));
+        assert_eq!(convert("nil", json!(null)), None);
+        assert_eq!(convert("Vector2", json!({ "x": 1, "y": 2 })), Some(Variant::Vector2(Vector2::new(1.0, 2.0))));
+        assert_eq!(
+            convert("Vector3", json!({ "x": 1, "y": 2, "z": 3 })),
+            Some(Variant::Vector3(Vector3::new(1.0, 2.0, 3.0)))
+        );
+        assert_eq!(
+            convert("Color3", json!({ "r": 1, "g": 0.5, "b": 0 })),
+            Some(Variant::Color3(Color3::new(1.0, 0.5, 0.0)))
+        );
+        assert_eq!(
+            convert("Color3uint8", json!({ "r": 255, "g": 128, "b": 0 })),
+            Some(Variant::Color3uint8(Color3uint8::new(255, 128, 0)))
+        );
+        assert_eq!(convert("BrickColor", json!(1004)), Some(Variant::BrickColor(BrickColor::ReallyRed)));
+        assert_eq!(convert("BrickColor", json!(9999)), Some(Variant::BrickColor(BrickColor::MediumStoneGrey)));
+        assert_eq!(
+            convert("UDim", json!({ "scale": 0.5, "offset": 10 })),
+            Some(Variant::UDim(UDim::new(0.5, 10)))
+        );
+        assert_eq!(
+            convert("UDim2", json!({ "x": { "scale": 1, "offset": 0 }, "y": { "scale": 0, "offset": 20 } })),
+            Some(Variant::UDim2(UDim2::new(UDim::new(1.0, 0), UDim::new(0.0, 20))))
+        );
+        let cframe = convert(
+            "CFrame",
+            json!({ "position": [1, 2, 3], "rotation": [1, 0, 0, 0, 1, 0, 0, 0, 1] }),
+        );
+        assert_eq!(
+            cframe,
+            Some(Variant::CFrame(CFrame::new(Vector3::new(1.0, 2.0, 3.0), Matrix3::identity())))
+        );
+        assert_eq!(convert("CFrame", json!({ "position": [1, 2, 3], "rotation": [1, 0] })), None);
+        assert_eq!(
+            convert("Enum", json!({ "enumType": "Material", "value": 256 })),
+            Some(Variant::Enum(Enum::from_u32(256)))
+        );
+        assert_eq!(
+            convert("Enum", json!({ "enumType": "Material", "value": "Plastic" })),
+            Some(Variant::Enum(Enum::from_u32(256)))
+        );
+        assert_eq!(convert("Enum", json!({ "enumType": "Material", "value": "Cheese" })), None);
+        assert_eq!(
+            convert("Rect", json!({ "min": { "x": 0, "y": 1 }, "max": { "x": 2, "y": 3 } })),
+            Some(Variant::Rect(Rect::new(Vector2::new(0.0, 1.0), Vector2::new(2.0, 3.0))))
+        );
+        assert_eq!(
+            convert("NumberRange", json!({ "min": 1, "max": 4 })),
+            Some(Variant::NumberRange(NumberRange::new(1.0, 4.0)))
+        );
+        let Some(Variant::Font(font)) =
+            convert("Font", json!({ "family": "rbxasset://fonts/families/Arial.json", "weight": 700, "style": "Italic" }))
+        else {
+            panic!("expected a Font");
+        };
+        assert_eq!(font.weight, FontWeight::Bold);
+        assert_eq!(font.style, FontStyle::Italic);
+        let Some(Variant::Font(font)) = convert("Font", json!({ "family": "rbxasset://x.json" })) else {
+            panic!("expected a Font");
+        };
+        assert_eq!((font.weight, font.style), (FontWeight::Regular, FontStyle::Normal));
+        assert_eq!(
+            convert("Content", json!("rbxassetid://1")),
+            Some(Variant::Content(Content::from("rbxassetid://1".to_string())))
+        );
+        assert_eq!(convert("Ref", json!("abc")), None);
+        assert_eq!(convert("Ref", json!(null)), Some(Variant::Ref(Ref::none())));
+        assert_eq!(convert("ColorSequence", json!([])), None);
+        assert_eq!(convert("Vector3", json!({ "x": 1 })), None);
+
+        // Sequences, physics, Ray, Faces and Axes as the plugin extracts them
+        let sequences_and_physics = [
+            (
+                "Size",
+                convert(
+                    "NumberSequence",
+                    json!({ "keypoints": [{ "time": 0, "value": 1, "envelope": 0.5 }, { "time": 1, "value": 0 }] }),
+                ),
+                Variant::NumberSequence(NumberSequence {
+                    keypoints: vec![NumberSequenceKeypoint::new(0.0, 1.0, 0.5), NumberSequenceKeypoint::new(1.0, 0.0, 0.0)],
+                }),
+            ),
+            (
+                "Color",
+                convert(
+                    "ColorSequence",
+                    json!([{ "time": 0, "color": { "r": 1, "g": 0, "b": 0 } }, { "time": 1, "color": { "r": 0, "g": 0, "b": 1 } }]),
+                ),
+                Variant::ColorSequence(ColorSequence {
+                    keypoints: vec![
+                        ColorSequenceKeypoint::new(0.0, Color3::new(1.0, 0.0, 0.0)),
+                        ColorSequenceKeypoint::new(1.0, Color3::new(0.0, 0.0, 1.0)),
+                    ],
+                }),
+            ),
+            (
+                "CustomPhysicalProperties",
+                convert(
+                    "PhysicalProperties",
+                    json!({ "density": 0.5, "friction": 0.25, "elasticity": 1, "frictionWeight": 2, "elasticity_weight": 3 }),
+                ),
+                Variant::PhysicalProperties(PhysicalProperties::Custom(CustomPhysicalProperties {
+                    density: 0.5,
+                    friction: 0.25,
+                    elasticity: 1.0,
+                    friction_weight: 2.0,
+                    elasticity_weight: 3.0,
+                })),
+            ),
+            (
+                "Ray",
+                convert("Ray", json!({ "origin": { "x": 0, "y": 5, "z": 0 }, "direction": { "x": 0, "y": -1, "z": 0 } })),
+                Variant::Ray(Ray::new(Vector3::new(0.0, 5.0, 0.0), Vector3::new(0.0, -1.0, 0.0))),
+            ),
+            (
+                "Faces",
+                convert("Faces", json!({ "top": true, "bottom": false, "left": true, "front": true })),
+                Variant::Faces(Faces::from_bits(2 | 8 | 32).unwrap()),
+            ),
+            (
+                "Axes",
+                convert("Axes", json!({ "x": true, "y": false, "z": true })),
+                Variant::Axes(Axes::from_bits(1 | 4).unwrap()),
+            ),
+        ];
+        for (_, converted, expected) in &sequences_and_physics {
+            assert_eq!(converted.as_ref(), Some(expected));
+        }
+        assert_eq!(
+            json_to_variant(&json!({ "type": "PhysicalProperties" })),
+            Some(Variant::PhysicalProperties(PhysicalProperties::Default))
+        );
+        assert_eq!(convert("PhysicalProperties", json!({ "density": 1 })), None);
+
+        // Each survives a write to a binary model and back
+        let mut dom = rbx_dom_weak::WeakDom::new(rbx_dom_weak::InstanceBuilder::new("Folder"));
+        let mut part = rbx_dom_weak::InstanceBuilder::new("Folder");
+        for (name, _, value) in &sequences_and_physics {
+            part = part.with_property(*name, value.clone());
+        }
+        let part = dom.insert(dom.root_ref(), part);
+        let mut bytes = Vec::new();
+        rbx_binary::to_writer(&mut bytes, &dom, &[part]).unwrap();
+        let read = rbx_binary::from_reader(bytes.as_slice()).unwrap();
+        let read_part = read.get_by_ref(read.root().children()[0]).unwrap();
+        for (name, _, value) in &sequences_and_physics {
+            assert_eq!(read_part.properties.get(*name), Some(value), "{} round-trips", name);
+        }
+
+        // Untyped values are taken as-is
+        assert_eq!(json_to_variant(&json!("raw")), Some(Variant::String("raw".into())));
+        assert_eq!(json_to_variant(&json!(false)), Some(Variant::Bool(false)));
+        assert_eq!(json_to_variant(&json!(7)), Some(Variant::Int32(7)));
+        assert_eq!(json_to_variant(&json!(0.5)), Some(Variant::Float64(0.5)));
+        assert_eq!(json_to_variant(&json!(null)), None);
+    }
+
+    #[test]
+    fn test_remaining_types_round_trip() {
+        let values = [
+            json!({ "type": "Vector2int16", "value": { "x": -3, "y": 4 } }),
+            json!({ "type": "Vector3int16", "value": { "x": 1, "y": -2, "z": 3 } }),
+            json!({ "type": "OptionalCFrame", "value": {
+                "position": [1.0, 2.0, 3.0], "rotation": [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]
+            } }),
+            json!({ "type": "OptionalCFrame", "value": null }),
+            json!({ "type": "Region3", "value": { "min": { "x": 0.0, "y": 0.0, "z": 0.0 }, "max": { "x": 4.0, "y": 2.0, "z": 4.0 } } }),
+            json!({ "type": "Region3int16", "value": { "min": { "x": 0, "y": 0, "z": 0 }, "max": { "x": 8, "y": 8, "z": 8 } } }),
+            json!({ "type": "BinaryString", "value": "AAEC/w==" }),
+            json!({ "type": "SecurityCapabilities", "value": 5 }),
+            json!({ "type": "Tags", "value": ["Enemy", "Boss"] }),
+            json!({ "type": "float", "value": "Infinity" }),
+            json!({ "type": "Font", "value": { "family": "rbxasset://fonts/families/Arial.json", "weight": "SemiBold", "style": "Italic" } }),
+            json!({ "type": "Enum", "value": { "enumType": "Material", "value": "Neon" } }),
+        ];
+        for value in &values {
+            let variant = json_to_variant(value).unwrap_or_else(|| panic!("{} converts", value));
+            let class = if value["type"] == "Enum" { "Part" } else { "" };
+            let property = if value["type"] == "Enum" { "Material" } else { "" };
+            assert_eq!(variant_to_json(class, property, &variant, &HashMap::new()).as_ref(), Some(value), "{} round-trips", value);
+        }
+        assert_eq!(convert("ProtectedString", json!("return 1")), Some(Variant::String("return 1".into())));
+        assert_eq!(convert("BinaryString", json!("")), None, "empty is the plugin's placeholder");
+        assert_eq!(convert("SharedString", json!({ "hash": "abc", "file": null })), None);
+
+        // Attribute numbers are doubles whatever type they were written as
+        let attributes = json_to_attributes(&json!({
+            "Health": { "type": "int", "value": 100 },
+            "Speed": { "type": "float", "value": 0.5 },
+            "Team": { "type": "string", "value": "Red" }
+        }))
+        .unwrap();
+        let read: HashMap<&String, &Variant> = attributes.iter().collect();
+        assert_eq!(read[&"Health".to_string()], &Variant::Float64(100.0));
+        assert_eq!(read[&"Speed".to_string()], &Variant::Float64(0.5));
+        assert_eq!(read[&"Team".to_string()], &Variant::String("Red".into()));
+        let written = attributes_to_json(&attributes);
+        assert_eq!(written["Health"], json!({ "type": "int", "value": 100 }));
+        assert_eq!(written["Speed"], json!({ "type": "float", "value": 0.5 }));
+    }
+
+    #[test]
+    fn test_normalize_properties() {
+        let mut instance = json!({
+            "className": "Part",
+            "properties": {
+                "Material": { "type": "Enum", "value": { "value": 256 } },
+                "Shape": { "type": "Enum", "value": { "enumType": "PartType", "value": 1 } },
+                "TopSurface": { "type": "Enum", "value": { "enumType": "SurfaceType", "value": "Smooth" } },
+                "Unknown": { "type": "Enum", "value": { "enumType": "Material", "value": 9999 } },
+                "Size": { "type": "Vector3", "value": { "x": 1, "y": 2, "z": 3 } }
+            }
+        });
+        let original = instance.clone();
+        normalize_properties(&mut instance);
+        let properties = &instance["properties"];
+        assert_eq!(properties["Material"]["value"], json!({ "enumType": "Material", "value": "Plastic" }));
+        assert_eq!(properties["Shape"]["value"], json!({ "enumType": "PartType", "value": "Block" }));
+        assert_eq!(properties["TopSurface"], original["properties"]["TopSurface"]);
+        assert_eq!(properties["Unknown"], original["properties"]["Unknown"]);
+        assert_eq!(properties["Size"], original["properties"]["Size"]);
+
+        let mut label = json!({ "className": "TextLabel", "properties": {
+            "FontFace": { "type": "Font", "value": { "family": "rbxasset://x.json", "weight": 700, "style": "Normal" } }
+        }});
+        normalize_properties(&mut label);
+        assert_eq!(label["properties"]["FontFace"]["value"]["weight"], "Bold");
+    }
+
+    #[test]
+    fn test_reflection_lookups() {
+        assert_eq!(convert("Enum", json!("Enum.Material.Neon")), Some(Variant::Enum(Enum::from_u32(288))));
+        assert_eq!(
+            convert("Enum", json!({ "enumType": "Material", "value": "Enum.Material.Neon" })),
+            Some(Variant::Enum(Enum::from_u32(288)))
+        );
+        assert_eq!(convert("Enum", json!("Material.Neon")), None);
+
+        assert!(is_known_property("Part", "Anchored"));
+        assert!(is_known_property("Part", "Name"), "inherited from Instance");
+        assert!(!is_known_property("Part", "Anchord"));
+        assert!(is_known_property("NotAClass", "Anything"));
+
+        let mut part = json!({ "className": "Part", "properties": {
+            "Anchored": { "type": "bool", "value": false },
+            "CanCollide": { "type": "bool", "value": false },
+            "Transparency": { "type": "float", "value": 0 },
+            "Material": { "type": "Enum", "value": "Enum.Material.Plastic" }
+        }});
+        assert_eq!(strip_defaults(&mut part), 3);
+        let left: Vec<&String> = part["properties"].as_object().unwrap().keys().collect();
+        assert_eq!(left, vec!["CanCollide"]);
+
+        assert!(omit_defaults(Some(&json!({ "config": { "omitDefaultProperties": true } }))));
+        assert!(!omit_defaults(Some(&json!({ "config": {} }))));
+    }
+
+    #[test]
+    fn test_validate_value() {
+        assert!(validate_value(&json!({ "type": "Vector3", "value": { "x": 0, "y": 5, "z": 0 } })).is_ok());
+        assert!(validate_value(&json!({ "type": "Enum", "value": "Enum.Material.Neon" })).is_ok());
+        assert!(validate_value(&json!({ "type": "Enum", "value": { "enumType": "Material", "value": 288 } })).is_ok());
+
+        let error = validate_value(&json!({ "type": "Vector3", "value": { "x": 0, "y": 5 } })).unwrap_err();
+        assert_eq!(error, "not a valid Vector3 value: expected { x, y, z }");
+        assert!(validate_value(&json!({ "type": "Color3", "value": "red" })).is_err());
+        assert!(validate_value(&json!({ "type": "CFrame", "value": { "position": [0, 0, 0] } })).is_err());
+        // An enum needs its type named, and the item must exist
+        assert!(validate_value(&json!({ "type": "Enum", "value": { "value": 288 } })).is_err());
+        assert!(validate_value(&json!({ "type": "Enum", "value": "Enum.Material.Glowing" })).is_err());
+        assert!(validate_value(&json!({ "type": "Enum", "value": { "enumType": "Material", "value": 9999 } })).is_err());
+        assert!(validate_value(&json!({ "type": "Ref", "value": null })).is_err());
+        assert!(validate_value(&json!(5)).is_err());
+    }
+}
+
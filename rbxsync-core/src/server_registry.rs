@@ -0,0 +1,169 @@
+//! Registry of running rbxsync servers
+//!
+//! `~/.rbxsync/servers.json` lists the servers started on this machine with
+//! their port and version. When the default port is held by another rbxsync
+//! version and a server fails over to a free port, the registry is how the
+//! CLI and MCP server of the matching version find it again.
+//!
+//! Each project a server works with also gets `.rbxsync/server.json`, so
+//! tools started inside that workspace know which port to use without
+//! guessing.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Port the server listens on unless told otherwise
+pub const DEFAULT_PORT: u16 = 44755;
+
+/// Ports tried, in order, when the default one is taken
+pub const FAILOVER_PORTS: std::ops::RangeInclusive<u16> = 44756..=44765;
+
+/// One running server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerEntry {
+    pub port: u16,
+    pub pid: u32,
+    pub version: String,
+    /// Directory the server was started in
+    #[serde(default)]
+    pub cwd: String,
+    /// Unix seconds
+    #[serde(default)]
+    pub started_at: u64,
+}
+
+/// Port file written into each project a server works with
+pub const WORKSPACE_SERVER_FILE: &str = ".rbxsync/server.json";
+
+/// `~/.rbxsync/servers.json`
+pub fn registry_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".rbxsync").join("servers.json"))
+}
+
+/// Entries in a registry file (empty if missing or unreadable)
+pub fn load_registry(path: &Path) -> Vec<ServerEntry> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_registry(path: &Path, entries: &[ServerEntry]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(entries).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
+}
+
+/// Add a server, replacing any earlier entry for the same port
+pub fn register_server(path: &Path, entry: ServerEntry) -> std::io::Result<()> {
+    let mut entries = load_registry(path);
+    entries.retain(|e| e.port != entry.port);
+    entries.push(entry);
+    entries.sort_by_key(|e| e.port);
+    save_registry(path, &entries)
+}
+
+/// Remove the entry for a port
+pub fn unregister_server(path: &Path, port: u16) -> std::io::Result<()> {
+    let mut entries = load_registry(path);
+    let before = entries.len();
+    entries.retain(|e| e.port != port);
+    if entries.len() == before {
+        return Ok(());
+    }
+    save_registry(path, &entries)
+}
+
+/// Record the server working with a project in its `.rbxsync/server.json`
+pub fn write_workspace_server(project_dir: &Path, entry: &ServerEntry) -> std::io::Result<()> {
+    let path = project_dir.join(WORKSPACE_SERVER_FILE);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(entry).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
+}
+
+/// Server last recorded for a project. Like registry entries it may be stale.
+pub fn read_workspace_server(project_dir: &Path) -> Option<ServerEntry> {
+    let content = std::fs::read_to_string(project_dir.join(WORKSPACE_SERVER_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Ports a server of any version might be on: the default port, the
+/// failover range and anything else in the registry
+pub fn discovery_ports(entries: &[ServerEntry]) -> Vec<u16> {
+    let mut ports: Vec<u16> = std::iter::once(DEFAULT_PORT)
+        .chain(FAILOVER_PORTS)
+        .chain(entries.iter().map(|e| e.port))
+        .collect();
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+}
+
+/// Port of the registered server running `version`, preferring the default port.
+///
+/// Entries can be stale (a killed server doesn't unregister), so callers that
+/// need certainty should still check the server's `/health`.
+pub fn port_for_version(entries: &[ServerEntry], version: &str) -> Option<u16> {
+    let mut matching: Vec<u16> = entries.iter().filter(|e| e.version == version).map(|e| e.port).collect();
+    matching.sort_by_key(|port| (*port != DEFAULT_PORT, *port));
+    matching.first().copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_registry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("servers.json");
+        assert!(load_registry(&path).is_empty());
+
+        let entry = |port: u16, version: &str| ServerEntry {
+            port,
+            pid: 100 + port as u32,
+            version: version.to_string(),
+            cwd: "/games/obby".to_string(),
+            started_at: 0,
+        };
+        register_server(&path, entry(DEFAULT_PORT, "1.2.0")).unwrap();
+        register_server(&path, entry(44756, "1.3.0")).unwrap();
+        register_server(&path, entry(44756, "1.3.1")).unwrap();
+
+        let entries = load_registry(&path);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(port_for_version(&entries, "1.3.1"), Some(44756));
+        assert_eq!(port_for_version(&entries, "1.3.0"), None);
+
+        unregister_server(&path, DEFAULT_PORT).unwrap();
+        assert_eq!(load_registry(&path).len(), 1);
+    }
+
+    #[test]
+    fn test_workspace_server_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(read_workspace_server(dir.path()), None);
+
+        let entry = ServerEntry {
+            port: 44757,
+            pid: 4242,
+            version: "1.3.0".to_string(),
+            cwd: String::new(),
+            started_at: 0,
+        };
+        write_workspace_server(dir.path(), &entry).unwrap();
+        assert_eq!(read_workspace_server(dir.path()), Some(entry.clone()));
+
+        let ports = discovery_ports(&[ServerEntry { port: 50000, ..entry }]);
+        assert_eq!(ports.first(), Some(&DEFAULT_PORT));
+        assert_eq!(ports.len(), 12);
+        assert_eq!(ports.last(), Some(&50000));
+    }
+}
@@ -0,0 +1,629 @@
+//! Static site export for design review
+//!
+//! Renders a project snapshot as plain HTML: the instance tree, every script's
+//! source with syntax highlighting, every instance's properties, and the
+//! require graph between scripts. Pages link to each other with relative URLs
+//! and need no JavaScript or server, so the bundle can be zipped, attached to
+//! a review or hosted anywhere.
+//!
+//! Requires are found by a lightweight scanner, not a Luau parser. It follows
+//! `script`, `.Parent`, `game:GetService(...)` and `:WaitForChild(...)` chains,
+//! locals assigned from them, and relative string requires (`"./Util"`).
+//! Anything else is left out of the graph.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+use serde_json::{Map, Value};
+
+use crate::migrate::strip_comments;
+
+/// Longest property value shown, in characters
+const MAX_VALUE_LEN: usize = 200;
+
+const KEYWORDS: &[&str] = &[
+    "and", "break", "continue", "do", "else", "elseif", "end", "export", "false", "for", "function", "if", "in",
+    "local", "nil", "not", "or", "repeat", "return", "then", "true", "type", "typeof", "until", "while",
+];
+const BUILTINS: &[&str] = &["game", "script", "workspace", "require", "self", "shared", "_G"];
+
+const STYLE: &str = "body{font:14px/1.5 system-ui,sans-serif;margin:0;color:#1f2328}\
+header{background:#24292f;color:#fff;padding:10px 20px}header a{color:#fff;margin-right:16px}\
+main{padding:20px;max-width:1200px}a{color:#0969da;text-decoration:none}a:hover{text-decoration:underline}\
+ul.tree{list-style:none;padding-left:18px}ul.tree li{margin:2px 0}.cls{color:#6e7781;font-size:12px;margin-left:6px}\
+table{border-collapse:collapse;margin:8px 0 16px}td,th{border:1px solid #d0d7de;padding:4px 8px;text-align:left;vertical-align:top}\
+td code{white-space:pre-wrap;word-break:break-all}pre{background:#f6f8fa;padding:12px;overflow:auto;font-size:13px}\
+.k{color:#cf222e}.s{color:#0a3069}.c{color:#6e7781;font-style:italic}.n{color:#0550ae}.b{color:#8250df}\
+svg text{font:12px system-ui,sans-serif}";
+
+/// One instance of the snapshot
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SiteInstance {
+    /// DataModel path with `/` separators
+    pub instance_path: String,
+    pub class_name: String,
+    /// File relative to the project
+    pub file: Option<String>,
+    /// Script source
+    pub source: Option<String>,
+    /// Properties as stored in .rbxjson (`{ "type", "value" }` per property)
+    pub properties: Map<String, Value>,
+    pub attributes: Map<String, Value>,
+}
+
+/// One file of the generated site
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitePage {
+    /// Path relative to the site root
+    pub path: String,
+    pub content: String,
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Length of the long bracket opening at the start of `text` (`[[`, `[==[`), and its level
+fn long_bracket(text: &str) -> Option<(usize, usize)> {
+    let rest = text.strip_prefix('[')?;
+    let level = rest.chars().take_while(|c| *c == '=').count();
+    rest[level..].starts_with('[').then_some((level + 2, level))
+}
+
+/// Luau source as HTML with `k` (keyword), `b` (builtin), `s` (string), `n` (number) and `c` (comment) spans
+pub fn highlight_luau(source: &str) -> String {
+    let mut out = String::with_capacity(source.len() * 2);
+    let span = |out: &mut String, class: &str, text: &str| {
+        let _ = write!(out, "<span class=\"{}\">{}</span>", class, escape_html(text));
+    };
+    let mut i = 0;
+    while i < source.len() {
+        let rest = &source[i..];
+        let c = rest.chars().next().unwrap_or(' ');
+        let len = if let Some(comment) = rest.strip_prefix("--") {
+            let len = match long_bracket(comment) {
+                Some((open, level)) => {
+                    let close = format!("]{}]", "=".repeat(level));
+                    comment[open..].find(&close).map_or(comment.len(), |end| open + end + close.len())
+                }
+                None => comment.find('\n').unwrap_or(comment.len()),
+            };
+            span(&mut out, "c", &rest[..len + 2]);
+            len + 2
+        } else if let Some((open, level)) = long_bracket(rest) {
+            let close = format!("]{}]", "=".repeat(level));
+            let len = rest[open..].find(&close).map_or(rest.len(), |end| open + end + close.len());
+            span(&mut out, "s", &rest[..len]);
+            len
+        } else if c == '"' || c == '\'' || c == '`' {
+            let mut escaped = false;
+            let end = rest[1..]
+                .char_indices()
+                .find(|(_, ch)| {
+                    let done = !escaped && (*ch == c || *ch == '\n');
+                    escaped = !escaped && *ch == '\\';
+                    done
+                })
+                .map_or(rest.len(), |(pos, ch)| pos + 1 + ch.len_utf8());
+            span(&mut out, "s", &rest[..end]);
+            end
+        } else if c.is_ascii_digit() {
+            let len = rest
+                .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '.' || ch == '_'))
+                .unwrap_or(rest.len());
+            span(&mut out, "n", &rest[..len]);
+            len
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rest.find(|ch: char| !(ch.is_alphanumeric() || ch == '_')).unwrap_or(rest.len());
+            let word = &rest[..len];
+            match () {
+                _ if KEYWORDS.contains(&word) => span(&mut out, "k", word),
+                _ if BUILTINS.contains(&word) => span(&mut out, "b", word),
+                _ => out.push_str(word),
+            }
+            len
+        } else {
+            out.push_str(&escape_html(&rest[..c.len_utf8()]));
+            c.len_utf8()
+        };
+        i += len;
+    }
+    out
+}
+
+/// Instance path a path expression points to (`script.Parent.Util`,
+/// `game:GetService("ReplicatedStorage"):WaitForChild("Shared")`, ...)
+fn resolve_expression(expr: &str, script_path: &[String], aliases: &HashMap<String, Vec<String>>) -> Option<Vec<String>> {
+    let expr = expr.trim();
+    let base_len = expr.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(expr.len());
+    let mut path = match &expr[..base_len] {
+        "script" => script_path.to_vec(),
+        "game" => Vec::new(),
+        "workspace" => vec!["Workspace".to_string()],
+        alias => aliases.get(alias)?.clone(),
+    };
+    let mut rest = expr[base_len..].trim_start();
+    while !rest.is_empty() {
+        let quoted = |text: &str| -> Option<(String, usize)> {
+            let text = text.trim_start();
+            let quote = text.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let end = text[1..].find(quote)?;
+            Some((text[1..end + 1].to_string(), end + 2))
+        };
+        if let Some(after) = rest.strip_prefix('.') {
+            let len = after.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(after.len());
+            match &after[..len] {
+                "" => return None,
+                "Parent" => {
+                    path.pop()?;
+                }
+                name => path.push(name.to_string()),
+            }
+            rest = &after[len..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let (name, len) = quoted(after)?;
+            path.push(name);
+            rest = after.trim_start()[len..].trim_start().strip_prefix(']')?;
+        } else if let Some(after) = rest.strip_prefix(':') {
+            let len = after.find('(')?;
+            if !matches!(&after[..len], "GetService" | "WaitForChild" | "FindFirstChild") {
+                return None;
+            }
+            let args = &after[len + 1..];
+            let (name, arg_len) = quoted(args)?;
+            let trimmed = args.trim_start();
+            let close = trimmed[arg_len..].find(')')?;
+            path.push(name);
+            rest = &trimmed[arg_len + close + 1..];
+        } else {
+            return None;
+        }
+        rest = rest.trim_start();
+    }
+    Some(path)
+}
+
+/// Instance path of a string require relative to the script (`"./Util"`, `"../Shared/Types"`)
+fn resolve_string_require(literal: &str, script_path: &[String]) -> Option<Vec<String>> {
+    let mut path = script_path.to_vec();
+    path.pop()?;
+    let mut segments = literal.split('/');
+    match segments.next()? {
+        "." => {}
+        ".." => {
+            path.pop()?;
+        }
+        _ => return None,
+    }
+    for segment in segments {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                path.pop()?;
+            }
+            name => path.push(name.trim_end_matches(".luau").trim_end_matches(".lua").to_string()),
+        }
+    }
+    Some(path)
+}
+
+/// Instance paths a script requires, in order of first appearance
+pub fn find_requires(source: &str, script_path: &str) -> Vec<String> {
+    let source = strip_comments(source);
+    let script: Vec<String> = script_path.split('/').map(str::to_string).collect();
+    let mut aliases: HashMap<String, Vec<String>> = HashMap::new();
+    let mut requires: Vec<String> = Vec::new();
+
+    for line in source.lines() {
+        // local Shared = game:GetService("ReplicatedStorage").Shared
+        if let Some((name, expr)) = line
+            .trim()
+            .strip_prefix("local ")
+            .and_then(|decl| decl.split_once('='))
+            .filter(|(_, expr)| !expr.trim_start().starts_with("require"))
+        {
+            let name = name.split(':').next().unwrap_or(name).trim();
+            if name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                if let Some(path) = resolve_expression(expr, &script, &aliases) {
+                    aliases.insert(name.to_string(), path);
+                }
+            }
+        }
+
+        let mut rest = line;
+        while let Some(pos) = rest.find("require") {
+            let boundary = !rest[..pos].ends_with(|c: char| c.is_alphanumeric() || c == '_');
+            rest = &rest[pos + "require".len()..];
+            let Some(args) = rest.trim_start().strip_prefix('(').filter(|_| boundary) else {
+                continue;
+            };
+            let Some(close) = args.rfind(')') else {
+                continue;
+            };
+            let arg = args[..close].trim();
+            let target = match arg.chars().next() {
+                Some('"' | '\'') => resolve_string_require(arg.trim_matches(|c| c == '"' || c == '\''), &script),
+                _ => {
+                    // Cut at the matching parenthesis of require(...)
+                    let mut depth = 0;
+                    let end = arg
+                        .char_indices()
+                        .find(|(_, c)| {
+                            match c {
+                                '(' => depth += 1,
+                                ')' if depth == 0 => return true,
+                                ')' => depth -= 1,
+                                _ => {}
+                            }
+                            false
+                        })
+                        .map_or(arg.len(), |(i, _)| i);
+                    resolve_expression(&arg[..end], &script, &aliases)
+                }
+            };
+            if let Some(target) = target.filter(|t| !t.is_empty()).map(|t| t.join("/")) {
+                if !requires.contains(&target) {
+                    requires.push(target);
+                }
+            }
+        }
+    }
+    requires
+}
+
+/// Short display of a stored property value
+fn display_value(value: &Value) -> String {
+    let inner = value.get("value").filter(|_| value.get("type").is_some()).unwrap_or(value);
+    let text = match inner {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if text.chars().count() > MAX_VALUE_LEN {
+        format!("{}…", text.chars().take(MAX_VALUE_LEN).collect::<String>())
+    } else {
+        text
+    }
+}
+
+/// Page file names for instance paths: readable, safe and unique
+fn page_names(paths: &[&str]) -> HashMap<String, String> {
+    let mut used: BTreeSet<String> = BTreeSet::new();
+    let mut names = HashMap::new();
+    for path in paths {
+        let base: String = path
+            .chars()
+            .map(|c| match c {
+                '/' => '.',
+                c if c.is_ascii_alphanumeric() || c == '-' || c == '_' => c,
+                _ => '_',
+            })
+            .collect();
+        let mut name = format!("{}.html", base);
+        let mut n = 2;
+        while !used.insert(name.to_lowercase()) {
+            name = format!("{}-{}.html", base, n);
+            n += 1;
+        }
+        names.insert(path.to_string(), name);
+    }
+    names
+}
+
+fn page(title: &str, root: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <link rel=\"stylesheet\" href=\"{root}style.css\">\n</head>\n<body>\n\
+         <header><a href=\"{root}index.html\">Tree</a><a href=\"{root}graph.html\">Dependencies</a></header>\n\
+         <main>\n{}</main>\n</body>\n</html>\n",
+        escape_html(title),
+        body,
+        root = root
+    )
+}
+
+fn render_tree(out: &mut String, path: &str, children: &BTreeMap<String, Vec<String>>, classes: &HashMap<String, String>, names: &HashMap<String, String>) {
+    let Some(kids) = children.get(path).filter(|k| !k.is_empty()) else {
+        return;
+    };
+    out.push_str("<ul class=\"tree\">\n");
+    for child in kids {
+        let name = child.rsplit('/').next().unwrap_or(child);
+        let _ = write!(
+            out,
+            "<li><a href=\"instances/{}\">{}</a><span class=\"cls\">{}</span>",
+            names[child],
+            escape_html(name),
+            escape_html(&classes[child])
+        );
+        render_tree(out, child, children, classes, names);
+        out.push_str("</li>\n");
+    }
+    out.push_str("</ul>\n");
+}
+
+/// Layered SVG drawing of the require graph, sources on the left
+fn render_graph_svg(edges: &BTreeSet<(String, String)>, names: &HashMap<String, String>) -> String {
+    let nodes: BTreeSet<&str> = edges.iter().flat_map(|(a, b)| [a.as_str(), b.as_str()]).collect();
+    let mut layer: HashMap<&str, usize> = nodes.iter().map(|n| (*n, 0)).collect();
+    // Longest path from a source; capped so cycles terminate
+    for _ in 0..nodes.len() {
+        let mut changed = false;
+        for (from, to) in edges {
+            let next = layer[from.as_str()] + 1;
+            if next > layer[to.as_str()] && next < nodes.len() {
+                layer.insert(to.as_str(), next);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let (col_width, row_height, box_width) = (240, 32, 200);
+    let mut rows: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut position: HashMap<&str, (usize, usize)> = HashMap::new();
+    for node in &nodes {
+        let column = layer[node];
+        let row = rows.entry(column).or_default();
+        position.insert(node, (20 + column * col_width, 20 + *row * row_height));
+        *row += 1;
+    }
+    let width = 40 + (rows.len().max(1)) * col_width;
+    let height = 40 + rows.values().max().copied().unwrap_or(0) * row_height;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n\
+         <defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\">\
+         <path d=\"M0,0L10,5L0,10z\" fill=\"#8c959f\"/></marker></defs>\n",
+        width, height
+    );
+    for (from, to) in edges {
+        let ((x1, y1), (x2, y2)) = (position[from.as_str()], position[to.as_str()]);
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#8c959f\" marker-end=\"url(#arrow)\"/>",
+            x1 + box_width,
+            y1 + 11,
+            x2,
+            y2 + 11
+        );
+    }
+    for node in &nodes {
+        let (x, y) = position[node];
+        let label = node.rsplit('/').next().unwrap_or(node);
+        let _ = writeln!(
+            svg,
+            "<a href=\"instances/{}\"><title>{}</title><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"22\" rx=\"4\" fill=\"#ddf4ff\" stroke=\"#54aeff\"/>\
+             <text x=\"{}\" y=\"{}\">{}</text></a>",
+            names[*node],
+            escape_html(node),
+            x,
+            y,
+            box_width,
+            x + 6,
+            y + 15,
+            escape_html(label)
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn property_table(title: &str, values: &Map<String, Value>) -> String {
+    if values.is_empty() {
+        return String::new();
+    }
+    let mut out = format!("<h2>{}</h2>\n<table>\n<tr><th>Name</th><th>Type</th><th>Value</th></tr>\n", title);
+    for (name, value) in values {
+        let type_name = value.get("type").and_then(|t| t.as_str()).unwrap_or("");
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td><code>{}</code></td></tr>",
+            escape_html(name),
+            escape_html(type_name),
+            escape_html(&display_value(value))
+        );
+    }
+    out.push_str("</table>\n");
+    out
+}
+
+/// Render the site: `index.html` (tree), `graph.html`, `style.css` and one page per instance under `instances/`
+pub fn render_site(name: &str, instances: &[SiteInstance]) -> Vec<SitePage> {
+    // Every ancestor gets a page, as a Folder when it has no file of its own
+    let mut all: BTreeMap<String, Option<&SiteInstance>> = BTreeMap::new();
+    for inst in instances {
+        let mut prefix = String::new();
+        for segment in inst.instance_path.split('/') {
+            if !prefix.is_empty() {
+                prefix.push('/');
+            }
+            prefix.push_str(segment);
+            all.entry(prefix.clone()).or_insert(None);
+        }
+        all.insert(inst.instance_path.clone(), Some(inst));
+    }
+    let classes: HashMap<String, String> = all
+        .iter()
+        .map(|(path, inst)| (path.clone(), inst.map_or("Folder", |i| i.class_name.as_str()).to_string()))
+        .collect();
+    let mut children: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for path in all.keys() {
+        let parent = path.rsplit_once('/').map_or("", |(p, _)| p);
+        children.entry(parent.to_string()).or_default().push(path.clone());
+    }
+    let paths: Vec<&str> = all.keys().map(String::as_str).collect();
+    let names = page_names(&paths);
+
+    let mut edges: BTreeSet<(String, String)> = BTreeSet::new();
+    for inst in instances {
+        if let Some(source) = &inst.source {
+            for target in find_requires(source, &inst.instance_path) {
+                if all.contains_key(&target) && target != inst.instance_path {
+                    edges.insert((inst.instance_path.clone(), target));
+                }
+            }
+        }
+    }
+
+    let mut pages = vec![SitePage { path: "style.css".to_string(), content: STYLE.to_string() }];
+
+    let scripts = instances.iter().filter(|i| i.source.is_some()).count();
+    let mut body = format!(
+        "<h1>{}</h1>\n<p>{} instances, {} scripts, {} requires</p>\n",
+        escape_html(name),
+        all.len(),
+        scripts,
+        edges.len()
+    );
+    render_tree(&mut body, "", &children, &classes, &names);
+    pages.push(SitePage { path: "index.html".to_string(), content: page(name, "", &body) });
+
+    let mut body = format!("<h1>Dependencies</h1>\n<p>{} requires between scripts</p>\n", edges.len());
+    if !edges.is_empty() {
+        body.push_str(&render_graph_svg(&edges, &names));
+        body.push_str("<table>\n<tr><th>Script</th><th>Requires</th></tr>\n");
+        for (from, to) in &edges {
+            let _ = writeln!(
+                body,
+                "<tr><td><a href=\"instances/{}\">{}</a></td><td><a href=\"instances/{}\">{}</a></td></tr>",
+                names[from],
+                escape_html(from),
+                names[to],
+                escape_html(to)
+            );
+        }
+        body.push_str("</table>\n");
+    }
+    pages.push(SitePage { path: "graph.html".to_string(), content: page("Dependencies", "", &body) });
+
+    for (path, inst) in &all {
+        let mut body = String::from("<p>");
+        let mut prefix = String::new();
+        for segment in path.split('/') {
+            if !prefix.is_empty() {
+                prefix.push('/');
+                body.push_str(" / ");
+            }
+            prefix.push_str(segment);
+            let _ = write!(body, "<a href=\"{}\">{}</a>", names[&prefix], escape_html(segment));
+        }
+        let _ = write!(
+            body,
+            "</p>\n<h1>{} <span class=\"cls\">{}</span></h1>\n",
+            escape_html(path.rsplit('/').next().unwrap_or(path)),
+            escape_html(&classes[path])
+        );
+        if let Some(file) = inst.and_then(|i| i.file.as_deref()) {
+            let _ = writeln!(body, "<p><code>{}</code></p>", escape_html(file));
+        }
+
+        let link_list = |title: &str, targets: Vec<&String>| {
+            if targets.is_empty() {
+                return String::new();
+            }
+            let items: String = targets
+                .iter()
+                .map(|t| format!("<li><a href=\"{}\">{}</a></li>\n", names[*t], escape_html(t)))
+                .collect();
+            format!("<h2>{}</h2>\n<ul>\n{}</ul>\n", title, items)
+        };
+        body.push_str(&link_list("Children", children.get(path).map(|k| k.iter().collect()).unwrap_or_default()));
+        body.push_str(&link_list("Requires", edges.iter().filter(|(f, _)| f == path).map(|(_, t)| t).collect()));
+        body.push_str(&link_list("Required by", edges.iter().filter(|(_, t)| t == path).map(|(f, _)| f).collect()));
+        if let Some(inst) = inst {
+            body.push_str(&property_table("Properties", &inst.properties));
+            body.push_str(&property_table("Attributes", &inst.attributes));
+            if let Some(source) = &inst.source {
+                let _ = writeln!(body, "<h2>Source</h2>\n<pre><code>{}</code></pre>", highlight_luau(source));
+            }
+        }
+        pages.push(SitePage { path: format!("instances/{}", names[path]), content: page(path, "../", &body) });
+    }
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_site_with_requires() {
+        let source = r#"
+local ReplicatedStorage = game:GetService("ReplicatedStorage")
+local Shared = ReplicatedStorage:WaitForChild("Shared")
+local Util = require(script.Parent.Util) -- require(script.Nope)
+local Types = require(Shared["Types"])
+local Signal = require("./Signal")
+local Remote = require(game.ReplicatedStorage.Shared:FindFirstChild("Net"))
+local unknown = require(someTable.Module)
+print("<b>" .. [[long
+string]], 0x1F)
+"#;
+        assert_eq!(
+            find_requires(source, "ServerScriptService/Main"),
+            vec![
+                "ServerScriptService/Util",
+                "ReplicatedStorage/Shared/Types",
+                "ServerScriptService/Signal",
+                "ReplicatedStorage/Shared/Net"
+            ]
+        );
+        assert_eq!(find_requires("return require(script.Parent.Parent.Parent.X)", "A/B"), Vec::<String>::new());
+
+        let html = highlight_luau("local s = \"<b>\" -- note\nreturn 42");
+        assert_eq!(
+            html,
+            "<span class=\"k\">local</span> s = <span class=\"s\">&quot;&lt;b&gt;&quot;</span> \
+             <span class=\"c\">-- note</span>\n<span class=\"k\">return</span> <span class=\"n\">42</span>"
+        );
+
+        let mut properties = Map::new();
+        properties.insert("Anchored".to_string(), serde_json::json!({ "type": "bool", "value": true }));
+        let instances = vec![
+            SiteInstance {
+                instance_path: "ServerScriptService/Main".to_string(),
+                class_name: "Script".to_string(),
+                file: Some("src/ServerScriptService/Main.server.luau".to_string()),
+                source: Some(source.to_string()),
+                ..Default::default()
+            },
+            SiteInstance {
+                instance_path: "ServerScriptService/Util".to_string(),
+                class_name: "ModuleScript".to_string(),
+                source: Some("return {}".to_string()),
+                ..Default::default()
+            },
+            SiteInstance {
+                instance_path: "Workspace/Lobby/Door?".to_string(),
+                class_name: "Part".to_string(),
+                properties,
+                ..Default::default()
+            },
+        ];
+        let pages = render_site("Obby <dev>", &instances);
+        let get = |path: &str| &pages.iter().find(|p| p.path == path).unwrap().content;
+        assert!(get("index.html").contains("<h1>Obby &lt;dev&gt;</h1>"));
+        assert!(get("index.html").contains("<a href=\"instances/Workspace.Lobby.html\">Lobby</a>"));
+        assert!(get("graph.html").contains("<svg") && get("graph.html").contains("instances/ServerScriptService.Util.html"));
+        let main = get("instances/ServerScriptService.Main.html");
+        assert!(main.contains("<h2>Requires</h2>") && main.contains("<span class=\"b\">require</span>"));
+        assert!(!main.contains("Shared.Types.html"), "targets outside the project aren't linked");
+        assert!(get("instances/ServerScriptService.Util.html").contains("<h2>Required by</h2>"));
+        let door = get("instances/Workspace.Lobby.Door_.html");
+        assert!(door.contains("<td>Anchored</td><td>bool</td><td><code>true</code></td>"));
+        assert!(door.contains("href=\"../style.css\""));
+        assert_eq!(pages.len(), 3 + 6);
+    }
+}
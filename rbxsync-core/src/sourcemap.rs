@@ -0,0 +1,399 @@
+//! sourcemap.json generation for Luau LSP
+//!
+//! Walks src/ the way the builder does and emits Rojo's sourcemap format:
+//! nested `name`/`className`/`filePaths`/`children` nodes rooted at the
+//! DataModel. `treeMapping` directories and Wally Packages folders are
+//! placed at their DataModel paths, so requires into them resolve.
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::builder::{child_instance_path, find_sibling_script, meta_class_name, parse_script_name};
+use crate::containers::container_class_name;
+use crate::types::ProjectConfig;
+
+/// Default output file, relative to the project directory
+pub const SOURCEMAP_FILE: &str = "sourcemap.json";
+
+/// Init files, in the builder's order, then the `.lua` ones Wally packages ship
+const INIT_FILES: [(&str, &str); 6] = [
+    ("init.server.luau", "Script"),
+    ("init.client.luau", "LocalScript"),
+    ("init.luau", "ModuleScript"),
+    ("init.server.lua", "Script"),
+    ("init.client.lua", "LocalScript"),
+    ("init.lua", "ModuleScript"),
+];
+
+/// A directory's init file and the script class it gives the directory
+fn init_file(dir_path: &Path) -> Option<(PathBuf, &'static str)> {
+    INIT_FILES
+        .iter()
+        .map(|(file, class)| (dir_path.join(file), *class))
+        .find(|(path, _)| path.is_file())
+}
+
+fn init_class(dir_path: &Path) -> Option<&'static str> {
+    init_file(dir_path).map(|(_, class)| class)
+}
+
+/// A directory the sourcemap places at a DataModel path other than its own
+/// place under src/: a `treeMapping` entry or a Wally Packages folder
+struct SourcemapMount {
+    inst_path: String,
+    dir: PathBuf,
+}
+
+/// Mounts for a project's rbxsync.json. Packages folders are mounted when
+/// `packages.enabled` is set or, without a `packages` section, when the
+/// folder exists, the same as sync.
+fn sourcemap_mounts(project_dir: &Path, src_dir: &Path) -> Vec<SourcemapMount> {
+    let config: ProjectConfig = std::fs::read_to_string(project_dir.join("rbxsync.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    let mut mounts: Vec<SourcemapMount> = config
+        .tree_mapping
+        .iter()
+        .map(|(inst_path, fs_path)| SourcemapMount {
+            inst_path: inst_path.trim_matches('/').to_string(),
+            dir: src_dir.join(fs_path.trim_start_matches("./").trim_end_matches('/')),
+        })
+        .collect();
+
+    let packages = config.packages.clone().unwrap_or_default();
+    let packages_dir = project_dir.join(&packages.packages_folder);
+    let enabled = match &config.packages {
+        Some(packages) => packages.enabled,
+        None => packages_dir.is_dir(),
+    };
+    if enabled {
+        // Server packages live inside the Packages folder for sync, or next to it where Wally puts them
+        if let Some(server_dir) = [packages_dir.join("ServerPackages"), project_dir.join("ServerPackages")]
+            .into_iter()
+            .find(|dir| dir.is_dir())
+        {
+            mounts.push(SourcemapMount { inst_path: packages.server_packages_path.clone(), dir: server_dir });
+        }
+        mounts.push(SourcemapMount { inst_path: packages.shared_packages_path.clone(), dir: packages_dir });
+    }
+
+    // Parents first, so a nested mount lands inside the node its parent mount created
+    mounts.sort_by_key(|m| (m.inst_path.split('/').count(), m.inst_path.clone()));
+    mounts
+}
+
+/// Put a node at a DataModel path, creating the instances above it that
+/// aren't in the tree yet. A node already at that path is replaced.
+fn graft_sourcemap_node(root: &mut Value, inst_path: &str, node: Value) {
+    let segments: Vec<&str> = inst_path.split('/').filter(|s| !s.is_empty()).collect();
+    let mut current = root;
+    for (i, segment) in segments.iter().enumerate() {
+        if !current.get("children").is_some_and(|c| c.is_array()) {
+            current["children"] = json!([]);
+        }
+        let Some(children) = current["children"].as_array_mut() else {
+            return;
+        };
+        let existing = children.iter().position(|c| c["name"] == *segment);
+        if i + 1 == segments.len() {
+            match existing {
+                Some(idx) => children[idx] = node,
+                None => children.push(node),
+            }
+            return;
+        }
+        let idx = existing.unwrap_or_else(|| {
+            let class_name = container_class_name(&segments[..i].join("/"), segment).unwrap_or("Folder");
+            children.push(json!({
+                "name": segment,
+                "className": class_name,
+                "filePaths": [],
+                "children": []
+            }));
+            children.len() - 1
+        });
+        current = &mut children[idx];
+    }
+}
+
+/// Build the sourcemap for a project: src/ walked as the DataModel, with
+/// `treeMapping` directories and Packages folders placed at their
+/// DataModel paths
+pub fn generate_sourcemap(project_dir: &Path, include_non_scripts: bool) -> io::Result<Value> {
+    let src_dir = project_dir.join("src");
+    let mounts = sourcemap_mounts(project_dir, &src_dir);
+    let skip: HashSet<PathBuf> = mounts.iter().map(|m| m.dir.clone()).collect();
+
+    let mut root = build_sourcemap_node("game", "DataModel", "", &src_dir, include_non_scripts, &skip)?;
+    for mount in &mounts {
+        if !mount.dir.is_dir() || mount.inst_path.is_empty() {
+            continue;
+        }
+        let (parent, name) = mount.inst_path.rsplit_once('/').unwrap_or(("", &mount.inst_path));
+        let class_name = init_class(&mount.dir)
+            .or(container_class_name(parent, name))
+            .map(String::from)
+            .or_else(|| meta_class_name(&mount.dir))
+            .unwrap_or_else(|| "Folder".to_string());
+        let node = build_sourcemap_node(name, &class_name, &mount.inst_path, &mount.dir, include_non_scripts, &skip)?;
+        graft_sourcemap_node(&mut root, &mount.inst_path, node);
+    }
+    Ok(root)
+}
+
+/// Build a sourcemap node recursively
+///
+/// `inst_path` is the DataModel path of this node (empty for the root) and is used
+/// to detect special containers like StarterPlayerScripts and Workspace.Camera.
+/// Directories in `skip` are mounted elsewhere and left out.
+fn build_sourcemap_node(
+    name: &str,
+    class_name: &str,
+    inst_path: &str,
+    dir_path: &Path,
+    include_non_scripts: bool,
+    skip: &HashSet<PathBuf>,
+) -> io::Result<Value> {
+    let mut children = Vec::new();
+    let mut file_paths = Vec::new();
+
+    // Add the directory itself as a file path, and its init script so requires resolve to it
+    file_paths.push(dir_path.to_string_lossy().to_string());
+    if let Some((init_path, _)) = init_file(dir_path) {
+        file_paths.push(init_path.to_string_lossy().to_string());
+    }
+
+    if dir_path.exists() && dir_path.is_dir() {
+        let mut entries: Vec<_> = std::fs::read_dir(dir_path)?.filter_map(|e| e.ok()).collect();
+
+        // Sort for consistent output
+        entries.sort_by_key(|entry| entry.file_name());
+
+        // Script files that belong to a sibling directory (scripts with children)
+        let mut consumed_scripts: HashSet<PathBuf> = HashSet::new();
+
+        for entry in &entries {
+            let entry_path = entry.path();
+            let entry_name = entry.file_name().to_string_lossy().to_string();
+
+            if entry_path.is_dir() && !skip.contains(&entry_path) {
+                let child_inst_path = child_instance_path(inst_path, &entry_name);
+                let sibling_script = find_sibling_script(&entry_path);
+
+                let special_class = container_class_name(inst_path, &entry_name);
+
+                // Class priority: init file, sibling script, special container, _meta.rbxjson
+                let actual_class = init_class(&entry_path)
+                    .or(sibling_script.as_ref().map(|(_, class)| *class))
+                    .or(special_class)
+                    .map(String::from)
+                    .or_else(|| meta_class_name(&entry_path))
+                    .unwrap_or_else(|| "Folder".to_string());
+
+                let mut child_node = build_sourcemap_node(
+                    &entry_name,
+                    &actual_class,
+                    &child_inst_path,
+                    &entry_path,
+                    include_non_scripts,
+                    skip,
+                )?;
+                // Folders are kept when scripts under them need a path to be required through
+                let has_children = child_node["children"].as_array().is_some_and(|c| !c.is_empty());
+                if include_non_scripts || actual_class != "Folder" || has_children {
+                    if let Some((script_path, _)) = sibling_script {
+                        if let Some(paths) = child_node.get_mut("filePaths").and_then(|p| p.as_array_mut()) {
+                            paths.push(json!(script_path.to_string_lossy()));
+                        }
+                        consumed_scripts.insert(script_path);
+                    }
+                    children.push(child_node);
+                } else if let Some((script_path, _)) = sibling_script {
+                    consumed_scripts.insert(script_path);
+                }
+            }
+        }
+
+        for entry in &entries {
+            let entry_path = entry.path();
+            let entry_name = entry.file_name().to_string_lossy().to_string();
+
+            if entry_path.is_dir() || consumed_scripts.contains(&entry_path) {
+                continue;
+            }
+
+            if let Some(ext) = entry_path.extension() {
+                if ext == "luau" || ext == "lua" {
+                    // Script file
+                    let (script_name, script_class) = parse_script_name(&entry_name);
+
+                    // Skip init files (handled by directory)
+                    if script_name == "init" {
+                        continue;
+                    }
+
+                    children.push(json!({
+                        "name": script_name,
+                        "className": script_class,
+                        "filePaths": [entry_path.to_string_lossy()]
+                    }));
+                } else if ext == "rbxjson" && entry_name != "_meta.rbxjson" {
+                    // Instance JSON file
+                    let instance_name = entry_path
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_default();
+
+                    // Leaf special containers (e.g. Workspace.Camera) are always included
+                    let special_class = container_class_name(inst_path, &instance_name);
+                    if !include_non_scripts && special_class.is_none() {
+                        continue;
+                    }
+
+                    // Try to read class name from JSON
+                    let class_name = if let Ok(content) = std::fs::read_to_string(&entry_path) {
+                        serde_json::from_str::<Value>(&content)
+                            .ok()
+                            .and_then(|v| v.get("className").and_then(|c| c.as_str()).map(String::from))
+                            .unwrap_or_else(|| special_class.unwrap_or("Instance").to_string())
+                    } else {
+                        special_class.unwrap_or("Instance").to_string()
+                    };
+
+                    children.push(json!({
+                        "name": instance_name,
+                        "className": class_name,
+                        "filePaths": [entry_path.to_string_lossy()]
+                    }));
+                }
+            }
+        }
+    }
+
+    Ok(json!({
+        "name": name,
+        "className": class_name,
+        "filePaths": file_paths,
+        "children": children
+    }))
+}
+
+/// Regenerate a project's sourcemap and write it to `output`
+pub fn write_sourcemap(project_dir: &Path, output: &Path, include_non_scripts: bool) -> io::Result<Value> {
+    let root = generate_sourcemap(project_dir, include_non_scripts)?;
+    std::fs::write(output, serde_json::to_string_pretty(&root)?)?;
+    Ok(root)
+}
+
+/// Whether a file change can change the sourcemap. Instances appear,
+/// disappear and move with creates, deletes and renames; an edited
+/// `.rbxjson` may change its `className`. Script edits don't matter, nor
+/// does writing the sourcemap itself.
+pub fn affects_sourcemap(kind: &str, path: &str) -> bool {
+    if Path::new(path).file_name().is_some_and(|name| name == SOURCEMAP_FILE) {
+        return false;
+    }
+    kind != "modify" || path.ends_with(".rbxjson")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn child<'a>(node: &'a Value, name: &str) -> &'a Value {
+        node["children"].as_array().unwrap().iter().find(|c| c["name"] == name).unwrap()
+    }
+
+    #[test]
+    fn test_sourcemap_mounts() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("rbxsync.json"), r#"{ "name": "Obby", "treeMapping": { "ReplicatedStorage": "shared" } }"#).unwrap();
+        fs::create_dir_all(root.join("src/shared/Util")).unwrap();
+        fs::write(root.join("src/shared/Util/Math.luau"), "return {}").unwrap();
+        fs::create_dir_all(root.join("src/ServerScriptService")).unwrap();
+        fs::write(root.join("src/ServerScriptService/Main.server.luau"), "").unwrap();
+        fs::create_dir_all(root.join("Packages/_Index/sleitnick_signal@1.5.0/signal")).unwrap();
+        fs::write(root.join("Packages/_Index/sleitnick_signal@1.5.0/signal/init.lua"), "return {}").unwrap();
+        fs::write(root.join("Packages/Signal.lua"), "").unwrap();
+
+        let sourcemap = generate_sourcemap(root, false).unwrap();
+        assert!(sourcemap["children"].as_array().unwrap().iter().all(|c| c["name"] != "shared"));
+        let replicated = child(&sourcemap, "ReplicatedStorage");
+        assert_eq!(replicated["className"], "ReplicatedStorage");
+        // Folders holding scripts are kept
+        assert_eq!(child(child(replicated, "Util"), "Math")["className"], "ModuleScript");
+
+        let packages = child(replicated, "Packages");
+        assert_eq!(child(packages, "Signal")["className"], "ModuleScript");
+        let signal = child(child(child(packages, "_Index"), "sleitnick_signal@1.5.0"), "signal");
+        assert_eq!(signal["className"], "ModuleScript");
+        assert_eq!(child(&sourcemap, "ServerScriptService")["children"][0]["className"], "Script");
+
+        assert!(affects_sourcemap("create", "src/Workspace/Part.rbxjson"));
+        assert!(affects_sourcemap("modify", "src/Workspace/Part.rbxjson"));
+        assert!(!affects_sourcemap("modify", "src/ReplicatedStorage/Shop.luau"));
+        assert!(!affects_sourcemap("create", "/game/sourcemap.json"));
+    }
+
+    #[test]
+    fn test_sourcemap_nested_mounts() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("rbxsync.json"),
+            r#"{ "name": "Obby", "treeMapping": { "ReplicatedStorage/Shared/Vendor": "vendor", "ReplicatedStorage": "shared" } }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("src/shared")).unwrap();
+        fs::write(root.join("src/shared/Config.luau"), "return {}").unwrap();
+        fs::create_dir_all(root.join("src/vendor")).unwrap();
+        fs::write(root.join("src/vendor/Promise.luau"), "return {}").unwrap();
+
+        // The deeper mount lands inside the node its parent mount created,
+        // with a Folder for the level neither of them has
+        let sourcemap = generate_sourcemap(root, false).unwrap();
+        let replicated = child(&sourcemap, "ReplicatedStorage");
+        assert_eq!(child(replicated, "Config")["className"], "ModuleScript");
+        let shared = child(replicated, "Shared");
+        assert_eq!(shared["className"], "Folder");
+        assert_eq!(child(child(shared, "Vendor"), "Promise")["className"], "ModuleScript");
+        assert!(sourcemap["children"].as_array().unwrap().iter().all(|c| c["name"] != "vendor"));
+    }
+
+    #[test]
+    fn test_sourcemap_overlapping_mounts() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("rbxsync.json"),
+            r#"{ "name": "Obby", "treeMapping": { "ReplicatedStorage": "shared", "ServerStorage/Lib": "shared/lib" } }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("src/shared/lib")).unwrap();
+        fs::write(root.join("src/shared/Config.luau"), "return {}").unwrap();
+        fs::write(root.join("src/shared/lib/Secret.luau"), "return {}").unwrap();
+        // src/ also has its own ReplicatedStorage; the mount takes its place
+        fs::create_dir_all(root.join("src/ReplicatedStorage")).unwrap();
+        fs::write(root.join("src/ReplicatedStorage/Stale.luau"), "return {}").unwrap();
+
+        let sourcemap = generate_sourcemap(root, false).unwrap();
+        let replicated_nodes: Vec<_> =
+            sourcemap["children"].as_array().unwrap().iter().filter(|c| c["name"] == "ReplicatedStorage").collect();
+        assert_eq!(replicated_nodes.len(), 1);
+        let replicated = replicated_nodes[0];
+        assert!(replicated["children"].as_array().unwrap().iter().all(|c| c["name"] != "Stale"));
+
+        // A directory mounted inside another mount appears only where it's mapped
+        assert!(replicated["children"].as_array().unwrap().iter().all(|c| c["name"] != "lib"));
+        let server_storage = child(&sourcemap, "ServerStorage");
+        assert_eq!(server_storage["className"], "ServerStorage");
+        assert_eq!(child(child(server_storage, "Lib"), "Secret")["className"], "ModuleScript");
+    }
+}
@@ -0,0 +1,609 @@
+//! Compact project summaries
+//!
+//! Agents and new contributors otherwise read hundreds of files to learn a
+//! project's shape. A summary lists the service tree with instance counts,
+//! each ModuleScript's exported functions with their signatures, the remotes,
+//! and config-like constants, in one short document.
+//!
+//! Exports are found by a lightweight line scanner, not a full Luau parser.
+//! It relies on conventional formatting: module-level statements start at
+//! column 0 and the module ends with `return Name` or `return { ... }`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::migrate::{strip_comments, strip_strings_and_comments};
+
+/// Longest constant value kept, in characters
+const MAX_VALUE_LEN: usize = 60;
+
+/// Lines a function's parameter list may span
+const MAX_SIGNATURE_LINES: usize = 20;
+
+/// Children listed per service in the rendered summary
+const MAX_CHILDREN: usize = 15;
+
+/// Classes listed as remotes
+pub const REMOTE_CLASSES: &[&str] = &["RemoteEvent", "RemoteFunction", "UnreliableRemoteEvent"];
+
+/// An exported function
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionSignature {
+    pub name: String,
+    /// Parameter list as written, without the parentheses
+    pub params: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub returns: Option<String>,
+    /// Declared with `:` (takes self)
+    #[serde(default)]
+    pub method: bool,
+}
+
+/// A constant with a literal value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstantEntry {
+    pub name: String,
+    pub value: String,
+}
+
+/// What a ModuleScript exposes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleApi {
+    /// Name of the returned table, when the module returns a local
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
+    #[serde(default)]
+    pub functions: Vec<FunctionSignature>,
+    /// Upper-case locals and literal fields of the returned table
+    #[serde(default)]
+    pub constants: Vec<ConstantEntry>,
+}
+
+fn is_ident(s: &str) -> bool {
+    !s.is_empty()
+        && !s.starts_with(|c: char| c.is_ascii_digit())
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_constant_name(s: &str) -> bool {
+    is_ident(s) && s.chars().any(|c| c.is_ascii_uppercase()) && !s.chars().any(|c| c.is_ascii_lowercase())
+}
+
+/// Leading identifier of `s` and the rest
+fn split_ident(s: &str) -> (&str, &str) {
+    let end = s.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+/// Whether a value is a literal worth listing as a constant
+fn is_literal(value: &str) -> bool {
+    let value = value.trim_end_matches(',').trim();
+    if value.is_empty() || value.starts_with("function") || value.contains("require(") {
+        return false;
+    }
+    let first = value.chars().next().unwrap_or(' ');
+    matches!(value, "true" | "false")
+        || first.is_ascii_digit()
+        || (first == '-' && value[1..].starts_with(|c: char| c.is_ascii_digit()))
+        || matches!(first, '"' | '\'' | '`')
+        || value.starts_with("Enum.")
+        || (first == '{' && value.ends_with('}'))
+        || ["Vector3.", "Vector2.", "Color3.", "UDim2.", "UDim.", "CFrame.", "NumberRange.", "BrickColor."]
+            .iter()
+            .any(|prefix| value.starts_with(prefix) && value.ends_with(')'))
+}
+
+fn shorten(value: &str) -> String {
+    let value = value.trim().trim_end_matches(',').trim();
+    if value.chars().count() > MAX_VALUE_LEN {
+        format!("{}...", value.chars().take(MAX_VALUE_LEN).collect::<String>())
+    } else {
+        value.to_string()
+    }
+}
+
+/// Source split into lines twice: without comments, and with strings and
+/// comments blanked out. Both keep the same character columns.
+struct Lines {
+    source: Vec<Vec<char>>,
+    code: Vec<Vec<char>>,
+}
+
+impl Lines {
+    fn new(source: &str) -> Self {
+        let split = |text: String| text.lines().map(|l| l.chars().collect()).collect();
+        Self {
+            source: split(strip_comments(source)),
+            code: split(strip_strings_and_comments(source)),
+        }
+    }
+
+    fn code(&self, line: usize) -> String {
+        self.code.get(line).map(|l| l.iter().collect()).unwrap_or_default()
+    }
+
+    /// Text of `line` from `start`, without a trailing comment
+    fn source_from(&self, line: usize, start: usize) -> String {
+        let source = &self.source[line];
+        source[start.min(source.len())..].iter().collect::<String>().trim().to_string()
+    }
+
+    /// Parameters and return type of a function whose `(` is at `line`, `col`
+    fn signature(&self, line: usize, col: usize) -> Option<(String, Option<String>)> {
+        let mut depth = 0;
+        let mut params = String::new();
+        for l in line..(line + MAX_SIGNATURE_LINES).min(self.code.len()) {
+            let start = if l == line { col } else { 0 };
+            for c in start..self.code[l].len() {
+                match self.code[l][c] {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                if depth == 0 {
+                    let rest = self.code(l).chars().skip(c + 1).collect::<String>();
+                    let returns = rest.trim_start().strip_prefix(':').map(|_| {
+                        let colon = c + 1 + rest.find(':').unwrap_or(0);
+                        self.source_from(l, colon + 1).trim().to_string()
+                    });
+                    return Some((params.split_whitespace().collect::<Vec<_>>().join(" "), returns));
+                }
+                if !(l == line && c == col) {
+                    params.push(self.source[l].get(c).copied().unwrap_or(' '));
+                }
+            }
+            params.push(' ');
+        }
+        None
+    }
+}
+
+/// Find the exported functions and constants of a ModuleScript
+pub fn scan_module(source: &str) -> ModuleApi {
+    let lines = Lines::new(source);
+    let count = lines.code.len();
+
+    // `return Name` or `return {` at module level, last one wins
+    let returned = (0..count).rev().find_map(|i| {
+        let code = lines.code(i);
+        let rest = code.strip_prefix("return")?;
+        (rest.is_empty() || rest.starts_with(char::is_whitespace)).then(|| (i, rest.trim().to_string()))
+    });
+    let table = returned.as_ref().map(|(_, r)| r.clone()).filter(|r| is_ident(r));
+
+    let mut api = ModuleApi { table: table.clone(), ..Default::default() };
+    let mut local_functions: BTreeMap<String, (String, Option<String>)> = BTreeMap::new();
+    let mut table_literal: Option<usize> = returned.as_ref().filter(|(_, r)| r.starts_with('{')).map(|(i, _)| *i);
+
+    for i in 0..count {
+        let code = lines.code(i);
+        if code.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let code = code.trim_end();
+
+        if let Some(rest) = code.strip_prefix("local function ") {
+            let (name, after) = split_ident(rest.trim_start());
+            if after.trim_start().starts_with('(') {
+                let col = code.len() - after.trim_start().len();
+                if let Some(signature) = lines.signature(i, col) {
+                    local_functions.insert(name.to_string(), signature);
+                }
+            }
+            continue;
+        }
+
+        if let Some(rest) = code.strip_prefix("local ") {
+            let (name, after) = split_ident(rest);
+            if let Some(value) = after.trim_start().strip_prefix('=') {
+                if Some(name) == table.as_deref() && value.trim_start().starts_with('{') {
+                    table_literal = Some(i);
+                } else if is_constant_name(name) {
+                    // Strings are blanked in the code text, so check the source
+                    let value = lines.source_from(i, code.len() - value.trim_start().len());
+                    if is_literal(&value) {
+                        api.constants.push(ConstantEntry { name: name.to_string(), value: shorten(&value) });
+                    }
+                }
+            }
+            continue;
+        }
+
+        let Some(table) = table.as_deref() else {
+            continue;
+        };
+
+        // function M.name(...) / function M:name(...)
+        if let Some(rest) = code.strip_prefix("function ").and_then(|r| r.strip_prefix(table)) {
+            let method = rest.starts_with(':');
+            if let Some(rest) = rest.strip_prefix(['.', ':']) {
+                let (name, after) = split_ident(rest);
+                if after.starts_with('(') {
+                    let col = code.len() - after.len();
+                    if let Some((params, returns)) = lines.signature(i, col) {
+                        api.functions.push(FunctionSignature { name: name.to_string(), params, returns, method });
+                    }
+                }
+            }
+            continue;
+        }
+
+        // M.name = function(...) / M.NAME = literal
+        if let Some(rest) = code.strip_prefix(table).and_then(|r| r.strip_prefix('.')) {
+            let (name, after) = split_ident(rest);
+            if let Some(value) = after.trim_start().strip_prefix('=') {
+                field(&lines, &mut api, &local_functions, i, code.len() - value.trim_start().len(), name, value.trim());
+            }
+        }
+    }
+
+    // Fields of `local M = {` or `return {`, at the indentation of the first field
+    if let Some(start) = table_literal {
+        let mut depth = 0i32;
+        let mut indent: Option<usize> = None;
+        for i in start..count {
+            let code = lines.code(i);
+            if i > start && depth == 1 {
+                let trimmed = code.trim_start();
+                let this_indent = code.len() - trimmed.len();
+                if !trimmed.is_empty() && *indent.get_or_insert(this_indent) == this_indent {
+                    let (name, after) = split_ident(trimmed);
+                    if let Some(value) = after.trim_start().strip_prefix('=') {
+                        let col = code.len() - value.trim_start().len();
+                        field(&lines, &mut api, &local_functions, i, col, name, value.trim());
+                    }
+                }
+            }
+            for c in code.chars() {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+            }
+            if i > start && depth <= 0 {
+                break;
+            }
+        }
+    }
+
+    api
+}
+
+/// Record `name = value` on the exported table as a function or constant
+fn field(
+    lines: &Lines,
+    api: &mut ModuleApi,
+    local_functions: &BTreeMap<String, (String, Option<String>)>,
+    line: usize,
+    value_col: usize,
+    name: &str,
+    value: &str,
+) {
+    if name.is_empty() {
+        return;
+    }
+    let value = value.trim_end_matches(',').trim();
+    if let Some(rest) = value.strip_prefix("function") {
+        if rest.trim_start().starts_with('(') {
+            let col = value_col + value.find('(').unwrap_or(0);
+            if let Some((params, returns)) = lines.signature(line, col) {
+                api.functions.push(FunctionSignature { name: name.to_string(), params, returns, method: false });
+            }
+        }
+    } else if let Some((params, returns)) = local_functions.get(value) {
+        api.functions.push(FunctionSignature {
+            name: name.to_string(),
+            params: params.clone(),
+            returns: returns.clone(),
+            method: false,
+        });
+    } else {
+        let literal = lines.source_from(line, value_col);
+        if is_literal(&literal) {
+            api.constants.push(ConstantEntry { name: name.to_string(), value: shorten(&literal) });
+        }
+    }
+}
+
+/// One instance found in the project's files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryEntry {
+    /// DataModel path with `/` separators
+    pub instance_path: String,
+    pub class_name: String,
+    /// File relative to the project
+    pub file: String,
+    /// Exports, for ModuleScripts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api: Option<ModuleApi>,
+}
+
+/// A child of a service with its subtree counts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeNode {
+    pub name: String,
+    pub class_name: String,
+    /// Instances in the subtree, including this one
+    pub instances: usize,
+    pub scripts: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TreeNode>,
+}
+
+/// A ModuleScript and its exports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleSummary {
+    pub path: String,
+    pub file: String,
+    #[serde(flatten)]
+    pub api: ModuleApi,
+}
+
+/// A RemoteEvent, RemoteFunction or UnreliableRemoteEvent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteSummary {
+    pub path: String,
+    pub class_name: String,
+}
+
+/// The whole project at a glance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSummary {
+    pub name: String,
+    pub instances: usize,
+    pub scripts: usize,
+    /// Services, each with its direct children
+    pub services: Vec<TreeNode>,
+    pub modules: Vec<ModuleSummary>,
+    pub remotes: Vec<RemoteSummary>,
+}
+
+fn is_script(class_name: &str) -> bool {
+    matches!(class_name, "Script" | "LocalScript" | "ModuleScript")
+}
+
+/// Build a summary from the instances found in the project's files.
+///
+/// Folders without a file of their own count as `Folder` instances.
+pub fn build_summary(name: &str, entries: &[SummaryEntry]) -> ProjectSummary {
+    let mut classes: BTreeMap<&str, &str> = BTreeMap::new();
+    for entry in entries {
+        classes.insert(entry.instance_path.as_str(), entry.class_name.as_str());
+    }
+    let mut instances: BTreeSet<String> = BTreeSet::new();
+    for entry in entries {
+        let mut path = entry.instance_path.as_str();
+        loop {
+            instances.insert(path.to_string());
+            match path.rsplit_once('/') {
+                Some((parent, _)) => path = parent,
+                None => break,
+            }
+        }
+    }
+
+    // Counts per service and per direct child
+    let mut services: BTreeMap<String, TreeNode> = BTreeMap::new();
+    for path in &instances {
+        let class_name = classes.get(path.as_str()).copied().unwrap_or("Folder");
+        let script = usize::from(is_script(class_name));
+        let mut segments = path.splitn(3, '/');
+        let service_name = segments.next().unwrap_or_default();
+        let service = services.entry(service_name.to_string()).or_insert_with(|| TreeNode {
+            name: service_name.to_string(),
+            class_name: service_name.to_string(),
+            instances: 0,
+            scripts: 0,
+            children: Vec::new(),
+        });
+        if let Some(child_name) = segments.next() {
+            service.instances += 1;
+            service.scripts += script;
+            let child_path = format!("{}/{}", service_name, child_name);
+            let idx = match service.children.iter().position(|c| c.name == child_name) {
+                Some(idx) => idx,
+                None => {
+                    service.children.push(TreeNode {
+                        name: child_name.to_string(),
+                        class_name: classes.get(child_path.as_str()).copied().unwrap_or("Folder").to_string(),
+                        instances: 0,
+                        scripts: 0,
+                        children: Vec::new(),
+                    });
+                    service.children.len() - 1
+                }
+            };
+            service.children[idx].instances += 1;
+            service.children[idx].scripts += script;
+        }
+    }
+    let mut services: Vec<TreeNode> = services.into_values().collect();
+    for service in &mut services {
+        service.children.sort_by(|a, b| b.instances.cmp(&a.instances).then_with(|| a.name.cmp(&b.name)));
+    }
+
+    let mut modules: Vec<ModuleSummary> = entries
+        .iter()
+        .filter_map(|e| {
+            Some(ModuleSummary {
+                path: e.instance_path.clone(),
+                file: e.file.clone(),
+                api: e.api.clone().filter(|_| e.class_name == "ModuleScript")?,
+            })
+        })
+        .collect();
+    modules.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut remotes: Vec<RemoteSummary> = entries
+        .iter()
+        .filter(|e| REMOTE_CLASSES.contains(&e.class_name.as_str()))
+        .map(|e| RemoteSummary { path: e.instance_path.clone(), class_name: e.class_name.clone() })
+        .collect();
+    remotes.sort_by(|a, b| a.path.cmp(&b.path));
+
+    ProjectSummary {
+        name: name.to_string(),
+        instances: services.iter().map(|s| s.instances).sum(),
+        scripts: services.iter().map(|s| s.scripts).sum(),
+        services,
+        modules,
+        remotes,
+    }
+}
+
+impl FunctionSignature {
+    /// `Shop.buy(player: Player): boolean`, qualified with the module's table name
+    pub fn display(&self, table: Option<&str>) -> String {
+        let qualified = match table {
+            Some(table) => format!("{}{}{}", table, if self.method { ":" } else { "." }, self.name),
+            None => self.name.clone(),
+        };
+        match &self.returns {
+            Some(returns) => format!("{}({}): {}", qualified, self.params, returns),
+            None => format!("{}({})", qualified, self.params),
+        }
+    }
+}
+
+/// Render a summary as compact Markdown
+pub fn render_markdown(summary: &ProjectSummary) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", summary.name);
+    let _ = writeln!(
+        out,
+        "{} instances, {} scripts, {} modules, {} remotes\n",
+        summary.instances,
+        summary.scripts,
+        summary.modules.len(),
+        summary.remotes.len()
+    );
+
+    out.push_str("## Services\n\n");
+    for service in &summary.services {
+        let _ = writeln!(out, "- {} ({} instances, {} scripts)", service.name, service.instances, service.scripts);
+        for child in service.children.iter().take(MAX_CHILDREN) {
+            let _ = writeln!(out, "  - {} [{}] ({} instances, {} scripts)", child.name, child.class_name, child.instances, child.scripts);
+        }
+        if service.children.len() > MAX_CHILDREN {
+            let _ = writeln!(out, "  - ... {} more", service.children.len() - MAX_CHILDREN);
+        }
+    }
+
+    let exporting: Vec<&ModuleSummary> = summary.modules.iter().filter(|m| !m.api.functions.is_empty()).collect();
+    if !exporting.is_empty() {
+        out.push_str("\n## Modules\n");
+        for module in exporting {
+            let _ = writeln!(out, "\n### {} ({})", module.path, module.file);
+            for function in &module.api.functions {
+                let _ = writeln!(out, "- {}", function.display(module.api.table.as_deref()));
+            }
+        }
+    }
+
+    if !summary.remotes.is_empty() {
+        out.push_str("\n## Remotes\n\n");
+        for remote in &summary.remotes {
+            let _ = writeln!(out, "- {} [{}]", remote.path, remote.class_name);
+        }
+    }
+
+    let constants: Vec<&ModuleSummary> = summary.modules.iter().filter(|m| !m.api.constants.is_empty()).collect();
+    if !constants.is_empty() {
+        out.push_str("\n## Constants\n");
+        for module in constants {
+            let _ = writeln!(out, "\n### {}", module.path);
+            for constant in &module.api.constants {
+                let _ = writeln!(out, "- {} = {}", constant.name, constant.value);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_module_and_summary() {
+        let source = r#"--!strict
+local Players = game:GetService("Players")
+
+local MAX_ITEMS = 20 -- per player
+local DEFAULT_NAME = "Guest"
+
+local Shop = {}
+Shop.CURRENCY = "Coins"
+
+local function price(item: string): number
+	return 10
+end
+
+-- function Shop.commented(x)
+function Shop.buy(player: Player, item: string): boolean
+	local inner = function(a) end
+	return true
+end
+
+function Shop:refresh(
+	force: boolean?,
+	reason: string
+)
+end
+
+Shop.price = price
+Shop.onBought = function(callback: (Player) -> ()) end
+
+return Shop
+"#;
+        let api = scan_module(source);
+        assert_eq!(api.table.as_deref(), Some("Shop"));
+        let names: Vec<&str> = api.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["buy", "refresh", "price", "onBought"]);
+        assert_eq!(api.functions[0].display(Some("Shop")), "Shop.buy(player: Player, item: string): boolean");
+        assert_eq!(api.functions[1].display(Some("Shop")), "Shop:refresh(force: boolean?, reason: string)");
+        assert_eq!(api.functions[2].returns.as_deref(), Some("number"));
+        assert_eq!(api.functions[3].params, "callback: (Player) -> ()");
+        let constants: Vec<(&str, &str)> = api.constants.iter().map(|c| (c.name.as_str(), c.value.as_str())).collect();
+        assert_eq!(constants, [("MAX_ITEMS", "20"), ("DEFAULT_NAME", "\"Guest\""), ("CURRENCY", "\"Coins\"")]);
+
+        let config = scan_module("return {\n\tRoundTime = 120,\n\tLobby = {\n\t\tSize = 4,\n\t},\n\tstart = function(map) end,\n}\n");
+        assert_eq!(config.constants, vec![ConstantEntry { name: "RoundTime".to_string(), value: "120".to_string() }]);
+        assert_eq!(config.functions[0].name, "start");
+
+        let entry = |path: &str, class: &str, api: Option<ModuleApi>| SummaryEntry {
+            instance_path: path.to_string(),
+            class_name: class.to_string(),
+            file: format!("src/{}.luau", path),
+            api,
+        };
+        let summary = build_summary(
+            "Obby",
+            &[
+                entry("ReplicatedStorage/Shared/Shop", "ModuleScript", Some(api)),
+                entry("ReplicatedStorage/Remotes/BuyItem", "RemoteEvent", None),
+                entry("ServerScriptService/Main", "Script", None),
+            ],
+        );
+        assert_eq!(summary.instances, 5);
+        assert_eq!(summary.scripts, 2);
+        assert_eq!(summary.services[0].children[0].name, "Remotes");
+        assert_eq!(summary.remotes.len(), 1);
+
+        let markdown = render_markdown(&summary);
+        assert!(markdown.contains("- ReplicatedStorage (4 instances, 1 scripts)"));
+        assert!(markdown.contains("- Shop.buy(player: Player, item: string): boolean"));
+        assert!(markdown.contains("- ReplicatedStorage/Remotes/BuyItem [RemoteEvent]"));
+        assert!(markdown.contains("- MAX_ITEMS = 20"));
+    }
+}
@@ -0,0 +1,183 @@
+//! Selective sync
+//!
+//! `sync.include` and `sync.exclude` in rbxsync.json limit syncing to part of
+//! the game. Patterns are matched against instance paths as they appear under
+//! `src/` (e.g. `ServerScriptService/Main`), segment by segment:
+//!
+//! - `*` matches any run of characters within one segment, `?` one character
+//! - `**` matches any number of segments, including none, so
+//!   `Workspace/GeneratedMap/**` covers the folder itself and everything in it
+//!
+//! With no include patterns everything is included. Exclude patterns win over
+//! include patterns.
+//!
+//! `protectedPaths` uses the same patterns for instances syncs must never
+//! delete, e.g. a map built in Studio that has no files.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::path_utils::is_under;
+
+/// Include/exclude globs for instance paths
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncFilter {
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl SyncFilter {
+    /// The filter in an rbxsync.json value, from its `sync` section
+    pub fn from_config(config: &Value) -> Self {
+        config
+            .get("sync")
+            .and_then(|sync| serde_json::from_value(sync.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Whether the filter lets everything through
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether the instance at `path` is synced
+    pub fn matches(&self, path: &str) -> bool {
+        let path = path.replace('\\', "/");
+        let included = self.include.is_empty() || self.include.iter().any(|p| glob_match(p, &path));
+        included && !self.exclude.iter().any(|p| glob_match(p, &path))
+    }
+}
+
+/// Instances no sync may delete (`protectedPaths` in rbxsync.json)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtectedPaths {
+    pub patterns: Vec<String>,
+}
+
+impl ProtectedPaths {
+    /// The patterns in an rbxsync.json value
+    pub fn from_config(config: &Value) -> Self {
+        let patterns = config
+            .get("protectedPaths")
+            .and_then(Value::as_array)
+            .map(|patterns| patterns.iter().filter_map(Value::as_str).map(str::to_string).collect())
+            .unwrap_or_default();
+        Self { patterns }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// The pattern that stops the instance at `path` from being deleted:
+    /// one matching it, or one whose fixed leading segments are under it,
+    /// since deleting an ancestor deletes what the pattern protects
+    pub fn protecting(&self, path: &str) -> Option<&str> {
+        let path = path.replace('\\', "/");
+        let path = path.trim_matches('/');
+        self.patterns
+            .iter()
+            .find(|pattern| {
+                let prefix = literal_prefix(pattern);
+                glob_match(pattern, path) || (!path.is_empty() && is_under(&prefix, path))
+            })
+            .map(String::as_str)
+    }
+
+    /// Whether the instance at `path` may not be deleted
+    pub fn protects(&self, path: &str) -> bool {
+        self.protecting(path).is_some()
+    }
+}
+
+/// Leading segments of a pattern before its first wildcard
+fn literal_prefix(pattern: &str) -> String {
+    let segments: Vec<&str> = pattern
+        .trim_matches('/')
+        .split('/')
+        .take_while(|segment| !segment.contains(['*', '?']))
+        .collect();
+    segments.join("/")
+}
+
+/// Match a `/`-separated path against a pattern with `*`, `?` and `**`
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.trim_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_matches('/').split('/').collect();
+    segments_match(&pattern, &path)
+}
+
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((segment, rest)) => path.split_first().is_some_and(|(first, tail)| {
+            let segment: Vec<char> = segment.chars().collect();
+            let first: Vec<char> = first.chars().collect();
+            wildcard_match(&segment, &first) && segments_match(rest, tail)
+        }),
+    }
+}
+
+fn wildcard_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some(('*', rest)) => (0..=text.len()).any(|skip| wildcard_match(rest, &text[skip..])),
+        Some(('?', rest)) => !text.is_empty() && wildcard_match(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && wildcard_match(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("ServerScriptService/**", "ServerScriptService"));
+        assert!(glob_match("ServerScriptService/**", "ServerScriptService/Modules/Util"));
+        assert!(!glob_match("ServerScriptService/**", "ServerStorage/Util"));
+        assert!(glob_match("Workspace/*/Spawn", "Workspace/Map/Spawn"));
+        assert!(!glob_match("Workspace/*/Spawn", "Workspace/Map/Lobby/Spawn"));
+        assert!(glob_match("**/Tests", "ReplicatedStorage/Shared/Tests"));
+        assert!(glob_match("Workspace/Part?", "Workspace/Part1"));
+        assert!(!glob_match("Workspace/Part?", "Workspace/Part12"));
+    }
+
+    #[test]
+    fn test_filter_from_config() {
+        let config = json!({ "sync": {
+            "include": ["ServerScriptService/**", "Workspace/**"],
+            "exclude": ["Workspace/GeneratedMap/**"]
+        }});
+        let filter = SyncFilter::from_config(&config);
+        assert!(filter.matches("ServerScriptService/Main"));
+        assert!(filter.matches("Workspace/Baseplate"));
+        assert!(!filter.matches("Workspace/GeneratedMap"));
+        assert!(!filter.matches("Workspace/GeneratedMap/Tile_1"));
+        assert!(!filter.matches("ReplicatedStorage/Shared"));
+
+        let everything = SyncFilter::from_config(&json!({ "sync": { "autoSync": true } }));
+        assert!(everything.is_empty());
+        assert!(everything.matches("ReplicatedStorage/Shared"));
+    }
+
+    #[test]
+    fn test_protected_paths() {
+        let protected = ProtectedPaths::from_config(&json!({ "protectedPaths": ["Workspace/Map/**", "Lighting/**"] }));
+        assert_eq!(protected.protecting("Workspace/Map/Tower"), Some("Workspace/Map/**"));
+        assert!(protected.protects("Lighting"));
+        assert!(protected.protects("Lighting/Sky"));
+        // Deleting an ancestor would delete the map too
+        assert!(protected.protects("Workspace"));
+        assert!(!protected.protects("Workspace/Baseplate"));
+        assert!(!protected.protects("Workspace/MapOld"));
+        // Deleting the folder deletes its children
+        let children = ProtectedPaths { patterns: vec!["ReplicatedStorage/Assets/*".to_string()] };
+        assert!(children.protects("ReplicatedStorage/Assets"));
+        assert!(ProtectedPaths::from_config(&json!({})).is_empty());
+    }
+}
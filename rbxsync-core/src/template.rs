@@ -0,0 +1,295 @@
+//! Project templates
+//!
+//! `rbxsync template create` copies a project into `~/.rbxsync/templates/<name>`
+//! without its project-specific data: build output, `.rbxsync/` state (harness
+//! logs, caches, journals), installed packages, secrets, and place/universe
+//! IDs in JSON configs. The project name is replaced with `{{name}}` in file
+//! contents and file names. `rbxsync init --template` fills it back in.
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Placeholder for the project name in template files
+pub const NAME_PLACEHOLDER: &str = "{{name}}";
+
+/// Manifest written at the template root
+pub const TEMPLATE_MANIFEST: &str = "template.json";
+
+/// Directories never copied into a template
+const SKIPPED_DIRS: &[&str] = &[
+    ".git",
+    ".rbxsync-trash",
+    "build",
+    "builds",
+    "node_modules",
+    "target",
+    "Packages",
+    "ServerPackages",
+    "DevPackages",
+];
+
+/// `.rbxsync/` files worth keeping (everything else there is local state)
+const KEPT_RBXSYNC_FILES: &[&str] = &["mocks.json"];
+
+/// Extensions of files that are never copied
+const SKIPPED_EXTENSIONS: &[&str] = &["rbxl", "rbxlx", "log"];
+
+/// Generated files that `init` recreates
+const SKIPPED_FILES: &[&str] = &["sourcemap.json", "wally.lock", TEMPLATE_MANIFEST];
+
+/// Extensions treated as text for name substitution
+const TEXT_EXTENSIONS: &[&str] = &[
+    "luau", "lua", "json", "rbxjson", "toml", "yaml", "yml", "md", "txt",
+];
+
+/// Template metadata (`template.json`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateManifest {
+    pub name: String,
+    /// Project name that was replaced with the placeholder
+    pub source_name: String,
+    pub files: usize,
+}
+
+/// What `create_template` left out
+#[derive(Debug, Default)]
+pub struct TemplateReport {
+    pub files: usize,
+    pub skipped: Vec<PathBuf>,
+    /// JSON keys removed from configs, as `file: key.path`
+    pub scrubbed: Vec<String>,
+}
+
+/// `~/.rbxsync/templates`
+pub fn templates_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".rbxsync").join("templates"))
+}
+
+/// Whether a config key holds a place ID or a secret
+fn is_scrubbed_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    matches!(
+        key.as_str(),
+        "placeid" | "placeids" | "serveplaceids" | "universeid" | "gameid" | "creatorid" | "license"
+    ) || ["key", "token", "secret", "password", "cookie"]
+        .iter()
+        .any(|suffix| key.ends_with(suffix))
+}
+
+/// Remove place IDs and secrets from a JSON value, recording their paths
+fn scrub_json(value: &mut serde_json::Value, path: &str, removed: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let keys: Vec<String> = map.keys().cloned().collect();
+            for key in keys {
+                let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                if is_scrubbed_key(&key) {
+                    map.remove(&key);
+                    removed.push(child_path);
+                } else if let Some(child) = map.get_mut(&key) {
+                    scrub_json(child, &child_path, removed);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                scrub_json(item, &format!("{}[{}]", path, i), removed);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_text(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| TEXT_EXTENSIONS.contains(&e))
+}
+
+fn should_skip(relative: &Path, is_dir: bool) -> bool {
+    let mut components = relative.components().map(|c| c.as_os_str().to_string_lossy().to_string());
+    let Some(first) = components.next() else {
+        return false;
+    };
+    let name = relative
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    if first == ".rbxsync" {
+        // Walk into .rbxsync/ itself, but only keep the listed files
+        let depth = relative.components().count();
+        let keep = if is_dir {
+            depth == 1
+        } else {
+            depth == 2 && KEPT_RBXSYNC_FILES.contains(&name.as_str())
+        };
+        return !keep;
+    }
+    if is_dir {
+        return SKIPPED_DIRS.contains(&name.as_str());
+    }
+    name.starts_with(".env")
+        || SKIPPED_FILES.contains(&name.as_str())
+        || relative
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| SKIPPED_EXTENSIONS.contains(&e))
+}
+
+/// Relative paths of all files under `root`, skipping what `should_skip` rejects
+fn collect_files(root: &Path, dir: &Path, skipped: &mut Vec<PathBuf>, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries: Vec<_> = std::fs::read_dir(dir)?.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+        let is_dir = path.is_dir();
+        if should_skip(&relative, is_dir) {
+            skipped.push(relative);
+        } else if is_dir {
+            collect_files(root, &path, skipped, files)?;
+        } else {
+            files.push(relative);
+        }
+    }
+    Ok(())
+}
+
+/// Replace `from` with `to` in a file name or content
+fn substitute(text: &str, from: &str, to: &str) -> String {
+    if from.is_empty() {
+        text.to_string()
+    } else {
+        text.replace(from, to)
+    }
+}
+
+/// Turn the project at `from` into a template at `dest`.
+///
+/// `project_name` (usually `name` from rbxsync.json) becomes `{{name}}`.
+pub fn create_template(from: &Path, dest: &Path, template_name: &str, project_name: &str) -> io::Result<TemplateReport> {
+    let mut report = TemplateReport::default();
+    let mut files = Vec::new();
+    collect_files(from, from, &mut report.skipped, &mut files)?;
+
+    let mut created_dirs = HashSet::new();
+    for relative in &files {
+        let target = dest.join(substitute(&relative.to_string_lossy(), project_name, NAME_PLACEHOLDER));
+        if let Some(parent) = target.parent() {
+            if created_dirs.insert(parent.to_path_buf()) {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+
+        let source = from.join(relative);
+        if !is_text(relative) {
+            std::fs::copy(&source, &target)?;
+            continue;
+        }
+
+        let mut content = std::fs::read_to_string(&source)?;
+        let is_config = relative.extension().is_some_and(|e| e == "json") && relative.components().count() == 1;
+        if is_config {
+            if let Ok(mut json) = serde_json::from_str::<serde_json::Value>(&content) {
+                let mut removed = Vec::new();
+                scrub_json(&mut json, "", &mut removed);
+                if !removed.is_empty() {
+                    content = serde_json::to_string_pretty(&json).map_err(io::Error::other)?;
+                    report
+                        .scrubbed
+                        .extend(removed.into_iter().map(|key| format!("{}: {}", relative.display(), key)));
+                }
+            }
+        }
+        std::fs::write(&target, substitute(&content, project_name, NAME_PLACEHOLDER))?;
+    }
+    report.files = files.len();
+
+    let manifest = TemplateManifest {
+        name: template_name.to_string(),
+        source_name: project_name.to_string(),
+        files: files.len(),
+    };
+    std::fs::write(
+        dest.join(TEMPLATE_MANIFEST),
+        serde_json::to_string_pretty(&manifest).map_err(io::Error::other)?,
+    )?;
+    Ok(report)
+}
+
+/// Copy a template into `project_dir`, filling in the project name.
+///
+/// Existing files are left alone; returns the number of files written.
+pub fn instantiate_template(template_dir: &Path, project_dir: &Path, project_name: &str) -> io::Result<usize> {
+    let mut skipped = Vec::new();
+    let mut files = Vec::new();
+    collect_files(template_dir, template_dir, &mut skipped, &mut files)?;
+
+    let mut written = 0;
+    for relative in files {
+        let target = project_dir.join(substitute(&relative.to_string_lossy(), NAME_PLACEHOLDER, project_name));
+        if target.exists() {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let source = template_dir.join(&relative);
+        if is_text(&relative) {
+            let content = std::fs::read_to_string(&source)?;
+            std::fs::write(&target, substitute(&content, NAME_PLACEHOLDER, project_name))?;
+        } else {
+            std::fs::copy(&source, &target)?;
+        }
+        written += 1;
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_round_trip() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let project = temp.path().join("Obby");
+        let write = |path: &str, content: &str| {
+            let path = project.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "rbxsync.json",
+            r#"{"name": "Obby", "license": {"key": "abc"}, "nightly": {"publish": {"universeId": 1, "placeId": 2}}}"#,
+        );
+        write("src/ServerScriptService/ObbyServer.server.luau", "print(\"Obby\")");
+        write(".rbxsync/harness/sessions/1.yaml", "log");
+        write(".rbxsync/mocks.json", "{}");
+        write("builds/nightly-20261016-020000.rbxl", "x");
+        write(".env", "TOKEN=1");
+
+        let template = temp.path().join("template");
+        let report = create_template(&project, &template, "studio-standard", "Obby").unwrap();
+        assert_eq!(report.files, 3);
+        assert_eq!(report.scrubbed.len(), 3);
+
+        let config = std::fs::read_to_string(template.join("rbxsync.json")).unwrap();
+        assert!(config.contains("{{name}}") && !config.contains("placeId") && !config.contains("abc"));
+        assert!(template.join("src/ServerScriptService/{{name}}Server.server.luau").exists());
+        assert!(template.join(".rbxsync/mocks.json").exists());
+        assert!(!template.join(".rbxsync/harness").exists());
+        assert!(!template.join(".env").exists());
+
+        let fresh = temp.path().join("Kart");
+        assert_eq!(instantiate_template(&template, &fresh, "Kart").unwrap(), 3);
+        let script = std::fs::read_to_string(fresh.join("src/ServerScriptService/KartServer.server.luau")).unwrap();
+        assert_eq!(script, "print(\"Kart\")");
+        assert!(!fresh.join(TEMPLATE_MANIFEST).exists());
+    }
+}
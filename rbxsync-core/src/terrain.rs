@@ -0,0 +1,352 @@
+//! Chunked terrain storage
+//!
+//! Terrain voxels are kept outside `src/`, in `terrain/` at the project root.
+//! `meta.json` holds the chunk size, resolution, bounds and Terrain
+//! properties, and each `r.<x>.<y>.<z>.bin` holds the chunks of one region
+//! ([`REGION_CHUNKS`] chunks along each axis), gzip-compressed. Writes only
+//! touch the regions a batch of chunks falls in, so extraction never holds the
+//! whole map in memory, and editing one area of the map only changes its files.
+//!
+//! Chunks use the plugin's encoding (see `TerrainHandler.luau`): `materials`
+//! is run-length encoded as `[count, material, ...]` with counts up to 255,
+//! and `occupancies` holds one 0-255 value per voxel. [`read_terrain`] returns
+//! the same JSON the plugin's `applyTerrain` takes.
+//!
+//! Projects from before this format have a single `terrain.rbxjson` under
+//! `src/Workspace/Terrain/`; [`migrate_legacy`] converts it on first use.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// Terrain directory, relative to the project
+pub const TERRAIN_DIR: &str = "terrain";
+
+pub const META_FILE: &str = "meta.json";
+
+/// Chunks along each axis of a region file
+pub const REGION_CHUNKS: i32 = 4;
+
+/// The single-file format this replaces
+pub const LEGACY_FILE: &str = "terrain.rbxjson";
+
+const MAGIC: &[u8; 4] = b"RBXT";
+const FORMAT_VERSION: u8 = 1;
+
+/// Metadata fields kept in `meta.json`
+const META_FIELDS: [&str; 4] = ["chunkSize", "resolution", "region", "properties"];
+
+/// One chunk of voxels, as the plugin sends it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TerrainChunk {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    /// `[count, material, ...]` runs of Material enum values
+    pub materials: Vec<u16>,
+    /// Occupancy per voxel, quantized to 0-255
+    pub occupancies: Vec<u8>,
+}
+
+impl TerrainChunk {
+    fn region(&self) -> (i32, i32, i32) {
+        (
+            self.x.div_euclid(REGION_CHUNKS),
+            self.y.div_euclid(REGION_CHUNKS),
+            self.z.div_euclid(REGION_CHUNKS),
+        )
+    }
+}
+
+pub fn terrain_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join(TERRAIN_DIR)
+}
+
+/// Where older versions wrote terrain: `Workspace/Terrain/terrain.rbxjson`,
+/// and before that `Workspace/Terrain.rbxjson`
+pub fn legacy_files(project_dir: &Path) -> [PathBuf; 2] {
+    let workspace = project_dir.join("src").join("Workspace");
+    [workspace.join("Terrain").join(LEGACY_FILE), workspace.join("Terrain.rbxjson")]
+}
+
+/// Whether the project has terrain in the chunked format
+pub fn has_terrain(project_dir: &Path) -> bool {
+    terrain_dir(project_dir).join(META_FILE).is_file()
+}
+
+/// Remove stored terrain, in either format, before a new extraction writes it
+pub fn clear(project_dir: &Path) -> std::io::Result<()> {
+    let dir = terrain_dir(project_dir);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    for file in legacy_files(project_dir) {
+        if is_legacy_terrain(&file) {
+            std::fs::remove_file(&file)?;
+        }
+    }
+    Ok(())
+}
+
+/// Merge the metadata fields of an extraction batch (or a whole legacy file)
+/// into `meta.json`. Fields a batch leaves out are kept.
+pub fn write_meta(project_dir: &Path, terrain: &Value) -> std::io::Result<()> {
+    let dir = terrain_dir(project_dir);
+    let path = dir.join(META_FILE);
+    let mut meta: Map<String, Value> = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    for field in META_FIELDS {
+        if let Some(value) = terrain.get(field).filter(|v| !v.is_null()) {
+            meta.insert(field.to_string(), value.clone());
+        }
+    }
+    meta.insert("format".to_string(), json!(FORMAT_VERSION));
+
+    std::fs::create_dir_all(&dir)?;
+    let json = serde_json::to_string_pretty(&meta).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
+}
+
+/// Add chunks to their region files, replacing chunks at the same position.
+/// Returns the number of chunks written.
+pub fn write_chunks(project_dir: &Path, chunks: &[TerrainChunk]) -> std::io::Result<usize> {
+    let dir = terrain_dir(project_dir);
+    std::fs::create_dir_all(&dir)?;
+
+    let mut regions: BTreeMap<(i32, i32, i32), Vec<&TerrainChunk>> = BTreeMap::new();
+    for chunk in chunks {
+        regions.entry(chunk.region()).or_default().push(chunk);
+    }
+    for (region, new_chunks) in regions {
+        let path = dir.join(region_file_name(region));
+        let mut stored: BTreeMap<(i32, i32, i32), TerrainChunk> = if path.exists() {
+            read_region(&path)?.into_iter().map(|c| ((c.x, c.y, c.z), c)).collect()
+        } else {
+            BTreeMap::new()
+        };
+        for chunk in new_chunks {
+            stored.insert((chunk.x, chunk.y, chunk.z), chunk.clone());
+        }
+        write_region(&path, stored.values())?;
+    }
+    Ok(chunks.len())
+}
+
+/// Store one extraction batch (`{ chunkSize, resolution, region, properties?, chunks }`).
+/// The first batch of an extraction replaces the project's terrain.
+pub fn write_batch(project_dir: &Path, batch: &Value, first: bool) -> std::io::Result<usize> {
+    let chunks: Vec<TerrainChunk> = serde_json::from_value(batch.get("chunks").cloned().unwrap_or_else(|| json!([])))
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    if first {
+        clear(project_dir)?;
+    }
+    write_meta(project_dir, batch)?;
+    write_chunks(project_dir, &chunks)
+}
+
+/// A project's terrain in the JSON form the plugin applies, migrating a legacy
+/// `terrain.rbxjson` first. `None` when the project has no terrain.
+pub fn read_terrain(project_dir: &Path) -> std::io::Result<Option<Value>> {
+    migrate_legacy(project_dir)?;
+    let dir = terrain_dir(project_dir);
+    let Ok(content) = std::fs::read_to_string(dir.join(META_FILE)) else {
+        return Ok(None);
+    };
+    let mut terrain: Map<String, Value> = serde_json::from_str(&content).map_err(std::io::Error::other)?;
+    terrain.remove("format");
+
+    let mut region_files: Vec<PathBuf> = std::fs::read_dir(&dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("r.") && n.ends_with(".bin")))
+        .collect();
+    region_files.sort();
+    let mut chunks = Vec::new();
+    for path in region_files {
+        chunks.extend(read_region(&path)?);
+    }
+    chunks.sort_by_key(|c| (c.x, c.y, c.z));
+    terrain.insert("chunks".to_string(), serde_json::to_value(chunks).map_err(std::io::Error::other)?);
+    Ok(Some(Value::Object(terrain)))
+}
+
+/// Convert a legacy `terrain.rbxjson` to the chunked format and delete it.
+/// When the project already has chunked terrain, the legacy file is stale and
+/// is just deleted. Returns whether a file was converted.
+pub fn migrate_legacy(project_dir: &Path) -> std::io::Result<bool> {
+    let Some(file) = legacy_files(project_dir).into_iter().find(|f| is_legacy_terrain(f)) else {
+        return Ok(false);
+    };
+    if has_terrain(project_dir) {
+        std::fs::remove_file(&file)?;
+        return Ok(false);
+    }
+
+    let content = std::fs::read_to_string(&file)?;
+    let legacy: Value = serde_json::from_str(&content).map_err(std::io::Error::other)?;
+    write_batch(project_dir, &legacy, false)?;
+    std::fs::remove_file(&file)?;
+    if let Some(parent) = file.parent() {
+        // Workspace/Terrain/ held nothing but the legacy file
+        if std::fs::read_dir(parent).is_ok_and(|mut entries| entries.next().is_none()) {
+            let _ = std::fs::remove_dir(parent);
+        }
+    }
+    Ok(true)
+}
+
+/// A `.rbxjson` holding terrain voxels rather than an instance
+fn is_legacy_terrain(file: &Path) -> bool {
+    file.is_file()
+        && std::fs::read_to_string(file)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .is_some_and(|value| value.get("chunks").is_some_and(|c| c.is_array()))
+}
+
+fn region_file_name((x, y, z): (i32, i32, i32)) -> String {
+    format!("r.{}.{}.{}.bin", x, y, z)
+}
+
+fn write_region<'a>(path: &Path, chunks: impl ExactSizeIterator<Item = &'a TerrainChunk>) -> std::io::Result<()> {
+    let mut out = GzEncoder::new(Vec::new(), Compression::default());
+    out.write_all(MAGIC)?;
+    out.write_all(&[FORMAT_VERSION])?;
+    out.write_all(&(chunks.len() as u32).to_le_bytes())?;
+    for chunk in chunks {
+        for coordinate in [chunk.x, chunk.y, chunk.z] {
+            out.write_all(&coordinate.to_le_bytes())?;
+        }
+        out.write_all(&(chunk.materials.len() as u32).to_le_bytes())?;
+        for material in &chunk.materials {
+            out.write_all(&material.to_le_bytes())?;
+        }
+        out.write_all(&(chunk.occupancies.len() as u32).to_le_bytes())?;
+        out.write_all(&chunk.occupancies)?;
+    }
+    std::fs::write(path, out.finish()?)
+}
+
+fn read_region(path: &Path) -> std::io::Result<Vec<TerrainChunk>> {
+    let invalid = |message: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{}: {}", path.display(), message));
+    let mut data = Vec::new();
+    GzDecoder::new(std::fs::File::open(path)?).read_to_end(&mut data)?;
+
+    let mut reader = data.as_slice();
+    let mut header = [0u8; 5];
+    reader.read_exact(&mut header).map_err(|_| invalid("truncated header"))?;
+    if &header[..4] != MAGIC {
+        return Err(invalid("not a terrain region file"));
+    }
+    if header[4] != FORMAT_VERSION {
+        return Err(invalid(&format!("unsupported format version {}", header[4])));
+    }
+
+    let count = read_u32(&mut reader).map_err(|_| invalid("truncated header"))?;
+    let mut chunks = Vec::with_capacity(count.min(1024) as usize);
+    for _ in 0..count {
+        let chunk = read_chunk(&mut reader).map_err(|_| invalid("truncated chunk"))?;
+        chunks.push(chunk);
+    }
+    Ok(chunks)
+}
+
+fn read_chunk(reader: &mut &[u8]) -> std::io::Result<TerrainChunk> {
+    let x = read_u32(reader)? as i32;
+    let y = read_u32(reader)? as i32;
+    let z = read_u32(reader)? as i32;
+    let material_count = read_u32(reader)? as usize;
+    let mut materials = Vec::with_capacity(material_count.min(reader.len() / 2));
+    for _ in 0..material_count {
+        let mut bytes = [0u8; 2];
+        reader.read_exact(&mut bytes)?;
+        materials.push(u16::from_le_bytes(bytes));
+    }
+    let occupancy_count = read_u32(reader)? as usize;
+    if occupancy_count > reader.len() {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    let (occupancies, rest) = reader.split_at(occupancy_count);
+    let occupancies = occupancies.to_vec();
+    *reader = rest;
+    Ok(TerrainChunk { x, y, z, materials, occupancies })
+}
+
+fn read_u32(reader: &mut &[u8]) -> std::io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(x: i32, y: i32, z: i32, material: u16) -> TerrainChunk {
+        TerrainChunk { x, y, z, materials: vec![255, material, 10, 1792], occupancies: vec![0, 128, 255] }
+    }
+
+    #[test]
+    fn test_write_and_read_chunks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = dir.path();
+        let first = json!({
+            "chunkSize": 32,
+            "resolution": 4,
+            "properties": { "WaterWaveSize": 0.15 },
+            "chunks": [chunk(0, 0, 0, 256), chunk(-1, 0, 5, 512)]
+        });
+        assert_eq!(write_batch(project, &first, true).unwrap(), 2);
+        // A later batch without properties keeps them, and replaces a chunk
+        let second = json!({ "chunkSize": 32, "resolution": 4, "chunks": [chunk(0, 0, 0, 1280), chunk(1, 0, 0, 256)] });
+        write_batch(project, &second, false).unwrap();
+        assert!(write_batch(project, &json!({ "chunks": [{ "x": 0, "y": 0, "z": 0, "materials": [], "occupancies": [300] }] }), false).is_err());
+
+        let mut regions: Vec<String> = std::fs::read_dir(terrain_dir(project))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|n| n.ends_with(".bin"))
+            .collect();
+        regions.sort();
+        assert_eq!(regions, vec!["r.-1.0.1.bin", "r.0.0.0.bin"]);
+
+        let terrain = read_terrain(project).unwrap().unwrap();
+        assert_eq!(terrain["properties"]["WaterWaveSize"], 0.15);
+        assert!(terrain.get("format").is_none());
+        let chunks: Vec<TerrainChunk> = serde_json::from_value(terrain["chunks"].clone()).unwrap();
+        assert_eq!(chunks, vec![chunk(-1, 0, 5, 512), chunk(0, 0, 0, 1280), chunk(1, 0, 0, 256)]);
+
+        clear(project).unwrap();
+        assert!(read_terrain(project).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_migrate_legacy() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = dir.path();
+        let legacy = project.join("src/Workspace/Terrain").join(LEGACY_FILE);
+        std::fs::create_dir_all(legacy.parent().unwrap()).unwrap();
+        let chunks = serde_json::to_value(vec![chunk(2, -1, 0, 256)]).unwrap();
+        std::fs::write(&legacy, json!({ "chunkSize": 32, "resolution": 4, "chunks": chunks }).to_string()).unwrap();
+
+        let terrain = read_terrain(project).unwrap().unwrap();
+        assert_eq!(terrain["chunks"], chunks);
+        assert!(!legacy.exists());
+        assert!(!project.join("src/Workspace/Terrain").exists());
+        assert!(!migrate_legacy(project).unwrap());
+
+        // The Terrain instance's own file isn't terrain data
+        let instance = project.join("src/Workspace/Terrain.rbxjson");
+        std::fs::write(&instance, r#"{ "className": "Terrain", "properties": {} }"#).unwrap();
+        clear(project).unwrap();
+        assert!(instance.exists());
+    }
+}
@@ -3,6 +3,7 @@
 //! An Instance is the fundamental unit in Roblox's DataModel.
 //! This module defines how we serialize instances to JSON.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -10,7 +11,7 @@ use uuid::Uuid;
 use super::{AttributeValue, PropertyValue};
 
 /// A serialized Roblox instance
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Instance {
     /// The Roblox class name (e.g., "Part", "Script", "Folder")
@@ -121,7 +122,7 @@ impl Instance {
 }
 
 /// Metadata for an instance stored in `_meta.rbxjson` files
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct InstanceMeta {
     /// The Roblox class name
@@ -160,7 +161,7 @@ impl From<&Instance> for InstanceMeta {
 }
 
 /// Terrain-specific data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TerrainData {
     /// Size of each voxel chunk
@@ -176,14 +177,14 @@ pub struct TerrainData {
     pub chunks_dir: String,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
 pub struct Region {
     pub min: [i32; 3],
     pub max: [i32; 3],
 }
 
 /// CSG/Union operation data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CsgData {
     /// Cloud asset ID if available
@@ -78,6 +78,10 @@ pub struct ExtractionConfig {
     #[serde(default = "default_binary_asset_types")]
     pub binary_asset_types: HashSet<String>,
 
+    /// Download the content of extracted assets into the assets directory
+    #[serde(default)]
+    pub download_assets: bool,
+
     /// Services to exclude from extraction
     #[serde(default = "default_exclude_services")]
     pub exclude_services: HashSet<String>,
@@ -105,6 +109,18 @@ pub struct ExtractionConfig {
     /// Generate tooling config files on extraction (default.project.json, selene.toml, wally.toml)
     #[serde(default = "default_true")]
     pub generate_tooling_files: bool,
+
+    /// `$schema` reference written into .rbxjson files (null to omit)
+    #[serde(default = "default_rbxjson_schema")]
+    pub rbxjson_schema: Option<String>,
+
+    /// Leave properties at their class default out of extracted .rbxjson files
+    #[serde(default)]
+    pub omit_default_properties: bool,
+
+    /// Formatter run on extracted .luau files, e.g. `stylua`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format_command: Option<String>,
 }
 
 fn default_true() -> bool {
@@ -125,6 +141,10 @@ fn default_exclude_services() -> HashSet<String> {
         .collect()
 }
 
+fn default_rbxjson_schema() -> Option<String> {
+    Some(crate::schema::SCHEMA_URL.to_string())
+}
+
 fn default_chunk_size() -> usize {
     1000
 }
@@ -134,6 +154,7 @@ impl Default for ExtractionConfig {
         Self {
             extract_binary_assets: true,
             binary_asset_types: default_binary_asset_types(),
+            download_assets: false,
             exclude_services: default_exclude_services(),
             exclude_classes: HashSet::new(),
             script_source_mode: ScriptSourceMode::default(),
@@ -141,6 +162,9 @@ impl Default for ExtractionConfig {
             csg_mode: CsgMode::default(),
             chunk_size: default_chunk_size(),
             generate_tooling_files: true,
+            rbxjson_schema: default_rbxjson_schema(),
+            omit_default_properties: false,
+            format_command: None,
         }
     }
 }
@@ -206,6 +230,35 @@ pub struct SyncConfig {
     /// Paths to watch for changes (relative to project root)
     #[serde(default)]
     pub watch_paths: Vec<PathBuf>,
+
+    /// What to do with legacy .lua files found on extract and sync
+    #[serde(default)]
+    pub legacy_lua: LegacyLuaMode,
+
+    /// Instance path globs to sync (default: everything)
+    #[serde(default)]
+    pub include: Vec<String>,
+
+    /// Instance path globs to leave out of sync
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// Instance paths whose Studio-only descendants are never deleted, like
+    /// Rojo's `$ignoreUnknownInstances`
+    #[serde(default)]
+    pub ignore_unknown_instances: Vec<String>,
+
+    /// Regenerate sourcemap.json when instances are created, renamed or
+    /// deleted, from file changes or from Studio
+    #[serde(default)]
+    pub sourcemap: bool,
+}
+
+impl SyncConfig {
+    /// The include/exclude globs as a filter
+    pub fn filter(&self) -> crate::SyncFilter {
+        crate::SyncFilter { include: self.include.clone(), exclude: self.exclude.clone() }
+    }
 }
 
 impl Default for SyncConfig {
@@ -215,6 +268,11 @@ impl Default for SyncConfig {
             conflict_resolution: ConflictResolution::default(),
             auto_sync: false,
             watch_paths: vec![PathBuf::from("./src")],
+            legacy_lua: LegacyLuaMode::default(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            ignore_unknown_instances: Vec::new(),
+            sourcemap: false,
         }
     }
 }
@@ -234,6 +292,21 @@ pub enum SyncMode {
     Bidirectional,
 }
 
+/// How to handle legacy .lua script files
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LegacyLuaMode {
+    /// Report .lua files so they can be migrated with `rbxsync migrate --from lua`
+    #[default]
+    Warn,
+
+    /// Rename .lua files to .luau automatically
+    Migrate,
+
+    /// Leave .lua files alone without reporting them
+    Ignore,
+}
+
 /// How to handle sync conflicts
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -3,11 +3,12 @@
 //! All property values are wrapped in a typed container for JSON serialization.
 //! This ensures we preserve type information and can round-trip accurately.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// A typed property value that can be serialized to JSON
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(tag = "type", content = "value")]
 pub enum PropertyValue {
     // Primitive types
@@ -105,9 +106,9 @@ pub enum PropertyValue {
     #[serde(rename = "Axes")]
     Axes(AxesValue),
 
-    // Physics types
+    // Physics types (None is the material's default physics)
     #[serde(rename = "PhysicalProperties")]
-    PhysicalProperties(PhysicalPropertiesValue),
+    PhysicalProperties(Option<PhysicalPropertiesValue>),
 
     #[serde(rename = "Ray")]
     Ray(RayValue),
@@ -137,26 +138,26 @@ pub enum PropertyValue {
 
 // === Vector Types ===
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct Vector2 {
     pub x: f32,
     pub y: f32,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Vector2int16 {
     pub x: i16,
     pub y: i16,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct Vector3 {
     pub x: f32,
     pub y: f32,
     pub z: f32,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Vector3int16 {
     pub x: i16,
     pub y: i16,
@@ -166,7 +167,7 @@ pub struct Vector3int16 {
 // === Transform Types ===
 
 /// CFrame (Coordinate Frame) - position + rotation matrix
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct CFrame {
     pub position: [f32; 3],
     /// 3x3 rotation matrix stored as row-major array
@@ -185,7 +186,7 @@ impl Default for CFrame {
 // === Color Types ===
 
 /// Color3 with components in 0-1 range
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct Color3 {
     pub r: f32,
     pub g: f32,
@@ -193,7 +194,7 @@ pub struct Color3 {
 }
 
 /// Color3uint8 with components in 0-255 range
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Color3uint8 {
     pub r: u8,
     pub g: u8,
@@ -202,19 +203,19 @@ pub struct Color3uint8 {
 
 // === UI Types ===
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct UDim {
     pub scale: f32,
     pub offset: i32,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct UDim2 {
     pub x: UDim,
     pub y: UDim,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct Rect {
     pub min: Vector2,
     pub max: Vector2,
@@ -222,30 +223,30 @@ pub struct Rect {
 
 // === Sequence Types ===
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct NumberSequence {
     pub keypoints: Vec<NumberSequenceKeypoint>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct NumberSequenceKeypoint {
     pub time: f32,
     pub value: f32,
     pub envelope: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct ColorSequence {
     pub keypoints: Vec<ColorSequenceKeypoint>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct ColorSequenceKeypoint {
     pub time: f32,
     pub color: Color3,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct NumberRange {
     pub min: f32,
     pub max: f32,
@@ -253,7 +254,7 @@ pub struct NumberRange {
 
 // === Enum Type ===
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct EnumValue {
     #[serde(rename = "enumType")]
     pub enum_type: String,
@@ -262,7 +263,7 @@ pub struct EnumValue {
 
 // === Reference Types ===
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct SharedStringRef {
     pub hash: String,
     pub file: Option<String>,
@@ -270,7 +271,7 @@ pub struct SharedStringRef {
 
 // === Font Type ===
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct FontValue {
     pub family: String,
     pub weight: String,
@@ -279,7 +280,7 @@ pub struct FontValue {
 
 // === Face/Axes Types ===
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct FacesValue {
     pub top: bool,
     pub bottom: bool,
@@ -289,7 +290,7 @@ pub struct FacesValue {
     pub back: bool,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct AxesValue {
     pub x: bool,
     pub y: bool,
@@ -298,28 +299,33 @@ pub struct AxesValue {
 
 // === Physics Types ===
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+/// Custom physics. Weights are camelCase as the plugin writes them; the
+/// snake_case spelling is still read.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct PhysicalPropertiesValue {
     pub density: f32,
     pub friction: f32,
     pub elasticity: f32,
+    #[serde(alias = "friction_weight")]
     pub friction_weight: f32,
+    #[serde(alias = "elasticity_weight")]
     pub elasticity_weight: f32,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct RayValue {
     pub origin: Vector3,
     pub direction: Vector3,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct Region3Value {
     pub min: Vector3,
     pub max: Vector3,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Region3int16Value {
     pub min: Vector3int16,
     pub max: Vector3int16,
@@ -328,7 +334,7 @@ pub struct Region3int16Value {
 // === Attribute Types (for Instance Attributes) ===
 
 /// Attributes can only store a subset of property types
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(tag = "type", content = "value")]
 pub enum AttributeValue {
     #[serde(rename = "bool")]
@@ -380,6 +386,7 @@ pub enum AttributeValue {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_vector3_serialization() {
@@ -401,6 +408,45 @@ mod tests {
         assert_eq!(cf, deserialized);
     }
 
+    #[test]
+    fn test_sequence_and_physics_round_trip() {
+        // Shapes as the plugin's Serializer writes them
+        let cases = [
+            json!({ "type": "NumberSequence", "value": { "keypoints": [
+                { "time": 0.0, "value": 1.0, "envelope": 0.0 },
+                { "time": 1.0, "value": 0.5, "envelope": 0.25 }
+            ] } }),
+            json!({ "type": "ColorSequence", "value": { "keypoints": [
+                { "time": 0.0, "color": { "r": 1.0, "g": 0.5, "b": 0.0 } },
+                { "time": 1.0, "color": { "r": 0.0, "g": 0.0, "b": 1.0 } }
+            ] } }),
+            json!({ "type": "PhysicalProperties", "value": {
+                "density": 0.75, "friction": 0.25, "elasticity": 0.5, "frictionWeight": 1.0, "elasticityWeight": 2.0
+            } }),
+            json!({ "type": "PhysicalProperties", "value": null }),
+            json!({ "type": "Ray", "value": {
+                "origin": { "x": 0.0, "y": 5.0, "z": 0.0 }, "direction": { "x": 0.0, "y": -1.0, "z": 0.0 }
+            } }),
+            json!({ "type": "Faces", "value": {
+                "top": true, "bottom": false, "left": true, "right": false, "front": false, "back": true
+            } }),
+            json!({ "type": "Axes", "value": { "x": true, "y": false, "z": true } }),
+        ];
+        for case in cases {
+            let value: PropertyValue = serde_json::from_value(case.clone()).unwrap();
+            assert_eq!(serde_json::to_value(&value).unwrap(), case);
+        }
+
+        // The plugin omits the value of default physics, and older files used snake_case weights
+        let default: PropertyValue = serde_json::from_value(json!({ "type": "PhysicalProperties" })).unwrap();
+        assert_eq!(default, PropertyValue::PhysicalProperties(None));
+        let legacy: PropertyValue = serde_json::from_value(json!({ "type": "PhysicalProperties", "value": {
+            "density": 1.0, "friction": 0.3, "elasticity": 0.5, "friction_weight": 1.0, "elasticity_weight": 1.0
+        } }))
+        .unwrap();
+        assert!(matches!(legacy, PropertyValue::PhysicalProperties(Some(p)) if p.friction_weight == 1.0));
+    }
+
     #[test]
     fn test_enum_serialization() {
         let e = PropertyValue::Enum(EnumValue {
@@ -4,9 +4,10 @@
 //! to enable Wally package compatibility in RbxSync.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::Path;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 /// Wally manifest (wally.toml)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,7 +92,36 @@ pub struct WallyLockedPackage {
 
     /// Dependencies of this package
     #[serde(default)]
-    pub dependencies: Vec<String>,
+    pub dependencies: Vec<WallyLockDependency>,
+}
+
+/// A dependency in wally.lock: `["Signal", "sleitnick/signal@1.5.0"]`, or a
+/// bare package name in older lock files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WallyLockDependency {
+    Aliased(String, String),
+    Name(String),
+}
+
+impl WallyLockDependency {
+    /// Name of the link script for this dependency
+    pub fn alias(&self) -> &str {
+        match self {
+            Self::Aliased(alias, _) => alias,
+            Self::Name(spec) => {
+                let name = split_package_spec(spec).0;
+                name.rsplit('/').next().unwrap_or(name)
+            }
+        }
+    }
+
+    /// `scope/name@version`, or just `scope/name`
+    pub fn spec(&self) -> &str {
+        match self {
+            Self::Aliased(_, spec) | Self::Name(spec) => spec,
+        }
+    }
 }
 
 /// Errors that can occur when parsing Wally files
@@ -108,6 +138,12 @@ pub enum WallyError {
 
     #[error("Wally lock file not found at {0}")]
     LockNotFound(String),
+
+    #[error("{0} is not in wally.lock; run `wally install` or `wally update` to resolve it")]
+    NotLocked(String),
+
+    #[error("Invalid package archive: {0}")]
+    ZipError(#[from] zip::result::ZipError),
 }
 
 impl WallyManifest {
@@ -201,6 +237,416 @@ pub fn is_package_path<P: AsRef<Path>>(path: P) -> bool {
         || path_str.ends_with("\\packages")
 }
 
+/// Folders `wally install` creates next to wally.toml, one per realm
+pub const WALLY_PACKAGE_FOLDERS: [&str; 3] = ["Packages", "ServerPackages", "DevPackages"];
+
+/// A package found in a Packages `_Index` folder
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledPackage {
+    /// Package name (scope/name)
+    pub name: String,
+    pub version: String,
+    /// Packages folder it was found in, relative to the project
+    pub folder: String,
+}
+
+/// A package installed at a different version than wally.lock resolved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionDrift {
+    pub name: String,
+    pub locked: String,
+    pub installed: String,
+}
+
+/// Differences between wally.lock and the installed Packages folders
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WallyDrift {
+    /// Locked but not installed
+    pub missing: Vec<WallyLockedPackage>,
+    /// Installed at another version
+    pub mismatched: Vec<VersionDrift>,
+    /// Installed but not in the lock file
+    pub extra: Vec<InstalledPackage>,
+}
+
+impl WallyDrift {
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty() && self.mismatched.is_empty() && self.extra.is_empty()
+    }
+
+    /// One line per problem, e.g. `sleitnick/signal: locked 1.5.0, installed 1.4.0`
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .mismatched
+            .iter()
+            .map(|d| format!("{}: locked {}, installed {}", d.name, d.locked, d.installed))
+            .collect();
+        lines.extend(self.missing.iter().map(|p| format!("{}@{}: in wally.lock, not installed", p.name, p.version)));
+        lines.extend(
+            self.extra
+                .iter()
+                .map(|p| format!("{}@{}: installed in {}, not in wally.lock", p.name, p.version, p.folder)),
+        );
+        lines
+    }
+}
+
+/// Parse an `_Index` folder name (`sleitnick_signal@1.5.0`) into name and version
+pub fn parse_index_folder(folder_name: &str) -> Option<(String, String)> {
+    let (package, version) = folder_name.rsplit_once('@')?;
+    let (scope, name) = package.split_once('_')?;
+    if scope.is_empty() || name.is_empty() || version.is_empty() {
+        return None;
+    }
+    Some((format!("{}/{}", scope, name), version.to_string()))
+}
+
+/// Packages installed in the `_Index` folder of each Packages folder
+pub fn installed_packages<P: AsRef<Path>>(project_dir: P, packages_folders: &[&str]) -> Vec<InstalledPackage> {
+    let project_dir = project_dir.as_ref();
+    let mut installed: Vec<InstalledPackage> = Vec::new();
+    for folder in packages_folders {
+        let Ok(entries) = fs::read_dir(project_dir.join(folder).join("_Index")) else {
+            continue;
+        };
+        let mut found: Vec<InstalledPackage> = entries
+            .flatten()
+            .filter(|e| e.path().is_dir())
+            .filter_map(|e| parse_index_folder(&e.file_name().to_string_lossy()))
+            .filter(|(name, version)| !installed.iter().any(|p| p.name == *name && p.version == *version))
+            .map(|(name, version)| InstalledPackage { name, version, folder: folder.to_string() })
+            .collect();
+        found.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+        installed.extend(found);
+    }
+    installed
+}
+
+/// Compare a lock file against what's installed. The project's own package
+/// (`root`, from wally.toml) is listed in the lock but never installed.
+pub fn compare_wally_lock(lock: &WallyLock, installed: &[InstalledPackage], root: Option<&str>) -> WallyDrift {
+    let same = |a: &str, b: &str| a.eq_ignore_ascii_case(b);
+    let locked: Vec<&WallyLockedPackage> = lock
+        .packages
+        .iter()
+        .filter(|p| root.is_none_or(|root| !same(&p.name, root)))
+        .collect();
+
+    let mut missing: Vec<WallyLockedPackage> = locked
+        .iter()
+        .filter(|l| !installed.iter().any(|i| same(&i.name, &l.name) && i.version == l.version))
+        .map(|l| (*l).clone())
+        .collect();
+    let mut extra: Vec<InstalledPackage> = installed
+        .iter()
+        .filter(|i| !locked.iter().any(|l| same(&i.name, &l.name) && i.version == l.version))
+        .cloned()
+        .collect();
+
+    // A missing and an extra entry for the same package are one version mismatch
+    let mut mismatched = Vec::new();
+    missing.retain(|l| match extra.iter().position(|i| same(&i.name, &l.name)) {
+        Some(idx) => {
+            let installed = extra.remove(idx);
+            mismatched.push(VersionDrift {
+                name: l.name.clone(),
+                locked: l.version.clone(),
+                installed: installed.version,
+            });
+            false
+        }
+        None => true,
+    });
+
+    WallyDrift { missing, mismatched, extra }
+}
+
+/// Check a project's wally.lock against its Packages folders.
+///
+/// `packages_folder` is the shared Packages folder (rbxsync.json
+/// `packages.packagesFolder`); ServerPackages and DevPackages are checked too,
+/// next to it and inside it. Returns None when the project has no wally.lock.
+pub fn check_wally_drift<P: AsRef<Path>>(project_dir: P, packages_folder: &str) -> Result<Option<WallyDrift>, WallyError> {
+    let project_dir = project_dir.as_ref();
+    let Some(lock_path) = find_wally_lock(project_dir) else {
+        return Ok(None);
+    };
+    let lock = WallyLock::from_file(&lock_path)?;
+    let root = find_wally_manifest(project_dir)
+        .and_then(|path| WallyManifest::from_file(path).ok())
+        .map(|manifest| manifest.package.name);
+
+    let mut folders: Vec<String> = vec![packages_folder.to_string()];
+    for folder in &WALLY_PACKAGE_FOLDERS[1..] {
+        folders.push(folder.to_string());
+        folders.push(format!("{}/{}", packages_folder, folder));
+    }
+    let folders: Vec<&str> = folders.iter().map(String::as_str).collect();
+    let installed = installed_packages(project_dir, &folders);
+
+    Ok(Some(compare_wally_lock(&lock, &installed, root.as_deref())))
+}
+
+/// Split `scope/name@version` into the package name and version (or
+/// version requirement, in wally.toml)
+pub fn split_package_spec(spec: &str) -> (&str, Option<&str>) {
+    match spec.split_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (spec, None),
+    }
+}
+
+/// `_Index` folder name for a package (`sleitnick_signal@1.5.0`)
+pub fn index_folder_name(name: &str, version: &str) -> String {
+    format!("{}@{}", name.replacen('/', "_", 1), version)
+}
+
+/// The locked package a spec refers to: the exact version if the spec has
+/// one that's locked, else the first locked package with that name
+fn resolve_locked<'a>(lock: &'a WallyLock, spec: &str) -> Option<&'a WallyLockedPackage> {
+    let (name, version) = split_package_spec(spec);
+    let named = |p: &&WallyLockedPackage| p.name.eq_ignore_ascii_case(name);
+    lock.packages
+        .iter()
+        .filter(named)
+        .find(|p| version == Some(p.version.as_str()))
+        .or_else(|| lock.packages.iter().find(named))
+}
+
+/// Link script requiring an installed package
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageLink {
+    /// Script name, the dependency's alias
+    pub alias: String,
+    pub name: String,
+    pub version: String,
+}
+
+impl PackageLink {
+    /// Source of the link script; `index` is the path from the script to the
+    /// `_Index` folder
+    pub fn source(&self, index: &str) -> String {
+        let short = self.name.rsplit('/').next().unwrap_or(&self.name);
+        format!(
+            "return require({}[\"{}\"][\"{}\"])\n",
+            index,
+            index_folder_name(&self.name, &self.version),
+            short
+        )
+    }
+}
+
+/// A locked package and the Packages folder it's installed in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedPackage {
+    pub name: String,
+    pub version: String,
+    /// Packages folder, relative to the project
+    pub folder: String,
+    /// Link scripts next to the package in `_Index`, one per dependency
+    pub dependencies: Vec<PackageLink>,
+}
+
+impl PlannedPackage {
+    /// `<folder>/_Index/<scope>_<name>@<version>`
+    pub fn index_dir(&self, project_dir: &Path) -> PathBuf {
+        project_dir.join(&self.folder).join("_Index").join(index_folder_name(&self.name, &self.version))
+    }
+
+    /// Where the package's own files go, inside `index_dir`
+    pub fn package_dir(&self, project_dir: &Path) -> PathBuf {
+        self.index_dir(project_dir).join(self.name.rsplit('/').next().unwrap_or(&self.name))
+    }
+
+    pub fn is_installed(&self, project_dir: &Path) -> bool {
+        self.package_dir(project_dir).is_dir()
+    }
+}
+
+/// What installing a wally.lock lays out on disk
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WallyInstallPlan {
+    /// Index repository the lock was resolved against
+    pub registry: String,
+    /// Packages folders for the shared, server and dev realms
+    pub folders: Vec<String>,
+    pub packages: Vec<PlannedPackage>,
+    /// Packages folder -> link scripts at its top level
+    pub links: BTreeMap<String, Vec<PackageLink>>,
+}
+
+impl WallyInstallPlan {
+    /// Add a package and everything it depends on to a Packages folder
+    fn add(&mut self, lock: &WallyLock, folder: &str, package: &WallyLockedPackage) -> Result<(), WallyError> {
+        if self
+            .packages
+            .iter()
+            .any(|p| p.folder == folder && p.name == package.name && p.version == package.version)
+        {
+            return Ok(());
+        }
+        let mut dependencies = Vec::new();
+        let mut locked = Vec::new();
+        for dependency in &package.dependencies {
+            let resolved =
+                resolve_locked(lock, dependency.spec()).ok_or_else(|| WallyError::NotLocked(dependency.spec().to_string()))?;
+            dependencies.push(PackageLink {
+                alias: dependency.alias().to_string(),
+                name: resolved.name.clone(),
+                version: resolved.version.clone(),
+            });
+            locked.push(resolved);
+        }
+        self.packages.push(PlannedPackage {
+            name: package.name.clone(),
+            version: package.version.clone(),
+            folder: folder.to_string(),
+            dependencies,
+        });
+        for dependency in locked {
+            self.add(lock, folder, dependency)?;
+        }
+        Ok(())
+    }
+}
+
+/// Work out where every package in a project's wally.lock goes.
+///
+/// Dependencies go in `packages_folder` (rbxsync.json `packages.packagesFolder`),
+/// server dependencies in `ServerPackages` and dev dependencies in
+/// `DevPackages`, each with everything they depend on in the same `_Index`.
+/// Versions come from wally.lock; a dependency that isn't locked is an error,
+/// since resolving it needs the registry index.
+pub fn plan_wally_install<P: AsRef<Path>>(project_dir: P, packages_folder: &str) -> Result<WallyInstallPlan, WallyError> {
+    let project_dir = project_dir.as_ref();
+    let manifest_path = find_wally_manifest(project_dir)
+        .ok_or_else(|| WallyError::ManifestNotFound(project_dir.join("wally.toml").display().to_string()))?;
+    let manifest = WallyManifest::from_file(manifest_path)?;
+    let lock_path =
+        find_wally_lock(project_dir).ok_or_else(|| WallyError::LockNotFound(project_dir.join("wally.lock").display().to_string()))?;
+    let lock = WallyLock::from_file(lock_path)?;
+    // The project's own entry has the exact versions its requirements resolved to
+    let root = lock.packages.iter().find(|p| p.name.eq_ignore_ascii_case(&manifest.package.name));
+
+    let mut plan = WallyInstallPlan {
+        registry: lock.registry.clone(),
+        folders: vec![packages_folder.to_string()],
+        ..Default::default()
+    };
+    plan.folders.extend(WALLY_PACKAGE_FOLDERS[1..].iter().map(|f| f.to_string()));
+
+    let realms = [&manifest.dependencies, &manifest.server_dependencies, &manifest.dev_dependencies];
+    for (folder, dependencies) in plan.folders.clone().iter().zip(realms) {
+        let mut aliases: Vec<(&String, &String)> = dependencies.iter().collect();
+        aliases.sort();
+        let mut links = Vec::new();
+        for (alias, spec) in aliases {
+            let locked_spec = root
+                .and_then(|r| r.dependencies.iter().find(|d| d.alias() == alias))
+                .map_or(spec.as_str(), |d| d.spec());
+            let package =
+                resolve_locked(&lock, locked_spec).ok_or_else(|| WallyError::NotLocked(format!("{} ({})", alias, spec)))?;
+            links.push(PackageLink {
+                alias: alias.clone(),
+                name: package.name.clone(),
+                version: package.version.clone(),
+            });
+            plan.add(&lock, folder, package)?;
+        }
+        if !links.is_empty() {
+            plan.links.insert(folder.clone(), links);
+        }
+    }
+    Ok(plan)
+}
+
+/// Unpack a package archive from the registry into the package's `_Index`
+/// folder, replacing whatever was there
+pub fn unpack_wally_package<P: AsRef<Path>>(project_dir: P, package: &PlannedPackage, archive: &[u8]) -> Result<(), WallyError> {
+    let project_dir = project_dir.as_ref();
+    let index_dir = package.index_dir(project_dir);
+    if index_dir.exists() {
+        fs::remove_dir_all(&index_dir)?;
+    }
+    let package_dir = package.package_dir(project_dir);
+    fs::create_dir_all(&package_dir)?;
+
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(archive))?;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        // Entries that would land outside the package folder are skipped
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let target = package_dir.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        fs::write(&target, content)?;
+    }
+    Ok(())
+}
+
+/// Whether a file at the top of a Packages folder is a generated link script
+fn is_link_script(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("lua" | "luau"))
+        && fs::read_to_string(path).is_ok_and(|s| s.starts_with("return require(script.Parent._Index["))
+}
+
+/// Regenerate every link script in a plan and remove what it no longer has:
+/// `_Index` folders of packages that aren't planned and stale links at the
+/// top of each Packages folder. Returns the removed `_Index` folders,
+/// relative to the project.
+pub fn finish_wally_install<P: AsRef<Path>>(project_dir: P, plan: &WallyInstallPlan) -> Result<Vec<String>, WallyError> {
+    let project_dir = project_dir.as_ref();
+    let mut removed = Vec::new();
+    for folder in &plan.folders {
+        let folder_dir = project_dir.join(folder);
+        if let Ok(entries) = fs::read_dir(folder_dir.join("_Index")) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let planned = plan
+                    .packages
+                    .iter()
+                    .any(|p| &p.folder == folder && index_folder_name(&p.name, &p.version) == name);
+                if !planned && entry.path().is_dir() {
+                    fs::remove_dir_all(entry.path())?;
+                    removed.push(format!("{}/_Index/{}", folder, name));
+                }
+            }
+        }
+        if let Ok(entries) = fs::read_dir(&folder_dir) {
+            for path in entries.flatten().map(|e| e.path()) {
+                if path.is_file() && is_link_script(&path) {
+                    fs::remove_file(&path)?;
+                }
+            }
+        }
+    }
+
+    for (folder, links) in &plan.links {
+        let folder_dir = project_dir.join(folder);
+        fs::create_dir_all(&folder_dir)?;
+        for link in links {
+            fs::write(folder_dir.join(format!("{}.lua", link.alias)), link.source("script.Parent._Index"))?;
+        }
+    }
+    for package in &plan.packages {
+        let index_dir = package.index_dir(project_dir);
+        fs::create_dir_all(&index_dir)?;
+        for link in &package.dependencies {
+            fs::write(index_dir.join(format!("{}.lua", link.alias)), link.source("script.Parent.Parent"))?;
+        }
+    }
+    Ok(removed)
+}
+
 /// Get the standard Packages directory paths for different realms
 #[derive(Debug, Clone)]
 pub struct PackageDirectories {
@@ -271,9 +717,174 @@ dependencies = []
 name = "evaera/promise"
 version = "4.0.0"
 dependencies = ["sleitnick/signal"]
+
+[[package]]
+name = "studio/game"
+version = "0.1.0"
+dependencies = [["Signal", "sleitnick/signal@1.5.0"]]
 "#;
         let lock: WallyLock = toml::from_str(toml_content).unwrap();
-        assert_eq!(lock.packages.len(), 2);
+        assert_eq!(lock.packages.len(), 3);
         assert!(lock.find_package("sleitnick/signal").is_some());
+        assert_eq!(lock.packages[1].dependencies[0].alias(), "signal");
+        let root = lock.find_package("studio/game").unwrap();
+        assert_eq!(root.dependencies[0].alias(), "Signal");
+        assert_eq!(root.dependencies[0].spec(), "sleitnick/signal@1.5.0");
+    }
+
+    fn package_archive(files: &[(&str, &str)]) -> Vec<u8> {
+        use std::io::Write;
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_wally_install() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("wally.toml"),
+            r#"
+[package]
+name = "studio/game"
+version = "0.1.0"
+
+[dependencies]
+Promise = "evaera/promise@^4.0.0"
+
+[server-dependencies]
+Signal = "sleitnick/signal@1.5.0"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("wally.lock"),
+            r#"
+registry = "https://github.com/UpliftGames/wally-index"
+
+[[package]]
+name = "studio/game"
+version = "0.1.0"
+dependencies = [["Promise", "evaera/promise@4.0.1"], ["Signal", "sleitnick/signal@1.5.0"]]
+
+[[package]]
+name = "evaera/promise"
+version = "4.0.1"
+dependencies = [["Janitor", "howmanysmall/janitor@1.0.0"]]
+
+[[package]]
+name = "howmanysmall/janitor"
+version = "1.0.0"
+dependencies = []
+
+[[package]]
+name = "sleitnick/signal"
+version = "1.5.0"
+dependencies = []
+"#,
+        )
+        .unwrap();
+        // Left over from an older lock
+        fs::create_dir_all(root.join("Packages/_Index/evaera_promise@4.0.0/promise")).unwrap();
+        fs::create_dir_all(root.join("Packages")).unwrap();
+        fs::write(root.join("Packages/Old.lua"), "return require(script.Parent._Index[\"a_old@1.0.0\"][\"old\"])\n").unwrap();
+        fs::write(root.join("Packages/README.md"), "kept").unwrap();
+
+        let plan = plan_wally_install(root, "Packages").unwrap();
+        assert_eq!(plan.packages.len(), 3);
+        assert_eq!(plan.links["Packages"][0].version, "4.0.1");
+        assert_eq!(plan.links["ServerPackages"][0].alias, "Signal");
+        let janitor = plan.packages.iter().find(|p| p.name == "howmanysmall/janitor").unwrap();
+        assert_eq!(janitor.folder, "Packages");
+
+        for package in &plan.packages {
+            assert!(!package.is_installed(root));
+            let archive = package_archive(&[("init.lua", "return {}"), ("src/util.lua", "return 1"), ("../escape.lua", "")]);
+            unpack_wally_package(root, package, &archive).unwrap();
+            assert!(package.is_installed(root));
+        }
+        assert!(root.join("Packages/_Index/evaera_promise@4.0.1/promise/src/util.lua").exists());
+        assert!(!root.join("Packages/_Index/evaera_promise@4.0.1/escape.lua").exists());
+
+        let removed = finish_wally_install(root, &plan).unwrap();
+        assert_eq!(removed, vec!["Packages/_Index/evaera_promise@4.0.0".to_string()]);
+        assert_eq!(
+            fs::read_to_string(root.join("Packages/Promise.lua")).unwrap(),
+            "return require(script.Parent._Index[\"evaera_promise@4.0.1\"][\"promise\"])\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("Packages/_Index/evaera_promise@4.0.1/Janitor.lua")).unwrap(),
+            "return require(script.Parent.Parent[\"howmanysmall_janitor@1.0.0\"][\"janitor\"])\n"
+        );
+        assert!(root.join("ServerPackages/Signal.lua").exists());
+        assert!(!root.join("Packages/Old.lua").exists());
+        assert!(root.join("Packages/README.md").exists());
+        assert!(check_wally_drift(root, "Packages").unwrap().unwrap().is_clean());
+
+        fs::write(root.join("wally.toml"), "[package]\nname = \"studio/game\"\nversion = \"0.1.0\"\n\n[dependencies]\nT = \"osyrisrblx/t@3.0.0\"\n").unwrap();
+        assert!(matches!(plan_wally_install(root, "Packages"), Err(WallyError::NotLocked(_))));
+    }
+
+    #[test]
+    fn test_wally_drift() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("wally.toml"), "[package]\nname = \"studio/game\"\nversion = \"0.1.0\"\n").unwrap();
+        fs::write(
+            root.join("wally.lock"),
+            r#"
+registry = "test"
+
+[[package]]
+name = "studio/game"
+version = "0.1.0"
+dependencies = []
+
+[[package]]
+name = "sleitnick/signal"
+version = "1.5.0"
+
+[[package]]
+name = "evaera/promise"
+version = "4.0.0"
+
+[[package]]
+name = "madstudioroblox/profileservice"
+version = "1.0.0"
+"#,
+        )
+        .unwrap();
+        assert_eq!(check_wally_drift(root, "Packages").unwrap().unwrap().missing.len(), 3);
+
+        for index in [
+            "Packages/_Index/sleitnick_signal@1.4.0/signal",
+            "Packages/_Index/evaera_promise@4.0.0/promise",
+            "Packages/_Index/osyrisrblx_t@3.0.0/t",
+            "ServerPackages/_Index/madstudioroblox_profileservice@1.0.0/profileservice",
+        ] {
+            fs::create_dir_all(root.join(index)).unwrap();
+        }
+        fs::write(root.join("Packages/Signal.lua"), "").unwrap();
+
+        let drift = check_wally_drift(root, "Packages").unwrap().unwrap();
+        assert!(drift.missing.is_empty());
+        assert_eq!(
+            drift.mismatched,
+            vec![VersionDrift {
+                name: "sleitnick/signal".to_string(),
+                locked: "1.5.0".to_string(),
+                installed: "1.4.0".to_string()
+            }]
+        );
+        assert_eq!(drift.extra.len(), 1);
+        assert_eq!(drift.extra[0].name, "osyrisrblx/t");
+        assert_eq!(drift.lines().len(), 2);
+
+        assert_eq!(parse_index_folder("roblox_roact-compat@0.1.0"), Some(("roblox/roact-compat".to_string(), "0.1.0".to_string())));
+        assert!(check_wally_drift(tempfile::tempdir().unwrap().path(), "Packages").unwrap().is_none());
     }
 }
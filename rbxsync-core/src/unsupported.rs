@@ -0,0 +1,152 @@
+//! Unsupported property reporting
+//!
+//! Some property types can't be read by the plugin (binary strings) or have no
+//! .rbxjson encoding yet; the plugin falls back to a placeholder or a string
+//! with `originalType` set, and the build drops values it can't convert. This
+//! module collects those properties so extraction and build can report them,
+//! and fail in strict mode, instead of losing data silently.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A property that couldn't be carried over, grouped by class and type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsupportedProperty {
+    pub class_name: String,
+    pub property: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// Instances affected
+    pub count: usize,
+    /// Path of the first affected instance
+    pub example: String,
+}
+
+/// Unsupported properties seen during an extraction or build
+#[derive(Debug, Clone, Default)]
+pub struct UnsupportedReport {
+    entries: BTreeMap<(String, String, String), (usize, String)>,
+}
+
+/// Original type of a property value the plugin couldn't serialize faithfully.
+///
+/// Covers the `tostring` fallback (`originalType` set), unknown values, and
+/// binary data the plugin can't read from Luau.
+pub fn unsupported_type(prop: &serde_json::Value) -> Option<&str> {
+    if let Some(original) = prop.get("originalType").and_then(|t| t.as_str()) {
+        return Some(original);
+    }
+    let type_str = prop.get("type")?.as_str()?;
+    match type_str {
+        "unknown" | "SecurityCapabilities" => Some(type_str),
+        "BinaryString" | "SharedString" => {
+            let empty = prop.get("value").and_then(|v| v.as_str()).is_none_or(str::is_empty);
+            empty.then_some(type_str)
+        }
+        _ => None,
+    }
+}
+
+impl UnsupportedReport {
+    pub fn add(&mut self, class_name: &str, property: &str, type_name: &str, path: &str) {
+        self.entries
+            .entry((class_name.to_string(), property.to_string(), type_name.to_string()))
+            .or_insert_with(|| (0, path.to_string()))
+            .0 += 1;
+    }
+
+    /// Record the unsupported properties of one serialized instance
+    pub fn add_instance(&mut self, instance: &serde_json::Value) {
+        let Some(props) = instance.get("properties").and_then(|p| p.as_object()) else {
+            return;
+        };
+        let class_name = instance.get("className").and_then(|c| c.as_str()).unwrap_or("Instance");
+        let path = instance.get("path").and_then(|p| p.as_str()).unwrap_or_default();
+        for (name, value) in props {
+            if let Some(type_name) = unsupported_type(value) {
+                self.add(class_name, name, type_name, path);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Affected property values across all instances
+    pub fn total(&self) -> usize {
+        self.entries.values().map(|(count, _)| count).sum()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Entries sorted by class, property and type
+    pub fn entries(&self) -> Vec<UnsupportedProperty> {
+        self.entries
+            .iter()
+            .map(|((class_name, property, type_name), (count, example))| UnsupportedProperty {
+                class_name: class_name.clone(),
+                property: property.clone(),
+                type_name: type_name.clone(),
+                count: *count,
+                example: example.clone(),
+            })
+            .collect()
+    }
+
+    /// See [`summarize_unsupported`]
+    pub fn summary(&self, limit: usize) -> String {
+        summarize_unsupported(&self.entries(), limit)
+    }
+}
+
+/// One line per entry, `Class.Property (Type) xN, e.g. path`, cut off after `limit`
+pub fn summarize_unsupported(entries: &[UnsupportedProperty], limit: usize) -> String {
+    let mut lines: Vec<String> = entries
+        .iter()
+        .take(limit)
+        .map(|e| format!("{}.{} ({}) x{}, e.g. {}", e.class_name, e.property, e.type_name, e.count, e.example))
+        .collect();
+    if entries.len() > limit {
+        lines.push(format!("... and {} more", entries.len() - limit));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_unsupported_report() {
+        let part = |path: &str| {
+            json!({
+                "className": "Part",
+                "path": path,
+                "properties": {
+                    "Anchored": { "type": "bool", "value": true },
+                    "PhysicalConfigData": { "type": "BinaryString", "value": "" },
+                    "Capabilities": { "type": "string", "value": "x", "originalType": "Capabilities" }
+                }
+            })
+        };
+        let mut report = UnsupportedReport::default();
+        report.add_instance(&part("Workspace/A"));
+        report.add_instance(&part("Workspace/B"));
+        assert_eq!(report.total(), 4);
+
+        let entries = report.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].property, "Capabilities");
+        assert_eq!(entries[0].count, 2);
+        assert_eq!(entries[0].example, "Workspace/A");
+        assert_eq!(report.summary(1).lines().count(), 2);
+
+        assert_eq!(unsupported_type(&json!({ "type": "BinaryString", "value": "AAAA" })), None);
+    }
+}
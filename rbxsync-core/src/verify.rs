@@ -0,0 +1,248 @@
+//! Round-trip verification
+//!
+//! `rbxsync verify` and the server's `/verify` endpoint check that a project
+//! survives a build: `src` is built into a DOM, written as the chosen format,
+//! read back and compared with the DOM it was written from. Three kinds of
+//! loss are reported:
+//!
+//! - property values the build drops because `json_to_variant` can't convert them
+//! - instances that are missing after reading the file back
+//! - property values that change or disappear in serialization
+//!
+//! Values that serialization stores as a different type (a `Color3` written as
+//! `Color3uint8`) only count as lost when the converted value differs. References are compared by target path.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use rbx_dom_weak::types::{Ref, Variant};
+use rbx_dom_weak::WeakDom;
+use serde::Serialize;
+
+use crate::builder::{build_dom, child_instance_path, BuildError, BuildFormat, BuildOptions};
+use crate::unsupported::UnsupportedProperty;
+
+/// A property value that didn't survive serialization
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LossyProperty {
+    pub path: String,
+    pub class_name: String,
+    pub property: String,
+    /// The value built from src
+    pub built: String,
+    /// The value read back, `None` if the property is gone
+    pub read_back: Option<String>,
+}
+
+/// What a round trip lost
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+    pub format: BuildFormat,
+    /// Instances built from src, not counting the root
+    pub instances: usize,
+    /// Property values dropped by the build, grouped by class and type
+    pub unbuildable: Vec<UnsupportedProperty>,
+    /// Instances missing from the file read back
+    pub missing: Vec<String>,
+    pub lossy: Vec<LossyProperty>,
+}
+
+impl VerifyReport {
+    /// Whether everything in src made it through the round trip
+    pub fn is_lossless(&self) -> bool {
+        self.unbuildable.is_empty() && self.missing.is_empty() && self.lossy.is_empty()
+    }
+
+    /// Number of lost values and instances
+    pub fn problems(&self) -> usize {
+        self.unbuildable.iter().map(|u| u.count).sum::<usize>() + self.missing.len() + self.lossy.len()
+    }
+}
+
+/// Build a project, serialize it as `format`, read it back and compare
+pub fn verify_project(project_dir: &Path, format: BuildFormat) -> Result<VerifyReport, BuildError> {
+    let (dom, report) = build_dom(project_dir, &BuildOptions { format, ..Default::default() })?;
+    let read_back = round_trip(&dom, format)?;
+    let (missing, lossy) = compare_doms(&dom, &read_back);
+    Ok(VerifyReport {
+        format,
+        instances: report.instances,
+        unbuildable: report.unsupported,
+        missing,
+        lossy,
+    })
+}
+
+/// Write a DOM's top-level instances in memory and parse them again
+pub fn round_trip(dom: &WeakDom, format: BuildFormat) -> Result<WeakDom, BuildError> {
+    let refs: Vec<Ref> = dom.root().children().to_vec();
+    let mut bytes = Vec::new();
+    if format.is_xml() {
+        rbx_xml::to_writer_default(&mut bytes, dom, &refs).map_err(|e| BuildError::Write(e.to_string()))?;
+        rbx_xml::from_reader_default(bytes.as_slice()).map_err(|e| BuildError::ReadBack(e.to_string()))
+    } else {
+        rbx_binary::to_writer(&mut bytes, dom, &refs).map_err(|e| BuildError::Write(e.to_string()))?;
+        rbx_binary::from_reader(bytes.as_slice()).map_err(|e| BuildError::ReadBack(e.to_string()))
+    }
+}
+
+/// Compare two DOMs instance by instance, matching children by name (and
+/// position among same-named siblings). Returns the paths of instances
+/// missing from `actual` and the properties whose values differ.
+pub fn compare_doms(expected: &WeakDom, actual: &WeakDom) -> (Vec<String>, Vec<LossyProperty>) {
+    let expected_index = index_dom(expected);
+    let actual_index = index_dom(actual);
+    let expected_paths: HashMap<Ref, &str> = expected_index.iter().map(|(p, r)| (*r, p.as_str())).collect();
+    let actual_paths: HashMap<Ref, &str> = actual_index.iter().map(|(p, r)| (*r, p.as_str())).collect();
+    let actual_refs: HashMap<&str, Ref> = actual_index.iter().map(|(p, r)| (p.as_str(), *r)).collect();
+
+    let mut missing = Vec::new();
+    let mut lossy = Vec::new();
+    for (path, referent) in &expected_index {
+        let Some(instance) = expected.get_by_ref(*referent) else {
+            continue;
+        };
+        let Some(other) = actual_refs.get(path.as_str()).and_then(|r| actual.get_by_ref(*r)) else {
+            missing.push(path.clone());
+            continue;
+        };
+        let mut lost = |property: &str, built: String, read_back: Option<String>| {
+            lossy.push(LossyProperty {
+                path: path.clone(),
+                class_name: instance.class.clone(),
+                property: property.to_string(),
+                built,
+                read_back,
+            });
+        };
+        if other.class != instance.class {
+            lost("ClassName", instance.class.clone(), Some(other.class.clone()));
+        }
+
+        let mut properties: Vec<_> = instance.properties.iter().collect();
+        properties.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, value) in properties {
+            match other.properties.get(name) {
+                Some(read) if equivalent(value, read, &expected_paths, &actual_paths) => {}
+                read => lost(name, describe(value), read.map(describe)),
+            }
+        }
+    }
+    (missing, lossy)
+}
+
+/// Every instance below the root with its path, parents first. Repeated
+/// sibling names get `#2`, `#3`... so each path is unique.
+fn index_dom(dom: &WeakDom) -> Vec<(String, Ref)> {
+    let mut index = Vec::new();
+    let mut stack = vec![(String::new(), dom.root_ref())];
+    while let Some((parent_path, parent)) = stack.pop() {
+        let Some(parent_instance) = dom.get_by_ref(parent) else {
+            continue;
+        };
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        for &child in parent_instance.children() {
+            let Some(instance) = dom.get_by_ref(child) else {
+                continue;
+            };
+            let occurrence = seen.entry(instance.name.as_str()).or_insert(0);
+            *occurrence += 1;
+            let mut path = child_instance_path(&parent_path, &instance.name);
+            if *occurrence > 1 {
+                path = format!("{}#{}", path, occurrence);
+            }
+            index.push((path.clone(), child));
+            stack.push((path, child));
+        }
+    }
+    index
+}
+
+/// Whether a value read back is the value that was written
+fn equivalent(a: &Variant, b: &Variant, a_paths: &HashMap<Ref, &str>, b_paths: &HashMap<Ref, &str>) -> bool {
+    let to_u8 = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    match (a, b) {
+        (Variant::Color3(c), Variant::Color3uint8(u)) | (Variant::Color3uint8(u), Variant::Color3(c)) => {
+            (to_u8(c.r), to_u8(c.g), to_u8(c.b)) == (u.r, u.g, u.b)
+        }
+        (Variant::Float32(x), Variant::Float32(y)) => x == y || (x.is_nan() && y.is_nan()),
+        (Variant::Float64(x), Variant::Float64(y)) => x == y || (x.is_nan() && y.is_nan()),
+        (Variant::Ref(x), Variant::Ref(y)) => a_paths.get(x) == b_paths.get(y),
+        _ => a == b,
+    }
+}
+
+/// Short form of a value for reports
+fn describe(value: &Variant) -> String {
+    let text = match value {
+        Variant::String(s) => format!("{:?}", s),
+        other => format!("{:?}", other),
+    };
+    match text.char_indices().nth(80) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rbx_dom_weak::types::Vector3;
+    use rbx_dom_weak::InstanceBuilder;
+
+    #[test]
+    fn test_verify_project_round_trips() {
+        let project = tempfile::tempdir().unwrap();
+        let sss = project.path().join("src/ServerScriptService");
+        std::fs::create_dir_all(&sss).unwrap();
+        std::fs::write(sss.join("Main.server.luau"), "print('hi')").unwrap();
+        std::fs::write(
+            sss.join("Settings.rbxjson"),
+            r#"{ "className": "Part", "properties": {
+                "Size": { "type": "Vector3", "value": { "x": 1, "y": 2.5, "z": 3 } },
+                "Blob": { "type": "SharedString", "value": "AAEC" }
+            } }"#,
+        )
+        .unwrap();
+
+        for format in [BuildFormat::Rbxl, BuildFormat::Rbxlx] {
+            let report = verify_project(project.path(), format).unwrap();
+            assert_eq!(report.instances, 3, "{:?}", format);
+            assert!(report.missing.is_empty(), "{:?}: {:?}", format, report.missing);
+            assert!(report.lossy.is_empty(), "{:?}: {:?}", format, report.lossy);
+            // Only the value json_to_variant can't convert is lost
+            assert_eq!(report.unbuildable.len(), 1);
+            assert!(!report.is_lossless());
+            assert_eq!(report.problems(), 1);
+        }
+    }
+
+    #[test]
+    fn test_compare_doms_reports_changes() {
+        let build = |size: f32, with_child: bool| {
+            let mut dom = WeakDom::new(InstanceBuilder::new("DataModel"));
+            let workspace = dom.insert(dom.root_ref(), InstanceBuilder::new("Workspace").with_name("Workspace"));
+            for _ in 0..2 {
+                dom.insert(
+                    workspace,
+                    InstanceBuilder::new("Part").with_name("Floor").with_property("Size", Vector3::new(size, 1.0, 1.0)),
+                );
+            }
+            if with_child {
+                dom.insert(workspace, InstanceBuilder::new("Folder").with_name("Props"));
+            }
+            dom
+        };
+
+        let (missing, lossy) = compare_doms(&build(4.0, true), &build(4.0, true));
+        assert!(missing.is_empty() && lossy.is_empty());
+
+        let (missing, lossy) = compare_doms(&build(4.0, true), &build(2.0, false));
+        assert_eq!(missing, vec!["Workspace/Props".to_string()]);
+        let paths: Vec<&str> = lossy.iter().map(|l| l.path.as_str()).collect();
+        assert_eq!(paths, vec!["Workspace/Floor", "Workspace/Floor#2"]);
+        assert_eq!(lossy[0].property, "Size");
+    }
+}
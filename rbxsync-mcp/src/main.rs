@@ -4,6 +4,7 @@ use rmcp::{
     schemars, tool, tool_handler, tool_router, ServerHandler, ServiceExt,
     transport::stdio,
 };
+use rbxsync_core::api::{GitBranch, GitRemoteResult, GitStashResult};
 use serde::Deserialize;
 use std::borrow::Cow;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -71,6 +72,84 @@ pub struct GitStatusParams {
     pub project_dir: String,
 }
 
+/// Parameters for git_branch tool
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GitBranchParams {
+    /// The project directory
+    #[schemars(description = "The project directory")]
+    pub project_dir: String,
+    /// Branch to create; lists branches when omitted
+    #[schemars(description = "Branch to create (optional; lists branches when omitted)")]
+    pub name: Option<String>,
+    /// Commit or branch to start from
+    #[schemars(description = "Commit or branch to start the new branch from (optional, default: HEAD)")]
+    pub start_point: Option<String>,
+    /// Check the new branch out
+    #[schemars(description = "Check the new branch out (optional, default: false)")]
+    pub checkout: Option<bool>,
+}
+
+/// Parameters for git_checkout tool
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GitCheckoutParams {
+    /// The project directory
+    #[schemars(description = "The project directory")]
+    pub project_dir: String,
+    /// Branch or commit to check out
+    #[schemars(description = "Branch or commit to check out")]
+    pub target: String,
+}
+
+/// Parameters for git_pull and git_push tools
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GitRemoteParams {
+    /// The project directory
+    #[schemars(description = "The project directory")]
+    pub project_dir: String,
+    /// Remote name
+    #[schemars(description = "Remote (optional, default: the upstream's)")]
+    pub remote: Option<String>,
+    /// Remote branch
+    #[schemars(description = "Branch (optional, default: the upstream / current branch)")]
+    pub branch: Option<String>,
+    /// Pull with --rebase, or push with --set-upstream
+    #[schemars(description = "Pull: rebase instead of merging. Push: set the upstream (optional, default: false)")]
+    pub rebase_or_track: Option<bool>,
+}
+
+/// Parameters for git_stash tool
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GitStashParams {
+    /// The project directory
+    #[schemars(description = "The project directory")]
+    pub project_dir: String,
+    /// push, pop, apply, drop or list
+    #[schemars(description = "push (default), pop, apply, drop or list")]
+    pub action: Option<String>,
+    /// Message for push
+    #[schemars(description = "Stash message for push (optional)")]
+    pub message: Option<String>,
+    /// Stash index for pop, apply and drop
+    #[schemars(description = "Stash index for pop, apply and drop (optional, default: 0)")]
+    pub index: Option<usize>,
+}
+
+/// Parameters for error_summary tool
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ErrorSummaryParams {
+    /// Errors to list
+    #[schemars(description = "Number of most frequent errors to list (optional, default: 5)")]
+    pub top: Option<usize>,
+}
+
+/// Parameters for project_summary tool
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ProjectSummaryParams {
+    /// The project directory
+    #[schemars(description = "The project directory")]
+    pub project_dir: String,
+}
+
 /// Parameters for run_code tool
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct RunCodeParams {
@@ -167,6 +246,30 @@ pub struct BotCommandParams {
     pub args: Option<serde_json::Value>,
 }
 
+/// Parameters for bot_social tool - chat, proximity prompts and dialog choices
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BotSocialParams {
+    /// Social action: chat, prompt, dialog
+    #[schemars(description = "Action: chat, prompt, dialog")]
+    pub action: String,
+    /// Chat message to send (chat)
+    #[schemars(description = "Chat message to send (for chat)")]
+    pub message: Option<String>,
+    /// Luau pattern a reply must match; waits for it when set (chat)
+    #[schemars(description = "Luau pattern to wait for in replies (for chat)")]
+    pub wait_for: Option<String>,
+    /// Seconds to wait for a reply (default: 10, max: 25)
+    #[schemars(description = "Seconds to wait for a reply (default: 10, max: 25)")]
+    pub timeout: Option<f64>,
+    /// Dotted path of the ProximityPrompt or Dialog (prompt, dialog)
+    #[schemars(description = "Path of the ProximityPrompt or Dialog, e.g. Workspace.Shop.Counter.ProximityPrompt")]
+    pub path: Option<String>,
+    /// Dialog choices to pick in order, by text or name (dialog)
+    #[schemars(description = "Dialog choices to select in order, by text or name (for dialog)")]
+    pub choices: Option<Vec<String>>,
+}
+
 /// Parameters for bot_query_server tool - execute Luau code on server during playtest
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct BotQueryServerParams {
@@ -292,6 +395,51 @@ pub struct ReadPropertiesParams {
     pub path: String,
 }
 
+/// Parameters for get_script_source tool
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetScriptSourceParams {
+    /// Script path in the hierarchy (e.g., "ServerScriptService/Main")
+    #[schemars(description = "Script path (e.g., 'ServerScriptService/Main')")]
+    pub path: String,
+}
+
+/// Parameters for set_script_source tool
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SetScriptSourceParams {
+    /// Script path in the hierarchy
+    #[schemars(description = "Script path (e.g., 'ServerScriptService/Main')")]
+    pub path: String,
+    /// The complete new source
+    #[schemars(description = "New source for the script")]
+    pub source: String,
+    /// Project whose .luau file to update. Optional when one project is connected.
+    #[schemars(description = "Project directory (optional when a single project is connected)")]
+    pub project_dir: Option<String>,
+}
+
+/// Parameters for edit_script_lines tool
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct EditScriptLinesParams {
+    /// Script path in the hierarchy
+    #[schemars(description = "Script path (e.g., 'ServerScriptService/Main')")]
+    pub path: String,
+    /// First line to replace (1-based)
+    #[schemars(description = "First line to replace, from 1")]
+    #[serde(rename = "startLine")]
+    pub start_line: usize,
+    /// Last line to replace (inclusive). startLine - 1 inserts before startLine.
+    #[schemars(description = "Last line to replace, inclusive (startLine - 1 to insert)")]
+    #[serde(rename = "endLine")]
+    pub end_line: usize,
+    /// Replacement lines; empty deletes the range
+    #[schemars(description = "Replacement lines (empty to delete)")]
+    #[serde(rename = "newText", default)]
+    pub new_text: String,
+    /// Project whose .luau file to update. Optional when one project is connected.
+    #[schemars(description = "Project directory (optional when a single project is connected)")]
+    pub project_dir: Option<String>,
+}
+
 /// Parameters for explore_hierarchy tool
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ExploreHierarchyParams {
@@ -331,11 +479,53 @@ fn mcp_error(msg: impl Into<String>) -> McpError {
     }
 }
 
+/// HEAD and changed files after a checkout, pull or push
+fn describe_git_result(result: &GitRemoteResult) -> String {
+    let mut lines = vec![result.output.clone()];
+    if result.up_to_date {
+        lines.push(format!("Already up to date on {}", result.head.label()));
+    } else {
+        lines.push(format!("Now on {}", result.head.label()));
+    }
+    if !result.changed_files.is_empty() {
+        lines.push(format!("Changed ({}):", result.changed_files.len()));
+        lines.extend(result.changed_files.iter().map(|f| format!("  ~ {}", f)));
+    }
+    lines.join("\n").trim().to_string()
+}
+
+/// What a set_script_source or edit_script_lines call did, or why it failed
+fn script_edit_summary(path: &str, result: tools::ScriptSourceResponse) -> String {
+    if !result.success {
+        return format!("Failed to edit script: {}", result.error.unwrap_or_default());
+    }
+    match result.file {
+        Some(file) => format!("Updated {} in Studio and {} ({} lines)", path, file, result.line_count),
+        None => format!("Updated {} in Studio ({} lines); no project to write a file to", path, result.line_count),
+    }
+}
+
+/// Port of the server that owns the current project, else the rbxsync server
+/// of this version: the default one, or the port it failed over to when
+/// another version held the default
+fn server_port() -> u16 {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| rbxsync_core::read_workspace_server(&cwd))
+        .map(|entry| entry.port)
+        .or_else(|| {
+            rbxsync_core::registry_path().and_then(|path| {
+                rbxsync_core::port_for_version(&rbxsync_core::load_registry(&path), env!("CARGO_PKG_VERSION"))
+            })
+        })
+        .unwrap_or(rbxsync_core::DEFAULT_PORT)
+}
+
 #[tool_router]
 impl RbxSyncServer {
     pub fn new() -> Self {
         Self {
-            client: RbxSyncClient::new(44755),
+            client: RbxSyncClient::new(server_port()),
             tool_router: Self::tool_router(),
         }
     }
@@ -525,6 +715,140 @@ impl RbxSyncServer {
         }
     }
 
+    /// List branches, or create one.
+    #[tool(description = "List git branches (with upstream, ahead/behind), or create one when a name is given")]
+    async fn git_branch(
+        &self,
+        Parameters(params): Parameters<GitBranchParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let Some(name) = params.name else {
+            let branches: Vec<GitBranch> = self
+                .client
+                .git("branches", serde_json::json!({ "project_dir": params.project_dir }))
+                .await
+                .map_err(|e| mcp_error(e.to_string()))?;
+            let lines: Vec<String> = branches
+                .iter()
+                .map(|b| {
+                    let tracking = b
+                        .upstream
+                        .as_ref()
+                        .map(|u| format!(" [{} +{}/-{}]", u, b.ahead, b.behind))
+                        .unwrap_or_default();
+                    format!("{} {} {} {}{}", if b.current { "*" } else { " " }, b.name, b.commit, b.subject, tracking)
+                })
+                .collect();
+            return Ok(CallToolResult::success(vec![Content::text(lines.join("\n"))]));
+        };
+        let branch: GitBranch = self
+            .client
+            .git(
+                "branch/create",
+                serde_json::json!({
+                    "project_dir": params.project_dir,
+                    "name": name,
+                    "start_point": params.start_point,
+                    "checkout": params.checkout.unwrap_or(false)
+                }),
+            )
+            .await
+            .map_err(|e| mcp_error(e.to_string()))?;
+        Ok(CallToolResult::success(vec![Content::text(format!("Created branch {} at {}", branch.name, branch.commit))]))
+    }
+
+    /// Check out a branch or commit.
+    #[tool(description = "Check out a git branch or commit. Studio is resynced per the project's sync.branchSync setting.")]
+    async fn git_checkout(
+        &self,
+        Parameters(params): Parameters<GitCheckoutParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let result: GitRemoteResult = self
+            .client
+            .git("checkout", serde_json::json!({ "project_dir": params.project_dir, "target": params.target }))
+            .await
+            .map_err(|e| mcp_error(e.to_string()))?;
+        Ok(CallToolResult::success(vec![Content::text(describe_git_result(&result))]))
+    }
+
+    /// Pull from the remote.
+    #[tool(description = "Pull from the git remote; lists the files that changed")]
+    async fn git_pull(
+        &self,
+        Parameters(params): Parameters<GitRemoteParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let body = serde_json::json!({
+            "project_dir": params.project_dir,
+            "remote": params.remote,
+            "branch": params.branch,
+            "rebase": params.rebase_or_track.unwrap_or(false)
+        });
+        let result: GitRemoteResult = self.client.git("pull", body).await.map_err(|e| mcp_error(e.to_string()))?;
+        Ok(CallToolResult::success(vec![Content::text(describe_git_result(&result))]))
+    }
+
+    /// Push to the remote.
+    #[tool(description = "Push to the git remote")]
+    async fn git_push(
+        &self,
+        Parameters(params): Parameters<GitRemoteParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let body = serde_json::json!({
+            "project_dir": params.project_dir,
+            "remote": params.remote,
+            "branch": params.branch,
+            "set_upstream": params.rebase_or_track.unwrap_or(false)
+        });
+        let result: GitRemoteResult = self.client.git("push", body).await.map_err(|e| mcp_error(e.to_string()))?;
+        Ok(CallToolResult::success(vec![Content::text(describe_git_result(&result))]))
+    }
+
+    /// Stash changes, or pop, apply, drop or list stashes.
+    #[tool(description = "git stash: push (default), pop, apply, drop or list")]
+    async fn git_stash(
+        &self,
+        Parameters(params): Parameters<GitStashParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let body = serde_json::json!({
+            "project_dir": params.project_dir,
+            "action": params.action,
+            "message": params.message,
+            "index": params.index
+        });
+        let result: GitStashResult = self.client.git("stash", body).await.map_err(|e| mcp_error(e.to_string()))?;
+        let mut lines = vec![result.output];
+        if result.stashes.is_empty() {
+            lines.push("No stashes.".to_string());
+        }
+        lines.extend(result.stashes.iter().map(|s| format!("stash@{{{}}} {}", s.index, s.message)));
+        Ok(CallToolResult::success(vec![Content::text(lines.join("\n").trim().to_string())]))
+    }
+
+    /// Errors from the Studio console grouped by message and stack, so
+    /// repeated spam reads as one error with a count.
+    #[tool(description = "Get deduplicated errors from the Studio console: number of unique errors, the most frequent ones with counts, first/last seen and file:line. Check this after a playtest or sync instead of reading raw output.")]
+    async fn error_summary(
+        &self,
+        Parameters(params): Parameters<ErrorSummaryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let summary = self.client.get_error_summary(params.top).await.map_err(|e| mcp_error(e.to_string()))?;
+        Ok(CallToolResult::success(vec![Content::text(summary.describe())]))
+    }
+
+    /// Compact overview of a project: service tree with counts, module exports
+    /// with signatures, remotes and constants. Cheaper than reading the files.
+    #[tool(description = "Get a compact project overview (service tree, module function signatures, remotes, constants). Read this before opening individual files.")]
+    async fn project_summary(
+        &self,
+        Parameters(params): Parameters<ProjectSummaryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let markdown = self
+            .client
+            .get_project_summary(&params.project_dir)
+            .await
+            .map_err(|e| mcp_error(e.to_string()))?;
+        Ok(CallToolResult::success(vec![Content::text(markdown)]))
+    }
+
     /// Execute Luau code in Roblox Studio.
     #[tool(description = "Execute Luau code in Roblox Studio")]
     async fn run_code(
@@ -798,6 +1122,49 @@ impl RbxSyncServer {
         ))]))
     }
 
+    /// Social interactions during a playtest: send a chat message and wait for a reply,
+    /// trigger a ProximityPrompt by path, or walk a Dialog tree by choice text.
+    /// Must be called during an active playtest.
+    #[tool(description = "Chat (optionally wait for a reply), trigger a proximity prompt by path, or select dialog choices")]
+    async fn bot_social(
+        &self,
+        Parameters(params): Parameters<BotSocialParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let mut body = serde_json::json!({
+            "action": params.action,
+            "message": params.message,
+            "waitFor": params.wait_for,
+            "timeout": params.timeout,
+            "path": params.path,
+        });
+        if let Some(choices) = &params.choices {
+            body["choice"] = serde_json::json!(choices);
+        }
+
+        let response = self.client
+            .bot_social(body)
+            .await
+            .map_err(|e| mcp_error(e.to_string()))?;
+
+        let result = response.get("result").unwrap_or(&response);
+        if result.get("success").and_then(|v| v.as_bool()) == Some(false) {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Social action '{}' failed: {}",
+                params.action,
+                result.get("error").and_then(|v| v.as_str()).unwrap_or_default()
+            ))]));
+        }
+
+        let result_json = serde_json::to_string_pretty(result.get("result").unwrap_or(result))
+            .unwrap_or_else(|_| "{}".to_string());
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Social action '{}' result:\n{}",
+            params.action,
+            result_json
+        ))]))
+    }
+
     /// Execute Luau code on the game server during an active playtest.
     /// Use this to query game state that only exists on the server (currency, DataStores, services).
     /// Returns the result of the code execution.
@@ -1099,6 +1466,62 @@ impl RbxSyncServer {
         Ok(CallToolResult::success(vec![Content::text(output.join("\n"))]))
     }
 
+    /// Read a script's source as Studio currently has it.
+    #[tool(description = "Read the source of a script in Studio (e.g., 'ServerScriptService/Main')")]
+    async fn get_script_source(
+        &self,
+        Parameters(params): Parameters<GetScriptSourceParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = self.client
+            .get_script_source(&params.path)
+            .await
+            .map_err(|e| mcp_error(e.to_string()))?;
+
+        if !result.success {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Failed to read script: {}",
+                result.error.unwrap_or_default()
+            ))]));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(result.source.unwrap_or_default())]))
+    }
+
+    /// Replace a script's source in Studio and write it to the script's .luau file,
+    /// so small edits don't need a full sync.
+    #[tool(description = "Replace a script's source in Studio and in its .luau file")]
+    async fn set_script_source(
+        &self,
+        Parameters(params): Parameters<SetScriptSourceParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = self.client
+            .set_script_source(&params.path, &params.source, params.project_dir.as_deref())
+            .await
+            .map_err(|e| mcp_error(e.to_string()))?;
+
+        Ok(CallToolResult::success(vec![Content::text(script_edit_summary(&params.path, result))]))
+    }
+
+    /// Replace a range of lines in a script, in Studio and in its .luau file.
+    #[tool(description = "Replace lines startLine..endLine (inclusive, 1-based) of a script in Studio and in its .luau file. Use endLine = startLine - 1 to insert, or empty newText to delete.")]
+    async fn edit_script_lines(
+        &self,
+        Parameters(params): Parameters<EditScriptLinesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = self.client
+            .edit_script_lines(
+                &params.path,
+                params.start_line,
+                params.end_line,
+                &params.new_text,
+                params.project_dir.as_deref(),
+            )
+            .await
+            .map_err(|e| mcp_error(e.to_string()))?;
+
+        Ok(CallToolResult::success(vec![Content::text(script_edit_summary(&params.path, result))]))
+    }
+
     /// Explore the game hierarchy to discover instances.
     /// Returns a tree of instances with their className, name, and childCount.
     /// Use path to start from a specific location, or omit for top-level services.
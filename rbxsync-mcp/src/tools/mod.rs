@@ -296,8 +296,13 @@ pub struct DiffResponse {
 
 impl RbxSyncClient {
     pub fn new(port: u16) -> Self {
+        // Servers started with --auth want the token on mutating requests
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(value) = rbxsync_core::client_token().and_then(|t| reqwest::header::HeaderValue::from_str(&t).ok()) {
+            headers.insert(rbxsync_core::TOKEN_HEADER, value);
+        }
         Self {
-            client: reqwest::Client::new(),
+            client: reqwest::Client::builder().default_headers(headers).build().unwrap_or_default(),
             base_url: format!("http://127.0.0.1:{}", port),
         }
     }
@@ -509,6 +514,24 @@ impl RbxSyncClient {
         Ok(resp)
     }
 
+    /// Call a /git endpoint that answers `{ success, data }` and return `data`
+    pub async fn git<T: serde::de::DeserializeOwned>(&self, endpoint: &str, body: serde_json::Value) -> anyhow::Result<T> {
+        let resp: CommandResponse<serde_json::Value> = self
+            .client
+            .post(format!("{}/git/{}", self.base_url, endpoint))
+            .json(&body)
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        if !resp.success {
+            anyhow::bail!(resp.error.unwrap_or_else(|| format!("git {} failed", endpoint)));
+        }
+        let data = resp.data.ok_or_else(|| anyhow::anyhow!("Missing data in git {} response", endpoint))?;
+        Ok(serde_json::from_value(data)?)
+    }
+
     pub async fn run_code(&self, code: &str) -> anyhow::Result<String> {
         let resp: RunCodeResponse = self
             .client
@@ -746,6 +769,55 @@ impl RbxSyncClient {
             .map_err(|e| anyhow::anyhow!("Failed to parse bot_action response: {}. Body: {}", e, body))
     }
 
+    /// Project summary as Markdown
+    pub async fn get_project_summary(&self, project_dir: &str) -> anyhow::Result<String> {
+        let url = format!("{}/summary", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .query(&[("projectDir", project_dir)])
+            .timeout(std::time::Duration::from_secs(60))
+            .send()
+            .await?;
+
+        let body = response.text().await?;
+        debug_log_response("get_project_summary", &body);
+
+        let resp: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| anyhow::anyhow!("Failed to parse summary response: {}. Body: {}", e, body))?;
+        match resp.get("markdown").and_then(|m| m.as_str()) {
+            Some(markdown) => Ok(markdown.to_string()),
+            None => anyhow::bail!("{}", resp.get("error").and_then(|e| e.as_str()).unwrap_or("Failed to build summary")),
+        }
+    }
+
+    /// Deduplicated errors from the Studio console
+    pub async fn get_error_summary(&self, top: Option<usize>) -> anyhow::Result<rbxsync_core::api::ErrorSummary> {
+        let url = format!("{}/console/errors/summary", self.base_url);
+        let response = self.client.get(&url).query(&[("top", top.unwrap_or(5))]).send().await?;
+        let body = response.text().await?;
+        debug_log_response("get_error_summary", &body);
+        serde_json::from_str(&body).map_err(|e| anyhow::anyhow!("Failed to parse error summary: {}. Body: {}", e, body))
+    }
+
+    /// Chat, trigger a proximity prompt or pick dialog choices
+    pub async fn bot_social(&self, params: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+        let url = format!("{}/bot/social", self.base_url);
+        let response = self
+            .client
+            .post(&url)
+            .json(&params)
+            .timeout(std::time::Duration::from_secs(60))
+            .send()
+            .await?;
+
+        let body = response.text().await?;
+        debug_log_response("bot_social", &body);
+
+        serde_json::from_str(&body)
+            .map_err(|e| anyhow::anyhow!("Failed to parse bot_social response: {}. Body: {}", e, body))
+    }
+
     /// Send generic bot command
     pub async fn bot_command(
         &self,
@@ -960,6 +1032,63 @@ impl RbxSyncClient {
             .map_err(|e| anyhow::anyhow!("Failed to parse read_properties response: {}. Body: {}", e, body))
     }
 
+    /// Read a script's source from Studio
+    pub async fn get_script_source(&self, path: &str) -> anyhow::Result<ScriptSourceResponse> {
+        self.script_request("get", serde_json::json!({ "path": path })).await
+    }
+
+    /// Replace a script's source in Studio and in its .luau file
+    pub async fn set_script_source(
+        &self,
+        path: &str,
+        source: &str,
+        project_dir: Option<&str>,
+    ) -> anyhow::Result<ScriptSourceResponse> {
+        self.script_request(
+            "set",
+            serde_json::json!({ "path": path, "source": source, "projectDir": project_dir }),
+        )
+        .await
+    }
+
+    /// Replace lines `start_line..=end_line` of a script in Studio and in its .luau file
+    pub async fn edit_script_lines(
+        &self,
+        path: &str,
+        start_line: usize,
+        end_line: usize,
+        new_text: &str,
+        project_dir: Option<&str>,
+    ) -> anyhow::Result<ScriptSourceResponse> {
+        self.script_request(
+            "edit-lines",
+            serde_json::json!({
+                "path": path,
+                "startLine": start_line,
+                "endLine": end_line,
+                "newText": new_text,
+                "projectDir": project_dir
+            }),
+        )
+        .await
+    }
+
+    async fn script_request(&self, action: &str, body: serde_json::Value) -> anyhow::Result<ScriptSourceResponse> {
+        let response = self
+            .client
+            .post(format!("{}/script/{}", self.base_url, action))
+            .json(&body)
+            .timeout(std::time::Duration::from_secs(30))
+            .send()
+            .await?;
+
+        let body = response.text().await?;
+        debug_log_response("script", &body);
+
+        serde_json::from_str(&body)
+            .map_err(|e| anyhow::anyhow!("Failed to parse script response: {}. Body: {}", e, body))
+    }
+
     /// Explore the game hierarchy
     pub async fn explore_hierarchy(
         &self,
@@ -1167,6 +1296,22 @@ pub struct ReadPropertiesResponse {
     pub data: Option<serde_json::Value>,
 }
 
+/// Response from the /script endpoints
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptSourceResponse {
+    pub success: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub line_count: u64,
+    /// The .luau file written, when the script belongs to a project
+    #[serde(default)]
+    pub file: Option<String>,
+}
+
 /// Response from explore_hierarchy
 #[derive(Debug, Deserialize)]
 pub struct ExploreHierarchyResponse {
@@ -0,0 +1,36 @@
+//! Sync pipeline benchmarks on synthetic games
+//!
+//! Runs each stage in `rbxsync_server::bench` at 10k, 100k and 500k instances.
+//! Set `RBXSYNC_BENCH_SIZES` (comma-separated) to run other sizes, e.g.
+//! `RBXSYNC_BENCH_SIZES=10000 cargo bench -p rbxsync-server`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rbxsync_server::bench::{Pipeline, STAGES};
+
+const DEFAULT_SIZES: [usize; 3] = [10_000, 100_000, 500_000];
+
+fn sizes() -> Vec<usize> {
+    std::env::var("RBXSYNC_BENCH_SIZES")
+        .ok()
+        .map(|sizes| sizes.split(',').filter_map(|s| s.trim().parse().ok()).collect())
+        .unwrap_or_else(|| DEFAULT_SIZES.to_vec())
+}
+
+fn sync_pipeline(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("sync_pipeline");
+    group.sample_size(10);
+    for size in sizes() {
+        let project = tempfile::tempdir().unwrap();
+        let pipeline = runtime.block_on(Pipeline::new(project.path(), size)).unwrap();
+        for stage in STAGES {
+            group.bench_with_input(BenchmarkId::new(stage, size), &stage, |b, stage| {
+                b.iter(|| runtime.block_on(pipeline.run_stage(stage)).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, sync_pipeline);
+criterion_main!(benches);
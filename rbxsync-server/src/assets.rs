@@ -0,0 +1,460 @@
+//! Extracted assets
+//!
+//! After an extraction, [`record_extraction`] writes the place's asset
+//! references to `assets/manifest.json` (see [`rbxsync_core::assets`]).
+//! `POST /assets/download` fetches the content of every asset in the manifest
+//! that has no file yet, and extraction does the same in the background when
+//! `config.downloadAssets` is set. Assets are fetched through Open Cloud when
+//! `RBXSYNC_OPEN_CLOUD_KEY` is set, and from the public asset delivery
+//! endpoint otherwise; `rbxthumb://` URLs come from the thumbnail API.
+//!
+//! On sync, [`resolve_instances`] turns values naming a downloaded file back
+//! into the asset's URL before they reach Studio.
+//!
+//! `POST /assets/upload` (and `rbxsync asset upload`) goes the other way:
+//! local images, audio and models are uploaded through the Open Cloud Assets
+//! API for the creator in rbxsync.json's `assetUpload`, and the `.rbxjson`
+//! values naming each file are rewritten to the new asset's URL.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use rbxsync_core::assets::{assets_dir, download_source, download_stem, rewrite_file_references, upload_type, AssetSource};
+use rbxsync_core::AssetManifest;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::nightly::OPEN_CLOUD_KEY_ENV;
+use crate::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadRequest {
+    pub project_dir: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DownloadReport {
+    pub downloaded: usize,
+    /// Already downloaded, or built-in `rbxasset://` content
+    pub skipped: usize,
+    pub failed: Vec<DownloadFailure>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DownloadFailure {
+    pub url: String,
+    pub error: String,
+}
+
+/// How long to wait for Open Cloud to finish processing an upload
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize)]
+pub struct UploadRequest {
+    /// Files to upload, relative to the project or absolute
+    pub files: Vec<String>,
+    pub project_dir: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct UploadReport {
+    pub uploaded: Vec<UploadedAsset>,
+    pub failed: Vec<UploadFailure>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadedAsset {
+    pub file: String,
+    pub asset_id: u64,
+    pub url: String,
+    /// Properties rewritten to the new URL, as `file.rbxjson:Property`
+    pub updated: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UploadFailure {
+    pub file: String,
+    pub error: String,
+}
+
+/// `assetUpload` in rbxsync.json: who uploads own, and where the key is
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadConfig {
+    pub user_id: Option<u64>,
+    pub group_id: Option<u64>,
+    /// Environment variable holding the Open Cloud key (default `RBXSYNC_OPEN_CLOUD_KEY`)
+    pub api_key_env: Option<String>,
+}
+
+impl UploadConfig {
+    pub fn load(project_dir: &Path) -> Self {
+        crate::load_project_config(&project_dir.to_string_lossy())
+            .and_then(|config| config.get("assetUpload").cloned())
+            .and_then(|upload| serde_json::from_value(upload).ok())
+            .unwrap_or_default()
+    }
+
+    /// `creationContext.creator` for Open Cloud
+    fn creator(&self) -> Result<Value, String> {
+        match (self.user_id, self.group_id) {
+            (_, Some(group_id)) => Ok(json!({ "groupId": group_id.to_string() })),
+            (Some(user_id), None) => Ok(json!({ "userId": user_id.to_string() })),
+            (None, None) => Err(
+                "No upload creator; add \"assetUpload\": { \"userId\": ... } or { \"groupId\": ... } to rbxsync.json"
+                    .to_string(),
+            ),
+        }
+    }
+
+    fn api_key(&self) -> Result<String, String> {
+        let name = self.api_key_env.as_deref().unwrap_or(OPEN_CLOUD_KEY_ENV);
+        std::env::var(name).map_err(|_| format!("{} is not set", name))
+    }
+}
+
+/// POST /assets/upload
+pub async fn handle_upload(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<UploadRequest>,
+) -> impl IntoResponse {
+    let Some(project_dir) = crate::flags::resolve_project(&state, req.project_dir.as_deref()).await else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "success": false, "error": "project_dir is required when zero or several projects are connected" })),
+        );
+    };
+    if req.files.is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": "No files given" })));
+    }
+    let project_dir = PathBuf::from(project_dir);
+    let files: Vec<PathBuf> = req.files.iter().map(|file| project_dir.join(file)).collect();
+    match upload_assets(&project_dir, &files).await {
+        Ok(report) => (
+            StatusCode::OK,
+            Json(json!({ "success": true, "uploaded": report.uploaded, "failed": report.failed })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(json!({ "success": false, "error": e }))),
+    }
+}
+
+/// POST /assets/download
+pub async fn handle_download(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<DownloadRequest>,
+) -> impl IntoResponse {
+    let Some(project_dir) = crate::flags::resolve_project(&state, req.project_dir.as_deref()).await else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "success": false, "error": "project_dir is required when zero or several projects are connected" })),
+        );
+    };
+    let report = download_assets(Path::new(&project_dir)).await;
+    (
+        StatusCode::OK,
+        Json(json!({
+            "success": true,
+            "downloaded": report.downloaded,
+            "skipped": report.skipped,
+            "failed": report.failed
+        })),
+    )
+}
+
+/// Rewrite `assets/manifest.json` from an extraction's instances. `None` when
+/// `config.extractBinaryAssets` is off, otherwise the number of assets recorded.
+pub fn record_extraction(project_dir: &Path, instances: &[Value], config: Option<&Value>) -> Option<usize> {
+    let mut assets = ExtractionAssets::begin(project_dir, config)?;
+    assets.add(instances);
+    assets.finish(project_dir)
+}
+
+/// An extraction's assets, recorded a chunk at a time for extractions too
+/// large to hold in memory at once
+#[derive(Debug)]
+pub struct ExtractionAssets {
+    manifest: AssetManifest,
+    kinds: HashSet<String>,
+}
+
+impl ExtractionAssets {
+    /// `None` when `config.extractBinaryAssets` is off
+    pub fn begin(project_dir: &Path, config: Option<&Value>) -> Option<Self> {
+        let extraction = config.and_then(|c| c.get("config"));
+        if extraction.and_then(|c| c.get("extractBinaryAssets")).and_then(|v| v.as_bool()) == Some(false) {
+            return None;
+        }
+        let kinds: HashSet<String> = extraction
+            .and_then(|c| c.get("binaryAssetTypes"))
+            .and_then(|t| t.as_array())
+            .map(|types| types.iter().filter_map(|t| t.as_str()).map(str::to_string).collect())
+            .unwrap_or_else(|| rbxsync_core::types::ExtractionConfig::default().binary_asset_types);
+
+        let mut manifest = AssetManifest::load(project_dir);
+        manifest.clear_usage();
+        Some(Self { manifest, kinds })
+    }
+
+    pub fn add(&mut self, instances: &[Value]) {
+        self.manifest.add_usage(instances, &self.kinds);
+    }
+
+    /// Save the manifest; the number of assets recorded, or `None` if it
+    /// couldn't be written
+    pub fn finish(mut self, project_dir: &Path) -> Option<usize> {
+        let recorded = self.manifest.drop_unused();
+        if let Err(e) = self.manifest.save(project_dir) {
+            tracing::warn!("Failed to write asset manifest: {}", e);
+            return None;
+        }
+        tracing::info!("Recorded {} assets in the asset manifest", recorded);
+        Some(recorded)
+    }
+}
+
+/// Whether `config.downloadAssets` is set
+pub fn download_enabled(config: Option<&Value>) -> bool {
+    config
+        .and_then(|c| c.get("config"))
+        .and_then(|c| c.get("downloadAssets"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// Resolve downloaded-file references in instances read from a project
+pub fn resolve_instances(project_dir: &Path, instances: &mut [Value]) {
+    let manifest = AssetManifest::load(project_dir);
+    for instance in instances {
+        for file in rbxsync_core::resolve_assets(instance, &manifest) {
+            tracing::warn!("{} isn't in the asset manifest; sent as written", file);
+        }
+    }
+}
+
+/// Download every asset in the manifest that has no file yet
+pub async fn download_assets(project_dir: &Path) -> DownloadReport {
+    let mut report = DownloadReport::default();
+    let mut manifest = AssetManifest::load(project_dir);
+    let dir = assets_dir(project_dir);
+    let client = reqwest::Client::new();
+
+    for (url, entry) in manifest.assets.iter_mut() {
+        if entry.file.as_ref().is_some_and(|file| project_dir.join(file).is_file()) {
+            report.skipped += 1;
+            continue;
+        }
+        let (Some(source), Some(stem)) = (download_source(url), download_stem(url, entry.kind)) else {
+            report.skipped += 1;
+            continue;
+        };
+        let content = match fetch(&client, &source).await {
+            Ok(content) => content,
+            Err(error) => {
+                report.failed.push(DownloadFailure { url: url.clone(), error });
+                continue;
+            }
+        };
+        let file = dir.join(format!("{}.{}", stem, entry.kind.extension(&content)));
+        let written = file
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&file, &content));
+        match written {
+            Ok(()) => {
+                entry.file = Some(rbxsync_core::path_to_string(file.strip_prefix(project_dir).unwrap_or(&file)));
+                report.downloaded += 1;
+            }
+            Err(e) => report.failed.push(DownloadFailure { url: url.clone(), error: e.to_string() }),
+        }
+    }
+
+    if let Err(e) = manifest.save(project_dir) {
+        tracing::warn!("Failed to write asset manifest: {}", e);
+    }
+    report
+}
+
+/// Upload files through Open Cloud, record them in the manifest and point the
+/// `.rbxjson` values naming them at the new assets. Fails before uploading
+/// anything when there's no key or creator.
+pub async fn upload_assets(project_dir: &Path, files: &[PathBuf]) -> Result<UploadReport, String> {
+    let config = UploadConfig::load(project_dir);
+    let creator = config.creator()?;
+    let key = config.api_key()?;
+    let client = reqwest::Client::new();
+    let mut manifest = AssetManifest::load(project_dir);
+    let mut report = UploadReport::default();
+
+    for file in files {
+        let relative = rbxsync_core::path_to_string(file.strip_prefix(project_dir).unwrap_or(file));
+        let Some((kind, asset_type, content_type)) = upload_type(file) else {
+            report.failed.push(UploadFailure { file: relative, error: "Not an image, audio or model file".to_string() });
+            continue;
+        };
+        let asset_id = match upload(&client, &key, &creator, file, asset_type, content_type).await {
+            Ok(asset_id) => asset_id,
+            Err(error) => {
+                report.failed.push(UploadFailure { file: relative, error });
+                continue;
+            }
+        };
+
+        let url = format!("rbxassetid://{}", asset_id);
+        manifest.record_upload(&url, kind, &relative);
+        let updated = rewrite_file_references(&project_dir.join("src"), &relative, &url).unwrap_or_else(|e| {
+            tracing::warn!("Failed to update references to {}: {}", relative, e);
+            Vec::new()
+        });
+        tracing::info!("Uploaded {} as {}", relative, url);
+        report.uploaded.push(UploadedAsset { file: relative, asset_id, url, updated });
+    }
+
+    if !report.uploaded.is_empty() {
+        if let Err(e) = manifest.save(project_dir) {
+            tracing::warn!("Failed to write asset manifest: {}", e);
+        }
+    }
+    Ok(report)
+}
+
+/// Upload one file and wait for Open Cloud to assign its asset id
+async fn upload(
+    client: &reqwest::Client,
+    key: &str,
+    creator: &Value,
+    file: &Path,
+    asset_type: &str,
+    content_type: &str,
+) -> Result<u64, String> {
+    let content = tokio::fs::read(file).await.map_err(|e| e.to_string())?;
+    let file_name = file.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let display_name = file.file_stem().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let request = json!({
+        "assetType": asset_type,
+        "displayName": display_name,
+        "description": "Uploaded by RbxSync",
+        "creationContext": { "creator": creator }
+    });
+
+    // Open Cloud takes the request and the file as multipart form data
+    let boundary = format!("rbxsync-{}", uuid::Uuid::new_v4().simple());
+    let mut body = format!(
+        "--{b}\r\nContent-Disposition: form-data; name=\"request\"\r\nContent-Type: application/json\r\n\r\n{request}\r\n\
+         --{b}\r\nContent-Disposition: form-data; name=\"fileContent\"; filename=\"{file_name}\"\r\nContent-Type: {content_type}\r\n\r\n",
+        b = boundary,
+    )
+    .into_bytes();
+    body.extend_from_slice(&content);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+    let url = "https://apis.roblox.com/assets/v1/assets";
+    let mut operation = get_json(
+        client
+            .post(url)
+            .header("x-api-key", key)
+            .header("content-type", format!("multipart/form-data; boundary={}", boundary))
+            .body(body),
+        url,
+    )
+    .await?;
+
+    // Uploads are processed asynchronously; poll the operation until it's done
+    let started = std::time::Instant::now();
+    while operation["done"] != true {
+        if started.elapsed() > UPLOAD_TIMEOUT {
+            return Err("Timed out waiting for Open Cloud to process the upload".to_string());
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let path = operation["path"].as_str().ok_or("No operation in the upload response")?;
+        let url = format!("https://apis.roblox.com/assets/v1/{}", path);
+        operation = get_json(client.get(&url).header("x-api-key", key), &url).await?;
+    }
+    operation["response"]["assetId"]
+        .as_str()
+        .and_then(|id| id.parse().ok())
+        .or_else(|| operation["response"]["assetId"].as_u64())
+        .ok_or_else(|| "Open Cloud response had no assetId".to_string())
+}
+
+/// Fetch an asset's content
+async fn fetch(client: &reqwest::Client, source: &AssetSource) -> Result<Vec<u8>, String> {
+    let location = match source {
+        AssetSource::Asset(id) => match std::env::var(OPEN_CLOUD_KEY_ENV) {
+            // Open Cloud answers with where to fetch the content from
+            Ok(key) => {
+                let url = format!("https://apis.roblox.com/asset-delivery-api/v1/assetId/{}", id);
+                let info = get_json(client.get(&url).header("x-api-key", key), &url).await?;
+                info["location"].as_str().ok_or("No location in the asset delivery response")?.to_string()
+            }
+            Err(_) => format!("https://assetdelivery.roblox.com/v1/asset/?id={}", id),
+        },
+        AssetSource::Thumbnail { id, size } => {
+            let url = format!("https://thumbnails.roblox.com/v1/assets?assetIds={}&size={}&format=Png", id, size);
+            let info = get_json(client.get(&url), &url).await?;
+            info["data"][0]["imageUrl"]
+                .as_str()
+                .filter(|u| !u.is_empty())
+                .ok_or("The thumbnail isn't available")?
+                .to_string()
+        }
+    };
+
+    let response = client.get(&location).send().await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", location, response.status()));
+    }
+    response.bytes().await.map(|b| b.to_vec()).map_err(|e| e.to_string())
+}
+
+async fn get_json(request: reqwest::RequestBuilder, url: &str) -> Result<Value, String> {
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        // Open Cloud explains rejections (bad key, moderation, quota) in `message`
+        let body: Value = response.json().await.unwrap_or_default();
+        return Err(match body["message"].as_str() {
+            Some(message) => format!("{} returned {}: {}", url, status, message),
+            None => format!("{} returned {}", url, status),
+        });
+    }
+    response.json().await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_extraction() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let instances = vec![json!({ "path": "Workspace/Music", "className": "Sound", "properties": {
+            "SoundId": { "type": "Content", "value": "rbxassetid://42" }
+        }})];
+
+        assert_eq!(record_extraction(dir.path(), &instances, None), Some(1));
+        let manifest = AssetManifest::load(dir.path());
+        assert!(manifest.assets["rbxassetid://42"].used_by.contains("Workspace/Music.SoundId"));
+
+        let off = json!({ "config": { "extractBinaryAssets": false } });
+        assert_eq!(record_extraction(dir.path(), &instances, Some(&off)), None);
+        let images_only = json!({ "config": { "binaryAssetTypes": ["Image"] } });
+        assert_eq!(record_extraction(dir.path(), &instances, Some(&images_only)), Some(0));
+        assert!(download_enabled(Some(&json!({ "config": { "downloadAssets": true } }))));
+    }
+
+    #[test]
+    fn test_upload_config() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(UploadConfig::load(dir.path()).creator().is_err());
+
+        std::fs::write(dir.path().join("rbxsync.json"), r#"{ "assetUpload": { "groupId": 42, "apiKeyEnv": "RBXSYNC_TEST_UNSET_KEY" } }"#)
+            .unwrap();
+        let config = UploadConfig::load(dir.path());
+        assert_eq!(config.creator().unwrap(), json!({ "groupId": "42" }));
+        assert_eq!(config.api_key().unwrap_err(), "RBXSYNC_TEST_UNSET_KEY is not set");
+    }
+}
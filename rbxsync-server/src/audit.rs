@@ -0,0 +1,167 @@
+//! Audit log of Studio-originated writes
+//!
+//! Edits made in Studio reach the files through `/sync/from-studio`, so git
+//! only learns about them when someone commits, under that person's name.
+//! Each operation is appended to `.rbxsync/audit.jsonl` with the Studio user
+//! and place it came from, which `rbxsync blame` merges into an instance's
+//! history.
+//!
+//! A server with auth also records every mutating request in
+//! `~/.rbxsync/requests.jsonl`, with the identity its token resolved to.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::recovery::JournalOp;
+
+/// Audit log, relative to the project directory
+pub const AUDIT_FILE: &str = ".rbxsync/audit.jsonl";
+
+/// One Studio change written to files
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// Unix seconds when the files were written
+    pub timestamp: u64,
+    /// `create`, `modify`, `delete` or `rename`
+    pub operation: String,
+    /// DataModel path (the new path for renames)
+    pub instance_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    /// Files touched, relative to the project directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    /// Roblox user signed in to Studio
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub place_name: Option<String>,
+}
+
+impl AuditEntry {
+    /// Whether the entry is about `instance_path` (either side of a rename)
+    pub fn concerns(&self, instance_path: &str) -> bool {
+        self.instance_path == instance_path || self.old_path.as_deref() == Some(instance_path)
+    }
+}
+
+pub fn audit_file(project_dir: &Path) -> PathBuf {
+    project_dir.join(AUDIT_FILE)
+}
+
+/// Files a journaled change touches, relative to the project directory
+pub fn journal_files(project_dir: &Path, op: &JournalOp) -> Vec<String> {
+    let paths = match op {
+        JournalOp::Write { path, .. } | JournalOp::Remove { path, .. } | JournalOp::RemoveDir { path, .. } => {
+            vec![path.as_str()]
+        }
+        JournalOp::Rename { from, to } => vec![from.as_str(), to.as_str()],
+        JournalOp::ReplaceSrc { .. } => vec![],
+    };
+    paths
+        .into_iter()
+        .map(|p| {
+            let path = Path::new(p);
+            rbxsync_core::path_to_string(path.strip_prefix(project_dir).unwrap_or(path))
+        })
+        .collect()
+}
+
+/// One mutating request to a server with auth
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestAuditEntry {
+    /// Unix seconds when the response was sent
+    pub timestamp: u64,
+    pub method: String,
+    pub path: String,
+    /// User the token belongs to
+    pub user: String,
+    /// Provider that identified them: `token`, `keychain` or `oidc`
+    pub provider: String,
+    /// HTTP status of the response
+    pub status: u16,
+}
+
+/// `~/.rbxsync/requests.jsonl`
+pub fn request_audit_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".rbxsync").join("requests.jsonl"))
+}
+
+fn append_lines<T: Serialize>(path: &Path, entries: &[T]) -> std::io::Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    for entry in entries {
+        let line = serde_json::to_string(entry).map_err(std::io::Error::other)?;
+        writeln!(file, "{}", line)?;
+    }
+    Ok(())
+}
+
+fn load_lines<T: serde::de::DeserializeOwned>(path: &Path) -> Vec<T> {
+    std::fs::read_to_string(path)
+        .map(|content| content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Append entries to the project's audit log
+pub fn record_entries(project_dir: &Path, entries: &[AuditEntry]) -> std::io::Result<()> {
+    append_lines(&audit_file(project_dir), entries)
+}
+
+/// All entries, oldest first. Lines that don't parse are skipped.
+pub fn load_entries(project_dir: &Path) -> Vec<AuditEntry> {
+    load_lines(&audit_file(project_dir))
+}
+
+/// Append a request to the request audit log at `path`
+pub fn record_request(path: &Path, entry: &RequestAuditEntry) -> std::io::Result<()> {
+    append_lines(path, std::slice::from_ref(entry))
+}
+
+/// All recorded requests, oldest first
+pub fn load_requests(path: &Path) -> Vec<RequestAuditEntry> {
+    load_lines(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_load_audit_entries() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        let write = JournalOp::Write {
+            path: rbxsync_core::path_to_string(&root.join("src/Workspace/Door.rbxjson")),
+            content: "{}".to_string(),
+            previous: None,
+        };
+        let entry = AuditEntry {
+            timestamp: 1_760_600_000,
+            operation: "rename".to_string(),
+            instance_path: "Workspace/Gate".to_string(),
+            old_path: Some("Workspace/Door".to_string()),
+            files: journal_files(root, &write),
+            user_id: Some(42),
+            place_name: Some("Obby".to_string()),
+        };
+        assert_eq!(entry.files, vec!["src/Workspace/Door.rbxjson".to_string()]);
+        assert!(entry.concerns("Workspace/Door") && entry.concerns("Workspace/Gate"));
+        assert!(!entry.concerns("Workspace"));
+
+        assert!(load_entries(root).is_empty());
+        record_entries(root, std::slice::from_ref(&entry)).unwrap();
+        std::fs::OpenOptions::new().append(true).open(audit_file(root)).unwrap().write_all(b"not json\n").unwrap();
+        record_entries(root, std::slice::from_ref(&entry)).unwrap();
+        assert_eq!(load_entries(root), vec![entry.clone(), entry]);
+    }
+}
@@ -0,0 +1,356 @@
+//! Token auth for mutating requests
+//!
+//! Binding to localhost keeps other machines out, but anything running on
+//! this one could still `POST /run` and execute Luau in Studio. With
+//! `rbxsync serve --auth`, every request other than `GET`, `HEAD` and
+//! `OPTIONS` must carry the token from `~/.rbxsync/token` (or `RBXSYNC_TOKEN`)
+//! in the `X-RbxSync-Token` header. Reads stay open so `/health` keeps
+//! working for discovery. When the server listens beyond loopback, reads need
+//! the token as well, since the project's source is readable through them.
+//!
+//! A team server can accept more than its own token. Each [`AuthProvider`]
+//! it was started with gets a turn at mapping the presented token to a user:
+//! [`KeychainUsers`] for per-user tokens (`serve --users`) and
+//! [`crate::oidc::OidcProvider`] for OIDC ID tokens (`serve --oidc-issuer`).
+//! Every mutating request is then recorded in the request audit log with the
+//! identity that made it.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures::future::{BoxFuture, FutureExt};
+use rbxsync_core::TOKEN_HEADER;
+
+use crate::audit::{self, RequestAuditEntry};
+use crate::AppState;
+
+/// Routes called from inside a running game, which can't hold the token.
+/// `/console/ingest` has its own key (see `production`).
+pub const EXEMPT_ROUTES: &[&str] = &["/console/ingest", "/bot/result", "/bot/state", "/bot/lifecycle"];
+
+/// Route prefixes exempt for the same reason: the playtest HTTP mock shim
+/// (see `mocks`), which only reads back the responses in `mocks.json`
+pub const EXEMPT_PREFIXES: &[&str] = &["/mock/"];
+
+/// Who a request came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub user: String,
+    /// Provider that vouched for them: `token`, `keychain` or `oidc`
+    pub provider: &'static str,
+}
+
+/// Maps a presented token to the user it belongs to
+pub trait AuthProvider: Send + Sync {
+    /// The token's owner, or `None` if this provider doesn't know the token
+    fn authenticate<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Option<Identity>>;
+}
+
+/// Per-user tokens kept in the OS keychain, for the users listed in
+/// `~/.rbxsync/users.json` (see `rbxsync users add`)
+pub struct KeychainUsers {
+    /// (user, token)
+    tokens: Vec<(String, String)>,
+}
+
+impl KeychainUsers {
+    pub fn new(tokens: Vec<(String, String)>) -> Self {
+        Self { tokens }
+    }
+
+    /// Read each listed user's token from the keychain. Users whose token is
+    /// missing are skipped with a warning.
+    pub fn load(users_file: &Path) -> Self {
+        let tokens = rbxsync_core::auth::load_users(users_file)
+            .into_iter()
+            .filter_map(|user| match rbxsync_core::auth::keychain_token(&rbxsync_core::auth::user_account(&user)) {
+                Some(token) => Some((user, token)),
+                None => {
+                    tracing::warn!("No keychain token for team user {}; they can't sign in", user);
+                    None
+                }
+            })
+            .collect();
+        Self { tokens }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
+
+impl AuthProvider for KeychainUsers {
+    fn authenticate<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Option<Identity>> {
+        let user = self.tokens.iter().find(|(_, expected)| tokens_match(token, expected));
+        futures::future::ready(user.map(|(user, _)| Identity { user: user.clone(), provider: "keychain" })).boxed()
+    }
+}
+
+/// Whether a request needs the token. `all_methods` covers reads too, for a
+/// server reachable from other machines.
+pub fn requires_token(method: &Method, path: &str, all_methods: bool) -> bool {
+    (all_methods || !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS))
+        && !EXEMPT_ROUTES.contains(&path)
+        && !EXEMPT_PREFIXES.iter().any(|prefix| path.starts_with(prefix))
+}
+
+/// Compare without stopping at the first differing byte
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Whether the request's `Origin`, if it has one, is a page on this machine.
+/// Browsers add the header to cross-site requests; the CLI, the plugin and
+/// editor extensions don't send it.
+pub fn is_local_origin(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    let Some(authority) = origin
+        .to_str()
+        .ok()
+        .and_then(|o| o.strip_prefix("http://").or_else(|| o.strip_prefix("https://")))
+    else {
+        return false;
+    };
+    let host = match authority.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+    crate::is_loopback_host(host)
+}
+
+/// Whether the body is declared as `mime`, ignoring parameters like `charset`
+fn has_content_type(headers: &HeaderMap, mime: &str) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .is_some_and(|v| v.trim().eq_ignore_ascii_case(mime))
+}
+
+/// Refuse what any web page could send on its own: requests from a foreign
+/// `Origin` (403) and bodies not declared as `mime` (415). A page can only
+/// post `application/json` or `application/octet-stream` cross-site after a
+/// CORS preflight, which this server never approves.
+pub fn reject_cross_site(headers: &HeaderMap, mime: &str) -> Option<Response> {
+    let (status, error) = if !is_local_origin(headers) {
+        (StatusCode::FORBIDDEN, "Requests from web pages on other origins are not accepted".to_string())
+    } else if !has_content_type(headers, mime) {
+        (StatusCode::UNSUPPORTED_MEDIA_TYPE, format!("Content-Type must be {}", mime))
+    } else {
+        return None;
+    };
+    Some((status, Json(serde_json::json!({ "success": false, "error": error }))).into_response())
+}
+
+/// The server's own token first, then each provider in turn
+async fn identify(state: &AppState, token: &str) -> Option<Identity> {
+    if state.auth_token.get().is_some_and(|expected| tokens_match(token, expected)) {
+        return Some(Identity { user: "owner".to_string(), provider: "token" });
+    }
+    for provider in state.auth_providers.get().into_iter().flatten() {
+        if let Some(identity) = provider.authenticate(token).await {
+            return Some(identity);
+        }
+    }
+    None
+}
+
+/// Middleware rejecting mutating requests (or, beyond loopback, any request)
+/// without a token some provider accepts, and auditing the ones it lets through
+pub async fn require_token(State(state): State<Arc<AppState>>, mut request: Request, next: Next) -> Response {
+    let has_providers = state.auth_providers.get().is_some_and(|providers| !providers.is_empty());
+    if state.auth_token.get().is_none() && !has_providers {
+        return next.run(request).await;
+    }
+    let all_methods = state.auth_all_methods.load(std::sync::atomic::Ordering::Relaxed);
+    if !requires_token(request.method(), request.uri().path(), all_methods) {
+        return next.run(request).await;
+    }
+
+    let provided = request.headers().get(TOKEN_HEADER).and_then(|v| v.to_str().ok());
+    let identity = match provided {
+        Some(token) => identify(&state, token).await,
+        None => None,
+    };
+    let Some(identity) = identity else {
+        tracing::warn!("Rejected {} {} without a valid token", request.method(), request.uri().path());
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({
+                "success": false,
+                "error": format!(
+                    "Missing or wrong {} header. Clients read the token from ~/.rbxsync/token or `rbxsync login`; run `rbxsync token` to show the server's",
+                    TOKEN_HEADER
+                )
+            })),
+        )
+            .into_response();
+    };
+
+    let mutating = requires_token(request.method(), request.uri().path(), false);
+    let (method, path) = (request.method().to_string(), request.uri().path().to_string());
+    request.extensions_mut().insert(identity.clone());
+    let response = next.run(request).await;
+
+    if let Some(log) = state.request_audit.get().filter(|_| mutating) {
+        let entry = RequestAuditEntry {
+            timestamp: crate::nightly::now_secs(),
+            method,
+            path,
+            user: identity.user,
+            provider: identity.provider.to_string(),
+            status: response.status().as_u16(),
+        };
+        if let Err(e) = audit::record_request(log, &entry) {
+            tracing::warn!("Failed to write request audit log: {}", e);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use tower::Service;
+
+    #[test]
+    fn test_requires_token() {
+        assert!(requires_token(&Method::POST, "/run", false));
+        assert!(requires_token(&Method::DELETE, "/flags", false));
+        assert!(!requires_token(&Method::GET, "/health", false));
+        assert!(!requires_token(&Method::POST, "/bot/lifecycle", false));
+        assert!(requires_token(&Method::GET, "/health", true));
+        assert!(requires_token(&Method::OPTIONS, "/run", true));
+        assert!(!requires_token(&Method::POST, "/bot/lifecycle", true));
+        assert!(!requires_token(&Method::POST, "/mock/api.example.com/scores", true));
+        assert!(requires_token(&Method::POST, "/mocks", false));
+        assert!(tokens_match("abc", "abc"));
+        assert!(!tokens_match("abd", "abc"));
+        assert!(!tokens_match("ab", "abc"));
+    }
+
+    async fn status(state: &Arc<AppState>, method: Method, uri: &str, token: Option<&str>) -> StatusCode {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header(TOKEN_HEADER, token);
+        }
+        let request = builder.body(Body::empty()).unwrap();
+        match crate::create_router(state.clone()).call(request).await {
+            Ok(response) => response.status(),
+            Err(never) => match never {},
+        }
+    }
+
+    #[test]
+    fn test_is_local_origin() {
+        let with_origin = |origin: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ORIGIN, origin.parse().unwrap());
+            is_local_origin(&headers)
+        };
+        assert!(is_local_origin(&HeaderMap::new()));
+        assert!(with_origin("http://localhost:5173"));
+        assert!(with_origin("http://127.0.0.1"));
+        assert!(with_origin("https://[::1]:8443"));
+        assert!(!with_origin("https://evil.example"));
+        assert!(!with_origin("http://localhost.evil.example"));
+        assert!(!with_origin("null"));
+    }
+
+    #[tokio::test]
+    async fn test_loopback_leaves_reads_open() {
+        let state = AppState::new();
+        state.auth_token.set("secret".to_string()).unwrap();
+
+        assert_eq!(status(&state, Method::GET, "/health", None).await, StatusCode::OK);
+        assert_eq!(status(&state, Method::HEAD, "/health", None).await, StatusCode::OK);
+        assert_eq!(status(&state, Method::POST, "/sync/pause", None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(&state, Method::POST, "/sync/pause", Some("secret")).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_non_loopback_guards_every_method() {
+        let state = AppState::new();
+        state.auth_token.set("secret".to_string()).unwrap();
+        state.auth_all_methods.store(true, std::sync::atomic::Ordering::Relaxed);
+
+        for method in [Method::GET, Method::HEAD, Method::OPTIONS] {
+            assert_eq!(status(&state, method, "/health", None).await, StatusCode::UNAUTHORIZED);
+        }
+        assert_eq!(status(&state, Method::GET, "/health", Some("wrong")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(&state, Method::GET, "/health", Some("secret")).await, StatusCode::OK);
+        assert_eq!(status(&state, Method::POST, "/sync/pause", None).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_providers_identify_and_audit_requests() {
+        let temp = tempfile::tempdir().unwrap();
+        let log = temp.path().join("requests.jsonl");
+        let state = AppState::new();
+        let users = KeychainUsers::new(vec![("alice".to_string(), "alice-token".to_string())]);
+        let _ = state.auth_providers.set(vec![Arc::new(users) as Arc<dyn AuthProvider>]);
+        state.request_audit.set(log.clone()).unwrap();
+
+        assert_eq!(status(&state, Method::GET, "/health", None).await, StatusCode::OK);
+        assert_eq!(status(&state, Method::POST, "/sync/pause", Some("bob-token")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(&state, Method::POST, "/sync/pause", Some("alice-token")).await, StatusCode::OK);
+        assert_eq!(status(&state, Method::GET, "/health", Some("alice-token")).await, StatusCode::OK);
+
+        // Only the accepted mutating request is recorded
+        let requests = audit::load_requests(&log);
+        assert_eq!(requests.len(), 1);
+        assert_eq!((requests[0].method.as_str(), requests[0].path.as_str()), ("POST", "/sync/pause"));
+        assert_eq!((requests[0].user.as_str(), requests[0].provider.as_str()), ("alice", "keychain"));
+        assert_eq!(requests[0].status, 200);
+    }
+
+    #[tokio::test]
+    async fn test_mock_shim_works_with_auth() {
+        let project = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(project.path().join(".rbxsync")).unwrap();
+        std::fs::write(
+            project.path().join(crate::mocks::MOCKS_FILE),
+            r#"{ "routes": [{ "url": "api.example.com/scores", "status": 201 }] }"#,
+        )
+        .unwrap();
+        let state = AppState::new();
+        state.auth_token.set("secret".to_string()).unwrap();
+
+        // The shim runs inside the game and has no token, on loopback or off it
+        for all_methods in [false, true] {
+            state.auth_all_methods.store(all_methods, std::sync::atomic::Ordering::Relaxed);
+            for method in [Method::GET, Method::POST] {
+                let request = Request::builder()
+                    .method(method)
+                    .uri("/mock/api.example.com/scores")
+                    .header(crate::mocks::PROJECT_HEADER, rbxsync_core::path_to_string(project.path()))
+                    .body(Body::empty())
+                    .unwrap();
+                let response = match crate::create_router(state.clone()).call(request).await {
+                    Ok(response) => response,
+                    Err(never) => match never {},
+                };
+                assert_eq!(response.status(), StatusCode::CREATED);
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Automatic commit checkpoints
+//!
+//! With `"git": { "autoCommit": true }` in rbxsync.json, the server commits
+//! `src` (and `terrain`) after every successful extraction and every Studio
+//! change written to the files, so the history holds each state Studio was in
+//! and any of them can be diffed or reverted with plain git. Only those paths
+//! are committed: anything else the user has staged stays staged.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+use crate::git;
+
+/// Paths a checkpoint commits, relative to the project
+const CHECKPOINT_PATHS: [&str; 2] = ["src", "terrain"];
+
+/// One commit at a time per server: git holds `index.lock` while committing
+static COMMITTING: Mutex<()> = Mutex::new(());
+
+/// `git.autoCommit` in rbxsync.json
+pub fn enabled(config: &Option<Value>) -> bool {
+    config
+        .as_ref()
+        .and_then(|c| c.get("git")?.get("autoCommit")?.as_bool())
+        .unwrap_or(false)
+}
+
+/// What produced the state being committed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Extraction,
+    StudioSync,
+    /// A scheduled extraction (see [`crate::autosave`])
+    AutoSave,
+}
+
+/// A state of the project to commit
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    pub source: Source,
+    pub place_name: Option<String>,
+    /// Instances extracted, or Studio changes applied
+    pub instances: usize,
+    pub files: usize,
+    /// Extraction session or Studio session id
+    pub session_id: Option<String>,
+}
+
+impl Checkpoint {
+    /// Subject line plus `Key: value` trailers, so the history can be
+    /// searched with `git log --grep`
+    pub fn message(&self) -> String {
+        let place = self.place_name.as_deref().unwrap_or("Studio");
+        let subject = match self.source {
+            Source::Extraction => format!("rbxsync: extract {} ({} instances)", place, self.instances),
+            Source::StudioSync => format!("rbxsync: sync {} change(s) from {}", self.instances, place),
+            Source::AutoSave => format!("rbxsync: auto-save {} ({} instances)", place, self.instances),
+        };
+        let mut trailers = vec![
+            format!(
+                "Rbxsync-Source: {}",
+                match self.source {
+                    Source::Extraction => "extraction",
+                    Source::StudioSync => "studio-sync",
+                    Source::AutoSave => "auto-save",
+                }
+            ),
+            format!("Rbxsync-Instances: {}", self.instances),
+            format!("Rbxsync-Files: {}", self.files),
+        ];
+        if let Some(place) = &self.place_name {
+            trailers.insert(0, format!("Rbxsync-Place: {}", place));
+        }
+        if let Some(session) = &self.session_id {
+            trailers.push(format!("Rbxsync-Session: {}", session));
+        }
+        format!("{}\n\n{}", subject, trailers.join("\n"))
+    }
+}
+
+/// Commit the checkpoint in the background if the project has
+/// `git.autoCommit` on. Failures (no repository, a rebase in progress, no
+/// git identity) are logged and otherwise ignored.
+pub fn spawn(project_dir: &str, config: &Option<Value>, checkpoint: Checkpoint) {
+    if enabled(config) {
+        commit(project_dir, checkpoint);
+    }
+}
+
+/// Commit the checkpoint in the background whatever the setting
+pub fn commit(project_dir: &str, checkpoint: Checkpoint) {
+    let project = PathBuf::from(project_dir);
+    tokio::task::spawn_blocking(move || {
+        let message = checkpoint.message();
+        let _guard = COMMITTING.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match git::commit_paths(&project, &CHECKPOINT_PATHS, &message) {
+            Ok(Some(hash)) => tracing::info!("Auto-commit {}: {}", hash, message.lines().next().unwrap_or("")),
+            Ok(None) => tracing::debug!("Auto-commit skipped: no changes in src"),
+            Err(e) => tracing::warn!("Auto-commit skipped: {}", e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_message_and_setting() {
+        let extraction = Checkpoint {
+            source: Source::Extraction,
+            place_name: Some("Obby".to_string()),
+            instances: 1520,
+            files: 310,
+            session_id: Some("4f1c".to_string()),
+        };
+        assert_eq!(
+            extraction.message(),
+            "rbxsync: extract Obby (1520 instances)\n\nRbxsync-Place: Obby\nRbxsync-Source: extraction\n\
+             Rbxsync-Instances: 1520\nRbxsync-Files: 310\nRbxsync-Session: 4f1c"
+        );
+        let sync = Checkpoint { source: Source::StudioSync, place_name: None, instances: 3, files: 2, session_id: None };
+        assert!(sync.message().starts_with("rbxsync: sync 3 change(s) from Studio\n\nRbxsync-Source: studio-sync"));
+
+        assert!(enabled(&Some(serde_json::json!({ "git": { "autoCommit": true } }))));
+        assert!(!enabled(&Some(serde_json::json!({ "git": {} }))));
+        assert!(!enabled(&None));
+    }
+}
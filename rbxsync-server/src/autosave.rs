@@ -0,0 +1,234 @@
+//! Scheduled extraction ("auto-save from Studio")
+//!
+//! Projects opt in with `extract.autoIntervalMinutes` in rbxsync.json:
+//!
+//! ```json
+//! { "extract": { "autoIntervalMinutes": 30 } }
+//! ```
+//!
+//! Every interval, each project with a connected Studio session gets an
+//! incremental extraction, started by the server the way `rbxsync extract
+//! --incremental` would. Only instances that changed are rewritten, so files
+//! edited locally and not yet synced are left alone unless Studio changed the
+//! same instance. The result is committed (see [`crate::autocommit`]) unless
+//! `extract.autoCommit` is false, so the repository keeps a recent copy of
+//! work done only in Studio.
+//!
+//! A run is skipped, and retried on the next check, while an extraction or
+//! resync is running, live sync is paused, or the project is in safe mode.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::{extract::State, Json};
+use rbxsync_core::api::ExtractStartRequest;
+use serde_json::Value;
+
+use crate::autocommit::{self, Checkpoint, Source};
+use crate::{AppState, ExtractionSession};
+
+/// How often projects are checked for a due auto-save
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long to wait for Studio to send and finalize an extraction
+const EXTRACT_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// A project's auto-save settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoSaveConfig {
+    pub interval: Duration,
+    /// Commit each auto-save (`extract.autoCommit`, default true)
+    pub commit: bool,
+}
+
+/// The `extract` auto-save settings, if `autoIntervalMinutes` is set above 0
+pub fn load_config(config: &Option<Value>) -> Option<AutoSaveConfig> {
+    let extract = config.as_ref()?.get("extract")?;
+    let minutes = extract.get("autoIntervalMinutes")?.as_u64().filter(|m| *m > 0)?;
+    Some(AutoSaveConfig {
+        interval: Duration::from_secs(minutes * 60),
+        commit: extract.get("autoCommit").and_then(|v| v.as_bool()).unwrap_or(true),
+    })
+}
+
+/// Start an incremental extraction of `project_dir`, returning its session id
+async fn start_extract(state: &Arc<AppState>, project_dir: &str) -> Result<String, String> {
+    let request = ExtractStartRequest {
+        project_dir: Some(project_dir.to_string()),
+        incremental: Some(true),
+        ..Default::default()
+    };
+    let start = crate::handler_json(crate::handle_extract_start(State(state.clone()), Json(request)).await).await?;
+    start["sessionId"]
+        .as_str()
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| "the server did not return an extraction session".to_string())
+}
+
+/// Where extraction `session_id` stands: `None` while Studio is still sending
+/// it, otherwise whether it finalized
+fn extract_outcome(session: Option<&ExtractionSession>, session_id: &str) -> Option<Result<(), String>> {
+    match session {
+        Some(s) if s.id != session_id => Some(Err("another extraction replaced it".to_string())),
+        Some(s) if s.finalized => Some(Ok(())),
+        Some(_) => None,
+        None => Some(Err("the extraction session was discarded".to_string())),
+    }
+}
+
+/// Wait for the plugin to finalize extraction `session_id`
+async fn wait_for_extract(state: &Arc<AppState>, session_id: &str) -> Result<(), String> {
+    let deadline = Instant::now() + EXTRACT_TIMEOUT;
+    loop {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        if let Some(outcome) = extract_outcome(state.extraction_session.read().await.as_ref(), session_id) {
+            return outcome;
+        }
+        if Instant::now() > deadline {
+            return Err("timed out waiting for Studio to send the game".to_string());
+        }
+    }
+}
+
+/// Wait for the extraction of `project_dir` to finish and commit the result
+/// if configured
+async fn run_auto_save(
+    state: &Arc<AppState>,
+    project_dir: &str,
+    config: &AutoSaveConfig,
+    session_id: &str,
+) -> Result<(), String> {
+    wait_for_extract(state, session_id).await?;
+
+    if config.commit {
+        // Finalize recorded the run; its totals go in the commit
+        let run = crate::stats::load_runs(Path::new(project_dir))
+            .into_iter()
+            .rev()
+            .find(|run| run.direction == crate::stats::EXTRACT);
+        let place = crate::connected_place(state, project_dir).await;
+        autocommit::commit(
+            project_dir,
+            Checkpoint {
+                source: Source::AutoSave,
+                place_name: place.as_ref().map(|p| p.place_name.clone()),
+                instances: run.as_ref().map(|r| r.instances).unwrap_or(0),
+                files: run.as_ref().map(|r| r.files).unwrap_or(0),
+                session_id: place.and_then(|p| p.session_id),
+            },
+        );
+    }
+    Ok(())
+}
+
+/// Projects with a Studio session connected
+async fn connected_projects(state: &AppState) -> Vec<String> {
+    let mut projects: Vec<String> = state
+        .place_registry
+        .read()
+        .await
+        .values()
+        .map(|place| place.project_dir.clone())
+        .filter(|dir| !dir.is_empty())
+        .collect();
+    projects.sort();
+    projects.dedup();
+    projects
+}
+
+/// Background task: run due auto-saves. The first one for a project comes a
+/// full interval after the server first sees it connected.
+///
+/// Each auto-save waits for Studio and commits on its own task, so a slow
+/// project doesn't hold up the others. The server has one extraction session,
+/// so a project that comes due while another's extraction is in flight is
+/// picked up on a later check.
+pub async fn run_scheduler(state: Arc<AppState>) {
+    let mut last_run: HashMap<String, Instant> = HashMap::new();
+    let running: Arc<std::sync::Mutex<HashSet<String>>> = Arc::default();
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+        if crate::idle::is_idle(&state) {
+            continue;
+        }
+        let projects = connected_projects(&state).await;
+        last_run.retain(|dir, _| projects.contains(dir));
+        for project_dir in projects {
+            if running.lock().unwrap().contains(&project_dir) {
+                continue;
+            }
+            let Some(config) = load_config(&crate::load_project_config(&project_dir)) else {
+                continue;
+            };
+            let since = *last_run.entry(project_dir.clone()).or_insert_with(Instant::now);
+            if since.elapsed() < config.interval {
+                continue;
+            }
+            if let Some(reason) = crate::branch::blocked(&state, &project_dir).await {
+                tracing::debug!("Auto-save of {} waiting: {}", project_dir, reason);
+                continue;
+            }
+            if state.extraction_session.read().await.as_ref().is_some_and(|s| !s.finalized) {
+                continue;
+            }
+
+            tracing::info!("Auto-save: extracting {} from Studio", project_dir);
+            last_run.insert(project_dir.clone(), Instant::now());
+            let session_id = match start_extract(&state, &project_dir).await {
+                Ok(id) => id,
+                Err(e) => {
+                    tracing::warn!("Auto-save of {} failed: {}", project_dir, e);
+                    continue;
+                }
+            };
+            running.lock().unwrap().insert(project_dir.clone());
+            let (state, running) = (state.clone(), running.clone());
+            tokio::spawn(async move {
+                if let Err(e) = run_auto_save(&state, &project_dir, &config, &session_id).await {
+                    tracing::warn!("Auto-save of {} failed: {}", project_dir, e);
+                }
+                running.lock().unwrap().remove(&project_dir);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_save_config() {
+        let config = |value: Value| load_config(&Some(value));
+        assert_eq!(
+            config(serde_json::json!({ "extract": { "autoIntervalMinutes": 30 } })),
+            Some(AutoSaveConfig { interval: Duration::from_secs(1800), commit: true })
+        );
+        assert_eq!(
+            config(serde_json::json!({ "extract": { "autoIntervalMinutes": 5, "autoCommit": false } })),
+            Some(AutoSaveConfig { interval: Duration::from_secs(300), commit: false })
+        );
+        assert_eq!(config(serde_json::json!({ "extract": { "autoIntervalMinutes": 0 } })), None);
+        assert_eq!(config(serde_json::json!({ "extract": {} })), None);
+        assert_eq!(load_config(&None), None);
+    }
+
+    #[test]
+    fn test_extract_outcome_matches_session() {
+        let project = tempfile::tempdir().unwrap();
+        let project_dir = rbxsync_core::path_to_string(project.path());
+        let mut session = ExtractionSession::open("mine", Some(&project_dir));
+        assert_eq!(extract_outcome(Some(&session), "mine"), None);
+        session.finalized = true;
+        assert_eq!(extract_outcome(Some(&session), "mine"), Some(Ok(())));
+
+        // Someone else's finished extraction is not ours
+        let mut other = ExtractionSession::open("other", Some(&project_dir));
+        other.finalized = true;
+        assert!(matches!(extract_outcome(Some(&other), "mine"), Some(Err(_))));
+        assert!(matches!(extract_outcome(None, "mine"), Some(Err(_))));
+    }
+}
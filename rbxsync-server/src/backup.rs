@@ -0,0 +1,369 @@
+//! Compressed src backups
+//!
+//! Extraction replaces `src/` wholesale. Rather than keeping a full copy of the
+//! previous tree, which doubles the disk footprint of large projects, the old
+//! tree is streamed into `.rbxsync-backup/src-YYYYMMDD-HHMMSS.tar.zst` and then
+//! removed. Archives are plain tar + zstd, so they can also be opened with
+//! `tar --zstd -xf`. Restores can be limited to a few paths, which only
+//! decompresses the archive once.
+//!
+//! Old archives are deleted according to the `backups` section of rbxsync.json:
+//!
+//! ```json
+//! { "backups": { "keep": 5, "maxSizeMb": 2048 } }
+//! ```
+//!
+//! The newest archive is always kept. Creating and restoring archives is
+//! blocking work; async callers run it with `spawn_blocking`.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Backup directory, relative to the project
+pub const BACKUP_DIR: &str = ".rbxsync-backup";
+
+const ARCHIVE_PREFIX: &str = "src-";
+const ARCHIVE_SUFFIX: &str = ".tar.zst";
+
+/// zstd level: fast, and still far smaller than the tree it replaces
+const COMPRESSION_LEVEL: i32 = 3;
+
+fn default_keep() -> usize {
+    5
+}
+
+fn default_max_size_mb() -> u64 {
+    2048
+}
+
+/// `backups` section of rbxsync.json
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupConfig {
+    /// Archives to keep (0 keeps all)
+    #[serde(default = "default_keep")]
+    pub keep: usize,
+    /// Total size of all archives before the oldest are deleted (0 for no limit)
+    #[serde(default = "default_max_size_mb")]
+    pub max_size_mb: u64,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            keep: default_keep(),
+            max_size_mb: default_max_size_mb(),
+        }
+    }
+}
+
+/// A backup archive on disk
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub name: String,
+    /// Snapshot id, the archive's timestamp (YYYYMMDD-HHMMSS), with a `-N`
+    /// counter for the Nth archive made within the same second
+    pub id: String,
+    /// Compressed size in bytes
+    pub size: u64,
+    /// UTC creation time, YYYY-MM-DDTHH:MM:SSZ
+    pub created_at: String,
+}
+
+/// Read a project's backup settings
+pub fn load_config(project_dir: &Path) -> BackupConfig {
+    crate::load_project_config(&project_dir.to_string_lossy())
+        .and_then(|c| c.get("backups").cloned())
+        .and_then(|b| serde_json::from_value(b).ok())
+        .unwrap_or_default()
+}
+
+pub fn backup_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join(BACKUP_DIR)
+}
+
+/// Backup archives of a project, newest first
+pub fn list_backups(project_dir: &Path) -> Vec<BackupInfo> {
+    let Ok(entries) = std::fs::read_dir(backup_dir(project_dir)) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<BackupInfo> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let stamp = name.strip_prefix(ARCHIVE_PREFIX)?.strip_suffix(ARCHIVE_SUFFIX)?;
+            let (date, time, _) = parse_stamp(stamp)?;
+            Some(BackupInfo {
+                created_at: format!(
+                    "{}-{}-{}T{}:{}:{}Z",
+                    &date[..4], &date[4..6], &date[6..], &time[..2], &time[2..4], &time[4..]
+                ),
+                size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                id: stamp.to_string(),
+                name,
+            })
+        })
+        .collect();
+    // By time, then counter: as names, `-2` would sort before the base name
+    // and `-10` before `-2`
+    backups.sort_by(|a, b| parse_stamp(&b.id).cmp(&parse_stamp(&a.id)));
+    backups
+}
+
+/// Split an archive id into (date, time, counter), the base archive of a
+/// second counting as 1
+fn parse_stamp(stamp: &str) -> Option<(&str, &str, u32)> {
+    let mut parts = stamp.split('-');
+    let (date, time) = (parts.next()?, parts.next()?);
+    let counter = match parts.next() {
+        Some(n) => n.parse().ok().filter(|n| *n >= 2)?,
+        None => 1,
+    };
+    let digits = |s: &str, len| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+    (digits(date, 8) && digits(time, 6) && parts.next().is_none()).then_some((date, time, counter))
+}
+
+/// Path of a backup archive by name or snapshot id, or the newest if `name`
+/// is `None`
+pub fn find_backup(project_dir: &Path, name: Option<&str>) -> Option<PathBuf> {
+    let backups = list_backups(project_dir);
+    let backup = match name {
+        Some(name) => backups.into_iter().find(|b| b.name == name || b.id == name)?,
+        None => backups.into_iter().next()?,
+    };
+    Some(backup_dir(project_dir).join(backup.name))
+}
+
+/// Delete archives beyond the newest `keep`, then the oldest until the total
+/// fits in `max_size_mb`; returns removed names
+pub fn apply_retention(project_dir: &Path, config: &BackupConfig) -> Vec<String> {
+    let max_bytes = config.max_size_mb * 1024 * 1024;
+    let mut total = 0;
+    let mut removed = Vec::new();
+    for (i, backup) in list_backups(project_dir).into_iter().enumerate() {
+        total += backup.size;
+        let over_count = config.keep > 0 && i >= config.keep;
+        let over_size = max_bytes > 0 && total > max_bytes && i > 0;
+        if (over_count || over_size) && std::fs::remove_file(backup_dir(project_dir).join(&backup.name)).is_ok() {
+            removed.push(backup.name);
+        }
+    }
+    removed
+}
+
+/// Chunk files the server writes into src/ while an extraction is running
+fn is_extraction_temp(relative: &Path) -> bool {
+    let name = relative.to_string_lossy();
+    relative.components().count() == 1 && name.starts_with("chunk_") && name.ends_with(".json")
+}
+
+/// Archive `src/` into a new backup and apply the retention policy.
+///
+/// Returns `None` when there was nothing worth backing up.
+pub fn create_backup(project_dir: &Path) -> io::Result<Option<BackupInfo>> {
+    let src_dir = project_dir.join("src");
+    if !src_dir.is_dir() {
+        return Ok(None);
+    }
+    let dir = backup_dir(project_dir);
+    std::fs::create_dir_all(&dir)?;
+
+    let (date, time) = rbxsync_core::defines::utc_stamps(crate::nightly::now_secs());
+    let mut name = format!("{}{}-{}{}", ARCHIVE_PREFIX, date, time, ARCHIVE_SUFFIX);
+    let mut n = 2;
+    while dir.join(&name).exists() {
+        name = format!("{}{}-{}-{}{}", ARCHIVE_PREFIX, date, time, n, ARCHIVE_SUFFIX);
+        n += 1;
+    }
+
+    // Write under a temporary name so a crash never leaves a truncated archive
+    let partial = dir.join(format!("{}.partial", name));
+    let files = match write_archive(&src_dir, &partial) {
+        Ok(files) => files,
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial);
+            return Err(e);
+        }
+    };
+    if files == 0 {
+        std::fs::remove_file(&partial)?;
+        return Ok(None);
+    }
+    std::fs::rename(&partial, dir.join(&name))?;
+
+    let removed = apply_retention(project_dir, &load_config(project_dir));
+    if !removed.is_empty() {
+        tracing::info!("Removed {} old backups: {}", removed.len(), removed.join(", "));
+    }
+    Ok(list_backups(project_dir).into_iter().find(|b| b.name == name))
+}
+
+/// Stream `src_dir` into a zstd-compressed tar at `archive`; returns the
+/// file count
+fn write_archive(src_dir: &Path, archive: &Path) -> io::Result<usize> {
+    let encoder = zstd::Encoder::new(BufWriter::new(File::create(archive)?), COMPRESSION_LEVEL)?;
+    let mut builder = tar::Builder::new(encoder);
+    let mut files = 0;
+    append_dir(&mut builder, src_dir, src_dir, &mut files)?;
+    builder.into_inner()?.finish()?.flush()?;
+    Ok(files)
+}
+
+fn append_dir<W: Write>(builder: &mut tar::Builder<W>, root: &Path, dir: &Path, files: &mut usize) -> io::Result<()> {
+    let mut entries: Vec<_> = std::fs::read_dir(dir)?.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            builder.append_dir(relative, &path)?;
+            append_dir(builder, root, &path, files)?;
+        } else if file_type.is_file() && !is_extraction_temp(relative) {
+            builder.append_path_with_name(&path, relative)?;
+            *files += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Whether an archive entry should be restored, given the requested paths
+fn is_selected(name: &str, paths: &[String]) -> bool {
+    paths.is_empty()
+        || paths.iter().any(|p| {
+            let p = p.trim_matches('/');
+            name == p || name.strip_prefix(p).is_some_and(|rest| rest.starts_with('/'))
+        })
+}
+
+/// Extract an archive into `dest`, limited to `paths` (relative to src/, with
+/// their descendants) unless it's empty. Existing files at the selected paths
+/// are overwritten; returns the number of files written.
+pub fn restore(archive: &Path, dest: &Path, paths: &[String]) -> io::Result<usize> {
+    let mut archive = tar::Archive::new(zstd::Decoder::new(File::open(archive)?)?);
+    let mut written = 0;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.to_string_lossy().replace('\\', "/");
+        let name = path.trim_end_matches('/');
+        let safe = Path::new(name).components().all(|c| matches!(c, Component::Normal(_)));
+        if !safe || name.is_empty() || !is_selected(name, paths) {
+            continue;
+        }
+        let is_file = entry.header().entry_type().is_file();
+        if entry.unpack_in(dest)? && is_file {
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+/// Replace `dest` entirely with the contents of an archive
+pub fn restore_all(archive: &Path, dest: &Path) -> io::Result<usize> {
+    if dest.exists() {
+        std::fs::remove_dir_all(dest)?;
+    }
+    std::fs::create_dir_all(dest)?;
+    restore(archive, dest, &[])
+}
+
+/// Restore some paths from an archive, removing what's there now first so
+/// files deleted since the backup don't linger
+pub fn restore_paths(archive: &Path, dest: &Path, paths: &[String]) -> io::Result<usize> {
+    for path in paths {
+        let relative = Path::new(path.trim_matches('/'));
+        if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid path: {}", path)));
+        }
+        let target = dest.join(relative);
+        if target.is_dir() {
+            std::fs::remove_dir_all(&target)?;
+        } else if target.exists() {
+            std::fs::remove_file(&target)?;
+        }
+    }
+    restore(archive, dest, paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_round_trip() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let project = temp.path();
+        let write = |path: &str, content: &str| {
+            let path = project.join("src").join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        let long_dir = "Deep".repeat(30);
+        write("ServerScriptService/Main.server.luau", "print(1)");
+        write(&format!("Workspace/{}/Part.rbxjson", long_dir), "{}");
+        write("chunk_000001.json", "[]");
+        std::fs::create_dir_all(project.join("src/ReplicatedStorage/Empty")).unwrap();
+
+        let info = create_backup(project).unwrap().unwrap();
+        assert!(info.name.starts_with("src-") && info.name.ends_with(".tar.zst"));
+        let archive = find_backup(project, None).unwrap();
+        let id = &list_backups(project)[0].id;
+        assert_eq!(format!("src-{}.tar.zst", id), info.name);
+        assert_eq!(find_backup(project, Some(id)), Some(archive.clone()));
+
+        let restored = temp.path().join("restored");
+        assert_eq!(restore_all(&archive, &restored).unwrap(), 2);
+        assert_eq!(
+            std::fs::read_to_string(restored.join("ServerScriptService/Main.server.luau")).unwrap(),
+            "print(1)"
+        );
+        assert!(restored.join(format!("Workspace/{}/Part.rbxjson", long_dir)).exists());
+        assert!(restored.join("ReplicatedStorage/Empty").is_dir());
+        assert!(!restored.join("chunk_000001.json").exists());
+
+        // Selective restore replaces only the requested subtree
+        std::fs::write(restored.join("ServerScriptService/Main.server.luau"), "changed").unwrap();
+        std::fs::write(restored.join("ServerScriptService/New.luau"), "new").unwrap();
+        std::fs::remove_dir_all(restored.join("Workspace")).unwrap();
+        assert_eq!(restore_paths(&archive, &restored, &["ServerScriptService".to_string()]).unwrap(), 1);
+        assert!(!restored.join("ServerScriptService/New.luau").exists());
+        assert!(!restored.join("Workspace").exists());
+
+        let config = BackupConfig { keep: 1, max_size_mb: 0 };
+        std::fs::write(backup_dir(project).join("src-20200101-000000.tar.zst"), b"old").unwrap();
+        assert_eq!(apply_retention(project, &config), vec!["src-20200101-000000.tar.zst"]);
+        assert_eq!(list_backups(project).len(), 1);
+    }
+
+    #[test]
+    fn test_same_second_backups_order_by_counter() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let project = temp.path();
+        std::fs::create_dir_all(backup_dir(project)).unwrap();
+        for name in [
+            "src-20240101-120000.tar.zst",
+            "src-20240101-120000-2.tar.zst",
+            "src-20240101-120000-10.tar.zst",
+            "src-20231231-235959-3.tar.zst",
+        ] {
+            std::fs::write(backup_dir(project).join(name), b"archive").unwrap();
+        }
+
+        let ids: Vec<String> = list_backups(project).into_iter().map(|b| b.id).collect();
+        assert_eq!(ids, ["20240101-120000-10", "20240101-120000-2", "20240101-120000", "20231231-235959-3"]);
+        assert_eq!(
+            find_backup(project, None),
+            Some(backup_dir(project).join("src-20240101-120000-10.tar.zst"))
+        );
+
+        let config = BackupConfig { keep: 2, max_size_mb: 0 };
+        assert_eq!(
+            apply_retention(project, &config),
+            vec!["src-20240101-120000.tar.zst", "src-20231231-235959-3.tar.zst"]
+        );
+    }
+}
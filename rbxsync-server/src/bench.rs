@@ -0,0 +1,325 @@
+//! Sync pipeline benchmarks
+//!
+//! Drives the stages that have fallen over on large games (RBXSYNC-26)
+//! through the router, on a synthetic extraction: finalize (chunks in, files
+//! out), read-tree, diff against a simulated Studio, and incremental sync with
+//! 1% of files touched. `benches/sync_pipeline.rs` runs them under criterion at
+//! 10k/100k/500k instances; `rbxsync bench` runs a small pass and compares it
+//! against a saved baseline.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{create_router, AppState, PluginResponse};
+
+/// Stages in the order they run
+pub const STAGES: [&str; 4] = ["finalize", "read-tree", "diff", "incremental"];
+
+/// Instances per extraction chunk, as the plugin sends them
+const CHUNK_SIZE: usize = 500;
+
+/// Children per synthetic folder
+const FOLDER_SIZE: usize = 50;
+
+const SERVICES: [&str; 3] = ["Workspace", "ReplicatedStorage", "ServerScriptService"];
+
+/// Median time of one stage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageResult {
+    pub stage: String,
+    pub instances: usize,
+    pub millis: f64,
+}
+
+/// A stage that got slower than the baseline allows
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Regression {
+    pub stage: String,
+    pub baseline_millis: f64,
+    pub millis: f64,
+    /// Slowdown in percent
+    pub percent: f64,
+}
+
+/// Extraction chunk data for a synthetic game of about `count` instances:
+/// folders of parts across three services, every tenth child a ModuleScript
+pub fn synthetic_instances(count: usize) -> Vec<Value> {
+    let mut instances: Vec<Value> = SERVICES
+        .iter()
+        .map(|service| json!({ "path": service, "name": service, "className": service, "properties": {} }))
+        .collect();
+    let mut folder = 0;
+    while instances.len() < count {
+        let service = SERVICES[folder % SERVICES.len()];
+        let folder_path = format!("{}/Folder_{}", service, folder);
+        instances.push(json!({
+            "path": folder_path,
+            "name": format!("Folder_{}", folder),
+            "className": "Folder",
+            "referenceId": format!("{:032x}", instances.len()),
+            "properties": {}
+        }));
+        for child in 0..FOLDER_SIZE.min(count.saturating_sub(instances.len())) {
+            let reference = format!("{:032x}", instances.len());
+            let instance = if child % 10 == 0 {
+                let name = format!("Module_{}", child);
+                json!({
+                    "path": format!("{}/{}", folder_path, name),
+                    "name": name,
+                    "className": "ModuleScript",
+                    "referenceId": reference,
+                    "properties": {
+                        "Source": { "type": "string", "value": format!("local M = {{}}\nfunction M.run()\n\treturn {}\nend\nreturn M\n", child) }
+                    }
+                })
+            } else {
+                let name = format!("Part_{}", child);
+                json!({
+                    "path": format!("{}/{}", folder_path, name),
+                    "name": name,
+                    "className": "Part",
+                    "referenceId": reference,
+                    "properties": {
+                        "Anchored": { "type": "bool", "value": true },
+                        "Size": { "type": "Vector3", "value": { "x": 4, "y": 1, "z": 2 } },
+                        "CFrame": { "type": "CFrame", "value": {
+                            "position": [child as f64, 0.0, folder as f64],
+                            "rotation": [1, 0, 0, 0, 1, 0, 0, 0, 1]
+                        } },
+                        "Color": { "type": "Color3", "value": { "r": 0.5, "g": 0.5, "b": 0.5 } }
+                    }
+                })
+            };
+            instances.push(instance);
+        }
+        folder += 1;
+    }
+    instances
+}
+
+/// A synthetic project and a server on a local port to run the stages against
+pub struct Pipeline {
+    state: Arc<AppState>,
+    client: reqwest::Client,
+    base_url: String,
+    server: tokio::task::JoinHandle<()>,
+    project_dir: PathBuf,
+    instances: Vec<Value>,
+}
+
+impl Drop for Pipeline {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+impl Pipeline {
+    /// Extract the synthetic game into `project_dir/src` and mark it synced
+    pub async fn new(project_dir: &Path, count: usize) -> Result<Self, String> {
+        std::fs::create_dir_all(project_dir).map_err(|e| e.to_string())?;
+        let state = AppState::new();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.map_err(|e| e.to_string())?;
+        let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+        let router = create_router(state.clone());
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, router).await;
+        });
+        let pipeline = Self {
+            state,
+            client: reqwest::Client::new(),
+            base_url: format!("http://127.0.0.1:{}", port),
+            server,
+            project_dir: project_dir.to_path_buf(),
+            instances: synthetic_instances(count),
+        };
+        pipeline.finalize().await?;
+        pipeline.post("/sync/incremental", json!({ "project_dir": pipeline.dir(), "mark_synced": true })).await?;
+        pipeline.touch_files(100)?;
+        Ok(pipeline)
+    }
+
+    pub fn instance_count(&self) -> usize {
+        self.instances.len()
+    }
+
+    fn dir(&self) -> String {
+        self.project_dir.to_string_lossy().to_string()
+    }
+
+    async fn post(&self, uri: &str, body: Value) -> Result<Value, String> {
+        let response = self
+            .client
+            .post(format!("{}{}", self.base_url, uri))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status();
+        let value: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() || value.get("success") == Some(&Value::Bool(false)) {
+            return Err(format!("{} failed ({}): {}", uri, status, value));
+        }
+        Ok(value)
+    }
+
+    /// Push every `every`th file's modification time past the last sync
+    fn touch_files(&self, every: usize) -> Result<(), String> {
+        let mut files = Vec::new();
+        crate::summary::collect_files(&self.project_dir.join("src"), &mut files);
+        files.sort();
+        let later = SystemTime::now() + Duration::from_secs(3600);
+        for file in files.iter().step_by(every.max(1)) {
+            std::fs::File::options()
+                .write(true)
+                .open(file)
+                .and_then(|f| f.set_modified(later))
+                .map_err(|e| format!("{}: {}", file.display(), e))?;
+        }
+        Ok(())
+    }
+
+    /// Upload the extraction in chunks and write it to src
+    pub async fn finalize(&self) -> Result<(), String> {
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let chunks: Vec<&[Value]> = self.instances.chunks(CHUNK_SIZE).collect();
+        for (index, chunk) in chunks.iter().enumerate() {
+            self.post(
+                "/extract/chunk",
+                json!({
+                    "session_id": session_id,
+                    "chunk_index": index,
+                    "total_chunks": chunks.len(),
+                    "data": chunk,
+                    "project_dir": self.dir()
+                }),
+            )
+            .await?;
+        }
+        self.post("/extract/finalize", json!({ "project_dir": self.dir() })).await?;
+        Ok(())
+    }
+
+    pub async fn read_tree(&self) -> Result<(), String> {
+        self.post("/sync/read-tree", json!({ "project_dir": self.dir() })).await.map(|_| ())
+    }
+
+    /// Diff the files against a Studio that has exactly the synthetic game
+    pub async fn diff(&self) -> Result<(), String> {
+        let paths: Vec<Value> = self
+            .instances
+            .iter()
+            .map(|i| json!({ "path": i["path"], "className": i["className"], "properties": i["properties"] }))
+            .collect();
+        let state = self.state.clone();
+        let studio = tokio::spawn(async move {
+            loop {
+                let mut waiter = state.wakeups.subscribe(None, None);
+                let request = state.request_queue.lock().await.pop_front();
+                match request {
+                    Some(request) if request.command == "studio:paths" => {
+                        if let Some(tx) = state.response_channels.read().await.get(&request.id) {
+                            let _ = tx.send(PluginResponse {
+                                id: request.id,
+                                success: true,
+                                data: json!({ "paths": paths }),
+                                error: None,
+                            });
+                        }
+                        return;
+                    }
+                    Some(_) => {}
+                    None => waiter.wait().await,
+                }
+            }
+        });
+        let result = self.post("/diff", json!({ "project_dir": self.dir() })).await;
+        studio.abort();
+        result.map(|_| ())
+    }
+
+    pub async fn incremental(&self) -> Result<(), String> {
+        self.post("/sync/incremental", json!({ "project_dir": self.dir() })).await.map(|_| ())
+    }
+
+    pub async fn run_stage(&self, stage: &str) -> Result<(), String> {
+        match stage {
+            "finalize" => self.finalize().await,
+            "read-tree" => self.read_tree().await,
+            "diff" => self.diff().await,
+            "incremental" => self.incremental().await,
+            _ => Err(format!("Unknown stage '{}'", stage)),
+        }
+    }
+}
+
+/// Median time of each stage over `iterations` runs on a game of `count` instances
+pub async fn run(project_dir: &Path, count: usize, iterations: usize) -> Result<Vec<StageResult>, String> {
+    let pipeline = Pipeline::new(project_dir, count).await?;
+    let mut results = Vec::new();
+    for stage in STAGES {
+        let mut times = Vec::new();
+        for _ in 0..iterations.max(1) {
+            let started = Instant::now();
+            pipeline.run_stage(stage).await?;
+            times.push(started.elapsed().as_secs_f64() * 1000.0);
+        }
+        times.sort_by(f64::total_cmp);
+        results.push(StageResult {
+            stage: stage.to_string(),
+            instances: pipeline.instance_count(),
+            millis: times[times.len() / 2],
+        });
+    }
+    Ok(results)
+}
+
+/// Stages more than `max_percent` slower than the baseline run of the same size
+pub fn compare(baseline: &[StageResult], current: &[StageResult], max_percent: f64) -> Vec<Regression> {
+    current
+        .iter()
+        .filter_map(|result| {
+            let base = baseline
+                .iter()
+                .find(|b| b.stage == result.stage && b.instances == result.instances)?;
+            let percent = (result.millis / base.millis.max(f64::EPSILON) - 1.0) * 100.0;
+            (percent > max_percent).then(|| Regression {
+                stage: result.stage.clone(),
+                baseline_millis: base.millis,
+                millis: result.millis,
+                percent,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pipeline_stages_and_compare() {
+        let instances = synthetic_instances(120);
+        assert_eq!(instances.len(), 120);
+        assert_eq!(instances[3]["path"], "Workspace/Folder_0");
+        assert_eq!(instances[4]["className"], "ModuleScript");
+
+        let project = tempfile::tempdir().unwrap();
+        let results = run(project.path(), 120, 1).await.unwrap();
+        assert_eq!(results.iter().map(|r| r.stage.as_str()).collect::<Vec<_>>(), STAGES);
+        assert!(project.path().join("src/Workspace/Folder_0/Module_0.luau").exists());
+        assert!(project.path().join("src/ServerScriptService/Folder_2/Part_1.rbxjson").exists());
+
+        let result = |stage: &str, millis| StageResult { stage: stage.to_string(), instances: 120, millis };
+        let baseline = vec![result("finalize", 100.0), result("diff", 10.0)];
+        let current = vec![result("finalize", 110.0), result("diff", 20.0), result("read-tree", 5.0)];
+        let regressions = compare(&baseline, &current, 25.0);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!((regressions[0].stage.as_str(), regressions[0].percent), ("diff", 100.0));
+    }
+}
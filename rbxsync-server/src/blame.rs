@@ -0,0 +1,302 @@
+//! Change history for one instance
+//!
+//! `rbxsync blame <instance-path>` maps a DataModel path to the files that
+//! hold it (its `.rbxjson`, script file, or folder with `_meta.rbxjson` and
+//! init script), runs `git log` on them, blames each property in the
+//! `.rbxjson` to the commit that last touched it, and merges in the audit log
+//! of Studio-originated writes. The result is one history, newest first, that
+//! answers "who changed this part and when" for instances that aren't scripts.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::audit;
+
+/// Files that can describe an instance, relative to its mapped path
+const FILE_SUFFIXES: &[&str] = &[".rbxjson", ".server.luau", ".client.luau", ".luau"];
+/// Files inside an instance's directory that describe the instance itself
+const DIR_FILES: &[&str] = &["_meta.rbxjson", "init.server.luau", "init.client.luau", "init.luau"];
+
+/// The commit that last changed one property
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyBlame {
+    /// Property name; attributes are `Attributes.Name`
+    pub property: String,
+    /// Short commit hash, or `None` for changes not committed yet
+    pub commit: Option<String>,
+    pub author: String,
+    /// Unix seconds
+    pub timestamp: u64,
+    pub summary: String,
+}
+
+/// One change to the instance, from git or from Studio
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    /// Unix seconds
+    pub timestamp: u64,
+    /// `git` or `studio`
+    pub source: String,
+    pub author: String,
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceHistory {
+    pub instance_path: String,
+    /// Files holding the instance, relative to the project directory
+    pub files: Vec<String>,
+    pub properties: Vec<PropertyBlame>,
+    pub history: Vec<HistoryEntry>,
+    /// Why parts of the history may be missing (no git repository, ...)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+/// Files holding an instance, relative to the project directory
+pub fn instance_files(project_dir: &Path, instance_path: &str) -> Vec<String> {
+    let dir = project_dir.to_string_lossy();
+    let mapping = crate::get_tree_mapping(&crate::load_project_config(&dir));
+    let fs_path = crate::apply_tree_mapping(instance_path.trim_matches('/'), &mapping);
+    let base = PathBuf::from("src").join(&fs_path);
+
+    let mut files: Vec<PathBuf> = FILE_SUFFIXES
+        .iter()
+        .map(|suffix| rbxsync_core::pathbuf_with_suffix(&base, suffix))
+        .collect();
+    files.extend(DIR_FILES.iter().map(|name| base.join(name)));
+    files
+        .into_iter()
+        .filter(|f| project_dir.join(f).is_file())
+        .map(|f| rbxsync_core::path_to_string(&f))
+        .collect()
+}
+
+fn git(project_dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(project_dir)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Commits touching the files, newest first
+fn git_history(project_dir: &Path, files: &[String], limit: usize) -> Result<Vec<HistoryEntry>, String> {
+    let limit = format!("-n{}", limit);
+    let mut args = vec!["log", "--format=%H%x1f%an%x1f%at%x1f%s", limit.as_str(), "--"];
+    args.extend(files.iter().map(String::as_str));
+    let output = git(project_dir, &args)?;
+
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\x1f');
+            let (commit, author, time, summary) = (fields.next()?, fields.next()?, fields.next()?, fields.next()?);
+            let changed = git(project_dir, &["show", "--format=", "--name-only", commit, "--"])
+                .ok()
+                .map(|names| names.lines().filter(|n| files.iter().any(|f| f == n)).map(str::to_string).collect())
+                .unwrap_or_default();
+            Some(HistoryEntry {
+                timestamp: time.parse().ok()?,
+                source: "git".to_string(),
+                author: author.to_string(),
+                summary: summary.to_string(),
+                commit: Some(commit.to_string()),
+                files: changed,
+            })
+        })
+        .collect())
+}
+
+/// Last change per property, from `git blame --line-porcelain` of a pretty-printed .rbxjson.
+///
+/// `newest_first` lists commits newest first (as `git log` prints them) and
+/// orders commits made within the same second.
+pub fn parse_property_blame(porcelain: &str, newest_first: &[String]) -> Vec<PropertyBlame> {
+    let rank = |blame: &PropertyBlame| match &blame.commit {
+        None => 0,
+        Some(commit) => newest_first.iter().position(|c| c.starts_with(commit.as_str())).map_or(usize::MAX, |i| i + 1),
+    };
+    let mut blames: Vec<PropertyBlame> = Vec::new();
+    let mut section: Option<&str> = None;
+    let mut current: Option<String> = None;
+    let (mut commit, mut author, mut time, mut summary) = ("", "", 0u64, "");
+
+    for line in porcelain.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            // Sections are top-level keys (2 spaces); their entries are 4 spaces deep
+            if let Some(key) = content.strip_prefix("  \"").and_then(|k| k.split('"').next()) {
+                section = matches!(key, "properties" | "attributes").then_some(key);
+                current = None;
+                if section.is_some() {
+                    continue;
+                }
+            }
+            if content.starts_with("  }") {
+                section = None;
+                current = None;
+                continue;
+            }
+            let Some(section) = section else {
+                continue;
+            };
+            if let Some(key) = content.strip_prefix("    \"").and_then(|k| k.split('"').next()) {
+                current = Some(match section {
+                    "attributes" => format!("Attributes.{}", key),
+                    _ => key.to_string(),
+                });
+            }
+            let Some(property) = &current else {
+                continue;
+            };
+            let uncommitted = commit.bytes().all(|b| b == b'0');
+            let blame = PropertyBlame {
+                property: property.clone(),
+                commit: (!uncommitted).then(|| commit.chars().take(7).collect()),
+                author: if uncommitted { "(uncommitted)".to_string() } else { author.to_string() },
+                timestamp: time,
+                summary: summary.to_string(),
+            };
+            match blames.iter_mut().find(|b| b.property == *property) {
+                Some(existing)
+                    if (uncommitted && existing.commit.is_some())
+                        || (existing.timestamp, std::cmp::Reverse(rank(existing)))
+                            < (blame.timestamp, std::cmp::Reverse(rank(&blame))) =>
+                {
+                    *existing = blame
+                }
+                Some(_) => {}
+                None => blames.push(blame),
+            }
+        } else if let Some(value) = line.strip_prefix("author ") {
+            author = value;
+        } else if let Some(value) = line.strip_prefix("author-time ") {
+            time = value.parse().unwrap_or(0);
+        } else if let Some(value) = line.strip_prefix("summary ") {
+            summary = value;
+        } else if let Some(hash) = line.split(' ').next().filter(|h| h.len() == 40) {
+            commit = hash;
+        }
+    }
+    blames
+}
+
+/// Change history of one instance, newest first
+pub fn blame(project_dir: &Path, instance_path: &str, limit: usize) -> Result<InstanceHistory, String> {
+    let instance_path = instance_path.trim_matches('/').to_string();
+    let files = instance_files(project_dir, &instance_path);
+
+    let mut history: Vec<HistoryEntry> = audit::load_entries(project_dir)
+        .into_iter()
+        .filter(|e| e.concerns(&instance_path))
+        .map(|e| HistoryEntry {
+            timestamp: e.timestamp,
+            source: "studio".to_string(),
+            author: match (e.user_id, &e.place_name) {
+                (Some(user), Some(place)) => format!("Studio user {} ({})", user, place),
+                (Some(user), None) => format!("Studio user {}", user),
+                (None, Some(place)) => format!("Studio ({})", place),
+                (None, None) => "Studio".to_string(),
+            },
+            summary: match &e.old_path {
+                Some(old) => format!("rename from {}", old),
+                None => e.operation.clone(),
+            },
+            commit: None,
+            files: e.files,
+        })
+        .collect();
+    if files.is_empty() && history.is_empty() {
+        return Err(format!("No files or Studio changes found for {}", instance_path));
+    }
+
+    let mut result = InstanceHistory {
+        instance_path,
+        ..Default::default()
+    };
+    if !files.is_empty() {
+        let commits = git_history(project_dir, &files, usize::MAX).unwrap_or_else(|e| {
+            result.notes.push(format!("No git history: {}", e));
+            Vec::new()
+        });
+        let newest_first: Vec<String> = commits.iter().filter_map(|c| c.commit.clone()).collect();
+        for file in files.iter().filter(|f| f.ends_with(".rbxjson")) {
+            if let Ok(porcelain) = git(project_dir, &["blame", "--line-porcelain", "--", file]) {
+                result.properties.extend(parse_property_blame(&porcelain, &newest_first));
+            }
+        }
+        history.extend(commits.into_iter().take(limit).map(|mut c| {
+            c.commit = c.commit.map(|h| h.chars().take(7).collect());
+            c
+        }));
+    }
+
+    history.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+    history.truncate(limit);
+    result.files = files;
+    result.history = history;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instance_files_and_property_blame() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        std::fs::write(root.join("rbxsync.json"), r#"{ "treeMapping": { "Workspace": "world" } }"#).unwrap();
+        std::fs::create_dir_all(root.join("src/world/Door")).unwrap();
+        std::fs::write(root.join("src/world/Door.rbxjson"), "{}").unwrap();
+        std::fs::write(root.join("src/world/Door/init.server.luau"), "").unwrap();
+        assert_eq!(
+            instance_files(root, "/Workspace/Door"),
+            vec!["src/world/Door.rbxjson".to_string(), "src/world/Door/init.server.luau".to_string()]
+        );
+        assert!(blame(root, "Workspace/Missing", 10).is_err());
+
+        let header = |hash: &str, author: &str, time: u64| {
+            format!("{} 1 1 1\nauthor {}\nauthor-time {}\nsummary Change by {}\nfilename x\n", hash, author, time, author)
+        };
+        let (a, b, local) = ("a".repeat(40), "b".repeat(40), "0".repeat(40));
+        let porcelain = [
+            (a.as_str(), "Ana", 100, "{"),
+            (a.as_str(), "Ana", 100, "  \"className\": \"Part\","),
+            (a.as_str(), "Ana", 100, "  \"properties\": {"),
+            (a.as_str(), "Ana", 100, "    \"Size\": {"),
+            // Same second as Ana's commit; git log order says Ben's is newer
+            (b.as_str(), "Ben", 100, "      \"value\": { \"x\": 4 }"),
+            (a.as_str(), "Ana", 100, "    },"),
+            (a.as_str(), "Ana", 100, "    \"Anchored\": { \"type\": \"bool\", \"value\": true }"),
+            (a.as_str(), "Ana", 100, "  },"),
+            (local.as_str(), "Not Committed Yet", 300, "  \"attributes\": {"),
+            (local.as_str(), "Not Committed Yet", 300, "    \"Speed\": 5"),
+            (a.as_str(), "Ana", 100, "  }"),
+            (a.as_str(), "Ana", 100, "}"),
+        ]
+        .iter()
+        .map(|(hash, author, time, content)| format!("{}\t{}\n", header(hash, author, *time), content))
+        .collect::<String>();
+
+        let blames = parse_property_blame(&porcelain, &[b.clone(), a.clone()]);
+        let names: Vec<&str> = blames.iter().map(|b| b.property.as_str()).collect();
+        assert_eq!(names, vec!["Size", "Anchored", "Attributes.Speed"]);
+        assert_eq!((blames[0].author.as_str(), blames[0].commit.as_deref()), ("Ben", Some("bbbbbbb")));
+        assert_eq!(blames[1].summary, "Change by Ana");
+        assert_eq!((blames[2].author.as_str(), blames[2].commit.as_deref()), ("(uncommitted)", None));
+    }
+}
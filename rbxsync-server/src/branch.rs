@@ -0,0 +1,356 @@
+//! Branch-aware sync
+//!
+//! Switching branches rewrites hundreds of files at once. Live sync sees
+//! them as a burst of unrelated edits, and incremental sync goes by mtimes,
+//! which a checkout doesn't order usefully. The server reads `.git/HEAD` of
+//! every live-synced project every few seconds. When it moves, the project
+//! is marked for a full resync: the files are diffed with Studio, every
+//! instance is pushed and orphans are deleted, and the log says which branch
+//! Studio now represents.
+//!
+//! `sync.branchSync` in rbxsync.json picks what happens: `confirm` (the
+//! default) waits for `rbxsync sync resync` or `POST /git/branch-sync`,
+//! `auto` resyncs once HEAD has settled, and `off` only logs the switch.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use rbxsync_core::api::{DiffRequest, ReadTreeRequest, SyncBatchRequest};
+use serde::{Deserialize, Serialize};
+
+use crate::git::{self, GitHead};
+use crate::AppState;
+
+/// How often HEAD is read
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// HEAD has to stay put this long before an automatic resync; a rebase
+/// moves it several times
+const SETTLE: Duration = Duration::from_secs(3);
+
+/// `sync.branchSync` in rbxsync.json
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BranchSyncMode {
+    Auto,
+    #[default]
+    Confirm,
+    Off,
+}
+
+/// A project's branch mode from its config
+pub fn mode(config: &Option<serde_json::Value>) -> BranchSyncMode {
+    match config.as_ref().and_then(|c| c.get("sync")?.get("branchSync")?.as_str()) {
+        Some("auto") => BranchSyncMode::Auto,
+        Some("off") => BranchSyncMode::Off,
+        _ => BranchSyncMode::Confirm,
+    }
+}
+
+/// Branch state of one live-synced project
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBranch {
+    /// HEAD as last read
+    pub head: GitHead,
+    /// What Studio was last fully synced from, if a resync has run
+    pub studio: Option<GitHead>,
+    /// HEAD moved and the full resync hasn't run yet
+    pub pending: bool,
+    pub running: bool,
+    #[serde(skip)]
+    head_file: String,
+    #[serde(skip)]
+    changed_at: Instant,
+}
+
+/// HEAD of every live-synced project
+#[derive(Debug, Default)]
+pub struct BranchTracker {
+    projects: Mutex<HashMap<String, ProjectBranch>>,
+}
+
+impl BranchTracker {
+    /// Record HEAD's contents for a project. Returns the previous and new
+    /// HEAD when it moved; `resolve` only runs then (or the first time).
+    pub fn observe(&self, project_dir: &str, head_file: &str, resolve: impl FnOnce() -> Option<GitHead>) -> Option<(GitHead, GitHead)> {
+        let mut projects = self.projects.lock().ok()?;
+        if projects.get(project_dir).is_some_and(|p| p.head_file == head_file) {
+            return None;
+        }
+        let head = resolve()?;
+        let Some(project) = projects.get_mut(project_dir) else {
+            projects.insert(
+                project_dir.to_string(),
+                ProjectBranch {
+                    head,
+                    studio: None,
+                    pending: false,
+                    running: false,
+                    head_file: head_file.to_string(),
+                    changed_at: Instant::now(),
+                },
+            );
+            return None;
+        };
+        let previous = std::mem::replace(&mut project.head, head.clone());
+        project.head_file = head_file.to_string();
+        project.changed_at = Instant::now();
+        // Back where Studio already is (e.g. checking out a branch and returning)
+        project.pending = project.studio.as_ref() != Some(&head);
+        Some((previous, head))
+    }
+
+    /// Claim a project's resync: a pending one that has settled, or any
+    /// project when `forced`. Returns the HEAD being synced.
+    pub fn start(&self, project_dir: &str, forced: bool) -> Option<GitHead> {
+        let mut projects = self.projects.lock().ok()?;
+        let project = projects.get_mut(project_dir)?;
+        let settled = project.pending && project.changed_at.elapsed() >= SETTLE;
+        if project.running || !(forced || settled) {
+            return None;
+        }
+        project.running = true;
+        Some(project.head.clone())
+    }
+
+    /// A resync ended; `synced` is the HEAD Studio now matches, if it worked
+    pub fn finish(&self, project_dir: &str, synced: Option<GitHead>) {
+        let Ok(mut projects) = self.projects.lock() else { return };
+        let Some(project) = projects.get_mut(project_dir) else { return };
+        project.running = false;
+        if let Some(head) = synced {
+            // HEAD may have moved again while syncing
+            project.pending = project.head != head;
+            project.studio = Some(head);
+        }
+    }
+
+    pub fn is_running(&self, project_dir: &str) -> bool {
+        self.projects.lock().ok().and_then(|p| p.get(project_dir).map(|p| p.running)).unwrap_or(false)
+    }
+
+    /// Drop a pending resync without running it
+    pub fn dismiss(&self, project_dir: &str) {
+        if let Some(project) = self.projects.lock().ok().as_mut().and_then(|p| p.get_mut(project_dir)) {
+            project.pending = false;
+        }
+    }
+
+    /// Forget projects no longer live-synced
+    fn retain(&self, project_dirs: &[String]) {
+        if let Ok(mut projects) = self.projects.lock() {
+            projects.retain(|dir, _| project_dirs.contains(dir));
+        }
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, ProjectBranch> {
+        self.projects.lock().map(|p| p.iter().map(|(k, v)| (k.clone(), v.clone())).collect()).unwrap_or_default()
+    }
+}
+
+/// Why a resync can't run now
+pub(crate) async fn blocked(state: &AppState, project_dir: &str) -> Option<&'static str> {
+    if state.live_sync_paused.load(std::sync::atomic::Ordering::Relaxed) {
+        return Some("an extraction is running");
+    }
+    if state.sync_pause.is_paused() {
+        return Some("live sync is paused");
+    }
+    crate::recovery::check_safe_mode(state, project_dir).await;
+    if state.safe_mode_projects.read().await.contains_key(project_dir) {
+        return Some("the project is in safe mode");
+    }
+    if !state.place_registry.read().await.values().any(|place| place.project_dir == project_dir) {
+        return Some("no Studio session is connected to the project");
+    }
+    None
+}
+
+/// Diff the files with Studio and push everything: every instance is
+/// updated and orphans are deleted. Returns the number of operations sent.
+pub async fn full_resync(state: &Arc<AppState>, project_dir: &str) -> Result<usize, String> {
+    push_tree(state, project_dir, project_dir).await
+}
+
+/// Make Studio match the tree in `tree_dir` (a project directory, or a copy
+/// of one such as a snapshot), sending the batch as `project_dir`'s
+pub(crate) async fn push_tree(state: &Arc<AppState>, project_dir: &str, tree_dir: &str) -> Result<usize, String> {
+    // Queued live sync batches are covered by this sync
+    if let Some(queue) = state.project_queues.write().await.get_mut(project_dir) {
+        queue.retain(|request| !crate::priority::is_live_sync(request));
+    }
+
+    let read_tree = ReadTreeRequest { project_dir: tree_dir.to_string(), variant: None, paths: Vec::new() };
+    let tree = crate::handler_json(crate::handle_sync_read_tree(Json(read_tree)).await).await?;
+    let mut operations: Vec<serde_json::Value> = tree["instances"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|instance| serde_json::json!({ "type": "update", "path": instance.get("path"), "data": instance }))
+        .collect();
+
+    let diff_request = DiffRequest { project_dir: tree_dir.to_string(), include_properties: Some(false) };
+    let diff = crate::handler_json(crate::handle_diff(State(state.clone()), Json(diff_request)).await).await?;
+    for entry in diff["removed"].as_array().into_iter().flatten() {
+        operations.push(serde_json::json!({ "type": "delete", "path": entry["path"] }));
+    }
+
+    let count = operations.len();
+    let batch = SyncBatchRequest { operations, project_dir: Some(project_dir.to_string()), paths: Vec::new() };
+    crate::handler_json(crate::handle_sync_batch(State(state.clone()), Json(batch)).await).await?;
+
+    // Studio matches the files now; incremental sync starts from here
+    if tree_dir == project_dir {
+        state.sync_state.write().await.entry(project_dir.to_string()).or_default().last_sync = Some(std::time::SystemTime::now());
+    }
+    Ok(count)
+}
+
+/// Run a claimed resync and record the outcome
+async fn run_resync(state: &Arc<AppState>, project_dir: &str, head: GitHead) -> Result<usize, String> {
+    tracing::info!("Full resync of {} for {}", project_dir, head.label());
+    let result = full_resync(state, project_dir).await;
+    match &result {
+        Ok(count) => {
+            tracing::info!("Studio now represents {} of {} ({} operations)", head.label(), project_dir, count);
+            state.branches.finish(project_dir, Some(head));
+        }
+        Err(e) => {
+            tracing::warn!("Full resync of {} failed: {}", project_dir, e);
+            state.branches.finish(project_dir, None);
+        }
+    }
+    result
+}
+
+/// Directories with live sync on
+async fn live_synced_projects(state: &AppState) -> Vec<String> {
+    let watcher = state.file_watcher_state.read().await;
+    watcher.watched_dirs.iter().filter(|dir| !watcher.event_only_dirs.contains(*dir)).cloned().collect()
+}
+
+/// Watch HEAD of every live-synced project (spawned at startup)
+pub async fn run_watcher(state: Arc<AppState>) {
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        if crate::idle::is_idle(&state) {
+            continue;
+        }
+        let projects = live_synced_projects(&state).await;
+        state.branches.retain(&projects);
+        for project_dir in projects {
+            let Some(head_file) = git::read_head_file(Path::new(&project_dir)) else {
+                continue;
+            };
+            let branch_mode = mode(&crate::load_project_config(&project_dir));
+            let moved = state.branches.observe(&project_dir, &head_file, || git::read_head(Path::new(&project_dir)));
+            if let Some((previous, head)) = moved {
+                tracing::info!("HEAD of {} moved: {} -> {}", project_dir, previous.label(), head.label());
+                match branch_mode {
+                    BranchSyncMode::Off => state.branches.dismiss(&project_dir),
+                    BranchSyncMode::Confirm => tracing::warn!(
+                        "Studio may not match {} any more. Run `rbxsync sync resync` to diff and push the whole project",
+                        head.label()
+                    ),
+                    BranchSyncMode::Auto => {}
+                }
+            }
+
+            if branch_mode != BranchSyncMode::Auto {
+                continue;
+            }
+            if let Some(reason) = blocked(&state, &project_dir).await {
+                tracing::debug!("Branch resync of {} waiting: {}", project_dir, reason);
+                continue;
+            }
+            if let Some(head) = state.branches.start(&project_dir, false) {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    let _ = run_resync(&state, &project_dir, head).await;
+                });
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchSyncRequest {
+    pub project_dir: String,
+}
+
+/// Branch state of live-synced projects (GET /git/branch-sync)
+pub async fn handle_status(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    Json(serde_json::json!({ "success": true, "projects": state.branches.snapshot() }))
+}
+
+/// Run the full resync now, e.g. to confirm one held after a branch switch
+/// (POST /git/branch-sync)
+pub async fn handle_resync(State(state): State<Arc<AppState>>, Json(req): Json<BranchSyncRequest>) -> impl IntoResponse {
+    let project_dir = req.project_dir;
+    if let Some(reason) = blocked(&state, &project_dir).await {
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "success": false, "error": format!("Can't resync now: {}", reason) })),
+        );
+    }
+    if state.branches.is_running(&project_dir) {
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "success": false, "error": "A resync of this project is already running" })),
+        );
+    }
+    let result = match state.branches.start(&project_dir, true) {
+        Some(head) => run_resync(&state, &project_dir, head.clone()).await.map(|count| (count, head)),
+        // Live sync is off for the project, so nothing tracks its HEAD
+        None => {
+            let head = git::read_head(Path::new(&project_dir)).unwrap_or(GitHead { branch: None, commit: None });
+            full_resync(&state, &project_dir).await.map(|count| (count, head))
+        }
+    };
+    match result {
+        Ok((count, head)) => (StatusCode::OK, Json(serde_json::json!({ "success": true, "operations": count, "head": head }))),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "success": false, "error": e }))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head(branch: &str) -> Option<GitHead> {
+        Some(GitHead { branch: Some(branch.to_string()), commit: Some("1a2b3c4".to_string()) })
+    }
+
+    #[test]
+    fn test_branch_switch_marks_resync() {
+        let tracker = BranchTracker::default();
+        let dir = "/games/obby";
+        assert_eq!(tracker.observe(dir, "ref: refs/heads/main", || head("main")), None);
+        assert_eq!(tracker.observe(dir, "ref: refs/heads/main", || panic!("HEAD didn't move")), None);
+
+        let (from, to) = tracker.observe(dir, "ref: refs/heads/combat", || head("combat")).unwrap();
+        assert_eq!((from.branch.as_deref(), to.branch.as_deref()), (Some("main"), Some("combat")));
+        assert!(tracker.snapshot()[dir].pending);
+        // Not settled yet, so only a forced (confirmed) resync starts
+        assert_eq!(tracker.start(dir, false), None);
+        assert_eq!(tracker.start(dir, true), head("combat"));
+        assert_eq!(tracker.start(dir, true), None);
+        tracker.finish(dir, head("combat"));
+        let project = &tracker.snapshot()[dir];
+        assert!(!project.pending && !project.running);
+        assert_eq!(project.studio, head("combat"));
+
+        // Going back and forth ends where Studio already is
+        tracker.observe(dir, "ref: refs/heads/main", || head("main"));
+        tracker.observe(dir, "ref: refs/heads/combat", || head("combat"));
+        assert!(!tracker.snapshot()[dir].pending);
+
+        assert_eq!(mode(&Some(serde_json::json!({ "sync": { "branchSync": "auto" } }))), BranchSyncMode::Auto);
+        assert_eq!(mode(&None), BranchSyncMode::Confirm);
+    }
+}
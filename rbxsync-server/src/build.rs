@@ -0,0 +1,195 @@
+//! Headless builds over HTTP
+//!
+//! `POST /build` writes a place or model file from a project's src directory
+//! with `rbxsync_core::builder`, the same pipeline as `rbxsync build`. Nightly
+//! builds and `/studio/publish` build through here as well. `POST /verify`
+//! round-trips the build in memory and reports what it loses, for CI.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use rbxsync_core::{BuildError, BuildFormat, BuildOptions, BuildProfile, BuildReport, VerifyReport};
+use serde::Deserialize;
+
+use crate::AppState;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildRequest {
+    pub project_dir: Option<String>,
+    /// Output file, relative to the project directory (default `build/game.<format>`)
+    pub output: Option<String>,
+    /// rbxl, rbxm, rbxlx or rbxmx (default rbxl)
+    pub format: Option<String>,
+    pub variant: Option<String>,
+    #[serde(default)]
+    pub strict: bool,
+    #[serde(default)]
+    pub obfuscate: bool,
+    /// Define tokens on top of the config's `defines`
+    #[serde(default)]
+    pub defines: BTreeMap<String, String>,
+    /// Build profile from the config's `profiles`; the other fields win over it
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyRequest {
+    pub project_dir: Option<String>,
+    /// Format to round-trip through: rbxl, rbxm, rbxlx or rbxmx (default rbxl)
+    pub format: Option<String>,
+}
+
+/// Where a build is written: `output` relative to the project, or `build/game.<ext>`
+pub fn output_path(project_dir: &Path, output: Option<&str>, format: BuildFormat) -> PathBuf {
+    match output {
+        Some(output) => project_dir.join(output),
+        None => project_dir.join("build").join(format!("game.{}", format.extension())),
+    }
+}
+
+const UNKNOWN_FORMAT: &str = "Unknown format. Use rbxl, rbxm, rbxlx, or rbxmx";
+
+/// The requested format, rbxl if none was given; `None` if it's unknown
+fn parse_format(format: Option<&str>) -> Option<BuildFormat> {
+    match format {
+        None => Some(BuildFormat::default()),
+        Some(format) => BuildFormat::parse(format),
+    }
+}
+
+/// Build a project off the async runtime
+pub(crate) async fn build_project(
+    project_dir: &str,
+    output: &Path,
+    options: BuildOptions,
+) -> Result<BuildReport, BuildError> {
+    let project_dir = PathBuf::from(project_dir);
+    let output = output.to_path_buf();
+    tokio::task::spawn_blocking(move || rbxsync_core::build_project(&project_dir, &output, &options))
+        .await
+        .unwrap_or_else(|e| Err(BuildError::Write(e.to_string())))
+}
+
+/// Build a project's src directory into a place or model file
+pub async fn handle_build(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<BuildRequest>,
+) -> impl IntoResponse {
+    let error = |status: StatusCode, message: String| {
+        (status, Json(serde_json::json!({ "success": false, "error": message })))
+    };
+    let Some(project_dir) = crate::flags::resolve_project(&state, req.project_dir.as_deref()).await else {
+        return error(StatusCode::BAD_REQUEST, "projectDir is required".to_string());
+    };
+    let mut options = BuildOptions {
+        variant: req.variant,
+        strict: req.strict,
+        obfuscate: req.obfuscate,
+        defines: req.defines,
+        ..Default::default()
+    };
+    let mut output = req.output;
+    let mut format = req.format;
+    if let Some(name) = &req.profile {
+        let config = crate::load_project_config(&project_dir).unwrap_or_default();
+        let profile = match BuildProfile::from_config(&config, name) {
+            Ok(profile) => profile,
+            Err(e) => return error(StatusCode::BAD_REQUEST, e),
+        };
+        profile.apply_to(&mut options);
+        output = output.or_else(|| profile.output.as_ref().map(|o| o.to_string_lossy().to_string()));
+        format = format.or_else(|| profile.format().map(|f| f.extension().to_string()));
+    }
+    let Some(format) = parse_format(format.as_deref()) else {
+        return error(StatusCode::BAD_REQUEST, UNKNOWN_FORMAT.to_string());
+    };
+    options.format = format;
+
+    let output = output_path(Path::new(&project_dir), output.as_deref(), format);
+    tracing::info!("Building {} -> {}", project_dir, output.display());
+    match build_project(&project_dir, &output, options).await {
+        Ok(report) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "success": true,
+                "output": output.to_string_lossy(),
+                "format": format,
+                "report": report
+            })),
+        ),
+        Err(e @ (BuildError::MissingSource(_) | BuildError::Overrides(_) | BuildError::Obfuscation(_))) => {
+            error(StatusCode::BAD_REQUEST, e.to_string())
+        }
+        Err(e @ BuildError::Unbuildable { .. }) => error(StatusCode::UNPROCESSABLE_ENTITY, e.to_string()),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// Verify a project off the async runtime
+pub(crate) async fn verify_project(project_dir: &str, format: BuildFormat) -> Result<VerifyReport, BuildError> {
+    let project_dir = PathBuf::from(project_dir);
+    tokio::task::spawn_blocking(move || rbxsync_core::verify_project(&project_dir, format))
+        .await
+        .unwrap_or_else(|e| Err(BuildError::Write(e.to_string())))
+}
+
+/// Build src in memory, read it back and report what the round trip lost
+pub async fn handle_verify(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<VerifyRequest>,
+) -> impl IntoResponse {
+    let error = |status: StatusCode, message: String| {
+        (status, Json(serde_json::json!({ "success": false, "error": message })))
+    };
+    let Some(project_dir) = crate::flags::resolve_project(&state, req.project_dir.as_deref()).await else {
+        return error(StatusCode::BAD_REQUEST, "projectDir is required".to_string());
+    };
+    let Some(format) = parse_format(req.format.as_deref()) else {
+        return error(StatusCode::BAD_REQUEST, UNKNOWN_FORMAT.to_string());
+    };
+
+    match verify_project(&project_dir, format).await {
+        Ok(report) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "success": true,
+                "lossless": report.is_lossless(),
+                "problems": report.problems(),
+                "report": report
+            })),
+        ),
+        Err(e @ (BuildError::MissingSource(_) | BuildError::Overrides(_))) => {
+            error(StatusCode::BAD_REQUEST, e.to_string())
+        }
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_path() {
+        let project = Path::new("/games/obby");
+        assert_eq!(
+            output_path(project, None, BuildFormat::Rbxmx),
+            PathBuf::from("/games/obby/build/game.rbxmx")
+        );
+        assert_eq!(
+            output_path(project, Some("out/ci.rbxl"), BuildFormat::Rbxl),
+            PathBuf::from("/games/obby/out/ci.rbxl")
+        );
+        assert_eq!(
+            output_path(project, Some("/tmp/ci.rbxl"), BuildFormat::Rbxl),
+            PathBuf::from("/tmp/ci.rbxl")
+        );
+        assert_eq!(parse_format(None), Some(BuildFormat::Rbxl));
+        assert_eq!(parse_format(Some("place-xml")), Some(BuildFormat::Rbxlx));
+        assert_eq!(parse_format(Some("fbx")), None);
+    }
+}
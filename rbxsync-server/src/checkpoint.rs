@@ -0,0 +1,270 @@
+//! Checkpointed extraction
+//!
+//! Chunks of a running extraction are written to `.rbxsync/extract_<session>/`
+//! as they arrive, next to a `manifest.json` recording what each chunk index
+//! holds. On a 200k-instance game a dropped connection no longer means
+//! starting over: the plugin asks `/extract/resume` which indices are missing
+//! and re-sends only those, and a restarted server picks the session back up
+//! from disk.
+//!
+//! A chunk can arrive in pieces (one instance per request when the whole chunk
+//! is too large, or an oversized instance in parts), so each piece is its own
+//! file and the chunk counts as complete once it holds as many instances as
+//! the plugin said it has. Instances are keyed by `referenceId` (or path), so
+//! re-sending a chunk that partly made it stores nothing twice.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Index of what a checkpoint holds
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub session_id: String,
+    #[serde(default)]
+    pub total_chunks: Option<usize>,
+    /// Chunk index -> what has been stored for it
+    #[serde(default)]
+    pub chunks: BTreeMap<usize, ChunkEntry>,
+}
+
+/// Stored pieces of one chunk
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkEntry {
+    /// Files holding the chunk's instances, in arrival order
+    pub files: Vec<String>,
+    /// Instances stored so far
+    pub instances: usize,
+    /// Instances the plugin said the chunk has (older plugins don't say)
+    #[serde(default)]
+    pub expected: Option<usize>,
+}
+
+impl ChunkEntry {
+    pub fn is_complete(&self) -> bool {
+        self.expected.is_none_or(|expected| self.instances >= expected)
+    }
+}
+
+/// An extraction's chunks on disk
+#[derive(Debug)]
+pub struct Checkpoint {
+    dir: PathBuf,
+    manifest: Manifest,
+    /// Instance keys stored per chunk index
+    seen: HashMap<usize, HashSet<String>>,
+}
+
+/// What identifies an instance across re-sends
+fn instance_key(instance: &Value) -> Option<String> {
+    ["referenceId", "path"]
+        .iter()
+        .find_map(|key| instance.get(*key).and_then(|v| v.as_str()))
+        .map(str::to_string)
+}
+
+impl Checkpoint {
+    /// Directory for a session's checkpoint: under the project when there is
+    /// one, otherwise under the working directory
+    pub fn dir_for(project_dir: Option<&str>, session_id: &str) -> PathBuf {
+        let name = format!("extract_{}", rbxsync_core::sanitize_filename(session_id));
+        match project_dir.filter(|dir| !dir.is_empty()) {
+            Some(dir) => Path::new(dir).join(".rbxsync").join(name),
+            None => Path::new(".rbxsync").join(name),
+        }
+    }
+
+    /// Start an empty checkpoint, replacing any earlier one for the session
+    pub fn create(project_dir: Option<&str>, session_id: &str) -> Self {
+        let dir = Self::dir_for(project_dir, session_id);
+        let _ = std::fs::remove_dir_all(&dir);
+        let checkpoint = Self {
+            dir,
+            manifest: Manifest { session_id: session_id.to_string(), ..Default::default() },
+            seen: HashMap::new(),
+        };
+        checkpoint.save();
+        checkpoint
+    }
+
+    /// Reopen a session's checkpoint. The stored chunks stay on disk; read
+    /// them with [`instances`](Self::instances).
+    pub fn open(project_dir: Option<&str>, session_id: &str) -> Option<Self> {
+        let dir = Self::dir_for(project_dir, session_id);
+        let manifest: Manifest = serde_json::from_str(&std::fs::read_to_string(dir.join(MANIFEST_FILE)).ok()?).ok()?;
+        let mut checkpoint = Self { dir, manifest, seen: HashMap::new() };
+        for (index, entry) in &checkpoint.manifest.chunks {
+            for file in &entry.files {
+                if let Some(instances) = read_chunk(&checkpoint.dir.join(file)) {
+                    checkpoint.seen.entry(*index).or_default().extend(instances.iter().filter_map(instance_key));
+                }
+            }
+        }
+        Some(checkpoint)
+    }
+
+    /// Files holding the stored instances, in chunk order
+    pub fn chunk_files(&self) -> Vec<PathBuf> {
+        self.manifest
+            .chunks
+            .values()
+            .flat_map(|entry| entry.files.iter().map(|file| self.dir.join(file)))
+            .collect()
+    }
+
+    /// The stored instances, one file at a time
+    pub fn instances(&self) -> impl Iterator<Item = Vec<Value>> {
+        self.chunk_files().into_iter().filter_map(|file| read_chunk(&file))
+    }
+
+    /// Instances stored so far
+    pub fn instance_count(&self) -> usize {
+        self.manifest.chunks.values().map(|entry| entry.instances).sum()
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn total_chunks(&self) -> Option<usize> {
+        self.manifest.total_chunks
+    }
+
+    pub fn set_total_chunks(&mut self, total: usize) {
+        if self.manifest.total_chunks != Some(total) {
+            self.manifest.total_chunks = Some(total);
+            self.save();
+        }
+    }
+
+    /// Chunk indices that have all their instances
+    pub fn complete_chunks(&self) -> usize {
+        self.manifest.chunks.values().filter(|c| c.is_complete()).count()
+    }
+
+    /// Chunk indices still to be sent, in order (empty until the total is known)
+    pub fn missing(&self) -> Vec<usize> {
+        let total = self.manifest.total_chunks.unwrap_or(0);
+        (0..total)
+            .filter(|index| !self.manifest.chunks.get(index).is_some_and(|c| c.is_complete()))
+            .collect()
+    }
+
+    /// Store instances received for chunk `index`; returns the ones not
+    /// already stored, which is what the caller should keep
+    pub fn add(&mut self, index: usize, total: usize, expected: Option<usize>, instances: Vec<Value>) -> Vec<Value> {
+        let seen = self.seen.entry(index).or_default();
+        let fresh: Vec<Value> = instances
+            .into_iter()
+            .filter(|instance| instance_key(instance).is_none_or(|key| seen.insert(key)))
+            .collect();
+
+        self.manifest.total_chunks = Some(total);
+        let entry = self.manifest.chunks.entry(index).or_default();
+        if expected.is_some() {
+            entry.expected = expected;
+        }
+        if !fresh.is_empty() {
+            let file = format!("chunk_{:06}_{}.json", index, entry.files.len());
+            let _ = std::fs::create_dir_all(&self.dir);
+            match std::fs::write(self.dir.join(&file), serde_json::to_string(&fresh).unwrap_or_default()) {
+                Ok(()) => {
+                    entry.files.push(file);
+                    entry.instances += fresh.len();
+                }
+                Err(e) => tracing::warn!("Failed to save chunk {} to checkpoint: {}", index, e),
+            }
+        }
+        self.save();
+        fresh
+    }
+
+    fn save(&self) {
+        let _ = std::fs::create_dir_all(&self.dir);
+        let manifest = serde_json::to_string_pretty(&self.manifest).unwrap_or_default();
+        if let Err(e) = std::fs::write(self.dir.join(MANIFEST_FILE), manifest) {
+            tracing::warn!("Failed to write extraction manifest: {}", e);
+        }
+    }
+
+    /// Delete the checkpoint once the extraction has been written to src
+    pub fn remove(&self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Instances in one checkpoint file
+pub fn read_chunk(file: &Path) -> Option<Vec<Value>> {
+    let instances = std::fs::read_to_string(file)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+    if instances.is_none() {
+        tracing::warn!("Checkpoint file {} is missing or unreadable", file.display());
+    }
+    instances
+}
+
+/// Delete checkpoints of earlier extractions of a project, keeping `keep`
+pub fn prune(project_dir: &str, keep: &str) {
+    let keep = Checkpoint::dir_for(Some(project_dir), keep);
+    let Ok(entries) = std::fs::read_dir(Path::new(project_dir).join(".rbxsync")) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_checkpoint = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with("extract_"));
+        if is_checkpoint && path.is_dir() && path != keep {
+            let _ = std::fs::remove_dir_all(&path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn instance(reference: &str) -> Value {
+        json!({ "referenceId": reference, "path": format!("Workspace/{}", reference), "className": "Part" })
+    }
+
+    #[test]
+    fn test_checkpoint_tracks_missing_chunks_across_reopen() {
+        let project = tempfile::tempdir().unwrap();
+        let project_dir = project.path().to_str();
+        let mut checkpoint = Checkpoint::create(project_dir, "session-1");
+        assert!(checkpoint.dir().ends_with(".rbxsync/extract_session-1"));
+
+        assert_eq!(checkpoint.add(0, 3, Some(2), vec![instance("a"), instance("b")]).len(), 2);
+        // Chunk 2 is being sent one instance at a time and only one arrived
+        assert_eq!(checkpoint.add(2, 3, Some(2), vec![instance("e")]).len(), 1);
+        assert_eq!(checkpoint.missing(), vec![1, 2]);
+        assert_eq!(checkpoint.complete_chunks(), 1);
+
+        // A restarted server sees the same chunks, and re-sends aren't stored twice
+        let mut reopened = Checkpoint::open(project_dir, "session-1").unwrap();
+        assert_eq!(reopened.instances().map(|c| c.len()).sum::<usize>(), 3);
+        assert_eq!(reopened.instance_count(), 3);
+        assert_eq!(reopened.missing(), vec![1, 2]);
+        let fresh = reopened.add(2, 3, Some(2), vec![instance("e"), instance("f")]);
+        assert_eq!(fresh, vec![instance("f")]);
+        reopened.add(1, 3, Some(2), vec![instance("c"), instance("d")]);
+        assert!(reopened.missing().is_empty());
+
+        // Starting another extraction clears the old checkpoint
+        let next = Checkpoint::create(project_dir, "session-2");
+        prune(project_dir.unwrap(), "session-2");
+        assert!(Checkpoint::open(project_dir, "session-1").is_none());
+        next.remove();
+        assert!(!next.dir().exists());
+    }
+}
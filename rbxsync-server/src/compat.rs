@@ -0,0 +1,232 @@
+//! Plugin version handshake
+//!
+//! The Studio plugin sends its `PLUGIN_VERSION` with `/rbxsync/register`.
+//! Each server release speaks to a range of plugin releases ([`COMPATIBILITY`]);
+//! a plugin outside it is flagged on `/health` and `/rbxsync/places`, and the
+//! plugin is told in the register response so it can prompt for an upgrade.
+//! `GET /plugin/download` serves a built `.rbxm` of the matching plugin.
+
+use std::path::PathBuf;
+
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::{AppState, PlaceInfo};
+
+/// This server's version
+pub const SERVER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Where the plugin fetches a matching build
+pub const DOWNLOAD_PATH: &str = "/plugin/download";
+
+/// Plugin releases one server release speaks to, as (major, minor)
+#[derive(Debug, Clone, Copy)]
+pub struct CompatRange {
+    pub server: (u64, u64),
+    pub oldest_plugin: (u64, u64),
+    pub newest_plugin: (u64, u64),
+}
+
+/// Add a row with every release that changes the protocol. A server missing
+/// from the table only speaks to plugins of its own minor version.
+pub const COMPATIBILITY: &[CompatRange] = &[
+    CompatRange { server: (1, 3), oldest_plugin: (1, 2), newest_plugin: (1, 3) },
+    CompatRange { server: (1, 2), oldest_plugin: (1, 1), newest_plugin: (1, 2) },
+];
+
+/// How a plugin's version fits the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PluginCompat {
+    Compatible,
+    /// Older than the server supports
+    Outdated,
+    /// Newer than the server supports
+    TooNew,
+    /// The plugin sent no version (it predates the handshake) or an unreadable one
+    Unknown,
+}
+
+/// A connected plugin that doesn't match the server
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionWarning {
+    pub place_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin_version: Option<String>,
+    pub server_version: String,
+    pub status: PluginCompat,
+    pub message: String,
+}
+
+/// (major, minor, patch) of `1.2.1` or `v1.2.1`; a missing patch is 0
+pub fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.trim().trim_start_matches('v').splitn(3, '.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    // Ignore pre-release and build suffixes like `-beta.1`
+    let patch = parts
+        .next()
+        .map(|p| p.split(|c: char| !c.is_ascii_digit()).next().unwrap_or("").parse().unwrap_or(0))
+        .unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+/// How `plugin` fits a server at `server`
+pub fn check_against(server: &str, plugin: Option<&str>) -> PluginCompat {
+    let (Some(server), Some(plugin)) = (parse_version(server), plugin.and_then(parse_version)) else {
+        return PluginCompat::Unknown;
+    };
+    let server = (server.0, server.1);
+    let plugin = (plugin.0, plugin.1);
+    let range = COMPATIBILITY
+        .iter()
+        .find(|range| range.server == server)
+        .copied()
+        .unwrap_or(CompatRange { server, oldest_plugin: server, newest_plugin: server });
+    if plugin < range.oldest_plugin {
+        PluginCompat::Outdated
+    } else if plugin > range.newest_plugin {
+        PluginCompat::TooNew
+    } else {
+        PluginCompat::Compatible
+    }
+}
+
+/// How `plugin` fits this server
+pub fn check(plugin: Option<&str>) -> PluginCompat {
+    check_against(SERVER_VERSION, plugin)
+}
+
+/// What to tell the user about a plugin that doesn't fit
+pub fn mismatch_message(status: PluginCompat, plugin: Option<&str>) -> Option<String> {
+    let plugin = plugin.unwrap_or("unknown");
+    match status {
+        PluginCompat::Compatible => None,
+        PluginCompat::Outdated => Some(format!(
+            "Plugin v{} is too old for server v{}. Update it with `rbxsync plugin install` or from {}",
+            plugin, SERVER_VERSION, DOWNLOAD_PATH
+        )),
+        PluginCompat::TooNew => Some(format!(
+            "Plugin v{} is newer than server v{}. Update the CLI with `rbxsync update`",
+            plugin, SERVER_VERSION
+        )),
+        PluginCompat::Unknown => Some(format!(
+            "The plugin didn't report its version, so it predates server v{}. Update it with `rbxsync plugin install` or from {}",
+            SERVER_VERSION, DOWNLOAD_PATH
+        )),
+    }
+}
+
+/// The warning for a connected place, if its plugin doesn't fit
+pub fn place_warning(place: &PlaceInfo) -> Option<VersionWarning> {
+    let plugin = place.plugin_version.as_deref();
+    let status = check(plugin);
+    Some(VersionWarning {
+        place_name: place.place_name.clone(),
+        session_id: place.session_id.clone(),
+        plugin_version: place.plugin_version.clone(),
+        server_version: SERVER_VERSION.to_string(),
+        status,
+        message: mismatch_message(status, plugin)?,
+    })
+}
+
+/// Warnings for every connected place whose plugin doesn't fit
+pub async fn version_warnings(state: &AppState) -> Vec<VersionWarning> {
+    state.place_registry.read().await.values().filter_map(place_warning).collect()
+}
+
+/// Places a built plugin may be found, best first: the CLI's download
+/// folder, a `build-plugin` build of the server's working directory, next to
+/// the server binary, and Studio's plugins folder
+fn plugin_candidates() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        candidates.push(home.join(".rbxsync").join("downloads").join("RbxSync.rbxm"));
+    }
+    candidates.push(PathBuf::from("build/RbxSync.rbxm"));
+    if let Some(dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(PathBuf::from)) {
+        candidates.push(dir.join("RbxSync.rbxm"));
+    }
+    if let Some(plugins) = rbxsync_core::get_studio_plugins_folder() {
+        candidates.push(plugins.join("RbxSync.rbxm"));
+    }
+    candidates
+}
+
+/// A built plugin matching this server: the same version if there is one,
+/// else any compatible one. Returns the file, its bytes and its version.
+pub fn find_matching_plugin() -> Option<(PathBuf, Vec<u8>, String)> {
+    let mut compatible = None;
+    for path in plugin_candidates() {
+        let Ok(rbxm) = std::fs::read(&path) else { continue };
+        let Some(version) = rbxsync_core::read_plugin_modules(&rbxm).ok().and_then(|root| root.version()) else {
+            continue;
+        };
+        if version == SERVER_VERSION {
+            return Some((path, rbxm, version));
+        }
+        if compatible.is_none() && check(Some(&version)) == PluginCompat::Compatible {
+            compatible = Some((path, rbxm, version));
+        }
+    }
+    compatible
+}
+
+/// Serve a plugin .rbxm matching this server (GET /plugin/download)
+pub async fn handle_download() -> Response {
+    match tokio::task::spawn_blocking(find_matching_plugin).await.ok().flatten() {
+        Some((path, rbxm, version)) => {
+            tracing::info!("Serving plugin v{} from {}", version, path.display());
+            (
+                [
+                    (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+                    (header::CONTENT_DISPOSITION, "attachment; filename=\"RbxSync.rbxm\"".to_string()),
+                    (header::HeaderName::from_static("x-rbxsync-plugin-version"), version),
+                ],
+                rbxm,
+            )
+                .into_response()
+        }
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "success": false,
+                "error": format!("No built plugin matching server v{}. Run `rbxsync plugin install --download`", SERVER_VERSION),
+                "releaseUrl": format!("https://github.com/Smokestack-Games/rbxsync/releases/tag/v{}", SERVER_VERSION)
+            })),
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugin_compatibility() {
+        assert_eq!(parse_version("v1.2.1"), Some((1, 2, 1)));
+        assert_eq!(parse_version("1.3.0-beta.2"), Some((1, 3, 0)));
+        assert_eq!(parse_version("dev"), None);
+
+        assert_eq!(check_against("1.3.0", Some("1.2.1")), PluginCompat::Compatible);
+        assert_eq!(check_against("1.3.0", Some("1.3.4")), PluginCompat::Compatible);
+        assert_eq!(check_against("1.3.0", Some("1.1.9")), PluginCompat::Outdated);
+        assert_eq!(check_against("1.3.0", Some("1.4.0")), PluginCompat::TooNew);
+        assert_eq!(check_against("1.3.0", None), PluginCompat::Unknown);
+        // Not in the table: only the same minor version
+        assert_eq!(check_against("2.0.1", Some("2.0.0")), PluginCompat::Compatible);
+        assert_eq!(check_against("2.0.1", Some("1.3.0")), PluginCompat::Outdated);
+
+        assert!(mismatch_message(PluginCompat::Compatible, Some("1.3.0")).is_none());
+        assert!(mismatch_message(PluginCompat::Outdated, Some("1.0.0")).unwrap().contains(DOWNLOAD_PATH));
+    }
+}
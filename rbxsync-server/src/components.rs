@@ -0,0 +1,186 @@
+//! Component (tag + attribute) endpoints
+//!
+//! Lists and diffs a project grouped by CollectionService tag instead of by path,
+//! for teams using tag/attribute component frameworks.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use rbxsync_core::{ComponentEntry, ComponentIndex};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::protocol::{PluginCommand, StudioPaths};
+use crate::{normalize_path_for_comparison, AppState};
+
+/// Request for component listing and diffing
+#[derive(Debug, Deserialize)]
+pub struct ComponentsRequest {
+    pub project_dir: String,
+    /// Only report this tag
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+/// Build a component index from the .rbxjson files under `src_dir`
+pub fn index_local_components(src_dir: &Path) -> ComponentIndex {
+    fn walk(dir: &Path, base: &Path, index: &mut ComponentIndex) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                walk(&path, base, index);
+                continue;
+            }
+            if path.extension().map(|e| e != "rbxjson").unwrap_or(true) {
+                continue;
+            }
+            if path.file_name().map(|n| n == "terrain.rbxjson").unwrap_or(false) {
+                continue;
+            }
+            let Some(inst) = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+            else {
+                continue;
+            };
+
+            let rel = rbxsync_core::path_to_string(path.strip_prefix(base).unwrap_or(&path));
+            let inst_path = rel
+                .strip_suffix("/_meta.rbxjson")
+                .or_else(|| rel.strip_suffix(".rbxjson"))
+                .unwrap_or(&rel);
+            let inst_path = normalize_path_for_comparison(inst_path);
+
+            if let Some(component) = ComponentEntry::from_instance_json(&inst_path, &inst) {
+                index.insert(component);
+            }
+        }
+    }
+
+    let mut index = ComponentIndex::new();
+    walk(src_dir, src_dir, &mut index);
+    index
+}
+
+/// Build a component index from a `studio:paths` response
+fn index_studio_components(data: &serde_json::Value) -> ComponentIndex {
+    let mut index = ComponentIndex::new();
+    if let Some(paths) = data.get("paths").and_then(|v| v.as_array()) {
+        for entry in paths {
+            if let Some(path) = entry.get("path").and_then(|v| v.as_str()) {
+                if let Some(component) = ComponentEntry::from_instance_json(path, entry) {
+                    index.insert(component);
+                }
+            }
+        }
+    }
+    index
+}
+
+fn src_dir_or_error(project_dir: &str) -> Result<PathBuf, (StatusCode, Json<serde_json::Value>)> {
+    let src_dir = PathBuf::from(project_dir).join("src");
+    if src_dir.exists() {
+        Ok(src_dir)
+    } else {
+        Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "success": false,
+                "error": "Source directory does not exist"
+            })),
+        ))
+    }
+}
+
+/// List local instances grouped by tag
+pub async fn handle_components_list(Json(req): Json<ComponentsRequest>) -> impl IntoResponse {
+    let src_dir = match src_dir_or_error(&req.project_dir) {
+        Ok(dir) => dir,
+        Err(err) => return err,
+    };
+
+    let index = index_local_components(&src_dir);
+    let mut groups = index.groups();
+    if let Some(tag) = &req.tag {
+        groups.retain(|g| &g.tag == tag);
+    }
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "success": true,
+            "components": groups,
+            "instanceCount": index.len()
+        })),
+    )
+}
+
+/// Diff local components against Studio, grouped by tag
+pub async fn handle_components_diff(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ComponentsRequest>,
+) -> impl IntoResponse {
+    let src_dir = match src_dir_or_error(&req.project_dir) {
+        Ok(dir) => dir,
+        Err(err) => return err,
+    };
+    let local = index_local_components(&src_dir);
+
+    // Ask Studio for its paths, including tags and attribute names
+    let request_id = Uuid::new_v4();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    state.response_channels.write().await.insert(request_id, tx);
+    let command = PluginCommand::StudioPaths(StudioPaths { include_components: true, ..Default::default() });
+    state.request_queue.lock().await.push_back(command.request(request_id));
+    state.wakeups.notify_all();
+
+    let result = tokio::time::timeout(tokio::time::Duration::from_secs(60), rx.recv()).await;
+    state.response_channels.write().await.remove(&request_id);
+
+    let studio_data = match result {
+        Ok(Some(response)) if response.success => response.data,
+        Ok(Some(response)) => {
+            return (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "success": false,
+                    "error": response.error.unwrap_or_else(|| "Plugin returned error".to_string())
+                })),
+            );
+        }
+        Ok(None) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"success": false, "error": "Channel closed"})),
+            );
+        }
+        Err(_) => {
+            return (
+                StatusCode::GATEWAY_TIMEOUT,
+                Json(serde_json::json!({"success": false, "error": "Timeout waiting for Studio paths"})),
+            );
+        }
+    };
+    let studio = index_studio_components(&studio_data);
+
+    // Studio is the old state, files are the new state (what a sync would apply)
+    let mut diff = studio.diff(&local);
+    if let Some(tag) = &req.tag {
+        diff.retain(|d| &d.tag == tag);
+    }
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "success": true,
+            "components": diff,
+            "fileCount": local.len(),
+            "studioCount": studio.len()
+        })),
+    )
+}
@@ -0,0 +1,182 @@
+//! Sync conflict detection
+//!
+//! Without a common ancestor, a change made in Studio simply overwrites the
+//! instance's files, even if they were edited since the last sync. To tell a
+//! local edit from a stale file, the server remembers a base hash per instance
+//! (keyed by its path under `src/`, without extension): the hash of its files
+//! when they last matched Studio, i.e. after a sync to Studio or a write from
+//! Studio. Bases are kept in `.rbxsync/sync_base.json`.
+//!
+//! When Studio changes an instance whose files no longer match the base, both
+//! sides changed. The Studio change is parked in `.rbxsync/conflicts.json`
+//! instead of being written, and files → Studio syncs skip the instance until
+//! the conflict is resolved with the file, Studio or a merged version.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use rbxsync_core::api::SyncConflict;
+
+pub const BASE_FILE: &str = "sync_base.json";
+pub const CONFLICTS_FILE: &str = "conflicts.json";
+
+/// Suffixes of the files that can make up one instance
+const INSTANCE_SUFFIXES: [&str; 4] = [".rbxjson", ".server.luau", ".client.luau", ".luau"];
+
+/// Sync bookkeeping for one project
+#[derive(Debug, Default)]
+pub struct ProjectSyncState {
+    /// When files were last synced to Studio
+    pub last_sync: Option<SystemTime>,
+    /// Instance key -> hash of its files when they last matched Studio,
+    /// loaded from disk on first use
+    bases: Option<BTreeMap<String, String>>,
+}
+
+impl ProjectSyncState {
+    pub fn bases(&mut self, project_dir: &Path) -> &mut BTreeMap<String, String> {
+        self.bases.get_or_insert_with(|| {
+            std::fs::read_to_string(project_dir.join(".rbxsync").join(BASE_FILE))
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Record the current files of `key` as matching Studio
+    pub fn set_base(&mut self, project_dir: &Path, key: &str) {
+        let hash = hash_files(&instance_files(&project_dir.join("src").join(key)));
+        let bases = self.bases(project_dir);
+        match hash {
+            Some(hash) => bases.insert(key.to_string(), hash),
+            None => bases.remove(key),
+        };
+    }
+
+    /// Forget every base, e.g. after an extraction rewrote src
+    pub fn clear_bases(&mut self, project_dir: &Path) {
+        self.bases = Some(BTreeMap::new());
+        let _ = std::fs::remove_file(project_dir.join(".rbxsync").join(BASE_FILE));
+    }
+
+    pub fn save_bases(&self, project_dir: &Path) {
+        let Some(bases) = &self.bases else {
+            return;
+        };
+        let dir = project_dir.join(".rbxsync");
+        let _ = std::fs::create_dir_all(&dir);
+        if let Err(e) = std::fs::write(dir.join(BASE_FILE), serde_json::to_string(bases).unwrap_or_default()) {
+            tracing::warn!("Failed to write sync bases: {}", e);
+        }
+    }
+
+    /// Whether the files of `key` changed since they last matched Studio.
+    /// Instances without a base never count as edited.
+    pub fn edited_locally(&mut self, project_dir: &Path, key: &str) -> bool {
+        let current = hash_files(&instance_files(&project_dir.join("src").join(key)));
+        self.bases(project_dir).get(key).is_some_and(|base| Some(base) != current.as_ref())
+    }
+}
+
+/// Key of the instance a file under `src/` belongs to: its path relative to
+/// src without extension, with `_meta.rbxjson` standing for its folder
+pub fn instance_key(relative: &str) -> String {
+    let relative = relative.replace('\\', "/");
+    if let Some(folder) = relative.strip_suffix("/_meta.rbxjson") {
+        return folder.to_string();
+    }
+    INSTANCE_SUFFIXES
+        .iter()
+        .find_map(|suffix| relative.strip_suffix(suffix))
+        .unwrap_or(&relative)
+        .to_string()
+}
+
+/// Existing files of the instance at `full_path` (src joined with its key)
+pub fn instance_files(full_path: &Path) -> Vec<PathBuf> {
+    INSTANCE_SUFFIXES
+        .iter()
+        .map(|suffix| rbxsync_core::pathbuf_with_suffix(full_path, suffix))
+        .chain(std::iter::once(full_path.join("_meta.rbxjson")))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// FNV-1a hash of the files' names and contents, or `None` if there are none
+pub fn hash_files(files: &[PathBuf]) -> Option<String> {
+    if files.is_empty() {
+        return None;
+    }
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for file in files {
+        let name = file.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let content = std::fs::read(file).unwrap_or_default();
+        for byte in name.as_bytes().iter().chain(&[0]).chain(&content) {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    Some(format!("{:016x}", hash))
+}
+
+pub fn load_conflicts(project_dir: &Path) -> Vec<SyncConflict> {
+    std::fs::read_to_string(project_dir.join(".rbxsync").join(CONFLICTS_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_conflicts(project_dir: &Path, conflicts: &[SyncConflict]) {
+    let path = project_dir.join(".rbxsync").join(CONFLICTS_FILE);
+    if conflicts.is_empty() {
+        let _ = std::fs::remove_file(path);
+        return;
+    }
+    let _ = std::fs::create_dir_all(project_dir.join(".rbxsync"));
+    if let Err(e) = std::fs::write(&path, serde_json::to_string_pretty(conflicts).unwrap_or_default()) {
+        tracing::warn!("Failed to write sync conflicts: {}", e);
+    }
+}
+
+/// Add or replace the conflict for an instance; a later Studio change to an
+/// instance already in conflict replaces the parked one
+pub fn upsert(conflicts: &mut Vec<SyncConflict>, conflict: SyncConflict) {
+    match conflicts.iter_mut().find(|c| c.key == conflict.key) {
+        Some(existing) => *existing = conflict,
+        None => conflicts.push(conflict),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edited_locally_against_base() {
+        let project = tempfile::tempdir().unwrap();
+        let src = project.path().join("src");
+        std::fs::create_dir_all(src.join("ServerScriptService")).unwrap();
+        let script = src.join("ServerScriptService/Main.server.luau");
+        std::fs::write(&script, "print('a')").unwrap();
+
+        assert_eq!(instance_key("ServerScriptService/Main.server.luau"), "ServerScriptService/Main");
+        assert_eq!(instance_key("Workspace/Map/_meta.rbxjson"), "Workspace/Map");
+
+        let key = "ServerScriptService/Main";
+        let mut state = ProjectSyncState::default();
+        // No base yet: nothing to conflict with
+        assert!(!state.edited_locally(project.path(), key));
+
+        state.set_base(project.path(), key);
+        state.save_bases(project.path());
+        assert!(!state.edited_locally(project.path(), key));
+
+        std::fs::write(&script, "print('b')").unwrap();
+        let mut reloaded = ProjectSyncState::default();
+        assert!(reloaded.edited_locally(project.path(), key));
+
+        reloaded.clear_bases(project.path());
+        assert!(!reloaded.edited_locally(project.path(), key));
+    }
+}
@@ -0,0 +1,386 @@
+//! Console history filters, export and error summaries
+//!
+//! `/console/history` and `/console/export` take the same filters: minimum
+//! level, source, a substring or regular expression, and a time range. Times
+//! go by when the server received a message (`received_at`), since Studio
+//! stamps messages with its local time of day only.
+//!
+//! Repeated errors are grouped by [`fingerprint`]: the message with its
+//! variable parts (numbers, ids) masked, plus its stack frames. The same nil
+//! index on line 88 for every player is then one error seen 40 times, and
+//! `/console/errors/summary` can say "3 unique errors" instead of handing an
+//! agent the raw spam.
+
+use std::sync::{Arc, OnceLock};
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use rbxsync_core::api::{ConsoleHistoryQuery, ConsoleMessage, ErrorSummary, TopError};
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::production::{aggregate_errors, ErrorGroup};
+use crate::AppState;
+
+/// Errors listed in a summary when the query doesn't say
+const DEFAULT_TOP: usize = 5;
+
+fn severity(level: &str) -> Option<u8> {
+    match level.to_ascii_lowercase().as_str() {
+        "error" => Some(2),
+        "warn" | "warning" => Some(1),
+        "info" | "output" => Some(0),
+        _ => None,
+    }
+}
+
+/// Unix seconds from either Unix seconds or an age before `now` (`30s`,
+/// `10m`, `2h`, `1d`)
+pub fn parse_time(value: &str, now: u64) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(secs);
+    }
+    let (amount, unit) = value.split_at(value.char_indices().last()?.0);
+    let unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return None,
+    };
+    Some(now.saturating_sub(amount.parse::<u64>().ok()? * unit))
+}
+
+enum Text {
+    Contains(String),
+    Regex(Regex),
+}
+
+/// A compiled [`ConsoleHistoryQuery`]
+pub struct ConsoleFilter {
+    level: u8,
+    source: Option<String>,
+    text: Option<Text>,
+    since: Option<u64>,
+    until: Option<u64>,
+}
+
+impl ConsoleFilter {
+    /// Check the query's values; `now` is Unix seconds, for ages
+    pub fn new(query: &ConsoleHistoryQuery, now: u64) -> Result<Self, String> {
+        let level = match &query.level {
+            Some(level) => severity(level).ok_or("level must be one of info, warn, error")?,
+            None => 0,
+        };
+        let text = match &query.grep {
+            Some(pattern) if query.regex.unwrap_or(false) => Some(Text::Regex(
+                Regex::new(pattern).map_err(|e| format!("Invalid regex: {}", e))?,
+            )),
+            Some(text) => Some(Text::Contains(text.to_lowercase())),
+            None => None,
+        };
+        let time = |value: &Option<String>, name: &str| match value {
+            Some(value) => parse_time(value, now)
+                .map(Some)
+                .ok_or_else(|| format!("{} must be Unix seconds or an age like 30s, 10m, 2h, 1d", name)),
+            None => Ok(None),
+        };
+        Ok(Self {
+            level,
+            source: query.source.as_ref().map(|s| s.to_lowercase()),
+            text,
+            since: time(&query.since, "since")?,
+            until: time(&query.until, "until")?,
+        })
+    }
+
+    pub fn matches(&self, message: &ConsoleMessage) -> bool {
+        if severity(&message.message_type).unwrap_or(0) < self.level {
+            return false;
+        }
+        if let Some(wanted) = &self.source {
+            let source = message.source.as_deref().unwrap_or_default().to_lowercase();
+            if source != *wanted && !source.strip_prefix(wanted.as_str()).is_some_and(|rest| rest.starts_with(':')) {
+                return false;
+            }
+        }
+        match &self.text {
+            Some(Text::Contains(text)) if !message.message.to_lowercase().contains(text) => return false,
+            Some(Text::Regex(regex)) if !regex.is_match(&message.message) => return false,
+            _ => {}
+        }
+        // Messages from before received_at was recorded only match open ranges
+        if self.since.is_some() || self.until.is_some() {
+            let Some(at) = message.received_at else {
+                return false;
+            };
+            if self.since.is_some_and(|since| at < since) || self.until.is_some_and(|until| at > until) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Matching messages in `messages` (oldest first), keeping the newest `limit`
+    pub fn select<'a>(
+        &self,
+        messages: impl Iterator<Item = &'a ConsoleMessage>,
+        limit: Option<usize>,
+    ) -> (Vec<ConsoleMessage>, usize) {
+        let matched: Vec<&ConsoleMessage> = messages.filter(|m| self.matches(m)).collect();
+        let skip = limit.map(|limit| matched.len().saturating_sub(limit)).unwrap_or(0);
+        let count = matched.len();
+        (matched.into_iter().skip(skip).cloned().collect(), count)
+    }
+}
+
+/// One line per message: `[time] LEVEL source: message`
+pub fn format_text(messages: &[ConsoleMessage]) -> String {
+    let mut out = String::new();
+    for message in messages {
+        let time = match message.received_at {
+            Some(at) => rbxsync_core::defines::utc_timestamp(at),
+            None => message.timestamp.clone(),
+        };
+        out.push_str(&format!(
+            "[{}] {:<5} {}: {}\n",
+            time,
+            message.message_type.to_uppercase(),
+            message.source.as_deref().unwrap_or("unknown"),
+            message.message
+        ));
+    }
+    out
+}
+
+/// JSON lines, one message per line
+pub fn format_jsonl(messages: &[ConsoleMessage]) -> String {
+    messages
+        .iter()
+        .filter_map(|m| serde_json::to_string(m).ok())
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// Whether an export to `path` is JSON lines, from `format` or the extension
+pub fn is_jsonl(path: &str, format: Option<&str>) -> Result<bool, String> {
+    match format.map(str::to_ascii_lowercase).as_deref() {
+        Some("jsonl" | "json") => Ok(true),
+        Some("text" | "txt") => Ok(false),
+        Some(other) => Err(format!("Unknown export format {:?}: use text or jsonl", other)),
+        None => Ok(path.ends_with(".json") || path.ends_with(".jsonl")),
+    }
+}
+
+/// `ServerScriptService.Combat:88: ` at the start of a Roblox error
+fn location_prefix() -> &'static Regex {
+    static PREFIX: OnceLock<Regex> = OnceLock::new();
+    PREFIX.get_or_init(|| Regex::new(r"^([^:\n]+):(\d+):\s*").unwrap())
+}
+
+/// Runs of digits and long hex ids, which differ between occurrences
+fn variable_parts() -> &'static Regex {
+    static VARIABLE: OnceLock<Regex> = OnceLock::new();
+    VARIABLE.get_or_init(|| Regex::new(r"\b[0-9a-fA-F]{8,}(?:-[0-9a-fA-F]+)*\b|\d+").unwrap())
+}
+
+/// What makes two errors the same: the location prefix as is, the rest of
+/// the first line with numbers and ids masked, and the stack frames
+pub fn fingerprint(message: &str) -> String {
+    let mut lines = message.lines().map(str::trim).filter(|line| !line.is_empty());
+    let first = lines.next().unwrap_or_default();
+    let (location, text) = match location_prefix().find(first) {
+        Some(prefix) => first.split_at(prefix.end()),
+        None => ("", first),
+    };
+    let mut key = format!("{}{}", location, variable_parts().replace_all(text, "#"));
+    for frame in lines.filter(|line| !line.starts_with("Stack Begin") && !line.starts_with("Stack End")) {
+        key.push('\n');
+        key.push_str(frame);
+    }
+    key
+}
+
+/// An error's first line without its location prefix, and a short location
+/// (`Combat.server.luau:88`, or `Combat:88` when it has no local file)
+pub fn describe(group: &ErrorGroup) -> (String, Option<String>) {
+    let first = group.message.lines().next().unwrap_or_default();
+    let prefix = location_prefix().captures(first);
+    let text = match &prefix {
+        Some(captures) => first[captures.get(0).map_or(0, |m| m.end())..].to_string(),
+        None => first.to_string(),
+    };
+    let location = match (group.locations.first(), prefix) {
+        (Some(location), _) => {
+            let file = std::path::Path::new(&location.file).file_name().map(|f| f.to_string_lossy().to_string());
+            Some(format!("{}:{}", file.unwrap_or_else(|| location.instance_path.clone()), location.line))
+        }
+        (None, Some(captures)) => {
+            let path = &captures[1];
+            Some(format!("{}:{}", path.rsplit('.').next().unwrap_or(path), &captures[2]))
+        }
+        (None, None) => None,
+    };
+    (text, location)
+}
+
+/// Summarize error groups (most frequent first, as from [`aggregate_errors`])
+/// and warning groups, listing the `limit` most frequent errors
+pub fn summarize(errors: &[ErrorGroup], warnings: &[ErrorGroup], limit: usize) -> ErrorSummary {
+    let top: Vec<TopError> = errors
+        .iter()
+        .take(limit)
+        .map(|group| {
+            let (message, location) = describe(group);
+            TopError {
+                message,
+                location,
+                count: group.count,
+                first_seen: group.first_seen.clone(),
+                last_seen: group.last_seen.clone(),
+                sources: group.sources.clone(),
+                link: group.locations.first().map(|l| l.link.clone()),
+            }
+        })
+        .collect();
+    let total = errors.iter().map(|g| g.count).sum();
+    let summary = match top.first() {
+        None => "No errors".to_string(),
+        Some(first) => format!(
+            "{} unique error{} ({} total), top: {}{} (x{})",
+            errors.len(),
+            if errors.len() == 1 { "" } else { "s" },
+            total,
+            first.message,
+            first.location.as_ref().map(|l| format!(" in {}", l)).unwrap_or_default(),
+            first.count
+        ),
+    };
+    ErrorSummary {
+        unique: errors.len(),
+        total,
+        unique_warnings: warnings.len(),
+        warnings: warnings.iter().map(|g| g.count).sum(),
+        top,
+        summary,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ErrorSummaryQuery {
+    /// Only messages with this tag (`production`, or `studio` for untagged)
+    pub tag: Option<String>,
+    /// Errors to list (default: 5)
+    pub top: Option<usize>,
+}
+
+/// Deduplicated errors and warnings (GET /console/errors/summary)
+pub async fn handle_error_summary(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ErrorSummaryQuery>,
+) -> impl IntoResponse {
+    let buffer = state.console_buffer.read().await;
+    let tag = params.tag.as_deref();
+    let errors = aggregate_errors(buffer.iter(), tag, false);
+    let warnings = aggregate_errors(buffer.iter().filter(|m| m.message_type == "warn"), tag, true);
+    let summary = summarize(&errors, &warnings, params.top.unwrap_or(DEFAULT_TOP));
+    let mut body = serde_json::to_value(summary).unwrap_or_default();
+    body["success"] = serde_json::Value::Bool(true);
+    Json(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(message_type: &str, source: &str, text: &str, received_at: Option<u64>) -> ConsoleMessage {
+        ConsoleMessage {
+            timestamp: "12:00:00".to_string(),
+            message_type: message_type.to_string(),
+            message: text.to_string(),
+            source: Some(source.to_string()),
+            tags: Vec::new(),
+            count: None,
+            locations: Vec::new(),
+            received_at,
+        }
+    }
+
+    #[test]
+    fn test_console_filter() {
+        let now = 10_000;
+        let messages = [
+            message("info", "studio", "Loaded map", Some(9_000)),
+            message("warn", "studio", "Infinite yield possible on Players.Bob", Some(9_500)),
+            message("error", "production:1818", "DataStore request was throttled", Some(9_900)),
+            message("error", "plugin", "Old message", None),
+        ];
+        let select = |query: ConsoleHistoryQuery| {
+            let (selected, _) = ConsoleFilter::new(&query, now).unwrap().select(messages.iter(), None);
+            selected.into_iter().map(|m| m.message).collect::<Vec<_>>()
+        };
+
+        assert_eq!(select(ConsoleHistoryQuery::default()).len(), 4);
+        assert_eq!(
+            select(ConsoleHistoryQuery { level: Some("warn".into()), grep: Some("YIELD".into()), ..Default::default() }),
+            vec!["Infinite yield possible on Players.Bob"]
+        );
+        assert_eq!(
+            select(ConsoleHistoryQuery { source: Some("production".into()), ..Default::default() }),
+            vec!["DataStore request was throttled"]
+        );
+        assert_eq!(
+            select(ConsoleHistoryQuery { grep: Some(r"^(Loaded|Old) ".into()), regex: Some(true), ..Default::default() }),
+            vec!["Loaded map", "Old message"]
+        );
+        assert_eq!(
+            select(ConsoleHistoryQuery { since: Some("10m".into()), until: Some("9800".into()), ..Default::default() }),
+            vec!["Infinite yield possible on Players.Bob"]
+        );
+
+        let (newest, matched) = ConsoleFilter::new(&ConsoleHistoryQuery::default(), now).unwrap().select(messages.iter(), Some(1));
+        assert_eq!((newest[0].message.as_str(), matched), ("Old message", 4));
+
+        assert!(ConsoleFilter::new(&ConsoleHistoryQuery { level: Some("loud".into()), ..Default::default() }, now).is_err());
+        assert!(ConsoleFilter::new(&ConsoleHistoryQuery { grep: Some("(".into()), regex: Some(true), ..Default::default() }, now)
+            .is_err());
+        assert_eq!(parse_time("2h", now), Some(2_800));
+        assert_eq!(parse_time("soon", now), None);
+        assert_eq!(is_jsonl("out/console.jsonl", None), Ok(true));
+        assert_eq!(is_jsonl("console.log", None), Ok(false));
+    }
+
+    #[test]
+    fn test_error_summary() {
+        assert_eq!(
+            fingerprint("ServerScriptService.Combat:88: attempt to index nil with 'Health' (player 1234)"),
+            "ServerScriptService.Combat:88: attempt to index nil with 'Health' (player #)"
+        );
+        assert_eq!(
+            fingerprint("Boom\nStack Begin\nScript 'ServerScriptService.Combat', Line 88\nStack End"),
+            "Boom\nScript 'ServerScriptService.Combat', Line 88"
+        );
+
+        let messages = vec![
+            message("error", "studio", "ServerScriptService.Combat:88: attempt to index nil (player 17)", None),
+            message("error", "studio", "ServerScriptService.Combat:88: attempt to index nil (player 4022)", None),
+            message("error", "studio", "ServerScriptService.Combat:91: attempt to index nil (player 17)", None),
+            message("warn", "studio", "Infinite yield possible on 'Players.Bob:WaitForChild(\"Tool\")'", None),
+            message("warn", "studio", "Infinite yield possible on 'Players.Bob:WaitForChild(\"Tool\")'", None),
+        ];
+        let errors = aggregate_errors(&messages, None, false);
+        let warnings = aggregate_errors(messages.iter().filter(|m| m.message_type == "warn"), None, true);
+        let summary = summarize(&errors, &warnings, 5);
+        assert_eq!((summary.unique, summary.total, summary.unique_warnings, summary.warnings), (2, 3, 1, 2));
+        assert_eq!(summary.top[0].location.as_deref(), Some("Combat:88"));
+        assert_eq!(
+            summary.summary,
+            "2 unique errors (3 total), top: attempt to index nil (player 17) in Combat:88 (x2)"
+        );
+        assert_eq!(summarize(&[], &[], 5).summary, "No errors");
+    }
+}
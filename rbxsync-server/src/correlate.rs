@@ -0,0 +1,205 @@
+//! Runtime log correlation
+//!
+//! Playtest errors reference scripts by their DataModel path, e.g.
+//! `ServerScriptService.Combat.Handler:12: attempt to index nil` or a stack
+//! line like `Script 'ServerScriptService.Combat', Line 40`. This module pulls
+//! those locations out of a message and resolves them through the project's
+//! treeMapping to the local `.luau` file, so console events and the error
+//! summary can carry `file:line` links an editor can open directly.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub use rbxsync_core::api::SourceLocation;
+
+/// Extensions a script file can have, longest first
+const SCRIPT_EXTENSIONS: [&str; 4] = [".server.luau", ".client.luau", ".luau", ".lua"];
+
+/// Script paths and line numbers mentioned in a message, in order of appearance.
+///
+/// Recognizes `Path.To.Script:12`, `Script 'Path.To.Script', Line 12` and
+/// `Path.To.Script, line 12`. A leading `game.` is dropped.
+pub fn parse_locations(message: &str) -> Vec<(String, u32)> {
+    let mut found: Vec<(String, u32)> = Vec::new();
+    for line in message.lines() {
+        let line = line.trim();
+        let parsed = parse_quoted(line)
+            .or_else(|| parse_comma_line(line))
+            .into_iter()
+            .chain(parse_colon_lines(line));
+        for (path, number) in parsed {
+            let path = path.strip_prefix("game.").unwrap_or(&path).to_string();
+            if path.contains('.') && !found.iter().any(|(p, n)| *p == path && *n == number) {
+                found.push((path, number));
+            }
+        }
+    }
+    found
+}
+
+/// `Script 'Path.To.Script', Line 12`
+fn parse_quoted(line: &str) -> Option<(String, u32)> {
+    let rest = line.strip_prefix("Script '")?;
+    let (path, rest) = rest.split_once("', Line ")?;
+    Some((path.to_string(), leading_number(rest)?))
+}
+
+/// `Path.To.Script, line 12 - function foo`
+fn parse_comma_line(line: &str) -> Option<(String, u32)> {
+    let (path, rest) = line.split_once(", line ")?;
+    if path.contains(' ') {
+        return None;
+    }
+    Some((path.to_string(), leading_number(rest)?))
+}
+
+/// Every `Path.To.Script:12` in the line
+fn parse_colon_lines(line: &str) -> Vec<(String, u32)> {
+    let mut found = Vec::new();
+    for (idx, _) in line.match_indices(':') {
+        let Some(number) = leading_number(&line[idx + 1..]) else {
+            continue;
+        };
+        let start = line[..idx]
+            .rfind(|c: char| c.is_whitespace() || c == '"' || c == '\'' || c == '(')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let path = &line[start..idx];
+        if !path.is_empty() && !path.contains(':') {
+            found.push((path.to_string(), number));
+        }
+    }
+    found
+}
+
+fn leading_number(s: &str) -> Option<u32> {
+    let digits: &str = &s[..s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len())];
+    digits.parse().ok()
+}
+
+/// Strip a script extension and the `_xxxxxxxx` disambiguation suffix from a file or directory name
+fn instance_name(file_name: &str) -> (String, bool) {
+    for ext in SCRIPT_EXTENSIONS {
+        if let Some(stem) = file_name.strip_suffix(ext) {
+            return (crate::strip_disambiguation_suffix(stem), true);
+        }
+    }
+    (crate::strip_disambiguation_suffix(file_name), false)
+}
+
+/// Resolve a dotted DataModel path to a script file under the project's src directory
+pub fn resolve(instance_path: &str, project_dir: &Path, tree_mapping: &HashMap<String, String>) -> Option<PathBuf> {
+    let segments: Vec<&str> = instance_path.split('.').collect();
+    let src_dir = project_dir.join("src");
+
+    // Longest treeMapping prefix, compared segment by segment
+    let mut start = (src_dir.clone(), 0);
+    let mut best_len = 0;
+    for (dm_prefix, fs_prefix) in tree_mapping {
+        let prefix: Vec<&str> = dm_prefix.split('/').collect();
+        if prefix.len() > best_len && segments.len() > prefix.len() && segments[..prefix.len()] == prefix[..] {
+            best_len = prefix.len();
+            start = (src_dir.join(fs_prefix), prefix.len());
+        }
+    }
+
+    resolve_in(&start.0, &segments[start.1..]).or_else(|| resolve_in(&src_dir, &segments))
+}
+
+/// Match the remaining segments against directory entries. Instance names
+/// may themselves contain dots, so several segments can form one name.
+fn resolve_in(dir: &Path, segments: &[&str]) -> Option<PathBuf> {
+    if segments.is_empty() {
+        return init_script(dir);
+    }
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir).ok()?.flatten().map(|e| e.path()).collect();
+    entries.sort();
+
+    for take in 1..=segments.len() {
+        let name = segments[..take].join(".");
+        let rest = &segments[take..];
+        for path in &entries {
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            let (entry_name, is_script) = instance_name(file_name);
+            if entry_name != name {
+                continue;
+            }
+            if path.is_dir() {
+                if let Some(found) = resolve_in(path, rest) {
+                    return Some(found);
+                }
+            } else if is_script && rest.is_empty() {
+                return Some(path.clone());
+            }
+        }
+    }
+    None
+}
+
+/// `init.server.luau`, `init.client.luau` or `init.luau` of a script folder
+fn init_script(dir: &Path) -> Option<PathBuf> {
+    SCRIPT_EXTENSIONS
+        .iter()
+        .map(|ext| dir.join(format!("init{}", ext)))
+        .find(|path| path.is_file())
+}
+
+/// Resolve every location in a message against the candidate projects; the first project that has the script wins
+pub fn correlate(message: &str, project_dirs: &[PathBuf]) -> Vec<SourceLocation> {
+    let parsed = parse_locations(message);
+    if parsed.is_empty() {
+        return Vec::new();
+    }
+    let mappings: Vec<HashMap<String, String>> = project_dirs
+        .iter()
+        .map(|dir| crate::get_tree_mapping(&crate::load_project_config(&dir.to_string_lossy())))
+        .collect();
+
+    parsed
+        .into_iter()
+        .filter_map(|(instance_path, line)| {
+            let file = project_dirs
+                .iter()
+                .zip(&mappings)
+                .find_map(|(dir, mapping)| resolve(&instance_path, dir, mapping))?;
+            let file = std::fs::canonicalize(&file).unwrap_or(file).to_string_lossy().to_string();
+            Some(SourceLocation {
+                link: format!("{}:{}", file, line),
+                instance_path,
+                line,
+                file,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_correlate_console_error() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        std::fs::write(root.join("rbxsync.json"), r#"{ "treeMapping": { "ServerScriptService": "server" } }"#).unwrap();
+        std::fs::create_dir_all(root.join("src/server/Combat")).unwrap();
+        std::fs::write(root.join("src/server/Combat/init.server.luau"), "").unwrap();
+        std::fs::write(root.join("src/server/Combat/Hit.Box.luau"), "").unwrap();
+        std::fs::create_dir_all(root.join("src/ReplicatedStorage")).unwrap();
+        std::fs::write(root.join("src/ReplicatedStorage/Util_a1b2c3d4.luau"), "").unwrap();
+
+        let message = "ServerScriptService.Combat.Hit.Box:12: attempt to index nil\n\
+                       Script 'ServerScriptService.Combat', Line 40\n\
+                       game.ReplicatedStorage.Util, line 3 - function clamp";
+        assert_eq!(parse_locations(message).len(), 3);
+
+        let locations = correlate(message, &[root.to_path_buf()]);
+        assert_eq!(locations.len(), 3);
+        assert!(locations[0].file.ends_with("Hit.Box.luau"));
+        assert_eq!(locations[0].line, 12);
+        assert!(locations[1].link.ends_with("init.server.luau:40"));
+        assert_eq!(locations[2].instance_path, "ReplicatedStorage.Util");
+
+        assert!(correlate("Workspace.Missing:1: boom", &[root.to_path_buf()]).is_empty());
+    }
+}
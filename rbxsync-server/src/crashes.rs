@@ -0,0 +1,289 @@
+//! Supervised restarts and crash reports
+//!
+//! `rbxsync serve --background --supervised` runs a small supervisor process
+//! that starts the server as a child and restarts it whenever it exits with an
+//! error, waiting twice as long after each crash in a row. Every crash leaves
+//! a report in `~/.rbxsync/crashes/` with the panic message, the last lines
+//! the server logged and the journaled operations that were in flight;
+//! `/health` and `rbxsync status` show the most recent one.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Set in the environment of supervised servers
+pub const SUPERVISED_ENV: &str = "RBXSYNC_SUPERVISED";
+
+/// Log lines kept for a crash report
+const LOG_TAIL_LINES: usize = 200;
+
+/// Restart delay after the first crash, doubled for each crash in a row
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A server that ran this long before crashing starts over at the minimum delay
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+
+/// Journaled operations in progress, by project directory
+static OPEN_OPERATIONS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// Written by the panic hook for the supervisor to pick up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PanicRecord {
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub open_operations: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    /// Unix seconds when the server exited
+    pub timestamp: u64,
+    pub pid: u32,
+    /// How the process ended (e.g. "exit status: 101")
+    pub exit_status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub panic: Option<PanicRecord>,
+    /// Last lines written to stdout and stderr
+    #[serde(default)]
+    pub log_tail: Vec<String>,
+    /// Restarts by this supervisor before this crash
+    pub restarts: u32,
+}
+
+/// `~/.rbxsync/crashes`
+pub fn crash_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".rbxsync").join("crashes"))
+}
+
+/// Called when a journaled operation starts writing to a project
+pub fn operation_started(project_dir: &Path, operation: &str) {
+    OPEN_OPERATIONS
+        .lock()
+        .unwrap()
+        .insert(project_dir.to_string_lossy().to_string(), operation.to_string());
+}
+
+pub fn operation_finished(project_dir: &Path) {
+    OPEN_OPERATIONS.lock().unwrap().remove(project_dir.to_string_lossy().as_ref());
+}
+
+/// Operations in progress, as `<operation> in <project_dir>`
+pub fn open_operations() -> Vec<String> {
+    // The hook may run while a panicking thread holds the lock
+    let operations = match OPEN_OPERATIONS.lock() {
+        Ok(operations) => operations,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    operations.iter().map(|(dir, op)| format!("{} in {}", op, dir)).collect()
+}
+
+fn panic_file(dir: &Path, pid: u32) -> PathBuf {
+    dir.join(format!("panic-{}.json", pid))
+}
+
+/// Record panics for the supervisor, when running under one
+pub fn install_panic_hook() {
+    if std::env::var_os(SUPERVISED_ENV).is_none() {
+        return;
+    }
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        let record = PanicRecord {
+            message,
+            location: info.location().map(|l| format!("{}:{}", l.file(), l.line())),
+            open_operations: open_operations(),
+        };
+        if let Some(dir) = crash_dir() {
+            let _ = std::fs::create_dir_all(&dir);
+            if let Ok(json) = serde_json::to_string(&record) {
+                let _ = std::fs::write(panic_file(&dir, std::process::id()), json);
+            }
+        }
+        previous(info);
+    }));
+}
+
+/// The panic recorded by a server process, removing its file
+pub fn take_panic(dir: &Path, pid: u32) -> Option<PanicRecord> {
+    let path = panic_file(dir, pid);
+    let record = std::fs::read_to_string(&path).ok().and_then(|c| serde_json::from_str(&c).ok());
+    let _ = std::fs::remove_file(path);
+    record
+}
+
+/// Delay before restarting after `consecutive` crashes in a row
+pub fn backoff(consecutive: u32) -> Duration {
+    MIN_BACKOFF
+        .checked_mul(1 << consecutive.saturating_sub(1).min(16))
+        .map_or(MAX_BACKOFF, |d| d.min(MAX_BACKOFF))
+}
+
+pub fn write_report(dir: &Path, report: &CrashReport) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("crash-{}-{}.json", report.timestamp, report.pid));
+    let json = serde_json::to_string_pretty(report).map_err(std::io::Error::other)?;
+    std::fs::write(&path, json)?;
+    Ok(path)
+}
+
+/// Most recent crash report in `dir`
+pub fn latest_crash(dir: &Path) -> Option<(PathBuf, CrashReport)> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("crash-")))
+        .filter_map(|path| {
+            let report: CrashReport = serde_json::from_str(&std::fs::read_to_string(&path).ok()?).ok()?;
+            Some((path, report))
+        })
+        .max_by_key(|(path, report)| (report.timestamp, path.clone()))
+}
+
+/// Most recent crash report in `~/.rbxsync/crashes`
+pub fn last_crash() -> Option<(PathBuf, CrashReport)> {
+    latest_crash(&crash_dir()?)
+}
+
+/// Run the server command until it exits cleanly, restarting it after crashes
+/// and writing a report to `dir` for each
+pub fn supervise(mut server: impl FnMut() -> Command, dir: &Path) -> std::io::Result<()> {
+    let mut restarts = 0;
+    let mut consecutive = 0;
+    loop {
+        let started = Instant::now();
+        let mut child = server()
+            .env(SUPERVISED_ENV, "1")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let pid = child.id();
+
+        let tail: Arc<Mutex<VecDeque<String>>> = Arc::default();
+        let readers: Vec<std::thread::JoinHandle<()>> = [
+            child.stdout.take().map(|s| Box::new(s) as Box<dyn std::io::Read + Send>),
+            child.stderr.take().map(|s| Box::new(s) as Box<dyn std::io::Read + Send>),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|stream| {
+            let tail = tail.clone();
+            std::thread::spawn(move || {
+                for line in std::io::BufReader::new(stream).lines().map_while(Result::ok) {
+                    let mut tail = tail.lock().unwrap();
+                    if tail.len() == LOG_TAIL_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line);
+                }
+            })
+        })
+        .collect();
+
+        let status = child.wait()?;
+        for reader in readers {
+            let _ = reader.join();
+        }
+        if status.success() {
+            return Ok(());
+        }
+
+        let report = CrashReport {
+            timestamp: crate::nightly::now_secs(),
+            pid,
+            exit_status: status.to_string(),
+            panic: take_panic(dir, pid),
+            log_tail: tail.lock().unwrap().iter().cloned().collect(),
+            restarts,
+        };
+        match write_report(dir, &report) {
+            Ok(path) => eprintln!("Server crashed ({}), report: {}", report.exit_status, path.display()),
+            Err(e) => eprintln!("Server crashed ({}), failed to write report: {}", report.exit_status, e),
+        }
+
+        consecutive = if started.elapsed() >= STABLE_AFTER { 1 } else { consecutive + 1 };
+        std::thread::sleep(backoff(consecutive));
+        restarts += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crash_reports_and_supervised_restart() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(3), Duration::from_secs(4));
+        assert_eq!(backoff(40), MAX_BACKOFF);
+
+        operation_started(Path::new("/games/obby"), "sync-from-studio");
+        assert_eq!(open_operations(), vec!["sync-from-studio in /games/obby".to_string()]);
+        operation_finished(Path::new("/games/obby"));
+        assert!(open_operations().is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let crashes = dir.path().join("crashes");
+        assert!(latest_crash(&crashes).is_none());
+        let record = PanicRecord { message: "boom".to_string(), location: None, open_operations: vec![] };
+        std::fs::create_dir_all(&crashes).unwrap();
+        std::fs::write(panic_file(&crashes, 7), serde_json::to_string(&record).unwrap()).unwrap();
+        assert_eq!(take_panic(&crashes, 7), Some(record.clone()));
+        assert_eq!(take_panic(&crashes, 7), None);
+
+        let report = |timestamp, pid| CrashReport {
+            timestamp,
+            pid,
+            exit_status: "exit status: 101".to_string(),
+            panic: Some(record.clone()),
+            log_tail: vec![],
+            restarts: 0,
+        };
+        write_report(&crashes, &report(200, 2)).unwrap();
+        write_report(&crashes, &report(100, 1)).unwrap();
+        assert_eq!(latest_crash(&crashes).unwrap().1, report(200, 2));
+
+        // Crashes once (leaving a log line), then exits cleanly on restart
+        #[cfg(unix)]
+        {
+            let marker = dir.path().join("restarted");
+            let script = format!(
+                "if [ -f '{0}' ]; then exit 0; fi; touch '{0}'; echo 'listening'; echo 'fatal' >&2; exit 3",
+                marker.display()
+            );
+            let empty = dir.path().join("supervised");
+            supervise(
+                || {
+                    let mut command = Command::new("sh");
+                    command.args(["-c", &script]);
+                    command
+                },
+                &empty,
+            )
+            .unwrap();
+            let (_, crash) = latest_crash(&empty).unwrap();
+            assert_eq!(crash.exit_status, "exit status: 3");
+            assert_eq!(crash.restarts, 0);
+            let mut tail = crash.log_tail.clone();
+            tail.sort();
+            assert_eq!(tail, vec!["fatal".to_string(), "listening".to_string()]);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! File change event stream
+//!
+//! Exposes the server's file watcher to other tools over Server-Sent Events, so
+//! `rbxsync build --watch` and `rbxsync dev` react to the same debounced changes
+//! that drive live sync instead of running watchers of their own.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+};
+use serde::Deserialize;
+use tokio::sync::broadcast;
+
+use crate::{file_watcher, load_project_config, normalize_path, AppState};
+
+/// Query for subscribing to file events
+#[derive(Debug, Deserialize)]
+pub struct EventsQuery {
+    /// Only stream changes for this project (and start watching it)
+    #[serde(default)]
+    pub project_dir: Option<String>,
+    /// Also live-sync changes to Studio. Without this, a project that isn't
+    /// otherwise watched is watched for events only.
+    #[serde(default)]
+    pub live_sync: bool,
+}
+
+/// Start watching a project directory.
+///
+/// With `live_sync` the project's changes are pushed to Studio; otherwise they
+/// are only broadcast to `/events` subscribers, unless something else (e.g. a
+/// VS Code workspace) already enabled live sync for it.
+pub async fn watch_project(state: &Arc<AppState>, project_dir: &str, live_sync: bool) {
+    crate::logs::register_project(project_dir);
+    crate::claim_workspace(state, project_dir);
+    crate::persist::restore_project(state, project_dir).await;
+    {
+        let mut watcher = state.file_watcher_state.write().await;
+        if live_sync {
+            watcher.event_only_dirs.remove(project_dir);
+        } else if !watcher.watched_dirs.contains(project_dir) {
+            watcher.event_only_dirs.insert(project_dir.to_string());
+        }
+    }
+
+    let watcher_state = state.file_watcher_state.clone();
+    let dir = project_dir.to_string();
+
+    // Load config to check package sync settings
+    let config = load_project_config(&dir);
+    let packages_config = config.as_ref().and_then(|c| c.get("packages"));
+
+    // Check if packages should sync (excludeFromWatch: false means sync packages)
+    let sync_packages = packages_config
+        .and_then(|p| p.get("excludeFromWatch"))
+        .and_then(|v| v.as_bool())
+        .map(|exclude| !exclude) // Invert: excludeFromWatch=false means sync_packages=true
+        .unwrap_or(false); // Default: don't sync packages (for backwards compatibility)
+
+    tokio::spawn(async move {
+        if let Err(e) = file_watcher::start_file_watcher(dir, watcher_state, sync_packages).await {
+            tracing::error!("Failed to start file watcher: {}", e);
+        }
+    });
+}
+
+/// Subscribe to debounced file change batches via Server-Sent Events (GET /events)
+pub async fn handle_events(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<EventsQuery>,
+) -> impl IntoResponse {
+    let project_dir = query.project_dir.as_deref().map(normalize_path);
+    crate::idle::resume(&state).await;
+    if let Some(dir) = &project_dir {
+        watch_project(&state, dir, query.live_sync).await;
+    }
+
+    let mut rx = state.file_events_tx.subscribe();
+
+    let stream = async_stream::stream! {
+        loop {
+            match rx.recv().await {
+                Ok(batch) => {
+                    if project_dir.as_ref().is_some_and(|dir| *dir != batch.project_dir) {
+                        continue;
+                    }
+                    let json = serde_json::to_string(&batch).unwrap_or_default();
+                    yield Ok::<_, Infallible>(Event::default().event("change").data(json));
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    // Client fell behind, continue
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    break;
+                }
+            }
+        }
+    };
+
+    Sse::new(stream).keep_alive(
+        KeepAlive::new()
+            .interval(std::time::Duration::from_secs(15))
+            .text("keepalive"),
+    )
+}
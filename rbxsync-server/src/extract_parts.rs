@@ -0,0 +1,204 @@
+//! Multi-part extraction payloads
+//!
+//! A single instance with a huge Source string or serialized blob can be larger
+//! than one request may be. The plugin sends such an instance as its JSON text
+//! split into parts (`part` set on `/extract/chunk`), and the server joins them
+//! back together once all have arrived, checking the length and an Adler-32
+//! checksum of the whole text before parsing it.
+//!
+//! The limits the plugin should split by are advertised in `/extract/start`.
+
+use std::collections::HashMap;
+
+pub use rbxsync_core::api::{ChunkPart, ExtractLimits};
+
+/// Largest request body the server accepts
+pub const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/// Largest instance the plugin should send in a regular chunk, and the size of
+/// each part. Kept well under Roblox's HttpService request limit, leaving room
+/// for JSON escaping.
+pub const MAX_PART_BYTES: usize = 512 * 1024;
+
+/// Largest instance that can be reassembled from parts
+pub const MAX_INSTANCE_BYTES: usize = 256 * 1024 * 1024;
+
+pub fn limits() -> ExtractLimits {
+    ExtractLimits {
+        max_body_bytes: MAX_BODY_BYTES,
+        max_part_bytes: MAX_PART_BYTES,
+        max_instance_bytes: MAX_INSTANCE_BYTES,
+    }
+}
+
+/// Parts received so far for one instance
+struct PartialInstance {
+    total: usize,
+    total_bytes: usize,
+    checksum: u32,
+    parts: Vec<Option<String>>,
+    received: usize,
+}
+
+/// Collects parts of oversized instances until they are complete
+#[derive(Default)]
+pub struct PartAssembler {
+    pending: HashMap<String, PartialInstance>,
+}
+
+// Manual impl so session logs don't dump part contents
+impl std::fmt::Debug for PartAssembler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.pending.iter().map(|(id, p)| (id, format!("{}/{} parts", p.received, p.total))))
+            .finish()
+    }
+}
+
+/// Result of adding a part
+#[derive(Debug)]
+pub enum PartProgress {
+    /// Still waiting for more parts
+    Pending { received: usize, total: usize },
+    /// All parts arrived and the instance checked out
+    Complete(serde_json::Value),
+}
+
+impl PartAssembler {
+    /// Instances with parts still missing
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Add one part. A part that doesn't match the others, or a completed
+    /// instance that fails its integrity checks, discards the instance.
+    pub fn add(&mut self, part: &ChunkPart, data: &serde_json::Value) -> Result<PartProgress, String> {
+        let result = self.add_inner(part, data);
+        if result.is_err() {
+            self.pending.remove(&part.instance_id);
+        }
+        result
+    }
+
+    fn add_inner(&mut self, part: &ChunkPart, data: &serde_json::Value) -> Result<PartProgress, String> {
+        let text = data
+            .as_str()
+            .ok_or_else(|| format!("Part {} of {} must be a string", part.index, part.instance_id))?;
+        if part.total == 0 || part.index >= part.total {
+            return Err(format!("Part index {} out of range (total {})", part.index, part.total));
+        }
+        if part.total_bytes > MAX_INSTANCE_BYTES {
+            return Err(format!(
+                "Instance {} is {} bytes, over the {} byte limit",
+                part.instance_id, part.total_bytes, MAX_INSTANCE_BYTES
+            ));
+        }
+
+        let partial = self
+            .pending
+            .entry(part.instance_id.clone())
+            .or_insert_with(|| PartialInstance {
+                total: part.total,
+                total_bytes: part.total_bytes,
+                checksum: part.checksum,
+                parts: vec![None; part.total],
+                received: 0,
+            });
+
+        if partial.total != part.total || partial.total_bytes != part.total_bytes || partial.checksum != part.checksum {
+            return Err(format!("Part {} of {} disagrees with earlier parts", part.index, part.instance_id));
+        }
+        if partial.parts[part.index].is_none() {
+            partial.received += 1;
+        }
+        // A retried part replaces the earlier copy
+        partial.parts[part.index] = Some(text.to_string());
+
+        if partial.received < partial.total {
+            return Ok(PartProgress::Pending {
+                received: partial.received,
+                total: partial.total,
+            });
+        }
+
+        let partial = self.pending.remove(&part.instance_id).unwrap();
+        let joined: String = partial.parts.into_iter().flatten().collect();
+        if joined.len() != partial.total_bytes {
+            return Err(format!(
+                "Instance {} is {} bytes after reassembly, expected {}",
+                part.instance_id,
+                joined.len(),
+                partial.total_bytes
+            ));
+        }
+        let checksum = adler32(joined.as_bytes());
+        if checksum != partial.checksum {
+            return Err(format!(
+                "Checksum mismatch for instance {} (got {:08x}, expected {:08x})",
+                part.instance_id, checksum, partial.checksum
+            ));
+        }
+        serde_json::from_str(&joined)
+            .map(PartProgress::Complete)
+            .map_err(|e| format!("Reassembled instance {} is not valid JSON: {}", part.instance_id, e))
+    }
+}
+
+/// Adler-32 checksum (simple enough for the plugin to compute in Luau)
+pub fn adler32(bytes: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 is the most bytes that can be summed before b could overflow
+    for block in bytes.chunks(5552) {
+        for &byte in block {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reassemble_parts() {
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+
+        let json = serde_json::json!({ "className": "ModuleScript", "source": "ü".repeat(100) }).to_string();
+        let mid = (json.len() / 2..).find(|&i| json.is_char_boundary(i)).unwrap();
+        let (first, second) = json.split_at(mid);
+        let part = |index: usize, checksum: u32| ChunkPart {
+            instance_id: "big".to_string(),
+            index,
+            total: 2,
+            total_bytes: json.len(),
+            checksum,
+        };
+
+        let mut assembler = PartAssembler::default();
+        let checksum = adler32(json.as_bytes());
+        // Parts may arrive out of order
+        assert!(matches!(
+            assembler.add(&part(1, checksum), &second.into()),
+            Ok(PartProgress::Pending { received: 1, total: 2 })
+        ));
+        assert_eq!(assembler.pending(), 1);
+        match assembler.add(&part(0, checksum), &first.into()) {
+            Ok(PartProgress::Complete(value)) => assert_eq!(value["className"], "ModuleScript"),
+            other => panic!("expected complete instance, got {:?}", other),
+        }
+        assert_eq!(assembler.pending(), 0);
+
+        assembler.add(&part(0, checksum + 1), &first.into()).unwrap();
+        assert!(assembler.add(&part(1, checksum + 1), &second.into()).is_err());
+        assert_eq!(assembler.pending(), 0);
+    }
+}
@@ -0,0 +1,375 @@
+//! Streaming extraction writes
+//!
+//! A full extraction used to hold every instance of the game in memory until
+//! finalize wrote `src/` in one go, which on a 500k-instance place meant
+//! gigabytes of JSON. Instead, [`SrcWriter`] writes each chunk as it arrives,
+//! into [`STAGING_DIR`] so `src/` stays as it was until finalize swaps the new
+//! tree in (see [`crate::recovery::swap_src`]). An instance is written as a
+//! sibling `.rbxjson` until one of its children shows up; that file then moves
+//! into the instance's directory as `_meta.rbxjson`, which is where finalize
+//! always put it.
+//!
+//! Duplicate sibling names are told apart as before: the first instance at a
+//! path keeps it and later ones get a `_<referenceId>` suffix. What that needs
+//! to remember is appended to `paths.jsonl` in the extraction's checkpoint
+//! directory, one line per instance, so a restarted server carries on where
+//! it left off. Finalize only reads the checkpoint back, a file at a time, for
+//! the extraction cache and asset manifest.
+//!
+//! Incremental extractions still merge in finalize, since the chunks only hold
+//! what changed.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::assets::ExtractionAssets;
+use crate::checkpoint;
+use crate::incremental::CacheWriter;
+
+pub const INDEX_FILE: &str = "paths.jsonl";
+
+/// Where a full extraction is written before it replaces src, relative to the
+/// project directory
+pub const STAGING_DIR: &str = ".rbxsync/src.new";
+
+pub fn staging_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join(STAGING_DIR)
+}
+
+/// One written instance, as recorded in the index
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexEntry {
+    /// Path the plugin sent
+    path: String,
+    /// Path after telling duplicates apart
+    resolved: String,
+    /// Where the instance went, relative to the output directory, before any suffix
+    fs_path: String,
+    /// Written as a sibling `.rbxjson` rather than `_meta.rbxjson`
+    leaf: bool,
+    scripts: usize,
+    files: usize,
+    failed: usize,
+}
+
+/// Files written so far
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WriteTotals {
+    pub files: usize,
+    pub scripts: usize,
+    pub failed: usize,
+}
+
+/// Writes an extraction's instances to the staging directory as their chunks
+/// arrive
+#[derive(Debug)]
+pub struct SrcWriter {
+    project_dir: PathBuf,
+    out_dir: PathBuf,
+    index: PathBuf,
+    tree_mapping: HashMap<String, String>,
+    schema_ref: Option<String>,
+    omit_defaults: bool,
+    /// Instances seen per path the plugin sent
+    path_counts: HashMap<String, usize>,
+    /// Resolved paths something has been written under
+    containers: HashSet<String>,
+    /// Instances still written as sibling files, by resolved path
+    leaves: HashMap<String, PathBuf>,
+    services: HashSet<String>,
+    totals: WriteTotals,
+}
+
+/// File extension of a script's source, for classes that have one
+pub fn script_extension(class_name: &str) -> Option<&'static str> {
+    match class_name {
+        "Script" => Some(".server.luau"),
+        "LocalScript" => Some(".client.luau"),
+        "ModuleScript" => Some(".luau"),
+        _ => None,
+    }
+}
+
+impl SrcWriter {
+    fn new(project_dir: &Path, checkpoint_dir: &Path) -> Self {
+        let config = crate::load_project_config(&project_dir.to_string_lossy());
+        Self {
+            project_dir: project_dir.to_path_buf(),
+            out_dir: staging_dir(project_dir),
+            index: checkpoint_dir.join(INDEX_FILE),
+            tree_mapping: crate::get_tree_mapping(&config),
+            schema_ref: rbxsync_core::schema_ref(config.as_ref()),
+            omit_defaults: rbxsync_core::omit_defaults(config.as_ref()),
+            path_counts: HashMap::new(),
+            containers: HashSet::new(),
+            leaves: HashMap::new(),
+            services: HashSet::new(),
+            totals: WriteTotals::default(),
+        }
+    }
+
+    /// Start writing an extraction into an empty staging directory
+    pub fn create(project_dir: &Path, checkpoint_dir: &Path) -> Self {
+        let writer = Self::new(project_dir, checkpoint_dir);
+        let _ = std::fs::remove_dir_all(&writer.out_dir);
+        let _ = std::fs::create_dir_all(&writer.out_dir);
+        let _ = std::fs::create_dir_all(checkpoint_dir);
+        if let Err(e) = std::fs::write(&writer.index, "") {
+            tracing::warn!("Failed to create extraction index: {}", e);
+        }
+        writer
+    }
+
+    /// Carry on an extraction from its index; `None` when the extraction in
+    /// `checkpoint_dir` wasn't being streamed
+    pub fn resume(project_dir: &Path, checkpoint_dir: &Path) -> Option<Self> {
+        let file = std::fs::File::open(checkpoint_dir.join(INDEX_FILE)).ok()?;
+        let mut writer = Self::new(project_dir, checkpoint_dir);
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            if let Ok(entry) = serde_json::from_str::<IndexEntry>(&line) {
+                writer.track(&entry);
+            }
+        }
+        tracing::info!("Resumed writing extraction to {} ({} files so far)", writer.out_dir.display(), writer.totals.files);
+        Some(writer)
+    }
+
+    pub fn project_dir(&self) -> &Path {
+        &self.project_dir
+    }
+
+    /// Where the extraction is being written
+    pub fn out_dir(&self) -> &Path {
+        &self.out_dir
+    }
+
+    pub fn totals(&self) -> WriteTotals {
+        self.totals
+    }
+
+    /// Top-level folders that instances went into
+    pub fn services(&self) -> &HashSet<String> {
+        &self.services
+    }
+
+    /// Write freshly received instances and record them in the index
+    pub fn write(&mut self, instances: &[Value]) {
+        let index = OpenOptions::new().create(true).append(true).open(&self.index);
+        let mut index = match index {
+            Ok(file) => Some(BufWriter::new(file)),
+            Err(e) => {
+                tracing::warn!("Failed to open extraction index: {}", e);
+                None
+            }
+        };
+
+        for instance in instances {
+            let Some(entry) = self.write_instance(instance) else {
+                continue;
+            };
+            for full_path in self.track(&entry) {
+                move_into_directory(&full_path);
+            }
+            if let Some(index) = index.as_mut() {
+                let line = serde_json::to_string(&entry).unwrap_or_default();
+                if let Err(e) = writeln!(index, "{}", line) {
+                    tracing::warn!("Failed to write extraction index: {}", e);
+                }
+            }
+        }
+        if let Some(mut index) = index {
+            let _ = index.flush();
+        }
+    }
+
+    fn write_instance(&self, instance: &Value) -> Option<IndexEntry> {
+        let path = instance.get("path").and_then(|v| v.as_str()).filter(|p| !p.is_empty())?;
+        let class_name = instance.get("className").and_then(|v| v.as_str()).unwrap_or("Unknown");
+        let ref_id = instance.get("referenceId").and_then(|v| v.as_str()).unwrap_or("");
+
+        let resolved = if self.path_counts.contains_key(path) && !ref_id.is_empty() {
+            let suffix = if ref_id.len() >= 8 { &ref_id[..8] } else { ref_id };
+            tracing::warn!(
+                "Duplicate instance path detected: '{}' ({}). Disambiguating to '{}_{}'",
+                path, class_name, path, suffix
+            );
+            format!("{}_{}", path, suffix)
+        } else {
+            path.to_string()
+        };
+        let normalized = crate::normalize_package_path(&resolved);
+        let fs_path = crate::apply_tree_mapping(&normalized, &self.tree_mapping);
+        let full_path = self.out_dir.join(&fs_path);
+        let leaf = !self.containers.contains(&resolved) && !rbxsync_core::is_special_container(&normalized);
+
+        let mut entry = IndexEntry {
+            path: path.to_string(),
+            resolved,
+            fs_path,
+            leaf,
+            scripts: 0,
+            files: 0,
+            failed: 0,
+        };
+        if let Some(parent) = full_path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        let source = instance
+            .get("properties")
+            .and_then(|p| p.get("Source"))
+            .and_then(|v| v.get("value"))
+            .and_then(|v| v.as_str());
+        if let (Some(extension), Some(source)) = (script_extension(class_name), source) {
+            match std::fs::write(rbxsync_core::path_with_suffix(&full_path, extension), source) {
+                Ok(()) => entry.scripts += 1,
+                Err(_) => entry.failed += 1,
+            }
+        }
+
+        let json_path = if leaf {
+            rbxsync_core::pathbuf_with_suffix(&full_path, ".rbxjson")
+        } else {
+            let _ = std::fs::create_dir_all(&full_path);
+            full_path.join("_meta.rbxjson")
+        };
+        let clean = crate::clean_instance(instance, self.omit_defaults, self.schema_ref.as_deref());
+        let written = serde_json::to_string_pretty(&clean)
+            .map_err(std::io::Error::from)
+            .and_then(|json| std::fs::write(&json_path, json));
+        match written {
+            Ok(()) => entry.files += 1,
+            Err(_) => entry.failed += 1,
+        }
+        Some(entry)
+    }
+
+    /// Update what's known about written instances with one more. Returns the
+    /// sibling files that now have children and need to become directories.
+    fn track(&mut self, entry: &IndexEntry) -> Vec<PathBuf> {
+        *self.path_counts.entry(entry.path.clone()).or_insert(0) += 1;
+
+        let mut now_containers = Vec::new();
+        for (i, _) in entry.resolved.match_indices('/') {
+            let ancestor = &entry.resolved[..i];
+            if self.containers.insert(ancestor.to_string()) {
+                now_containers.extend(self.leaves.remove(ancestor));
+            }
+        }
+        if entry.leaf && !self.containers.contains(&entry.resolved) {
+            self.leaves.insert(entry.resolved.clone(), self.out_dir.join(&entry.fs_path));
+        }
+
+        if let Some(service) = entry.fs_path.split('/').next() {
+            if !self.services.contains(service) {
+                self.services.insert(service.to_string());
+            }
+        }
+        self.totals.files += entry.files;
+        self.totals.scripts += entry.scripts;
+        self.totals.failed += entry.failed;
+        now_containers
+    }
+}
+
+/// Move `<path>.rbxjson` to `<path>/_meta.rbxjson`
+fn move_into_directory(full_path: &Path) {
+    let from = rbxsync_core::pathbuf_with_suffix(full_path, ".rbxjson");
+    let moved = std::fs::create_dir_all(full_path).and_then(|_| std::fs::rename(&from, full_path.join("_meta.rbxjson")));
+    if let Err(e) = moved {
+        tracing::warn!("Failed to move {} into its directory: {}", from.display(), e);
+    }
+}
+
+/// Read a streamed extraction back from its checkpoint files, one at a time,
+/// to save the extraction cache and record assets. Returns the number of
+/// assets recorded, as [`crate::assets::record_extraction`] does.
+pub fn finish(project_dir: &Path, chunk_files: &[PathBuf], mut assets: Option<ExtractionAssets>) -> Option<usize> {
+    let mut cache = CacheWriter::create(project_dir)
+        .map_err(|e| tracing::warn!("Failed to write extraction cache: {}", e))
+        .ok();
+    for file in chunk_files {
+        let Some(instances) = checkpoint::read_chunk(file) else {
+            continue;
+        };
+        if let Some(assets) = assets.as_mut() {
+            assets.add(&instances);
+        }
+        if let Some(Err(e)) = cache.as_mut().map(|cache| cache.add(&instances)) {
+            tracing::warn!("Failed to write extraction cache: {}", e);
+            cache = None;
+        }
+    }
+    if let Some(Err(e)) = cache.map(CacheWriter::finish) {
+        tracing::warn!("Failed to write extraction cache: {}", e);
+    }
+    assets.and_then(|assets| assets.finish(project_dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn instance(path: &str, class_name: &str, reference: &str) -> Value {
+        json!({ "path": path, "className": class_name, "referenceId": reference, "properties": {} })
+    }
+
+    #[test]
+    fn test_children_turn_siblings_into_directories() {
+        let project = tempfile::tempdir().unwrap();
+        let checkpoint_dir = project.path().join(".rbxsync/extract_test");
+        let staged = staging_dir(project.path());
+        let mut writer = SrcWriter::create(project.path(), &checkpoint_dir);
+
+        // A child arriving after its parent moves the parent's file
+        writer.write(&[instance("Workspace/Model", "Model", "aaaaaaaa")]);
+        assert!(staged.join("Workspace/Model.rbxjson").exists());
+        writer.write(&[instance("Workspace/Model/Part", "Part", "bbbbbbbb")]);
+        assert!(!staged.join("Workspace/Model.rbxjson").exists());
+        assert!(staged.join("Workspace/Model/_meta.rbxjson").exists());
+        assert!(staged.join("Workspace/Model/Part.rbxjson").exists());
+
+        // A child arriving first makes the parent a directory from the start
+        let mut script = instance("ReplicatedStorage/Lib", "ModuleScript", "cccccccc");
+        script["properties"]["Source"] = json!({ "type": "string", "value": "return {}" });
+        writer.write(&[instance("ReplicatedStorage/Lib/Util", "Folder", "dddddddd"), script]);
+        assert!(staged.join("ReplicatedStorage/Lib/_meta.rbxjson").exists());
+        assert_eq!(std::fs::read_to_string(staged.join("ReplicatedStorage/Lib.luau")).unwrap(), "return {}");
+        let meta: Value = serde_json::from_str(&std::fs::read_to_string(staged.join("ReplicatedStorage/Lib/_meta.rbxjson")).unwrap()).unwrap();
+        assert!(meta["properties"].get("Source").is_none());
+
+        let totals = writer.totals();
+        assert_eq!((totals.files, totals.scripts, totals.failed), (4, 1, 0));
+        let mut services: Vec<&String> = writer.services().iter().collect();
+        services.sort();
+        assert_eq!(services, vec!["ReplicatedStorage", "Workspace"]);
+    }
+
+    #[test]
+    fn test_duplicates_resolved_across_resume() {
+        let project = tempfile::tempdir().unwrap();
+        let checkpoint_dir = project.path().join(".rbxsync/extract_test");
+        let staged = staging_dir(project.path());
+        let mut writer = SrcWriter::create(project.path(), &checkpoint_dir);
+        writer.write(&[instance("Workspace/Part", "Part", "11111111aaaa")]);
+
+        // A restarted server still knows Workspace/Part is taken and is a leaf
+        let mut writer = SrcWriter::resume(project.path(), &checkpoint_dir).unwrap();
+        writer.write(&[
+            instance("Workspace/Part", "Part", "22222222bbbb"),
+            instance("Workspace/Part/Attachment", "Attachment", "33333333cccc"),
+        ]);
+        assert!(staged.join("Workspace/Part_22222222.rbxjson").exists());
+        assert!(staged.join("Workspace/Part/_meta.rbxjson").exists());
+        assert!(!staged.join("Workspace/Part.rbxjson").exists());
+        assert_eq!(writer.totals().files, 3);
+
+        assert!(SrcWriter::resume(project.path(), &project.path().join(".rbxsync/other")).is_none());
+    }
+}
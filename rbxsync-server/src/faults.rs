@@ -0,0 +1,344 @@
+//! Fault injection for bot testing
+//!
+//! Makes the bot command pipeline misbehave on purpose: commands can be held
+//! back before delivery (latency and jitter) or dropped, and the in-game runner
+//! can pause and resume physics. Decisions come from a seeded generator and
+//! every injected fault is logged, so a run that exposed flaky behavior can be
+//! replayed with the same seed.
+//!
+//! Faults apply to every command going through `/bot/pending` until cleared;
+//! clearing returns the report for the run.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::AppState;
+
+/// Fault settings for a run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaultConfig {
+    /// Delay before each command is delivered to the game
+    #[serde(default)]
+    pub latency_ms: u64,
+    /// Extra random delay, up to this many milliseconds
+    #[serde(default)]
+    pub jitter_ms: u64,
+    /// Percentage of commands to drop (0-100)
+    #[serde(default)]
+    pub drop_percent: f64,
+    /// Seed for reproducible runs (random if omitted; the report includes it)
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl FaultConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=100.0).contains(&self.drop_percent) {
+            return Err("dropPercent must be between 0 and 100".to_string());
+        }
+        Ok(())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.latency_ms == 0 && self.jitter_ms == 0 && self.drop_percent <= 0.0
+    }
+}
+
+/// One injected fault
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaultEvent {
+    /// Milliseconds since faults were configured
+    pub at_ms: u64,
+    /// "delayed", "dropped", "physicsPaused" or "physicsResumed"
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+}
+
+/// What happened during a run
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaultReport {
+    pub seed: u64,
+    pub delivered: usize,
+    pub delayed: usize,
+    pub dropped: usize,
+    pub events: Vec<FaultEvent>,
+}
+
+/// What to do with the command at the front of the bot queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    Deliver,
+    /// Not yet; leave it queued
+    Hold,
+    Drop,
+}
+
+/// Planned fate of a command, decided the first time it is polled
+struct Pending {
+    ready_at: Instant,
+    delay_ms: u64,
+    drop: bool,
+}
+
+/// Fault injection state for the bot pipeline
+#[derive(Default)]
+pub struct FaultInjector {
+    config: Option<FaultConfig>,
+    rng: u64,
+    started: Option<Instant>,
+    pending: HashMap<String, Pending>,
+    report: FaultReport,
+}
+
+impl FaultInjector {
+    /// Start a run with new settings, resetting the report
+    pub fn configure(&mut self, config: FaultConfig) {
+        let seed = config.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(1)
+        });
+        // xorshift state must be non-zero
+        self.rng = seed.max(1);
+        self.started = Some(Instant::now());
+        self.pending.clear();
+        self.report = FaultReport {
+            seed,
+            ..Default::default()
+        };
+        self.config = Some(config);
+    }
+
+    /// Stop injecting faults and return the run's report
+    pub fn clear(&mut self) -> FaultReport {
+        self.config = None;
+        self.pending.clear();
+        std::mem::take(&mut self.report)
+    }
+
+    pub fn config(&self) -> Option<&FaultConfig> {
+        self.config.as_ref()
+    }
+
+    pub fn report(&self) -> &FaultReport {
+        &self.report
+    }
+
+    /// Uniform value in [0, 1) (xorshift64*)
+    fn next_f64(&mut self) -> f64 {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        let value = self.rng.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        (value >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn record(&mut self, kind: &str, command_id: Option<&str>, delay_ms: Option<u64>) {
+        let at_ms = self
+            .started
+            .map(|s| s.elapsed().as_millis() as u64)
+            .unwrap_or(0);
+        self.report.events.push(FaultEvent {
+            at_ms,
+            kind: kind.to_string(),
+            command_id: command_id.map(str::to_string),
+            delay_ms,
+        });
+    }
+
+    /// Decide whether the command `id` at the front of the queue goes out now
+    pub fn on_pending(&mut self, id: &str) -> Delivery {
+        let Some(config) = self.config.clone() else {
+            return Delivery::Deliver;
+        };
+        if config.is_empty() {
+            self.report.delivered += 1;
+            return Delivery::Deliver;
+        }
+
+        if !self.pending.contains_key(id) {
+            let jitter = (self.next_f64() * config.jitter_ms as f64) as u64;
+            let delay_ms = config.latency_ms + jitter;
+            let drop = self.next_f64() * 100.0 < config.drop_percent;
+            self.pending.insert(
+                id.to_string(),
+                Pending {
+                    ready_at: Instant::now() + Duration::from_millis(delay_ms),
+                    delay_ms,
+                    drop,
+                },
+            );
+        }
+
+        let pending = &self.pending[id];
+        if Instant::now() < pending.ready_at {
+            return Delivery::Hold;
+        }
+
+        let Pending { delay_ms, drop, .. } = self.pending.remove(id).unwrap();
+        if delay_ms > 0 {
+            self.report.delayed += 1;
+            self.record("delayed", Some(id), Some(delay_ms));
+        }
+        if drop {
+            self.report.dropped += 1;
+            self.record("dropped", Some(id), None);
+            Delivery::Drop
+        } else {
+            self.report.delivered += 1;
+            Delivery::Deliver
+        }
+    }
+}
+
+/// Configure, clear or trigger faults (POST /bot/faults)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaultsRequest {
+    /// Stop injecting faults and return the report
+    #[serde(default)]
+    pub clear: bool,
+    /// "pause" or "resume" physics in the running game
+    #[serde(default)]
+    pub physics: Option<String>,
+    #[serde(flatten)]
+    pub config: FaultConfig,
+}
+
+/// Result a dropped command resolves with, so callers fail fast
+pub fn dropped_result(id: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "dropped": true,
+        "result": {
+            "success": false,
+            "error": "Command dropped by fault injection"
+        }
+    })
+}
+
+/// Current fault settings and report (GET /bot/faults)
+pub async fn handle_get_faults(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let faults = state.bot_faults.lock().await;
+    Json(serde_json::json!({
+        "success": true,
+        "active": faults.config().is_some(),
+        "config": faults.config(),
+        "report": faults.report()
+    }))
+}
+
+pub async fn handle_set_faults(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<FaultsRequest>,
+) -> impl IntoResponse {
+    if req.clear {
+        let report = state.bot_faults.lock().await.clear();
+        tracing::info!(
+            "Bot faults cleared ({} delayed, {} dropped)",
+            report.delayed,
+            report.dropped
+        );
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({ "success": true, "active": false, "report": report })),
+        );
+    }
+
+    if let Some(physics) = &req.physics {
+        let (action, kind) = match physics.as_str() {
+            "pause" => ("pausePhysics", "physicsPaused"),
+            "resume" => ("resumePhysics", "physicsResumed"),
+            other => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({
+                        "success": false,
+                        "error": format!("Unknown physics action '{}', use pause or resume", other)
+                    })),
+                )
+            }
+        };
+
+        // Physics commands bypass the fault queue so they aren't delayed or dropped
+        let id = Uuid::new_v4().to_string();
+        state.bot_faults.lock().await.record(kind, Some(&id), None);
+        state.bot_command_queue.lock().await.push_front(serde_json::json!({
+            "id": id,
+            "command": { "action": action },
+            "bypassFaults": true
+        }));
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({ "success": true, "queued": true, "id": id })),
+        );
+    }
+
+    if let Err(error) = req.config.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "success": false, "error": error })),
+        );
+    }
+
+    let mut faults = state.bot_faults.lock().await;
+    faults.configure(req.config);
+    tracing::info!("Bot faults configured: {:?}", faults.config());
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "success": true,
+            "active": true,
+            "config": faults.config(),
+            "seed": faults.report().seed
+        })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(seed: u64) -> Vec<Delivery> {
+        let mut faults = FaultInjector::default();
+        faults.configure(FaultConfig {
+            drop_percent: 50.0,
+            seed: Some(seed),
+            ..Default::default()
+        });
+        (0..20).map(|i| faults.on_pending(&i.to_string())).collect()
+    }
+
+    #[test]
+    fn test_faults_are_reproducible() {
+        let first = run(42);
+        assert_eq!(first, run(42));
+        assert!(first.contains(&Delivery::Drop));
+        assert!(first.contains(&Delivery::Deliver));
+
+        let mut faults = FaultInjector::default();
+        faults.configure(FaultConfig {
+            latency_ms: 60_000,
+            seed: Some(7),
+            ..Default::default()
+        });
+        assert_eq!(faults.on_pending("a"), Delivery::Hold);
+        assert_eq!(faults.on_pending("a"), Delivery::Hold);
+
+        let report = faults.clear();
+        assert_eq!(report.seed, 7);
+        assert_eq!(faults.on_pending("a"), Delivery::Deliver);
+    }
+}
@@ -2,14 +2,21 @@
 //!
 //! Watches project directories for file changes and pushes updates to Studio.
 //! Supports Wally package exclusion to prevent package files from being synced.
+//!
+//! Renames are sent to Studio as `rename` operations, which move the existing
+//! instance instead of replacing it, so its connections and attributes
+//! survive. Besides the renames the OS reports, a delete and a create of the
+//! same content within the debounce window count as one (see `pair_renames`).
 
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
-use notify::event::{ModifyKind, DataChange};
+use notify::event::{ModifyKind, DataChange, RenameMode};
+use serde::Serialize;
 use tokio::sync::{mpsc, RwLock};
 
 use rbxsync_core::is_package_path;
@@ -20,6 +27,14 @@ pub struct FileChange {
     pub path: PathBuf,
     pub project_dir: String,
     pub kind: FileChangeKind,
+    /// Whether the path is (or was, for deletions) a directory
+    pub is_dir: bool,
+    /// File size after the change (None for deletions and directories)
+    pub size: Option<u64>,
+    /// Size difference in bytes, when the previous size is known
+    pub size_delta: Option<i64>,
+    /// Hash of a synced file's content (its last known content, for deletions)
+    pub content_hash: Option<u64>,
 }
 
 /// Kind of file change
@@ -28,12 +43,244 @@ pub enum FileChangeKind {
     Create,
     Modify,
     Delete,
+    /// Moved or renamed from another path (the change's `path` is the new one)
+    Rename { from: PathBuf },
+}
+
+impl FileChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileChangeKind::Create => "create",
+            FileChangeKind::Modify => "modify",
+            FileChangeKind::Delete => "delete",
+            FileChangeKind::Rename { .. } => "rename",
+        }
+    }
+}
+
+/// Last known sizes of watched files, for size deltas, and content hashes of
+/// synced files, for spotting renames
+#[derive(Default)]
+struct SizeTracker {
+    files: HashMap<PathBuf, u64>,
+    dirs: HashSet<PathBuf>,
+    hashes: HashMap<PathBuf, u64>,
+}
+
+/// Hash of a synced file's content
+fn content_hash(path: &Path) -> Option<u64> {
+    use std::hash::{Hash, Hasher};
+
+    if !is_synced_file(path) {
+        return None;
+    }
+    let content = std::fs::read(path).ok()?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    content.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+impl SizeTracker {
+    /// Record everything under `dir`
+    fn scan(&mut self, dir: &Path) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                self.dirs.insert(path.clone());
+                self.scan(&path);
+            } else if let Ok(meta) = entry.metadata() {
+                if let Some(hash) = content_hash(&path) {
+                    self.hashes.insert(path.clone(), hash);
+                }
+                self.files.insert(path, meta.len());
+            }
+        }
+    }
+
+    /// Build a change for `path`, updating the recorded sizes
+    fn change(&mut self, path: PathBuf, project_dir: &str, kind: FileChangeKind) -> FileChange {
+        let (is_dir, size, size_delta) = match &kind {
+            FileChangeKind::Delete => {
+                let was_dir = self.dirs.remove(&path);
+                if was_dir {
+                    self.files.retain(|p, _| !p.starts_with(&path));
+                    self.dirs.retain(|p| !p.starts_with(&path));
+                    self.hashes.retain(|p, _| !p.starts_with(&path));
+                }
+                let old = self.files.remove(&path);
+                // Unknown paths without an extension are most likely folders
+                let is_dir = was_dir || (old.is_none() && path.extension().is_none());
+                (is_dir, None, old.map(|old| -(old as i64)))
+            }
+            FileChangeKind::Rename { from } => {
+                let is_dir = path.is_dir();
+                if is_dir {
+                    self.dirs.insert(path.clone());
+                    // Re-key everything that moved with the directory
+                    self.dirs.retain(|p| !p.starts_with(from));
+                    self.files.retain(|p, _| !p.starts_with(from));
+                    self.hashes.retain(|p, _| !p.starts_with(from));
+                    self.scan(&path);
+                    (true, None, None)
+                } else {
+                    self.hashes.remove(from);
+                    let old = self.files.remove(from);
+                    let size = std::fs::metadata(&path).ok().map(|m| m.len());
+                    if let Some(size) = size {
+                        self.files.insert(path.clone(), size);
+                    }
+                    (false, size, old.zip(size).map(|(old, new)| new as i64 - old as i64))
+                }
+            }
+            FileChangeKind::Create | FileChangeKind::Modify => {
+                if path.is_dir() {
+                    self.dirs.insert(path.clone());
+                    (true, None, None)
+                } else {
+                    let size = std::fs::metadata(&path).ok().map(|m| m.len());
+                    let old = match size {
+                        Some(size) => self.files.insert(path.clone(), size),
+                        None => self.files.remove(&path),
+                    };
+                    let old = match (&kind, old) {
+                        (FileChangeKind::Create, None) => Some(0),
+                        (_, old) => old,
+                    };
+                    (false, size, old.zip(size).map(|(old, new)| new as i64 - old as i64))
+                }
+            }
+        };
+
+        let content_hash = match &kind {
+            FileChangeKind::Delete => self.hashes.remove(&path),
+            _ if is_dir => None,
+            _ => {
+                let hash = content_hash(&path);
+                match hash {
+                    Some(hash) => self.hashes.insert(path.clone(), hash),
+                    None => self.hashes.remove(&path),
+                };
+                hash
+            }
+        };
+
+        FileChange {
+            path,
+            project_dir: project_dir.to_string(),
+            kind,
+            is_dir,
+            size,
+            size_delta,
+            content_hash,
+        }
+    }
+}
+
+/// What a delete and a create must share to be two halves of one rename
+pub fn rename_key(change: &FileChange) -> Option<(String, u64)> {
+    match change.kind {
+        FileChangeKind::Delete | FileChangeKind::Create if !change.is_dir => {
+            change.content_hash.map(|hash| (change.project_dir.clone(), hash))
+        }
+        _ => None,
+    }
+}
+
+/// Turn each delete and create of the same content into a rename of the
+/// deleted file. Only one create is paired with each delete, so copies made
+/// in the same window stay creates.
+pub fn pair_renames(changes: &mut Vec<FileChange>) {
+    let mut deleted: HashMap<(String, u64), Vec<usize>> = HashMap::new();
+    for (i, change) in changes.iter().enumerate() {
+        if change.kind == FileChangeKind::Delete {
+            if let Some(key) = rename_key(change) {
+                deleted.entry(key).or_default().push(i);
+            }
+        }
+    }
+    if deleted.is_empty() {
+        return;
+    }
+
+    let mut paired = HashSet::new();
+    for i in 0..changes.len() {
+        if changes[i].kind != FileChangeKind::Create {
+            continue;
+        }
+        let Some(key) = rename_key(&changes[i]) else {
+            continue;
+        };
+        let Some(from) = deleted.get_mut(&key).and_then(|indices| indices.pop()) else {
+            continue;
+        };
+        let from_path = changes[from].path.clone();
+        let delete_delta = changes[from].size_delta;
+        let change = &mut changes[i];
+        change.kind = FileChangeKind::Rename { from: from_path };
+        change.size_delta = change.size_delta.zip(delete_delta).map(|(a, b)| a + b);
+        paired.insert(from);
+    }
+
+    let mut index = 0;
+    changes.retain(|_| {
+        let keep = !paired.contains(&index);
+        index += 1;
+        keep
+    });
+}
+
+/// A debounced batch of changes for one project, broadcast to `/events` subscribers
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileEventBatch {
+    pub project_dir: String,
+    pub changes: Vec<FileEvent>,
+}
+
+/// A single changed path within a [`FileEventBatch`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileEvent {
+    pub path: String,
+    /// "create", "modify", "delete" or "rename"
+    pub kind: &'static str,
+    /// Previous path of a rename
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    pub is_dir: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_delta: Option<i64>,
+}
+
+impl From<&FileChange> for FileEvent {
+    fn from(change: &FileChange) -> Self {
+        Self {
+            path: rbxsync_core::path_to_string(&change.path),
+            kind: change.kind.as_str(),
+            old_path: match &change.kind {
+                FileChangeKind::Rename { from } => Some(rbxsync_core::path_to_string(from)),
+                _ => None,
+            },
+            is_dir: change.is_dir,
+            size: change.size,
+            size_delta: change.size_delta,
+        }
+    }
 }
 
 /// File watcher state
 pub struct FileWatcherState {
     /// Directories being watched
     pub watched_dirs: HashSet<String>,
+    /// Watched directories that only feed `/events` subscribers (no live sync)
+    pub event_only_dirs: HashSet<String>,
+    /// Stop flags for running watcher threads (project_dir -> flag)
+    pub stop_flags: HashMap<String, Arc<AtomicBool>>,
     /// Debounce tracking (path -> last event time)
     pub pending_changes: HashMap<PathBuf, (Instant, FileChangeKind)>,
     /// Channel to send file changes
@@ -44,10 +291,42 @@ impl FileWatcherState {
     pub fn new(change_tx: mpsc::UnboundedSender<FileChange>) -> Self {
         Self {
             watched_dirs: HashSet::new(),
+            event_only_dirs: HashSet::new(),
+            stop_flags: HashMap::new(),
             pending_changes: HashMap::new(),
             change_tx,
         }
     }
+
+    /// Stop the watcher for one directory. Returns whether it was running.
+    pub fn stop(&mut self, dir: &str) -> bool {
+        if let Some(flag) = self.stop_flags.remove(dir) {
+            flag.store(true, Ordering::Relaxed);
+        }
+        self.event_only_dirs.remove(dir);
+        self.watched_dirs.remove(dir)
+    }
+
+    /// Stop every running watcher.
+    ///
+    /// Returns the stopped directories with whether each was event-only, so they
+    /// can be restarted the same way later.
+    pub fn stop_all(&mut self) -> Vec<(String, bool)> {
+        for flag in self.stop_flags.values() {
+            flag.store(true, Ordering::Relaxed);
+        }
+        self.stop_flags.clear();
+        self.pending_changes.clear();
+
+        let event_only = std::mem::take(&mut self.event_only_dirs);
+        self.watched_dirs
+            .drain()
+            .map(|dir| {
+                let is_event_only = event_only.contains(&dir);
+                (dir, is_event_only)
+            })
+            .collect()
+    }
 }
 
 /// Start the file watcher for a project directory
@@ -76,10 +355,15 @@ pub async fn start_file_watcher(
 
     tracing::info!("Starting file watcher for: {:?}", src_dir);
 
+    // Selective sync globs, read once; restart the watcher to pick up changes
+    let filter = crate::get_sync_filter(&crate::load_project_config(&project_dir));
+
     // Mark as watching
+    let stop = Arc::new(AtomicBool::new(false));
     {
         let mut state = state.write().await;
         state.watched_dirs.insert(project_dir.clone());
+        state.stop_flags.insert(project_dir.clone(), stop.clone());
     }
 
     let project_dir_clone = project_dir.clone();
@@ -113,10 +397,53 @@ pub async fn start_file_watcher(
 
         tracing::info!("File watcher active for: {:?}", src_dir);
 
+        let mut sizes = SizeTracker::default();
+        sizes.scan(&src_dir);
+
+        // Whether a path under src belongs to an instance that is synced
+        let selected = |path: &Path| match path.strip_prefix(&src_dir) {
+            Ok(rel) => filter.matches(&crate::conflicts::instance_key(&rbxsync_core::path_to_string(rel))),
+            Err(_) => true,
+        };
+
+        let send = |change: FileChange| {
+            let renamed_from_selected = matches!(&change.kind, FileChangeKind::Rename { from } if selected(from));
+            if !selected(&change.path) && !renamed_from_selected {
+                tracing::trace!("Skipping path outside selective sync: {:?}", change.path);
+                return;
+            }
+            let state = state_clone.clone();
+            rt.spawn(async move {
+                let state = state.read().await;
+                let _ = state.change_tx.send(change);
+            });
+        };
+
         // Process events
         loop {
+            // Stopped while idle; dropping the watcher releases the OS handles
+            if stop.load(Ordering::Relaxed) {
+                tracing::info!("File watcher stopped for: {:?}", src_dir);
+                break;
+            }
+
             match rx.recv_timeout(Duration::from_secs(1)) {
                 Ok(event) => {
+                    // Renames reported with both paths (inotify, Windows) become one event;
+                    // macOS reports each side separately, handled below as create/delete
+                    if let EventKind::Modify(ModifyKind::Name(RenameMode::Both)) = &event.kind {
+                        if let [from, to] = event.paths.as_slice() {
+                            let is_dir = to.is_dir();
+                            let relevant = is_dir || is_synced_file(from) || is_synced_file(to);
+                            if relevant && (sync_packages || !is_package_path(to)) {
+                                // A folder moves with everything in it
+                                let kind = FileChangeKind::Rename { from: from.clone() };
+                                send(sizes.change(to.clone(), &project_dir_clone, kind));
+                            }
+                            continue;
+                        }
+                    }
+
                     // Process each path in the event with macOS-aware kind detection
                     for path in event.paths.iter() {
                         // Determine the event kind using Argon's macOS approach:
@@ -146,8 +473,9 @@ pub async fn start_file_watcher(
                                         }
                                     }
                                     // Data changes - only care about content changes
+                                    // (inotify reports plain writes as Data(Any))
                                     ModifyKind::Data(data_change) => {
-                                        if *data_change == DataChange::Content {
+                                        if matches!(data_change, DataChange::Content | DataChange::Any) {
                                             // Verify file still exists (another macOS quirk)
                                             if path.exists() {
                                                 Some(FileChangeKind::Modify)
@@ -177,25 +505,11 @@ pub async fn start_file_watcher(
                             let path = path.clone();
                             // Check if it's a directory that was created (for undo operations)
                             if kind == FileChangeKind::Create && path.is_dir() {
-                                // Scan directory for script files and send Create events for each
-                                if let Ok(entries) = std::fs::read_dir(&path) {
-                                    for entry in entries.flatten() {
-                                        let entry_path = entry.path();
-                                        if let Some(ext) = entry_path.extension().and_then(|e| e.to_str()) {
-                                            if ext == "luau" || ext == "rbxjson" {
-                                                let change = FileChange {
-                                                    path: entry_path,
-                                                    project_dir: project_dir_clone.clone(),
-                                                    kind: FileChangeKind::Create,
-                                                };
-                                                let state = state_clone.clone();
-                                                rt.spawn(async move {
-                                                    let state = state.read().await;
-                                                    let _ = state.change_tx.send(change);
-                                                });
-                                            }
-                                        }
-                                    }
+                                // Report the folder, then send Create events for its script files
+                                let files = synced_files_in(&path);
+                                send(sizes.change(path, &project_dir_clone, FileChangeKind::Create));
+                                for file in files {
+                                    send(sizes.change(file, &project_dir_clone, FileChangeKind::Create));
                                 }
                                 continue;
                             }
@@ -207,8 +521,8 @@ pub async fn start_file_watcher(
                             }
 
                             // Check if it's a file we care about
-                            let should_process = if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                                ext == "luau" || ext == "rbxjson"
+                            let should_process = if path.extension().is_some() {
+                                is_synced_file(&path)
                             } else {
                                 // For deletions, also handle directories (no extension)
                                 // Check that path is inside src (has at least one segment after src)
@@ -228,18 +542,8 @@ pub async fn start_file_watcher(
                             };
 
                             if should_process {
-                                let change = FileChange {
-                                    path: path.clone(),
-                                    project_dir: project_dir_clone.clone(),
-                                    kind: kind.clone(),
-                                };
-
                                 // Send to async handler
-                                let state = state_clone.clone();
-                                rt.spawn(async move {
-                                    let state = state.read().await;
-                                    let _ = state.change_tx.send(change);
-                                });
+                                send(sizes.change(path, &project_dir_clone, kind));
                             }
                         }
                     }
@@ -258,39 +562,124 @@ pub async fn start_file_watcher(
     Ok(())
 }
 
-/// Process a file change and prepare sync operation
-pub fn process_file_change(
-    change: &FileChange,
-) -> Option<serde_json::Value> {
-    let path = &change.path;
+/// Whether a file is synced to Studio (scripts and instance JSON)
+fn is_synced_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| ext == "luau" || ext == "rbxjson")
+}
+
+/// All synced files under a directory, recursively
+fn synced_files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                files.extend(synced_files_in(&path));
+            } else if is_synced_file(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+/// Process a file change and prepare its sync operations.
+///
+/// Most changes map to one operation. A rename moves the instance to its new
+/// path with a `rename` operation, followed for files by an update in case
+/// the content changed too. A rename that changes the script class (e.g.
+/// `Foo.luau` -> `Bar.server.luau`) can't keep the instance and is sent as a
+/// delete and a create.
+pub fn process_file_change(change: &FileChange) -> Vec<serde_json::Value> {
     let project_dir = PathBuf::from(&change.project_dir);
     let src_dir = project_dir.join("src");
 
-    // Get relative path from src directory
-    let rel_path = match path.strip_prefix(&src_dir) {
-        Ok(p) => p,
-        Err(_) => return None,
+    let FileChangeKind::Rename { from } = &change.kind else {
+        return sync_operation(&src_dir, &change.path, &change.kind).into_iter().collect();
     };
 
-    // Convert to instance path (e.g., "ServerScriptService/MyScript")
+    let (old_path, new_path) = match (instance_path(&src_dir, from), instance_path(&src_dir, &change.path)) {
+        // Moved in from outside src
+        (None, _) if change.is_dir => {
+            return synced_files_in(&change.path)
+                .iter()
+                .filter_map(|file| sync_operation(&src_dir, file, &FileChangeKind::Create))
+                .collect();
+        }
+        (None, _) => return sync_operation(&src_dir, &change.path, &FileChangeKind::Create).into_iter().collect(),
+        // Same instance path (e.g. Foo.luau -> Foo.server.luau): an update in place
+        (Some(old), Some(new)) if old == new => {
+            return match change.is_dir {
+                true => Vec::new(),
+                false => sync_operation(&src_dir, &change.path, &FileChangeKind::Modify).into_iter().collect(),
+            };
+        }
+        (Some(old), None) => return vec![serde_json::json!({ "type": "delete", "path": old, "isFolder": change.is_dir })],
+        (Some(old), Some(new)) => (old, new),
+    };
+
+    if !change.is_dir && file_class(from) != file_class(&change.path) {
+        let mut operations = vec![serde_json::json!({
+            "type": "delete",
+            "path": old_path,
+            "isFolder": false,
+        })];
+        operations.extend(sync_operation(&src_dir, &change.path, &FileChangeKind::Create));
+        return operations;
+    }
+
+    let mut operations = vec![serde_json::json!({
+        "type": "rename",
+        "path": old_path,
+        "newPath": new_path,
+        "isFolder": change.is_dir,
+    })];
+    if !change.is_dir {
+        operations.extend(sync_operation(&src_dir, &change.path, &FileChangeKind::Modify));
+    }
+    operations
+}
+
+/// Instance class a file's name implies (`rbxjson` when it's in the content)
+fn file_class(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+    [
+        (".server.luau", "Script"),
+        (".client.luau", "LocalScript"),
+        (".luau", "ModuleScript"),
+        (".rbxjson", "rbxjson"),
+    ]
+    .into_iter()
+    .find(|(suffix, _)| name.ends_with(suffix))
+    .map(|(_, class)| class)
+}
+
+/// Instance path for a file under `src` (e.g. "ServerScriptService/MyScript")
+fn instance_path(src_dir: &Path, path: &Path) -> Option<String> {
+    let rel_path = path.strip_prefix(src_dir).ok()?;
+
     // Handle _meta.rbxjson specially - it represents the parent folder
     let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-    let inst_path = if filename == "_meta.rbxjson" {
-        // _meta.rbxjson represents the parent folder
-        rel_path
-            .parent()
-            .map(rbxsync_core::path_to_string)
-            .unwrap_or_default()
-    } else {
+    if filename == "_meta.rbxjson" {
+        return Some(rel_path.parent().map(rbxsync_core::path_to_string).unwrap_or_default());
+    }
+    Some(
         rbxsync_core::path_to_string(rel_path)
             .trim_end_matches(".server.luau")
             .trim_end_matches(".client.luau")
             .trim_end_matches(".luau")
             .trim_end_matches(".rbxjson")
-            .to_string()
-    };
+            .to_string(),
+    )
+}
 
-    match change.kind {
+/// Sync operation for a created, modified or deleted path
+fn sync_operation(src_dir: &Path, path: &Path, kind: &FileChangeKind) -> Option<serde_json::Value> {
+    let inst_path = instance_path(src_dir, path)?;
+
+    match kind {
         FileChangeKind::Delete => {
             // For folder deletions, the path won't have an extension
             // The inst_path will be the folder path in the instance tree
@@ -300,6 +689,7 @@ pub fn process_file_change(
                 "isFolder": path.extension().is_none(),
             }))
         }
+        FileChangeKind::Rename { .. } => None,
         FileChangeKind::Create | FileChangeKind::Modify => {
             // Check if file still exists (macOS reports deletions as Modify events)
             if !path.exists() {
@@ -337,7 +727,7 @@ pub fn process_file_change(
                 let instance_name = inst_path.rsplit('/').next().unwrap_or(&inst_path);
 
                 Some(serde_json::json!({
-                    "type": if change.kind == FileChangeKind::Create { "create" } else { "update" },
+                    "type": if *kind == FileChangeKind::Create { "create" } else { "update" },
                     "path": inst_path,
                     "data": {
                         "className": class_name,
@@ -370,6 +760,11 @@ pub fn process_file_change(
                     }
                 };
 
+                rbxsync_core::normalize_properties(&mut data);
+                if let Some(project_dir) = src_dir.parent() {
+                    crate::assets::resolve_instances(project_dir, std::slice::from_mut(&mut data));
+                }
+
                 // Ensure path is set from file location (used for tracking, not naming)
                 if let Some(obj) = data.as_object_mut() {
                     obj.insert("path".to_string(), serde_json::Value::String(inst_path.clone()));
@@ -382,7 +777,7 @@ pub fn process_file_change(
                 }
 
                 Some(serde_json::json!({
-                    "type": if change.kind == FileChangeKind::Create { "create" } else { "update" },
+                    "type": if *kind == FileChangeKind::Create { "create" } else { "update" },
                     "path": inst_path,
                     "data": data
                 }))
@@ -392,3 +787,87 @@ pub fn process_file_change(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_change() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let project_dir = temp.path().to_string_lossy().to_string();
+        let src = temp.path().join("src").join("ServerScriptService");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("Old.server.luau"), "print(1)").unwrap();
+
+        let mut sizes = SizeTracker::default();
+        sizes.scan(&temp.path().join("src"));
+
+        std::fs::rename(src.join("Old.server.luau"), src.join("New.server.luau")).unwrap();
+        std::fs::write(src.join("New.server.luau"), "print(12)").unwrap();
+        let kind = FileChangeKind::Rename { from: src.join("Old.server.luau") };
+        let change = sizes.change(src.join("New.server.luau"), &project_dir, kind);
+        assert!(!change.is_dir);
+        assert_eq!(change.size, Some(9));
+        assert_eq!(change.size_delta, Some(1));
+
+        let event = FileEvent::from(&change);
+        assert_eq!(event.kind, "rename");
+        assert!(event.old_path.unwrap().ends_with("Old.server.luau"));
+
+        let ops = process_file_change(&change);
+        assert_eq!(ops.len(), 2);
+        assert_eq!(ops[0]["type"], "rename");
+        assert_eq!(ops[0]["path"], "ServerScriptService/Old");
+        assert_eq!(ops[0]["newPath"], "ServerScriptService/New");
+        assert_eq!(ops[1]["type"], "update");
+        assert_eq!(ops[1]["path"], "ServerScriptService/New");
+
+        // A new class can't keep the instance
+        std::fs::rename(src.join("New.server.luau"), src.join("Module.luau")).unwrap();
+        let kind = FileChangeKind::Rename { from: src.join("New.server.luau") };
+        let ops = process_file_change(&sizes.change(src.join("Module.luau"), &project_dir, kind));
+        assert_eq!(ops.len(), 2);
+        assert_eq!(ops[0]["type"], "delete");
+        assert_eq!(ops[1]["type"], "create");
+        assert_eq!(ops[1]["data"]["className"], "ModuleScript");
+
+        let deleted = sizes.change(src.join("Module.luau"), &project_dir, FileChangeKind::Delete);
+        assert_eq!(deleted.size_delta, Some(-9));
+    }
+
+    #[test]
+    fn test_pair_renames() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let project_dir = temp.path().to_string_lossy().to_string();
+        let src = temp.path().join("src").join("ReplicatedStorage");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("Foo.luau"), "return {}").unwrap();
+        std::fs::write(src.join("Other.luau"), "return 1").unwrap();
+
+        let mut sizes = SizeTracker::default();
+        sizes.scan(&temp.path().join("src"));
+
+        // Reported as a delete and a create, e.g. by an editor or on macOS
+        std::fs::rename(src.join("Foo.luau"), src.join("Bar.luau")).unwrap();
+        std::fs::remove_file(src.join("Other.luau")).unwrap();
+        std::fs::write(src.join("New.luau"), "return 2").unwrap();
+        let mut changes = vec![
+            sizes.change(src.join("Foo.luau"), &project_dir, FileChangeKind::Delete),
+            sizes.change(src.join("Other.luau"), &project_dir, FileChangeKind::Delete),
+            sizes.change(src.join("New.luau"), &project_dir, FileChangeKind::Create),
+            sizes.change(src.join("Bar.luau"), &project_dir, FileChangeKind::Create),
+        ];
+        pair_renames(&mut changes);
+
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].path, src.join("Other.luau"));
+        assert_eq!(changes[1].kind, FileChangeKind::Create);
+        assert_eq!(changes[2].kind, FileChangeKind::Rename { from: src.join("Foo.luau") });
+        assert_eq!(changes[2].size_delta, Some(0));
+
+        let ops = process_file_change(&changes[2]);
+        assert_eq!(ops[0]["type"], "rename");
+        assert_eq!(ops[0]["newPath"], "ReplicatedStorage/Bar");
+    }
+}
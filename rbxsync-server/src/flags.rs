@@ -0,0 +1,284 @@
+//! Feature flags for playtests
+//!
+//! Flags live in `flags.json` at the project root as a map of name to on/off:
+//!
+//! ```json
+//! { "newShop": true, "fastRespawn": false }
+//! ```
+//!
+//! Every change regenerates `ReplicatedStorage/RbxSyncFlags` under src (through
+//! treeMapping), so the file watcher syncs it like any other module. The module
+//! carries the flags as constants; at playtest start the server copy also asks
+//! `/flags` for the current state and mirrors it into attributes, which
+//! replicate to clients, so toggles made after the last sync still apply.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+
+use crate::AppState;
+
+/// Flag definitions, relative to the project directory
+pub const FLAGS_FILE: &str = "flags.json";
+
+/// DataModel path of the generated module
+pub const MODULE_PATH: &str = "ReplicatedStorage/RbxSyncFlags";
+
+pub type Flags = BTreeMap<String, bool>;
+
+/// Flag names are Luau identifiers and attribute names, so they follow both rules
+pub fn valid_flag_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 100
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && !name.starts_with("RBX")
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parse `on`/`off` style values
+pub fn parse_flag_value(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "on" | "true" | "1" | "yes" | "enable" | "enabled" => Some(true),
+        "off" | "false" | "0" | "no" | "disable" | "disabled" => Some(false),
+        _ => None,
+    }
+}
+
+/// Load a project's flags (empty when there is no flags file)
+pub fn load_flags(project_dir: &Path) -> Result<Flags, String> {
+    match std::fs::read_to_string(project_dir.join(FLAGS_FILE)) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| format!("Invalid {}: {}", FLAGS_FILE, e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Flags::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", FLAGS_FILE, e)),
+    }
+}
+
+/// Write `flags.json` and regenerate the Luau module, which fetches live
+/// flags from `server_url`. Returns the module path.
+pub fn save_flags(project_dir: &Path, flags: &Flags, server_url: &str) -> Result<PathBuf, String> {
+    let json = serde_json::to_string_pretty(flags).map_err(|e| e.to_string())?;
+    std::fs::write(project_dir.join(FLAGS_FILE), json + "\n")
+        .map_err(|e| format!("Failed to write {}: {}", FLAGS_FILE, e))?;
+    write_module(project_dir, flags, server_url)
+}
+
+/// Where the generated module goes under src, after treeMapping
+pub fn module_file(project_dir: &Path) -> PathBuf {
+    let config = crate::load_project_config(&project_dir.to_string_lossy());
+    let mapped = crate::apply_tree_mapping(MODULE_PATH, &crate::get_tree_mapping(&config));
+    project_dir.join("src").join(format!("{}.luau", mapped))
+}
+
+/// Regenerate the Luau module from the current flags
+pub fn write_module(project_dir: &Path, flags: &Flags, server_url: &str) -> Result<PathBuf, String> {
+    let path = module_file(project_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&path, module_source(flags, &project_dir.to_string_lossy(), server_url))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Luau source of the flags module, fetching live flags from the server at
+/// `server_url`. Invalid names in a hand-edited flags file are left out.
+pub fn module_source(flags: &Flags, project_dir: &str, server_url: &str) -> String {
+    let project = serde_json::to_string(project_dir).unwrap_or_else(|_| "\"\"".to_string());
+    let constants: String = flags
+        .iter()
+        .filter(|(name, _)| valid_flag_name(name))
+        .map(|(name, enabled)| format!("\t{} = {},\n", name, enabled))
+        .collect();
+    format!(
+        r#"-- Generated by RbxSync from flags.json. Do not edit; use `rbxsync flags set <name> on|off`.
+-- local Flags = require(game:GetService("ReplicatedStorage"):WaitForChild("RbxSyncFlags"))
+-- if Flags.isEnabled("newShop") then ... end
+
+local HttpService = game:GetService("HttpService")
+local RunService = game:GetService("RunService")
+
+local SERVER_URL = "{server}"
+local PROJECT_DIR = {project}
+
+local defaults: {{[string]: boolean}} = {{
+{constants}}}
+
+-- In a Studio playtest, pick up flags toggled since the last sync. Attributes
+-- replicate, so clients see the server's copy.
+if RunService:IsStudio() and RunService:IsServer() and RunService:IsRunning() then
+	pcall(function()
+		local response = HttpService:GetAsync(SERVER_URL .. "/flags?projectDir=" .. HttpService:UrlEncode(PROJECT_DIR))
+		local data = HttpService:JSONDecode(response)
+		if data.success and data.flags then
+			for name, enabled in data.flags do
+				script:SetAttribute(name, enabled)
+			end
+		end
+	end)
+end
+
+local Flags = {{}}
+
+function Flags.isEnabled(name: string): boolean
+	local live = script:GetAttribute(name)
+	if live ~= nil then
+		return live == true
+	end
+	return defaults[name] == true
+end
+
+function Flags.all(): {{[string]: boolean}}
+	local all = table.clone(defaults)
+	for name, enabled in script:GetAttributes() do
+		all[name] = enabled == true
+	end
+	return all
+end
+
+return Flags
+"#,
+        server = server_url,
+        project = project,
+        constants = constants,
+    )
+}
+
+/// Query for `GET /flags`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlagsQuery {
+    #[serde(default)]
+    pub project_dir: Option<String>,
+}
+
+/// Body of `POST /flags`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFlagsRequest {
+    #[serde(default)]
+    pub project_dir: Option<String>,
+    /// Flags to set
+    #[serde(default)]
+    pub flags: Flags,
+    /// Flags to remove
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+/// The given project, or the only connected place's
+pub(crate) async fn resolve_project(state: &Arc<AppState>, project_dir: Option<&str>) -> Option<String> {
+    if let Some(dir) = project_dir {
+        return Some(crate::normalize_path(dir));
+    }
+    let registry = state.place_registry.read().await;
+    let mut dirs: Vec<&String> = registry.values().map(|p| &p.project_dir).collect();
+    dirs.dedup();
+    match dirs.as_slice() {
+        [one] => Some((*one).clone()),
+        _ => None,
+    }
+}
+
+fn error_response(status: StatusCode, error: String) -> (StatusCode, Json<serde_json::Value>) {
+    (status, Json(serde_json::json!({ "success": false, "error": error })))
+}
+
+/// GET /flags
+pub async fn handle_get_flags(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<FlagsQuery>,
+) -> impl IntoResponse {
+    let Some(project_dir) = resolve_project(&state, query.project_dir.as_deref()).await else {
+        return error_response(StatusCode::BAD_REQUEST, "projectDir is required".to_string());
+    };
+    match load_flags(Path::new(&project_dir)) {
+        Ok(flags) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "success": true, "projectDir": project_dir, "flags": flags })),
+        ),
+        Err(error) => error_response(StatusCode::INTERNAL_SERVER_ERROR, error),
+    }
+}
+
+/// POST /flags
+pub async fn handle_set_flags(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<SetFlagsRequest>,
+) -> impl IntoResponse {
+    let Some(project_dir) = resolve_project(&state, req.project_dir.as_deref()).await else {
+        return error_response(StatusCode::BAD_REQUEST, "projectDir is required".to_string());
+    };
+    if let Some(bad) = req.flags.keys().find(|name| !valid_flag_name(name)) {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid flag name '{}': use letters, digits and underscores", bad),
+        );
+    }
+
+    let project = Path::new(&project_dir);
+    let mut flags = match load_flags(project) {
+        Ok(flags) => flags,
+        Err(error) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, error),
+    };
+    for name in &req.remove {
+        flags.remove(name);
+    }
+    flags.extend(req.flags);
+
+    match save_flags(project, &flags, &crate::local_url(&state)) {
+        Ok(module) => {
+            tracing::info!("Feature flags updated ({} defined)", flags.len());
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "success": true,
+                    "flags": flags,
+                    "module": module.to_string_lossy()
+                })),
+            )
+        }
+        Err(error) => error_response(StatusCode::INTERNAL_SERVER_ERROR, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags_roundtrip() {
+        let project = tempfile::tempdir().unwrap();
+        std::fs::write(
+            project.path().join("rbxsync.json"),
+            r#"{ "treeMapping": { "ReplicatedStorage": "shared" } }"#,
+        )
+        .unwrap();
+        assert!(load_flags(project.path()).unwrap().is_empty());
+
+        let mut flags = Flags::new();
+        flags.insert("newShop".to_string(), true);
+        flags.insert("fast_respawn".to_string(), false);
+        let module = save_flags(project.path(), &flags, "https://127.0.0.1:44757").unwrap();
+        assert_eq!(module, project.path().join("src/shared/RbxSyncFlags.luau"));
+        assert_eq!(load_flags(project.path()).unwrap(), flags);
+
+        let source = std::fs::read_to_string(module).unwrap();
+        assert!(source.contains("\tnewShop = true,\n"));
+        assert!(source.contains("\tfast_respawn = false,\n"));
+        assert!(source.contains("local SERVER_URL = \"https://127.0.0.1:44757\""));
+
+        assert!(valid_flag_name("newShop"));
+        assert!(!valid_flag_name("new-shop"));
+        assert!(!valid_flag_name("2x"));
+        assert_eq!(parse_flag_value("On"), Some(true));
+        assert_eq!(parse_flag_value("maybe"), None);
+    }
+}
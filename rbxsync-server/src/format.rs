@@ -0,0 +1,117 @@
+//! Formatting extracted scripts
+//!
+//! Studio keeps scripts the way they were typed, so extracting into a repo
+//! formatted with StyLua rewrote every script it touched. With
+//! `config.formatCommand` set (e.g. `"stylua"`), finalize runs the command
+//! over the `.luau` files the extraction wrote, before they replace src. It
+//! runs from the project directory, so a `stylua.toml` there applies, and
+//! gets the files as trailing arguments, a batch at a time.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+
+/// Files passed to one run of the formatter
+pub const FORMAT_BATCH: usize = 200;
+
+/// What the formatter did during one finalize
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatReport {
+    /// Scripts in batches the formatter accepted
+    pub formatted: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// `config.formatCommand`, split into program and arguments
+pub fn format_command(config: &Option<serde_json::Value>) -> Option<Vec<String>> {
+    let command = config.as_ref()?.get("config")?.get("formatCommand")?.as_str()?;
+    let parts: Vec<String> = command.split_whitespace().map(String::from).collect();
+    (!parts.is_empty()).then_some(parts)
+}
+
+/// `.luau` files under `dir`, leaving out the directories in `skip`
+pub fn find_scripts(dir: &Path, skip: &[PathBuf]) -> Vec<PathBuf> {
+    fn walk(dir: &Path, skip: &[PathBuf], out: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.is_dir() {
+                if !skip.contains(&path) {
+                    walk(&path, skip, out);
+                }
+            } else if path.extension().is_some_and(|e| e == "luau") {
+                out.push(path);
+            }
+        }
+    }
+
+    let mut scripts = Vec::new();
+    walk(dir, skip, &mut scripts);
+    scripts.sort();
+    scripts
+}
+
+/// Run the formatter over `files`. A batch it fails on is left as extracted;
+/// a formatter that can't be started stops the rest.
+pub fn format_scripts(project_dir: &Path, command: &[String], files: &[PathBuf]) -> FormatReport {
+    let mut report = FormatReport::default();
+    let Some((program, args)) = command.split_first() else {
+        return report;
+    };
+    for batch in files.chunks(FORMAT_BATCH) {
+        match Command::new(program).args(args).args(batch).current_dir(project_dir).output() {
+            Ok(output) if output.status.success() => report.formatted += batch.len(),
+            Ok(output) => report.errors.push(format!(
+                "{} exited with {}: {}",
+                program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
+            Err(e) => {
+                report.errors.push(format!("Failed to run {}: {}", program, e));
+                break;
+            }
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_scripts() {
+        let config = Some(serde_json::json!({ "config": { "formatCommand": "stylua --search-parent-directories" } }));
+        assert_eq!(format_command(&config), Some(vec!["stylua".to_string(), "--search-parent-directories".to_string()]));
+        assert_eq!(format_command(&Some(serde_json::json!({ "config": { "formatCommand": " " } }))), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir_all(src.join("ReplicatedStorage/Packages")).unwrap();
+        std::fs::write(src.join("ReplicatedStorage/Shop.luau"), "local  x=1").unwrap();
+        std::fs::write(src.join("ReplicatedStorage/Shop.rbxjson"), "{}").unwrap();
+        std::fs::write(src.join("ReplicatedStorage/Packages/Signal.luau"), "local  y=1").unwrap();
+        let scripts = find_scripts(&src, &[src.join("ReplicatedStorage/Packages")]);
+        assert_eq!(scripts, vec![src.join("ReplicatedStorage/Shop.luau")]);
+
+        #[cfg(unix)]
+        {
+            let command: Vec<String> = ["sh", "-c", "for f; do printf 'local x = 1\\n' > \"$f\"; done", "sh"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect();
+            let report = format_scripts(dir.path(), &command, &scripts);
+            assert_eq!(report.formatted, 1);
+            assert_eq!(std::fs::read_to_string(&scripts[0]).unwrap(), "local x = 1\n");
+
+            let report = format_scripts(dir.path(), &["rbxsync-missing-formatter".to_string()], &scripts);
+            assert_eq!(report.formatted, 0);
+            assert_eq!(report.errors.len(), 1);
+        }
+    }
+}
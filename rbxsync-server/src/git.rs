@@ -1,41 +1,15 @@
 //! Git integration module
 //!
 //! Provides git commands for the RbxSync plugin to display status,
-//! commit changes, and view history.
+//! commit changes, and view history, and reads HEAD so the server can tell
+//! when the branch under a project changes.
 
-use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-/// Git repository status
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GitStatus {
-    pub branch: String,
-    pub is_dirty: bool,
-    pub staged_count: usize,
-    pub unstaged_count: usize,
-    pub untracked_count: usize,
-    pub ahead: usize,
-    pub behind: usize,
-    /// List of changed files (relative paths)
-    pub changed_files: Vec<ChangedFile>,
-}
-
-/// A changed file with its status
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ChangedFile {
-    pub path: String,
-    pub status: String, // "modified", "added", "deleted", "renamed", "untracked"
-}
-
-/// Git commit information
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GitCommit {
-    pub hash: String,
-    pub message: String,
-    pub author: String,
-    pub date: String,
-}
+pub use rbxsync_core::api::{
+    ChangedFile, GitBranch, GitCommit, GitHead, GitRemoteResult, GitStashEntry, GitStashResult, GitStatus,
+};
 
 /// Get git status for a project directory
 pub fn get_status(project_dir: &Path) -> Result<GitStatus, String> {
@@ -250,3 +224,303 @@ pub fn init(project_dir: &Path) -> Result<String, String> {
         Err(String::from_utf8_lossy(&output.stderr).to_string())
     }
 }
+
+/// Run git in a repository. Returns stdout and stderr on success (git
+/// reports progress on stderr), else its error output.
+fn run(project_dir: &Path, args: &[&str]) -> Result<(String, String), String> {
+    if !project_dir.join(".git").exists() {
+        return Err("Not a git repository".to_string());
+    }
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(project_dir)
+        // Fail instead of waiting for credentials nobody can type
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|e| format!("Failed to run git {}: {}", args[0], e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    if output.status.success() {
+        Ok((stdout, stderr))
+    } else if stderr.trim().is_empty() {
+        Err(stdout.trim().to_string())
+    } else {
+        Err(stderr.trim().to_string())
+    }
+}
+
+/// stdout and stderr of a git command as one message
+fn combined((stdout, stderr): (String, String)) -> String {
+    format!("{}\n{}", stdout.trim(), stderr.trim()).trim().to_string()
+}
+
+/// Refuse names git would read as options
+fn check_arg<'a>(what: &str, value: &'a str) -> Result<&'a str, String> {
+    if value.is_empty() || value.starts_with('-') {
+        return Err(format!("Invalid {}: {:?}", what, value));
+    }
+    Ok(value)
+}
+
+/// Full hash of HEAD
+fn head_commit(project_dir: &Path) -> Option<String> {
+    run(project_dir, &["rev-parse", "HEAD"]).ok().map(|(stdout, _)| stdout.trim().to_string())
+}
+
+/// Files that differ between two commits
+fn changed_between(project_dir: &Path, before: Option<&str>, after: Option<&str>) -> Vec<String> {
+    let (Some(before), Some(after)) = (before, after) else { return Vec::new() };
+    if before == after {
+        return Vec::new();
+    }
+    run(project_dir, &["diff", "--name-only", before, after])
+        .map(|(stdout, _)| stdout.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+const BRANCH_FORMAT: &str =
+    "--format=%(refname:short)%09%(HEAD)%09%(upstream:short)%09%(upstream:track,nobracket)%09%(objectname:short)%09%(contents:subject)";
+
+/// One line of `git for-each-ref` in [`BRANCH_FORMAT`]
+fn parse_branch(line: &str) -> Option<GitBranch> {
+    let mut fields = line.splitn(6, '\t');
+    let name = fields.next()?.to_string();
+    let current = fields.next()? == "*";
+    let upstream = Some(fields.next()?.to_string()).filter(|u| !u.is_empty());
+    // "ahead 2, behind 1", "behind 3", "gone" or empty
+    let mut ahead = 0;
+    let mut behind = 0;
+    for part in fields.next()?.split(", ") {
+        match part.split_once(' ') {
+            Some(("ahead", n)) => ahead = n.parse().unwrap_or(0),
+            Some(("behind", n)) => behind = n.parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+    Some(GitBranch {
+        name,
+        current,
+        upstream,
+        ahead,
+        behind,
+        commit: fields.next()?.to_string(),
+        subject: fields.next().unwrap_or("").to_string(),
+    })
+}
+
+/// Local branches, most recently committed first
+pub fn list_branches(project_dir: &Path) -> Result<Vec<GitBranch>, String> {
+    let (stdout, _) = run(project_dir, &["for-each-ref", "--sort=-committerdate", BRANCH_FORMAT, "refs/heads"])?;
+    Ok(stdout.lines().filter_map(parse_branch).collect())
+}
+
+/// Create a branch, optionally checking it out
+pub fn create_branch(project_dir: &Path, name: &str, start_point: Option<&str>, checkout: bool) -> Result<GitBranch, String> {
+    let name = check_arg("branch name", name)?;
+    run(project_dir, &["check-ref-format", "--branch", name]).map_err(|_| format!("Invalid branch name: {:?}", name))?;
+    let mut args = if checkout { vec!["checkout", "-b", name] } else { vec!["branch", name] };
+    if let Some(start) = start_point {
+        args.push(check_arg("start point", start)?);
+    }
+    run(project_dir, &args)?;
+    list_branches(project_dir)?
+        .into_iter()
+        .find(|branch| branch.name == name)
+        .ok_or_else(|| format!("Branch {} was not created", name))
+}
+
+/// Check out a branch or commit
+pub fn checkout(project_dir: &Path, target: &str) -> Result<GitRemoteResult, String> {
+    let target = check_arg("checkout target", target)?;
+    let before = head_commit(project_dir);
+    let before_head = read_head(project_dir);
+    // `--` so a target that is also a file name still means the revision
+    let output = combined(run(project_dir, &["checkout", target, "--"])?);
+    let after = head_commit(project_dir);
+    let head = read_head(project_dir).unwrap_or(GitHead { branch: None, commit: None });
+    Ok(GitRemoteResult {
+        up_to_date: before_head.as_ref() == Some(&head),
+        changed_files: changed_between(project_dir, before.as_deref(), after.as_deref()),
+        head,
+        output,
+    })
+}
+
+/// Remote and branch arguments; a branch needs a remote in front of it
+fn remote_args<'a>(remote: Option<&'a str>, branch: Option<&'a str>) -> Result<Vec<&'a str>, String> {
+    let mut args = Vec::new();
+    if remote.is_some() || branch.is_some() {
+        args.push(check_arg("remote", remote.unwrap_or("origin"))?);
+    }
+    if let Some(branch) = branch {
+        args.push(check_arg("branch", branch)?);
+    }
+    Ok(args)
+}
+
+/// Pull, merging (or rebasing onto) the remote branch
+pub fn pull(project_dir: &Path, remote: Option<&str>, branch: Option<&str>, rebase: bool) -> Result<GitRemoteResult, String> {
+    let before = head_commit(project_dir);
+    let mut args = vec!["pull", "--no-edit"];
+    if rebase {
+        args.push("--rebase");
+    }
+    args.extend(remote_args(remote, branch)?);
+    let output = combined(run(project_dir, &args)?);
+    let after = head_commit(project_dir);
+    Ok(GitRemoteResult {
+        head: read_head(project_dir).unwrap_or(GitHead { branch: None, commit: None }),
+        up_to_date: before == after,
+        changed_files: changed_between(project_dir, before.as_deref(), after.as_deref()),
+        output,
+    })
+}
+
+/// Push the current branch (or `branch`)
+pub fn push(project_dir: &Path, remote: Option<&str>, branch: Option<&str>, set_upstream: bool) -> Result<GitRemoteResult, String> {
+    let head = read_head(project_dir).unwrap_or(GitHead { branch: None, commit: None });
+    let mut args = vec!["push"];
+    // Setting the upstream needs the branch spelled out
+    let branch = branch.or(if set_upstream { head.branch.as_deref() } else { None });
+    if set_upstream {
+        args.push("--set-upstream");
+    }
+    args.extend(remote_args(remote, branch)?);
+    let output = combined(run(project_dir, &args)?);
+    Ok(GitRemoteResult { up_to_date: output.contains("Everything up-to-date"), changed_files: Vec::new(), head, output })
+}
+
+/// One line of `git stash list --format=%gd%x09%gs`
+fn parse_stash(line: &str) -> Option<GitStashEntry> {
+    let (reference, message) = line.split_once('\t')?;
+    let index = reference.strip_prefix("stash@{")?.strip_suffix('}')?.parse().ok()?;
+    Some(GitStashEntry { index, message: message.to_string() })
+}
+
+/// Stash changes, or pop, apply or drop a stash, and list what's left
+pub fn stash(project_dir: &Path, action: &str, message: Option<&str>, index: usize) -> Result<GitStashResult, String> {
+    let reference = format!("stash@{{{}}}", index);
+    let output = match action {
+        "push" => {
+            let mut args = vec!["stash", "push"];
+            if let Some(message) = message {
+                args.extend(["-m", message]);
+            }
+            combined(run(project_dir, &args)?)
+        }
+        "pop" | "apply" | "drop" => combined(run(project_dir, &["stash", action, reference.as_str()])?),
+        "list" => String::new(),
+        _ => return Err(format!("Unknown stash action {:?}: use push, pop, apply, drop or list", action)),
+    };
+    let (stdout, _) = run(project_dir, &["stash", "list", "--format=%gd%x09%gs"])?;
+    Ok(GitStashResult { stashes: stdout.lines().filter_map(parse_stash).collect(), output })
+}
+
+/// A merge, rebase or cherry-pick left waiting in the repository
+fn operation_in_progress(project_dir: &Path) -> Option<&'static str> {
+    let dir = git_dir(project_dir)?;
+    [("MERGE_HEAD", "merge"), ("rebase-merge", "rebase"), ("rebase-apply", "rebase"), ("CHERRY_PICK_HEAD", "cherry-pick")]
+        .into_iter()
+        .find(|(marker, _)| dir.join(marker).exists())
+        .map(|(_, operation)| operation)
+}
+
+/// Commit everything under `paths` (relative to the project) and nothing
+/// else, leaving other staged changes staged. Returns the short hash, or
+/// None when those paths have no changes.
+pub fn commit_paths(project_dir: &Path, paths: &[&str], message: &str) -> Result<Option<String>, String> {
+    if let Some(operation) = operation_in_progress(project_dir) {
+        return Err(format!("a {} is in progress", operation));
+    }
+    let paths: Vec<&str> = paths.iter().copied().filter(|path| project_dir.join(path).exists()).collect();
+    if paths.is_empty() {
+        return Ok(None);
+    }
+    let with_paths = |args: &[&'static str]| [args, &["--"], paths.as_slice()].concat();
+
+    let (status, _) = run(project_dir, &with_paths(&["status", "--porcelain"]))?;
+    if status.trim().is_empty() {
+        return Ok(None);
+    }
+    run(project_dir, &with_paths(&["add", "-A"]))?;
+    run(project_dir, &[&["commit", "-m", message][..], &["--"], paths.as_slice()].concat())?;
+    Ok(read_head(project_dir).and_then(|head| head.commit))
+}
+
+/// The repository's git directory. In linked worktrees and submodules
+/// `.git` is a file pointing at it (`gitdir: ...`).
+pub fn git_dir(project_dir: &Path) -> Option<PathBuf> {
+    let dot_git = project_dir.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let content = std::fs::read_to_string(&dot_git).ok()?;
+    let target = PathBuf::from(content.strip_prefix("gitdir:")?.trim());
+    Some(if target.is_absolute() { target } else { project_dir.join(target) })
+}
+
+/// Raw contents of HEAD; cheap enough to compare every few seconds
+pub fn read_head_file(project_dir: &Path) -> Option<String> {
+    std::fs::read_to_string(git_dir(project_dir)?.join("HEAD")).ok().map(|head| head.trim().to_string())
+}
+
+/// Branch named by HEAD's contents (`ref: refs/heads/main`), None when detached
+pub fn head_branch(head_file: &str) -> Option<String> {
+    head_file.strip_prefix("ref:")?.trim().strip_prefix("refs/heads/").map(str::to_string)
+}
+
+/// Read HEAD, resolving its commit with `git rev-parse`
+pub fn read_head(project_dir: &Path) -> Option<GitHead> {
+    let head_file = read_head_file(project_dir)?;
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .current_dir(project_dir)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|commit| !commit.is_empty());
+    Some(GitHead { branch: head_branch(&head_file), commit })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_head_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(read_head_file(dir.path()), None);
+
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".git/HEAD"), "ref: refs/heads/feature/combat\n").unwrap();
+        let head = read_head_file(dir.path()).unwrap();
+        assert_eq!(head_branch(&head).as_deref(), Some("feature/combat"));
+        assert_eq!(head_branch("1a2b3c4d5e6f"), None);
+
+        // A linked worktree's .git file points at its git directory
+        let worktree = tempfile::tempdir().unwrap();
+        std::fs::write(worktree.path().join(".git"), format!("gitdir: {}\n", dir.path().join(".git").display())).unwrap();
+        assert_eq!(read_head_file(worktree.path()), Some(head));
+
+        let detached = GitHead { branch: None, commit: Some("1a2b3c4".to_string()) };
+        assert_eq!(detached.label(), "detached at 1a2b3c4");
+    }
+
+    #[test]
+    fn test_parse_branches_and_stashes() {
+        let branch = parse_branch("main\t*\torigin/main\tahead 2, behind 1\t1a2b3c4\tFix spawn\tlocation").unwrap();
+        assert_eq!((branch.name.as_str(), branch.current, branch.upstream.as_deref()), ("main", true, Some("origin/main")));
+        assert_eq!((branch.ahead, branch.behind, branch.subject.as_str()), (2, 1, "Fix spawn\tlocation"));
+        let local = parse_branch("combat\t \t\t\t9f8e7d6\tWIP").unwrap();
+        assert_eq!((local.current, local.upstream, local.ahead), (false, None, 0));
+
+        let entry = parse_stash("stash@{1}\tOn main: before rebase").unwrap();
+        assert_eq!((entry.index, entry.message.as_str()), (1, "On main: before rebase"));
+        assert!(parse_stash("garbage").is_none());
+
+        assert_eq!(remote_args(None, Some("main")).unwrap(), vec!["origin", "main"]);
+        assert!(remote_args(Some("--upload-pack=evil"), None).is_err());
+        assert!(check_arg("checkout target", "-f").is_err());
+    }
+}
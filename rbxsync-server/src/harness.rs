@@ -15,7 +15,14 @@ use rbxsync_core::{
     Feature, FeaturePriority, FeatureStatus, FeaturesFile, GameDefinition,
     SessionLog, SessionLogEntry,
 };
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
+
+// Request/response bodies shared with rbxsync-client
+pub use rbxsync_core::api::{
+    FeatureSummary, FeatureUpdateRequest, FeatureUpdateResponse, HarnessInitRequest,
+    HarnessInitResponse, HarnessStatusRequest, HarnessStatusResponse, SessionEndRequest,
+    SessionEndResponse, SessionStartRequest, SessionStartResponse, SessionSummary,
+};
 
 // Embed templates at compile time
 mod templates {
@@ -104,45 +111,6 @@ fn get_harness_dir(project_dir: &str) -> PathBuf {
     PathBuf::from(project_dir).join(HARNESS_DIR)
 }
 
-/// Request to initialize a harness for a project
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct HarnessInitRequest {
-    /// Project directory path
-    pub project_dir: String,
-
-    /// Game name
-    pub game_name: String,
-
-    /// Optional game description
-    #[serde(default)]
-    pub description: Option<String>,
-
-    /// Optional game genre
-    #[serde(default)]
-    pub genre: Option<String>,
-
-    /// Optional template to initialize with (tycoon, obby, simulator, rpg, horror)
-    #[serde(default)]
-    pub template: Option<String>,
-}
-
-/// Response from harness init
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct HarnessInitResponse {
-    pub success: bool,
-    pub message: String,
-    pub harness_dir: String,
-    pub game_id: Option<String>,
-    /// Template that was applied (if any)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub template_applied: Option<String>,
-    /// Number of features added from template
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub features_added: Option<usize>,
-}
-
 /// Initialize harness directory structure for a project
 pub async fn handle_harness_init(
     State(_state): State<Arc<AppState>>,
@@ -343,28 +311,6 @@ pub async fn handle_harness_init(
     )
 }
 
-/// Request to start a new session
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SessionStartRequest {
-    /// Project directory path
-    pub project_dir: String,
-
-    /// Optional initial summary/goals for the session
-    #[serde(default)]
-    pub initial_goals: Option<String>,
-}
-
-/// Response from session start
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SessionStartResponse {
-    pub success: bool,
-    pub message: String,
-    pub session_id: Option<String>,
-    pub session_path: Option<String>,
-}
-
 /// Start a new development session
 pub async fn handle_session_start(
     State(_state): State<Arc<AppState>>,
@@ -452,33 +398,6 @@ pub async fn handle_session_start(
     )
 }
 
-/// Request to end a session
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SessionEndRequest {
-    /// Project directory path
-    pub project_dir: String,
-
-    /// Session ID to end
-    pub session_id: String,
-
-    /// Summary of what was accomplished
-    #[serde(default)]
-    pub summary: Option<String>,
-
-    /// Handoff notes for future sessions
-    #[serde(default)]
-    pub handoff_notes: Option<Vec<String>>,
-}
-
-/// Response from session end
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SessionEndResponse {
-    pub success: bool,
-    pub message: String,
-}
-
 /// End a development session
 pub async fn handle_session_end(
     State(_state): State<Arc<AppState>>,
@@ -567,75 +486,6 @@ pub async fn handle_session_end(
     )
 }
 
-/// Request to update a feature
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct FeatureUpdateRequest {
-    /// Project directory path
-    pub project_dir: String,
-
-    /// Feature ID (if updating existing feature)
-    #[serde(default)]
-    pub feature_id: Option<String>,
-
-    /// Feature name (required for new features)
-    #[serde(default)]
-    pub name: Option<String>,
-
-    /// Feature description
-    #[serde(default)]
-    pub description: Option<String>,
-
-    /// New status
-    #[serde(default)]
-    pub status: Option<FeatureStatus>,
-
-    /// Priority
-    #[serde(default)]
-    pub priority: Option<FeaturePriority>,
-
-    /// Tags to add
-    #[serde(default)]
-    pub tags: Option<Vec<String>>,
-
-    /// Acceptance criteria
-    #[serde(default)]
-    pub acceptance_criteria: Option<Vec<String>>,
-
-    /// Note to add
-    #[serde(default)]
-    pub add_note: Option<String>,
-
-    /// Files affected
-    #[serde(default)]
-    pub affected_files: Option<Vec<String>>,
-
-    /// Session ID working on this feature
-    #[serde(default)]
-    pub session_id: Option<String>,
-
-    /// Blocked reason (if setting status to blocked)
-    #[serde(default)]
-    pub blocked_reason: Option<String>,
-
-    /// Dependencies (feature IDs)
-    #[serde(default)]
-    pub dependencies: Option<Vec<String>>,
-
-    /// Complexity (1-5)
-    #[serde(default)]
-    pub complexity: Option<u8>,
-}
-
-/// Response from feature update
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct FeatureUpdateResponse {
-    pub success: bool,
-    pub message: String,
-    pub feature_id: Option<String>,
-}
-
 /// Update or create a feature
 pub async fn handle_feature_update(
     State(_state): State<Arc<AppState>>,
@@ -838,49 +688,6 @@ pub async fn handle_feature_update(
     )
 }
 
-/// Request for harness status
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct HarnessStatusRequest {
-    /// Project directory path
-    pub project_dir: String,
-}
-
-/// Response with harness status
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct HarnessStatusResponse {
-    pub success: bool,
-    pub initialized: bool,
-    pub game: Option<GameDefinition>,
-    pub features: Vec<Feature>,
-    pub feature_summary: FeatureSummary,
-    pub recent_sessions: Vec<SessionSummary>,
-}
-
-/// Summary of feature statuses
-#[derive(Debug, Default, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct FeatureSummary {
-    pub total: usize,
-    pub planned: usize,
-    pub in_progress: usize,
-    pub completed: usize,
-    pub blocked: usize,
-    pub cancelled: usize,
-}
-
-/// Brief session summary
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SessionSummary {
-    pub id: String,
-    pub started_at: String,
-    pub ended_at: Option<String>,
-    pub summary: String,
-    pub features_count: usize,
-}
-
 /// Get harness status for a project
 pub async fn handle_harness_status(
     State(_state): State<Arc<AppState>>,
@@ -0,0 +1,251 @@
+//! Hot reload for Studio plugin development
+//!
+//! Trying a plugin change used to mean rebuilding, reinstalling and
+//! restarting Studio. `rbxsync build-plugin --watch --hot` posts every build
+//! to `POST /plugin/hot`, which keeps the latest `.rbxm` and sends a
+//! `plugin:reload` command to the hot-reload bootstrap plugin running in
+//! Studio. The bootstrap long-polls `GET /plugin/hot/poll`, fetches the new
+//! scripts from `GET /plugin/hot/modules` and re-requires them in place.
+//! `GET /plugin/hot` serves the `.rbxm` itself.
+//!
+//! The bootstrap has its own poll rather than a project queue, so it never
+//! takes requests meant for the main plugin.
+//!
+//! Whatever is posted ends up running in Studio, so uploads must be
+//! `application/octet-stream` with the `X-RbxSync-Client` header and no
+//! foreign `Origin`. A web page can't send that on its own.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use rbxsync_core::PluginModule;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::protocol::{PluginCommand, PluginReload};
+use crate::{AppState, PluginRequest};
+
+/// Wakeup scope the bootstrap's polls wait on
+pub const HOT_RELOAD_SCOPE: &str = "rbxsync:plugin-hot-reload";
+
+/// Command telling the bootstrap to reload
+pub const RELOAD_COMMAND: &str = "plugin:reload";
+
+/// The latest plugin build posted for hot reload
+#[derive(Debug, Clone)]
+pub struct HotPlugin {
+    /// Increases with every build, starting at 1
+    pub version: u64,
+    pub name: String,
+    pub rbxm: Vec<u8>,
+    pub modules: PluginModule,
+}
+
+impl HotPlugin {
+    fn reload_request(&self) -> PluginRequest {
+        PluginCommand::PluginReload(PluginReload { version: self.version, name: self.name.clone() }).request(Uuid::new_v4())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PublishQuery {
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PollQuery {
+    /// Version the bootstrap is running (0 for none)
+    #[serde(default)]
+    pub since: u64,
+}
+
+/// Keep a new build and wake the bootstrap; returns the new version
+pub async fn publish(state: &AppState, name: Option<String>, rbxm: Vec<u8>) -> anyhow::Result<u64> {
+    let modules = rbxsync_core::read_plugin_modules(&rbxm)?;
+    let mut hot = state.hot_plugin.write().await;
+    let version = hot.as_ref().map_or(0, |h| h.version) + 1;
+    *hot = Some(HotPlugin {
+        version,
+        name: name.unwrap_or_else(|| modules.name.clone()),
+        rbxm,
+        modules,
+    });
+    drop(hot);
+    state.wakeups.notify_project(HOT_RELOAD_SCOPE);
+    Ok(version)
+}
+
+/// The reload command for a bootstrap running `since`, if there's a newer build
+async fn pending_reload(state: &AppState, since: u64) -> Option<PluginRequest> {
+    state
+        .hot_plugin
+        .read()
+        .await
+        .as_ref()
+        .filter(|hot| hot.version > since)
+        .map(HotPlugin::reload_request)
+}
+
+fn not_published() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({
+            "success": false,
+            "error": "No plugin build yet. Run `rbxsync build-plugin --watch --hot`"
+        })),
+    )
+        .into_response()
+}
+
+/// Take a freshly built plugin .rbxm (POST /plugin/hot)
+pub async fn handle_publish(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PublishQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if let Some(rejection) = crate::auth::reject_cross_site(&headers, "application/octet-stream") {
+        return rejection;
+    }
+    if !headers.contains_key(rbxsync_core::auth::CLIENT_HEADER) {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "success": false,
+                "error": format!("Missing {} header", rbxsync_core::auth::CLIENT_HEADER)
+            })),
+        )
+            .into_response();
+    }
+    match publish(&state, query.name, body.to_vec()).await {
+        Ok(version) => {
+            tracing::info!("Hot reload: plugin build {} ({} bytes)", version, body.len());
+            (StatusCode::OK, Json(serde_json::json!({ "success": true, "version": version }))).into_response()
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "success": false, "error": format!("{:#}", e) })),
+        )
+            .into_response(),
+    }
+}
+
+/// The latest .rbxm (GET /plugin/hot)
+pub async fn handle_rbxm(State(state): State<Arc<AppState>>) -> Response {
+    match state.hot_plugin.read().await.as_ref() {
+        Some(hot) => (
+            [
+                (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.rbxm\"", hot.name)),
+            ],
+            hot.rbxm.clone(),
+        )
+            .into_response(),
+        None => not_published(),
+    }
+}
+
+/// The latest build's scripts (GET /plugin/hot/modules)
+pub async fn handle_modules(State(state): State<Arc<AppState>>) -> Response {
+    match state.hot_plugin.read().await.as_ref() {
+        Some(hot) => Json(serde_json::json!({
+            "success": true,
+            "version": hot.version,
+            "name": hot.name,
+            "root": hot.modules
+        }))
+        .into_response(),
+        None => not_published(),
+    }
+}
+
+/// Long poll for the bootstrap: a `plugin:reload` request once there's a
+/// build newer than `since`, else 204 after 15 seconds (GET /plugin/hot/poll)
+pub async fn handle_poll(State(state): State<Arc<AppState>>, Query(query): Query<PollQuery>) -> impl IntoResponse {
+    let mut waiter = state.wakeups.subscribe(Some(HOT_RELOAD_SCOPE), None);
+    let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(15);
+    loop {
+        if let Some(request) = pending_reload(&state, query.since).await {
+            return (StatusCode::OK, Json(serde_json::to_value(&request).unwrap()));
+        }
+        tokio::select! {
+            _ = tokio::time::sleep_until(deadline) => {
+                return (StatusCode::NO_CONTENT, Json(serde_json::json!(null)));
+            }
+            _ = waiter.wait() => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_wakes_the_bootstrap() {
+        let temp = tempfile::tempdir().unwrap();
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("init.server.luau"), "return function(plugin) end").unwrap();
+        let output = rbxsync_core::build_plugin(&rbxsync_core::PluginBuildConfig {
+            source_dir: src,
+            output_path: temp.path().join("Hot.rbxm"),
+            plugin_name: "Hot".to_string(),
+            obfuscate: false,
+            obfuscate_config: None,
+            cache_dir: None,
+        })
+        .unwrap();
+
+        let state = AppState::new();
+        assert!(pending_reload(&state, 0).await.is_none());
+        assert!(publish(&state, None, b"garbage".to_vec()).await.is_err());
+
+        let mut waiter = state.wakeups.subscribe(Some(HOT_RELOAD_SCOPE), None);
+        let version = publish(&state, None, std::fs::read(&output).unwrap()).await.unwrap();
+        assert_eq!(version, 1);
+        tokio::time::timeout(std::time::Duration::from_millis(50), waiter.wait()).await.unwrap();
+
+        let request = pending_reload(&state, 0).await.unwrap();
+        assert_eq!(request.command, RELOAD_COMMAND);
+        assert_eq!(request.payload["name"], "Hot");
+        assert!(pending_reload(&state, version).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_publish_refuses_cross_site_uploads() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::Service;
+
+        let state = AppState::new();
+        let upload = |content_type: &str, client: bool, origin: Option<&str>| {
+            let mut builder = Request::post("/plugin/hot").header(header::CONTENT_TYPE, content_type);
+            if client {
+                builder = builder.header(rbxsync_core::auth::CLIENT_HEADER, "cli");
+            }
+            if let Some(origin) = origin {
+                builder = builder.header(header::ORIGIN, origin);
+            }
+            builder.body(Body::from("garbage")).unwrap()
+        };
+        let status = |request: Request<Body>| {
+            let mut router = crate::create_router(state.clone());
+            async move { router.call(request).await.unwrap().status() }
+        };
+
+        assert_eq!(status(upload("text/plain", true, None)).await, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(status(upload("application/octet-stream", false, None)).await, StatusCode::FORBIDDEN);
+        let foreign = upload("application/octet-stream", true, Some("https://evil.example"));
+        assert_eq!(status(foreign).await, StatusCode::FORBIDDEN);
+        // Past the checks, a body that isn't a model is the only problem left
+        assert_eq!(status(upload("application/octet-stream", true, None)).await, StatusCode::BAD_REQUEST);
+        assert!(state.hot_plugin.read().await.is_none());
+    }
+}
@@ -0,0 +1,123 @@
+//! Idle resource management
+//!
+//! When no Studio place, VS Code workspace or `/events` subscriber has been
+//! connected for a while, the server stops its file watchers, parks the live
+//! sync loop and sweeps registrations less often, so a forgotten
+//! `rbxsync serve` doesn't keep a laptop awake. Any registration resumes it.
+
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::{cleanup_stale_registrations, cleanup_stale_vscode_workspaces, events, AppState};
+
+/// How often to check for connected clients while active
+pub const ACTIVE_SWEEP_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often to sweep stale registrations while idle
+pub const IDLE_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long the server must have no clients before suspending
+pub const IDLE_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+/// Whether the server is currently suspended
+pub fn is_idle(state: &AppState) -> bool {
+    state.idle.load(Ordering::Relaxed)
+}
+
+/// Whether anything is connected that needs watchers running
+async fn has_clients(state: &Arc<AppState>) -> bool {
+    cleanup_stale_registrations(state).await;
+    cleanup_stale_vscode_workspaces(state).await;
+
+    !state.place_registry.read().await.is_empty()
+        || !state.vscode_workspaces.read().await.is_empty()
+        || state.file_events_tx.receiver_count() > 0
+}
+
+/// Stop file watchers and park background loops
+pub async fn suspend(state: &Arc<AppState>) {
+    if state.idle.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    let stopped = state.file_watcher_state.write().await.stop_all();
+    let count = stopped.len();
+    state.suspended_watchers.write().await.extend(stopped);
+
+    tracing::info!("No clients connected, going idle ({} file watchers suspended)", count);
+}
+
+/// Restart suspended watchers and wake background loops. Cheap when not idle.
+pub async fn resume(state: &Arc<AppState>) {
+    if !state.idle.swap(false, Ordering::Relaxed) {
+        return;
+    }
+
+    let suspended: Vec<(String, bool)> = state.suspended_watchers.write().await.drain().collect();
+    for (dir, event_only) in &suspended {
+        events::watch_project(state, dir, !event_only).await;
+    }
+    state.idle_wake.notify_one();
+
+    tracing::info!("Client connected, resuming ({} file watchers restarted)", suspended.len());
+}
+
+/// Background task that suspends the server after a period with no clients
+pub async fn run_idle_monitor(state: Arc<AppState>) {
+    let mut idle_since: Option<Instant> = None;
+
+    loop {
+        let interval = if is_idle(&state) { IDLE_SWEEP_INTERVAL } else { ACTIVE_SWEEP_INTERVAL };
+        tokio::time::sleep(interval).await;
+
+        if has_clients(&state).await {
+            idle_since = None;
+            resume(&state).await;
+            continue;
+        }
+
+        let since = *idle_since.get_or_insert_with(Instant::now);
+        if !is_idle(&state) && since.elapsed() >= IDLE_GRACE_PERIOD {
+            suspend(&state).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_suspend_and_resume() {
+        let state = AppState::new();
+        let dir = tempfile::TempDir::new().unwrap();
+        let project_dir = dir.path().to_string_lossy().to_string();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+
+        crate::file_watcher::start_file_watcher(
+            project_dir.clone(),
+            state.file_watcher_state.clone(),
+            false,
+        )
+        .await
+        .unwrap();
+        assert!(!has_clients(&state).await);
+
+        suspend(&state).await;
+        assert!(is_idle(&state));
+        assert!(state.file_watcher_state.read().await.watched_dirs.is_empty());
+        assert_eq!(state.suspended_watchers.read().await.len(), 1);
+
+        resume(&state).await;
+        assert!(!is_idle(&state));
+        assert!(state.suspended_watchers.read().await.is_empty());
+
+        // watch_project restarts the watcher in a spawned task
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(state.file_watcher_state.read().await.watched_dirs.contains(&project_dir));
+
+        // Let the runtime shut down
+        state.file_watcher_state.write().await.stop_all();
+    }
+}
@@ -0,0 +1,236 @@
+//! Incremental extraction
+//!
+//! Every finalized extraction leaves `.rbxsync/extract_cache.json`: the
+//! instances it wrote, as the plugin serialized them, each carrying the
+//! `hash` the plugin computed over its content. `rbxsync extract --incremental`
+//! sends the plugin those hashes keyed by path; the plugin serializes the game
+//! as usual but only uploads instances whose hash differs (or whose path is
+//! new), then lists the known paths it no longer has. Finalize merges that
+//! into the cached instances and only touches the files that changed.
+//!
+//! Hashes skip session-specific fields (`referenceId`, `parentId`), but `Ref`
+//! property values are Studio debug ids, so instances with references are
+//! re-sent after Studio restarts. That costs time, not correctness.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const CACHE_FILE: &str = "extract_cache.json";
+
+/// Instances written by the last extraction of a project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractCache {
+    pub instances: Vec<Value>,
+}
+
+fn instance_path(instance: &Value) -> Option<&str> {
+    instance.get("path").and_then(|v| v.as_str()).filter(|p| !p.is_empty())
+}
+
+impl ExtractCache {
+    pub fn path(project_dir: &Path) -> PathBuf {
+        project_dir.join(".rbxsync").join(CACHE_FILE)
+    }
+
+    pub fn load(project_dir: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(Self::path(project_dir)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self, project_dir: &Path) {
+        let path = Self::path(project_dir);
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let content = serde_json::to_string(self).unwrap_or_default();
+        if let Err(e) = std::fs::write(&path, content) {
+            tracing::warn!("Failed to write extraction cache: {}", e);
+        }
+    }
+
+    /// Content hash per path, as sent to the plugin. Paths held by more than
+    /// one instance map to an empty hash so the plugin always re-sends them.
+    pub fn hashes(&self) -> BTreeMap<String, String> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for path in self.instances.iter().filter_map(instance_path) {
+            *counts.entry(path).or_insert(0) += 1;
+        }
+        self.instances
+            .iter()
+            .filter_map(|instance| {
+                let path = instance_path(instance)?;
+                let hash = match counts.get(path) {
+                    Some(1) => instance.get("hash").and_then(|v| v.as_str()).unwrap_or(""),
+                    _ => "",
+                };
+                Some((path.to_string(), hash.to_string()))
+            })
+            .collect()
+    }
+
+    /// Apply an incremental extraction: `changed` replaces every cached
+    /// instance at the same path and `removed` paths are dropped
+    pub fn merge(self, changed: Vec<Value>, removed: &[String]) -> Vec<Value> {
+        let replaced: HashSet<&str> = changed
+            .iter()
+            .filter_map(instance_path)
+            .chain(removed.iter().map(String::as_str))
+            .collect();
+        let mut merged: Vec<Value> = self
+            .instances
+            .into_iter()
+            .filter(|instance| instance_path(instance).is_none_or(|path| !replaced.contains(path)))
+            .collect();
+        merged.extend(changed);
+        merged
+    }
+}
+
+/// Writes the cache a chunk at a time, for extractions streamed to src that
+/// never hold all their instances at once. The cache is replaced on
+/// [`finish`](Self::finish), so a failed write leaves the previous one.
+pub struct CacheWriter {
+    file: BufWriter<File>,
+    temp: PathBuf,
+    target: PathBuf,
+    empty: bool,
+}
+
+impl CacheWriter {
+    pub fn create(project_dir: &Path) -> io::Result<Self> {
+        let target = ExtractCache::path(project_dir);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp = target.with_extension("json.tmp");
+        let mut file = BufWriter::new(File::create(&temp)?);
+        file.write_all(b"{\"instances\":[")?;
+        Ok(Self { file, temp, target, empty: true })
+    }
+
+    pub fn add(&mut self, instances: &[Value]) -> io::Result<()> {
+        for instance in instances {
+            if !self.empty {
+                self.file.write_all(b",")?;
+            }
+            serde_json::to_writer(&mut self.file, instance)?;
+            self.empty = false;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.file.write_all(b"]}")?;
+        self.file.flush()?;
+        std::fs::rename(&self.temp, &self.target)
+    }
+}
+
+/// Delete instance files under `dir` that an incremental extraction no longer
+/// writes, skipping `skip` trees, then any directories left empty. Returns
+/// the number of files removed.
+pub fn prune_stale(dir: &Path, keep: &HashSet<PathBuf>, skip: &[PathBuf]) -> usize {
+    let mut removed = 0;
+    for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if skip.iter().any(|s| path.starts_with(s)) {
+            continue;
+        }
+        if path.is_dir() {
+            removed += prune_stale(&path, keep, skip);
+            if std::fs::read_dir(&path).is_ok_and(|mut entries| entries.next().is_none()) {
+                let _ = std::fs::remove_dir(&path);
+            }
+            continue;
+        }
+        let is_instance_file = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| matches!(e, "rbxjson" | "luau" | "lua"));
+        let is_terrain = path.file_name().is_some_and(|n| n == "terrain.rbxjson");
+        if is_instance_file && !is_terrain && !keep.contains(&path) && std::fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn instance(path: &str, hash: &str) -> Value {
+        json!({ "path": path, "className": "Part", "hash": hash })
+    }
+
+    #[test]
+    fn test_merge_replaces_changed_and_drops_removed() {
+        let project = tempfile::tempdir().unwrap();
+        ExtractCache {
+            instances: vec![
+                instance("Workspace/A", "1"),
+                instance("Workspace/B", "2"),
+                instance("Workspace/C", "3"),
+                instance("Workspace/C", "4"),
+            ],
+        }
+        .save(project.path());
+
+        let cache = ExtractCache::load(project.path()).unwrap();
+        let hashes = cache.hashes();
+        assert_eq!(hashes["Workspace/A"], "1");
+        // Duplicate paths can't be matched by hash
+        assert_eq!(hashes["Workspace/C"], "");
+
+        let merged = cache.merge(vec![instance("Workspace/A", "5"), instance("Workspace/D", "6")], &["Workspace/C".to_string()]);
+        let mut paths: Vec<(&str, &str)> = merged
+            .iter()
+            .map(|i| (i["path"].as_str().unwrap(), i["hash"].as_str().unwrap()))
+            .collect();
+        paths.sort();
+        assert_eq!(paths, vec![("Workspace/A", "5"), ("Workspace/B", "2"), ("Workspace/D", "6")]);
+    }
+
+    #[test]
+    fn test_cache_writer_matches_save() {
+        let project = tempfile::tempdir().unwrap();
+        let mut writer = CacheWriter::create(project.path()).unwrap();
+        writer.add(&[instance("Workspace/A", "1"), instance("Workspace/B", "2")]).unwrap();
+        writer.add(&[]).unwrap();
+        writer.add(&[instance("Workspace/C", "3")]).unwrap();
+        writer.finish().unwrap();
+
+        let cache = ExtractCache::load(project.path()).unwrap();
+        let paths: Vec<&str> = cache.instances.iter().map(|i| i["path"].as_str().unwrap()).collect();
+        assert_eq!(paths, vec!["Workspace/A", "Workspace/B", "Workspace/C"]);
+        assert!(!ExtractCache::path(project.path()).with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn test_prune_stale_keeps_written_and_skipped_files() {
+        let src = tempfile::tempdir().unwrap();
+        let write = |rel: &str| {
+            let path = src.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, "{}").unwrap();
+            path
+        };
+        let kept = write("Workspace/_meta.rbxjson");
+        write("Workspace/Gone/_meta.rbxjson");
+        write("Workspace/Gone/Part.rbxjson");
+        let terrain = write("Workspace/Terrain/terrain.rbxjson");
+        let package = write("ReplicatedStorage/Packages/Roact.luau");
+
+        let keep: HashSet<PathBuf> = [kept.clone()].into_iter().collect();
+        let skip = [src.path().join("ReplicatedStorage/Packages")];
+        assert_eq!(prune_stale(src.path(), &keep, &skip), 2);
+        assert!(kept.exists() && terrain.exists() && package.exists());
+        assert!(!src.path().join("Workspace/Gone").exists());
+    }
+}
@@ -0,0 +1,291 @@
+//! Job introspection
+//!
+//! When a sync hangs it is usually waiting on the plugin: a request nobody
+//! polls for, or one the plugin took and never answered. `GET /jobs` lists
+//! every queued request (global, per-project and per-session queues) and every
+//! request handed to the plugin that hasn't been answered, with its command,
+//! age and payload size. `POST /jobs/:id/cancel` drops a stuck request and
+//! answers whoever is waiting on it with an error, so the endpoint that sent it
+//! returns instead of timing out. `rbxsync status` prints the same list.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{AppState, PluginRequest, PluginResponse, RequestPollQuery};
+
+/// In-flight requests older than this are assumed abandoned and forgotten
+const IN_FLIGHT_TTL: Duration = Duration::from_secs(600);
+
+/// A request the plugin took but hasn't answered
+#[derive(Debug, Clone)]
+struct InFlight {
+    command: String,
+    project_dir: Option<String>,
+    session_id: Option<String>,
+    payload_bytes: usize,
+    queued_at: Instant,
+    dispatched_at: Instant,
+}
+
+/// Requests handed to the plugin, kept until it answers
+#[derive(Debug, Default)]
+pub struct JobTracker {
+    in_flight: Mutex<HashMap<Uuid, InFlight>>,
+}
+
+impl JobTracker {
+    /// A polling plugin took `request`
+    pub fn dispatched(&self, request: &PluginRequest, poll: &RequestPollQuery) {
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            let now = Instant::now();
+            in_flight.retain(|_, job| now.duration_since(job.dispatched_at) < IN_FLIGHT_TTL);
+            in_flight.insert(
+                request.id,
+                InFlight {
+                    command: request.command.clone(),
+                    project_dir: poll.project_dir.clone(),
+                    session_id: poll.session_id.clone(),
+                    payload_bytes: payload_bytes(request),
+                    queued_at: request.queued_at,
+                    dispatched_at: now,
+                },
+            );
+        }
+    }
+
+    /// The plugin answered (or the request was cancelled); true if it was in flight
+    pub fn finished(&self, id: Uuid) -> bool {
+        self.in_flight.lock().map(|mut in_flight| in_flight.remove(&id).is_some()).unwrap_or(false)
+    }
+
+    fn snapshot(&self) -> Vec<(Uuid, InFlight)> {
+        self.in_flight
+            .lock()
+            .map(|in_flight| in_flight.iter().map(|(id, job)| (*id, job.clone())).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Where a job is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobState {
+    /// Waiting in a queue for the plugin to poll
+    Queued,
+    /// Taken by the plugin, no response yet
+    InFlight,
+}
+
+/// One queued or in-flight request
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    pub id: Uuid,
+    pub command: String,
+    pub state: JobState,
+    /// `global`, `project` or `session`, for queued jobs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Place in its queue, from 0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<usize>,
+    /// Time since the request was queued
+    pub age_ms: u64,
+    /// Time since the plugin took it, for in-flight jobs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_flight_ms: Option<u64>,
+    pub payload_bytes: usize,
+    /// Whether an endpoint is still waiting on the response
+    pub awaited: bool,
+}
+
+/// Job counts for one project
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectJobs {
+    pub queued: usize,
+    pub in_flight: usize,
+    pub oldest_ms: u64,
+}
+
+fn payload_bytes(request: &PluginRequest) -> usize {
+    serde_json::to_vec(&request.payload).map(|bytes| bytes.len()).unwrap_or(0)
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+/// Every queued and in-flight job, oldest first
+pub async fn list_jobs(state: &AppState) -> Vec<Job> {
+    let now = Instant::now();
+    let awaited = state.response_channels.read().await;
+    let queued = |request: &PluginRequest, queue: &'static str, key: Option<&String>, position: usize| Job {
+        id: request.id,
+        command: request.command.clone(),
+        state: JobState::Queued,
+        queue: Some(queue),
+        project_dir: (queue == "project").then(|| key.cloned()).flatten(),
+        session_id: (queue == "session").then(|| key.cloned()).flatten(),
+        position: Some(position),
+        age_ms: millis(now.duration_since(request.queued_at)),
+        in_flight_ms: None,
+        payload_bytes: payload_bytes(request),
+        awaited: awaited.contains_key(&request.id),
+    };
+
+    let mut jobs: Vec<Job> = Vec::new();
+    for (position, request) in state.request_queue.lock().await.iter().enumerate() {
+        jobs.push(queued(request, "global", None, position));
+    }
+    for (dir, queue) in state.project_queues.read().await.iter() {
+        jobs.extend(queue.iter().enumerate().map(|(position, request)| queued(request, "project", Some(dir), position)));
+    }
+    // Session queues are keyed by session; report the place's project too
+    let places = state.place_registry.read().await;
+    for (session, queue) in state.session_queues.read().await.iter() {
+        let project_dir = places.get(session).map(|place| place.project_dir.clone());
+        jobs.extend(queue.iter().enumerate().map(|(position, request)| Job {
+            project_dir: project_dir.clone(),
+            ..queued(request, "session", Some(session), position)
+        }));
+    }
+    drop(places);
+
+    for (id, job) in state.jobs.snapshot() {
+        jobs.push(Job {
+            id,
+            command: job.command,
+            state: JobState::InFlight,
+            queue: None,
+            project_dir: job.project_dir,
+            session_id: job.session_id,
+            position: None,
+            age_ms: millis(now.duration_since(job.queued_at)),
+            in_flight_ms: Some(millis(now.duration_since(job.dispatched_at))),
+            payload_bytes: job.payload_bytes,
+            awaited: awaited.contains_key(&id),
+        });
+    }
+    jobs.sort_by_key(|job| std::cmp::Reverse(job.age_ms));
+    jobs
+}
+
+/// Counts per project; jobs without one are under `""`
+pub fn by_project(jobs: &[Job]) -> BTreeMap<String, ProjectJobs> {
+    let mut projects: BTreeMap<String, ProjectJobs> = BTreeMap::new();
+    for job in jobs {
+        let entry = projects.entry(job.project_dir.clone().unwrap_or_default()).or_default();
+        match job.state {
+            JobState::Queued => entry.queued += 1,
+            JobState::InFlight => entry.in_flight += 1,
+        }
+        entry.oldest_ms = entry.oldest_ms.max(job.age_ms);
+    }
+    projects
+}
+
+/// Remove a request from whichever queue holds it
+fn take_from(queue: &mut VecDeque<PluginRequest>, id: Uuid) -> Option<PluginRequest> {
+    let index = queue.iter().position(|request| request.id == id)?;
+    queue.remove(index)
+}
+
+/// Drop a queued or in-flight request and fail whoever waits on it.
+/// Returns the cancelled job's state, or None if no such job exists.
+pub async fn cancel(state: &AppState, id: Uuid) -> Option<JobState> {
+    let mut cancelled = take_from(&mut *state.request_queue.lock().await, id).map(|_| JobState::Queued);
+    if cancelled.is_none() {
+        cancelled = state.project_queues.write().await.values_mut().find_map(|queue| take_from(queue, id)).map(|_| JobState::Queued);
+    }
+    if cancelled.is_none() {
+        cancelled = state.session_queues.write().await.values_mut().find_map(|queue| take_from(queue, id)).map(|_| JobState::Queued);
+    }
+    if cancelled.is_none() && state.jobs.finished(id) {
+        cancelled = Some(JobState::InFlight);
+    }
+
+    if cancelled.is_some() {
+        if let Some(sender) = state.response_channels.read().await.get(&id) {
+            let _ = sender.send(PluginResponse {
+                id,
+                success: false,
+                data: serde_json::Value::Null,
+                error: Some("Cancelled via /jobs".to_string()),
+            });
+        }
+        tracing::warn!("Cancelled request {} ({:?})", id, cancelled);
+    }
+    cancelled
+}
+
+/// Queued and in-flight plugin requests (GET /jobs)
+pub async fn handle_list(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let jobs = list_jobs(&state).await;
+    Json(serde_json::json!({
+        "success": true,
+        "projects": by_project(&jobs),
+        "jobs": jobs
+    }))
+}
+
+/// Cancel a stuck request (POST /jobs/:id/cancel)
+pub async fn handle_cancel(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> impl IntoResponse {
+    let Ok(id) = Uuid::parse_str(&id) else {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "success": false, "error": "Invalid job id" })));
+    };
+    match cancel(&state, id).await {
+        Some(was) => (StatusCode::OK, Json(serde_json::json!({ "success": true, "id": id, "was": was }))),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "success": false, "error": format!("No queued or in-flight job {}", id) })),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{NoPayload, PluginCommand};
+
+    #[tokio::test]
+    async fn test_list_and_cancel_jobs() {
+        let state = AppState::new();
+        let queued = PluginCommand::TestStart(NoPayload {}).request(Uuid::new_v4());
+        let queued_id = queued.id;
+        state.project_queues.write().await.entry("/games/obby".to_string()).or_default().push_back(queued);
+
+        let taken = PluginCommand::TestStop(NoPayload {}).request(Uuid::new_v4());
+        let poll = RequestPollQuery { project_dir: Some("/games/obby".to_string()), session_id: None };
+        state.jobs.dispatched(&taken, &poll);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        state.response_channels.write().await.insert(taken.id, tx);
+
+        let jobs = list_jobs(&state).await;
+        assert_eq!(jobs.len(), 2);
+        let projects = by_project(&jobs);
+        assert_eq!((projects["/games/obby"].queued, projects["/games/obby"].in_flight), (1, 1));
+        let in_flight = jobs.iter().find(|job| job.state == JobState::InFlight).unwrap();
+        assert_eq!((in_flight.command.as_str(), in_flight.awaited), ("test:stop", true));
+
+        assert_eq!(cancel(&state, queued_id).await, Some(JobState::Queued));
+        assert_eq!(cancel(&state, taken.id).await, Some(JobState::InFlight));
+        assert_eq!(rx.recv().await.unwrap().error.as_deref(), Some("Cancelled via /jobs"));
+        assert_eq!(cancel(&state, taken.id).await, None);
+        assert!(list_jobs(&state).await.is_empty());
+    }
+}
@@ -3,9 +3,63 @@
 //! HTTP server that communicates with the Roblox Studio plugin
 //! for game extraction and synchronization.
 
+pub mod assets;
+pub mod audit;
+pub mod auth;
+pub mod autocommit;
+pub mod autosave;
+pub mod backup;
+pub mod bench;
+pub mod blame;
+pub mod branch;
+pub mod build;
+pub mod checkpoint;
+pub mod compat;
+pub mod components;
+pub mod conflicts;
+pub mod console;
+pub mod correlate;
+pub mod crashes;
+pub mod events;
+pub mod extract_parts;
+pub mod extract_stream;
+pub mod faults;
 pub mod git;
 pub mod file_watcher;
+pub mod flags;
+pub mod format;
 pub mod harness;
+pub mod hot_reload;
+pub mod idle;
+pub mod incremental;
+pub mod jobs;
+pub mod logs;
+pub mod metrics;
+pub mod mcp;
+pub mod mocks;
+pub mod nightly;
+pub mod oidc;
+pub mod overrides;
+pub mod pause;
+pub mod persist;
+pub mod places;
+pub mod ports;
+pub mod priority;
+pub mod production;
+pub mod properties;
+pub mod protocol;
+pub mod recovery;
+pub mod scenario;
+pub mod scripts;
+pub mod site;
+pub mod snapshot;
+pub mod social;
+pub mod stats;
+pub mod studio;
+pub mod summary;
+pub mod test_run;
+pub mod wakeups;
+pub mod workspaces;
 
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
@@ -16,13 +70,30 @@ use axum::{
     extract::{DefaultBodyLimit, Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
-    routing::{get, post},
+    routing::{any, get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
-use tokio::sync::{broadcast, mpsc, watch, Mutex, RwLock};
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
 use uuid::Uuid;
 
+use protocol::{NoPayload, PluginCommand};
+
+// Request/response bodies shared with rbxsync-client
+pub use rbxsync_core::api::{
+    BotActionRequest, BotCommandRequest, BotMoveRequest, BotObserveRequest, BotQueryServerRequest,
+    ConflictsRequest, ConflictsResponse, ConsoleExportRequest, ConsoleHistoryQuery, ConsoleHistoryResponse, ConsoleMessage,
+    ConsolePushRequest, DiffEntry,
+    DiffRequest, DiffResult, ExtractChunkRequest, ExtractResumeRequest, ExtractResumeResponse,
+    ExtractStartRequest, ExtractStartResponse,
+    FinalizeRequest, GitBranchCreateRequest, GitCheckoutRequest, GitCommitRequest, GitLogRequest, GitProjectRequest,
+    GitRemoteRequest, GitStashRequest, GitStatusRequest,
+    IncrementalSyncRequest, PendingChangesRequest, ReadTreeRequest, ResolveConflictRequest,
+    StudioChangeOperation, SyncBatchRequest, SyncCommandRequest, SyncConflict, SyncFromStudioRequest,
+    TestConsoleMessage,
+    TestStatusResponse,
+};
+
 /// Normalize Windows paths by converting backslashes to forward slashes.
 /// This ensures consistent path handling across platforms and prevents issues
 /// with backslash escape sequences in JSON/strings.
@@ -31,6 +102,41 @@ fn normalize_path(path: &str) -> String {
     path.replace('\\', "/")
 }
 
+/// A shared response struct's fields with `"success": true` alongside
+fn success_body<T: Serialize>(body: &T) -> serde_json::Value {
+    let mut value = serde_json::to_value(body).unwrap_or_default();
+    if let Some(fields) = value.as_object_mut() {
+        fields.insert("success".to_string(), serde_json::Value::Bool(true));
+    }
+    value
+}
+
+/// A handler's JSON body. Fails with its `error` when it answers with an
+/// error status or `success: false`. Background tasks call handlers directly
+/// and read their result with this, rather than going back through the
+/// router and its auth layer.
+async fn handler_json(response: impl IntoResponse) -> Result<serde_json::Value, String> {
+    let response = response.into_response();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .map_err(|e| e.to_string())?;
+    let value: serde_json::Value = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+    if !status.is_success() || value.get("success") == Some(&serde_json::Value::Bool(false)) {
+        return Err(value
+            .get("error")
+            .and_then(|e| e.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("Request failed ({})", status)));
+    }
+    Ok(value)
+}
+
+/// The Studio place linked to a project, if one is connected
+async fn connected_place(state: &AppState, project_dir: &str) -> Option<PlaceInfo> {
+    state.place_registry.read().await.values().find(|place| place.project_dir == project_dir).cloned()
+}
+
 /// Load project config from rbxsync.json
 fn load_project_config(project_dir: &str) -> Option<serde_json::Value> {
     let config_path = PathBuf::from(project_dir).join("rbxsync.json");
@@ -44,6 +150,69 @@ fn load_project_config(project_dir: &str) -> Option<serde_json::Value> {
     None
 }
 
+/// Report or migrate legacy .lua files in a project, per `sync.legacyLua`.
+///
+/// Returns None when there is nothing to report.
+fn check_legacy_lua(project_dir: &str) -> Option<serde_json::Value> {
+    let mode = load_project_config(project_dir)
+        .and_then(|c| c.get("sync").and_then(|s| s.get("legacyLua")).cloned())
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+
+    let src_dir = PathBuf::from(project_dir).join("src");
+    let files = match mode {
+        rbxsync_core::types::LegacyLuaMode::Ignore => return None,
+        _ => rbxsync_core::find_legacy_lua_files(&src_dir),
+    };
+    if files.is_empty() {
+        return None;
+    }
+
+    if mode == rbxsync_core::types::LegacyLuaMode::Migrate {
+        return match rbxsync_core::migrate_lua_files(std::path::Path::new(project_dir), false) {
+            Ok(report) => {
+                tracing::info!("Migrated {} legacy .lua files to .luau", report.migrated.len());
+                Some(serde_json::json!({ "migrated": report }))
+            }
+            Err(e) => {
+                tracing::warn!("Failed to migrate legacy .lua files: {}", e);
+                Some(serde_json::json!({ "error": e.to_string() }))
+            }
+        };
+    }
+
+    let files: Vec<String> = files
+        .iter()
+        .map(|f| rbxsync_core::path_to_string(f.strip_prefix(project_dir).unwrap_or(f)))
+        .collect();
+    tracing::warn!(
+        "{} legacy .lua files are not synced; run `rbxsync migrate --from lua` to convert them",
+        files.len()
+    );
+    Some(serde_json::json!({ "files": files }))
+}
+
+/// A project's shared Packages folder (`packages.packagesFolder`, default `Packages`)
+pub fn packages_folder(project_dir: &str) -> String {
+    load_project_config(project_dir)
+        .and_then(|c| c.get("packages")?.get("packagesFolder")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| "Packages".to_string())
+}
+
+/// Differences between a project's wally.lock and its installed Packages.
+///
+/// None when the project has no wally.lock or everything matches.
+pub fn check_wally_drift(project_dir: &str) -> Option<rbxsync_core::WallyDrift> {
+    let packages_folder = packages_folder(project_dir);
+    match rbxsync_core::check_wally_drift(project_dir, &packages_folder) {
+        Ok(drift) => drift.filter(|d| !d.is_clean()),
+        Err(e) => {
+            tracing::warn!("Failed to check wally.lock in {}: {}", project_dir, e);
+            None
+        }
+    }
+}
+
 /// Apply tree mapping to convert DataModel path to filesystem path
 fn apply_tree_mapping(datamodel_path: &str, tree_mapping: &HashMap<String, String>) -> String {
     // Try to find longest matching prefix
@@ -70,6 +239,50 @@ fn apply_tree_mapping(datamodel_path: &str, tree_mapping: &HashMap<String, Strin
     }
 }
 
+/// Collapse duplicated Packages folders (`Packages/Packages/`) in an extracted path
+fn normalize_package_path(path: &str) -> String {
+    // Fix case variations and duplications like "Packages/Packages" or "packages/Packages"
+    let mut normalized = path.to_string();
+
+    // Replace various case-insensitive duplications
+    let patterns = [
+        ("Packages/Packages/", "Packages/"),
+        ("packages/packages/", "packages/"),
+        ("Packages/packages/", "Packages/"),
+        ("packages/Packages/", "Packages/"),
+    ];
+
+    for (from, to) in patterns {
+        while normalized.contains(from) {
+            normalized = normalized.replace(from, to);
+        }
+    }
+
+    normalized
+}
+
+/// An extracted instance as written to its `.rbxjson`: without script source
+/// (that goes in the `.luau` file) or the extraction cache's content hash
+fn clean_instance(inst: &serde_json::Value, omit_defaults: bool, schema_ref: Option<&str>) -> serde_json::Value {
+    let mut clean_inst = inst.clone();
+    let class_name = inst.get("className").and_then(|v| v.as_str()).unwrap_or("Unknown");
+    if extract_stream::script_extension(class_name).is_some() {
+        if let Some(props) = clean_inst.get_mut("properties") {
+            if let Some(obj) = props.as_object_mut() {
+                obj.remove("Source");
+            }
+        }
+    }
+    if let Some(obj) = clean_inst.as_object_mut() {
+        obj.remove("hash");
+    }
+    if omit_defaults {
+        rbxsync_core::strip_defaults(&mut clean_inst);
+    }
+    rbxsync_core::with_schema_ref(&mut clean_inst, schema_ref);
+    clean_inst
+}
+
 /// Recursively copy a directory
 fn copy_dir_recursive(src: &PathBuf, dst: &PathBuf) -> std::io::Result<()> {
     std::fs::create_dir_all(dst)?;
@@ -87,7 +300,6 @@ fn copy_dir_recursive(src: &PathBuf, dst: &PathBuf) -> std::io::Result<()> {
 }
 
 /// Apply reverse tree mapping to convert filesystem path to DataModel path
-#[allow(dead_code)]
 fn apply_reverse_tree_mapping(fs_path: &str, tree_mapping: &HashMap<String, String>) -> String {
     // Try to find longest matching prefix (reverse lookup)
     let mut best_match: Option<(&str, &str)> = None;
@@ -127,6 +339,71 @@ fn get_tree_mapping(config: &Option<serde_json::Value>) -> HashMap<String, Strin
         .unwrap_or_default()
 }
 
+/// Whether a project keeps sourcemap.json current (`sync.sourcemap`)
+fn sourcemap_enabled(config: &Option<serde_json::Value>) -> bool {
+    config
+        .as_ref()
+        .and_then(|c| c.get("sync")?.get("sourcemap")?.as_bool())
+        .unwrap_or(false)
+}
+
+/// Regenerate a project's sourcemap.json in the background
+fn regenerate_sourcemap(project_dir: &str) {
+    let dir = PathBuf::from(project_dir);
+    tokio::task::spawn_blocking(move || {
+        let output = dir.join(rbxsync_core::sourcemap::SOURCEMAP_FILE);
+        if let Err(e) = rbxsync_core::sourcemap::write_sourcemap(&dir, &output, false) {
+            tracing::warn!("Failed to regenerate sourcemap: {}", e);
+        }
+    });
+}
+
+/// Extract the selective sync filter (`sync.include` / `sync.exclude`) from config JSON
+fn get_sync_filter(config: &Option<serde_json::Value>) -> rbxsync_core::SyncFilter {
+    config.as_ref().map(rbxsync_core::SyncFilter::from_config).unwrap_or_default()
+}
+
+/// Instances no sync may delete (`protectedPaths`)
+fn get_protected_paths(config: &Option<serde_json::Value>) -> rbxsync_core::ProtectedPaths {
+    config.as_ref().map(rbxsync_core::ProtectedPaths::from_config).unwrap_or_default()
+}
+
+/// Remove delete operations on protected instances, warning about each.
+/// Returns the paths whose deletes were skipped.
+fn skip_protected_deletes(protected: &rbxsync_core::ProtectedPaths, operations: &mut Vec<serde_json::Value>) -> Vec<String> {
+    let mut skipped = Vec::new();
+    operations.retain(|op| {
+        let path = op.get("path").and_then(|p| p.as_str()).unwrap_or("");
+        match protected.protecting(path).filter(|_| op.get("type").and_then(|t| t.as_str()) == Some("delete")) {
+            Some(pattern) => {
+                tracing::warn!("Not deleting {}: protected by {}", path, pattern);
+                skipped.push(path.to_string());
+                false
+            }
+            None => true,
+        }
+    });
+    skipped
+}
+
+/// Where Studio-only instances are left alone: `$ignoreUnknownInstances` in
+/// the project's Rojo file, plus `sync.ignoreUnknownInstances` in rbxsync.json
+fn get_unknown_instance_settings(project_dir: &str, config: &Option<serde_json::Value>) -> HashMap<String, bool> {
+    let mut settings = rbxsync_core::find_rojo_project(std::path::Path::new(project_dir))
+        .and_then(|path| rbxsync_core::parse_rojo_project(&path))
+        .map(|project| rbxsync_core::unknown_instance_settings(&project))
+        .unwrap_or_default();
+    let paths = config
+        .as_ref()
+        .and_then(|c| c.get("sync"))
+        .and_then(|s| s.get("ignoreUnknownInstances"))
+        .and_then(|v| v.as_array());
+    for path in paths.into_iter().flatten().filter_map(|v| v.as_str()) {
+        settings.insert(rbxsync_core::normalize_instance_root(path), true);
+    }
+    settings
+}
+
 /// Strip disambiguation suffix from a path segment (RBXSYNC-68)
 /// Extraction adds `_{8 hex chars}` suffix for duplicates
 /// e.g., "Part_a1b2c3d4" -> "Part", "MyModel" -> "MyModel"
@@ -147,7 +424,7 @@ fn strip_disambiguation_suffix(segment: &str) -> String {
 
 /// Strip disambiguation suffixes from all path segments (RBXSYNC-68)
 /// e.g., "Workspace/Part_a1b2c3d4/Child" -> "Workspace/Part/Child"
-fn normalize_path_for_comparison(path: &str) -> String {
+pub fn normalize_path_for_comparison(path: &str) -> String {
     path.split('/')
         .map(strip_disambiguation_suffix)
         .collect::<Vec<_>>()
@@ -158,7 +435,45 @@ fn normalize_path_for_comparison(path: &str) -> String {
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
     pub port: u16,
+    /// Address to bind. Anything other than loopback needs `token`.
     pub host: String,
+    /// Require this token on mutating requests (see `auth`)
+    pub token: Option<String>,
+    /// Also accept the keychain tokens of the users in `~/.rbxsync/users.json`
+    pub users: bool,
+    /// Also accept ID tokens from an OpenID Connect issuer
+    pub oidc: Option<oidc::OidcConfig>,
+    /// Serve HTTPS instead of HTTP
+    pub tls: Option<TlsConfig>,
+}
+
+/// PEM certificate chain and private key for serving HTTPS
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+/// Whether a bind address only accepts connections from this machine
+pub fn is_loopback_host(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Base URL that code running on this machine, such as Luau generated for a
+/// Studio playtest, reaches the server at: the port it actually bound, which
+/// may have failed over, and `https` when TLS is on
+pub fn local_url(state: &AppState) -> String {
+    let port = match state.port.load(std::sync::atomic::Ordering::Relaxed) {
+        0 => rbxsync_core::DEFAULT_PORT,
+        port => port,
+    };
+    let scheme = if state.tls.load(std::sync::atomic::Ordering::Relaxed) { "https" } else { "http" };
+    format!("{}://127.0.0.1:{}", scheme, port)
 }
 
 impl Default for ServerConfig {
@@ -166,6 +481,10 @@ impl Default for ServerConfig {
         Self {
             port: 44755,
             host: "127.0.0.1".to_string(),
+            token: None,
+            users: false,
+            oidc: None,
+            tls: None,
         }
     }
 }
@@ -174,19 +493,16 @@ impl Default for ServerConfig {
 #[derive(Debug, Clone, Serialize)]
 pub struct VsCodeWorkspace {
     pub workspace_dir: String,
+    /// Canonical form of `workspace_dir`, used to detect duplicate registrations
+    pub canonical_dir: String,
+    /// Editor window identifier, if the client sends one
+    pub client_id: Option<String>,
+    #[serde(skip)]
+    pub registered_at: Instant,
     #[serde(skip)]
     pub last_heartbeat: Option<Instant>,
 }
 
-/// Console message from Studio
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ConsoleMessage {
-    pub timestamp: String,
-    pub message_type: String,  // "info", "warn", "error"
-    pub message: String,
-    pub source: Option<String>,  // e.g., "sync", "extract", "plugin"
-}
-
 /// Max console messages to keep in buffer
 const CONSOLE_BUFFER_SIZE: usize = 1000;
 
@@ -218,6 +534,9 @@ pub struct AppState {
     /// Per-project request queues for multi-workspace support
     pub project_queues: RwLock<HashMap<String, VecDeque<PluginRequest>>>,
 
+    /// Per-session request queues, for commands aimed at one Studio instance
+    pub session_queues: RwLock<HashMap<String, VecDeque<PluginRequest>>>,
+
     /// Registry of connected Studio places (session_id → PlaceInfo)
     pub place_registry: RwLock<HashMap<String, PlaceInfo>>,
 
@@ -230,11 +549,8 @@ pub struct AppState {
     /// Map of request ID to response channel
     pub response_channels: RwLock<HashMap<Uuid, mpsc::UnboundedSender<PluginResponse>>>,
 
-    /// Trigger to wake up long-polling requests
-    pub trigger: watch::Sender<()>,
-
-    /// Receiver for trigger notifications
-    pub trigger_rx: watch::Receiver<()>,
+    /// Wakes long-polling plugins, per project/session or all at once
+    pub wakeups: wakeups::Wakeups,
 
     /// Active extraction session
     pub extraction_session: RwLock<Option<ExtractionSession>>,
@@ -242,12 +558,18 @@ pub struct AppState {
     /// Flag to pause live sync during extraction (avoids syncing files that were just extracted)
     pub live_sync_paused: std::sync::atomic::AtomicBool,
 
+    /// Live sync paused by the user (`/sync/pause`), holding changes until resumed
+    pub sync_pause: pause::SyncPause,
+
     /// File watcher state for live sync
     pub file_watcher_state: Arc<RwLock<file_watcher::FileWatcherState>>,
 
     /// Channel to receive file changes
     pub file_change_rx: Mutex<mpsc::UnboundedReceiver<file_watcher::FileChange>>,
 
+    /// Broadcast channel for debounced file change batches (GET /events)
+    pub file_events_tx: broadcast::Sender<file_watcher::FileEventBatch>,
+
     /// Track which VS Code workspaces we've logged (to prevent spam)
     pub logged_vscode_workspaces: RwLock<HashSet<String>>,
 
@@ -260,8 +582,8 @@ pub struct AppState {
     /// Broadcast channel for real-time console streaming
     pub console_tx: broadcast::Sender<ConsoleMessage>,
 
-    /// Sync state per project (project_dir -> last_sync_time)
-    pub sync_state: RwLock<HashMap<String, std::time::SystemTime>>,
+    /// Sync state per project (project_dir -> last sync time and conflict bases)
+    pub sync_state: RwLock<HashMap<String, conflicts::ProjectSyncState>>,
 
     /// Bot command queue for AI-controlled playtesting
     pub bot_command_queue: Mutex<VecDeque<serde_json::Value>>,
@@ -272,6 +594,9 @@ pub struct AppState {
     /// Bot command results (command_id -> result)
     pub bot_command_results: RwLock<HashMap<Uuid, serde_json::Value>>,
 
+    /// Injected latency/drops for bot commands (see `faults`)
+    pub bot_faults: Mutex<faults::FaultInjector>,
+
     /// Whether a playtest is currently active (detected via bot heartbeats)
     pub playtest_active: std::sync::atomic::AtomicBool,
 
@@ -287,26 +612,75 @@ pub struct AppState {
     /// Current operation state per project (RBXSYNC-77)
     /// Allows VS Code to display server-initiated operations (CLI/MCP)
     pub operation_state: RwLock<HashMap<String, OperationInfo>>,
+
+    /// Projects in safe mode after an interrupted operation (project_dir -> journal)
+    /// Syncs are blocked until `rbxsync recover` resolves them
+    pub safe_mode_projects: RwLock<HashMap<String, recovery::OperationJournal>>,
+
+    /// Whether the server is idle (no clients; watchers suspended)
+    pub idle: std::sync::atomic::AtomicBool,
+
+    /// Wakes loops parked while idle
+    pub idle_wake: tokio::sync::Notify,
+
+    /// Watchers stopped while idle (project_dir -> event-only), restarted on resume
+    pub suspended_watchers: RwLock<HashMap<String, bool>>,
+
+    /// Port the server is listening on (set once bound)
+    pub port: std::sync::atomic::AtomicU16,
+
+    /// Whether the server is serving HTTPS (set once bound)
+    pub tls: std::sync::atomic::AtomicBool,
+
+    /// Counters and histograms for GET /metrics
+    pub metrics: metrics::Metrics,
+
+    /// Token required on mutating requests, when the server was started with one
+    pub auth_token: std::sync::OnceLock<String>,
+
+    /// Require the token on reads too (set when listening beyond loopback)
+    pub auth_all_methods: std::sync::atomic::AtomicBool,
+
+    /// Providers accepting tokens other than `auth_token` (see `auth`)
+    pub auth_providers: std::sync::OnceLock<Vec<Arc<dyn auth::AuthProvider>>>,
+
+    /// Where to record authenticated mutating requests (see `audit`)
+    pub request_audit: std::sync::OnceLock<PathBuf>,
+
+    /// Latest plugin build posted for hot reload (see `hot_reload`)
+    pub hot_plugin: RwLock<Option<hot_reload::HotPlugin>>,
+
+    /// Requests handed to the plugin and not yet answered, for GET /jobs
+    pub jobs: jobs::JobTracker,
+
+    /// HEAD of live-synced projects, for a full resync after a branch switch
+    pub branches: branch::BranchTracker,
+
+    /// What `persist` last wrote (or read) per project; a project missing
+    /// here hasn't been restored yet
+    pub persisted: std::sync::Mutex<HashMap<String, String>>,
 }
 
 impl AppState {
     pub fn new() -> Arc<Self> {
-        let (trigger, trigger_rx) = watch::channel(());
         let (file_change_tx, file_change_rx) = mpsc::unbounded_channel();
         let (console_tx, _) = broadcast::channel(100);  // Buffer 100 messages for slow subscribers
+        let (file_events_tx, _) = broadcast::channel(100);
         Arc::new(Self {
             request_queue: Mutex::new(VecDeque::new()),
             project_queues: RwLock::new(HashMap::new()),
+            session_queues: RwLock::new(HashMap::new()),
             place_registry: RwLock::new(HashMap::new()),
             vscode_workspaces: RwLock::new(HashMap::new()),
             session_counter: std::sync::atomic::AtomicU64::new(1),
             response_channels: RwLock::new(HashMap::new()),
-            trigger,
-            trigger_rx,
+            wakeups: wakeups::Wakeups::new(),
             extraction_session: RwLock::new(None),
             live_sync_paused: std::sync::atomic::AtomicBool::new(false),
+            sync_pause: pause::SyncPause::default(),
             file_watcher_state: Arc::new(RwLock::new(file_watcher::FileWatcherState::new(file_change_tx))),
             file_change_rx: Mutex::new(file_change_rx),
+            file_events_tx,
             logged_vscode_workspaces: RwLock::new(HashSet::new()),
             logged_studio_places: RwLock::new(HashSet::new()),
             console_buffer: RwLock::new(VecDeque::with_capacity(CONSOLE_BUFFER_SIZE)),
@@ -315,26 +689,48 @@ impl AppState {
             bot_command_queue: Mutex::new(VecDeque::new()),
             bot_state: RwLock::new(None),
             bot_command_results: RwLock::new(HashMap::new()),
+            bot_faults: Mutex::new(faults::FaultInjector::default()),
             playtest_active: std::sync::atomic::AtomicBool::new(false),
             last_bot_heartbeat: RwLock::new(None),
             playtest_started: RwLock::new(None),
             playtest_ended: RwLock::new(None),
             operation_state: RwLock::new(HashMap::new()),
+            safe_mode_projects: RwLock::new(HashMap::new()),
+            idle: std::sync::atomic::AtomicBool::new(false),
+            idle_wake: tokio::sync::Notify::new(),
+            suspended_watchers: RwLock::new(HashMap::new()),
+            port: std::sync::atomic::AtomicU16::new(0),
+            tls: std::sync::atomic::AtomicBool::new(false),
+            metrics: metrics::Metrics::new(),
+            auth_token: std::sync::OnceLock::new(),
+            auth_all_methods: std::sync::atomic::AtomicBool::new(false),
+            auth_providers: std::sync::OnceLock::new(),
+            request_audit: std::sync::OnceLock::new(),
+            hot_plugin: RwLock::new(None),
+            jobs: jobs::JobTracker::default(),
+            branches: branch::BranchTracker::default(),
+            persisted: std::sync::Mutex::new(HashMap::new()),
         })
     }
 }
 
-/// Request to send to the Studio plugin
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Request to send to the Studio plugin; built from a
+/// [`protocol::PluginCommand`] except for `/sync/command` passthroughs
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct PluginRequest {
+    #[schemars(with = "String")]
     pub id: Uuid,
     pub command: String,
     pub payload: serde_json::Value,
+    /// When the request was created, for `/jobs`
+    #[serde(skip, default = "Instant::now")]
+    pub queued_at: Instant,
 }
 
 /// Response from the Studio plugin
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct PluginResponse {
+    #[schemars(with = "String")]
     pub id: Uuid,
     pub success: bool,
     #[serde(default)]
@@ -352,6 +748,75 @@ pub struct ExtractionSession {
     pub data: Vec<serde_json::Value>,
     /// Whether finalize has been called (extraction complete even if 0 chunks)
     pub finalized: bool,
+    /// Oversized instances still arriving in parts
+    pub parts: extract_parts::PartAssembler,
+    /// Fail finalize if any property couldn't be serialized
+    pub strict: bool,
+    /// Properties the plugin couldn't serialize faithfully
+    pub unsupported: rbxsync_core::UnsupportedReport,
+    /// When the first chunk arrived or the session was started, for sync stats
+    pub started: Instant,
+    /// Received chunks on disk, for resuming after a dropped connection
+    pub checkpoint: checkpoint::Checkpoint,
+    /// Record the place's assets in assets/manifest.json on finalize
+    pub include_assets: bool,
+    /// Writes instances to the staging directory as they arrive; `None` for
+    /// incremental extractions and ones without a project, kept in `data`
+    pub writer: Option<extract_stream::SrcWriter>,
+    /// Project being extracted into, if any
+    pub project_dir: Option<String>,
+}
+
+impl ExtractionSession {
+    fn new(id: String, checkpoint: checkpoint::Checkpoint) -> Self {
+        Self {
+            id,
+            chunks_received: 0,
+            total_chunks: None,
+            data: Vec::new(),
+            finalized: false,
+            parts: Default::default(),
+            strict: false,
+            unsupported: Default::default(),
+            started: Instant::now(),
+            checkpoint,
+            include_assets: true,
+            writer: None,
+            project_dir: None,
+        }
+    }
+
+    /// Pick up a session from its checkpoint, or start it with an empty one
+    fn open(id: &str, project_dir: Option<&str>) -> Self {
+        let mut session = match checkpoint::Checkpoint::open(project_dir, id) {
+            Some(checkpoint) => {
+                let mut session = Self::new(id.to_string(), checkpoint);
+                // A streamed extraction's instances are already in src
+                session.writer = project_dir
+                    .filter(|dir| !dir.is_empty())
+                    .and_then(|dir| extract_stream::SrcWriter::resume(std::path::Path::new(dir), session.checkpoint.dir()));
+                for chunk in session.checkpoint.instances() {
+                    for instance in &chunk {
+                        session.unsupported.add_instance(instance);
+                    }
+                    if session.writer.is_none() {
+                        session.data.push(serde_json::Value::Array(chunk));
+                    }
+                }
+                session.total_chunks = session.checkpoint.total_chunks();
+                session.chunks_received = session.checkpoint.complete_chunks();
+                tracing::info!(
+                    "Resumed extraction {} from checkpoint ({} chunks complete)",
+                    id,
+                    session.chunks_received
+                );
+                session
+            }
+            None => Self::new(id.to_string(), checkpoint::Checkpoint::create(project_dir, id)),
+        };
+        session.project_dir = project_dir.filter(|dir| !dir.is_empty()).map(str::to_string);
+        session
+    }
 }
 
 /// Connected Studio place information
@@ -362,6 +827,8 @@ pub struct PlaceInfo {
     pub project_dir: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub session_id: Option<String>,  // Unique session ID for this Studio instance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin_version: Option<String>,
     #[serde(skip)]
     pub last_heartbeat: Option<Instant>,
 }
@@ -373,6 +840,8 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/rbxsync/request", get(handle_request_poll))
         .route("/rbxsync/response", post(handle_response))
         .route("/rbxsync/register", post(handle_register))
+        // All rbxsync servers on this machine and their projects
+        .route("/servers", get(handle_servers))
         .route("/rbxsync/unregister", post(handle_unregister))
         .route("/rbxsync/register-vscode", post(handle_register_vscode))
         .route("/rbxsync/update-project-path", post(handle_update_project_path))
@@ -380,43 +849,72 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/rbxsync/unlink-studio", post(handle_unlink_studio))
         .route("/rbxsync/check-status", post(handle_check_status))
         .route("/rbxsync/undo-extract", post(handle_undo_extract))
+        // Compressed src backups made before each extraction
+        .route("/rbxsync/backups", get(handle_list_backups))
         .route("/rbxsync/places", get(handle_list_places))
         .route("/rbxsync/workspaces", get(handle_list_workspaces))
         .route("/rbxsync/server-info", get(handle_server_info))
         .route("/rbxsync/status", get(handle_operation_status))
+        // Crash recovery (safe mode)
+        .route("/recovery/status", get(recovery::handle_recovery_status))
+        .route("/recovery/recover", post(recovery::handle_recover))
         // New extraction endpoints
         .route("/extract/start", post(handle_extract_start))
         .route("/extract/chunk", post(handle_extract_chunk))
         .route("/extract/status", get(handle_extract_status))
+        .route("/extract/resume", post(handle_extract_resume))
         .route("/extract/export", post(handle_extract_export))
         .route("/extract/finalize", post(handle_extract_finalize))
         .route("/extract/terrain", post(handle_extract_terrain))
         // Sync endpoints
         .route("/sync/command", post(handle_sync_command))
         .route("/sync/batch", post(handle_sync_batch))
+        .route("/sync/pause", post(pause::handle_pause))
+        .route("/sync/resume", post(pause::handle_resume))
         .route("/sync/read-tree", post(handle_sync_read_tree))
         .route("/sync/read-terrain", post(handle_sync_read_terrain))
         .route("/sync/from-studio", post(handle_sync_from_studio))
         .route("/sync/pending-changes", post(handle_sync_pending_changes))
         .route("/sync/incremental", post(handle_sync_incremental))
+        .route("/sync/conflicts", post(handle_sync_conflicts))
+        .route("/sync/conflicts/resolve", post(handle_sync_resolve_conflict))
         // Diff endpoints
         .route("/studio/paths", post(handle_studio_paths))
         .route("/diff", post(handle_diff))
+        .route("/diff/places", post(places::handle_diff_places))
+        // Component (tag/attribute) endpoints
+        .route("/components/list", post(components::handle_components_list))
+        .route("/components/diff", post(components::handle_components_diff))
         // Git endpoints
         .route("/git/status", post(handle_git_status))
         .route("/git/log", post(handle_git_log))
         .route("/git/commit", post(handle_git_commit))
         .route("/git/init", post(handle_git_init))
+        .route("/git/branches", post(handle_git_branches))
+        .route("/git/branch/create", post(handle_git_branch_create))
+        .route("/git/checkout", post(handle_git_checkout))
+        .route("/git/pull", post(handle_git_pull))
+        .route("/git/push", post(handle_git_push))
+        .route("/git/stash", post(handle_git_stash))
+        .route("/git/branch-sync", get(branch::handle_status).post(branch::handle_resync))
+        // Named snapshots of the extracted tree
+        .route("/snapshot/create", post(snapshot::handle_create))
+        .route("/snapshot/list", get(snapshot::handle_list))
+        .route("/snapshot/restore", post(snapshot::handle_restore))
         // Test runner endpoints (for AI-powered development workflows)
         .route("/test/start", post(handle_test_start))
         .route("/test/status", get(handle_test_status))
         .route("/test/stop", post(handle_test_stop))
         .route("/test/playtest-status", get(handle_test_playtest_status))
+        .route("/test/run", post(test_run::handle_run))
         // Bot controller endpoints (AI-powered automated gameplay testing)
         .route("/bot/command", post(handle_bot_command))
         .route("/bot/state", get(handle_bot_state).post(handle_bot_state_update))
         .route("/bot/move", post(handle_bot_move))
         .route("/bot/action", post(handle_bot_action))
+        // Chat, proximity prompts and dialog choices
+        .route("/bot/social", post(social::handle_bot_social))
+        .route("/bot/scenario/run", post(scenario::handle_run))
         .route("/bot/observe", post(handle_bot_observe))
         .route("/bot/query-server", post(handle_bot_query_server))
         // Direct bot command queue (for HTTP polling from running game)
@@ -426,10 +924,50 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/bot/result/:id", get(handle_bot_result_get))
         .route("/bot/playtest", get(handle_bot_playtest_status))
         .route("/bot/lifecycle", post(handle_bot_lifecycle))
+        // Fault injection for bot commands (latency, drops, physics pause)
+        .route("/bot/faults", get(faults::handle_get_faults).post(faults::handle_set_faults))
+        // Feature flags (flags.json) and the generated RbxSyncFlags module
+        .route("/flags", get(flags::handle_get_flags).post(flags::handle_set_flags))
+        // Sync statistics and trends
+        .route("/stats/summary", get(stats::handle_summary))
+        // Compact project overview for AI context
+        .route("/summary", get(summary::handle_summary))
+        // Mocked HTTP endpoints for playtests (.rbxsync/mocks.json)
+        .route("/mock/*url", any(mocks::handle_mock))
         // Console output streaming (for E2E testing mode)
         .route("/console/push", post(handle_console_push))
         .route("/console/subscribe", get(handle_console_subscribe))
         .route("/console/history", get(handle_console_history))
+        .route("/console/export", post(handle_console_export))
+        // Nightly build artifacts
+        .route("/builds/list", get(nightly::handle_list))
+        .route("/builds/download/:name", get(nightly::handle_download))
+        .route("/builds/run", post(nightly::handle_run))
+        // Plugin hot reload: builds from `build-plugin --hot`, polled by the bootstrap plugin
+        .route("/plugin/hot", get(hot_reload::handle_rbxm).post(hot_reload::handle_publish))
+        .route("/plugin/hot/modules", get(hot_reload::handle_modules))
+        .route("/plugin/hot/poll", get(hot_reload::handle_poll))
+        .route("/plugin/download", get(compat::handle_download))
+        .route("/schema", get(protocol::handle_schema))
+        .route("/jobs", get(jobs::handle_list))
+        .route("/jobs/:id/cancel", post(jobs::handle_cancel))
+        // Errors from live game servers, and aggregated error views
+        .route("/console/ingest", post(production::handle_ingest))
+        .route("/console/errors", get(production::handle_errors))
+        .route("/console/errors/summary", get(console::handle_error_summary))
+        // File change stream (build --watch, rbxsync dev)
+        .route("/events", get(events::handle_events))
+        // Headless place/model build from project files
+        .route("/build", post(build::handle_build))
+        .route("/verify", post(build::handle_verify))
+        // Remote Studio control: save, publish, playtest
+        .route("/studio/save", post(studio::handle_save))
+        .route("/studio/publish", post(studio::handle_publish))
+        .route("/publish", post(studio::handle_publish))
+        .route("/studio/play", post(studio::handle_play))
+        .route("/studio/stop", post(studio::handle_stop))
+        // MCP over Streamable HTTP, for AI IDEs
+        .route("/mcp", post(mcp::handle_mcp))
         // Run arbitrary Luau code (for MCP)
         .route("/run", post(handle_run_code))
         // Read instance properties (for MCP)
@@ -438,34 +976,131 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/explore-hierarchy", post(handle_explore_hierarchy))
         // Find instances by criteria (for MCP)
         .route("/find-instances", post(handle_find_instances))
+        // Download the content of assets in assets/manifest.json
+        .route("/assets/download", post(assets::handle_download))
+        .route("/assets/upload", post(assets::handle_upload))
+        // Set several properties at once, keeping .rbxjson files in step
+        .route("/instance/set-properties", post(properties::handle_set_properties))
+        // Read and edit script source, keeping .luau files in step (for MCP)
+        .route("/script/get", post(scripts::handle_script_get))
+        .route("/script/set", post(scripts::handle_script_set))
+        .route("/script/edit-lines", post(scripts::handle_script_edit_lines))
         // Insert model from marketplace (for MCP)
         .route("/insert-model", post(handle_insert_model))
         // Health check
         .route("/health", get(handle_health))
+        // Structured server logs, also written to .rbxsync/logs/
+        .route("/logs/query", get(logs::handle_query))
+        // Prometheus metrics
+        .route("/metrics", get(metrics::handle_metrics))
         // Shutdown endpoint
         .route("/shutdown", post(handle_shutdown))
+        // Port collisions with other local dev tools
+        .route("/doctor", get(handle_doctor))
         // Harness system for multi-session AI development
         .route("/harness/init", post(harness::handle_harness_init))
         .route("/harness/session/start", post(harness::handle_session_start))
         .route("/harness/session/end", post(harness::handle_session_end))
         .route("/harness/feature/update", post(harness::handle_feature_update))
         .route("/harness/status", post(harness::handle_harness_status))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), auth::require_token))
         .with_state(state)
         // Allow large body sizes for extraction chunks (10MB limit)
-        .layer(DefaultBodyLimit::max(10 * 1024 * 1024))
+        .layer(DefaultBodyLimit::max(extract_parts::MAX_BODY_BYTES))
 }
 
 /// Health check endpoint
-async fn handle_health() -> impl IntoResponse {
+async fn handle_health(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     Json(serde_json::json!({
         "status": "ok",
-        "version": env!("CARGO_PKG_VERSION")
+        "service": "rbxsync",
+        "version": env!("CARGO_PKG_VERSION"),
+        "idle": idle::is_idle(&state),
+        "auth": state.auth_token.get().is_some(),
+        "port": state.port.load(std::sync::atomic::Ordering::Relaxed),
+        "projects": connected_projects(&state).await,
+        "pluginWarnings": compat::version_warnings(&state).await,
+        "liveSync": state.sync_pause.status(state.live_sync_paused.load(std::sync::atomic::Ordering::Relaxed)),
+        "supervised": std::env::var_os(crashes::SUPERVISED_ENV).is_some(),
+        "lastCrash": crashes::last_crash().map(|(path, report)| serde_json::json!({
+            "timestamp": report.timestamp,
+            "exitStatus": report.exit_status,
+            "panic": report.panic.map(|p| p.message),
+            "restarts": report.restarts,
+            "report": path.to_string_lossy()
+        }))
+    }))
+}
+
+/// Project directories of the connected Studio places and VS Code workspaces
+async fn connected_projects(state: &AppState) -> Vec<String> {
+    let mut projects: Vec<String> = state
+        .place_registry
+        .read()
+        .await
+        .values()
+        .map(|place| place.project_dir.clone())
+        .chain(state.vscode_workspaces.read().await.values().map(|w| w.workspace_dir.clone()))
+        .filter(|dir| !dir.is_empty())
+        .collect();
+    projects.sort();
+    projects.dedup();
+    projects
+}
+
+/// Every rbxsync server running on this machine and the projects it owns
+async fn handle_servers(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let port = state.port.load(std::sync::atomic::Ordering::Relaxed);
+    Json(serde_json::json!({
+        "success": true,
+        "port": port,
+        "servers": ports::discover_servers().await
+    }))
+}
+
+/// Self-check: who owns the well-known local dev ports, and which rbxsync
+/// servers are registered
+async fn handle_doctor(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let port = state.port.load(std::sync::atomic::Ordering::Relaxed);
+    let ports: Vec<ports::PortStatus> = ports::check_ports(port)
+        .await
+        .into_iter()
+        .filter(|p| p.port != port)
+        .collect();
+    let servers = rbxsync_core::registry_path()
+        .map(|path| rbxsync_core::load_registry(&path))
+        .unwrap_or_default();
+    let conflicts: Vec<&ports::PortStatus> = ports
+        .iter()
+        .filter(|p| p.in_use && p.default_for == "rbxsync" && !p.is_rbxsync())
+        .collect();
+    let wally: Vec<serde_json::Value> = correlation_projects(&state)
+        .await
+        .iter()
+        .filter_map(|dir| {
+            let project_dir = dir.to_string_lossy().to_string();
+            let drift = check_wally_drift(&project_dir)?;
+            Some(serde_json::json!({ "projectDir": project_dir, "drift": drift, "problems": drift.lines() }))
+        })
+        .collect();
+
+    Json(serde_json::json!({
+        "success": true,
+        "port": port,
+        "version": env!("CARGO_PKG_VERSION"),
+        "failover": port != rbxsync_core::DEFAULT_PORT,
+        "ports": ports,
+        "conflicts": conflicts,
+        "servers": servers,
+        "wally": wally
     }))
 }
 
 /// Shutdown endpoint - gracefully stops the server
-async fn handle_shutdown() -> impl IntoResponse {
+async fn handle_shutdown(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     tracing::info!("Shutdown requested via API");
+    persist::save_all(&state).await;
+    unregister_self();
     // Spawn a task to exit after response is sent
     tokio::spawn(async {
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
@@ -484,6 +1119,9 @@ pub struct RegisterRequest {
     pub project_dir: String,
     #[serde(default)]
     pub session_id: Option<String>,  // Unique session ID for this Studio instance
+    /// The plugin's PLUGIN_VERSION (plugins before the handshake send none)
+    #[serde(default)]
+    pub version: Option<String>,
 }
 
 /// Handle Studio plugin registration
@@ -493,6 +1131,12 @@ async fn handle_register(
 ) -> impl IntoResponse {
     // Normalize path separators for Windows compatibility
     let project_dir = normalize_path(&req.project_dir);
+    logs::register_project(&project_dir);
+    claim_workspace(&state, &project_dir);
+    persist::restore_project(&state, &project_dir).await;
+    recovery::check_safe_mode(&state, &project_dir).await;
+
+    idle::resume(&state).await;
 
     let mut registry = state.place_registry.write().await;
 
@@ -529,6 +1173,7 @@ async fn handle_register(
         place_name: req.place_name.clone(),
         project_dir: project_dir.clone(),
         session_id: req.session_id.clone(),
+        plugin_version: req.version.clone(),
         last_heartbeat: Some(Instant::now()),
     });
     drop(registry); // Release lock before acquiring another
@@ -552,11 +1197,16 @@ async fn handle_register(
             project_dir
         );
 
+        let plugin_compat = compat::check(req.version.as_deref());
+        if let Some(message) = compat::mismatch_message(plugin_compat, req.version.as_deref()) {
+            tracing::warn!("⚠️  {} ({})", message, req.place_name);
+        }
+
         // Check for path mismatch with VS Code workspaces
         let workspaces = state.vscode_workspaces.read().await;
         if !workspaces.is_empty() {
             let studio_dir = project_dir.as_str();
-            let vscode_dirs: Vec<&str> = workspaces.keys().map(|s| s.as_str()).collect();
+            let vscode_dirs: Vec<&str> = workspaces.values().map(|ws| ws.workspace_dir.as_str()).collect();
 
             // Check if Studio project matches or is parent/child of any VS Code workspace
             let has_match = vscode_dirs.iter().any(|vscode_dir| {
@@ -582,9 +1232,14 @@ async fn handle_register(
         }
     }
 
+    let plugin_compat = compat::check(req.version.as_deref());
     Json(serde_json::json!({
         "success": true,
-        "message": "Registered successfully"
+        "message": "Registered successfully",
+        "serverVersion": compat::SERVER_VERSION,
+        "compatibility": plugin_compat,
+        "warning": compat::mismatch_message(plugin_compat, req.version.as_deref()),
+        "downloadUrl": compat::DOWNLOAD_PATH
     }))
 }
 
@@ -598,6 +1253,7 @@ async fn handle_unregister(
 
     let mut registry = state.place_registry.write().await;
     let removed = registry.remove(&key).is_some();
+    state.session_queues.write().await.remove(&key);
 
     if removed {
         tracing::info!(
@@ -648,9 +1304,11 @@ async fn handle_list_places(
 
     let registry = state.place_registry.read().await;
     let places: Vec<&PlaceInfo> = registry.values().collect();
+    let warnings: Vec<compat::VersionWarning> = registry.values().filter_map(compat::place_warning).collect();
 
     Json(serde_json::json!({
-        "places": places
+        "places": places,
+        "warnings": warnings
     }))
 }
 
@@ -658,6 +1316,10 @@ async fn handle_list_places(
 #[derive(Debug, Deserialize)]
 pub struct RegisterVsCodeRequest {
     pub workspace_dir: String,
+    /// Identifies the editor window, so two editors on the same project are
+    /// told apart
+    #[serde(default)]
+    pub client_id: Option<String>,
 }
 
 /// Handle VS Code workspace registration
@@ -674,25 +1336,84 @@ async fn handle_register_vscode(
 
     // Normalize path separators for Windows compatibility
     let workspace_dir = normalize_path(&req.workspace_dir);
+    logs::register_project(&workspace_dir);
+    claim_workspace(&state, &workspace_dir);
+    persist::restore_project(&state, &workspace_dir).await;
+
+    idle::resume(&state).await;
 
     // Update heartbeat timestamp
-    let mut workspaces = state.vscode_workspaces.write().await;
-    let is_new = !workspaces.contains_key(&workspace_dir);
-    workspaces.insert(workspace_dir.clone(), VsCodeWorkspace {
-        workspace_dir: workspace_dir.clone(),
-        last_heartbeat: Some(Instant::now()),
-    });
-    drop(workspaces); // Release lock before acquiring another
+    let key = workspaces::registration_key(&workspace_dir, req.client_id.as_deref());
+    let mut registered = state.vscode_workspaces.write().await;
+    let now = Instant::now();
+    registered
+        .entry(key.clone())
+        .and_modify(|ws| ws.last_heartbeat = Some(now))
+        .or_insert_with(|| VsCodeWorkspace {
+            workspace_dir: workspace_dir.clone(),
+            canonical_dir: workspaces::canonical_dir(&workspace_dir),
+            client_id: req.client_id.clone(),
+            registered_at: now,
+            last_heartbeat: Some(now),
+        });
+
+    // Another editor registered the same project first: it keeps live sync
+    let role = workspaces::role_of(&registered, &key);
+    let duplicate = (role == workspaces::WorkspaceRole::Secondary)
+        .then(|| {
+            let canonical = &registered[&key].canonical_dir;
+            workspaces::primary_for(&registered, canonical).map(|(_, ws)| ws.workspace_dir.clone())
+        })
+        .flatten()
+        .map(|primary_path| {
+            serde_json::json!({
+                "primary_path": primary_path,
+                "warning": format!(
+                    "This project is already open in another editor ('{}'). Live sync runs through that editor to avoid duplicate changes in Studio.",
+                    primary_path
+                )
+            })
+        });
+    drop(registered); // Release lock before acquiring another
 
-    // Only log and start file watcher if this is a new workspace this session
+    // Start the primary's file watcher (and stop any duplicate)
+    workspaces::reconcile_watchers(&state).await;
+
+    // Only log once per workspace this session
     // Use a separate set to prevent spam from heartbeat registrations
     let mut logged = state.logged_vscode_workspaces.write().await;
-    let should_log = !logged.contains(&workspace_dir);
+    let should_log = !logged.contains(&key);
+    let mut wally_drift = None;
     if should_log {
-        logged.insert(workspace_dir.clone());
+        logged.insert(key.clone());
         drop(logged); // Release lock
 
         tracing::info!("VS Code workspace registered: {}", workspace_dir);
+        if let Some(duplicate) = &duplicate {
+            tracing::warn!(
+                "⚠️  DUPLICATE WORKSPACE: '{}' is the same project as '{}'; live sync stays with the first editor",
+                workspace_dir,
+                duplicate["primary_path"].as_str().unwrap_or_default()
+            );
+        }
+        recovery::check_safe_mode(&state, &workspace_dir).await;
+
+        // Packages that don't match wally.lock break requires in confusing ways
+        wally_drift = check_wally_drift(&workspace_dir).map(|drift| {
+            let lines = drift.lines();
+            tracing::warn!("⚠️  WALLY DRIFT: Packages don't match wally.lock in '{}':", workspace_dir);
+            for line in &lines {
+                tracing::warn!("   {}", line);
+            }
+            tracing::warn!("   To fix: run 'rbxsync wally check --fix' (or 'wally install')");
+            serde_json::json!({
+                "drift": drift,
+                "warning": format!(
+                    "Installed Wally packages don't match wally.lock ({} differences). Run 'rbxsync wally check --fix' to reinstall.",
+                    lines.len()
+                )
+            })
+        });
 
         // Check for path mismatch with Studio registrations
         let registry = state.place_registry.read().await;
@@ -724,6 +1445,9 @@ async fn handle_register_vscode(
                 return Json(serde_json::json!({
                     "success": true,
                     "message": "Workspace registered",
+                    "role": role,
+                    "duplicate": duplicate,
+                    "wally_drift": wally_drift,
                     "path_mismatch": {
                         "vscode_path": vscode_dir,
                         "studio_paths": studio_dirs,
@@ -736,35 +1460,14 @@ async fn handle_register_vscode(
                 }));
             }
         }
-
-        // Start file watcher for new workspaces
-        if is_new {
-            let watcher_state = state.file_watcher_state.clone();
-            let dir = workspace_dir.clone();
-
-            // Load config to check package sync settings
-            let config = load_project_config(&dir);
-            let packages_config = config.as_ref().and_then(|c| c.get("packages"));
-
-            // Check if packages should sync (excludeFromWatch: false means sync packages)
-            // Also enable if packages.enabled is true and excludeFromWatch is not explicitly set
-            let sync_packages = packages_config
-                .and_then(|p| p.get("excludeFromWatch"))
-                .and_then(|v| v.as_bool())
-                .map(|exclude| !exclude)  // Invert: excludeFromWatch=false means sync_packages=true
-                .unwrap_or(false);  // Default: don't sync packages (for backwards compatibility)
-
-            tokio::spawn(async move {
-                if let Err(e) = file_watcher::start_file_watcher(dir, watcher_state, sync_packages).await {
-                    tracing::error!("Failed to start file watcher: {}", e);
-                }
-            });
-        }
     }
 
     Json(serde_json::json!({
         "success": true,
-        "message": "Workspace registered"
+        "message": "Workspace registered",
+        "role": role,
+        "duplicate": duplicate,
+        "wally_drift": wally_drift
     }))
 }
 
@@ -825,6 +1528,7 @@ async fn handle_update_project_path(
             }
         }
     }
+    state.wakeups.notify_project(&req.project_dir);
 
     Json(serde_json::json!({
         "success": true,
@@ -981,16 +1685,54 @@ async fn handle_check_status(
 #[derive(Deserialize)]
 struct UndoExtractRequest {
     project_dir: String,
+    /// Backup archive to restore (default: the newest)
+    #[serde(default)]
+    backup: Option<String>,
+    /// Restore only these paths, relative to src/ (default: the whole tree)
+    #[serde(default)]
+    paths: Vec<String>,
 }
 
 async fn handle_undo_extract(
     Json(req): Json<UndoExtractRequest>,
 ) -> impl IntoResponse {
-    let src_dir = PathBuf::from(&req.project_dir).join("src");
-    let backup_dir = PathBuf::from(&req.project_dir).join(".rbxsync-backup");
-    let backup_src = backup_dir.join("src");
+    let project_dir = PathBuf::from(&req.project_dir);
+    let src_dir = project_dir.join("src");
+
+    if let Some(archive) = backup::find_backup(&project_dir, req.backup.as_deref()) {
+        let name = archive.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let paths = req.paths.clone();
+        let dest = src_dir.clone();
+        let restored = tokio::task::spawn_blocking(move || {
+            if paths.is_empty() {
+                backup::restore_all(&archive, &dest)
+            } else {
+                backup::restore_paths(&archive, &dest, &paths)
+            }
+        })
+        .await
+        .unwrap_or_else(|e| Err(std::io::Error::other(e)));
+
+        return match restored {
+            Ok(files) => {
+                tracing::info!("Restored {} files from {} for {}", files, name, req.project_dir);
+                Json(serde_json::json!({
+                    "success": true,
+                    "backup": name,
+                    "filesRestored": files,
+                    "message": "Extraction undone - src restored from backup"
+                }))
+            }
+            Err(e) => Json(serde_json::json!({
+                "success": false,
+                "error": format!("Failed to restore from {}: {}", name, e)
+            })),
+        };
+    }
 
-    if !backup_src.exists() {
+    // Backups made before archives were introduced are plain directories
+    let backup_src = backup::backup_dir(&project_dir).join("src");
+    if req.backup.is_some() || !req.paths.is_empty() || !backup_src.exists() {
         return Json(serde_json::json!({
             "success": false,
             "error": "No backup found to restore"
@@ -1027,6 +1769,21 @@ async fn handle_undo_extract(
     }))
 }
 
+/// List src backup archives (GET /rbxsync/backups)
+async fn handle_list_backups(Query(params): Query<nightly::BuildsQuery>) -> impl IntoResponse {
+    let project_dir = match params.project_dir {
+        Some(dir) => PathBuf::from(normalize_path(&dir)),
+        None => std::env::current_dir().unwrap_or_default(),
+    };
+    let config = backup::load_config(&project_dir);
+    Json(serde_json::json!({
+        "success": true,
+        "keep": config.keep,
+        "maxSizeMb": config.max_size_mb,
+        "backups": backup::list_backups(&project_dir)
+    }))
+}
+
 /// Clean up stale VS Code workspace registrations (no heartbeat in 30 seconds)
 async fn cleanup_stale_vscode_workspaces(state: &Arc<AppState>) {
     let mut workspaces = state.vscode_workspaces.write().await;
@@ -1047,6 +1804,12 @@ async fn cleanup_stale_vscode_workspaces(state: &Arc<AppState>) {
         workspaces.remove(key);
         tracing::info!("Removed stale VS Code workspace: {}", key);
     }
+    drop(workspaces);
+
+    // A secondary may need to take over live sync
+    if !stale_keys.is_empty() {
+        workspaces::reconcile_watchers(state).await;
+    }
 }
 
 /// List registered VS Code workspace directories
@@ -1056,15 +1819,31 @@ async fn handle_list_workspaces(
     // Clean up stale workspaces first
     cleanup_stale_vscode_workspaces(&state).await;
 
-    let workspaces = state.vscode_workspaces.read().await;
-    let mut workspace_dirs: Vec<String> = workspaces
+    let registered = state.vscode_workspaces.read().await;
+    let mut workspace_dirs: Vec<String> = registered
         .values()
         .map(|ws| ws.workspace_dir.clone())
         .collect();
     workspace_dirs.sort();
+    workspace_dirs.dedup();
+
+    // Per-editor details, including which registration drives live sync
+    let mut registrations: Vec<serde_json::Value> = registered
+        .iter()
+        .map(|(key, ws)| {
+            serde_json::json!({
+                "workspace_dir": ws.workspace_dir,
+                "canonical_dir": ws.canonical_dir,
+                "client_id": ws.client_id,
+                "role": workspaces::role_of(&registered, key)
+            })
+        })
+        .collect();
+    registrations.sort_by_key(|r| r["workspace_dir"].as_str().unwrap_or_default().to_string());
 
     Json(serde_json::json!({
-        "workspaces": workspace_dirs
+        "workspaces": workspace_dirs,
+        "registrations": registrations
     }))
 }
 
@@ -1128,6 +1907,8 @@ async fn handle_operation_status(
 pub struct RequestPollQuery {
     #[serde(rename = "projectDir")]
     pub project_dir: Option<String>,
+    #[serde(rename = "sessionId")]
+    pub session_id: Option<String>,
 }
 
 /// Long-polling endpoint for plugin to receive requests
@@ -1138,25 +1919,41 @@ async fn handle_request_poll(
     // Helper to check queues
     async fn try_pop_request(
         state: &Arc<AppState>,
-        project_dir: &Option<String>,
+        params: &RequestPollQuery,
     ) -> Option<PluginRequest> {
-        // First try project-specific queue if projectDir provided
-        if let Some(ref dir) = project_dir {
-            let mut queues = state.project_queues.write().await;
-            if let Some(queue) = queues.get_mut(dir) {
-                if let Some(request) = queue.pop_front() {
-                    return Some(request);
+        // Take the highest-priority request across the session, project and
+        // global (legacy) queues, so a burst of live sync in the project queue
+        // can't hold up an interactive command in the global one. Ties go to
+        // the queue aimed most closely at this Studio instance.
+        let mut session_queues = state.session_queues.write().await;
+        let mut project_queues = state.project_queues.write().await;
+        let mut global = state.request_queue.lock().await;
+        let session = params.session_id.as_ref().and_then(|id| session_queues.get_mut(id));
+        let project = params.project_dir.as_ref().and_then(|dir| project_queues.get_mut(dir));
+
+        let mut candidates: Vec<&mut VecDeque<PluginRequest>> = Vec::new();
+        candidates.extend(session);
+        candidates.extend(project);
+        candidates.push(&mut *global);
+        let mut best: Option<(priority::Priority, usize)> = None;
+        for (index, queue) in candidates.iter().enumerate() {
+            if let Some(next) = priority::peek(queue) {
+                if best.is_none_or(|(priority, _)| next > priority) {
+                    best = Some((next, index));
                 }
             }
         }
-
-        // Fall back to global queue (legacy support)
-        let mut queue = state.request_queue.lock().await;
-        queue.pop_front()
+        let (_, index) = best?;
+        priority::pop(candidates.swap_remove(index))
     }
 
+    // Subscribe first so a request queued while checking still wakes this poll
+    let mut waiter = state.wakeups.subscribe(params.project_dir.as_deref(), params.session_id.as_deref());
+
     // First check if there's already a request
-    if let Some(request) = try_pop_request(&state, &params.project_dir).await {
+    if let Some(request) = try_pop_request(&state, &params).await {
+        state.metrics.request_dispatched(request.id);
+        state.jobs.dispatched(&request, &params);
         return (StatusCode::OK, Json(serde_json::to_value(&request).unwrap()));
     }
 
@@ -1169,19 +1966,25 @@ async fn handle_request_poll(
             }
         }
     }
+    if let Some(ref session_id) = params.session_id {
+        if let Some(place) = state.place_registry.write().await.get_mut(session_id) {
+            place.last_heartbeat = Some(Instant::now());
+        }
+    }
 
     // Wait for a request or timeout after 15 seconds
     let timeout = tokio::time::Duration::from_secs(15);
-    let mut trigger_rx = state.trigger_rx.clone();
 
     tokio::select! {
         _ = tokio::time::sleep(timeout) => {
             // Timeout - return empty response
             (StatusCode::NO_CONTENT, Json(serde_json::json!(null)))
         }
-        _ = trigger_rx.changed() => {
+        _ = waiter.wait() => {
             // Check if there's a request
-            if let Some(request) = try_pop_request(&state, &params.project_dir).await {
+            if let Some(request) = try_pop_request(&state, &params).await {
+                state.metrics.request_dispatched(request.id);
+                state.jobs.dispatched(&request, &params);
                 (StatusCode::OK, Json(serde_json::to_value(&request).unwrap()))
             } else {
                 (StatusCode::NO_CONTENT, Json(serde_json::json!(null)))
@@ -1196,6 +1999,8 @@ async fn handle_response(
     Json(response): Json<PluginResponse>,
 ) -> impl IntoResponse {
     tracing::info!("Received response for request {}: success={}", response.id, response.success);
+    state.metrics.response_received(response.id);
+    state.jobs.finished(response.id);
     let channels = state.response_channels.read().await;
     if let Some(sender) = channels.get(&response.id) {
         tracing::info!("Found channel for request {}, sending response", response.id);
@@ -1206,19 +2011,6 @@ async fn handle_response(
     Json(serde_json::json!({"ok": true}))
 }
 
-/// Start extraction request
-#[derive(Debug, Deserialize)]
-pub struct ExtractStartRequest {
-    /// Project directory to extract to
-    pub project_dir: Option<String>,
-    /// Services to extract
-    pub services: Option<Vec<String>>,
-    /// Include terrain
-    pub include_terrain: Option<bool>,
-    /// Include binary assets
-    pub include_assets: Option<bool>,
-}
-
 async fn handle_extract_start(
     State(state): State<Arc<AppState>>,
     Json(req): Json<ExtractStartRequest>,
@@ -1227,15 +2019,18 @@ async fn handle_extract_start(
     let session_uuid = Uuid::new_v4();
     let session_id = session_uuid.to_string();
 
-    // Create extraction session
+    // Create extraction session, with a fresh checkpoint
     {
+        let project_dir = req.project_dir.as_deref();
+        if let Some(dir) = project_dir.filter(|d| !d.is_empty()) {
+            checkpoint::prune(dir, &session_id);
+        }
         let mut session = state.extraction_session.write().await;
         *session = Some(ExtractionSession {
-            id: session_id.clone(),
-            chunks_received: 0,
-            total_chunks: None,
-            data: Vec::new(),
-            finalized: false,
+            strict: req.strict.unwrap_or(false),
+            include_assets: req.include_assets.unwrap_or(true),
+            project_dir: project_dir.filter(|d| !d.is_empty()).map(str::to_string),
+            ..ExtractionSession::new(session_id.clone(), checkpoint::Checkpoint::create(project_dir, &session_id))
         });
     }
 
@@ -1295,80 +2090,62 @@ async fn handle_extract_start(
         }
     }
 
-    // Clear existing src folder before extraction to remove stale files (Fixes RBXSYNC-27)
-    if let Some(ref project_dir) = req.project_dir {
-        if !project_dir.is_empty() {
-            let src_dir = PathBuf::from(project_dir).join("src");
-
-            if src_dir.exists() {
-                let backup_dir = PathBuf::from(project_dir).join(".rbxsync-backup");
-                let backup_src = backup_dir.join("src");
-
-                // Remove old backup if exists
-                if backup_src.exists() {
-                    let _ = std::fs::remove_dir_all(&backup_src);
-                }
-
-                // Create backup directory
-                let _ = std::fs::create_dir_all(&backup_dir);
-
-                // Move src to backup (rename is atomic and fast)
-                if let Err(e) = std::fs::rename(&src_dir, &backup_src) {
-                    // If rename fails (cross-device), fall back to copy+delete
-                    tracing::warn!("Rename failed, falling back to copy: {}", e);
-                    if let Err(e) = copy_dir_recursive(&src_dir, &backup_src) {
-                        tracing::warn!("Failed to backup src directory: {}", e);
-                    }
-                    // Delete original src after backup
-                    let _ = std::fs::remove_dir_all(&src_dir);
-                }
-
-                tracing::info!("Cleared src folder before extraction (backed up to .rbxsync-backup/src)");
+    // Incremental extraction compares against the last extraction's cache and
+    // leaves src in place; without a cache it falls back to a full extraction
+    let cache = match (req.incremental.unwrap_or(false), req.project_dir.as_deref()) {
+        (true, Some(dir)) if !dir.is_empty() => {
+            let cache = incremental::ExtractCache::load(std::path::Path::new(dir));
+            if cache.is_none() {
+                tracing::info!("No previous extraction to compare against, running a full extraction");
             }
+            cache
+        }
+        _ => None,
+    };
+    let incremental = cache.is_some();
 
-            // Create fresh src directory
-            let _ = std::fs::create_dir_all(&src_dir);
+    // A full extraction is written to a fresh staging directory, so no stale
+    // files survive (RBXSYNC-27), and finalize swaps it in for src. src stays
+    // as it is until then; archive it now for undo.
+    if let Some(ref project_dir) = req.project_dir {
+        if !project_dir.is_empty() && !incremental {
+            if let Some(ref mut session) = *state.extraction_session.write().await {
+                start_streaming(session, project_dir, true).await;
+            }
         }
     }
 
     // Queue request to plugin
-    let plugin_request = PluginRequest {
-        id: session_uuid,
-        command: "extract:start".to_string(),
-        payload: serde_json::json!({
-            "project_dir": req.project_dir,
-            "services": req.services.unwrap_or_default(),
-            "extractTerrain": req.include_terrain.unwrap_or(false),
-            "includeAssets": req.include_assets.unwrap_or(true),
-        }),
-    };
+    let plugin_request = PluginCommand::ExtractStart(protocol::ExtractStart {
+        project_dir: req.project_dir,
+        services: req.services.unwrap_or_default(),
+        extract_terrain: req.include_terrain.unwrap_or(false),
+        include_assets: req.include_assets.unwrap_or(true),
+        limits: extract_parts::limits(),
+        incremental,
+        known_hashes: cache.map(|c| c.hashes()),
+    })
+    .request(session_uuid);
 
     {
         let mut queue = state.request_queue.lock().await;
         queue.push_back(plugin_request);
     }
-    let _ = state.trigger.send(());
-
-    Json(serde_json::json!({
-        "sessionId": session_id,
-        "status": "started"
-    }))
-}
+    state.wakeups.notify_all();
 
-/// Handle extraction chunk from plugin
-#[derive(Debug, Deserialize)]
-pub struct ExtractChunkRequest {
-    pub session_id: String,
-    pub chunk_index: usize,
-    pub total_chunks: usize,
-    pub data: serde_json::Value,
-    pub project_dir: Option<String>,
+    Json(ExtractStartResponse {
+        session_id,
+        status: "started".to_string(),
+        limits: extract_parts::limits(),
+        incremental,
+    })
 }
 
 async fn handle_extract_chunk(
     State(state): State<Arc<AppState>>,
     Json(req): Json<ExtractChunkRequest>,
 ) -> impl IntoResponse {
+    state.metrics.extraction_chunks.inc();
     let mut session_guard = state.extraction_session.write().await;
 
     // Determine output directory: use project_dir/src if provided, otherwise fallback
@@ -1383,44 +2160,106 @@ async fn handle_extract_chunk(
     };
 
     // Auto-create session if plugin started extraction directly
+    let mut opened = false;
+    let mut archive_src = true;
     if session_guard.is_none() {
         tracing::info!("Auto-created extraction session: {} -> {}", &req.session_id, &output_dir);
 
         // Create output directory for this session
         let _ = std::fs::create_dir_all(&output_dir);
 
-        *session_guard = Some(ExtractionSession {
-            id: req.session_id.clone(),
-            chunks_received: 0,
-            total_chunks: None,
-            data: Vec::new(),
-            finalized: false,
-        });
+        // A restarted server picks up chunks checkpointed before it went down
+        *session_guard = Some(ExtractionSession::open(&req.session_id, req.project_dir.as_deref()));
+        opened = true;
     }
 
     if let Some(ref mut session) = *session_guard {
         // Accept chunks from any session (plugin may have restarted)
         if session.id != req.session_id {
             tracing::info!("Session ID changed from {} to {}, resetting -> {}", session.id, &req.session_id, &output_dir);
-            session.id = req.session_id.clone();
-            session.chunks_received = 0;
-            session.data.clear();
+            // src was archived when the abandoned session started
+            archive_src = session.writer.is_none();
+            *session = ExtractionSession {
+                strict: session.strict,
+                include_assets: session.include_assets,
+                ..ExtractionSession::open(&req.session_id, req.project_dir.as_deref())
+            };
+            opened = true;
 
             // Create new output directory
             let _ = std::fs::create_dir_all(&output_dir);
         }
 
+        // A session the plugin started itself is staged like one from
+        // /extract/start
+        let project_dir = req.project_dir.as_deref().filter(|dir| !dir.is_empty());
+        let fresh = session.writer.is_none() && session.checkpoint.instance_count() == 0;
+        if let (true, true, Some(project_dir)) = (opened, fresh, project_dir) {
+            start_streaming(session, project_dir, archive_src).await;
+        }
+
+        // Parts of an oversized instance don't count as chunks; the plugin sends
+        // them before the rest of their chunk
+        if let Some(part) = &req.part {
+            return match session.parts.add(part, &req.data) {
+                Ok(extract_parts::PartProgress::Pending { received, total }) => (
+                    StatusCode::OK,
+                    Json(serde_json::json!({
+                        "partsReceived": received,
+                        "partsTotal": total,
+                        "complete": false
+                    })),
+                ),
+                Ok(extract_parts::PartProgress::Complete(instance)) => {
+                    let fresh = session.checkpoint.add(req.chunk_index, req.total_chunks, req.instance_count, vec![instance]);
+                    for instance in &fresh {
+                        session.unsupported.add_instance(instance);
+                    }
+                    if let Some(writer) = session.writer.as_mut() {
+                        writer.write(&fresh);
+                    } else if !fresh.is_empty() {
+                        session.data.push(serde_json::Value::Array(fresh));
+                    }
+                    session.total_chunks = Some(req.total_chunks);
+                    session.chunks_received = session.checkpoint.complete_chunks();
+                    tracing::info!("Reassembled oversized instance {} from {} parts", part.instance_id, part.total);
+                    (
+                        StatusCode::OK,
+                        Json(serde_json::json!({
+                            "partsReceived": part.total,
+                            "partsTotal": part.total,
+                            "complete": true
+                        })),
+                    )
+                }
+                Err(e) => {
+                    tracing::warn!("Rejected instance part: {}", e);
+                    (StatusCode::UNPROCESSABLE_ENTITY, Json(serde_json::json!({ "error": e })))
+                }
+            };
+        }
+
+        // Checkpoint to disk immediately; instances already stored (a re-sent
+        // chunk) are dropped
+        let instances = match req.data {
+            serde_json::Value::Array(instances) => instances,
+            _ => Vec::new(),
+        };
+        let fresh = session.checkpoint.add(req.chunk_index, req.total_chunks, req.instance_count, instances);
         session.total_chunks = Some(req.total_chunks);
-        session.chunks_received += 1;
+        session.chunks_received = session.checkpoint.complete_chunks();
 
-        // Save chunk to disk immediately
-        let chunk_path = format!("{}/chunk_{:06}.json", output_dir, session.chunks_received);
-        if let Err(e) = std::fs::write(&chunk_path, serde_json::to_string(&req.data).unwrap_or_default()) {
-            tracing::warn!("Failed to save chunk to disk: {}", e);
+        for instance in &fresh {
+            session.unsupported.add_instance(instance);
         }
 
-        // Also keep in memory for quick access
-        session.data.push(req.data);
+        // Write to src now, or keep in memory until finalize when the
+        // extraction isn't streamed
+        if let Some(writer) = session.writer.as_mut() {
+            writer.write(&fresh);
+        } else if !fresh.is_empty() {
+            session.data.push(serde_json::Value::Array(fresh));
+        }
 
         tracing::info!("Received chunk {}/{}", session.chunks_received, req.total_chunks);
 
@@ -1439,19 +2278,92 @@ async fn handle_extract_chunk(
     }
 }
 
+/// Have a new session write its chunks to the staging directory, archiving
+/// src first (for undo) if `archive`
+async fn start_streaming(session: &mut ExtractionSession, project_dir: &str, archive: bool) {
+    if archive {
+        let project = PathBuf::from(project_dir);
+        let archived = tokio::task::spawn_blocking(move || backup::create_backup(&project))
+            .await
+            .unwrap_or_else(|e| Err(std::io::Error::other(e)));
+        match archived {
+            Ok(Some(info)) => tracing::info!("Backed up src to {}/{}", backup::BACKUP_DIR, info.name),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to back up src directory: {}", e),
+        }
+    }
+    session.writer = Some(extract_stream::SrcWriter::create(
+        std::path::Path::new(project_dir),
+        session.checkpoint.dir(),
+    ));
+}
+
+/// Which chunks of an extraction the server still needs, so the plugin can
+/// re-send only those after a dropped connection
+async fn handle_extract_resume(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ExtractResumeRequest>,
+) -> impl IntoResponse {
+    let mut session_guard = state.extraction_session.write().await;
+
+    let active = session_guard
+        .as_ref()
+        .is_some_and(|s| s.id == req.session_id && !s.finalized);
+    if !active {
+        // After a server restart the session only exists on disk
+        let checkpoint_dir = checkpoint::Checkpoint::dir_for(req.project_dir.as_deref(), &req.session_id);
+        if !checkpoint_dir.join(checkpoint::MANIFEST_FILE).exists() {
+            return Json(ExtractResumeResponse {
+                session_id: req.session_id,
+                resumable: false,
+                total_chunks: req.total_chunks,
+                chunks_received: 0,
+                missing: (0..req.total_chunks.unwrap_or(0)).collect(),
+            });
+        }
+        *session_guard = Some(ExtractionSession::open(&req.session_id, req.project_dir.as_deref()));
+        state.live_sync_paused.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    let session = session_guard.as_mut().unwrap();
+    if let Some(total) = req.total_chunks {
+        session.checkpoint.set_total_chunks(total);
+        session.total_chunks = Some(total);
+    }
+    let missing = session.checkpoint.missing();
+    tracing::info!(
+        "Extraction {} resuming: {} chunks complete, {} missing",
+        session.id,
+        session.chunks_received,
+        missing.len()
+    );
+
+    Json(ExtractResumeResponse {
+        session_id: session.id.clone(),
+        resumable: true,
+        total_chunks: session.total_chunks,
+        chunks_received: session.chunks_received,
+        missing,
+    })
+}
+
 /// Get extraction status
 async fn handle_extract_status(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     let session = state.extraction_session.read().await;
 
     if let Some(ref s) = *session {
         // Complete if finalized (handles 0 chunks case) OR all chunks received
-        let complete = s.finalized || s.total_chunks.map(|t| s.chunks_received >= t).unwrap_or(false);
+        let complete = s.finalized
+            || (s.parts.pending() == 0 && s.total_chunks.map(|t| s.chunks_received >= t).unwrap_or(false));
         Json(serde_json::json!({
             "sessionId": s.id,
             "chunksReceived": s.chunks_received,
             "totalChunks": s.total_chunks,
+            "pendingParts": s.parts.pending(),
             "complete": complete,
-            "finalized": s.finalized
+            "finalized": s.finalized,
+            "strict": s.strict,
+            "unsupportedProperties": s.unsupported.entries()
         }))
     } else {
         Json(serde_json::json!({
@@ -1474,11 +2386,23 @@ async fn handle_extract_export(
     let session = state.extraction_session.read().await;
 
     if let Some(ref s) = *session {
-        // Flatten all chunks into a single array of instances
+        // Flatten all chunks into a single array of instances. A streamed
+        // extraction is read back from its checkpoint, or from the extraction
+        // cache once finalized.
         let mut all_instances = Vec::new();
-        for chunk in &s.data {
-            if let Some(instances) = chunk.as_array() {
-                all_instances.extend(instances.iter().cloned());
+        match &s.writer {
+            Some(writer) if s.finalized => {
+                all_instances = incremental::ExtractCache::load(writer.project_dir())
+                    .map(|cache| cache.instances)
+                    .unwrap_or_default();
+            }
+            Some(_) => all_instances.extend(s.checkpoint.instances().flatten()),
+            None => {
+                for chunk in &s.data {
+                    if let Some(instances) = chunk.as_array() {
+                        all_instances.extend(instances.iter().cloned());
+                    }
+                }
             }
         }
 
@@ -1661,7 +2585,7 @@ fn generate_project_json(project_name: &str, src_dir: &std::path::Path, service_
                     for entry in entries.flatten() {
                         if entry.path().is_dir() {
                             let child_name = entry.file_name().to_string_lossy().to_string();
-                            let child_class = service_map.get(child_name.as_str());
+                            let child_class = rbxsync_core::container_class_name("StarterPlayer", &child_name);
                             if let Some(class) = child_class {
                                 sp_node[&child_name] = serde_json::json!({
                                     "$className": class,
@@ -1703,12 +2627,6 @@ fn generate_project_json(project_name: &str, src_dir: &std::path::Path, service_
     serde_json::to_string_pretty(&project)
 }
 
-/// Finalize extraction - build proper file tree from chunks
-#[derive(Debug, Deserialize)]
-pub struct FinalizeRequest {
-    pub project_dir: String,
-}
-
 async fn handle_extract_finalize(
     State(state): State<Arc<AppState>>,
     Json(req): Json<FinalizeRequest>,
@@ -1725,12 +2643,73 @@ async fn handle_extract_finalize(
         );
     }
 
+    if let Some(err) = recovery::safe_mode_error(&state, &req.project_dir).await {
+        return err;
+    }
+
+    // An incremental extraction only sent what changed, so it needs the cache
+    // of the last extraction to merge into
+    let cache = if req.incremental {
+        match incremental::ExtractCache::load(std::path::Path::new(&req.project_dir)) {
+            Some(cache) => Some(cache),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({
+                        "success": false,
+                        "error": "No previous extraction to apply an incremental extraction to"
+                    })),
+                );
+            }
+        }
+    } else {
+        None
+    };
+    let incremental = cache.is_some();
+
     let session = session_guard.as_ref().unwrap();
     let src_dir = PathBuf::from(&req.project_dir).join("src");
+    let started = session.started;
+    // A full extraction written to the staging directory as its chunks arrived
+    let streamed = session.writer.as_ref().filter(|_| !incremental);
+
+    // Strict mode: leave the project as it was rather than write lossy files
+    if session.strict && !session.unsupported.is_empty() {
+        let summary = session.unsupported.summary(20);
+        let unsupported = session.unsupported.entries();
+        tracing::warn!("Strict extraction failed, unsupported properties:\n{}", summary);
+
+        // src hasn't been touched; drop what was staged
+        let staged = extract_stream::staging_dir(std::path::Path::new(&req.project_dir));
+        let _ = tokio::task::spawn_blocking(move || std::fs::remove_dir_all(staged)).await;
+
+        let mut run = stats::SyncRun::finished(stats::EXTRACT, started)
+            .with_errors(&[format!("Strict mode: {} unsupported properties", unsupported.len())]);
+        run.instances = session.checkpoint.instance_count();
+        stats::record(&req.project_dir, run);
+
+        drop(session_guard);
+        if let Some(ref mut s) = *state.extraction_session.write().await {
+            s.finalized = true;
+        }
+        state.operation_state.write().await.remove(&req.project_dir);
+        state.live_sync_paused.store(false, std::sync::atomic::Ordering::Relaxed);
+
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({
+                "success": false,
+                "error": format!("Strict mode: {} properties can't be serialized:\n{}", unsupported.len(), summary),
+                "unsupportedProperties": unsupported
+            })),
+        );
+    }
 
     // Load project config and tree mapping
     let config = load_project_config(&req.project_dir);
     let tree_mapping = get_tree_mapping(&config);
+    let schema_ref = rbxsync_core::schema_ref(config.as_ref());
+    let omit_defaults = rbxsync_core::omit_defaults(config.as_ref());
     tracing::info!("Tree mapping loaded: {:?}", tree_mapping);
 
     // Check package preservation settings from config JSON
@@ -1756,49 +2735,50 @@ async fn handle_extract_finalize(
     if preserve_packages {
         tracing::info!("Package preservation enabled - Packages folder: {}", packages_folder);
     }
+    // Where Packages folders are looked for, relative to src
+    let package_locations: Vec<String> = vec![
+        "ReplicatedStorage/Packages".to_string(),
+        "ServerScriptService/Packages".to_string(),
+        "ServerStorage/Packages".to_string(),
+        // Also check root-level Packages folder
+        packages_folder.clone(),
+    ];
 
-    // Backup existing src directory before clearing (for undo support)
-    let backup_dir = PathBuf::from(&req.project_dir).join(".rbxsync-backup");
-    let backup_src = backup_dir.join("src");
-
-    // IMPORTANT: Back up terrain data BEFORE any directory operations
-    // Terrain is saved during extraction and must survive the src clear
-    let terrain_file = src_dir.join("Workspace").join("Terrain").join("terrain.rbxjson");
-    let terrain_data = if terrain_file.exists() {
-        tracing::info!("Backing up terrain.rbxjson before finalize");
-        std::fs::read_to_string(&terrain_file).ok()
-    } else {
-        None
-    };
+    // Terrain lives in terrain/, outside src; move a legacy terrain.rbxjson
+    // there before src is replaced
+    if let Err(e) = rbxsync_core::terrain::migrate_legacy(std::path::Path::new(&req.project_dir)) {
+        tracing::warn!("Failed to migrate terrain.rbxjson: {}", e);
+    }
 
-    if src_dir.exists() {
-        // Remove old backup if exists
-        if backup_src.exists() {
-            let _ = std::fs::remove_dir_all(&backup_src);
-        }
-        // Create backup directory
-        let _ = std::fs::create_dir_all(&backup_dir);
-        // Move src to backup (rename is atomic and fast)
-        if let Err(e) = std::fs::rename(&src_dir, &backup_src) {
-            // If rename fails (cross-device), fall back to copy+delete
-            tracing::warn!("Rename failed, falling back to copy: {}", e);
-            if let Err(e) = copy_dir_recursive(&src_dir, &backup_src) {
-                tracing::warn!("Failed to backup src directory: {}", e);
-            }
+    // Extraction writes .luau, so any .lua scripts in the current tree are replaced
+    let legacy_lua_replaced = rbxsync_core::find_legacy_lua_files(&src_dir).len();
 
-            for entry in std::fs::read_dir(&src_dir).unwrap_or_else(|_| std::fs::read_dir(".").unwrap()).flatten() {
-                let path = entry.path();
-                if path.is_dir() {
-                    let _ = std::fs::remove_dir_all(&path);
-                } else {
-                    let _ = std::fs::remove_file(&path);
-                }
+    // A full extraction is written to a staging directory that replaces src
+    // once complete, so a crash never leaves src half-written. A streamed
+    // extraction archived src (for undo) before its first chunk; otherwise
+    // archive it now. An incremental extraction rewrites files in place.
+    let project_path = PathBuf::from(&req.project_dir);
+    let out_dir = match streamed {
+        Some(writer) => writer.out_dir().to_path_buf(),
+        None if incremental => src_dir.clone(),
+        None => {
+            let staged = extract_stream::staging_dir(&project_path);
+            let project = project_path.clone();
+            let archived = tokio::task::spawn_blocking(move || backup::create_backup(&project))
+                .await
+                .unwrap_or_else(|e| Err(std::io::Error::other(e)));
+            match archived {
+                Ok(Some(info)) => tracing::info!("Backed up src to {}/{}", backup::BACKUP_DIR, info.name),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to back up src directory: {}", e),
             }
+            let _ = std::fs::remove_dir_all(&staged);
+            staged
         }
-        tracing::info!("Backed up src to .rbxsync-backup/src");
-    }
+    };
 
-    // Flatten all chunks into a single array of instances
+    // Flatten all chunks into a single array of instances (none when they
+    // were streamed)
     let mut all_instances: Vec<serde_json::Value> = Vec::new();
     for chunk in &session.data {
         if let Some(instances) = chunk.as_array() {
@@ -1806,20 +2786,41 @@ async fn handle_extract_finalize(
         }
     }
 
-    tracing::info!("Finalizing {} instances to {}", all_instances.len(), src_dir.display());
-
-    // Create src directory
-    let _ = std::fs::create_dir_all(&src_dir);
+    // Incremental: the chunks hold only changed instances, everything else
+    // comes from the last extraction
+    let changed_count = all_instances.len();
+    let changed_paths: HashSet<String> = all_instances
+        .iter()
+        .filter_map(|inst| inst.get("path").and_then(|v| v.as_str()))
+        .map(str::to_string)
+        .collect();
+    if let Some(cache) = cache {
+        all_instances = cache.merge(all_instances, &req.removed);
+        tracing::info!(
+            "Incremental extraction: {} changed, {} removed, {} total instances",
+            changed_count,
+            req.removed.len(),
+            all_instances.len()
+        );
+    }
 
-    // Restore terrain data that was backed up before clearing
-    if let Some(data) = terrain_data {
-        let terrain_dir = src_dir.join("Workspace").join("Terrain");
-        let _ = std::fs::create_dir_all(&terrain_dir);
-        if std::fs::write(terrain_dir.join("terrain.rbxjson"), &data).is_ok() {
-            tracing::info!("Restored terrain.rbxjson after finalize");
-        }
+    let total_instances = match streamed {
+        Some(_) => session.checkpoint.instance_count(),
+        None => all_instances.len(),
+    };
+    tracing::info!("Finalizing {} instances to {}", total_instances, out_dir.display());
+    let unsupported = session.unsupported.entries();
+    if !unsupported.is_empty() {
+        tracing::warn!(
+            "{} properties couldn't be serialized and were written as placeholders:\n{}",
+            unsupported.len(),
+            session.unsupported.summary(20)
+        );
     }
 
+    // Create output directory
+    let _ = std::fs::create_dir_all(&out_dir);
+
     // Track which services we've seen to create folders for them
     let mut service_folders: std::collections::HashSet<String> = std::collections::HashSet::new();
 
@@ -1862,36 +2863,15 @@ async fn handle_extract_finalize(
         tracing::info!("Found {} duplicate instance paths - these have been disambiguated", duplicate_count);
     }
 
-    // Collect all disambiguated paths for container detection
-    let all_paths: std::collections::HashSet<String> = ref_to_path.values().cloned().collect();
+    // Every proper ancestor of a disambiguated path, for container detection.
+    // A set lookup per instance keeps this linear on large games.
+    let parent_paths: std::collections::HashSet<&str> = ref_to_path
+        .values()
+        .flat_map(|p| p.match_indices('/').map(move |(i, _)| &p[..i]))
+        .collect();
 
     // Helper to check if a path has children (is a container)
-    let has_children = |path: &str| -> bool {
-        let prefix = format!("{}/", path);
-        all_paths.iter().any(|p| p.starts_with(&prefix))
-    };
-
-    // Helper to normalize package paths (fix duplicated Packages folders)
-    let normalize_path = |path: &str| -> String {
-        // Fix case variations and duplications like "Packages/Packages" or "packages/Packages"
-        let mut normalized = path.to_string();
-
-        // Replace various case-insensitive duplications
-        let patterns = [
-            ("Packages/Packages/", "Packages/"),
-            ("packages/packages/", "packages/"),
-            ("Packages/packages/", "Packages/"),
-            ("packages/Packages/", "Packages/"),
-        ];
-
-        for (from, to) in patterns {
-            while normalized.contains(from) {
-                normalized = normalized.replace(from, to);
-            }
-        }
-
-        normalized
-    };
+    let has_children = |path: &str| -> bool { parent_paths.contains(path) };
 
     // PERFORMANCE OPTIMIZATION for large games (RBXSYNC-26):
     // Instead of writing files sequentially (which causes PC hang on 180k+ instances),
@@ -1906,6 +2886,8 @@ async fn handle_extract_finalize(
     struct WriteOp {
         path: PathBuf,
         content: String,
+        /// The instance was sent in this extraction (always, unless incremental)
+        changed: bool,
     }
 
     // First pass: Collect all directories needed and prepare write operations
@@ -1930,14 +2912,17 @@ async fn handle_extract_finalize(
             continue;
         }
 
+        let changed = !incremental
+            || inst.get("path").and_then(|v| v.as_str()).is_some_and(|p| changed_paths.contains(p));
+
         // Normalize path to fix package folder duplication
-        let inst_path = normalize_path(inst_path);
+        let inst_path = normalize_package_path(inst_path);
 
         // Apply tree mapping to convert DataModel path to filesystem path
         let fs_path = apply_tree_mapping(&inst_path, &tree_mapping);
 
         // Use mapped path for filesystem operations
-        let full_path = src_dir.join(&fs_path);
+        let full_path = out_dir.join(&fs_path);
 
         // Track service name (first segment of mapped path) for folder creation
         if let Some(service_name) = fs_path.split('/').next() {
@@ -1950,24 +2935,20 @@ async fn handle_extract_finalize(
         }
 
         // Check if this instance has children (use normalized path)
-        let is_container = has_children(&inst_path);
+        // Services and StarterPlayer script containers stay directories even when
+        // empty so build and sourcemap keep their class
+        let is_container = has_children(&inst_path) || rbxsync_core::is_special_container(&inst_path);
 
         // Check if this is a script with source
-        let is_script = matches!(class_name, "Script" | "LocalScript" | "ModuleScript");
-
-        if is_script {
+        if let Some(extension) = extract_stream::script_extension(class_name) {
             // Prepare script source write operation
             if let Some(props) = inst.get("properties") {
                 if let Some(source) = props.get("Source").and_then(|v| v.get("value")).and_then(|v| v.as_str()) {
-                    let extension = match class_name {
-                        "Script" => ".server.luau",
-                        "LocalScript" => ".client.luau",
-                        _ => ".luau",
-                    };
                     let script_path = rbxsync_core::path_with_suffix(&full_path, extension);
                     script_write_ops.push(WriteOp {
                         path: PathBuf::from(script_path),
                         content: source.to_string(),
+                        changed,
                     });
                 }
             }
@@ -1984,19 +2965,13 @@ async fn handle_extract_finalize(
         };
 
         // Create a clean instance object without source (for scripts)
-        let mut clean_inst = inst.clone();
-        if is_script {
-            if let Some(props) = clean_inst.get_mut("properties") {
-                if let Some(obj) = props.as_object_mut() {
-                    obj.remove("Source");
-                }
-            }
-        }
+        let clean_inst = clean_instance(inst, omit_defaults, schema_ref.as_deref());
 
         if let Ok(json) = serde_json::to_string_pretty(&clean_inst) {
             json_write_ops.push(WriteOp {
                 path: json_path,
                 content: json,
+                changed,
             });
         }
     }
@@ -2009,6 +2984,38 @@ async fn handle_extract_finalize(
         json_write_ops.len()
     );
 
+    // Incremental: keep files of unchanged instances, write only what changed
+    // or moved, and remove files of instances that are gone. Preserved
+    // packages are local, so extraction leaves them alone.
+    let mut stale_removed = 0;
+    if incremental {
+        let keep: HashSet<PathBuf> = script_write_ops
+            .iter()
+            .chain(json_write_ops.iter())
+            .map(|op| op.path.clone())
+            .collect();
+        let skip: Vec<PathBuf> = if preserve_packages {
+            package_locations.iter().map(|rel| out_dir.join(rel)).collect()
+        } else {
+            Vec::new()
+        };
+        let needed = |op: &WriteOp| !skip.iter().any(|s| op.path.starts_with(s)) && (op.changed || !op.path.exists());
+        script_write_ops.retain(needed);
+        json_write_ops.retain(needed);
+        directories_needed.retain(|dir| !dir.exists());
+
+        let prune_dir = out_dir.clone();
+        stale_removed = tokio::task::spawn_blocking(move || incremental::prune_stale(&prune_dir, &keep, &skip))
+            .await
+            .unwrap_or(0);
+        tracing::info!(
+            "Incremental extraction: writing {} scripts and {} json files, removed {} stale files",
+            script_write_ops.len(),
+            json_write_ops.len(),
+            stale_removed
+        );
+    }
+
     // Batch create all directories (run in blocking task to not block async runtime)
     let dirs_to_create: Vec<PathBuf> = directories_needed.into_iter().collect();
     let dir_count = dirs_to_create.len();
@@ -2023,11 +3030,14 @@ async fn handle_extract_finalize(
     });
     tracing::info!("Created {} directories in {:?}", dir_count, dir_start.elapsed());
 
+    // An incremental extraction formats only the scripts it rewrites
+    let written_scripts: Vec<PathBuf> = script_write_ops.iter().map(|op| op.path.clone()).collect();
+
     // Write files in parallel with bounded concurrency
     let write_start = std::time::Instant::now();
 
     // Write scripts in parallel
-    let script_count = script_write_ops.len();
+    let mut script_count = script_write_ops.len();
     let script_results: Vec<bool> = stream::iter(script_write_ops)
         .map(|op| async move {
             tokio::fs::write(&op.path, &op.content).await.is_ok()
@@ -2035,10 +3045,10 @@ async fn handle_extract_finalize(
         .buffer_unordered(MAX_CONCURRENT_WRITES)
         .collect()
         .await;
-    let scripts_written = script_results.iter().filter(|&&ok| ok).count();
+    let mut scripts_written = script_results.iter().filter(|&&ok| ok).count();
 
     // Write JSON files in parallel
-    let json_count = json_write_ops.len();
+    let mut json_count = json_write_ops.len();
     let json_results: Vec<bool> = stream::iter(json_write_ops)
         .map(|op| async move {
             tokio::fs::write(&op.path, &op.content).await.is_ok()
@@ -2046,13 +3056,24 @@ async fn handle_extract_finalize(
         .buffer_unordered(MAX_CONCURRENT_WRITES)
         .collect()
         .await;
-    let files_written = json_results.iter().filter(|&&ok| ok).count();
+    let mut files_written = json_results.iter().filter(|&&ok| ok).count();
 
     tracing::info!(
         "Wrote {} scripts and {} json files in {:?} ({} concurrent writes)",
         scripts_written, files_written, write_start.elapsed(), MAX_CONCURRENT_WRITES
     );
 
+    // A streamed extraction's files were written as its chunks arrived
+    if let Some(writer) = streamed {
+        let totals = writer.totals();
+        scripts_written += totals.scripts;
+        files_written += totals.files;
+        // Failures aren't split by kind, so count them all as json files
+        script_count += totals.scripts;
+        json_count += totals.files + totals.failed;
+        service_folders.extend(writer.services().iter().cloned());
+    }
+
     // Log if there were any failures
     let script_failures = script_count - scripts_written;
     let json_failures = json_count - files_written;
@@ -2064,7 +3085,7 @@ async fn handle_extract_finalize(
     }
 
     // Clean up chunk files
-    if let Ok(entries) = std::fs::read_dir(&src_dir) {
+    if let Ok(entries) = std::fs::read_dir(&out_dir) {
         for entry in entries.flatten() {
             let path = entry.path();
             if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
@@ -2077,49 +3098,98 @@ async fn handle_extract_finalize(
 
     // Create service folders even if they're empty
     for service in &service_folders {
-        let service_folder = src_dir.join(service);
+        let service_folder = out_dir.join(service);
         // Create the folder if it doesn't exist
         let _ = std::fs::create_dir_all(&service_folder);
     }
 
-    // Restore Packages folder from backup if preservation is enabled
-    let mut packages_preserved = false;
-    if preserve_packages {
-        // Look for Packages folders in common locations within backup
-        let package_restore_locations: Vec<(String, String)> = vec![
-            ("ReplicatedStorage/Packages".to_string(), "ReplicatedStorage/Packages".to_string()),
-            ("ServerScriptService/Packages".to_string(), "ServerScriptService/Packages".to_string()),
-            ("ServerStorage/Packages".to_string(), "ServerStorage/Packages".to_string()),
-            // Also check root-level Packages folder
-            (packages_folder.clone(), packages_folder.clone()),
-        ];
-
-        for (backup_rel, dest_rel) in &package_restore_locations {
-            let backup_packages = backup_src.join(backup_rel);
-            let dest_packages = src_dir.join(dest_rel);
-
-            if backup_packages.exists() && backup_packages.is_dir() {
-                // Remove any extracted packages (from Studio) to replace with local
-                if dest_packages.exists() {
-                    let _ = std::fs::remove_dir_all(&dest_packages);
-                }
+    // Format the extracted scripts before they replace src. Packages are
+    // left alone: local ones are kept below and aren't ours to reformat.
+    let format_report = match format::format_command(&config) {
+        Some(command) => {
+            let files = if incremental {
+                written_scripts
+            } else {
+                let skip: Vec<PathBuf> = package_locations.iter().map(|rel| out_dir.join(rel)).collect();
+                format::find_scripts(&out_dir, &skip)
+            };
+            let program = command[0].clone();
+            let project = project_path.clone();
+            let report = tokio::task::spawn_blocking(move || format::format_scripts(&project, &command, &files))
+                .await
+                .unwrap_or_default();
+            tracing::info!("Formatted {} extracted scripts with {}", report.formatted, program);
+            for error in &report.errors {
+                tracing::warn!("Formatting extracted scripts: {}", error);
+            }
+            Some(report)
+        }
+        None => None,
+    };
 
-                // Ensure parent directory exists
-                if let Some(parent) = dest_packages.parent() {
-                    let _ = std::fs::create_dir_all(parent);
-                }
+    // Keep the local Packages folders rather than the copies from Studio
+    // (an incremental extraction never removed them)
+    let mut packages_preserved = false;
+    if preserve_packages && !incremental {
+        for rel in &package_locations {
+            let local_packages = src_dir.join(rel);
+            let dest_packages = out_dir.join(rel);
+            if !local_packages.is_dir() {
+                continue;
+            }
 
-                // Restore packages from backup
-                if let Err(e) = copy_dir_recursive(&backup_packages, &dest_packages) {
-                    tracing::warn!("Failed to restore packages from {}: {}", backup_rel, e);
-                } else {
-                    tracing::info!("Restored Wally packages from backup: {}", backup_rel);
-                    packages_preserved = true;
-                }
+            // Remove any extracted packages (from Studio) to replace with local
+            if dest_packages.exists() {
+                let _ = std::fs::remove_dir_all(&dest_packages);
+            }
+            let copied = tokio::task::spawn_blocking(move || copy_dir_recursive(&local_packages, &dest_packages))
+                .await
+                .unwrap_or_else(|e| Err(std::io::Error::other(e)));
+            if let Err(e) = copied {
+                tracing::warn!("Failed to keep packages in {}: {}", rel, e);
+            } else {
+                tracing::info!("Kept local Wally packages: {}", rel);
+                packages_preserved = true;
             }
         }
     }
 
+    // Swap the new tree in for src, with the file watcher stopped so it
+    // isn't left watching the old directory
+    if !incremental {
+        let watch_key = normalize_path(&req.project_dir);
+        let watcher = {
+            let mut watchers = state.file_watcher_state.write().await;
+            let event_only = watchers.event_only_dirs.contains(&watch_key);
+            watchers.stop(&watch_key).then_some(event_only)
+        };
+        let (project, staged) = (project_path.clone(), out_dir.clone());
+        let swapped = tokio::task::spawn_blocking(move || recovery::swap_src(&project, &staged))
+            .await
+            .unwrap_or_else(|e| Err(std::io::Error::other(e)));
+        if let Some(event_only) = watcher {
+            events::watch_project(&state, &watch_key, !event_only).await;
+        }
+
+        if let Err(e) = swapped {
+            tracing::error!("Failed to replace src with the extracted tree: {}", e);
+            drop(session_guard);
+            state.operation_state.write().await.remove(&req.project_dir);
+            state.live_sync_paused.store(false, std::sync::atomic::Ordering::Relaxed);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "success": false,
+                    "error": format!(
+                        "Failed to replace src: {}. src is unchanged; the extracted files are in {}",
+                        e,
+                        out_dir.display()
+                    )
+                })),
+            );
+        }
+    }
+
     tracing::info!(
         "Finalize complete: {} .rbxjson files, {} .luau scripts, {} services{}",
         files_written,
@@ -2128,9 +3198,81 @@ async fn handle_extract_finalize(
         if packages_preserved { ", packages preserved" } else { "" }
     );
 
+    if legacy_lua_replaced > 0 {
+        tracing::info!("{} legacy .lua files were replaced by .luau during extraction", legacy_lua_replaced);
+    }
+
+    state.metrics.extraction_duration.observe_duration(started.elapsed());
+    let mut run = stats::SyncRun::finished(stats::EXTRACT, started);
+    run.instances = total_instances;
+    run.files = files_written + scripts_written;
+    run.errors = script_failures + json_failures;
+    stats::record(&req.project_dir, run);
+
     // Generate tooling config files (RBXSYNC-83)
     generate_tooling_files(&req.project_dir, &service_folders, &config);
 
+    // Commit the extracted tree if the project asks for checkpoints
+    autocommit::spawn(
+        &req.project_dir,
+        &config,
+        autocommit::Checkpoint {
+            source: autocommit::Source::Extraction,
+            place_name: connected_place(&state, &req.project_dir).await.map(|place| place.place_name),
+            instances: total_instances,
+            files: files_written + scripts_written,
+            session_id: Some(session.id.clone()),
+        },
+    );
+
+    // src now mirrors Studio, so earlier sync bases and conflicts no longer apply
+    state
+        .sync_state
+        .write()
+        .await
+        .entry(req.project_dir.clone())
+        .or_default()
+        .clear_bases(&project_path);
+    conflicts::save_conflicts(&project_path, &[]);
+
+    // Record the assets the place uses, and fetch them in the background if
+    // configured. A streamed extraction is read back from its checkpoint,
+    // which also gives the extraction cache.
+    let assets_recorded = match streamed {
+        Some(_) => {
+            let extraction_assets = session
+                .include_assets
+                .then(|| assets::ExtractionAssets::begin(&project_path, config.as_ref()))
+                .flatten();
+            let chunk_files = session.checkpoint.chunk_files();
+            let project = project_path.clone();
+            tokio::task::spawn_blocking(move || extract_stream::finish(&project, &chunk_files, extraction_assets))
+                .await
+                .unwrap_or_default()
+        }
+        None if session.include_assets => assets::record_extraction(&project_path, &all_instances, config.as_ref()),
+        None => None,
+    };
+    if assets_recorded.is_some() && assets::download_enabled(config.as_ref()) {
+        let project = project_path.clone();
+        tokio::spawn(async move {
+            let report = assets::download_assets(&project).await;
+            tracing::info!(
+                "Asset download: {} downloaded, {} already present or built-in, {} failed",
+                report.downloaded,
+                report.skipped,
+                report.failed.len()
+            );
+        });
+    }
+
+    // Remember what was written so the next extraction can be incremental
+    if streamed.is_none() {
+        let cache = incremental::ExtractCache { instances: all_instances };
+        let cache_project = project_path.clone();
+        let _ = tokio::task::spawn_blocking(move || cache.save(&cache_project)).await;
+    }
+
     // Clear any file change events that accumulated during extraction (from the files we just wrote)
     // This prevents them from being synced back to Studio after extraction
     // We do this in a spawned task to avoid blocking the response
@@ -2164,6 +3306,7 @@ async fn handle_extract_finalize(
         let mut session_write = state.extraction_session.write().await;
         if let Some(ref mut s) = *session_write {
             s.finalized = true;
+            s.checkpoint.remove();
             tracing::info!("Extraction session marked as finalized");
         }
     }
@@ -2180,7 +3323,14 @@ async fn handle_extract_finalize(
             "success": true,
             "filesWritten": files_written,
             "scriptsWritten": scripts_written,
-            "totalInstances": all_instances.len()
+            "totalInstances": total_instances,
+            "legacyLuaReplaced": legacy_lua_replaced,
+            "unsupportedProperties": unsupported,
+            "incremental": incremental,
+            "changedInstances": changed_count,
+            "removedFiles": stale_removed,
+            "assetsRecorded": assets_recorded,
+            "formatted": format_report
         })),
     )
 }
@@ -2195,110 +3345,56 @@ pub struct TerrainRequest {
     pub total_batches: Option<i32>,
 }
 
-/// Handle terrain data from extraction (supports batched uploads)
+/// Handle terrain data from extraction (supports batched uploads). Each batch's
+/// chunks are added to their region files under `terrain/`; the first batch
+/// replaces whatever terrain the project had.
 async fn handle_extract_terrain(Json(req): Json<TerrainRequest>) -> impl IntoResponse {
-    tracing::info!("Received terrain data for project: {}", req.project_dir);
-    let terrain_dir = PathBuf::from(&req.project_dir).join("src").join("Workspace").join("Terrain");
-    tracing::info!("Terrain directory: {}", terrain_dir.display());
-
-    // Create terrain directory
-    if let Err(e) = std::fs::create_dir_all(&terrain_dir) {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "success": false,
-                "error": format!("Failed to create terrain directory: {}", e)
-            })),
-        );
-    }
-
-    let terrain_file = terrain_dir.join("terrain.rbxjson");
+    let project_dir = PathBuf::from(&req.project_dir);
     let batch_index = req.batch_index.unwrap_or(1);
     let total_batches = req.total_batches.unwrap_or(1);
 
-    // For batched uploads, merge with existing data
-    let final_terrain = if batch_index == 1 {
-        // First batch - use as base
-        req.terrain.clone()
-    } else {
-        // Subsequent batch - merge chunks with existing file
-        let existing = std::fs::read_to_string(&terrain_file)
-            .ok()
-            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok());
-
-        if let Some(mut existing_terrain) = existing {
-            // Append new chunks to existing
-            if let (Some(existing_chunks), Some(new_chunks)) = (
-                existing_terrain.get_mut("chunks").and_then(|c| c.as_array_mut()),
-                req.terrain.get("chunks").and_then(|c| c.as_array()),
-            ) {
-                for chunk in new_chunks {
-                    existing_chunks.push(chunk.clone());
-                }
-            }
-            existing_terrain
-        } else {
-            req.terrain.clone()
-        }
-    };
-
-    // Write terrain data to file
-    let terrain_json = match serde_json::to_string_pretty(&final_terrain) {
-        Ok(json) => json,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
+    let written = rbxsync_core::terrain::write_batch(&project_dir, &req.terrain, batch_index == 1);
+    let terrain_dir = rbxsync_core::terrain::terrain_dir(&project_dir);
+    match written {
+        Ok(chunk_count) => {
+            tracing::info!(
+                "Terrain batch {}/{} saved: {} chunks to {}",
+                batch_index,
+                total_batches,
+                chunk_count,
+                terrain_dir.display()
+            );
+            (
+                StatusCode::OK,
                 Json(serde_json::json!({
-                    "success": false,
-                    "error": format!("Failed to serialize terrain: {}", e)
+                    "success": true,
+                    "chunksWritten": chunk_count,
+                    "path": terrain_dir.to_string_lossy()
                 })),
-            );
+            )
         }
-    };
-
-    if let Err(e) = std::fs::write(&terrain_file, terrain_json) {
-        return (
+        Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(serde_json::json!({
                 "success": false,
-                "error": format!("Failed to write terrain file: {}", e)
+                "error": format!("Failed to write terrain: {}", e)
             })),
-        );
+        ),
     }
-
-    let chunk_count = final_terrain.get("chunks")
-        .and_then(|c| c.as_array())
-        .map(|a| a.len())
-        .unwrap_or(0);
-
-    tracing::info!("Terrain batch {}/{} saved: {} total chunks", batch_index, total_batches, chunk_count);
-
-    tracing::info!("Terrain saved: {} chunks to {}", chunk_count, terrain_file.display());
-
-    (
-        StatusCode::OK,
-        Json(serde_json::json!({
-            "success": true,
-            "chunksWritten": chunk_count,
-            "path": terrain_file.to_string_lossy()
-        })),
-    )
-}
-
-/// Sync command request
-#[derive(Debug, Deserialize)]
-pub struct SyncCommandRequest {
-    pub command: String,
-    pub payload: serde_json::Value,
 }
 
 /// Handle sync command - sends to plugin and waits for response
 async fn handle_sync_command(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<SyncCommandRequest>,
+    Json(mut req): Json<SyncCommandRequest>,
 ) -> impl IntoResponse {
     let request_id = Uuid::new_v4();
 
+    // Test runs get the HTTP mock shim when the project defines mocks
+    if req.command == "test:run" {
+        mocks::attach_shim(&state, &mut req.payload).await;
+    }
+
     // Create response channel
     let (tx, mut rx) = mpsc::unbounded_channel();
     {
@@ -2311,13 +3407,14 @@ async fn handle_sync_command(
         id: request_id,
         command: req.command.clone(),
         payload: req.payload,
+        queued_at: Instant::now(),
     };
 
     {
         let mut queue = state.request_queue.lock().await;
         queue.push_back(plugin_request);
     }
-    let _ = state.trigger.send(());
+    state.wakeups.notify_all();
 
     tracing::info!("Sent sync command: {} ({})", req.command, request_id);
 
@@ -2353,21 +3450,39 @@ async fn handle_sync_command(
     }
 }
 
-/// Sync batch request
-#[derive(Debug, Deserialize)]
-pub struct SyncBatchRequest {
-    pub operations: Vec<serde_json::Value>,
-    /// Optional project directory for operation tracking (RBXSYNC-77)
-    #[serde(rename = "projectDir")]
-    pub project_dir: Option<String>,
-}
-
 /// Handle sync batch - sends batch of operations to plugin
 async fn handle_sync_batch(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<SyncBatchRequest>,
+    Json(mut req): Json<SyncBatchRequest>,
 ) -> impl IntoResponse {
+    if let Some(ref project_dir) = req.project_dir {
+        if let Some(err) = recovery::safe_mode_error(&state, project_dir).await {
+            return err;
+        }
+    }
+
+    // Partial sync: only operations on instances under the requested paths
+    if !req.paths.is_empty() {
+        let roots: Vec<String> = req.paths.iter().map(|p| rbxsync_core::normalize_instance_root(p)).collect();
+        let before = req.operations.len();
+        req.operations.retain(|op| {
+            ["path", "newPath"]
+                .iter()
+                .filter_map(|key| op.get(*key).and_then(|p| p.as_str()))
+                .any(|path| rbxsync_core::is_under_any(&normalize_path(path), &roots))
+        });
+        tracing::info!("Partial sync of {:?}: {} of {} operations", roots, req.operations.len(), before);
+    }
+
+    // Never delete protected instances, whoever built the batch
+    let mut skipped_protected = Vec::new();
+    if let Some(ref project_dir) = req.project_dir {
+        let protected = get_protected_paths(&load_project_config(project_dir));
+        skipped_protected = skip_protected_deletes(&protected, &mut req.operations);
+    }
+
     let request_id = Uuid::new_v4();
+    let started = Instant::now();
 
     // Set operation state for VS Code UI (RBXSYNC-77)
     if let Some(ref project_dir) = req.project_dir {
@@ -2393,21 +3508,18 @@ async fn handle_sync_batch(
     }
 
     // Queue batch request to plugin
-    let plugin_request = PluginRequest {
-        id: request_id,
-        command: "sync:batch".to_string(),
-        payload: serde_json::json!({
-            "operations": req.operations
-        }),
-    };
+    let operation_count = req.operations.len();
+    let plugin_request = PluginCommand::SyncBatch(protocol::SyncBatch { operations: req.operations, source: None })
+        .request(request_id);
 
     {
         let mut queue = state.request_queue.lock().await;
         queue.push_back(plugin_request);
     }
-    let _ = state.trigger.send(());
+    state.wakeups.notify_all();
 
-    tracing::info!("Sent sync batch with {} operations ({})", req.operations.len(), request_id);
+    state.metrics.sync_batch_size.observe(operation_count as f64);
+    tracing::info!("Sent sync batch with {} operations ({})", operation_count, request_id);
 
     // Wait for response with longer timeout for batch operations
     let timeout = tokio::time::Duration::from_secs(300); // 5 minutes for large batches
@@ -2428,7 +3540,27 @@ async fn handle_sync_batch(
     match result {
         Ok(Some(response)) => {
             tracing::info!("Batch complete for {}: success={}", request_id, response.success);
-            (StatusCode::OK, Json(serde_json::to_value(&response).unwrap()))
+            if let Some(ref project_dir) = req.project_dir {
+                let errors: Vec<String> = response.data["results"]
+                    .as_array()
+                    .map(|results| {
+                        results
+                            .iter()
+                            .filter(|r| r["success"] == false)
+                            .map(|r| r["error"].as_str().unwrap_or("unknown error").to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let mut run = stats::SyncRun::finished(stats::TO_STUDIO, started).with_errors(&errors);
+                run.instances = operation_count;
+                run.files = response.data["applied"].as_u64().unwrap_or(0) as usize;
+                stats::record(project_dir, run);
+            }
+            let mut body = serde_json::to_value(&response).unwrap();
+            if !skipped_protected.is_empty() {
+                body["skippedProtected"] = serde_json::json!(skipped_protected);
+            }
+            (StatusCode::OK, Json(body))
         }
         Ok(None) => {
             (
@@ -2445,29 +3577,160 @@ async fn handle_sync_batch(
     }
 }
 
-/// Sync changes from Studio back to files
-#[derive(Debug, Deserialize)]
-pub struct SyncFromStudioRequest {
-    pub operations: Vec<StudioChangeOperation>,
-    #[serde(rename = "projectDir")]
-    pub project_dir: String,
-}
+/// Plan the file changes for one change made in Studio
+fn plan_studio_change(
+    op: &StudioChangeOperation,
+    src_dir: &std::path::Path,
+    tree_mapping: &HashMap<String, String>,
+    schema_ref: Option<&str>,
+    planned: &mut Vec<recovery::JournalOp>,
+    errors: &mut Vec<String>,
+) {
+    // Convert instance path to file path with tree mapping
+    let inst_path = &op.path;
+    let fs_path = apply_tree_mapping(inst_path, tree_mapping);
+    let full_path = src_dir.join(&fs_path);
+
+    match op.change_type.as_str() {
+        "delete" => {
+            // Delete both .luau and .rbxjson files
+            let luau_extensions = [".server.luau", ".client.luau", ".luau", ".rbxjson"];
+            for ext in luau_extensions {
+                let file_path = rbxsync_core::path_with_suffix(&full_path, ext);
+                if std::path::Path::new(&file_path).exists() {
+                    tracing::info!("Studio sync: deleting {}", file_path);
+                    planned.push(recovery::JournalOp::Remove { path: file_path, previous: None });
+                }
+            }
 
-#[derive(Debug, Deserialize)]
-pub struct StudioChangeOperation {
-    #[serde(rename = "type")]
-    pub change_type: String,  // "create", "modify", "delete", "rename"
-    pub path: String,
-    #[serde(rename = "className")]
-    pub class_name: Option<String>,
-    pub data: Option<serde_json::Value>,
-}
+            // Delete as a directory (for Folder instances)
+            if full_path.is_dir() {
+                tracing::info!("Studio sync: deleting folder {:?}", full_path);
+                planned.push(recovery::JournalOp::RemoveDir {
+                    path: rbxsync_core::path_to_string(&full_path),
+                    backup: None,
+                });
+            }
+        }
+        "rename" => {
+            // Handle rename: move files from old path to new path
+            if let Some(data) = &op.data {
+                let old_inst_path = data.get("oldPath").and_then(|v| v.as_str());
+                let new_inst_path = data.get("newPath").and_then(|v| v.as_str());
+
+                if let (Some(old_path), Some(new_path)) = (old_inst_path, new_inst_path) {
+                    let old_fs_path = apply_tree_mapping(old_path, tree_mapping);
+                    let new_fs_path = apply_tree_mapping(new_path, tree_mapping);
+                    let old_full_path = src_dir.join(&old_fs_path);
+                    let new_full_path = src_dir.join(&new_fs_path);
+
+                    tracing::info!("Studio sync: renaming {:?} -> {:?}", old_full_path, new_full_path);
+
+                    // Rename directory (for folders with children)
+                    if old_full_path.is_dir() {
+                        planned.push(recovery::JournalOp::Rename {
+                            from: rbxsync_core::path_to_string(&old_full_path),
+                            to: rbxsync_core::path_to_string(&new_full_path),
+                        });
+                    } else {
+                        // Rename script files (try all extensions)
+                        let extensions = [".server.luau", ".client.luau", ".luau", ".rbxjson"];
+                        for ext in extensions {
+                            let old_file = rbxsync_core::path_with_suffix(&old_full_path, ext);
+                            if std::path::Path::new(&old_file).exists() {
+                                planned.push(recovery::JournalOp::Rename {
+                                    from: old_file,
+                                    to: rbxsync_core::path_with_suffix(&new_full_path, ext),
+                                });
+                            }
+                        }
+                    }
+                } else {
+                    errors.push("Rename operation missing oldPath or newPath".to_string());
+                }
+            }
+        }
+        "create" | "modify" => {
+            if let Some(data) = &op.data {
+                // Check if this is a script with source
+                let class_name = op.class_name.as_deref()
+                    .or_else(|| data.get("className").and_then(|v| v.as_str()))
+                    .unwrap_or("");
+
+                let is_script = matches!(class_name, "Script" | "LocalScript" | "ModuleScript");
+                tracing::info!("Processing {} - class_name: '{}', is_script: {}, data: {:?}", inst_path, class_name, is_script, data);
+
+                if is_script {
+                    // Extract script source - try multiple formats
+                    // Format 1: data.source (from ChangeTracker)
+                    // Format 2: data.properties.Source.value (from full extraction)
+                    let source = data.get("source")
+                        .and_then(|v| v.as_str())
+                        .or_else(|| {
+                            data.get("properties")
+                                .and_then(|p| p.get("Source"))
+                                .and_then(|s| s.get("value"))
+                                .and_then(|v| v.as_str())
+                        });
+
+                    tracing::debug!("Source extraction result: {:?}", source.map(|s| s.len()));
+                    if let Some(source) = source {
+                        let extension = match class_name {
+                            "Script" => ".server.luau",
+                            "LocalScript" => ".client.luau",
+                            _ => ".luau",
+                        };
+                        planned.push(recovery::JournalOp::Write {
+                            path: rbxsync_core::path_with_suffix(&full_path, extension),
+                            content: source.to_string(),
+                            previous: None,
+                        });
+                    }
+                }
 
-/// Handle changes from Studio and write them to files
-async fn handle_sync_from_studio(Json(req): Json<SyncFromStudioRequest>) -> impl IntoResponse {
-    tracing::info!("handle_sync_from_studio called with {} operations", req.operations.len());
-    for (i, op) in req.operations.iter().enumerate() {
-        tracing::info!("  Op {}: type={}, path={}, className={:?}, has_data={}",
+                // Write .rbxjson for non-source properties
+                let mut clean_data = data.clone();
+                if is_script {
+                    // Remove source from both formats
+                    if let Some(obj) = clean_data.as_object_mut() {
+                        obj.remove("source");
+                    }
+                    if let Some(props) = clean_data.get_mut("properties") {
+                        if let Some(obj) = props.as_object_mut() {
+                            obj.remove("Source");
+                        }
+                    }
+                }
+                rbxsync_core::with_schema_ref(&mut clean_data, schema_ref);
+
+                if let Ok(json) = serde_json::to_string_pretty(&clean_data) {
+                    planned.push(recovery::JournalOp::Write {
+                        path: rbxsync_core::path_with_suffix(&full_path, ".rbxjson"),
+                        content: json,
+                        previous: None,
+                    });
+                }
+            }
+        }
+        _ => {
+            errors.push(format!("Unknown change type: {}", op.change_type));
+        }
+    }
+}
+
+/// Handle changes from Studio and write them to files
+async fn handle_sync_from_studio(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<SyncFromStudioRequest>,
+) -> impl IntoResponse {
+    if let Some(err) = recovery::safe_mode_error(&state, &req.project_dir).await {
+        return err;
+    }
+
+    let started = Instant::now();
+    tracing::info!("handle_sync_from_studio called with {} operations", req.operations.len());
+    for (i, op) in req.operations.iter().enumerate() {
+        tracing::info!("  Op {}: type={}, path={}, className={:?}, has_data={}",
             i, op.change_type, op.path, op.class_name, op.data.is_some());
     }
     let src_dir = PathBuf::from(&req.project_dir).join("src");
@@ -2485,205 +3748,288 @@ async fn handle_sync_from_studio(Json(req): Json<SyncFromStudioRequest>) -> impl
     // Load project config and tree mapping
     let config = load_project_config(&req.project_dir);
     let tree_mapping = get_tree_mapping(&config);
+    let schema_ref = rbxsync_core::schema_ref(config.as_ref());
+    let filter = get_sync_filter(&config);
 
-    let mut files_written = 0;
     let mut errors: Vec<String> = Vec::new();
 
+    // Plan every filesystem change first so it can be journaled before any
+    // file is touched (write-ahead log for crash recovery)
+    let mut planned: Vec<recovery::JournalOp> = Vec::new();
+    let project_path = std::path::Path::new(&req.project_dir);
+    let timestamp = nightly::now_secs();
+    let mut audit_entries: Vec<audit::AuditEntry> = Vec::new();
+
+    let mut sync_state = state.sync_state.write().await;
+    let project_state = sync_state.entry(req.project_dir.clone()).or_default();
+    let mut conflict_list = conflicts::load_conflicts(project_path);
+    let mut new_conflicts: Vec<String> = Vec::new();
+    // Instances whose files were written, for updating their bases
+    let mut touched: Vec<String> = Vec::new();
+
     for op in &req.operations {
-        // Convert instance path to file path with tree mapping
-        let inst_path = &op.path;
-        let fs_path = apply_tree_mapping(inst_path, &tree_mapping);
-        let full_path = src_dir.join(&fs_path);
-
-        match op.change_type.as_str() {
-            "delete" => {
-                // Try to delete both .luau and .rbxjson files
-                let luau_extensions = [".server.luau", ".client.luau", ".luau"];
-                let mut deleted_any = false;
-                for ext in luau_extensions {
-                    let script_path = rbxsync_core::path_with_suffix(&full_path, ext);
-                    if std::fs::remove_file(&script_path).is_ok() {
-                        deleted_any = true;
-                        tracing::info!("Studio sync: deleted {}", script_path);
-                    }
-                }
-                let json_path = rbxsync_core::path_with_suffix(&full_path, ".rbxjson");
-                if std::fs::remove_file(&json_path).is_ok() {
-                    deleted_any = true;
-                    tracing::info!("Studio sync: deleted {}", json_path);
-                }
+        // Selective sync: changes outside the synced paths stay in Studio
+        let key = apply_tree_mapping(&op.path, &tree_mapping);
+        if !filter.matches(&key) {
+            continue;
+        }
 
-                // Try to delete as a directory (for Folder instances)
-                if full_path.is_dir()
-                    && std::fs::remove_dir_all(&full_path).is_ok() {
-                        deleted_any = true;
-                        tracing::info!("Studio sync: deleted folder {:?}", full_path);
-                    }
+        let first_planned = planned.len();
 
-                if deleted_any {
-                    files_written += 1;
-                }
-            }
-            "rename" => {
-                // Handle rename: move files from old path to new path
-                if let Some(data) = &op.data {
-                    let old_inst_path = data.get("oldPath").and_then(|v| v.as_str());
-                    let new_inst_path = data.get("newPath").and_then(|v| v.as_str());
-
-                    if let (Some(old_path), Some(new_path)) = (old_inst_path, new_inst_path) {
-                        let old_fs_path = apply_tree_mapping(old_path, &tree_mapping);
-                        let new_fs_path = apply_tree_mapping(new_path, &tree_mapping);
-                        let old_full_path = src_dir.join(&old_fs_path);
-                        let new_full_path = src_dir.join(&new_fs_path);
-
-                        tracing::info!("Studio sync: renaming {:?} -> {:?}", old_full_path, new_full_path);
-
-                        // Ensure new parent directory exists
-                        if let Some(parent) = new_full_path.parent() {
-                            let _ = std::fs::create_dir_all(parent);
-                        }
+        plan_studio_change(op, &src_dir, &tree_mapping, schema_ref.as_deref(), &mut planned, &mut errors);
 
-                        // Try to rename directory (for folders with children)
-                        if old_full_path.is_dir() {
-                            match std::fs::rename(&old_full_path, &new_full_path) {
-                                Ok(_) => {
-                                    tracing::info!("Studio sync: renamed folder {:?} -> {:?}", old_full_path, new_full_path);
-                                    files_written += 1;
-                                }
-                                Err(e) => {
-                                    errors.push(format!("Failed to rename folder {:?}: {}", old_full_path, e));
-                                }
-                            }
-                        } else {
-                            // Rename script files (try all extensions)
-                            let extensions = [".server.luau", ".client.luau", ".luau", ".rbxjson"];
-                            let mut renamed_any = false;
-                            for ext in extensions {
-                                let old_file_str = rbxsync_core::path_with_suffix(&old_full_path, ext);
-                                let new_file_str = rbxsync_core::path_with_suffix(&new_full_path, ext);
-                                let old_file = PathBuf::from(&old_file_str);
-                                let new_file = PathBuf::from(&new_file_str);
-                                if old_file.exists() {
-                                    match std::fs::rename(&old_file, &new_file) {
-                                        Ok(_) => {
-                                            tracing::info!("Studio sync: renamed {:?} -> {:?}", old_file, new_file);
-                                            renamed_any = true;
-                                        }
-                                        Err(e) => {
-                                            errors.push(format!("Failed to rename {:?}: {}", old_file, e));
-                                        }
-                                    }
-                                }
-                            }
-                            if renamed_any {
-                                files_written += 1;
-                            }
-                        }
-                    } else {
-                        errors.push("Rename operation missing oldPath or newPath".to_string());
-                    }
+        // Both sides changed: park the Studio change instead of overwriting the
+        // local edit, unless it leaves the files exactly as they are
+        if matches!(op.change_type.as_str(), "create" | "modify" | "delete") {
+            let already_conflicted = conflict_list.iter().any(|c| c.key == key);
+            if already_conflicted || project_state.edited_locally(project_path, &key) {
+                let unchanged = planned[first_planned..].iter().all(|p| {
+                    matches!(p, recovery::JournalOp::Write { path, content, .. }
+                        if std::fs::read_to_string(path).is_ok_and(|current| &current == content))
+                });
+                if unchanged {
+                    conflict_list.retain(|c| c.key != key);
+                } else {
+                    tracing::warn!("Sync conflict: {} changed in both Studio and files", op.path);
+                    planned.truncate(first_planned);
+                    conflicts::upsert(&mut conflict_list, SyncConflict {
+                        path: op.path.clone(),
+                        key,
+                        class_name: op.class_name.clone(),
+                        detected_at: timestamp,
+                        studio: op.clone(),
+                    });
+                    new_conflicts.push(op.path.clone());
+                    continue;
                 }
             }
-            "create" | "modify" => {
-                if let Some(data) = &op.data {
-                    // Ensure parent directory exists
-                    if let Some(parent) = full_path.parent() {
-                        let _ = std::fs::create_dir_all(parent);
-                    }
+        }
+        if op.change_type == "rename" {
+            let rename_key = |k: &str| {
+                op.data.as_ref().and_then(|d| d.get(k)).and_then(|v| v.as_str()).map(|p| apply_tree_mapping(p, &tree_mapping))
+            };
+            touched.extend(rename_key("oldPath"));
+            touched.extend(rename_key("newPath"));
+        } else {
+            touched.push(key);
+        }
 
-                    // Check if this is a script with source
-                    let class_name = op.class_name.as_deref()
-                        .or_else(|| data.get("className").and_then(|v| v.as_str()))
-                        .unwrap_or("");
-
-                    let is_script = matches!(class_name, "Script" | "LocalScript" | "ModuleScript");
-                    tracing::info!("Processing {} - class_name: '{}', is_script: {}, data: {:?}", inst_path, class_name, is_script, data);
-
-                    if is_script {
-                        // Extract script source - try multiple formats
-                        // Format 1: data.source (from ChangeTracker)
-                        // Format 2: data.properties.Source.value (from full extraction)
-                        let source = data.get("source")
-                            .and_then(|v| v.as_str())
-                            .or_else(|| {
-                                data.get("properties")
-                                    .and_then(|p| p.get("Source"))
-                                    .and_then(|s| s.get("value"))
-                                    .and_then(|v| v.as_str())
-                            });
-
-                        tracing::debug!("Source extraction result: {:?}", source.map(|s| s.len()));
-                        if let Some(source) = source {
-                            let extension = match class_name {
-                                "Script" => ".server.luau",
-                                "LocalScript" => ".client.luau",
-                                _ => ".luau",
-                            };
-                            let script_path = rbxsync_core::path_with_suffix(&full_path, extension);
-
-                            match std::fs::write(&script_path, source) {
-                                Ok(_) => {
-                                    tracing::info!("Studio sync: wrote {}", script_path);
-                                    files_written += 1;
-                                }
-                                Err(e) => {
-                                    errors.push(format!("Failed to write {}: {}", script_path, e));
-                                }
-                            }
-                        }
-                    }
+        let files: Vec<String> = planned[first_planned..]
+            .iter()
+            .flat_map(|p| audit::journal_files(project_path, p))
+            .collect();
+        if !files.is_empty() {
+            let rename_path = |key: &str| {
+                op.data.as_ref().and_then(|d| d.get(key)).and_then(|v| v.as_str()).map(str::to_string)
+            };
+            let (instance_path, old_path) = match op.change_type.as_str() {
+                "rename" => (rename_path("newPath").unwrap_or_else(|| op.path.clone()), rename_path("oldPath")),
+                _ => (op.path.clone(), None),
+            };
+            audit_entries.push(audit::AuditEntry {
+                timestamp,
+                operation: op.change_type.clone(),
+                instance_path,
+                old_path,
+                files,
+                user_id: req.user_id,
+                place_name: req.place_name.clone(),
+            });
+        }
+    }
 
-                    // Write .rbxjson for non-source properties
-                    let mut clean_data = data.clone();
-                    if is_script {
-                        // Remove source from both formats
-                        if let Some(obj) = clean_data.as_object_mut() {
-                            obj.remove("source");
-                        }
-                        if let Some(props) = clean_data.get_mut("properties") {
-                            if let Some(obj) = props.as_object_mut() {
-                                obj.remove("Source");
-                            }
-                        }
-                    }
+    if let Err(e) = recovery::begin_operation(project_path, "sync-from-studio", planned.clone()) {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "success": false,
+                "error": format!("Failed to write operation journal: {}", e)
+            })),
+        );
+    }
 
-                    let json_path = rbxsync_core::path_with_suffix(&full_path, ".rbxjson");
-                    if let Ok(json) = serde_json::to_string_pretty(&clean_data) {
-                        match std::fs::write(&json_path, json) {
-                            Ok(_) => {
-                                files_written += 1;
-                            }
-                            Err(e) => {
-                                errors.push(format!("Failed to write {}: {}", json_path, e));
-                            }
+    let apply_errors = recovery::apply_ops(&planned);
+    let files_written = planned.len() - apply_errors.len();
+    errors.extend(apply_errors);
+
+    if let Err(e) = recovery::commit_operation(project_path) {
+        tracing::warn!("Failed to commit operation journal: {}", e);
+    }
+
+    // What was just written matches Studio
+    for key in &touched {
+        project_state.set_base(project_path, key);
+    }
+    project_state.save_bases(project_path);
+    conflicts::save_conflicts(project_path, &conflict_list);
+    drop(sync_state);
+
+    tracing::info!("Studio sync complete: {} files written, {} errors", files_written, errors.len());
+
+    // Instances created, renamed or deleted in Studio change the sourcemap
+    if files_written > 0 && req.operations.iter().any(|op| op.change_type != "modify") && sourcemap_enabled(&config) {
+        regenerate_sourcemap(&req.project_dir);
+    }
+
+    if let Err(e) = audit::record_entries(project_path, &audit_entries) {
+        tracing::warn!("Failed to write audit log: {}", e);
+    }
+
+    let mut run = stats::SyncRun::finished(stats::FROM_STUDIO, started).with_errors(&errors);
+    run.instances = req.operations.len();
+    run.files = files_written;
+    stats::record(&req.project_dir, run);
+
+    if errors.is_empty() && files_written > 0 {
+        let place = connected_place(&state, &req.project_dir).await;
+        autocommit::spawn(
+            &req.project_dir,
+            &config,
+            autocommit::Checkpoint {
+                source: autocommit::Source::StudioSync,
+                place_name: req.place_name.clone().or_else(|| place.as_ref().map(|p| p.place_name.clone())),
+                instances: req.operations.len(),
+                files: files_written,
+                session_id: place.and_then(|p| p.session_id),
+            },
+        );
+    }
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "success": errors.is_empty(),
+            "filesWritten": files_written,
+            "errors": errors,
+            "conflicts": new_conflicts
+        })),
+    )
+}
+
+/// List Studio changes held back by sync conflicts
+async fn handle_sync_conflicts(Json(req): Json<ConflictsRequest>) -> impl IntoResponse {
+    let conflicts = conflicts::load_conflicts(std::path::Path::new(&req.project_dir));
+    (StatusCode::OK, Json(success_body(&ConflictsResponse { conflicts })))
+}
+
+/// Resolve a sync conflict with the files, the Studio change or a merge of both
+async fn handle_sync_resolve_conflict(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ResolveConflictRequest>,
+) -> impl IntoResponse {
+    if let Some(err) = recovery::safe_mode_error(&state, &req.project_dir).await {
+        return err;
+    }
+
+    let project_path = std::path::Path::new(&req.project_dir);
+    let src_dir = project_path.join("src");
+    let mut sync_state = state.sync_state.write().await;
+    let project_state = sync_state.entry(req.project_dir.clone()).or_default();
+    let mut conflict_list = conflicts::load_conflicts(project_path);
+    let Some(index) = conflict_list.iter().position(|c| c.path == req.path) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "success": false,
+                "error": format!("No sync conflict for {}", req.path)
+            })),
+        );
+    };
+    let conflict = conflict_list.remove(index);
+
+    let mut planned: Vec<recovery::JournalOp> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+    match req.resolution.as_str() {
+        "file" => {
+            // Rewrite the files as they are so the next sync sends them to Studio
+            for file in conflicts::instance_files(&src_dir.join(&conflict.key)) {
+                if let Ok(content) = std::fs::read_to_string(&file) {
+                    planned.push(recovery::JournalOp::Write {
+                        path: rbxsync_core::path_to_string(&file),
+                        content,
+                        previous: None,
+                    });
+                }
+            }
+        }
+        "studio" | "merged" => {
+            let mut op = conflict.studio.clone();
+            if req.resolution == "merged" {
+                let error = if op.change_type == "delete" {
+                    Some("The instance was deleted in Studio; resolve with file or studio")
+                } else if req.source.is_none() && req.properties.is_none() {
+                    Some("A merged resolution needs a source or properties")
+                } else {
+                    None
+                };
+                if let Some(error) = error {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(serde_json::json!({ "success": false, "error": error })),
+                    );
+                }
+                if let Some(serde_json::Value::Object(data)) = op.data.as_mut() {
+                    if let Some(source) = &req.source {
+                        data.insert("source".to_string(), serde_json::json!(source));
+                    }
+                    if let Some(serde_json::Value::Object(merged)) = &req.properties {
+                        let props = data.entry("properties").or_insert_with(|| serde_json::json!({}));
+                        if let Some(props) = props.as_object_mut() {
+                            props.extend(merged.iter().map(|(k, v)| (k.clone(), v.clone())));
                         }
                     }
                 }
             }
-            _ => {
-                errors.push(format!("Unknown change type: {}", op.change_type));
-            }
+            let config = load_project_config(&req.project_dir);
+            let schema_ref = rbxsync_core::schema_ref(config.as_ref());
+            plan_studio_change(&op, &src_dir, &get_tree_mapping(&config), schema_ref.as_deref(), &mut planned, &mut errors);
+        }
+        other => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "success": false,
+                    "error": format!("Unknown resolution '{}' (expected file, studio or merged)", other)
+                })),
+            );
         }
     }
 
-    tracing::info!("Studio sync complete: {} files written, {} errors", files_written, errors.len());
+    if let Err(e) = recovery::begin_operation(project_path, "resolve-conflict", planned.clone()) {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "success": false,
+                "error": format!("Failed to write operation journal: {}", e)
+            })),
+        );
+    }
+    let apply_errors = recovery::apply_ops(&planned);
+    let files_written = planned.len() - apply_errors.len();
+    errors.extend(apply_errors);
+    if let Err(e) = recovery::commit_operation(project_path) {
+        tracing::warn!("Failed to commit operation journal: {}", e);
+    }
+
+    // The resolved files are the new base; anything Studio doesn't have yet
+    // goes out with the next sync
+    project_state.set_base(project_path, &conflict.key);
+    project_state.save_bases(project_path);
+    conflicts::save_conflicts(project_path, &conflict_list);
+    tracing::info!("Resolved sync conflict on {} with {}", conflict.path, req.resolution);
 
     (
         StatusCode::OK,
         Json(serde_json::json!({
             "success": errors.is_empty(),
+            "path": conflict.path,
+            "resolution": req.resolution,
             "filesWritten": files_written,
             "errors": errors
         })),
     )
 }
 
-/// Read file tree for sync - returns all instances from project directory
-#[derive(Debug, Deserialize)]
-pub struct ReadTreeRequest {
-    pub project_dir: String,
-}
-
 async fn handle_sync_read_tree(Json(req): Json<ReadTreeRequest>) -> impl IntoResponse {
     let project_dir = PathBuf::from(&req.project_dir);
     let src_dir = project_dir.join("src");
@@ -2698,6 +4044,9 @@ async fn handle_sync_read_tree(Json(req): Json<ReadTreeRequest>) -> impl IntoRes
         );
     }
 
+    // Legacy .lua files aren't read below; migrate or report them first
+    let legacy_lua = check_legacy_lua(&req.project_dir);
+
     // Load project config for package settings
     let config = load_project_config(&req.project_dir);
     let packages_config = config.as_ref().and_then(|c| c.get("packages"));
@@ -2721,6 +4070,9 @@ async fn handle_sync_read_tree(Json(req): Json<ReadTreeRequest>) -> impl IntoRes
         .and_then(|v| v.as_str())
         .unwrap_or("ServerScriptService/Packages");
 
+    // Partial sync: only the subtrees under these instance paths
+    let roots: Vec<String> = req.paths.iter().map(|p| rbxsync_core::normalize_instance_root(p)).collect();
+
     // Recursively read all .rbxjson files
     let mut instances: Vec<serde_json::Value> = Vec::new();
     let mut scripts: std::collections::HashMap<String, String> = std::collections::HashMap::new();
@@ -2729,6 +4081,7 @@ async fn handle_sync_read_tree(Json(req): Json<ReadTreeRequest>) -> impl IntoRes
         dir: &std::path::Path,
         base: &std::path::Path,
         path_prefix: &str,
+        roots: &[String],
         instances: &mut Vec<serde_json::Value>,
         scripts: &mut std::collections::HashMap<String, String>,
     ) {
@@ -2736,7 +4089,15 @@ async fn handle_sync_read_tree(Json(req): Json<ReadTreeRequest>) -> impl IntoRes
             for entry in entries.flatten() {
                 let path = entry.path();
                 if path.is_dir() {
-                    walk_dir(&path, base, path_prefix, instances, scripts);
+                    // Don't read folders that can't hold anything under the roots
+                    if !roots.is_empty() {
+                        let rel = rbxsync_core::path_to_string(path.strip_prefix(base).unwrap_or(&path));
+                        let inst_path = if path_prefix.is_empty() { rel } else { format!("{}/{}", path_prefix, rel) };
+                        if !rbxsync_core::may_contain_any(&normalize_path_for_comparison(&inst_path), roots) {
+                            continue;
+                        }
+                    }
+                    walk_dir(&path, base, path_prefix, roots, instances, scripts);
                 } else if let Some(ext) = path.extension() {
                     if ext == "rbxjson" {
                         // Skip terrain.rbxjson - it has different format (terrain chunk data, not instance data)
@@ -2748,6 +4109,7 @@ async fn handle_sync_read_tree(Json(req): Json<ReadTreeRequest>) -> impl IntoRes
                         // Read instance JSON
                         if let Ok(content) = std::fs::read_to_string(&path) {
                             if let Ok(mut inst) = serde_json::from_str::<serde_json::Value>(&content) {
+                                rbxsync_core::normalize_properties(&mut inst);
                                 // Derive path from file system if not present in JSON
                                 let rel_path = path.strip_prefix(base).unwrap_or(&path);
                                 let path_str = rbxsync_core::path_to_string(rel_path);
@@ -2822,21 +4184,24 @@ async fn handle_sync_read_tree(Json(req): Json<ReadTreeRequest>) -> impl IntoRes
     }
 
     // Walk the main src directory (no prefix - paths map directly to DataModel)
-    walk_dir(&src_dir, &src_dir, "", &mut instances, &mut scripts);
+    walk_dir(&src_dir, &src_dir, "", &roots, &mut instances, &mut scripts);
 
     // Walk packages directory if enabled (packages_dir already validated when packages_enabled was set)
     if packages_enabled {
         tracing::info!("Reading Wally packages from {} -> {}", packages_folder, shared_packages_path);
-        walk_dir(&packages_dir, &packages_dir, shared_packages_path, &mut instances, &mut scripts);
+        walk_dir(&packages_dir, &packages_dir, shared_packages_path, &roots, &mut instances, &mut scripts);
 
         // Also check for server packages subdirectory
         let server_pkg_dir = packages_dir.join("ServerPackages");
         if server_pkg_dir.exists() && server_pkg_dir.is_dir() {
             tracing::info!("Reading server packages from ServerPackages -> {}", server_packages_path);
-            walk_dir(&server_pkg_dir, &server_pkg_dir, server_packages_path, &mut instances, &mut scripts);
+            walk_dir(&server_pkg_dir, &server_pkg_dir, server_packages_path, &roots, &mut instances, &mut scripts);
         }
     }
 
+    // Content values naming a downloaded asset file go to Studio as the asset's URL
+    assets::resolve_instances(&project_dir, &mut instances);
+
     // Merge script sources into their instance data
     for inst in &mut instances {
         if let Some(path) = inst.get("path").and_then(|v| v.as_str()) {
@@ -2854,6 +4219,34 @@ async fn handle_sync_read_tree(Json(req): Json<ReadTreeRequest>) -> impl IntoRes
         }
     }
 
+    // Selective sync: leave out instances outside sync.include / in sync.exclude
+    let filter = get_sync_filter(&config);
+    if !filter.is_empty() {
+        let before = instances.len();
+        instances.retain(|inst| inst.get("path").and_then(|v| v.as_str()).is_none_or(|path| filter.matches(path)));
+        tracing::info!("Selective sync left out {} instances", before - instances.len());
+    }
+    if !roots.is_empty() {
+        instances.retain(|inst| {
+            inst.get("path").and_then(|v| v.as_str()).is_some_and(|path| rbxsync_core::is_under_any(path, &roots))
+        });
+    }
+
+    // Variant overrides are applied last, on top of the files
+    let override_report = match overrides::load(&req.project_dir, req.variant.as_deref()) {
+        Ok(Some(selected)) => Some(overrides::apply_to_tree(&req.project_dir, &selected, &mut instances)),
+        Ok(None) => None,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "success": false,
+                    "error": e
+                })),
+            );
+        }
+    };
+
     tracing::info!("Read {} instances from {}", instances.len(), src_dir.display());
 
     (
@@ -2861,76 +4254,43 @@ async fn handle_sync_read_tree(Json(req): Json<ReadTreeRequest>) -> impl IntoRes
         Json(serde_json::json!({
             "success": true,
             "instances": instances,
-            "count": instances.len()
+            "count": instances.len(),
+            "legacyLua": legacy_lua,
+            "overrides": override_report,
+            "filter": (!filter.is_empty()).then_some(&filter),
+            "paths": roots
         })),
     )
 }
 
-/// Read terrain data for sync
+/// Read terrain data for sync, converting a legacy terrain.rbxjson on the way
 async fn handle_sync_read_terrain(Json(req): Json<ReadTreeRequest>) -> impl IntoResponse {
-    // Try both possible terrain file locations
-    let terrain_file_v1 = PathBuf::from(&req.project_dir)
-        .join("src")
-        .join("Workspace")
-        .join("Terrain.rbxjson");
-    let terrain_file_v2 = PathBuf::from(&req.project_dir)
-        .join("src")
-        .join("Workspace")
-        .join("Terrain")
-        .join("terrain.rbxjson");
-
-    let terrain_file = if terrain_file_v1.exists() {
-        terrain_file_v1
-    } else {
-        terrain_file_v2
-    };
-
-    if !terrain_file.exists() {
-        return (
+    match rbxsync_core::terrain::read_terrain(std::path::Path::new(&req.project_dir)) {
+        Ok(Some(terrain_data)) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "success": true,
+                "hasTerrain": true,
+                "terrain": terrain_data
+            })),
+        ),
+        Ok(None) => (
             StatusCode::OK,
             Json(serde_json::json!({
                 "success": true,
                 "hasTerrain": false
             })),
-        );
-    }
-
-    match std::fs::read_to_string(&terrain_file) {
-        Ok(content) => {
-            match serde_json::from_str::<serde_json::Value>(&content) {
-                Ok(terrain_data) => (
-                    StatusCode::OK,
-                    Json(serde_json::json!({
-                        "success": true,
-                        "hasTerrain": true,
-                        "terrain": terrain_data
-                    })),
-                ),
-                Err(e) => (
-                    StatusCode::OK,
-                    Json(serde_json::json!({
-                        "success": false,
-                        "error": format!("Failed to parse terrain data: {}", e)
-                    })),
-                ),
-            }
-        }
+        ),
         Err(e) => (
             StatusCode::OK,
             Json(serde_json::json!({
                 "success": false,
-                "error": format!("Failed to read terrain file: {}", e)
+                "error": format!("Failed to read terrain data: {}", e)
             })),
         ),
     }
 }
 
-/// Request to check pending changes count
-#[derive(Debug, Deserialize)]
-pub struct PendingChangesRequest {
-    pub project_dir: String,
-}
-
 /// Handle pending changes request - returns count of files waiting to sync
 async fn handle_sync_pending_changes(
     State(state): State<Arc<AppState>>,
@@ -2954,15 +4314,6 @@ async fn handle_sync_pending_changes(
     )
 }
 
-/// Request for incremental sync - returns only files changed since last sync
-#[derive(Debug, Deserialize)]
-pub struct IncrementalSyncRequest {
-    pub project_dir: String,
-    /// If true, mark current time as last sync (call after successful sync)
-    #[serde(default)]
-    pub mark_synced: bool,
-}
-
 /// Handle incremental sync - returns only files modified since last sync
 async fn handle_sync_incremental(
     State(state): State<Arc<AppState>>,
@@ -2983,13 +4334,39 @@ async fn handle_sync_incremental(
     // Get last sync time for this project
     let last_sync = {
         let sync_state = state.sync_state.read().await;
-        sync_state.get(&req.project_dir).copied()
+        sync_state.get(&req.project_dir).and_then(|s| s.last_sync)
     };
 
-    // If marking as synced, update the sync time and return empty
+    // If marking as synced, update the sync time and return empty. Files
+    // changed since the previous sync now match Studio, so they become the
+    // base for conflict detection.
     if req.mark_synced {
+        let project_path = std::path::Path::new(&req.project_dir);
         let mut sync_state = state.sync_state.write().await;
-        sync_state.insert(req.project_dir.clone(), std::time::SystemTime::now());
+        let project_state = sync_state.entry(req.project_dir.clone()).or_default();
+        project_state.last_sync = Some(std::time::SystemTime::now());
+
+        fn changed_keys(dir: &std::path::Path, base: &std::path::Path, since: Option<std::time::SystemTime>, keys: &mut HashSet<String>) {
+            for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    changed_keys(&path, base, since, keys);
+                    continue;
+                }
+                let modified = entry.metadata().and_then(|m| m.modified()).ok();
+                if since.is_none_or(|since| modified.is_none_or(|m| m > since)) {
+                    let rel_path = path.strip_prefix(base).unwrap_or(&path);
+                    keys.insert(conflicts::instance_key(&rbxsync_core::path_to_string(rel_path)));
+                }
+            }
+        }
+        let mut keys = HashSet::new();
+        changed_keys(&src_dir, &src_dir, last_sync, &mut keys);
+        for key in &keys {
+            project_state.set_base(project_path, key);
+        }
+        project_state.save_bases(project_path);
+
         return (
             StatusCode::OK,
             Json(serde_json::json!({
@@ -3054,6 +4431,7 @@ async fn handle_sync_incremental(
 
                         if let Ok(content) = std::fs::read_to_string(&path) {
                             if let Ok(mut inst) = serde_json::from_str::<serde_json::Value>(&content) {
+                                rbxsync_core::normalize_properties(&mut inst);
                                 let rel_path = path.strip_prefix(base).unwrap_or(&path);
                                 let path_str = rbxsync_core::path_to_string(rel_path);
                                 let is_meta = path_str.ends_with("/_meta.rbxjson") || path_str.ends_with("\\_meta.rbxjson");
@@ -3092,6 +4470,7 @@ async fn handle_sync_incremental(
     }
 
     walk_dir_incremental(&src_dir, &src_dir, &mut instances, &mut scripts, last_sync, &mut files_checked, &mut files_modified);
+    assets::resolve_instances(std::path::Path::new(&req.project_dir), &mut instances);
 
     // Merge script sources into their instance data
     for inst in &mut instances {
@@ -3141,6 +4520,23 @@ async fn handle_sync_incremental(
         }
     }
 
+    // Selective sync: leave out instances outside sync.include / in sync.exclude
+    let filter = get_sync_filter(&load_project_config(&req.project_dir));
+    if !filter.is_empty() {
+        instances.retain(|inst| inst.get("path").and_then(|v| v.as_str()).is_none_or(|path| filter.matches(path)));
+    }
+
+    // Instances in conflict stay as they are in Studio until resolved
+    let conflicted: HashSet<String> = conflicts::load_conflicts(std::path::Path::new(&req.project_dir))
+        .into_iter()
+        .map(|c| c.key)
+        .collect();
+    if !conflicted.is_empty() {
+        instances.retain(|inst| {
+            inst.get("path").and_then(|v| v.as_str()).is_none_or(|path| !conflicted.contains(path))
+        });
+    }
+
     let full_sync = last_sync.is_none();
 
     tracing::info!(
@@ -3156,7 +4552,8 @@ async fn handle_sync_incremental(
             "count": instances.len(),
             "full_sync": full_sync,
             "files_checked": files_checked,
-            "files_modified": files_modified
+            "files_modified": files_modified,
+            "conflicts": conflicted.len()
         })),
     )
 }
@@ -3204,17 +4601,13 @@ async fn handle_studio_paths(
     }
 
     // Queue request to plugin
-    let plugin_request = PluginRequest {
-        id: request_id,
-        command: "studio:paths".to_string(),
-        payload: serde_json::json!({}),
-    };
+    let plugin_request = PluginCommand::StudioPaths(protocol::StudioPaths::default()).request(request_id);
 
     {
         let mut queue = state.request_queue.lock().await;
         queue.push_back(plugin_request);
     }
-    let _ = state.trigger.send(());
+    state.wakeups.notify_all();
 
     tracing::info!("Requesting Studio paths ({})", request_id);
 
@@ -3250,28 +4643,6 @@ async fn handle_studio_paths(
     }
 }
 
-/// Diff request
-#[derive(Debug, Deserialize)]
-pub struct DiffRequest {
-    pub project_dir: String,
-}
-
-/// Single diff entry
-#[derive(Debug, Serialize)]
-pub struct DiffEntry {
-    pub path: String,
-    #[serde(rename = "className")]
-    pub class_name: String,
-}
-
-/// Diff result
-#[derive(Debug, Serialize)]
-pub struct DiffResult {
-    pub added: Vec<DiffEntry>,      // In files, not in Studio (would be created)
-    pub removed: Vec<DiffEntry>,    // In Studio, not in files (would be deleted)
-    pub common: usize,              // In both
-}
-
 /// Handle diff request - compares files with Studio
 async fn handle_diff(
     State(state): State<Arc<AppState>>,
@@ -3289,23 +4660,45 @@ async fn handle_diff(
         );
     }
 
-    // Collect file paths
+    // Property comparison needs Studio to serialize every instance, which is
+    // slower, so callers that only care about paths can turn it off
+    let include_properties = req.include_properties.unwrap_or(true);
+
+    // Collect file paths (and, when comparing properties, the instances and
+    // script sources)
     let mut file_paths: HashSet<String> = HashSet::new();
     let mut file_classes: HashMap<String, String> = HashMap::new();
+    let mut file_guids: HashMap<String, String> = HashMap::new();
+    let mut file_instances: Option<HashMap<String, serde_json::Value>> = include_properties.then(HashMap::new);
+    let mut file_sources: HashMap<String, String> = HashMap::new();
 
     fn collect_file_paths(
         dir: &std::path::Path,
         base: &std::path::Path,
         paths: &mut HashSet<String>,
         classes: &mut HashMap<String, String>,
+        guids: &mut HashMap<String, String>,
+        instances: &mut Option<HashMap<String, serde_json::Value>>,
+        sources: &mut HashMap<String, String>,
     ) {
         if let Ok(entries) = std::fs::read_dir(dir) {
             for entry in entries.flatten() {
                 let path = entry.path();
                 if path.is_dir() {
-                    collect_file_paths(&path, base, paths, classes);
+                    collect_file_paths(&path, base, paths, classes, guids, instances, sources);
                 } else if let Some(ext) = path.extension() {
-                    if ext == "rbxjson" {
+                    if ext == "luau" && instances.is_some() {
+                        let rel_path = path.strip_prefix(base).unwrap_or(&path);
+                        let path_str = rbxsync_core::path_to_string(rel_path);
+                        let inst_path = path_str
+                            .trim_end_matches(".server.luau")
+                            .trim_end_matches(".client.luau")
+                            .trim_end_matches(".luau")
+                            .replace('\\', "/");
+                        if let Ok(source) = std::fs::read_to_string(&path) {
+                            sources.insert(normalize_path_for_comparison(&inst_path), source);
+                        }
+                    } else if ext == "rbxjson" {
                         if let Ok(content) = std::fs::read_to_string(&path) {
                             if let Ok(inst) = serde_json::from_str::<serde_json::Value>(&content) {
                                 let rel_path = path.strip_prefix(base).unwrap_or(&path);
@@ -3322,8 +4715,14 @@ async fn handle_diff(
                                 // (RBXSYNC-68: extract adds _refId suffixes, Studio paths don't have them)
                                 let normalized_path = normalize_path_for_comparison(&inst_path);
                                 paths.insert(normalized_path.clone());
+                                if let Some(guid) = rbxsync_core::guid_of(&inst) {
+                                    guids.insert(normalized_path.clone(), guid.to_string());
+                                }
                                 if let Some(class) = inst.get("className").and_then(|v| v.as_str()) {
-                                    classes.insert(normalized_path, class.to_string());
+                                    classes.insert(normalized_path.clone(), class.to_string());
+                                }
+                                if let Some(instances) = instances.as_mut() {
+                                    instances.insert(normalized_path, inst);
                                 }
                             }
                         }
@@ -3333,9 +4732,30 @@ async fn handle_diff(
         }
     }
 
-    collect_file_paths(&src_dir, &src_dir, &mut file_paths, &mut file_classes);
+    collect_file_paths(
+        &src_dir,
+        &src_dir,
+        &mut file_paths,
+        &mut file_classes,
+        &mut file_guids,
+        &mut file_instances,
+        &mut file_sources,
+    );
     tracing::info!("Read {} file paths from {}", file_paths.len(), src_dir.display());
 
+    // Scripts keep their source in .luau rather than the .rbxjson
+    if let Some(instances) = file_instances.as_mut() {
+        for (path, source) in file_sources {
+            let props = instances
+                .get_mut(&path)
+                .and_then(|inst| inst.as_object_mut())
+                .map(|obj| obj.entry("properties").or_insert_with(|| serde_json::json!({})));
+            if let Some(serde_json::Value::Object(props)) = props {
+                props.insert("Source".to_string(), serde_json::json!({ "type": "string", "value": source }));
+            }
+        }
+    }
+
     // 2. Get Studio paths via plugin
     let request_id = Uuid::new_v4();
     let (tx, mut rx) = mpsc::unbounded_channel();
@@ -3344,19 +4764,16 @@ async fn handle_diff(
         channels.insert(request_id, tx);
     }
 
-    let plugin_request = PluginRequest {
-        id: request_id,
-        command: "studio:paths".to_string(),
-        payload: serde_json::json!({}),
-    };
+    let plugin_request = PluginCommand::StudioPaths(protocol::StudioPaths { include_properties, ..Default::default() })
+        .request(request_id);
 
     {
         let mut queue = state.request_queue.lock().await;
         queue.push_back(plugin_request);
     }
-    let _ = state.trigger.send(());
+    state.wakeups.notify_all();
 
-    let timeout = tokio::time::Duration::from_secs(60);
+    let timeout = tokio::time::Duration::from_secs(if include_properties { 180 } else { 60 });
     let result = tokio::time::timeout(timeout, rx.recv()).await;
 
     {
@@ -3392,11 +4809,17 @@ async fn handle_diff(
     // Parse studio paths
     let mut studio_paths: HashSet<String> = HashSet::new();
     let mut studio_classes: HashMap<String, String> = HashMap::new();
+    let mut studio_guids: HashMap<String, String> = HashMap::new();
+    let mut studio_entries: HashMap<String, &serde_json::Value> = HashMap::new();
 
     if let Some(paths) = studio_response.get("paths").and_then(|v| v.as_array()) {
         for entry in paths {
             if let Some(path) = entry.get("path").and_then(|v| v.as_str()) {
                 studio_paths.insert(path.to_string());
+                studio_entries.entry(path.to_string()).or_insert(entry);
+                if let Some(guid) = rbxsync_core::guid_of(entry) {
+                    studio_guids.insert(path.to_string(), guid.to_string());
+                }
                 if let Some(class) = entry.get("className").and_then(|v| v.as_str()) {
                     studio_classes.insert(path.to_string(), class.to_string());
                 }
@@ -3406,54 +4829,92 @@ async fn handle_diff(
 
     tracing::info!("Got {} Studio paths", studio_paths.len());
 
-    // 3. Compute diff
+    // Selective sync: only compare instances that are synced
+    let config = load_project_config(&req.project_dir);
+    let filter = get_sync_filter(&config);
+    if !filter.is_empty() {
+        file_paths.retain(|path| filter.matches(path));
+        studio_paths.retain(|path| filter.matches(path));
+    }
+
+    // 3. Compute diff: match by path, then pair leftovers by GUID (renamed/moved)
+    let only_files: Vec<(&str, &str)> = file_paths
+        .difference(&studio_paths)
+        .filter_map(|path| Some((path.as_str(), file_guids.get(path)?.as_str())))
+        .collect();
+    let only_studio: Vec<(&str, &str)> = studio_paths
+        .difference(&file_paths)
+        .filter_map(|path| Some((path.as_str(), studio_guids.get(path)?.as_str())))
+        .collect();
+    let moved: Vec<rbxsync_core::MovedEntry> = rbxsync_core::find_moves(only_studio, only_files)
+        .into_iter()
+        .map(|(from, to, guid)| rbxsync_core::MovedEntry {
+            from: from.to_string(),
+            to: to.to_string(),
+            class_name: file_classes.get(to).cloned().unwrap_or_default(),
+            guid: guid.to_string(),
+        })
+        .collect();
+    let moved_from: HashSet<&str> = moved.iter().map(|m| m.from.as_str()).collect();
+    let moved_to: HashSet<&str> = moved.iter().map(|m| m.to.as_str()).collect();
+
     let added: Vec<DiffEntry> = file_paths
         .difference(&studio_paths)
+        .filter(|path| !moved_to.contains(path.as_str()))
         .map(|path| DiffEntry {
             path: path.clone(),
             class_name: file_classes.get(path).cloned().unwrap_or_default(),
         })
         .collect();
 
-    let removed: Vec<DiffEntry> = studio_paths
+    // Studio-only instances under trees that ignore unknown instances are
+    // designer-managed: never offered for deletion
+    let unknown_settings = get_unknown_instance_settings(&req.project_dir, &config);
+    let (kept, removed): (Vec<DiffEntry>, Vec<DiffEntry>) = studio_paths
         .difference(&file_paths)
+        .filter(|path| !moved_from.contains(path.as_str()))
         .map(|path| DiffEntry {
             path: path.clone(),
             class_name: studio_classes.get(path).cloned().unwrap_or_default(),
         })
-        .collect();
-
-    let common = file_paths.intersection(&studio_paths).count();
+        .partition(|entry| rbxsync_core::keeps_unknown_instance(&entry.path, &unknown_settings));
+    // Protected instances are never offered for deletion either
+    let protected_paths = get_protected_paths(&config);
+    let (protected, removed): (Vec<DiffEntry>, Vec<DiffEntry>) =
+        removed.into_iter().partition(|entry| protected_paths.protects(&entry.path));
+    if !protected.is_empty() {
+        tracing::warn!("{} Studio-only instance(s) under protectedPaths left out of the diff's deletes", protected.len());
+    }
+
+    // Instances on both sides: compare their properties
+    let mut modified: Vec<rbxsync_core::ModifiedEntry> = match &file_instances {
+        Some(instances) => file_paths
+            .intersection(&studio_paths)
+            .filter_map(|path| rbxsync_core::diff_instance(path, instances.get(path)?, studio_entries.get(path)?))
+            .collect(),
+        None => Vec::new(),
+    };
+    modified.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let result = DiffResult {
+        added,
+        removed,
+        kept: kept.len(),
+        protected,
+        moved,
+        modified,
+        common: file_paths.intersection(&studio_paths).count(),
+        file_count: file_paths.len(),
+        studio_count: studio_paths.len(),
+    };
 
-    (
-        StatusCode::OK,
-        Json(serde_json::json!({
-            "success": true,
-            "added": added,
-            "removed": removed,
-            "common": common,
-            "file_count": file_paths.len(),
-            "studio_count": studio_paths.len()
-        })),
-    )
+    (StatusCode::OK, Json(success_body(&result)))
 }
 
 // ============================================================================
 // Git Endpoints
 // ============================================================================
 
-/// Git project directory request (shared by all git endpoints)
-#[derive(Debug, Deserialize)]
-pub struct GitProjectRequest {
-    pub project_dir: String,
-}
-
-/// Git status request
-#[derive(Debug, Deserialize)]
-pub struct GitStatusRequest {
-    pub project_dir: String,
-}
-
 /// Handle git status request
 async fn handle_git_status(Json(req): Json<GitStatusRequest>) -> impl IntoResponse {
     let project_path = PathBuf::from(&req.project_dir);
@@ -3476,13 +4937,6 @@ async fn handle_git_status(Json(req): Json<GitStatusRequest>) -> impl IntoRespon
     }
 }
 
-/// Git log request
-#[derive(Debug, Deserialize)]
-pub struct GitLogRequest {
-    pub project_dir: String,
-    pub limit: Option<usize>,
-}
-
 /// Handle git log request
 async fn handle_git_log(Json(req): Json<GitLogRequest>) -> impl IntoResponse {
     let project_path = PathBuf::from(&req.project_dir);
@@ -3506,14 +4960,6 @@ async fn handle_git_log(Json(req): Json<GitLogRequest>) -> impl IntoResponse {
     }
 }
 
-/// Git commit request
-#[derive(Debug, Deserialize)]
-pub struct GitCommitRequest {
-    pub project_dir: String,
-    pub message: String,
-    pub add_all: Option<bool>,
-}
-
 /// Handle git commit request
 async fn handle_git_commit(Json(req): Json<GitCommitRequest>) -> impl IntoResponse {
     let project_path = PathBuf::from(&req.project_dir);
@@ -3568,27 +5014,93 @@ async fn handle_git_init(Json(req): Json<GitProjectRequest>) -> impl IntoRespons
     }
 }
 
-// =============================================================================
-// Test Runner Endpoints
-// =============================================================================
+/// Success or error body for a git operation
+fn git_response<T: Serialize>(result: Result<T, String>) -> (StatusCode, Json<serde_json::Value>) {
+    match result {
+        Ok(data) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "success": true,
+                "data": data
+            })),
+        ),
+        Err(e) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "success": false,
+                "error": e
+            })),
+        ),
+    }
+}
+
+/// Run a git operation off the async runtime; pulls and pushes wait on the network
+async fn git_blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T, String> + Send + 'static) -> Result<T, String> {
+    tokio::task::spawn_blocking(f).await.map_err(|e| format!("git task failed: {}", e))?
+}
 
-/// Response from test operations
-#[derive(Debug, Serialize, Deserialize)]
-pub struct TestConsoleMessage {
-    pub message: String,
-    #[serde(rename = "type")]
-    pub msg_type: String,
-    pub timestamp: f64,
+/// Handle git branch list request
+async fn handle_git_branches(Json(req): Json<GitProjectRequest>) -> impl IntoResponse {
+    git_response(git::list_branches(&PathBuf::from(&req.project_dir)))
+}
+
+/// Handle git branch create request
+async fn handle_git_branch_create(Json(req): Json<GitBranchCreateRequest>) -> impl IntoResponse {
+    let project_path = PathBuf::from(&req.project_dir);
+    let result = git::create_branch(&project_path, &req.name, req.start_point.as_deref(), req.checkout);
+    if result.is_ok() {
+        tracing::info!("Created git branch {} in {}", req.name, req.project_dir);
+    }
+    git_response(result)
+}
+
+/// Handle git checkout request. Switching branches is picked up by the
+/// branch watcher, which resyncs Studio per `sync.branchSync`.
+async fn handle_git_checkout(Json(req): Json<GitCheckoutRequest>) -> impl IntoResponse {
+    let project_path = PathBuf::from(&req.project_dir);
+    let result = git_blocking(move || git::checkout(&project_path, &req.target)).await;
+    if let Ok(checkout) = &result {
+        tracing::info!("Git checkout: now on {}", checkout.head.label());
+    }
+    git_response(result)
 }
 
-/// Test status response
-#[derive(Debug, Serialize)]
-pub struct TestStatusResponse {
-    pub capturing: bool,
-    pub output: Vec<TestConsoleMessage>,
-    pub total_messages: usize,
+/// Handle git pull request
+async fn handle_git_pull(Json(req): Json<GitRemoteRequest>) -> impl IntoResponse {
+    let result = git_blocking(move || {
+        git::pull(&PathBuf::from(&req.project_dir), req.remote.as_deref(), req.branch.as_deref(), req.rebase)
+    })
+    .await;
+    match &result {
+        Ok(pull) => tracing::info!("Git pull: {} file(s) changed", pull.changed_files.len()),
+        Err(e) => tracing::warn!("Git pull failed: {}", e),
+    }
+    git_response(result)
+}
+
+/// Handle git push request
+async fn handle_git_push(Json(req): Json<GitRemoteRequest>) -> impl IntoResponse {
+    let result = git_blocking(move || {
+        git::push(&PathBuf::from(&req.project_dir), req.remote.as_deref(), req.branch.as_deref(), req.set_upstream)
+    })
+    .await;
+    if let Err(e) = &result {
+        tracing::warn!("Git push failed: {}", e);
+    }
+    git_response(result)
 }
 
+/// Handle git stash request
+async fn handle_git_stash(Json(req): Json<GitStashRequest>) -> impl IntoResponse {
+    let project_path = PathBuf::from(&req.project_dir);
+    let action = req.action.as_deref().unwrap_or("push");
+    git_response(git::stash(&project_path, action, req.message.as_deref(), req.index.unwrap_or(0)))
+}
+
+// =============================================================================
+// Test Runner Endpoints
+// =============================================================================
+
 /// Check if playtest has stale state (heartbeat timeout) and clear it
 /// Returns true if state was cleared
 async fn clear_stale_playtest_state(state: &Arc<AppState>) -> bool {
@@ -3622,11 +5134,7 @@ async fn handle_test_start(State(state): State<Arc<AppState>>) -> impl IntoRespo
 
     // Send command to plugin to start capture
     let request_id = Uuid::new_v4();
-    let request = PluginRequest {
-        id: request_id,
-        command: "test:start".to_string(),
-        payload: serde_json::json!({}),
-    };
+    let request = PluginCommand::TestStart(NoPayload {}).request(request_id);
 
     // Create response channel
     let (tx, mut rx) = mpsc::unbounded_channel();
@@ -3634,7 +5142,7 @@ async fn handle_test_start(State(state): State<Arc<AppState>>) -> impl IntoRespo
 
     // Queue the request
     state.request_queue.lock().await.push_back(request);
-    state.trigger.send(()).ok();
+    state.wakeups.notify_all();
 
     // Wait for response with timeout
     let timeout = tokio::time::Duration::from_secs(30);
@@ -3676,11 +5184,7 @@ async fn handle_test_start(State(state): State<Arc<AppState>>) -> impl IntoRespo
 async fn handle_test_status(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     // Send command to plugin to get current output
     let request_id = Uuid::new_v4();
-    let request = PluginRequest {
-        id: request_id,
-        command: "test:output".to_string(),
-        payload: serde_json::json!({}),
-    };
+    let request = PluginCommand::TestOutput(NoPayload {}).request(request_id);
 
     // Create response channel
     let (tx, mut rx) = mpsc::unbounded_channel();
@@ -3688,7 +5192,7 @@ async fn handle_test_status(State(state): State<Arc<AppState>>) -> impl IntoResp
 
     // Queue the request
     state.request_queue.lock().await.push_back(request);
-    state.trigger.send(()).ok();
+    state.wakeups.notify_all();
 
     // Wait for response with timeout
     let timeout = tokio::time::Duration::from_secs(10);
@@ -3728,11 +5232,7 @@ async fn handle_test_status(State(state): State<Arc<AppState>>) -> impl IntoResp
 async fn handle_test_stop(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     // Send command to plugin to stop capture
     let request_id = Uuid::new_v4();
-    let request = PluginRequest {
-        id: request_id,
-        command: "test:stop".to_string(),
-        payload: serde_json::json!({}),
-    };
+    let request = PluginCommand::TestStop(NoPayload {}).request(request_id);
 
     // Create response channel
     let (tx, mut rx) = mpsc::unbounded_channel();
@@ -3740,7 +5240,7 @@ async fn handle_test_stop(State(state): State<Arc<AppState>>) -> impl IntoRespon
 
     // Queue the request
     state.request_queue.lock().await.push_back(request);
-    state.trigger.send(()).ok();
+    state.wakeups.notify_all();
 
     // Wait for response with timeout
     let timeout = tokio::time::Duration::from_secs(30);
@@ -3815,60 +5315,6 @@ async fn handle_test_playtest_status(State(state): State<Arc<AppState>>) -> impl
 // Bot Controller Endpoints (AI-powered automated gameplay testing)
 // ============================================================================
 
-/// Generic bot command request
-#[derive(Debug, Deserialize)]
-struct BotCommandRequest {
-    #[serde(rename = "type")]
-    command_type: String,
-    command: String,
-    #[serde(default)]
-    args: serde_json::Value,
-}
-
-/// Bot movement request
-#[derive(Debug, Deserialize)]
-struct BotMoveRequest {
-    #[serde(default)]
-    position: Option<serde_json::Value>,
-    #[serde(default)]
-    object: Option<String>,
-    #[serde(rename = "objectName", default)]
-    object_name: Option<String>,
-}
-
-/// Bot action request
-#[derive(Debug, Deserialize)]
-struct BotActionRequest {
-    action: String,
-    #[serde(default)]
-    name: Option<String>,
-    #[serde(rename = "toolName", default)]
-    tool_name: Option<String>,
-    #[serde(rename = "objectName", default)]
-    object_name: Option<String>,
-}
-
-/// Bot observe request
-#[derive(Debug, Deserialize)]
-struct BotObserveRequest {
-    #[serde(rename = "type", default = "default_observe_type")]
-    observe_type: String,
-    #[serde(default)]
-    radius: Option<f64>,
-    #[serde(default)]
-    query: Option<String>,
-}
-
-fn default_observe_type() -> String {
-    "state".to_string()
-}
-
-/// Bot query server request
-#[derive(Debug, Deserialize)]
-struct BotQueryServerRequest {
-    code: String,
-}
-
 /// Helper function to send a bot command via the bot queue (for in-game execution)
 /// This routes commands through BotRunnerServer -> BotRunnerClient instead of the plugin
 async fn send_bot_command_via_queue(
@@ -3936,6 +5382,7 @@ async fn send_bot_command(
         id: request_id,
         command: command.to_string(),
         payload,
+        queued_at: Instant::now(),
     };
 
     // Create response channel
@@ -3944,7 +5391,7 @@ async fn send_bot_command(
 
     // Queue the request
     state.request_queue.lock().await.push_back(request);
-    state.trigger.send(()).ok();
+    state.wakeups.notify_all();
 
     // Wait for response with timeout (longer timeout for movement commands)
     let timeout = if command == "bot:move" {
@@ -4012,14 +5459,10 @@ async fn handle_bot_state(State(state): State<Arc<AppState>>) -> impl IntoRespon
     }
 }
 
-/// Handle bot movement command
-async fn handle_bot_move(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<BotMoveRequest>,
-) -> impl IntoResponse {
-    // Format command for BotController.executeCommand()
-    // BotController expects: { type, command, args }
-    let command = if req.position.is_some() {
+/// Format a move request for BotController.executeCommand(), which expects
+/// `{ type, command, args }`
+pub(crate) fn bot_move_command(req: BotMoveRequest) -> serde_json::Value {
+    if req.position.is_some() {
         serde_json::json!({
             "type": "move",
             "command": "moveTo",
@@ -4035,7 +5478,15 @@ async fn handle_bot_move(
                 "objectName": req.object_name.or(req.object)
             }
         })
-    };
+    }
+}
+
+/// Handle bot movement command
+async fn handle_bot_move(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<BotMoveRequest>,
+) -> impl IntoResponse {
+    let command = bot_move_command(req);
 
     match send_bot_command_via_queue(&state, command).await {
         Ok(data) => (StatusCode::OK, Json(data)),
@@ -4043,13 +5494,8 @@ async fn handle_bot_move(
     }
 }
 
-/// Handle bot action command
-async fn handle_bot_action(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<BotActionRequest>,
-) -> impl IntoResponse {
-    // Format command for BotController.executeCommand()
-    // BotController expects: { type, command, args }
+/// Format an action request for BotController.executeCommand()
+pub(crate) fn bot_action_command(req: BotActionRequest) -> serde_json::Value {
     // Map action names to BotController command names
     let (cmd_type, cmd_name) = match req.action.as_str() {
         "jump" => ("move", "jump"),
@@ -4062,14 +5508,22 @@ async fn handle_bot_action(
         other => ("action", other),
     };
 
-    let command = serde_json::json!({
+    serde_json::json!({
         "type": cmd_type,
         "command": cmd_name,
         "args": {
             "name": req.name.or(req.tool_name),
             "objectName": req.object_name
         }
-    });
+    })
+}
+
+/// Handle bot action command
+async fn handle_bot_action(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<BotActionRequest>,
+) -> impl IntoResponse {
+    let command = bot_action_command(req);
 
     match send_bot_command_via_queue(&state, command).await {
         Ok(data) => (StatusCode::OK, Json(data)),
@@ -4077,13 +5531,8 @@ async fn handle_bot_action(
     }
 }
 
-/// Handle bot observation command
-async fn handle_bot_observe(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<BotObserveRequest>,
-) -> impl IntoResponse {
-    // Format command for BotController.executeCommand()
-    // BotController expects: { type, command, args }
+/// Format an observe request for BotController.executeCommand()
+pub(crate) fn bot_observe_command(req: BotObserveRequest) -> serde_json::Value {
     // Map observe_type to BotController command names
     let cmd_name = match req.observe_type.as_str() {
         "state" => "getState",
@@ -4094,14 +5543,22 @@ async fn handle_bot_observe(
         other => other,
     };
 
-    let command = serde_json::json!({
+    serde_json::json!({
         "type": "observe",
         "command": cmd_name,
         "args": {
             "radius": req.radius,
             "query": req.query
         }
-    });
+    })
+}
+
+/// Handle bot observation command
+async fn handle_bot_observe(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<BotObserveRequest>,
+) -> impl IntoResponse {
+    let command = bot_observe_command(req);
 
     match send_bot_command_via_queue(&state, command).await {
         Ok(data) => (StatusCode::OK, Json(data)),
@@ -4170,6 +5627,34 @@ async fn handle_bot_queue(
 /// Get next pending command for bot (GET /bot/pending)
 async fn handle_bot_pending(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     let mut queue = state.bot_command_queue.lock().await;
+    let mut faults = state.bot_faults.lock().await;
+
+    // Injected faults can hold the front command back or drop it
+    while let Some(front) = queue.front() {
+        let id = front.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        if front.get("bypassFaults").and_then(|v| v.as_bool()).unwrap_or(false) {
+            break;
+        }
+        match faults.on_pending(&id) {
+            faults::Delivery::Deliver => break,
+            faults::Delivery::Hold => {
+                return Json(serde_json::json!({
+                    "success": true,
+                    "command": null
+                }));
+            }
+            faults::Delivery::Drop => {
+                queue.pop_front();
+                if let Ok(uuid) = Uuid::parse_str(&id) {
+                    let mut results = state.bot_command_results.write().await;
+                    results.insert(uuid, faults::dropped_result(&id));
+                }
+                tracing::info!("Dropped bot command {} (fault injection)", id);
+            }
+        }
+    }
+    drop(faults);
+
     if let Some(cmd) = queue.pop_front() {
         Json(serde_json::json!({
             "success": true,
@@ -4324,21 +5809,37 @@ async fn handle_bot_lifecycle(
 // Console Streaming Endpoints (for E2E Testing Mode)
 // ============================================================================
 
-/// Request to push console message(s) from plugin
-#[derive(Debug, Deserialize)]
-struct ConsolePushRequest {
-    messages: Vec<ConsoleMessage>,
-}
-
 /// Push console messages from plugin to server
 async fn handle_console_push(
     State(state): State<Arc<AppState>>,
     Json(req): Json<ConsolePushRequest>,
 ) -> impl IntoResponse {
+    let count = push_console_messages(&state, req.messages).await;
+
+    Json(serde_json::json!({
+        "success": true,
+        "received": count
+    }))
+}
+
+/// Broadcast messages to subscribers and append them to the console buffer
+async fn push_console_messages(state: &AppState, mut messages: Vec<ConsoleMessage>) -> usize {
+    // Link errors and warnings back to the scripts they came from
+    let wanted = |msg: &ConsoleMessage| msg.message_type != "info" && msg.locations.is_empty();
+    if messages.iter().any(wanted) {
+        let project_dirs = correlation_projects(state).await;
+        for msg in messages.iter_mut().filter(|m| wanted(m)) {
+            msg.locations = correlate::correlate(&msg.message, &project_dirs);
+        }
+    }
+
     let mut buffer = state.console_buffer.write().await;
-    let count = req.messages.len();
+    let count = messages.len();
+    let received_at = nightly::now_secs();
+
+    for mut msg in messages {
+        msg.received_at = Some(received_at);
 
-    for msg in req.messages {
         // Broadcast to any active subscribers
         let _ = state.console_tx.send(msg.clone());
 
@@ -4348,34 +5849,84 @@ async fn handle_console_push(
         }
         buffer.push_back(msg);
     }
+    count
+}
 
-    Json(serde_json::json!({
-        "success": true,
-        "received": count
-    }))
+/// Projects console locations are resolved against: registered places and
+/// VS Code workspaces, then the server's working directory
+async fn correlation_projects(state: &AppState) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for place in state.place_registry.read().await.values() {
+        dirs.push(PathBuf::from(&place.project_dir));
+    }
+    for workspace in state.vscode_workspaces.read().await.values() {
+        dirs.push(PathBuf::from(&workspace.workspace_dir));
+    }
+    if let Ok(cwd) = std::env::current_dir() {
+        dirs.push(cwd);
+    }
+    let mut seen = std::collections::HashSet::new();
+    dirs.retain(|dir| seen.insert(dir.clone()));
+    dirs
 }
 
-/// Get console message history
+/// Get console message history, filtered by level, source, text and time
 async fn handle_console_history(
     State(state): State<Arc<AppState>>,
     Query(params): Query<ConsoleHistoryQuery>,
 ) -> impl IntoResponse {
+    let filter = match console::ConsoleFilter::new(&params, nightly::now_secs()) {
+        Ok(filter) => filter,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "success": false, "error": e }))),
+    };
     let buffer = state.console_buffer.read().await;
     let limit = params.limit.unwrap_or(100).min(CONSOLE_BUFFER_SIZE);
 
-    // Get last N messages
-    let messages: Vec<&ConsoleMessage> = buffer.iter().rev().take(limit).collect();
-    let messages: Vec<&ConsoleMessage> = messages.into_iter().rev().collect();
+    // Newest N matching messages, oldest first
+    let (messages, matched) = filter.select(buffer.iter(), Some(limit));
 
-    Json(serde_json::json!({
-        "messages": messages,
-        "total": buffer.len()
-    }))
+    let response = ConsoleHistoryResponse { messages, total: buffer.len(), matched };
+    (StatusCode::OK, Json(serde_json::to_value(response).unwrap_or_default()))
 }
 
-#[derive(Debug, Deserialize)]
-struct ConsoleHistoryQuery {
-    limit: Option<usize>,
+/// Write the console buffer, filtered like the history, to a file
+async fn handle_console_export(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ConsoleExportRequest>,
+) -> impl IntoResponse {
+    let error = |status: StatusCode, message: String| (status, Json(serde_json::json!({ "success": false, "error": message })));
+    let filter = match console::ConsoleFilter::new(&req.filter, nightly::now_secs()) {
+        Ok(filter) => filter,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e),
+    };
+    let jsonl = match console::is_jsonl(&req.path, req.format.as_deref()) {
+        Ok(jsonl) => jsonl,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e),
+    };
+    let (messages, _) = filter.select(state.console_buffer.read().await.iter(), req.filter.limit);
+
+    let path = PathBuf::from(&req.path);
+    let content = if jsonl { console::format_jsonl(&messages) } else { console::format_text(&messages) };
+    let written = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, content));
+    if let Err(e) = written {
+        return error(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write {}: {}", req.path, e));
+    }
+
+    let path = std::fs::canonicalize(&path).unwrap_or(path);
+    tracing::info!("Exported {} console messages to {}", messages.len(), path.display());
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "success": true,
+            "path": path.to_string_lossy(),
+            "exported": messages.len(),
+            "format": if jsonl { "jsonl" } else { "text" }
+        })),
+    )
 }
 
 /// Subscribe to console messages via Server-Sent Events
@@ -4429,13 +5980,7 @@ async fn handle_run_code(
 ) -> impl IntoResponse {
     let request_id = Uuid::new_v4();
     tracing::info!("run:code request {} - queuing command", request_id);
-    let request = PluginRequest {
-        id: request_id,
-        command: "run:code".to_string(),
-        payload: serde_json::json!({
-            "code": req.code
-        }),
-    };
+    let request = PluginCommand::RunCode(protocol::RunCode { code: req.code }).request(request_id);
 
     // Create response channel
     let (tx, mut rx) = mpsc::unbounded_channel();
@@ -4448,7 +5993,7 @@ async fn handle_run_code(
         queue.len()
     };
     tracing::info!("run:code request {} - queued (queue length: {})", request_id, queue_len);
-    state.trigger.send(()).ok();
+    state.wakeups.notify_all();
 
     // Wait for response with timeout
     let timeout = tokio::time::Duration::from_secs(30);
@@ -4503,13 +6048,7 @@ async fn handle_read_properties(
 ) -> impl IntoResponse {
     let request_id = Uuid::new_v4();
     tracing::info!("read-properties:get request {} - path: {}", request_id, req.path);
-    let request = PluginRequest {
-        id: request_id,
-        command: "read-properties:get".to_string(),
-        payload: serde_json::json!({
-            "path": req.path
-        }),
-    };
+    let request = PluginCommand::ReadProperties(protocol::InstancePath { path: req.path }).request(request_id);
 
     // Create response channel
     let (tx, mut rx) = mpsc::unbounded_channel();
@@ -4522,7 +6061,7 @@ async fn handle_read_properties(
         queue.len()
     };
     tracing::info!("read-properties:get request {} - queued (queue length: {})", request_id, queue_len);
-    state.trigger.send(()).ok();
+    state.wakeups.notify_all();
 
     // Wait for response with timeout
     let timeout = tokio::time::Duration::from_secs(30);
@@ -4586,14 +6125,7 @@ async fn handle_explore_hierarchy(
         req.path,
         depth
     );
-    let request = PluginRequest {
-        id: request_id,
-        command: "explore-hierarchy:get".to_string(),
-        payload: serde_json::json!({
-            "path": req.path,
-            "depth": depth
-        }),
-    };
+    let request = PluginCommand::ExploreHierarchy(protocol::ExploreHierarchy { path: req.path, depth }).request(request_id);
 
     // Create response channel
     let (tx, mut rx) = mpsc::unbounded_channel();
@@ -4610,7 +6142,7 @@ async fn handle_explore_hierarchy(
         request_id,
         queue_len
     );
-    state.trigger.send(()).ok();
+    state.wakeups.notify_all();
 
     // Wait for response with timeout (longer for deep hierarchies)
     let timeout = tokio::time::Duration::from_secs(60);
@@ -4682,16 +6214,13 @@ async fn handle_find_instances(
         req.parent,
         limit
     );
-    let request = PluginRequest {
-        id: request_id,
-        command: "find-instances:search".to_string(),
-        payload: serde_json::json!({
-            "className": req.class_name,
-            "name": req.name,
-            "parent": req.parent,
-            "limit": limit
-        }),
-    };
+    let request = PluginCommand::FindInstances(protocol::FindInstances {
+        class_name: req.class_name,
+        name: req.name,
+        parent: req.parent,
+        limit,
+    })
+    .request(request_id);
 
     // Create response channel
     let (tx, mut rx) = mpsc::unbounded_channel();
@@ -4708,7 +6237,7 @@ async fn handle_find_instances(
         request_id,
         queue_len
     );
-    state.trigger.send(()).ok();
+    state.wakeups.notify_all();
 
     // Wait for response with timeout (longer for searching large hierarchies)
     let timeout = tokio::time::Duration::from_secs(60);
@@ -4775,14 +6304,8 @@ async fn handle_insert_model(
         req.asset_id,
         req.parent
     );
-    let request = PluginRequest {
-        id: request_id,
-        command: "insert:model".to_string(),
-        payload: serde_json::json!({
-            "assetId": req.asset_id,
-            "parent": req.parent
-        }),
-    };
+    let request = PluginCommand::InsertModel(protocol::InsertModel { asset_id: req.asset_id, parent: req.parent })
+        .request(request_id);
 
     // Create response channel
     let (tx, mut rx) = mpsc::unbounded_channel();
@@ -4799,7 +6322,7 @@ async fn handle_insert_model(
         request_id,
         queue_len
     );
-    state.trigger.send(()).ok();
+    state.wakeups.notify_all();
 
     // Wait for response with timeout (marketplace fetch may take time)
     let timeout = tokio::time::Duration::from_secs(60);
@@ -4848,23 +6371,195 @@ async fn handle_insert_model(
 
 /// Start the server
 pub async fn run_server(config: ServerConfig) -> anyhow::Result<()> {
+    // Anyone who can reach a non-loopback address could run code in Studio
+    if !is_loopback_host(&config.host) && config.token.is_none() {
+        anyhow::bail!(
+            "Listening on {} requires token auth: start with --auth or set {}",
+            config.host,
+            rbxsync_core::TOKEN_ENV
+        );
+    }
+    let tls = match &config.tls {
+        Some(tls) => Some(
+            axum_server::tls_rustls::RustlsConfig::from_pem_file(&tls.cert, &tls.key)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to load TLS certificate or key: {}", e))?,
+        ),
+        None => None,
+    };
+
+    crashes::install_panic_hook();
     let state = AppState::new();
+    if let Some(token) = config.token.clone() {
+        let _ = state.auth_token.set(token);
+    }
+    state
+        .auth_all_methods
+        .store(!is_loopback_host(&config.host), std::sync::atomic::Ordering::Relaxed);
+    let mut providers: Vec<Arc<dyn auth::AuthProvider>> = Vec::new();
+    if config.users {
+        let path = rbxsync_core::auth::users_path().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+        let users = auth::KeychainUsers::load(&path);
+        tracing::info!("Accepting keychain tokens for {} team user(s)", users.len());
+        providers.push(Arc::new(users));
+    }
+    if let Some(oidc) = &config.oidc {
+        let provider = oidc::OidcProvider::discover(oidc.clone())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to reach OIDC issuer {}: {:#}", oidc.issuer, e))?;
+        providers.push(Arc::new(provider));
+    }
+    let _ = state.auth_providers.set(providers);
+    if config.token.is_some() || config.users || config.oidc.is_some() {
+        if let Some(path) = audit::request_audit_path() {
+            let _ = state.request_audit.set(path);
+        }
+    }
     let router = create_router(state.clone());
 
+    // Enter safe mode if the last run crashed mid-operation
+    if let Ok(cwd) = std::env::current_dir() {
+        recovery::check_safe_mode(&state, &cwd.to_string_lossy()).await;
+    }
+
     // Start background task to process file changes for live sync
     let state_for_watcher = state.clone();
     tokio::spawn(async move {
         process_file_changes(state_for_watcher).await;
     });
 
-    let addr = format!("{}:{}", config.host, config.port);
+    // Suspend watchers and loops while nothing is connected
+    tokio::spawn(idle::run_idle_monitor(state.clone()));
+
+    // Nightly builds for projects that configure them
+    tokio::spawn(nightly::run_scheduler(state.clone()));
+
+    // Full resync of live-synced projects after a branch switch
+    tokio::spawn(branch::run_watcher(state.clone()));
+
+    // Scheduled incremental extractions for projects that ask for them
+    tokio::spawn(autosave::run_scheduler(state.clone()));
+
+    let addr = if config.host.contains(':') && !config.host.starts_with('[') {
+        format!("[{}]:{}", config.host, config.port)
+    } else {
+        format!("{}:{}", config.host, config.port)
+    };
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    tracing::info!("RbxSync server listening on {}", addr);
-    axum::serve(listener, router).await?;
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    tracing::info!("RbxSync server listening on {}://{}", scheme, addr);
+    state.port.store(config.port, std::sync::atomic::Ordering::Relaxed);
+    state.tls.store(tls.is_some(), std::sync::atomic::Ordering::Relaxed);
+
+    // Record the port so tools of the same version find it after a failover
+    if let Some(path) = rbxsync_core::registry_path() {
+        if let Err(e) = rbxsync_core::register_server(&path, server_entry(config.port)) {
+            tracing::warn!("Failed to update server registry: {}", e);
+        }
+    }
+    // Started inside a project: tools in it should find this server, and
+    // what it had queued before a restart comes back right away
+    if let Ok(cwd) = std::env::current_dir() {
+        if cwd.join("rbxsync.json").exists() {
+            claim_workspace(&state, &cwd.to_string_lossy());
+            persist::restore_project(&state, &cwd.to_string_lossy()).await;
+        }
+    }
+    tokio::spawn(persist::run_saver(state.clone()));
+
+    // Startup self-check of the other tools' ports
+    tokio::spawn(async move {
+        for status in ports::check_ports(config.port).await {
+            if status.port == config.port || !status.in_use {
+                continue;
+            }
+            if status.default_for.starts_with("rbxsync") && !status.is_rbxsync() {
+                tracing::warn!(
+                    "Port {} (rbxsync) is held by {}; the plugin may connect to it instead",
+                    status.port,
+                    status.owner_label()
+                );
+            } else {
+                tracing::info!("Port {} in use by {}", status.port, status.owner_label());
+            }
+        }
+    });
+
+    // Ctrl+C: save state and leave the registry clean, then exit as before
+    let state_for_signal = state.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            persist::save_all(&state_for_signal).await;
+            unregister_self();
+            std::process::exit(130);
+        }
+    });
+
+    match tls {
+        Some(tls) => {
+            axum_server::from_tcp_rustls(listener.into_std()?, tls)
+                .serve(router.into_make_service())
+                .await?
+        }
+        None => axum::serve(listener, router).await?,
+    }
 
     Ok(())
 }
 
+/// Projects whose `.rbxsync/server.json` this server wrote
+static CLAIMED_WORKSPACES: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+/// Registry entry describing this process
+fn server_entry(port: u16) -> rbxsync_core::ServerEntry {
+    rbxsync_core::ServerEntry {
+        port,
+        pid: std::process::id(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        cwd: std::env::current_dir().map(|d| d.to_string_lossy().to_string()).unwrap_or_default(),
+        started_at: nightly::now_secs(),
+    }
+}
+
+/// Point a project's `.rbxsync/server.json` at this server, once per project
+pub(crate) fn claim_workspace(state: &AppState, project_dir: &str) {
+    let port = state.port.load(std::sync::atomic::Ordering::Relaxed);
+    let dir = std::path::Path::new(project_dir);
+    if port == 0 || !dir.is_dir() {
+        return;
+    }
+    let Ok(mut claimed) = CLAIMED_WORKSPACES.lock() else {
+        return;
+    };
+    if claimed.iter().any(|d| d == project_dir) {
+        return;
+    }
+    match rbxsync_core::write_workspace_server(dir, &server_entry(port)) {
+        Ok(()) => claimed.push(project_dir.to_string()),
+        Err(e) => tracing::warn!("Failed to write {} in {}: {}", rbxsync_core::WORKSPACE_SERVER_FILE, project_dir, e),
+    }
+}
+
+/// Drop this process's entries from the server registry and the port files
+/// it wrote into projects
+fn unregister_self() {
+    if let Some(path) = rbxsync_core::registry_path() {
+        for entry in rbxsync_core::load_registry(&path) {
+            if entry.pid == std::process::id() {
+                let _ = rbxsync_core::unregister_server(&path, entry.port);
+            }
+        }
+    }
+    let claimed = CLAIMED_WORKSPACES.lock().map(|c| c.clone()).unwrap_or_default();
+    for dir in claimed {
+        let dir = std::path::Path::new(&dir);
+        // Another server may have claimed the project since
+        if rbxsync_core::read_workspace_server(dir).is_some_and(|e| e.pid == std::process::id()) {
+            let _ = std::fs::remove_file(dir.join(rbxsync_core::WORKSPACE_SERVER_FILE));
+        }
+    }
+}
+
 /// Background task to process file changes and send sync commands to the plugin
 async fn process_file_changes(state: Arc<AppState>) {
     use std::collections::HashMap;
@@ -4874,11 +6569,22 @@ async fn process_file_changes(state: Arc<AppState>) {
     let debounce_duration = Duration::from_millis(300);
 
     loop {
+        // Nothing to debounce while idle - park until a client connects
+        if idle::is_idle(&state) {
+            pending.clear();
+            state.idle_wake.notified().await;
+            continue;
+        }
+
         // Try to receive file changes
         {
             let mut rx = state.file_change_rx.lock().await;
-            while let Ok(change) = rx.try_recv() {
-                // Debounce: update pending changes
+            while let Ok(mut change) = rx.try_recv() {
+                state.metrics.file_watcher_events.inc();
+                // Debounce: update pending changes, keeping the total size delta
+                if let Some((previous, _)) = pending.get(&change.path) {
+                    change.size_delta = previous.size_delta.zip(change.size_delta).map(|(a, b)| a + b);
+                }
                 pending.insert(change.path.clone(), (change, Instant::now()));
             }
         }
@@ -4896,6 +6602,21 @@ async fn process_file_changes(state: Arc<AppState>) {
             }
         });
 
+        // The other half of a rename seen as delete + create may still be
+        // debouncing; take it along so the two pair up
+        let halves: HashSet<(String, u64)> = ready_changes.iter().filter_map(file_watcher::rename_key).collect();
+        if !halves.is_empty() {
+            pending.retain(|_, (change, _)| {
+                if file_watcher::rename_key(change).is_some_and(|key| halves.contains(&key)) {
+                    ready_changes.push(change.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+            file_watcher::pair_renames(&mut ready_changes);
+        }
+
         // Send ready changes to plugin (skip if live sync is paused during extraction)
         if !ready_changes.is_empty() {
             // Check if live sync is paused (during extraction)
@@ -4903,30 +6624,78 @@ async fn process_file_changes(state: Arc<AppState>) {
                 tracing::debug!("Live sync paused, skipping {} file changes", ready_changes.len());
                 continue;
             }
+            // Paused by the user: keep them for /sync/resume
+            if state.sync_pause.hold(&ready_changes) {
+                tracing::debug!("Live sync paused by user, holding {} file changes", ready_changes.len());
+                continue;
+            }
+
+            // Notify /events subscribers with one batch per project
+            let mut batches: HashMap<String, Vec<file_watcher::FileEvent>> = HashMap::new();
+            for change in &ready_changes {
+                batches.entry(change.project_dir.clone()).or_default().push(change.into());
+            }
+            for (project_dir, changes) in batches {
+                if changes.iter().any(|c| rbxsync_core::sourcemap::affects_sourcemap(c.kind, &c.path))
+                    && sourcemap_enabled(&load_project_config(&project_dir))
+                {
+                    regenerate_sourcemap(&project_dir);
+                }
+                // Keep an existing project summary current; only changed files are reread
+                let dir = PathBuf::from(&project_dir);
+                if summary::has_summary(&dir) {
+                    tokio::task::spawn_blocking(move || {
+                        if let Err(e) = summary::generate(&dir) {
+                            tracing::warn!("Failed to update project summary: {}", e);
+                        }
+                    });
+                }
+                let _ = state.file_events_tx.send(file_watcher::FileEventBatch { project_dir, changes });
+            }
+
+            // Projects watched only for /events aren't live-synced
+            let event_only = state.file_watcher_state.read().await.event_only_dirs.clone();
+            let live_changes: Vec<&file_watcher::FileChange> = ready_changes
+                .iter()
+                .filter(|c| !event_only.contains(&c.project_dir))
+                .collect();
 
             let mut operations = Vec::new();
 
-            for change in &ready_changes {
-                if let Some(op) = file_watcher::process_file_change(change) {
+            for change in &live_changes {
+                let ops = file_watcher::process_file_change(change);
+                if !ops.is_empty() {
                     tracing::info!("Live sync: {:?} -> {:?}", change.kind, change.path);
-                    operations.push(op);
+                    operations.extend(ops);
                 }
             }
 
             if !operations.is_empty() {
                 // Find project dir from first change
-                let project_dir = ready_changes.first().map(|c| c.project_dir.clone());
+                let project_dir = live_changes.first().map(|c| c.project_dir.clone());
+
+                // Don't push half-written files from a project in safe mode
+                if let Some(ref dir) = project_dir {
+                    if state.safe_mode_projects.read().await.contains_key(dir) {
+                        tracing::warn!("Project {} is in safe mode, skipping {} live sync operations", dir, operations.len());
+                        continue;
+                    }
+                    overrides::apply_to_operations(dir, &mut operations);
+                    skip_protected_deletes(&get_protected_paths(&load_project_config(dir)), &mut operations);
+                    if operations.is_empty() {
+                        continue;
+                    }
+                }
 
                 // Queue batch sync request to plugin
+                let operation_count = operations.len();
+                state.metrics.sync_batch_size.observe(operation_count as f64);
                 let request_id = Uuid::new_v4();
-                let plugin_request = PluginRequest {
-                    id: request_id,
-                    command: "sync:batch".to_string(),
-                    payload: serde_json::json!({
-                        "operations": operations,
-                        "source": "file_watcher"  // Mark as from file watcher
-                    }),
-                };
+                let plugin_request = PluginCommand::SyncBatch(protocol::SyncBatch {
+                    operations,
+                    source: Some("file_watcher".to_string()), // Mark as from file watcher
+                })
+                .request(request_id);
 
                 // Send to project-specific queue if we know the project
                 // Only fall back to global queue if project queue doesn't exist
@@ -4934,8 +6703,11 @@ async fn process_file_changes(state: Arc<AppState>) {
                 if let Some(ref dir) = project_dir {
                     let mut queues = state.project_queues.write().await;
                     if let Some(queue) = queues.get_mut(dir) {
-                        tracing::info!("Queued {} operations for project {}", operations.len(), dir);
-                        queue.push_back(plugin_request.clone());
+                        tracing::info!("Queued {} operations for project {}", operation_count, dir);
+                        let superseded = priority::enqueue(queue, plugin_request.clone());
+                        if superseded > 0 {
+                            tracing::info!("Dropped {} queued operation(s) superseded by newer edits", superseded);
+                        }
                         sent = true;
                     } else {
                         tracing::warn!("No queue for project {}, available queues: {:?}", dir, queues.keys().collect::<Vec<_>>());
@@ -4948,8 +6720,11 @@ async fn process_file_changes(state: Arc<AppState>) {
                     queue.push_back(plugin_request);
                 }
 
-                // Trigger long-polling requests to wake up
-                let _ = state.trigger.send(());
+                // Wake only the plugins that can take the batch
+                match project_dir.as_deref() {
+                    Some(dir) if sent => state.wakeups.notify_project(dir),
+                    _ => state.wakeups.notify_all(),
+                }
             }
         }
 
@@ -0,0 +1,460 @@
+//! Structured log files
+//!
+//! Everything the server logs through `tracing` is also written as JSON lines
+//! to `.rbxsync/logs/rbxsync.log` in each project, so a server running in the
+//! background still leaves a trail. An entry goes to the project named by a
+//! `project` field on the event (or an enclosing span), else to the project
+//! whose directory appears in the message, else to every project the server
+//! is serving. Files rotate at `MAX_LOG_BYTES`, keeping `ROTATED_FILES` old
+//! ones. Recent entries are also kept in memory for `/logs/query` and
+//! `rbxsync logs --follow`.
+
+use std::collections::{BTreeSet, VecDeque};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{extract::Query, http::StatusCode, response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::normalize_path;
+
+/// Log directory, relative to the project root
+pub const LOGS_DIR: &str = ".rbxsync/logs";
+
+/// Current log file within `LOGS_DIR`
+pub const LOG_FILE: &str = "rbxsync.log";
+
+/// Size at which the current file is rotated to `rbxsync.log.1`
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated files kept (`rbxsync.log.1` is the newest)
+const ROTATED_FILES: usize = 4;
+
+/// Entries kept in memory for live queries
+const RECENT_ENTRIES: usize = 2000;
+
+/// Entries returned by `/logs/query` without a limit
+const DEFAULT_LIMIT: usize = 200;
+
+/// Projects the server is serving, by normalized directory
+static PROJECTS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Entries logged by this process, oldest first
+static RECENT: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+
+static NEXT_SEQ: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// Increases with every entry this server process logs
+    pub seq: u64,
+    /// UTC, e.g. `2026-01-31T12:00:00.250Z`
+    pub timestamp: String,
+    /// `ERROR`, `WARN`, `INFO`, `DEBUG` or `TRACE`
+    pub level: String,
+    /// Module that logged the entry (e.g. `assets`, `file_watcher`)
+    pub subsystem: String,
+    pub message: String,
+    /// Project the entry was routed to; `None` for server-wide entries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Structured fields other than the message
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Start writing server-wide entries to a project's log
+pub fn register_project(project_dir: &str) {
+    if let Ok(mut projects) = PROJECTS.lock() {
+        projects.insert(normalize_path(project_dir));
+    }
+}
+
+/// Path of a project's current log file
+pub fn log_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(LOGS_DIR).join(LOG_FILE)
+}
+
+/// Subsystem for a tracing target: the module below the crate root, or the
+/// crate name without its `rbxsync_` prefix for the root itself
+pub fn subsystem(target: &str) -> String {
+    let mut segments = target.split("::");
+    let krate = segments.next().unwrap_or_default();
+    match segments.next() {
+        Some(module) => module.to_string(),
+        None => krate.strip_prefix("rbxsync_").unwrap_or(krate).to_string(),
+    }
+}
+
+/// Rank used by the level filter; higher is more severe
+fn severity(level: &str) -> Option<u8> {
+    match level.to_ascii_uppercase().as_str() {
+        "ERROR" => Some(4),
+        "WARN" | "WARNING" => Some(3),
+        "INFO" => Some(2),
+        "DEBUG" => Some(1),
+        "TRACE" => Some(0),
+        _ => None,
+    }
+}
+
+fn timestamp() -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let (date, time) = rbxsync_core::defines::utc_stamps(now.as_secs());
+    format!(
+        "{}-{}-{}T{}:{}:{}.{:03}Z",
+        &date[..4],
+        &date[4..6],
+        &date[6..],
+        &time[..2],
+        &time[2..4],
+        &time[4..],
+        now.subsec_millis()
+    )
+}
+
+/// Append a line to a log file, rotating it first once it is full
+fn append_line(path: &Path, line: &str) -> std::io::Result<()> {
+    if std::fs::metadata(path).map(|m| m.len() >= MAX_LOG_BYTES).unwrap_or(false) {
+        rotate(path)?;
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    PathBuf::from(format!("{}.{}", path.display(), n))
+}
+
+fn rotate(path: &Path) -> std::io::Result<()> {
+    let _ = std::fs::remove_file(rotated_path(path, ROTATED_FILES));
+    for n in (1..ROTATED_FILES).rev() {
+        let from = rotated_path(path, n);
+        if from.exists() {
+            std::fs::rename(&from, rotated_path(path, n + 1))?;
+        }
+    }
+    std::fs::rename(path, rotated_path(path, 1))
+}
+
+/// Filters shared by file and memory queries
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct LogQuery {
+    #[serde(default, alias = "projectDir")]
+    pub project_dir: Option<String>,
+    /// Minimum level (`warn` returns warnings and errors)
+    #[serde(default)]
+    pub level: Option<String>,
+    #[serde(default)]
+    pub subsystem: Option<String>,
+    /// Only entries logged after this sequence number, from memory. Used by
+    /// `rbxsync logs --follow`; without it, project queries read the files.
+    #[serde(default)]
+    pub after: Option<u64>,
+    /// Newest entries to return
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl LogQuery {
+    fn matches(&self, entry: &LogEntry) -> bool {
+        if let Some(level) = &self.level {
+            if severity(&entry.level).unwrap_or(0) < severity(level).unwrap_or(0) {
+                return false;
+            }
+        }
+        if let Some(subsystem) = &self.subsystem {
+            if !entry.subsystem.eq_ignore_ascii_case(subsystem) {
+                return false;
+            }
+        }
+        self.after.is_none_or(|after| entry.seq > after)
+    }
+}
+
+/// Keep the newest `limit` entries
+fn newest(mut entries: Vec<LogEntry>, limit: usize) -> Vec<LogEntry> {
+    let skip = entries.len().saturating_sub(limit);
+    entries.drain(..skip);
+    entries
+}
+
+/// Entries from a project's log files, oldest first
+pub fn read_project_log(project_dir: &Path, query: &LogQuery) -> Vec<LogEntry> {
+    let current = log_path(project_dir);
+    let files = (1..=ROTATED_FILES)
+        .rev()
+        .map(|n| rotated_path(&current, n))
+        .chain(std::iter::once(current.clone()));
+
+    let mut entries = Vec::new();
+    for path in files {
+        let Ok(file) = std::fs::File::open(&path) else {
+            continue;
+        };
+        for line in std::io::BufReader::new(file).lines().map_while(Result::ok) {
+            if let Ok(entry) = serde_json::from_str::<LogEntry>(&line) {
+                if query.matches(&entry) {
+                    entries.push(entry);
+                }
+            }
+        }
+    }
+    newest(entries, query.limit.unwrap_or(DEFAULT_LIMIT))
+}
+
+/// Entries logged by this process, oldest first. With a project, only the
+/// entries that went to that project's log.
+pub fn recent_entries(query: &LogQuery) -> Vec<LogEntry> {
+    let project = query.project_dir.as_deref().map(normalize_path);
+    let entries = RECENT
+        .lock()
+        .map(|recent| {
+            recent
+                .iter()
+                .filter(|entry| match (&project, &entry.project) {
+                    (Some(wanted), Some(project)) => wanted == project,
+                    _ => true,
+                })
+                .filter(|entry| query.matches(entry))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    newest(entries, query.limit.unwrap_or(DEFAULT_LIMIT))
+}
+
+/// Sequence number of the newest entry logged by this process
+pub fn latest_seq() -> u64 {
+    NEXT_SEQ.load(Ordering::Relaxed) - 1
+}
+
+/// Write an entry to the project logs it belongs to and keep it in memory
+fn record(mut entry: LogEntry) {
+    let projects: Vec<String> = match &entry.project {
+        Some(project) => vec![project.clone()],
+        None => {
+            let registered = PROJECTS.lock().map(|p| p.clone()).unwrap_or_default();
+            let mentioned = registered.iter().find(|dir| entry.message.contains(dir.as_str())).cloned();
+            match mentioned {
+                Some(dir) => {
+                    entry.project = Some(dir.clone());
+                    vec![dir]
+                }
+                None => registered.into_iter().collect(),
+            }
+        }
+    };
+
+    if let Ok(line) = serde_json::to_string(&entry) {
+        for project in &projects {
+            // Never create a project directory just to log into it
+            if Path::new(project).is_dir() {
+                let _ = append_line(&log_path(Path::new(project)), &line);
+            }
+        }
+    }
+
+    if let Ok(mut recent) = RECENT.lock() {
+        if recent.len() >= RECENT_ENTRIES {
+            recent.pop_front();
+        }
+        recent.push_back(entry);
+    }
+}
+
+/// `project` field recorded on a span
+struct SpanProject(String);
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    project: Option<String>,
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: serde_json::Value) {
+        match field.name() {
+            "message" => self.message = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string()),
+            "project" | "project_dir" => {
+                if let Some(dir) = value.as_str() {
+                    self.project = Some(normalize_path(dir));
+                }
+            }
+            name => {
+                self.fields.insert(name.to_string(), value);
+            }
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.insert(field, format!("{:?}", value).into());
+    }
+}
+
+/// `tracing` layer that writes project log files; add it next to the
+/// console formatter when the server starts
+pub struct ProjectLogLayer;
+
+impl<S> Layer<S> for ProjectLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(project), Some(span)) = (visitor.project, ctx.span(id)) {
+            span.extensions_mut().insert(SpanProject(project));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let project = visitor.project.or_else(|| {
+            ctx.event_scope(event)?
+                .find_map(|span| span.extensions().get::<SpanProject>().map(|p| p.0.clone()))
+        });
+
+        let metadata = event.metadata();
+        record(LogEntry {
+            seq: NEXT_SEQ.fetch_add(1, Ordering::Relaxed),
+            timestamp: timestamp(),
+            level: level_name(metadata.level()).to_string(),
+            subsystem: subsystem(metadata.target()),
+            message: visitor.message,
+            project,
+            fields: visitor.fields,
+        });
+    }
+}
+
+fn level_name(level: &Level) -> &'static str {
+    match *level {
+        Level::ERROR => "ERROR",
+        Level::WARN => "WARN",
+        Level::INFO => "INFO",
+        Level::DEBUG => "DEBUG",
+        Level::TRACE => "TRACE",
+    }
+}
+
+/// Query recent log entries (GET /logs/query)
+pub async fn handle_query(Query(query): Query<LogQuery>) -> impl IntoResponse {
+    if query.level.as_deref().is_some_and(|level| severity(level).is_none()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "success": false,
+                "error": "level must be one of error, warn, info, debug, trace"
+            })),
+        );
+    }
+
+    let entries = match (&query.project_dir, query.after) {
+        (Some(dir), None) => read_project_log(Path::new(&normalize_path(dir)), &query),
+        _ => recent_entries(&query),
+    };
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "success": true,
+            "latestSeq": latest_seq(),
+            "entries": entries
+        })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(seq: u64, level: &str, subsystem: &str) -> LogEntry {
+        LogEntry {
+            seq,
+            timestamp: timestamp(),
+            level: level.to_string(),
+            subsystem: subsystem.to_string(),
+            message: format!("entry {}", seq),
+            project: None,
+            fields: serde_json::Map::new(),
+        }
+    }
+
+    #[test]
+    fn test_subsystem() {
+        assert_eq!(subsystem("rbxsync_server::file_watcher"), "file_watcher");
+        assert_eq!(subsystem("rbxsync_server::mcp::tools"), "mcp");
+        assert_eq!(subsystem("rbxsync_server"), "server");
+        assert_eq!(subsystem("tower_http"), "tower_http");
+    }
+
+    #[test]
+    fn test_rotation_and_query() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = log_path(temp.path());
+
+        for seq in 1..=3 {
+            let level = if seq == 2 { "WARN" } else { "INFO" };
+            let line = serde_json::to_string(&entry(seq, level, "assets")).unwrap();
+            append_line(&path, &line).unwrap();
+            rotate(&path).unwrap();
+        }
+        append_line(&path, &serde_json::to_string(&entry(4, "ERROR", "build")).unwrap()).unwrap();
+        assert!(rotated_path(&path, 3).exists());
+
+        let all = read_project_log(temp.path(), &LogQuery::default());
+        assert_eq!(all.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+
+        let warnings = LogQuery { level: Some("warn".into()), ..Default::default() };
+        let seqs: Vec<u64> = read_project_log(temp.path(), &warnings).iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, vec![2, 4]);
+
+        let assets = LogQuery { subsystem: Some("assets".into()), limit: Some(1), ..Default::default() };
+        let seqs: Vec<u64> = read_project_log(temp.path(), &assets).iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, vec![3]);
+
+        // The oldest file is dropped once all rotations are in use
+        for _ in 0..=ROTATED_FILES {
+            rotate(&path).unwrap();
+            append_line(&path, "{}").unwrap();
+        }
+        assert!(!rotated_path(&path, ROTATED_FILES + 1).exists());
+        assert!(read_project_log(temp.path(), &LogQuery::default()).is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+//! Model Context Protocol endpoint
+//!
+//! `POST /mcp` implements MCP's Streamable HTTP transport, so AI IDEs can
+//! connect to the server directly instead of through the `rbxsync-mcp` bridge
+//! process. A request body is one JSON-RPC message or a batch of them, and the
+//! replies come back as plain JSON. The server never sends requests of its
+//! own, so it doesn't open an SSE stream and `GET /mcp` isn't supported.
+//!
+//! The tools (see [`tools`]) call the same HTTP handlers as the CLI and the
+//! VS Code extension, in-process.
+//!
+//! `run_code` executes Luau in Studio, so the endpoint only takes
+//! `application/json` bodies and refuses browser requests from other origins,
+//! as the transport requires. Otherwise any web page could post a `text/plain`
+//! form to it.
+
+pub(crate) mod tools;
+
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::{json, Value};
+
+use crate::AppState;
+
+/// Protocol revisions this endpoint speaks, newest first
+pub const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// POST /mcp
+pub async fn handle_mcp(State(state): State<Arc<AppState>>, headers: HeaderMap, body: String) -> Response {
+    if let Some(rejection) = crate::auth::reject_cross_site(&headers, "application/json") {
+        return rejection;
+    }
+    let message: Value = match serde_json::from_str(&body) {
+        Ok(message) => message,
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Json(error_reply(Value::Null, PARSE_ERROR, &e.to_string()))).into_response()
+        }
+    };
+    let reply = match message {
+        Value::Array(batch) => {
+            let mut replies = Vec::new();
+            for message in batch {
+                replies.extend(dispatch(&state, message).await);
+            }
+            (!replies.is_empty()).then_some(Value::Array(replies))
+        }
+        message => dispatch(&state, message).await,
+    };
+    match reply {
+        Some(reply) => (StatusCode::OK, Json(reply)).into_response(),
+        // Only notifications: nothing to answer
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+/// Handle one JSON-RPC message; `None` for notifications and responses
+pub async fn dispatch(state: &Arc<AppState>, message: Value) -> Option<Value> {
+    if message.get("result").is_some() || message.get("error").is_some() {
+        return None;
+    }
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(|m| m.as_str()) else {
+        return Some(error_reply(id.unwrap_or(Value::Null), INVALID_REQUEST, "Missing method"));
+    };
+    // Notifications such as notifications/initialized need no reply
+    let id = id?;
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let result = match method {
+        "initialize" => Ok(initialize(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools::definitions() })),
+        "tools/call" => call_tool(state, &params).await,
+        _ => Err((METHOD_NOT_FOUND, format!("Unknown method: {}", method))),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_reply(id, code, &message),
+    })
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(|v| v.as_str());
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "rbxsync", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Tools act on the Roblox Studio place connected to this RbxSync server. \
+            projectDir can be left out when a single project is connected."
+    })
+}
+
+async fn call_tool(state: &Arc<AppState>, params: &Value) -> Result<Value, (i64, String)> {
+    let Some(name) = params.get("name").and_then(|n| n.as_str()) else {
+        return Err((INVALID_PARAMS, "Missing tool name".to_string()));
+    };
+    if !tools::definitions().iter().any(|tool| tool["name"] == name) {
+        return Err((INVALID_PARAMS, format!("Unknown tool: {}", name)));
+    }
+    let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+    let (text, is_error) = match tools::call(state, name, &arguments).await {
+        Ok(text) => (text, false),
+        Err(error) => (error, true),
+    };
+    Ok(json!({ "content": [{ "type": "text", "text": text }], "isError": is_error }))
+}
+
+fn error_reply(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_dispatch() {
+        let state = AppState::new();
+        let reply = dispatch(
+            &state,
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": { "protocolVersion": "2024-11-05" } }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(reply["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(reply["result"]["serverInfo"]["name"], "rbxsync");
+
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert_eq!(dispatch(&state, notification).await, None);
+
+        let reply = dispatch(&state, json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" })).await.unwrap();
+        let names: Vec<&str> = reply["result"]["tools"].as_array().unwrap().iter().filter_map(|t| t["name"].as_str()).collect();
+        for name in ["run_code", "explore_hierarchy", "read_properties", "find_instances", "get_script_source", "sync", "extract", "diff", "error_summary"] {
+            assert!(names.contains(&name), "{} is listed", name);
+        }
+
+        let reply = dispatch(&state, json!({ "jsonrpc": "2.0", "id": 3, "method": "resources/list" })).await.unwrap();
+        assert_eq!(reply["error"]["code"], METHOD_NOT_FOUND);
+
+        // Tool failures are results, not protocol errors
+        let dir = tempfile::TempDir::new().unwrap();
+        let call = json!({ "jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": {
+            "name": "diff", "arguments": { "projectDir": dir.path().to_string_lossy() }
+        }});
+        let reply = dispatch(&state, call).await.unwrap();
+        assert_eq!(reply["result"]["isError"], true);
+        assert_eq!(reply["result"]["content"][0]["text"], "Source directory does not exist");
+
+        let call = json!({ "jsonrpc": "2.0", "id": 5, "method": "tools/call", "params": { "name": "error_summary", "arguments": {} } });
+        let reply = dispatch(&state, call).await.unwrap();
+        assert_eq!(reply["result"]["content"][0]["text"], "No errors");
+    }
+
+    #[tokio::test]
+    async fn test_refuses_cross_site_posts() {
+        use axum::body::Body;
+        use axum::http::{header, Request};
+        use tower::Service;
+
+        let state = AppState::new();
+        let ping = json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" }).to_string();
+        let post = |content_type: &str, origin: Option<&str>| {
+            let mut builder = Request::post("/mcp").header(header::CONTENT_TYPE, content_type);
+            if let Some(origin) = origin {
+                builder = builder.header(header::ORIGIN, origin);
+            }
+            builder.body(Body::from(ping.clone())).unwrap()
+        };
+        let status = |request: Request<Body>| {
+            let mut router = crate::create_router(state.clone());
+            async move { router.call(request).await.unwrap().status() }
+        };
+
+        assert_eq!(status(post("text/plain", None)).await, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(status(post("application/json", Some("https://evil.example"))).await, StatusCode::FORBIDDEN);
+        assert_eq!(status(post("application/json; charset=utf-8", None)).await, StatusCode::OK);
+        assert_eq!(status(post("application/json", Some("http://localhost:3000"))).await, StatusCode::OK);
+    }
+}
@@ -0,0 +1,338 @@
+//! MCP tools
+//!
+//! Each tool is a call (or, for `sync` and `extract`, a short sequence of
+//! calls) to the server's own HTTP endpoints, routed in-process. Tool errors
+//! are returned as text for the model to read rather than as protocol errors.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::body::Body;
+use axum::http::{header, Method, Request};
+use rbxsync_core::api::ErrorSummary;
+use serde_json::{json, Value};
+use tower::Service;
+
+use crate::AppState;
+
+/// How long `extract` waits for Studio to send the whole game
+const EXTRACT_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Tool descriptions and input schemas, as returned by `tools/list`
+pub fn definitions() -> Vec<Value> {
+    let project_dir = json!({
+        "type": "string",
+        "description": "Project directory (optional when a single project is connected)"
+    });
+    vec![
+        tool(
+            "run_code",
+            "Run Luau code in Roblox Studio and return its printed output",
+            json!({ "code": { "type": "string", "description": "Luau code to run" } }),
+            &["code"],
+        ),
+        tool(
+            "explore_hierarchy",
+            "List the instances under a path in the Studio DataModel",
+            json!({
+                "path": { "type": "string", "description": "Instance path, e.g. Workspace/Map (default: the DataModel)" },
+                "depth": { "type": "integer", "description": "Levels to descend, up to 10 (default: 1)" }
+            }),
+            &[],
+        ),
+        tool(
+            "read_properties",
+            "Read the properties of an instance in Studio",
+            json!({ "path": { "type": "string", "description": "Instance path, e.g. Workspace/SpawnLocation" } }),
+            &["path"],
+        ),
+        tool(
+            "find_instances",
+            "Search Studio for instances by class, name and ancestor",
+            json!({
+                "className": { "type": "string", "description": "Class to match, e.g. Part" },
+                "name": { "type": "string", "description": "Name to match" },
+                "parent": { "type": "string", "description": "Only search below this path" },
+                "limit": { "type": "integer", "description": "Most results to return, up to 1000 (default: 100)" }
+            }),
+            &[],
+        ),
+        tool(
+            "get_script_source",
+            "Read a script's source from Studio",
+            json!({ "path": { "type": "string", "description": "Script path, e.g. ServerScriptService/Main" } }),
+            &["path"],
+        ),
+        tool(
+            "set_script_source",
+            "Replace a script's source in Studio and in its .luau file",
+            json!({
+                "path": { "type": "string", "description": "Script path, e.g. ServerScriptService/Main" },
+                "source": { "type": "string", "description": "New source" },
+                "projectDir": project_dir
+            }),
+            &["path", "source"],
+        ),
+        tool(
+            "edit_script_lines",
+            "Replace lines startLine to endLine (inclusive, from 1) of a script in Studio and in its .luau file. \
+             Set endLine to startLine - 1 to insert before startLine, or newText to \"\" to delete the lines.",
+            json!({
+                "path": { "type": "string", "description": "Script path, e.g. ServerScriptService/Main" },
+                "startLine": { "type": "integer", "description": "First line to replace" },
+                "endLine": { "type": "integer", "description": "Last line to replace" },
+                "newText": { "type": "string", "description": "Replacement lines" },
+                "projectDir": project_dir
+            }),
+            &["path", "startLine", "endLine"],
+        ),
+        tool(
+            "sync",
+            "Push local file changes since the last sync to Studio",
+            json!({
+                "projectDir": project_dir,
+                "delete": { "type": "boolean", "description": "Also delete instances in Studio that have no files (default: false)" }
+            }),
+            &[],
+        ),
+        tool(
+            "extract",
+            "Extract the game open in Studio into the project's src directory",
+            json!({
+                "projectDir": project_dir,
+                "services": { "type": "array", "items": { "type": "string" }, "description": "Services to extract (default: all)" },
+                "includeTerrain": { "type": "boolean", "description": "Include terrain voxel data (default: true)" }
+            }),
+            &[],
+        ),
+        tool(
+            "error_summary",
+            "Errors from the Studio console, deduplicated: how many unique errors, the most frequent ones with counts, \
+             first and last seen, and the local file and line",
+            json!({ "top": { "type": "integer", "description": "Errors to list (default: 5)" } }),
+            &[],
+        ),
+        tool(
+            "diff",
+            "Compare the project's files with Studio: instances only in files, only in Studio, or with different properties",
+            json!({ "projectDir": project_dir }),
+            &[],
+        ),
+    ]
+}
+
+fn tool(name: &str, description: &str, properties: Value, required: &[&str]) -> Value {
+    json!({
+        "name": name,
+        "description": description,
+        "inputSchema": { "type": "object", "properties": properties, "required": required }
+    })
+}
+
+/// Run a tool; the text is what the model sees
+pub async fn call(state: &Arc<AppState>, name: &str, args: &Value) -> Result<String, String> {
+    match name {
+        "run_code" => {
+            let code = args.get("code").and_then(|c| c.as_str()).ok_or("code is required")?;
+            let result = post(state, "/run", json!({ "code": code })).await?;
+            Ok(result["output"].as_str().unwrap_or_default().to_string())
+        }
+        "explore_hierarchy" => {
+            let body = json!({ "path": args.get("path"), "depth": args.get("depth") });
+            Ok(pretty(&post(state, "/explore-hierarchy", body).await?))
+        }
+        "read_properties" => {
+            let path = args.get("path").and_then(|p| p.as_str()).ok_or("path is required")?;
+            Ok(pretty(&post(state, "/read-properties", json!({ "path": path })).await?))
+        }
+        "find_instances" => {
+            let body = json!({
+                "className": args.get("className"),
+                "name": args.get("name"),
+                "parent": args.get("parent"),
+                "limit": args.get("limit")
+            });
+            Ok(pretty(&post(state, "/find-instances", body).await?))
+        }
+        "get_script_source" => {
+            let path = args.get("path").and_then(|p| p.as_str()).ok_or("path is required")?;
+            let result = post(state, "/script/get", json!({ "path": path })).await?;
+            Ok(result["source"].as_str().unwrap_or_default().to_string())
+        }
+        "set_script_source" | "edit_script_lines" => {
+            let path = args.get("path").and_then(|p| p.as_str()).ok_or("path is required")?;
+            let (uri, body) = if name == "set_script_source" {
+                let source = args.get("source").and_then(|s| s.as_str()).ok_or("source is required")?;
+                ("/script/set", json!({ "path": path, "source": source, "projectDir": args.get("projectDir") }))
+            } else {
+                let line = |key: &str| args.get(key).and_then(|l| l.as_u64()).ok_or(format!("{} is required", key));
+                let body = json!({
+                    "path": path,
+                    "startLine": line("startLine")?,
+                    "endLine": line("endLine")?,
+                    "newText": args.get("newText").and_then(|t| t.as_str()).unwrap_or_default(),
+                    "projectDir": args.get("projectDir")
+                });
+                ("/script/edit-lines", body)
+            };
+            let result = post(state, uri, body).await?;
+            let file = result["file"].as_str().map(|f| format!(" and {}", f)).unwrap_or_default();
+            Ok(format!(
+                "Updated {} in Studio{} ({} lines).",
+                path,
+                file,
+                result["lineCount"].as_u64().unwrap_or(0)
+            ))
+        }
+        "sync" => sync(state, args).await,
+        "extract" => extract(state, args).await,
+        "error_summary" => {
+            let top = args.get("top").and_then(|t| t.as_u64()).unwrap_or(5);
+            let uri = format!("/console/errors/summary?top={}", top);
+            let summary: ErrorSummary = serde_json::from_value(request(state, Method::GET, &uri, None).await?)
+                .map_err(|e| e.to_string())?;
+            Ok(summary.describe())
+        }
+        "diff" => {
+            let project_dir = project_dir(state, args).await?;
+            Ok(pretty(&post(state, "/diff", json!({ "project_dir": project_dir })).await?))
+        }
+        _ => Err(format!("Unknown tool: {}", name)),
+    }
+}
+
+/// Send changed files to Studio, as `rbxsync sync` does
+async fn sync(state: &Arc<AppState>, args: &Value) -> Result<String, String> {
+    let project_dir = project_dir(state, args).await?;
+    let changes = post(state, "/sync/incremental", json!({ "project_dir": project_dir })).await?;
+    let mut operations: Vec<Value> = changes["instances"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|instance| Some(json!({ "type": "update", "path": instance.get("path")?.as_str()?, "data": instance })))
+        .collect();
+
+    let mut deleted = 0;
+    if args.get("delete").and_then(|d| d.as_bool()).unwrap_or(false) {
+        let diff = post(state, "/diff", json!({ "project_dir": project_dir, "include_properties": false })).await?;
+        for entry in diff["removed"].as_array().into_iter().flatten() {
+            if let Some(path) = entry.get("path").and_then(|p| p.as_str()) {
+                operations.push(json!({ "type": "delete", "path": path }));
+                deleted += 1;
+            }
+        }
+    }
+    if operations.is_empty() {
+        return Ok("No changes to sync.".to_string());
+    }
+
+    let result = post(state, "/sync/batch", json!({ "operations": operations, "projectDir": project_dir })).await?;
+    let data = result.get("data").unwrap_or(&result);
+    if let Some(reason) = data.get("reason").and_then(|r| r.as_str()) {
+        return Err(format!("Sync skipped: {}", reason));
+    }
+    let errors: Vec<&str> = data["errors"].as_array().into_iter().flatten().filter_map(|e| e.as_str()).collect();
+    if !errors.is_empty() {
+        return Err(format!("Sync finished with errors:\n{}", errors.join("\n")));
+    }
+    post(state, "/sync/incremental", json!({ "project_dir": project_dir, "mark_synced": true })).await?;
+
+    Ok(format!(
+        "Synced {} instances to Studio ({} of {} files changed, {} deleted).",
+        data["applied"].as_u64().unwrap_or(0),
+        changes["files_modified"].as_u64().unwrap_or(0),
+        changes["files_checked"].as_u64().unwrap_or(0),
+        deleted
+    ))
+}
+
+/// Start an extraction, wait for Studio to send it and write it to src
+async fn extract(state: &Arc<AppState>, args: &Value) -> Result<String, String> {
+    let project_dir = project_dir(state, args).await?;
+    let start = json!({
+        "project_dir": project_dir,
+        "services": args.get("services"),
+        "include_terrain": args.get("includeTerrain")
+    });
+    post(state, "/extract/start", start).await?;
+
+    let deadline = Instant::now() + EXTRACT_TIMEOUT;
+    loop {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let status = request(state, Method::GET, "/extract/status", None).await?;
+        if status["complete"].as_bool().unwrap_or(false) {
+            break;
+        }
+        if Instant::now() > deadline {
+            return Err("Timed out waiting for Studio to send the game".to_string());
+        }
+    }
+
+    let result = post(state, "/extract/finalize", json!({ "project_dir": project_dir })).await?;
+    Ok(format!(
+        "Extracted {} instances to {}: {} .rbxjson files and {} scripts written.",
+        result["totalInstances"].as_u64().unwrap_or(0),
+        project_dir,
+        result["filesWritten"].as_u64().unwrap_or(0),
+        result["scriptsWritten"].as_u64().unwrap_or(0)
+    ))
+}
+
+async fn project_dir(state: &Arc<AppState>, args: &Value) -> Result<String, String> {
+    let given = args.get("projectDir").and_then(|p| p.as_str());
+    crate::flags::resolve_project(state, given)
+        .await
+        .ok_or_else(|| "projectDir is required when zero or several projects are connected".to_string())
+}
+
+pub(crate) async fn post(state: &Arc<AppState>, uri: &str, body: Value) -> Result<Value, String> {
+    request(state, Method::POST, uri, Some(body)).await
+}
+
+/// Call an endpoint through the router, with the server's token when it has
+/// one. Fails with the endpoint's `error` when it answers with an error
+/// status or `success: false`.
+async fn request(state: &Arc<AppState>, method: Method, uri: &str, body: Option<Value>) -> Result<Value, String> {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(token) = state.auth_token.get() {
+        builder = builder.header(rbxsync_core::TOKEN_HEADER, token.as_str());
+    }
+    let request = builder
+        .body(Body::from(body.map(|b| b.to_string()).unwrap_or_default()))
+        .map_err(|e| e.to_string())?;
+    let response = match crate::create_router(state.clone()).call(request).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    };
+    crate::handler_json(response).await
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_process_calls_carry_the_token() {
+        let state = AppState::new();
+        state.auth_token.set("secret".to_string()).unwrap();
+
+        // The router itself still turns away callers without the token
+        let outside = Request::builder().method(Method::POST).uri("/sync/pause").body(Body::empty()).unwrap();
+        let response = match crate::create_router(state.clone()).call(outside).await {
+            Ok(response) => response,
+            Err(never) => match never {},
+        };
+        assert_eq!(response.status(), axum::http::StatusCode::UNAUTHORIZED);
+
+        let paused = post(&state, "/sync/pause", json!({ "reason": "test" })).await.unwrap();
+        assert_eq!(paused["paused"], true);
+        assert!(state.sync_pause.is_paused());
+    }
+}
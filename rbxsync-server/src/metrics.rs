@@ -0,0 +1,268 @@
+//! Prometheus metrics
+//!
+//! A small registry of counters and histograms kept in `AppState`, rendered in
+//! the Prometheus text format at `GET /metrics` for servers running on build
+//! machines. Queue depths and connected clients are gauges read from the
+//! state when scraped.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{extract::State, http::header, response::IntoResponse};
+use uuid::Uuid;
+
+use crate::AppState;
+
+/// Requests handed to the plugin but never answered are forgotten after this
+const PENDING_TTL: Duration = Duration::from_secs(600);
+
+const DURATION_BUCKETS: &[f64] = &[1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0];
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+const SIZE_BUCKETS: &[f64] = &[1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 5000.0];
+
+pub struct Counter {
+    name: &'static str,
+    help: &'static str,
+    value: AtomicU64,
+}
+
+impl Counter {
+    fn new(name: &'static str, help: &'static str) -> Self {
+        Self { name, help, value: AtomicU64::new(0) }
+    }
+
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    pub fn add(&self, n: u64) {
+        self.value.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    fn render(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} counter", self.name);
+        let _ = writeln!(out, "{} {}", self.name, self.get());
+    }
+}
+
+#[derive(Default)]
+struct HistogramData {
+    /// Per bucket, not cumulative; the last slot is `+Inf`
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+pub struct Histogram {
+    name: &'static str,
+    help: &'static str,
+    buckets: &'static [f64],
+    data: Mutex<HistogramData>,
+}
+
+impl Histogram {
+    fn new(name: &'static str, help: &'static str, buckets: &'static [f64]) -> Self {
+        let data = HistogramData { counts: vec![0; buckets.len() + 1], ..Default::default() };
+        Self { name, help, buckets, data: Mutex::new(data) }
+    }
+
+    pub fn observe(&self, value: f64) {
+        let slot = self.buckets.iter().position(|&le| value <= le).unwrap_or(self.buckets.len());
+        if let Ok(mut data) = self.data.lock() {
+            data.counts[slot] += 1;
+            data.sum += value;
+            data.count += 1;
+        }
+    }
+
+    pub fn observe_duration(&self, duration: Duration) {
+        self.observe(duration.as_secs_f64());
+    }
+
+    pub fn count(&self) -> u64 {
+        self.data.lock().map(|d| d.count).unwrap_or(0)
+    }
+
+    fn render(&self, out: &mut String) {
+        let Ok(data) = self.data.lock() else {
+            return;
+        };
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} histogram", self.name);
+        let mut cumulative = 0;
+        for (le, count) in self.buckets.iter().zip(&data.counts) {
+            cumulative += count;
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", self.name, le, cumulative);
+        }
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", self.name, data.count);
+        let _ = writeln!(out, "{}_sum {}", self.name, data.sum);
+        let _ = writeln!(out, "{}_count {}", self.name, data.count);
+    }
+}
+
+/// Metrics collected while the server runs
+pub struct Metrics {
+    pub extraction_duration: Histogram,
+    pub extraction_chunks: Counter,
+    pub sync_batch_size: Histogram,
+    pub plugin_round_trip: Histogram,
+    pub file_watcher_events: Counter,
+    /// When each request was handed to the plugin, for round-trip times
+    dispatched: Mutex<HashMap<Uuid, Instant>>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            extraction_duration: Histogram::new(
+                "rbxsync_extraction_duration_seconds",
+                "Time from the start of an extraction to its finalize.",
+                DURATION_BUCKETS,
+            ),
+            extraction_chunks: Counter::new(
+                "rbxsync_extraction_chunks_total",
+                "Extraction chunks received from the plugin.",
+            ),
+            sync_batch_size: Histogram::new(
+                "rbxsync_sync_batch_operations",
+                "Operations per sync batch sent to Studio.",
+                SIZE_BUCKETS,
+            ),
+            plugin_round_trip: Histogram::new(
+                "rbxsync_plugin_round_trip_seconds",
+                "Time from handing a request to the plugin to receiving its response.",
+                LATENCY_BUCKETS,
+            ),
+            file_watcher_events: Counter::new(
+                "rbxsync_file_watcher_events_total",
+                "File changes reported by the file watcher, before debouncing.",
+            ),
+            dispatched: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// A request was handed to a polling plugin
+    pub fn request_dispatched(&self, id: Uuid) {
+        if let Ok(mut dispatched) = self.dispatched.lock() {
+            let now = Instant::now();
+            dispatched.retain(|_, at| now.duration_since(*at) < PENDING_TTL);
+            dispatched.insert(id, now);
+        }
+    }
+
+    /// The plugin answered a request; records its round trip if it was dispatched
+    pub fn response_received(&self, id: Uuid) {
+        let at = self.dispatched.lock().ok().and_then(|mut d| d.remove(&id));
+        if let Some(at) = at {
+            self.plugin_round_trip.observe_duration(at.elapsed());
+        }
+    }
+
+    /// Counters and histograms in the Prometheus text format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.extraction_duration.render(&mut out);
+        self.extraction_chunks.render(&mut out);
+        self.sync_batch_size.render(&mut out);
+        self.plugin_round_trip.render(&mut out);
+        self.file_watcher_events.render(&mut out);
+        out
+    }
+}
+
+fn render_gauge(out: &mut String, name: &str, help: &str, samples: &[(&str, usize)]) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    for (labels, value) in samples {
+        if labels.is_empty() {
+            let _ = writeln!(out, "{} {}", name, value);
+        } else {
+            let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+        }
+    }
+}
+
+/// Prometheus scrape endpoint (GET /metrics)
+pub async fn handle_metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let mut out = state.metrics.render();
+
+    let project: usize = state.project_queues.read().await.values().map(|q| q.len()).sum();
+    let session: usize = state.session_queues.read().await.values().map(|q| q.len()).sum();
+    let global = state.request_queue.lock().await.len();
+    render_gauge(
+        &mut out,
+        "rbxsync_plugin_queue_depth",
+        "Requests waiting for a plugin to poll them.",
+        &[("queue=\"project\"", project), ("queue=\"session\"", session), ("queue=\"global\"", global)],
+    );
+
+    let pending = state.response_channels.read().await.len();
+    render_gauge(
+        &mut out,
+        "rbxsync_plugin_pending_responses",
+        "Requests waiting for the plugin's response.",
+        &[("", pending)],
+    );
+
+    let places = state.place_registry.read().await.len();
+    let workspaces = state.vscode_workspaces.read().await.len();
+    render_gauge(
+        &mut out,
+        "rbxsync_connected_clients",
+        "Registered Studio places and VS Code workspaces.",
+        &[("client=\"studio\"", places), ("client=\"vscode\"", workspaces)],
+    );
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_render() {
+        let histogram = Histogram::new("test_seconds", "Test.", &[1.0, 5.0]);
+        histogram.observe(0.5);
+        histogram.observe(3.0);
+        histogram.observe(10.0);
+
+        let mut out = String::new();
+        histogram.render(&mut out);
+        assert!(out.contains("# TYPE test_seconds histogram"));
+        assert!(out.contains("test_seconds_bucket{le=\"1\"} 1\n"));
+        assert!(out.contains("test_seconds_bucket{le=\"5\"} 2\n"));
+        assert!(out.contains("test_seconds_bucket{le=\"+Inf\"} 3\n"));
+        assert!(out.contains("test_seconds_sum 13.5\n"));
+        assert!(out.contains("test_seconds_count 3\n"));
+    }
+
+    #[test]
+    fn test_round_trip_needs_dispatch() {
+        let metrics = Metrics::new();
+        let id = Uuid::new_v4();
+
+        // Answered without being polled (e.g. a bench harness): not a round trip
+        metrics.response_received(id);
+        assert_eq!(metrics.plugin_round_trip.count(), 0);
+
+        metrics.request_dispatched(id);
+        metrics.response_received(id);
+        metrics.response_received(id);
+        assert_eq!(metrics.plugin_round_trip.count(), 1);
+    }
+}
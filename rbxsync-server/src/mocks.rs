@@ -0,0 +1,310 @@
+//! HTTP request mocking for playtests
+//!
+//! Routes defined in `.rbxsync/mocks.json` are served under `/mock/*`. When a
+//! test run starts, the plugin injects a generated `RbxSyncHttpMock` module
+//! that wraps HttpService and sends every request to `/mock/<host>/<path>`
+//! instead, so games that call external APIs can be tested deterministically.
+//!
+//! ```json
+//! {
+//!   "routes": [
+//!     { "method": "GET", "url": "api.example.com/v1/users/*", "body": { "name": "Builderman" } },
+//!     { "url": "https://api.example.com/v1/scores", "status": 503, "body": "Unavailable" }
+//!   ]
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+
+use crate::protocol::HttpMock;
+use crate::AppState;
+
+/// Mock definitions, relative to the project directory
+pub const MOCKS_FILE: &str = ".rbxsync/mocks.json";
+
+/// Header the shim uses to say which project's mocks to serve
+pub const PROJECT_HEADER: &str = "x-rbxsync-project";
+
+fn any_method() -> String {
+    "*".to_string()
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+/// One mocked endpoint
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockRoute {
+    /// HTTP method, or "*" for any
+    #[serde(default = "any_method")]
+    pub method: String,
+    /// URL pattern without scheme (a scheme is ignored if present). `*` matches
+    /// any run of characters. Patterns without `?` ignore the query string.
+    pub url: String,
+    #[serde(default = "default_status")]
+    pub status: u16,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// String bodies are sent as-is, anything else as JSON
+    #[serde(default)]
+    pub body: serde_json::Value,
+    /// Simulated latency
+    #[serde(default)]
+    pub delay_ms: u64,
+}
+
+/// Contents of `.rbxsync/mocks.json`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MocksFile {
+    #[serde(default)]
+    pub routes: Vec<MockRoute>,
+}
+
+/// Load a project's mocks, if it defines any
+pub fn load_mocks(project_dir: &Path) -> Option<MocksFile> {
+    let content = std::fs::read_to_string(project_dir.join(MOCKS_FILE)).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(mocks) => Some(mocks),
+        Err(e) => {
+            tracing::warn!("Invalid {}: {}", MOCKS_FILE, e);
+            None
+        }
+    }
+}
+
+fn strip_scheme(url: &str) -> &str {
+    url.split_once("://").map(|(_, rest)| rest).unwrap_or(url)
+}
+
+/// Match `text` against a pattern where `*` matches any run of characters
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard: exact match
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// First route matching a method and URL (scheme optional)
+pub fn find_route<'a>(mocks: &'a MocksFile, method: &str, url: &str) -> Option<&'a MockRoute> {
+    let url = strip_scheme(url);
+    let without_query = url.split('?').next().unwrap_or(url);
+
+    mocks.routes.iter().find(|route| {
+        let method_matches = route.method == "*" || route.method.eq_ignore_ascii_case(method);
+        let pattern = strip_scheme(&route.url);
+        let target = if pattern.contains('?') { url } else { without_query };
+        method_matches && glob_match(pattern, target)
+    })
+}
+
+/// Luau source for the HttpService wrapper injected during test runs, sending
+/// requests to the server at `server_url`
+pub fn shim_source(project_dir: &str, server_url: &str) -> String {
+    let project = serde_json::to_string(project_dir).unwrap_or_else(|_| "\"\"".to_string());
+    format!(
+        r#"-- Generated by RbxSync for test runs: sends HttpService requests to the mock server.
+-- Use from game code:
+--   local mock = game:GetService("ServerScriptService"):FindFirstChild("RbxSyncHttpMock")
+--   local HttpService = if mock then require(mock) else game:GetService("HttpService")
+
+local HttpService = game:GetService("HttpService")
+
+local MOCK_BASE_URL = "{base}"
+local PROJECT_DIR = {project}
+
+local function redirect(url: string): string
+	return MOCK_BASE_URL .. string.gsub(url, "^%a+://", "")
+end
+
+local function withProjectHeader(headers: {{[string]: string}}?): {{[string]: string}}
+	local merged = {{}}
+	if headers then
+		for key, value in headers do
+			merged[key] = value
+		end
+	end
+	merged["X-RbxSync-Project"] = PROJECT_DIR
+	return merged
+end
+
+local Mock = {{}}
+
+function Mock:GetAsync(url: string, nocache: boolean?, headers: any?): string
+	return HttpService:GetAsync(redirect(url), nocache, withProjectHeader(headers))
+end
+
+function Mock:PostAsync(url: string, data: string, contentType: Enum.HttpContentType?, compress: boolean?, headers: any?): string
+	return HttpService:PostAsync(redirect(url), data, contentType, compress, withProjectHeader(headers))
+end
+
+function Mock:RequestAsync(options: any): any
+	local redirected = table.clone(options)
+	redirected.Url = redirect(options.Url)
+	redirected.Headers = withProjectHeader(options.Headers)
+	return HttpService:RequestAsync(redirected)
+end
+
+-- Everything else (JSONEncode, GenerateGUID, ...) goes to the real service
+return setmetatable(Mock, {{
+	__index = function(_, key)
+		local value = (HttpService :: any)[key]
+		if type(value) == "function" then
+			return function(_, ...)
+				return value(HttpService, ...)
+			end
+		end
+		return value
+	end,
+}})
+"#,
+        base = format!("{}/mock/", server_url),
+        project = project,
+    )
+}
+
+/// The mock shim for a test run when the project defines mocks.
+///
+/// The project is `project_dir`, or the only connected place's.
+pub async fn mock_shim(state: &Arc<AppState>, project_dir: Option<&str>) -> Option<HttpMock> {
+    let project_dir = match project_dir {
+        Some(dir) => crate::normalize_path(dir),
+        None => {
+            let registry = state.place_registry.read().await;
+            let mut dirs: Vec<&String> = registry.values().map(|p| &p.project_dir).collect();
+            dirs.dedup();
+            match dirs.as_slice() {
+                [one] => (*one).clone(),
+                _ => return None,
+            }
+        }
+    };
+
+    let mocks = load_mocks(Path::new(&project_dir))?;
+    tracing::info!("Injecting HTTP mocks for test run ({} routes)", mocks.routes.len());
+    Some(HttpMock { source: shim_source(&project_dir, &crate::local_url(state)) })
+}
+
+/// Add the mock shim to a `test:run` payload when the project defines mocks
+pub async fn attach_shim(state: &Arc<AppState>, payload: &mut serde_json::Value) {
+    let project_dir = payload.get("projectDir").and_then(|p| p.as_str()).map(str::to_string);
+    let Some(shim) = mock_shim(state, project_dir.as_deref()).await else {
+        return;
+    };
+    if let Some(obj) = payload.as_object_mut() {
+        obj.insert("httpMock".to_string(), serde_json::json!(shim));
+    }
+}
+
+/// Serve a mocked response (ANY /mock/*url)
+pub async fn handle_mock(
+    State(state): State<Arc<AppState>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    let path = uri.path().strip_prefix("/mock/").unwrap_or_default();
+    let url = match uri.query() {
+        Some(query) => format!("{}?{}", path, query),
+        None => path.to_string(),
+    };
+
+    let project_dir = match headers.get(PROJECT_HEADER).and_then(|v| v.to_str().ok()) {
+        Some(dir) => Some(crate::normalize_path(dir)),
+        None => {
+            let registry = state.place_registry.read().await;
+            registry.values().next().map(|p| p.project_dir.clone())
+        }
+    };
+
+    let route = project_dir
+        .as_deref()
+        .and_then(|dir| load_mocks(Path::new(dir)))
+        .and_then(|mocks| find_route(&mocks, method.as_str(), &url).cloned());
+
+    let Some(route) = route else {
+        tracing::warn!("No mock for {} {}", method, url);
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "success": false,
+                "error": format!("No mock for {} {}", method, url)
+            })),
+        )
+            .into_response();
+    };
+
+    if route.delay_ms > 0 {
+        tokio::time::sleep(std::time::Duration::from_millis(route.delay_ms)).await;
+    }
+
+    let status = StatusCode::from_u16(route.status).unwrap_or(StatusCode::OK);
+    let mut response = match &route.body {
+        serde_json::Value::String(text) => (status, text.clone()).into_response(),
+        serde_json::Value::Null => status.into_response(),
+        body => (status, Json(body.clone())).into_response(),
+    };
+    for (name, value) in &route.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name.as_str()), HeaderValue::from_str(value)) {
+            response.headers_mut().insert(name, value);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_route() {
+        let mocks: MocksFile = serde_json::from_value(serde_json::json!({
+            "routes": [
+                { "method": "POST", "url": "api.example.com/v1/scores", "status": 201 },
+                { "url": "https://api.example.com/v1/users/*", "body": { "name": "Builderman" } },
+                { "url": "api.example.com/search?q=*", "body": [] }
+            ]
+        }))
+        .unwrap();
+
+        let route = find_route(&mocks, "GET", "https://api.example.com/v1/users/42?fields=name").unwrap();
+        assert_eq!(route.status, 200);
+        assert_eq!(route.body["name"], "Builderman");
+
+        assert_eq!(find_route(&mocks, "post", "api.example.com/v1/scores").unwrap().status, 201);
+        assert!(find_route(&mocks, "GET", "api.example.com/v1/scores").is_none());
+        assert!(find_route(&mocks, "GET", "api.example.com/search?q=cats").is_some());
+        assert!(find_route(&mocks, "GET", "other.example.com/v1/users/1").is_none());
+
+        assert!(glob_match("a*c*e", "abcde"));
+        assert!(!glob_match("a*c", "abcd"));
+        let shim = shim_source("C:/games/obby", "https://127.0.0.1:44756");
+        assert!(shim.contains("\"C:/games/obby\""));
+        assert!(shim.contains("local MOCK_BASE_URL = \"https://127.0.0.1:44756/mock/\""));
+    }
+}
@@ -0,0 +1,467 @@
+//! Scheduled nightly builds
+//!
+//! Projects opt in with a `nightly` section in rbxsync.json:
+//!
+//! ```json
+//! {
+//!   "nightly": {
+//!     "enabled": true,
+//!     "time": "02:00",
+//!     "format": "rbxl",
+//!     "keep": 7,
+//!     "publish": { "universeId": 123, "placeId": 456 }
+//!   }
+//! }
+//! ```
+//!
+//! While the server runs, each configured project (the server's working
+//! directory and any connected project) is built once a day after `time` (UTC)
+//! into `builds/nightly-YYYYMMDD-HHMMSS.<format>`, and nightlies beyond `keep`
+//! are deleted. Builds use the same pipeline as `rbxsync build`
+//! (`rbxsync_core::builder`). Publishing uses the Open Cloud place publishing API with the
+//! key in `RBXSYNC_OPEN_CLOUD_KEY`. Unlike live sync, the scheduler keeps
+//! running while the server is idle, since a build machine has no clients.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::{
+    extract::{Path as UrlPath, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+pub(crate) use rbxsync_core::defines::civil_from_days;
+use rbxsync_core::defines::utc_stamps;
+use rbxsync_core::{BuildFormat, BuildOptions};
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+
+/// Artifact directory, relative to the project
+pub const BUILDS_DIR: &str = "builds";
+
+/// Open Cloud API key for publishing (needs place publish permission)
+pub const OPEN_CLOUD_KEY_ENV: &str = "RBXSYNC_OPEN_CLOUD_KEY";
+
+const ARTIFACT_PREFIX: &str = "nightly-";
+
+/// Date (YYYYMMDD) of the last nightly run, so restarts don't build twice
+const LAST_RUN_FILE: &str = ".nightly-last-run";
+
+/// One line per publish, in the builds directory
+pub const PUBLISH_LOG: &str = "publishes.jsonl";
+
+/// How often the scheduler checks whether a build is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+fn default_time() -> String {
+    "02:00".to_string()
+}
+
+fn default_format() -> String {
+    "rbxl".to_string()
+}
+
+fn default_keep() -> usize {
+    7
+}
+
+/// `nightly` section of rbxsync.json
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NightlyConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// UTC time of day, HH:MM
+    #[serde(default = "default_time")]
+    pub time: String,
+    #[serde(default = "default_format")]
+    pub format: String,
+    /// Nightly artifacts to keep (0 keeps all)
+    #[serde(default = "default_keep")]
+    pub keep: usize,
+    #[serde(default)]
+    pub publish: Option<PublishTarget>,
+}
+
+/// Place to publish nightly builds to
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishTarget {
+    pub universe_id: u64,
+    pub place_id: u64,
+}
+
+/// A publish, as logged to `builds/publishes.jsonl`. Open Cloud doesn't keep
+/// a description with a place version, so this is where it's kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishRecord {
+    pub timestamp: u64,
+    pub universe_id: u64,
+    pub place_id: u64,
+    pub version_number: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Place file that was published
+    pub file: String,
+}
+
+/// A nightly build on disk
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
+    pub name: String,
+    pub size: u64,
+    /// UTC build time, YYYY-MM-DDTHH:MM:SSZ
+    pub built_at: String,
+    pub download_url: String,
+}
+
+/// Outcome of one nightly run
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NightlyResult {
+    pub artifact: String,
+    pub removed: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_version: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publish_error: Option<String>,
+}
+
+/// Read a project's nightly settings
+pub fn load_config(project_dir: &str) -> Option<NightlyConfig> {
+    crate::load_project_config(project_dir)
+        .and_then(|c| c.get("nightly").cloned())
+        .and_then(|n| serde_json::from_value(n).ok())
+}
+
+/// Minutes since midnight for "HH:MM"
+fn parse_time(time: &str) -> Option<u64> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let (hours, minutes): (u64, u64) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+pub(crate) fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Whether a build is due at `now` given the date of the last run
+pub fn is_due(config: &NightlyConfig, now: u64, last_run: Option<&str>) -> bool {
+    let Some(at) = parse_time(&config.time) else {
+        return false;
+    };
+    let (today, _) = utc_stamps(now);
+    config.enabled && (now % 86_400) / 60 >= at && last_run != Some(today.as_str())
+}
+
+pub(crate) fn builds_dir(project_dir: &str) -> PathBuf {
+    Path::new(project_dir).join(BUILDS_DIR)
+}
+
+fn read_last_run(project_dir: &str) -> Option<String> {
+    std::fs::read_to_string(builds_dir(project_dir).join(LAST_RUN_FILE))
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+/// Nightly artifacts of a project, newest first
+pub fn list_artifacts(project_dir: &str) -> Vec<Artifact> {
+    let Ok(entries) = std::fs::read_dir(builds_dir(project_dir)) else {
+        return Vec::new();
+    };
+    let mut artifacts: Vec<Artifact> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let stamp = name.strip_prefix(ARTIFACT_PREFIX)?.split('.').next()?.to_string();
+            let (date, time) = stamp.split_once('-')?;
+            if date.len() != 8 || time.len() != 6 {
+                return None;
+            }
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            Some(Artifact {
+                download_url: format!("/builds/download/{}", name),
+                built_at: format!(
+                    "{}-{}-{}T{}:{}:{}Z",
+                    &date[..4], &date[4..6], &date[6..], &time[..2], &time[2..4], &time[4..]
+                ),
+                name,
+                size,
+            })
+        })
+        .collect();
+    artifacts.sort_by(|a, b| b.name.cmp(&a.name));
+    artifacts
+}
+
+/// Delete nightly artifacts beyond the newest `keep`; returns removed names
+pub fn apply_retention(project_dir: &str, keep: usize) -> Vec<String> {
+    if keep == 0 {
+        return Vec::new();
+    }
+    list_artifacts(project_dir)
+        .into_iter()
+        .skip(keep)
+        .filter(|a| std::fs::remove_file(builds_dir(project_dir).join(&a.name)).is_ok())
+        .map(|a| a.name)
+        .collect()
+}
+
+/// Upload a place file through Open Cloud; returns the new version number
+pub(crate) async fn publish(target: &PublishTarget, artifact: &Path) -> Result<u64, String> {
+    let key = std::env::var(OPEN_CLOUD_KEY_ENV).map_err(|_| format!("{} is not set", OPEN_CLOUD_KEY_ENV))?;
+    let is_xml = artifact.extension().is_some_and(|e| e == "rbxlx");
+    let body = tokio::fs::read(artifact).await.map_err(|e| e.to_string())?;
+    let url = format!(
+        "https://apis.roblox.com/universes/v1/{}/places/{}/versions?versionType=Published",
+        target.universe_id, target.place_id
+    );
+
+    let response = reqwest::Client::new()
+        .post(&url)
+        .header("x-api-key", key)
+        .header(
+            header::CONTENT_TYPE.as_str(),
+            if is_xml { "application/xml" } else { "application/octet-stream" },
+        )
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
+    let result: serde_json::Value = response.json().await.unwrap_or_default();
+    if !status.is_success() {
+        let message = result.get("message").and_then(|m| m.as_str()).unwrap_or_default();
+        return Err(format!("Open Cloud returned {}: {}", status, message));
+    }
+    result
+        .get("versionNumber")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Open Cloud response had no versionNumber".to_string())
+}
+
+/// Append a publish to the project's publish log
+pub fn log_publish(project_dir: &str, record: &PublishRecord) -> std::io::Result<()> {
+    use std::io::Write;
+    let dir = builds_dir(project_dir);
+    std::fs::create_dir_all(&dir)?;
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(dir.join(PUBLISH_LOG))?;
+    writeln!(file, "{}", serde_json::to_string(record).map_err(std::io::Error::other)?)
+}
+
+/// Build a project now, prune old nightlies and publish if configured
+pub async fn run_nightly(project_dir: &str, config: &NightlyConfig) -> Result<NightlyResult, String> {
+    let Some(format) = BuildFormat::parse(&config.format) else {
+        return Err(format!("Unknown nightly format '{}'", config.format));
+    };
+
+    let dir = builds_dir(project_dir);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let now = now_secs();
+    let (date, time) = utc_stamps(now);
+    let name = format!("{}{}-{}.{}", ARTIFACT_PREFIX, date, time, format.extension());
+    let output = dir.join(&name);
+
+    tracing::info!("Nightly build for {} -> {}", project_dir, name);
+    let options = BuildOptions { format, ..Default::default() };
+    let built = crate::build::build_project(project_dir, &output, options)
+        .await
+        .map_err(|e| format!("Build failed: {}", e));
+    // Recorded even on failure so a broken build isn't retried every minute
+    let _ = std::fs::write(dir.join(LAST_RUN_FILE), &date);
+    built?;
+
+    let removed = apply_retention(project_dir, config.keep);
+
+    let (published_version, publish_error) = match &config.publish {
+        Some(target) if format.is_place() => match publish(target, &output).await {
+            Ok(version) => {
+                let record = PublishRecord {
+                    timestamp: now,
+                    universe_id: target.universe_id,
+                    place_id: target.place_id,
+                    version_number: version,
+                    description: Some(format!("Nightly build {}", date)),
+                    file: name.clone(),
+                };
+                if let Err(e) = log_publish(project_dir, &record) {
+                    tracing::warn!("Failed to log publish: {}", e);
+                }
+                (Some(version), None)
+            }
+            Err(e) => (None, Some(e)),
+        },
+        Some(_) => (None, Some("Only place formats (rbxl, rbxlx) can be published".to_string())),
+        None => (None, None),
+    };
+    if let Some(error) = &publish_error {
+        tracing::warn!("Nightly publish failed for {}: {}", project_dir, error);
+    }
+
+    Ok(NightlyResult {
+        artifact: name,
+        removed,
+        published_version,
+        publish_error,
+    })
+}
+
+/// Projects the scheduler considers: the working directory and connected projects
+async fn candidate_projects(state: &Arc<AppState>) -> Vec<String> {
+    let mut dirs: Vec<String> = Vec::new();
+    if let Ok(cwd) = std::env::current_dir() {
+        dirs.push(crate::normalize_path(&cwd.to_string_lossy()));
+    }
+    dirs.extend(state.place_registry.read().await.values().map(|p| p.project_dir.clone()));
+    dirs.extend(state.vscode_workspaces.read().await.values().map(|w| w.workspace_dir.clone()));
+
+    let mut seen = HashSet::new();
+    dirs.retain(|d| !d.is_empty() && seen.insert(d.clone()));
+    dirs
+}
+
+/// Background task: run due nightly builds
+pub async fn run_scheduler(state: Arc<AppState>) {
+    loop {
+        for project_dir in candidate_projects(&state).await {
+            let Some(config) = load_config(&project_dir) else {
+                continue;
+            };
+            if !is_due(&config, now_secs(), read_last_run(&project_dir).as_deref()) {
+                continue;
+            }
+            match run_nightly(&project_dir, &config).await {
+                Ok(result) => tracing::info!("Nightly build finished: {}", result.artifact),
+                Err(e) => tracing::warn!("Nightly build failed for {}: {}", project_dir, e),
+            }
+        }
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildsQuery {
+    /// Project to use (default: the server's working directory)
+    pub project_dir: Option<String>,
+}
+
+fn resolve_project(project_dir: Option<&str>) -> String {
+    match project_dir {
+        Some(dir) => crate::normalize_path(dir),
+        None => std::env::current_dir()
+            .map(|d| crate::normalize_path(&d.to_string_lossy()))
+            .unwrap_or_default(),
+    }
+}
+
+/// List nightly artifacts (GET /builds/list)
+pub async fn handle_list(Query(params): Query<BuildsQuery>) -> impl IntoResponse {
+    let project_dir = resolve_project(params.project_dir.as_deref());
+    let config = load_config(&project_dir);
+    Json(serde_json::json!({
+        "success": true,
+        "projectDir": project_dir,
+        "enabled": config.as_ref().is_some_and(|c| c.enabled),
+        "schedule": config.as_ref().map(|c| format!("{} UTC", c.time)),
+        "lastRun": read_last_run(&project_dir),
+        "artifacts": list_artifacts(&project_dir)
+    }))
+}
+
+/// Download a nightly artifact (GET /builds/download/:name)
+pub async fn handle_download(
+    UrlPath(name): UrlPath<String>,
+    Query(params): Query<BuildsQuery>,
+) -> Response {
+    let project_dir = resolve_project(params.project_dir.as_deref());
+    if !name.starts_with(ARTIFACT_PREFIX) || name.contains(['/', '\\']) || name.contains("..") {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "success": false, "error": "Not a nightly artifact" })),
+        )
+            .into_response();
+    }
+
+    match tokio::fs::read(builds_dir(&project_dir).join(&name)).await {
+        Ok(bytes) => (
+            [
+                (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", name)),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "success": false, "error": format!("No artifact named {}", name) })),
+        )
+            .into_response(),
+    }
+}
+
+/// Run a project's nightly build now (POST /builds/run)
+pub async fn handle_run(Json(params): Json<BuildsQuery>) -> impl IntoResponse {
+    let project_dir = resolve_project(params.project_dir.as_deref());
+    let Some(config) = load_config(&project_dir) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "success": false,
+                "error": "No nightly section in rbxsync.json"
+            })),
+        );
+    };
+
+    match run_nightly(&project_dir, &config).await {
+        Ok(result) => (StatusCode::OK, Json(serde_json::json!({ "success": true, "result": result }))),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "success": false, "error": e })),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nightly_schedule_and_retention() {
+        let config: NightlyConfig =
+            serde_json::from_value(serde_json::json!({ "enabled": true, "time": "02:30" })).unwrap();
+        // 2026-10-16 01:00 UTC, then 03:00 UTC
+        let early = 1_792_112_400;
+        let late = early + 2 * 3600;
+        assert_eq!(utc_stamps(early), ("20261016".to_string(), "010000".to_string()));
+        assert!(!is_due(&config, early, None));
+        assert!(is_due(&config, late, Some("20261015")));
+        assert!(!is_due(&config, late, Some("20261016")));
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let project = temp.path().to_string_lossy().to_string();
+        let dir = builds_dir(&project);
+        std::fs::create_dir_all(&dir).unwrap();
+        for day in 10..15 {
+            std::fs::write(dir.join(format!("nightly-202610{}-020000.rbxl", day)), b"x").unwrap();
+        }
+        std::fs::write(dir.join("game.rbxl"), b"x").unwrap();
+
+        let removed = apply_retention(&project, 2);
+        assert_eq!(removed.len(), 3);
+        let kept = list_artifacts(&project);
+        assert_eq!(kept[0].name, "nightly-20261014-020000.rbxl");
+        assert_eq!(kept[0].built_at, "2026-10-14T02:00:00Z");
+        assert_eq!(kept.len(), 2);
+        assert!(dir.join("game.rbxl").exists());
+    }
+}
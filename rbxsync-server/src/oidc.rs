@@ -0,0 +1,413 @@
+//! OpenID Connect sign-in for team servers
+//!
+//! `rbxsync login --issuer` runs the OAuth device authorization grant
+//! (RFC 8628) against the issuer: it shows a code to enter in a browser, polls
+//! until the user approves, and keeps the ID token in the OS keychain. A
+//! server started with `--oidc-issuer` verifies those tokens itself against
+//! the issuer's published signing keys. A valid signature isn't enough: the
+//! token must have been issued to the server's `--oidc-client-id`, or any app
+//! signed in with the same issuer could get in, and the user must be on the
+//! `--oidc-allow` list, or anyone with an account at the issuer could.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context};
+use futures::future::{BoxFuture, FutureExt};
+use jsonwebtoken::jwk::{AlgorithmParameters, JwkSet};
+use jsonwebtoken::{DecodingKey, Validation};
+use serde::Deserialize;
+
+use crate::auth::{AuthProvider, Identity};
+
+/// Scopes asked for at sign-in; `profile` and `email` name the user
+pub const SCOPES: &str = "openid profile email";
+
+/// Least time between refetches of the issuer's keys for an unknown key id
+const JWKS_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Grant type for polling the token endpoint
+const DEVICE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// The parts of an issuer's `/.well-known/openid-configuration` used here
+#[derive(Debug, Clone, Deserialize)]
+pub struct Discovery {
+    pub issuer: String,
+    pub device_authorization_endpoint: Option<String>,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+/// Fetch the issuer's discovery document
+pub async fn discover(client: &reqwest::Client, issuer: &str) -> anyhow::Result<Discovery> {
+    let url = format!("{}/.well-known/openid-configuration", issuer.trim_end_matches('/'));
+    client
+        .get(&url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .json()
+        .await
+        .context("Invalid OpenID configuration")
+}
+
+/// Code for the user to enter, from the device authorization endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceCode {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    /// Verification URL with the code filled in, if the issuer offers one
+    pub verification_uri_complete: Option<String>,
+    /// Seconds until the code expires
+    pub expires_in: u64,
+    /// Seconds to wait between polls
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+}
+
+fn default_interval() -> u64 {
+    5
+}
+
+/// Start a device sign-in: the returned code is shown to the user
+pub async fn start_device_login(
+    client: &reqwest::Client,
+    discovery: &Discovery,
+    client_id: &str,
+) -> anyhow::Result<DeviceCode> {
+    let Some(endpoint) = &discovery.device_authorization_endpoint else {
+        bail!("The issuer doesn't support device sign-in (no device_authorization_endpoint)");
+    };
+    client
+        .post(endpoint)
+        .form(&[("client_id", client_id), ("scope", SCOPES)])
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .context("Device authorization request failed")?
+        .json()
+        .await
+        .context("Invalid device authorization response")
+}
+
+/// Poll the token endpoint until the user approves the code, returning the
+/// ID token
+pub async fn finish_device_login(
+    client: &reqwest::Client,
+    discovery: &Discovery,
+    client_id: &str,
+    code: &DeviceCode,
+) -> anyhow::Result<String> {
+    let deadline = Instant::now() + Duration::from_secs(code.expires_in);
+    let mut interval = code.interval;
+    while Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        let response: serde_json::Value = client
+            .post(&discovery.token_endpoint)
+            .form(&[("grant_type", DEVICE_GRANT), ("device_code", &code.device_code), ("client_id", client_id)])
+            .send()
+            .await
+            .context("Token request failed")?
+            .json()
+            .await
+            .context("Invalid token response")?;
+        if let Some(token) = response["id_token"].as_str() {
+            return Ok(token.to_string());
+        }
+        match response["error"].as_str() {
+            Some("authorization_pending") => {}
+            Some("slow_down") => interval += 5,
+            Some(error) => bail!(
+                "Sign-in failed: {}",
+                response["error_description"].as_str().unwrap_or(error)
+            ),
+            None => bail!("Token response has neither id_token nor error"),
+        }
+    }
+    bail!("The code expired before sign-in was approved")
+}
+
+/// What a server started with `--oidc-issuer` accepts
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    pub issuer: String,
+    /// Tokens must be issued to this client (`aud`, and `azp` when present)
+    pub client_id: String,
+    /// Users let in: an email address, `@domain` for every address there, or
+    /// a subject id. Emails only count when the issuer has verified them.
+    pub allow: Vec<String>,
+}
+
+/// The ID token claims checked here; `iss`, `aud` and `exp` are checked by
+/// [`Validation`]
+#[derive(Debug, Deserialize)]
+struct Claims {
+    sub: String,
+    azp: Option<String>,
+    email: Option<String>,
+    email_verified: Option<bool>,
+    preferred_username: Option<String>,
+}
+
+impl Claims {
+    fn verified_email(&self) -> Option<&str> {
+        self.email.as_deref().filter(|_| self.email_verified != Some(false))
+    }
+
+    /// Readable name for the user: their username, else email, else subject
+    fn user(&self) -> String {
+        [self.preferred_username.as_deref(), self.email.as_deref()]
+            .into_iter()
+            .flatten()
+            .find(|v| !v.is_empty())
+            .unwrap_or(&self.sub)
+            .to_string()
+    }
+}
+
+/// Accepts ID tokens that one issuer signed for our client, from allowed users
+pub struct OidcProvider {
+    config: OidcConfig,
+    jwks_uri: String,
+    client: reqwest::Client,
+    /// The issuer's signing keys and when they were fetched
+    keys: tokio::sync::RwLock<(JwkSet, Instant)>,
+}
+
+impl OidcProvider {
+    /// Provider for `config.issuer`, found through its discovery document
+    pub async fn discover(config: OidcConfig) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        let discovery = discover(&client, &config.issuer).await?;
+        if discovery.issuer.trim_end_matches('/') != config.issuer.trim_end_matches('/') {
+            bail!("The discovery document is for issuer {}", discovery.issuer);
+        }
+        let keys = fetch_keys(&client, &discovery.jwks_uri).await?;
+        Ok(Self {
+            config: OidcConfig { issuer: discovery.issuer, ..config },
+            jwks_uri: discovery.jwks_uri,
+            client,
+            keys: tokio::sync::RwLock::new((keys, Instant::now())),
+        })
+    }
+
+    /// The key `kid` names, refetching the set once in a while in case the
+    /// issuer has rotated its keys
+    async fn key(&self, kid: &str) -> Option<DecodingKey> {
+        let find = |keys: &JwkSet| {
+            keys.find(kid)
+                // Shared secrets have no place in a public key set
+                .filter(|jwk| !matches!(jwk.algorithm, AlgorithmParameters::OctetKey(_)))
+                .and_then(|jwk| DecodingKey::from_jwk(jwk).ok())
+        };
+        {
+            let keys = self.keys.read().await;
+            if let Some(key) = find(&keys.0) {
+                return Some(key);
+            }
+            if keys.1.elapsed() < JWKS_REFRESH_INTERVAL {
+                return None;
+            }
+        }
+        let mut keys = self.keys.write().await;
+        if keys.1.elapsed() >= JWKS_REFRESH_INTERVAL {
+            match fetch_keys(&self.client, &self.jwks_uri).await {
+                Ok(fresh) => keys.0 = fresh,
+                Err(e) => tracing::warn!("Failed to refresh OIDC signing keys: {:#}", e),
+            }
+            keys.1 = Instant::now();
+        }
+        find(&keys.0)
+    }
+
+    /// Claims of `token` if it is a valid ID token for our client
+    async fn verify(&self, token: &str) -> Option<Claims> {
+        let header = jsonwebtoken::decode_header(token).ok()?;
+        let key = self.key(header.kid.as_deref()?).await?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.config.issuer]);
+        validation.set_audience(&[&self.config.client_id]);
+        let claims = jsonwebtoken::decode::<Claims>(token, &key, &validation).ok()?.claims;
+        // With several audiences, the party the token was issued to is `azp`
+        if claims.azp.as_deref().is_some_and(|azp| azp != self.config.client_id) {
+            return None;
+        }
+        Some(claims)
+    }
+
+    fn allowed(&self, claims: &Claims) -> bool {
+        let email = claims.verified_email();
+        self.config.allow.iter().any(|entry| match entry.strip_prefix('@') {
+            Some(domain) => email
+                .and_then(|email| email.rsplit_once('@'))
+                .is_some_and(|(_, d)| d.eq_ignore_ascii_case(domain)),
+            None => email.is_some_and(|email| email.eq_ignore_ascii_case(entry)) || claims.sub == *entry,
+        })
+    }
+}
+
+async fn fetch_keys(client: &reqwest::Client, jwks_uri: &str) -> anyhow::Result<JwkSet> {
+    client
+        .get(jwks_uri)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to fetch {}", jwks_uri))?
+        .json()
+        .await
+        .context("Invalid JSON Web Key Set")
+}
+
+impl AuthProvider for OidcProvider {
+    fn authenticate<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Option<Identity>> {
+        async move {
+            let claims = self.verify(token).await?;
+            if !self.allowed(&claims) {
+                tracing::warn!("Refused OIDC sign-in from {}: not on --oidc-allow", claims.user());
+                return None;
+            }
+            Some(Identity { user: claims.user(), provider: "oidc" })
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use axum::{routing::get, routing::post, Json, Router};
+    use jsonwebtoken::{Algorithm, EncodingKey, Header};
+
+    /// Throwaway RSA key the mock issuer signs with
+    const TEST_KEY: &str = include_str!("oidc/test_key.pem");
+    /// Its modulus, base64url
+    const TEST_KEY_N: &str = "vM-2kfYm3P9JFV74qs-_yWIDQ03a4Ro6q1sfQoniKIMXG9bVnSrehWfJmNmZIqWBSCpz5baitkCiVf-YGl4fozNWaUzednRBFBXv_fmcf4OG2SByjPhPqzHhCmShEWUfy5OLkxKvOiZq7_asLJa4g6efUKFEb0ZogbwplxP4Mi5Gt4uRfk7aP81u0vI8vhIX5FMYWNkR6h3ItD3DFoY6NEi4pqbeTRpEbUajmZISLRwQiSQBvZLaQCZYcowXWPWB_Fq23sh91Kaheeu6TzhPjDlsVZsKNXrh573hTVCX8tp_-c4A0CmymvwVRuMENcsghYA9TVbWT17UMkb1MBFX1w";
+
+    /// ID token from `issuer` with `claims` added to a valid set for rbxsync-cli
+    fn id_token(issuer: &str, claims: serde_json::Value) -> String {
+        let mut body = serde_json::json!({
+            "iss": issuer,
+            "aud": "rbxsync-cli",
+            "sub": "1234",
+            "exp": crate::nightly::now_secs() + 600,
+        });
+        body.as_object_mut().unwrap().extend(claims.as_object().unwrap().clone());
+        let header = Header { kid: Some("key-1".to_string()), ..Header::new(Algorithm::RS256) };
+        jsonwebtoken::encode(&header, &body, &EncodingKey::from_rsa_pem(TEST_KEY.as_bytes()).unwrap()).unwrap()
+    }
+
+    /// Issuer that approves the device code on the second poll
+    async fn mock_issuer(jwks_calls: Arc<AtomicUsize>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let polls = Arc::new(AtomicUsize::new(0));
+        let discovery = serde_json::json!({
+            "issuer": base,
+            "device_authorization_endpoint": format!("{}/device", base),
+            "token_endpoint": format!("{}/token", base),
+            "jwks_uri": format!("{}/jwks", base),
+        });
+        let token = id_token(&base, serde_json::json!({ "email": "alice@example.com", "email_verified": true }));
+        let router = Router::new()
+            .route("/.well-known/openid-configuration", get(move || async move { Json(discovery) }))
+            .route(
+                "/device",
+                post(|| async {
+                    Json(serde_json::json!({
+                        "device_code": "device-1",
+                        "user_code": "WDJB-MJHT",
+                        "verification_uri": "https://issuer.example/device",
+                        "expires_in": 60,
+                        "interval": 0
+                    }))
+                }),
+            )
+            .route(
+                "/token",
+                post(move || async move {
+                    if polls.fetch_add(1, Ordering::SeqCst) == 0 {
+                        Json(serde_json::json!({ "error": "authorization_pending" }))
+                    } else {
+                        Json(serde_json::json!({ "access_token": "access-1", "id_token": token }))
+                    }
+                }),
+            )
+            .route(
+                "/jwks",
+                get(move || async move {
+                    jwks_calls.fetch_add(1, Ordering::SeqCst);
+                    Json(serde_json::json!({
+                        "keys": [{ "kty": "RSA", "kid": "key-1", "alg": "RS256", "use": "sig", "n": TEST_KEY_N, "e": "AQAB" }]
+                    }))
+                }),
+            );
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        base
+    }
+
+    fn config(issuer: &str, allow: &[&str]) -> OidcConfig {
+        OidcConfig {
+            issuer: issuer.to_string(),
+            client_id: "rbxsync-cli".to_string(),
+            allow: allow.iter().map(|entry| entry.to_string()).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_device_login_and_verify() {
+        let jwks_calls = Arc::new(AtomicUsize::new(0));
+        let issuer = mock_issuer(jwks_calls.clone()).await;
+        let client = reqwest::Client::new();
+
+        let discovery = discover(&client, &issuer).await.unwrap();
+        let code = start_device_login(&client, &discovery, "rbxsync-cli").await.unwrap();
+        assert_eq!(code.user_code, "WDJB-MJHT");
+        let token = finish_device_login(&client, &discovery, "rbxsync-cli", &code).await.unwrap();
+
+        let provider = OidcProvider::discover(config(&issuer, &["@example.com"])).await.unwrap();
+        let alice = Identity { user: "alice@example.com".to_string(), provider: "oidc" };
+        assert_eq!(provider.authenticate(&token).await, Some(alice.clone()));
+        assert_eq!(provider.authenticate(&token).await, Some(alice));
+        assert_eq!(provider.authenticate("access-1").await, None);
+        // Verifying is local: the keys were fetched once, at discovery
+        assert_eq!(jwks_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_refuses_other_clients_and_users() {
+        let issuer = mock_issuer(Arc::new(AtomicUsize::new(0))).await;
+        let provider = OidcProvider::discover(config(&issuer, &["alice@example.com", "5678"])).await.unwrap();
+        let accepted = |claims| {
+            let token = id_token(&issuer, claims);
+            let provider = &provider;
+            async move { provider.authenticate(&token).await.is_some() }
+        };
+
+        assert!(accepted(serde_json::json!({ "email": "alice@example.com" })).await);
+        assert!(accepted(serde_json::json!({ "sub": "5678" })).await);
+        // Issued to another app signed in with the same issuer
+        assert!(!accepted(serde_json::json!({ "email": "alice@example.com", "aud": "other-app" })).await);
+        assert!(!accepted(serde_json::json!({ "email": "alice@example.com", "aud": ["rbxsync-cli", "other-app"], "azp": "other-app" })).await);
+        // Someone else with an account at the issuer
+        assert!(!accepted(serde_json::json!({ "email": "mallory@example.com" })).await);
+        assert!(!accepted(serde_json::json!({ "email": "alice@example.com", "email_verified": false })).await);
+        // Another issuer, or expired
+        assert!(!accepted(serde_json::json!({ "email": "alice@example.com", "iss": "https://evil.example" })).await);
+        assert!(!accepted(serde_json::json!({ "email": "alice@example.com", "exp": 1000 })).await);
+    }
+
+    #[test]
+    fn test_claims_user() {
+        let claims = |value| serde_json::from_value::<Claims>(value).unwrap();
+        let user = claims(serde_json::json!({ "sub": "1234", "email": "a@example.com" }));
+        assert_eq!(user.user(), "a@example.com");
+        assert_eq!(claims(serde_json::json!({ "sub": "1234" })).user(), "1234");
+    }
+}
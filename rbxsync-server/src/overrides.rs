@@ -0,0 +1,139 @@
+//! Per-variant property overrides for syncs
+//!
+//! Loads the selected variant's overrides (see `rbxsync_core::overrides`) and
+//! applies them as the last step of `/sync/read-tree` and live sync. Paths in
+//! the config are DataModel paths; instances here carry file system paths, so
+//! they go through the reverse tree mapping before matching.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use rbxsync_core::{OverrideReport, Overrides};
+
+/// Overrides for a project's selected variant (`variant` wins over the environment and config)
+pub fn load(project_dir: &str, variant: Option<&str>) -> Result<Option<Overrides>, String> {
+    let config = crate::load_project_config(project_dir).unwrap_or_default();
+    Overrides::from_config(&config, variant)
+}
+
+fn tree_mapping(project_dir: &str) -> HashMap<String, String> {
+    crate::get_tree_mapping(&crate::load_project_config(project_dir))
+}
+
+/// Script class and file for an instance path with no .rbxjson (scripts are plain .luau files)
+fn script_file(src_dir: &Path, fs_path: &str) -> Option<(&'static str, std::path::PathBuf)> {
+    [(".server.luau", "Script"), (".client.luau", "LocalScript"), (".luau", "ModuleScript")]
+        .into_iter()
+        .map(|(ext, class)| (class, src_dir.join(format!("{}{}", fs_path, ext))))
+        .find(|(_, path)| path.is_file())
+}
+
+/// Apply overrides to the instances read from a project. Overridden scripts
+/// that only exist as .luau files are added so the override reaches Studio.
+pub fn apply_to_tree(project_dir: &str, overrides: &Overrides, instances: &mut Vec<serde_json::Value>) -> OverrideReport {
+    let mapping = tree_mapping(project_dir);
+    let to_instance_path =
+        |fs_path: &str| crate::normalize_path_for_comparison(&crate::apply_reverse_tree_mapping(fs_path, &mapping));
+    let mut report = overrides.apply_all(instances, to_instance_path);
+
+    let src_dir = Path::new(project_dir).join("src");
+    let mut added = Vec::new();
+    for path in std::mem::take(&mut report.missing) {
+        let fs_path = crate::apply_tree_mapping(&path, &mapping);
+        let Some((class_name, file)) = script_file(&src_dir, &fs_path) else {
+            report.missing.push(path);
+            continue;
+        };
+        let source = std::fs::read_to_string(&file).unwrap_or_default();
+        let mut inst = serde_json::json!({
+            "className": class_name,
+            "name": path.rsplit('/').next().unwrap_or(&path),
+            "path": crate::normalize_path_for_comparison(&fs_path),
+            "properties": { "Source": { "type": "string", "value": source } }
+        });
+        added.extend(overrides.apply(&path, &mut inst));
+        instances.push(inst);
+    }
+    report.applied.extend(added);
+    report
+}
+
+/// Apply overrides to live sync operations for the project
+pub fn apply_to_operations(project_dir: &str, operations: &mut [serde_json::Value]) {
+    let overrides = match load(project_dir, None) {
+        Ok(Some(overrides)) if !overrides.is_empty() => overrides,
+        Ok(_) => return,
+        Err(e) => {
+            tracing::warn!("Ignoring overrides for {}: {}", project_dir, e);
+            return;
+        }
+    };
+    let mapping = tree_mapping(project_dir);
+    for op in operations.iter_mut() {
+        let Some(path) = op.get("path").and_then(|p| p.as_str()) else {
+            continue;
+        };
+        let path = crate::normalize_path_for_comparison(&crate::apply_reverse_tree_mapping(path, &mapping));
+        if let Some(data) = op.get_mut("data") {
+            for applied in overrides.apply(&path, data) {
+                tracing::info!(
+                    "Override ({}): {}.{} = {}",
+                    overrides.variant,
+                    applied.path,
+                    applied.property,
+                    applied.value
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_overrides_to_tree_and_operations() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        let dir = root.to_string_lossy().to_string();
+        std::fs::write(
+            root.join("rbxsync.json"),
+            r#"{
+                "treeMapping": { "ServerScriptService": "server" },
+                "overrides": { "dev": {
+                    "ServerScriptService/Analytics": { "Enabled": false },
+                    "ServerScriptService/Combat": { "RunContext": "Server" },
+                    "ServerScriptService/Missing": { "Enabled": false }
+                } }
+            }"#,
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("src/server")).unwrap();
+        std::fs::write(root.join("src/server/Analytics.server.luau"), "print('track')").unwrap();
+
+        assert!(load(&dir, Some("prod")).is_err());
+        let overrides = load(&dir, Some("dev")).unwrap().unwrap();
+        let mut instances = vec![serde_json::json!({ "path": "server/Combat", "className": "Script" })];
+        let report = apply_to_tree(&dir, &overrides, &mut instances);
+        assert_eq!(report.variant, "dev");
+        assert_eq!(report.applied.len(), 2);
+        assert_eq!(report.missing, vec!["ServerScriptService/Missing".to_string()]);
+        assert_eq!(instances.len(), 2);
+        assert_eq!(instances[1]["path"], "server/Analytics");
+        assert_eq!(instances[1]["className"], "Script");
+        assert_eq!(instances[1]["properties"]["Enabled"]["value"], false);
+        assert_eq!(instances[1]["properties"]["Source"]["value"], "print('track')");
+
+        // Live sync only applies a variant chosen by the environment or config
+        let mut operations = vec![serde_json::json!({ "type": "update", "path": "server/Analytics", "data": {} })];
+        apply_to_operations(&dir, &mut operations);
+        assert!(operations[0]["data"].get("properties").is_none());
+        let mut config: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(root.join("rbxsync.json")).unwrap()).unwrap();
+        config["variant"] = serde_json::json!("dev");
+        std::fs::write(root.join("rbxsync.json"), config.to_string()).unwrap();
+        apply_to_operations(&dir, &mut operations);
+        assert_eq!(operations[0]["data"]["properties"]["Enabled"]["value"], false);
+    }
+}
@@ -0,0 +1,248 @@
+//! Pausing live sync
+//!
+//! A `git checkout` or rebase rewrites hundreds of files at once, and live
+//! sync would push every intermediate state to Studio. `POST /sync/pause`
+//! holds file changes instead; `POST /sync/resume` sends what changed while
+//! paused, one change per path, compared against the files as they are now.
+//! Extraction pauses live sync on its own and drops the changes it causes;
+//! that flag is separate, so finishing an extraction doesn't end a pause
+//! the user asked for.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::file_watcher::{FileChange, FileChangeKind};
+use crate::AppState;
+
+/// A path that changed while paused
+#[derive(Debug, Clone)]
+struct Held {
+    project_dir: String,
+    is_dir: bool,
+    /// Whether Studio has it, i.e. it existed before its first held change
+    existed: bool,
+}
+
+#[derive(Debug)]
+struct Paused {
+    since: SystemTime,
+    reason: Option<String>,
+    held: HashMap<PathBuf, Held>,
+}
+
+/// Live sync paused by the user, and the changes held meanwhile
+#[derive(Debug, Default)]
+pub struct SyncPause {
+    paused: Mutex<Option<Paused>>,
+}
+
+/// Pause state reported on `/health` and by `rbxsync status`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PauseStatus {
+    pub paused: bool,
+    /// `user` or `extraction`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paused_by: Option<&'static str>,
+    /// Unix millis the user paused at
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Paths waiting for resume
+    pub held_changes: usize,
+}
+
+impl SyncPause {
+    /// Start holding changes. False if already paused.
+    pub fn pause(&self, reason: Option<String>) -> bool {
+        let Ok(mut paused) = self.paused.lock() else { return false };
+        if paused.is_some() {
+            return false;
+        }
+        *paused = Some(Paused { since: SystemTime::now(), reason, held: HashMap::new() });
+        true
+    }
+
+    /// Stop holding changes and return the ones to send, or None if not paused
+    pub fn resume(&self) -> Option<Vec<FileChange>> {
+        let paused = self.paused.lock().ok()?.take()?;
+        Some(replay(paused.held))
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.lock().map(|paused| paused.is_some()).unwrap_or(false)
+    }
+
+    /// Hold `changes` if paused; true if they were held
+    pub fn hold(&self, changes: &[FileChange]) -> bool {
+        let Ok(mut guard) = self.paused.lock() else { return false };
+        let Some(paused) = guard.as_mut() else { return false };
+        for change in changes {
+            let mut record = |path: &PathBuf, existed: bool| {
+                paused.held.entry(path.clone()).or_insert_with(|| Held {
+                    project_dir: change.project_dir.clone(),
+                    is_dir: change.is_dir,
+                    existed,
+                });
+            };
+            match &change.kind {
+                FileChangeKind::Create => record(&change.path, false),
+                FileChangeKind::Modify | FileChangeKind::Delete => record(&change.path, true),
+                FileChangeKind::Rename { from } => {
+                    record(from, true);
+                    record(&change.path, false);
+                }
+            }
+        }
+        true
+    }
+
+    /// What `/health` reports; `extracting` is the extraction flag
+    pub fn status(&self, extracting: bool) -> PauseStatus {
+        let guard = self.paused.lock().ok();
+        let paused = guard.as_ref().and_then(|guard| guard.as_ref());
+        PauseStatus {
+            paused: paused.is_some() || extracting,
+            paused_by: match (paused.is_some(), extracting) {
+                (true, _) => Some("user"),
+                (false, true) => Some("extraction"),
+                (false, false) => None,
+            },
+            since: paused.and_then(|p| p.since.duration_since(UNIX_EPOCH).ok()).map(|d| d.as_millis() as u64),
+            reason: paused.and_then(|p| p.reason.clone()),
+            held_changes: paused.map(|p| p.held.len()).unwrap_or(0),
+        }
+    }
+}
+
+/// One change per held path, from whether Studio has it and whether the
+/// file is there now. A file created and removed while paused is dropped.
+fn replay(held: HashMap<PathBuf, Held>) -> Vec<FileChange> {
+    let mut changes: Vec<FileChange> = held
+        .into_iter()
+        .filter_map(|(path, held)| {
+            let kind = match (held.existed, path.exists()) {
+                (false, true) => FileChangeKind::Create,
+                (true, true) if !held.is_dir => FileChangeKind::Modify,
+                (true, false) => FileChangeKind::Delete,
+                _ => return None,
+            };
+            let size = (kind != FileChangeKind::Delete && !held.is_dir)
+                .then(|| std::fs::metadata(&path).ok().map(|m| m.len()))
+                .flatten();
+            Some(FileChange {
+                path,
+                project_dir: held.project_dir,
+                kind,
+                is_dir: held.is_dir,
+                size,
+                size_delta: None,
+                content_hash: None,
+            })
+        })
+        .collect();
+    // Parents before children, so folders exist before what goes in them
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    changes
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PauseRequest {
+    /// Shown on `/health`, e.g. "rebasing"
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Hold live sync changes until resumed (POST /sync/pause)
+pub async fn handle_pause(State(state): State<Arc<AppState>>, body: Option<Json<PauseRequest>>) -> impl IntoResponse {
+    let reason = body.and_then(|Json(req)| req.reason).filter(|r| !r.trim().is_empty());
+    if !state.sync_pause.pause(reason.clone()) {
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "success": false, "error": "Live sync is already paused" })),
+        );
+    }
+    tracing::info!("Live sync paused{}", reason.map(|r| format!(": {}", r)).unwrap_or_default());
+    (StatusCode::OK, Json(serde_json::json!({ "success": true, "paused": true })))
+}
+
+/// Send the changes held while paused and resume live sync (POST /sync/resume)
+pub async fn handle_resume(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let Some(changes) = state.sync_pause.resume() else {
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "success": false, "error": "Live sync isn't paused" })),
+        );
+    };
+    let count = changes.len();
+    {
+        let watcher = state.file_watcher_state.read().await;
+        for change in changes {
+            let _ = watcher.change_tx.send(change);
+        }
+    }
+    tracing::info!("Live sync resumed, sending {} held change(s)", count);
+    (StatusCode::OK, Json(serde_json::json!({ "success": true, "paused": false, "changes": count })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(path: &std::path::Path, kind: FileChangeKind) -> FileChange {
+        FileChange {
+            path: path.to_path_buf(),
+            project_dir: "/games/obby".to_string(),
+            kind,
+            is_dir: false,
+            size: None,
+            size_delta: None,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_pause_holds_one_change_per_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let edited = dir.path().join("Edited.luau");
+        let added = dir.path().join("Added.luau");
+        let scratch = dir.path().join("Scratch.luau");
+        let removed = dir.path().join("Removed.luau");
+        std::fs::write(&edited, "return 2").unwrap();
+        std::fs::write(&added, "return {}").unwrap();
+
+        let pause = SyncPause::default();
+        assert!(!pause.hold(&[change(&edited, FileChangeKind::Modify)]));
+        assert!(pause.pause(Some("rebasing".to_string())));
+        assert!(!pause.pause(None));
+        assert!(pause.hold(&[
+            change(&edited, FileChangeKind::Modify),
+            change(&added, FileChangeKind::Create),
+            change(&scratch, FileChangeKind::Create),
+            change(&scratch, FileChangeKind::Delete),
+            change(&removed, FileChangeKind::Modify),
+            change(&removed, FileChangeKind::Delete),
+            change(&edited, FileChangeKind::Modify),
+        ]));
+        let status = pause.status(false);
+        assert_eq!((status.paused_by, status.reason.as_deref(), status.held_changes), (Some("user"), Some("rebasing"), 4));
+
+        let kinds: Vec<(PathBuf, FileChangeKind)> = pause.resume().unwrap().into_iter().map(|c| (c.path, c.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (added, FileChangeKind::Create),
+                (edited, FileChangeKind::Modify),
+                (removed, FileChangeKind::Delete),
+            ]
+        );
+        assert!(pause.resume().is_none());
+        assert_eq!(pause.status(true).paused_by, Some("extraction"));
+    }
+}
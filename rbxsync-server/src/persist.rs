@@ -0,0 +1,326 @@
+//! Durable server state
+//!
+//! Queued plugin requests, sync timestamps and the running extraction only
+//! lived in memory, so a restart dropped them without a word: sync commands
+//! waiting for the plugin never reached Studio, and the next sync treated
+//! every file as changed. Each project's share of that state is written to
+//! `.rbxsync/state.json` every few seconds and on shutdown, and read back the
+//! first time the restarted server sees the project (or at startup, for the
+//! project it was started in).
+//!
+//! Queued requests are only put back when the project opts in with
+//! `"restoreQueue": true` in rbxsync.json, and only if they were saved in the
+//! last [`QUEUE_MAX_AGE`]: they are writes and deletes that nobody is waiting
+//! on any more, and replaying a stale one would undo newer work. Otherwise
+//! they are dropped with a warning. Restored requests still reach the plugin;
+//! their responses are dropped.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{checkpoint, AppState, ExtractionSession, PluginRequest};
+
+pub const STATE_FILE: &str = "state.json";
+
+/// How often changed state is written
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Oldest saved queue that `restoreQueue` still replays
+pub const QUEUE_MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// What survives a restart for one project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedState {
+    /// Unix millis when the file was written
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saved_at: Option<u64>,
+    /// Unix millis of the last sync to Studio
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_sync: Option<u64>,
+    /// Requests waiting for the project's plugin, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub queue: Vec<PluginRequest>,
+    /// Unfinished extraction checkpointed in this project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extraction: Option<PersistedExtraction>,
+}
+
+impl PersistedState {
+    pub fn is_empty(&self) -> bool {
+        self.last_sync.is_none() && self.queue.is_empty() && self.extraction.is_none()
+    }
+}
+
+/// Enough to reopen an extraction from its checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedExtraction {
+    pub session_id: String,
+    #[serde(default)]
+    pub strict: bool,
+    #[serde(default = "default_true")]
+    pub include_assets: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// `<project>/.rbxsync/state.json`
+pub fn state_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(".rbxsync").join(STATE_FILE)
+}
+
+/// Saved state for a project, if it has any
+pub fn load(project_dir: &Path) -> Option<PersistedState> {
+    let content = std::fs::read_to_string(state_path(project_dir)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn to_millis(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH).ok().map(|d| d.as_millis() as u64)
+}
+
+/// The project's current share of the in-memory state
+async fn snapshot(state: &AppState, project_dir: &str) -> PersistedState {
+    let queue = state
+        .project_queues
+        .read()
+        .await
+        .get(project_dir)
+        .map(|queue| queue.iter().cloned().collect())
+        .unwrap_or_default();
+    let last_sync = state
+        .sync_state
+        .read()
+        .await
+        .get(project_dir)
+        .and_then(|s| s.last_sync)
+        .and_then(to_millis);
+    let extraction = state
+        .extraction_session
+        .read()
+        .await
+        .as_ref()
+        .filter(|s| !s.finalized && s.project_dir.as_deref() == Some(project_dir))
+        .map(|s| PersistedExtraction {
+            session_id: s.id.clone(),
+            strict: s.strict,
+            include_assets: s.include_assets,
+        });
+    PersistedState { saved_at: None, last_sync, queue, extraction }
+}
+
+/// Whether the project opted in to replaying its saved queue
+fn restore_queue_enabled(project_dir: &str) -> bool {
+    crate::load_project_config(project_dir)
+        .and_then(|config| config.get("restoreQueue")?.as_bool())
+        .unwrap_or(false)
+}
+
+/// Whether a queue saved at `saved_at` should be replayed now
+fn queue_is_fresh(saved_at: Option<u64>, now: SystemTime) -> bool {
+    let Some(saved_at) = saved_at else {
+        return false;
+    };
+    to_millis(now).is_some_and(|now| now.saturating_sub(saved_at) <= QUEUE_MAX_AGE.as_millis() as u64)
+}
+
+/// What is compared to decide whether the file needs writing: the state
+/// without its timestamp, which changes on every write
+fn fingerprint(saved: &PersistedState) -> String {
+    let unstamped = PersistedState { saved_at: None, ..saved.clone() };
+    if unstamped.is_empty() {
+        return String::new();
+    }
+    serde_json::to_string(&unstamped).unwrap_or_default()
+}
+
+/// Put a project's saved state back, once per project. Requests restored to
+/// the queue go before anything queued since the restart.
+pub async fn restore_project(state: &AppState, project_dir: &str) {
+    if project_dir.is_empty() {
+        return;
+    }
+    let saved = {
+        let Ok(mut written) = state.persisted.lock() else {
+            return;
+        };
+        if written.contains_key(project_dir) {
+            return;
+        }
+        let saved = load(Path::new(project_dir));
+        written.insert(project_dir.to_string(), saved.as_ref().map(fingerprint).unwrap_or_default());
+        saved
+    };
+    let Some(mut saved) = saved else {
+        return;
+    };
+
+    if !saved.queue.is_empty() && !restore_queue_enabled(project_dir) {
+        tracing::warn!(
+            "Dropped {} request(s) queued for {} before the restart (set \"restoreQueue\": true in rbxsync.json to replay them)",
+            saved.queue.len(),
+            project_dir
+        );
+        saved.queue.clear();
+    } else if !saved.queue.is_empty() && !queue_is_fresh(saved.saved_at, SystemTime::now()) {
+        tracing::warn!(
+            "Dropped {} request(s) queued for {} more than {} minutes ago",
+            saved.queue.len(),
+            project_dir,
+            QUEUE_MAX_AGE.as_secs() / 60
+        );
+        saved.queue.clear();
+    }
+
+    if !saved.queue.is_empty() {
+        let mut queues = state.project_queues.write().await;
+        let queue = queues.entry(project_dir.to_string()).or_default();
+        let restored: Vec<PluginRequest> =
+            saved.queue.into_iter().filter(|r| !queue.iter().any(|q| q.id == r.id)).collect();
+        tracing::info!("Restored {} queued request(s) for {}", restored.len(), project_dir);
+        for request in restored.into_iter().rev() {
+            queue.push_front(request);
+        }
+    }
+
+    if let Some(millis) = saved.last_sync {
+        let mut sync_state = state.sync_state.write().await;
+        let project_state = sync_state.entry(project_dir.to_string()).or_default();
+        if project_state.last_sync.is_none() {
+            project_state.last_sync = Some(UNIX_EPOCH + Duration::from_millis(millis));
+        }
+    }
+
+    if let Some(extraction) = saved.extraction {
+        let checkpoint_dir = checkpoint::Checkpoint::dir_for(Some(project_dir), &extraction.session_id);
+        let mut session = state.extraction_session.write().await;
+        // The plugin resumes it through /extract/resume; until then it's only reported
+        if session.is_none() && checkpoint_dir.join(checkpoint::MANIFEST_FILE).exists() {
+            *session = Some(ExtractionSession {
+                strict: extraction.strict,
+                include_assets: extraction.include_assets,
+                ..ExtractionSession::open(&extraction.session_id, Some(project_dir))
+            });
+        }
+    }
+}
+
+/// Write a project's state if it changed since the last write. A project
+/// with nothing left to keep has its file removed.
+pub async fn save_project(state: &AppState, project_dir: &str) {
+    // Never overwrite a file that hasn't been read back yet
+    restore_project(state, project_dir).await;
+
+    let mut snapshot = snapshot(state, project_dir).await;
+    let fingerprint = fingerprint(&snapshot);
+    let Ok(mut written) = state.persisted.lock() else {
+        return;
+    };
+    if written.get(project_dir) == Some(&fingerprint) {
+        return;
+    }
+
+    let path = state_path(Path::new(project_dir));
+    let result = if fingerprint.is_empty() {
+        std::fs::remove_file(&path).or_else(|e| if e.kind() == std::io::ErrorKind::NotFound { Ok(()) } else { Err(e) })
+    } else {
+        snapshot.saved_at = to_millis(SystemTime::now());
+        serde_json::to_vec_pretty(&snapshot)
+            .map_err(std::io::Error::other)
+            .and_then(|content| crate::recovery::write_atomic(&path, &content))
+    };
+    match result {
+        Ok(()) => {
+            written.insert(project_dir.to_string(), fingerprint);
+        }
+        Err(e) => tracing::warn!("Failed to save {}: {}", path.display(), e),
+    }
+}
+
+/// Projects that have, or had, something to save
+async fn known_projects(state: &AppState) -> Vec<String> {
+    let mut projects: Vec<String> = state.project_queues.read().await.keys().cloned().collect();
+    projects.extend(state.sync_state.read().await.keys().cloned());
+    if let Some(dir) = state.extraction_session.read().await.as_ref().and_then(|s| s.project_dir.clone()) {
+        projects.push(dir);
+    }
+    if let Ok(written) = state.persisted.lock() {
+        projects.extend(written.keys().cloned());
+    }
+    projects.retain(|dir| !dir.is_empty() && Path::new(dir).is_dir());
+    projects.sort();
+    projects.dedup();
+    projects
+}
+
+/// Save every project's state
+pub async fn save_all(state: &AppState) {
+    for project_dir in known_projects(state).await {
+        save_project(state, &project_dir).await;
+    }
+}
+
+/// Background task saving state every `SAVE_INTERVAL`
+pub async fn run_saver(state: Arc<AppState>) {
+    loop {
+        tokio::time::sleep(SAVE_INTERVAL).await;
+        save_all(&state).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_state_survives_restart() {
+        let temp = tempfile::tempdir().unwrap();
+        let project_dir = temp.path().to_string_lossy().to_string();
+        let request = PluginRequest {
+            id: Uuid::new_v4(),
+            command: "sync:batch".to_string(),
+            payload: serde_json::json!({ "operations": [] }),
+            queued_at: std::time::Instant::now(),
+        };
+
+        let before = AppState::new();
+        before.project_queues.write().await.entry(project_dir.clone()).or_default().push_back(request.clone());
+        let synced = UNIX_EPOCH + Duration::from_millis(1_760_000_000_000);
+        before.sync_state.write().await.entry(project_dir.clone()).or_default().last_sync = Some(synced);
+        save_all(&before).await;
+
+        let saved = load(temp.path()).unwrap();
+        assert_eq!(saved.queue.len(), 1);
+        assert_eq!(saved.last_sync, Some(1_760_000_000_000));
+        assert!(queue_is_fresh(saved.saved_at, SystemTime::now()));
+        assert!(!queue_is_fresh(saved.saved_at, SystemTime::now() + QUEUE_MAX_AGE * 2));
+        assert!(!queue_is_fresh(None, SystemTime::now()));
+
+        // A new server seeing the project for the first time keeps the sync
+        // time but not the queue, which the project hasn't opted in to
+        let after = AppState::new();
+        restore_project(&after, &project_dir).await;
+        assert!(after.project_queues.read().await.get(&project_dir).is_none_or(|q| q.is_empty()));
+        assert_eq!(after.sync_state.read().await[&project_dir].last_sync, Some(synced));
+
+        std::fs::write(temp.path().join("rbxsync.json"), r#"{ "restoreQueue": true }"#).unwrap();
+        let after = AppState::new();
+        restore_project(&after, &project_dir).await;
+        let queues = after.project_queues.read().await;
+        assert_eq!(queues[&project_dir].front().map(|r| r.id), Some(request.id));
+        drop(queues);
+
+        // Once delivered and forgotten, the file goes away
+        after.project_queues.write().await.clear();
+        after.sync_state.write().await.clear();
+        save_all(&after).await;
+        assert!(!state_path(temp.path()).exists());
+    }
+}
@@ -0,0 +1,160 @@
+//! Place-to-place diff endpoint
+//!
+//! Compares two connected Studio sessions directly (e.g. a test place and the
+//! production place), without going through project files.
+
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::protocol::{PluginCommand, StudioPaths};
+use crate::{cleanup_stale_registrations, AppState, PlaceInfo};
+
+/// Request to diff two connected places
+#[derive(Debug, Deserialize)]
+pub struct DiffPlacesRequest {
+    /// First place: session ID, place ID or place name
+    pub place_a: String,
+    /// Second place: session ID, place ID or place name
+    pub place_b: String,
+    /// Also compare serialized properties (slower on large places)
+    #[serde(default)]
+    pub include_properties: bool,
+    /// Only compare instances under this DataModel path (e.g. "ReplicatedStorage")
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+type ErrorResponse = (StatusCode, Json<serde_json::Value>);
+
+fn error(status: StatusCode, message: impl Into<String>) -> ErrorResponse {
+    (
+        status,
+        Json(serde_json::json!({ "success": false, "error": message.into() })),
+    )
+}
+
+/// Find a registered place by session ID, place ID or name
+fn resolve_place<'a>(
+    registry: &'a std::collections::HashMap<String, PlaceInfo>,
+    selector: &str,
+) -> Result<(&'a String, &'a PlaceInfo), ErrorResponse> {
+    if let Some((key, place)) = registry.get_key_value(selector) {
+        return Ok((key, place));
+    }
+
+    let matches: Vec<(&String, &PlaceInfo)> = registry
+        .iter()
+        .filter(|(_, p)| p.place_id.to_string() == selector || p.place_name.eq_ignore_ascii_case(selector))
+        .collect();
+
+    match matches.as_slice() {
+        [one] => Ok(*one),
+        [] => Err(error(
+            StatusCode::NOT_FOUND,
+            format!("No connected place matches '{}'", selector),
+        )),
+        _ => Err(error(
+            StatusCode::BAD_REQUEST,
+            format!("'{}' matches {} places; use a session ID", selector, matches.len()),
+        )),
+    }
+}
+
+/// Send `studio:paths` to one Studio session and wait for its listing
+async fn fetch_paths(
+    state: &Arc<AppState>,
+    session_key: &str,
+    include_properties: bool,
+) -> Result<Vec<serde_json::Value>, ErrorResponse> {
+    let request_id = Uuid::new_v4();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    state.response_channels.write().await.insert(request_id, tx);
+    state
+        .session_queues
+        .write()
+        .await
+        .entry(session_key.to_string())
+        .or_default()
+        .push_back(
+            PluginCommand::StudioPaths(StudioPaths { include_properties, ..Default::default() }).request(request_id),
+        );
+    state.wakeups.notify_session(session_key);
+
+    let timeout = if include_properties { 180 } else { 60 };
+    let result = tokio::time::timeout(tokio::time::Duration::from_secs(timeout), rx.recv()).await;
+    state.response_channels.write().await.remove(&request_id);
+
+    match result {
+        Ok(Some(response)) if response.success => Ok(response
+            .data
+            .get("paths")
+            .and_then(|p| p.as_array())
+            .cloned()
+            .unwrap_or_default()),
+        Ok(Some(response)) => Err(error(
+            StatusCode::OK,
+            response.error.unwrap_or_else(|| "Plugin returned error".to_string()),
+        )),
+        Ok(None) => Err(error(StatusCode::INTERNAL_SERVER_ERROR, "Channel closed")),
+        Err(_) => {
+            // Don't leave the request for a session that never picked it up
+            if let Some(queue) = state.session_queues.write().await.get_mut(session_key) {
+                queue.retain(|r| r.id != request_id);
+            }
+            Err(error(
+                StatusCode::GATEWAY_TIMEOUT,
+                format!("Timeout waiting for paths from session {}", session_key),
+            ))
+        }
+    }
+}
+
+/// Diff the instance trees of two connected Studio places (POST /diff/places)
+pub async fn handle_diff_places(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<DiffPlacesRequest>,
+) -> impl IntoResponse {
+    cleanup_stale_registrations(&state).await;
+
+    let (key_a, place_a, key_b, place_b) = {
+        let registry = state.place_registry.read().await;
+        let resolved = resolve_place(&registry, &req.place_a)
+            .and_then(|a| resolve_place(&registry, &req.place_b).map(|b| (a, b)));
+        match resolved {
+            Ok(((key_a, a), (key_b, b))) => (key_a.clone(), a.clone(), key_b.clone(), b.clone()),
+            Err(err) => return err,
+        }
+    };
+
+    if key_a == key_b {
+        return error(StatusCode::BAD_REQUEST, "Both selectors resolve to the same place");
+    }
+
+    // Both sessions list their trees in parallel
+    let (paths_a, paths_b) = tokio::join!(
+        fetch_paths(&state, &key_a, req.include_properties),
+        fetch_paths(&state, &key_b, req.include_properties)
+    );
+    let (paths_a, paths_b) = match (paths_a, paths_b) {
+        (Ok(a), Ok(b)) => (a, b),
+        (Err(err), _) | (_, Err(err)) => return err,
+    };
+
+    let diff = rbxsync_core::diff_places(&paths_a, &paths_b, req.path.as_deref());
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "success": true,
+            "placeA": place_a,
+            "placeB": place_b,
+            "countA": paths_a.len(),
+            "countB": paths_b.len(),
+            "diff": diff
+        })),
+    )
+}
@@ -0,0 +1,240 @@
+//! Port self-check
+//!
+//! Rojo, MCP servers and other rbxsync versions like to sit on the same local
+//! ports. This probes the well-known ones, asks whoever answers who they are
+//! (rbxsync's `/health`, Rojo's `/api/rojo`), and finds a free failover port
+//! when the configured one belongs to another rbxsync version. Used at startup,
+//! by `rbxsync serve` and by `/doctor`. `discover_servers` finds every rbxsync
+//! server on the machine for `/servers` and `rbxsync status --all`.
+
+use std::time::Duration;
+
+use rbxsync_core::{ServerEntry, DEFAULT_PORT, FAILOVER_PORTS};
+use serde::Serialize;
+
+/// Ports local Roblox dev tools listen on by default
+pub const WELL_KNOWN_PORTS: &[(u16, &str)] = &[(DEFAULT_PORT, "rbxsync"), (34872, "Rojo"), (8000, "Argon")];
+
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(300);
+const HTTP_TIMEOUT: Duration = Duration::from_millis(800);
+
+/// What was found on one port
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortStatus {
+    pub port: u16,
+    /// Tool that uses this port by default
+    pub default_for: String,
+    pub in_use: bool,
+    /// Tool that answered, if it identified itself
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl PortStatus {
+    pub fn is_rbxsync(&self) -> bool {
+        self.owner.as_deref() == Some("rbxsync")
+    }
+
+    /// `Rojo 7.4.1`, `rbxsync 1.3.0`, or `unknown`
+    pub fn owner_label(&self) -> String {
+        match (&self.owner, &self.version) {
+            (Some(owner), Some(version)) => format!("{} {}", owner, version),
+            (Some(owner), None) => owner.clone(),
+            _ => "unknown".to_string(),
+        }
+    }
+}
+
+/// Name a tool from its rbxsync `/health` or Rojo `/api/rojo` response
+pub fn identify(health: Option<&serde_json::Value>, rojo: Option<&serde_json::Value>) -> (Option<String>, Option<String>) {
+    let str_field = |v: &serde_json::Value, key: &str| v.get(key).and_then(|f| f.as_str()).map(str::to_string);
+
+    if let Some(health) = health {
+        let is_rbxsync = str_field(health, "service").as_deref() == Some("rbxsync")
+            || (str_field(health, "status").as_deref() == Some("ok") && health.get("version").is_some());
+        if is_rbxsync {
+            return (Some("rbxsync".to_string()), str_field(health, "version"));
+        }
+    }
+    if let Some(rojo) = rojo {
+        if rojo.get("serverVersion").is_some() {
+            return (Some("Rojo".to_string()), str_field(rojo, "serverVersion"));
+        }
+    }
+    (None, None)
+}
+
+async fn get_json(client: &reqwest::Client, url: &str) -> Option<serde_json::Value> {
+    client.get(url).send().await.ok()?.json().await.ok()
+}
+
+/// Check one port: is something listening, and what is it
+pub async fn probe_port(port: u16, default_for: &str) -> PortStatus {
+    let addr = format!("127.0.0.1:{}", port);
+    let in_use = matches!(
+        tokio::time::timeout(CONNECT_TIMEOUT, tokio::net::TcpStream::connect(&addr)).await,
+        Ok(Ok(_))
+    );
+
+    let (owner, version) = if in_use {
+        let client = reqwest::Client::builder().timeout(HTTP_TIMEOUT).build().unwrap_or_default();
+        let health = get_json(&client, &format!("http://{}/health", addr)).await;
+        let rojo = match &health {
+            Some(_) => None,
+            None => get_json(&client, &format!("http://{}/api/rojo", addr)).await,
+        };
+        identify(health.as_ref(), rojo.as_ref())
+    } else {
+        (None, None)
+    };
+
+    PortStatus {
+        port,
+        default_for: default_for.to_string(),
+        in_use,
+        owner,
+        version,
+    }
+}
+
+/// Probe the configured port, the well-known ports and the failover range
+pub async fn check_ports(configured: u16) -> Vec<PortStatus> {
+    let mut ports: Vec<(u16, &str)> = vec![(configured, "rbxsync")];
+    for &(port, tool) in WELL_KNOWN_PORTS {
+        if port != configured {
+            ports.push((port, tool));
+        }
+    }
+    for port in FAILOVER_PORTS {
+        if port != configured {
+            ports.push((port, "rbxsync (failover)"));
+        }
+    }
+
+    let probes = ports.into_iter().map(|(port, tool)| probe_port(port, tool));
+    futures::future::join_all(probes).await
+}
+
+/// An rbxsync server that answered on this machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredServer {
+    pub port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// From `~/.rbxsync/servers.json`, when the server is registered there
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    /// Project directories of its connected places and workspaces
+    pub projects: Vec<String>,
+    pub auth: bool,
+}
+
+/// Build a `DiscoveredServer` from a `/health` response, if it's rbxsync's
+pub fn discovered_server(port: u16, health: &serde_json::Value, registry: &[ServerEntry]) -> Option<DiscoveredServer> {
+    let (owner, version) = identify(Some(health), None);
+    if owner.as_deref() != Some("rbxsync") {
+        return None;
+    }
+    let projects = health
+        .get("projects")
+        .and_then(|p| p.as_array())
+        .map(|p| p.iter().filter_map(|dir| dir.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+    Some(DiscoveredServer {
+        port,
+        version,
+        pid: registry.iter().find(|e| e.port == port).map(|e| e.pid),
+        projects,
+        auth: health.get("auth").and_then(|a| a.as_bool()).unwrap_or(false),
+    })
+}
+
+/// Ask every port an rbxsync server might be on for its `/health`
+pub async fn discover_servers() -> Vec<DiscoveredServer> {
+    let registry = rbxsync_core::registry_path()
+        .map(|path| rbxsync_core::load_registry(&path))
+        .unwrap_or_default();
+    let client = reqwest::Client::builder().timeout(HTTP_TIMEOUT).build().unwrap_or_default();
+
+    let probes = rbxsync_core::discovery_ports(&registry).into_iter().map(|port| {
+        let client = &client;
+        async move {
+            let addr = format!("127.0.0.1:{}", port);
+            match tokio::time::timeout(CONNECT_TIMEOUT, tokio::net::TcpStream::connect(&addr)).await {
+                Ok(Ok(_)) => get_json(client, &format!("http://{}/health", addr)).await.map(|h| (port, h)),
+                _ => None,
+            }
+        }
+    });
+    futures::future::join_all(probes)
+        .await
+        .into_iter()
+        .flatten()
+        .filter_map(|(port, health)| discovered_server(port, &health, &registry))
+        .collect()
+}
+
+/// First failover port nothing is listening on
+pub fn find_free_port() -> Option<u16> {
+    FAILOVER_PORTS.into_iter().find(|port| std::net::TcpListener::bind(("127.0.0.1", *port)).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_identify_port_owner() {
+        let rbxsync = json!({ "status": "ok", "version": "1.2.0", "idle": false });
+        assert_eq!(identify(Some(&rbxsync), None), (Some("rbxsync".to_string()), Some("1.2.0".to_string())));
+
+        let rojo = json!({ "serverVersion": "7.4.1", "protocolVersion": 4, "projectName": "obby" });
+        assert_eq!(identify(None, Some(&rojo)), (Some("Rojo".to_string()), Some("7.4.1".to_string())));
+
+        assert_eq!(identify(Some(&json!({ "ok": true })), None), (None, None));
+
+        let status = PortStatus {
+            port: 34872,
+            default_for: "Rojo".to_string(),
+            in_use: true,
+            owner: Some("Rojo".to_string()),
+            version: Some("7.4.1".to_string()),
+        };
+        assert_eq!(status.owner_label(), "Rojo 7.4.1");
+        assert!(!status.is_rbxsync());
+    }
+
+    #[test]
+    fn test_discovered_server() {
+        let registry = vec![ServerEntry {
+            port: 44756,
+            pid: 4242,
+            version: "1.3.0".to_string(),
+            cwd: String::new(),
+            started_at: 0,
+        }];
+        let health = json!({
+            "status": "ok",
+            "service": "rbxsync",
+            "version": "1.3.0",
+            "auth": true,
+            "projects": ["/games/obby", "/games/tycoon"]
+        });
+        let server = discovered_server(44756, &health, &registry).unwrap();
+        assert_eq!(server.pid, Some(4242));
+        assert_eq!(server.projects, vec!["/games/obby", "/games/tycoon"]);
+        assert!(server.auth);
+
+        // Older servers don't list projects
+        let old = discovered_server(DEFAULT_PORT, &json!({ "status": "ok", "version": "1.0.0" }), &registry).unwrap();
+        assert_eq!(old.pid, None);
+        assert!(old.projects.is_empty());
+
+        assert_eq!(discovered_server(34872, &json!({ "serverVersion": "7.4.1" }), &registry), None);
+    }
+}
@@ -0,0 +1,230 @@
+//! Request priority and live-sync coalescing
+//!
+//! Saving a dozen files at once (a formatter run, a branch switch) queues a
+//! burst of `sync:batch` requests, and a plugin polling one request at a time
+//! would work through all of them before a `run:code` queued behind them.
+//! Requests fall into three classes: interactive commands someone is waiting
+//! on, sync batches, and background work like extraction. Project queues keep
+//! higher classes in front, and a poll takes the highest class across the
+//! session, project and global queues.
+//!
+//! A new live-sync batch also supersedes operations still queued for the same
+//! paths: the newest edit to a file is the only one worth sending.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde_json::Value;
+
+use crate::PluginRequest;
+
+/// How soon a request should reach the plugin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    /// Extraction and tree scans that take a while anyway
+    Background,
+    /// File changes on their way to Studio
+    Sync,
+    /// Commands an endpoint (and usually a person) is waiting on
+    Interactive,
+}
+
+impl Priority {
+    /// The class of a plugin command
+    pub fn of(command: &str) -> Self {
+        if command.starts_with("sync:") {
+            Priority::Sync
+        } else if command.starts_with("extract:") || command == "studio:paths" {
+            Priority::Background
+        } else {
+            Priority::Interactive
+        }
+    }
+}
+
+/// Index `request` belongs at: behind everything of its class or higher
+fn insert_index(queue: &VecDeque<PluginRequest>, priority: Priority) -> usize {
+    queue.iter().position(|queued| Priority::of(&queued.command) < priority).unwrap_or(queue.len())
+}
+
+/// Queue `request` by priority. A live-sync batch first supersedes operations
+/// queued for the same paths, and isn't queued at all if every one of its
+/// operations was folded into a queued one. Returns how many queued
+/// operations were superseded.
+pub fn enqueue(queue: &mut VecDeque<PluginRequest>, mut request: PluginRequest) -> usize {
+    let mut superseded = 0;
+    if is_live_sync(&request) {
+        superseded = coalesce(queue, &mut request);
+        if operations(&request).is_some_and(|ops| ops.is_empty()) {
+            return superseded;
+        }
+    }
+    let index = insert_index(queue, Priority::of(&request.command));
+    queue.insert(index, request);
+    superseded
+}
+
+/// Index of the request a poll should take next
+pub fn next_index(queue: &VecDeque<PluginRequest>) -> Option<usize> {
+    let best = queue.iter().map(|request| Priority::of(&request.command)).max()?;
+    queue.iter().position(|request| Priority::of(&request.command) == best)
+}
+
+/// Priority of the request a poll would take next
+pub fn peek(queue: &VecDeque<PluginRequest>) -> Option<Priority> {
+    next_index(queue).map(|index| Priority::of(&queue[index].command))
+}
+
+/// Take the highest-priority request, oldest first within a class
+pub fn pop(queue: &mut VecDeque<PluginRequest>) -> Option<PluginRequest> {
+    next_index(queue).and_then(|index| queue.remove(index))
+}
+
+/// A `sync:batch` from the file watcher. Batches from endpoints are left
+/// alone, since their callers expect every operation to run.
+pub fn is_live_sync(request: &PluginRequest) -> bool {
+    request.command == "sync:batch" && request.payload.get("source").and_then(Value::as_str) == Some("file_watcher")
+}
+
+fn operations(request: &PluginRequest) -> Option<&Vec<Value>> {
+    request.payload.get("operations").and_then(Value::as_array)
+}
+
+fn op_type(op: &Value) -> &str {
+    op.get("type").and_then(Value::as_str).unwrap_or("")
+}
+
+fn op_path(op: &Value) -> Option<&str> {
+    op.get("path").and_then(Value::as_str)
+}
+
+/// Whether an operation leaves nothing of an older one on the same path
+fn supersedes(newer: &str, older: &str) -> bool {
+    matches!((newer, older), ("create" | "update" | "delete", "update") | ("create" | "delete", "create"))
+}
+
+/// The newest operation seen so far for a path, walking the queue backwards
+struct Newest {
+    kind: String,
+    /// Index in the new batch, until it's folded into a queued create
+    index: Option<usize>,
+    /// False once an older operation can't be dropped (e.g. a delete before
+    /// a create); anything older than that has to run as queued
+    open: bool,
+}
+
+/// Drop or fold queued live-sync operations that `request` makes redundant.
+/// Queued batches left empty are removed.
+fn coalesce(queue: &mut VecDeque<PluginRequest>, request: &mut PluginRequest) -> usize {
+    // A rename moves everything under a path, so paths before and after it
+    // don't name the same instance
+    let has_rename = |request: &PluginRequest| operations(request).is_some_and(|ops| ops.iter().any(|op| op_type(op) == "rename"));
+    if has_rename(request) || queue.iter().any(|queued| is_live_sync(queued) && has_rename(queued)) {
+        return 0;
+    }
+    let Some(new_ops) = request.payload.get_mut("operations").and_then(Value::as_array_mut) else {
+        return 0;
+    };
+
+    let mut newest: HashMap<String, Newest> = HashMap::new();
+    for (index, op) in new_ops.iter().enumerate() {
+        if let Some(path) = op_path(op) {
+            newest.insert(path.to_string(), Newest { kind: op_type(op).to_string(), index: Some(index), open: true });
+        }
+    }
+
+    let mut superseded = 0;
+    let mut folded = Vec::new();
+    for queued in queue.iter_mut().rev().filter(|queued| is_live_sync(queued)) {
+        let Some(old_ops) = queued.payload.get_mut("operations").and_then(Value::as_array_mut) else {
+            continue;
+        };
+        for old in (0..old_ops.len()).rev() {
+            let Some(entry) = op_path(&old_ops[old]).and_then(|path| newest.get_mut(path)) else {
+                continue;
+            };
+            if !entry.open {
+                continue;
+            }
+            let old_kind = op_type(&old_ops[old]);
+            let fold = entry.index.filter(|_| entry.kind == "update" && old_kind == "create");
+            if supersedes(&entry.kind, old_kind) {
+                old_ops.remove(old);
+                superseded += 1;
+            } else if let Some(index) = fold {
+                // The instance isn't in Studio yet: create it with the newest content
+                old_ops[old]["data"] = new_ops[index]["data"].clone();
+                folded.push(index);
+                entry.index = None;
+                entry.kind = "create".to_string();
+                superseded += 1;
+            } else {
+                entry.open = false;
+            }
+        }
+    }
+
+    folded.sort_unstable();
+    for index in folded.into_iter().rev() {
+        new_ops.remove(index);
+    }
+    queue.retain(|queued| !(is_live_sync(queued) && operations(queued).is_some_and(|ops| ops.is_empty())));
+    superseded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{NoPayload, PluginCommand, RunCode, SyncBatch};
+    use uuid::Uuid;
+
+    fn live_sync(operations: Vec<Value>) -> PluginRequest {
+        PluginCommand::SyncBatch(SyncBatch { operations, source: Some("file_watcher".to_string()) }).request(Uuid::new_v4())
+    }
+
+    fn op(kind: &str, path: &str, source: &str) -> Value {
+        serde_json::json!({ "type": kind, "path": path, "data": { "source": source } })
+    }
+
+    fn queued_ops(queue: &VecDeque<PluginRequest>) -> Vec<(String, String, String)> {
+        queue
+            .iter()
+            .filter_map(operations)
+            .flatten()
+            .map(|op| (op_type(op).to_string(), op_path(op).unwrap().to_string(), op["data"]["source"].as_str().unwrap_or("").to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_priority_and_coalescing() {
+        let mut queue = VecDeque::new();
+        enqueue(&mut queue, PluginCommand::StudioPaths(Default::default()).request(Uuid::new_v4()));
+        enqueue(&mut queue, live_sync(vec![op("update", "A", "a1"), op("create", "B", "b1")]));
+        enqueue(&mut queue, live_sync(vec![op("delete", "C", "")]));
+
+        // The newest edit wins; an update to a queued create becomes the create
+        assert_eq!(enqueue(&mut queue, live_sync(vec![op("update", "A", "a2"), op("update", "B", "b2")])), 2);
+        assert_eq!(
+            queued_ops(&queue),
+            vec![
+                ("create".to_string(), "B".to_string(), "b2".to_string()),
+                ("delete".to_string(), "C".to_string(), String::new()),
+                ("update".to_string(), "A".to_string(), "a2".to_string()),
+            ]
+        );
+        // A delete can't be dropped for a later create
+        assert_eq!(enqueue(&mut queue, live_sync(vec![op("create", "C", "c1")])), 0);
+
+        let run_code = PluginCommand::RunCode(RunCode { code: "print(1)".to_string() }).request(Uuid::new_v4());
+        let run_id = run_code.id;
+        enqueue(&mut queue, run_code);
+        assert_eq!(queue.front().map(|request| request.id), Some(run_id));
+        assert_eq!(queue.back().map(|request| request.command.as_str()), Some("studio:paths"));
+
+        // Pops take the best class even when it was queued at the back
+        let mut legacy = VecDeque::from([live_sync(vec![op("update", "D", "d1")])]);
+        legacy.push_back(PluginCommand::TestStop(NoPayload {}).request(Uuid::new_v4()));
+        assert_eq!(peek(&legacy), Some(Priority::Interactive));
+        assert_eq!(pop(&mut legacy).map(|request| request.command), Some("test:stop".to_string()));
+        assert_eq!(Priority::of("extract:start"), Priority::Background);
+    }
+}
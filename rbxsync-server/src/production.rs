@@ -0,0 +1,495 @@
+//! Console ingestion from live game servers
+//!
+//! Production errors arrive two ways: a generated in-game reporter script
+//! POSTs batches to `/console/ingest`, and `rbxsync console import` sends the
+//! rows of an analytics export there. Both are normalized into
+//! [`ConsoleMessage`]s tagged `production` and merged into the console buffer,
+//! so `/console/errors` aggregates them alongside Studio output.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, ConsoleMessage};
+
+/// Tag added to every ingested message
+pub const PRODUCTION_TAG: &str = "production";
+
+/// When set, `/console/ingest` requires this key in [`INGEST_KEY_HEADER`]
+pub const INGEST_KEY_ENV: &str = "RBXSYNC_INGEST_KEY";
+
+pub const INGEST_KEY_HEADER: &str = "x-rbxsync-ingest-key";
+
+fn default_count() -> u64 {
+    1
+}
+
+/// One error event from a live server or an analytics export row
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductionEvent {
+    pub message: String,
+    /// "error" (default), "warn" or "info"
+    #[serde(default)]
+    pub level: Option<String>,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub stack: Option<String>,
+    /// Script that raised the error
+    #[serde(default)]
+    pub script: Option<String>,
+    /// Occurrences this event stands for (exports are often pre-aggregated)
+    #[serde(default = "default_count")]
+    pub count: u64,
+}
+
+/// Batch of events (POST /console/ingest)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestRequest {
+    #[serde(default)]
+    pub place_id: Option<u64>,
+    #[serde(default)]
+    pub job_id: Option<String>,
+    pub events: Vec<ProductionEvent>,
+}
+
+/// Current UTC time as HH:MM:SS, matching Studio console timestamps
+fn now_timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format!("{:02}:{:02}:{:02}", secs / 3600 % 24, secs / 60 % 60, secs % 60)
+}
+
+/// Convert an event into a console message tagged `production`
+pub fn normalize_event(event: &ProductionEvent, place_id: Option<u64>, job_id: Option<&str>) -> ConsoleMessage {
+    let message_type = match event.level.as_deref().map(str::to_ascii_lowercase).as_deref() {
+        Some("warn" | "warning") => "warn",
+        Some("info" | "output" | "print") => "info",
+        _ => "error",
+    };
+
+    let mut message = event.message.trim().to_string();
+    if let Some(script) = event.script.as_deref().filter(|s| !s.is_empty()) {
+        if !message.starts_with(script) {
+            message = format!("{}: {}", script, message);
+        }
+    }
+    if let Some(stack) = event.stack.as_deref().filter(|s| !s.trim().is_empty()) {
+        message = format!("{}\n{}", message, stack.trim_end());
+    }
+
+    let source = match (place_id, job_id) {
+        (Some(place), Some(job)) => format!("production:{}/{}", place, job),
+        (Some(place), None) => format!("production:{}", place),
+        _ => PRODUCTION_TAG.to_string(),
+    };
+
+    ConsoleMessage {
+        timestamp: event.timestamp.clone().unwrap_or_else(now_timestamp),
+        message_type: message_type.to_string(),
+        message,
+        source: Some(source),
+        tags: vec![PRODUCTION_TAG.to_string()],
+        count: (event.count > 1).then_some(event.count),
+        locations: Vec::new(),
+        received_at: None,
+    }
+}
+
+/// Split one CSV line, honoring quotes and doubled quotes
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Map an export column name to an event field
+fn export_field(column: &str) -> Option<&'static str> {
+    let column: String = column
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    match column.as_str() {
+        "message" | "error" | "errormessage" => Some("message"),
+        "count" | "occurrences" | "errorcount" => Some("count"),
+        "timestamp" | "time" | "date" | "lastseen" => Some("timestamp"),
+        "level" | "severity" | "type" | "messagetype" => Some("level"),
+        "stack" | "stacktrace" | "traceback" => Some("stack"),
+        "script" | "source" | "scriptpath" => Some("script"),
+        _ => None,
+    }
+}
+
+fn event_from_fields(fields: impl IntoIterator<Item = (&'static str, String)>) -> Option<ProductionEvent> {
+    let mut event = ProductionEvent {
+        count: 1,
+        ..Default::default()
+    };
+    for (field, value) in fields {
+        let value = value.trim().to_string();
+        if value.is_empty() {
+            continue;
+        }
+        match field {
+            "message" => event.message = value,
+            "count" => event.count = value.parse().unwrap_or(1),
+            "timestamp" => event.timestamp = Some(value),
+            "level" => event.level = Some(value),
+            "stack" => event.stack = Some(value),
+            "script" => event.script = Some(value),
+            _ => {}
+        }
+    }
+    (!event.message.is_empty()).then_some(event)
+}
+
+/// Parse an analytics error export: a JSON array, JSON lines, or CSV with a header row.
+///
+/// Columns are matched loosely (`Error Message`, `Occurrences`, `Stack Trace`, ...);
+/// rows without a message are skipped.
+pub fn parse_analytics_export(content: &str) -> Result<Vec<ProductionEvent>, String> {
+    let trimmed = content.trim_start_matches('\u{feff}').trim();
+
+    let objects: Option<Vec<serde_json::Value>> = if trimmed.starts_with('[') {
+        Some(serde_json::from_str(trimmed).map_err(|e| format!("Invalid JSON export: {}", e))?)
+    } else if trimmed.starts_with('{') {
+        let rows: Result<Vec<_>, _> = trimmed
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect();
+        Some(rows.map_err(|e| format!("Invalid JSON lines export: {}", e))?)
+    } else {
+        None
+    };
+
+    if let Some(objects) = objects {
+        return Ok(objects
+            .iter()
+            .filter_map(|obj| obj.as_object())
+            .filter_map(|obj| {
+                event_from_fields(obj.iter().filter_map(|(key, value)| {
+                    let text = match value {
+                        serde_json::Value::String(s) => s.clone(),
+                        serde_json::Value::Null => return None,
+                        other => other.to_string(),
+                    };
+                    export_field(key).map(|field| (field, text))
+                }))
+            })
+            .collect());
+    }
+
+    let mut lines = trimmed.lines();
+    let header: Vec<Option<&'static str>> = lines
+        .next()
+        .map(|line| split_csv_line(line).iter().map(|c| export_field(c)).collect())
+        .unwrap_or_default();
+    if !header.contains(&Some("message")) {
+        return Err("Export has no message column".to_string());
+    }
+
+    Ok(lines
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            event_from_fields(
+                header
+                    .iter()
+                    .zip(split_csv_line(line))
+                    .filter_map(|(field, value)| field.map(|f| (f, value))),
+            )
+        })
+        .collect())
+}
+
+/// Receive production events (POST /console/ingest)
+pub async fn handle_ingest(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<IngestRequest>,
+) -> impl IntoResponse {
+    if let Ok(expected) = std::env::var(INGEST_KEY_ENV) {
+        let provided = headers.get(INGEST_KEY_HEADER).and_then(|v| v.to_str().ok());
+        if !expected.is_empty() && provided != Some(expected.as_str()) {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({
+                    "success": false,
+                    "error": format!("Missing or wrong {} header", INGEST_KEY_HEADER)
+                })),
+            );
+        }
+    }
+
+    let messages: Vec<ConsoleMessage> = req
+        .events
+        .iter()
+        .filter(|e| !e.message.trim().is_empty())
+        .map(|e| normalize_event(e, req.place_id, req.job_id.as_deref()))
+        .collect();
+    let count = crate::push_console_messages(&state, messages).await;
+    tracing::info!("Ingested {} production console messages", count);
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "success": true, "received": count })),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ErrorsQuery {
+    /// Only messages with this tag (e.g. "production")
+    pub tag: Option<String>,
+    /// Include warnings as well as errors
+    #[serde(default)]
+    pub warnings: bool,
+}
+
+/// Occurrences of one error, grouped by [`crate::console::fingerprint`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorGroup {
+    /// First line of the first occurrence
+    pub message: String,
+    pub count: u64,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub tags: Vec<String>,
+    pub sources: Vec<String>,
+    /// Full text of the latest occurrence (with stack)
+    pub sample: String,
+    /// Local files and lines of the latest occurrence
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<crate::correlate::SourceLocation>,
+}
+
+/// Group error messages by fingerprint, most frequent first
+pub fn aggregate_errors<'a>(
+    messages: impl IntoIterator<Item = &'a ConsoleMessage>,
+    tag: Option<&str>,
+    include_warnings: bool,
+) -> Vec<ErrorGroup> {
+    let mut order: Vec<String> = Vec::new();
+    let mut groups: HashMap<String, ErrorGroup> = HashMap::new();
+
+    for msg in messages {
+        let wanted = msg.message_type == "error" || (include_warnings && msg.message_type == "warn");
+        let studio_tag = msg.tags.is_empty() && tag == Some("studio");
+        if !wanted || (tag.is_some() && !studio_tag && !msg.tags.iter().any(|t| Some(t.as_str()) == tag)) {
+            continue;
+        }
+
+        let key = crate::console::fingerprint(&msg.message);
+        let group = groups.entry(key.clone()).or_insert_with(|| {
+            order.push(key);
+            ErrorGroup {
+                message: msg.message.lines().next().unwrap_or_default().trim().to_string(),
+                count: 0,
+                first_seen: msg.timestamp.clone(),
+                last_seen: msg.timestamp.clone(),
+                tags: Vec::new(),
+                sources: Vec::new(),
+                sample: String::new(),
+                locations: Vec::new(),
+            }
+        });
+        group.count += msg.count.unwrap_or(1);
+        group.last_seen = msg.timestamp.clone();
+        group.sample = msg.message.clone();
+        if !msg.locations.is_empty() {
+            group.locations = msg.locations.clone();
+        }
+        for tag in &msg.tags {
+            if !group.tags.contains(tag) {
+                group.tags.push(tag.clone());
+            }
+        }
+        if let Some(source) = &msg.source {
+            if !group.sources.contains(source) {
+                group.sources.push(source.clone());
+            }
+        }
+    }
+
+    let mut result: Vec<ErrorGroup> = order.into_iter().filter_map(|k| groups.remove(&k)).collect();
+    // Stable sort keeps first-seen order among equal counts
+    result.sort_by_key(|g| std::cmp::Reverse(g.count));
+    result
+}
+
+/// Aggregated errors from the console buffer (GET /console/errors)
+pub async fn handle_errors(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ErrorsQuery>,
+) -> impl IntoResponse {
+    let buffer = state.console_buffer.read().await;
+    let groups = aggregate_errors(buffer.iter(), params.tag.as_deref(), params.warnings);
+    let total: u64 = groups.iter().map(|g| g.count).sum();
+    Json(serde_json::json!({
+        "success": true,
+        "groups": groups,
+        "total": total
+    }))
+}
+
+/// Luau source for the in-game reporter (a server Script)
+pub fn reporter_source(ingest_url: &str, ingest_key: Option<&str>) -> String {
+    let url = serde_json::to_string(ingest_url).unwrap_or_else(|_| "\"\"".to_string());
+    let key = serde_json::to_string(ingest_key.unwrap_or_default()).unwrap_or_else(|_| "\"\"".to_string());
+    format!(
+        r#"--!strict
+-- Generated by `rbxsync console reporter`: sends server errors from live games
+-- to the RbxSync console. Requires HttpService.HttpEnabled and a publicly
+-- reachable RbxSync server (or a relay in front of it).
+
+local HttpService = game:GetService("HttpService")
+local RunService = game:GetService("RunService")
+local ScriptContext = game:GetService("ScriptContext")
+
+local INGEST_URL = {url}
+local INGEST_KEY = {key}
+local FLUSH_INTERVAL = 10 -- seconds between batches
+local MAX_BATCH = 50
+
+-- Studio output already reaches RbxSync through the plugin
+if RunService:IsStudio() then
+	return
+end
+
+type Event = {{ message: string, stack: string?, script: string?, timestamp: string, count: number }}
+
+local pending: {{ [string]: Event }} = {{}}
+local pendingCount = 0
+
+ScriptContext.Error:Connect(function(message: string, stack: string, origin: Instance?)
+	local key = message .. "\0" .. (origin and origin:GetFullName() or "")
+	local existing = pending[key]
+	if existing then
+		existing.count += 1
+		existing.timestamp = os.date("!%Y-%m-%dT%H:%M:%SZ")
+		return
+	end
+	if pendingCount >= MAX_BATCH then
+		return
+	end
+	pending[key] = {{
+		message = message,
+		stack = stack,
+		script = origin and origin:GetFullName() or nil,
+		timestamp = os.date("!%Y-%m-%dT%H:%M:%SZ"),
+		count = 1,
+	}}
+	pendingCount += 1
+end)
+
+local function flush()
+	if pendingCount == 0 then
+		return
+	end
+	local events = {{}}
+	for _, event in pending do
+		table.insert(events, event)
+	end
+	pending = {{}}
+	pendingCount = 0
+
+	local headers: {{ [string]: string }} = {{ ["Content-Type"] = "application/json" }}
+	if INGEST_KEY ~= "" then
+		headers["X-RbxSync-Ingest-Key"] = INGEST_KEY
+	end
+	pcall(function()
+		HttpService:RequestAsync({{
+			Url = INGEST_URL,
+			Method = "POST",
+			Headers = headers,
+			Body = HttpService:JSONEncode({{
+				placeId = game.PlaceId,
+				jobId = game.JobId,
+				events = events,
+			}}),
+		}})
+	end)
+end
+
+task.spawn(function()
+	while true do
+		task.wait(FLUSH_INTERVAL)
+		flush()
+	end
+end)
+
+game:BindToClose(flush)
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_production_errors() {
+        let csv = "Error Message,Occurrences,Stack Trace\n\
+                   \"attempt to index nil with 'Health'\",12,\"ServerScriptService.Combat:40\"\n\
+                   ,3,\n\
+                   Data store budget exceeded,2,\n";
+        let events = parse_analytics_export(csv).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].count, 12);
+        assert_eq!(events[0].stack.as_deref(), Some("ServerScriptService.Combat:40"));
+
+        let json = r#"[{"message": "Data store budget exceeded", "count": 5, "level": "warning"}]"#;
+        let from_json = parse_analytics_export(json).unwrap();
+        assert_eq!(from_json[0].count, 5);
+
+        let mut messages: Vec<ConsoleMessage> = events.iter().map(|e| normalize_event(e, Some(1818), None)).collect();
+        assert_eq!(messages[0].source.as_deref(), Some("production:1818"));
+        assert_eq!(messages[0].tags, vec![PRODUCTION_TAG.to_string()]);
+        messages.push(ConsoleMessage {
+            timestamp: "12:00:00".to_string(),
+            message_type: "error".to_string(),
+            message: "Data store budget exceeded".to_string(),
+            source: Some("studio".to_string()),
+            tags: Vec::new(),
+            count: None,
+            locations: Vec::new(),
+            received_at: None,
+        });
+
+        let all = aggregate_errors(&messages, None, false);
+        assert_eq!(all[0].count, 12);
+        assert_eq!(all[1].count, 3);
+        assert_eq!(all[1].sources.len(), 2);
+
+        let production = aggregate_errors(&messages, Some(PRODUCTION_TAG), false);
+        assert_eq!(production[1].count, 2);
+        assert_eq!(aggregate_errors(&messages, Some("studio"), false).len(), 1);
+    }
+}
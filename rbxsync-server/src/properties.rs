@@ -0,0 +1,222 @@
+//! Batch property edits
+//!
+//! `POST /instance/set-properties` takes a list of `{ path, property, value }`
+//! edits, where `value` is a typed value as in `.rbxjson` files. Each value is
+//! checked with [`rbxsync_core::validate_value`] first, so a malformed Vector3
+//! or an unknown enum item fails with a message instead of reaching Studio.
+//! The valid edits go to the plugin in one command (one undo step), and each
+//! one Studio accepts is written to the instance's `.rbxjson` file. Results
+//! come back per edit, in the order given.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::protocol::{self, PluginCommand, SetProperties};
+use crate::studio::{error, plugin_command, plugin_error};
+use crate::AppState;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Properties an edit can't change: renaming or reparenting would move files
+const FIXED_PROPERTIES: [&str; 3] = ["Name", "Parent", "ClassName"];
+
+#[derive(Debug, Deserialize)]
+pub struct PropertyEdit {
+    /// Instance path, e.g. `Workspace/SpawnLocation`
+    pub path: String,
+    pub property: String,
+    /// Typed value, e.g. `{ "type": "Vector3", "value": { "x": 0, "y": 5, "z": 0 } }`
+    pub value: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetPropertiesRequest {
+    pub edits: Vec<PropertyEdit>,
+    /// Project whose files to update (default: the only connected one)
+    pub project_dir: Option<String>,
+}
+
+/// POST /instance/set-properties
+pub async fn handle_set_properties(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<SetPropertiesRequest>,
+) -> impl IntoResponse {
+    if req.edits.is_empty() {
+        return error(StatusCode::BAD_REQUEST, "No edits given");
+    }
+
+    let mut results: Vec<Value> = Vec::with_capacity(req.edits.len());
+    let mut sent: Vec<(usize, protocol::PropertyEdit)> = Vec::new();
+    for (index, edit) in req.edits.iter().enumerate() {
+        match check_edit(edit) {
+            Ok(value) => {
+                sent.push((index, protocol::PropertyEdit { path: edit.path.clone(), property: edit.property.clone(), value }));
+                results.push(Value::Null);
+            }
+            Err(e) => results.push(json!({ "path": edit.path, "property": edit.property, "success": false, "error": e })),
+        }
+    }
+
+    if !sent.is_empty() {
+        let edits = sent.iter().map(|(_, edit)| edit.clone()).collect();
+        let response = match plugin_command(&state, PluginCommand::SetProperties(SetProperties { edits }), TIMEOUT).await {
+            Ok(response) => response,
+            Err(err) => return err,
+        };
+        if !response.success {
+            return error(StatusCode::BAD_REQUEST, plugin_error(&response));
+        }
+
+        let project_dir = crate::flags::resolve_project(&state, req.project_dir.as_deref()).await;
+        let studio_results = response.data["results"].as_array().cloned().unwrap_or_default();
+        let mut written = Vec::new();
+        for (n, (index, edit)) in sent.iter().enumerate() {
+            let mut result = studio_results.get(n).cloned().unwrap_or_else(|| {
+                json!({ "path": edit.path, "property": edit.property, "success": false, "error": "No result from Studio" })
+            });
+            if let (Some(project_dir), true) = (&project_dir, result["success"] == true) {
+                let path = edit.path.as_str();
+                let class_name = result["className"].as_str().unwrap_or("Folder");
+                let (key, file) = properties_file(Path::new(project_dir), path);
+                match write_property(&file, path, class_name, &edit.property, &edit.value) {
+                    Ok(()) => {
+                        result["file"] = json!(file.to_string_lossy());
+                        written.push(key);
+                    }
+                    Err(e) => result["fileError"] = json!(format!("Couldn't write {}: {}", file.display(), e)),
+                }
+            }
+            results[*index] = result;
+        }
+
+        if let (Some(project_dir), false) = (&project_dir, written.is_empty()) {
+            let project_path = Path::new(project_dir);
+            let mut sync_state = state.sync_state.write().await;
+            let project_state = sync_state.entry(project_dir.clone()).or_default();
+            for key in &written {
+                project_state.set_base(project_path, key);
+            }
+            project_state.save_bases(project_path);
+        }
+    }
+
+    let applied = results.iter().filter(|r| r["success"] == true).count();
+    (
+        StatusCode::OK,
+        Json(json!({
+            "success": true,
+            "applied": applied,
+            "failed": results.len() - applied,
+            "results": results
+        })),
+    )
+}
+
+/// Validate an edit and return its value in the form the plugin applies
+fn check_edit(edit: &PropertyEdit) -> Result<Value, String> {
+    if edit.path.is_empty() || edit.property.is_empty() {
+        return Err("path and property are required".to_string());
+    }
+    if FIXED_PROPERTIES.contains(&edit.property.as_str()) {
+        return Err(format!("{} can't be set by an edit", edit.property));
+    }
+    if edit.property == "Source" {
+        return Err("Use /script/set to change a script's source".to_string());
+    }
+    rbxsync_core::validate_value(&edit.value)?;
+
+    let mut properties = Map::new();
+    properties.insert(edit.property.clone(), edit.value.clone());
+    let mut instance = json!({ "properties": properties });
+    rbxsync_core::normalize_properties(&mut instance);
+    Ok(instance["properties"][edit.property.as_str()].clone())
+}
+
+/// Instance key and `.rbxjson` file of the instance at `path`: `_meta.rbxjson`
+/// in its directory when it has one, otherwise a sibling file
+pub fn properties_file(project_dir: &Path, path: &str) -> (String, PathBuf) {
+    let config = crate::load_project_config(&project_dir.to_string_lossy());
+    let key = crate::apply_tree_mapping(path, &crate::get_tree_mapping(&config));
+    let full_path = project_dir.join("src").join(&key);
+    let file = if full_path.is_dir() {
+        full_path.join("_meta.rbxjson")
+    } else {
+        rbxsync_core::pathbuf_with_suffix(&full_path, ".rbxjson")
+    };
+    (key, file)
+}
+
+/// Set one property in an instance's `.rbxjson`, creating the file if needed
+fn write_property(file: &Path, path: &str, class_name: &str, property: &str, value: &Value) -> std::io::Result<()> {
+    let mut instance = std::fs::read_to_string(file)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .filter(|instance| instance.is_object())
+        .unwrap_or_else(|| {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            json!({ "className": class_name, "name": name, "properties": {} })
+        });
+    if !instance["properties"].is_object() {
+        instance["properties"] = json!({});
+    }
+    instance["properties"][property] = value.clone();
+
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(file, serde_json::to_string_pretty(&instance).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(property: &str, value: Value) -> PropertyEdit {
+        PropertyEdit { path: "Workspace/Part".to_string(), property: property.to_string(), value }
+    }
+
+    #[test]
+    fn test_check_edit() {
+        let value = check_edit(&edit("Material", json!({ "type": "Enum", "value": "Enum.Material.Neon" }))).unwrap();
+        assert_eq!(value, json!({ "type": "Enum", "value": { "enumType": "Material", "value": "Neon" } }));
+
+        let error = check_edit(&edit("Size", json!({ "type": "Vector3", "value": { "x": 1 } }))).unwrap_err();
+        assert!(error.contains("Vector3"), "{}", error);
+        assert!(check_edit(&edit("Name", json!({ "type": "string", "value": "Other" }))).is_err());
+        assert!(check_edit(&edit("Source", json!({ "type": "string", "value": "" }))).is_err());
+    }
+
+    #[test]
+    fn test_write_property() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/Workspace/Model")).unwrap();
+        std::fs::write(
+            root.join("src/Workspace/Part.rbxjson"),
+            r#"{ "className": "Part", "name": "Part", "properties": { "Anchored": { "type": "bool", "value": true } } }"#,
+        )
+        .unwrap();
+
+        let (key, file) = properties_file(root, "Workspace/Part");
+        assert_eq!(key, "Workspace/Part");
+        let size = json!({ "type": "Vector3", "value": { "x": 1, "y": 2, "z": 3 } });
+        write_property(&file, "Workspace/Part", "Part", "Size", &size).unwrap();
+        let written: Value = serde_json::from_str(&std::fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(written["properties"]["Size"], size);
+        assert_eq!(written["properties"]["Anchored"]["value"], true);
+
+        // An instance with children keeps its properties in _meta.rbxjson
+        let (_, file) = properties_file(root, "Workspace/Model");
+        assert_eq!(file, root.join("src/Workspace/Model/_meta.rbxjson"));
+        write_property(&file, "Workspace/Model", "Model", "LevelOfDetail", &json!({ "type": "Enum", "value": "Enum.ModelLevelOfDetail.Disabled" })).unwrap();
+        let written: Value = serde_json::from_str(&std::fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(written["className"], "Model");
+        assert_eq!(written["name"], "Model");
+    }
+}
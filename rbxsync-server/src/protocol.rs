@@ -0,0 +1,386 @@
+//! Plugin protocol types
+//!
+//! Every command the server sends the Studio plugin is a variant of
+//! [`PluginCommand`], with a typed payload, instead of a `json!` blob built at
+//! each call site. The wire format is unchanged: a [`PluginRequest`] with the
+//! command name and its payload, answered by a [`PluginResponse`] whose `data`
+//! has the command's response type.
+//!
+//! `GET /schema` serves JSON Schemas generated from these types, so the
+//! plugin and the VS Code extension can validate what they send and receive.
+//! Commands only reached through `/sync/command` (like `test:run`) pass
+//! through untyped.
+
+use std::collections::BTreeMap;
+
+use axum::{response::IntoResponse, Json};
+use rbxsync_core::api::ExtractLimits;
+use schemars::{JsonSchema, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{PluginRequest, PluginResponse};
+
+/// Bumped when a payload or response changes incompatibly
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// A command for the Studio plugin
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "command", content = "payload")]
+pub enum PluginCommand {
+    #[serde(rename = "extract:start")]
+    ExtractStart(ExtractStart),
+    #[serde(rename = "sync:batch")]
+    SyncBatch(SyncBatch),
+    #[serde(rename = "studio:paths")]
+    StudioPaths(StudioPaths),
+    #[serde(rename = "studio:save")]
+    StudioSave(NoPayload),
+    #[serde(rename = "studio:play")]
+    StudioPlay(StudioPlay),
+    #[serde(rename = "test:start")]
+    TestStart(NoPayload),
+    #[serde(rename = "test:output")]
+    TestOutput(NoPayload),
+    #[serde(rename = "test:stop")]
+    TestStop(NoPayload),
+    #[serde(rename = "test:status")]
+    TestStatus(NoPayload),
+    #[serde(rename = "test:finish")]
+    TestFinish(NoPayload),
+    #[serde(rename = "test:specs")]
+    TestSpecs(TestSpecs),
+    #[serde(rename = "run:code")]
+    RunCode(RunCode),
+    #[serde(rename = "read-properties:get")]
+    ReadProperties(InstancePath),
+    #[serde(rename = "explore-hierarchy:get")]
+    ExploreHierarchy(ExploreHierarchy),
+    #[serde(rename = "find-instances:search")]
+    FindInstances(FindInstances),
+    #[serde(rename = "insert:model")]
+    InsertModel(InsertModel),
+    #[serde(rename = "properties:set")]
+    SetProperties(SetProperties),
+    #[serde(rename = "script:get")]
+    ScriptGet(InstancePath),
+    #[serde(rename = "script:set")]
+    ScriptSet(ScriptSet),
+    #[serde(rename = "script:edit-lines")]
+    ScriptEditLines(ScriptEditLines),
+    #[serde(rename = "plugin:reload")]
+    PluginReload(PluginReload),
+}
+
+impl PluginCommand {
+    /// The command's wire name, e.g. `studio:paths`
+    pub fn name(&self) -> String {
+        self.split().0
+    }
+
+    /// The request to queue for the plugin
+    pub fn request(self, id: Uuid) -> PluginRequest {
+        let (command, payload) = self.split();
+        PluginRequest { id, command, payload, queued_at: std::time::Instant::now() }
+    }
+
+    fn split(&self) -> (String, Value) {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        let command = value["command"].as_str().unwrap_or_default().to_string();
+        let payload = value.get_mut("payload").map(Value::take).unwrap_or_else(|| Value::Object(Default::default()));
+        (command, payload)
+    }
+}
+
+/// Payload of commands that take none (sent as `{}`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct NoPayload {}
+
+/// Payload naming one instance
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InstancePath {
+    /// DataModel path, e.g. `ServerScriptService/Main`
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractStart {
+    #[serde(rename = "project_dir")]
+    pub project_dir: Option<String>,
+    /// Services to extract; empty extracts them all
+    pub services: Vec<String>,
+    pub extract_terrain: bool,
+    pub include_assets: bool,
+    #[schemars(with = "Value")]
+    pub limits: ExtractLimits,
+    /// Send only instances whose hash differs from `known_hashes`
+    pub incremental: bool,
+    pub known_hashes: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SyncBatch {
+    /// `create`, `update` and `delete` operations, applied in order
+    pub operations: Vec<Value>,
+    /// `file_watcher` for live sync
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StudioPaths {
+    /// Add tags and attribute names (component diff)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_components: bool,
+    /// Add serialized properties (place-to-place diff)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_properties: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StudioPlay {
+    /// `play`, `run` or `server-and-clients`
+    pub mode: String,
+    /// Seconds before the session stops itself
+    pub duration: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub players: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_dir: Option<String>,
+    /// HTTP mock shim for projects with `mocks.json`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_mock: Option<HttpMock>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TestSpecs {
+    /// `play` or `run`
+    pub mode: String,
+    /// Seconds before the playtest stops itself
+    pub timeout: u64,
+    /// Luau source of the Script that runs the specs and ends the test with
+    /// their results
+    pub bootstrap: String,
+    /// HTTP mock shim for projects with `mocks.json`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_mock: Option<HttpMock>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HttpMock {
+    /// Luau source of the shim module
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RunCode {
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExploreHierarchy {
+    /// Start here; services when absent
+    pub path: Option<String>,
+    pub depth: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FindInstances {
+    pub class_name: Option<String>,
+    pub name: Option<String>,
+    pub parent: Option<String>,
+    pub limit: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertModel {
+    pub asset_id: u64,
+    /// Parent path; Workspace when absent
+    pub parent: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetProperties {
+    pub edits: Vec<PropertyEdit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PropertyEdit {
+    pub path: String,
+    pub property: String,
+    /// Value in .rbxjson form, e.g. `{ "type": "Vector3", "value": [0, 5, 0] }`
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScriptSet {
+    pub path: String,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptEditLines {
+    pub path: String,
+    /// First line to replace, from 1
+    pub start_line: usize,
+    /// Last line to replace; `start_line - 1` inserts
+    pub end_line: usize,
+    pub new_text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PluginReload {
+    pub version: u64,
+    pub name: String,
+}
+
+/// `data` of a `studio:paths` response
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StudioPathsData {
+    pub paths: Vec<StudioPath>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StudioPath {
+    pub path: String,
+    pub class_name: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Attribute names
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<Value>,
+}
+
+/// `data` of `script:get`, `script:set` and `script:edit-lines` responses
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptData {
+    pub path: String,
+    pub class_name: String,
+    /// The source Studio has after the command
+    pub source: String,
+    pub line_count: usize,
+}
+
+/// `data` of a `properties:set` response
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetPropertiesData {
+    pub results: Vec<PropertyResult>,
+    pub applied: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyResult {
+    pub path: String,
+    pub property: String,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// `data` of a `studio:save` response
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StudioSaveData {
+    pub place_id: u64,
+    pub place_name: String,
+}
+
+/// `data` of a `studio:play` response
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StudioPlayData {
+    pub mode: String,
+    pub message: String,
+}
+
+/// One command's payload and response schemas
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandSchema {
+    pub payload: Schema,
+    /// Schema of the response's `data`
+    pub response: Schema,
+}
+
+fn command<P: JsonSchema, R: JsonSchema>() -> CommandSchema {
+    CommandSchema { payload: schemars::schema_for!(P), response: schemars::schema_for!(R) }
+}
+
+/// Schemas of every typed command, by wire name
+pub fn command_schemas() -> BTreeMap<&'static str, CommandSchema> {
+    BTreeMap::from([
+        ("extract:start", command::<ExtractStart, Value>()),
+        ("sync:batch", command::<SyncBatch, Value>()),
+        ("studio:paths", command::<StudioPaths, StudioPathsData>()),
+        ("studio:save", command::<NoPayload, StudioSaveData>()),
+        ("studio:play", command::<StudioPlay, StudioPlayData>()),
+        ("test:start", command::<NoPayload, Value>()),
+        ("test:output", command::<NoPayload, Value>()),
+        ("test:stop", command::<NoPayload, Value>()),
+        ("test:status", command::<NoPayload, Value>()),
+        ("test:finish", command::<NoPayload, Value>()),
+        ("test:specs", command::<TestSpecs, Value>()),
+        ("run:code", command::<RunCode, Value>()),
+        ("read-properties:get", command::<InstancePath, Value>()),
+        ("explore-hierarchy:get", command::<ExploreHierarchy, Value>()),
+        ("find-instances:search", command::<FindInstances, Value>()),
+        ("insert:model", command::<InsertModel, Value>()),
+        ("properties:set", command::<SetProperties, SetPropertiesData>()),
+        ("script:get", command::<InstancePath, ScriptData>()),
+        ("script:set", command::<ScriptSet, ScriptData>()),
+        ("script:edit-lines", command::<ScriptEditLines, ScriptData>()),
+        ("plugin:reload", command::<PluginReload, Value>()),
+    ])
+}
+
+/// The protocol's schemas (GET /schema)
+pub async fn handle_schema() -> impl IntoResponse {
+    Json(serde_json::json!({
+        "protocolVersion": PROTOCOL_VERSION,
+        "serverVersion": env!("CARGO_PKG_VERSION"),
+        "request": schemars::schema_for!(PluginRequest),
+        "response": schemars::schema_for!(PluginResponse),
+        "command": schemars::schema_for!(PluginCommand),
+        "commands": command_schemas()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_keep_their_wire_format() {
+        let id = Uuid::new_v4();
+        let request = PluginCommand::StudioPaths(StudioPaths { include_components: true, ..Default::default() }).request(id);
+        assert_eq!((request.id, request.command.as_str()), (id, "studio:paths"));
+        assert_eq!(request.payload, serde_json::json!({ "includeComponents": true }));
+        assert_eq!(PluginCommand::TestStart(NoPayload {}).request(id).payload, serde_json::json!({}));
+
+        let request = PluginCommand::InsertModel(InsertModel { asset_id: 42, parent: None }).request(id);
+        assert_eq!(request.payload, serde_json::json!({ "assetId": 42, "parent": null }));
+
+        // Every command variant has a schema entry under its wire name
+        let schemas = command_schemas();
+        let reload = PluginCommand::PluginReload(PluginReload { version: 1, name: "Hot".to_string() });
+        assert!(schemas.contains_key(reload.name().as_str()));
+        let schema = serde_json::to_value(&schemas["script:edit-lines"].payload).unwrap();
+        assert!(schema["properties"]["startLine"].is_object());
+    }
+}
@@ -0,0 +1,813 @@
+//! Crash recovery and safe mode
+//!
+//! Operations that rewrite `src/` (extraction finalize, sync from Studio) write
+//! an operation journal before touching any file. The journal lists every
+//! intended filesystem change along with what it replaces, and is fsynced
+//! before the first change is applied. Once all changes are applied it's marked
+//! committed and removed.
+//!
+//! If the server dies in between, the journal is still there on the next start:
+//! the project enters safe mode, syncs are blocked, and `rbxsync recover` either
+//! replays the remaining changes or reverts the ones already applied.
+//!
+//! Extraction goes further: finalize writes the new tree next to `src/` and
+//! [`swap_src`] puts it in place with two journaled renames, so `src/` is
+//! always either the old tree or the new one. It records each rename in the
+//! journal once done, and replay starts after the last recorded one, so a
+//! crash between the renames and the commit can't move the new tree aside.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::{copy_dir_recursive, normalize_path, AppState};
+
+/// Journal location relative to the project directory
+pub const JOURNAL_FILE: &str = ".rbxsync/journal.json";
+
+/// Directory holding copies of directories an operation is about to delete
+pub const JOURNAL_BACKUP_DIR: &str = ".rbxsync/journal-backup";
+
+/// Where [`swap_src`] moves the old src while the new one takes its place
+pub const OLD_SRC_DIR: &str = ".rbxsync/src.old";
+
+/// A single intended filesystem change.
+///
+/// Paths are absolute. `previous`/`backup` hold what the change replaces and are
+/// captured by [`begin_operation`], so callers only describe the new state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum JournalOp {
+    /// Write a file
+    Write {
+        path: String,
+        content: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        previous: Option<String>,
+    },
+    /// Remove a file
+    Remove {
+        path: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        previous: Option<String>,
+    },
+    /// Remove a directory tree
+    RemoveDir {
+        path: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        backup: Option<String>,
+    },
+    /// Move a file or directory
+    Rename { from: String, to: String },
+    /// Rewrite the whole src directory; the previous src was moved to `backup`,
+    /// a directory or a backup archive
+    ReplaceSrc {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        backup: Option<String>,
+    },
+}
+
+impl JournalOp {
+    /// Apply the change. Safe to call again on an already-applied change: each
+    /// one checks what's on disk first.
+    pub fn apply(&self) -> std::io::Result<()> {
+        match self {
+            JournalOp::Write { path, content, .. } => {
+                if let Some(parent) = Path::new(path).parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, content)
+            }
+            JournalOp::Remove { path, .. } => match std::fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+            JournalOp::RemoveDir { path, .. } => match std::fs::remove_dir_all(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+            JournalOp::Rename { from, to } => {
+                let (from, to) = (Path::new(from), Path::new(to));
+                if !from.exists() && to.exists() {
+                    return Ok(());
+                }
+                // Never move a tree onto another one; this is what replaying an
+                // already-applied rename whose source was refilled looks like
+                if from.is_dir() && to.exists() {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::AlreadyExists,
+                        format!("{} already exists", to.display()),
+                    ));
+                }
+                if let Some(parent) = to.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::rename(from, to)
+            }
+            // The rewrite itself isn't journaled file by file; keeping the
+            // written files is all that replaying can do
+            JournalOp::ReplaceSrc { .. } => Ok(()),
+        }
+    }
+
+    /// Undo the change, restoring what it replaced
+    pub fn revert(&self, project_dir: &Path) -> std::io::Result<()> {
+        match self {
+            JournalOp::Write { path, previous, .. } | JournalOp::Remove { path, previous } => {
+                match previous {
+                    Some(content) => {
+                        if let Some(parent) = Path::new(path).parent() {
+                            std::fs::create_dir_all(parent)?;
+                        }
+                        std::fs::write(path, content)
+                    }
+                    None => match std::fs::remove_file(path) {
+                        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                        _ => Ok(()),
+                    },
+                }
+            }
+            JournalOp::RemoveDir { path, backup } => {
+                if let Some(backup) = backup {
+                    let _ = std::fs::remove_dir_all(path);
+                    copy_dir_recursive(&PathBuf::from(backup), &PathBuf::from(path))?;
+                }
+                Ok(())
+            }
+            JournalOp::Rename { from, to } => {
+                let (from, to) = (Path::new(from), Path::new(to));
+                if to.exists() && !from.exists() {
+                    std::fs::rename(to, from)?;
+                }
+                Ok(())
+            }
+            JournalOp::ReplaceSrc { backup } => {
+                let src_dir = project_dir.join("src");
+                if let Some(backup) = backup.as_ref().map(PathBuf::from) {
+                    if !backup.exists() {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::NotFound,
+                            format!("Backup not found: {}", backup.display()),
+                        ));
+                    }
+                    if backup.is_file() {
+                        // A compressed archive (see `backup`); it stays for undo
+                        return crate::backup::restore_all(&backup, &src_dir).map(|_| ());
+                    }
+                    if src_dir.exists() {
+                        std::fs::remove_dir_all(&src_dir)?;
+                    }
+                    if std::fs::rename(&backup, &src_dir).is_err() {
+                        copy_dir_recursive(&backup, &src_dir)?;
+                        let _ = std::fs::remove_dir_all(&backup);
+                    }
+                } else if src_dir.exists() {
+                    // No previous src existed - drop the partial one
+                    std::fs::remove_dir_all(&src_dir)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Write-ahead record of an operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationJournal {
+    /// Operation name (e.g. "finalize", "sync-from-studio")
+    pub operation: String,
+    /// Unix timestamp (seconds) when the operation started
+    pub started_at: u64,
+    /// [`instance_id`] of the server that started the operation
+    #[serde(default)]
+    pub instance: String,
+    /// Intended changes, in application order
+    #[serde(default)]
+    pub ops: Vec<JournalOp>,
+    /// How many of `ops` are known to be applied. Operations that record
+    /// progress (see [`record_progress`]) are replayed from here.
+    #[serde(default)]
+    pub completed: usize,
+    /// Set once every change has been applied
+    #[serde(default)]
+    pub committed: bool,
+}
+
+/// How to resolve an interrupted operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecoveryAction {
+    /// Replay the journaled changes so the operation completes
+    #[serde(alias = "replay")]
+    Finish,
+    /// Revert the journaled changes and restore the previous state
+    #[serde(alias = "revert")]
+    Rollback,
+}
+
+/// Random id for this server run. Journals record it rather than the process
+/// id, which a restarted server often gets again (always 1 in a container).
+pub fn instance_id() -> &'static str {
+    static INSTANCE_ID: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    INSTANCE_ID.get_or_init(|| uuid::Uuid::new_v4().to_string())
+}
+
+/// Path to the journal file for a project
+pub fn journal_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(JOURNAL_FILE)
+}
+
+/// Durably write the journal
+fn write_journal(project_dir: &Path, journal: &OperationJournal) -> std::io::Result<()> {
+    let json = serde_json::to_vec_pretty(journal).map_err(std::io::Error::other)?;
+    write_atomic(&journal_path(project_dir), &json)
+}
+
+/// Write a file so a crash leaves either the old or the new content (write to
+/// a temp file, fsync, rename)
+pub fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    {
+        let mut file = std::fs::File::create(&tmp_path)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+    }
+    std::fs::rename(&tmp_path, path)?;
+
+    // Persist the rename itself
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        std::fs::File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Fail if the project has a journal that isn't committed: an operation that
+/// is still running, or one interrupted by a crash whose backups `rbxsync
+/// recover` needs
+fn ensure_no_pending(project_dir: &Path) -> std::io::Result<()> {
+    match read_journal(project_dir) {
+        Some(journal) if !journal.committed => Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!(
+                "An unfinished '{}' operation is journaled; run `rbxsync recover` if it was interrupted",
+                journal.operation
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Record the intended changes of an operation before applying any of them.
+///
+/// Captures the current contents each change replaces so it can be reverted.
+/// Refuses to start while another operation's journal is uncommitted.
+pub fn begin_operation(project_dir: &Path, operation: &str, ops: Vec<JournalOp>) -> std::io::Result<OperationJournal> {
+    ensure_no_pending(project_dir)?;
+    let backup_root = project_dir.join(JOURNAL_BACKUP_DIR);
+    let _ = std::fs::remove_dir_all(&backup_root);
+
+    let ops = ops
+        .into_iter()
+        .enumerate()
+        .map(|(i, op)| -> std::io::Result<JournalOp> {
+            Ok(match op {
+                JournalOp::Write { path, content, .. } => {
+                    let previous = std::fs::read_to_string(&path).ok();
+                    JournalOp::Write { path, content, previous }
+                }
+                JournalOp::Remove { path, .. } => {
+                    let previous = std::fs::read_to_string(&path).ok();
+                    JournalOp::Remove { path, previous }
+                }
+                JournalOp::RemoveDir { path, .. } => {
+                    let backup = if Path::new(&path).is_dir() {
+                        let dest = backup_root.join(i.to_string());
+                        copy_dir_recursive(&PathBuf::from(&path), &dest)?;
+                        Some(rbxsync_core::path_to_string(&dest))
+                    } else {
+                        None
+                    };
+                    JournalOp::RemoveDir { path, backup }
+                }
+                other => other,
+            })
+        })
+        .collect::<std::io::Result<Vec<_>>>()?;
+
+    let journal = OperationJournal {
+        operation: operation.to_string(),
+        started_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        instance: instance_id().to_string(),
+        ops,
+        completed: 0,
+        committed: false,
+    };
+    write_journal(project_dir, &journal)?;
+    crate::crashes::operation_started(project_dir, operation);
+    Ok(journal)
+}
+
+/// Durably record that the first `completed` changes of `journal` are applied
+pub fn record_progress(project_dir: &Path, journal: &mut OperationJournal, completed: usize) -> std::io::Result<()> {
+    journal.completed = completed.min(journal.ops.len());
+    write_journal(project_dir, journal)
+}
+
+/// Mark the operation committed, then remove the journal and its backups
+pub fn commit_operation(project_dir: &Path) -> std::io::Result<()> {
+    if let Some(mut journal) = read_journal(project_dir) {
+        journal.committed = true;
+        write_journal(project_dir, &journal)?;
+    }
+    cleanup(project_dir)
+}
+
+fn cleanup(project_dir: &Path) -> std::io::Result<()> {
+    crate::crashes::operation_finished(project_dir);
+    let _ = std::fs::remove_dir_all(project_dir.join(JOURNAL_BACKUP_DIR));
+    let path = journal_path(project_dir);
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Replace `src/` with the tree written to `staged` (on the same filesystem):
+/// src moves to [`OLD_SRC_DIR`], then `staged` moves to src, under a journal.
+/// If a rename fails the old src is moved back and the error returned; if the
+/// server dies in between, `rbxsync recover` finishes or reverts the swap.
+/// The old tree is deleted once the swap is committed.
+pub fn swap_src(project_dir: &Path, staged: &Path) -> std::io::Result<()> {
+    let src_dir = project_dir.join("src");
+    let old_dir = project_dir.join(OLD_SRC_DIR);
+    // An interrupted swap may still need the old tree
+    ensure_no_pending(project_dir)?;
+    // Left over from a swap that committed but wasn't cleaned up
+    if old_dir.exists() {
+        std::fs::remove_dir_all(&old_dir)?;
+    }
+
+    let mut ops = Vec::new();
+    if src_dir.exists() {
+        ops.push(JournalOp::Rename {
+            from: rbxsync_core::path_to_string(&src_dir),
+            to: rbxsync_core::path_to_string(&old_dir),
+        });
+    }
+    ops.push(JournalOp::Rename {
+        from: rbxsync_core::path_to_string(staged),
+        to: rbxsync_core::path_to_string(&src_dir),
+    });
+    let mut journal = begin_operation(project_dir, "finalize", ops.clone())?;
+
+    for (i, op) in ops.iter().enumerate() {
+        if let Err(e) = op.apply() {
+            for applied in ops[..i].iter().rev() {
+                // Leave the journal for `rbxsync recover` if src can't be put back
+                applied.revert(project_dir)?;
+            }
+            cleanup(project_dir)?;
+            return Err(e);
+        }
+        record_progress(project_dir, &mut journal, i + 1)?;
+    }
+    commit_operation(project_dir)?;
+
+    if let Err(e) = std::fs::remove_dir_all(&old_dir) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("Failed to remove {}: {}", old_dir.display(), e);
+        }
+    }
+    Ok(())
+}
+
+/// Apply journaled changes in order, returning per-change errors
+pub fn apply_ops(ops: &[JournalOp]) -> Vec<String> {
+    ops.iter()
+        .filter_map(|op| op.apply().err().map(|e| format!("{:?}: {}", op, e)))
+        .collect()
+}
+
+/// Apply the changes of an interrupted journal that aren't recorded as done,
+/// recording each as it completes so an interrupted replay resumes too
+fn replay(project_dir: &Path, journal: &mut OperationJournal) -> Result<(), String> {
+    for i in journal.completed.min(journal.ops.len())..journal.ops.len() {
+        let op = &journal.ops[i];
+        op.apply().map_err(|e| format!("{:?}: {}", op, e))?;
+        record_progress(project_dir, journal, i + 1).map_err(|e| format!("Failed to update journal: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Read the journal for a project, if any
+pub fn read_journal(project_dir: &Path) -> Option<OperationJournal> {
+    let content = std::fs::read_to_string(journal_path(project_dir)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Find an operation that was interrupted by a crash.
+///
+/// Committed journals only need cleanup, and a journal written by this server
+/// run belongs to an operation still running, so neither is reported.
+pub fn find_interrupted(project_dir: &Path) -> Option<OperationJournal> {
+    read_journal(project_dir).filter(|j| !j.committed && j.instance != instance_id())
+}
+
+/// Resolve an interrupted operation. Returns a description of what was done.
+pub fn recover(project_dir: &Path, action: RecoveryAction) -> Result<String, String> {
+    let mut journal = read_journal(project_dir)
+        .ok_or_else(|| "No interrupted operation found".to_string())?;
+
+    let message = if journal.committed {
+        format!("'{}' had already committed - cleared journal", journal.operation)
+    } else {
+        match action {
+            RecoveryAction::Finish => {
+                let remaining = journal.ops.len() - journal.completed.min(journal.ops.len());
+                replay(project_dir, &mut journal).map_err(|e| format!("Replay failed: {}", e))?;
+                format!(
+                    "Replayed {} change(s) from interrupted '{}' operation",
+                    remaining, journal.operation
+                )
+            }
+            RecoveryAction::Rollback => {
+                for op in journal.ops.iter().rev() {
+                    op.revert(project_dir)
+                        .map_err(|e| format!("Revert failed for {:?}: {}", op, e))?;
+                }
+                format!(
+                    "Reverted {} change(s) from interrupted '{}' operation",
+                    journal.ops.len(),
+                    journal.operation
+                )
+            }
+        }
+    };
+
+    cleanup(project_dir).map_err(|e| format!("Failed to clear journal: {}", e))?;
+    Ok(message)
+}
+
+/// Check a project for an interrupted operation and enter safe mode if found
+pub async fn check_safe_mode(state: &AppState, project_dir: &str) {
+    let project_dir = normalize_path(project_dir);
+    if state.safe_mode_projects.read().await.contains_key(&project_dir) {
+        return;
+    }
+    if let Some(journal) = find_interrupted(Path::new(&project_dir)) {
+        tracing::warn!(
+            "Interrupted '{}' operation detected in {} - entering safe mode. Run `rbxsync recover` to resolve.",
+            journal.operation,
+            project_dir
+        );
+        state.safe_mode_projects.write().await.insert(project_dir, journal);
+    }
+}
+
+/// Error response if a project is in safe mode. Checks the project's journal
+/// first, so a crash left behind by another server run is caught even if
+/// nothing has registered the project since.
+pub async fn safe_mode_error(
+    state: &AppState,
+    project_dir: &str,
+) -> Option<(StatusCode, Json<serde_json::Value>)> {
+    check_safe_mode(state, project_dir).await;
+    let safe_mode = state.safe_mode_projects.read().await;
+    let journal = safe_mode.get(&normalize_path(project_dir))?;
+    Some((
+        StatusCode::CONFLICT,
+        Json(serde_json::json!({
+            "success": false,
+            "safeMode": true,
+            "error": format!(
+                "Project is in safe mode after an interrupted '{}' operation. Run `rbxsync recover` to resolve.",
+                journal.operation
+            )
+        })),
+    ))
+}
+
+/// List projects in safe mode
+pub async fn handle_recovery_status(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let safe_mode = state.safe_mode_projects.read().await;
+    let projects: Vec<serde_json::Value> = safe_mode
+        .iter()
+        .map(|(dir, journal)| serde_json::json!({ "projectDir": dir, "journal": journal }))
+        .collect();
+
+    Json(serde_json::json!({
+        "success": true,
+        "projects": projects
+    }))
+}
+
+/// Recover request
+#[derive(Debug, Deserialize)]
+pub struct RecoverRequest {
+    pub project_dir: String,
+    pub action: RecoveryAction,
+}
+
+/// Finish or roll back an interrupted operation and leave safe mode
+pub async fn handle_recover(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<RecoverRequest>,
+) -> impl IntoResponse {
+    let project_dir = normalize_path(&req.project_dir);
+
+    match recover(Path::new(&project_dir), req.action) {
+        Ok(message) => {
+            state.safe_mode_projects.write().await.remove(&project_dir);
+            tracing::info!("Recovery for {}: {}", project_dir, message);
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "success": true,
+                    "message": message
+                })),
+            )
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "success": false,
+                "error": e
+            })),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(path: &Path) -> String {
+        rbxsync_core::path_to_string(path)
+    }
+
+    #[test]
+    fn test_journal_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        begin_operation(dir.path(), "finalize", vec![JournalOp::ReplaceSrc { backup: None }]).unwrap();
+
+        let journal = read_journal(dir.path()).unwrap();
+        assert_eq!(journal.operation, "finalize");
+        assert!(!journal.committed);
+        // Written by this process, so it's still running - not interrupted
+        assert!(find_interrupted(dir.path()).is_none());
+
+        commit_operation(dir.path()).unwrap();
+        assert!(read_journal(dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_begin_captures_previous_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("src/A.luau");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, "old").unwrap();
+        let folder = dir.path().join("src/Folder");
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("B.luau"), "b").unwrap();
+
+        let journal = begin_operation(
+            dir.path(),
+            "sync-from-studio",
+            vec![
+                JournalOp::Write { path: p(&file), content: "new".into(), previous: None },
+                JournalOp::RemoveDir { path: p(&folder), backup: None },
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            journal.ops[0],
+            JournalOp::Write { path: p(&file), content: "new".into(), previous: Some("old".into()) }
+        );
+        match &journal.ops[1] {
+            JournalOp::RemoveDir { backup: Some(backup), .. } => {
+                assert!(Path::new(backup).join("B.luau").exists());
+            }
+            other => panic!("unexpected op {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_begin_refuses_while_uncommitted() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path().join("src/Folder");
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("B.luau"), "b").unwrap();
+        begin_operation(dir.path(), "finalize", vec![JournalOp::RemoveDir { path: p(&folder), backup: None }]).unwrap();
+
+        // A second operation must leave the journal and its backups alone
+        assert!(begin_operation(dir.path(), "sync-from-studio", Vec::new()).is_err());
+        assert!(swap_src(dir.path(), &dir.path().join(".rbxsync/src.new")).is_err());
+        assert_eq!(read_journal(dir.path()).unwrap().operation, "finalize");
+        assert!(dir.path().join(JOURNAL_BACKUP_DIR).join("0/B.luau").exists());
+
+        commit_operation(dir.path()).unwrap();
+        begin_operation(dir.path(), "sync-from-studio", Vec::new()).unwrap();
+    }
+
+    #[test]
+    fn test_interrupted_by_another_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut journal = begin_operation(dir.path(), "finalize", Vec::new()).unwrap();
+        // A restarted server can have the same pid; its instance id differs
+        journal.instance = "previous-run".to_string();
+        write_journal(dir.path(), &journal).unwrap();
+        assert!(find_interrupted(dir.path()).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_journal_blocks_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_dir = p(dir.path());
+        let mut journal = begin_operation(dir.path(), "finalize", Vec::new()).unwrap();
+        journal.instance = "previous-run".to_string();
+        write_journal(dir.path(), &journal).unwrap();
+
+        // Nothing has registered the project, but syncing still checks the journal
+        let state = AppState::new();
+        let sync = crate::SyncFromStudioRequest {
+            operations: Vec::new(),
+            project_dir: project_dir.clone(),
+            user_id: None,
+            place_name: None,
+        };
+        let error = crate::handler_json(crate::handle_sync_from_studio(State(state.clone()), Json(sync)).await)
+            .await
+            .unwrap_err();
+        assert!(error.contains("safe mode"), "{}", error);
+
+        // Studio registering the project puts it in safe mode
+        let state = AppState::new();
+        let register = crate::RegisterRequest {
+            place_id: 1,
+            place_name: "Place".to_string(),
+            project_dir: project_dir.clone(),
+            session_id: None,
+            version: None,
+        };
+        let _ = crate::handle_register(State(state.clone()), Json(register)).await;
+        assert!(state.safe_mode_projects.read().await.contains_key(&normalize_path(&project_dir)));
+    }
+
+    #[test]
+    fn test_replay_and_revert() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("src/A.luau");
+        let removed = dir.path().join("src/B.luau");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, "old").unwrap();
+        std::fs::write(&removed, "b").unwrap();
+
+        let ops = vec![
+            JournalOp::Write { path: p(&file), content: "new".into(), previous: None },
+            JournalOp::Remove { path: p(&removed), previous: None },
+        ];
+
+        // Simulate a crash after only the first change was applied
+        let journal = begin_operation(dir.path(), "sync-from-studio", ops).unwrap();
+        journal.ops[0].apply().unwrap();
+        recover(dir.path(), RecoveryAction::Rollback).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "old");
+        assert!(removed.exists());
+
+        let journal = begin_operation(dir.path(), "sync-from-studio", journal.ops).unwrap();
+        journal.ops[0].apply().unwrap();
+        recover(dir.path(), RecoveryAction::Finish).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "new");
+        assert!(!removed.exists());
+        assert!(read_journal(dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_rollback_restores_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let backup = dir.path().join(".rbxsync-backup/src");
+        std::fs::create_dir_all(&backup).unwrap();
+        std::fs::write(backup.join("old.luau"), "old").unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/partial.luau"), "partial").unwrap();
+
+        begin_operation(dir.path(), "finalize", vec![JournalOp::ReplaceSrc { backup: Some(p(&backup)) }]).unwrap();
+        recover(dir.path(), RecoveryAction::Rollback).unwrap();
+
+        assert!(dir.path().join("src/old.luau").exists());
+        assert!(!dir.path().join("src/partial.luau").exists());
+        assert!(read_journal(dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_swap_src() {
+        let dir = tempfile::tempdir().unwrap();
+        let staged = dir.path().join(".rbxsync/src.new");
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/old.luau"), "old").unwrap();
+        std::fs::create_dir_all(&staged).unwrap();
+        std::fs::write(staged.join("new.luau"), "new").unwrap();
+
+        swap_src(dir.path(), &staged).unwrap();
+        assert!(dir.path().join("src/new.luau").exists());
+        assert!(!dir.path().join("src/old.luau").exists());
+        assert!(!staged.exists() && !dir.path().join(OLD_SRC_DIR).exists());
+        assert!(read_journal(dir.path()).is_none());
+
+        // A failed swap leaves src as it was
+        assert!(swap_src(dir.path(), &staged).is_err());
+        assert!(dir.path().join("src/new.luau").exists());
+        assert!(read_journal(dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_interrupted_swap_rolls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        let staged = dir.path().join(".rbxsync/src.new");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("old.luau"), "old").unwrap();
+        std::fs::create_dir_all(&staged).unwrap();
+
+        // Simulate a crash after src was moved aside
+        let ops = vec![
+            JournalOp::Rename { from: p(&src), to: p(&dir.path().join(OLD_SRC_DIR)) },
+            JournalOp::Rename { from: p(&staged), to: p(&src) },
+        ];
+        let journal = begin_operation(dir.path(), "finalize", ops).unwrap();
+        journal.ops[0].apply().unwrap();
+        assert!(!src.exists());
+
+        recover(dir.path(), RecoveryAction::Rollback).unwrap();
+        assert!(src.join("old.luau").exists());
+        assert!(staged.exists());
+    }
+
+    #[test]
+    fn test_interrupted_swap_finishes_after_both_renames() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        let old = dir.path().join(OLD_SRC_DIR);
+        let staged = dir.path().join(".rbxsync/src.new");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("old.luau"), "old").unwrap();
+        std::fs::create_dir_all(&staged).unwrap();
+        std::fs::write(staged.join("new.luau"), "new").unwrap();
+
+        // Simulate `swap_src` dying after both renames, before the second was
+        // recorded and before the journal was committed
+        let ops = vec![
+            JournalOp::Rename { from: p(&src), to: p(&old) },
+            JournalOp::Rename { from: p(&staged), to: p(&src) },
+        ];
+        let mut journal = begin_operation(dir.path(), "finalize", ops).unwrap();
+        journal.ops[0].apply().unwrap();
+        record_progress(dir.path(), &mut journal, 1).unwrap();
+        journal.ops[1].apply().unwrap();
+        journal.instance = "previous-run".to_string();
+        write_journal(dir.path(), &journal).unwrap();
+
+        // Replaying from the start must not move the new src aside again
+        assert!(journal.ops[0].apply().is_err());
+        assert!(find_interrupted(dir.path()).is_some());
+        recover(dir.path(), RecoveryAction::Finish).unwrap();
+        assert!(src.join("new.luau").exists());
+        assert!(!src.join("old.luau").exists());
+        assert!(old.join("old.luau").exists());
+        assert!(read_journal(dir.path()).is_none());
+
+        // Both renames recorded: nothing is left to replay
+        let mut journal = begin_operation(dir.path(), "finalize", journal.ops).unwrap();
+        record_progress(dir.path(), &mut journal, 2).unwrap();
+        recover(dir.path(), RecoveryAction::Finish).unwrap();
+        assert!(src.join("new.luau").exists());
+    }
+
+    #[test]
+    fn test_finish_keeps_src() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/partial.luau"), "partial").unwrap();
+
+        begin_operation(dir.path(), "finalize", vec![JournalOp::ReplaceSrc { backup: None }]).unwrap();
+        recover(dir.path(), RecoveryAction::Finish).unwrap();
+
+        assert!(dir.path().join("src/partial.luau").exists());
+        assert!(read_journal(dir.path()).is_none());
+        assert!(recover(dir.path(), RecoveryAction::Finish).is_err());
+    }
+}
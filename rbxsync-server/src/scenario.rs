@@ -0,0 +1,664 @@
+//! Bot scenarios
+//!
+//! A scenario is a list of bot steps run one after another, so an end-to-end
+//! gameplay test is one file instead of dozens of `/bot/*` calls. It can be
+//! written in YAML or JSON:
+//!
+//! ```yaml
+//! name: Buy a potion
+//! steps:
+//!   - move: { objectName: Shopkeeper }
+//!   - social: { action: prompt, path: Workspace.Shopkeeper.BuyPrompt }
+//!   - wait: 1
+//!   - assert: { path: inventory, contains: Potion }
+//!   - assert: { path: health, greaterThan: 50 }
+//! ```
+//!
+//! Steps are `move`, `action`, `observe`, `social` (the bodies of the matching
+//! `/bot/*` endpoints), `wait` (seconds), `query` (Luau run on the playtest
+//! server), `assert`, which checks a dotted path into the bot state, and
+//! `faults`, a `/bot/faults` body. A failed step skips the rest unless
+//! `continueOnFailure` is set.
+//!
+//! A top-level `faults` table (latency, jitter, drops and seed, see
+//! [`crate::faults`]) is applied for the whole run. Faults the scenario turned
+//! on are cleared when it ends, and their reports go in the scenario report,
+//! so a flaky run can be replayed with the same seed.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use rbxsync_core::api::{
+    BotActionRequest, BotMoveRequest, BotObserveRequest, ScenarioReport, ScenarioRunRequest, ScenarioStepResult,
+    ScenarioStepStatus,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::faults::{FaultConfig, FaultsRequest};
+use crate::social::SocialRequest;
+use crate::AppState;
+
+/// Longest `wait` step
+pub const MAX_WAIT_SECS: f64 = 60.0;
+
+/// How close `near` wants the position, in studs, when `within` isn't given
+const DEFAULT_NEAR_DISTANCE: f64 = 5.0;
+
+/// A scenario file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scenario {
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Keep running after a step fails
+    #[serde(default)]
+    pub continue_on_failure: bool,
+    /// Faults injected from the first step until the scenario ends
+    #[serde(default)]
+    pub faults: Option<FaultConfig>,
+    pub steps: Vec<Step>,
+}
+
+/// One scenario step, written as a single-key map (`- wait: 2`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Step {
+    Move(BotMoveRequest),
+    Action(ActionStep),
+    Observe(BotObserveRequest),
+    Social(SocialRequest),
+    Wait(f64),
+    Query(String),
+    Assert(Assertion),
+    /// Change faults mid-run, clear them, or pause and resume physics
+    Faults(FaultsRequest),
+}
+
+/// `- action: jump`, or the `/bot/action` body for actions that take a name
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ActionStep {
+    Name(String),
+    Request(BotActionRequest),
+}
+
+impl ActionStep {
+    fn request(&self) -> BotActionRequest {
+        match self {
+            ActionStep::Name(action) => BotActionRequest { action: action.clone(), ..Default::default() },
+            ActionStep::Request(req) => req.clone(),
+        }
+    }
+}
+
+/// A check against the bot state (`GET /bot/state`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Assertion {
+    /// Dotted path into the state, e.g. `health`, `position.y` or `nearbyNPCs.0.name`
+    pub path: String,
+    #[serde(default)]
+    pub equals: Option<Value>,
+    #[serde(default)]
+    pub not_equals: Option<Value>,
+    #[serde(default)]
+    pub greater_than: Option<f64>,
+    #[serde(default)]
+    pub less_than: Option<f64>,
+    /// Substring of a string, element of a list (or the `name` of one), or
+    /// key of an object
+    #[serde(default)]
+    pub contains: Option<Value>,
+    #[serde(default)]
+    pub exists: Option<bool>,
+    /// Position, as `[x, y, z]` or `{ x, y, z }`, the value must be close to
+    #[serde(default)]
+    pub near: Option<Value>,
+    /// Studs from `near` that still pass (default 5)
+    #[serde(default)]
+    pub within: Option<f64>,
+}
+
+/// Parse a scenario from YAML or JSON (JSON is valid YAML)
+pub fn parse(source: &str) -> Result<Scenario, String> {
+    let value: Value = serde_yaml::from_str(source).map_err(|e| format!("Invalid scenario: {}", e))?;
+    from_value(value)
+}
+
+fn from_value(value: Value) -> Result<Scenario, String> {
+    let scenario: Scenario = serde_json::from_value(value).map_err(|e| format!("Invalid scenario: {}", e))?;
+    scenario.validate()?;
+    Ok(scenario)
+}
+
+impl Scenario {
+    /// Catch mistakes before the bot starts moving
+    pub fn validate(&self) -> Result<(), String> {
+        if self.steps.is_empty() {
+            return Err("Scenario has no steps".to_string());
+        }
+        if let Some(faults) = &self.faults {
+            faults.validate().map_err(|e| format!("faults: {}", e))?;
+        }
+        for (i, step) in self.steps.iter().enumerate() {
+            step.validate().map_err(|e| format!("Step {}: {}", i + 1, e))?;
+        }
+        Ok(())
+    }
+}
+
+impl Step {
+    fn kind(&self) -> &'static str {
+        match self {
+            Step::Move(_) => "move",
+            Step::Action(_) => "action",
+            Step::Observe(_) => "observe",
+            Step::Social(_) => "social",
+            Step::Wait(_) => "wait",
+            Step::Query(_) => "query",
+            Step::Assert(_) => "assert",
+            Step::Faults(_) => "faults",
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Step::Move(req) if req.position.is_none() && req.object_name.is_none() && req.object.is_none() => {
+                Err("move needs position or objectName".to_string())
+            }
+            Step::Action(action) if action.request().action.trim().is_empty() => Err("action needs an action".to_string()),
+            Step::Social(req) => req.to_command().map(|_| ()),
+            Step::Wait(secs) if !(0.0..=MAX_WAIT_SECS).contains(secs) => {
+                Err(format!("wait must be between 0 and {} seconds", MAX_WAIT_SECS))
+            }
+            Step::Query(code) if code.trim().is_empty() => Err("query needs Luau code".to_string()),
+            Step::Assert(assertion) => assertion.validate(),
+            Step::Faults(req) => match req.physics.as_deref() {
+                Some("pause" | "resume") | None => req.config.validate(),
+                Some(other) => Err(format!("unknown physics action '{}', use pause or resume", other)),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// One-line summary for the report
+    fn describe(&self) -> String {
+        match self {
+            Step::Move(req) => match (&req.position, req.object_name.as_ref().or(req.object.as_ref())) {
+                (Some(position), _) => format!("move to {}", position),
+                (None, Some(object)) => format!("move to {}", object),
+                (None, None) => "move".to_string(),
+            },
+            Step::Action(action) => {
+                let req = action.request();
+                match req.name.as_ref().or(req.tool_name.as_ref()).or(req.object_name.as_ref()) {
+                    Some(target) => format!("{} {}", req.action, target),
+                    None => req.action,
+                }
+            }
+            Step::Observe(req) => match &req.query {
+                Some(query) => format!("observe {} {}", req.observe_type, query),
+                None => format!("observe {}", req.observe_type),
+            },
+            Step::Social(req) => {
+                let target = req.message.as_ref().or(req.path.as_ref()).cloned().unwrap_or_default();
+                format!("{} {}", req.action, target).trim_end().to_string()
+            }
+            Step::Wait(secs) => format!("wait {}s", secs),
+            Step::Query(code) => {
+                let first = code.lines().next().unwrap_or_default().trim();
+                format!("query {}", first)
+            }
+            Step::Assert(assertion) => assertion.describe(),
+            Step::Faults(req) => describe_faults(req),
+        }
+    }
+
+    /// The command for the in-game runner; `None` for steps handled here
+    fn command(&self) -> Option<Value> {
+        match self {
+            Step::Move(req) => Some(crate::bot_move_command(req.clone())),
+            Step::Action(action) => Some(crate::bot_action_command(action.request())),
+            Step::Observe(req) => Some(crate::bot_observe_command(req.clone())),
+            // Checked by validate
+            Step::Social(req) => req.to_command().ok(),
+            Step::Query(code) => Some(serde_json::json!({ "action": "queryServer", "code": code })),
+            Step::Wait(_) | Step::Assert(_) | Step::Faults(_) => None,
+        }
+    }
+}
+
+fn describe_faults(req: &FaultsRequest) -> String {
+    if req.clear {
+        return "clear faults".to_string();
+    }
+    if let Some(physics) = &req.physics {
+        return format!("{} physics", physics);
+    }
+    let config = &req.config;
+    let mut parts = vec![format!("latency {}ms", config.latency_ms)];
+    if config.jitter_ms > 0 {
+        parts.push(format!("jitter {}ms", config.jitter_ms));
+    }
+    if config.drop_percent > 0.0 {
+        parts.push(format!("drop {}%", config.drop_percent));
+    }
+    format!("faults {}", parts.join(", "))
+}
+
+/// Read a dotted path; list elements are addressed by index. Null counts as
+/// missing, since that's how Luau's nil comes through.
+fn lookup<'a>(state: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut value = state;
+    for key in path.split('.').filter(|k| !k.is_empty()) {
+        value = match value {
+            Value::Object(map) => map.get(key)?,
+            Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value).filter(|v| !v.is_null())
+}
+
+/// Equal, treating 100 and 100.0 as the same number
+fn same(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// `[x, y, z]` or `{ x, y, z }` (serialized Vector3s are the latter)
+fn vector(value: &Value) -> Option<[f64; 3]> {
+    let component = |index: usize, key: &str| match value {
+        Value::Array(items) => items.get(index)?.as_f64(),
+        _ => value.get(key)?.as_f64(),
+    };
+    Some([component(0, "x")?, component(1, "y")?, component(2, "z")?])
+}
+
+impl Assertion {
+    fn validate(&self) -> Result<(), String> {
+        if self.path.trim().is_empty() {
+            return Err("assert needs a path".to_string());
+        }
+        if self.exists.is_none() && !self.has_comparison() {
+            return Err(format!(
+                "assert on '{}' needs equals, notEquals, greaterThan, lessThan, contains, exists or near",
+                self.path
+            ));
+        }
+        if self.near.as_ref().is_some_and(|near| vector(near).is_none()) {
+            return Err("near must be [x, y, z] or { x, y, z }".to_string());
+        }
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        let mut checks = Vec::new();
+        if let Some(expected) = &self.equals {
+            checks.push(format!("== {}", expected));
+        }
+        if let Some(expected) = &self.not_equals {
+            checks.push(format!("!= {}", expected));
+        }
+        if let Some(bound) = self.greater_than {
+            checks.push(format!("> {}", bound));
+        }
+        if let Some(bound) = self.less_than {
+            checks.push(format!("< {}", bound));
+        }
+        if let Some(expected) = &self.contains {
+            checks.push(format!("contains {}", expected));
+        }
+        if let Some(exists) = self.exists {
+            checks.push(if exists { "exists" } else { "does not exist" }.to_string());
+        }
+        if let Some(near) = &self.near {
+            checks.push(format!("within {} of {}", self.within.unwrap_or(DEFAULT_NEAR_DISTANCE), near));
+        }
+        format!("{} {}", self.path, checks.join(" and "))
+    }
+
+    /// Check the assertion against a bot state
+    pub fn check(&self, state: &Value) -> Result<(), String> {
+        let value = lookup(state, &self.path);
+        match (self.exists, value) {
+            (Some(true), None) => return Err(format!("{} does not exist", self.path)),
+            (Some(false), Some(value)) => return Err(format!("{} exists ({})", self.path, value)),
+            (Some(false), None) => return Ok(()),
+            _ => {}
+        }
+        if self.exists.is_some() && !self.has_comparison() {
+            return Ok(());
+        }
+        let value = value.ok_or_else(|| format!("{} does not exist", self.path))?;
+
+        if let Some(expected) = &self.equals {
+            if !same(value, expected) {
+                return Err(format!("expected {} to equal {}, got {}", self.path, expected, value));
+            }
+        }
+        if let Some(unexpected) = &self.not_equals {
+            if same(value, unexpected) {
+                return Err(format!("expected {} not to equal {}", self.path, unexpected));
+            }
+        }
+        if self.greater_than.is_some() || self.less_than.is_some() {
+            let number = value.as_f64().ok_or_else(|| format!("{} is not a number ({})", self.path, value))?;
+            if let Some(bound) = self.greater_than.filter(|bound| number <= *bound) {
+                return Err(format!("expected {} > {}, got {}", self.path, bound, number));
+            }
+            if let Some(bound) = self.less_than.filter(|bound| number >= *bound) {
+                return Err(format!("expected {} < {}, got {}", self.path, bound, number));
+            }
+        }
+        if let Some(expected) = &self.contains {
+            let found = match value {
+                Value::String(text) => expected.as_str().is_some_and(|needle| text.contains(needle)),
+                Value::Array(items) => items
+                    .iter()
+                    .any(|item| same(item, expected) || item.get("name").is_some_and(|name| same(name, expected))),
+                Value::Object(map) => expected.as_str().is_some_and(|key| map.contains_key(key)),
+                _ => false,
+            };
+            if !found {
+                return Err(format!("expected {} to contain {}, got {}", self.path, expected, value));
+            }
+        }
+        if let Some(near) = &self.near {
+            let target = vector(near).ok_or("near must be [x, y, z] or { x, y, z }")?;
+            let position = vector(value).ok_or_else(|| format!("{} is not a position ({})", self.path, value))?;
+            let distance = position.iter().zip(target).map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt();
+            let within = self.within.unwrap_or(DEFAULT_NEAR_DISTANCE);
+            if distance > within {
+                return Err(format!("expected {} within {} of {}, was {:.1} away", self.path, within, near, distance));
+            }
+        }
+        Ok(())
+    }
+
+    fn has_comparison(&self) -> bool {
+        self.equals.is_some()
+            || self.not_equals.is_some()
+            || self.greater_than.is_some()
+            || self.less_than.is_some()
+            || self.contains.is_some()
+            || self.near.is_some()
+    }
+}
+
+/// The command's own result from a queue response (`{ id, result, state }`)
+fn command_result(response: &Value) -> &Value {
+    response.get("result").filter(|r| !r.is_null()).unwrap_or(response)
+}
+
+async fn send(state: &Arc<AppState>, command: Value) -> Result<Value, String> {
+    let response = crate::send_bot_command_via_queue(state, command)
+        .await
+        .map_err(|(_, Json(body))| body["error"].as_str().unwrap_or("Bot command failed").to_string())?;
+    let result = command_result(&response);
+    if result.get("success").and_then(|s| s.as_bool()) == Some(false) {
+        return Err(result["error"].as_str().unwrap_or("Bot command failed").to_string());
+    }
+    Ok(response)
+}
+
+/// Faults a scenario turned on, and the reports of the runs it ended
+#[derive(Default)]
+struct FaultRuns {
+    active: bool,
+    reports: Vec<Value>,
+}
+
+impl FaultRuns {
+    /// Send a `/bot/faults` request. Starting or clearing a fault run ends the
+    /// one in progress, whose report is kept.
+    async fn apply(&mut self, state: &Arc<AppState>, req: FaultsRequest) -> Result<Value, String> {
+        // Physics commands leave the fault run alone
+        let physics = req.physics.is_some();
+        if !physics {
+            self.end(state).await?;
+            if req.clear {
+                return Ok(Value::Null);
+            }
+        }
+        let response = crate::handler_json(crate::faults::handle_set_faults(State(state.clone()), Json(req)).await).await?;
+        self.active |= !physics;
+        Ok(response)
+    }
+
+    /// Clear the faults this scenario turned on
+    async fn end(&mut self, state: &Arc<AppState>) -> Result<(), String> {
+        if !std::mem::take(&mut self.active) {
+            return Ok(());
+        }
+        let clear = FaultsRequest { clear: true, ..Default::default() };
+        let response =
+            crate::handler_json(crate::faults::handle_set_faults(State(state.clone()), Json(clear)).await).await?;
+        self.reports.push(response["report"].clone());
+        Ok(())
+    }
+}
+
+/// Run one step; returns what the bot reported for observe and query steps
+async fn execute(state: &Arc<AppState>, step: &Step, faults: &mut FaultRuns) -> Result<Option<Value>, String> {
+    match step {
+        Step::Faults(req) => {
+            faults.apply(state, req.clone()).await?;
+            Ok(None)
+        }
+        Step::Wait(secs) => {
+            tokio::time::sleep(std::time::Duration::from_secs_f64(*secs)).await;
+            Ok(None)
+        }
+        Step::Assert(assertion) => {
+            let response = send(state, serde_json::json!({ "action": "getState" })).await?;
+            let bot_state = command_result(&response).get("state").or_else(|| response.get("state"));
+            assertion.check(bot_state.unwrap_or(&Value::Null))?;
+            Ok(None)
+        }
+        _ => {
+            let command = step.command().ok_or("Step has no bot command")?;
+            let response = send(state, command).await?;
+            match step {
+                Step::Observe(_) | Step::Query(_) => Ok(Some(command_result(&response).clone())),
+                _ => Ok(None),
+            }
+        }
+    }
+}
+
+/// Run the steps in order against the playtest's bot
+pub async fn run(state: &Arc<AppState>, scenario: &Scenario) -> ScenarioReport {
+    let started = Instant::now();
+    let mut steps = Vec::with_capacity(scenario.steps.len());
+    let mut failed = false;
+
+    let mut faults = FaultRuns::default();
+    if let Some(config) = &scenario.faults {
+        let req = FaultsRequest { config: config.clone(), ..Default::default() };
+        if let Err(e) = faults.apply(state, req).await {
+            tracing::warn!("Scenario faults not applied: {}", e);
+        }
+    }
+
+    for (i, step) in scenario.steps.iter().enumerate() {
+        let mut result = ScenarioStepResult {
+            index: i + 1,
+            kind: step.kind().to_string(),
+            description: step.describe(),
+            status: ScenarioStepStatus::Skipped,
+            time: 0.0,
+            message: None,
+            result: None,
+        };
+        if failed && !scenario.continue_on_failure {
+            steps.push(result);
+            continue;
+        }
+
+        let step_started = Instant::now();
+        match execute(state, step, &mut faults).await {
+            Ok(value) => {
+                result.status = ScenarioStepStatus::Passed;
+                result.result = value;
+            }
+            Err(message) => {
+                result.status = ScenarioStepStatus::Failed;
+                result.message = Some(message);
+                failed = true;
+            }
+        }
+        result.time = step_started.elapsed().as_secs_f64();
+        steps.push(result);
+    }
+
+    if let Err(e) = faults.end(state).await {
+        tracing::warn!("Scenario faults not cleared: {}", e);
+    }
+
+    let count = |status| steps.iter().filter(|s| s.status == status).count();
+    ScenarioReport {
+        name: scenario.name.clone().unwrap_or_else(|| "scenario".to_string()),
+        success: !failed,
+        passed: count(ScenarioStepStatus::Passed),
+        failed: count(ScenarioStepStatus::Failed),
+        skipped: count(ScenarioStepStatus::Skipped),
+        time: started.elapsed().as_secs_f64(),
+        steps,
+        faults: faults.reports,
+    }
+}
+
+/// POST /bot/scenario/run
+pub async fn handle_run(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ScenarioRunRequest>,
+) -> impl IntoResponse {
+    let scenario = match (req.source, req.scenario) {
+        (Some(source), _) => parse(&source),
+        (None, Some(value)) => from_value(value),
+        (None, None) => Err("Provide source (YAML or JSON) or scenario".to_string()),
+    };
+    let scenario = match scenario {
+        Ok(scenario) => scenario,
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "success": false, "error": error })),
+            )
+        }
+    };
+
+    let report = run(&state, &scenario).await;
+    tracing::info!(
+        "Scenario '{}': {} passed, {} failed, {} skipped",
+        report.name,
+        report.passed,
+        report.failed,
+        report.skipped
+    );
+    (StatusCode::OK, Json(serde_json::to_value(&report).unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_scenario_parse_and_assertions() {
+        let scenario = parse(
+            r#"
+name: Buy a potion
+steps:
+  - move: { objectName: Shopkeeper }
+  - action: jump
+  - action: { action: equip, name: Sword }
+  - wait: 1.5
+  - query: return workspace.Coins.Value
+  - assert: { path: health, greaterThan: 50 }
+"#,
+        )
+        .unwrap();
+        assert_eq!(scenario.name.as_deref(), Some("Buy a potion"));
+        let kinds: Vec<_> = scenario.steps.iter().map(Step::kind).collect();
+        assert_eq!(kinds, ["move", "action", "action", "wait", "query", "assert"]);
+        assert_eq!(scenario.steps[0].command().unwrap()["command"], "moveToObject");
+        assert_eq!(scenario.steps[1].command().unwrap()["command"], "jump");
+        assert_eq!(scenario.steps[2].command().unwrap()["args"]["name"], "Sword");
+        assert_eq!(scenario.steps[2].describe(), "equip Sword");
+        assert_eq!(scenario.steps[5].describe(), "health > 50");
+
+        // JSON is accepted too, and mistakes are caught before anything runs
+        let json_scenario = parse(r#"{ "steps": [{ "wait": 0 }] }"#).unwrap();
+        assert!(!json_scenario.continue_on_failure);
+        assert_eq!(parse("steps: []").unwrap_err(), "Scenario has no steps");
+        assert_eq!(parse("steps: [{ wait: 120 }]").unwrap_err(), "Step 1: wait must be between 0 and 60 seconds");
+        assert!(parse("steps: [{ move: {} }]").unwrap_err().contains("move needs position"));
+        assert!(parse("steps: [{ assert: { path: health } }]").unwrap_err().contains("needs equals"));
+        assert!(parse("steps: [{ teleport: Spawn }]").unwrap_err().starts_with("Invalid scenario"));
+
+        let state = json!({
+            "health": 80,
+            "position": { "x": 10.0, "y": 3.0, "z": -4.0, "_type": "Vector3" },
+            "inventory": ["Sword", "Potion"],
+            "nearbyNPCs": [{ "name": "Shopkeeper", "distance": 4.2 }],
+            "equipped": null
+        });
+        let assertion = |value: Value| serde_json::from_value::<Assertion>(value).unwrap().check(&state);
+        assert!(assertion(json!({ "path": "health", "equals": 80.0 })).is_ok());
+        assert_eq!(
+            assertion(json!({ "path": "health", "lessThan": 50 })).unwrap_err(),
+            "expected health < 50, got 80"
+        );
+        assert!(assertion(json!({ "path": "inventory", "contains": "Potion" })).is_ok());
+        assert!(assertion(json!({ "path": "nearbyNPCs", "contains": "Shopkeeper" })).is_ok());
+        assert!(assertion(json!({ "path": "nearbyNPCs.0.distance", "lessThan": 5 })).is_ok());
+        assert!(assertion(json!({ "path": "position", "near": [12, 3, -4] })).is_ok());
+        assert!(assertion(json!({ "path": "position", "near": [30, 3, -4], "within": 10 }))
+            .unwrap_err()
+            .contains("was 20.0 away"));
+        assert!(assertion(json!({ "path": "equipped", "exists": false })).is_ok());
+        assert_eq!(
+            assertion(json!({ "path": "position.w", "equals": 1 })).unwrap_err(),
+            "position.w does not exist"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scenario_faults() {
+        let scenario = parse(
+            r#"
+name: Lag
+faults: { latencyMs: 300, dropPercent: 10, seed: 1234 }
+steps:
+  - wait: 0
+  - faults: { physics: pause }
+  - faults: { jitterMs: 50, seed: 99 }
+  - wait: 0
+"#,
+        )
+        .unwrap();
+        assert_eq!(scenario.steps[1].describe(), "pause physics");
+        assert_eq!(scenario.steps[2].describe(), "faults latency 0ms, jitter 50ms");
+        assert!(parse("faults: { dropPercent: 150 }\nsteps: [{ wait: 0 }]").unwrap_err().contains("dropPercent"));
+        assert!(parse("steps: [{ faults: { physics: stop } }]").unwrap_err().contains("unknown physics action"));
+
+        let state = AppState::new();
+        let report = run(&state, &scenario).await;
+        assert!(report.success, "{:?}", report.steps);
+
+        // Each fault run the scenario started is reported, then cleared
+        let seeds: Vec<_> = report.faults.iter().map(|r| r["seed"].clone()).collect();
+        assert_eq!(seeds, [json!(1234), json!(99)]);
+        assert_eq!(report.faults[0]["events"][0]["kind"], "physicsPaused");
+        assert!(state.bot_faults.lock().await.config().is_none());
+        // The physics command went to the front of the bot queue
+        let queued = state.bot_command_queue.lock().await.pop_front().unwrap();
+        assert_eq!(queued["command"]["action"], "pausePhysics");
+    }
+}
@@ -0,0 +1,192 @@
+//! Script source editing
+//!
+//! Quick edits without a full sync, mainly for MCP clients:
+//!
+//! - `POST /script/get` returns a script's source as Studio has it
+//! - `POST /script/set` replaces the source
+//! - `POST /script/edit-lines` replaces lines `startLine..=endLine` with
+//!   `newText` (`endLine = startLine - 1` inserts before `startLine`)
+//!
+//! Edits are made by the plugin, and the source Studio ends up with is
+//! written to the script's `.luau` file, so the file and Studio stay in step.
+//! The file then counts as matching Studio for conflict detection.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::protocol::{InstancePath, PluginCommand, ScriptEditLines, ScriptSet};
+use crate::studio::{error, plugin_command, plugin_error, HandlerResult};
+use crate::AppState;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Script file suffixes, in the order an existing file is looked for
+const SCRIPT_SUFFIXES: [&str; 3] = [".server.luau", ".client.luau", ".luau"];
+
+#[derive(Debug, Deserialize)]
+pub struct ScriptGetRequest {
+    /// Instance path, e.g. `ServerScriptService/Main`
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptSetRequest {
+    pub path: String,
+    pub source: String,
+    /// Project whose file to update (default: the only connected one)
+    pub project_dir: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptEditLinesRequest {
+    pub path: String,
+    /// First line to replace, from 1
+    pub start_line: usize,
+    /// Last line to replace
+    pub end_line: usize,
+    /// Replacement lines, joined with `\n`; empty deletes the range
+    #[serde(default)]
+    pub new_text: String,
+    pub project_dir: Option<String>,
+}
+
+/// POST /script/get
+pub async fn handle_script_get(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ScriptGetRequest>,
+) -> impl IntoResponse {
+    match script_command(&state, PluginCommand::ScriptGet(InstancePath { path: req.path })).await {
+        Ok(data) => (StatusCode::OK, Json(with_success(data, None))),
+        Err(err) => err,
+    }
+}
+
+/// POST /script/set
+pub async fn handle_script_set(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ScriptSetRequest>,
+) -> impl IntoResponse {
+    let command = PluginCommand::ScriptSet(ScriptSet { path: req.path.clone(), source: req.source });
+    edit(&state, command, &req.path, req.project_dir.as_deref()).await
+}
+
+/// POST /script/edit-lines
+pub async fn handle_script_edit_lines(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ScriptEditLinesRequest>,
+) -> impl IntoResponse {
+    if req.start_line == 0 || req.end_line + 1 < req.start_line {
+        return error(StatusCode::BAD_REQUEST, "startLine must be at least 1 and endLine at least startLine - 1");
+    }
+    let command = PluginCommand::ScriptEditLines(ScriptEditLines {
+        path: req.path.clone(),
+        start_line: req.start_line,
+        end_line: req.end_line,
+        new_text: req.new_text,
+    });
+    edit(&state, command, &req.path, req.project_dir.as_deref()).await
+}
+
+/// Edit in Studio, then write Studio's new source to the script's file
+async fn edit(
+    state: &Arc<AppState>,
+    command: PluginCommand,
+    path: &str,
+    project_dir: Option<&str>,
+) -> HandlerResult {
+    let data = match script_command(state, command).await {
+        Ok(data) => data,
+        Err(err) => return err,
+    };
+    let Some(project_dir) = crate::flags::resolve_project(state, project_dir).await else {
+        // Studio was edited; there's no project to keep in step
+        return (StatusCode::OK, Json(with_success(data, None)));
+    };
+    let source = data.get("source").and_then(|s| s.as_str()).unwrap_or_default();
+    let class_name = data.get("className").and_then(|c| c.as_str()).unwrap_or("ModuleScript");
+    let (key, file) = script_file(Path::new(&project_dir), path, class_name);
+    if let Err(e) = std::fs::create_dir_all(file.parent().unwrap_or(Path::new("."))).and_then(|_| std::fs::write(&file, source)) {
+        return error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Studio was updated but {} couldn't be written: {}", file.display(), e),
+        );
+    }
+
+    let project_path = Path::new(&project_dir);
+    let mut sync_state = state.sync_state.write().await;
+    let project_state = sync_state.entry(project_dir.clone()).or_default();
+    project_state.set_base(project_path, &key);
+    project_state.save_bases(project_path);
+
+    (StatusCode::OK, Json(with_success(data, Some(&file))))
+}
+
+/// Send a script command and return the plugin's data
+async fn script_command(state: &Arc<AppState>, command: PluginCommand) -> Result<Value, HandlerResult> {
+    let response = plugin_command(state, command, TIMEOUT).await?;
+    if !response.success {
+        return Err(error(StatusCode::BAD_REQUEST, plugin_error(&response)));
+    }
+    Ok(response.data)
+}
+
+fn with_success(data: Value, file: Option<&Path>) -> Value {
+    let mut body = json!({ "success": true });
+    if let (Some(obj), Some(data)) = (body.as_object_mut(), data.as_object()) {
+        obj.extend(data.clone());
+        if let Some(file) = file {
+            obj.insert("file".to_string(), json!(file.to_string_lossy()));
+        }
+    }
+    body
+}
+
+/// Instance key and `.luau` file of the script at `path`: its existing file,
+/// or where a script of `class_name` would be written
+pub fn script_file(project_dir: &Path, path: &str, class_name: &str) -> (String, PathBuf) {
+    let config = crate::load_project_config(&project_dir.to_string_lossy());
+    let key = crate::apply_tree_mapping(path, &crate::get_tree_mapping(&config));
+    let full_path = project_dir.join("src").join(&key);
+    let file = SCRIPT_SUFFIXES
+        .iter()
+        .map(|suffix| PathBuf::from(rbxsync_core::path_with_suffix(&full_path, suffix)))
+        .find(|file| file.is_file())
+        .unwrap_or_else(|| {
+            let suffix = match class_name {
+                "Script" => ".server.luau",
+                "LocalScript" => ".client.luau",
+                _ => ".luau",
+            };
+            PathBuf::from(rbxsync_core::path_with_suffix(&full_path, suffix))
+        });
+    (key, file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("rbxsync.json"), r#"{ "treeMapping": { "ServerScriptService": "server" } }"#).unwrap();
+        std::fs::create_dir_all(root.join("src/server")).unwrap();
+        std::fs::write(root.join("src/server/Main.server.luau"), "print(1)").unwrap();
+
+        let (key, file) = script_file(root, "ServerScriptService/Main", "Script");
+        assert_eq!(key, "server/Main");
+        assert_eq!(file, root.join("src/server/Main.server.luau"));
+
+        // A ModuleScript with no file yet gets a plain .luau
+        let (_, file) = script_file(root, "ReplicatedStorage/Util", "ModuleScript");
+        assert_eq!(file, root.join("src/ReplicatedStorage/Util.luau"));
+    }
+}
@@ -0,0 +1,133 @@
+//! Static site export
+//!
+//! Reads a project's src files back into instances (through the tree mapping,
+//! like the summary) and writes the HTML bundle rendered by
+//! `rbxsync_core::site`. Used by `rbxsync export --site`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use rbxsync_core::SiteInstance;
+
+use crate::summary::{collect_files, script_file};
+
+/// Instances described by the files under src, sorted by path
+pub fn collect_instances(project_dir: &Path) -> Result<Vec<SiteInstance>, String> {
+    let src_dir = project_dir.join("src");
+    if !src_dir.is_dir() {
+        return Err(format!("Source directory does not exist: {}", src_dir.display()));
+    }
+    let config = crate::load_project_config(&project_dir.to_string_lossy());
+    let tree_mapping = crate::get_tree_mapping(&config);
+
+    let mut files = Vec::new();
+    collect_files(&src_dir, &mut files);
+    files.sort();
+    let mut instances: BTreeMap<String, SiteInstance> = BTreeMap::new();
+    for path in files {
+        let Ok(rel) = path.strip_prefix(&src_dir) else {
+            continue;
+        };
+        let Some(file_name) = rel.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let parent = rel.parent().map(rbxsync_core::path_to_string).unwrap_or_default();
+        let join = |name: &str| if parent.is_empty() { name.to_string() } else { format!("{}/{}", parent, name) };
+        let file = rbxsync_core::path_to_string(path.strip_prefix(project_dir).unwrap_or(&path));
+
+        // A script and its .rbxjson (or a folder's init script and _meta.rbxjson) are one instance
+        if let Some((class, stem)) = script_file(file_name) {
+            let fs_path = if stem == "init" { parent.clone() } else { join(stem) };
+            if fs_path.is_empty() {
+                continue;
+            }
+            let instance_path =
+                crate::normalize_path_for_comparison(&crate::apply_reverse_tree_mapping(&fs_path, &tree_mapping));
+            let inst = instances.entry(instance_path.clone()).or_default();
+            inst.instance_path = instance_path;
+            inst.class_name = class.to_string();
+            inst.file = Some(file);
+            inst.source = std::fs::read_to_string(&path).ok();
+        } else if file_name.ends_with(".rbxjson") && file_name != "terrain.rbxjson" {
+            let Some(json) = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            else {
+                continue;
+            };
+            let fs_path = if file_name == "_meta.rbxjson" {
+                parent.clone()
+            } else {
+                join(file_name.trim_end_matches(".rbxjson"))
+            };
+            if fs_path.is_empty() {
+                continue;
+            }
+            let instance_path =
+                crate::normalize_path_for_comparison(&crate::apply_reverse_tree_mapping(&fs_path, &tree_mapping));
+            let object = |key: &str| json.get(key).and_then(|v| v.as_object()).cloned().unwrap_or_default();
+            let inst = instances.entry(instance_path.clone()).or_default();
+            inst.instance_path = instance_path;
+            if inst.source.is_none() {
+                inst.class_name = json.get("className").and_then(|c| c.as_str()).unwrap_or("Folder").to_string();
+                inst.file = Some(file);
+            }
+            inst.properties = object("properties");
+            inst.attributes = object("attributes");
+        }
+    }
+    Ok(instances.into_values().collect())
+}
+
+/// Render the project into `out_dir`, returning the number of files written
+pub fn export_site(project_dir: &Path, out_dir: &Path) -> Result<usize, String> {
+    let instances = collect_instances(project_dir)?;
+    let name = crate::load_project_config(&project_dir.to_string_lossy())
+        .and_then(|c| c.get("name").and_then(|n| n.as_str()).map(str::to_string))
+        .or_else(|| project_dir.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "Project".to_string());
+
+    let pages = rbxsync_core::render_site(&name, &instances);
+    std::fs::create_dir_all(out_dir.join("instances"))
+        .map_err(|e| format!("Failed to create {}: {}", out_dir.display(), e))?;
+    for page in &pages {
+        let path = out_dir.join(&page.path);
+        std::fs::write(&path, &page.content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(pages.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_site() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        std::fs::write(root.join("rbxsync.json"), r#"{ "name": "Obby", "treeMapping": { "ReplicatedStorage": "shared" } }"#)
+            .unwrap();
+        std::fs::create_dir_all(root.join("src/shared/Shop")).unwrap();
+        std::fs::create_dir_all(root.join("src/ServerScriptService")).unwrap();
+        std::fs::write(root.join("src/shared/Shop/init.luau"), "return {}").unwrap();
+        std::fs::write(root.join("src/shared/Shop/_meta.rbxjson"), r#"{ "className": "ModuleScript", "attributes": { "Price": { "type": "number", "value": 5 } } }"#).unwrap();
+        std::fs::write(
+            root.join("src/ServerScriptService/Main.server.luau"),
+            "local Shop = require(game:GetService(\"ReplicatedStorage\").Shop)",
+        )
+        .unwrap();
+
+        let instances = collect_instances(root).unwrap();
+        assert_eq!(instances.len(), 2);
+        let shop = &instances[0];
+        assert_eq!(shop.instance_path, "ReplicatedStorage/Shop");
+        assert_eq!((shop.class_name.as_str(), shop.file.as_deref()), ("ModuleScript", Some("src/shared/Shop/init.luau")));
+        assert!(shop.attributes.contains_key("Price"));
+
+        let out = root.join("site");
+        assert_eq!(export_site(root, &out).unwrap(), 3 + 4);
+        let main = std::fs::read_to_string(out.join("instances/ServerScriptService.Main.html")).unwrap();
+        assert!(main.contains("<a href=\"ReplicatedStorage.Shop.html\">ReplicatedStorage/Shop</a>"));
+        assert!(std::fs::read_to_string(out.join("index.html")).unwrap().contains("<h1>Obby</h1>"));
+    }
+}
@@ -0,0 +1,317 @@
+//! Named snapshots
+//!
+//! `rbxsync snapshot save <name>` copies the extracted tree (`src`, optionally
+//! `terrain`, and rbxsync.json for its tree mapping) into
+//! `.rbxsync/snapshots/<name>/`. Restoring pushes that exact tree to Studio:
+//! every instance in it is updated and Studio-only instances are deleted,
+//! like a resync after a branch switch, but read from the snapshot instead of
+//! `src`. Take one before a risky bulk edit (an AI refactor, a replace across
+//! the map) and restore it if the result is wrong.
+//!
+//! A restore only changes Studio unless `files` is set, in which case `src`
+//! is replaced by the snapshot's copy too (the old `src` is archived to
+//! `.rbxsync-backup` first, like before an extraction).
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use rbxsync_core::api::SyncCommandRequest;
+use serde::{Deserialize, Serialize};
+
+use crate::git::{self, GitHead};
+use crate::AppState;
+
+/// Snapshot directory, relative to the project
+pub const SNAPSHOT_DIR: &str = ".rbxsync/snapshots";
+
+const INFO_FILE: &str = "snapshot.json";
+
+/// A saved snapshot, from its `snapshot.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    pub name: String,
+    /// UTC creation time, YYYY-MM-DDTHH:MM:SSZ
+    pub created_at: String,
+    /// Files copied from src
+    pub files: usize,
+    /// Whether terrain was saved with it
+    pub terrain: bool,
+    /// Git HEAD when it was taken, if the project is a repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head: Option<GitHead>,
+}
+
+pub fn snapshot_dir(project_dir: &Path, name: &str) -> PathBuf {
+    project_dir.join(SNAPSHOT_DIR).join(name)
+}
+
+/// Names become directory names: letters, digits, `-`, `_` and `.`, not
+/// starting with `.`
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid snapshot name {:?}: use letters, digits, '-', '_' and '.'", name))
+    }
+}
+
+/// Copy a directory tree; returns the number of files copied
+fn copy_tree(from: &Path, to: &Path) -> io::Result<usize> {
+    std::fs::create_dir_all(to)?;
+    let mut files = 0;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            files += copy_tree(&entry.path(), &dest)?;
+        } else {
+            std::fs::copy(entry.path(), &dest)?;
+            files += 1;
+        }
+    }
+    Ok(files)
+}
+
+/// Save the project's `src` (and `terrain` if asked) as snapshot `name`.
+/// An existing snapshot of that name is only replaced with `overwrite`.
+pub fn create(project_dir: &Path, name: &str, terrain: bool, overwrite: bool) -> Result<SnapshotInfo, String> {
+    validate_name(name)?;
+    let src_dir = project_dir.join("src");
+    if !src_dir.is_dir() {
+        return Err("Source directory does not exist".to_string());
+    }
+    let dir = snapshot_dir(project_dir, name);
+    if dir.exists() && !overwrite {
+        return Err(format!("Snapshot {} already exists", name));
+    }
+
+    // Copy under a temporary name so a failed copy never replaces a good snapshot
+    let partial = project_dir.join(SNAPSHOT_DIR).join(format!(".{}.partial", name));
+    let _ = std::fs::remove_dir_all(&partial);
+    let copy = || -> io::Result<SnapshotInfo> {
+        let files = copy_tree(&src_dir, &partial.join("src"))?;
+        let terrain_dir = project_dir.join("terrain");
+        let terrain = terrain && terrain_dir.is_dir();
+        if terrain {
+            copy_tree(&terrain_dir, &partial.join("terrain"))?;
+        }
+        let config = project_dir.join("rbxsync.json");
+        if config.is_file() {
+            std::fs::copy(config, partial.join("rbxsync.json"))?;
+        }
+        let info = SnapshotInfo {
+            name: name.to_string(),
+            created_at: rbxsync_core::defines::utc_timestamp(crate::nightly::now_secs()),
+            files,
+            terrain,
+            head: git::read_head(project_dir),
+        };
+        std::fs::write(partial.join(INFO_FILE), serde_json::to_string_pretty(&info)?)?;
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+        std::fs::rename(&partial, &dir)?;
+        Ok(info)
+    };
+    copy().map_err(|e| {
+        let _ = std::fs::remove_dir_all(&partial);
+        format!("Failed to save snapshot {}: {}", name, e)
+    })
+}
+
+/// A snapshot's info, if it exists
+pub fn load(project_dir: &Path, name: &str) -> Option<SnapshotInfo> {
+    let content = std::fs::read_to_string(snapshot_dir(project_dir, name).join(INFO_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Saved snapshots, newest first
+pub fn list(project_dir: &Path) -> Vec<SnapshotInfo> {
+    let Ok(entries) = std::fs::read_dir(project_dir.join(SNAPSHOT_DIR)) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<SnapshotInfo> = entries
+        .flatten()
+        .filter_map(|entry| load(project_dir, &entry.file_name().to_string_lossy()))
+        .collect();
+    snapshots.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.name.cmp(&b.name)));
+    snapshots
+}
+
+/// Replace `src` with the snapshot's copy, archiving the current one first
+fn restore_files(project_dir: &Path, name: &str) -> io::Result<usize> {
+    let staged = crate::extract_stream::staging_dir(project_dir);
+    let _ = std::fs::remove_dir_all(&staged);
+    let files = copy_tree(&snapshot_dir(project_dir, name).join("src"), &staged)?;
+    if let Some(info) = crate::backup::create_backup(project_dir)? {
+        tracing::info!("Backed up src to {}/{}", crate::backup::BACKUP_DIR, info.name);
+    }
+    crate::recovery::swap_src(project_dir, &staged)?;
+    Ok(files)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRequest {
+    pub project_dir: String,
+    pub name: String,
+    /// Save `terrain/` too
+    #[serde(default)]
+    pub terrain: bool,
+    /// Replace a snapshot of the same name
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreRequest {
+    pub project_dir: String,
+    pub name: String,
+    /// Also replace `src` with the snapshot's files
+    #[serde(default)]
+    pub files: bool,
+    /// Restore the snapshot's terrain, if it has any (default: true)
+    #[serde(default)]
+    pub terrain: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListQuery {
+    pub project_dir: String,
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> (StatusCode, Json<serde_json::Value>) {
+    (status, Json(serde_json::json!({ "success": false, "error": message.into() })))
+}
+
+/// Save a snapshot (POST /snapshot/create)
+pub async fn handle_create(Json(req): Json<CreateRequest>) -> impl IntoResponse {
+    let project = PathBuf::from(&req.project_dir);
+    let name = req.name.clone();
+    let result = tokio::task::spawn_blocking(move || create(&project, &name, req.terrain, req.overwrite))
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+    match result {
+        Ok(info) => {
+            tracing::info!("Saved snapshot {} ({} files) of {}", info.name, info.files, req.project_dir);
+            (StatusCode::OK, Json(serde_json::json!({ "success": true, "snapshot": info })))
+        }
+        Err(e) => error(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+/// List a project's snapshots (GET /snapshot/list?projectDir=...)
+pub async fn handle_list(Query(query): Query<ListQuery>) -> impl IntoResponse {
+    let snapshots = list(Path::new(&query.project_dir));
+    Json(serde_json::json!({ "success": true, "snapshots": snapshots }))
+}
+
+/// Push a snapshot to Studio, and optionally to src (POST /snapshot/restore)
+pub async fn handle_restore(State(state): State<Arc<AppState>>, Json(req): Json<RestoreRequest>) -> impl IntoResponse {
+    let project = PathBuf::from(&req.project_dir);
+    let Some(info) = load(&project, &req.name) else {
+        return error(StatusCode::NOT_FOUND, format!("No snapshot named {}", req.name));
+    };
+    if let Some(reason) = crate::branch::blocked(&state, &req.project_dir).await {
+        return error(StatusCode::CONFLICT, format!("Can't restore now: {}", reason));
+    }
+
+    let mut files_restored = None;
+    if req.files {
+        // The swap rewrites every file; hold live sync so it isn't pushed twice
+        state.live_sync_paused.store(true, std::sync::atomic::Ordering::Relaxed);
+        let (dir, name) = (project.clone(), req.name.clone());
+        let restored = tokio::task::spawn_blocking(move || restore_files(&dir, &name))
+            .await
+            .unwrap_or_else(|e| Err(io::Error::other(e)));
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        {
+            let mut rx = state.file_change_rx.lock().await;
+            while rx.try_recv().is_ok() {}
+        }
+        state.live_sync_paused.store(false, std::sync::atomic::Ordering::Relaxed);
+        match restored {
+            Ok(files) => files_restored = Some(files),
+            Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to restore src: {}", e)),
+        }
+    }
+
+    let tree_dir = snapshot_dir(&project, &req.name).to_string_lossy().to_string();
+    let operations = match crate::branch::push_tree(&state, &req.project_dir, &tree_dir).await {
+        Ok(count) => count,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to push snapshot to Studio: {}", e)),
+    };
+
+    let mut terrain_chunks = None;
+    if info.terrain && req.terrain.unwrap_or(true) {
+        let terrain = rbxsync_core::terrain::read_terrain(Path::new(&tree_dir)).ok().flatten();
+        if let Some(terrain) = terrain {
+            let command = SyncCommandRequest {
+                command: "terrain:sync".to_string(),
+                payload: serde_json::json!({ "terrain": terrain, "clear": true }),
+            };
+            match crate::handler_json(crate::handle_sync_command(State(state.clone()), Json(command)).await).await {
+                Ok(result) => terrain_chunks = result["data"]["chunksApplied"].as_u64(),
+                Err(e) => tracing::warn!("Snapshot {}: terrain restore failed: {}", req.name, e),
+            }
+        }
+    }
+
+    tracing::info!("Restored snapshot {} to Studio ({} operations)", req.name, operations);
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "success": true,
+            "snapshot": info,
+            "operations": operations,
+            "terrainChunks": terrain_chunks,
+            "filesRestored": files_restored
+        })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_create_and_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path();
+        std::fs::create_dir_all(project.join("src/Workspace/Map")).unwrap();
+        std::fs::write(project.join("src/Workspace/Map/_meta.rbxjson"), "{}").unwrap();
+        std::fs::write(project.join("src/Workspace/Spawn.rbxjson"), "{}").unwrap();
+        std::fs::create_dir_all(project.join("terrain")).unwrap();
+        std::fs::write(project.join("terrain/region_0_0_0.rbxterrain"), "").unwrap();
+
+        let info = create(project, "before-refactor", true, false).unwrap();
+        assert_eq!((info.files, info.terrain), (2, true));
+        assert!(snapshot_dir(project, "before-refactor").join("src/Workspace/Spawn.rbxjson").exists());
+        assert!(create(project, "before-refactor", false, false).is_err());
+        assert!(!create(project, "before-refactor", false, true).unwrap().terrain);
+
+        assert!(validate_name("../src").is_err());
+        assert!(validate_name(".hidden").is_err());
+        assert!(create(project, "a/b", false, false).is_err());
+
+        create(project, "second", false, false).unwrap();
+        let names: Vec<String> = list(project).into_iter().map(|s| s.name).collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&"second".to_string()));
+        assert!(load(project, "missing").is_none());
+    }
+}
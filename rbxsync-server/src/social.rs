@@ -0,0 +1,170 @@
+//! Social interaction commands for bot testing
+//!
+//! Chat, proximity prompts and dialog trees normally need someone at the
+//! keyboard. `/bot/social` lets a test send a chat message (optionally waiting
+//! for a reply that matches a Luau pattern), trigger a ProximityPrompt by its
+//! DataModel path, or pick a choice in a Dialog. Requests are checked here
+//! before they are queued, and again by the in-game runner, which knows where
+//! the character is and what the chat filter did to the message.
+
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::Deserialize;
+
+use crate::AppState;
+
+/// Roblox rejects chat messages longer than this
+pub const MAX_CHAT_LENGTH: usize = 200;
+
+/// Longest wait for a chat reply, kept under the bot command timeout
+pub const MAX_REPLY_TIMEOUT_SECS: f64 = 25.0;
+
+const DEFAULT_REPLY_TIMEOUT_SECS: f64 = 10.0;
+
+/// Social command request
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SocialRequest {
+    /// "chat", "prompt" or "dialog"
+    pub action: String,
+    /// Chat message to send
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Luau pattern a reply must match; the command waits for it when set
+    #[serde(default)]
+    pub wait_for: Option<String>,
+    /// Seconds to wait for the reply (default 10)
+    #[serde(default)]
+    pub timeout: Option<f64>,
+    /// Text channel name (default RBXGeneral)
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Dotted path of the ProximityPrompt or Dialog (or the part holding it)
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Dialog choice, by UserDialog text or instance name. Nested choices can
+    /// be given as a list to walk down the tree
+    #[serde(default, alias = "choices")]
+    pub choice: Option<serde_json::Value>,
+}
+
+fn required<'a>(value: &'a Option<String>, field: &str, action: &str) -> Result<&'a str, String> {
+    match value.as_deref().map(str::trim) {
+        Some(v) if !v.is_empty() => Ok(v),
+        _ => Err(format!("'{}' requires {}", action, field)),
+    }
+}
+
+impl SocialRequest {
+    /// Validate the request and build the command for the in-game runner
+    pub fn to_command(&self) -> Result<serde_json::Value, String> {
+        match self.action.as_str() {
+            "chat" => {
+                let message = required(&self.message, "message", "chat")?;
+                if message.chars().count() > MAX_CHAT_LENGTH {
+                    return Err(format!("message is longer than {} characters", MAX_CHAT_LENGTH));
+                }
+                let timeout = self.timeout.unwrap_or(DEFAULT_REPLY_TIMEOUT_SECS);
+                if !(timeout > 0.0 && timeout <= MAX_REPLY_TIMEOUT_SECS) {
+                    return Err(format!("timeout must be between 0 and {} seconds", MAX_REPLY_TIMEOUT_SECS));
+                }
+                let wait_for = self.wait_for.as_deref().filter(|p| !p.is_empty());
+                Ok(serde_json::json!({
+                    "type": "social",
+                    "command": "chat",
+                    "args": {
+                        "message": message,
+                        "channel": self.channel.as_deref().unwrap_or("RBXGeneral"),
+                        "waitFor": wait_for,
+                        "timeout": wait_for.map(|_| timeout)
+                    }
+                }))
+            }
+            "prompt" => {
+                let path = required(&self.path, "path", "prompt")?;
+                Ok(serde_json::json!({
+                    "type": "social",
+                    "command": "triggerPrompt",
+                    "args": { "path": path }
+                }))
+            }
+            "dialog" => {
+                let path = required(&self.path, "path", "dialog")?;
+                let choices: Vec<&str> = match &self.choice {
+                    Some(serde_json::Value::String(choice)) => vec![choice.as_str()],
+                    Some(serde_json::Value::Array(choices)) => choices.iter().filter_map(|c| c.as_str()).collect(),
+                    _ => Vec::new(),
+                };
+                if choices.is_empty() || choices.iter().any(|c| c.trim().is_empty()) {
+                    return Err("'dialog' requires choice (a string or a list of strings)".to_string());
+                }
+                Ok(serde_json::json!({
+                    "type": "social",
+                    "command": "selectDialog",
+                    "args": { "path": path, "choices": choices }
+                }))
+            }
+            other => Err(format!("Unknown social action '{}', use chat, prompt or dialog", other)),
+        }
+    }
+}
+
+/// POST /bot/social
+pub async fn handle_bot_social(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<SocialRequest>,
+) -> impl IntoResponse {
+    let command = match req.to_command() {
+        Ok(command) => command,
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "success": false, "error": error })),
+            )
+        }
+    };
+
+    match crate::send_bot_command_via_queue(&state, command).await {
+        Ok(data) => (StatusCode::OK, Json(data)),
+        Err((status, json)) => (status, json),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_social_request_validation() {
+        let chat: SocialRequest = serde_json::from_value(json!({
+            "action": "chat",
+            "message": "hello",
+            "waitFor": "^Welcome"
+        }))
+        .unwrap();
+        let command = chat.to_command().unwrap();
+        assert_eq!(command["command"], "chat");
+        assert_eq!(command["args"]["channel"], "RBXGeneral");
+        assert_eq!(command["args"]["timeout"], 10.0);
+
+        let too_long = SocialRequest {
+            action: "chat".to_string(),
+            message: Some("a".repeat(MAX_CHAT_LENGTH + 1)),
+            ..Default::default()
+        };
+        assert!(too_long.to_command().is_err());
+
+        let dialog: SocialRequest = serde_json::from_value(json!({
+            "action": "dialog",
+            "path": "Workspace.Shopkeeper.Head.Dialog",
+            "choices": ["What do you sell?", "Buy potion"]
+        }))
+        .unwrap();
+        assert_eq!(dialog.to_command().unwrap()["args"]["choices"][1], "Buy potion");
+
+        let prompt = SocialRequest { action: "prompt".to_string(), ..Default::default() };
+        assert_eq!(prompt.to_command().unwrap_err(), "'prompt' requires path");
+    }
+}
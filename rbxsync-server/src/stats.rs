@@ -0,0 +1,335 @@
+//! Sync statistics
+//!
+//! Every extraction and sync run appends one line to `.rbxsync/stats.jsonl` in
+//! the project: direction, instance and file counts, duration and errors.
+//! `/stats/summary` and `rbxsync stats` roll the history up by week, so a team
+//! can see when syncs started taking longer and whether the project's growth
+//! explains it.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+
+/// History file, relative to the project directory
+pub const STATS_FILE: &str = ".rbxsync/stats.jsonl";
+
+/// Studio to files, through extraction
+pub const EXTRACT: &str = "extract";
+/// Files to Studio
+pub const TO_STUDIO: &str = "to-studio";
+/// Studio edits written back to files
+pub const FROM_STUDIO: &str = "from-studio";
+
+/// Error messages kept per run; the count is always exact
+const MAX_ERROR_MESSAGES: usize = 5;
+
+const WEEK_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// One sync or extraction run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncRun {
+    /// Unix seconds when the run finished
+    pub timestamp: u64,
+    /// `extract`, `to-studio` or `from-studio`
+    pub direction: String,
+    /// Instances in the run (for extractions, the whole game)
+    #[serde(default)]
+    pub instances: usize,
+    /// Files written, or operations applied in Studio
+    #[serde(default)]
+    pub files: usize,
+    #[serde(default)]
+    pub errors: usize,
+    pub duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub error_messages: Vec<String>,
+}
+
+impl SyncRun {
+    /// A run in `direction` that started at `started` and finished now
+    pub fn finished(direction: &str, started: Instant) -> Self {
+        Self {
+            timestamp: crate::nightly::now_secs(),
+            direction: direction.to_string(),
+            instances: 0,
+            files: 0,
+            errors: 0,
+            duration_ms: started.elapsed().as_millis() as u64,
+            error_messages: Vec::new(),
+        }
+    }
+
+    /// Count the errors and keep the first few messages
+    pub fn with_errors(mut self, errors: &[String]) -> Self {
+        self.errors = errors.len();
+        self.error_messages = errors.iter().take(MAX_ERROR_MESSAGES).cloned().collect();
+        self
+    }
+}
+
+/// `YYYY-MM-DD` (UTC) for a Unix time, for week labels
+pub fn date_label(secs: u64) -> String {
+    let (year, month, day) = crate::nightly::civil_from_days((secs / 86_400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+pub fn stats_file(project_dir: &Path) -> PathBuf {
+    project_dir.join(STATS_FILE)
+}
+
+/// Append a run to the project's history
+pub fn record_run(project_dir: &Path, run: &SyncRun) -> std::io::Result<()> {
+    let path = stats_file(project_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let line = serde_json::to_string(run).map_err(std::io::Error::other)?;
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// Record a run, logging rather than failing the sync if the history can't be written
+pub fn record(project_dir: &str, run: SyncRun) {
+    if project_dir.is_empty() {
+        return;
+    }
+    if let Err(e) = record_run(Path::new(project_dir), &run) {
+        tracing::warn!("Failed to record sync stats: {}", e);
+    }
+}
+
+/// All recorded runs, oldest first. Lines that don't parse are skipped.
+pub fn load_runs(project_dir: &Path) -> Vec<SyncRun> {
+    std::fs::read_to_string(stats_file(project_dir))
+        .map(|content| content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Totals for one direction
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectionSummary {
+    pub direction: String,
+    pub runs: usize,
+    pub avg_duration_ms: u64,
+    pub last_duration_ms: u64,
+    pub last_instances: usize,
+    pub errors: usize,
+}
+
+/// Runs in one week
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeekSummary {
+    /// Unix seconds at the start of the week
+    pub week_start: u64,
+    pub runs: usize,
+    pub avg_duration_ms: u64,
+    /// Largest instance count seen that week
+    pub max_instances: usize,
+    pub errors: usize,
+}
+
+/// Rolled-up history
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsSummary {
+    pub total_runs: usize,
+    pub directions: Vec<DirectionSummary>,
+    /// Oldest first
+    pub weeks: Vec<WeekSummary>,
+    /// Average sync time in the latest week against the earliest, in percent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_change_pct: Option<f64>,
+    /// Largest instance count in the latest week against the earliest, in percent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_growth_pct: Option<f64>,
+    /// Plain-language reading of the trend
+    pub notes: Vec<String>,
+    /// Latest runs, newest first
+    pub recent: Vec<SyncRun>,
+}
+
+fn average(total: u64, count: usize) -> u64 {
+    if count == 0 {
+        0
+    } else {
+        total / count as u64
+    }
+}
+
+fn change_pct(from: f64, to: f64) -> Option<f64> {
+    (from > 0.0).then(|| ((to - from) / from * 1000.0).round() / 10.0)
+}
+
+/// Roll runs up by direction and by week, keeping the last `weeks` weeks for the trend
+pub fn summarize(runs: &[SyncRun], weeks: usize) -> StatsSummary {
+    let mut directions: Vec<DirectionSummary> = Vec::new();
+    for direction in [EXTRACT, TO_STUDIO, FROM_STUDIO] {
+        let matching: Vec<&SyncRun> = runs.iter().filter(|r| r.direction == direction).collect();
+        let Some(last) = matching.last() else {
+            continue;
+        };
+        directions.push(DirectionSummary {
+            direction: direction.to_string(),
+            runs: matching.len(),
+            avg_duration_ms: average(matching.iter().map(|r| r.duration_ms).sum(), matching.len()),
+            last_duration_ms: last.duration_ms,
+            last_instances: last.instances,
+            errors: matching.iter().map(|r| r.errors).sum(),
+        });
+    }
+
+    let mut by_week: BTreeMap<u64, Vec<&SyncRun>> = BTreeMap::new();
+    for run in runs {
+        by_week.entry(run.timestamp / WEEK_SECS).or_default().push(run);
+    }
+    let skip = by_week.len().saturating_sub(weeks.max(1));
+    let week_summaries: Vec<WeekSummary> = by_week
+        .into_iter()
+        .skip(skip)
+        .map(|(week, runs)| WeekSummary {
+            week_start: week * WEEK_SECS,
+            runs: runs.len(),
+            avg_duration_ms: average(runs.iter().map(|r| r.duration_ms).sum(), runs.len()),
+            max_instances: runs.iter().map(|r| r.instances).max().unwrap_or(0),
+            errors: runs.iter().map(|r| r.errors).sum(),
+        })
+        .collect();
+
+    let (duration_change_pct, instance_growth_pct) = match (week_summaries.first(), week_summaries.last()) {
+        (Some(first), Some(last)) if week_summaries.len() > 1 => (
+            change_pct(first.avg_duration_ms as f64, last.avg_duration_ms as f64),
+            change_pct(first.max_instances as f64, last.max_instances as f64),
+        ),
+        _ => (None, None),
+    };
+
+    StatsSummary {
+        total_runs: runs.len(),
+        notes: trend_notes(duration_change_pct, instance_growth_pct, week_summaries.len()),
+        directions,
+        weeks: week_summaries,
+        duration_change_pct,
+        instance_growth_pct,
+        recent: runs.iter().rev().take(10).cloned().collect(),
+    }
+}
+
+/// Explain a slowdown: growth that accounts for it, or none that does
+fn trend_notes(duration: Option<f64>, growth: Option<f64>, weeks: usize) -> Vec<String> {
+    let Some(duration) = duration else {
+        return Vec::new();
+    };
+    let growth = growth.unwrap_or(0.0);
+    let mut notes = Vec::new();
+    if duration >= 20.0 {
+        if growth >= duration / 2.0 {
+            notes.push(format!(
+                "Syncs are {:.0}% slower than {} weeks ago; the project grew {:.0}% in instances over the same period",
+                duration, weeks, growth
+            ));
+        } else {
+            notes.push(format!(
+                "Syncs are {:.0}% slower than {} weeks ago without matching growth ({:.0}% more instances); look for large scripts, meshes or unions added recently",
+                duration, weeks, growth
+            ));
+        }
+    } else if duration <= -20.0 {
+        notes.push(format!("Syncs are {:.0}% faster than {} weeks ago", -duration, weeks));
+    }
+    notes
+}
+
+/// Query for `GET /stats/summary`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsQuery {
+    #[serde(default)]
+    pub project_dir: Option<String>,
+    /// Weeks of history in the trend (default 8)
+    #[serde(default)]
+    pub weeks: Option<usize>,
+}
+
+/// GET /stats/summary
+pub async fn handle_summary(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StatsQuery>,
+) -> impl IntoResponse {
+    let Some(project_dir) = crate::flags::resolve_project(&state, query.project_dir.as_deref()).await else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "success": false, "error": "projectDir is required" })),
+        );
+    };
+    let summary = summarize(&load_runs(Path::new(&project_dir)), query.weeks.unwrap_or(8));
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "success": true, "projectDir": project_dir, "summary": summary })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_summary() {
+        let project = tempfile::tempdir().unwrap();
+        assert!(load_runs(project.path()).is_empty());
+
+        let run = |week: u64, direction: &str, instances: usize, duration_ms: u64| SyncRun {
+            timestamp: week * WEEK_SECS + 60,
+            direction: direction.to_string(),
+            instances,
+            files: instances / 2,
+            errors: 0,
+            duration_ms,
+            error_messages: Vec::new(),
+        };
+        for r in [
+            run(2900, EXTRACT, 1000, 2000),
+            run(2900, TO_STUDIO, 1000, 1000),
+            run(2903, EXTRACT, 1100, 4000),
+            run(2903, FROM_STUDIO, 3, 100).with_errors(&["Failed to write Foo.luau".to_string()]),
+        ] {
+            record_run(project.path(), &r).unwrap();
+        }
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(stats_file(project.path()))
+            .and_then(|mut f| writeln!(f, "not json"))
+            .unwrap();
+
+        let runs = load_runs(project.path());
+        assert_eq!(runs.len(), 4);
+        assert_eq!(runs[3].error_messages.len(), 1);
+
+        let summary = summarize(&runs, 8);
+        assert_eq!(summary.total_runs, 4);
+        assert_eq!(summary.directions.len(), 3);
+        assert_eq!(summary.directions[0].avg_duration_ms, 3000);
+        assert_eq!(summary.weeks.len(), 2);
+        assert_eq!(summary.weeks[1].max_instances, 1100);
+        assert_eq!(summary.instance_growth_pct, Some(10.0));
+        assert_eq!(summary.duration_change_pct, Some(36.7));
+        assert!(summary.notes[0].contains("without matching growth"));
+        assert_eq!(summary.recent[0].direction, FROM_STUDIO);
+
+        assert_eq!(summarize(&runs, 1).weeks.len(), 1);
+    }
+}
@@ -0,0 +1,391 @@
+//! Remote Studio control
+//!
+//! Save, publish and playtest without touching the Studio UI:
+//!
+//! - `POST /studio/save` asks the plugin to save the open place to Roblox
+//! - `POST /studio/play` starts a Play or Run session (optionally waiting for
+//!   it to finish and returning the captured output); `POST /studio/stop` ends it
+//! - `POST /publish` (or `/studio/publish`) publishes a place file, or a fresh
+//!   build of the project, through Open Cloud. The target is `universeId` and
+//!   `placeId` from the request, defaulting to the place in rbxsync.json:
+//!
+//! ```json
+//! { "publish": { "universeId": 123, "placeId": 456 } }
+//! ```
+//!
+//! (`nightly.publish` is used when there is no top-level `publish`). Since a
+//! publish goes live, it needs the target place ID repeated in `confirm` and
+//! the Open Cloud key in `RBXSYNC_OPEN_CLOUD_KEY`. Each publish is logged,
+//! with its version description, to `builds/publishes.jsonl`.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use rbxsync_core::BuildOptions;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::nightly::{PublishRecord, PublishTarget};
+use crate::protocol::{NoPayload, PluginCommand, StudioPlay};
+use crate::{AppState, PluginResponse};
+
+/// Play (solo), Run (server only) or a server with several clients
+pub const PLAY_MODES: &[&str] = &["play", "run", "server-and-clients"];
+
+/// Most clients Studio starts for a local server test
+pub const MAX_PLAYERS: u32 = 8;
+
+/// Longest playtest the server will start
+pub const MAX_DURATION_SECS: u64 = 3600;
+
+const DEFAULT_DURATION_SECS: u64 = 60;
+
+/// Extra time after a playtest's duration before waiting gives up
+const WAIT_GRACE: Duration = Duration::from_secs(30);
+
+pub(crate) type HandlerResult = (StatusCode, Json<serde_json::Value>);
+
+pub(crate) fn error(status: StatusCode, message: impl Into<String>) -> HandlerResult {
+    (status, Json(serde_json::json!({ "success": false, "error": message.into() })))
+}
+
+/// Send a command to the plugin and wait for its response
+pub(crate) async fn plugin_command(
+    state: &Arc<AppState>,
+    command: PluginCommand,
+    timeout: Duration,
+) -> Result<PluginResponse, HandlerResult> {
+    let request_id = Uuid::new_v4();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    state.response_channels.write().await.insert(request_id, tx);
+    state.request_queue.lock().await.push_back(command.request(request_id));
+    state.wakeups.notify_all();
+
+    let result = tokio::time::timeout(timeout, rx.recv()).await;
+    state.response_channels.write().await.remove(&request_id);
+    match result {
+        Ok(Some(response)) => Ok(response),
+        Ok(None) => Err(error(StatusCode::INTERNAL_SERVER_ERROR, "Channel closed")),
+        Err(_) => Err(error(
+            StatusCode::GATEWAY_TIMEOUT,
+            "Plugin response timeout - make sure Studio is connected",
+        )),
+    }
+}
+
+/// Plugin error message, falling back to `message` for TestRunner results
+pub(crate) fn plugin_error(response: &PluginResponse) -> String {
+    response
+        .error
+        .clone()
+        .or_else(|| response.data.get("message").and_then(|m| m.as_str()).map(str::to_string))
+        .unwrap_or_else(|| "Studio reported an error".to_string())
+}
+
+/// POST /studio/save
+pub async fn handle_save(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match plugin_command(&state, PluginCommand::StudioSave(NoPayload {}), Duration::from_secs(60)).await {
+        Ok(response) if response.success => {
+            let mut body = serde_json::json!({ "success": true });
+            if let (Some(obj), Some(data)) = (body.as_object_mut(), response.data.as_object()) {
+                obj.extend(data.clone());
+            }
+            (StatusCode::OK, Json(body))
+        }
+        Ok(response) => error(StatusCode::CONFLICT, plugin_error(&response)),
+        Err(err) => err,
+    }
+}
+
+/// Request for `POST /studio/play`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayRequest {
+    /// `play`, `run` or `server-and-clients`
+    #[serde(default = "default_mode")]
+    pub mode: String,
+    /// Clients for `server-and-clients`
+    #[serde(default)]
+    pub players: Option<u32>,
+    /// Seconds before the session stops itself
+    #[serde(default)]
+    pub duration: Option<u64>,
+    /// Wait for the session to end and return its output
+    #[serde(default)]
+    pub wait: bool,
+    #[serde(default)]
+    pub project_dir: Option<String>,
+}
+
+fn default_mode() -> String {
+    "play".to_string()
+}
+
+/// Check a play request and build the plugin payload
+pub fn play_payload(req: &PlayRequest) -> Result<StudioPlay, String> {
+    let mode = req.mode.to_lowercase();
+    if !PLAY_MODES.contains(&mode.as_str()) {
+        return Err(format!("Unknown mode '{}' (expected {})", req.mode, PLAY_MODES.join(", ")));
+    }
+    let players = match (mode.as_str(), req.players) {
+        ("server-and-clients", Some(n)) if (1..=MAX_PLAYERS).contains(&n) => Some(n),
+        ("server-and-clients", Some(n)) => {
+            return Err(format!("players must be between 1 and {} (got {})", MAX_PLAYERS, n))
+        }
+        ("server-and-clients", None) => Some(1),
+        (_, Some(_)) => return Err("players only applies to server-and-clients".to_string()),
+        (_, None) => None,
+    };
+    let duration = req.duration.unwrap_or(DEFAULT_DURATION_SECS);
+    if duration == 0 || duration > MAX_DURATION_SECS {
+        return Err(format!("duration must be between 1 and {} seconds", MAX_DURATION_SECS));
+    }
+
+    Ok(StudioPlay { mode, duration, players, project_dir: req.project_dir.clone(), http_mock: None })
+}
+
+/// Summary of a finished session's captured output
+fn session_result(data: &serde_json::Value) -> serde_json::Value {
+    let output = data.get("output").and_then(|o| o.as_array()).cloned().unwrap_or_default();
+    let count = |kind: &str| {
+        output
+            .iter()
+            .filter(|m| m.get("type").and_then(|t| t.as_str()) == Some(kind))
+            .count()
+    };
+    serde_json::json!({
+        "success": data.get("error").is_none_or(|e| e.is_null()),
+        "error": data.get("error"),
+        "errors": count("MessageError"),
+        "warnings": count("MessageWarning"),
+        "totalMessages": output.len(),
+        "output": output,
+    })
+}
+
+/// POST /studio/play
+pub async fn handle_play(State(state): State<Arc<AppState>>, Json(req): Json<PlayRequest>) -> impl IntoResponse {
+    let mut payload = match play_payload(&req) {
+        Ok(payload) => payload,
+        Err(message) => return error(StatusCode::BAD_REQUEST, message),
+    };
+    if state.playtest_active.load(std::sync::atomic::Ordering::Relaxed) {
+        return error(StatusCode::CONFLICT, "A playtest is already running; stop it first");
+    }
+    payload.http_mock = crate::mocks::mock_shim(&state, payload.project_dir.as_deref()).await;
+
+    let response = match plugin_command(&state, PluginCommand::StudioPlay(payload), Duration::from_secs(30)).await {
+        Ok(response) => response,
+        Err(err) => return err,
+    };
+    if !response.success {
+        return error(StatusCode::CONFLICT, plugin_error(&response));
+    }
+    if !req.wait {
+        return (StatusCode::OK, Json(serde_json::json!({ "success": true, "mode": req.mode.to_lowercase(), "started": true })));
+    }
+
+    // Poll until the session stops itself, then collect its output
+    let deadline = tokio::time::Instant::now()
+        + Duration::from_secs(req.duration.unwrap_or(DEFAULT_DURATION_SECS))
+        + WAIT_GRACE;
+    loop {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        match plugin_command(&state, PluginCommand::TestStatus(NoPayload {}), Duration::from_secs(10)).await {
+            Ok(status) if status.data.get("complete").and_then(|c| c.as_bool()) == Some(true) => break,
+            Ok(_) | Err(_) if tokio::time::Instant::now() < deadline => continue,
+            _ => break,
+        }
+    }
+    match plugin_command(&state, PluginCommand::TestFinish(NoPayload {}), Duration::from_secs(30)).await {
+        Ok(finished) => (StatusCode::OK, Json(session_result(&finished.data))),
+        Err(err) => err,
+    }
+}
+
+/// POST /studio/stop
+pub async fn handle_stop(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match plugin_command(&state, PluginCommand::TestFinish(NoPayload {}), Duration::from_secs(30)).await {
+        Ok(finished) => (StatusCode::OK, Json(session_result(&finished.data))),
+        Err(err) => err,
+    }
+}
+
+/// Request for `POST /publish`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishRequest {
+    #[serde(default)]
+    pub project_dir: Option<String>,
+    /// Must repeat the target place ID
+    #[serde(default)]
+    pub confirm: Option<u64>,
+    /// Target universe, over rbxsync.json's `publish.universeId`
+    #[serde(default)]
+    pub universe_id: Option<u64>,
+    /// Target place, over rbxsync.json's `publish.placeId`
+    #[serde(default)]
+    pub place_id: Option<u64>,
+    /// Place file to publish, relative to the project (default: a fresh build)
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Kept in `builds/publishes.jsonl` with the version number
+    #[serde(default)]
+    pub version_description: Option<String>,
+}
+
+/// Publish target for a project: `publish`, then `nightly.publish`
+pub fn publish_target(project_dir: &str) -> Option<PublishTarget> {
+    let config = crate::load_project_config(project_dir)?;
+    config
+        .get("publish")
+        .or_else(|| config.get("nightly").and_then(|n| n.get("publish")))
+        .and_then(|t| serde_json::from_value(t.clone()).ok())
+}
+
+/// Target for a publish: the given IDs, filled in from the project's target
+pub fn resolve_target(project_dir: &str, universe_id: Option<u64>, place_id: Option<u64>) -> Option<PublishTarget> {
+    match (universe_id, place_id, publish_target(project_dir)) {
+        (Some(universe_id), Some(place_id), _) => Some(PublishTarget { universe_id, place_id }),
+        (universe_id, place_id, Some(target)) => Some(PublishTarget {
+            universe_id: universe_id.unwrap_or(target.universe_id),
+            place_id: place_id.unwrap_or(target.place_id),
+        }),
+        _ => None,
+    }
+}
+
+/// Publish a place file, or a fresh build of the project when there's none,
+/// and log it to `builds/publishes.jsonl`. Returns the new version number.
+pub async fn publish_project(
+    project_dir: &str,
+    target: &PublishTarget,
+    file: Option<&Path>,
+    description: Option<&str>,
+) -> Result<u64, (StatusCode, String)> {
+    if std::env::var(crate::nightly::OPEN_CLOUD_KEY_ENV).is_err() {
+        return Err((StatusCode::UNAUTHORIZED, format!("{} is not set", crate::nightly::OPEN_CLOUD_KEY_ENV)));
+    }
+
+    let output = match file {
+        Some(file) => {
+            let is_place = file.extension().is_some_and(|e| e == "rbxl" || e == "rbxlx");
+            if !is_place || !file.is_file() {
+                return Err((StatusCode::BAD_REQUEST, format!("{} is not a .rbxl or .rbxlx file", file.display())));
+            }
+            file.to_path_buf()
+        }
+        None => {
+            let dir = crate::nightly::builds_dir(project_dir);
+            std::fs::create_dir_all(&dir).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            let output = dir.join("publish.rbxl");
+            crate::build::build_project(project_dir, &output, BuildOptions::default())
+                .await
+                .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, format!("Build failed: {}", e)))?;
+            output
+        }
+    };
+
+    tracing::info!("Publishing {} to place {}", output.display(), target.place_id);
+    let version = crate::nightly::publish(target, &output)
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e))?;
+    let record = PublishRecord {
+        timestamp: crate::nightly::now_secs(),
+        universe_id: target.universe_id,
+        place_id: target.place_id,
+        version_number: version,
+        description: description.map(str::to_string),
+        file: output.to_string_lossy().to_string(),
+    };
+    if let Err(e) = crate::nightly::log_publish(project_dir, &record) {
+        tracing::warn!("Failed to log publish: {}", e);
+    }
+    Ok(version)
+}
+
+/// POST /publish (also /studio/publish)
+pub async fn handle_publish(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<PublishRequest>,
+) -> impl IntoResponse {
+    let Some(project_dir) = crate::flags::resolve_project(&state, req.project_dir.as_deref()).await else {
+        return error(StatusCode::BAD_REQUEST, "projectDir is required");
+    };
+    let Some(target) = resolve_target(&project_dir, req.universe_id, req.place_id) else {
+        return error(
+            StatusCode::BAD_REQUEST,
+            "No publish target; pass universeId and placeId, or add \"publish\": { \"universeId\": ..., \"placeId\": ... } to rbxsync.json",
+        );
+    };
+    if req.confirm != Some(target.place_id) {
+        return error(
+            StatusCode::PRECONDITION_FAILED,
+            format!("Publishing goes live; pass confirm: {} to publish to place {}", target.place_id, target.place_id),
+        );
+    }
+
+    let file = req.file.as_ref().map(|file| Path::new(&project_dir).join(file));
+    match publish_project(&project_dir, &target, file.as_deref(), req.version_description.as_deref()).await {
+        Ok(version) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "success": true,
+                "universeId": target.universe_id,
+                "placeId": target.place_id,
+                "versionNumber": version
+            })),
+        ),
+        Err((status, e)) => error(status, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_play_payload_and_publish_target() {
+        let request = |mode: &str, players: Option<u32>, duration: Option<u64>| PlayRequest {
+            mode: mode.to_string(),
+            players,
+            duration,
+            wait: false,
+            project_dir: None,
+        };
+        let payload = play_payload(&request("Run", None, None)).unwrap();
+        assert_eq!(payload.mode, "run");
+        assert_eq!(payload.duration, DEFAULT_DURATION_SECS);
+        assert_eq!(play_payload(&request("server-and-clients", None, Some(30))).unwrap().players, Some(1));
+        assert!(play_payload(&request("server-and-clients", Some(9), None)).is_err());
+        assert!(play_payload(&request("play", Some(2), None)).is_err());
+        assert!(play_payload(&request("play", None, Some(0))).is_err());
+        assert!(play_payload(&request("team-test", None, None)).is_err());
+
+        let project = tempfile::tempdir().unwrap();
+        let dir = project.path().to_string_lossy().to_string();
+        assert!(publish_target(&dir).is_none());
+        std::fs::write(
+            project.path().join("rbxsync.json"),
+            r#"{ "nightly": { "publish": { "universeId": 1, "placeId": 2 } } }"#,
+        )
+        .unwrap();
+        assert_eq!(publish_target(&dir).unwrap().place_id, 2);
+        std::fs::write(
+            project.path().join("rbxsync.json"),
+            r#"{ "publish": { "universeId": 3, "placeId": 4 }, "nightly": { "publish": { "universeId": 1, "placeId": 2 } } }"#,
+        )
+        .unwrap();
+        assert_eq!(publish_target(&dir).unwrap().place_id, 4);
+
+        let target = resolve_target(&dir, None, Some(9)).unwrap();
+        assert_eq!((target.universe_id, target.place_id), (3, 9));
+        let empty = tempfile::tempdir().unwrap();
+        let empty_dir = empty.path().to_string_lossy().to_string();
+        assert!(resolve_target(&empty_dir, None, Some(9)).is_none());
+        assert_eq!(resolve_target(&empty_dir, Some(5), Some(6)).unwrap().place_id, 6);
+    }
+}
@@ -0,0 +1,263 @@
+//! Project summary for AI context
+//!
+//! Builds the compact overview from `rbxsync_core::summary` out of a project's
+//! src files and writes it to `.rbxsync/summary.md`. Each file's class and
+//! exports are cached in `.rbxsync/summary-cache.json` by modification time,
+//! so regenerating only rereads what changed. Once a project has a summary,
+//! the file watcher keeps it current.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use rbxsync_core::{ProjectSummary, SummaryEntry};
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+
+/// Rendered summary, relative to the project directory
+pub const SUMMARY_FILE: &str = ".rbxsync/summary.md";
+
+/// Per-file cache, relative to the project directory
+pub const CACHE_FILE: &str = ".rbxsync/summary-cache.json";
+
+/// A file's contribution to the summary, valid while its mtime is unchanged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedFile {
+    modified_ms: u64,
+    entry: SummaryEntry,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SummaryCache {
+    files: BTreeMap<String, CachedFile>,
+}
+
+fn modified_ms(path: &Path) -> u64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Script class and instance name from a script file name
+pub(crate) fn script_file(file_name: &str) -> Option<(&'static str, &str)> {
+    [(".server.luau", "Script"), (".client.luau", "LocalScript"), (".luau", "ModuleScript"), (".lua", "ModuleScript")]
+        .into_iter()
+        .find_map(|(ext, class)| file_name.strip_suffix(ext).map(|stem| (class, stem)))
+}
+
+/// Read one file under src into a summary entry
+fn read_entry(
+    project_dir: &Path,
+    src_dir: &Path,
+    path: &Path,
+    tree_mapping: &std::collections::HashMap<String, String>,
+) -> Option<SummaryEntry> {
+    let rel = path.strip_prefix(src_dir).ok()?;
+    let file_name = rel.file_name()?.to_str()?;
+    let parent = rel.parent().map(rbxsync_core::path_to_string).unwrap_or_default();
+    let join = |name: &str| if parent.is_empty() { name.to_string() } else { format!("{}/{}", parent, name) };
+
+    let (fs_path, class_name, api) = if let Some((class, stem)) = script_file(file_name) {
+        let api = (class == "ModuleScript")
+            .then(|| std::fs::read_to_string(path).ok())
+            .flatten()
+            .map(|source| rbxsync_core::scan_module(&source));
+        // init scripts are their folder
+        let fs_path = if stem == "init" { parent.clone() } else { join(stem) };
+        (fs_path, class.to_string(), api)
+    } else if file_name.ends_with(".rbxjson") && file_name != "terrain.rbxjson" {
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+        let class = json.get("className").and_then(|c| c.as_str()).unwrap_or("Folder").to_string();
+        let fs_path = if file_name == "_meta.rbxjson" {
+            parent.clone()
+        } else {
+            join(file_name.trim_end_matches(".rbxjson"))
+        };
+        (fs_path, class, None)
+    } else {
+        return None;
+    };
+    if fs_path.is_empty() {
+        return None;
+    }
+
+    let instance_path = crate::normalize_path_for_comparison(&crate::apply_reverse_tree_mapping(&fs_path, tree_mapping));
+    Some(SummaryEntry {
+        instance_path,
+        class_name,
+        file: rbxsync_core::path_to_string(path.strip_prefix(project_dir).unwrap_or(path)),
+        api,
+    })
+}
+
+pub(crate) fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// Whether a summary has been generated for this project (the file watcher only refreshes those)
+pub fn has_summary(project_dir: &Path) -> bool {
+    project_dir.join(CACHE_FILE).exists()
+}
+
+/// Build the summary, rereading only files changed since the last run, and
+/// write `.rbxsync/summary.md`
+pub fn generate(project_dir: &Path) -> Result<ProjectSummary, String> {
+    let src_dir = project_dir.join("src");
+    if !src_dir.is_dir() {
+        return Err(format!("Source directory does not exist: {}", src_dir.display()));
+    }
+    let config = crate::load_project_config(&project_dir.to_string_lossy());
+    let tree_mapping = crate::get_tree_mapping(&config);
+
+    let cache_path = project_dir.join(CACHE_FILE);
+    let previous: SummaryCache = std::fs::read_to_string(&cache_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    let mut files = Vec::new();
+    collect_files(&src_dir, &mut files);
+    let mut cache = SummaryCache::default();
+    let mut reread = 0;
+    for path in files {
+        let key = rbxsync_core::path_to_string(path.strip_prefix(&src_dir).unwrap_or(&path));
+        let modified = modified_ms(&path);
+        let cached = previous.files.get(&key).filter(|c| c.modified_ms == modified).cloned();
+        let cached = match cached {
+            Some(cached) => cached,
+            None => {
+                let Some(entry) = read_entry(project_dir, &src_dir, &path, &tree_mapping) else {
+                    continue;
+                };
+                reread += 1;
+                CachedFile { modified_ms: modified, entry }
+            }
+        };
+        cache.files.insert(key, cached);
+    }
+
+    let name = config
+        .as_ref()
+        .and_then(|c| c.get("name").and_then(|n| n.as_str()).map(str::to_string))
+        .or_else(|| project_dir.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "Project".to_string());
+    let entries: Vec<SummaryEntry> = cache.files.values().map(|c| c.entry.clone()).collect();
+    let summary = rbxsync_core::build_summary(&name, &entries);
+
+    std::fs::create_dir_all(project_dir.join(".rbxsync")).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string(&cache).map_err(|e| e.to_string())?;
+    std::fs::write(&cache_path, json).map_err(|e| format!("Failed to write {}: {}", CACHE_FILE, e))?;
+    std::fs::write(project_dir.join(SUMMARY_FILE), rbxsync_core::render_markdown(&summary))
+        .map_err(|e| format!("Failed to write {}: {}", SUMMARY_FILE, e))?;
+    tracing::debug!("Project summary updated ({} of {} files reread)", reread, entries.len());
+    Ok(summary)
+}
+
+/// Query for `GET /summary`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryQuery {
+    #[serde(default)]
+    pub project_dir: Option<String>,
+    /// "markdown" (default) or "json"
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// GET /summary
+pub async fn handle_summary(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SummaryQuery>,
+) -> impl IntoResponse {
+    let Some(project_dir) = crate::flags::resolve_project(&state, query.project_dir.as_deref()).await else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "success": false, "error": "projectDir is required" })),
+        );
+    };
+    let dir = PathBuf::from(&project_dir);
+    let result = tokio::task::spawn_blocking(move || generate(&dir))
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+
+    match result {
+        Ok(summary) if query.format.as_deref() == Some("json") => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "success": true, "projectDir": project_dir, "summary": summary })),
+        ),
+        Ok(summary) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "success": true,
+                "projectDir": project_dir,
+                "markdown": rbxsync_core::render_markdown(&summary)
+            })),
+        ),
+        Err(error) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "success": false, "error": error }))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_summary_incrementally() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        std::fs::write(root.join("rbxsync.json"), r#"{ "name": "Obby", "treeMapping": { "ReplicatedStorage": "shared" } }"#)
+            .unwrap();
+        std::fs::create_dir_all(root.join("src/shared/Remotes")).unwrap();
+        std::fs::create_dir_all(root.join("src/ServerScriptService/Rounds_a1b2c3d4")).unwrap();
+        std::fs::write(root.join("src/shared/Remotes/_meta.rbxjson"), r#"{ "className": "Folder" }"#).unwrap();
+        std::fs::write(root.join("src/shared/Remotes/Buy.rbxjson"), r#"{ "className": "RemoteEvent" }"#).unwrap();
+        std::fs::write(root.join("src/shared/Shop.luau"), "local Shop = {}\nfunction Shop.buy(item: string)\nend\nreturn Shop\n")
+            .unwrap();
+        std::fs::write(root.join("src/ServerScriptService/Rounds_a1b2c3d4/init.server.luau"), "print(1)").unwrap();
+
+        assert!(!has_summary(root));
+        let summary = generate(root).unwrap();
+        assert!(has_summary(root));
+        assert_eq!(summary.name, "Obby");
+        assert_eq!(summary.remotes[0].path, "ReplicatedStorage/Remotes/Buy");
+        assert_eq!(summary.modules[0].path, "ReplicatedStorage/Shop");
+        assert_eq!(summary.services[1].children[0].class_name, "Script");
+        assert_eq!(summary.services[1].children[0].name, "Rounds");
+
+        // Unchanged files come from the cache; changed ones are reread
+        std::fs::write(
+            root.join("src/shared/Shop.luau"),
+            "local Shop = {}\nfunction Shop.buy(item: string)\nend\nfunction Shop.sell(item: string)\nend\nreturn Shop\n",
+        )
+        .unwrap();
+        let file = std::fs::File::options().write(true).open(root.join("src/shared/Shop.luau")).unwrap();
+        file.set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(5)).unwrap();
+        let summary = generate(root).unwrap();
+        assert_eq!(summary.modules[0].api.functions.len(), 2);
+
+        let markdown = std::fs::read_to_string(root.join(SUMMARY_FILE)).unwrap();
+        assert!(markdown.contains("- Shop.sell(item: string)"));
+    }
+}
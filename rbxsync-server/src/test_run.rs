@@ -0,0 +1,438 @@
+//! Test runs with structured results
+//!
+//! `POST /test/run` runs a bootstrap script that finds the place's specs and
+//! reports on them. The built-in bootstrap (`test_run/bootstrap.luau`) runs
+//! them through TestEZ when the place has it, or runs the functions exported
+//! by each `*.spec` module. It runs on one of two [`Backend`]s:
+//!
+//! - Studio: a playtest in the connected Studio, with the bootstrap injected
+//!   into ServerScriptService. The server waits for the results (or stops the
+//!   playtest at the timeout).
+//! - Cloud: Open Cloud's Luau Execution API against the published place, for
+//!   CI machines with no Studio (see [`cloud`]).
+//!
+//! Either way the answer is a JUnit-style [`TestReport`].
+//!
+//! A project can bring its own bootstrap with `bootstrap` (a Luau file
+//! relative to the project). It gets the same `ROOTS` table and `finish`
+//! function, and must end with:
+//!
+//! ```lua
+//! return finish({
+//!     framework = "custom",
+//!     cases = { { suite = "Inventory", name = "adds items", status = "passed", time = 0.01 } },
+//! })
+//! ```
+//!
+//! `status` is `passed`, `failed` or `skipped`; failed cases can add `message`
+//! and `trace`. Script locations in either are resolved to files in the
+//! project (see [`crate::correlate`]), and [`report`] renders the result as
+//! JUnit XML or GitHub Actions annotations for CI.
+
+pub mod cloud;
+pub mod report;
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use rbxsync_core::api::{TestCase, TestCaseStatus, TestFailure, TestReport, TestRunRequest, TestSuite};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::protocol::{NoPayload, PluginCommand, TestSpecs};
+use crate::studio::{error, plugin_command, plugin_error, HandlerResult, MAX_DURATION_SECS};
+use crate::AppState;
+
+const BOOTSTRAP: &str = include_str!("test_run/bootstrap.luau");
+
+/// Searched for specs when the request names no roots
+pub const DEFAULT_ROOTS: &[&str] = &["ReplicatedStorage", "ServerScriptService", "ServerStorage"];
+
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+/// Extra time after the timeout before the server stops the playtest itself
+const WAIT_GRACE: Duration = Duration::from_secs(30);
+
+/// Where the specs run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// A playtest in the connected Studio
+    Studio,
+    /// Open Cloud Luau Execution against the published place
+    Cloud,
+}
+
+impl Backend {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "studio" => Some(Self::Studio),
+            "cloud" => Some(Self::Cloud),
+            _ => None,
+        }
+    }
+
+    /// The requested backend, else `test.backend` in the project's
+    /// rbxsync.json, else Studio
+    pub fn resolve(requested: Option<&str>, project_dir: Option<&str>) -> Result<Self, String> {
+        let configured = project_dir
+            .and_then(crate::load_project_config)
+            .and_then(|config| config.get("test")?.get("backend")?.as_str().map(str::to_string));
+        match requested.map(str::to_string).or(configured) {
+            Some(name) => Self::parse(&name).ok_or_else(|| format!("Unknown test backend '{}' (expected studio or cloud)", name)),
+            None => Ok(Self::Studio),
+        }
+    }
+
+    /// Defines `finish(results)`, which the bootstrap ends with
+    fn prelude(self) -> &'static str {
+        match self {
+            Self::Studio => {
+                "local function finish(results)\n\tgame:GetService(\"StudioTestService\"):EndTest({ rbxsyncTests = results })\n\treturn nil\nend"
+            }
+            Self::Cloud => "local function finish(results)\n\treturn { rbxsyncTests = results }\nend",
+        }
+    }
+}
+
+/// Bootstrap source: the `ROOTS` table and `finish`, then the built-in or
+/// custom script
+pub fn bootstrap_source(roots: &[String], custom: Option<&str>, backend: Backend) -> String {
+    let roots: Vec<String> = if roots.is_empty() {
+        DEFAULT_ROOTS.iter().map(|root| serde_json::to_string(root).unwrap_or_default()).collect()
+    } else {
+        roots.iter().map(|root| serde_json::to_string(root).unwrap_or_default()).collect()
+    };
+    format!(
+        "-- Generated by RbxSync for `rbxsync test`\nlocal ROOTS = {{ {} }}\n{}\n\n{}",
+        roots.join(", "),
+        backend.prelude(),
+        custom.unwrap_or(BOOTSTRAP)
+    )
+}
+
+/// Results the bootstrap ends the test with
+#[derive(Debug, Default, Deserialize)]
+struct RawResults {
+    #[serde(default)]
+    framework: Option<String>,
+    #[serde(default)]
+    cases: Vec<RawCase>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawCase {
+    #[serde(default)]
+    suite: String,
+    #[serde(default)]
+    classname: Option<String>,
+    name: String,
+    status: TestCaseStatus,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    trace: Option<String>,
+    #[serde(default)]
+    time: f64,
+}
+
+/// Group the cases into suites, in the order the bootstrap ran them
+fn suites(cases: Vec<RawCase>) -> Vec<TestSuite> {
+    let mut suites: Vec<TestSuite> = Vec::new();
+    for case in cases {
+        let suite_name = if case.suite.is_empty() { "specs".to_string() } else { case.suite };
+        let index = match suites.iter().position(|s| s.name == suite_name) {
+            Some(index) => index,
+            None => {
+                suites.push(TestSuite {
+                    name: suite_name.clone(),
+                    tests: 0,
+                    failures: 0,
+                    skipped: 0,
+                    time: 0.0,
+                    cases: Vec::new(),
+                });
+                suites.len() - 1
+            }
+        };
+        let suite = &mut suites[index];
+        suite.tests += 1;
+        suite.time += case.time;
+        match case.status {
+            TestCaseStatus::Failed => suite.failures += 1,
+            TestCaseStatus::Skipped => suite.skipped += 1,
+            TestCaseStatus::Passed => {}
+        }
+        let failure = (case.status == TestCaseStatus::Failed).then(|| TestFailure {
+            message: case.message.unwrap_or_else(|| "Failed".to_string()),
+            trace: case.trace,
+            location: None,
+        });
+        suite.cases.push(TestCase {
+            name: case.name,
+            classname: case.classname.unwrap_or_else(|| suite_name.clone()),
+            time: case.time,
+            status: case.status,
+            failure,
+        });
+    }
+    suites
+}
+
+/// Build the report from what the playtest ended with (the `EndTest`
+/// argument), `timeout` being the seconds it was given
+pub fn build_report(result: &Value, timeout: u64, time: f64, output: Vec<String>) -> TestReport {
+    let mut timed_out = false;
+    let raw = match result.get("rbxsyncTests") {
+        Some(raw) => serde_json::from_value::<RawResults>(raw.clone()).unwrap_or_else(|e| RawResults {
+            error: Some(format!("Malformed results from the bootstrap: {}", e)),
+            ..Default::default()
+        }),
+        None => {
+            let error = if result.get("autoStopped").and_then(|v| v.as_bool()) == Some(true) {
+                timed_out = true;
+                format!("Timed out after {}s before the specs finished", timeout)
+            } else if result.is_null() {
+                "The playtest ended without results".to_string()
+            } else {
+                "The playtest was stopped before the specs finished".to_string()
+            };
+            RawResults { error: Some(error), ..Default::default() }
+        }
+    };
+
+    let suites = suites(raw.cases);
+    let tests = suites.iter().map(|s| s.tests).sum();
+    let failures = suites.iter().map(|s| s.failures).sum();
+    let skipped = suites.iter().map(|s| s.skipped).sum();
+    TestReport {
+        success: raw.error.is_none() && failures == 0,
+        framework: raw.framework.unwrap_or_else(|| "custom".to_string()),
+        tests,
+        passed: tests - failures - skipped,
+        failures,
+        skipped,
+        time,
+        timed_out,
+        error: raw.error,
+        suites,
+        output,
+    }
+}
+
+/// Point each failure at the first script location in its message or trace
+/// that resolves to a file in the project
+pub fn locate_failures(report: &mut TestReport, project_dir: &Path) {
+    let project_dirs = [project_dir.to_path_buf()];
+    let failures = report.suites.iter_mut().flat_map(|s| &mut s.cases).filter_map(|c| c.failure.as_mut());
+    for failure in failures {
+        let text = format!("{}\n{}", failure.message, failure.trace.as_deref().unwrap_or_default());
+        failure.location = crate::correlate::correlate(&text, &project_dirs).into_iter().next();
+    }
+}
+
+/// Console lines of a finished playtest
+fn console_lines(data: &Value) -> Vec<String> {
+    data.get("output")
+        .and_then(|o| o.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|m| m.get("message").and_then(|t| t.as_str()).map(str::to_string))
+        .collect()
+}
+
+/// The request's timeout, checked against the backend's longest
+fn check_timeout(req: &TestRunRequest, max: u64) -> Result<u64, String> {
+    let timeout = req.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS);
+    if timeout == 0 || timeout > max {
+        return Err(format!("timeout must be between 1 and {} seconds", max));
+    }
+    Ok(timeout)
+}
+
+/// The request's bootstrap script, for the given backend
+fn load_bootstrap(req: &TestRunRequest, project_dir: Option<&str>, backend: Backend) -> Result<String, String> {
+    let custom = match &req.bootstrap {
+        Some(file) => {
+            let project_dir = project_dir.ok_or("projectDir is required for a custom bootstrap")?;
+            let path = Path::new(project_dir).join(file);
+            Some(std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?)
+        }
+        None => None,
+    };
+    Ok(bootstrap_source(&req.roots, custom.as_deref(), backend))
+}
+
+/// Check a run request and build the plugin payload
+fn specs_payload(req: &TestRunRequest, project_dir: Option<&str>) -> Result<TestSpecs, String> {
+    let mode = req.mode.as_deref().unwrap_or("play").to_lowercase();
+    if mode != "play" && mode != "run" {
+        return Err(format!("Unknown mode '{}' (expected play or run)", mode));
+    }
+    let timeout = check_timeout(req, MAX_DURATION_SECS)?;
+    let bootstrap = load_bootstrap(req, project_dir, Backend::Studio)?;
+    Ok(TestSpecs { mode, timeout, bootstrap, http_mock: None })
+}
+
+/// Run the specs through Open Cloud against the project's published place.
+/// Needs no server or Studio, so `rbxsync test` calls it directly.
+pub async fn run_cloud(req: &TestRunRequest, project_dir: &str) -> Result<TestReport, (StatusCode, String)> {
+    let target = cloud::load_target(project_dir).ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            "No place to test; add \"test\": { \"cloud\": { \"universeId\": ..., \"placeId\": ... } } to rbxsync.json"
+                .to_string(),
+        )
+    })?;
+    let timeout = check_timeout(req, cloud::MAX_TIMEOUT_SECS).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let script = load_bootstrap(req, Some(project_dir), Backend::Cloud).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let started = Instant::now();
+    let (result, output) = cloud::execute(&target, &script, timeout)
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e))?;
+    let mut report = build_report(&result, timeout, started.elapsed().as_secs_f64(), output);
+    locate_failures(&mut report, Path::new(project_dir));
+    Ok(report)
+}
+
+/// Run the specs on the request's backend
+async fn run(state: &Arc<AppState>, req: &TestRunRequest) -> Result<TestReport, HandlerResult> {
+    let project_dir = crate::flags::resolve_project(state, req.project_dir.as_deref()).await;
+    let backend = Backend::resolve(req.backend.as_deref(), project_dir.as_deref())
+        .map_err(|message| error(StatusCode::BAD_REQUEST, message))?;
+    match (backend, project_dir) {
+        (Backend::Studio, project_dir) => run_studio(state, req, project_dir).await,
+        (Backend::Cloud, Some(project_dir)) => {
+            run_cloud(req, &project_dir).await.map_err(|(status, message)| error(status, message))
+        }
+        (Backend::Cloud, None) => Err(error(StatusCode::BAD_REQUEST, "projectDir is required for the cloud backend")),
+    }
+}
+
+/// Start the playtest, wait for its results and stop it
+async fn run_studio(
+    state: &Arc<AppState>,
+    req: &TestRunRequest,
+    project_dir: Option<String>,
+) -> Result<TestReport, HandlerResult> {
+    let mut payload =
+        specs_payload(req, project_dir.as_deref()).map_err(|message| error(StatusCode::BAD_REQUEST, message))?;
+    if state.playtest_active.load(std::sync::atomic::Ordering::Relaxed) {
+        return Err(error(StatusCode::CONFLICT, "A playtest is already running; stop it first"));
+    }
+    payload.http_mock = crate::mocks::mock_shim(state, project_dir.as_deref()).await;
+    let timeout = payload.timeout;
+
+    let started = Instant::now();
+    let response = plugin_command(state, PluginCommand::TestSpecs(payload), Duration::from_secs(30)).await?;
+    if !response.success {
+        return Err(error(StatusCode::CONFLICT, plugin_error(&response)));
+    }
+
+    // The playtest stops itself at the timeout; past the grace period the
+    // server stops it instead
+    let deadline = started + Duration::from_secs(timeout) + WAIT_GRACE;
+    let mut timed_out = false;
+    loop {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let status = plugin_command(state, PluginCommand::TestStatus(NoPayload {}), Duration::from_secs(10)).await;
+        if let Ok(status) = &status {
+            if status.data.get("complete").and_then(|c| c.as_bool()) == Some(true) {
+                break;
+            }
+        }
+        if Instant::now() > deadline {
+            timed_out = true;
+            break;
+        }
+    }
+
+    let finished = plugin_command(state, PluginCommand::TestFinish(NoPayload {}), Duration::from_secs(30)).await?;
+    let data = &finished.data;
+    let result = if timed_out { serde_json::json!({ "autoStopped": true }) } else { data["testResult"].clone() };
+    let mut report = build_report(&result, timeout, started.elapsed().as_secs_f64(), console_lines(data));
+    if let Some(e) = data.get("error").and_then(|e| e.as_str()) {
+        report.success = false;
+        if report.error.is_none() {
+            report.error = Some(e.to_string());
+        }
+    }
+    if let Some(project_dir) = &project_dir {
+        locate_failures(&mut report, Path::new(project_dir));
+    }
+    Ok(report)
+}
+
+/// POST /test/run
+pub async fn handle_run(State(state): State<Arc<AppState>>, Json(req): Json<TestRunRequest>) -> impl IntoResponse {
+    match run(&state, &req).await {
+        Ok(report) => {
+            tracing::info!(
+                "Test run: {} passed, {} failed, {} skipped{}",
+                report.passed,
+                report.failures,
+                report.skipped,
+                report.error.as_deref().map(|e| format!(" ({})", e)).unwrap_or_default()
+            );
+            (StatusCode::OK, Json(serde_json::to_value(&report).unwrap_or_default()))
+        }
+        Err(err) => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_report() {
+        let result = serde_json::json!({ "rbxsyncTests": {
+            "framework": "spec",
+            "cases": [
+                { "suite": "ReplicatedStorage/Inventory", "name": "adds items", "status": "passed", "time": 0.25 },
+                { "suite": "ReplicatedStorage/Inventory", "name": "rejects full", "status": "failed",
+                  "message": "Inventory.spec:12: expected false", "trace": "Inventory.spec:12", "time": 0.5 },
+                { "suite": "ReplicatedStorage/Shop", "name": "buys", "status": "skipped" }
+            ]
+        } });
+        let report = build_report(&result, 60, 3.0, vec!["hello".to_string()]);
+        assert!(!report.success);
+        assert_eq!(report.framework, "spec");
+        assert_eq!((report.tests, report.passed, report.failures, report.skipped), (3, 1, 1, 1));
+        assert_eq!(report.suites.len(), 2);
+        let inventory = &report.suites[0];
+        assert_eq!((inventory.name.as_str(), inventory.tests, inventory.failures), ("ReplicatedStorage/Inventory", 2, 1));
+        assert_eq!(inventory.time, 0.75);
+        assert_eq!(inventory.cases[0].classname, "ReplicatedStorage/Inventory");
+        assert_eq!(inventory.cases[1].failure.as_ref().unwrap().message, "Inventory.spec:12: expected false");
+        assert_eq!(report.output, vec!["hello"]);
+
+        let passing = serde_json::json!({ "rbxsyncTests": { "cases": [{ "name": "ok", "status": "passed" }] } });
+        let report = build_report(&passing, 60, 1.0, vec![]);
+        assert!(report.success);
+        assert_eq!((report.framework.as_str(), report.suites[0].name.as_str()), ("custom", "specs"));
+
+        let report = build_report(&serde_json::json!({ "autoStopped": true }), 60, 61.0, vec![]);
+        assert!(!report.success && report.timed_out);
+        assert_eq!(report.error.as_deref(), Some("Timed out after 60s before the specs finished"));
+        let report = build_report(&serde_json::json!({ "rbxsyncTests": { "error": "boom" } }), 60, 1.0, vec![]);
+        assert_eq!((report.success, report.error.as_deref()), (false, Some("boom")));
+        assert!(!build_report(&Value::Null, 60, 1.0, vec![]).success);
+
+        let source = bootstrap_source(&[], None, Backend::Studio);
+        assert!(source.contains("local ROOTS = { \"ReplicatedStorage\", \"ServerScriptService\", \"ServerStorage\" }"));
+        assert!(source.contains(":EndTest({ rbxsyncTests = results })"));
+        assert!(source.ends_with("return finish(if ok then report else { error = tostring(report) })\n"));
+        let source = bootstrap_source(&["Workspace/Tests".to_string()], Some("print(ROOTS[1])"), Backend::Cloud);
+        assert!(source.contains("local ROOTS = { \"Workspace/Tests\" }\nlocal function finish(results)\n\treturn { rbxsyncTests = results }"));
+        assert!(source.ends_with("\n\nprint(ROOTS[1])"));
+
+        assert_eq!(Backend::resolve(Some("Cloud"), None), Ok(Backend::Cloud));
+        assert_eq!(Backend::resolve(None, None), Ok(Backend::Studio));
+        assert!(Backend::resolve(Some("lune"), None).is_err());
+    }
+}
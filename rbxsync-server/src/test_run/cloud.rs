@@ -0,0 +1,186 @@
+//! Open Cloud test backend
+//!
+//! Runs the test bootstrap through Open Cloud's Luau Execution API against a
+//! published place, so specs can run in CI with no Studio open. The place is
+//! `test.cloud` in rbxsync.json, falling back to the publish target:
+//!
+//! ```json
+//! { "test": { "backend": "cloud", "cloud": { "universeId": 123, "placeId": 456 } } }
+//! ```
+//!
+//! `versionId` pins a place version (default: the latest). The API key, which
+//! needs the `universe.place.luau-execution-session:write` scope, is read from
+//! `RBXSYNC_OPEN_CLOUD_KEY` as for publishing. The specs run in a server of
+//! the published place, so publish the changes under test first.
+
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::nightly::OPEN_CLOUD_KEY_ENV;
+
+const API_BASE: &str = "https://apis.roblox.com/cloud/v2";
+
+/// Longest task Open Cloud runs
+pub const MAX_TIMEOUT_SECS: u64 = 300;
+
+/// How long a task may sit in Open Cloud's queue on top of its timeout
+const QUEUE_GRACE: Duration = Duration::from_secs(120);
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Place the specs run against
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudTarget {
+    pub universe_id: u64,
+    pub place_id: u64,
+    #[serde(default)]
+    pub version_id: Option<u64>,
+}
+
+impl CloudTarget {
+    fn tasks_url(&self) -> String {
+        match self.version_id {
+            Some(version) => format!(
+                "{}/universes/{}/places/{}/versions/{}/luau-execution-session-tasks",
+                API_BASE, self.universe_id, self.place_id, version
+            ),
+            None => format!(
+                "{}/universes/{}/places/{}/luau-execution-session-tasks",
+                API_BASE, self.universe_id, self.place_id
+            ),
+        }
+    }
+}
+
+/// `test.cloud` in rbxsync.json, else the project's publish target
+pub fn load_target(project_dir: &str) -> Option<CloudTarget> {
+    let configured = crate::load_project_config(project_dir)
+        .and_then(|config| config.get("test")?.get("cloud").cloned())
+        .and_then(|target| serde_json::from_value(target).ok());
+    configured.or_else(|| {
+        crate::studio::publish_target(project_dir).map(|target| CloudTarget {
+            universe_id: target.universe_id,
+            place_id: target.place_id,
+            version_id: None,
+        })
+    })
+}
+
+/// What a finished task ended with: the first value the script returned, or
+/// why it didn't return. `None` while the task is queued or running.
+///
+/// A task Open Cloud stopped at its timeout ends as `{ "autoStopped": true }`,
+/// like a Studio playtest that hit its duration.
+pub fn task_result(task: &Value) -> Option<Result<Value, String>> {
+    match task.get("state").and_then(|s| s.as_str()).unwrap_or_default() {
+        "COMPLETE" => Some(Ok(task.pointer("/output/results/0").cloned().unwrap_or(Value::Null))),
+        "FAILED" if task.pointer("/error/code").and_then(|c| c.as_str()) == Some("DEADLINE_EXCEEDED") => {
+            Some(Ok(serde_json::json!({ "autoStopped": true })))
+        }
+        "FAILED" => Some(Err(task
+            .pointer("/error/message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("The Luau task failed")
+            .to_string())),
+        "CANCELLED" => Some(Err("The Luau task was cancelled".to_string())),
+        _ => None,
+    }
+}
+
+/// Printed lines from a task's logs
+fn log_lines(logs: &Value) -> Vec<String> {
+    logs.get("luauExecutionSessionTaskLogs")
+        .and_then(|l| l.as_array())
+        .into_iter()
+        .flatten()
+        .flat_map(|page| page.get("messages").and_then(|m| m.as_array()).cloned().unwrap_or_default())
+        .filter_map(|message| message.as_str().map(str::to_string))
+        .collect()
+}
+
+async fn send(request: reqwest::RequestBuilder) -> Result<Value, String> {
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or_default();
+    if !status.is_success() {
+        let message = body.get("message").and_then(|m| m.as_str()).unwrap_or_default();
+        return Err(format!("Open Cloud returned {}: {}", status, message));
+    }
+    Ok(body)
+}
+
+/// Run `script` on the target and wait for it; returns what it ended with
+/// (see [`task_result`]) and what it printed
+pub async fn execute(target: &CloudTarget, script: &str, timeout: u64) -> Result<(Value, Vec<String>), String> {
+    let key = std::env::var(OPEN_CLOUD_KEY_ENV).map_err(|_| format!("{} is not set", OPEN_CLOUD_KEY_ENV))?;
+    let client = reqwest::Client::new();
+
+    let created = send(
+        client
+            .post(target.tasks_url())
+            .header("x-api-key", &key)
+            .json(&serde_json::json!({ "script": script, "timeout": format!("{}s", timeout) })),
+    )
+    .await?;
+    let path = created
+        .get("path")
+        .and_then(|p| p.as_str())
+        .ok_or("Open Cloud response had no task path")?
+        .to_string();
+    tracing::info!("Running specs on place {} ({})", target.place_id, path);
+
+    let deadline = Instant::now() + Duration::from_secs(timeout) + QUEUE_GRACE;
+    let result = loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let task = send(client.get(format!("{}/{}", API_BASE, path)).header("x-api-key", &key)).await?;
+        if let Some(result) = task_result(&task) {
+            break result?;
+        }
+        if Instant::now() > deadline {
+            break serde_json::json!({ "autoStopped": true });
+        }
+    };
+
+    // The log is a nice-to-have; a failed fetch still returns the results
+    let logs = send(client.get(format!("{}/{}/logs", API_BASE, path)).header("x-api-key", &key)).await;
+    Ok((result, logs.map(|logs| log_lines(&logs)).unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cloud_task_result_and_target() {
+        let complete = serde_json::json!({
+            "state": "COMPLETE",
+            "output": { "results": [{ "rbxsyncTests": { "cases": [] } }] }
+        });
+        assert_eq!(task_result(&complete), Some(Ok(serde_json::json!({ "rbxsyncTests": { "cases": [] } }))));
+        assert_eq!(task_result(&serde_json::json!({ "state": "PROCESSING" })), None);
+        let failed = serde_json::json!({ "state": "FAILED", "error": { "code": "SCRIPT_ERROR", "message": "boom" } });
+        assert_eq!(task_result(&failed), Some(Err("boom".to_string())));
+        let timed_out = serde_json::json!({ "state": "FAILED", "error": { "code": "DEADLINE_EXCEEDED" } });
+        assert_eq!(task_result(&timed_out), Some(Ok(serde_json::json!({ "autoStopped": true }))));
+
+        let logs = serde_json::json!({ "luauExecutionSessionTaskLogs": [{ "messages": ["one", "two"] }, { "messages": ["three"] }] });
+        assert_eq!(log_lines(&logs), vec!["one", "two", "three"]);
+
+        let project = tempfile::tempdir().unwrap();
+        let dir = project.path().to_string_lossy().to_string();
+        assert!(load_target(&dir).is_none());
+        std::fs::write(project.path().join("rbxsync.json"), r#"{ "publish": { "universeId": 1, "placeId": 2 } }"#).unwrap();
+        assert_eq!(load_target(&dir), Some(CloudTarget { universe_id: 1, place_id: 2, version_id: None }));
+        std::fs::write(
+            project.path().join("rbxsync.json"),
+            r#"{ "publish": { "universeId": 1, "placeId": 2 }, "test": { "cloud": { "universeId": 3, "placeId": 4, "versionId": 7 } } }"#,
+        )
+        .unwrap();
+        let target = load_target(&dir).unwrap();
+        assert_eq!(target.version_id, Some(7));
+        assert!(target.tasks_url().ends_with("/universes/3/places/4/versions/7/luau-execution-session-tasks"));
+    }
+}
@@ -0,0 +1,210 @@
+//! Test reports for CI
+//!
+//! `rbxsync test --report junit` writes a [`TestReport`] as JUnit XML, which
+//! most CI systems can display. `--report github` prints GitHub Actions
+//! workflow commands, so each failed spec shows up as an annotation on the
+//! line of the script that failed.
+
+use std::path::Path;
+
+use rbxsync_core::api::{TestCaseStatus, TestReport};
+
+/// Formats `rbxsync test --report` accepts
+pub const FORMATS: &[&str] = &["junit", "github"];
+
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// The report as JUnit XML
+pub fn junit_xml(report: &TestReport) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"rbxsync\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n",
+        report.tests,
+        report.failures,
+        usize::from(report.error.is_some()),
+        report.skipped,
+        report.time
+    ));
+
+    for suite in &report.suites {
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\" time=\"{:.3}\">\n",
+            escape_xml(&suite.name),
+            suite.tests,
+            suite.failures,
+            suite.skipped,
+            suite.time
+        ));
+        for case in &suite.cases {
+            let mut attributes = format!(
+                "name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                escape_xml(&case.name),
+                escape_xml(&case.classname),
+                case.time
+            );
+            if let Some(location) = case.failure.as_ref().and_then(|f| f.location.as_ref()) {
+                attributes.push_str(&format!(" file=\"{}\" line=\"{}\"", escape_xml(&location.file), location.line));
+            }
+            match (&case.status, &case.failure) {
+                (TestCaseStatus::Failed, Some(failure)) => {
+                    let mut body = failure.message.clone();
+                    if let Some(trace) = &failure.trace {
+                        body.push('\n');
+                        body.push_str(trace);
+                    }
+                    xml.push_str(&format!(
+                        "    <testcase {}>\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+                        attributes,
+                        escape_xml(failure.message.lines().next().unwrap_or_default()),
+                        escape_xml(&body)
+                    ));
+                }
+                (TestCaseStatus::Skipped, _) => {
+                    xml.push_str(&format!("    <testcase {}>\n      <skipped/>\n    </testcase>\n", attributes))
+                }
+                _ => xml.push_str(&format!("    <testcase {}/>\n", attributes)),
+            }
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+
+    // A run that produced no results still fails the build
+    if let Some(error) = &report.error {
+        xml.push_str(&format!(
+            "  <testsuite name=\"rbxsync\" tests=\"1\" failures=\"0\" errors=\"1\" skipped=\"0\" time=\"0\">\n    \
+             <testcase name=\"test run\" classname=\"rbxsync\">\n      <error message=\"{}\"/>\n    </testcase>\n  \
+             </testsuite>\n",
+            escape_xml(error)
+        ));
+    }
+    if !report.output.is_empty() {
+        xml.push_str(&format!("  <system-out>{}</system-out>\n", escape_xml(&report.output.join("\n"))));
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// Escape a workflow command's message
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escape a workflow command's property value
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// One `::error` workflow command per failed spec (and one for a run that
+/// produced no results). File paths are made relative to `base`, which
+/// should be the repository root GitHub checked out.
+pub fn github_annotations(report: &TestReport, base: &Path) -> String {
+    let mut out = String::new();
+    for suite in &report.suites {
+        for case in &suite.cases {
+            let Some(failure) = case.failure.as_ref().filter(|_| case.status == TestCaseStatus::Failed) else {
+                continue;
+            };
+            let mut properties = Vec::new();
+            if let Some(location) = &failure.location {
+                let file = Path::new(&location.file);
+                let file = file.strip_prefix(base).unwrap_or(file).to_string_lossy().replace('\\', "/");
+                properties.push(format!("file={}", escape_property(&file)));
+                properties.push(format!("line={}", location.line));
+            }
+            properties.push(format!("title={}", escape_property(&format!("{} > {}", case.classname, case.name))));
+            out.push_str(&format!("::error {}::{}\n", properties.join(","), escape_data(&failure.message)));
+        }
+    }
+    if let Some(error) = &report.error {
+        out.push_str(&format!("::error title=rbxsync test::{}\n", escape_data(error)));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rbxsync_core::api::{SourceLocation, TestCase, TestFailure, TestSuite};
+
+    #[test]
+    fn test_junit_and_github_reports() {
+        let case = |name: &str, status: TestCaseStatus, failure: Option<TestFailure>| TestCase {
+            name: name.to_string(),
+            classname: "Shared/Inventory".to_string(),
+            time: 0.5,
+            status,
+            failure,
+        };
+        let failure = TestFailure {
+            message: "Inventory.spec:14: expected <false>, got true".to_string(),
+            trace: Some("ReplicatedStorage.Shared.Inventory.spec:14".to_string()),
+            location: Some(SourceLocation {
+                instance_path: "ReplicatedStorage.Shared.Inventory.spec".to_string(),
+                line: 14,
+                file: "/work/game/src/shared/Inventory.spec.luau".to_string(),
+                link: "/work/game/src/shared/Inventory.spec.luau:14".to_string(),
+            }),
+        };
+        let report = TestReport {
+            success: false,
+            framework: "spec".to_string(),
+            tests: 3,
+            passed: 1,
+            failures: 1,
+            skipped: 1,
+            time: 4.0,
+            timed_out: false,
+            error: None,
+            suites: vec![TestSuite {
+                name: "Shared/Inventory".to_string(),
+                tests: 3,
+                failures: 1,
+                skipped: 1,
+                time: 1.5,
+                cases: vec![
+                    case("adds items", TestCaseStatus::Passed, None),
+                    case("rejects full", TestCaseStatus::Failed, Some(failure)),
+                    case("sorts", TestCaseStatus::Skipped, None),
+                ],
+            }],
+            output: vec!["loaded & ready".to_string()],
+        };
+
+        let xml = junit_xml(&report);
+        assert!(xml.contains("<testsuites name=\"rbxsync\" tests=\"3\" failures=\"1\" errors=\"0\" skipped=\"1\" time=\"4.000\">"));
+        assert!(xml.contains("<testcase name=\"adds items\" classname=\"Shared/Inventory\" time=\"0.500\"/>"));
+        assert!(xml.contains(
+            "file=\"/work/game/src/shared/Inventory.spec.luau\" line=\"14\">\n      \
+             <failure message=\"Inventory.spec:14: expected &lt;false&gt;, got true\">"
+        ));
+        assert!(xml.contains("<skipped/>"));
+        assert!(xml.contains("<system-out>loaded &amp; ready</system-out>"));
+
+        let annotations = github_annotations(&report, Path::new("/work/game"));
+        assert_eq!(
+            annotations,
+            "::error file=src/shared/Inventory.spec.luau,line=14,title=Shared/Inventory > rejects full::\
+             Inventory.spec:14: expected <false>, got true\n"
+        );
+
+        let failed_run = TestReport { error: Some("Timed out after 60s\nbefore the specs finished".to_string()), ..report };
+        assert!(junit_xml(&failed_run).contains("errors=\"1\""));
+        assert!(github_annotations(&failed_run, Path::new("/"))
+            .ends_with("::error title=rbxsync test::Timed out after 60s%0Abefore the specs finished\n"));
+    }
+}
@@ -0,0 +1,146 @@
+//! Long-poll wakeups scoped to a project or Studio session
+//!
+//! Each connected Studio long-polls `/rbxsync/request` with its projectDir and
+//! session ID. A request pushed to a project's queue only wakes the plugins
+//! polling for that project, and one pushed to a session queue only that
+//! session. Requests on the shared (legacy) queue can be taken by any plugin,
+//! so those still wake everyone.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tokio::sync::watch;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Scope {
+    Project(String),
+    Session(String),
+}
+
+/// Notification channels for long-polling plugins
+pub struct Wakeups {
+    all: watch::Sender<u64>,
+    scoped: Mutex<HashMap<Scope, watch::Sender<u64>>>,
+}
+
+/// One poller's subscriptions; only notifications sent after subscribing wake it
+pub struct Waiter {
+    all: watch::Receiver<u64>,
+    project: Option<watch::Receiver<u64>>,
+    session: Option<watch::Receiver<u64>>,
+}
+
+impl Default for Wakeups {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Wakeups {
+    pub fn new() -> Self {
+        Self {
+            all: watch::Sender::new(0),
+            scoped: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wake every poller (after a push to the shared queue)
+    pub fn notify_all(&self) {
+        self.all.send_modify(|n| *n += 1);
+    }
+
+    /// Wake the pollers for one project
+    pub fn notify_project(&self, project_dir: &str) {
+        self.notify(Scope::Project(project_dir.to_string()));
+    }
+
+    /// Wake the poller for one Studio session
+    pub fn notify_session(&self, session_id: &str) {
+        self.notify(Scope::Session(session_id.to_string()));
+    }
+
+    fn notify(&self, scope: Scope) {
+        let mut scoped = self.scoped.lock().unwrap();
+        // Nobody is waiting on channels whose pollers have gone
+        scoped.retain(|_, tx| tx.receiver_count() > 0);
+        if let Some(tx) = scoped.get(&scope) {
+            tx.send_modify(|n| *n += 1);
+        }
+    }
+
+    /// Subscribe before checking the queues so a push in between isn't missed
+    pub fn subscribe(&self, project_dir: Option<&str>, session_id: Option<&str>) -> Waiter {
+        let mut scoped = self.scoped.lock().unwrap();
+        let mut subscribe = |scope: Scope| scoped.entry(scope).or_insert_with(|| watch::Sender::new(0)).subscribe();
+        Waiter {
+            all: self.all.subscribe(),
+            project: project_dir.map(|dir| subscribe(Scope::Project(dir.to_string()))),
+            session: session_id.map(|id| subscribe(Scope::Session(id.to_string()))),
+        }
+    }
+}
+
+impl Waiter {
+    /// Wait for a notification for this poller's project, session or everyone
+    pub async fn wait(&mut self) {
+        async fn changed(rx: Option<&mut watch::Receiver<u64>>) {
+            match rx {
+                Some(rx) => {
+                    let _ = rx.changed().await;
+                }
+                None => std::future::pending().await,
+            }
+        }
+        tokio::select! {
+            _ = self.all.changed() => {}
+            _ = changed(self.project.as_mut()) => {}
+            _ = changed(self.session.as_mut()) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Whether the waiter wakes within a short timeout
+    async fn wakes(waiter: &mut Waiter) -> bool {
+        tokio::time::timeout(Duration::from_millis(50), waiter.wait()).await.is_ok()
+    }
+
+    #[tokio::test]
+    async fn test_wakeups_are_scoped_to_project_and_session() {
+        let wakeups = Wakeups::new();
+        let mut obby = wakeups.subscribe(Some("/games/obby"), Some("session-1"));
+        let mut tycoon = wakeups.subscribe(Some("/games/tycoon"), Some("session-2"));
+        let mut tycoon_2 = wakeups.subscribe(Some("/games/tycoon"), Some("session-3"));
+
+        // Notifications from before subscribing don't count
+        let mut late = {
+            wakeups.notify_project("/games/obby");
+            wakeups.subscribe(Some("/games/obby"), None)
+        };
+        assert!(!wakes(&mut late).await);
+
+        assert!(wakes(&mut obby).await);
+        assert!(!wakes(&mut tycoon).await);
+        assert!(!wakes(&mut tycoon_2).await);
+
+        wakeups.notify_session("session-3");
+        assert!(!wakes(&mut obby).await);
+        assert!(!wakes(&mut tycoon).await);
+        assert!(wakes(&mut tycoon_2).await);
+
+        wakeups.notify_all();
+        assert!(wakes(&mut obby).await);
+        assert!(wakes(&mut tycoon).await);
+        assert!(wakes(&mut tycoon_2).await);
+
+        // Channels without pollers are dropped on the next notification
+        drop((obby, late));
+        wakeups.notify_project("/games/tycoon");
+        assert!(!wakeups.scoped.lock().unwrap().contains_key(&Scope::Project("/games/obby".to_string())));
+        assert!(wakes(&mut tycoon).await);
+    }
+}
@@ -0,0 +1,164 @@
+//! Duplicate VS Code workspace detection
+//!
+//! Two editors can register the same project under different paths (e.g. one
+//! through a network share, one locally). Each would get its own file watcher
+//! and every save would reach Studio twice. Registrations are grouped by
+//! canonical path: the earliest one in a group is the primary and the only one
+//! whose directory is live-synced. The others are secondaries, and one of them
+//! takes over when the primary stops sending heartbeats.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::{events, normalize_path, AppState, VsCodeWorkspace};
+
+/// Whether a registration drives live sync for its project
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkspaceRole {
+    Primary,
+    Secondary,
+}
+
+/// Resolve symlinks, `..` and mount aliases so the same project compares equal
+pub fn canonical_dir(dir: &str) -> String {
+    match std::fs::canonicalize(dir) {
+        Ok(path) => {
+            let path = path.to_string_lossy();
+            // Windows verbatim prefix from canonicalize
+            normalize_path(path.strip_prefix(r"\\?\").unwrap_or(&path))
+        }
+        Err(_) => normalize_path(dir),
+    }
+}
+
+/// Key for a registration: one per editor window when a client ID is sent
+pub fn registration_key(workspace_dir: &str, client_id: Option<&str>) -> String {
+    match client_id {
+        Some(id) if !id.is_empty() => format!("{}#{}", workspace_dir, id),
+        _ => workspace_dir.to_string(),
+    }
+}
+
+/// The earliest registration for a canonical directory
+pub fn primary_for<'a>(
+    workspaces: &'a HashMap<String, VsCodeWorkspace>,
+    canonical: &str,
+) -> Option<(&'a String, &'a VsCodeWorkspace)> {
+    workspaces
+        .iter()
+        .filter(|(_, ws)| ws.canonical_dir == canonical)
+        .min_by(|(key_a, a), (key_b, b)| {
+            a.registered_at
+                .cmp(&b.registered_at)
+                .then_with(|| key_a.cmp(key_b))
+        })
+}
+
+/// Role of a registration within its canonical group
+pub fn role_of(workspaces: &HashMap<String, VsCodeWorkspace>, key: &str) -> WorkspaceRole {
+    let Some(ws) = workspaces.get(key) else {
+        return WorkspaceRole::Secondary;
+    };
+    match primary_for(workspaces, &ws.canonical_dir) {
+        Some((primary_key, _)) if primary_key == key => WorkspaceRole::Primary,
+        _ => WorkspaceRole::Secondary,
+    }
+}
+
+/// Live-sync each registered project through its primary's directory only.
+///
+/// Starts the primary's watcher if needed and stops watchers on other paths to
+/// the same project (secondaries, or a primary that went stale).
+pub async fn reconcile_watchers(state: &Arc<AppState>) {
+    if crate::idle::is_idle(state) {
+        return;
+    }
+
+    // canonical dir -> primary's workspace dir
+    let primaries: HashMap<String, String> = {
+        let workspaces = state.vscode_workspaces.read().await;
+        let canonical: HashSet<&String> = workspaces.values().map(|ws| &ws.canonical_dir).collect();
+        canonical
+            .into_iter()
+            .filter_map(|dir| {
+                primary_for(&workspaces, dir).map(|(_, ws)| (dir.clone(), ws.workspace_dir.clone()))
+            })
+            .collect()
+    };
+
+    let (to_start, to_stop) = {
+        let watcher = state.file_watcher_state.read().await;
+        let to_start: Vec<String> = primaries
+            .values()
+            .filter(|dir| !watcher.watched_dirs.contains(*dir) || watcher.event_only_dirs.contains(*dir))
+            .cloned()
+            .collect();
+        let to_stop: Vec<String> = watcher
+            .watched_dirs
+            .iter()
+            .filter(|dir| !watcher.event_only_dirs.contains(*dir))
+            .filter(|dir| {
+                primaries
+                    .get(&canonical_dir(dir))
+                    .is_some_and(|primary| primary != *dir)
+            })
+            .cloned()
+            .collect();
+        (to_start, to_stop)
+    };
+
+    if !to_stop.is_empty() {
+        let mut watcher = state.file_watcher_state.write().await;
+        for dir in &to_stop {
+            watcher.stop(dir);
+            tracing::info!("Stopped duplicate file watcher for {}", dir);
+        }
+    }
+    for dir in &to_start {
+        events::watch_project(state, dir, true).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn workspace(dir: &str, registered_at: Instant) -> VsCodeWorkspace {
+        VsCodeWorkspace {
+            workspace_dir: dir.to_string(),
+            canonical_dir: canonical_dir(dir),
+            client_id: None,
+            registered_at,
+            last_heartbeat: Some(registered_at),
+        }
+    }
+
+    #[test]
+    fn test_duplicate_workspaces() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let project = temp.path().join("game");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        let direct = normalize_path(&project.to_string_lossy());
+        let aliased = normalize_path(&project.join("src").join("..").to_string_lossy());
+        assert_eq!(canonical_dir(&direct), canonical_dir(&aliased));
+
+        let now = Instant::now();
+        let mut workspaces = HashMap::new();
+        workspaces.insert(direct.clone(), workspace(&direct, now));
+        workspaces.insert(aliased.clone(), workspace(&aliased, now + Duration::from_secs(1)));
+        let other = normalize_path(&temp.path().to_string_lossy());
+        workspaces.insert(other.clone(), workspace(&other, now + Duration::from_secs(2)));
+
+        assert_eq!(role_of(&workspaces, &direct), WorkspaceRole::Primary);
+        assert_eq!(role_of(&workspaces, &aliased), WorkspaceRole::Secondary);
+        assert_eq!(role_of(&workspaces, &other), WorkspaceRole::Primary);
+
+        // The secondary takes over once the primary is gone
+        workspaces.remove(&direct);
+        assert_eq!(role_of(&workspaces, &aliased), WorkspaceRole::Primary);
+    }
+}
@@ -645,10 +645,10 @@ function getPropertyTypeSchemas(): Record<string, JsonSchema> {
             density: { type: 'number' },
             friction: { type: 'number' },
             elasticity: { type: 'number' },
-            friction_weight: { type: 'number' },
-            elasticity_weight: { type: 'number' }
+            frictionWeight: { type: 'number' },
+            elasticityWeight: { type: 'number' }
           },
-          required: ['density', 'friction', 'elasticity', 'friction_weight', 'elasticity_weight'],
+          required: ['density', 'friction', 'elasticity', 'frictionWeight', 'elasticityWeight'],
           additionalProperties: false
         }
       },
@@ -1,6 +1,6 @@
 import * as vscode from 'vscode';
 import * as http from 'http';
-import { RbxSyncClient } from '../server/client';
+import { RbxSyncClient, authHeaders } from '../server/client';
 import { StatusBarManager } from '../views/statusBar';
 
 let serverTerminal: vscode.Terminal | null = null;
@@ -95,6 +95,7 @@ export async function disconnectCommand(
         port,
         path: '/shutdown',
         method: 'POST',
+        headers: authHeaders(),
         timeout: 2000
       }, (res) => {
         resolve();
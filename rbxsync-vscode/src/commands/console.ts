@@ -12,6 +12,17 @@ interface ConsoleMessage {
   message_type: string;
   message: string;
   source?: string;
+  tags?: string[];
+  count?: number;
+  locations?: SourceLocation[];
+}
+
+/** Local script location resolved by the server from a console message */
+interface SourceLocation {
+  instancePath: string;
+  line: number;
+  file: string;
+  link: string;
 }
 
 // ANSI color codes
@@ -34,7 +45,16 @@ function formatMessage(msg: ConsoleMessage): string {
     : msg.message_type === 'warn' ? 'WRN'
     : 'INF';
 
-  return `${COLORS.dim}[${msg.timestamp}]${COLORS.reset} ${typeColor}[${typeLabel}]${COLORS.reset} ${msg.message}\r\n`;
+  // Errors ingested from live game servers
+  const production = msg.tags?.includes('production') ? `${COLORS.cyan}[PROD]${COLORS.reset} ` : '';
+  const count = msg.count && msg.count > 1 ? ` ${COLORS.dim}(×${msg.count})${COLORS.reset}` : '';
+
+  // file:line links are clickable in the terminal
+  const locations = (msg.locations ?? [])
+    .map(loc => `  ${COLORS.blue}→ ${loc.link}${COLORS.reset}\r\n`)
+    .join('');
+
+  return `${COLORS.dim}[${msg.timestamp}]${COLORS.reset} ${typeColor}[${typeLabel}]${COLORS.reset} ${production}${msg.message}${count}\r\n${locations}`;
 }
 
 /**
@@ -494,13 +494,13 @@ export class CompletionProvider {
       },
       // Physics types
       'PhysicalProperties': {
-        fields: ['density', 'friction', 'elasticity', 'friction_weight', 'elasticity_weight'],
+        fields: ['density', 'friction', 'elasticity', 'frictionWeight', 'elasticityWeight'],
         descriptions: {
           density: 'Material density (f32)',
           friction: 'Surface friction (f32)',
           elasticity: 'Bounciness (f32)',
-          friction_weight: 'Friction blend weight (f32)',
-          elasticity_weight: 'Elasticity blend weight (f32)'
+          frictionWeight: 'Friction blend weight (f32)',
+          elasticityWeight: 'Elasticity blend weight (f32)'
         }
       },
       'Ray': {
@@ -1061,7 +1061,7 @@ export class CompletionProvider {
         return `"${propName}": {\n\t"type": "Axes",\n\t"value": {\n\t\t"x": \${1:false},\n\t\t"y": \${2:false},\n\t\t"z": \${3:false}\n\t}\n}`;
 
       case 'PhysicalProperties':
-        return `"${propName}": {\n\t"type": "PhysicalProperties",\n\t"value": {\n\t\t"density": \${1:1},\n\t\t"friction": \${2:0.3},\n\t\t"elasticity": \${3:0.5},\n\t\t"friction_weight": \${4:1},\n\t\t"elasticity_weight": \${5:1}\n\t}\n}`;
+        return `"${propName}": {\n\t"type": "PhysicalProperties",\n\t"value": {\n\t\t"density": \${1:1},\n\t\t"friction": \${2:0.3},\n\t\t"elasticity": \${3:0.5},\n\t\t"frictionWeight": \${4:1},\n\t\t"elasticityWeight": \${5:1}\n\t}\n}`;
 
       case 'Ray':
         return `"${propName}": {\n\t"type": "Ray",\n\t"value": {\n\t\t"origin": { "x": \${1:0}, "y": \${2:0}, "z": \${3:0} },\n\t\t"direction": { "x": \${4:0}, "y": \${5:1}, "z": \${6:0} }\n\t}\n}`;
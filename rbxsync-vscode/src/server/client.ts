@@ -1,4 +1,7 @@
 import axios, { AxiosInstance, AxiosError } from 'axios';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
 import * as vscode from 'vscode';
 import {
   HealthResponse,
@@ -29,11 +32,36 @@ import {
   PathMismatch
 } from './types';
 
+const TOKEN_HEADER = 'X-RbxSync-Token';
+
+// Token for servers started with `rbxsync serve --auth`, read on every request
+// so a regenerated token is picked up without reloading the window
+function readToken(): string | undefined {
+  const fromEnv = process.env.RBXSYNC_TOKEN?.trim();
+  if (fromEnv) {
+    return fromEnv;
+  }
+  try {
+    const token = fs.readFileSync(path.join(os.homedir(), '.rbxsync', 'token'), 'utf8').trim();
+    return token || undefined;
+  } catch {
+    return undefined;
+  }
+}
+
+// Headers for requests made outside RbxSyncClient
+export function authHeaders(): Record<string, string> {
+  const token = readToken();
+  return token ? { [TOKEN_HEADER]: token } : {};
+}
+
 export class RbxSyncClient {
   private client: AxiosInstance;
   private _connectionState: ConnectionState = { connected: false };
   private _onConnectionChange = new vscode.EventEmitter<ConnectionState>();
   private _projectDir: string = '';
+  private _duplicateWarned = false;
+  private _wallyWarned = false;
 
   public readonly onConnectionChange = this._onConnectionChange.event;
 
@@ -45,6 +73,13 @@ export class RbxSyncClient {
         'Content-Type': 'application/json'
       }
     });
+    this.client.interceptors.request.use((config) => {
+      const token = readToken();
+      if (token) {
+        config.headers[TOKEN_HEADER] = token;
+      }
+      return config;
+    });
   }
 
   get connectionState(): ConnectionState {
@@ -104,9 +139,24 @@ export class RbxSyncClient {
   async registerWorkspace(workspaceDir: string): Promise<PathMismatch | null> {
     try {
       const response = await this.client.post<RegisterWorkspaceResponse>('/rbxsync/register-vscode', {
-        workspace_dir: workspaceDir
+        workspace_dir: workspaceDir,
+        client_id: vscode.env.sessionId
       });
 
+      // Another editor has this project open; warn once per session
+      if (response.data.duplicate && !this._duplicateWarned) {
+        this._duplicateWarned = true;
+        vscode.window.showWarningMessage(response.data.duplicate.warning);
+      } else if (!response.data.duplicate) {
+        this._duplicateWarned = false;
+      }
+
+      // Packages out of sync with wally.lock; only reported on first registration
+      if (response.data.wally_drift && !this._wallyWarned) {
+        this._wallyWarned = true;
+        vscode.window.showWarningMessage(response.data.wally_drift.warning);
+      }
+
       // Check for path mismatch and show warning with action button
       if (response.data.path_mismatch) {
         const mismatch = response.data.path_mismatch;
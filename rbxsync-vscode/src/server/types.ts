@@ -204,10 +204,29 @@ export interface PathMismatch {
   warning: string;
 }
 
+// Set when another editor registered the same project first
+export interface DuplicateWorkspace {
+  primary_path: string;
+  warning: string;
+}
+
+// Set when installed Wally packages don't match wally.lock
+export interface WallyDriftWarning {
+  drift: {
+    missing: { name: string; version: string }[];
+    mismatched: { name: string; locked: string; installed: string }[];
+    extra: { name: string; version: string; folder: string }[];
+  };
+  warning: string;
+}
+
 // Registration response that may include path mismatch
 export interface RegisterWorkspaceResponse {
   success: boolean;
   message: string;
+  role?: 'primary' | 'secondary';
+  duplicate?: DuplicateWorkspace | null;
+  wally_drift?: WallyDriftWarning | null;
   path_mismatch?: PathMismatch;
 }
 